@@ -1,8 +1,9 @@
 use anyhow::Context as _;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine as _;
-use ed25519_dalek::{Signer, SigningKey};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use time::{Duration, OffsetDateTime};
 
 const EXPECTED_APP_ID: &str = "com.dstankovski.pausaler-app";
@@ -19,20 +20,52 @@ struct ActivationCodePayload {
   app_id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-enum LicenseType {
+pub enum LicenseType {
   Yearly,
   Lifetime,
 }
 
-#[derive(Debug, Serialize)]
-struct LicensePayload {
-  license_type: LicenseType,
-  valid_from: String,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicensePayload {
+  pub license_type: LicenseType,
+  pub valid_from: String,
   #[serde(skip_serializing_if = "Option::is_none")]
-  valid_until: Option<String>,
-  pib_hash: String,
+  pub valid_until: Option<String>,
+  pub pib_hash: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub app_id: Option<String>,
+}
+
+/// A problem with one field of an activation code (`code` for the code as a whole).
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+  pub field: String,
+  pub message: String,
+}
+
+/// What an activation code contains, for checking before a license is issued for it.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationCodePreview {
+  pub pib_hash: Option<String>,
+  /// `issued_at` as "YYYY-MM-DD HH:MM:SS UTC".
+  pub issued_at: Option<String>,
+  pub app_id: Option<String>,
+  pub nonce: Option<String>,
+  /// Empty when a license can be issued for the code.
+  pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicensePreview {
+  /// Key id prefix, for `kid.payload.sig` licenses.
+  pub kid: Option<String>,
+  pub payload: LicensePayload,
+  /// Whether the key this app signs with produced the signature.
+  pub signature_valid: bool,
 }
 
 pub fn generate_license(activation_code: &str, license_type: &str) -> anyhow::Result<String> {
@@ -64,6 +97,7 @@ pub fn generate_license(activation_code: &str, license_type: &str) -> anyhow::Re
     valid_from,
     valid_until,
     pib_hash: activation.pib_hash,
+    app_id: Some(EXPECTED_APP_ID.to_string()),
   };
 
   let payload_bytes = serde_json::to_vec(&payload)?;
@@ -101,6 +135,104 @@ pub fn public_key_pem() -> anyhow::Result<String> {
   Ok(out)
 }
 
+/// Decodes an activation code field by field, so every problem is reported, not just the first.
+pub fn decode_activation_code_preview(code: &str) -> ActivationCodePreview {
+  let mut preview = ActivationCodePreview::default();
+  let mut error = |field: &str, message: String| {
+    preview.errors.push(FieldError {
+      field: field.to_string(),
+      message,
+    })
+  };
+  let json = match URL_SAFE_NO_PAD.decode(code.trim()) {
+    Ok(bytes) => serde_json::from_slice::<Value>(&bytes)
+      .map_err(|e| format!("not valid JSON: {e}")),
+    Err(e) => Err(format!("not valid base64url: {e}")),
+  };
+  let json = match json {
+    Ok(Value::Object(map)) => map,
+    Ok(_) => {
+      error("code", "not a JSON object".to_string());
+      return preview;
+    }
+    Err(message) => {
+      error("code", message);
+      return preview;
+    }
+  };
+  let text = |key: &str| json.get(key).and_then(Value::as_str).map(str::to_string);
+
+  let pib_hash = text("pib_hash");
+  if pib_hash.as_deref().is_none_or(str::is_empty) {
+    error("pib_hash", "missing".to_string());
+  }
+  let issued_at = json.get("issued_at").and_then(Value::as_i64);
+  let issued_at_text = issued_at
+    .filter(|t| *t > 0)
+    .and_then(|t| OffsetDateTime::from_unix_timestamp(t).ok())
+    .map(|t| {
+      format!(
+        "{} {:02}:{:02}:{:02} UTC",
+        t.date(),
+        t.hour(),
+        t.minute(),
+        t.second()
+      )
+    });
+  if issued_at_text.is_none() {
+    error("issued_at", "missing or not a valid timestamp".to_string());
+  }
+  let app_id = text("app_id");
+  if app_id.as_deref() != Some(EXPECTED_APP_ID) {
+    error(
+      "app_id",
+      format!("expected {EXPECTED_APP_ID}, got {}", app_id.as_deref().unwrap_or("nothing")),
+    );
+  }
+  let nonce = text("nonce");
+  if nonce.as_deref().is_none_or(str::is_empty) {
+    error("nonce", "missing".to_string());
+  }
+
+  ActivationCodePreview {
+    pib_hash,
+    issued_at: issued_at_text,
+    app_id,
+    nonce,
+    errors: preview.errors,
+  }
+}
+
+/// Decodes `payload.sig` or `kid.payload.sig` and checks the signature against the signing key.
+pub fn decode_license(license: &str) -> anyhow::Result<LicensePreview> {
+  let parts: Vec<&str> = license.trim().split('.').collect();
+  let (kid, payload_b64, sig_b64) = match parts.as_slice() {
+    [payload, sig] => (None, *payload, *sig),
+    [kid, payload, sig] => (Some(kid.to_string()), *payload, *sig),
+    _ => anyhow::bail!("license must be <payload>.<signature> or <kid>.<payload>.<signature>"),
+  };
+  let payload_bytes = URL_SAFE_NO_PAD
+    .decode(payload_b64)
+    .map_err(|e| anyhow::anyhow!("invalid payload base64url: {e}"))?;
+  let signature: [u8; 64] = URL_SAFE_NO_PAD
+    .decode(sig_b64)
+    .map_err(|e| anyhow::anyhow!("invalid signature base64url: {e}"))?
+    .try_into()
+    .map_err(|_| anyhow::anyhow!("invalid signature length"))?;
+  let payload: LicensePayload = serde_json::from_slice(&payload_bytes)
+    .map_err(|e| anyhow::anyhow!("invalid payload json: {e}"))?;
+
+  let vk: VerifyingKey = signing_key_from_dev_seed()?.verifying_key();
+  let signature_valid = vk
+    .verify_strict(&payload_bytes, &ed25519_dalek::Signature::from_bytes(&signature))
+    .is_ok();
+  Ok(LicensePreview {
+    kid,
+    payload,
+    signature_valid,
+  })
+}
+
 fn decode_activation_code(code: &str) -> anyhow::Result<ActivationCodePayload> {
   let bytes = URL_SAFE_NO_PAD
     .decode(code.trim())
@@ -131,3 +263,49 @@ fn signing_key_from_dev_seed() -> anyhow::Result<SigningKey> {
   seed_bytes.copy_from_slice(&seed);
   Ok(SigningKey::from_bytes(&seed_bytes))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn activation_code(json: &str) -> String {
+    URL_SAFE_NO_PAD.encode(json)
+  }
+
+  #[test]
+  fn activation_code_preview_reports_every_bad_field() {
+    let ok = decode_activation_code_preview(&activation_code(&format!(
+      r#"{{"pib_hash":"abc","issued_at":1750000000,"nonce":"n1","app_id":"{EXPECTED_APP_ID}"}}"#
+    )));
+    assert!(ok.errors.is_empty(), "{:?}", ok.errors);
+    assert_eq!(ok.issued_at.as_deref(), Some("2025-06-15 15:06:40 UTC"));
+    assert_eq!(ok.pib_hash.as_deref(), Some("abc"));
+
+    let bad = decode_activation_code_preview(&activation_code(
+      r#"{"pib_hash":"","issued_at":-5,"app_id":"com.example.other"}"#,
+    ));
+    let fields: Vec<&str> = bad.errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, ["pib_hash", "issued_at", "app_id", "nonce"]);
+    assert_eq!(bad.app_id.as_deref(), Some("com.example.other"));
+
+    let garbage = decode_activation_code_preview("%%%");
+    assert_eq!(garbage.errors[0].field, "code");
+  }
+
+  #[test]
+  fn generated_licenses_decode_with_a_valid_signature() {
+    let code = activation_code(&format!(
+      r#"{{"pib_hash":"abc","issued_at":1750000000,"nonce":"n1","app_id":"{EXPECTED_APP_ID}"}}"#
+    ));
+    let license = generate_license(&code, "yearly").unwrap();
+    let preview = decode_license(&license).unwrap();
+    assert!(preview.signature_valid);
+    assert_eq!(preview.payload.pib_hash, "abc");
+    assert!(preview.payload.valid_until.is_some());
+
+    let (payload, _) = license.split_once('.').unwrap();
+    let forged = format!("{payload}.{}", URL_SAFE_NO_PAD.encode([0u8; 64]));
+    assert!(!decode_license(&forged).unwrap().signature_valid);
+    assert!(decode_license("nope").is_err());
+  }
+}
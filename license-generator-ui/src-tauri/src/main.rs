@@ -17,6 +17,16 @@ fn generate_license(args: GenerateLicenseArgs) -> Result<String, String> {
   licensing::generate_license(&args.activation_code, &args.license_type).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn decode_activation_code(code: String) -> licensing::ActivationCodePreview {
+  licensing::decode_activation_code_preview(&code)
+}
+
+#[tauri::command]
+fn decode_license(license: String) -> Result<licensing::LicensePreview, String> {
+  licensing::decode_license(&license).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn public_key_pem() -> Result<String, String> {
   licensing::public_key_pem().map_err(|e| e.to_string())
@@ -24,7 +34,12 @@ fn public_key_pem() -> Result<String, String> {
 
 fn main() {
   tauri::Builder::default()
-    .invoke_handler(tauri::generate_handler![generate_license, public_key_pem])
+    .invoke_handler(tauri::generate_handler![
+      generate_license,
+      decode_activation_code,
+      decode_license,
+      public_key_pem
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
@@ -2,6 +2,28 @@ import { invoke } from '@tauri-apps/api/core';
 
 type LicenseKind = 'yearly' | 'lifetime';
 
+type FieldError = { field: string; message: string };
+
+type ActivationCodePreview = {
+  pibHash: string | null;
+  issuedAt: string | null;
+  appId: string | null;
+  nonce: string | null;
+  errors: FieldError[];
+};
+
+type LicensePreview = {
+  kid: string | null;
+  payload: {
+    license_type: 'YEARLY' | 'LIFETIME';
+    valid_from: string;
+    valid_until?: string;
+    pib_hash: string;
+    app_id?: string;
+  };
+  signatureValid: boolean;
+};
+
 function $(id: string): HTMLElement {
   const el = document.getElementById(id);
   if (!el) throw new Error(`Missing element: ${id}`);
@@ -15,12 +37,76 @@ const copyEl = $('copyLicense') as HTMLButtonElement;
 const showPubEl = $('showPub') as HTMLButtonElement;
 const licenseEl = $('license') as HTMLTextAreaElement;
 const statusEl = $('status') as HTMLDivElement;
+const activationPreviewEl = $('activationPreview') as HTMLDListElement;
+const checkLicenseEl = $('checkLicense') as HTMLButtonElement;
+const licensePreviewEl = $('licensePreview') as HTMLDListElement;
 
 function setStatus(msg: string, kind: 'ok' | 'error' | 'info' = 'info') {
   statusEl.textContent = msg;
   statusEl.className = `status ${kind === 'ok' ? 'ok' : kind === 'error' ? 'error' : ''}`;
 }
 
+/** Fills a <dl> with label/value rows; `error` rows are highlighted. */
+function renderPreview(el: HTMLDListElement, rows: Array<{ label: string; value: string; error?: boolean }>) {
+  el.replaceChildren();
+  for (const row of rows) {
+    const dt = document.createElement('dt');
+    dt.textContent = row.label;
+    const dd = document.createElement('dd');
+    dd.textContent = row.value;
+    if (row.error) dd.className = 'error';
+    el.append(dt, dd);
+  }
+}
+
+async function previewActivationCode(): Promise<ActivationCodePreview | null> {
+  const code = activationEl.value.trim();
+  if (!code) {
+    renderPreview(activationPreviewEl, []);
+    return null;
+  }
+  const preview = await invoke<ActivationCodePreview>('decode_activation_code', { code });
+  const errorFor = (field: string) => preview.errors.find((e) => e.field === field)?.message;
+  const row = (label: string, field: string, value: string | null) => {
+    const error = errorFor(field);
+    return { label, value: error ? `${value ?? ''} (${error})`.trim() : value ?? '', error: !!error };
+  };
+  const codeError = errorFor('code');
+  renderPreview(
+    activationPreviewEl,
+    codeError
+      ? [{ label: 'Code', value: codeError, error: true }]
+      : [
+          row('PIB hash', 'pib_hash', preview.pibHash),
+          row('Issued at', 'issued_at', preview.issuedAt),
+          row('App', 'app_id', preview.appId),
+          row('Nonce', 'nonce', preview.nonce),
+        ]
+  );
+  return preview;
+}
+
+async function checkLicense(license: string) {
+  try {
+    const preview = await invoke<LicensePreview>('decode_license', { license });
+    renderPreview(licensePreviewEl, [
+      { label: 'Type', value: preview.payload.license_type },
+      { label: 'Valid from', value: preview.payload.valid_from },
+      { label: 'Valid until', value: preview.payload.valid_until ?? '—' },
+      { label: 'PIB hash', value: preview.payload.pib_hash },
+      { label: 'App', value: preview.payload.app_id ?? '—' },
+      { label: 'Key id', value: preview.kid ?? '—' },
+      {
+        label: 'Signature',
+        value: preview.signatureValid ? 'valid (current key)' : 'NOT signed by the current key',
+        error: !preview.signatureValid,
+      },
+    ]);
+  } catch (e) {
+    renderPreview(licensePreviewEl, [{ label: 'License', value: String(e), error: true }]);
+  }
+}
+
 async function copyToClipboard(text: string) {
   await navigator.clipboard.writeText(text);
 }
@@ -33,6 +119,11 @@ generateEl.addEventListener('click', async () => {
     setStatus('Activation code is required.', 'error');
     return;
   }
+  const preview = await previewActivationCode();
+  if (preview && preview.errors.length > 0) {
+    setStatus('Fix the activation code before generating a license.', 'error');
+    return;
+  }
 
   generateEl.disabled = true;
   copyEl.disabled = true;
@@ -48,6 +139,7 @@ generateEl.addEventListener('click', async () => {
 
     licenseEl.value = license;
     copyEl.disabled = false;
+    await checkLicense(license);
     setStatus('License generated.', 'ok');
   } catch (e) {
     const msg = (e as any)?.message ?? String(e);
@@ -57,6 +149,15 @@ generateEl.addEventListener('click', async () => {
   }
 });
 
+activationEl.addEventListener('input', () => {
+  void previewActivationCode();
+});
+
+checkLicenseEl.addEventListener('click', () => {
+  const license = licenseEl.value.trim();
+  if (license) void checkLicense(license);
+});
+
 copyEl.addEventListener('click', async () => {
   const text = licenseEl.value.trim();
   if (!text) return;
@@ -1,12 +1,41 @@
-use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use argon2::Argon2;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
 use base64::Engine as _;
-use clap::{Parser, Subcommand, ValueEnum};
-use ed25519_dalek::{Signer, SigningKey};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use time::{Duration, OffsetDateTime};
+use zeroize::Zeroizing;
 
 const EXPECTED_APP_ID: &str = "com.dstankovski.pausaler-app";
 
+/// Environment variable holding the hex seed, as an alternative to `--key-file`.
+const SIGNING_KEY_ENV: &str = "LICENSE_SIGNING_KEY";
+
+const ENCRYPTED_KEY_FORMAT: &str = "pausaler-signing-key";
+const ENCRYPTED_KEY_VERSION: u32 = 1;
+
+/// Where `generate` and `generate-batch` record every license they issue.
+const DEFAULT_AUDIT_LOG: &str = "issued-licenses.csv";
+
+/// SPKI DER header of an Ed25519 public key; the 32 key bytes follow.
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+  0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// The key the app ships with; `verify` checks against it unless `--public-key` is given.
+const APP_PUBLIC_KEY_PEM: &str = include_str!("../../src-tauri/assets/public_key.pem");
+
+/// `kind` of the receipts written by the app's `deactivate_license`.
+const RECEIPT_KIND: &str = "deactivation";
+
+/// Published in the repo, so anyone can sign with it. Only used with `--dev`.
 const DEV_PRIVATE_KEY_SEED_HEX: &str =
   "c590af4308cc0f6a1a4faccf7c05ff00b3d7d4d38a9ad52b1af10f0c6b3a3f10";
 
@@ -25,9 +54,118 @@ enum Command {
 
     #[arg(long, value_enum)]
     r#type: LicenseKind,
+
+    /// Customer name for the audit log.
+    #[arg(long, default_value = "")]
+    customer: String,
+
+    #[command(flatten)]
+    key: KeySource,
+
+    #[command(flatten)]
+    audit: AuditLog,
   },
 
-  PublicKey,
+  /// Signs a license for every row of a CSV with the columns customer_name, activation_code and
+  /// type (yearly/lifetime). Bad rows are reported and skipped; the rest are still issued.
+  GenerateBatch {
+    #[arg(long)]
+    input: PathBuf,
+
+    /// CSV with the license, license_type, valid_until and pib_hash (or the error) per row.
+    #[arg(long)]
+    output: PathBuf,
+
+    #[command(flatten)]
+    key: KeySource,
+
+    #[command(flatten)]
+    audit: AuditLog,
+  },
+
+  /// Replaces a license the customer deactivated in the app (e.g. after changing PIB) with one
+  /// for their new activation code, of the same type and expiry.
+  Reissue {
+    /// The receipt the app showed when the license was deactivated.
+    #[arg(long)]
+    receipt: String,
+
+    /// Activation code generated in the app with the new PIB.
+    #[arg(long)]
+    activation_code: String,
+
+    /// Customer name for the audit log.
+    #[arg(long, default_value = "")]
+    customer: String,
+
+    #[command(flatten)]
+    key: KeySource,
+
+    #[command(flatten)]
+    audit: AuditLog,
+  },
+
+  /// Decodes a license and checks its signature.
+  Verify {
+    license: String,
+
+    /// PEM public key to check against (repeatable; default: the key shipped in the app).
+    #[arg(long = "public-key")]
+    public_keys: Vec<PathBuf>,
+
+    /// Also check that the license was issued for this PIB.
+    #[arg(long)]
+    pib: Option<String>,
+  },
+
+  PublicKey {
+    #[command(flatten)]
+    key: KeySource,
+  },
+
+  /// Creates a new signing key file (readable only by its owner) and prints its public key.
+  Keygen {
+    /// Where to write the key; an existing file is never overwritten.
+    #[arg(long)]
+    out: PathBuf,
+
+    /// Encrypt the key with a passphrase (prompted).
+    #[arg(long)]
+    encrypt: bool,
+  },
+}
+
+/// The signing key: `--key-file`, then `--dev`, then the `LICENSE_SIGNING_KEY` hex seed.
+#[derive(Args, Debug)]
+struct KeySource {
+  /// Key file written by `keygen`: a hex Ed25519 seed, or one encrypted with a passphrase.
+  #[arg(long)]
+  key_file: Option<PathBuf>,
+
+  /// Sign with the development key from this repo. Never use it for real customers.
+  #[arg(long, conflicts_with = "key_file")]
+  dev: bool,
+}
+
+#[derive(Args, Debug)]
+struct AuditLog {
+  /// CSV every issued license is appended to.
+  #[arg(long, default_value = DEFAULT_AUDIT_LOG)]
+  audit_log: PathBuf,
+}
+
+/// `keygen --encrypt` output. The seed is sealed with ChaCha20-Poly1305 under a key derived from
+/// the passphrase with Argon2id.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKeyFile {
+  format: String,
+  version: u32,
+  m_cost: u32,
+  t_cost: u32,
+  p_cost: u32,
+  salt: String,
+  nonce: String,
+  ciphertext: String,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -44,13 +182,22 @@ struct ActivationCodePayload {
   app_id: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Copy, Debug, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 enum LicenseType {
   Yearly,
   Lifetime,
 }
 
+impl LicenseType {
+  fn as_str(self) -> &'static str {
+    match self {
+      LicenseType::Yearly => "YEARLY",
+      LicenseType::Lifetime => "LIFETIME",
+    }
+  }
+}
+
 #[derive(Debug, Serialize)]
 struct LicensePayload {
   license_type: LicenseType,
@@ -58,6 +205,68 @@ struct LicensePayload {
   #[serde(skip_serializing_if = "Option::is_none")]
   valid_until: Option<String>,
   pib_hash: String,
+  app_id: &'static str,
+}
+
+/// Receipt of a license deactivated in the app, as written by `deactivate_license`.
+#[derive(Debug, Deserialize)]
+struct DeactivationReceipt {
+  kind: String,
+  app_id: String,
+  pib_hash: String,
+  license_hash: String,
+  license: String,
+  deactivated_at: String,
+  install_key: String,
+}
+
+/// A signed license and what went into it.
+#[derive(Debug)]
+struct IssuedLicense {
+  license: String,
+  license_type: LicenseType,
+  valid_until: Option<String>,
+  pib_hash: String,
+  kid: String,
+  issued_at: String,
+}
+
+/// A `generate-batch` input row (the customer name is read from the raw record, so failed rows
+/// still report it).
+#[derive(Debug, Deserialize)]
+struct BatchRow {
+  activation_code: String,
+  r#type: String,
+}
+
+/// A `generate-batch` output row; `error` is set instead of the license fields when the row failed.
+#[derive(Debug, Default, Serialize)]
+struct BatchResult {
+  customer_name: String,
+  license: String,
+  license_type: String,
+  valid_until: String,
+  pib_hash: String,
+  error: String,
+}
+
+/// What `generate-batch` did: every row's result, and the licenses issued with their customer
+/// names.
+#[derive(Debug, Default)]
+struct Batch {
+  results: Vec<BatchResult>,
+  issued: Vec<(String, IssuedLicense)>,
+}
+
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+  issued_at: &'a str,
+  customer_name: &'a str,
+  license_type: &'static str,
+  valid_until: &'a str,
+  pib_hash: &'a str,
+  kid: &'a str,
+  license: &'a str,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -67,69 +276,158 @@ fn main() -> anyhow::Result<()> {
     Command::Generate {
       activation_code,
       r#type,
+      customer,
+      key,
+      audit,
     } => {
-      let activation = decode_activation_code(&activation_code)?;
-      if activation.app_id != EXPECTED_APP_ID {
-        anyhow::bail!(
-          "activation code app_id mismatch: expected {}, got {}",
-          EXPECTED_APP_ID,
-          activation.app_id
-        );
-      }
-
-      let now = OffsetDateTime::now_utc().replace_nanosecond(0)?;
-      let valid_from = now.format(&time::format_description::well_known::Rfc3339)?;
-
-      let (license_type, valid_until) = match r#type {
-        LicenseKind::Yearly => {
-          let until = (now + Duration::days(365))
-            .replace_nanosecond(0)?
-            .format(&time::format_description::well_known::Rfc3339)?;
-          (LicenseType::Yearly, Some(until))
-        }
-        LicenseKind::Lifetime => (LicenseType::Lifetime, None),
-      };
+      let issued = issue_license(&key.signing_key()?, &activation_code, r#type)?;
+      append_audit_log(&audit.audit_log, &[(customer.as_str(), &issued)])?;
+      println!("{}", issued.license);
+    }
 
-      let payload = LicensePayload {
-        license_type,
-        valid_from,
-        valid_until,
-        pib_hash: activation.pib_hash,
-      };
+    Command::GenerateBatch {
+      input,
+      output,
+      key,
+      audit,
+    } => {
+      let file = std::fs::File::open(&input)
+        .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", input.display()))?;
+      let Batch { results, issued } = generate_batch(&key.signing_key()?, file)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", input.display()))?;
 
-      let payload_bytes = serde_json::to_vec(&payload)?;
-      let signature_bytes = signing_key_from_dev_seed()?.sign(&payload_bytes).to_bytes();
+      let audit_rows: Vec<(&str, &IssuedLicense)> =
+        issued.iter().map(|(c, l)| (c.as_str(), l)).collect();
+      append_audit_log(&audit.audit_log, &audit_rows)?;
 
-      let payload_b64 = URL_SAFE_NO_PAD.encode(payload_bytes);
-      let sig_b64 = URL_SAFE_NO_PAD.encode(signature_bytes);
+      let mut writer = csv::Writer::from_path(&output)
+        .map_err(|e| anyhow::anyhow!("failed to create {}: {e}", output.display()))?;
+      for result in &results {
+        writer.serialize(result)?;
+      }
+      writer.flush()?;
 
-      println!("{}.{}", payload_b64, sig_b64);
+      eprintln!(
+        "issued {} of {} licenses; wrote {}",
+        issued.len(),
+        results.len(),
+        output.display()
+      );
     }
 
-    Command::PublicKey => {
-      let sk = signing_key_from_dev_seed()?;
-      let vk = sk.verifying_key();
+    Command::Reissue {
+      receipt,
+      activation_code,
+      customer,
+      key,
+      audit,
+    } => {
+      let receipt = read_deactivation_receipt(&receipt)?;
+      let issued = reissue_license(&key.signing_key()?, &receipt, &activation_code)?;
+      eprintln!(
+        "replacing the license deactivated on {} (pib_hash {})",
+        receipt.deactivated_at, receipt.pib_hash
+      );
+      append_audit_log(&audit.audit_log, &[(customer.as_str(), &issued)])?;
+      println!("{}", issued.license);
+    }
 
-      let prefix: [u8; 12] = [
-        0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
-      ];
+    Command::Verify {
+      license,
+      public_keys,
+      pib,
+    } => {
+      let pems = if public_keys.is_empty() {
+        vec![APP_PUBLIC_KEY_PEM.to_string()]
+      } else {
+        public_keys
+          .iter()
+          .map(|p| {
+            std::fs::read_to_string(p)
+              .map_err(|e| anyhow::anyhow!("failed to read {}: {e}", p.display()))
+          })
+          .collect::<anyhow::Result<_>>()?
+      };
+      let keys = pems
+        .iter()
+        .map(|pem| parse_public_key_pem(pem))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+      verify_license(&license, &keys, pib.as_deref())?;
+    }
 
-      let mut der = Vec::with_capacity(44);
-      der.extend_from_slice(&prefix);
-      der.extend_from_slice(&vk.to_bytes());
+    Command::PublicKey { key } => {
+      print_public_key(&key.signing_key()?.verifying_key())?;
+    }
 
-      let b64 = base64::engine::general_purpose::STANDARD.encode(der);
-      println!("-----BEGIN PUBLIC KEY-----");
-      for chunk in b64.as_bytes().chunks(64) {
-        println!("{}", std::str::from_utf8(chunk)?);
-      }
-      println!("-----END PUBLIC KEY-----");
+    Command::Keygen { out, encrypt } => {
+      let sk = SigningKey::generate(&mut OsRng);
+      let seed_hex = Zeroizing::new(hex::encode(sk.to_bytes()));
+      let contents = if encrypt {
+        let passphrase = prompt_new_passphrase()?;
+        serde_json::to_string_pretty(&encrypt_seed(&sk.to_bytes(), &passphrase)?)?
+      } else {
+        seed_hex.to_string()
+      };
+      write_private_file(&out, contents.as_bytes())?;
+      eprintln!("wrote {}", out.display());
+      print_public_key(&sk.verifying_key())?;
     }
   }
 
   Ok(())
 }
 
+/// Issues a license for every row of a `generate-batch` CSV. A row that fails gets its error in
+/// its result and the rest of the batch goes on.
+fn generate_batch(sk: &SigningKey, input: impl std::io::Read) -> anyhow::Result<Batch> {
+  let mut reader = csv::ReaderBuilder::new()
+    .trim(csv::Trim::All)
+    .from_reader(input);
+  let headers = reader.headers()?.clone();
+  let Some(customer_column) = headers.iter().position(|h| h == "customer_name") else {
+    anyhow::bail!("no customer_name column");
+  };
+
+  let mut batch = Batch::default();
+  for (i, record) in reader.records().enumerate() {
+    let customer_name = record
+      .as_ref()
+      .ok()
+      .and_then(|r| r.get(customer_column))
+      .unwrap_or_default()
+      .to_string();
+    let outcome = record.map_err(anyhow::Error::from).and_then(|record| {
+      let row: BatchRow = record.deserialize(Some(&headers))?;
+      let kind = LicenseKind::from_str(&row.r#type, true)
+        .map_err(|_| anyhow::anyhow!("unknown license type {:?}", row.r#type))?;
+      issue_license(sk, &row.activation_code, kind)
+    });
+    match outcome {
+      Ok(license) => {
+        batch.results.push(BatchResult {
+          customer_name: customer_name.clone(),
+          license: license.license.clone(),
+          license_type: license.license_type.as_str().to_string(),
+          valid_until: license.valid_until.clone().unwrap_or_default(),
+          pib_hash: license.pib_hash.clone(),
+          error: String::new(),
+        });
+        batch.issued.push((customer_name, license));
+      }
+      Err(e) => {
+        // Line 1 is the header.
+        eprintln!("line {} ({customer_name}): {e}", i + 2);
+        batch.results.push(BatchResult {
+          customer_name,
+          error: e.to_string(),
+          ..Default::default()
+        });
+      }
+    }
+  }
+  Ok(batch)
+}
+
 fn decode_activation_code(code: &str) -> anyhow::Result<ActivationCodePayload> {
   let bytes = URL_SAFE_NO_PAD
     .decode(code.trim())
@@ -150,12 +448,692 @@ fn decode_activation_code(code: &str) -> anyhow::Result<ActivationCodePayload> {
   Ok(payload)
 }
 
-fn signing_key_from_dev_seed() -> anyhow::Result<SigningKey> {
-  let seed = hex::decode(DEV_PRIVATE_KEY_SEED_HEX)?;
-  if seed.len() != 32 {
-    anyhow::bail!("dev seed must be 32 bytes");
+/// Signs a license for `activation_code`, valid from now.
+fn issue_license(
+  sk: &SigningKey,
+  activation_code: &str,
+  kind: LicenseKind,
+) -> anyhow::Result<IssuedLicense> {
+  let now = OffsetDateTime::now_utc().replace_nanosecond(0)?;
+  let (license_type, valid_until) = match kind {
+    LicenseKind::Yearly => {
+      let until = (now + Duration::days(365))
+        .replace_nanosecond(0)?
+        .format(&time::format_description::well_known::Rfc3339)?;
+      (LicenseType::Yearly, Some(until))
+    }
+    LicenseKind::Lifetime => (LicenseType::Lifetime, None),
+  };
+  sign_license(sk, activation_code, license_type, valid_until, now)
+}
+
+/// Signs a license for `activation_code` valid from `now` until `valid_until`.
+fn sign_license(
+  sk: &SigningKey,
+  activation_code: &str,
+  license_type: LicenseType,
+  valid_until: Option<String>,
+  now: OffsetDateTime,
+) -> anyhow::Result<IssuedLicense> {
+  let activation = decode_activation_code(activation_code)?;
+  if activation.app_id != EXPECTED_APP_ID {
+    anyhow::bail!(
+      "activation code app_id mismatch: expected {}, got {}",
+      EXPECTED_APP_ID,
+      activation.app_id
+    );
+  }
+
+  let valid_from = now.format(&time::format_description::well_known::Rfc3339)?;
+
+  let payload = LicensePayload {
+    license_type,
+    valid_from: valid_from.clone(),
+    valid_until: valid_until.clone(),
+    pib_hash: activation.pib_hash.clone(),
+    app_id: EXPECTED_APP_ID,
+  };
+
+  let payload_bytes = serde_json::to_vec(&payload)?;
+  let signature_bytes = sk.sign(&payload_bytes).to_bytes();
+
+  let payload_b64 = URL_SAFE_NO_PAD.encode(payload_bytes);
+  let sig_b64 = URL_SAFE_NO_PAD.encode(signature_bytes);
+  let kid = key_id(&sk.verifying_key());
+
+  Ok(IssuedLicense {
+    license: format!("{kid}.{payload_b64}.{sig_b64}"),
+    license_type,
+    valid_until,
+    pib_hash: activation.pib_hash,
+    kid,
+    issued_at: valid_from,
+  })
+}
+
+/// Appends `(customer name, license)` rows to the audit CSV, writing the header when the file is
+/// new.
+fn append_audit_log(path: &Path, licenses: &[(&str, &IssuedLicense)]) -> anyhow::Result<()> {
+  if licenses.is_empty() {
+    return Ok(());
+  }
+  let file = std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .map_err(|e| anyhow::anyhow!("failed to open audit log {}: {e}", path.display()))?;
+  let is_new = file.metadata()?.len() == 0;
+  let mut writer = csv::WriterBuilder::new()
+    .has_headers(is_new)
+    .from_writer(file);
+  for (customer_name, l) in licenses {
+    writer.serialize(AuditRecord {
+      issued_at: &l.issued_at,
+      customer_name,
+      license_type: l.license_type.as_str(),
+      valid_until: l.valid_until.as_deref().unwrap_or_default(),
+      pib_hash: &l.pib_hash,
+      kid: &l.kid,
+      license: &l.license,
+    })?;
+  }
+  writer.flush()?;
+  Ok(())
+}
+
+fn parse_public_key_pem(pem: &str) -> anyhow::Result<VerifyingKey> {
+  let b64: String = pem
+    .lines()
+    .map(str::trim)
+    .filter(|l| !l.is_empty() && !l.starts_with("-----"))
+    .collect();
+  let der = STANDARD
+    .decode(b64)
+    .map_err(|e| anyhow::anyhow!("invalid public key pem base64: {e}"))?;
+  if der.len() != 44 || der[..12] != ED25519_SPKI_PREFIX {
+    anyhow::bail!("unsupported public key format");
+  }
+  let mut pk = [0u8; 32];
+  pk.copy_from_slice(&der[12..]);
+  VerifyingKey::from_bytes(&pk).map_err(|e| anyhow::anyhow!("invalid public key bytes: {e}"))
+}
+
+/// A license's kid and payload, and the key among `keys` that signed it, if any.
+fn decode_license(
+  license: &str,
+  keys: &[VerifyingKey],
+) -> anyhow::Result<(Option<String>, serde_json::Value, Option<VerifyingKey>)> {
+  let parts: Vec<&str> = license.trim().split('.').collect();
+  let (kid, payload_b64, sig_b64) = match parts.as_slice() {
+    [payload, sig] => (None, *payload, *sig),
+    [kid, payload, sig] => (Some(kid.to_string()), *payload, *sig),
+    _ => anyhow::bail!("license must be <payload>.<signature> or <kid>.<payload>.<signature>"),
+  };
+  let payload_bytes = URL_SAFE_NO_PAD
+    .decode(payload_b64)
+    .map_err(|e| anyhow::anyhow!("invalid payload base64url: {e}"))?;
+  let signature: [u8; 64] = URL_SAFE_NO_PAD
+    .decode(sig_b64)
+    .map_err(|e| anyhow::anyhow!("invalid signature base64url: {e}"))?
+    .try_into()
+    .map_err(|_| anyhow::anyhow!("invalid signature length"))?;
+  let payload: serde_json::Value = serde_json::from_slice(&payload_bytes)
+    .map_err(|e| anyhow::anyhow!("invalid payload json: {e}"))?;
+
+  let signature = ed25519_dalek::Signature::from_bytes(&signature);
+  let signer = keys
+    .iter()
+    .find(|vk| vk.verify_strict(&payload_bytes, &signature).is_ok())
+    .copied();
+  Ok((kid, payload, signer))
+}
+
+/// PIB hash the app puts into activation codes: SHA-256 of `<app id>:<pib>`, hex.
+fn pib_hash(pib: &str) -> String {
+  hex::encode(Sha256::digest(format!("{EXPECTED_APP_ID}:{}", pib.trim())))
+}
+
+/// Unsalted SHA-256 of the PIB, used by activation codes from older app versions.
+fn legacy_pib_hash(pib: &str) -> String {
+  hex::encode(Sha256::digest(pib.trim()))
+}
+
+/// Prints the license's kid and payload and fails unless one of `keys` signed it and, given a
+/// PIB, the license carries its salted or legacy hash.
+fn verify_license(license: &str, keys: &[VerifyingKey], pib: Option<&str>) -> anyhow::Result<()> {
+  let (kid, payload, signer) = decode_license(license, keys)?;
+  println!("kid: {}", kid.as_deref().unwrap_or("(none)"));
+  println!("payload: {}", serde_json::to_string_pretty(&payload)?);
+  if let Some(pib) = pib {
+    let hash = payload.get("pib_hash").and_then(|v| v.as_str()).unwrap_or_default();
+    if hash == pib_hash(pib) {
+      println!("pib: matches");
+    } else if hash == legacy_pib_hash(pib) {
+      println!("pib: matches (legacy unsalted hash)");
+    } else {
+      anyhow::bail!("pib: license was issued for a different PIB");
+    }
+  }
+  match signer {
+    Some(vk) => {
+      println!("signature: valid (kid {})", key_id(&vk));
+      Ok(())
+    }
+    None => anyhow::bail!("signature: INVALID for the given public key(s)"),
+  }
+}
+
+/// Checks a receipt from the app's `deactivate_license`: signed by the installation key it
+/// names, for this app, and holding the license it claims.
+fn read_deactivation_receipt(text: &str) -> anyhow::Result<DeactivationReceipt> {
+  let (payload_b64, sig_b64) = text
+    .trim()
+    .split_once('.')
+    .ok_or_else(|| anyhow::anyhow!("receipt must be <payload>.<signature>"))?;
+  let payload = URL_SAFE_NO_PAD
+    .decode(payload_b64)
+    .map_err(|e| anyhow::anyhow!("invalid receipt base64url: {e}"))?;
+  let receipt: DeactivationReceipt = serde_json::from_slice(&payload)
+    .map_err(|e| anyhow::anyhow!("invalid receipt json: {e}"))?;
+
+  let install_key: [u8; 32] = URL_SAFE_NO_PAD
+    .decode(&receipt.install_key)
+    .map_err(|e| anyhow::anyhow!("invalid receipt install_key: {e}"))?
+    .try_into()
+    .map_err(|_| anyhow::anyhow!("invalid receipt install_key"))?;
+  let signature: [u8; 64] = URL_SAFE_NO_PAD
+    .decode(sig_b64)
+    .map_err(|e| anyhow::anyhow!("invalid receipt signature base64url: {e}"))?
+    .try_into()
+    .map_err(|_| anyhow::anyhow!("invalid receipt signature length"))?;
+  VerifyingKey::from_bytes(&install_key)?
+    .verify_strict(&payload, &ed25519_dalek::Signature::from_bytes(&signature))
+    .map_err(|_| anyhow::anyhow!("receipt signature does not verify"))?;
+
+  if receipt.kind != RECEIPT_KIND {
+    anyhow::bail!("not a deactivation receipt: {}", receipt.kind);
+  }
+  if receipt.app_id != EXPECTED_APP_ID {
+    anyhow::bail!("receipt is for another app: {}", receipt.app_id);
+  }
+  if hex::encode(Sha256::digest(receipt.license.as_bytes())) != receipt.license_hash {
+    anyhow::bail!("receipt license_hash does not match its license");
+  }
+  Ok(receipt)
+}
+
+/// A license for `activation_code` replacing the one in `receipt`: same type, same expiry.
+fn reissue_license(
+  sk: &SigningKey,
+  receipt: &DeactivationReceipt,
+  activation_code: &str,
+) -> anyhow::Result<IssuedLicense> {
+  let trusted = [sk.verifying_key(), parse_public_key_pem(APP_PUBLIC_KEY_PEM)?];
+  let (_, payload, signer) = decode_license(&receipt.license, &trusted)?;
+  if signer.is_none() {
+    anyhow::bail!("the deactivated license was not signed by this key or the app's key");
+  }
+  let field = |name: &str| payload.get(name).and_then(serde_json::Value::as_str);
+  if field("pib_hash") != Some(receipt.pib_hash.as_str()) {
+    anyhow::bail!("receipt pib_hash does not match the deactivated license");
+  }
+
+  let now = OffsetDateTime::now_utc().replace_nanosecond(0)?;
+  let (license_type, valid_until) = match field("license_type") {
+    Some("LIFETIME") => (LicenseType::Lifetime, None),
+    Some("YEARLY") => {
+      let until = field("valid_until")
+        .ok_or_else(|| anyhow::anyhow!("the deactivated yearly license has no valid_until"))?;
+      let until_time =
+        OffsetDateTime::parse(until, &time::format_description::well_known::Rfc3339)?;
+      if until_time <= now {
+        anyhow::bail!("the deactivated license expired on {until}; issue a new one instead");
+      }
+      (LicenseType::Yearly, Some(until.to_string()))
+    }
+    other => anyhow::bail!("unknown license type in the deactivated license: {other:?}"),
+  };
+  sign_license(sk, activation_code, license_type, valid_until, now)
+}
+
+/// Matches the app's key id: the first 8 bytes of the public key in hex.
+fn key_id(vk: &VerifyingKey) -> String {
+  hex::encode(&vk.to_bytes()[..8])
+}
+
+/// PEM (SPKI) for `PUBLIC_KEYS` in the app on stdout, the kid on stderr.
+fn print_public_key(vk: &VerifyingKey) -> anyhow::Result<()> {
+  eprintln!("kid: {}", key_id(vk));
+  print!("{}", public_key_pem(vk)?);
+  Ok(())
+}
+
+fn public_key_pem(vk: &VerifyingKey) -> anyhow::Result<String> {
+  let mut der = Vec::with_capacity(44);
+  der.extend_from_slice(&ED25519_SPKI_PREFIX);
+  der.extend_from_slice(&vk.to_bytes());
+
+  let b64 = STANDARD.encode(der);
+  let mut pem = String::from("-----BEGIN PUBLIC KEY-----\n");
+  for chunk in b64.as_bytes().chunks(64) {
+    pem.push_str(std::str::from_utf8(chunk)?);
+    pem.push('\n');
+  }
+  pem.push_str("-----END PUBLIC KEY-----\n");
+  Ok(pem)
+}
+
+impl KeySource {
+  fn signing_key(&self) -> anyhow::Result<SigningKey> {
+    if let Some(path) = &self.key_file {
+      return signing_key_from_file(path);
+    }
+    if self.dev {
+      eprintln!("################################################################");
+      eprintln!("# WARNING: signing with the DEVELOPMENT key (--dev).           #");
+      eprintln!("# Its seed is public in this repository; anyone can forge      #");
+      eprintln!("# licenses signed with it. Never give these to customers.      #");
+      eprintln!("################################################################");
+      return signing_key_from_seed_hex(DEV_PRIVATE_KEY_SEED_HEX);
+    }
+    match std::env::var(SIGNING_KEY_ENV) {
+      Ok(seed_hex) => {
+        let seed_hex = Zeroizing::new(seed_hex);
+        signing_key_from_seed_hex(seed_hex.trim())
+          .map_err(|e| anyhow::anyhow!("invalid {SIGNING_KEY_ENV}: {e}"))
+      }
+      Err(_) => anyhow::bail!(
+        "no signing key: pass --key-file <path>, set {SIGNING_KEY_ENV}, or use --dev for local testing"
+      ),
+    }
+  }
+}
+
+fn signing_key_from_file(path: &Path) -> anyhow::Result<SigningKey> {
+  let text = Zeroizing::new(
+    std::fs::read_to_string(path)
+      .map_err(|e| anyhow::anyhow!("failed to read key file {}: {e}", path.display()))?,
+  );
+  let text = text.trim();
+  if !text.starts_with('{') {
+    return signing_key_from_seed_hex(text);
+  }
+  let file: EncryptedKeyFile = serde_json::from_str(text)
+    .map_err(|e| anyhow::anyhow!("invalid encrypted key file {}: {e}", path.display()))?;
+  let passphrase = Zeroizing::new(rpassword::prompt_password(format!(
+    "Passphrase for {}: ",
+    path.display()
+  ))?);
+  let seed = decrypt_seed(&file, &passphrase)?;
+  Ok(SigningKey::from_bytes(&seed))
+}
+
+fn signing_key_from_seed_hex(seed_hex: &str) -> anyhow::Result<SigningKey> {
+  let seed = Zeroizing::new(hex::decode(seed_hex)?);
+  let seed: [u8; 32] = seed
+    .as_slice()
+    .try_into()
+    .map_err(|_| anyhow::anyhow!("seed must be 32 bytes"))?;
+  Ok(SigningKey::from_bytes(&seed))
+}
+
+fn prompt_new_passphrase() -> anyhow::Result<Zeroizing<String>> {
+  let passphrase = Zeroizing::new(rpassword::prompt_password("New passphrase: ")?);
+  if passphrase.is_empty() {
+    anyhow::bail!("passphrase must not be empty");
+  }
+  let again = Zeroizing::new(rpassword::prompt_password("Repeat passphrase: ")?);
+  if *passphrase != *again {
+    anyhow::bail!("passphrases do not match");
+  }
+  Ok(passphrase)
+}
+
+fn derive_key(
+  passphrase: &str,
+  salt: &[u8],
+  params: argon2::Params,
+) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+  let mut key = Zeroizing::new([0u8; 32]);
+  Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+    .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+    .map_err(|e| anyhow::anyhow!("key derivation failed: {e}"))?;
+  Ok(key)
+}
+
+fn encrypt_seed(seed: &[u8; 32], passphrase: &str) -> anyhow::Result<EncryptedKeyFile> {
+  let mut salt = [0u8; 16];
+  let mut nonce = [0u8; 12];
+  OsRng.fill_bytes(&mut salt);
+  OsRng.fill_bytes(&mut nonce);
+
+  let params = argon2::Params::default();
+  let key = derive_key(passphrase, &salt, params.clone())?;
+  let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+    .encrypt(Nonce::from_slice(&nonce), seed.as_slice())
+    .map_err(|_| anyhow::anyhow!("encryption failed"))?;
+
+  Ok(EncryptedKeyFile {
+    format: ENCRYPTED_KEY_FORMAT.to_string(),
+    version: ENCRYPTED_KEY_VERSION,
+    m_cost: params.m_cost(),
+    t_cost: params.t_cost(),
+    p_cost: params.p_cost(),
+    salt: STANDARD.encode(salt),
+    nonce: STANDARD.encode(nonce),
+    ciphertext: STANDARD.encode(ciphertext),
+  })
+}
+
+fn decrypt_seed(file: &EncryptedKeyFile, passphrase: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+  if file.format != ENCRYPTED_KEY_FORMAT || file.version != ENCRYPTED_KEY_VERSION {
+    anyhow::bail!("unsupported key file format {} v{}", file.format, file.version);
+  }
+  let params = argon2::Params::new(file.m_cost, file.t_cost, file.p_cost, None)
+    .map_err(|e| anyhow::anyhow!("invalid key file parameters: {e}"))?;
+  let salt = STANDARD.decode(&file.salt)?;
+  let nonce = STANDARD.decode(&file.nonce)?;
+  if nonce.len() != 12 {
+    anyhow::bail!("invalid key file nonce");
+  }
+  let key = derive_key(passphrase, &salt, params)?;
+  let seed = Zeroizing::new(
+    ChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+      .decrypt(Nonce::from_slice(&nonce), STANDARD.decode(&file.ciphertext)?.as_slice())
+      .map_err(|_| anyhow::anyhow!("wrong passphrase or corrupted key file"))?,
+  );
+  let mut out = Zeroizing::new([0u8; 32]);
+  if seed.len() != out.len() {
+    anyhow::bail!("seed must be 32 bytes");
+  }
+  out.copy_from_slice(&seed);
+  Ok(out)
+}
+
+/// Creates `path` (failing if it exists) so that only the owner can read it.
+fn write_private_file(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options
+    .open(path)
+    .map_err(|e| anyhow::anyhow!("failed to create {}: {e}", path.display()))?;
+  file.write_all(contents)?;
+  file.write_all(b"\n")?;
+  Ok(())
+}
+
+/// The app's license verifier, built into the tests so that issued licenses are checked by the
+/// code that reads them.
+#[cfg(test)]
+#[allow(dead_code)]
+#[rustfmt::skip]
+#[path = "../../src-tauri/src/license"]
+mod license {
+  pub mod crypto;
+  pub mod license_payload;
+  pub mod license_validator;
+}
+
+#[cfg(test)]
+mod license_activation {
+  pub(crate) const APP_ID: &str = crate::EXPECTED_APP_ID;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PIB: &str = "101134702";
+
+  fn dev_key() -> SigningKey {
+    signing_key_from_seed_hex(DEV_PRIVATE_KEY_SEED_HEX).unwrap()
+  }
+
+  /// An activation code as the app builds it for `pib_hash`.
+  fn activation_code(pib_hash: &str) -> String {
+    let payload = serde_json::json!({
+      "pib_hash": pib_hash,
+      "issued_at": 1_700_000_000,
+      "nonce": "bm9uY2U",
+      "app_id": EXPECTED_APP_ID,
+    });
+    URL_SAFE_NO_PAD.encode(payload.to_string())
+  }
+
+  fn install_key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32])
+  }
+
+  /// A receipt as the app's `deactivate_license` writes it for `install_key()`, signed by
+  /// `signer`.
+  fn receipt(license: &str, pib_hash: &str, signer: &SigningKey) -> String {
+    let payload = serde_json::json!({
+      "kind": RECEIPT_KIND,
+      "app_id": EXPECTED_APP_ID,
+      "pib_hash": pib_hash,
+      "license_hash": hex::encode(Sha256::digest(license.as_bytes())),
+      "license": license,
+      "deactivated_at": "2025-01-01T00:00:00Z",
+      "install_key": URL_SAFE_NO_PAD.encode(install_key().verifying_key().to_bytes()),
+    })
+    .to_string();
+    let signature = signer.sign(payload.as_bytes()).to_bytes();
+    format!(
+      "{}.{}",
+      URL_SAFE_NO_PAD.encode(payload),
+      URL_SAFE_NO_PAD.encode(signature)
+    )
+  }
+
+  fn rfc3339(t: OffsetDateTime) -> String {
+    t.format(&time::format_description::well_known::Rfc3339)
+      .unwrap()
+  }
+
+  #[test]
+  fn license_for_a_salted_pib_hash_verifies() {
+    let sk = dev_key();
+    let issued = issue_license(&sk, &activation_code(&pib_hash(PIB)), LicenseKind::Yearly).unwrap();
+    assert_eq!(issued.pib_hash, pib_hash(PIB));
+    verify_license(&issued.license, &[sk.verifying_key()], Some(PIB)).unwrap();
+  }
+
+  #[test]
+  fn license_for_a_legacy_pib_hash_is_still_accepted() {
+    let sk = dev_key();
+    let code = activation_code(&legacy_pib_hash(PIB));
+    let issued = issue_license(&sk, &code, LicenseKind::Lifetime).unwrap();
+    verify_license(&issued.license, &[sk.verifying_key()], Some(PIB)).unwrap();
+  }
+
+  #[test]
+  fn license_for_another_pib_is_rejected() {
+    let sk = dev_key();
+    let issued = issue_license(&sk, &activation_code(&pib_hash(PIB)), LicenseKind::Yearly).unwrap();
+    let err =
+      verify_license(&issued.license, &[sk.verifying_key()], Some("100000024")).unwrap_err();
+    assert!(err.to_string().contains("different PIB"), "{err}");
+  }
+
+  #[test]
+  fn receipt_signed_by_another_key_is_rejected() {
+    let sk = dev_key();
+    let issued = issue_license(&sk, &activation_code(&pib_hash(PIB)), LicenseKind::Yearly).unwrap();
+    let text = receipt(
+      &issued.license,
+      &issued.pib_hash,
+      &SigningKey::from_bytes(&[8; 32]),
+    );
+    let err = read_deactivation_receipt(&text).unwrap_err();
+    assert!(err.to_string().contains("does not verify"), "{err}");
+  }
+
+  #[test]
+  fn receipt_for_another_pib_is_not_reissued() {
+    let sk = dev_key();
+    let issued = issue_license(&sk, &activation_code(&pib_hash(PIB)), LicenseKind::Yearly).unwrap();
+    let text = receipt(&issued.license, &pib_hash("100000024"), &install_key());
+    let receipt = read_deactivation_receipt(&text).unwrap();
+    let err = reissue_license(&sk, &receipt, &activation_code(&pib_hash(PIB))).unwrap_err();
+    assert!(err.to_string().contains("pib_hash does not match"), "{err}");
+  }
+
+  #[test]
+  fn expired_yearly_license_is_not_reissued() {
+    let sk = dev_key();
+    let issued_at = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap() - Duration::days(400);
+    let until = rfc3339(issued_at + Duration::days(365));
+    let code = activation_code(&pib_hash(PIB));
+    let expired = sign_license(&sk, &code, LicenseType::Yearly, Some(until), issued_at).unwrap();
+    let text = receipt(&expired.license, &expired.pib_hash, &install_key());
+    let receipt = read_deactivation_receipt(&text).unwrap();
+    let err = reissue_license(&sk, &receipt, &code).unwrap_err();
+    assert!(err.to_string().contains("expired"), "{err}");
+  }
+
+  #[test]
+  fn reissued_license_keeps_the_type_and_expiry() {
+    let sk = dev_key();
+    let issued_at = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap() - Duration::days(100);
+    let until = rfc3339(issued_at + Duration::days(365));
+    let old_code = activation_code(&pib_hash(PIB));
+    let old = sign_license(
+      &sk,
+      &old_code,
+      LicenseType::Yearly,
+      Some(until.clone()),
+      issued_at,
+    )
+    .unwrap();
+    let text = receipt(&old.license, &old.pib_hash, &install_key());
+    let receipt = read_deactivation_receipt(&text).unwrap();
+    assert_eq!(receipt.license, old.license);
+
+    let new_code = activation_code(&legacy_pib_hash(PIB));
+    let reissued = reissue_license(&sk, &receipt, &new_code).unwrap();
+    assert_eq!(reissued.license_type.as_str(), "YEARLY");
+    assert_eq!(reissued.valid_until.as_deref(), Some(until.as_str()));
+    assert_eq!(reissued.pib_hash, legacy_pib_hash(PIB));
+    let (_, payload, signer) = decode_license(&reissued.license, &[sk.verifying_key()]).unwrap();
+    assert!(signer.is_some());
+    assert_eq!(payload["valid_until"], until.as_str());
+  }
+  #[test]
+  fn encrypted_seed_round_trips() {
+    let seed = [42; 32];
+    let file = encrypt_seed(&seed, "correct horse").unwrap();
+    assert_eq!(*decrypt_seed(&file, "correct horse").unwrap(), seed);
+  }
+
+  #[test]
+  fn encrypted_seed_needs_the_passphrase() {
+    let file = encrypt_seed(&[42; 32], "correct horse").unwrap();
+    let err = decrypt_seed(&file, "battery staple").unwrap_err();
+    assert!(err.to_string().contains("wrong passphrase"), "{err}");
+  }
+
+  #[test]
+  fn tampered_encrypted_seed_is_rejected() {
+    let mut file = encrypt_seed(&[42; 32], "correct horse").unwrap();
+    let mut ciphertext = STANDARD.decode(&file.ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    file.ciphertext = STANDARD.encode(ciphertext);
+    let err = decrypt_seed(&file, "correct horse").unwrap_err();
+    assert!(err.to_string().contains("corrupted"), "{err}");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn private_file_is_readable_by_the_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path =
+      std::env::temp_dir().join(format!("license-generator-test-{}.key", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    write_private_file(&path, b"seed").unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(contents, "seed\n");
+    // Never overwrites an existing key.
+    write_private_file(&path, b"first").unwrap();
+    let err = write_private_file(&path, b"second").unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(err.to_string().contains("failed to create"), "{err}");
+  }
+
+  #[test]
+  fn batch_reports_bad_rows_and_issues_the_rest() {
+    let sk = dev_key();
+    let input = format!(
+      "customer_name,activation_code,type\n\
+       Prva,{},yearly\n\
+       Druga,not-a-code,yearly\n\
+       Treca,{},monthly\n\
+       Cetvrta,{},lifetime\n",
+      activation_code(&pib_hash(PIB)),
+      activation_code(&pib_hash(PIB)),
+      activation_code(&legacy_pib_hash(PIB)),
+    );
+    let batch = generate_batch(&sk, input.as_bytes()).unwrap();
+
+    let types: Vec<&str> = batch
+      .results
+      .iter()
+      .map(|r| r.license_type.as_str())
+      .collect();
+    assert_eq!(types, ["YEARLY", "", "", "LIFETIME"]);
+    let errors: Vec<&str> = batch.results.iter().map(|r| r.error.as_str()).collect();
+    assert!(errors[0].is_empty() && errors[3].is_empty(), "{errors:?}");
+    assert!(errors[1].contains("activation code"), "{errors:?}");
+    assert!(errors[2].contains("unknown license type"), "{errors:?}");
+    assert_eq!(batch.results[1].customer_name, "Druga");
+    let customers: Vec<&str> = batch.issued.iter().map(|(c, _)| c.as_str()).collect();
+    assert_eq!(customers, ["Prva", "Cetvrta"]);
+  }
+
+  #[test]
+  fn batch_without_a_customer_column_fails() {
+    let input = format!(
+      "activation_code,type\n{},yearly\n",
+      activation_code(&pib_hash(PIB))
+    );
+    let err = generate_batch(&dev_key(), input.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("customer_name"), "{err}");
+  }
+
+  #[test]
+  fn batch_licenses_verify_in_the_app() {
+    let sk = dev_key();
+    let input = format!(
+      "customer_name,activation_code,type\nPrva,{},yearly\n",
+      activation_code(&pib_hash(PIB))
+    );
+    let batch = generate_batch(&sk, input.as_bytes()).unwrap();
+    let license = &batch.results[0].license;
+    assert_eq!(license.split('.').count(), 3, "{license}");
+    assert!(license.starts_with(&format!("{}.", key_id(&sk.verifying_key()))));
+
+    let pem = public_key_pem(&sk.verifying_key()).unwrap();
+    let info = license::license_validator::verify_license(
+      license,
+      &[&pib_hash(PIB)],
+      &[&pem],
+      OffsetDateTime::now_utc(),
+    )
+    .unwrap();
+    assert!(info.is_valid, "{:?}", info.reason);
+    assert_eq!(info.license_type.as_deref(), Some("YEARLY"));
+    assert_eq!(
+      info.valid_until.as_deref(),
+      Some(batch.results[0].valid_until.as_str())
+    );
+    assert_eq!(info.key_id, Some(key_id(&sk.verifying_key())));
   }
-  let mut seed_bytes = [0u8; 32];
-  seed_bytes.copy_from_slice(&seed);
-  Ok(SigningKey::from_bytes(&seed_bytes))
 }
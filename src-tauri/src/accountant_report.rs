@@ -0,0 +1,315 @@
+//! Monthly exports emailed to the accountant: the invoice and expense CSVs of a period and the KPO
+//! book of each year it touches, attached to one message to `Settings::accountant_email`. The body
+//! sums up each report in the company's language.
+//!
+//! Every attachment is rendered before the message is built, so a report that fails (a KPO book
+//! with a missing exchange rate, say) sends nothing. A sent report is recorded in the audit log as
+//! `email.accountant_report`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, Message, MultiPart, SinglePart};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use crate::features::{self, Feature};
+use crate::kpo::{kpo_book_in_conn, render_kpo_csv};
+use crate::locales::{self, LocaleTable, NumberStyle};
+use crate::period_archive::{totals_by_currency, CurrencyTotals};
+use crate::{
+    audit, escape_html, expenses_for_export, invoices_for_export, is_bilingual_pdf_language, normalize_date_range,
+    read_settings_from_conn, render_expenses_csv, render_invoices_csv, reply_to_mailbox, send_email_via_smtp,
+    sender_mailbox, trial, validate_smtp_settings, validation_to_sql_error, DbState, Settings,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportKind {
+    InvoicesCsv,
+    ExpensesCsv,
+    /// The KPO book of every calendar year the period touches.
+    Kpo,
+}
+
+/// One attachment with the figures the email body gives for it.
+#[derive(Debug, Clone)]
+pub(crate) struct ReportPart {
+    pub kind: ReportKind,
+    /// Set for KPO books.
+    pub year: Option<i32>,
+    pub filename: String,
+    pub csv: String,
+    /// Entry count and amount per currency; empty when the report has no entries.
+    pub totals: BTreeMap<String, CurrencyTotals>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountantReportResult {
+    pub recipient: String,
+    pub from: String,
+    pub to: String,
+    pub attachments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportLabels {
+    subject: String,
+    greeting: String,
+    intro: String,
+    invoices: String,
+    expenses: String,
+    kpo: String,
+    count: String,
+    total: String,
+    no_entries: String,
+    closing: String,
+    generated_from_app: String,
+}
+
+static REPORT_LABELS: OnceLock<LocaleTable> = OnceLock::new();
+
+fn report_labels(lang: &str) -> Result<ReportLabels, String> {
+    let table = REPORT_LABELS
+        .get_or_init(|| locales::parse_table(include_str!("../../src/shared/accountantReportLabels.json")));
+    locales::resolve(table, lang).map_err(|e| format!("Invalid embedded src/shared/accountantReportLabels.json: {e}"))
+}
+
+/// Renders the chosen reports for `[from, to]`, in the order asked for; a kind asked for twice is
+/// attached once.
+pub(crate) fn report_parts(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    kinds: &[ReportKind],
+) -> Result<Vec<ReportPart>, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let opts = settings.csv_options;
+    let mut seen: Vec<ReportKind> = Vec::new();
+    let mut parts = Vec::new();
+    for &kind in kinds {
+        if seen.contains(&kind) {
+            continue;
+        }
+        seen.push(kind);
+        match kind {
+            ReportKind::InvoicesCsv => {
+                let (currency, invoices) = invoices_for_export(conn, from, to)?;
+                parts.push(ReportPart {
+                    kind,
+                    year: None,
+                    filename: format!("invoices_{from}_{to}.csv"),
+                    csv: render_invoices_csv(&currency, &invoices, &opts),
+                    totals: totals_by_currency(invoices.iter().map(|i| (i.currency.as_str(), i.total))),
+                });
+            }
+            ReportKind::ExpensesCsv => {
+                let (currency, expenses) = expenses_for_export(conn, from, to)?;
+                parts.push(ReportPart {
+                    kind,
+                    year: None,
+                    filename: format!("expenses_{from}_{to}.csv"),
+                    csv: render_expenses_csv(&currency, &expenses, &opts),
+                    totals: totals_by_currency(expenses.iter().map(|e| (e.currency.as_str(), e.amount))),
+                });
+            }
+            ReportKind::Kpo => {
+                let year = |d: &str| d.get(..4).and_then(|y| y.parse::<i32>().ok()).unwrap_or_default();
+                for y in year(from)..=year(to) {
+                    let book = kpo_book_in_conn(conn, y).map_err(|e| validation_to_sql_error(format!("KPO {y}: {e}")))?;
+                    let mut totals = BTreeMap::new();
+                    if !book.entries.is_empty() {
+                        totals.insert(
+                            book.currency.clone(),
+                            CurrencyTotals {
+                                count: book.entries.len(),
+                                amount: book.total,
+                            },
+                        );
+                    }
+                    parts.push(ReportPart {
+                        kind,
+                        year: Some(y),
+                        filename: format!("kpo-{y}.csv"),
+                        csv: render_kpo_csv(&book, &opts),
+                        totals,
+                    });
+                }
+            }
+        }
+    }
+    Ok(parts)
+}
+
+/// Subject and plain-text body in the settings language ("bilingual" writes Serbian).
+pub(crate) fn render_report_email(
+    settings: &Settings,
+    from: &str,
+    to: &str,
+    parts: &[ReportPart],
+) -> Result<(String, String), String> {
+    let lang = if is_bilingual_pdf_language(&settings.language) { "sr" } else { settings.language.as_str() };
+    let labels = report_labels(lang)?;
+    let numbers = NumberStyle::for_language(lang);
+    let company = settings.company_name.trim();
+    let fill = |s: &str| s.replace("{FROM}", from).replace("{TO}", to);
+
+    let mut text = format!("{}\n\n{}\n", labels.greeting, fill(&labels.intro));
+    for part in parts {
+        let title = match part.kind {
+            ReportKind::InvoicesCsv => labels.invoices.clone(),
+            ReportKind::ExpensesCsv => labels.expenses.clone(),
+            ReportKind::Kpo => labels.kpo.replace("{YEAR}", &part.year.unwrap_or_default().to_string()),
+        };
+        text.push_str(&format!("\n{title} ({})\n", part.filename));
+        if part.totals.is_empty() {
+            text.push_str(&format!("  {}\n", labels.no_entries));
+            continue;
+        }
+        let count: usize = part.totals.values().map(|t| t.count).sum();
+        text.push_str(&format!("  {}: {count}\n", labels.count));
+        for (currency, t) in &part.totals {
+            text.push_str(&format!("  {}: {} {currency}\n", labels.total, numbers.money(t.amount)));
+        }
+    }
+    text.push_str(&format!("\n{}\n{company}\n\n{}\n", labels.closing, labels.generated_from_app));
+    let subject = match company {
+        "" => fill(&labels.subject),
+        _ => format!("{} – {company}", fill(&labels.subject)),
+    };
+    Ok((subject, text))
+}
+
+/// Sends the chosen reports for `[from, to]` to the accountant address from the settings.
+#[tauri::command]
+pub(crate) async fn email_report_to_accountant(
+    state: tauri::State<'_, DbState>,
+    from: String,
+    to: String,
+    report_kinds: Vec<ReportKind>,
+) -> Result<AccountantReportResult, AppError> {
+    state
+        .with_write("email_report_to_accountant_license", |conn| {
+            trial::require_license(conn)?;
+            features::require_feature(conn, Feature::EmailSending)
+        })
+        .await?;
+    let (from, to) = normalize_date_range(&from, &to)?;
+    if report_kinds.is_empty() {
+        return Err(AppError::invalid("Choose at least one report to send."));
+    }
+    let (range_from, range_to) = (from.clone(), to.clone());
+    let (settings, parts) = state
+        .with_read("email_report_to_accountant_prepare", move |conn| {
+            Ok((read_settings_from_conn(conn)?, report_parts(conn, &range_from, &range_to, &report_kinds)?))
+        })
+        .await?;
+    validate_smtp_settings(&settings)?;
+    let recipient = settings.accountant_email.trim().to_string();
+    if recipient.is_empty() {
+        return Err(AppError::validation(
+            "accountantEmail",
+            "required",
+            "Accountant email address is not set (Settings → Email).",
+        ));
+    }
+    let mailbox: Mailbox = recipient
+        .parse()
+        .map_err(|_| format!("Invalid accountant email address: \"{recipient}\""))?;
+
+    let (subject, text) = render_report_email(&settings, &from, &to, &parts)?;
+    let html = format!(
+        "<!DOCTYPE html><html><body style=\"font-family:Arial,Helvetica,sans-serif;color:#111827;line-height:1.6;\"><p>{}</p></body></html>",
+        escape_html(&text).replace('\n', "<br />")
+    );
+    let mut builder = Message::builder().from(sender_mailbox(&settings)?).to(mailbox).subject(subject);
+    if let Some(reply_to) = reply_to_mailbox(&settings)? {
+        builder = builder.reply_to(reply_to);
+    }
+    let content_type = ContentType::parse("text/csv; charset=utf-8")
+        .map_err(|e| format!("Failed to build CSV attachment content type: {e}"))?;
+    let mut mixed = MultiPart::mixed().multipart(
+        MultiPart::alternative()
+            .singlepart(SinglePart::plain(text))
+            .singlepart(SinglePart::html(html)),
+    );
+    for part in &parts {
+        mixed = mixed.singlepart(Attachment::new(part.filename.clone()).body(part.csv.clone().into_bytes(), content_type.clone()));
+    }
+    let email = builder.multipart(mixed).map_err(|e| format!("Failed to build email: {e}"))?;
+
+    send_email_via_smtp(Arc::new(settings), email, "accountant_report").await?;
+    tracing::info!(target: "email", op = "accountant_report", from = %from, to = %to, attachments = parts.len(), "email sent");
+
+    let attachments: Vec<String> = parts.into_iter().map(|p| p.filename).collect();
+    let details = serde_json::json!({ "from": from, "to": to, "attachments": attachments });
+    let logged_recipient = recipient.clone();
+    state
+        .with_write("email_report_to_accountant_log", move |conn| {
+            audit::record(conn, "email.accountant_report", &logged_recipient, &details)
+        })
+        .await
+        .map_err(|e| format!("Email sent, but failed to record it: {e}"))?;
+
+    Ok(AccountantReportResult {
+        recipient,
+        from,
+        to,
+        attachments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::invoice;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let patch: crate::SettingsPatch =
+            serde_json::from_value(serde_json::json!({ "companyName": "Firma", "language": "sr" })).unwrap();
+        let _ = crate::apply_settings_patch(&conn, patch).unwrap();
+        conn
+    }
+
+    fn insert_invoice(conn: &Connection, id: &str, date: &str, currency: &str, total: f64) {
+        invoice(id).number(&format!("2025-{id}")).issued(date).amount(currency, total).one_line("Rad").insert(conn);
+    }
+
+    #[test]
+    fn summary_lists_totals_per_report() {
+        let conn = setup();
+        insert_invoice(&conn, "1", "2025-03-01", "RSD", 1000.0);
+        insert_invoice(&conn, "2", "2025-03-20", "RSD", 1500.5);
+        insert_invoice(&conn, "3", "2025-04-02", "RSD", 7.0);
+
+        let kinds = [ReportKind::InvoicesCsv, ReportKind::ExpensesCsv, ReportKind::Kpo, ReportKind::InvoicesCsv];
+        let parts = report_parts(&conn, "2025-03-01", "2025-03-31", &kinds).unwrap();
+        let names: Vec<&str> = parts.iter().map(|p| p.filename.as_str()).collect();
+        assert_eq!(names, ["invoices_2025-03-01_2025-03-31.csv", "expenses_2025-03-01_2025-03-31.csv", "kpo-2025.csv"]);
+        assert!(parts[0].csv.contains("2025-2"));
+
+        let settings = read_settings_from_conn(&conn).unwrap();
+        let (subject, text) = render_report_email(&settings, "2025-03-01", "2025-03-31", &parts).unwrap();
+        assert_eq!(subject, "Izveštaji za period 2025-03-01 – 2025-03-31 – Firma");
+        assert!(text.contains("Fakture (invoices_2025-03-01_2025-03-31.csv)\n  Broj stavki: 2\n  Ukupno: 2.500,50 RSD\n"), "{text}");
+        assert!(text.contains("Troškovi (expenses_2025-03-01_2025-03-31.csv)\n  Nema stavki u periodu.\n"), "{text}");
+        assert!(text.contains("KPO knjiga 2025 (kpo-2025.csv)\n  Broj stavki: 3\n  Ukupno: 2.507,50 RSD\n"), "{text}");
+    }
+
+    #[test]
+    fn a_failing_report_stops_the_whole_email() {
+        let conn = setup();
+        insert_invoice(&conn, "1", "2025-03-01", "EUR", 100.0);
+        let err = report_parts(&conn, "2025-03-01", "2025-03-31", &[ReportKind::InvoicesCsv, ReportKind::Kpo])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("KPO 2025") && err.contains("2025-1"), "{err}");
+    }
+}
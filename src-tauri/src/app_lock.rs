@@ -0,0 +1,311 @@
+//! Optional app PIN and lock screen.
+//!
+//! The PIN is stored in `app_meta` as an iterated, salted SHA-256 hash. While the app is locked
+//! every command except the few the lock screen needs is rejected before it runs (see `guarded`),
+//! so no data reaches the window until the PIN is entered; background workers keep running. The
+//! app starts locked whenever a PIN is set.
+//!
+//! After `FREE_ATTEMPTS` wrong PINs each further attempt has to wait, doubling from
+//! `FIRST_DELAY_SECS` up to `MAX_DELAY_SECS`. The counter lives in `app_meta`, so restarting the
+//! app doesn't reset it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use base64::Engine as _;
+use rand::RngCore;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::ipc::Invoke;
+use tauri::{Emitter, Manager, Runtime};
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::{app_meta_get, app_meta_set, validation_to_sql_error, DbState};
+
+const PIN_HASH_KEY: &str = "app_pin_hash";
+const FAILURES_KEY: &str = "app_pin_failures";
+const RETRY_AT_KEY: &str = "app_pin_retry_at";
+
+const HASH_ROUNDS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+
+const FREE_ATTEMPTS: i64 = 3;
+const FIRST_DELAY_SECS: i64 = 5;
+const MAX_DELAY_SECS: i64 = 15 * 60;
+
+/// Everything the lock screen calls; all other commands are rejected while locked.
+const ALLOWED_WHILE_LOCKED: [&str; 4] = ["get_app_lock_status", "verify_app_pin", "lock_app", "quit_app"];
+
+const LOCK_CHANGED_EVENT: &str = "app_lock://changed";
+
+/// Managed lock flag, checked by `guarded` on every command.
+pub(crate) struct AppLock {
+    locked: AtomicBool,
+}
+
+impl AppLock {
+    pub(crate) fn new(locked: bool) -> Self {
+        AppLock {
+            locked: AtomicBool::new(locked),
+        }
+    }
+
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    fn set_locked(&self, locked: bool) {
+        self.locked.store(locked, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppLockStatus {
+    pub pin_set: bool,
+    pub locked: bool,
+    /// Seconds until the next PIN attempt is accepted, after too many wrong ones.
+    pub retry_after_secs: Option<i64>,
+}
+
+/// Wraps the command handler so that commands are rejected while the app is locked.
+pub(crate) fn guarded<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let locked = invoke
+            .message
+            .webview()
+            .try_state::<AppLock>()
+            .is_some_and(|lock| lock.is_locked());
+        if locked && !ALLOWED_WHILE_LOCKED.contains(&invoke.message.command()) {
+            invoke.resolver.reject(AppError::AppLocked);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+/// `app_meta` keys that only the PIN commands may read or write.
+pub(crate) fn is_reserved_meta_key(key: &str) -> bool {
+    key.starts_with("app_pin_")
+}
+
+fn unix_now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if !(4..=12).contains(&pin.len()) || !pin.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("The PIN must be 4 to 12 digits.".to_string());
+    }
+    Ok(())
+}
+
+fn hash_pin(pin: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut digest: [u8; 32] = Sha256::new().chain_update(salt).chain_update(pin.as_bytes()).finalize().into();
+    for _ in 1..rounds {
+        digest = Sha256::new().chain_update(digest).chain_update(salt).finalize().into();
+    }
+    digest
+}
+
+/// `sha256:<rounds>:<salt>:<hash>`, salt and hash in base64.
+fn encode_pin(pin: &str, rounds: u32) -> String {
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let hash = hash_pin(pin, &salt, rounds);
+    format!("sha256:{rounds}:{}:{}", b64.encode(salt), b64.encode(hash))
+}
+
+fn pin_matches(stored: &str, pin: &str) -> bool {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let parts: Vec<&str> = stored.split(':').collect();
+    let [algo, rounds, salt, hash] = parts.as_slice() else { return false };
+    let (Ok(rounds), Ok(salt), Ok(hash)) = (rounds.parse::<u32>(), b64.decode(salt), b64.decode(hash)) else {
+        return false;
+    };
+    if *algo != "sha256" || rounds == 0 || hash.len() != 32 {
+        return false;
+    }
+    let actual = hash_pin(pin, &salt, rounds);
+    // Constant time, so the comparison doesn't leak how much of the hash matched.
+    actual.iter().zip(&hash).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn meta_i64(conn: &Connection, key: &str) -> Result<i64, rusqlite::Error> {
+    Ok(app_meta_get(conn, key)?.and_then(|v| v.parse().ok()).unwrap_or(0))
+}
+
+/// Wait after the `failures`-th wrong PIN: none for the first few, then 5 s doubling up to 15 min.
+fn delay_after(failures: i64) -> i64 {
+    if failures < FREE_ATTEMPTS {
+        return 0;
+    }
+    let doublings = (failures - FREE_ATTEMPTS).min(20) as u32;
+    FIRST_DELAY_SECS.saturating_mul(1 << doublings).min(MAX_DELAY_SECS)
+}
+
+pub(crate) fn pin_is_set(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    Ok(app_meta_get(conn, PIN_HASH_KEY)?.is_some_and(|h| !h.is_empty()))
+}
+
+fn retry_after_secs(conn: &Connection, now: i64) -> Result<Option<i64>, rusqlite::Error> {
+    let retry_at = meta_i64(conn, RETRY_AT_KEY)?;
+    Ok((retry_at > now).then_some(retry_at - now))
+}
+
+fn reset_failures(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM app_meta WHERE key IN (?1, ?2)", params![FAILURES_KEY, RETRY_AT_KEY])?;
+    Ok(())
+}
+
+/// Checks `pin`, counting a wrong one; fails without checking while the delay hasn't passed.
+fn check_pin_in_conn(conn: &Connection, pin: &str, now: i64) -> Result<bool, rusqlite::Error> {
+    let Some(stored) = app_meta_get(conn, PIN_HASH_KEY)?.filter(|h| !h.is_empty()) else {
+        return Ok(true);
+    };
+    if let Some(wait) = retry_after_secs(conn, now)? {
+        return Err(validation_to_sql_error(format!("Too many wrong PINs. Try again in {wait} s.")));
+    }
+    if pin_matches(&stored, pin) {
+        reset_failures(conn)?;
+        return Ok(true);
+    }
+    let failures = meta_i64(conn, FAILURES_KEY)? + 1;
+    app_meta_set(conn, FAILURES_KEY, &failures.to_string())?;
+    let delay = delay_after(failures);
+    if delay > 0 {
+        app_meta_set(conn, RETRY_AT_KEY, &(now + delay).to_string())?;
+    }
+    Ok(false)
+}
+
+fn status_in_conn(conn: &Connection, locked: bool) -> Result<AppLockStatus, rusqlite::Error> {
+    Ok(AppLockStatus {
+        pin_set: pin_is_set(conn)?,
+        locked,
+        retry_after_secs: retry_after_secs(conn, unix_now())?,
+    })
+}
+
+async fn emit_status(app: &tauri::AppHandle, state: &DbState) {
+    let locked = app.try_state::<AppLock>().is_some_and(|lock| lock.is_locked());
+    if let Ok(status) = state.with_read("app_lock_status", move |conn| status_in_conn(conn, locked)).await {
+        let _ = app.emit(LOCK_CHANGED_EVENT, status);
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_app_lock_status(
+    state: tauri::State<'_, DbState>,
+    lock: tauri::State<'_, AppLock>,
+) -> Result<AppLockStatus, AppError> {
+    let locked = lock.is_locked();
+    state.with_read("get_app_lock_status", move |conn| status_in_conn(conn, locked)).await
+}
+
+/// Sets or replaces the PIN. The app stays unlocked until `lock_app` or the next start.
+#[tauri::command]
+pub(crate) async fn set_app_pin(state: tauri::State<'_, DbState>, app: tauri::AppHandle, pin: String) -> Result<(), AppError> {
+    validate_pin(&pin)?;
+    state
+        .with_write("set_app_pin", move |conn| {
+            app_meta_set(conn, PIN_HASH_KEY, &encode_pin(&pin, HASH_ROUNDS))?;
+            reset_failures(conn)
+        })
+        .await?;
+    emit_status(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn clear_app_pin(
+    state: tauri::State<'_, DbState>,
+    lock: tauri::State<'_, AppLock>,
+    app: tauri::AppHandle,
+) -> Result<(), AppError> {
+    state
+        .with_write("clear_app_pin", |conn| {
+            conn.execute("DELETE FROM app_meta WHERE key = ?1", params![PIN_HASH_KEY])?;
+            reset_failures(conn)
+        })
+        .await?;
+    lock.set_locked(false);
+    emit_status(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn lock_app(
+    state: tauri::State<'_, DbState>,
+    lock: tauri::State<'_, AppLock>,
+    app: tauri::AppHandle,
+) -> Result<(), AppError> {
+    if !state.with_read("lock_app", pin_is_set).await? {
+        return Err(AppError::invalid("Set a PIN before locking the app."));
+    }
+    lock.set_locked(true);
+    emit_status(&app, &state).await;
+    Ok(())
+}
+
+/// Unlocks the app when `pin` is right; `false` for a wrong PIN.
+#[tauri::command]
+pub(crate) async fn verify_app_pin(
+    state: tauri::State<'_, DbState>,
+    lock: tauri::State<'_, AppLock>,
+    app: tauri::AppHandle,
+    pin: String,
+) -> Result<bool, AppError> {
+    let ok = state
+        .with_write("verify_app_pin", move |conn| check_pin_in_conn(conn, &pin, unix_now()))
+        .await?;
+    if ok {
+        lock.set_locked(false);
+        emit_status(&app, &state).await;
+    }
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrong_pins_are_counted_and_delayed_until_the_right_one() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        assert!(check_pin_in_conn(&conn, "0000", 1_000).unwrap(), "no PIN set");
+        app_meta_set(&conn, PIN_HASH_KEY, &encode_pin("4821", 10)).unwrap();
+        assert!(pin_is_set(&conn).unwrap());
+
+        assert!(!check_pin_in_conn(&conn, "1111", 1_000).unwrap());
+        assert!(!check_pin_in_conn(&conn, "2222", 1_000).unwrap());
+        assert_eq!(retry_after_secs(&conn, 1_000).unwrap(), None);
+        assert!(!check_pin_in_conn(&conn, "3333", 1_000).unwrap());
+        assert_eq!(retry_after_secs(&conn, 1_000).unwrap(), Some(FIRST_DELAY_SECS));
+        let err = check_pin_in_conn(&conn, "4821", 1_002).unwrap_err();
+        assert!(err.to_string().contains("Try again in 3 s"), "{err}");
+
+        assert!(!check_pin_in_conn(&conn, "5555", 1_005).unwrap());
+        assert_eq!(retry_after_secs(&conn, 1_005).unwrap(), Some(2 * FIRST_DELAY_SECS));
+        assert!(check_pin_in_conn(&conn, "4821", 1_015).unwrap());
+        assert_eq!(meta_i64(&conn, FAILURES_KEY).unwrap(), 0);
+    }
+
+    #[test]
+    fn pin_hash_is_salted_and_rejects_malformed_values() {
+        let a = encode_pin("123456", 10);
+        assert_ne!(a, encode_pin("123456", 10));
+        assert!(pin_matches(&a, "123456"));
+        assert!(!pin_matches(&a, "123457"));
+        assert!(!pin_matches("123456", "123456"));
+        assert!(validate_pin("12a4").is_err());
+        assert!(validate_pin("123").is_err());
+    }
+}
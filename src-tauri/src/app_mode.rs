@@ -0,0 +1,187 @@
+//! Read-only mode, for a database that can't be written: another program (or a second instance)
+//! holds the write lock, or the file sits on a read-only volume or network share.
+//!
+//! `DbState` probes at startup and switches into read-only mode whenever a write fails with
+//! SQLITE_BUSY or SQLITE_READONLY. While read-only, `with_write` fails straight away with
+//! [`AppError::ReadOnly`] instead of waiting out the busy timeout, and `app_mode://changed` lets the
+//! window show a banner. `retry_writable` probes again and leaves read-only mode once the lock
+//! clears.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{Connection, DatabaseName, ErrorCode};
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::errors::AppError;
+use crate::{DbState, SQLITE_BUSY_TIMEOUT};
+
+const MODE_CHANGED_EVENT: &str = "app_mode://changed";
+
+/// How long the probe waits for the write lock; a holder that doesn't let go this fast usually
+/// won't within the normal busy timeout either.
+const PROBE_BUSY_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum ReadOnlyReason {
+    /// Another process holds the write lock.
+    Locked,
+    /// The file or its folder can't be written.
+    ReadOnlyFile,
+}
+
+impl ReadOnlyReason {
+    /// Key suffix in `errorMessages.json`.
+    pub(crate) fn key(self) -> &'static str {
+        match self {
+            ReadOnlyReason::Locked => "locked",
+            ReadOnlyReason::ReadOnlyFile => "readOnlyFile",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppMode {
+    pub read_only: bool,
+    pub reason: Option<ReadOnlyReason>,
+}
+
+/// Read-only flag shared by every clone of `DbState`.
+pub(crate) struct WriteMode {
+    read_only: Mutex<Option<ReadOnlyReason>>,
+    /// Set when startup ran read-only, so the startup writes still have to happen.
+    startup_writes_pending: AtomicBool,
+}
+
+impl WriteMode {
+    pub(crate) fn new(read_only: Option<ReadOnlyReason>) -> Self {
+        WriteMode {
+            read_only: Mutex::new(read_only),
+            startup_writes_pending: AtomicBool::new(read_only.is_some()),
+        }
+    }
+
+    pub(crate) fn read_only(&self) -> Option<ReadOnlyReason> {
+        self.read_only.lock().map(|r| *r).unwrap_or(None)
+    }
+
+    pub(crate) fn status(&self) -> AppMode {
+        let reason = self.read_only();
+        AppMode {
+            read_only: reason.is_some(),
+            reason,
+        }
+    }
+
+    /// Switches mode and tells the window when it actually changed.
+    pub(crate) fn set(&self, app: &tauri::AppHandle, reason: Option<ReadOnlyReason>) {
+        let Ok(mut current) = self.read_only.lock() else {
+            return;
+        };
+        if *current == reason {
+            return;
+        }
+        *current = reason;
+        drop(current);
+        match reason {
+            Some(reason) => tracing::warn!(target: "sqlite", ?reason, "database is read-only"),
+            None => tracing::info!(target: "sqlite", "database is writable again"),
+        }
+        let _ = app.emit(MODE_CHANGED_EVENT, self.status());
+    }
+}
+
+/// Whether `err` means the database can't be written right now.
+pub(crate) fn read_only_reason(err: &rusqlite::Error) -> Option<ReadOnlyReason> {
+    match err.sqlite_error_code()? {
+        ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => Some(ReadOnlyReason::Locked),
+        ErrorCode::ReadOnly => Some(ReadOnlyReason::ReadOnlyFile),
+        _ => None,
+    }
+}
+
+/// Takes and releases the write lock; `Ok(None)` when writes go through.
+pub(crate) fn probe_writable(conn: &Connection) -> Result<Option<ReadOnlyReason>, rusqlite::Error> {
+    // SQLite falls back to a read-only open when the file can't be written; taking the lock
+    // still works then, so ask first.
+    if conn.is_readonly(DatabaseName::Main)? {
+        return Ok(Some(ReadOnlyReason::ReadOnlyFile));
+    }
+    conn.busy_timeout(PROBE_BUSY_TIMEOUT)?;
+    let result = conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;");
+    conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
+    match result {
+        Ok(()) => Ok(None),
+        Err(e) => read_only_reason(&e).map(Some).ok_or(e),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_app_mode(db: tauri::State<'_, DbState>) -> Result<AppMode, AppError> {
+    Ok(db.mode.status())
+}
+
+/// Probes again; leaves read-only mode (and catches up on skipped startup writes) when writes work.
+#[tauri::command]
+pub(crate) async fn retry_writable(db: tauri::State<'_, DbState>) -> Result<AppMode, AppError> {
+    let conn = db.conn.clone();
+    let write_lock = db.write_lock.clone();
+    let mode = db.mode.clone();
+    let app = db.app.clone();
+    let reason = tauri::async_runtime::spawn_blocking(move || {
+        let _wg = write_lock.lock().map_err(|_| AppError::Database("write mutex poisoned".to_string()))?;
+        let guard = conn.lock().map_err(|_| AppError::Database("db mutex poisoned".to_string()))?;
+        let reason = probe_writable(&guard)?;
+        if reason.is_none() && mode.startup_writes_pending.swap(false, Ordering::SeqCst) {
+            crate::run_startup_writes(&app, &guard);
+        }
+        Ok::<_, AppError>(reason)
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))??;
+    db.mode.set(&db.app, reason);
+    Ok(db.mode.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pausaler-{label}-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn probe_sees_a_held_write_lock_and_a_read_only_file() {
+        let path = temp_db("app-mode");
+        let holder = Connection::open(&path).unwrap();
+        crate::configure_sqlite(&holder).unwrap();
+        holder.execute_batch("CREATE TABLE t (x INTEGER);").unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        crate::configure_sqlite(&conn).unwrap();
+        assert_eq!(probe_writable(&conn).unwrap(), None);
+
+        holder.execute_batch("BEGIN IMMEDIATE; INSERT INTO t VALUES (1);").unwrap();
+        assert_eq!(probe_writable(&conn).unwrap(), Some(ReadOnlyReason::Locked));
+        conn.busy_timeout(PROBE_BUSY_TIMEOUT).unwrap();
+        let err = conn.execute("INSERT INTO t VALUES (2)", []).unwrap_err();
+        assert_eq!(read_only_reason(&err), Some(ReadOnlyReason::Locked));
+        holder.execute_batch("COMMIT;").unwrap();
+        assert_eq!(probe_writable(&conn).unwrap(), None);
+        drop(holder);
+        drop(conn);
+
+        let reader = Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        assert_eq!(probe_writable(&reader).unwrap(), Some(ReadOnlyReason::ReadOnlyFile));
+        drop(reader);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+}
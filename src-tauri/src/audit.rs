@@ -0,0 +1,19 @@
+//! Append-only audit log for destructive or bulk operations (merges, forced deletes, …).
+
+use rusqlite::{params, Connection};
+
+use crate::now_iso;
+
+/// Records an audit entry; call it inside the same transaction as the change it describes.
+pub(crate) fn record(
+    conn: &Connection,
+    action: &str,
+    entity_id: &str,
+    details: &serde_json::Value,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO audit_log (createdAt, action, entityId, data_json) VALUES (?1, ?2, ?3, ?4)",
+        params![now_iso(), action, entity_id, details.to_string()],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! Automatic database backups: on startup, before migrations run, the database is copied into
+//! `backups/pausaler-YYYYMMDD-HHMMSS.db` when the newest copy is older than a day, and old copies
+//! are pruned to the configured retention. Any backup can be staged for restore on next start.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use serde::Serialize;
+use time::macros::format_description;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::errors::AppError;
+use crate::{now_iso_basic, resolve_app_data_root, RestoreStageResult, DEFAULT_AUTO_BACKUP_KEEP, SETTINGS_ID};
+
+pub(crate) const BACKUPS_DIR: &str = "backups";
+const MIN_BACKUP_INTERVAL: Duration = Duration::hours(24);
+
+/// Outcome of the startup backup, kept so the UI can show a failure it missed the event for.
+#[derive(Default)]
+pub(crate) struct AutoBackupState {
+    pub(crate) last_error: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupEntry {
+    pub name: String,
+    /// RFC 3339, UTC.
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupList {
+    /// Newest first.
+    pub backups: Vec<BackupEntry>,
+    /// Why the last startup backup failed, if it did.
+    pub last_error: Option<String>,
+}
+
+fn backup_file_name(at: OffsetDateTime) -> String {
+    let stamp = at
+        .format(format_description!("[year][month][day]-[hour][minute][second]"))
+        .unwrap_or_default();
+    format!("pausaler-{stamp}.db")
+}
+
+/// Creation time encoded in a backup file name; `None` for anything that isn't one.
+fn parse_backup_name(name: &str) -> Option<OffsetDateTime> {
+    let stamp = name.strip_prefix("pausaler-")?.strip_suffix(".db")?;
+    PrimitiveDateTime::parse(stamp, format_description!("[year][month][day]-[hour][minute][second]"))
+        .ok()
+        .map(|dt| dt.assume_utc())
+}
+
+fn list_backups_in(dir: &Path) -> Vec<(OffsetDateTime, BackupEntry)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut out: Vec<(OffsetDateTime, BackupEntry)> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let at = parse_backup_name(&name)?;
+            let meta = e.metadata().ok().filter(|m| m.is_file())?;
+            let created_at = at.format(&time::format_description::well_known::Rfc3339).unwrap_or_default();
+            Some((
+                at,
+                BackupEntry {
+                    name,
+                    created_at,
+                    size_bytes: meta.len(),
+                },
+            ))
+        })
+        .collect();
+    out.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    out
+}
+
+/// Reads the backup settings straight from the file. Runs before migrations, so a database that
+/// predates the columns (or can't be read) falls back to the defaults.
+fn read_backup_settings(conn: &Connection) -> (bool, i64) {
+    conn.query_row(
+        "SELECT autoBackupEnabled, autoBackupKeep FROM settings WHERE id = ?1",
+        params![SETTINGS_ID],
+        |r| Ok((r.get::<_, i64>(0)? != 0, r.get::<_, i64>(1)?)),
+    )
+    .optional()
+    .ok()
+    .flatten()
+    .unwrap_or((true, DEFAULT_AUTO_BACKUP_KEEP))
+}
+
+fn prune_backups(dir: &Path, keep: usize) -> Result<(), String> {
+    for (_, old) in list_backups_in(dir).into_iter().skip(keep) {
+        fs::remove_file(dir.join(&old.name)).map_err(|e| format!("Failed to delete old backup {}: {e}", old.name))?;
+    }
+    Ok(())
+}
+
+/// Backs up `db_path` into `dir` if enabled and due. Returns the new file, if one was written.
+pub(crate) fn run_startup_backup(db_path: &Path, dir: &Path, now: OffsetDateTime) -> Result<Option<PathBuf>, String> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("Failed to open database for backup: {e}"))?;
+    let (enabled, keep) = read_backup_settings(&conn);
+    if !enabled {
+        return Ok(None);
+    }
+    if let Some((newest, _)) = list_backups_in(dir).first() {
+        if now - *newest < MIN_BACKUP_INTERVAL {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create backups directory: {e}"))?;
+    let target = dir.join(backup_file_name(now));
+    let tmp = dir.join(".pausaler-backup.db.tmp");
+    let _ = fs::remove_file(&tmp);
+    // VACUUM INTO writes a consistent copy that includes anything still in the WAL.
+    conn.execute("VACUUM INTO ?1", params![tmp.to_string_lossy()])
+        .map_err(|e| format!("Failed to write backup: {e}"))?;
+    drop(conn);
+    fs::rename(&tmp, &target).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to move backup into place: {e}")
+    })?;
+
+    prune_backups(dir, usize::try_from(keep.max(1)).unwrap_or(1))?;
+    Ok(Some(target))
+}
+
+/// Automatic backups, newest first, plus the last startup failure.
+#[tauri::command]
+pub(crate) async fn list_backups(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AutoBackupState>,
+) -> Result<BackupList, AppError> {
+    let dir = resolve_app_data_root(&app)?.join(BACKUPS_DIR);
+    let last_error = state.last_error.lock().map_err(|_| "Backup state lock poisoned".to_string())?.clone();
+    Ok(BackupList {
+        backups: list_backups_in(&dir).into_iter().map(|(_, b)| b).collect(),
+        last_error,
+    })
+}
+
+/// Stages `name` from the backups folder for restore; it replaces the database on next start.
+#[tauri::command]
+pub(crate) async fn restore_backup(app: tauri::AppHandle, name: String) -> Result<RestoreStageResult, AppError> {
+    // Only names we generate are accepted, which also rules out path traversal.
+    if parse_backup_name(&name).is_none() {
+        return Err(AppError::invalid("Invalid backup name"));
+    }
+    let root = resolve_app_data_root(&app)?;
+    let source = root.join(BACKUPS_DIR).join(&name);
+    if !source.is_file() {
+        return Err(AppError::not_found("backup", name));
+    }
+
+    let restore_dir = root.join("restore");
+    fs::create_dir_all(&restore_dir).map_err(|e| e.to_string())?;
+    let staged_target = restore_dir.join("pausaler.db");
+    if staged_target.exists() {
+        let _ = fs::remove_file(&staged_target);
+    }
+    fs::copy(&source, &staged_target).map_err(|e| e.to_string())?;
+
+    let plan = serde_json::json!({
+        "backupName": name,
+        "stagedDbPath": staged_target.to_string_lossy().to_string(),
+        "createdAt": now_iso_basic(),
+    });
+    let plan_path = restore_dir.join("restore-plan.json");
+    fs::write(&plan_path, serde_json::to_vec(&plan).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+
+    Ok(RestoreStageResult {
+        staged_at: plan["createdAt"].as_str().unwrap_or("").to_string(),
+        requires_restart: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pausaler-{label}-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn startup_backup_runs_once_a_day_and_prunes_to_retention() {
+        let root = temp_dir("backups");
+        let db_path = root.join("pausaler.db");
+        let dir = root.join(BACKUPS_DIR);
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            crate::init_schema(&conn).unwrap();
+            crate::ensure_settings_row(&conn).unwrap();
+            conn.execute("UPDATE settings SET autoBackupKeep = 2", []).unwrap();
+        }
+
+        let t0 = datetime!(2025-03-01 08:00:00 UTC);
+        let first = run_startup_backup(&db_path, &dir, t0).unwrap().unwrap();
+        assert_eq!(first.file_name().unwrap(), "pausaler-20250301-080000.db");
+        let copy = Connection::open(&first).unwrap();
+        let keep: i64 = copy.query_row("SELECT autoBackupKeep FROM settings", [], |r| r.get(0)).unwrap();
+        assert_eq!(keep, 2);
+        drop(copy);
+
+        assert!(run_startup_backup(&db_path, &dir, t0 + Duration::hours(23)).unwrap().is_none());
+        run_startup_backup(&db_path, &dir, t0 + Duration::hours(24)).unwrap().unwrap();
+        run_startup_backup(&db_path, &dir, t0 + Duration::days(3)).unwrap().unwrap();
+        fs::write(dir.join("notes.txt"), "keep me").unwrap();
+
+        let names: Vec<_> = list_backups_in(&dir).into_iter().map(|(_, b)| b.name).collect();
+        assert_eq!(names, vec!["pausaler-20250304-080000.db", "pausaler-20250302-080000.db"]);
+        assert!(dir.join("notes.txt").exists());
+
+        Connection::open(&db_path)
+            .unwrap()
+            .execute("UPDATE settings SET autoBackupEnabled = 0", [])
+            .unwrap();
+        assert!(run_startup_backup(&db_path, &dir, t0 + Duration::days(10)).unwrap().is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn only_generated_names_are_backups() {
+        assert!(parse_backup_name("pausaler-20250301-080000.db").is_some());
+        assert!(parse_backup_name("../pausaler-20250301-080000.db").is_none());
+        assert!(parse_backup_name("pausaler-2025-03-01.db").is_none());
+        assert_eq!(backup_file_name(datetime!(2025-12-31 23:59:58 UTC)), "pausaler-20251231-235958.db");
+    }
+}
@@ -0,0 +1,228 @@
+//! Company bank accounts. A company usually has a dinar account and may have a foreign-currency
+//! (devizni) one; invoices print the account kept in their currency, or the default account when
+//! there is none. `Settings::bank_account` mirrors the default account's number for the code that
+//! only needs "the" account (setup checks, UBL export, older settings files).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings_validation::{check_bank_account, check_iban, check_swift, SettingsError};
+use crate::Settings;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BankAccount {
+    /// Domestic account in the `3-13-2` form; may be empty for an IBAN-only foreign account.
+    #[serde(default)]
+    pub account_number: String,
+    /// ISO code of the currency the account is kept in.
+    #[serde(default = "default_account_currency")]
+    pub currency: String,
+    #[serde(default)]
+    pub bank_name: String,
+    #[serde(default)]
+    pub iban: String,
+    #[serde(default)]
+    pub swift: String,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+fn default_account_currency() -> String {
+    "RSD".to_string()
+}
+
+/// Validates and normalizes the accounts from the settings form: account numbers get the
+/// control-number check, IBAN and SWIFT are checked when present, and exactly one account ends up
+/// as the default (the first one unless another is marked). Messages follow `lang`.
+pub(crate) fn normalize_bank_accounts(accounts: Vec<BankAccount>, lang: &str) -> Result<Vec<BankAccount>, String> {
+    let fail = |e: SettingsError, value: &str| e.message(lang, value);
+    let mut out = Vec::with_capacity(accounts.len());
+    for a in accounts {
+        let currency = a.currency.trim().to_ascii_uppercase();
+        if currency.len() != 3 || !currency.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(fail(SettingsError::CurrencyInvalid, &currency));
+        }
+        let account_number = match a.account_number.trim() {
+            "" => String::new(),
+            v => check_bank_account(v).map_err(|e| fail(e, v))?,
+        };
+        let iban = match a.iban.trim() {
+            "" => String::new(),
+            v => check_iban(v).map_err(|e| fail(e, v))?,
+        };
+        let swift = match a.swift.trim() {
+            "" => String::new(),
+            v => check_swift(v).map_err(|e| fail(e, v))?,
+        };
+        if account_number.is_empty() && iban.is_empty() {
+            return Err(fail(SettingsError::BankAccountRequired, ""));
+        }
+        out.push(BankAccount {
+            account_number,
+            currency,
+            bank_name: a.bank_name.trim().to_string(),
+            iban,
+            swift,
+            is_default: a.is_default,
+        });
+    }
+    let default_index = out.iter().position(|a| a.is_default).unwrap_or(0);
+    for (i, a) in out.iter_mut().enumerate() {
+        a.is_default = i == default_index;
+    }
+    Ok(out)
+}
+
+pub(crate) fn default_account(accounts: &[BankAccount]) -> Option<&BankAccount> {
+    accounts.iter().find(|a| a.is_default).or_else(|| accounts.first())
+}
+
+/// The account an invoice in `currency` is paid to: one kept in that currency (the default one
+/// if several are), otherwise the default account.
+pub(crate) fn account_for_currency<'a>(accounts: &'a [BankAccount], currency: &str) -> Option<&'a BankAccount> {
+    let currency = currency.trim();
+    let mut matching = accounts.iter().filter(|a| a.currency.eq_ignore_ascii_case(currency));
+    let first = matching.next();
+    first
+        .filter(|a| a.is_default)
+        .or_else(|| matching.find(|a| a.is_default))
+        .or(first)
+        .or_else(|| default_account(accounts))
+}
+
+/// Payment details printed on an invoice and in its email.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PaymentAccount {
+    pub account_number: String,
+    /// Only for foreign-currency invoices; a dinar invoice is paid to the domestic number.
+    pub iban: Option<String>,
+    pub swift: Option<String>,
+}
+
+/// The account `settings` give for an invoice in `currency`; falls back to `bank_account` for
+/// settings that have no account list yet.
+pub(crate) fn payment_account(settings: &Settings, currency: &str) -> PaymentAccount {
+    let Some(account) = account_for_currency(&settings.bank_accounts, currency) else {
+        return PaymentAccount {
+            account_number: settings.bank_account.trim().to_string(),
+            ..Default::default()
+        };
+    };
+    let foreign = !currency.trim().eq_ignore_ascii_case("RSD");
+    let non_empty = |v: &str| Some(v.trim().to_string()).filter(|v| foreign && !v.is_empty());
+    PaymentAccount {
+        account_number: account.account_number.trim().to_string(),
+        iban: non_empty(&account.iban),
+        swift: non_empty(&account.swift),
+    }
+}
+
+/// Value for the `bank_account` mirror: the default account's number, or its IBAN when it has none.
+pub(crate) fn default_account_number(accounts: &[BankAccount]) -> String {
+    default_account(accounts)
+        .map(|a| if a.account_number.is_empty() { a.iban.clone() } else { a.account_number.clone() })
+        .unwrap_or_default()
+}
+
+/// Applies a single-account update (setup wizard, settings files from before the account list):
+/// `number` replaces the default account's number, or becomes a default dinar account.
+pub(crate) fn set_default_account_number(accounts: &mut Vec<BankAccount>, number: &str) {
+    let number = number.trim();
+    match accounts.iter().position(|a| a.is_default).or_else(|| (!accounts.is_empty()).then_some(0)) {
+        Some(i) if number.is_empty() && accounts[i].iban.is_empty() => {
+            accounts.remove(i);
+            if let Some(first) = accounts.first_mut() {
+                first.is_default = true;
+            }
+        }
+        Some(i) => {
+            accounts[i].account_number = number.to_string();
+            accounts[i].is_default = true;
+        }
+        None if number.is_empty() => {}
+        None => accounts.push(BankAccount {
+            account_number: number.to_string(),
+            currency: default_account_currency(),
+            is_default: true,
+            ..Default::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(number: &str, currency: &str, is_default: bool) -> BankAccount {
+        BankAccount {
+            account_number: number.to_string(),
+            currency: currency.to_string(),
+            is_default,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_and_keeps_one_default() {
+        let accounts = vec![
+            account("160-123456-54", "rsd", false),
+            BankAccount {
+                iban: "rs35 2600 0560 1001 6113 79".to_string(),
+                swift: "aikbrs22".to_string(),
+                ..account("", "EUR", true)
+            },
+            account("160-0000000123456-54", "USD", true),
+        ];
+        let out = normalize_bank_accounts(accounts, "en").unwrap();
+        assert_eq!(out[0].account_number, "160-0000000123456-54");
+        assert_eq!(out[0].currency, "RSD");
+        assert_eq!((out[1].iban.as_str(), out[1].swift.as_str()), ("RS35260005601001611379", "AIKBRS22"));
+        assert_eq!(out.iter().map(|a| a.is_default).collect::<Vec<_>>(), [false, true, false]);
+        assert_eq!(default_account_number(&out), "RS35260005601001611379");
+
+        let err = normalize_bank_accounts(vec![account("160-123456-55", "RSD", true)], "en").unwrap_err();
+        assert!(err.contains("control digits"), "{err}");
+        assert!(normalize_bank_accounts(vec![account("", "EUR", false)], "en").is_err());
+        assert!(normalize_bank_accounts(vec![account("160-123456-54", "euro", false)], "en").is_err());
+    }
+
+    #[test]
+    fn picks_the_account_in_the_invoice_currency() {
+        let accounts = vec![
+            account("160-123456-54", "RSD", true),
+            account("160-111-11", "EUR", false),
+            account("160-222-22", "EUR", false),
+        ];
+        assert_eq!(account_for_currency(&accounts, "eur").unwrap().account_number, "160-111-11");
+        assert_eq!(account_for_currency(&accounts, "RSD").unwrap().account_number, "160-123456-54");
+        assert_eq!(account_for_currency(&accounts, "CHF").unwrap().account_number, "160-123456-54");
+        assert!(account_for_currency(&[], "RSD").is_none());
+
+        let mut accounts = accounts;
+        set_default_account_number(&mut accounts, "265-1-1");
+        assert_eq!(accounts[0].account_number, "265-1-1");
+        let mut empty = Vec::new();
+        set_default_account_number(&mut empty, "160-123456-54");
+        assert_eq!(empty, vec![account("160-123456-54", "RSD", true)]);
+
+        let mut settings = crate::default_settings();
+        settings.bank_account = "160-123456-54".to_string();
+        assert_eq!(payment_account(&settings, "EUR").account_number, "160-123456-54");
+        settings.bank_accounts = vec![
+            BankAccount {
+                iban: "RS35260005601001611379".to_string(),
+                swift: "AIKBRS22".to_string(),
+                ..account("160-123456-54", "RSD", true)
+            },
+            BankAccount {
+                iban: "RS35260005601001611379".to_string(),
+                swift: "AIKBRS22".to_string(),
+                ..account("", "EUR", false)
+            },
+        ];
+        assert_eq!(payment_account(&settings, "RSD").iban, None);
+        let eur = payment_account(&settings, "EUR");
+        assert_eq!((eur.account_number.as_str(), eur.swift.as_deref()), ("", Some("AIKBRS22")));
+    }
+}
@@ -0,0 +1,255 @@
+//! TypeScript types for the command inputs and outputs, generated from the Rust structs into
+//! `src/app/types/bindings.ts` so the frontend stops drifting from them.
+//!
+//! The types derive `JsonSchema`, which follows their serde attributes (renames, defaults,
+//! flatten, tagged enums); this module turns those schemas into TypeScript. Field names are the
+//! serialized ones: serde aliases such as `maticniBroj` or the camelCase spellings on the PDF
+//! payload are only accepted on input and never appear in the bindings.
+//!
+//! Debug builds rewrite the file on startup when it changed, and `bindings_are_up_to_date` fails
+//! while it is stale; `UPDATE_BINDINGS=1 cargo test bindings` regenerates it.
+
+use std::path::Path;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject, SingleOrVec};
+
+use crate::clients::{
+    ClientCsvMapping, ClientImportReport, CreateClientResult, DeleteClientResult, DuplicateClientGroup,
+    MergeClientsResult,
+};
+use crate::file_opener::ExportedFile;
+use crate::{
+    Client, CreatedInvoice, Expense, ExpensePatch, Invoice, InvoicePatch, InvoicePdfPayload, NewClient, NewExpense,
+    NewInvoice, SendInvoiceEmailInput, Settings, SettingsPatch,
+};
+
+const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/app/types/bindings.ts");
+
+const HEADER: &str = "// Generated from the Rust command types by src-tauri/src/bindings.rs. Do not edit by hand;\n\
+// run `UPDATE_BINDINGS=1 cargo test bindings` in src-tauri (debug builds also rewrite it on startup).\n";
+
+/// Command inputs and outputs; the types they reference are picked up on the way.
+fn register_types(gen: &mut SchemaGenerator) {
+    gen.subschema_for::<Settings>();
+    gen.subschema_for::<SettingsPatch>();
+    gen.subschema_for::<Client>();
+    gen.subschema_for::<NewClient>();
+    gen.subschema_for::<CreateClientResult>();
+    gen.subschema_for::<DeleteClientResult>();
+    gen.subschema_for::<MergeClientsResult>();
+    gen.subschema_for::<DuplicateClientGroup>();
+    gen.subschema_for::<ClientCsvMapping>();
+    gen.subschema_for::<ClientImportReport>();
+    gen.subschema_for::<Invoice>();
+    gen.subschema_for::<NewInvoice>();
+    gen.subschema_for::<InvoicePatch>();
+    gen.subschema_for::<CreatedInvoice>();
+    gen.subschema_for::<Expense>();
+    gen.subschema_for::<NewExpense>();
+    gen.subschema_for::<ExpensePatch>();
+    gen.subschema_for::<InvoicePdfPayload>();
+    gen.subschema_for::<SendInvoiceEmailInput>();
+    gen.subschema_for::<ExportedFile>();
+}
+
+/// The whole `bindings.ts`, types in name order.
+pub(crate) fn render() -> String {
+    let mut gen = SchemaSettings::draft07().into_generator();
+    register_types(&mut gen);
+    let mut definitions: Vec<_> = gen.definitions().iter().collect();
+    definitions.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut out = String::from(HEADER);
+    for (name, schema) in definitions {
+        out.push('\n');
+        render_definition(&mut out, name, schema);
+    }
+    out
+}
+
+/// Writes the bindings next to the frontend sources when they changed. Only for debug builds
+/// running from a checkout; elsewhere the folder doesn't exist and nothing happens.
+#[cfg(debug_assertions)]
+pub(crate) fn export() {
+    let path = Path::new(BINDINGS_PATH);
+    if !path.parent().is_some_and(Path::is_dir) {
+        return;
+    }
+    let rendered = render();
+    if std::fs::read_to_string(path).is_ok_and(|current| current.replace("\r\n", "\n") == rendered) {
+        return;
+    }
+    match std::fs::write(path, rendered) {
+        Ok(()) => tracing::info!(target: "startup", "regenerated TypeScript bindings"),
+        Err(e) => tracing::warn!(target: "startup", error = %e, "failed to write TypeScript bindings"),
+    }
+}
+
+fn render_definition(out: &mut String, name: &str, schema: &Schema) {
+    let Schema::Object(obj) = schema else {
+        out.push_str(&format!("export type {name} = {};\n", ts_type(schema)));
+        return;
+    };
+    push_doc(out, "", description(obj));
+    match obj.object.as_deref().filter(|o| !o.properties.is_empty() && obj.subschemas.is_none()) {
+        Some(object) => {
+            out.push_str(&format!("export interface {name} {{\n"));
+            for (prop, schema) in &object.properties {
+                if let Schema::Object(p) = schema {
+                    push_doc(out, "  ", description(p));
+                }
+                out.push_str(&format!("  {};\n", property(object, prop, schema)));
+            }
+            out.push_str("}\n");
+        }
+        None => out.push_str(&format!("export type {name} = {};\n", object_type(obj))),
+    }
+}
+
+fn description(obj: &SchemaObject) -> Option<&str> {
+    obj.metadata.as_ref()?.description.as_deref()
+}
+
+fn push_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    let Some(doc) = doc.map(str::trim).filter(|d| !d.is_empty()) else {
+        return;
+    };
+    if !doc.contains('\n') {
+        out.push_str(&format!("{indent}/** {doc} */\n"));
+        return;
+    }
+    out.push_str(&format!("{indent}/**\n"));
+    for line in doc.lines() {
+        out.push_str(&format!("{indent} *{}{}\n", if line.is_empty() { "" } else { " " }, line));
+    }
+    out.push_str(&format!("{indent} */\n"));
+}
+
+/// `name: T`, or `name?: T` for a field serde fills in when it is missing.
+fn property(object: &ObjectValidation, name: &str, schema: &Schema) -> String {
+    let is_ident = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let key = if is_ident { name.to_string() } else { format!("{name:?}") };
+    let optional = if object.required.contains(name) { "" } else { "?" };
+    format!("{key}{optional}: {}", ts_type(schema))
+}
+
+fn ts_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(obj) => object_type(obj),
+    }
+}
+
+fn object_type(obj: &SchemaObject) -> String {
+    if let Some(reference) = &obj.reference {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    if let Some(value) = &obj.const_value {
+        return value.to_string();
+    }
+    if let Some(values) = &obj.enum_values {
+        return union(values.iter().map(|v| v.to_string()));
+    }
+    if let Some(sub) = &obj.subschemas {
+        // A documented or defaulted field of a named type comes wrapped in a one-element `allOf`.
+        if let Some(all) = &sub.all_of {
+            return all.iter().map(|s| parenthesize(ts_type(s))).collect::<Vec<_>>().join(" & ");
+        }
+        if let Some(any) = sub.any_of.as_ref().or(sub.one_of.as_ref()) {
+            return union(any.iter().map(ts_type));
+        }
+    }
+    match &obj.instance_type {
+        None => "unknown".to_string(),
+        Some(SingleOrVec::Single(t)) => instance_type(t, obj),
+        Some(SingleOrVec::Vec(types)) => union(types.iter().map(|t| instance_type(t, obj))),
+    }
+}
+
+fn instance_type(t: &InstanceType, obj: &SchemaObject) -> String {
+    match t {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Integer | InstanceType::Number => "number".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => match obj.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => format!("{}[]", parenthesize(ts_type(item))),
+            Some(SingleOrVec::Vec(items)) => {
+                format!("[{}]", items.iter().map(ts_type).collect::<Vec<_>>().join(", "))
+            }
+            None => "unknown[]".to_string(),
+        },
+        InstanceType::Object => match obj.object.as_deref() {
+            Some(object) if !object.properties.is_empty() => {
+                let props: Vec<_> = object.properties.iter().map(|(n, s)| property(object, n, s)).collect();
+                format!("{{ {} }}", props.join("; "))
+            }
+            Some(ObjectValidation { additional_properties: Some(values), .. }) => {
+                format!("Record<string, {}>", ts_type(values))
+            }
+            _ => "Record<string, unknown>".to_string(),
+        },
+    }
+}
+
+/// `a | b`, flattening nested unions so `Option<Option<T>>` doesn't come out as `T | null | null`.
+fn union(members: impl Iterator<Item = String>) -> String {
+    let mut seen: Vec<String> = Vec::new();
+    for m in members {
+        let parts: Vec<String> = if m.contains(['{', '(']) {
+            vec![m]
+        } else {
+            m.split(" | ").map(str::to_string).collect()
+        };
+        for part in parts {
+            if !seen.contains(&part) {
+                seen.push(part);
+            }
+        }
+    }
+    seen.join(" | ")
+}
+
+fn parenthesize(t: String) -> String {
+    if t.contains(" | ") || t.contains(" & ") {
+        format!("({t})")
+    } else {
+        t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_are_up_to_date() {
+        let rendered = render();
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            std::fs::write(BINDINGS_PATH, &rendered).unwrap();
+        }
+        let on_disk = std::fs::read_to_string(BINDINGS_PATH).unwrap_or_default();
+        assert!(
+            on_disk.replace("\r\n", "\n") == rendered,
+            "src/app/types/bindings.ts is stale; run `UPDATE_BINDINGS=1 cargo test bindings` in src-tauri"
+        );
+    }
+
+    #[test]
+    fn fields_use_their_serialized_names() {
+        let rendered = render();
+        // Aliases are input-only; the canonical name is what serde writes.
+        assert!(rendered.contains("  registrationNumber?: string;"), "{rendered}");
+        assert!(!rendered.contains("maticni"));
+        // The PDF payload is snake_case with camelCase aliases.
+        assert!(rendered.contains("  issued_by?: string | null;"));
+        assert!(rendered.contains("  invoice_number: string;"));
+        // Flattened and tagged types.
+        assert!(rendered.contains("export interface CreatedInvoice {"));
+        assert!(rendered.contains("status: \"created\""));
+        assert!(rendered.contains("export type InvoiceStatus = \"DRAFT\" | \"SENT\" | \"PAID\" | \"CANCELLED\";"));
+    }
+}
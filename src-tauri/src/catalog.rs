@@ -0,0 +1,486 @@
+//! Catalog of the products and services billed over and over ("Izrada web sajta", hourly
+//! consulting, …). An invoice item picked from the catalog keeps the catalog id next to its own
+//! description, unit and price, so editing or deleting a catalog item never changes an invoice
+//! already written; the id only links the line back for the usage report.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::search::to_latin;
+use crate::{now_iso, normalize_ymd, validation_to_sql_error, DbState, Invoice, InvoiceItem};
+
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+const MAX_SEARCH_LIMIT: u32 = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogItem {
+    pub id: String,
+    pub name: String,
+    /// Unit printed on the invoice line ("kom", "sat", …).
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Price of one unit in the invoice currency.
+    pub unit_price: f64,
+    /// VAT rate in percent, for users in the VAT system; `None` when not applicable.
+    #[serde(default)]
+    pub vat_rate: Option<f64>,
+    /// Inactive items are kept for the usage report but not offered when writing an invoice.
+    pub active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCatalogItem {
+    pub name: String,
+    #[serde(default)]
+    pub unit: Option<String>,
+    pub unit_price: f64,
+    #[serde(default)]
+    pub vat_rate: Option<f64>,
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogItemPatch {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub unit: Option<Option<String>>,
+    #[serde(default)]
+    pub unit_price: Option<f64>,
+    #[serde(default)]
+    pub vat_rate: Option<Option<f64>>,
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+/// How one catalog item was billed in one currency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogUsage {
+    pub catalog_item_id: String,
+    /// Current catalog name, or the last invoiced description when the item was deleted.
+    pub name: String,
+    pub currency: String,
+    /// Invoice lines that used the item.
+    pub lines: usize,
+    /// Distinct invoices those lines are on.
+    pub invoices: usize,
+    pub quantity: f64,
+    /// Sum of the line totals (after line discounts).
+    pub total: f64,
+    /// Unit price weighted by quantity.
+    pub average_unit_price: f64,
+    pub last_billed: String,
+}
+
+fn clean_optional(v: Option<String>) -> Option<String> {
+    v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Lowercase Latin without diacritics, so "Računar", "racunar" and "рачунар" compare equal.
+fn fold(v: &str) -> String {
+    to_latin(&v.to_lowercase())
+        .chars()
+        .map(|c| match c {
+            'č' | 'ć' => 'c',
+            'š' => 's',
+            'ž' => 'z',
+            'đ' => 'd',
+            c => c,
+        })
+        .collect()
+}
+
+fn validate(conn: &Connection, item: &CatalogItem) -> Result<(), String> {
+    if item.name.is_empty() {
+        return Err("Name is required.".to_string());
+    }
+    if !item.unit_price.is_finite() || item.unit_price < 0.0 {
+        return Err("Price must not be negative.".to_string());
+    }
+    if item.vat_rate.is_some_and(|r| !r.is_finite() || !(0.0..=100.0).contains(&r)) {
+        return Err("VAT rate must be between 0 and 100.".to_string());
+    }
+    let name = fold(&item.name);
+    let taken = load_catalog(conn, true)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .any(|other| other.id != item.id && fold(&other.name) == name);
+    if taken {
+        return Err(format!("A catalog item named \"{}\" already exists.", item.name));
+    }
+    Ok(())
+}
+
+const CATALOG_COLUMNS: &str = "id, name, unit, unitPrice, vatRate, active, createdAt, updatedAt";
+
+fn catalog_from_row(r: &rusqlite::Row<'_>) -> Result<CatalogItem, rusqlite::Error> {
+    Ok(CatalogItem {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        unit: r.get(2)?,
+        unit_price: r.get(3)?,
+        vat_rate: r.get(4)?,
+        active: r.get::<_, i64>(5)? != 0,
+        created_at: r.get(6)?,
+        updated_at: r.get(7)?,
+    })
+}
+
+fn read_catalog_item(conn: &Connection, id: &str) -> Result<Option<CatalogItem>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {CATALOG_COLUMNS} FROM catalog_items WHERE id = ?1"),
+        params![id],
+        catalog_from_row,
+    )
+    .optional()
+}
+
+fn load_catalog(conn: &Connection, include_inactive: bool) -> Result<Vec<CatalogItem>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {CATALOG_COLUMNS} FROM catalog_items WHERE active = 1 OR ?1 ORDER BY name COLLATE NOCASE"
+    ))?;
+    let rows = stmt.query_map(params![include_inactive], catalog_from_row)?;
+    rows.collect()
+}
+
+fn write_catalog_item(conn: &Connection, item: &CatalogItem) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"INSERT INTO catalog_items (id, name, unit, unitPrice, vatRate, active, createdAt, updatedAt)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+           ON CONFLICT(id) DO UPDATE SET name=excluded.name, unit=excluded.unit,
+               unitPrice=excluded.unitPrice, vatRate=excluded.vatRate, active=excluded.active,
+               updatedAt=excluded.updatedAt"#,
+        params![
+            item.id,
+            item.name,
+            item.unit,
+            item.unit_price,
+            item.vat_rate,
+            item.active,
+            item.created_at,
+            item.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Active items whose name contains every word of `query`, names starting with the query first.
+pub(crate) fn search_catalog_in_conn(conn: &Connection, query: &str, limit: u32) -> Result<Vec<CatalogItem>, rusqlite::Error> {
+    let query = fold(query.trim());
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut hits: Vec<(bool, CatalogItem)> = load_catalog(conn, false)?
+        .into_iter()
+        .filter_map(|item| {
+            let name = fold(&item.name);
+            words
+                .iter()
+                .all(|w| name.contains(w))
+                .then(|| (!name.starts_with(&query), item))
+        })
+        .collect();
+    hits.sort_by_key(|(later, _)| *later);
+    Ok(hits
+        .into_iter()
+        .map(|(_, item)| item)
+        .take(limit.clamp(1, MAX_SEARCH_LIMIT) as usize)
+        .collect())
+}
+
+/// Fills the blank description and unit of items picked from the catalog. Anything the user
+/// typed on the line wins; an id that is no longer in the catalog is kept with its snapshot.
+pub(crate) fn apply_catalog_snapshots(conn: &Connection, items: &mut [InvoiceItem]) -> Result<(), rusqlite::Error> {
+    for item in items.iter_mut() {
+        item.catalog_item_id = clean_optional(item.catalog_item_id.take());
+        let Some(id) = item.catalog_item_id.as_deref() else {
+            continue;
+        };
+        let Some(entry) = read_catalog_item(conn, id)? else {
+            continue;
+        };
+        if item.description.trim().is_empty() {
+            item.description = entry.name;
+        }
+        if item.unit.as_deref().is_none_or(|u| u.trim().is_empty()) {
+            item.unit = entry.unit;
+        }
+    }
+    Ok(())
+}
+
+/// Lines of sent and paid invoices issued between `from` and `to` (inclusive, either open)
+/// that came from the catalog, per item and currency; most used first.
+pub(crate) fn catalog_usage_in_conn(
+    conn: &Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<CatalogUsage>, rusqlite::Error> {
+    let names: BTreeMap<String, String> = load_catalog(conn, true)?.into_iter().map(|c| (c.id, c.name)).collect();
+    let mut stmt = conn.prepare(
+        r#"SELECT data_json
+           FROM invoices
+           WHERE status IN ('SENT', 'PAID')
+             AND (?1 IS NULL OR issueDate >= ?1) AND (?2 IS NULL OR issueDate <= ?2)
+           ORDER BY issueDate ASC, createdAt ASC"#,
+    )?;
+    let rows = stmt.query_map(params![from, to], |r| r.get::<_, String>(0))?;
+
+    let mut usage: BTreeMap<(String, String), (CatalogUsage, BTreeSet<String>, f64)> = BTreeMap::new();
+    for json in rows {
+        let Ok(invoice) = serde_json::from_str::<Invoice>(&json?) else {
+            continue;
+        };
+        for item in &invoice.items {
+            let Some(id) = item.catalog_item_id.as_deref() else {
+                continue;
+            };
+            let (entry, invoices, billed) = usage
+                .entry((id.to_string(), invoice.currency.clone()))
+                .or_insert_with(|| {
+                    let usage = CatalogUsage {
+                        catalog_item_id: id.to_string(),
+                        name: String::new(),
+                        currency: invoice.currency.clone(),
+                        lines: 0,
+                        invoices: 0,
+                        quantity: 0.0,
+                        total: 0.0,
+                        average_unit_price: 0.0,
+                        last_billed: String::new(),
+                    };
+                    (usage, BTreeSet::new(), 0.0)
+                });
+            entry.name = names.get(id).cloned().unwrap_or_else(|| item.description.clone());
+            entry.lines += 1;
+            entry.quantity += item.quantity;
+            entry.total += item.total;
+            entry.last_billed = invoice.issue_date.clone();
+            invoices.insert(invoice.id.clone());
+            *billed += item.quantity * item.unit_price;
+        }
+    }
+
+    let mut out: Vec<CatalogUsage> = usage
+        .into_values()
+        .map(|(mut entry, invoices, billed)| {
+            entry.invoices = invoices.len();
+            entry.average_unit_price = if entry.quantity > 0.0 { round2(billed / entry.quantity) } else { 0.0 };
+            entry.quantity = round2(entry.quantity);
+            entry.total = round2(entry.total);
+            entry
+        })
+        .collect();
+    out.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.name.cmp(&b.name)));
+    Ok(out)
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[tauri::command]
+pub(crate) async fn list_catalog_items(
+    state: tauri::State<'_, DbState>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<CatalogItem>, AppError> {
+    state
+        .with_read("list_catalog_items", move |conn| load_catalog(conn, include_inactive.unwrap_or(false)))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_catalog_item(
+    state: tauri::State<'_, DbState>,
+    input: NewCatalogItem,
+) -> Result<CatalogItem, AppError> {
+    let now = now_iso();
+    let item = CatalogItem {
+        id: Uuid::new_v4().to_string(),
+        name: input.name.trim().to_string(),
+        unit: clean_optional(input.unit),
+        unit_price: input.unit_price,
+        vat_rate: input.vat_rate,
+        active: input.active.unwrap_or(true),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    state
+        .with_create_notify("create_catalog_item", move |conn, _| {
+            validate(conn, &item).map_err(validation_to_sql_error)?;
+            write_catalog_item(conn, &item)?;
+            Ok(item)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn update_catalog_item(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    patch: CatalogItemPatch,
+) -> Result<Option<CatalogItem>, AppError> {
+    state
+        .with_write("update_catalog_item", move |conn| {
+            let Some(mut item) = read_catalog_item(conn, &id)? else {
+                return Ok(None);
+            };
+            if let Some(v) = patch.name {
+                item.name = v.trim().to_string();
+            }
+            if let Some(v) = patch.unit {
+                item.unit = clean_optional(v);
+            }
+            if let Some(v) = patch.unit_price {
+                item.unit_price = v;
+            }
+            if let Some(v) = patch.vat_rate {
+                item.vat_rate = v;
+            }
+            if let Some(v) = patch.active {
+                item.active = v;
+            }
+            item.updated_at = now_iso();
+            validate(conn, &item).map_err(validation_to_sql_error)?;
+            write_catalog_item(conn, &item)?;
+            Ok(Some(item))
+        })
+        .await
+}
+
+/// Invoices that used the item keep their lines; only the link in the usage report is lost.
+#[tauri::command]
+pub(crate) async fn delete_catalog_item(state: tauri::State<'_, DbState>, id: String) -> Result<bool, AppError> {
+    state
+        .with_write("delete_catalog_item", move |conn| {
+            Ok(conn.execute("DELETE FROM catalog_items WHERE id = ?1", params![id])? > 0)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn search_catalog(
+    state: tauri::State<'_, DbState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<CatalogItem>, AppError> {
+    state
+        .with_read("search_catalog", move |conn| {
+            search_catalog_in_conn(conn, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn catalog_usage_report(
+    state: tauri::State<'_, DbState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<CatalogUsage>, AppError> {
+    let from = match from.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(d) => Some(normalize_ymd("From date", d)?),
+        None => None,
+    };
+    let to = match to.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(d) => Some(normalize_ymd("To date", d)?),
+        None => None,
+    };
+    state
+        .with_read("catalog_usage_report", move |conn| {
+            catalog_usage_in_conn(conn, from.as_deref(), to.as_deref())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, name: &str, price: f64, active: bool) -> CatalogItem {
+        CatalogItem {
+            id: id.to_string(),
+            name: name.to_string(),
+            unit: Some("kom".to_string()),
+            unit_price: price,
+            vat_rate: None,
+            active,
+            created_at: "t".to_string(),
+            updated_at: "t".to_string(),
+        }
+    }
+
+    #[test]
+    fn search_folds_script_and_diacritics_and_skips_inactive() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        write_catalog_item(&conn, &item("a", "Održavanje sajta", 5000.0, true)).unwrap();
+        write_catalog_item(&conn, &item("b", "Izrada web sajta", 25000.0, true)).unwrap();
+        write_catalog_item(&conn, &item("c", "Stari sajt", 1.0, false)).unwrap();
+
+        let ids = |q: &str| -> Vec<String> {
+            search_catalog_in_conn(&conn, q, 10).unwrap().into_iter().map(|i| i.id).collect()
+        };
+        assert_eq!(ids("sajt"), vec!["b", "a"]);
+        assert_eq!(ids("odrzavanje"), vec!["a"]);
+        assert_eq!(ids("САЈТ изр"), vec!["b"]);
+        assert_eq!(ids("izrada sajta"), vec!["b"]);
+
+        let duplicate = item("d", "izrada WEB sajta", 1.0, true);
+        assert!(validate(&conn, &duplicate).unwrap_err().contains("already exists"));
+    }
+
+    #[test]
+    fn usage_report_groups_billed_lines_per_item_and_currency() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        write_catalog_item(&conn, &item("web", "Izrada web sajta", 25000.0, true)).unwrap();
+        let line = |catalog: Option<&str>, qty: f64, price: f64| {
+            serde_json::json!({"id": "x", "description": "Stari opis", "quantity": qty, "unitPrice": price,
+                               "total": qty * price, "catalogItemId": catalog})
+        };
+        let invoices = [
+            ("i1", "SENT", "2025-01-10", "RSD", vec![line(Some("web"), 1.0, 20000.0), line(None, 1.0, 10.0)]),
+            ("i2", "PAID", "2025-02-10", "RSD", vec![line(Some("web"), 3.0, 30000.0), line(Some("web"), 1.0, 1.0)]),
+            ("i3", "DRAFT", "2025-02-11", "RSD", vec![line(Some("web"), 9.0, 1.0)]),
+            ("i4", "SENT", "2025-03-01", "EUR", vec![line(Some("gone"), 2.0, 100.0)]),
+        ];
+        for (id, status, date, currency, items) in invoices {
+            let json = serde_json::json!({
+                "id": id, "invoiceNumber": id, "clientId": "c", "clientName": "K", "issueDate": date,
+                "serviceDate": date, "status": status, "currency": currency, "items": items,
+                "subtotal": 0.0, "total": 0.0, "notes": "", "createdAt": "t"
+            });
+            conn.execute(
+                "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+                 VALUES (?1, ?1, 'c', ?2, ?3, ?4, 0, 't', ?5)",
+                params![id, date, status, currency, json.to_string()],
+            )
+            .unwrap();
+        }
+
+        let report = catalog_usage_in_conn(&conn, None, None).unwrap();
+        let rows: Vec<_> = report
+            .iter()
+            .map(|u| (u.catalog_item_id.as_str(), u.name.as_str(), u.currency.as_str(), u.lines, u.invoices))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![("web", "Izrada web sajta", "RSD", 3, 2), ("gone", "Stari opis", "EUR", 1, 1)]
+        );
+        assert_eq!((report[0].quantity, report[0].total, report[0].average_unit_price), (5.0, 110001.0, 22000.2));
+        assert_eq!(report[0].last_billed, "2025-02-10");
+
+        let january = catalog_usage_in_conn(&conn, Some("2025-01-01"), Some("2025-01-31")).unwrap();
+        assert_eq!((january.len(), january[0].lines), (1, 1));
+    }
+}
@@ -0,0 +1,890 @@
+//! Duplicate client detection (same PIB, same registration number (MB) or same name),
+//! merging of duplicates, guarded deletion and CSV import.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::csv_reader::{self, CsvTable};
+use crate::data_events::{DataEntity, DataOp};
+use crate::{audit, now_iso, tax_ids, read_client_from_conn, validation_to_sql_error, Client, DbState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateMatch {
+    Pib,
+    RegistrationNumber,
+    Name,
+}
+
+/// Result of `create_client`: either the new client or the existing one it collides with.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum CreateClientResult {
+    Created {
+        client: Box<Client>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Duplicate {
+        existing_id: String,
+        existing_name: String,
+        matched_on: DuplicateMatch,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateClientGroup {
+    pub matched_on: DuplicateMatch,
+    /// The normalized value the clients share.
+    pub key: String,
+    pub clients: Vec<Client>,
+}
+
+/// PIB and MB are compared by their digits only, so "100 000 001" matches "100000001".
+pub(crate) fn normalize_id_number(v: &str) -> String {
+    v.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+pub(crate) fn normalize_name(v: &str) -> String {
+    v.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+pub(crate) fn load_all_clients(conn: &Connection) -> Result<Vec<Client>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT data_json FROM clients ORDER BY createdAt")?;
+    let rows = stmt.query_map([], |r| r.get::<_, Option<String>>(0))?;
+    let mut out = Vec::new();
+    for json in rows {
+        if let Some(c) = json?.and_then(|j| serde_json::from_str::<Client>(&j).ok()) {
+            out.push(c);
+        }
+    }
+    Ok(out)
+}
+
+/// The first existing client with the same PIB or MB as the new one, if any.
+pub(crate) fn find_conflicting_client(
+    conn: &Connection,
+    pib: &str,
+    registration_number: &str,
+) -> Result<Option<(Client, DuplicateMatch)>, rusqlite::Error> {
+    let pib = normalize_id_number(pib);
+    let mb = normalize_id_number(registration_number);
+    if pib.is_empty() && mb.is_empty() {
+        return Ok(None);
+    }
+    for c in load_all_clients(conn)? {
+        if !pib.is_empty() && normalize_id_number(&c.pib) == pib {
+            return Ok(Some((c, DuplicateMatch::Pib)));
+        }
+        if !mb.is_empty() && normalize_id_number(&c.registration_number) == mb {
+            return Ok(Some((c, DuplicateMatch::RegistrationNumber)));
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn group_duplicates(clients: &[Client]) -> Vec<DuplicateClientGroup> {
+    let mut groups: BTreeMap<(DuplicateMatch, String), Vec<Client>> = BTreeMap::new();
+    for c in clients {
+        let keys = [
+            (DuplicateMatch::Pib, normalize_id_number(&c.pib)),
+            (DuplicateMatch::RegistrationNumber, normalize_id_number(&c.registration_number)),
+            (DuplicateMatch::Name, normalize_name(&c.name)),
+        ];
+        for key in keys.into_iter().filter(|(_, k)| !k.is_empty()) {
+            groups.entry(key).or_default().push(c.clone());
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, clients)| clients.len() > 1)
+        .map(|((matched_on, key), clients)| DuplicateClientGroup {
+            matched_on,
+            key,
+            clients,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeClientsResult {
+    pub client: Client,
+    pub invoices_moved: usize,
+}
+
+fn is_blank(v: &str) -> bool {
+    v.trim().is_empty()
+}
+
+/// Copies fields the kept client is missing from the removed one. The address (street, postal
+/// code, city) is taken as a whole so two half-addresses are never mixed.
+fn union_missing_fields(keep: &mut Client, other: &Client) {
+    if is_blank(&keep.registration_number) {
+        keep.registration_number = other.registration_number.clone();
+    }
+    if is_blank(&keep.pib) {
+        keep.pib = other.pib.clone();
+    }
+    if is_blank(&keep.address) && is_blank(&keep.postal_code) && is_blank(&keep.city) {
+        keep.address = other.address.clone();
+        keep.postal_code = other.postal_code.clone();
+        keep.city = other.city.clone();
+    }
+    if is_blank(&keep.email) {
+        keep.email = other.email.clone();
+    }
+    if keep.phone.is_none() {
+        keep.phone = other.phone.clone();
+    }
+    if keep.website.is_none() {
+        keep.website = other.website.clone();
+    }
+    if keep.default_currency.is_none() {
+        keep.default_currency = other.default_currency.clone();
+    }
+    if keep.preferred_language.is_none() {
+        keep.preferred_language = other.preferred_language.clone();
+    }
+    if keep.payment_terms_days.is_none() {
+        keep.payment_terms_days = other.payment_terms_days;
+    }
+    if keep.billing_email.is_none() {
+        keep.billing_email = other.billing_email.clone();
+    }
+    if keep.is_foreign && keep.country.is_none() {
+        keep.country = other.country.clone();
+    }
+}
+
+pub(crate) fn insert_client_row(conn: &Connection, c: &Client) -> Result<(), rusqlite::Error> {
+    let json = serde_json::to_string(c).unwrap_or_else(|_| "{}".to_string());
+    conn.execute(
+        r#"INSERT INTO clients (id, name, maticniBroj, pib, address, email, phone, website,
+               defaultCurrency, preferredLanguage, paymentTermsDays, billingEmail, archived, isForeign, country,
+               createdAt, data_json)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)"#,
+        params![
+            c.id,
+            c.name,
+            c.registration_number,
+            c.pib,
+            c.address,
+            c.email,
+            c.phone,
+            c.website,
+            c.default_currency,
+            c.preferred_language,
+            c.payment_terms_days,
+            c.billing_email,
+            c.archived,
+            c.is_foreign,
+            c.country,
+            c.created_at,
+            json,
+        ],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn write_client_row(conn: &Connection, c: &Client) -> Result<(), rusqlite::Error> {
+    let json = serde_json::to_string(c).unwrap_or_else(|_| "{}".to_string());
+    conn.execute(
+        r#"UPDATE clients SET name=?2, maticniBroj=?3, pib=?4, address=?5, email=?6, phone=?7, website=?8,
+               defaultCurrency=?9, preferredLanguage=?10, paymentTermsDays=?11, billingEmail=?12, archived=?13,
+               isForeign=?14, country=?15, data_json=?16
+           WHERE id=?1"#,
+        params![
+            c.id,
+            c.name,
+            c.registration_number,
+            c.pib,
+            c.address,
+            c.email,
+            c.phone,
+            c.website,
+            c.default_currency,
+            c.preferred_language,
+            c.payment_terms_days,
+            c.billing_email,
+            c.archived,
+            c.is_foreign,
+            c.country,
+            json
+        ],
+    )?;
+    Ok(())
+}
+
+const MAX_PAYMENT_TERMS_DAYS: i64 = 365;
+
+/// Validates the per-client invoice defaults and country, and normalizes their case.
+pub(crate) fn normalize_client_defaults(mut c: Client) -> Result<Client, String> {
+    if let Some(cur) = c.default_currency.as_mut() {
+        *cur = cur.trim().to_ascii_uppercase();
+        if cur.len() != 3 || !cur.chars().all(|ch| ch.is_ascii_alphabetic()) {
+            return Err(format!("Default currency \"{cur}\" must be a 3-letter ISO code (e.g. EUR)."));
+        }
+    }
+    if let Some(lang) = c.preferred_language.as_mut() {
+        *lang = lang.trim().to_ascii_lowercase();
+        if !matches!(lang.as_str(), "sr" | "en" | "bilingual") {
+            return Err(format!("Preferred language \"{lang}\" must be sr, en or bilingual."));
+        }
+    }
+    if let Some(country) = c.country.as_mut() {
+        *country = country.trim().to_ascii_uppercase();
+        if country.len() != 2 || !country.chars().all(|ch| ch.is_ascii_alphabetic()) {
+            return Err(format!("Country \"{country}\" must be a 2-letter ISO code (e.g. DE)."));
+        }
+    }
+    if let Some(days) = c.payment_terms_days {
+        if !(0..=MAX_PAYMENT_TERMS_DAYS).contains(&days) {
+            return Err(format!("Payment terms must be between 0 and {MAX_PAYMENT_TERMS_DAYS} days."));
+        }
+    }
+    if let Some(email) = c.billing_email.as_deref() {
+        if email.parse::<lettre::Address>().is_err() {
+            return Err(format!("Invalid billing email address \"{email}\"."));
+        }
+    }
+    Ok(c)
+}
+
+/// Points every invoice of `from_id` at `to_id`, in the column and in `data_json`. The
+/// invoice's `clientName` snapshot is left as issued.
+fn reassign_invoices(conn: &Connection, from_id: &str, to_id: &str) -> Result<usize, rusqlite::Error> {
+    let rows: Vec<(String, String)> = {
+        let mut stmt = conn.prepare("SELECT id, data_json FROM invoices WHERE clientId = ?1")?;
+        let rows = stmt.query_map(params![from_id], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    for (id, data_json) in &rows {
+        let mut value: serde_json::Value = serde_json::from_str(data_json)
+            .map_err(|e| validation_to_sql_error(format!("Invoice {id} has invalid data: {e}")))?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("clientId".to_string(), serde_json::Value::String(to_id.to_string()));
+        }
+        conn.execute(
+            "UPDATE invoices SET clientId = ?2, data_json = ?3 WHERE id = ?1",
+            params![id, to_id, value.to_string()],
+        )?;
+    }
+    Ok(rows.len())
+}
+
+/// Rewrites the `clientName` snapshot of every invoice of the client; returns the ids that changed.
+pub(crate) fn rename_client_on_invoices(conn: &Connection, client_id: &str, name: &str) -> Result<Vec<String>, rusqlite::Error> {
+    let rows: Vec<(String, String)> = {
+        let mut stmt = conn.prepare("SELECT id, data_json FROM invoices WHERE clientId = ?1")?;
+        let rows = stmt.query_map(params![client_id], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    let mut changed = Vec::new();
+    for (id, data_json) in rows {
+        let mut value: serde_json::Value = serde_json::from_str(&data_json)
+            .map_err(|e| validation_to_sql_error(format!("Invoice {id} has invalid data: {e}")))?;
+        let Some(obj) = value.as_object_mut() else {
+            continue;
+        };
+        if obj.get("clientName").and_then(|v| v.as_str()) == Some(name) {
+            continue;
+        }
+        obj.insert("clientName".to_string(), serde_json::Value::String(name.to_string()));
+        conn.execute("UPDATE invoices SET data_json = ?2 WHERE id = ?1", params![id, value.to_string()])?;
+        changed.push(id);
+    }
+    Ok(changed)
+}
+
+pub(crate) fn merge_clients_in_conn(
+    conn: &mut Connection,
+    keep_id: &str,
+    remove_id: &str,
+) -> Result<MergeClientsResult, rusqlite::Error> {
+    if keep_id == remove_id {
+        return Err(validation_to_sql_error("Cannot merge a client into itself.".to_string()));
+    }
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut keep = read_client_from_conn(&tx, keep_id)?
+        .ok_or_else(|| validation_to_sql_error("Client to keep not found".to_string()))?;
+    let remove = read_client_from_conn(&tx, remove_id)?
+        .ok_or_else(|| validation_to_sql_error("Client to remove not found".to_string()))?;
+
+    union_missing_fields(&mut keep, &remove);
+    write_client_row(&tx, &keep)?;
+    let invoices_moved = reassign_invoices(&tx, remove_id, keep_id)?;
+    tx.execute("DELETE FROM clients WHERE id = ?1", params![remove_id])?;
+    audit::record(
+        &tx,
+        "client.merge",
+        keep_id,
+        &serde_json::json!({
+            "keptId": keep_id,
+            "removed": remove,
+            "invoicesMoved": invoices_moved,
+        }),
+    )?;
+    tx.commit()?;
+
+    Ok(MergeClientsResult {
+        client: keep,
+        invoices_moved,
+    })
+}
+
+/// Result of `delete_client`. `blocked` means invoices still reference the client and `force`
+/// was not set; nothing was deleted.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteClientResult {
+    pub deleted: bool,
+    pub blocked: bool,
+    pub invoice_count: i64,
+}
+
+/// Deletes a client unless invoices reference it. With `force`, the client row is removed and its
+/// invoices keep their `clientName` snapshot.
+pub(crate) fn delete_client_in_conn(
+    conn: &mut Connection,
+    id: &str,
+    force: bool,
+) -> Result<DeleteClientResult, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let invoice_count: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM invoices WHERE clientId = ?1",
+        params![id],
+        |r| r.get(0),
+    )?;
+    if invoice_count > 0 && !force {
+        return Ok(DeleteClientResult {
+            deleted: false,
+            blocked: true,
+            invoice_count,
+        });
+    }
+
+    let removed = read_client_from_conn(&tx, id)?;
+    let deleted = tx.execute("DELETE FROM clients WHERE id = ?1", params![id])? > 0;
+    if deleted && invoice_count > 0 {
+        audit::record(
+            &tx,
+            "client.forceDelete",
+            id,
+            &serde_json::json!({ "removed": removed, "invoiceCount": invoice_count }),
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(DeleteClientResult {
+        deleted,
+        blocked: false,
+        invoice_count,
+    })
+}
+
+/// CSV header for each client field; only `name` is required.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCsvMapping {
+    pub name: String,
+    #[serde(default)]
+    pub pib: Option<String>,
+    #[serde(default)]
+    pub registration_number: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub postal_code: Option<String>,
+    #[serde(default)]
+    pub city: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientImportStatus {
+    Imported,
+    Skipped,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientImportRow {
+    /// 1-based line in the CSV file.
+    pub line: usize,
+    pub name: String,
+    pub status: ClientImportStatus,
+    pub message: Option<String>,
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientImportReport {
+    pub imported: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+    pub rows: Vec<ClientImportRow>,
+}
+
+/// Resolved column indexes for a mapping; an unknown header is a hard failure.
+struct MappedColumns {
+    name: usize,
+    pib: Option<usize>,
+    registration_number: Option<usize>,
+    address: Option<usize>,
+    postal_code: Option<usize>,
+    city: Option<usize>,
+    email: Option<usize>,
+    phone: Option<usize>,
+}
+
+fn resolve_mapping(table: &CsvTable, mapping: &ClientCsvMapping) -> Result<MappedColumns, String> {
+    let column = |header: &str| {
+        table
+            .column(header)
+            .ok_or_else(|| format!("Column \"{}\" was not found in the CSV header.", header.trim()))
+    };
+    let optional = |header: &Option<String>| match header.as_deref().map(str::trim) {
+        Some(h) if !h.is_empty() => column(h).map(Some),
+        _ => Ok(None),
+    };
+    if mapping.name.trim().is_empty() {
+        return Err("Choose the CSV column that holds the client name.".to_string());
+    }
+    Ok(MappedColumns {
+        name: column(&mapping.name)?,
+        pib: optional(&mapping.pib)?,
+        registration_number: optional(&mapping.registration_number)?,
+        address: optional(&mapping.address)?,
+        postal_code: optional(&mapping.postal_code)?,
+        city: optional(&mapping.city)?,
+        email: optional(&mapping.email)?,
+        phone: optional(&mapping.phone)?,
+    })
+}
+
+fn cell(row: &[String], idx: Option<usize>) -> String {
+    idx.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+fn client_from_csv_row(cols: &MappedColumns, row: &[String]) -> Result<Client, String> {
+    let name = cell(row, Some(cols.name));
+    if name.is_empty() {
+        return Err("Name is empty.".to_string());
+    }
+    let pib = cell(row, cols.pib);
+    let pib = if pib.is_empty() {
+        pib
+    } else {
+        tax_ids::check_pib(&pib).map_err(|e| e.message("en", &pib))?
+    };
+    let mb = cell(row, cols.registration_number);
+    let mb = if mb.is_empty() {
+        mb
+    } else {
+        tax_ids::check_mb(&mb).map_err(|e| e.message("en", &mb))?
+    };
+    let email = cell(row, cols.email);
+    if !email.is_empty() && email.parse::<lettre::Address>().is_err() {
+        return Err(format!("Invalid email address \"{email}\"."));
+    }
+    let phone = cell(row, cols.phone);
+
+    Ok(Client {
+        id: Uuid::new_v4().to_string(),
+        name,
+        registration_number: mb,
+        pib,
+        address: cell(row, cols.address),
+        city: cell(row, cols.city),
+        postal_code: cell(row, cols.postal_code),
+        email,
+        phone: Some(phone).filter(|p| !p.is_empty()),
+        website: None,
+        default_currency: None,
+        preferred_language: None,
+        payment_terms_days: None,
+        billing_email: None,
+        archived: false,
+        is_foreign: false,
+        country: None,
+        created_at: now_iso(),
+    })
+}
+
+/// Imports every valid row in one transaction. Invalid rows are skipped and rows whose PIB or MB
+/// already exists (in the database or earlier in the file) are reported as duplicates; any
+/// database error rolls back the whole import.
+pub(crate) fn import_clients_from_table(
+    conn: &mut Connection,
+    table: &CsvTable,
+    mapping: &ClientCsvMapping,
+) -> Result<ClientImportReport, rusqlite::Error> {
+    let cols = resolve_mapping(table, mapping).map_err(validation_to_sql_error)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let mut known_pibs: HashSet<String> = HashSet::new();
+    let mut known_mbs: HashSet<String> = HashSet::new();
+    for c in load_all_clients(&tx)? {
+        known_pibs.insert(normalize_id_number(&c.pib));
+        known_mbs.insert(normalize_id_number(&c.registration_number));
+    }
+    known_pibs.remove("");
+    known_mbs.remove("");
+
+    let mut rows = Vec::with_capacity(table.rows.len());
+    for (line, row) in &table.rows {
+        let mut report = ClientImportRow {
+            line: *line,
+            name: cell(row, Some(cols.name)),
+            status: ClientImportStatus::Skipped,
+            message: None,
+            client_id: None,
+        };
+        match client_from_csv_row(&cols, row) {
+            Err(e) => report.message = Some(e),
+            Ok(client) => {
+                let duplicate = if known_pibs.contains(&client.pib) {
+                    Some(format!("A client with PIB {} already exists.", client.pib))
+                } else if known_mbs.contains(&client.registration_number) {
+                    Some(format!("A client with MB {} already exists.", client.registration_number))
+                } else {
+                    None
+                };
+                if let Some(msg) = duplicate {
+                    report.status = ClientImportStatus::Duplicate;
+                    report.message = Some(msg);
+                } else {
+                    insert_client_row(&tx, &client)?;
+                    if !client.pib.is_empty() {
+                        known_pibs.insert(client.pib.clone());
+                    }
+                    if !client.registration_number.is_empty() {
+                        known_mbs.insert(client.registration_number.clone());
+                    }
+                    report.status = ClientImportStatus::Imported;
+                    report.client_id = Some(client.id);
+                }
+            }
+        }
+        rows.push(report);
+    }
+    tx.commit()?;
+
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+    Ok(ClientImportReport {
+        imported: count(ClientImportStatus::Imported),
+        skipped: count(ClientImportStatus::Skipped),
+        duplicates: count(ClientImportStatus::Duplicate),
+        rows,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn import_clients_csv(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    mapping: ClientCsvMapping,
+) -> Result<ClientImportReport, AppError> {
+    let table = csv_reader::read_csv_file(&path)?;
+    state
+        .with_create_notify("import_clients_csv", move |conn, changes| {
+            let report = import_clients_from_table(conn, &table, &mapping)?;
+            let created = report
+                .rows
+                .iter()
+                .filter(|r| matches!(r.status, ClientImportStatus::Imported))
+                .filter_map(|r| r.client_id.clone());
+            changes.record_all(DataEntity::Clients, DataOp::Created, created);
+            Ok(report)
+        })
+        .await
+}
+
+fn set_client_archived(conn: &Connection, id: &str, archived: bool) -> Result<Option<Client>, rusqlite::Error> {
+    let Some(mut client) = read_client_from_conn(conn, id)? else {
+        return Ok(None);
+    };
+    client.archived = archived;
+    write_client_row(conn, &client)?;
+    Ok(Some(client))
+}
+
+#[tauri::command]
+pub(crate) async fn archive_client(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Client>, AppError> {
+    state
+        .with_write_notify("archive_client", move |conn, changes| {
+            let client = set_client_archived(conn, &id, true)?;
+            changes.record_all(DataEntity::Clients, DataOp::Updated, client.as_ref().map(|c| c.id.clone()));
+            Ok(client)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn unarchive_client(
+    state: tauri::State<'_, DbState>,
+    id: String,
+) -> Result<Option<Client>, AppError> {
+    state
+        .with_write_notify("unarchive_client", move |conn, changes| {
+            let client = set_client_archived(conn, &id, false)?;
+            changes.record_all(DataEntity::Clients, DataOp::Updated, client.as_ref().map(|c| c.id.clone()));
+            Ok(client)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn merge_clients(
+    state: tauri::State<'_, DbState>,
+    keep_id: String,
+    remove_id: String,
+) -> Result<MergeClientsResult, AppError> {
+    state
+        .with_write_notify("merge_clients", move |conn, changes| {
+            let result = merge_clients_in_conn(conn, &keep_id, &remove_id)?;
+            changes.record(DataEntity::Clients, DataOp::Updated, keep_id);
+            changes.record(DataEntity::Clients, DataOp::Deleted, remove_id);
+            if result.invoices_moved > 0 {
+                changes.replaced(DataEntity::Invoices);
+            }
+            Ok(result)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn find_duplicate_clients(
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<DuplicateClientGroup>, AppError> {
+    state
+        .with_read("find_duplicate_clients", |conn| {
+            Ok(group_duplicates(&load_all_clients(conn)?))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(id: &str, name: &str, pib: &str, mb: &str) -> Client {
+        Client {
+            id: id.to_string(),
+            name: name.to_string(),
+            registration_number: mb.to_string(),
+            pib: pib.to_string(),
+            address: String::new(),
+            city: String::new(),
+            postal_code: String::new(),
+            email: String::new(),
+            phone: None,
+            website: None,
+            default_currency: None,
+            preferred_language: None,
+            payment_terms_days: None,
+            billing_email: None,
+            archived: false,
+            is_foreign: false,
+            country: None,
+            created_at: "t".to_string(),
+        }
+    }
+
+    fn insert_client(conn: &Connection, c: &Client) {
+        insert_client_row(conn, c).unwrap();
+    }
+
+    fn insert_invoice(conn: &Connection, id: &str, client_id: &str) {
+        let json = serde_json::json!({ "id": id, "clientId": client_id, "clientName": "Old name" });
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json) VALUES (?1, ?1, ?2, '2024-01-01', 'RSD', 0, 't', ?3)",
+            params![id, client_id, json.to_string()],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn merge_moves_invoices_and_fills_missing_fields() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let keep = client("keep", "Acme", "100000001", "");
+        let mut remove = client("dup", "ACME doo", "100000001", "20000001");
+        remove.email = "office@acme.rs".into();
+        remove.phone = Some("011 123".into());
+        insert_client(&conn, &keep);
+        insert_client(&conn, &remove);
+        insert_invoice(&conn, "i1", "dup");
+        insert_invoice(&conn, "i2", "dup");
+        insert_invoice(&conn, "i3", "keep");
+
+        assert!(merge_clients_in_conn(&mut conn, "keep", "keep").is_err());
+
+        let res = merge_clients_in_conn(&mut conn, "keep", "dup").unwrap();
+        assert_eq!(res.invoices_moved, 2);
+        assert_eq!(res.client.email, "office@acme.rs");
+        assert_eq!(res.client.registration_number, "20000001");
+        assert_eq!(res.client.phone.as_deref(), Some("011 123"));
+
+        assert!(read_client_from_conn(&conn, "dup").unwrap().is_none());
+        let (col, json): (String, String) = conn
+            .query_row("SELECT clientId, data_json FROM invoices WHERE id = 'i1'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(col, "keep");
+        assert_eq!(json["clientId"], "keep");
+        assert_eq!(json["clientName"], "Old name");
+        let audits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'client.merge'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(audits, 1);
+    }
+
+    #[test]
+    fn rename_rewrites_only_that_clients_invoice_snapshots() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        insert_invoice(&conn, "i1", "a");
+        insert_invoice(&conn, "i2", "b");
+
+        assert_eq!(rename_client_on_invoices(&conn, "a", "New name").unwrap(), ["i1"]);
+        assert_eq!(rename_client_on_invoices(&conn, "a", "New name").unwrap().len(), 0);
+        let names: Vec<String> = ["i1", "i2"]
+            .iter()
+            .map(|id| {
+                conn.query_row(
+                    "SELECT json_extract(data_json, '$.clientName') FROM invoices WHERE id = ?1",
+                    [id],
+                    |r| r.get(0),
+                )
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(names, vec!["New name".to_string(), "Old name".to_string()]);
+    }
+
+    #[test]
+    fn delete_is_blocked_by_invoices_unless_forced() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        insert_client(&conn, &client("a", "Acme", "", ""));
+        insert_invoice(&conn, "i1", "a");
+
+        let res = delete_client_in_conn(&mut conn, "a", false).unwrap();
+        assert!(res.blocked && !res.deleted);
+        assert_eq!(res.invoice_count, 1);
+        assert!(read_client_from_conn(&conn, "a").unwrap().is_some());
+
+        let res = delete_client_in_conn(&mut conn, "a", true).unwrap();
+        assert!(res.deleted && !res.blocked);
+        assert!(read_client_from_conn(&conn, "a").unwrap().is_none());
+        let name: String = conn
+            .query_row("SELECT json_extract(data_json, '$.clientName') FROM invoices WHERE id = 'i1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(name, "Old name");
+
+        let res = delete_client_in_conn(&mut conn, "missing", false).unwrap();
+        assert!(!res.deleted && !res.blocked);
+    }
+
+    #[test]
+    fn csv_import_reports_each_row() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        insert_client(&conn, &client("a", "Existing", "101134702", ""));
+        let table = csv_reader::parse_csv(
+            "Naziv;PIB;MB;Email\n\
+             Nova firma;100 002 887;07015410;office@nova.rs\n\
+             Postojeća;101134702;;\n\
+             Loš PIB;12345;;\n\
+             ;104052135;;\n\
+             Ponovo nova;;07015410;\n\
+             Pogrešna kontrolna cifra;100000001;;\n",
+        )
+        .unwrap();
+        let mapping = ClientCsvMapping {
+            name: "naziv".into(),
+            pib: Some("PIB".into()),
+            registration_number: Some("MB".into()),
+            email: Some("Email".into()),
+            ..Default::default()
+        };
+        let report = import_clients_from_table(&mut conn, &table, &mapping).unwrap();
+        let statuses: Vec<(usize, ClientImportStatus)> = report.rows.iter().map(|r| (r.line, r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (2, ClientImportStatus::Imported),
+                (3, ClientImportStatus::Duplicate),
+                (4, ClientImportStatus::Skipped),
+                (5, ClientImportStatus::Skipped),
+                (6, ClientImportStatus::Duplicate),
+                (7, ClientImportStatus::Skipped),
+            ]
+        );
+        assert_eq!((report.imported, report.skipped, report.duplicates), (1, 3, 2));
+        let imported = read_client_from_conn(&conn, report.rows[0].client_id.as_deref().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(imported.pib, "100002887");
+
+        let bad = ClientCsvMapping {
+            name: "Ime".into(),
+            ..Default::default()
+        };
+        assert!(import_clients_from_table(&mut conn, &table, &bad).is_err());
+    }
+
+    #[test]
+    fn archived_flag_is_stored_in_column_and_json() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        insert_client(&conn, &client("a", "Acme", "", ""));
+        assert!(set_client_archived(&conn, "a", true).unwrap().unwrap().archived);
+        let col: bool = conn
+            .query_row("SELECT archived FROM clients WHERE id = 'a'", [], |r| r.get(0))
+            .unwrap();
+        assert!(col);
+        assert!(read_client_from_conn(&conn, "a").unwrap().unwrap().archived);
+        assert!(set_client_archived(&conn, "missing", true).unwrap().is_none());
+    }
+
+    #[test]
+    fn groups_by_pib_mb_and_name() {
+        let clients = vec![
+            client("a", "Acme DOO", "100 000 001", ""),
+            client("b", "ACME d.o.o.", "100000001", "20000001"),
+            client("c", "  acme   doo ", "", "20000001"),
+            client("d", "Other", "", ""),
+        ];
+        let groups = group_duplicates(&clients);
+        let summary: Vec<(DuplicateMatch, Vec<&str>)> = groups
+            .iter()
+            .map(|g| (g.matched_on, g.clients.iter().map(|c| c.id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DuplicateMatch::Pib, vec!["a", "b"]),
+                (DuplicateMatch::RegistrationNumber, vec!["b", "c"]),
+                (DuplicateMatch::Name, vec!["a", "c"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! The company logo lives in `<app data>/logo.png`; settings keep only its path, so
+//! `get_settings` no longer carries megabytes of base64. Older versions stored a data URL in the
+//! settings row, which `migrate_logo_setting` moves out at startup.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use printpdf::image_crate::ImageOutputFormat;
+use rusqlite::{params, Connection};
+
+use crate::errors::AppError;
+use crate::data_events::{DataEntity, DataOp};
+use crate::{
+    decode_logo_bytes, logo_error, now_iso, read_logo_bytes, read_settings_from_conn, resolve_app_data_root,
+    settings_for_ui, DbState, Settings, MAX_LOGO_BYTES, SETTINGS_ID,
+};
+
+pub(crate) const LOGO_FILE_NAME: &str = "logo.png";
+
+/// Longer side of the stored logo. The PDF prints it at most 45 mm wide, so more pixels only
+/// make every PDF bigger.
+const MAX_STORED_LOGO_PX: u32 = 1200;
+
+pub(crate) fn logo_path(root: &Path) -> PathBuf {
+    root.join(LOGO_FILE_NAME)
+}
+
+/// Image bytes from a data URL, a `file://` URL, an absolute path or bare base64.
+fn source_bytes(source: &str) -> Result<Vec<u8>, String> {
+    let is_url = source
+        .get(..5)
+        .is_some_and(|p| p.eq_ignore_ascii_case("data:") || p.eq_ignore_ascii_case("file:"));
+    if is_url || Path::new(source).is_absolute() {
+        return read_logo_bytes(source);
+    }
+    if source.len() / 4 * 3 > MAX_LOGO_BYTES {
+        return Err(logo_error(format!("image is larger than {} MB", MAX_LOGO_BYTES / 1024 / 1024)));
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(source)
+        .map_err(|e| logo_error(format!("invalid base64 data ({e})")))
+}
+
+/// Validates the image with the PDF's limits, downscales it and writes it as PNG to
+/// `<root>/logo.png`.
+pub(crate) fn store_logo(root: &Path, source: &str) -> Result<PathBuf, String> {
+    let image = decode_logo_bytes(&source_bytes(source.trim())?)?;
+    let image = if image.width().max(image.height()) > MAX_STORED_LOGO_PX {
+        image.thumbnail(MAX_STORED_LOGO_PX, MAX_STORED_LOGO_PX)
+    } else {
+        image
+    };
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| logo_error(format!("failed to encode PNG ({e})")))?;
+
+    std::fs::create_dir_all(root).map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+    let path = logo_path(root);
+    let tmp = root.join(format!("{LOGO_FILE_NAME}.tmp"));
+    std::fs::write(&tmp, &png).map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Sets the logo in the column and in `data_json`, which `read_settings_from_conn` prefers.
+pub(crate) fn write_logo_setting(conn: &Connection, value: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"UPDATE settings SET
+             logoUrl = ?2,
+             data_json = CASE WHEN json_valid(data_json) THEN json_set(data_json, '$.logoUrl', ?2) ELSE data_json END,
+             updatedAt = ?3
+           WHERE id = ?1"#,
+        params![SETTINGS_ID, value, now_iso()],
+    )?;
+    Ok(())
+}
+
+/// Startup fix-up of the stored logo reference. Returns whether the settings row changed.
+///
+/// A data URL from an older version is moved into `<root>/logo.png`. A managed `logo.png` path
+/// that no longer exists (a database restored on another computer; backups hold only the
+/// database) is pointed at this computer's logo, or cleared so PDFs don't fail on it.
+pub(crate) fn migrate_logo_setting(conn: &Connection, root: &Path) -> Result<bool, String> {
+    let current = read_settings_from_conn(conn).map_err(|e| e.to_string())?.logo_url;
+    let current = current.trim();
+    let value = if current.get(..5).is_some_and(|p| p.eq_ignore_ascii_case("data:")) {
+        store_logo(root, current)?.to_string_lossy().into_owned()
+    } else {
+        let path = Path::new(current);
+        let managed = path.file_name().is_some_and(|n| n == LOGO_FILE_NAME);
+        if !managed || path.exists() {
+            return Ok(false);
+        }
+        let local = logo_path(root);
+        if local.exists() { local.to_string_lossy().into_owned() } else { String::new() }
+    };
+    write_logo_setting(conn, &value).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Stores a new logo from a data URL, base64 or a file path; an empty `source` removes it.
+#[tauri::command]
+pub(crate) async fn set_company_logo(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    source: String,
+) -> Result<Settings, AppError> {
+    let root = resolve_app_data_root(&app)?;
+    let value = if source.trim().is_empty() {
+        String::new()
+    } else {
+        let root = root.clone();
+        let path = tauri::async_runtime::spawn_blocking(move || store_logo(&root, &source))
+            .await
+            .map_err(|e| e.to_string())??;
+        path.to_string_lossy().into_owned()
+    };
+    let removed = value.is_empty();
+    let settings = state
+        .with_write_notify("set_company_logo", move |conn, changes| {
+            write_logo_setting(conn, &value)?;
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            read_settings_from_conn(conn)
+        })
+        .await?;
+    if removed {
+        let _ = std::fs::remove_file(logo_path(&root));
+    }
+    Ok(settings_for_ui(settings))
+}
+
+/// PNG bytes of the current logo, empty when none is set.
+#[tauri::command]
+pub(crate) async fn get_company_logo(state: tauri::State<'_, DbState>) -> Result<tauri::ipc::Response, AppError> {
+    let source = state
+        .with_read("get_company_logo", |conn| Ok(read_settings_from_conn(conn)?.logo_url))
+        .await?;
+    let source = source.trim().to_string();
+    let bytes = if source.is_empty() {
+        Vec::new()
+    } else {
+        tauri::async_runtime::spawn_blocking(move || read_logo_bytes(&source))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::image_crate::{DynamicImage, RgbImage};
+
+    #[test]
+    fn inline_logo_is_moved_to_a_downscaled_file() {
+        let root = std::env::temp_dir().join(format!("pausaler-logo-{}", uuid::Uuid::new_v4()));
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(2400, 600))
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageOutputFormat::Png)
+            .unwrap();
+        let data_url = format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png));
+        write_logo_setting(&conn, &data_url).unwrap();
+
+        assert!(migrate_logo_setting(&conn, &root).unwrap());
+        let stored = read_settings_from_conn(&conn).unwrap().logo_url;
+        assert_eq!(PathBuf::from(&stored), logo_path(&root));
+        let column: String = conn
+            .query_row("SELECT logoUrl FROM settings WHERE id = ?1", params![SETTINGS_ID], |r| r.get(0))
+            .unwrap();
+        assert_eq!(column, stored);
+        let image = crate::decode_logo_image(&stored).unwrap();
+        assert_eq!((image.width(), image.height()), (1200, 300));
+        assert!(!migrate_logo_setting(&conn, &root).unwrap());
+
+        // Restored from another computer: the old path is re-pointed at the local file.
+        let elsewhere = std::env::temp_dir().join(format!("pausaler-missing-{}", uuid::Uuid::new_v4()));
+        write_logo_setting(&conn, &logo_path(&elsewhere).to_string_lossy()).unwrap();
+        assert!(migrate_logo_setting(&conn, &root).unwrap());
+        assert_eq!(read_settings_from_conn(&conn).unwrap().logo_url, stored);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
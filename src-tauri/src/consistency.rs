@@ -0,0 +1,418 @@
+//! Checks that the indexed columns of `invoices` and `clients` agree with the same values inside
+//! `data_json`, and repairs rows where they drifted apart by rewriting one side from the other.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::errors::AppError;
+use crate::data_events::DataEntity;
+use crate::{audit, DbState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Real,
+    Int,
+    Bool,
+}
+
+/// A column mirrored in `data_json` under `key`. Non-nullable columns are never set to NULL
+/// by a repair; such rows stay reported.
+struct MirroredField {
+    column: &'static str,
+    key: &'static str,
+    kind: FieldKind,
+    nullable: bool,
+}
+
+const fn field(column: &'static str, key: &'static str, kind: FieldKind, nullable: bool) -> MirroredField {
+    MirroredField {
+        column,
+        key,
+        kind,
+        nullable,
+    }
+}
+
+const INVOICE_FIELDS: [MirroredField; 8] = [
+    field("invoiceNumber", "invoiceNumber", FieldKind::Text, false),
+    field("clientId", "clientId", FieldKind::Text, false),
+    field("issueDate", "issueDate", FieldKind::Text, false),
+    field("status", "status", FieldKind::Text, false),
+    field("dueDate", "dueDate", FieldKind::Text, true),
+    field("paidAt", "paidAt", FieldKind::Text, true),
+    field("currency", "currency", FieldKind::Text, false),
+    field("totalAmount", "total", FieldKind::Real, false),
+];
+
+const CLIENT_FIELDS: [MirroredField; 14] = [
+    field("name", "name", FieldKind::Text, false),
+    field("maticniBroj", "registrationNumber", FieldKind::Text, false),
+    field("pib", "pib", FieldKind::Text, false),
+    field("address", "address", FieldKind::Text, false),
+    field("email", "email", FieldKind::Text, false),
+    field("phone", "phone", FieldKind::Text, true),
+    field("website", "website", FieldKind::Text, true),
+    field("defaultCurrency", "defaultCurrency", FieldKind::Text, true),
+    field("preferredLanguage", "preferredLanguage", FieldKind::Text, true),
+    field("paymentTermsDays", "paymentTermsDays", FieldKind::Int, true),
+    field("billingEmail", "billingEmail", FieldKind::Text, true),
+    field("archived", "archived", FieldKind::Bool, false),
+    field("isForeign", "isForeign", FieldKind::Bool, false),
+    field("country", "country", FieldKind::Text, true),
+];
+
+const TABLES: [(&str, &[MirroredField]); 2] = [("invoices", &INVOICE_FIELDS), ("clients", &CLIENT_FIELDS)];
+
+/// Which side is trusted when repairing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairStrategy {
+    /// `data_json` is the source of truth; the columns are rewritten.
+    FromJson,
+    /// The columns are the source of truth; the values inside `data_json` are rewritten.
+    FromColumns,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyIssue {
+    pub table: String,
+    pub id: String,
+    /// Column name; the JSON key may differ (`totalAmount` is `total`).
+    pub field: String,
+    pub column_value: JsonValue,
+    pub json_value: JsonValue,
+}
+
+/// A row whose `data_json` is not a JSON object; neither strategy can repair it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadableRow {
+    pub table: String,
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    pub invoices_checked: usize,
+    pub clients_checked: usize,
+    pub issues: Vec<ConsistencyIssue>,
+    pub unreadable: Vec<UnreadableRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyRepairResult {
+    pub strategy: RepairStrategy,
+    pub rows_repaired: usize,
+    pub fields_repaired: usize,
+    /// A fresh check after the repair; left-overs are rows no strategy can fix.
+    pub remaining: ConsistencyReport,
+}
+
+fn column_to_json(kind: FieldKind, v: SqlValue) -> JsonValue {
+    match (kind, v) {
+        (_, SqlValue::Null) => JsonValue::Null,
+        (FieldKind::Bool, SqlValue::Integer(i)) => JsonValue::Bool(i != 0),
+        (_, SqlValue::Integer(i)) => JsonValue::from(i),
+        (_, SqlValue::Real(f)) => JsonValue::from(f),
+        (_, SqlValue::Text(s)) => JsonValue::String(s),
+        (_, SqlValue::Blob(_)) => JsonValue::Null,
+    }
+}
+
+fn json_to_column(kind: FieldKind, v: &JsonValue) -> SqlValue {
+    match (kind, v) {
+        (FieldKind::Bool, v) => SqlValue::Integer(v.as_bool().unwrap_or(false) as i64),
+        (FieldKind::Text, JsonValue::String(s)) => SqlValue::Text(s.clone()),
+        (FieldKind::Real, v) if v.is_number() => SqlValue::Real(v.as_f64().unwrap_or_default()),
+        (FieldKind::Int, v) if v.is_number() => SqlValue::Integer(v.as_i64().unwrap_or_default()),
+        _ => SqlValue::Null,
+    }
+}
+
+/// Equal up to representation: missing, null and "" are the same text, a missing flag is
+/// `false`, and amounts match to the cent.
+fn same_value(kind: FieldKind, column: &JsonValue, json: &JsonValue) -> bool {
+    match kind {
+        FieldKind::Text => {
+            let text = |v: &JsonValue| match v {
+                JsonValue::Null => String::new(),
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            text(column) == text(json)
+        }
+        FieldKind::Real => match (column.as_f64(), json.as_f64()) {
+            (Some(a), Some(b)) => (a - b).abs() < 0.005,
+            (a, b) => a.is_none() && b.is_none(),
+        },
+        FieldKind::Int => column.as_i64() == json.as_i64() && column.is_null() == json.is_null(),
+        FieldKind::Bool => column.as_bool().unwrap_or(false) == json.as_bool().unwrap_or(false),
+    }
+}
+
+/// One row's mismatching fields, with the row's parsed JSON object.
+struct RowDrift {
+    id: String,
+    object: serde_json::Map<String, JsonValue>,
+    mismatched: Vec<(&'static MirroredField, JsonValue, JsonValue)>,
+}
+
+fn scan_table(
+    conn: &Connection,
+    table: &str,
+    fields: &'static [MirroredField],
+    unreadable: &mut Vec<UnreadableRow>,
+) -> Result<(usize, Vec<RowDrift>), rusqlite::Error> {
+    let columns: Vec<&str> = fields.iter().map(|f| f.column).collect();
+    let mut stmt = conn.prepare(&format!("SELECT id, data_json, {} FROM {table} ORDER BY id", columns.join(", ")))?;
+    let mut rows = stmt.query([])?;
+    let mut checked = 0;
+    let mut drifted = Vec::new();
+    while let Some(row) = rows.next()? {
+        checked += 1;
+        let id: String = row.get(0)?;
+        let data_json: Option<String> = row.get(1)?;
+        let object = match serde_json::from_str::<JsonValue>(data_json.as_deref().unwrap_or("")) {
+            Ok(JsonValue::Object(o)) => o,
+            Ok(_) => {
+                unreadable.push(UnreadableRow {
+                    table: table.to_string(),
+                    id,
+                    error: "data_json is not a JSON object".to_string(),
+                });
+                continue;
+            }
+            Err(e) => {
+                unreadable.push(UnreadableRow {
+                    table: table.to_string(),
+                    id,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let mut mismatched = Vec::new();
+        for (i, f) in fields.iter().enumerate() {
+            let column_value = column_to_json(f.kind, row.get::<_, SqlValue>(i + 2)?);
+            let json_value = object.get(f.key).cloned().unwrap_or(JsonValue::Null);
+            if !same_value(f.kind, &column_value, &json_value) {
+                mismatched.push((f, column_value, json_value));
+            }
+        }
+        if !mismatched.is_empty() {
+            drifted.push(RowDrift { id, object, mismatched });
+        }
+    }
+    Ok((checked, drifted))
+}
+
+pub(crate) fn check_consistency(conn: &Connection) -> Result<ConsistencyReport, rusqlite::Error> {
+    let mut unreadable = Vec::new();
+    let mut issues = Vec::new();
+    let mut checked = [0; 2];
+    for (i, (table, fields)) in TABLES.iter().enumerate() {
+        let (count, drifted) = scan_table(conn, table, fields, &mut unreadable)?;
+        checked[i] = count;
+        for row in drifted {
+            for (f, column_value, json_value) in row.mismatched {
+                issues.push(ConsistencyIssue {
+                    table: table.to_string(),
+                    id: row.id.clone(),
+                    field: f.column.to_string(),
+                    column_value,
+                    json_value,
+                });
+            }
+        }
+    }
+    Ok(ConsistencyReport {
+        invoices_checked: checked[0],
+        clients_checked: checked[1],
+        issues,
+        unreadable,
+    })
+}
+
+/// Rewrites every drifted field from the trusted side in one transaction, recording the old
+/// values of each repaired row in the audit log.
+pub(crate) fn repair_consistency(
+    conn: &mut Connection,
+    strategy: RepairStrategy,
+) -> Result<ConsistencyRepairResult, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut rows_repaired = 0;
+    let mut fields_repaired = 0;
+    for (table, fields) in TABLES {
+        let (_, drifted) = scan_table(&tx, table, fields, &mut Vec::new())?;
+        for mut row in drifted {
+            let mut repaired = Vec::new();
+            match strategy {
+                RepairStrategy::FromJson => {
+                    let mut sets = Vec::new();
+                    let mut values = vec![SqlValue::Text(row.id.clone())];
+                    for (f, _, json_value) in &row.mismatched {
+                        let value = json_to_column(f.kind, json_value);
+                        if value == SqlValue::Null && !f.nullable {
+                            continue;
+                        }
+                        values.push(value);
+                        sets.push(format!("{} = ?{}", f.column, values.len()));
+                        repaired.push(f.column);
+                    }
+                    if !sets.is_empty() {
+                        tx.execute(
+                            &format!("UPDATE {table} SET {} WHERE id = ?1", sets.join(", ")),
+                            params_from_iter(values),
+                        )?;
+                    }
+                }
+                RepairStrategy::FromColumns => {
+                    for (f, column_value, _) in &row.mismatched {
+                        row.object.insert(f.key.to_string(), column_value.clone());
+                        repaired.push(f.column);
+                    }
+                    tx.execute(
+                        &format!("UPDATE {table} SET data_json = ?2 WHERE id = ?1"),
+                        rusqlite::params![row.id, JsonValue::Object(row.object.clone()).to_string()],
+                    )?;
+                }
+            }
+            if repaired.is_empty() {
+                continue;
+            }
+            let before: Vec<JsonValue> = row
+                .mismatched
+                .iter()
+                .map(|(f, column_value, json_value)| {
+                    serde_json::json!({ "field": f.column, "column": column_value, "json": json_value })
+                })
+                .collect();
+            audit::record(
+                &tx,
+                "data.repair",
+                &row.id,
+                &serde_json::json!({ "table": table, "strategy": strategy, "before": before }),
+            )?;
+            rows_repaired += 1;
+            fields_repaired += repaired.len();
+        }
+    }
+    let remaining = check_consistency(&tx)?;
+    tx.commit()?;
+    Ok(ConsistencyRepairResult {
+        strategy,
+        rows_repaired,
+        fields_repaired,
+        remaining,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn check_data_consistency(state: tauri::State<'_, DbState>) -> Result<ConsistencyReport, AppError> {
+    state.with_read("check_data_consistency", check_consistency).await
+}
+
+#[tauri::command]
+pub(crate) async fn repair_data_consistency(
+    state: tauri::State<'_, DbState>,
+    strategy: RepairStrategy,
+) -> Result<ConsistencyRepairResult, AppError> {
+    state
+        .with_write_notify("repair_data_consistency", move |conn, changes| {
+            let result = repair_consistency(conn, strategy)?;
+            if result.rows_repaired > 0 {
+                changes.replaced(DataEntity::Invoices);
+                changes.replaced(DataEntity::Clients);
+            }
+            Ok(result)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            r#"INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, paidAt, currency, totalAmount, createdAt, data_json)
+               VALUES ('i1', '2025-1', 'c1', '2025-01-10', 'PAID', NULL, 'RSD', 1000, 't',
+                       '{"id":"i1","invoiceNumber":"2025-1","clientId":"c1","issueDate":"2025-01-10","status":"DRAFT",
+                         "paidAt":null,"currency":"RSD","total":1200.0,"clientName":"Klijent"}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+               VALUES ('i2', '2025-2', 'c1', '2025-01-11', 'RSD', 50.004, 't',
+                       '{"invoiceNumber":"2025-2","clientId":"c1","issueDate":"2025-01-11","status":"DRAFT","currency":"RSD","total":50}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+               VALUES ('i3', '2025-3', 'c1', '2025-01-12', 'RSD', 1, 't', 'not json');
+               INSERT INTO clients (id, name, pib, address, email, archived, createdAt, data_json)
+               VALUES ('c1', 'Klijent', '100000024', '', '', 1, 't',
+                       '{"id":"c1","name":"Klijent doo","pib":"100000024","address":"","email":"","createdAt":"t"}');"#,
+        )
+        .unwrap();
+        conn
+    }
+
+    fn fields(report: &ConsistencyReport) -> Vec<(String, String)> {
+        report.issues.iter().map(|i| (i.id.clone(), i.field.clone())).collect()
+    }
+
+    #[test]
+    fn reports_columns_that_disagree_with_data_json() {
+        let conn = setup();
+        let report = check_consistency(&conn).unwrap();
+        assert_eq!((report.invoices_checked, report.clients_checked), (3, 1));
+        assert_eq!(
+            fields(&report),
+            vec![
+                ("i1".to_string(), "status".to_string()),
+                ("i1".to_string(), "totalAmount".to_string()),
+                ("c1".to_string(), "name".to_string()),
+                ("c1".to_string(), "archived".to_string()),
+            ]
+        );
+        assert_eq!(report.issues[1].column_value, serde_json::json!(1000.0));
+        assert_eq!(report.issues[1].json_value, serde_json::json!(1200.0));
+        assert_eq!(report.unreadable.len(), 1);
+        assert_eq!(report.unreadable[0].id, "i3");
+    }
+
+    #[test]
+    fn repairs_from_either_side() {
+        let mut conn = setup();
+        let result = repair_consistency(&mut conn, RepairStrategy::FromJson).unwrap();
+        assert_eq!((result.rows_repaired, result.fields_repaired), (2, 4));
+        assert!(result.remaining.issues.is_empty());
+        let (status, total, name, archived): (String, f64, String, bool) = conn
+            .query_row(
+                "SELECT i.status, i.totalAmount, c.name, c.archived FROM invoices i JOIN clients c ON c.id = i.clientId WHERE i.id = 'i1'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), total, name.as_str(), archived), ("DRAFT", 1200.0, "Klijent doo", false));
+
+        let mut conn = setup();
+        let result = repair_consistency(&mut conn, RepairStrategy::FromColumns).unwrap();
+        assert_eq!(result.rows_repaired, 2);
+        assert!(result.remaining.issues.is_empty());
+        let json: String = conn
+            .query_row("SELECT data_json FROM invoices WHERE id = 'i1'", [], |r| r.get(0))
+            .unwrap();
+        let invoice: JsonValue = serde_json::from_str(&json).unwrap();
+        assert_eq!((&invoice["status"], &invoice["total"]), (&serde_json::json!("PAID"), &serde_json::json!(1000.0)));
+        assert_eq!(invoice["clientName"], "Klijent");
+        let audits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'data.repair'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(audits, 2);
+    }
+}
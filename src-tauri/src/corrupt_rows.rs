@@ -0,0 +1,343 @@
+//! Invoice and client rows whose `data_json` no longer deserializes. The list readers used to skip
+//! them, so an invoice vanished from the app while the SQL reports kept counting it. They now log
+//! each such row and emit `data://corrupt-row`; `list_corrupt_rows` finds all of them and
+//! `repair_corrupt_row` rebuilds the document from the typed columns so the row can be edited again.
+//!
+//! A repair keeps every key of the old document that still fits, takes the mirrored fields from the
+//! columns, applies the caller's patch on top and records the old document in the audit log.
+
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value as JsonValue};
+use tauri::Emitter;
+
+use crate::data_events::{DataEntity, DataOp};
+use crate::errors::AppError;
+use crate::{audit, clients, Client, DbState, Invoice};
+
+pub(crate) const CORRUPT_ROW_EVENT: &str = "data://corrupt-row";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptRow {
+    /// `invoices` or `clients`.
+    pub table: String,
+    pub id: String,
+    /// Invoice number or client name, from the columns.
+    pub label: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptRowRepair {
+    pub table: String,
+    pub id: String,
+    /// Keys of the old document that had to be dropped to make it valid.
+    pub dropped_keys: Vec<String>,
+}
+
+/// Deserializes one row's `data_json`; a row that doesn't parse goes into `corrupt` instead.
+pub(crate) fn parse_row<T: DeserializeOwned>(
+    table: &str,
+    id: String,
+    label: String,
+    json: Option<String>,
+    corrupt: &mut Vec<CorruptRow>,
+) -> Option<T> {
+    let error = match json.as_deref().map(serde_json::from_str::<T>) {
+        Some(Ok(value)) => return Some(value),
+        Some(Err(e)) => e.to_string(),
+        None => "data_json is missing".to_string(),
+    };
+    corrupt.push(CorruptRow { table: table.to_string(), id, label, error });
+    None
+}
+
+/// Logs the rows a reader skipped and tells the UI about them.
+pub(crate) fn report(app: &tauri::AppHandle, rows: &[CorruptRow]) {
+    for row in rows {
+        tracing::warn!(target: "sqlite", table = %row.table, id = %row.id, error = %row.error, "skipped a row whose data_json does not parse");
+        let _ = app.emit(CORRUPT_ROW_EVENT, row);
+    }
+}
+
+fn scan<T: DeserializeOwned>(
+    conn: &Connection,
+    table: &str,
+    label_column: &str,
+    out: &mut Vec<CorruptRow>,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("SELECT id, {label_column}, data_json FROM {table} ORDER BY createdAt"))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        parse_row::<T>(table, row.get(0)?, row.get(1)?, row.get(2)?, out);
+    }
+    Ok(())
+}
+
+pub(crate) fn list_corrupt_rows_in_conn(conn: &Connection) -> Result<Vec<CorruptRow>, rusqlite::Error> {
+    let mut out = Vec::new();
+    scan::<Invoice>(conn, "invoices", "invoiceNumber", &mut out)?;
+    scan::<Client>(conn, "clients", "name", &mut out)?;
+    Ok(out)
+}
+
+/// The document rebuilt from the columns, and the stored `data_json`.
+type FromColumns = (Map<String, JsonValue>, Option<String>);
+
+/// The fields the columns hold, plus empty values for the other required ones.
+fn invoice_from_columns(conn: &Connection, id: &str) -> Result<Option<FromColumns>, rusqlite::Error> {
+    conn.query_row(
+        r#"SELECT i.invoiceNumber, i.clientId, COALESCE(c.name, ''), i.issueDate, i.status, i.dueDate, i.paidAt,
+                  i.currency, i.totalAmount, i.createdAt, i.data_json
+           FROM invoices i LEFT JOIN clients c ON c.id = i.clientId
+           WHERE i.id = ?1"#,
+        params![id],
+        |r| {
+            let issue_date: String = r.get(3)?;
+            let total: f64 = r.get(8)?;
+            let object = json!({
+                "id": id,
+                "invoiceNumber": r.get::<_, String>(0)?,
+                "clientId": r.get::<_, String>(1)?,
+                "clientName": r.get::<_, String>(2)?,
+                "issueDate": issue_date,
+                "serviceDate": issue_date,
+                "status": r.get::<_, String>(4)?,
+                "dueDate": r.get::<_, Option<String>>(5)?,
+                "paidAt": r.get::<_, Option<String>>(6)?,
+                "currency": r.get::<_, String>(7)?,
+                "items": [],
+                "subtotal": total,
+                "total": total,
+                "notes": "",
+                "createdAt": r.get::<_, String>(9)?,
+            });
+            Ok((object.as_object().cloned().unwrap_or_default(), r.get(10)?))
+        },
+    )
+    .optional()
+}
+
+fn client_from_columns(conn: &Connection, id: &str) -> Result<Option<FromColumns>, rusqlite::Error> {
+    conn.query_row(
+        r#"SELECT name, maticniBroj, pib, address, email, phone, website, defaultCurrency, preferredLanguage,
+                  paymentTermsDays, billingEmail, archived, isForeign, country, createdAt, data_json
+           FROM clients WHERE id = ?1"#,
+        params![id],
+        |r| {
+            let object = json!({
+                "id": id,
+                "name": r.get::<_, String>(0)?,
+                "registrationNumber": r.get::<_, String>(1)?,
+                "pib": r.get::<_, String>(2)?,
+                "address": r.get::<_, String>(3)?,
+                "email": r.get::<_, String>(4)?,
+                "phone": r.get::<_, Option<String>>(5)?,
+                "website": r.get::<_, Option<String>>(6)?,
+                "defaultCurrency": r.get::<_, Option<String>>(7)?,
+                "preferredLanguage": r.get::<_, Option<String>>(8)?,
+                "paymentTermsDays": r.get::<_, Option<i64>>(9)?,
+                "billingEmail": r.get::<_, Option<String>>(10)?,
+                "archived": r.get::<_, bool>(11)?,
+                "isForeign": r.get::<_, bool>(12)?,
+                "country": r.get::<_, Option<String>>(13)?,
+                "createdAt": r.get::<_, String>(14)?,
+            });
+            Ok((object.as_object().cloned().unwrap_or_default(), r.get(15)?))
+        },
+    )
+    .optional()
+}
+
+/// `base` with the keys of the old document the columns don't hold, one at a time, each kept only
+/// if the result still deserializes; then `patch` on top, which has to leave a valid `T`.
+fn rebuild<T: DeserializeOwned>(
+    entity: &str,
+    mut base: Map<String, JsonValue>,
+    old: Option<&str>,
+    patch: Option<JsonValue>,
+) -> Result<(T, Vec<String>), AppError> {
+    let fits = |object: &Map<String, JsonValue>| serde_json::from_value::<T>(JsonValue::Object(object.clone())).is_ok();
+    let mut dropped = Vec::new();
+    let old = old.and_then(|s| serde_json::from_str::<JsonValue>(s).ok());
+    if let Some(JsonValue::Object(old)) = old {
+        let column_keys: Vec<String> = base.keys().cloned().collect();
+        for (key, value) in old {
+            if column_keys.contains(&key) && !matches!(key.as_str(), "items" | "subtotal" | "notes" | "serviceDate" | "clientName") {
+                continue;
+            }
+            let previous = base.insert(key.clone(), value);
+            if !fits(&base) {
+                match previous {
+                    Some(v) => base.insert(key.clone(), v),
+                    None => base.remove(&key),
+                };
+                dropped.push(key);
+            }
+        }
+    }
+    match patch {
+        None | Some(JsonValue::Null) => {}
+        Some(JsonValue::Object(patch)) => base.extend(patch),
+        Some(_) => return Err(AppError::validation("patchJson", "invalid", "The patch must be a JSON object.")),
+    }
+    let value = serde_json::from_value(JsonValue::Object(base)).map_err(|e| {
+        AppError::validation("patchJson", "invalid", format!("The repaired {entity} is still not valid: {e}"))
+    })?;
+    Ok((value, dropped))
+}
+
+/// Rebuilds the row with `id` in `invoices` or `clients`; see the module docs.
+pub(crate) fn repair_corrupt_row_in_conn(
+    conn: &mut Connection,
+    id: &str,
+    patch: Option<JsonValue>,
+) -> Result<CorruptRowRepair, AppError> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let (table, old, dropped_keys) = if let Some((base, old)) = invoice_from_columns(&tx, id)? {
+        let (invoice, dropped) = rebuild::<Invoice>("invoice", base, old.as_deref(), patch)?;
+        tx.execute(
+            r#"UPDATE invoices SET invoiceNumber=?2, clientId=?3, issueDate=?4, status=?5, dueDate=?6, paidAt=?7,
+                   currency=?8, totalAmount=?9, data_json=?10
+               WHERE id=?1"#,
+            params![
+                id,
+                invoice.invoice_number,
+                invoice.client_id,
+                invoice.issue_date,
+                invoice.status.as_str(),
+                invoice.due_date,
+                invoice.paid_at,
+                invoice.currency,
+                invoice.total,
+                serde_json::to_string(&invoice).unwrap_or_else(|_| "{}".to_string()),
+            ],
+        )?;
+        ("invoices", old, dropped)
+    } else if let Some((base, old)) = client_from_columns(&tx, id)? {
+        let (client, dropped) = rebuild::<Client>("client", base, old.as_deref(), patch)?;
+        clients::write_client_row(&tx, &client)?;
+        ("clients", old, dropped)
+    } else {
+        return Err(AppError::not_found("invoice", id));
+    };
+    audit::record(
+        &tx,
+        "data.rebuild",
+        id,
+        &json!({ "table": table, "before": old, "droppedKeys": dropped_keys }),
+    )?;
+    tx.commit()?;
+    Ok(CorruptRowRepair { table: table.to_string(), id: id.to_string(), dropped_keys })
+}
+
+/// Invoices and clients the app can't read.
+#[tauri::command]
+pub(crate) async fn list_corrupt_rows(state: tauri::State<'_, DbState>) -> Result<Vec<CorruptRow>, AppError> {
+    state.with_read("list_corrupt_rows", list_corrupt_rows_in_conn).await
+}
+
+/// Rebuilds an unreadable row from its columns; `patch_json` (an object) is applied on top.
+#[tauri::command]
+pub(crate) async fn repair_corrupt_row(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    patch_json: Option<String>,
+) -> Result<CorruptRowRepair, AppError> {
+    let patch = patch_json
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| serde_json::from_str::<JsonValue>(&s))
+        .transpose()
+        .map_err(|e| AppError::validation("patchJson", "invalid", format!("The patch is not valid JSON: {e}")))?;
+    state
+        .with_write_notify("repair_corrupt_row", move |conn, changes| {
+            let repaired = repair_corrupt_row_in_conn(conn, &id, patch)?;
+            let entity = if repaired.table == "invoices" { DataEntity::Invoices } else { DataEntity::Clients };
+            changes.record(entity, DataOp::Updated, repaired.id.clone());
+            Ok(repaired)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            r#"INSERT INTO clients (id, name, pib, address, email, createdAt, data_json)
+               VALUES ('c1', 'Klijent', '100000024', 'Ulica 1', 'k@example.com', 't1',
+                       '{"id":"c1","name":"Klijent","pib":"100000024","address":"Ulica 1","email":"k@example.com","createdAt":"t1"}');
+               INSERT INTO clients (id, name, pib, address, email, phone, createdAt, data_json)
+               VALUES ('c2', 'Drugi', '', '', '', '011', 't2', NULL);
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+               VALUES ('i1', '2025-1', 'c1', '2025-01-10', 'SENT', 'RSD', 1200, 't1',
+                       '{"id":"i1","invoiceNumber":"2025-1","clientId":"c1","clientName":"Klijent","issueDate":"2025-01-10",
+                         "serviceDate":"2025-01-10","status":"SENT","currency":"RSD","items":[],"subtotal":1200,"total":1200,
+                         "notes":"","createdAt":"t1"}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+               VALUES ('i2', '2025-2', 'c1', '2025-01-11', 'PAID', 'RSD', 500, 't2',
+                       '{"id":"i2","invoiceNumber":"2025-2","status":"WHATEVER","total":"five hundred","notes":"Hvala",
+                         "items":[{"id":"x","description":"Rad","quantity":1,"unitPrice":500,"total":500}]}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+               VALUES ('i3', '2025-3', 'c1', '2025-01-12', 'RSD', 1, 't3', '{not json');"#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn unreadable_rows_are_reported_not_dropped() {
+        let conn = setup();
+        let corrupt = list_corrupt_rows_in_conn(&conn).unwrap();
+        let ids: Vec<_> = corrupt.iter().map(|r| (r.table.as_str(), r.id.as_str(), r.label.as_str())).collect();
+        assert_eq!(ids, vec![("invoices", "i2", "2025-2"), ("invoices", "i3", "2025-3"), ("clients", "c2", "Drugi")]);
+        assert_eq!(corrupt[2].error, "data_json is missing");
+
+        // Every invoice row is either returned or reported.
+        let mut stmt = conn.prepare("SELECT id, invoiceNumber, data_json FROM invoices").unwrap();
+        let mut rows = stmt.query([]).unwrap();
+        let (mut read, mut skipped) = (Vec::new(), Vec::new());
+        while let Some(r) = rows.next().unwrap() {
+            if let Some(inv) = parse_row::<Invoice>("invoices", r.get(0).unwrap(), r.get(1).unwrap(), r.get(2).unwrap(), &mut skipped) {
+                read.push(inv.id);
+            }
+        }
+        assert_eq!((read.len(), skipped.len()), (1, 2));
+    }
+
+    #[test]
+    fn repair_rebuilds_from_columns_and_keeps_what_still_fits() {
+        let mut conn = setup();
+        let repaired = repair_corrupt_row_in_conn(&mut conn, "i2", None).unwrap();
+        assert_eq!(repaired.table, "invoices");
+        assert!(repaired.dropped_keys.is_empty(), "{:?}", repaired.dropped_keys);
+        let json: String = conn.query_row("SELECT data_json FROM invoices WHERE id = 'i2'", [], |r| r.get(0)).unwrap();
+        let invoice: Invoice = serde_json::from_str(&json).unwrap();
+        // Columns win for the mirrored fields; the items and notes of the old document survive.
+        assert_eq!((invoice.status.as_str(), invoice.total, invoice.client_name.as_str()), ("PAID", 500.0, "Klijent"));
+        assert_eq!((invoice.items.len(), invoice.notes.as_str()), (1, "Hvala"));
+
+        let patch = json!({ "notes": "Popravljeno", "total": 750.0 });
+        repair_corrupt_row_in_conn(&mut conn, "i3", Some(patch)).unwrap();
+        let total: f64 = conn.query_row("SELECT totalAmount FROM invoices WHERE id = 'i3'", [], |r| r.get(0)).unwrap();
+        assert_eq!(total, 750.0);
+
+        let err = repair_corrupt_row_in_conn(&mut conn, "c2", Some(json!({ "archived": "yes" }))).unwrap_err();
+        assert_eq!(err.code(), "VALIDATION");
+        let repaired = repair_corrupt_row_in_conn(&mut conn, "c2", None).unwrap();
+        assert_eq!(repaired.table, "clients");
+        assert!(list_corrupt_rows_in_conn(&conn).unwrap().is_empty());
+        assert!(repair_corrupt_row_in_conn(&mut conn, "missing", None).is_err());
+
+        let audits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log WHERE action = 'data.rebuild'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(audits, 3);
+    }
+}
@@ -0,0 +1,138 @@
+//! Minimal CSV reader for imports: RFC 4180 quoting, UTF-8 BOM, and comma or semicolon
+//! delimiters (Excel in a Serbian locale writes semicolons).
+
+use crate::errors::AppError;
+
+/// Parsed file: the header row plus data rows, each with its 1-based line number in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<(usize, Vec<String>)>,
+}
+
+impl CsvTable {
+    /// Index of a header, compared case-insensitively and ignoring surrounding whitespace.
+    pub(crate) fn column(&self, header: &str) -> Option<usize> {
+        let wanted = header.trim();
+        self.headers.iter().position(|h| h.trim().eq_ignore_ascii_case(wanted))
+    }
+}
+
+/// Picks `;` when the first line has more semicolons than commas outside quotes.
+fn detect_delimiter(text: &str) -> char {
+    let (mut commas, mut semicolons, mut in_quotes) = (0, 0, false);
+    for c in text.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => commas += 1,
+            ';' if !in_quotes => semicolons += 1,
+            '\n' if !in_quotes => break,
+            _ => {}
+        }
+    }
+    if semicolons > commas {
+        ';'
+    } else {
+        ','
+    }
+}
+
+fn parse_records(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!("CSV line {record_line}: unterminated quoted field."));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    // Blank lines (typically a trailing newline or separator rows) carry no data.
+    records.retain(|(_, r)| r.iter().any(|f| !f.trim().is_empty()));
+    Ok(records)
+}
+
+pub(crate) fn parse_csv(text: &str) -> Result<CsvTable, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = parse_records(text, detect_delimiter(text))?.into_iter();
+    let (_, headers) = records.next().ok_or_else(|| "The CSV file is empty.".to_string())?;
+    Ok(CsvTable {
+        headers: headers.into_iter().map(|h| h.trim().to_string()).collect(),
+        rows: records.collect(),
+    })
+}
+
+pub(crate) fn read_csv_file(path: &str) -> Result<CsvTable, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let text = String::from_utf8(bytes)
+        .map_err(|_| "The CSV file is not UTF-8 encoded; save it as \"CSV UTF-8\" and try again.".to_string())?;
+    parse_csv(&text)
+}
+
+/// Header row of a CSV file, for building an import column mapping in the UI.
+#[tauri::command]
+pub(crate) async fn read_csv_headers(path: String) -> Result<Vec<String>, AppError> {
+    Ok(read_csv_file(&path)?.headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quotes_bom_and_semicolons() {
+        let table = parse_csv("\u{feff}Naziv;PIB;Adresa\r\n\"Firma \"\"A\"\" doo\";100000001;\"Ulica 1;\nBeograd\"\r\n\r\nB;;x\r\n").unwrap();
+        assert_eq!(table.headers, vec!["Naziv", "PIB", "Adresa"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                (2, vec!["Firma \"A\" doo".to_string(), "100000001".into(), "Ulica 1;\nBeograd".into()]),
+                (5, vec!["B".to_string(), "".into(), "x".into()]),
+            ]
+        );
+        assert_eq!(table.column(" pib "), Some(1));
+    }
+
+    #[test]
+    fn comma_delimiter_and_unterminated_quote() {
+        let table = parse_csv("name,email\nA,a@example.com").unwrap();
+        assert_eq!(table.rows, vec![(2, vec!["A".to_string(), "a@example.com".into()])]);
+        assert!(parse_csv("name\n\"A").unwrap_err().contains("line 2"));
+    }
+}
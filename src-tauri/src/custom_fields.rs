@@ -0,0 +1,117 @@
+//! Extra label/value rows on an invoice, for clients that want their PO number or cost-center code
+//! printed on it. They go into the details block of the PDF and the email, and into the CSV export
+//! as one JSON column. Settings keep a list of default labels the invoice form offers.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+
+pub(crate) const MAX_CUSTOM_FIELDS: usize = 5;
+const MAX_LABEL_CHARS: usize = 40;
+const MAX_VALUE_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+fn too_long(field: &str, what: &str, max: usize) -> AppError {
+    AppError::validation(field, "tooLong", format!("{what} can be at most {max} characters."))
+}
+
+fn too_many(field: &str) -> AppError {
+    AppError::validation(field, "tooMany", format!("At most {MAX_CUSTOM_FIELDS} custom fields are allowed."))
+}
+
+/// Trims the rows and drops the ones without a value (a default label the user left empty).
+pub(crate) fn normalize_custom_fields(fields: Vec<CustomField>) -> Result<Vec<CustomField>, AppError> {
+    let mut out = Vec::new();
+    for f in fields {
+        let label = f.label.trim().to_string();
+        let value = f.value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        if label.is_empty() {
+            return Err(AppError::validation(
+                "customFields",
+                "labelRequired",
+                format!("The custom field \"{value}\" needs a label."),
+            ));
+        }
+        if label.chars().count() > MAX_LABEL_CHARS {
+            return Err(too_long("customFields", "Custom field labels", MAX_LABEL_CHARS));
+        }
+        if value.chars().count() > MAX_VALUE_CHARS {
+            return Err(too_long("customFields", "Custom field values", MAX_VALUE_CHARS));
+        }
+        out.push(CustomField { label, value });
+    }
+    if out.len() > MAX_CUSTOM_FIELDS {
+        return Err(too_many("customFields"));
+    }
+    Ok(out)
+}
+
+/// Default labels from the settings form: trimmed, without blanks and repeats.
+pub(crate) fn normalize_custom_field_labels(labels: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut out: Vec<String> = Vec::new();
+    for label in labels {
+        let label = label.trim();
+        if label.is_empty() || out.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            continue;
+        }
+        if label.chars().count() > MAX_LABEL_CHARS {
+            return Err(too_long("customFieldLabels", "Custom field labels", MAX_LABEL_CHARS));
+        }
+        out.push(label.to_string());
+    }
+    if out.len() > MAX_CUSTOM_FIELDS {
+        return Err(too_many("customFieldLabels"));
+    }
+    Ok(out)
+}
+
+/// The settings column; `[]` when there are none.
+pub(crate) fn custom_field_labels_json(labels: &[String]) -> String {
+    serde_json::to_string(labels).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// The CSV column: the fields as a JSON array, or empty when there are none.
+pub(crate) fn custom_fields_csv(fields: &[CustomField]) -> String {
+    if fields.is_empty() {
+        return String::new();
+    }
+    serde_json::to_string(fields).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(label: &str, value: &str) -> CustomField {
+        CustomField { label: label.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn fields_are_trimmed_and_limited() {
+        let fields = normalize_custom_fields(vec![field(" PO broj ", " 4500123 "), field("Mesto troška", "  ")]).unwrap();
+        assert_eq!(fields, vec![field("PO broj", "4500123")]);
+
+        let err = normalize_custom_fields(vec![field("", "4500123")]).unwrap_err();
+        assert_eq!(err.code(), "VALIDATION");
+        assert!(normalize_custom_fields(vec![field(&"x".repeat(41), "1")]).is_err());
+        assert!(normalize_custom_fields(vec![field("PO", &"9".repeat(121))]).is_err());
+        assert!(normalize_custom_fields(vec![field("A", "1"); 6]).is_err());
+
+        let labels = normalize_custom_field_labels(vec![" PO ".into(), "".into(), "po".into(), "Cost center".into()]).unwrap();
+        assert_eq!(labels, vec!["PO".to_string(), "Cost center".to_string()]);
+        assert_eq!(custom_fields_csv(&[]), "");
+        assert_eq!(custom_fields_csv(&fields), r#"[{"label":"PO broj","value":"4500123"}]"#);
+    }
+}
@@ -0,0 +1,511 @@
+//! Per-month revenue figures for the overview page, aggregated in SQL per currency.
+//! Invoiced/outstanding follow the issue date (accrual); paid follows `paidAt` (cash), so an
+//! invoice issued in March and paid in April counts as invoiced in March and paid in April.
+//!
+//! The top clients and top items rankings count paid revenue the same way. Both run on every
+//! dashboard load, so ranking, shares and the per-currency limit are all done in SQL; item lines
+//! are read with `json_each` instead of deserializing the invoices.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::{normalize_optional_date_range, DbState};
+
+const DEFAULT_TOP_LIMIT: u32 = 5;
+const MAX_TOP_LIMIT: u32 = 50;
+
+/// Payment day of a PAID invoice: `paidAt` may be a bare date or a full timestamp, and an invoice
+/// marked paid without one counts on its issue date.
+const PAID_ON_SQL: &str = "substr(COALESCE(NULLIF(TRIM(paidAt), ''), issueDate), 1, 10)";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardTotals {
+    /// SENT and PAID invoices issued in the period.
+    pub invoiced: f64,
+    pub invoiced_count: i64,
+    /// PAID invoices by payment date (issue date when `paidAt` is missing).
+    pub paid: f64,
+    pub paid_count: i64,
+    /// SENT invoices issued in the period that are still unpaid.
+    pub outstanding: f64,
+    pub outstanding_count: i64,
+    pub expenses: f64,
+    pub expense_count: i64,
+    /// Cash-basis result: `paid - expenses`.
+    pub net: f64,
+}
+
+impl DashboardTotals {
+    fn add(&mut self, other: &DashboardTotals) {
+        self.invoiced += other.invoiced;
+        self.invoiced_count += other.invoiced_count;
+        self.paid += other.paid;
+        self.paid_count += other.paid_count;
+        self.outstanding += other.outstanding;
+        self.outstanding_count += other.outstanding_count;
+        self.expenses += other.expenses;
+        self.expense_count += other.expense_count;
+    }
+
+    fn finish(&mut self) {
+        self.invoiced = round2(self.invoiced);
+        self.paid = round2(self.paid);
+        self.outstanding = round2(self.outstanding);
+        self.expenses = round2(self.expenses);
+        self.net = round2(self.paid - self.expenses);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardMonth {
+    /// `YYYY-MM`.
+    pub month: String,
+    #[serde(flatten)]
+    pub totals: DashboardTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardCurrency {
+    pub currency: String,
+    /// Always twelve entries, January first.
+    pub months: Vec<DashboardMonth>,
+    pub year_to_date: DashboardTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSummary {
+    pub year: i32,
+    pub currencies: Vec<DashboardCurrency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopClient {
+    pub client_id: String,
+    /// Current client name; the name on the newest invoice when the client was deleted.
+    pub name: String,
+    pub revenue: f64,
+    pub invoice_count: i64,
+    /// Percent of the currency's paid revenue in the period.
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopItem {
+    pub description: String,
+    pub revenue: f64,
+    /// Invoices the item appears on.
+    pub invoice_count: i64,
+    pub share: f64,
+}
+
+/// One currency's ranking; amounts in different currencies are never added up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopRanking<T> {
+    pub currency: String,
+    /// Paid revenue of the whole period, not only of the listed entries.
+    pub total: f64,
+    pub entries: Vec<T>,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn share(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        round2(part / total * 100.0)
+    } else {
+        0.0
+    }
+}
+
+/// Runs a ranking query whose rows start with `currency, currency_total`, grouping the entries
+/// by currency in the order the query returns them.
+fn collect_ranking<T, F>(
+    conn: &Connection,
+    sql: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    limit: u32,
+    entry: F,
+) -> Result<Vec<TopRanking<T>>, rusqlite::Error>
+where
+    F: Fn(&rusqlite::Row<'_>, f64) -> Result<T, rusqlite::Error>,
+{
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query(params![from, to, limit])?;
+    let mut out: Vec<TopRanking<T>> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let currency: String = row.get(0)?;
+        let total: f64 = row.get(1)?;
+        if out.last().is_none_or(|r| r.currency != currency) {
+            out.push(TopRanking { currency, total: round2(total), entries: Vec::new() });
+        }
+        let ranking = out.last_mut().expect("pushed above");
+        ranking.entries.push(entry(row, total)?);
+    }
+    Ok(out)
+}
+
+/// Clients by paid revenue between `from` and `to` (payment dates, inclusive, either open), at
+/// most `limit` per currency. Only PAID invoices count, so drafts and cancelled ones never do.
+pub(crate) fn top_clients_in_conn(
+    conn: &Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+    limit: u32,
+) -> Result<Vec<TopRanking<TopClient>>, rusqlite::Error> {
+    let sql = format!(
+        r#"WITH paid AS (
+             SELECT clientId, currency, totalAmount, json_extract(data_json, '$.clientName') AS client_name,
+                    issueDate
+             FROM invoices
+             WHERE status = 'PAID'
+               AND (?1 IS NULL OR {PAID_ON_SQL} >= ?1) AND (?2 IS NULL OR {PAID_ON_SQL} <= ?2)
+           ),
+           per_client AS (
+             SELECT currency, clientId, SUM(totalAmount) AS revenue, COUNT(*) AS invoice_count,
+                    (SELECT p2.client_name FROM paid p2
+                     WHERE p2.clientId = paid.clientId AND p2.currency = paid.currency
+                     ORDER BY p2.issueDate DESC LIMIT 1) AS invoice_name
+             FROM paid
+             GROUP BY currency, clientId
+           ),
+           ranked AS (
+             SELECT *, SUM(revenue) OVER (PARTITION BY currency) AS currency_total,
+                    ROW_NUMBER() OVER (PARTITION BY currency ORDER BY revenue DESC, clientId) AS rank
+             FROM per_client
+           )
+           SELECT r.currency, r.currency_total, r.clientId, COALESCE(c.name, r.invoice_name, ''),
+                  r.revenue, r.invoice_count
+           FROM ranked r LEFT JOIN clients c ON c.id = r.clientId
+           WHERE r.rank <= ?3
+           ORDER BY r.currency, r.rank"#
+    );
+    collect_ranking(conn, &sql, from, to, limit, |r, total| {
+        let revenue: f64 = r.get(4)?;
+        Ok(TopClient {
+            client_id: r.get(2)?,
+            name: r.get(3)?,
+            revenue: round2(revenue),
+            invoice_count: r.get(5)?,
+            share: share(revenue, total),
+        })
+    })
+}
+
+/// Invoice line descriptions by paid revenue, like `top_clients_in_conn`. A line's revenue is its
+/// total less its part of the invoice-level discount, so the items of an invoice add up to what
+/// was paid for it.
+pub(crate) fn top_items_in_conn(
+    conn: &Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+    limit: u32,
+) -> Result<Vec<TopRanking<TopItem>>, rusqlite::Error> {
+    let sql = format!(
+        r#"WITH lines AS (
+             SELECT i.id AS invoice_id, i.currency, i.totalAmount,
+                    TRIM(COALESCE(json_extract(it.value, '$.description'), '')) AS description,
+                    COALESCE(json_extract(it.value, '$.total'), 0) AS line_total,
+                    SUM(COALESCE(json_extract(it.value, '$.total'), 0)) OVER (PARTITION BY i.id) AS lines_total
+             FROM invoices i, json_each(i.data_json, '$.items') it
+             WHERE i.status = 'PAID'
+               AND (?1 IS NULL OR {PAID_ON_SQL} >= ?1) AND (?2 IS NULL OR {PAID_ON_SQL} <= ?2)
+           ),
+           per_item AS (
+             SELECT currency, description,
+                    SUM(CASE WHEN lines_total > 0 THEN line_total * totalAmount / lines_total ELSE 0 END) AS revenue,
+                    COUNT(DISTINCT invoice_id) AS invoice_count
+             FROM lines
+             WHERE description <> ''
+             GROUP BY currency, description
+           ),
+           ranked AS (
+             SELECT *, SUM(revenue) OVER (PARTITION BY currency) AS currency_total,
+                    ROW_NUMBER() OVER (PARTITION BY currency ORDER BY revenue DESC, description) AS rank
+             FROM per_item
+           )
+           SELECT currency, currency_total, description, revenue, invoice_count
+           FROM ranked
+           WHERE rank <= ?3
+           ORDER BY currency, rank"#
+    );
+    collect_ranking(conn, &sql, from, to, limit, |r, total| {
+        let revenue: f64 = r.get(3)?;
+        Ok(TopItem {
+            description: r.get(2)?,
+            revenue: round2(revenue),
+            invoice_count: r.get(4)?,
+            share: share(revenue, total),
+        })
+    })
+}
+
+fn top_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_TOP_LIMIT).clamp(1, MAX_TOP_LIMIT)
+}
+
+/// Runs `sql` (binding the year's first and last day) and folds each `(currency, month, …)` row
+/// into `months` with `apply`.
+fn collect<F>(
+    conn: &Connection,
+    sql: &str,
+    year: i32,
+    months: &mut BTreeMap<String, [DashboardTotals; 12]>,
+    apply: F,
+) -> Result<(), rusqlite::Error>
+where
+    F: Fn(&mut DashboardTotals, &rusqlite::Row<'_>) -> Result<(), rusqlite::Error>,
+{
+    let mut stmt = conn.prepare(sql)?;
+    let mut rows = stmt.query(params![format!("{year:04}-01-01"), format!("{year:04}-12-31")])?;
+    while let Some(row) = rows.next()? {
+        let currency: String = row.get(0)?;
+        let month: String = row.get(1)?;
+        let Some(idx) = month.parse::<usize>().ok().filter(|m| (1..=12).contains(m)) else {
+            continue;
+        };
+        let slot = &mut months.entry(currency).or_default()[idx - 1];
+        apply(slot, row)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn dashboard_summary_in_conn(conn: &Connection, year: i32) -> Result<DashboardSummary, rusqlite::Error> {
+    let mut months: BTreeMap<String, [DashboardTotals; 12]> = BTreeMap::new();
+
+    collect(
+        conn,
+        r#"SELECT currency, substr(issueDate, 6, 2),
+                  SUM(totalAmount), COUNT(*),
+                  SUM(CASE WHEN status = 'SENT' THEN totalAmount ELSE 0 END),
+                  SUM(CASE WHEN status = 'SENT' THEN 1 ELSE 0 END)
+           FROM invoices
+           WHERE status IN ('SENT', 'PAID') AND issueDate >= ?1 AND issueDate <= ?2
+           GROUP BY 1, 2"#,
+        year,
+        &mut months,
+        |t, r| {
+            t.invoiced = r.get(2)?;
+            t.invoiced_count = r.get(3)?;
+            t.outstanding = r.get(4)?;
+            t.outstanding_count = r.get(5)?;
+            Ok(())
+        },
+    )?;
+
+    // paidAt may be a bare date or a full timestamp; only the date part decides the month.
+    collect(
+        conn,
+        r#"SELECT currency, substr(paid_on, 6, 2), SUM(totalAmount), COUNT(*)
+           FROM (
+             SELECT currency, totalAmount,
+                    substr(COALESCE(NULLIF(TRIM(paidAt), ''), issueDate), 1, 10) AS paid_on
+             FROM invoices
+             WHERE status = 'PAID'
+           )
+           WHERE paid_on >= ?1 AND paid_on <= ?2
+           GROUP BY 1, 2"#,
+        year,
+        &mut months,
+        |t, r| {
+            t.paid = r.get(2)?;
+            t.paid_count = r.get(3)?;
+            Ok(())
+        },
+    )?;
+
+    collect(
+        conn,
+        r#"SELECT currency, substr(date, 6, 2), SUM(amount), COUNT(*)
+           FROM expenses
+           WHERE deletedAt IS NULL AND date >= ?1 AND date <= ?2
+           GROUP BY 1, 2"#,
+        year,
+        &mut months,
+        |t, r| {
+            t.expenses = r.get(2)?;
+            t.expense_count = r.get(3)?;
+            Ok(())
+        },
+    )?;
+
+    let currencies = months
+        .into_iter()
+        .map(|(currency, totals)| {
+            let mut year_to_date = DashboardTotals::default();
+            let months = totals
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut t)| {
+                    year_to_date.add(&t);
+                    t.finish();
+                    DashboardMonth {
+                        month: format!("{year:04}-{:02}", i + 1),
+                        totals: t,
+                    }
+                })
+                .collect();
+            year_to_date.finish();
+            DashboardCurrency {
+                currency,
+                months,
+                year_to_date,
+            }
+        })
+        .collect();
+
+    Ok(DashboardSummary { year, currencies })
+}
+
+/// Monthly invoiced/paid/outstanding/expense totals for `year`, per currency.
+#[tauri::command]
+pub(crate) async fn dashboard_summary(
+    state: tauri::State<'_, DbState>,
+    year: i32,
+) -> Result<DashboardSummary, AppError> {
+    state
+        .with_read("dashboard_summary", move |conn| dashboard_summary_in_conn(conn, year))
+        .await
+}
+
+/// Best-paying clients per currency between `from` and `to` (`YYYY-MM-DD`, both optional).
+#[tauri::command]
+pub(crate) async fn top_clients(
+    state: tauri::State<'_, DbState>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TopRanking<TopClient>>, AppError> {
+    let (from, to) = normalize_optional_date_range(from, to)?;
+    state
+        .with_read("top_clients", move |conn| {
+            top_clients_in_conn(conn, from.as_deref(), to.as_deref(), top_limit(limit))
+        })
+        .await
+}
+
+/// Best-selling invoice items per currency between `from` and `to` (`YYYY-MM-DD`, both optional).
+#[tauri::command]
+pub(crate) async fn top_items(
+    state: tauri::State<'_, DbState>,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TopRanking<TopItem>>, AppError> {
+    let (from, to) = normalize_optional_date_range(from, to)?;
+    state
+        .with_read("top_items", move |conn| {
+            top_items_in_conn(conn, from.as_deref(), to.as_deref(), top_limit(limit))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{client, expense, invoice};
+
+    #[test]
+    fn paid_follows_payment_month_while_invoiced_follows_issue_month() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let paid = |id: &str, issue: &str, paid_at: &str, currency: &str, total: f64| {
+            invoice(id).issued(issue).status("PAID").paid_at(paid_at).amount(currency, total).insert(&conn)
+        };
+        paid("a", "2025-03-20", "2025-04-02T10:00:00Z", "RSD", 1000.0);
+        invoice("b").issued("2025-03-25").amount("RSD", 500.0).insert(&conn);
+        invoice("c").issued("2025-03-01").status("DRAFT").amount("RSD", 9999.0).insert(&conn);
+        paid("d", "2025-04-10", "2025-04-11", "EUR", 100.0);
+        paid("e", "2024-12-30", "2025-01-03", "RSD", 200.0);
+        expense("x1").title("Knjigovođa").on("2025-04-15").amount("RSD", 300.0).insert(&conn);
+        expense("x2").title("Obrisano").on("2025-04-16").amount("RSD", 50.0).insert(&conn);
+        conn.execute("UPDATE expenses SET deletedAt = 't' WHERE id = 'x2'", []).unwrap();
+
+        let s = dashboard_summary_in_conn(&conn, 2025).unwrap();
+        let currencies: Vec<_> = s.currencies.iter().map(|c| c.currency.as_str()).collect();
+        assert_eq!(currencies, vec!["EUR", "RSD"]);
+        let rsd = &s.currencies[1];
+        assert_eq!(rsd.months.len(), 12);
+
+        let jan = &rsd.months[0].totals;
+        assert_eq!((jan.invoiced, jan.paid, jan.paid_count), (0.0, 200.0, 1));
+        let mar = &rsd.months[2].totals;
+        assert_eq!(rsd.months[2].month, "2025-03");
+        assert_eq!((mar.invoiced, mar.invoiced_count, mar.paid), (1500.0, 2, 0.0));
+        assert_eq!((mar.outstanding, mar.outstanding_count), (500.0, 1));
+        let apr = &rsd.months[3].totals;
+        assert_eq!((apr.invoiced, apr.paid, apr.expenses, apr.expense_count, apr.net), (0.0, 1000.0, 300.0, 1, 700.0));
+
+        let ytd = &rsd.year_to_date;
+        assert_eq!((ytd.invoiced, ytd.invoiced_count, ytd.paid, ytd.paid_count), (1500.0, 2, 1200.0, 2));
+        assert_eq!((ytd.outstanding, ytd.expenses, ytd.net), (500.0, 300.0, 900.0));
+        assert_eq!(s.currencies[0].year_to_date.paid, 100.0);
+
+        let prev = dashboard_summary_in_conn(&conn, 2024).unwrap();
+        assert_eq!(prev.currencies[0].months[11].totals.invoiced, 200.0);
+        assert_eq!(prev.currencies[0].year_to_date.paid, 0.0);
+    }
+
+    fn insert_paid(conn: &Connection, id: &str, client: &str, paid_at: &str, currency: &str, total: f64, items: &str) {
+        invoice(id)
+            .client(client, &format!("{client} (stari naziv)"))
+            .status("PAID")
+            .paid_at(paid_at)
+            .amount(currency, total)
+            .items(serde_json::from_str(items).unwrap())
+            .insert(conn);
+    }
+
+    #[test]
+    fn rankings_are_per_currency_and_count_only_paid_invoices() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        client("acme", "Acme d.o.o.").insert(&conn);
+        // 1000 of lines with a 100 invoice discount: each line gives up a tenth.
+        insert_paid(&conn, "a", "acme", "2025-02-01", "RSD", 900.0,
+            r#"[{"description": "Razvoj", "total": 800}, {"description": "Hosting", "total": 200}]"#);
+        insert_paid(&conn, "b", "beta", "2025-02-10T09:00:00Z", "RSD", 300.0, r#"[{"description": "Hosting", "total": 300}]"#);
+        insert_paid(&conn, "c", "acme", "2025-03-01", "EUR", 50.0, r#"[{"description": "Razvoj", "total": 50}]"#);
+        insert_paid(&conn, "old", "beta", "2024-12-31", "RSD", 5000.0, r#"[{"description": "Hosting", "total": 5000}]"#);
+        invoice("sent").issued("2025-02-01").amount("RSD", 7000.0).insert(&conn);
+        invoice("cancelled")
+            .issued("2025-02-01")
+            .status("CANCELLED")
+            .paid_at("2025-02-01")
+            .amount("RSD", 7000.0)
+            .insert(&conn);
+
+        let clients = top_clients_in_conn(&conn, Some("2025-01-01"), Some("2025-12-31"), 5).unwrap();
+        assert_eq!(clients.iter().map(|c| c.currency.as_str()).collect::<Vec<_>>(), vec!["EUR", "RSD"]);
+        let rsd = &clients[1];
+        assert_eq!(rsd.total, 1200.0);
+        assert_eq!(rsd.entries[0].name, "Acme d.o.o.");
+        assert_eq!((rsd.entries[0].revenue, rsd.entries[0].invoice_count, rsd.entries[0].share), (900.0, 1, 75.0));
+        // A client that no longer exists keeps the name from its invoices.
+        assert_eq!(rsd.entries[1].name, "beta (stari naziv)");
+
+        let limited = top_clients_in_conn(&conn, None, None, 1).unwrap();
+        let rsd = &limited[1];
+        assert_eq!((rsd.total, rsd.entries.len(), rsd.entries[0].client_id.as_str()), (6200.0, 1, "beta"));
+
+        let items = top_items_in_conn(&conn, Some("2025-01-01"), Some("2025-12-31"), 5).unwrap();
+        let rsd = &items[1];
+        assert_eq!(rsd.total, 1200.0);
+        let ranked: Vec<_> = rsd.entries.iter().map(|i| (i.description.as_str(), i.revenue, i.invoice_count)).collect();
+        assert_eq!(ranked, vec![("Razvoj", 720.0, 1), ("Hosting", 480.0, 2)]);
+        assert_eq!(rsd.entries[1].share, 40.0);
+        assert_eq!(items[0].entries[0].revenue, 50.0);
+    }
+}
@@ -0,0 +1,497 @@
+//! Whole-database JSON archive for moving to another machine. Rows are stored column-for-column
+//! so a round trip is exact; the SMTP password is never written out. `formatVersion` describes
+//! the document layout and `schemaVersion` the database it came from.
+
+use std::collections::HashMap;
+
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::data_events::DataEntity;
+use crate::{now_iso, validation_to_sql_error, write_text_file, DbState, SETTINGS_ID};
+
+pub(crate) const ARCHIVE_FORMAT: &str = "pausaler-data";
+pub(crate) const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+type Row = Map<String, Value>;
+
+/// Exported tables with their key column, in import order (parents before the rows that
+/// reference them).
+const TABLES: [(&str, &str); 12] = [
+    ("app_meta", "key"),
+    ("catalog_items", "id"),
+    ("clients", "id"),
+    ("invoices", "id"),
+    ("recurring_expenses", "id"),
+    ("expenses", "id"),
+    ("offers", "id"),
+    ("obligations", "id"),
+    ("obligation_months", "month"),
+    ("invoice_payments", "id"),
+    ("time_entries", "id"),
+    ("quotes", "id"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataArchive {
+    pub format: String,
+    pub format_version: u32,
+    /// `PRAGMA user_version` of the exporting database.
+    pub schema_version: i64,
+    pub exported_at: String,
+    pub app_version: String,
+    pub settings: Option<Row>,
+    #[serde(default)]
+    pub app_meta: Vec<Row>,
+    #[serde(default)]
+    pub catalog_items: Vec<Row>,
+    #[serde(default)]
+    pub clients: Vec<Row>,
+    #[serde(default)]
+    pub invoices: Vec<Row>,
+    #[serde(default)]
+    pub recurring_expenses: Vec<Row>,
+    #[serde(default)]
+    pub expenses: Vec<Row>,
+    #[serde(default)]
+    pub offers: Vec<Row>,
+    #[serde(default)]
+    pub obligations: Vec<Row>,
+    #[serde(default)]
+    pub obligation_months: Vec<Row>,
+    #[serde(default)]
+    pub invoice_payments: Vec<Row>,
+    #[serde(default)]
+    pub time_entries: Vec<Row>,
+    #[serde(default)]
+    pub quotes: Vec<Row>,
+}
+
+impl DataArchive {
+    fn rows_mut(&mut self, table: &str) -> &mut Vec<Row> {
+        match table {
+            "app_meta" => &mut self.app_meta,
+            "catalog_items" => &mut self.catalog_items,
+            "clients" => &mut self.clients,
+            "invoices" => &mut self.invoices,
+            "recurring_expenses" => &mut self.recurring_expenses,
+            "expenses" => &mut self.expenses,
+            "obligations" => &mut self.obligations,
+            "obligation_months" => &mut self.obligation_months,
+            "invoice_payments" => &mut self.invoice_payments,
+            "time_entries" => &mut self.time_entries,
+            "quotes" => &mut self.quotes,
+            _ => &mut self.offers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveImportMode {
+    /// Wipe the data tables and the settings, then load the archive.
+    Replace,
+    /// Add archive rows next to the existing ones; current settings are kept.
+    Merge,
+}
+
+/// What to do in merge mode with a row whose id already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveConflict {
+    #[default]
+    Skip,
+    /// Insert under a new id; references from other archive rows follow it.
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTableReport {
+    pub table: String,
+    pub inserted: usize,
+    pub skipped: usize,
+    pub duplicated: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImportReport {
+    pub settings_imported: bool,
+    pub tables: Vec<ArchiveTableReport>,
+}
+
+fn sql_to_json(v: ValueRef<'_>) -> Value {
+    match v {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::from(b.to_vec()),
+    }
+}
+
+fn json_to_sql(v: &Value) -> SqlValue {
+    match v {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let cols = stmt.query_map([], |r| r.get::<_, String>(1))?.collect::<Result<Vec<_>, _>>()?;
+    Ok(cols)
+}
+
+fn read_rows(conn: &Connection, sql: &str) -> Result<Vec<Row>, rusqlite::Error> {
+    let mut stmt = conn.prepare(sql)?;
+    let names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+    let mut rows = stmt.query([])?;
+    let mut out = Vec::new();
+    while let Some(r) = rows.next()? {
+        let mut row = Row::new();
+        for (i, name) in names.iter().enumerate() {
+            row.insert(name.clone(), sql_to_json(r.get_ref(i)?));
+        }
+        out.push(row);
+    }
+    Ok(out)
+}
+
+/// Sets `smtpPassword` in the row and inside its `data_json` (when that parses).
+fn set_smtp_password(row: &mut Row, password: Value) {
+    if let Some(Value::String(json)) = row.get("data_json") {
+        if let Ok(Value::Object(mut data)) = serde_json::from_str::<Value>(json) {
+            data.insert("smtpPassword".to_string(), password.clone());
+            row.insert("data_json".to_string(), Value::from(Value::Object(data).to_string()));
+        }
+    }
+    row.insert("smtpPassword".to_string(), password);
+}
+
+pub(crate) fn export_archive_in_conn(conn: &Connection) -> Result<DataArchive, rusqlite::Error> {
+    let schema_version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    let mut archive = DataArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        format_version: ARCHIVE_FORMAT_VERSION,
+        schema_version,
+        exported_at: now_iso(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        ..Default::default()
+    };
+
+    let mut settings = read_rows(conn, &format!("SELECT * FROM settings WHERE id = '{SETTINGS_ID}'"))?;
+    archive.settings = settings.pop().map(|mut row| {
+        set_smtp_password(&mut row, Value::from(""));
+        row
+    });
+    for (table, key) in TABLES {
+        *archive.rows_mut(table) = read_rows(conn, &format!("SELECT * FROM {table} ORDER BY {key}"))?;
+    }
+    Ok(archive)
+}
+
+/// Brings an older archive layout up to the current one. Only version 1 exists so far.
+fn upgrade_archive(doc: Value) -> Result<DataArchive, String> {
+    let format = doc.get("format").and_then(Value::as_str).unwrap_or_default();
+    if format != ARCHIVE_FORMAT {
+        return Err("Not a Pausaler data archive.".to_string());
+    }
+    let version = doc.get("formatVersion").and_then(Value::as_u64).unwrap_or(0);
+    match version {
+        1 => serde_json::from_value(doc).map_err(|e| format!("Invalid data archive: {e}")),
+        v if v > u64::from(ARCHIVE_FORMAT_VERSION) => {
+            Err("This archive was created by a newer version of Pausaler. Update the app first.".to_string())
+        }
+        v => Err(format!("Unsupported data archive version {v}.")),
+    }
+}
+
+fn insert_row(conn: &Connection, table: &str, columns: &[String], row: &Row) -> Result<(), rusqlite::Error> {
+    let cols: Vec<&String> = columns.iter().filter(|c| row.contains_key(c.as_str())).collect();
+    let names = cols.iter().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", ");
+    let marks = (1..=cols.len()).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ");
+    conn.execute(
+        &format!("INSERT INTO {table} ({names}) VALUES ({marks})"),
+        params_from_iter(cols.iter().map(|c| json_to_sql(&row[c.as_str()]))),
+    )?;
+    Ok(())
+}
+
+fn replace_settings(conn: &Connection, mut row: Row) -> Result<(), rusqlite::Error> {
+    // The archive has no password; keep the one this machine has.
+    let current = read_rows(conn, &format!("SELECT smtpPassword FROM settings WHERE id = '{SETTINGS_ID}'"))?;
+    let password = current.first().and_then(|r| r.get("smtpPassword").cloned()).unwrap_or(Value::from(""));
+    set_smtp_password(&mut row, password);
+
+    let columns = table_columns(conn, "settings")?;
+    let cols: Vec<&String> = columns.iter().filter(|c| c.as_str() != "id" && row.contains_key(c.as_str())).collect();
+    let assignments = cols.iter().enumerate().map(|(i, c)| format!("\"{c}\" = ?{}", i + 2)).collect::<Vec<_>>().join(", ");
+    let mut values = vec![SqlValue::Text(SETTINGS_ID.to_string())];
+    values.extend(cols.iter().map(|c| json_to_sql(&row[c.as_str()])));
+    conn.execute(&format!("UPDATE settings SET {assignments} WHERE id = ?1"), params_from_iter(values))?;
+    Ok(())
+}
+
+/// Column of `table` that refers to an id of another archive table, with that table's name.
+fn references(table: &str) -> &'static [(&'static str, &'static str)] {
+    match table {
+        "invoices" => &[("clientId", "clients")],
+        "expenses" => &[("invoiceId", "invoices"), ("recurringId", "recurring_expenses")],
+        "obligation_months" => &[("obligationId", "obligations")],
+        "invoice_payments" => &[("invoiceId", "invoices")],
+        "time_entries" => &[("clientId", "clients"), ("billedInvoiceId", "invoices")],
+        "quotes" => &[("clientId", "clients"), ("invoiceId", "invoices")],
+        _ => &[],
+    }
+}
+
+/// Rewrites `column` (and the same key inside `data_json`) through `ids`.
+fn remap(row: &mut Row, column: &str, ids: &HashMap<String, String>) {
+    let Some(new_id) = row.get(column).and_then(Value::as_str).and_then(|old| ids.get(old)).cloned() else {
+        return;
+    };
+    if let Some(Value::String(json)) = row.get("data_json") {
+        if let Ok(Value::Object(mut data)) = serde_json::from_str::<Value>(json) {
+            if data.contains_key(column) {
+                data.insert(column.to_string(), Value::from(new_id.clone()));
+                row.insert("data_json".to_string(), Value::from(Value::Object(data).to_string()));
+            }
+        }
+    }
+    row.insert(column.to_string(), Value::from(new_id));
+}
+
+pub(crate) fn import_archive_in_conn(
+    conn: &mut Connection,
+    mut archive: DataArchive,
+    mode: ArchiveImportMode,
+    on_conflict: ArchiveConflict,
+) -> Result<ArchiveImportReport, rusqlite::Error> {
+    let schema_version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    if archive.schema_version > schema_version {
+        return Err(validation_to_sql_error(
+            "This archive was created by a newer version of Pausaler. Update the app first.".to_string(),
+        ));
+    }
+
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut settings_imported = false;
+    if mode == ArchiveImportMode::Replace {
+        for (table, _) in TABLES.iter().rev() {
+            tx.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        if let Some(row) = archive.settings.take() {
+            replace_settings(&tx, row)?;
+            settings_imported = true;
+        }
+    }
+
+    // Old id -> new id of rows duplicated in this import, per table.
+    let mut new_ids: HashMap<&str, HashMap<String, String>> = HashMap::new();
+    let mut tables = Vec::new();
+    for (table, key) in TABLES {
+        let columns = table_columns(&tx, table)?;
+        let mut report = ArchiveTableReport {
+            table: table.to_string(),
+            ..Default::default()
+        };
+        for mut row in std::mem::take(archive.rows_mut(table)) {
+            for (column, parent) in references(table) {
+                if let Some(ids) = new_ids.get(parent) {
+                    remap(&mut row, column, ids);
+                }
+            }
+            let id = row.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+            let exists: bool = tx.query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE {key} = ?1)"),
+                params![id],
+                |r| r.get(0),
+            )?;
+            if exists {
+                // app_meta keys can't be duplicated; the existing value wins.
+                if on_conflict == ArchiveConflict::Skip || key != "id" {
+                    report.skipped += 1;
+                    continue;
+                }
+                let new_id = Uuid::new_v4().to_string();
+                new_ids.entry(table).or_default().insert(id.clone(), new_id.clone());
+                remap(&mut row, "id", &HashMap::from([(id, new_id)]));
+                report.duplicated += 1;
+            } else {
+                report.inserted += 1;
+            }
+            insert_row(&tx, table, &columns, &row)?;
+        }
+        tables.push(report);
+    }
+    tx.commit()?;
+
+    Ok(ArchiveImportReport {
+        settings_imported,
+        tables,
+    })
+}
+
+/// Writes settings (without the SMTP password) and all business data to one JSON file.
+#[tauri::command]
+pub(crate) async fn export_all_json(state: tauri::State<'_, DbState>, output_path: String) -> Result<String, AppError> {
+    let archive = state.with_read("export_all_json", export_archive_in_conn).await?;
+    let json = serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+    write_text_file(std::path::Path::new(&output_path), &json)?;
+    Ok(output_path)
+}
+
+/// Loads a file written by `export_all_json` in one transaction.
+#[tauri::command]
+pub(crate) async fn import_all_json(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    mode: ArchiveImportMode,
+    on_conflict: Option<ArchiveConflict>,
+) -> Result<ArchiveImportReport, AppError> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read archive: {e}"))?;
+    let doc: Value = serde_json::from_str(&text).map_err(|e| format!("Invalid data archive: {e}"))?;
+    let archive = upgrade_archive(doc)?;
+    state
+        .with_create_notify("import_all_json", move |conn, changes| {
+            let report = import_archive_in_conn(conn, archive, mode, on_conflict.unwrap_or_default())?;
+            for entity in [DataEntity::Settings, DataEntity::Clients, DataEntity::Invoices, DataEntity::Expenses] {
+                changes.replaced(entity);
+            }
+            Ok(report)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        conn
+    }
+
+    fn seed(conn: &Connection) {
+        conn.execute_batch(
+            r#"UPDATE settings SET companyName = 'Firma', smtpPassword = 'tajna', nextInvoiceNumber = 8,
+                      data_json = '{"companyName":"Firma","smtpPassword":"tajna"}';
+               INSERT INTO app_meta (key, value) VALUES ('trial_start', '2025-01-01');
+               INSERT INTO clients (id, name, pib, address, email, paymentTermsDays, createdAt, data_json)
+                 VALUES ('c1', 'Klijent', '100000001', 'Ulica 1', 'k@example.com', 30, 't', '{"id":"c1","name":"Klijent"}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, paidAt, currency, totalAmount, createdAt, data_json)
+                 VALUES ('i1', 'INV-0007', 'c1', '2025-02-01', 'PAID', '2025-02-10', 'RSD', 1234.5, 't', '{"id":"i1","clientId":"c1"}');
+               INSERT INTO recurring_expenses (id, title, amount, currency, interval, startDate, nextRunDate, createdAt)
+                 VALUES ('r1', 'Zakup', 100, 'RSD', 'monthly', '2025-01-01', '2025-03-01', 't');
+               INSERT INTO expenses (id, title, amount, currency, date, createdAt, recurringId, invoiceId)
+                 VALUES ('e1', 'Zakup', 100, 'RSD', '2025-02-01', 't', 'r1', 'i1');"#,
+        )
+        .unwrap();
+    }
+
+    fn round_trip(archive: &DataArchive) -> DataArchive {
+        upgrade_archive(serde_json::from_str(&serde_json::to_string(archive).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn replace_round_trip_reproduces_the_data_without_the_password() {
+        let source = open_db();
+        seed(&source);
+        let exported = export_archive_in_conn(&source).unwrap();
+        let settings = exported.settings.as_ref().unwrap();
+        assert_eq!(settings["smtpPassword"], "");
+        assert!(!settings["data_json"].as_str().unwrap().contains("tajna"));
+
+        let mut target = open_db();
+        target.execute("UPDATE settings SET smtpPassword = 'lokalna'", []).unwrap();
+        target
+            .execute("INSERT INTO clients (id, name, pib, address, email, createdAt) VALUES ('old', 'Stari', '', '', '', 't')", [])
+            .unwrap();
+        let report = import_archive_in_conn(&mut target, round_trip(&exported), ArchiveImportMode::Replace, ArchiveConflict::Skip).unwrap();
+        assert!(report.settings_imported);
+
+        let mut again = export_archive_in_conn(&target).unwrap();
+        again.exported_at = exported.exported_at.clone();
+        assert_eq!(again, exported);
+        let password: String = target.query_row("SELECT smtpPassword FROM settings", [], |r| r.get(0)).unwrap();
+        assert_eq!(password, "lokalna");
+    }
+
+    #[test]
+    fn merge_skips_or_duplicates_colliding_ids() {
+        let source = open_db();
+        seed(&source);
+        let exported = export_archive_in_conn(&source).unwrap();
+
+        let mut skip = open_db();
+        seed(&skip);
+        let report = import_archive_in_conn(&mut skip, exported.clone(), ArchiveImportMode::Merge, ArchiveConflict::Skip).unwrap();
+        assert!(!report.settings_imported);
+        assert!(report.tables.iter().all(|t| t.inserted == 0 && t.duplicated == 0));
+        assert_eq!(report.tables.iter().map(|t| t.skipped).sum::<usize>(), 5);
+
+        let mut dup = open_db();
+        seed(&dup);
+        let report = import_archive_in_conn(&mut dup, exported, ArchiveImportMode::Merge, ArchiveConflict::Duplicate).unwrap();
+        let counts: Vec<_> = report.tables.iter().map(|t| (t.table.as_str(), t.skipped, t.duplicated)).collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("app_meta", 1, 0),
+                ("catalog_items", 0, 0),
+                ("clients", 0, 1),
+                ("invoices", 0, 1),
+                ("recurring_expenses", 0, 1),
+                ("expenses", 0, 1),
+                ("offers", 0, 0),
+                ("obligations", 0, 0),
+                ("obligation_months", 0, 0),
+                ("invoice_payments", 0, 0),
+                ("time_entries", 0, 0),
+                ("quotes", 0, 0),
+            ]
+        );
+
+        // The duplicated expense points at the duplicated invoice, which points at the new client.
+        let (invoice_id, recurring_id): (String, String) = dup
+            .query_row("SELECT invoiceId, recurringId FROM expenses WHERE id <> 'e1'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        let (client_id, json): (String, String) = dup
+            .query_row("SELECT clientId, data_json FROM invoices WHERE id = ?1", params![invoice_id], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_ne!(client_id, "c1");
+        assert!(json.contains(&invoice_id) && json.contains(&client_id), "{json}");
+        let name: String = dup.query_row("SELECT name FROM clients WHERE id = ?1", params![client_id], |r| r.get(0)).unwrap();
+        assert_eq!(name, "Klijent");
+        assert_ne!(recurring_id, "r1");
+    }
+
+    #[test]
+    fn archives_from_newer_versions_are_rejected() {
+        let mut doc = serde_json::to_value(export_archive_in_conn(&open_db()).unwrap()).unwrap();
+        doc["formatVersion"] = Value::from(ARCHIVE_FORMAT_VERSION + 1);
+        assert!(upgrade_archive(doc.clone()).unwrap_err().contains("newer version"));
+        doc["format"] = Value::from("something-else");
+        assert!(upgrade_archive(doc).is_err());
+
+        let mut archive = export_archive_in_conn(&open_db()).unwrap();
+        archive.schema_version += 1;
+        assert!(import_archive_in_conn(&mut open_db(), archive, ArchiveImportMode::Merge, ArchiveConflict::Skip).is_err());
+    }
+}
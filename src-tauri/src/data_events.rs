@@ -0,0 +1,116 @@
+//! `data://<entity>-changed` events, emitted after a write commits so every open window can
+//! refresh what it shows. Commands collect changes through [`DataChanges`] inside
+//! `DbState::with_write_notify`; nothing is emitted when the write fails.
+
+use serde::Serialize;
+use tauri::Emitter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DataEntity {
+    Invoices,
+    Clients,
+    Expenses,
+    Settings,
+}
+
+impl DataEntity {
+    pub(crate) fn event_name(self) -> &'static str {
+        match self {
+            DataEntity::Invoices => "data://invoices-changed",
+            DataEntity::Clients => "data://clients-changed",
+            DataEntity::Expenses => "data://expenses-changed",
+            DataEntity::Settings => "data://settings-changed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DataOp {
+    Created,
+    Updated,
+    Deleted,
+    /// Bulk change (archive import, repair): `ids` is empty and listeners should reload.
+    Replaced,
+}
+
+/// Payload of every `data://…-changed` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataChangedEvent {
+    pub(crate) op: DataOp,
+    pub(crate) ids: Vec<String>,
+}
+
+/// Changes made by one write, grouped per entity and operation.
+#[derive(Debug, Default)]
+pub(crate) struct DataChanges(Vec<(DataEntity, DataChangedEvent)>);
+
+impl DataChanges {
+    pub(crate) fn record(&mut self, entity: DataEntity, op: DataOp, id: impl Into<String>) {
+        self.record_all(entity, op, [id.into()]);
+    }
+
+    pub(crate) fn record_all(&mut self, entity: DataEntity, op: DataOp, ids: impl IntoIterator<Item = String>) {
+        let mut ids = ids.into_iter().peekable();
+        if ids.peek().is_none() {
+            return;
+        }
+        let pos = match self.0.iter().position(|(e, ev)| *e == entity && ev.op == op) {
+            Some(pos) => pos,
+            None => {
+                self.0.push((entity, DataChangedEvent { op, ids: Vec::new() }));
+                self.0.len() - 1
+            }
+        };
+        let event = &mut self.0[pos].1;
+        for id in ids {
+            if !event.ids.contains(&id) {
+                event.ids.push(id);
+            }
+        }
+    }
+
+    pub(crate) fn replaced(&mut self, entity: DataEntity) {
+        if !self.0.iter().any(|(e, ev)| *e == entity && ev.op == DataOp::Replaced) {
+            self.0.push((entity, DataChangedEvent { op: DataOp::Replaced, ids: Vec::new() }));
+        }
+    }
+
+    pub(crate) fn emit(self, app: &tauri::AppHandle) {
+        for (entity, event) in self.0 {
+            let _ = app.emit(entity.event_name(), event);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn events(&self) -> &[(DataEntity, DataChangedEvent)] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_grouped_per_entity_and_op() {
+        let mut changes = DataChanges::default();
+        changes.record(DataEntity::Invoices, DataOp::Created, "i1");
+        changes.record(DataEntity::Settings, DataOp::Updated, "settings");
+        changes.record_all(DataEntity::Invoices, DataOp::Created, ["i2".to_string(), "i1".to_string()]);
+        changes.record_all(DataEntity::Clients, DataOp::Updated, Vec::new());
+        changes.replaced(DataEntity::Expenses);
+        changes.replaced(DataEntity::Expenses);
+
+        let events = changes.events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].0, DataEntity::Invoices);
+        assert_eq!(events[0].1.ids, ["i1", "i2"]);
+        assert_eq!(events[2].1, DataChangedEvent { op: DataOp::Replaced, ids: Vec::new() });
+        assert_eq!(
+            serde_json::to_value(&events[0].1).unwrap(),
+            serde_json::json!({ "op": "created", "ids": ["i1", "i2"] })
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Where `pausaler.db` lives. The first start probes the usual locations and records the choice
+//! in `db-location.json` under the app data root; later starts only read that record, so the app
+//! never silently switches to another database file that happens to exist.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::read_pool::ReadPool;
+use crate::{
+    configure_sqlite, now_iso, resolve_app_data_root, shm_path, validation_to_sql_error, wal_path, DbState,
+};
+
+pub(crate) const DB_FILE_NAME: &str = "pausaler.db";
+const LOCATION_FILE: &str = "db-location.json";
+
+/// How the database path was first chosen; `moved` once the user relocated it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DbPathSource {
+    AppData,
+    AppLocalData,
+    ExeDir,
+    CurrentDir,
+    Moved,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationRecord {
+    path: String,
+    source: DbPathSource,
+    recorded_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DbLocation {
+    pub(crate) path: PathBuf,
+    pub(crate) source: DbPathSource,
+    /// Other probed locations that also hold a `pausaler.db`; the app ignores them.
+    pub(crate) others: Vec<(DbPathSource, PathBuf)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DbCandidateInfo {
+    pub(crate) source: DbPathSource,
+    pub(crate) path: String,
+    pub(crate) size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DatabaseInfo {
+    pub(crate) path: String,
+    pub(crate) size_bytes: u64,
+    pub(crate) wal_size_bytes: u64,
+    pub(crate) source: DbPathSource,
+    pub(crate) location_file: String,
+    pub(crate) other_candidates: Vec<DbCandidateInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DatabaseMoveResult {
+    pub(crate) database: DatabaseInfo,
+    /// The previous file, renamed next to where it was.
+    pub(crate) old_backup_path: String,
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Probe order used before a location was recorded.
+fn candidates(app: &tauri::AppHandle) -> Vec<(DbPathSource, PathBuf)> {
+    let mut out = Vec::new();
+    if let Ok(dir) = app.path().app_data_dir() {
+        out.push((DbPathSource::AppData, dir.join(DB_FILE_NAME)));
+    }
+    if let Ok(dir) = app.path().app_local_data_dir() {
+        out.push((DbPathSource::AppLocalData, dir.join(DB_FILE_NAME)));
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(dir) = exe.parent() {
+            out.push((DbPathSource::ExeDir, dir.join(DB_FILE_NAME)));
+        }
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        out.push((DbPathSource::CurrentDir, cwd.join(DB_FILE_NAME)));
+    }
+    out
+}
+
+fn read_record(root: &Path) -> Option<LocationRecord> {
+    let path = root.join(LOCATION_FILE);
+    let raw = fs::read(&path).ok()?;
+    match serde_json::from_slice::<LocationRecord>(&raw) {
+        Ok(rec) if !rec.path.trim().is_empty() => Some(rec),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                target: "sqlite",
+                path = %path.display(),
+                error = %e,
+                "ignoring unreadable database location record"
+            );
+            None
+        }
+    }
+}
+
+fn write_record(root: &Path, path: &Path, source: DbPathSource) -> Result<(), String> {
+    fs::create_dir_all(root).map_err(|e| e.to_string())?;
+    let rec = LocationRecord {
+        path: path.to_string_lossy().to_string(),
+        source,
+        recorded_at: now_iso(),
+    };
+    let json = serde_json::to_vec_pretty(&rec).map_err(|e| e.to_string())?;
+    let tmp = root.join(format!(".{LOCATION_FILE}.tmp"));
+    fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, root.join(LOCATION_FILE)).map_err(|e| e.to_string())
+}
+
+/// Uses the recorded location when there is one; otherwise picks the first existing candidate
+/// (or the first candidate for a fresh install) and records it.
+fn resolve_in(root: &Path, candidates: Vec<(DbPathSource, PathBuf)>) -> Result<DbLocation, String> {
+    let (path, source) = match read_record(root) {
+        Some(rec) => (PathBuf::from(rec.path), rec.source),
+        None => {
+            let (source, path) = candidates
+                .iter()
+                .find(|(_, p)| p.is_file())
+                .or_else(|| candidates.first())
+                .cloned()
+                .ok_or_else(|| "Unable to resolve database path".to_string())?;
+            if let Err(e) = write_record(root, &path, source) {
+                tracing::warn!(target: "sqlite", error = %e, "failed to record database location");
+            }
+            (path, source)
+        }
+    };
+    let others = candidates
+        .into_iter()
+        .filter(|(_, p)| p != &path && p.is_file())
+        .collect();
+    Ok(DbLocation { path, source, others })
+}
+
+pub(crate) fn resolve(app: &tauri::AppHandle) -> Result<DbLocation, String> {
+    resolve_in(&resolve_app_data_root(app)?, candidates(app))
+}
+
+fn info_for(root: &Path, loc: &DbLocation) -> DatabaseInfo {
+    DatabaseInfo {
+        path: loc.path.to_string_lossy().to_string(),
+        size_bytes: file_len(&loc.path),
+        wal_size_bytes: file_len(&wal_path(&loc.path)),
+        source: loc.source,
+        location_file: root.join(LOCATION_FILE).to_string_lossy().to_string(),
+        other_candidates: loc
+            .others
+            .iter()
+            .map(|(source, p)| DbCandidateInfo {
+                source: *source,
+                path: p.to_string_lossy().to_string(),
+                size_bytes: file_len(p),
+            })
+            .collect(),
+    }
+}
+
+/// Row count of every table; two files with the same counts after a byte copy are the same data.
+fn table_counts(conn: &Connection) -> Result<Vec<(String, i64)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    names
+        .into_iter()
+        .map(|name| {
+            let n = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")), [], |r| {
+                r.get::<_, i64>(0)
+            })?;
+            Ok((name, n))
+        })
+        .collect()
+}
+
+fn verify_copy(source: &Connection, copy: &Path) -> Result<(), String> {
+    let copy_conn = Connection::open_with_flags(copy, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Copied database cannot be opened: {e}"))?;
+    let integrity: String = copy_conn
+        .query_row("PRAGMA integrity_check(1)", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if integrity != "ok" {
+        return Err(format!("Copied database failed the integrity check: {integrity}"));
+    }
+    let expected = table_counts(source).map_err(|e| e.to_string())?;
+    let actual = table_counts(&copy_conn).map_err(|e| e.to_string())?;
+    if expected != actual {
+        return Err("Copied database does not match the original".to_string());
+    }
+    Ok(())
+}
+
+fn backup_name_for(old: &Path) -> PathBuf {
+    let name = old
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| DB_FILE_NAME.to_string());
+    let bak = old.with_file_name(format!("{name}.bak"));
+    if !bak.exists() {
+        return bak;
+    }
+    let suffix = OffsetDateTime::now_utc()
+        .format(&time::macros::format_description!("[year][month][day]-[hour][minute][second]"))
+        .unwrap_or_else(|_| "old".to_string());
+    old.with_file_name(format!("{name}.bak-{suffix}"))
+}
+
+/// Checkpoints the WAL, copies the file to `target`, verifies the copy, records the new location
+/// and swaps the open connection and the read pool over. The old file is kept as `<name>.bak`.
+fn relocate(
+    conn: &mut Connection,
+    readers: &ReadPool,
+    root: &Path,
+    target: &Path,
+) -> Result<DatabaseMoveResult, String> {
+    let current = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| "The database is not a file".to_string())?;
+    if target.exists() {
+        return Err(format!("A file already exists at {}", target.display()));
+    }
+    let parent = target.parent().ok_or_else(|| "Invalid destination path".to_string())?;
+    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+
+    let busy: i64 = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if busy != 0 {
+        return Err("The database is busy; try again in a moment".to_string());
+    }
+
+    let tmp = parent.join(format!(".{DB_FILE_NAME}.moving"));
+    let _ = fs::remove_file(&tmp);
+    fs::copy(&current, &tmp).map_err(|e| format!("Failed to copy the database: {e}"))?;
+    if let Err(e) = verify_copy(conn, &tmp).and_then(|_| fs::rename(&tmp, target).map_err(|e| e.to_string())) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+
+    // Record first: if the switch below fails the record is put back, and a crash in between
+    // leaves the app pointing at a complete, verified copy.
+    let previous = read_record(root);
+    if let Err(e) = write_record(root, target, DbPathSource::Moved) {
+        let _ = fs::remove_file(target);
+        return Err(format!("Failed to record the new location: {e}"));
+    }
+    let opened = Connection::open(target).and_then(|c| configure_sqlite(&c).map(|_| c));
+    let new_conn = match opened {
+        Ok(c) => c,
+        Err(e) => {
+            let source = previous.map(|r| r.source).unwrap_or(DbPathSource::AppData);
+            let _ = write_record(root, &current, source);
+            let _ = fs::remove_file(target);
+            return Err(format!("Failed to open the moved database: {e}"));
+        }
+    };
+    drop(std::mem::replace(conn, new_conn));
+    // Still under the write lock, so no write lands in the new file while reads see the old one.
+    if let Err(e) = readers.reset(target.to_path_buf()) {
+        tracing::warn!(
+            target: "sqlite",
+            op = "move_database",
+            error = %e,
+            "moved database, but could not reset the read pool"
+        );
+    }
+
+    let bak = backup_name_for(&current);
+    if let Err(e) = fs::rename(&current, &bak) {
+        tracing::warn!(
+            target: "sqlite",
+            op = "move_database",
+            error = %e,
+            "moved database, but could not rename the old file"
+        );
+    }
+    let _ = fs::remove_file(wal_path(&current));
+    let _ = fs::remove_file(shm_path(&current));
+
+    let loc = DbLocation {
+        path: target.to_path_buf(),
+        source: DbPathSource::Moved,
+        others: Vec::new(),
+    };
+    Ok(DatabaseMoveResult {
+        database: info_for(root, &loc),
+        old_backup_path: bak.to_string_lossy().to_string(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn get_database_info(app: tauri::AppHandle) -> Result<DatabaseInfo, AppError> {
+    let root = resolve_app_data_root(&app)?;
+    let loc = resolve_in(&root, candidates(&app))?;
+    Ok(info_for(&root, &loc))
+}
+
+/// `path` may be a folder (the file keeps its name) or a full file path.
+#[tauri::command]
+pub(crate) async fn move_database_to(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    path: String,
+) -> Result<DatabaseMoveResult, AppError> {
+    let root = resolve_app_data_root(&app)?;
+    let mut target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::validation("path", "required", "Destination is required"));
+    }
+    if target.is_dir() {
+        target = target.join(DB_FILE_NAME);
+    }
+    let readers = state.readers.clone();
+    state
+        .with_write("move_database_to", move |conn| {
+            relocate(conn, &readers, &root, &target).map_err(validation_to_sql_error)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pausaler-{label}-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn recorded_location_wins_over_later_candidates() {
+        let root = temp_dir("dbloc");
+        let a = root.join("a").join(DB_FILE_NAME);
+        let b = root.join("b").join(DB_FILE_NAME);
+        fs::create_dir_all(b.parent().unwrap()).unwrap();
+        fs::write(&b, b"").unwrap();
+        let cands = || vec![(DbPathSource::AppData, a.clone()), (DbPathSource::CurrentDir, b.clone())];
+
+        let first = resolve_in(&root, cands()).unwrap();
+        assert_eq!(first.path, b);
+        assert_eq!(first.source, DbPathSource::CurrentDir);
+        assert!(first.others.is_empty());
+
+        // A database later appearing in an earlier probe location must not take over.
+        fs::create_dir_all(a.parent().unwrap()).unwrap();
+        fs::write(&a, b"").unwrap();
+        let second = resolve_in(&root, cands()).unwrap();
+        assert_eq!(second.path, b);
+        assert_eq!(second.others, vec![(DbPathSource::AppData, a.clone())]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn relocate_copies_switches_and_keeps_backup() {
+        let root = temp_dir("dbmove");
+        let old = root.join(DB_FILE_NAME);
+        let target = root.join("elsewhere").join(DB_FILE_NAME);
+        let mut conn = Connection::open(&old).unwrap();
+        configure_sqlite(&conn).unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json) VALUES ('c1', 'Acme', '', '', '', '2025-01-01', '{}')",
+            [],
+        )
+        .unwrap();
+
+        let readers = ReadPool::new(old.clone(), 2);
+        let res = relocate(&mut conn, &readers, &root, &target).unwrap();
+        assert_eq!(res.database.source, DbPathSource::Moved);
+        assert_eq!(PathBuf::from(conn.path().unwrap()), target);
+        let n: i64 = conn.query_row("SELECT COUNT(*) FROM clients", [], |r| r.get(0)).unwrap();
+        assert_eq!(n, 1);
+        assert!(!old.exists());
+        assert!(PathBuf::from(&res.old_backup_path).is_file());
+        assert_eq!(resolve_in(&root, vec![(DbPathSource::AppData, old.clone())]).unwrap().path, target);
+
+        assert!(relocate(&mut conn, &readers, &root, &target).is_err());
+
+        drop(conn);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn reads_after_a_move_see_the_new_file() {
+        let root = temp_dir("dbmove-read");
+        let old = root.join(DB_FILE_NAME);
+        let target = root.join("elsewhere").join(DB_FILE_NAME);
+        let mut conn = Connection::open(&old).unwrap();
+        configure_sqlite(&conn).unwrap();
+        crate::init_schema(&conn).unwrap();
+        let readers = ReadPool::new(old.clone(), 2);
+        let count = |readers: &ReadPool| -> i64 {
+            readers.get().unwrap().query_row("SELECT COUNT(*) FROM clients", [], |r| r.get(0)).unwrap()
+        };
+        // Leaves an idle reader on the old file.
+        assert_eq!(count(&readers), 0);
+        let lent = readers.get().unwrap();
+
+        relocate(&mut conn, &readers, &root, &target).unwrap();
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json) VALUES ('c1', 'Acme', '', '', '', '2025-01-01', '{}')",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(count(&readers), 1);
+        assert_eq!(PathBuf::from(readers.get().unwrap().path().unwrap()), target);
+        // A reader lent out across the move is closed when it comes back, not reused.
+        drop(lent);
+        for _ in 0..3 {
+            assert_eq!(count(&readers), 1);
+        }
+
+        drop(readers);
+        drop(conn);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,208 @@
+//! Colors of the invoice email. Most mail clients drop `<style>` blocks, so the light palette goes
+//! into the inline styles; the dark palette overrides them from a `prefers-color-scheme` media query
+//! in the clients that honor it (Apple Mail, Outlook for Mac, some webmail). Settings keep the theme
+//! as one JSON column.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+
+/// Class names the dark-mode rules hook into, one per palette role.
+pub(crate) const BG: &str = "pz-bg";
+pub(crate) const CARD: &str = "pz-card";
+pub(crate) const BOX: &str = "pz-box";
+pub(crate) const RULE: &str = "pz-rule";
+pub(crate) const ACCENT: &str = "pz-accent";
+pub(crate) const TEXT: &str = "pz-text";
+pub(crate) const MUTED: &str = "pz-muted";
+pub(crate) const FAINT: &str = "pz-faint";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailPalette {
+    /// Around the card.
+    pub background: String,
+    pub card: String,
+    /// Outline of the card and its boxes, and the dividers.
+    pub border: String,
+    /// The title and the total.
+    pub accent: String,
+    pub text: String,
+    /// Row labels.
+    pub muted: String,
+    /// The issuer address and the footer.
+    pub faint: String,
+}
+
+/// The light palette is what every client shows unless it switches to dark mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailTheme {
+    pub light: EmailPalette,
+    pub dark: EmailPalette,
+}
+
+fn palette(colors: [&str; 7]) -> EmailPalette {
+    let [background, card, border, accent, text, muted, faint] = colors.map(str::to_string);
+    EmailPalette { background, card, border, accent, text, muted, faint }
+}
+
+impl Default for EmailTheme {
+    fn default() -> Self {
+        EmailTheme {
+            light: palette(["#f6f7f9", "#ffffff", "#e6e8ec", "#111827", "#111827", "#4b5563", "#6b7280"]),
+            dark: palette(["#0f1115", "#1b1e24", "#2f343d", "#f9fafb", "#e5e7eb", "#aab1bd", "#8e96a3"]),
+        }
+    }
+}
+
+/// `#rgb` or `#rrggbb`, returned as lowercase `#rrggbb`.
+fn hex_color(name: &str, value: &str) -> Result<String, AppError> {
+    let hex = value.trim().strip_prefix('#').unwrap_or_default();
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => String::new(),
+    };
+    if expanded.is_empty() || !expanded.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::validation(
+            "emailTheme",
+            "invalid",
+            format!("The email color \"{name}\" must be a hex color such as #1f2937, not \"{}\".", value.trim()),
+        ));
+    }
+    Ok(format!("#{}", expanded.to_ascii_lowercase()))
+}
+
+impl EmailPalette {
+    fn normalize(self, scheme: &str) -> Result<EmailPalette, AppError> {
+        let color = |role: &str, value: &str| hex_color(&format!("{scheme}.{role}"), value);
+        Ok(EmailPalette {
+            background: color("background", &self.background)?,
+            card: color("card", &self.card)?,
+            border: color("border", &self.border)?,
+            accent: color("accent", &self.accent)?,
+            text: color("text", &self.text)?,
+            muted: color("muted", &self.muted)?,
+            faint: color("faint", &self.faint)?,
+        })
+    }
+}
+
+/// Checks every color of a theme from the settings form.
+pub(crate) fn normalize_email_theme(theme: EmailTheme) -> Result<EmailTheme, AppError> {
+    Ok(EmailTheme { light: theme.light.normalize("light")?, dark: theme.dark.normalize("dark")? })
+}
+
+/// The settings column.
+pub(crate) fn email_theme_json(theme: &EmailTheme) -> String {
+    serde_json::to_string(theme).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Reads the settings column; anything unreadable falls back to the default theme.
+pub(crate) fn parse_email_theme(json: &str) -> EmailTheme {
+    serde_json::from_str(json).ok().and_then(|t| normalize_email_theme(t).ok()).unwrap_or_default()
+}
+
+/// The `<head>` part: the color-scheme declaration, so clients don't invert the colors on their
+/// own, and the dark palette for the classes above.
+pub(crate) fn head_html(theme: &EmailTheme) -> String {
+    let d = &theme.dark;
+    format!(
+        "<meta name=\"color-scheme\" content=\"light dark\"><meta name=\"supported-color-schemes\" content=\"light dark\">\
+<style>:root{{color-scheme:light dark;supported-color-schemes:light dark;}}\
+@media (prefers-color-scheme: dark){{\
+.{BG}{{background-color:{bg} !important;}}\
+.{CARD}{{background-color:{card} !important;border-color:{border} !important;}}\
+.{BOX}{{border-color:{border} !important;}}\
+.{RULE}{{background-color:{border} !important;}}\
+.{ACCENT}{{color:{accent} !important;}}\
+.{TEXT}{{color:{text} !important;}}\
+.{MUTED}{{color:{muted} !important;}}\
+.{FAINT}{{color:{faint} !important;}}\
+}}</style>",
+        bg = d.background,
+        card = d.card,
+        border = d.border,
+        accent = d.accent,
+        text = d.text,
+        muted = d.muted,
+        faint = d.faint,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_settings, render_invoice_email, Invoice};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/email");
+
+    fn sample_invoice() -> Invoice {
+        serde_json::from_value(serde_json::json!({
+            "id": "i", "invoiceNumber": "7/2025", "clientId": "c", "clientName": "Klijent d.o.o.",
+            "issueDate": "2025-03-01", "serviceDate": "2025-03-01", "dueDate": "2025-03-16", "status": "SENT",
+            "currency": "RSD", "items": [], "subtotal": 120000.0, "total": 120000.0, "notes": "",
+            "createdAt": "t", "paymentReference": "97 42-7-2025", "issuedBy": "Petar Petrović"
+        }))
+        .unwrap()
+    }
+
+    /// Compares with `fixtures/email/{name}`; `UPDATE_FIXTURES=1` rewrites the file instead.
+    fn assert_matches_fixture(name: &str, html: &str) {
+        let path = format!("{FIXTURES}/{name}");
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(&path, html).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            expected.replace("\r\n", "\n").trim_end() == html,
+            "{path} differs from the rendered email; run `UPDATE_FIXTURES=1 cargo test email_theme` if that is intended\n{html}"
+        );
+    }
+
+    #[test]
+    fn rendered_email_matches_fixtures() {
+        let mut settings = default_settings();
+        settings.company_name = "Petar Petrović PR".to_string();
+        settings.pib = "101134702".to_string();
+        settings.company_address_line = "Bulevar 1".to_string();
+        settings.company_postal_code = "11000".to_string();
+        settings.company_city = "Beograd".to_string();
+        settings.bank_account = "160-0000000123456-54".to_string();
+        let invoice = sample_invoice();
+
+        let (html, _) = render_invoice_email(&settings, &invoice, None, true, Some("Hvala!")).unwrap();
+        assert_matches_fixture("invoice_default.html", &html);
+
+        let patch = EmailTheme {
+            light: palette(["#FFF8F0", "#fff", "#f0d9c4", "#C2410C", "#1c1917", "#57534e", "#78716c"]),
+            dark: palette(["#1c1917", "#292524", "#44403c", "#fb923c", "#f5f5f4", "#d6d3d1", "#a8a29e"]),
+        };
+        settings.email_theme = normalize_email_theme(patch).unwrap();
+        assert_eq!(settings.email_theme.light.card, "#ffffff");
+        let (html, _) = render_invoice_email(&settings, &invoice, None, true, Some("Hvala!")).unwrap();
+        assert_matches_fixture("invoice_custom.html", &html);
+        // The default colors are gone from the inline styles and the dark rules alike.
+        assert!(!html.contains("#f6f7f9") && !html.contains("#0f1115"));
+    }
+
+    #[test]
+    fn colors_must_be_hex() {
+        let mut theme = EmailTheme::default();
+        theme.light.accent = "red".to_string();
+        let err = normalize_email_theme(theme).unwrap_err();
+        assert_eq!(err.code(), "VALIDATION");
+        assert!(err.to_string().contains("light.accent"), "{err}");
+
+        let mut theme = EmailTheme::default();
+        theme.dark.text = "#12345g".to_string();
+        assert!(normalize_email_theme(theme).is_err());
+
+        assert_eq!(parse_email_theme("{}"), EmailTheme::default());
+        assert_eq!(parse_email_theme("not json"), EmailTheme::default());
+        let stored = email_theme_json(&EmailTheme::default());
+        assert_eq!(parse_email_theme(&stored), EmailTheme::default());
+    }
+}
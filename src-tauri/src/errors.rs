@@ -0,0 +1,282 @@
+//! The error every command returns. It reaches the frontend as `{ code, message, details }`:
+//! `code` is stable for the UI to branch on, `message` is in the app language (from
+//! `src/shared/errorMessages.json`, falling back to the English text it was created with) and
+//! `details` carries the variant's fields.
+//!
+//! Code that still builds `String` errors converts with `?` into [`AppError::Other`], so the
+//! message is unchanged until the site is given a proper variant.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+use crate::app_mode::ReadOnlyReason;
+use crate::features::FeatureLocked;
+use crate::locales::{self, LocaleTable};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AppError {
+    /// `entity` is a camelCase name such as "invoice" or "expense".
+    NotFound { entity: &'static str, id: String },
+    /// `field` is the camelCase input name, `code` what is wrong with it ("required", "positive",
+    /// "date", "invalid"); `message` is the English text.
+    Validation { field: String, code: &'static str, message: String },
+    /// The change clashes with existing data (a duplicate, a used record, a constraint).
+    Conflict(String),
+    /// The SMTP settings can't be used; `code` names the problem ("missingHost", "tlsMismatch", …).
+    SmtpConfig { code: &'static str, message: String },
+    /// The server refused the email or couldn't be reached.
+    SmtpSend(String),
+    /// The email is larger than `Settings.email_max_message_mb`; `attachments` are file names with
+    /// their sizes in bytes.
+    AttachmentTooLarge { limit_mb: i64, message_bytes: usize, attachments: Vec<(String, usize)> },
+    Pdf(String),
+    Database(String),
+    /// The trial has ended and no license is stored.
+    LicenseRequired,
+    FeatureLocked(FeatureLocked),
+    /// The app lock is on; only the PIN commands run.
+    AppLocked,
+    /// The database can't be written right now (see `app_mode`).
+    ReadOnly(ReadOnlyReason),
+    /// Not classified yet.
+    Other(String),
+}
+
+impl AppError {
+    pub(crate) fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        AppError::NotFound { entity, id: id.into() }
+    }
+
+    pub(crate) fn validation(field: impl Into<String>, code: &'static str, message: impl Into<String>) -> Self {
+        AppError::Validation { field: field.into(), code, message: message.into() }
+    }
+
+    /// A validation message that isn't tied to one field.
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        Self::validation("", "invalid", message)
+    }
+
+    pub(crate) fn smtp_config(code: &'static str, message: impl Into<String>) -> Self {
+        AppError::SmtpConfig { code, message: message.into() }
+    }
+
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "NOT_FOUND",
+            AppError::Validation { .. } => "VALIDATION",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::SmtpConfig { .. } => "SMTP_CONFIG",
+            AppError::SmtpSend(_) => "SMTP_SEND",
+            AppError::AttachmentTooLarge { .. } => "ATTACHMENT_TOO_LARGE",
+            AppError::Pdf(_) => "PDF",
+            AppError::Database(_) => "DATABASE",
+            AppError::LicenseRequired => "LICENSE_REQUIRED",
+            AppError::FeatureLocked(_) => "FEATURE_LOCKED",
+            AppError::AppLocked => "APP_LOCKED",
+            AppError::ReadOnly(_) => "READ_ONLY",
+            AppError::Other(_) => "OTHER",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            AppError::NotFound { entity, id } => json!({ "entity": entity, "id": id }),
+            AppError::Validation { field, code, .. } => json!({ "field": field, "code": code }),
+            AppError::SmtpConfig { code, .. } => json!({ "code": code }),
+            AppError::AttachmentTooLarge { limit_mb, message_bytes, attachments } => json!({
+                "limitMb": limit_mb,
+                "messageBytes": message_bytes,
+                "attachments": attachments
+                    .iter()
+                    .map(|(name, bytes)| json!({ "name": name, "bytes": bytes }))
+                    .collect::<Vec<_>>(),
+            }),
+            AppError::FeatureLocked(locked) => serde_json::to_value(locked).unwrap_or(Value::Null),
+            AppError::ReadOnly(reason) => json!({ "reason": reason }),
+            _ => Value::Null,
+        }
+    }
+
+    /// The message in `lang`; the English text when the table has no entry for this error.
+    pub(crate) fn message(&self, lang: &str) -> String {
+        let strings = messages(lang);
+        let get = |key: &str| strings.get(key).map(String::as_str);
+        let localized = match self {
+            AppError::NotFound { entity, .. } => get(&format!("entity.{entity}"))
+                .and_then(|name| Some(get("notFound")?.replace("{entity}", name))),
+            AppError::Validation { field, code, .. } => get(&format!("field.{field}"))
+                .and_then(|name| Some(get(&format!("validation.{code}"))?.replace("{field}", name))),
+            AppError::SmtpConfig { code, .. } => get(&format!("smtpConfig.{code}")).map(str::to_string),
+            AppError::SmtpSend(detail) => get("smtpSend").map(|t| t.replace("{detail}", detail)),
+            AppError::AttachmentTooLarge { limit_mb, message_bytes, attachments } => get("attachmentTooLarge").map(|t| {
+                t.replace("{size}", &format_size(*message_bytes))
+                    .replace("{limit}", &limit_mb.to_string())
+                    .replace("{attachments}", &attachment_list(attachments))
+            }),
+            AppError::Database(detail) => get("database").map(|t| t.replace("{detail}", detail)),
+            AppError::LicenseRequired => get("licenseRequired").map(str::to_string),
+            AppError::FeatureLocked(_) => get("featureLocked").map(str::to_string),
+            AppError::AppLocked => get("appLocked").map(str::to_string),
+            AppError::ReadOnly(reason) => get(&format!("readOnly.{}", reason.key())).map(str::to_string),
+            AppError::Conflict(_) | AppError::Pdf(_) | AppError::Other(_) => None,
+        };
+        localized.unwrap_or_else(|| self.to_string())
+    }
+}
+
+impl std::fmt::Display for AppError {
+    /// The English text, for logs and for code that still works with `String` errors.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::NotFound { entity, id } if id.is_empty() => write!(f, "Not found: {entity}."),
+            AppError::NotFound { entity, id } => write!(f, "Not found: {entity} {id}."),
+            AppError::Validation { message, .. } | AppError::SmtpConfig { message, .. } => f.write_str(message),
+            AppError::SmtpSend(detail) => write!(f, "Sending the email failed: {detail}"),
+            AppError::AttachmentTooLarge { limit_mb, message_bytes, attachments } => write!(
+                f,
+                "The email is too large to send ({}, the limit is {limit_mb} MB). Attachments: {}.",
+                format_size(*message_bytes),
+                attachment_list(attachments)
+            ),
+            AppError::Database(detail) => write!(f, "Database error: {detail}"),
+            AppError::LicenseRequired => f.write_str("The trial has ended. Activate a license to continue."),
+            AppError::FeatureLocked(_) => f.write_str("Your plan doesn't include this feature."),
+            AppError::AppLocked => f.write_str("The app is locked. Enter the PIN to continue."),
+            AppError::ReadOnly(ReadOnlyReason::Locked) => {
+                f.write_str("The database is in use by another program, so changes can't be saved right now.")
+            }
+            AppError::ReadOnly(ReadOnlyReason::ReadOnlyFile) => {
+                f.write_str("The database file is read-only, so changes can't be saved.")
+            }
+            AppError::Conflict(message) | AppError::Pdf(message) | AppError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// `1.4 MB`, or `320 KB` below a megabyte.
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+/// `faktura.pdf (12.3 MB), faktura.xml (8 KB)`.
+fn attachment_list(attachments: &[(String, usize)]) -> String {
+    attachments
+        .iter()
+        .map(|(name, bytes)| format!("{name} ({})", format_size(*bytes)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 3)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.message(&current_language()))?;
+        s.serialize_field("details", &self.details())?;
+        s.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Errors returned inside `with_read`/`with_write` closures come back out unchanged.
+impl From<AppError> for rusqlite::Error {
+    fn from(err: AppError) -> Self {
+        rusqlite::Error::ToSqlConversionFailure(Box::new(err))
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err {
+            rusqlite::Error::ToSqlConversionFailure(inner) => match inner.downcast::<AppError>() {
+                Ok(app) => *app,
+                Err(other) => AppError::Other(other.to_string()),
+            },
+            rusqlite::Error::SqliteFailure(code, _) if code.code == rusqlite::ErrorCode::ConstraintViolation => {
+                AppError::Conflict(crate::sqlite_error_string(&err))
+            }
+            other => AppError::Database(crate::sqlite_error_string(&other)),
+        }
+    }
+}
+
+static LANGUAGE: RwLock<String> = RwLock::new(String::new());
+
+/// Language of the messages sent to the frontend; follows `Settings.language`.
+pub(crate) fn set_language(lang: &str) {
+    if let Ok(mut current) = LANGUAGE.write() {
+        lang.clone_into(&mut current);
+    }
+}
+
+fn current_language() -> String {
+    LANGUAGE.read().map(|l| l.clone()).unwrap_or_default()
+}
+
+static ERROR_MESSAGES: OnceLock<LocaleTable> = OnceLock::new();
+
+fn messages(lang: &str) -> HashMap<String, String> {
+    let table = ERROR_MESSAGES.get_or_init(|| locales::parse_table(include_str!("../../src/shared/errorMessages.json")));
+    // An unset language is Serbian, like the settings default.
+    let lang = if lang.trim().is_empty() { "sr" } else { lang };
+    locales::resolve(table, lang).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_localized_message_and_details() {
+        let err = AppError::validation("amount", "positive", "Amount must be greater than 0.");
+        assert_eq!(err.message("en"), "Amount must be greater than 0.");
+        assert_eq!(err.message("sr"), "Polje „Iznos“ mora biti veće od 0.");
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["code"], "VALIDATION");
+        assert_eq!(value["details"], json!({ "field": "amount", "code": "positive" }));
+
+        // Unknown fields and unclassified errors keep the text they were created with.
+        assert_eq!(AppError::validation("pib", "invalid", "Bad PIB.").message("sr"), "Bad PIB.");
+        assert_eq!(AppError::from("Disk full".to_string()).message("sr"), "Disk full");
+        assert_eq!(AppError::not_found("invoice", "x").message("sr"), "Nije pronađeno: faktura.");
+    }
+
+    #[test]
+    fn errors_pass_through_database_closures() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE t (id TEXT PRIMARY KEY); INSERT INTO t VALUES ('a');").unwrap();
+        let inner: rusqlite::Error = AppError::LicenseRequired.into();
+        assert_eq!(AppError::from(inner), AppError::LicenseRequired);
+        let dup = conn.execute("INSERT INTO t VALUES ('a')", []).unwrap_err();
+        assert_eq!(AppError::from(dup).code(), "CONFLICT");
+        let missing = conn.query_row("SELECT nope FROM t", [], |r| r.get::<_, String>(0)).unwrap_err();
+        assert_eq!(AppError::from(missing).code(), "DATABASE");
+    }
+}
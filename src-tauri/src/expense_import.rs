@@ -0,0 +1,426 @@
+//! Expense import from bank statement CSV exports: Serbian number and date formats, credit rows
+//! skipped, and rows already present (same date, amount and title) reported as duplicates.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::csv_reader::{self, CsvTable};
+use crate::data_events::{DataEntity, DataOp};
+use crate::{now_iso, validation_to_sql_error, DbState};
+
+/// CSV header for each expense field; date, title and amount are required.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseCsvMapping {
+    pub date: String,
+    pub title: String,
+    /// Debit amount column. With `signed_amounts`, a single column where debits are negative.
+    pub amount: String,
+    /// Separate credit (incoming) column; rows with a credit and no debit are skipped.
+    #[serde(default)]
+    pub credit: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub signed_amounts: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpenseImportStatus {
+    Imported,
+    Skipped,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseImportRow {
+    /// 1-based line in the CSV file.
+    pub line: usize,
+    pub date: Option<String>,
+    pub title: String,
+    pub amount: Option<f64>,
+    pub currency: Option<String>,
+    pub status: ExpenseImportStatus,
+    pub message: Option<String>,
+    pub expense_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseImportReport {
+    /// True when nothing was written; `imported` then counts rows that would be imported.
+    pub preview: bool,
+    pub imported: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+    pub rows: Vec<ExpenseImportRow>,
+}
+
+struct MappedColumns {
+    date: usize,
+    title: usize,
+    amount: usize,
+    credit: Option<usize>,
+    currency: Option<usize>,
+    category: Option<usize>,
+}
+
+fn resolve_mapping(table: &CsvTable, mapping: &ExpenseCsvMapping) -> Result<MappedColumns, String> {
+    let column = |header: &str| {
+        table
+            .column(header)
+            .ok_or_else(|| format!("Column \"{}\" was not found in the CSV header.", header.trim()))
+    };
+    let optional = |header: &Option<String>| match header.as_deref().map(str::trim) {
+        Some(h) if !h.is_empty() => column(h).map(Some),
+        _ => Ok(None),
+    };
+    for (header, field) in [(&mapping.date, "date"), (&mapping.title, "title"), (&mapping.amount, "amount")] {
+        if header.trim().is_empty() {
+            return Err(format!("Choose the CSV column that holds the {field}."));
+        }
+    }
+    Ok(MappedColumns {
+        date: column(&mapping.date)?,
+        title: column(&mapping.title)?,
+        amount: column(&mapping.amount)?,
+        credit: optional(&mapping.credit)?,
+        currency: optional(&mapping.currency)?,
+        category: optional(&mapping.category)?,
+    })
+}
+
+fn cell(row: &[String], idx: Option<usize>) -> String {
+    idx.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+/// Parses bank amounts such as `1.234,56`, `-1234,56`, `1,234.56` or `1234.56`. When both
+/// separators appear the last one is the decimal point; a lone comma is always decimal.
+pub(crate) fn parse_amount(v: &str) -> Option<f64> {
+    let mut s: String = v.chars().filter(|c| !c.is_whitespace() && *c != '\'').collect();
+    let negative = if let Some(rest) = s.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        s = rest.to_string();
+        true
+    } else if let Some(rest) = s.strip_suffix('-') {
+        s = rest.to_string();
+        true
+    } else {
+        false
+    };
+    let (thousands, decimal) = match (s.rfind(','), s.rfind('.')) {
+        (Some(c), Some(d)) if c > d => ('.', ','),
+        (Some(_), Some(_)) => (',', '.'),
+        (Some(_), None) => ('.', ','),
+        (None, Some(_)) if s.matches('.').count() > 1 => ('.', ','),
+        _ => (',', '.'),
+    };
+    let normalized: String = s
+        .chars()
+        .filter(|c| *c != thousands)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    if normalized.is_empty() || !normalized.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+')) {
+        return None;
+    }
+    let value: f64 = normalized.parse().ok()?;
+    value.is_finite().then_some(if negative { -value } else { value })
+}
+
+/// Parses `dd.mm.yyyy` (optionally with a trailing dot), `dd/mm/yyyy` or `yyyy-mm-dd`, ignoring
+/// a time part, into `YYYY-MM-DD`.
+pub(crate) fn parse_bank_date(v: &str) -> Option<String> {
+    let v = v.split_whitespace().next()?.trim_end_matches('.');
+    let parts: Vec<&str> = v.split(['.', '/', '-']).collect();
+    let [a, b, c] = parts.as_slice() else {
+        return None;
+    };
+    let (year, month, day) = if a.len() == 4 { (a, b, c) } else { (c, b, a) };
+    let year: i32 = year.parse().ok().filter(|_| year.len() == 4)?;
+    let month: u8 = month.parse().ok()?;
+    let day: u8 = day.parse().ok()?;
+    let date = time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day).ok()?;
+    Some(format!("{:04}-{:02}-{:02}", date.year(), u8::from(date.month()), date.day()))
+}
+
+fn duplicate_key(date: &str, amount: f64, title: &str) -> (String, i64, String) {
+    (
+        date.to_string(),
+        (amount * 100.0).round() as i64,
+        title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+    )
+}
+
+struct ParsedRow {
+    date: String,
+    title: String,
+    amount: f64,
+    currency: String,
+    category: Option<String>,
+}
+
+/// `Ok(None)` for credit rows.
+fn expense_from_csv_row(
+    cols: &MappedColumns,
+    mapping: &ExpenseCsvMapping,
+    default_currency: &str,
+    row: &[String],
+) -> Result<Option<ParsedRow>, String> {
+    let raw_amount = cell(row, Some(cols.amount));
+    let amount = if raw_amount.is_empty() {
+        0.0
+    } else {
+        parse_amount(&raw_amount).ok_or_else(|| format!("Invalid amount \"{raw_amount}\"."))?
+    };
+    let debit = if mapping.signed_amounts { -amount } else { amount };
+    if debit <= 0.0 {
+        let credit = cell(row, cols.credit);
+        let has_credit = parse_amount(&credit).is_some_and(|c| c != 0.0);
+        if has_credit || mapping.signed_amounts || debit < 0.0 {
+            return Ok(None);
+        }
+        return Err("Amount is empty.".to_string());
+    }
+
+    let raw_date = cell(row, Some(cols.date));
+    let date = parse_bank_date(&raw_date).ok_or_else(|| format!("Invalid date \"{raw_date}\"."))?;
+    let title = cell(row, Some(cols.title));
+    if title.is_empty() {
+        return Err("Title is empty.".to_string());
+    }
+    let currency = Some(cell(row, cols.currency).to_uppercase())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| default_currency.to_string());
+    Ok(Some(ParsedRow {
+        date,
+        title,
+        amount: (debit * 100.0).round() / 100.0,
+        currency,
+        category: Some(cell(row, cols.category)).filter(|c| !c.is_empty()),
+    }))
+}
+
+/// Imports every debit row in one transaction; with `preview` the rows are only checked and
+/// nothing is written. Duplicates are matched on date, amount and title against existing
+/// expenses and earlier rows of the same file.
+pub(crate) fn import_expenses_from_table(
+    conn: &mut Connection,
+    table: &CsvTable,
+    mapping: &ExpenseCsvMapping,
+    default_currency: &str,
+    preview: bool,
+) -> Result<ExpenseImportReport, rusqlite::Error> {
+    let cols = resolve_mapping(table, mapping).map_err(validation_to_sql_error)?;
+    let default_currency = default_currency.trim().to_uppercase();
+    if default_currency.is_empty() {
+        return Err(validation_to_sql_error("Currency is required.".to_string()));
+    }
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let mut known: HashSet<(String, i64, String)> = HashSet::new();
+    {
+        let mut stmt = tx.prepare("SELECT date, amount, title FROM expenses WHERE deletedAt IS NULL")?;
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, f64>(1)?, r.get::<_, String>(2)?)))?;
+        for row in rows {
+            let (date, amount, title) = row?;
+            known.insert(duplicate_key(&date, amount, &title));
+        }
+    }
+
+    let created_at = now_iso();
+    let mut rows = Vec::with_capacity(table.rows.len());
+    for (line, row) in &table.rows {
+        let mut report = ExpenseImportRow {
+            line: *line,
+            date: None,
+            title: cell(row, Some(cols.title)),
+            amount: None,
+            currency: None,
+            status: ExpenseImportStatus::Skipped,
+            message: None,
+            expense_id: None,
+        };
+        match expense_from_csv_row(&cols, mapping, &default_currency, row) {
+            Err(e) => report.message = Some(e),
+            Ok(None) => report.message = Some("Credit (incoming payment).".to_string()),
+            Ok(Some(parsed)) => {
+                report.date = Some(parsed.date.clone());
+                report.amount = Some(parsed.amount);
+                report.currency = Some(parsed.currency.clone());
+                if !known.insert(duplicate_key(&parsed.date, parsed.amount, &parsed.title)) {
+                    report.status = ExpenseImportStatus::Duplicate;
+                    report.message = Some("An expense with the same date, amount and title already exists.".to_string());
+                } else {
+                    report.status = ExpenseImportStatus::Imported;
+                    if !preview {
+                        let id = Uuid::new_v4().to_string();
+                        tx.execute(
+                            r#"INSERT INTO expenses (id, title, amount, currency, date, category, notes, createdAt)
+                               VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7)"#,
+                            params![
+                                id,
+                                parsed.title,
+                                parsed.amount,
+                                parsed.currency,
+                                parsed.date,
+                                parsed.category,
+                                created_at,
+                            ],
+                        )?;
+                        report.expense_id = Some(id);
+                    }
+                }
+            }
+        }
+        rows.push(report);
+    }
+    if !preview {
+        tx.commit()?;
+    }
+
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+    Ok(ExpenseImportReport {
+        preview,
+        imported: count(ExpenseImportStatus::Imported),
+        skipped: count(ExpenseImportStatus::Skipped),
+        duplicates: count(ExpenseImportStatus::Duplicate),
+        rows,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn import_expenses_csv(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    mapping: ExpenseCsvMapping,
+    default_currency: String,
+    preview: Option<bool>,
+) -> Result<ExpenseImportReport, AppError> {
+    let table = csv_reader::read_csv_file(&path)?;
+    let preview = preview.unwrap_or(false);
+    let op = if preview { "preview_expenses_csv" } else { "import_expenses_csv" };
+    state
+        .with_create_notify(op, move |conn, changes| {
+            let report = import_expenses_from_table(conn, &table, &mapping, &default_currency, preview)?;
+            if !report.preview {
+                let created = report
+                    .rows
+                    .iter()
+                    .filter(|r| matches!(r.status, ExpenseImportStatus::Imported))
+                    .filter_map(|r| r.expense_id.clone());
+                changes.record_all(DataEntity::Expenses, DataOp::Created, created);
+            }
+            Ok(report)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_serbian_amounts_and_dates() {
+        assert_eq!(parse_amount("1.234,56"), Some(1234.56));
+        assert_eq!(parse_amount("-1.234.567,5"), Some(-1234567.5));
+        assert_eq!(parse_amount("1,234.56"), Some(1234.56));
+        assert_eq!(parse_amount("999,00"), Some(999.0));
+        assert_eq!(parse_amount("12.50"), Some(12.5));
+        assert_eq!(parse_amount("1 500,00"), Some(1500.0));
+        assert_eq!(parse_amount("(80,00)"), Some(-80.0));
+        assert_eq!(parse_amount("abc"), None);
+        assert_eq!(parse_amount(""), None);
+
+        assert_eq!(parse_bank_date("05.01.2025."), Some("2025-01-05".to_string()));
+        assert_eq!(parse_bank_date("5.1.2025 14:32"), Some("2025-01-05".to_string()));
+        assert_eq!(parse_bank_date("2025-01-05"), Some("2025-01-05".to_string()));
+        assert_eq!(parse_bank_date("31/02/2025"), None);
+    }
+
+    fn expense_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM expenses", [], |r| r.get(0)).unwrap()
+    }
+
+    #[test]
+    fn imports_debits_and_reports_credits_and_duplicates() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO expenses (id, title, amount, currency, date, createdAt)
+             VALUES ('e', 'EPS struja', 4321.5, 'RSD', '2025-01-03', 't')",
+            [],
+        )
+        .unwrap();
+        let table = csv_reader::parse_csv(
+            "Datum;Opis;Zaduženje;Odobrenje\n\
+             03.01.2025;EPS  struja;4.321,50;\n\
+             04.01.2025;Uplata klijenta;;120.000,00\n\
+             05.01.2025;Telekom;2.499,00;\n\
+             05.01.2025;Telekom;2.499,00;\n\
+             06.01.2025;Knjigovođa;nije broj;\n",
+        )
+        .unwrap();
+        let mapping = ExpenseCsvMapping {
+            date: "Datum".into(),
+            title: "Opis".into(),
+            amount: "Zaduženje".into(),
+            credit: Some("Odobrenje".into()),
+            ..Default::default()
+        };
+
+        let preview = import_expenses_from_table(&mut conn, &table, &mapping, "rsd", true).unwrap();
+        assert_eq!((preview.imported, preview.skipped, preview.duplicates), (1, 2, 2));
+        assert_eq!(expense_count(&conn), 1);
+
+        let report = import_expenses_from_table(&mut conn, &table, &mapping, "rsd", false).unwrap();
+        let statuses: Vec<(usize, ExpenseImportStatus)> = report.rows.iter().map(|r| (r.line, r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (2, ExpenseImportStatus::Duplicate),
+                (3, ExpenseImportStatus::Skipped),
+                (4, ExpenseImportStatus::Imported),
+                (5, ExpenseImportStatus::Duplicate),
+                (6, ExpenseImportStatus::Skipped),
+            ]
+        );
+        assert_eq!(expense_count(&conn), 2);
+        let (amount, currency, date): (f64, String, String) = conn
+            .query_row("SELECT amount, currency, date FROM expenses WHERE title = 'Telekom'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })
+            .unwrap();
+        assert_eq!((amount, currency.as_str(), date.as_str()), (2499.0, "RSD", "2025-01-05"));
+    }
+
+    #[test]
+    fn signed_amount_column_skips_positive_rows() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let table = csv_reader::parse_csv(
+            "Date,Description,Amount,Currency\n\
+             2025-02-01,Hosting,\"-12,00\",eur\n\
+             2025-02-02,Refund,\"5,00\",EUR\n",
+        )
+        .unwrap();
+        let mapping = ExpenseCsvMapping {
+            date: "Date".into(),
+            title: "Description".into(),
+            amount: "Amount".into(),
+            currency: Some("Currency".into()),
+            signed_amounts: true,
+            ..Default::default()
+        };
+        let report = import_expenses_from_table(&mut conn, &table, &mapping, "RSD", false).unwrap();
+        assert_eq!((report.imported, report.skipped), (1, 1));
+        assert_eq!(report.rows[0].amount, Some(12.0));
+        assert_eq!(report.rows[0].currency.as_deref(), Some("EUR"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Expense totals for reports, aggregated in SQL so the frontend doesn't need every row.
+//! Amounts in different currencies are never added together: results are nested per currency.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use crate::DbState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpenseGroupBy {
+    Month,
+    Category,
+    Currency,
+}
+
+impl ExpenseGroupBy {
+    /// SQL expression used both as the group key and in `GROUP BY`.
+    fn key_sql(self) -> &'static str {
+        match self {
+            ExpenseGroupBy::Month => "substr(date, 1, 7)",
+            ExpenseGroupBy::Category => "COALESCE(NULLIF(TRIM(category), ''), '')",
+            ExpenseGroupBy::Currency => "currency",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseSummaryRow {
+    /// `YYYY-MM` for month, the category (empty when uncategorized), or the currency code.
+    pub key: String,
+    pub total: f64,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseCurrencySummary {
+    pub currency: String,
+    pub total: f64,
+    pub count: i64,
+    pub rows: Vec<ExpenseSummaryRow>,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+pub(crate) fn expense_summary_in_conn(
+    conn: &Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+    group_by: ExpenseGroupBy,
+) -> Result<Vec<ExpenseCurrencySummary>, rusqlite::Error> {
+    let key = group_by.key_sql();
+    let mut stmt = conn.prepare(&format!(
+        r#"SELECT currency, {key} AS k, SUM(amount), COUNT(*)
+           FROM expenses
+           WHERE deletedAt IS NULL
+             AND (?1 IS NULL OR date >= ?1)
+             AND (?2 IS NULL OR date <= ?2)
+           GROUP BY currency, k
+           ORDER BY currency, k"#
+    ))?;
+    let rows = stmt.query_map(params![from, to], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            ExpenseSummaryRow {
+                key: r.get(1)?,
+                total: r.get(2)?,
+                count: r.get(3)?,
+            },
+        ))
+    })?;
+
+    let mut out: Vec<ExpenseCurrencySummary> = Vec::new();
+    for row in rows {
+        let (currency, mut row) = row?;
+        let group = match out.last_mut() {
+            Some(g) if g.currency == currency => g,
+            _ => {
+                out.push(ExpenseCurrencySummary {
+                    currency,
+                    total: 0.0,
+                    count: 0,
+                    rows: Vec::new(),
+                });
+                out.last_mut().expect("just pushed")
+            }
+        };
+        group.total += row.total;
+        group.count += row.count;
+        row.total = round2(row.total);
+        group.rows.push(row);
+    }
+    for g in &mut out {
+        g.total = round2(g.total);
+    }
+    Ok(out)
+}
+
+/// Expense totals between `from` and `to` (inclusive `YYYY-MM-DD`, both optional).
+#[tauri::command]
+pub(crate) async fn expense_summary(
+    state: tauri::State<'_, DbState>,
+    from: Option<String>,
+    to: Option<String>,
+    group_by: ExpenseGroupBy,
+) -> Result<Vec<ExpenseCurrencySummary>, AppError> {
+    let from = from.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let to = to.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    state
+        .with_read("expense_summary", move |conn| {
+            expense_summary_in_conn(conn, from.as_deref(), to.as_deref(), group_by)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let rows = [
+            ("a", 1200.0, "RSD", "2024-12-05", Some("Zakup")),
+            ("b", 300.5, "RSD", "2024-12-31", None),
+            ("c", 1200.0, "RSD", "2025-01-05", Some("Zakup")),
+            ("d", 49.99, "EUR", "2024-12-20", Some("Hosting")),
+            ("e", 10.01, "EUR", "2025-01-02", Some("Hosting")),
+            ("f", 999.0, "RSD", "2025-02-01", Some("Zakup")),
+        ];
+        for (id, amount, currency, date, category) in rows {
+            conn.execute(
+                "INSERT INTO expenses (id, title, amount, currency, date, category, createdAt)
+                 VALUES (?1, 'x', ?2, ?3, ?4, ?5, 't')",
+                params![id, amount, currency, date, category],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn keys(s: &ExpenseCurrencySummary) -> Vec<(&str, f64, i64)> {
+        s.rows.iter().map(|r| (r.key.as_str(), r.total, r.count)).collect()
+    }
+
+    #[test]
+    fn month_groups_span_the_year_boundary_per_currency() {
+        let conn = seeded();
+        let out =
+            expense_summary_in_conn(&conn, Some("2024-12-01"), Some("2025-01-31"), ExpenseGroupBy::Month).unwrap();
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].currency, "EUR");
+        assert_eq!(keys(&out[0]), vec![("2024-12", 49.99, 1), ("2025-01", 10.01, 1)]);
+        assert_eq!((out[0].total, out[0].count), (60.0, 2));
+        assert_eq!(out[1].currency, "RSD");
+        assert_eq!(keys(&out[1]), vec![("2024-12", 1500.5, 2), ("2025-01", 1200.0, 1)]);
+        assert_eq!((out[1].total, out[1].count), (2700.5, 3));
+    }
+
+    #[test]
+    fn category_and_currency_groups() {
+        let conn = seeded();
+        let out = expense_summary_in_conn(&conn, None, None, ExpenseGroupBy::Category).unwrap();
+        assert_eq!(keys(&out[0]), vec![("Hosting", 60.0, 2)]);
+        assert_eq!(keys(&out[1]), vec![("", 300.5, 1), ("Zakup", 3399.0, 3)]);
+
+        let out = expense_summary_in_conn(&conn, Some("2025-01-01"), None, ExpenseGroupBy::Currency).unwrap();
+        assert_eq!(keys(&out[0]), vec![("EUR", 10.01, 1)]);
+        assert_eq!(keys(&out[1]), vec![("RSD", 2199.0, 2)]);
+    }
+}
@@ -0,0 +1,137 @@
+//! Features that depend on the license type. [`PLAN_FEATURES`] is the one table of what each
+//! plan includes; basic invoicing isn't listed and stays available to everyone the trial check
+//! lets through. A command behind a feature calls [`require_feature`], which fails with
+//! [`AppError::FeatureLocked`] naming the plan that unlocks it.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::license_activation::usable_license_type;
+use crate::trial::trial_active;
+use crate::DbState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+    /// Sending invoices and offers by email.
+    EmailSending,
+    /// Exporting every invoice PDF of a period at once.
+    BatchExport,
+}
+
+/// What the installation runs on; paid plans last, cheapest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Plan {
+    None,
+    Trial,
+    Yearly,
+    Lifetime,
+}
+
+/// Features included in each plan. The trial shows everything.
+const PLAN_FEATURES: [(Plan, &[Feature]); 4] = [
+    (Plan::None, &[]),
+    (Plan::Trial, &[Feature::EmailSending, Feature::BatchExport]),
+    (Plan::Yearly, &[Feature::EmailSending]),
+    (Plan::Lifetime, &[Feature::EmailSending, Feature::BatchExport]),
+];
+
+const PAID_PLANS: [Plan; 2] = [Plan::Yearly, Plan::Lifetime];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureLocked {
+    pub feature: Feature,
+    pub current_plan: Plan,
+    /// Cheapest license that includes the feature.
+    pub required_plan: Option<Plan>,
+}
+
+pub(crate) fn plan_includes(plan: Plan, feature: Feature) -> bool {
+    PLAN_FEATURES
+        .iter()
+        .any(|(p, features)| *p == plan && features.contains(&feature))
+}
+
+fn required_plan(feature: Feature) -> Option<Plan> {
+    PAID_PLANS.into_iter().find(|p| plan_includes(*p, feature))
+}
+
+/// A usable license decides the plan; without one it is the trial while that runs.
+fn plan_for(license_type: Option<&str>, trial_active: bool) -> Plan {
+    match license_type {
+        Some("LIFETIME") => Plan::Lifetime,
+        Some(_) => Plan::Yearly,
+        None if trial_active => Plan::Trial,
+        None => Plan::None,
+    }
+}
+
+fn current_plan(conn: &Connection) -> Result<Plan, rusqlite::Error> {
+    let now = OffsetDateTime::now_utc();
+    let license_type = usable_license_type(conn, now)?;
+    let trial = license_type.is_none() && trial_active(conn, now.unix_timestamp())?;
+    Ok(plan_for(license_type.as_deref(), trial))
+}
+
+/// Fails with [`AppError::FeatureLocked`] when the current plan doesn't include `feature`.
+pub(crate) fn require_feature(conn: &Connection, feature: Feature) -> Result<(), rusqlite::Error> {
+    let plan = current_plan(conn)?;
+    if plan_includes(plan, feature) {
+        return Ok(());
+    }
+    let locked = FeatureLocked {
+        feature,
+        current_plan: plan,
+        required_plan: required_plan(feature),
+    };
+    Err(AppError::FeatureLocked(locked).into())
+}
+
+#[tauri::command]
+pub(crate) async fn is_feature_enabled(state: tauri::State<'_, DbState>, feature: Feature) -> Result<bool, AppError> {
+    state
+        .with_write("is_feature_enabled", move |conn| Ok(plan_includes(current_plan(conn)?, feature)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(plan: Plan) -> Vec<Feature> {
+        [Feature::EmailSending, Feature::BatchExport]
+            .into_iter()
+            .filter(|f| plan_includes(plan, *f))
+            .collect()
+    }
+
+    #[test]
+    fn no_plan_has_no_premium_features() {
+        assert_eq!(plan_for(None, false), Plan::None);
+        assert!(features(Plan::None).is_empty());
+    }
+
+    #[test]
+    fn trial_has_every_feature() {
+        assert_eq!(plan_for(None, true), Plan::Trial);
+        assert_eq!(features(Plan::Trial), [Feature::EmailSending, Feature::BatchExport]);
+    }
+
+    #[test]
+    fn yearly_has_email_but_not_batch_export() {
+        assert_eq!(plan_for(Some("YEARLY"), false), Plan::Yearly);
+        assert_eq!(features(Plan::Yearly), [Feature::EmailSending]);
+        assert_eq!(required_plan(Feature::EmailSending), Some(Plan::Yearly));
+    }
+
+    #[test]
+    fn lifetime_has_every_feature() {
+        assert_eq!(plan_for(Some("LIFETIME"), true), Plan::Lifetime);
+        assert_eq!(features(Plan::Lifetime), [Feature::EmailSending, Feature::BatchExport]);
+        assert_eq!(required_plan(Feature::BatchExport), Some(Plan::Lifetime));
+    }
+}
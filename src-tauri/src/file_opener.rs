@@ -0,0 +1,114 @@
+//! Opening exported files in the system viewer and showing them in the file manager, through
+//! tauri-plugin-opener. Only exported document types are opened, so a path from the frontend
+//! can't launch a program. When the file manager can't highlight a file (on Linux this needs a
+//! desktop with the FileManager1 service) its folder is opened instead.
+
+use std::path::Path;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::errors::AppError;
+
+/// Extensions of the files the app exports.
+const OPENABLE_EXTENSIONS: [&str; 6] = ["pdf", "csv", "xlsx", "xml", "json", "zip"];
+
+/// An exported file and the outcome of opening it when that was asked for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFile {
+    pub path: String,
+    pub opened: bool,
+    /// Why the file couldn't be opened; `None` when it was, or wasn't asked to be.
+    pub open_error: Option<String>,
+}
+
+impl ExportedFile {
+    /// `path`, opened in the system viewer when `open` is set.
+    pub(crate) fn new(app: &tauri::AppHandle, path: String, open: bool) -> ExportedFile {
+        let (opened, open_error) = match open.then(|| open_file(app, &path)) {
+            Some(Ok(())) => (true, None),
+            Some(Err(e)) => (false, Some(e)),
+            None => (false, None),
+        };
+        ExportedFile { path, opened, open_error }
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// An existing file of a type the app exports.
+fn check_openable(path: &Path) -> Result<(), String> {
+    if !OPENABLE_EXTENSIONS.contains(&extension(path).as_str()) {
+        return Err(format!("Only exported documents can be opened: {}", path.display()));
+    }
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Opens `path` in the application registered for its type.
+pub(crate) fn open_file(app: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    let file = Path::new(path);
+    check_openable(file)?;
+    app.opener().open_path(path, None::<&str>).map_err(|e| {
+        format!(
+            "No application is registered to open .{} files ({e}). The file is saved at {path}.",
+            extension(file)
+        )
+    })
+}
+
+/// Shows `path` selected in Explorer/Finder/the file manager, or opens its folder.
+pub(crate) fn reveal_file(app: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    let file = Path::new(path);
+    if !file.exists() {
+        return Err(format!("File not found: {path}"));
+    }
+    let Err(reveal_error) = app.opener().reveal_item_in_dir(file) else {
+        return Ok(());
+    };
+    let folder = file
+        .parent()
+        .ok_or_else(|| format!("Could not show {path} in the file manager: {reveal_error}"))?;
+    app.opener()
+        .open_path(folder.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Could not show {path} in the file manager: {reveal_error}; opening its folder failed: {e}"))
+}
+
+#[tauri::command]
+pub(crate) fn open_path(app: tauri::AppHandle, path: String) -> Result<(), AppError> {
+    Ok(open_file(&app, &path)?)
+}
+
+#[tauri::command]
+pub(crate) fn reveal_in_folder(app: tauri::AppHandle, path: String) -> Result<(), AppError> {
+    Ok(reveal_file(&app, &path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_existing_exported_documents_can_be_opened() {
+        let dir = std::env::temp_dir().join(format!("pausaler-opener-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pdf = dir.join("Faktura 1.PDF");
+        let script = dir.join("run.sh");
+        std::fs::write(&pdf, b"%PDF").unwrap();
+        std::fs::write(&script, b"#!/bin/sh").unwrap();
+
+        assert!(check_openable(&pdf).is_ok());
+        assert!(check_openable(&script).unwrap_err().starts_with("Only exported documents"));
+        assert!(check_openable(&dir.join("missing.pdf")).unwrap_err().starts_with("File not found"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+//! Idempotency keys for create commands.
+//!
+//! The UI generates one key per opened create form. The first call stores the key with the id
+//! of the entity it created, in the same transaction; a repeated call with the same key within
+//! `KEY_TTL_SECS` gets the original entity back instead of creating a second one. Keys are
+//! scoped per entity type, so one key can't return an invoice from `create_client`.
+
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::Manager;
+use time::OffsetDateTime;
+
+use crate::DbState;
+
+/// How long a key keeps returning the original entity.
+const KEY_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// How often expired keys are removed while the app runs (also once at startup).
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IdempotencyScope {
+    Invoice,
+    Client,
+    Expense,
+}
+
+impl IdempotencyScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            IdempotencyScope::Invoice => "invoice",
+            IdempotencyScope::Client => "client",
+            IdempotencyScope::Expense => "expense",
+        }
+    }
+}
+
+fn unix_now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Id of the entity created under `key`, unless the key is unknown or expired.
+fn find(conn: &Connection, scope: IdempotencyScope, key: &str) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT entityId FROM idempotency WHERE scope = ?1 AND key = ?2 AND createdAt > ?3",
+        params![scope.as_str(), key, unix_now() - KEY_TTL_SECS],
+        |r| r.get(0),
+    )
+    .optional()
+}
+
+/// The entity created under `key`, read with `read`; `None` without a key, for an unknown or
+/// expired key, or when the entity has been deleted since (the caller then creates a new one).
+pub(crate) fn replay<T>(
+    conn: &Connection,
+    scope: IdempotencyScope,
+    key: Option<&str>,
+    read: impl FnOnce(&Connection, &str) -> Result<Option<T>, rusqlite::Error>,
+) -> Result<Option<T>, rusqlite::Error> {
+    let Some(key) = key else { return Ok(None) };
+    match find(conn, scope, key)? {
+        Some(id) => read(conn, &id),
+        None => Ok(None),
+    }
+}
+
+/// Records that `key` created `entity_id`; replaces an expired entry or one whose entity is gone.
+pub(crate) fn remember(
+    conn: &Connection,
+    scope: IdempotencyScope,
+    key: &str,
+    entity_id: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO idempotency (scope, key, entityId, createdAt) VALUES (?1, ?2, ?3, ?4)",
+        params![scope.as_str(), key, entity_id, unix_now()],
+    )?;
+    Ok(())
+}
+
+fn prune_expired(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    conn.execute("DELETE FROM idempotency WHERE createdAt <= ?1", params![unix_now() - KEY_TTL_SECS])
+}
+
+/// Spawns the job that removes expired keys. Must run after `DbState` is managed.
+pub(crate) fn spawn_idempotency_housekeeping(app: tauri::AppHandle) {
+    let db = app.state::<DbState>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match db.with_write("idempotency_prune", |conn| prune_expired(conn)).await {
+                Ok(n) if n > 0 => tracing::info!(target: "idempotency", count = n, "expired idempotency keys removed"),
+                Ok(_) => {}
+                Err(e) => tracing::error!(target: "idempotency", error = %e, "failed to prune idempotency keys"),
+            }
+            tokio::time::sleep(HOUSEKEEPING_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_scoped_and_expire() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        remember(&conn, IdempotencyScope::Invoice, "k1", "inv-1").unwrap();
+        assert_eq!(find(&conn, IdempotencyScope::Invoice, "k1").unwrap().as_deref(), Some("inv-1"));
+        assert_eq!(find(&conn, IdempotencyScope::Client, "k1").unwrap(), None);
+
+        conn.execute(
+            "INSERT INTO idempotency (scope, key, entityId, createdAt) VALUES ('expense', 'old', 'e-1', ?1)",
+            params![unix_now() - KEY_TTL_SECS - 1],
+        )
+        .unwrap();
+        assert_eq!(find(&conn, IdempotencyScope::Expense, "old").unwrap(), None);
+        assert_eq!(prune_expired(&conn).unwrap(), 1);
+        assert_eq!(find(&conn, IdempotencyScope::Invoice, "k1").unwrap().as_deref(), Some("inv-1"));
+    }
+}
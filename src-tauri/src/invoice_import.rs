@@ -0,0 +1,686 @@
+//! Invoice import from another invoicing tool's CSV export: one row per line item, rows grouped
+//! into invoices by number, the original numbers kept (the settings counter is not touched) and
+//! clients matched by PIB or name, or created.
+
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::clients::{insert_client_row, load_all_clients, normalize_id_number, normalize_name};
+use crate::csv_reader::{self, CsvTable};
+use crate::data_events::{DataEntity, DataOp};
+use crate::expense_import::{parse_amount, parse_bank_date};
+use crate::money::RoundingMode;
+use crate::{
+    compute_model97_reference, now_iso, read_settings_from_conn, tax_ids, validation_to_sql_error, Client,
+    DbState, Invoice, InvoiceItem, InvoiceStatus,
+};
+
+/// CSV header for each invoice field; number, issue date, client name and item description are
+/// required, as is a unit price or a line total column.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceCsvMapping {
+    pub invoice_number: String,
+    pub issue_date: String,
+    pub client_name: String,
+    pub item_description: String,
+    /// Defaults to 1 when not mapped or empty.
+    #[serde(default)]
+    pub item_quantity: Option<String>,
+    #[serde(default)]
+    pub item_unit: Option<String>,
+    #[serde(default)]
+    pub item_unit_price: Option<String>,
+    /// Line total; computed from quantity and unit price when not mapped.
+    #[serde(default)]
+    pub item_total: Option<String>,
+    #[serde(default)]
+    pub client_pib: Option<String>,
+    #[serde(default)]
+    pub service_date: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub paid_at: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceImportOptions {
+    #[serde(default)]
+    pub preview: bool,
+    /// Used when the currency column is not mapped or empty; the settings currency otherwise.
+    #[serde(default)]
+    pub default_currency: Option<String>,
+    /// Status values of the other tool (matched case-insensitively), checked before the built-in
+    /// English and Serbian names.
+    #[serde(default)]
+    pub status_map: HashMap<String, InvoiceStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InvoiceImportStatus {
+    Imported,
+    Skipped,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceImportRow {
+    /// 1-based line of the invoice's first row in the CSV file.
+    pub line: usize,
+    pub invoice_number: String,
+    pub client_name: String,
+    pub item_count: usize,
+    pub total: Option<f64>,
+    pub currency: Option<String>,
+    pub status: InvoiceImportStatus,
+    pub message: Option<String>,
+    /// The client did not exist and was (or, in a preview, would be) created.
+    pub client_created: bool,
+    pub invoice_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceImportReport {
+    /// True when nothing was written; the counts then describe what would be imported.
+    pub preview: bool,
+    pub imported: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+    pub clients_created: usize,
+    pub rows: Vec<InvoiceImportRow>,
+}
+
+struct MappedColumns {
+    invoice_number: usize,
+    issue_date: usize,
+    client_name: usize,
+    item_description: usize,
+    item_quantity: Option<usize>,
+    item_unit: Option<usize>,
+    item_unit_price: Option<usize>,
+    item_total: Option<usize>,
+    client_pib: Option<usize>,
+    service_date: Option<usize>,
+    due_date: Option<usize>,
+    paid_at: Option<usize>,
+    currency: Option<usize>,
+    status: Option<usize>,
+    notes: Option<usize>,
+}
+
+fn resolve_mapping(table: &CsvTable, mapping: &InvoiceCsvMapping) -> Result<MappedColumns, String> {
+    let column = |header: &str| {
+        table
+            .column(header)
+            .ok_or_else(|| format!("Column \"{}\" was not found in the CSV header.", header.trim()))
+    };
+    let optional = |header: &Option<String>| match header.as_deref().map(str::trim) {
+        Some(h) if !h.is_empty() => column(h).map(Some),
+        _ => Ok(None),
+    };
+    for (header, field) in [
+        (&mapping.invoice_number, "invoice number"),
+        (&mapping.issue_date, "issue date"),
+        (&mapping.client_name, "client name"),
+        (&mapping.item_description, "item description"),
+    ] {
+        if header.trim().is_empty() {
+            return Err(format!("Choose the CSV column that holds the {field}."));
+        }
+    }
+    let cols = MappedColumns {
+        invoice_number: column(&mapping.invoice_number)?,
+        issue_date: column(&mapping.issue_date)?,
+        client_name: column(&mapping.client_name)?,
+        item_description: column(&mapping.item_description)?,
+        item_quantity: optional(&mapping.item_quantity)?,
+        item_unit: optional(&mapping.item_unit)?,
+        item_unit_price: optional(&mapping.item_unit_price)?,
+        item_total: optional(&mapping.item_total)?,
+        client_pib: optional(&mapping.client_pib)?,
+        service_date: optional(&mapping.service_date)?,
+        due_date: optional(&mapping.due_date)?,
+        paid_at: optional(&mapping.paid_at)?,
+        currency: optional(&mapping.currency)?,
+        status: optional(&mapping.status)?,
+        notes: optional(&mapping.notes)?,
+    };
+    if cols.item_unit_price.is_none() && cols.item_total.is_none() {
+        return Err("Choose the CSV column that holds the item unit price or the item total.".to_string());
+    }
+    Ok(cols)
+}
+
+fn cell(row: &[String], idx: Option<usize>) -> String {
+    idx.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// An empty status is a draft; unknown values are an error rather than a guess.
+fn parse_status(v: &str, status_map: &HashMap<String, InvoiceStatus>) -> Result<InvoiceStatus, String> {
+    let key = v.trim().to_lowercase();
+    if key.is_empty() {
+        return Ok(InvoiceStatus::Draft);
+    }
+    if let Some((_, status)) = status_map.iter().find(|(k, _)| k.trim().to_lowercase() == key) {
+        return Ok(*status);
+    }
+    match key.as_str() {
+        "draft" | "nacrt" | "нацрт" => Ok(InvoiceStatus::Draft),
+        "sent" | "issued" | "poslata" | "izdata" | "послата" | "издата" => Ok(InvoiceStatus::Sent),
+        "paid" | "plaćena" | "placena" | "naplaćena" | "naplacena" | "плаћена" | "наплаћена" => {
+            Ok(InvoiceStatus::Paid)
+        }
+        "cancelled" | "canceled" | "stornirana" | "otkazana" | "сторнирана" | "отказана" => {
+            Ok(InvoiceStatus::Cancelled)
+        }
+        _ => Err(format!("Unknown status \"{}\".", v.trim())),
+    }
+}
+
+fn parse_date(label: &str, raw: &str) -> Result<String, String> {
+    parse_bank_date(raw).ok_or_else(|| format!("Invalid {label} \"{raw}\"."))
+}
+
+fn parse_optional_date(label: &str, raw: String) -> Result<Option<String>, String> {
+    if raw.is_empty() {
+        Ok(None)
+    } else {
+        parse_date(label, &raw).map(Some)
+    }
+}
+
+struct ParsedInvoice {
+    issue_date: String,
+    service_date: String,
+    due_date: Option<String>,
+    paid_at: Option<String>,
+    client_name: String,
+    client_pib: String,
+    currency: String,
+    status: InvoiceStatus,
+    notes: String,
+    items: Vec<InvoiceItem>,
+}
+
+fn item_from_csv_row(cols: &MappedColumns, line: usize, row: &[String]) -> Result<InvoiceItem, String> {
+    let description = cell(row, Some(cols.item_description));
+    if description.is_empty() {
+        return Err(format!("Line {line}: item description is empty."));
+    }
+    let number = |idx: Option<usize>, label: &str| -> Result<Option<f64>, String> {
+        let raw = cell(row, idx);
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        parse_amount(&raw)
+            .map(Some)
+            .ok_or_else(|| format!("Line {line}: invalid {label} \"{raw}\"."))
+    };
+    let quantity = number(cols.item_quantity, "quantity")?.unwrap_or(1.0);
+    if quantity <= 0.0 {
+        return Err(format!("Line {line}: quantity must be greater than zero."));
+    }
+    let (unit_price, total) = match (number(cols.item_unit_price, "unit price")?, number(cols.item_total, "total")?) {
+        (Some(price), Some(total)) => (price, total),
+        (Some(price), None) => (price, price * quantity),
+        (None, Some(total)) => (total / quantity, total),
+        (None, None) => return Err(format!("Line {line}: item has neither a unit price nor a total.")),
+    };
+    Ok(InvoiceItem {
+        id: Uuid::new_v4().to_string(),
+        description,
+        unit: Some(cell(row, cols.item_unit)).filter(|u| !u.is_empty()),
+        quantity,
+        unit_price: round2(unit_price),
+        discount_amount: None,
+        total: round2(total),
+        catalog_item_id: None,
+    })
+}
+
+/// Invoice-level fields come from the group's first row; every row contributes one item.
+fn invoice_from_group(
+    cols: &MappedColumns,
+    options: &InvoiceImportOptions,
+    default_currency: &str,
+    rows: &[(usize, &[String])],
+) -> Result<ParsedInvoice, String> {
+    let (first_line, first) = rows[0];
+    let client_name = cell(first, Some(cols.client_name));
+    if client_name.is_empty() {
+        return Err("Client name is empty.".to_string());
+    }
+    for (line, row) in &rows[1..] {
+        let other = cell(row, Some(cols.client_name));
+        if !other.is_empty() && normalize_name(&other) != normalize_name(&client_name) {
+            return Err(format!("Line {line} names a different client than line {first_line}."));
+        }
+    }
+    let client_pib = cell(first, cols.client_pib);
+    let client_pib = if client_pib.is_empty() {
+        client_pib
+    } else {
+        tax_ids::check_pib(&client_pib).map_err(|e| e.message("en", &client_pib))?
+    };
+
+    let issue_date = parse_date("issue date", &cell(first, Some(cols.issue_date)))?;
+    let service_date = parse_optional_date("service date", cell(first, cols.service_date))?
+        .unwrap_or_else(|| issue_date.clone());
+    let due_date = parse_optional_date("due date", cell(first, cols.due_date))?;
+    let status = parse_status(&cell(first, cols.status), &options.status_map)?;
+    // Paid invoices without a payment date count as paid on their issue date, so period
+    // reports place them in the year they were issued rather than the day of the import.
+    let paid_at = match parse_optional_date("payment date", cell(first, cols.paid_at))? {
+        Some(d) => Some(d),
+        None if status == InvoiceStatus::Paid => Some(issue_date.clone()),
+        None => None,
+    };
+    let currency = Some(cell(first, cols.currency).to_uppercase())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| default_currency.to_string());
+
+    let items = rows
+        .iter()
+        .map(|(line, row)| item_from_csv_row(cols, *line, row))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ParsedInvoice {
+        issue_date,
+        service_date,
+        due_date,
+        paid_at,
+        client_name,
+        client_pib,
+        currency,
+        status,
+        notes: cell(first, cols.notes),
+        items,
+    })
+}
+
+/// An invoice number with its `(line, row)` pairs.
+type RowGroup<'a> = (String, Vec<(usize, &'a [String])>);
+
+/// Rows grouped by trimmed invoice number in first-seen order; rows without a number each form
+/// their own (invalid) group.
+fn group_rows<'a>(table: &'a CsvTable, cols: &MappedColumns) -> Vec<RowGroup<'a>> {
+    let mut groups: Vec<RowGroup<'a>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (line, row) in &table.rows {
+        let number = cell(row, Some(cols.invoice_number));
+        if number.is_empty() {
+            groups.push((number, vec![(*line, row.as_slice())]));
+            continue;
+        }
+        match index.get(&number) {
+            Some(&i) => groups[i].1.push((*line, row.as_slice())),
+            None => {
+                index.insert(number.clone(), groups.len());
+                groups.push((number, vec![(*line, row.as_slice())]));
+            }
+        }
+    }
+    groups
+}
+
+/// Imports every valid invoice in one transaction; with `preview` the invoices are only checked
+/// and nothing is written. Numbers already used by an existing invoice are reported as
+/// duplicates. Clients are matched by PIB, then by name, and created when neither matches.
+pub(crate) fn import_invoices_from_table(
+    conn: &mut Connection,
+    table: &CsvTable,
+    mapping: &InvoiceCsvMapping,
+    options: &InvoiceImportOptions,
+) -> Result<InvoiceImportReport, rusqlite::Error> {
+    let cols = resolve_mapping(table, mapping).map_err(validation_to_sql_error)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let default_currency = match options.default_currency.as_deref().map(str::trim) {
+        Some(c) if !c.is_empty() => c.to_uppercase(),
+        _ => read_settings_from_conn(&tx)?.default_currency,
+    };
+
+    let mut known_numbers: HashSet<String> = HashSet::new();
+    {
+        let mut stmt = tx.prepare("SELECT invoiceNumber FROM invoices")?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        for number in rows {
+            known_numbers.insert(number?.trim().to_lowercase());
+        }
+    }
+    let mut by_pib: HashMap<String, String> = HashMap::new();
+    let mut by_name: HashMap<String, String> = HashMap::new();
+    for c in load_all_clients(&tx)? {
+        let pib = normalize_id_number(&c.pib);
+        if !pib.is_empty() {
+            by_pib.entry(pib).or_insert_with(|| c.id.clone());
+        }
+        by_name.entry(normalize_name(&c.name)).or_insert(c.id);
+    }
+
+    let created_at = now_iso();
+    let mut rows = Vec::new();
+    for (number, group) in group_rows(table, &cols) {
+        let mut report = InvoiceImportRow {
+            line: group[0].0,
+            invoice_number: number.clone(),
+            client_name: cell(group[0].1, Some(cols.client_name)),
+            item_count: group.len(),
+            total: None,
+            currency: None,
+            status: InvoiceImportStatus::Skipped,
+            message: None,
+            client_created: false,
+            invoice_id: None,
+        };
+        if number.is_empty() {
+            report.message = Some("Invoice number is empty.".to_string());
+            rows.push(report);
+            continue;
+        }
+        let parsed = match invoice_from_group(&cols, options, &default_currency, &group) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report.message = Some(e);
+                rows.push(report);
+                continue;
+            }
+        };
+        let subtotal = round2(parsed.items.iter().map(|i| i.total).sum());
+        report.total = Some(subtotal);
+        report.currency = Some(parsed.currency.clone());
+        if !known_numbers.insert(number.to_lowercase()) {
+            report.status = InvoiceImportStatus::Duplicate;
+            report.message = Some(format!("An invoice numbered {number} already exists."));
+            rows.push(report);
+            continue;
+        }
+
+        let existing = Some(parsed.client_pib.as_str())
+            .filter(|p| !p.is_empty())
+            .and_then(|p| by_pib.get(&normalize_id_number(p)))
+            .or_else(|| by_name.get(&normalize_name(&parsed.client_name)))
+            .cloned();
+        let client_id = match existing {
+            Some(id) => id,
+            None => {
+                let client = Client {
+                    id: Uuid::new_v4().to_string(),
+                    name: parsed.client_name.clone(),
+                    registration_number: String::new(),
+                    pib: parsed.client_pib.clone(),
+                    address: String::new(),
+                    city: String::new(),
+                    postal_code: String::new(),
+                    email: String::new(),
+                    phone: None,
+                    website: None,
+                    default_currency: None,
+                    preferred_language: None,
+                    payment_terms_days: None,
+                    billing_email: None,
+                    archived: false,
+                    is_foreign: false,
+                    country: None,
+                    created_at: created_at.clone(),
+                };
+                // In a preview nothing is written, but later invoices still match the client.
+                if !options.preview {
+                    insert_client_row(&tx, &client)?;
+                }
+                if !client.pib.is_empty() {
+                    by_pib.insert(normalize_id_number(&client.pib), client.id.clone());
+                }
+                by_name.insert(normalize_name(&client.name), client.id.clone());
+                report.client_created = true;
+                client.id
+            }
+        };
+
+        report.status = InvoiceImportStatus::Imported;
+        if !options.preview {
+            let invoice = Invoice {
+                id: Uuid::new_v4().to_string(),
+                payment_reference: compute_model97_reference(&number).ok(),
+                invoice_number: number,
+                client_id,
+                client_name: parsed.client_name,
+                issue_date: parsed.issue_date,
+                service_date: parsed.service_date,
+                status: parsed.status,
+                due_date: parsed.due_date,
+                paid_at: parsed.paid_at,
+                currency: parsed.currency,
+                items: parsed.items,
+                subtotal,
+                invoice_discount: None,
+                invoice_discount_percent: None,
+                total: subtotal,
+                notes: parsed.notes,
+                payment_method: None,
+                exchange_rate: None,
+                imported: true,
+                rounding_mode: RoundingMode::default(),
+                quote_id: None,
+                issued_by: None,
+                custom_fields: Vec::new(),
+                created_at: created_at.clone(),
+            };
+            let json = serde_json::to_string(&invoice).unwrap_or_else(|_| "{}".to_string());
+            tx.execute(
+                r#"INSERT INTO invoices (
+                    id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+                params![
+                    invoice.id,
+                    invoice.invoice_number,
+                    invoice.client_id,
+                    invoice.issue_date,
+                    invoice.status.as_str(),
+                    invoice.due_date,
+                    invoice.paid_at,
+                    invoice.currency,
+                    invoice.total,
+                    invoice.created_at,
+                    json,
+                ],
+            )?;
+            report.invoice_id = Some(invoice.id);
+        }
+        rows.push(report);
+    }
+    if !options.preview {
+        tx.commit()?;
+    }
+
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+    Ok(InvoiceImportReport {
+        preview: options.preview,
+        imported: count(InvoiceImportStatus::Imported),
+        skipped: count(InvoiceImportStatus::Skipped),
+        duplicates: count(InvoiceImportStatus::Duplicate),
+        clients_created: rows.iter().filter(|r| r.client_created).count(),
+        rows,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn import_invoices_csv(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    mapping: InvoiceCsvMapping,
+    options: Option<InvoiceImportOptions>,
+) -> Result<InvoiceImportReport, AppError> {
+    let table = csv_reader::read_csv_file(&path)?;
+    let options = options.unwrap_or_default();
+    let op = if options.preview { "preview_invoices_csv" } else { "import_invoices_csv" };
+    state
+        .with_create_notify(op, move |conn, changes| {
+            let report = import_invoices_from_table(conn, &table, &mapping, &options)?;
+            if !report.preview {
+                let created = report
+                    .rows
+                    .iter()
+                    .filter(|r| matches!(r.status, InvoiceImportStatus::Imported))
+                    .filter_map(|r| r.invoice_id.clone());
+                changes.record_all(DataEntity::Invoices, DataOp::Created, created);
+                if report.clients_created > 0 {
+                    changes.replaced(DataEntity::Clients);
+                }
+            }
+            Ok(report)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trial;
+
+    fn mapping() -> InvoiceCsvMapping {
+        InvoiceCsvMapping {
+            invoice_number: "Broj".into(),
+            issue_date: "Datum".into(),
+            client_name: "Kupac".into(),
+            item_description: "Opis".into(),
+            item_quantity: Some("Kol".into()),
+            item_unit_price: Some("Cena".into()),
+            client_pib: Some("PIB".into()),
+            status: Some("Status".into()),
+            ..Default::default()
+        }
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0)).unwrap()
+    }
+
+    #[test]
+    fn import_is_refused_after_the_trial_ends() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        trial::end_trial(&conn).unwrap();
+        let table = csv_reader::parse_csv(
+            "Broj;Datum;Kupac;PIB;Opis;Kol;Cena;Status\n\
+             OLD-1;05.01.2024;Acme;;Konsalting;1;100;\n",
+        )
+        .unwrap();
+
+        // What `import_invoices_csv` runs inside its write (`with_create_notify`).
+        let err = trial::require_license(&conn)
+            .and_then(|_| import_invoices_from_table(&mut conn, &table, &mapping(), &Default::default()))
+            .unwrap_err();
+        assert_eq!(AppError::from(err), AppError::LicenseRequired);
+        assert_eq!(count(&conn, "invoices"), 0);
+    }
+
+    #[test]
+    fn groups_items_keeps_numbers_and_matches_clients() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json)
+             VALUES ('c1', 'Acme', '100000024', '', '', 't',
+                     '{\"id\":\"c1\",\"name\":\"Acme\",\"pib\":\"100000024\",\"address\":\"\",\"email\":\"\",\"createdAt\":\"t\"}')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+             VALUES ('i', 'OLD-7', 'c1', '2023-01-01', 'RSD', 1, 't', '{}')",
+            [],
+        )
+        .unwrap();
+        let table = csv_reader::parse_csv(
+            "Broj;Datum;Kupac;PIB;Opis;Kol;Cena;Status\n\
+             OLD-1;05.01.2024;ACME ;100000024;Konsalting;2;10.000,00;Plaćena\n\
+             OLD-2;06.01.2024;Novi kupac;;Razvoj;1;5.000,00;sent\n\
+             OLD-1;05.01.2024;Acme;100000024;Putni troškovi;1;1.500,50;Plaćena\n\
+             OLD-7;07.01.2024;Acme;;Duplikat;1;1,00;\n\
+             OLD-3;08.01.2024;Novi kupac;;Podrška;1;700;arhivirana\n\
+             OLD-4;09.01.2024;Novi Kupac;;Hosting;1;300;\n",
+        )
+        .unwrap();
+
+        let options = InvoiceImportOptions {
+            preview: true,
+            ..Default::default()
+        };
+        let preview = import_invoices_from_table(&mut conn, &table, &mapping(), &options).unwrap();
+        assert_eq!((preview.imported, preview.skipped, preview.duplicates), (3, 1, 1));
+        assert_eq!(preview.clients_created, 1);
+        assert_eq!((count(&conn, "invoices"), count(&conn, "clients")), (1, 1));
+
+        let report = import_invoices_from_table(&mut conn, &table, &mapping(), &Default::default()).unwrap();
+        let statuses: Vec<(&str, usize, InvoiceImportStatus)> = report
+            .rows
+            .iter()
+            .map(|r| (r.invoice_number.as_str(), r.line, r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("OLD-1", 2, InvoiceImportStatus::Imported),
+                ("OLD-2", 3, InvoiceImportStatus::Imported),
+                ("OLD-7", 5, InvoiceImportStatus::Duplicate),
+                ("OLD-3", 6, InvoiceImportStatus::Skipped),
+                ("OLD-4", 7, InvoiceImportStatus::Imported),
+            ]
+        );
+        assert_eq!(report.rows[3].message.as_deref(), Some("Unknown status \"arhivirana\"."));
+        assert_eq!((count(&conn, "invoices"), count(&conn, "clients")), (4, 2));
+
+        let json: String = conn
+            .query_row("SELECT data_json FROM invoices WHERE invoiceNumber = 'OLD-1'", [], |r| r.get(0))
+            .unwrap();
+        let invoice: Invoice = serde_json::from_str(&json).unwrap();
+        assert!(invoice.imported);
+        assert_eq!(invoice.client_id, "c1");
+        assert_eq!(invoice.status, InvoiceStatus::Paid);
+        assert_eq!(invoice.paid_at.as_deref(), Some("2024-01-05"));
+        assert_eq!(invoice.items.len(), 2);
+        assert_eq!(invoice.total, 21500.5);
+        assert_eq!(invoice.currency, "RSD");
+
+        let new_clients: Vec<String> = ["OLD-2", "OLD-4"]
+            .iter()
+            .map(|n| {
+                conn.query_row("SELECT clientId FROM invoices WHERE invoiceNumber = ?1", [n], |r| r.get(0))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(new_clients[0], new_clients[1]);
+    }
+
+    #[test]
+    fn rejects_mappings_without_an_amount_column() {
+        let table = csv_reader::parse_csv("Broj,Datum,Kupac,Opis\n1,2024-01-01,A,B\n").unwrap();
+        let mut mapping = mapping();
+        mapping.item_quantity = None;
+        mapping.item_unit_price = None;
+        mapping.client_pib = None;
+        mapping.status = None;
+        let err = resolve_mapping(&table, &mapping).err().unwrap();
+        assert!(err.contains("unit price or the item total"), "{err}");
+    }
+}
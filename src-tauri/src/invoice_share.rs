@@ -0,0 +1,325 @@
+//! One invoice as a JSON file, for handing it to someone who also runs Pausaler. The file carries
+//! the invoice and a snapshot of its client, so it imports on its own.
+//!
+//! On import the client is matched by PIB, then by name, and created from the snapshot when
+//! neither matches. The invoice gets the next number from the settings counter, or keeps the
+//! number in the file with `keepNumber`; a number that is already taken is an error unless
+//! `renumberOnConflict` allows falling back to the counter.
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::clients::{insert_client_row, load_all_clients, normalize_id_number, normalize_name};
+use crate::data_events::{DataEntity, DataOp};
+use crate::errors::AppError;
+use crate::{
+    compute_model97_reference, format_invoice_number, now_iso, read_client_from_conn, read_invoice_from_conn,
+    validation_to_sql_error, write_text_file, Client, DbState, Invoice, SETTINGS_ID,
+};
+
+pub(crate) const INVOICE_FILE_FORMAT: &str = "pausaler-invoice";
+pub(crate) const INVOICE_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceFile {
+    pub format: String,
+    pub format_version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    pub invoice: Invoice,
+    /// The client as it was at export; `None` when it had been deleted.
+    #[serde(default)]
+    pub client: Option<Client>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceFileImportOptions {
+    /// Keep the invoice number from the file instead of taking the next one.
+    #[serde(default)]
+    pub keep_number: bool,
+    /// With `keep_number`, take the next number when the file's is already used.
+    #[serde(default)]
+    pub renumber_on_conflict: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceFileImportResult {
+    pub invoice: Invoice,
+    /// No client matched, so one was created from the file.
+    pub client_created: bool,
+    /// The invoice has the number from the file.
+    pub kept_number: bool,
+}
+
+pub(crate) fn invoice_file_in_conn(conn: &Connection, id: &str) -> Result<InvoiceFile, rusqlite::Error> {
+    let invoice = read_invoice_from_conn(conn, id)?.ok_or_else(|| AppError::not_found("invoice", id))?;
+    let client = read_client_from_conn(conn, &invoice.client_id)?;
+    Ok(InvoiceFile {
+        format: INVOICE_FILE_FORMAT.to_string(),
+        format_version: INVOICE_FILE_VERSION,
+        exported_at: now_iso(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        invoice,
+        client,
+    })
+}
+
+fn parse_invoice_file(doc: Value) -> Result<InvoiceFile, String> {
+    if doc.get("format").and_then(Value::as_str) != Some(INVOICE_FILE_FORMAT) {
+        return Err("This file is not a Pausaler invoice.".to_string());
+    }
+    let version = doc.get("formatVersion").and_then(Value::as_u64).unwrap_or(0);
+    if version > u64::from(INVOICE_FILE_VERSION) {
+        return Err("This invoice was exported by a newer version of Pausaler. Update the app first.".to_string());
+    }
+    serde_json::from_value(doc).map_err(|e| format!("Invalid invoice file: {e}"))
+}
+
+/// The id of the client to bill, creating it from the file when it isn't there yet.
+fn resolve_client(conn: &Connection, file: &InvoiceFile, created_at: &str) -> Result<(String, bool), rusqlite::Error> {
+    let snapshot = file.client.clone();
+    let name = snapshot.as_ref().map_or(file.invoice.client_name.as_str(), |c| c.name.as_str());
+    let pib = snapshot.as_ref().map(|c| normalize_id_number(&c.pib)).unwrap_or_default();
+    let clients = load_all_clients(conn)?;
+    let existing = clients
+        .iter()
+        .find(|c| !pib.is_empty() && normalize_id_number(&c.pib) == pib)
+        .or_else(|| clients.iter().find(|c| normalize_name(&c.name) == normalize_name(name)));
+    if let Some(c) = existing {
+        return Ok((c.id.clone(), false));
+    }
+
+    let client = match snapshot {
+        Some(c) => Client {
+            id: Uuid::new_v4().to_string(),
+            archived: false,
+            created_at: created_at.to_string(),
+            ..c
+        },
+        None => Client {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            registration_number: String::new(),
+            pib: String::new(),
+            address: String::new(),
+            city: String::new(),
+            postal_code: String::new(),
+            email: String::new(),
+            phone: None,
+            website: None,
+            default_currency: None,
+            preferred_language: None,
+            payment_terms_days: None,
+            billing_email: None,
+            archived: false,
+            is_foreign: false,
+            country: None,
+            created_at: created_at.to_string(),
+        },
+    };
+    insert_client_row(conn, &client)?;
+    Ok((client.id, true))
+}
+
+/// Imports the invoice in `file` in one transaction.
+pub(crate) fn import_invoice_file_in_conn(
+    conn: &mut Connection,
+    file: InvoiceFile,
+    options: &InvoiceFileImportOptions,
+) -> Result<InvoiceFileImportResult, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let number = file.invoice.invoice_number.trim().to_string();
+    let taken: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM invoices WHERE lower(trim(invoiceNumber)) = lower(?1))",
+        params![number],
+        |r| r.get(0),
+    )?;
+    let kept_number = options.keep_number && !number.is_empty() && !taken;
+    if options.keep_number && taken && !options.renumber_on_conflict {
+        return Err(AppError::Conflict(format!("An invoice numbered {number} already exists.")).into());
+    }
+    if options.keep_number && number.is_empty() && !options.renumber_on_conflict {
+        return Err(validation_to_sql_error("The invoice in the file has no number.".to_string()));
+    }
+
+    let created_at = now_iso();
+    let (client_id, client_created) = resolve_client(&tx, &file, &created_at)?;
+    let mut invoice = file.invoice;
+    if !kept_number {
+        let (prefix, next): (String, i64) = tx.query_row(
+            "SELECT invoicePrefix, nextInvoiceNumber FROM settings WHERE id = ?1",
+            params![SETTINGS_ID],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        invoice.invoice_number = format_invoice_number(&prefix, next);
+        invoice.payment_reference = compute_model97_reference(&invoice.invoice_number).ok();
+        tx.execute(
+            "UPDATE settings SET nextInvoiceNumber = nextInvoiceNumber + 1, updatedAt = ?2 WHERE id = ?1",
+            params![SETTINGS_ID, created_at],
+        )?;
+    }
+    invoice.id = Uuid::new_v4().to_string();
+    invoice.client_id = client_id;
+    invoice.imported = kept_number;
+    invoice.quote_id = None;
+    invoice.created_at = created_at;
+    // Catalog ids belong to the other installation's catalog.
+    for item in &mut invoice.items {
+        item.catalog_item_id = None;
+    }
+
+    let json = serde_json::to_string(&invoice).unwrap_or_else(|_| "{}".to_string());
+    tx.execute(
+        r#"INSERT INTO invoices (
+            id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+        params![
+            invoice.id,
+            invoice.invoice_number,
+            invoice.client_id,
+            invoice.issue_date,
+            invoice.status.as_str(),
+            invoice.due_date,
+            invoice.paid_at,
+            invoice.currency,
+            invoice.total,
+            invoice.created_at,
+            json,
+        ],
+    )?;
+    tx.commit()?;
+
+    Ok(InvoiceFileImportResult { invoice, client_created, kept_number })
+}
+
+/// Writes one invoice and its client to a JSON file.
+#[tauri::command]
+pub(crate) async fn export_invoice_json(state: tauri::State<'_, DbState>, id: String, path: String) -> Result<String, AppError> {
+    let file = state.with_read("export_invoice_json", move |conn| invoice_file_in_conn(conn, &id)).await?;
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    write_text_file(std::path::Path::new(&path), &json)?;
+    Ok(path)
+}
+
+/// Loads a file written by `export_invoice_json`.
+#[tauri::command]
+pub(crate) async fn import_invoice_json(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    options: Option<InvoiceFileImportOptions>,
+) -> Result<InvoiceFileImportResult, AppError> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read invoice file: {e}"))?;
+    let doc: Value = serde_json::from_str(&text).map_err(|e| format!("Invalid invoice file: {e}"))?;
+    let file = parse_invoice_file(doc)?;
+    let options = options.unwrap_or_default();
+    state
+        .with_create_notify("import_invoice_json", move |conn, changes| {
+            let result = import_invoice_file_in_conn(conn, file, &options)?;
+            changes.record(DataEntity::Invoices, DataOp::Created, result.invoice.id.clone());
+            if result.client_created {
+                changes.record(DataEntity::Clients, DataOp::Created, result.invoice.client_id.clone());
+            }
+            if !result.kept_number {
+                changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            }
+            Ok(result)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        conn
+    }
+
+    fn seed(conn: &Connection, client_name: &str, pib: &str) {
+        let client = serde_json::json!({
+            "id": "c1", "name": client_name, "pib": pib, "address": "Ulica 1", "email": "k@example.com", "createdAt": "t"
+        });
+        let invoice = serde_json::json!({
+            "id": "i1", "invoiceNumber": "INV-0007", "clientId": "c1", "clientName": client_name,
+            "issueDate": "2025-02-01", "serviceDate": "2025-02-01", "status": "SENT", "currency": "RSD",
+            "items": [{ "id": "l1", "description": "Usluga", "quantity": 1, "unitPrice": 1000, "total": 1000,
+                        "catalogItemId": "cat1" }],
+            "subtotal": 1000, "total": 1000, "notes": "", "createdAt": "t"
+        });
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json) VALUES ('c1', ?1, ?2, '', '', 't', ?3)",
+            params![client_name, pib, client.to_string()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+             VALUES ('i1', 'INV-0007', 'c1', '2025-02-01', 'SENT', 'RSD', 1000, 't', ?1)",
+            params![invoice.to_string()],
+        )
+        .unwrap();
+    }
+
+    fn round_trip(file: &InvoiceFile) -> InvoiceFile {
+        parse_invoice_file(serde_json::from_str(&serde_json::to_string(file).unwrap()).unwrap()).unwrap()
+    }
+
+    fn keep(renumber_on_conflict: bool) -> InvoiceFileImportOptions {
+        InvoiceFileImportOptions { keep_number: true, renumber_on_conflict }
+    }
+
+    #[test]
+    fn import_creates_the_client_and_numbers_the_invoice() {
+        let source = open_db();
+        seed(&source, "Đorđević doo", "100000024");
+        let file = round_trip(&invoice_file_in_conn(&source, "i1").unwrap());
+        assert_eq!(file.client.as_ref().unwrap().pib, "100000024");
+
+        let mut target = open_db();
+        target.execute("UPDATE settings SET invoicePrefix = 'F', nextInvoiceNumber = 3", []).unwrap();
+        let first = import_invoice_file_in_conn(&mut target, file.clone(), &InvoiceFileImportOptions::default()).unwrap();
+        assert!(first.client_created && !first.kept_number && !first.invoice.imported);
+        assert_eq!(first.invoice.invoice_number, format_invoice_number("F", 3));
+        assert_eq!(first.invoice.items[0].catalog_item_id, None);
+        let client = read_client_from_conn(&target, &first.invoice.client_id).unwrap().unwrap();
+        assert_eq!((client.name.as_str(), client.address.as_str()), ("Đorđević doo", "Ulica 1"));
+
+        // The client is found again by PIB; the original number is free and kept.
+        let second = import_invoice_file_in_conn(&mut target, file.clone(), &keep(false)).unwrap();
+        assert!(!second.client_created && second.kept_number && second.invoice.imported);
+        assert_eq!(second.invoice.client_id, first.invoice.client_id);
+        assert_eq!(second.invoice.invoice_number, "INV-0007");
+        let next: i64 = target.query_row("SELECT nextInvoiceNumber FROM settings", [], |r| r.get(0)).unwrap();
+        assert_eq!(next, 4);
+    }
+
+    #[test]
+    fn taken_number_is_reported_unless_renumbering_is_allowed() {
+        let mut conn = open_db();
+        seed(&conn, "Klijent", "");
+        let file = invoice_file_in_conn(&conn, "i1").unwrap();
+
+        let err = AppError::from(import_invoice_file_in_conn(&mut conn, file.clone(), &keep(false)).unwrap_err());
+        assert_eq!(err.code(), "CONFLICT");
+        assert!(err.to_string().contains("INV-0007"), "{err}");
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM invoices", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+
+        let renumbered = import_invoice_file_in_conn(&mut conn, file, &keep(true)).unwrap();
+        assert!(!renumbered.kept_number && !renumbered.client_created);
+        assert_ne!(renumbered.invoice.invoice_number, "INV-0007");
+
+        let mut newer = serde_json::to_value(invoice_file_in_conn(&conn, "i1").unwrap()).unwrap();
+        newer["formatVersion"] = (INVOICE_FILE_VERSION + 1).into();
+        assert!(parse_invoice_file(newer).unwrap_err().contains("newer version"));
+        assert!(parse_invoice_file(serde_json::json!({ "format": "pausaler-data" })).is_err());
+    }
+}
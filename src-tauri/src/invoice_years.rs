@@ -0,0 +1,97 @@
+//! Per-year figures for the year separators of the invoice list. The list calls this on every
+//! render, so it is one GROUP BY over the indexed columns and never reads `data_json`.
+//!
+//! The year is the first four characters of `issueDate` when it looks like `YYYY-MM-DD`;
+//! invoices with an empty or malformed issue date end up in the `year: null` bucket, listed last.
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::DbState;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceYearTotal {
+    pub status: String,
+    pub currency: String,
+    pub count: i64,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceYear {
+    /// `None` for invoices without a usable issue date.
+    pub year: Option<i32>,
+    /// All invoices of the year, whatever their status.
+    pub count: i64,
+    /// One entry per status and currency; amounts in different currencies are never added up.
+    pub totals: Vec<InvoiceYearTotal>,
+}
+
+pub(crate) fn invoices_by_year_in_conn(conn: &Connection) -> Result<Vec<InvoiceYear>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT CASE WHEN issueDate GLOB '[0-9][0-9][0-9][0-9]-[01][0-9]-[0-3][0-9]*'
+                       THEN CAST(substr(issueDate, 1, 4) AS INTEGER) END AS year,
+                  status, currency, COUNT(*), COALESCE(SUM(totalAmount), 0)
+           FROM invoices
+           GROUP BY 1, 2, 3
+           ORDER BY year IS NULL, year DESC, status, currency"#,
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut out: Vec<InvoiceYear> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let year: Option<i32> = row.get(0)?;
+        let total = InvoiceYearTotal {
+            status: row.get(1)?,
+            currency: row.get(2)?,
+            count: row.get(3)?,
+            total: (row.get::<_, f64>(4)? * 100.0).round() / 100.0,
+        };
+        if out.last().is_none_or(|y| y.year != year) {
+            out.push(InvoiceYear { year, count: 0, totals: Vec::new() });
+        }
+        let bucket = out.last_mut().expect("pushed above");
+        bucket.count += total.count;
+        bucket.totals.push(total);
+    }
+    Ok(out)
+}
+
+/// Invoice counts and totals per issue year, newest first.
+#[tauri::command]
+pub(crate) async fn invoices_by_year(state: tauri::State<'_, DbState>) -> Result<Vec<InvoiceYear>, AppError> {
+    state.with_read("invoices_by_year", invoices_by_year_in_conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::invoice;
+
+    #[test]
+    fn groups_by_issue_year_with_an_unknown_bucket() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let rows = [
+            ("a", "2024-03-01", "PAID", "RSD", 1000.0),
+            ("b", "2024-11-30", "PAID", "RSD", 500.5),
+            ("c", "2024-12-31T10:00:00", "SENT", "EUR", 200.0),
+            ("d", "2025-01-02", "DRAFT", "RSD", 50.0),
+            ("e", "", "SENT", "RSD", 10.0),
+            ("f", "31.12.2023", "PAID", "RSD", 20.0),
+        ];
+        for (id, issue_date, status, currency, total) in rows {
+            invoice(id).issued(issue_date).status(status).amount(currency, total).insert(&conn);
+        }
+
+        let years = invoices_by_year_in_conn(&conn).unwrap();
+        let summary: Vec<_> = years.iter().map(|y| (y.year, y.count, y.totals.len())).collect();
+        assert_eq!(summary, vec![(Some(2025), 1, 1), (Some(2024), 3, 2), (None, 2, 2)]);
+        let y2024 = &years[1].totals;
+        assert_eq!((y2024[0].status.as_str(), y2024[0].currency.as_str()), ("PAID", "RSD"));
+        assert_eq!((y2024[0].count, y2024[0].total), (2, 1500.5));
+        assert_eq!((y2024[1].status.as_str(), y2024[1].currency.as_str(), y2024[1].total), ("SENT", "EUR", 200.0));
+    }
+}
@@ -0,0 +1,327 @@
+//! KPO book ("Knjiga o ostvarenom prometu paušalno oporezovanih obveznika"): SENT and PAID
+//! invoices of one year in issue-date order, in the default currency, with a running total.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::file_opener::ExportedFile;
+use crate::pdf_util::{draw_rule, embed_font, push_line, push_line_right_measured, save_pdf, wrap_text_by_width_mm};
+use crate::{
+    csv_document, csv_header_row, csv_join_row, format_money_csv, format_money_sr, invoice_total_in_currency,
+    read_settings_from_conn, resolve_csv_options, validation_to_sql_error, write_text_file, CsvOptions, DbState,
+    Invoice, Settings,
+};
+
+const CSV_HEADER: [&str; 7] = [
+    "Redni broj",
+    "Datum knjiženja",
+    "Opis knjiženja",
+    "Prihod od delatnosti od prodaje proizvoda",
+    "Prihod od delatnosti od izvršenih usluga",
+    "Svega prihodi od delatnosti (3+4)",
+    "Kumulativno",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KpoEntry {
+    pub ordinal: usize,
+    /// Issue date, `YYYY-MM-DD`.
+    pub date: String,
+    pub invoice_number: String,
+    pub description: String,
+    pub products: f64,
+    pub services: f64,
+    pub total: f64,
+    pub cumulative: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KpoBook {
+    pub year: i32,
+    pub company_name: String,
+    pub pib: String,
+    pub company_address: String,
+    pub currency: String,
+    pub entries: Vec<KpoEntry>,
+    pub total: f64,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// `YYYY-MM-DD` → `DD.MM.YYYY`, as written in the paper book.
+pub(crate) fn format_date_dmy(ymd: &str) -> String {
+    let parts: Vec<&str> = ymd.split('-').collect();
+    match parts.as_slice() {
+        [y, m, d] => format!("{d}.{m}.{y}."),
+        _ => ymd.to_string(),
+    }
+}
+
+/// Street, postal code and city on one line, skipping empty parts.
+pub(crate) fn company_address_one_line(settings: &Settings) -> String {
+    [settings.company_address_line.trim(), settings.company_postal_code.trim(), settings.company_city.trim()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn kpo_book_in_conn(conn: &Connection, year: i32) -> Result<KpoBook, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let mut stmt = conn.prepare(
+        r#"SELECT data_json
+           FROM invoices
+           WHERE status IN ('SENT', 'PAID') AND issueDate >= ?1 AND issueDate <= ?2
+           ORDER BY issueDate ASC, createdAt ASC"#,
+    )?;
+    let mut rows = stmt.query(params![format!("{year:04}-01-01"), format!("{year:04}-12-31")])?;
+    let mut invoices: Vec<Invoice> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        if let Ok(inv) = serde_json::from_str::<Invoice>(&json) {
+            invoices.push(inv);
+        }
+    }
+
+    let mut entries = Vec::with_capacity(invoices.len());
+    let mut missing_rate: Vec<String> = Vec::new();
+    let mut cumulative = 0.0;
+    for inv in invoices {
+        let Some(amount) = invoice_total_in_currency(&inv, &settings.default_currency) else {
+            missing_rate.push(inv.invoice_number);
+            continue;
+        };
+        let services = round2(amount);
+        cumulative = round2(cumulative + services);
+        entries.push(KpoEntry {
+            ordinal: entries.len() + 1,
+            date: inv.issue_date,
+            description: format!("Faktura br. {}, {}", inv.invoice_number, inv.client_name),
+            invoice_number: inv.invoice_number,
+            products: 0.0,
+            services,
+            total: services,
+            cumulative,
+        });
+    }
+    if !missing_rate.is_empty() {
+        return Err(validation_to_sql_error(format!(
+            "Exchange rate to {} is missing on invoices: {}.",
+            settings.default_currency,
+            missing_rate.join(", ")
+        )));
+    }
+
+    Ok(KpoBook {
+        year,
+        company_address: company_address_one_line(&settings),
+        company_name: settings.company_name,
+        pib: settings.pib,
+        currency: settings.default_currency,
+        entries,
+        total: cumulative,
+    })
+}
+
+pub(crate) fn render_kpo_csv(book: &KpoBook, opts: &CsvOptions) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(csv_header_row(&CSV_HEADER, opts));
+    for e in &book.entries {
+        let row = [
+            e.ordinal.to_string(),
+            format_date_dmy(&e.date),
+            e.description.clone(),
+            format_money_csv(e.products, opts),
+            format_money_csv(e.services, opts),
+            format_money_csv(e.total, opts),
+            format_money_csv(e.cumulative, opts),
+        ];
+        lines.push(csv_join_row(&row, opts));
+    }
+    let products: f64 = book.entries.iter().map(|e| e.products).sum();
+    let total_row = [
+        String::new(),
+        String::new(),
+        "Ukupno".to_string(),
+        format_money_csv(products, opts),
+        format_money_csv(book.total - products, opts),
+        format_money_csv(book.total, opts),
+        format_money_csv(book.total, opts),
+    ];
+    lines.push(csv_join_row(&total_row, opts));
+    csv_document(&lines, opts)
+}
+
+pub(crate) fn render_kpo_pdf(book: &KpoBook) -> Result<Vec<u8>, String> {
+    use printpdf::{Mm, PdfDocument};
+
+    // Landscape A4: seven columns with money amounts don't fit portrait at a readable size.
+    const PAGE_W: f32 = 297.0;
+    const PAGE_H: f32 = 210.0;
+    const MARGIN_X: f32 = 12.0;
+    const MARGIN_TOP: f32 = 14.0;
+    const MARGIN_BOTTOM: f32 = 14.0;
+    const FONT: f32 = 8.0;
+    const ROW_H: f32 = 4.2;
+
+    let title = format!("KPO {}", book.year);
+    let (doc, page1, layer1) = PdfDocument::new(&title, Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+    let (font, face) = embed_font(&doc)?;
+
+    // Column right edges; text columns are left-aligned from the previous edge.
+    let left = MARGIN_X;
+    let right = PAGE_W - MARGIN_X;
+    let col_ordinal = left + 14.0;
+    let col_date = col_ordinal + 24.0;
+    let col_money = 34.0;
+    let col_cumulative = right;
+    let col_total = col_cumulative - col_money;
+    let col_services = col_total - col_money;
+    let col_products = col_services - col_money;
+    let desc_width = col_products - col_date - 4.0;
+
+    let mut layer = doc.get_page(page1).get_layer(layer1);
+    let mut y = PAGE_H - MARGIN_TOP;
+
+    push_line(&layer, &font, "KNJIGA O OSTVARENOM PROMETU PAUŠALNO OPOREZOVANIH OBVEZNIKA", 11.0, left, y);
+    push_line_right_measured(&layer, &font, &face, "Obrazac KPO", 9.0, right, y);
+    y -= 6.0;
+    push_line(&layer, &font, &format!("Obveznik: {}", book.company_name), FONT, left, y);
+    push_line_right_measured(&layer, &font, &face, &format!("Godina: {}", book.year), FONT, right, y);
+    y -= ROW_H;
+    push_line(&layer, &font, &format!("PIB: {}", book.pib), FONT, left, y);
+    push_line_right_measured(&layer, &font, &face, &format!("Iznosi u {}", book.currency), FONT, right, y);
+    y -= ROW_H;
+    if !book.company_address.is_empty() {
+        push_line(&layer, &font, &format!("Sedište: {}", book.company_address), FONT, left, y);
+        y -= ROW_H;
+    }
+    y -= 2.0;
+
+    let draw_header = |layer: &printpdf::PdfLayerReference, y: f32| -> f32 {
+        draw_rule(layer, left, right, y + ROW_H - 1.0);
+        push_line(layer, &font, "Rb.", FONT, left, y);
+        push_line(layer, &font, "Datum", FONT, col_ordinal, y);
+        push_line(layer, &font, "Opis knjiženja", FONT, col_date, y);
+        push_line_right_measured(layer, &font, &face, "Od proizvoda", FONT, col_products, y);
+        push_line_right_measured(layer, &font, &face, "Od usluga", FONT, col_services, y);
+        push_line_right_measured(layer, &font, &face, "Svega (3+4)", FONT, col_total, y);
+        push_line_right_measured(layer, &font, &face, "Kumulativno", FONT, col_cumulative, y);
+        draw_rule(layer, left, right, y - 1.6);
+        y - ROW_H - 0.8
+    };
+    y = draw_header(&layer, y);
+
+    for e in &book.entries {
+        let desc = wrap_text_by_width_mm(&face, &e.description, FONT, desc_width);
+        let needed = ROW_H * desc.len().max(1) as f32;
+        if y - needed < MARGIN_BOTTOM {
+            let (page, l) = doc.add_page(Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+            layer = doc.get_page(page).get_layer(l);
+            y = draw_header(&layer, PAGE_H - MARGIN_TOP);
+        }
+        push_line(&layer, &font, &e.ordinal.to_string(), FONT, left, y);
+        push_line(&layer, &font, &format_date_dmy(&e.date), FONT, col_ordinal, y);
+        push_line_right_measured(&layer, &font, &face, &format_money_sr(e.products), FONT, col_products, y);
+        push_line_right_measured(&layer, &font, &face, &format_money_sr(e.services), FONT, col_services, y);
+        push_line_right_measured(&layer, &font, &face, &format_money_sr(e.total), FONT, col_total, y);
+        push_line_right_measured(&layer, &font, &face, &format_money_sr(e.cumulative), FONT, col_cumulative, y);
+        for line in &desc {
+            push_line(&layer, &font, line, FONT, col_date, y);
+            y -= ROW_H;
+        }
+        if desc.is_empty() {
+            y -= ROW_H;
+        }
+    }
+
+    if y - ROW_H < MARGIN_BOTTOM {
+        let (page, l) = doc.add_page(Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+        layer = doc.get_page(page).get_layer(l);
+        y = PAGE_H - MARGIN_TOP;
+    }
+    draw_rule(&layer, left, right, y + ROW_H - 1.0);
+    let products: f64 = book.entries.iter().map(|e| e.products).sum();
+    push_line(&layer, &font, "Ukupno", FONT, col_date, y);
+    push_line_right_measured(&layer, &font, &face, &format_money_sr(products), FONT, col_products, y);
+    push_line_right_measured(&layer, &font, &face, &format_money_sr(book.total - products), FONT, col_services, y);
+    push_line_right_measured(&layer, &font, &face, &format_money_sr(book.total), FONT, col_total, y);
+    push_line_right_measured(&layer, &font, &face, &format_money_sr(book.total), FONT, col_cumulative, y);
+
+    save_pdf(doc)
+}
+
+/// Writes the KPO book for `year` as CSV with the official column layout.
+#[tauri::command]
+pub(crate) async fn export_kpo_csv(
+    state: tauri::State<'_, DbState>,
+    year: i32,
+    output_path: String,
+    csv_options: Option<CsvOptions>,
+) -> Result<String, AppError> {
+    let (opts, book) = state
+        .with_read("export_kpo_csv", move |conn| {
+            Ok((resolve_csv_options(conn, csv_options)?, kpo_book_in_conn(conn, year)?))
+        })
+        .await?;
+    write_text_file(std::path::Path::new(&output_path), &render_kpo_csv(&book, &opts))?;
+    Ok(output_path)
+}
+
+/// Writes the KPO book for `year` as a printable PDF, opening it when `open_after_export` is set.
+#[tauri::command]
+pub(crate) async fn export_kpo_pdf(
+    state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+    year: i32,
+    output_path: String,
+    open_after_export: Option<bool>,
+) -> Result<ExportedFile, AppError> {
+    let book = state.with_read("export_kpo_pdf", move |conn| kpo_book_in_conn(conn, year)).await?;
+    let bytes = render_kpo_pdf(&book)?;
+    let path = std::path::PathBuf::from(&output_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(ExportedFile::new(&app, output_path, open_after_export.unwrap_or(false)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::invoice;
+
+    #[test]
+    fn kpo_lists_sent_and_paid_invoices_with_converted_running_total() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        invoice("2025-3").issued("2025-03-10").status("PAID").amount("EUR", 100.0).exchange_rate(117.1234).insert(&conn);
+        invoice("2025-1").issued("2025-01-15").amount("RSD", 50000.0).insert(&conn);
+        invoice("2025-2").issued("2025-02-01").status("DRAFT").amount("RSD", 999.0).insert(&conn);
+        invoice("2025-4").issued("2025-04-01").status("CANCELLED").amount("RSD", 999.0).insert(&conn);
+        invoice("2024-9").issued("2024-12-31").status("PAID").amount("RSD", 999.0).insert(&conn);
+
+        let book = kpo_book_in_conn(&conn, 2025).unwrap();
+        let rows: Vec<_> = book.entries.iter().map(|e| (e.ordinal, e.invoice_number.as_str(), e.total, e.cumulative)).collect();
+        assert_eq!(rows, vec![(1, "2025-1", 50000.0, 50000.0), (2, "2025-3", 11712.34, 61712.34)]);
+        assert_eq!(book.total, 61712.34);
+
+        let csv = render_kpo_csv(&book, &CsvOptions::default());
+        let lines: Vec<&str> = csv.trim_end().split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "1,15.01.2025.,\"Faktura br. 2025-1, Klijent\",0.00,50000.00,50000.00,50000.00");
+        assert_eq!(lines[3], ",,Ukupno,0.00,61712.34,61712.34,61712.34");
+        assert!(render_kpo_pdf(&book).unwrap().starts_with(b"%PDF"));
+
+        invoice("2025-5").issued("2025-05-01").amount("USD", 10.0).insert(&conn);
+        let err = kpo_book_in_conn(&conn, 2025).unwrap_err();
+        assert!(err.to_string().contains("2025-5"), "{err}");
+    }
+}
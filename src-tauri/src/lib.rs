@@ -1,32 +1,176 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
 use tauri::Emitter;
 use tauri::path::BaseDirectory;
 use std::{
+    collections::HashSet,
     fs,
     path::PathBuf,
     sync::{Arc, Mutex},
     time::Duration,
 };
-use std::io::{Cursor, Write};
+use std::io::Write;
 use std::sync::OnceLock;
 
-use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use uuid::Uuid;
 
 use lettre::message::{header::ContentType, Attachment, Mailbox, Message, MultiPart, SinglePart};
-use lettre::transport::smtp::client::{Tls, TlsParameters};
-use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Certificate, CertificateStore, Tls, TlsParameters};
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
 use lettre::{SmtpTransport, Transport};
 use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
+mod accountant_report;
+mod app_lock;
+mod app_mode;
+mod audit;
+mod backups;
+mod bank_accounts;
+mod bindings;
+mod catalog;
+mod clients;
+mod company_logo;
+mod consistency;
+mod corrupt_rows;
+mod csv_reader;
+mod custom_fields;
+mod dashboard;
+mod data_archive;
+mod data_events;
+mod db_location;
+mod email_theme;
+mod errors;
+mod expense_import;
+mod expense_summary;
+mod features;
+mod file_opener;
+mod idempotency;
+mod invoice_import;
+mod invoice_share;
+mod invoice_years;
+mod kpo;
 mod license;
+mod license_activation;
+mod license_deactivation;
+mod locales;
+mod logging;
+mod maintenance;
+mod money;
+mod monthly_report;
+mod obligations;
 mod offers;
+mod outbox;
+mod payment_import;
+mod pdf_filename;
+mod pdf_logo;
+mod pdf_util;
+mod pdfa;
+mod period_archive;
+mod quotes;
+mod read_pool;
+mod receivables;
+mod recurring_expenses;
+mod search;
+mod secrets;
+mod settings_transfer;
+mod settings_validation;
+mod setup_wizard;
+mod single_instance;
+mod smtp_oauth;
+mod status_suggestions;
+mod tax_ids;
+mod tax_summary;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+mod time_entries;
+mod trial;
+mod turnover_limits;
+mod ubl;
+mod webhooks;
+mod xlsx_export;
 use offers::{
     create_offer, delete_offer, get_all_offers, get_offer_by_id, send_offer_email,
     update_offer,
 };
+use accountant_report::email_report_to_accountant;
+use app_lock::{clear_app_pin, get_app_lock_status, lock_app, set_app_pin, verify_app_pin};
+use app_mode::{get_app_mode, retry_writable};
+use bank_accounts::BankAccount;
+use custom_fields::CustomField;
+use email_theme::{EmailPalette, EmailTheme};
+use errors::AppError;
+use status_suggestions::suggest_status_updates;
+use backups::{list_backups, restore_backup};
+use time_entries::{
+    bill_time_entries, create_time_entry, delete_time_entry, get_active_timer, list_time_entries, start_timer,
+    stop_timer, unbilled_time_report, update_time_entry,
+};
+use catalog::{
+    catalog_usage_report, create_catalog_item, delete_catalog_item, list_catalog_items, search_catalog,
+    update_catalog_item,
+};
+use quotes::{
+    convert_quote_to_invoice, create_quote, delete_quote, export_quote_pdf_to_downloads, get_quote, list_quotes,
+    send_quote_email, update_quote,
+};
+use data_archive::{export_all_json, import_all_json};
+use clients::{
+    archive_client, find_duplicate_clients, import_clients_csv, merge_clients, unarchive_client,
+    CreateClientResult, DeleteClientResult,
+};
+use company_logo::{get_company_logo, set_company_logo};
+use consistency::{check_data_consistency, repair_data_consistency};
+use corrupt_rows::{list_corrupt_rows, repair_corrupt_row};
+use logging::{get_log_level, get_recent_logs, set_log_level};
+use maintenance::database_maintenance;
+use money::{from_minor, invoice_amounts, InvoiceAmounts, RoundingMode};
+use monthly_report::export_monthly_report_pdf;
+use obligations::{
+    create_obligation, delete_obligation, generate_monthly_obligation_expenses, list_obligations, obligations_status,
+    set_obligation_month_paid, update_obligation,
+};
+use csv_reader::read_csv_headers;
+use dashboard::{dashboard_summary, top_clients, top_items};
+use data_events::{DataChanges, DataEntity, DataOp};
+use db_location::{get_database_info, move_database_to};
+use expense_import::import_expenses_csv;
+use expense_summary::expense_summary;
+use features::{is_feature_enabled, Feature};
+use file_opener::{open_path, reveal_in_folder, ExportedFile};
+use idempotency::IdempotencyScope;
+use invoice_import::import_invoices_csv;
+use invoice_share::{export_invoice_json, import_invoice_json};
+use invoice_years::invoices_by_year;
+use kpo::{export_kpo_csv, export_kpo_pdf};
+use license_activation::{activate_license, get_activation_code, get_license_status, license_expiry_info};
+use license_deactivation::deactivate_license;
+use locales::{list_available_languages, LocaleTable, NumberStyle};
+use payment_import::{import_payments_csv, resolve_payment_match};
+use receivables::{accounts_receivable_report, export_accounts_receivable_csv};
+use recurring_expenses::{
+    create_recurring_expense, delete_recurring_expense, list_recurring_expenses, run_due_recurring_expenses,
+    update_recurring_expense,
+};
+use search::global_search;
+use settings_transfer::{export_settings, import_settings};
+use settings_validation::validate_settings;
+use setup_wizard::{complete_setup_step, finish_setup, get_setup_state};
+use tax_ids::validate_tax_ids;
+use tax_summary::{export_tax_summary_csv, tax_summary};
+use trial::get_trial_status;
+use turnover_limits::{limit_status, limit_warnings_for_invoice, LimitWarning};
+use webhooks::{list_webhook_deliveries, test_webhook};
+use xlsx_export::{export_expenses_xlsx, export_invoices_xlsx};
+use pdf_filename::{attachment_filename, ExistingFile, PdfFilenameFields};
+use pdf_util::{
+    draw_inline_labeled_row, draw_rule_with_thickness, draw_value_only_wrapped, fill_rect_gray, font_ascent_mm,
+    font_descent_mm, push_line, push_line_right_measured, split_and_wrap_lines_by_width_mm, text_width_mm_ttf,
+};
+use period_archive::export_period_archive;
+use outbox::{cancel_outbox_item, list_outbox, retry_outbox_item};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct BackupMetadataJson {
@@ -111,42 +255,35 @@ struct InvoiceEmailLabelsLocale {
     personal_note: String,
     personal_note_with_colon: String,
     bank_account: String,
+    iban: String,
+    swift: String,
+    issued_by: String,
+    reference_number: String,
+    payment_method: String,
+    payment_method_transfer: String,
+    payment_method_cash: String,
+    payment_method_card: String,
     generated_from_app: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct InvoiceEmailLabelsFile {
-    sr: InvoiceEmailLabelsLocale,
-    en: InvoiceEmailLabelsLocale,
-}
-
-static INVOICE_EMAIL_LABELS: OnceLock<Result<InvoiceEmailLabelsFile, String>> = OnceLock::new();
+static INVOICE_EMAIL_LABELS: OnceLock<LocaleTable> = OnceLock::new();
 
+/// Email labels of `lang`; "bilingual" invoices are emailed in Serbian.
 fn invoice_email_labels(lang: &str) -> Result<InvoiceEmailLabelsLocale, String> {
-    let file = INVOICE_EMAIL_LABELS.get_or_init(|| {
-        let json = include_str!("../../src/shared/invoiceEmailLabels.json");
-        serde_json::from_str::<InvoiceEmailLabelsFile>(json)
-            .map_err(|e| format!("Failed to parse embedded src/shared/invoiceEmailLabels.json: {e}"))
-    });
-
-    let file = file.as_ref().map_err(|e| e.clone())?;
-
-    let l = lang.to_ascii_lowercase();
-    if l.starts_with("en") {
-        Ok(file.en.clone())
-    } else {
-        Ok(file.sr.clone())
-    }
+    let table = INVOICE_EMAIL_LABELS
+        .get_or_init(|| locales::parse_table(include_str!("../../src/shared/invoiceEmailLabels.json")));
+    let lang = if is_bilingual_pdf_language(lang) { "sr" } else { lang };
+    locales::resolve(table, lang).map_err(|e| format!("Invalid embedded src/shared/invoiceEmailLabels.json: {e}"))
 }
 
 fn sanity_check_embedded_invoice_email_labels() {
-    for lang in ["sr", "en"] {
-        if let Err(e) = invoice_email_labels(lang) {
+    for lang in locales::available_languages() {
+        if let Err(e) = invoice_email_labels(&lang) {
             eprintln!("[labels] invoiceEmailLabels.json unavailable ({lang}): {e}");
         }
     }
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InvoicePdfCompany {
     pub company_name: String,
     #[serde(alias = "maticni_broj")]
@@ -160,13 +297,18 @@ pub struct InvoicePdfCompany {
     #[serde(default)]
     pub city: Option<String>,
     pub bank_account: String,
+    /// Set for foreign-currency invoices only, printed under the account number.
+    #[serde(default)]
+    pub iban: Option<String>,
+    #[serde(default)]
+    pub swift: Option<String>,
     #[serde(default)]
     pub email: Option<String>,
     #[serde(default)]
     pub phone: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InvoicePdfClient {
     pub name: String,
     #[serde(alias = "maticni_broj")]
@@ -182,9 +324,20 @@ pub struct InvoicePdfClient {
     pub email: Option<String>,
     #[serde(default)]
     pub phone: Option<String>,
+    /// Foreign buyer: no matični broj required, `pib` is printed as the VAT ID.
+    #[serde(default, alias = "isForeign")]
+    pub is_foreign: bool,
+    #[serde(default)]
+    pub country: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl InvoicePdfClient {
+    fn reverse_charge(&self) -> bool {
+        self.is_foreign && !self.pib.as_deref().unwrap_or("").trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InvoicePdfItem {
     pub description: String,
     #[serde(default)]
@@ -196,7 +349,7 @@ pub struct InvoicePdfItem {
     pub total: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InvoicePdfPayload {
     #[serde(default)]
     pub language: Option<String>,
@@ -207,42 +360,60 @@ pub struct InvoicePdfPayload {
     pub subtotal: f64,
     #[serde(default)]
     pub discount_total: f64,
+    /// Invoice-level discount amount (already resolved from flat/percent), applied after line discounts.
+    #[serde(default, alias = "invoiceDiscount")]
+    pub invoice_discount: Option<f64>,
+    #[serde(default, alias = "invoiceDiscountPercent")]
+    pub invoice_discount_percent: Option<f64>,
     pub total: f64,
     pub notes: Option<String>,
+    /// `TRANSFER` | `CASH` | `CARD`
+    #[serde(default, alias = "paymentMethod")]
+    pub payment_method: Option<PaymentMethod>,
+    /// Payment reference with model prefix; falls back to the invoice number when absent.
+    #[serde(default, alias = "paymentReference")]
+    pub payment_reference: Option<String>,
     pub company: InvoicePdfCompany,
     pub client: InvoicePdfClient,
     pub items: Vec<InvoicePdfItem>,
-}
-
-fn sanitize_filename(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    for ch in input.chars() {
-        let ok = ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.' || ch == ' ';
-        out.push(if ok { ch } else { '_' });
+    /// Taken from the settings when the PDF is generated, not from the frontend.
+    #[serde(default)]
+    pub legal_note_override: LegalNoteOverride,
+    /// Set for quotes: the PDF gets the quote title and a validity line instead of the service
+    /// date and payment details.
+    #[serde(default)]
+    pub valid_until: Option<String>,
+    /// Person who issued the invoice, printed in the signature area.
+    #[serde(default, alias = "issuedBy")]
+    pub issued_by: Option<String>,
+    /// Printed as "label: value" rows in the details block.
+    #[serde(default, alias = "customFields")]
+    pub custom_fields: Vec<CustomField>,
+}
+
+impl InvoicePdfPayload {
+    /// Recomputes line and invoice totals from the quantities, prices and discounts, rounded with
+    /// `mode`; `invoice_discount` is taken as the already-resolved amount.
+    fn round_amounts(&mut self, mode: RoundingMode) {
+        let lines = self.items.iter().map(|it| money::Line {
+            quantity: it.quantity,
+            unit_price: it.unit_price,
+            discount: it.discount_amount,
+        });
+        let amounts = invoice_amounts(lines, self.invoice_discount, None, mode);
+        for (item, line) in self.items.iter_mut().zip(&amounts.lines) {
+            item.discount_amount = (line.discount > 0).then(|| from_minor(line.discount));
+            item.total = from_minor(line.total);
+        }
+        self.subtotal = from_minor(amounts.subtotal);
+        self.discount_total = from_minor(amounts.line_discounts);
+        self.invoice_discount = (amounts.invoice_discount > 0).then(|| from_minor(amounts.invoice_discount));
+        self.total = from_minor(amounts.total);
     }
-    let trimmed = out.trim().to_string();
-    if trimmed.is_empty() { "invoice".to_string() } else { trimmed }
 }
 
 fn format_money(v: f64) -> String {
-    let s = format!("{:.2}", v);
-    let parts = s.split('.').collect::<Vec<_>>();
-    let int_part = parts[0];
-    let dec_part = parts.get(1).copied().unwrap_or("00");
-
-    let mut out = String::new();
-    let chars: Vec<char> = int_part.chars().collect();
-    let mut cnt = 0;
-    for i in (0..chars.len()).rev() {
-        if cnt == 3 {
-            out.push(',');
-            cnt = 0;
-        }
-        out.push(chars[i]);
-        cnt += 1;
-    }
-    let int_with_sep: String = out.chars().rev().collect();
-    format!("{}.{}", int_with_sep, dec_part)
+    NumberStyle::EN.money(v)
 }
 
 fn escape_html(input: &str) -> String {
@@ -368,7 +539,7 @@ fn serbia_zip_codes(app: &tauri::AppHandle) -> Result<&'static Vec<SerbiaCityDto
 }
 
 #[tauri::command]
-fn list_serbia_cities(app: tauri::AppHandle, search: Option<String>) -> Result<Vec<SerbiaCityDto>, String> {
+fn list_serbia_cities(app: tauri::AppHandle, search: Option<String>) -> Result<Vec<SerbiaCityDto>, AppError> {
     let rows = serbia_zip_codes(&app)?;
     let q = search
         .as_deref()
@@ -390,16 +561,16 @@ fn list_serbia_cities(app: tauri::AppHandle, search: Option<String>) -> Result<V
 /// Renders the invoice email body as (html, text).
 ///
 /// - Clean business-style layout, email-client-safe (tables + inline CSS).
-/// - Localized (sr/en) based on Settings.language.
+/// - Localized (see `locales`) based on the client's preferred language, else Settings.language.
 /// - User-provided message is rendered as an optional "personal note" section.
 fn render_invoice_email(
     settings: &Settings,
     invoice: &Invoice,
-    _client: Option<&Client>,
+    client: Option<&Client>,
     include_pdf: bool,
     personal_note: Option<&str>,
 ) -> Result<(String, String), String> {
-    let lang = settings.language.to_ascii_lowercase();
+    let lang = invoice_language(settings, client).to_ascii_lowercase();
     let labels = invoice_email_labels(&lang)?;
 
     // Fail fast if required labels are missing/empty (no silent fallbacks).
@@ -421,7 +592,7 @@ fn render_invoice_email(
     let invoice_number = invoice.invoice_number.trim();
     let issue_date = invoice.issue_date.trim();
     let due_date = invoice.due_date.as_deref().map(str::trim).filter(|s| !s.is_empty());
-    let total = format_money(invoice.total);
+    let total = NumberStyle::for_language(&lang).money(invoice.total);
     let currency = invoice.currency.trim();
 
     let company_name = settings.company_name.trim();
@@ -457,16 +628,28 @@ fn render_invoice_email(
         labels.intro_without_pdf.as_str()
     };
 
-    let bank_account = settings.bank_account.trim();
-    let bank_account = if bank_account.is_empty() {
-        None
-    } else {
-        Some(bank_account)
-    };
+    let payment_account = bank_accounts::payment_account(settings, currency);
+    let issued_by = invoice.issued_by.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let bank_account = Some(payment_account.account_number.as_str()).filter(|s| !s.is_empty());
+    let iban = payment_account.iban.as_deref();
+    let swift = payment_account.swift.as_deref();
+
+    let payment_reference = invoice
+        .payment_reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let payment_method = invoice.payment_method.map(|m| match m {
+        PaymentMethod::Transfer => labels.payment_method_transfer.as_str(),
+        PaymentMethod::Cash => labels.payment_method_cash.as_str(),
+        PaymentMethod::Card => labels.payment_method_card.as_str(),
+    });
 
     // Mandatory global invoice note (always)
-    let mandatory_note_text = mandatory_invoice_note_text(&lang, invoice_number);
-    let mandatory_note_html = mandatory_invoice_note_html(&lang, invoice_number);
+    let reverse_charge = client.is_some_and(Client::reverse_charge);
+    let overrides = &settings.legal_note_override;
+    let mandatory_note_text = mandatory_invoice_note_text(&lang, invoice_number, reverse_charge, overrides);
+    let mandatory_note_html = mandatory_invoice_note_html(&lang, invoice_number, reverse_charge, overrides);
 
     // ---- Plain-text fallback ----
     let mut text = String::new();
@@ -495,6 +678,9 @@ fn render_invoice_email(
         require_label("dueDate", &labels.due_date)?;
         push_kv_text(&mut text, &labels.due_date, d);
     }
+    for f in &invoice.custom_fields {
+        push_kv_text(&mut text, &f.label, &f.value);
+    }
 
     text.push('\n');
     text.push_str("--------------------------------\n");
@@ -513,6 +699,18 @@ fn render_invoice_email(
     if let Some(b) = bank_account {
         push_kv_text(&mut text, &labels.bank_account, b);
     }
+    if let Some(v) = iban {
+        push_kv_text(&mut text, &labels.iban, v);
+    }
+    if let Some(v) = swift {
+        push_kv_text(&mut text, &labels.swift, v);
+    }
+    if let Some(r) = payment_reference {
+        push_kv_text(&mut text, &labels.reference_number, r);
+    }
+    if let Some(m) = payment_method {
+        push_kv_text(&mut text, &labels.payment_method, m);
+    }
 
     text.push('\n');
     // Keep the intro line short and below the summary blocks.
@@ -524,6 +722,11 @@ fn render_invoice_email(
         text.push('\n');
     }
 
+    if let Some(p) = issued_by {
+        text.push('\n');
+        push_kv_text(&mut text, &labels.issued_by, p);
+    }
+
     text.push_str("\n--------------------------------\n");
     text.push_str(&mandatory_note_text);
     text.push('\n');
@@ -538,30 +741,44 @@ fn render_invoice_email(
     let html_company_name = escape_html(company_name);
     let html_company_address = company_address.as_deref().map(escape_html);
 
-    fn push_detail_row(html: &mut String, label: &str, value: &str) {
+    use email_theme::{ACCENT, BG, BOX, CARD, FAINT, MUTED, RULE, TEXT};
+    let c = &settings.email_theme.light;
+
+    fn push_detail_row(html: &mut String, c: &EmailPalette, label: &str, value: &str) {
         let v = value.trim();
         if v.is_empty() {
             return;
         }
         html.push_str(&format!(
-            "<tr><td style=\"padding:6px 0;font-size:13px;color:#4b5563;\">{}</td><td align=\"right\" style=\"padding:6px 0;font-size:13px;color:#111827;font-weight:600;\">{}</td></tr>",
+            "<tr><td class=\"{MUTED}\" style=\"padding:6px 0;font-size:13px;color:{};\">{}</td><td align=\"right\" class=\"{TEXT}\" style=\"padding:6px 0;font-size:13px;color:{};font-weight:600;\">{}</td></tr>",
+            c.muted,
             escape_html(label),
+            c.text,
             escape_html(v)
         ));
     }
 
     let mut html = String::new();
-    html.push_str("<!doctype html><html><head><meta charset=\"utf-8\"></head>");
-    html.push_str("<body style=\"margin:0;padding:0;background-color:#f6f7f9;font-family:Arial,Helvetica,sans-serif;\">");
-    html.push_str("<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\" style=\"background-color:#f6f7f9;padding:24px 0;\">\
+    html.push_str("<!doctype html><html><head><meta charset=\"utf-8\">");
+    html.push_str(&email_theme::head_html(&settings.email_theme));
+    html.push_str("</head>");
+    html.push_str(&format!(
+        "<body class=\"{BG}\" style=\"margin:0;padding:0;background-color:{};font-family:Arial,Helvetica,sans-serif;\">",
+        c.background
+    ));
+    html.push_str(&format!(
+        "<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\" class=\"{BG}\" style=\"background-color:{};padding:24px 0;\">\
 <tr><td align=\"center\">\
-<table role=\"presentation\" width=\"600\" cellspacing=\"0\" cellpadding=\"0\" style=\"width:600px;max-width:600px;background-color:#ffffff;border:1px solid #e6e8ec;border-radius:10px;overflow:hidden;\">\
-");
+<table role=\"presentation\" width=\"600\" cellspacing=\"0\" cellpadding=\"0\" class=\"{CARD}\" style=\"width:600px;max-width:600px;background-color:{};border:1px solid {};border-radius:10px;overflow:hidden;\">\
+",
+        c.background, c.card, c.border
+    ));
 
     // Header
     html.push_str("<tr><td style=\"padding:20px 24px;\">");
     html.push_str(&format!(
-        "<div style=\"font-size:18px;font-weight:700;color:#111827;\">{}</div>",
+        "<div class=\"{ACCENT}\" style=\"font-size:18px;font-weight:700;color:{};\">{}</div>",
+        c.accent,
         escape_html(labels.invoice.as_str())
     ));
     html.push_str("</td></tr>");
@@ -569,69 +786,91 @@ fn render_invoice_email(
     // Body
     html.push_str("<tr><td style=\"padding:0 24px 20px 24px;\">");
 
-    // A) INVOICE / ISSUER DETAILS (TOP BLOCK) — exact order
-    html.push_str("<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\" style=\"margin-top:16px;border:1px solid #e6e8ec;border-radius:10px;\">\
+    let open_box = |margin: &str| {
+        format!(
+            "<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\" class=\"{BOX}\" style=\"{margin}border:1px solid {};border-radius:10px;\">\
 <tr><td style=\"padding:14px;\">\
 <table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\">\
-");
+",
+            c.border
+        )
+    };
+
+    // A) INVOICE / ISSUER DETAILS (TOP BLOCK) — exact order
+    html.push_str(&open_box("margin-top:16px;"));
 
     html.push_str(&format!(
-        "<tr><td style=\"padding:6px 0;font-size:13px;color:#4b5563;\">{}</td><td align=\"right\" style=\"padding:6px 0;font-size:13px;color:#111827;font-weight:600;\"><div>{}</div>{}</td></tr>",
+        "<tr><td class=\"{MUTED}\" style=\"padding:6px 0;font-size:13px;color:{};\">{}</td><td align=\"right\" class=\"{TEXT}\" style=\"padding:6px 0;font-size:13px;color:{};font-weight:600;\"><div>{}</div>{}</td></tr>",
+        c.muted,
         escape_html(labels.company.as_str()),
+        c.text,
         html_company_name,
         html_company_address
             .as_deref()
-            .map(|a| format!("<div style=\\\"margin-top:2px;font-size:12px;color:#6b7280;font-weight:500;\\\">{}</div>", a))
-            .unwrap_or_else(|| "".to_string())
+            .map(|a| format!(
+                "<div class=\"{FAINT}\" style=\"margin-top:2px;font-size:12px;color:{};font-weight:500;\">{}</div>",
+                c.faint, a
+            ))
+            .unwrap_or_default()
     ));
 
-    push_detail_row(&mut html, labels.vat_id.as_str(), &html_vat_id);
-    push_detail_row(&mut html, labels.invoice_number.as_str(), invoice_number);
-    push_detail_row(&mut html, labels.issue_date.as_str(), issue_date);
+    push_detail_row(&mut html, c, labels.vat_id.as_str(), &html_vat_id);
+    push_detail_row(&mut html, c, labels.invoice_number.as_str(), invoice_number);
+    push_detail_row(&mut html, c, labels.issue_date.as_str(), issue_date);
     if let Some(d) = html_due_date.as_deref() {
-        push_detail_row(&mut html, labels.due_date.as_str(), d);
+        push_detail_row(&mut html, c, labels.due_date.as_str(), d);
+    }
+    for f in &invoice.custom_fields {
+        push_detail_row(&mut html, c, &f.label, &f.value);
     }
 
     html.push_str("</table></td></tr></table>");
 
     // Visual divider after top block
-    html.push_str("<div style=\"height:1px;background-color:#e6e8ec;margin:16px 0;\"></div>");
+    html.push_str(&format!(
+        "<div class=\"{RULE}\" style=\"height:1px;background-color:{};margin:16px 0;\"></div>",
+        c.border
+    ));
 
     // B) PAYMENT DETAILS (SECOND BLOCK) — exact order
-    html.push_str("<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\" style=\"border:1px solid #e6e8ec;border-radius:10px;\">\
-<tr><td style=\"padding:14px;\">\
-<table role=\"presentation\" width=\"100%\" cellspacing=\"0\" cellpadding=\"0\">\
-");
+    html.push_str(&open_box(""));
 
-    // Total (bold / strong) — first row in payment block
+    // Total (bold / strong) — first row in payment block; currency is appended only if present
     if !total.trim().is_empty() {
         let cur = currency.trim();
-        if cur.is_empty() {
-            html.push_str(&format!(
-                "<tr><td style=\"padding:6px 0;font-size:13px;color:#4b5563;\">{}</td><td align=\"right\" style=\"padding:6px 0;font-size:16px;color:#111827;font-weight:800;\">{}</td></tr>",
-                escape_html(labels.total.as_str()),
-                html_total
-            ));
-        } else {
-            html.push_str(&format!(
-                "<tr><td style=\"padding:6px 0;font-size:13px;color:#4b5563;\">{}</td><td align=\"right\" style=\"padding:6px 0;font-size:16px;color:#111827;font-weight:800;\">{} {}</td></tr>",
-                escape_html(labels.total.as_str()),
-                html_total,
-                html_currency
-            ));
-        }
+        let amount = if cur.is_empty() { html_total } else { format!("{html_total} {html_currency}") };
+        html.push_str(&format!(
+            "<tr><td class=\"{MUTED}\" style=\"padding:6px 0;font-size:13px;color:{};\">{}</td><td align=\"right\" class=\"{ACCENT}\" style=\"padding:6px 0;font-size:16px;color:{};font-weight:800;\">{}</td></tr>",
+            c.muted,
+            escape_html(labels.total.as_str()),
+            c.accent,
+            amount
+        ));
     }
 
     // Bank account — second row in payment block (only if present)
     if let Some(b) = html_bank_account.as_deref() {
-        push_detail_row(&mut html, labels.bank_account.as_str(), b);
+        push_detail_row(&mut html, c, labels.bank_account.as_str(), b);
+    }
+    if let Some(v) = iban {
+        push_detail_row(&mut html, c, labels.iban.as_str(), v);
+    }
+    if let Some(v) = swift {
+        push_detail_row(&mut html, c, labels.swift.as_str(), v);
+    }
+    if let Some(r) = payment_reference {
+        push_detail_row(&mut html, c, labels.reference_number.as_str(), r);
+    }
+    if let Some(m) = payment_method {
+        push_detail_row(&mut html, c, labels.payment_method.as_str(), m);
     }
 
     html.push_str("</table></td></tr></table>");
 
     // Keep the intro line short and below the summary blocks.
     html.push_str(&format!(
-        "<p style=\"margin:16px 0 0 0;font-size:14px;line-height:20px;color:#111827;\">{}</p>",
+        "<p class=\"{TEXT}\" style=\"margin:16px 0 0 0;font-size:14px;line-height:20px;color:{};\">{}</p>",
+        c.text,
         escape_html(intro_line)
     ));
 
@@ -639,12 +878,13 @@ fn render_invoice_email(
     if let Some(n) = html_note {
         html.push_str("<div style=\"margin-top:16px;\">");
         html.push_str(&format!(
-            "<div style=\"font-size:12px;color:#4b5563;font-weight:700;letter-spacing:0.02em;text-transform:uppercase;\">{}</div>",
+            "<div class=\"{MUTED}\" style=\"font-size:12px;color:{};font-weight:700;letter-spacing:0.02em;text-transform:uppercase;\">{}</div>",
+            c.muted,
             escape_html(labels.personal_note.as_str())
         ));
         html.push_str(&format!(
-            "<div style=\"margin-top:8px;padding:12px 14px;border:1px solid #e6e8ec;border-radius:10px;background-color:#ffffff;font-size:14px;line-height:20px;color:#111827;white-space:pre-wrap;\">{}</div>",
-            n
+            "<div class=\"{CARD} {TEXT}\" style=\"margin-top:8px;padding:12px 14px;border:1px solid {};border-radius:10px;background-color:{};font-size:14px;line-height:20px;color:{};white-space:pre-wrap;\">{}</div>",
+            c.border, c.card, c.text, n
         ));
         html.push_str("</div>");
     }
@@ -653,12 +893,24 @@ fn render_invoice_email(
 
     // Footer
     html.push_str("<tr><td style=\"padding:16px 24px 22px 24px;\">");
+    if let Some(p) = issued_by {
+        html.push_str(&format!(
+            "<div class=\"{TEXT}\" style=\"font-size:13px;color:{};\">{}: {}</div>",
+            c.text,
+            escape_html(labels.issued_by.as_str()),
+            escape_html(p)
+        ));
+    }
 
-    html.push_str("<div style=\"margin-top:12px;padding-top:12px;border-top:1px solid #e6e8ec;font-size:12px;line-height:18px;color:#6b7280;\">");
+    html.push_str(&format!(
+        "<div class=\"{BOX} {FAINT}\" style=\"margin-top:12px;padding-top:12px;border-top:1px solid {};font-size:12px;line-height:18px;color:{};\">",
+        c.border, c.faint
+    ));
     html.push_str(&mandatory_note_html);
     html.push_str("</div>");
     html.push_str(&format!(
-        "<div style=\"margin-top:8px;font-size:12px;color:#6b7280;\">{}</div>",
+        "<div class=\"{FAINT}\" style=\"margin-top:8px;font-size:12px;color:{};\">{}</div>",
+        c.faint,
         escape_html(labels.generated_from_app.as_str())
     ));
     html.push_str("</td></tr>");
@@ -668,59 +920,29 @@ fn render_invoice_email(
     Ok((html, text))
 }
 
-fn push_line(
-    layer: &printpdf::PdfLayerReference,
-    font: &printpdf::IndirectFontRef,
-    text: &str,
-    font_size: f32,
-    x: f32,
-    y: f32,
-) {
-    use printpdf::Mm;
-    layer.use_text(text, font_size, Mm(x), Mm(y), font);
-}
-
-fn wrap_text_lines(input: &str, max_chars: usize) -> Vec<String> {
-    let mut out: Vec<String> = Vec::new();
-    let mut current = String::new();
-
-    for word in input.split_whitespace() {
-        if current.is_empty() {
-            current.push_str(word);
-            continue;
-        }
-
-        if current.len() + 1 + word.len() <= max_chars {
-            current.push(' ');
-            current.push_str(word);
-        } else {
-            out.push(current);
-            current = word.to_string();
-        }
-    }
-
-    if !current.is_empty() {
-        out.push(current);
-    }
-
-    out
-}
-
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct PdfLabels {
     doc_title: String,
     invoice_title: String,
     invoice_title_service_invoice_no: String,
+    quote_title: String,
+    quote_title_no: String,
+    quote_valid_until: String,
 
     issuer_title: String,
     buyer_title: String,
     details_title: String,
 
     vat_id: String,
+    foreign_vat_id: String,
+    country: String,
     registration_number: String,
     address: String,
     bank_account: String,
+    iban: String,
+    swift: String,
+    issued_by: String,
     email: String,
     phone: String,
 
@@ -742,6 +964,7 @@ struct PdfLabels {
     totals_title: String,
     subtotal: String,
     discount: String,
+    invoice_discount: String,
     vat: String,
     total_for_payment: String,
 
@@ -749,6 +972,9 @@ struct PdfLabels {
     payment_deadline: String,
     reference_number: String,
     payment_method: String,
+    payment_method_transfer: String,
+    payment_method_cash: String,
+    payment_method_card: String,
 
     notes: String,
     legal_notes_title: String,
@@ -764,21 +990,29 @@ struct PdfLabels {
     footer_generated: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PdfLabelsLocale {
     doc_title: String,
     invoice_title: String,
     invoice_title_service_invoice_no: String,
+    quote_title: String,
+    quote_title_no: String,
+    quote_valid_until: String,
 
     issuer_title: String,
     buyer_title: String,
     details_title: String,
 
     vat_id: String,
+    foreign_vat_id: String,
+    country: String,
     registration_number: String,
     address: String,
     bank_account: String,
+    iban: String,
+    swift: String,
+    issued_by: String,
     email: String,
     phone: String,
 
@@ -800,6 +1034,7 @@ struct PdfLabelsLocale {
     totals_title: String,
     subtotal: String,
     discount: String,
+    invoice_discount: String,
     vat: String,
     total_for_payment: String,
 
@@ -807,6 +1042,9 @@ struct PdfLabelsLocale {
     payment_deadline: String,
     reference_number: String,
     payment_method: String,
+    payment_method_transfer: String,
+    payment_method_cash: String,
+    payment_method_card: String,
 
     notes: String,
     legal_notes_title: String,
@@ -822,127 +1060,80 @@ struct PdfLabelsLocale {
     footer_generated: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct PdfLabelsFile {
-    sr: PdfLabelsLocale,
-    en: PdfLabelsLocale,
+static PDF_LABELS: OnceLock<LocaleTable> = OnceLock::new();
+
+/// `src/shared/pdfLabels.json`; its languages are the ones invoices can be printed in.
+pub(crate) fn pdf_label_table() -> &'static LocaleTable {
+    PDF_LABELS.get_or_init(|| locales::parse_table(include_str!("../../src/shared/pdfLabels.json")))
 }
 
-static PDF_LABELS: OnceLock<PdfLabelsFile> = OnceLock::new();
+/// Joins the Serbian and English label in bilingual PDFs ("Faktura / Invoice").
+const PDF_BILINGUAL_SEPARATOR: &str = " / ";
 
-fn pdf_labels(lang: &str) -> PdfLabels {
-    let file = PDF_LABELS.get_or_init(|| {
-        let json = include_str!("../../src/shared/pdfLabels.json");
-        serde_json::from_str::<PdfLabelsFile>(json).unwrap_or_else(|_| PdfLabelsFile {
-            sr: PdfLabelsLocale {
-                doc_title: String::new(),
-                invoice_title: String::new(),
-                invoice_title_service_invoice_no: String::new(),
-                issuer_title: String::new(),
-                buyer_title: String::new(),
-                details_title: String::new(),
-                vat_id: String::new(),
-                registration_number: String::new(),
-                address: String::new(),
-                bank_account: String::new(),
-                email: String::new(),
-                phone: String::new(),
-                invoice_number: String::new(),
-                issue_date: String::new(),
-                service_date: String::new(),
-                place_of_service: String::new(),
-                place_of_issue: String::new(),
-                currency: String::new(),
-                items_title: String::new(),
-                col_description: String::new(),
-                col_unit: String::new(),
-                col_qty: String::new(),
-                col_unit_price: String::new(),
-                col_discount: String::new(),
-                col_amount: String::new(),
-                totals_title: String::new(),
-                subtotal: String::new(),
-                discount: String::new(),
-                vat: String::new(),
-                total_for_payment: String::new(),
-                payment_terms_title: String::new(),
-                payment_deadline: String::new(),
-                reference_number: String::new(),
-                payment_method: String::new(),
-                notes: String::new(),
-                legal_notes_title: String::new(),
-                err_company_registration_number_missing: String::new(),
-                err_client_registration_number_missing: String::new(),
-                err_not_enough_space_header_and_footer: String::new(),
-                err_not_enough_space_content_and_footer: String::new(),
-                err_too_many_items: String::new(),
-                err_missing_language: String::new(),
-                err_invalid_language: String::new(),
-                footer_generated: String::new(),
-            },
-            en: PdfLabelsLocale {
-                doc_title: String::new(),
-                invoice_title: String::new(),
-                invoice_title_service_invoice_no: String::new(),
-                issuer_title: String::new(),
-                buyer_title: String::new(),
-                details_title: String::new(),
-                vat_id: String::new(),
-                registration_number: String::new(),
-                address: String::new(),
-                bank_account: String::new(),
-                email: String::new(),
-                phone: String::new(),
-                invoice_number: String::new(),
-                issue_date: String::new(),
-                service_date: String::new(),
-                place_of_service: String::new(),
-                place_of_issue: String::new(),
-                currency: String::new(),
-                items_title: String::new(),
-                col_description: String::new(),
-                col_unit: String::new(),
-                col_qty: String::new(),
-                col_unit_price: String::new(),
-                col_discount: String::new(),
-                col_amount: String::new(),
-                totals_title: String::new(),
-                subtotal: String::new(),
-                discount: String::new(),
-                vat: String::new(),
-                total_for_payment: String::new(),
-                payment_terms_title: String::new(),
-                payment_deadline: String::new(),
-                reference_number: String::new(),
-                payment_method: String::new(),
-                notes: String::new(),
-                legal_notes_title: String::new(),
-                err_company_registration_number_missing: String::new(),
-                err_client_registration_number_missing: String::new(),
-                err_not_enough_space_header_and_footer: String::new(),
-                err_not_enough_space_content_and_footer: String::new(),
-                err_too_many_items: String::new(),
-                err_missing_language: String::new(),
-                err_invalid_language: String::new(),
-                footer_generated: String::new(),
-            },
-        })
-    });
+/// `InvoicePdfPayload.language` value for side-by-side sr + en labels.
+fn is_bilingual_pdf_language(lang: &str) -> bool {
+    let l = lang.trim().to_ascii_lowercase();
+    l == "bilingual" || l == "sr+en"
+}
 
-    let l = lang.to_ascii_lowercase();
-    let loc = if l.starts_with("en") { &file.en } else { &file.sr };
+/// Builds "sr / en" labels from both locales. Identical labels are kept once;
+/// error messages stay Serbian only.
+fn merge_bilingual_pdf_labels(sr: &PdfLabelsLocale, en: &PdfLabelsLocale) -> PdfLabelsLocale {
+    let (Ok(serde_json::Value::Object(sr_map)), Ok(serde_json::Value::Object(en_map))) =
+        (serde_json::to_value(sr), serde_json::to_value(en))
+    else {
+        return sr.clone();
+    };
+
+    let mut merged = serde_json::Map::new();
+    for (key, sr_value) in sr_map {
+        let sr_text = sr_value.as_str().unwrap_or("");
+        let en_text = en_map.get(&key).and_then(|v| v.as_str()).unwrap_or("").trim();
+        let value = if key.starts_with("err") || en_text.is_empty() || sr_text.trim() == en_text {
+            sr_text.to_string()
+        } else {
+            // Keep trailing whitespace (e.g. "Račun usluga broj: ") after the combined label.
+            let head = sr_text.trim_end();
+            let trailing = &sr_text[head.len()..];
+            format!("{head}{PDF_BILINGUAL_SEPARATOR}{en_text}{trailing}")
+        };
+        merged.insert(key, serde_json::Value::String(value));
+    }
+
+    serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_else(|_| sr.clone())
+}
+
+/// Labels of `lang`, with missing ones taken from English, then Serbian. An unreadable table
+/// gives empty labels.
+fn pdf_labels(lang: &str) -> PdfLabels {
+    let table = pdf_label_table();
+    let locale = |l: &str| locales::resolve::<PdfLabelsLocale>(table, l).unwrap_or_default();
+    if is_bilingual_pdf_language(lang) {
+        return pdf_labels_from_locale(&merge_bilingual_pdf_labels(&locale("sr"), &locale("en")));
+    }
+    pdf_labels_from_locale(&locale(lang))
+}
 
+fn pdf_labels_from_locale(loc: &PdfLabelsLocale) -> PdfLabels {
     PdfLabels {
         doc_title: loc.doc_title.clone(),
         invoice_title: loc.invoice_title.clone(),
         invoice_title_service_invoice_no: loc.invoice_title_service_invoice_no.clone(),
+        quote_title: loc.quote_title.clone(),
+        quote_title_no: loc.quote_title_no.clone(),
+        quote_valid_until: loc.quote_valid_until.clone(),
         issuer_title: loc.issuer_title.clone(),
         buyer_title: loc.buyer_title.clone(),
         details_title: loc.details_title.clone(),
         vat_id: loc.vat_id.clone(),
+        foreign_vat_id: loc.foreign_vat_id.clone(),
+        country: loc.country.clone(),
         registration_number: loc.registration_number.clone(),
         address: loc.address.clone(),
         bank_account: loc.bank_account.clone(),
+        iban: loc.iban.clone(),
+        swift: loc.swift.clone(),
+        issued_by: loc.issued_by.clone(),
         email: loc.email.clone(),
         phone: loc.phone.clone(),
         invoice_number: loc.invoice_number.clone(),
@@ -961,12 +1152,16 @@ fn pdf_labels(lang: &str) -> PdfLabels {
         totals_title: loc.totals_title.clone(),
         subtotal: loc.subtotal.clone(),
         discount: loc.discount.clone(),
+        invoice_discount: loc.invoice_discount.clone(),
         vat: loc.vat.clone(),
         total_for_payment: loc.total_for_payment.clone(),
         payment_terms_title: loc.payment_terms_title.clone(),
         payment_deadline: loc.payment_deadline.clone(),
         reference_number: loc.reference_number.clone(),
         payment_method: loc.payment_method.clone(),
+        payment_method_transfer: loc.payment_method_transfer.clone(),
+        payment_method_cash: loc.payment_method_cash.clone(),
+        payment_method_card: loc.payment_method_card.clone(),
         notes: loc.notes.clone(),
         legal_notes_title: loc.legal_notes_title.clone(),
         err_company_registration_number_missing: loc.err_company_registration_number_missing.clone(),
@@ -980,291 +1175,238 @@ fn pdf_labels(lang: &str) -> PdfLabels {
     }
 }
 
-#[allow(dead_code)]
-fn draw_rule(layer: &printpdf::PdfLayerReference, x1: f32, x2: f32, y: f32) {
-    use printpdf::Mm;
-    layer.add_line(printpdf::Line {
-        points: vec![
-            (printpdf::Point::new(Mm(x1), Mm(y)), false),
-            (printpdf::Point::new(Mm(x2), Mm(y)), false),
-        ],
-        is_closed: false,
-    });
-}
-
-fn draw_rule_with_thickness(
-    layer: &printpdf::PdfLayerReference,
-    x1: f32,
-    x2: f32,
-    y: f32,
-    thickness: f32,
-) {
-    use printpdf::Mm;
-    layer.set_outline_thickness(thickness);
-    layer.add_line(printpdf::Line {
-        points: vec![
-            (printpdf::Point::new(Mm(x1), Mm(y)), false),
-            (printpdf::Point::new(Mm(x2), Mm(y)), false),
-        ],
-        is_closed: false,
-    });
-}
-
-#[allow(dead_code)]
-fn push_line_right(
-    layer: &printpdf::PdfLayerReference,
-    font: &printpdf::IndirectFontRef,
-    text: &str,
-    font_size: f32,
-    x_right: f32,
-    y: f32,
-) {
-    // printpdf doesn't expose reliable text metrics; use a pragmatic estimate.
-    // This is good enough for numeric columns and matches the reference visually.
-    let width_est = (text.chars().count() as f32) * font_size * 0.42;
-    let x = (x_right - width_est).max(0.0);
-    push_line(layer, font, text, font_size, x, y);
-}
-
-fn text_width_mm_ttf(face: &ttf_parser::Face<'_>, text: &str, font_size_pt: f32) -> f32 {
-    // PDF font sizes are in points; our coordinates are in millimeters.
-    const PT_TO_MM: f32 = 25.4 / 72.0;
-    let units_per_em = face.units_per_em() as f32;
-    if units_per_em <= 0.0 {
-        return 0.0;
-    }
-
-    let mut width_units: i32 = 0;
-
-    for ch in text.chars() {
-        let Some(gid) = face.glyph_index(ch) else {
-            continue;
-        };
+fn format_money_sr(v: f64) -> String {
+    NumberStyle::SR.money(v)
+}
+
+/// Upper bound for logo bytes (data URL payload or file on disk).
+const MAX_LOGO_BYTES: usize = 5 * 1024 * 1024;
+/// Upper bound for either logo dimension; larger images are refused before decoding.
+const MAX_LOGO_DIMENSION_PX: u32 = 6000;
+/// Lower bound for either logo dimension; smaller images would print as a blurry smudge.
+const MIN_LOGO_DIMENSION_PX: u32 = 50;
+/// Maximum printed logo width. Wider logos are scaled down and centered vertically in the issuer block.
+const MAX_LOGO_W_MM: f32 = 45.0;
+const LOGO_DPI: f32 = 300.0;
+// Reserved area on the right for the logo (header row 1 only). Applied ONLY when a logo exists.
+// Slightly wider to let the logo feel less cramped.
+const LOGO_AREA_W: f32 = 52.0;
+// Gap between issuer text area and logo box.
+const LOGO_GAP: f32 = 6.0;
+
+// Invoice page geometry (A4, mm).
+const PAGE_W: f32 = 210.0;
+const PAGE_MARGIN_X: f32 = 15.0;
+
+fn logo_error(msg: impl std::fmt::Display) -> String {
+    format!("Logo could not be loaded: {msg}")
+}
+
+fn looks_like_svg(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(512)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg"))
+}
+
+/// Resolves the stored logo reference into raw image bytes.
+/// Accepts `data:image/*;base64,...` URLs, `file://` URLs and absolute file paths.
+fn read_logo_bytes(source: &str) -> Result<Vec<u8>, String> {
+    use base64::Engine as _;
 
-        width_units += face.glyph_hor_advance(gid).unwrap_or(0) as i32;
+    let lower = source.to_ascii_lowercase();
+    if lower.starts_with("data:") {
+        let comma = source
+            .find(',')
+            .ok_or_else(|| logo_error("malformed data URL"))?;
+        let (meta, data) = source.split_at(comma);
+        let meta = meta.to_ascii_lowercase();
+        if meta.contains("image/svg") {
+            return Err(logo_error("SVG logos are not supported, please use PNG or JPEG"));
+        }
+        if !meta.contains(";base64") {
+            return Err(logo_error("data URL must be base64-encoded"));
+        }
+        let b64 = &data[1..];
+        // Base64 expands by 4/3; reject early before allocating the decoded buffer.
+        if b64.len() / 4 * 3 > MAX_LOGO_BYTES {
+            return Err(logo_error(format!("image is larger than {} MB", MAX_LOGO_BYTES / 1024 / 1024)));
+        }
+        return base64::engine::general_purpose::STANDARD
+            .decode(b64.trim())
+            .map_err(|e| logo_error(format!("invalid base64 data ({e})")));
     }
 
-    let width_pt = (width_units as f32 / units_per_em) * font_size_pt;
-    width_pt * PT_TO_MM
-}
+    let path = if lower.starts_with("file:") {
+        tauri::Url::parse(source)
+            .ok()
+            .and_then(|u| u.to_file_path().ok())
+            .ok_or_else(|| logo_error("invalid file URL"))?
+    } else {
+        let p = PathBuf::from(source);
+        if !p.is_absolute() {
+            return Err(logo_error("expected a data URL, file:// URL or absolute file path"));
+        }
+        p
+    };
 
-fn font_ascent_mm(face: &ttf_parser::Face<'_>, font_size_pt: f32) -> f32 {
-    const PT_TO_MM: f32 = 25.4 / 72.0;
-    let units_per_em = face.units_per_em() as f32;
-    if units_per_em <= 0.0 {
-        return font_size_pt * PT_TO_MM * 0.80;
+    if path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("svg") || e.eq_ignore_ascii_case("svgz"))
+        .unwrap_or(false)
+    {
+        return Err(logo_error("SVG logos are not supported, please use PNG or JPEG"));
     }
 
-    let asc_units = face.ascender() as f32;
-    (asc_units / units_per_em) * font_size_pt * PT_TO_MM
-}
-
-fn font_descent_mm(face: &ttf_parser::Face<'_>, font_size_pt: f32) -> f32 {
-    const PT_TO_MM: f32 = 25.4 / 72.0;
-    let units_per_em = face.units_per_em() as f32;
-    if units_per_em <= 0.0 {
-        return font_size_pt * PT_TO_MM * 0.20;
+    let meta = std::fs::metadata(&path).map_err(|e| logo_error(format!("{} ({e})", path.display())))?;
+    if !meta.is_file() {
+        return Err(logo_error(format!("{} is not a file", path.display())));
     }
-
-    // descender is typically negative; convert to a positive magnitude in mm.
-    let desc_units = face.descender() as f32;
-    ((-desc_units).max(0.0) / units_per_em) * font_size_pt * PT_TO_MM
+    if meta.len() > MAX_LOGO_BYTES as u64 {
+        return Err(logo_error(format!("image is larger than {} MB", MAX_LOGO_BYTES / 1024 / 1024)));
+    }
+    std::fs::read(&path).map_err(|e| logo_error(format!("{} ({e})", path.display())))
 }
 
-fn push_line_right_measured(
-    layer: &printpdf::PdfLayerReference,
-    font: &printpdf::IndirectFontRef,
-    ttf_face: &ttf_parser::Face<'_>,
-    text: &str,
-    font_size: f32,
-    x_right: f32,
-    y: f32,
-) {
-    let width_mm = text_width_mm_ttf(ttf_face, text, font_size);
-    let x = (x_right - width_mm).max(0.0);
-    push_line(layer, font, text, font_size, x, y);
+/// Loads and decodes the logo, enforcing size/dimension limits.
+fn decode_logo_image(source: &str) -> Result<printpdf::image_crate::DynamicImage, String> {
+    decode_logo_bytes(&read_logo_bytes(source)?)
 }
 
-fn split_and_wrap_lines(input: &str, max_chars: usize) -> Vec<String> {
-    let mut out: Vec<String> = Vec::new();
-    for raw in input.lines() {
-        let s = raw.trim();
-        if s.is_empty() {
-            continue;
-        }
-        for line in wrap_text_lines(s, max_chars) {
-            out.push(line);
-        }
+fn decode_logo_bytes(bytes: &[u8]) -> Result<printpdf::image_crate::DynamicImage, String> {
+    if bytes.len() > MAX_LOGO_BYTES {
+        return Err(logo_error(format!("image is larger than {} MB", MAX_LOGO_BYTES / 1024 / 1024)));
+    }
+    if looks_like_svg(bytes) {
+        return Err(logo_error("SVG logos are not supported, please use PNG or JPEG"));
     }
-    out
-}
-
-fn format_money_sr(v: f64) -> String {
-    // Serbian style: thousands '.', decimals ',' (e.g., 16.200,00)
-    let s = format!("{:.2}", v);
-    let parts = s.split('.').collect::<Vec<_>>();
-    let int_part = parts[0];
-    let dec_part = parts.get(1).copied().unwrap_or("00");
 
-    let mut out = String::new();
-    let chars: Vec<char> = int_part.chars().collect();
-    let mut cnt = 0;
-    for i in (0..chars.len()).rev() {
-        if cnt == 3 {
-            out.push('.');
-            cnt = 0;
-        }
-        out.push(chars[i]);
-        cnt += 1;
+    let reader = printpdf::image_crate::io::Reader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(logo_error)?;
+    if reader.format().is_none() {
+        return Err(logo_error("unrecognized image format, please use PNG or JPEG"));
+    }
+    let (w, h) = reader
+        .into_dimensions()
+        .map_err(|e| logo_error(format!("failed to read image header ({e})")))?;
+    if w > MAX_LOGO_DIMENSION_PX || h > MAX_LOGO_DIMENSION_PX {
+        return Err(logo_error(format!(
+            "image is {w}×{h} px, maximum is {MAX_LOGO_DIMENSION_PX}×{MAX_LOGO_DIMENSION_PX} px"
+        )));
+    }
+    if w < MIN_LOGO_DIMENSION_PX || h < MIN_LOGO_DIMENSION_PX {
+        return Err(logo_error(format!(
+            "image is {w}×{h} px, minimum is {MIN_LOGO_DIMENSION_PX}×{MIN_LOGO_DIMENSION_PX} px"
+        )));
     }
-    let int_with_sep: String = out.chars().rev().collect();
-    format!("{},{}", int_with_sep, dec_part)
-}
 
-fn format_qty_sr(v: f64) -> String {
-    // Match reference (2 decimals, decimal comma)
-    let s = format!("{:.2}", v);
-    s.replace('.', ",")
+    printpdf::image_crate::load_from_memory(bytes)
+        .map_err(|e| logo_error(format!("failed to decode image ({e})")))
 }
 
-#[allow(dead_code)]
-fn fill_rect_gray(
-    layer: &printpdf::PdfLayerReference,
+#[derive(Debug, Clone, Copy)]
+struct LogoPlacement {
     x: f32,
-    y_top: f32,
-    w: f32,
-    h: f32,
-    gray: f32,
-) {
-    use printpdf::{path::PaintMode, Color, Mm, Rect, Rgb};
-
-    layer.set_fill_color(Color::Rgb(Rgb::new(gray, gray, gray, None)));
-    // printpdf uses bottom-left origin; our y coordinates are already in that space.
-    let rect = Rect::new(Mm(x), Mm(y_top - h), Mm(x + w), Mm(y_top)).with_mode(PaintMode::Fill);
-    layer.add_rect(rect);
-    // reset fill to black
-    layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
-}
-
-fn wrap_text_by_width_mm(
-    ttf_face: &ttf_parser::Face<'_>,
-    input: &str,
-    font_size: f32,
-    max_width_mm: f32,
-) -> Vec<String> {
-    let s = input.trim();
-    if s.is_empty() {
-        return Vec::new();
+    bottom_y: f32,
+    scale: f32,
+    h_mm: f32,
+}
+
+/// Header row 1 of the invoice PDF: the issuer column on the left and, when there is a logo, the
+/// box right of it that the logo is fitted into.
+#[derive(Debug, Clone, Copy)]
+struct HeaderColumns {
+    issuer_left_x: f32,
+    /// Where the issuer text wraps.
+    left_col_right_x: f32,
+    logo_box_left_x: f32,
+    right_x: f32,
+}
+
+impl HeaderColumns {
+    fn new(has_logo: bool) -> Self {
+        let issuer_left_x = PAGE_MARGIN_X;
+        let right_x = PAGE_W - PAGE_MARGIN_X;
+        let left_col_right_x = if has_logo {
+            (right_x - LOGO_AREA_W - LOGO_GAP).max(issuer_left_x)
+        } else {
+            right_x
+        };
+        HeaderColumns {
+            issuer_left_x,
+            left_col_right_x,
+            logo_box_left_x: (left_col_right_x + LOGO_GAP).min(right_x),
+            right_x,
+        }
     }
 
-    let mut out: Vec<String> = Vec::new();
-    let mut current = String::new();
+    /// Places a `px_w`×`px_h` logo in the logo box, beside an issuer block from `block_top_y`
+    /// down to `block_bottom_y`.
+    fn place_logo(&self, px_w: u32, px_h: u32, block_top_y: f32, block_bottom_y: f32) -> LogoPlacement {
+        place_logo(px_w, px_h, LOGO_DPI, self.logo_box_left_x, self.right_x, block_top_y, block_bottom_y)
+    }
+}
 
-    for word in s.split_whitespace() {
-        if current.is_empty() {
-            if text_width_mm_ttf(ttf_face, word, font_size) <= max_width_mm {
-                current.push_str(word);
-                continue;
-            }
+/// Fits a logo into the reserved box right of the issuer text.
+/// Normally the logo matches the issuer block height and is top-aligned with the company name;
+/// when that would exceed `MAX_LOGO_W_MM` (or the box) it is scaled down to fit and vertically centered.
+fn place_logo(
+    px_w: u32,
+    px_h: u32,
+    dpi: f32,
+    box_left: f32,
+    box_right: f32,
+    block_top_y: f32,
+    block_bottom_y: f32,
+) -> LogoPlacement {
+    let natural_w_mm = px_w.max(1) as f32 / dpi * 25.4;
+    let natural_h_mm = px_h.max(1) as f32 / dpi * 25.4;
 
-            // Split a single too-long word into chunks.
-            let mut chunk = String::new();
-            for ch in word.chars() {
-                let candidate = format!("{}{}", chunk, ch);
-                if text_width_mm_ttf(ttf_face, &candidate, font_size) <= max_width_mm {
-                    chunk = candidate;
-                } else {
-                    if !chunk.is_empty() {
-                        out.push(chunk);
-                    }
-                    chunk = ch.to_string();
-                }
-            }
-            if !chunk.is_empty() {
-                out.push(chunk);
-            }
-            continue;
-        }
+    let max_w = (box_right - box_left).clamp(1.0, MAX_LOGO_W_MM);
+    let block_h = (block_top_y - block_bottom_y).max(0.0);
 
-        let candidate = format!("{} {}", current, word);
-        if text_width_mm_ttf(ttf_face, &candidate, font_size) <= max_width_mm {
-            current = candidate;
-        } else {
-            out.push(std::mem::take(&mut current));
+    let scale_w = max_w / natural_w_mm;
+    let scale_h = block_h / natural_h_mm;
+    let width_limited = scale_w < scale_h;
+    let scale = scale_w.min(scale_h).max(0.001);
 
-            if text_width_mm_ttf(ttf_face, word, font_size) <= max_width_mm {
-                current.push_str(word);
-            } else {
-                let mut chunk = String::new();
-                for ch in word.chars() {
-                    let cand = format!("{}{}", chunk, ch);
-                    if text_width_mm_ttf(ttf_face, &cand, font_size) <= max_width_mm {
-                        chunk = cand;
-                    } else {
-                        if !chunk.is_empty() {
-                            out.push(chunk);
-                        }
-                        chunk = ch.to_string();
-                    }
-                }
-                current = chunk;
-            }
-        }
-    }
+    let w_mm = natural_w_mm * scale;
+    let h_mm = natural_h_mm * scale;
 
-    if !current.is_empty() {
-        out.push(current);
-    }
+    // Right-align within the reserved box.
+    let x = (box_right - w_mm).max(box_left);
+    let bottom_y = if width_limited {
+        block_bottom_y + (block_h - h_mm) / 2.0
+    } else {
+        (block_top_y - h_mm).max(block_bottom_y)
+    };
 
-    out
+    LogoPlacement { x, bottom_y, scale, h_mm }
 }
 
-fn draw_value_only_wrapped(
-    layer: &printpdf::PdfLayerReference,
-    font: &printpdf::IndirectFontRef,
-    ttf_face: &ttf_parser::Face<'_>,
-    value: &str,
-    font_size: f32,
-    x_value: f32,
-    y: f32,
-    max_width_value: f32,
-    line_height: f32,
-    row_gap: f32,
-) -> f32 {
-    let value_lines = wrap_text_by_width_mm(ttf_face, value, font_size, max_width_value);
-    if value_lines.is_empty() {
-        return y;
-    }
-
-    for (idx, line) in value_lines.iter().enumerate() {
-        let yy = y - (idx as f32) * line_height;
-        push_line(layer, font, line, font_size, x_value, yy);
-    }
-
-    y - (value_lines.len() as f32) * line_height - row_gap
+/// Renders the invoice PDF. With `archival`, the output is post-processed into PDF/A-1b.
+fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>, archival: bool) -> Result<Vec<u8>, AppError> {
+    render_pdf_bytes(payload, logo_url, archival).map_err(AppError::Pdf)
 }
 
-fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Result<Vec<u8>, String> {
-    use printpdf::{Image, ImageTransform, Mm, PdfDocument};
-    use base64::Engine as _;
+fn render_pdf_bytes(
+    payload: &InvoicePdfPayload,
+    logo_url: Option<&str>,
+    archival: bool,
+) -> Result<Vec<u8>, String> {
+    use printpdf::{ImageTransform, Mm, PdfDocument};
 
     // Language selection must be explicit (no implicit Serbian fallback).
     let lang_raw = payload.language.as_deref().map(str::trim).filter(|s| !s.is_empty());
     let lang_key = match lang_raw {
-        Some(l) => {
-            let lower = l.to_ascii_lowercase();
-            if lower.starts_with("en") {
-                "en"
-            } else if lower.starts_with("sr") {
-                "sr"
-            } else {
-                return Err(pdf_labels("en").err_invalid_language.clone());
-            }
-        }
-        None => {
-            return Err(pdf_labels("en").err_missing_language.clone());
-        }
+        Some(l) if is_bilingual_pdf_language(l) => "bilingual".to_string(),
+        Some(l) if locales::is_available_language(l) => locales::language_code(l),
+        Some(_) => return Err(pdf_labels("en").err_invalid_language.clone()),
+        None => return Err(pdf_labels("en").err_missing_language.clone()),
     };
+    let lang_key = lang_key.as_str();
 
     let labels = pdf_labels(lang_key);
 
@@ -1278,7 +1420,7 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         .as_deref()
         .unwrap_or("")
         .trim();
-    if client_mb.is_empty() {
+    if client_mb.is_empty() && !payload.client.is_foreign {
         return Err(labels.err_client_registration_number_missing.clone());
     }
 
@@ -1290,22 +1432,15 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     );
     let layer = doc.get_page(page1).get_layer(layer1);
 
-    // Embed a Unicode font to support Cyrillic (ћирилица) and other non-ASCII characters.
-    static FONT_BYTES: &[u8] = include_bytes!("../assets/DejaVuSans.ttf");
-    let font = doc
-        .add_external_font(Cursor::new(FONT_BYTES as &[u8]))
-        .map_err(|e| e.to_string())?;
+    // Embed a Unicode font to support Cyrillic (ћирилица) and other non-ASCII characters; the
+    // parsed face gives deterministic text widths (used for true right-alignment).
+    let (font, ttf_face) = pdf_util::embed_font(&doc)?;
     // Use the same embedded font for all text to ensure consistent Unicode rendering.
     let font_bold = font.clone();
 
-    // Parse the same embedded font for deterministic text width measurement (used for true right-alignment).
-    let ttf_face = ttf_parser::Face::parse(FONT_BYTES, 0)
-        .map_err(|_| "Failed to parse embedded font for measurement".to_string())?;
-
-    // Layout constants (language-agnostic)
-    const PAGE_W: f32 = 210.0;
+    // Layout constants (language-agnostic); the page width and side margins are shared with
+    // `HeaderColumns`.
     const PAGE_H: f32 = 297.0;
-    const PAGE_MARGIN_X: f32 = 15.0;
     const PAGE_MARGIN_TOP: f32 = 12.0;
     const PAGE_MARGIN_BOTTOM: f32 = 12.0;
 
@@ -1355,18 +1490,27 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     let footer_text_y = footer_y;
     // Reserve space for: (1) footer line, (2) place-of-issue line.
     let footer_note_bottom_y = footer_text_y + 10.0;
-    let footer_note_max_chars = 95;
+    let footer_note_font_size = 8.5;
 
     // ----- Template A – Classic Serbian Invoice (reference-driven) -----
 
-    // Language-dependent numeric formatting
-    let is_sr = lang_key == "sr";
-    let fmt_money = |v: f64| if is_sr { format_money_sr(v) } else { format_money(v) };
-    let fmt_qty = |v: f64| if is_sr { format_qty_sr(v) } else { format!("{:.2}", v) };
+    // Language-dependent numeric formatting (bilingual invoices are Serbian documents).
+    let is_bilingual = lang_key == "bilingual";
+    let numbers = NumberStyle::for_language(lang_key);
+    let fmt_money = |v: f64| numbers.money(v);
+    let fmt_qty = |v: f64| numbers.quantity(v);
+    let fmt_pct = |v: f64| numbers.percent(v);
 
     // Build legal-note lines from templates (already localized, with placeholders resolved)
-    let legal_note_text = mandatory_invoice_note_text(lang_key, &payload.invoice_number);
-    let legal_note_lines = split_and_wrap_lines(&legal_note_text, footer_note_max_chars);
+    let legal_note_text =
+        mandatory_invoice_note_text(
+            lang_key,
+            &payload.invoice_number,
+            payload.client.reverse_charge(),
+            &payload.legal_note_override,
+        );
+    let legal_note_lines =
+        split_and_wrap_lines_by_width_mm(&ttf_face, &legal_note_text, footer_note_font_size, content_width);
 
     // Flowing cursor
     let mut y = PAGE_H - PAGE_MARGIN_TOP;
@@ -1376,7 +1520,12 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     // without changing the internal alignment of the issuer/buyer columns.
     const TITLE_BLOCK_H: f32 = 14.0;
     const TITLE_TOP_PAD: f32 = 1.5;
-    let title_prefix = labels.invoice_title_service_invoice_no.as_str();
+    let valid_until = payload.valid_until.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let title_prefix = if valid_until.is_some() {
+        labels.quote_title_no.as_str()
+    } else {
+        labels.invoice_title_service_invoice_no.as_str()
+    };
     let title_text = format!("{}{}", title_prefix, payload.invoice_number.trim());
     let doc_title_size: f32 = 14.0;
     let doc_title_w = text_width_mm_ttf(&ttf_face, title_text.as_str(), doc_title_size);
@@ -1395,45 +1544,26 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     // Row 1: issuer/company (left) + logo (right reserved area)
     // Row 2: buyer/client (full width)
     // IMPORTANT: Remove the "Od:" and "Komitent:" labels (do not render section titles).
-    const LOGO_DPI: f32 = 300.0;
-    // Reserved area on the right for the logo (Row 1 only). Applied ONLY when a logo exists.
-    // Slightly wider to let the logo feel less cramped.
-    const LOGO_AREA_W: f32 = 52.0;
-    // Gap between issuer text area and logo box.
-    const LOGO_GAP: f32 = 6.0;
     const HEADER_ROWS_GAP_Y: f32 = 8.0;
 
     let name_size = 11.0;
     let text_size = 8.3;
     let line_h = 4.0;
 
-    // Decode a data URL logo (as stored from the UI: data:image/*;base64,...) into an image.
-    let decoded_logo = logo_url
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .and_then(|s| {
-            let lower = s.to_ascii_lowercase();
-            if !lower.starts_with("data:") {
-                return None;
-            }
-            let comma = s.find(',')?;
-            let (meta, data) = s.split_at(comma);
-            if !meta.to_ascii_lowercase().contains(";base64") {
-                return None;
-            }
-            let b64 = &data[1..];
-            let bytes = base64::engine::general_purpose::STANDARD.decode(b64).ok()?;
-            let img = printpdf::image_crate::load_from_memory(&bytes).ok()?;
-            Some(img)
-        })
-        ;
-
-    let row1_text_right_x = if decoded_logo.is_some() {
-        (content_right_x - LOGO_AREA_W - LOGO_GAP).max(content_left_x)
-    } else {
-        content_right_x
+    // Decode the logo (data URL as stored from the UI, or a file path / file:// URL).
+    // A configured logo that fails to load is an error rather than a silently logo-less PDF.
+    let decoded_logo = match logo_url.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(src) => Some(decode_logo_image(src).inspect_err(|e| {
+            tracing::warn!(target: "pdf", error = %e, "failed to load the logo")
+        })?),
+        None => None,
     };
-    let row1_text_w_mm = (row1_text_right_x - content_left_x).max(10.0);
+    // PDF/A-1 forbids transparency, so archival exports get the logo flattened onto white.
+    let decoded_logo = if archival { decoded_logo.map(pdfa::flatten_alpha) } else { decoded_logo };
+    let decoded_logo = decoded_logo.map(pdf_logo::shrink);
+
+    let header_columns = HeaderColumns::new(decoded_logo.is_some());
+    let row1_text_w_mm = (header_columns.left_col_right_x - header_columns.issuer_left_x).max(10.0);
     let row1_top_y = y;
 
     let company_address_line = payload.company.address_line.as_deref().unwrap_or("").trim();
@@ -1472,7 +1602,7 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         &font_bold,
         &payload.company.company_name,
         name_size,
-        content_left_x,
+        header_columns.issuer_left_x,
         y_issuer,
     );
     y_issuer -= 4.6;
@@ -1481,7 +1611,7 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     // `push_line` uses a baseline Y; ascent gets us to the visual top of the glyphs.
     let issuer_top_y = row1_top_y + font_ascent_mm(&ttf_face, name_size);
 
-    let issuer_x_label = content_left_x;
+    let issuer_x_label = header_columns.issuer_left_x;
     let issuer_full_w_mm = row1_text_w_mm;
 
     let mut issuer_rows: Vec<HeaderRow> = Vec::new();
@@ -1527,6 +1657,20 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
             value: bank_value.to_string(),
         });
     }
+    let iban_value = payload.company.iban.as_deref().unwrap_or("").trim();
+    if !iban_value.is_empty() {
+        issuer_rows.push(HeaderRow {
+            label: Some(labels.iban.clone()),
+            value: iban_value.to_string(),
+        });
+    }
+    let swift_value = payload.company.swift.as_deref().unwrap_or("").trim();
+    if !swift_value.is_empty() {
+        issuer_rows.push(HeaderRow {
+            label: Some(labels.swift.clone()),
+            value: swift_value.to_string(),
+        });
+    }
 
     let issuer_row_count = issuer_rows.len();
 
@@ -1578,42 +1722,18 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     // --- Row 1: logo (top-right within reserved area) ---
     let mut logo_h_mm: f32 = 0.0;
     if let Some(img) = decoded_logo {
-        let px_w = img.width().max(1) as f32;
-        let px_h = img.height().max(1) as f32;
-
-        let natural_w_mm = px_w / LOGO_DPI * 25.4;
-        let natural_h_mm = px_h / LOGO_DPI * 25.4;
-
-        let logo_box_left = (row1_text_right_x + LOGO_GAP).min(content_right_x);
-        let logo_box_right = content_right_x;
-        let logo_box_w = (logo_box_right - logo_box_left).max(1.0);
-
-        // Scale to visually match the issuer block height, but still contain within the logo box width.
-        // This keeps the logo prominent and vertically aligned with issuer content.
-        let target_h = issuer_block_h.max(0.0);
-        let scale_w = logo_box_w / natural_w_mm.max(1.0);
-        let scale_h = target_h / natural_h_mm.max(1.0);
-        let scale = scale_w.min(scale_h).max(0.01);
-
-        let scaled_w_mm = natural_w_mm * scale;
-        let scaled_h_mm = natural_h_mm * scale;
-        logo_h_mm = scaled_h_mm;
-
-        // Right-align within the reserved box; top-align with the company name line.
-        let logo_x = (logo_box_right - scaled_w_mm).max(logo_box_left);
-        // Place the logo so its top edge aligns with the company name, and clamp so the bottom
-        // doesn't extend below the issuer block.
-        let logo_bottom_y = (issuer_top_y - scaled_h_mm).max(issuer_bottom_y);
-
-        let image = Image::from_dynamic_image(&img);
+        let placement = header_columns.place_logo(img.width(), img.height(), issuer_top_y, issuer_bottom_y);
+        logo_h_mm = placement.h_mm;
+
+        let image = pdf_logo::embed(&img);
         image.add_to_layer(
             layer.clone(),
             ImageTransform {
-                translate_x: Some(Mm(logo_x)),
-                translate_y: Some(Mm(logo_bottom_y)),
+                translate_x: Some(Mm(placement.x)),
+                translate_y: Some(Mm(placement.bottom_y)),
                 rotate: None,
-                scale_x: Some(scale),
-                scale_y: Some(scale),
+                scale_x: Some(placement.scale),
+                scale_y: Some(placement.scale),
                 dpi: Some(LOGO_DPI),
             },
         );
@@ -1680,10 +1800,21 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     let buyer_pib = payload.client.pib.as_deref().unwrap_or("").trim();
     if !buyer_pib.is_empty() {
         buyer_rows.push(HeaderRow {
-            label: Some(labels.vat_id.clone()),
+            label: Some(if payload.client.is_foreign {
+                labels.foreign_vat_id.clone()
+            } else {
+                labels.vat_id.clone()
+            }),
             value: buyer_pib.to_string(),
         });
     }
+    let buyer_country = payload.client.country.as_deref().unwrap_or("").trim();
+    if payload.client.is_foreign && !buyer_country.is_empty() {
+        buyer_rows.push(HeaderRow {
+            label: Some(labels.country.clone()),
+            value: buyer_country.to_string(),
+        });
+    }
     if !client_mb.is_empty() {
         buyer_rows.push(HeaderRow {
             label: Some(labels.registration_number.clone()),
@@ -1756,8 +1887,8 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     let table_left = content_left_x;
     let table_right = content_right_x;
     let col_gap = 3.0;
-    let col_unit_w = 16.0;
-    let col_qty_w = 18.0;
+    let col_unit_w: f32 = 16.0;
+    let col_qty_w: f32 = 18.0;
     let col_price_w_base = 24.0;
     let col_disc_w_base = 20.0;
     let col_total_w_base = 26.0;
@@ -1769,15 +1900,32 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
 
     let header_size_measure: f32 = 8.6;
 
-    let min_disc_w = text_width_mm_ttf(&ttf_face, &labels.col_discount, header_size_measure)
+    // Bilingual column headers are stacked: one language per line.
+    let header_lines = |label: &str| -> Vec<String> {
+        if is_bilingual {
+            label.split(PDF_BILINGUAL_SEPARATOR).map(|s| s.trim().to_string()).collect()
+        } else {
+            vec![label.to_string()]
+        }
+    };
+    let header_w = |label: &str| -> f32 {
+        header_lines(label)
+            .iter()
+            .map(|l| text_width_mm_ttf(&ttf_face, l, header_size_measure))
+            .fold(0.0, f32::max)
+    };
+    let col_unit_w = col_unit_w.max(header_w(&labels.col_unit) + cell_pad_x);
+    let col_qty_w = col_qty_w.max(header_w(&labels.col_qty) + 2.0 * cell_pad_x);
+
+    let min_disc_w = header_w(&labels.col_discount)
         .max(text_width_mm_ttf(&ttf_face, &sample_discount, text_size))
         + 2.0 * cell_pad_x;
 
-    let min_price_w = text_width_mm_ttf(&ttf_face, &labels.col_unit_price, header_size_measure)
+    let min_price_w = header_w(&labels.col_unit_price)
         .max(text_width_mm_ttf(&ttf_face, &sample_big_money, text_size))
         + 2.0 * cell_pad_x;
 
-    let min_total_w = text_width_mm_ttf(&ttf_face, &labels.col_amount, header_size_measure)
+    let min_total_w = header_w(&labels.col_amount)
         .max(text_width_mm_ttf(&ttf_face, &sample_big_money, text_size))
         + 2.0 * cell_pad_x;
 
@@ -1808,6 +1956,7 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     let col_unit_right = col_qty_left - col_gap;
     let col_unit_left = col_unit_right - col_unit_w;
     let col_service_left = table_left;
+    let desc_col_w = (col_unit_left - col_gap - col_service_left).max(10.0);
 
     // Header row (authority) — anchor to the same grid as row values
     let header_size = 8.6;
@@ -1821,31 +1970,47 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     // Header background: fill the entire band BETWEEN the two framing rules.
     // Top rule Y is recorded right after the parties block; bottom rule Y is the line drawn after the header labels.
     const HEADER_ROW_ADVANCE: f32 = 6.0; // must match the y-step immediately after drawing header labels
+    const HEADER_LINE_H: f32 = 3.6;
+    let header_line_count = [
+        &labels.col_description,
+        &labels.col_unit,
+        &labels.col_qty,
+        &labels.col_unit_price,
+        &labels.col_discount,
+        &labels.col_amount,
+    ]
+    .iter()
+    .map(|l| header_lines(l).len())
+    .max()
+    .unwrap_or(1);
+    let header_extra_h = (header_line_count.saturating_sub(1)) as f32 * HEADER_LINE_H;
     let header_band_top_y = items_header_top_rule_y;
-    let header_band_bottom_y = y - HEADER_ROW_ADVANCE;
+    let header_band_bottom_y = y - HEADER_ROW_ADVANCE - header_extra_h;
     let header_band_h = (header_band_top_y - header_band_bottom_y).max(0.0);
     let header_band_w = (table_right - table_left).max(0.0);
     fill_rect_gray(&layer, table_left, header_band_top_y, header_band_w, header_band_h, 0.92);
 
-    push_line(&layer, &font_bold, &labels.col_description, header_size, service_header_x, y);
-    push_line(&layer, &font_bold, &labels.col_unit, header_size, unit_header_x, y);
-    push_line_right_measured(&layer, &font_bold, &ttf_face, &labels.col_qty, header_size, qty_right_x, y);
-    push_line_right_measured(
-        &layer,
-        &font_bold,
-        &ttf_face,
-        &labels.col_unit_price,
-        header_size,
-        price_right_x,
-        y,
-    );
-    push_line_right_measured(&layer, &font_bold, &ttf_face, &labels.col_discount, header_size, disc_right_x, y);
-    push_line_right_measured(&layer, &font_bold, &ttf_face, &labels.col_amount, header_size, numeric_right_x, y);
+    let draw_header = |label: &str, x: f32, right_aligned: bool, y: f32| {
+        for (i, line) in header_lines(label).iter().enumerate() {
+            let yy = y - i as f32 * HEADER_LINE_H;
+            if right_aligned {
+                push_line_right_measured(&layer, &font_bold, &ttf_face, line, header_size, x, yy);
+            } else {
+                push_line(&layer, &font_bold, line, header_size, x, yy);
+            }
+        }
+    };
+    draw_header(&labels.col_description, service_header_x, false, y);
+    draw_header(&labels.col_unit, unit_header_x, false, y);
+    draw_header(&labels.col_qty, qty_right_x, true, y);
+    draw_header(&labels.col_unit_price, price_right_x, true, y);
+    draw_header(&labels.col_discount, disc_right_x, true, y);
+    draw_header(&labels.col_amount, numeric_right_x, true, y);
 
     // Draw the top separator rule on top of the gray band.
     draw_rule_with_thickness(&layer, content_left_x, content_right_x, items_header_top_rule_y, 0.45);
 
-    y -= HEADER_ROW_ADVANCE;
+    y -= HEADER_ROW_ADVANCE + header_extra_h;
     draw_rule_with_thickness(&layer, table_left, table_right, y, 0.60);
     y -= 7.8;
 
@@ -1861,9 +2026,8 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
             return Err(labels.err_too_many_items.clone());
         }
 
-        // Description wraps in the first column
-        // Description wraps; keep it comfortably inside the service column.
-        let desc_lines = split_and_wrap_lines(&it.description, 44);
+        // Description wraps (measured) inside the service column.
+        let desc_lines = split_and_wrap_lines_by_width_mm(&ttf_face, &it.description, text_size, desc_col_w);
         let row_top_y = y;
 
         // Render first line at row_y, continuation lines below (only in service column)
@@ -1892,11 +2056,9 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         // Qty/Price/Discount/Total
         push_line_right_measured(&layer, &font, &ttf_face, &fmt_qty(it.quantity), text_size, qty_right_x, row_top_y);
         push_line_right_measured(&layer, &font, &ttf_face, &fmt_money(it.unit_price), text_size, price_right_x, row_top_y);
-        let line_subtotal = it.quantity * it.unit_price;
-        let line_discount = it.discount_amount.unwrap_or(0.0).clamp(0.0, line_subtotal);
-        let line_total = line_subtotal - line_discount;
+        let line_discount = it.discount_amount.unwrap_or(0.0);
         push_line_right_measured(&layer, &font, &ttf_face, &fmt_money(line_discount), text_size, disc_right_x, row_top_y);
-        push_line_right_measured(&layer, &font_bold, &ttf_face, &fmt_money(line_total), text_size, numeric_right_x, row_top_y);
+        push_line_right_measured(&layer, &font_bold, &ttf_face, &fmt_money(it.total), text_size, numeric_right_x, row_top_y);
 
         let mut row_h_used = 0.0;
         for extra in desc_lines.iter().skip(1) {
@@ -1915,7 +2077,7 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     draw_rule_with_thickness(&layer, table_left, table_right, y, 0.40);
     y -= 7.2;
 
-    // C) Totals area (3-row, boxed/striped like reference)
+    // C) Totals area (3-row, boxed/striped like reference; 4 rows when an invoice-level discount applies)
     let totals_left = table_left;
     // Single explicit padding between the numeric right edge (TOTAL column) and the totals box border.
     // Keep it grid-driven: col_total_right is anchored to the table; the box is a fixed pad away.
@@ -1932,12 +2094,22 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
     let label_x = col_service_left + col_gap;
     // IMPORTANT: use the exact same numeric right edge as the table TOTAL column, with cell padding.
     let value_right = numeric_right_x;
+    let invoice_discount = payload
+        .invoice_discount
+        .filter(|v| v.is_finite())
+        .unwrap_or(0.0)
+        .max(0.0);
+    let has_invoice_discount = invoice_discount > 0.0;
+    let totals_rows: f32 = if has_invoice_discount { 4.0 } else { 3.0 };
+
     let row1_top_y = totals_top_y;
     let row2_top_y = totals_top_y - totals_row_h;
-    let row3_top_y = totals_top_y - 2.0 * totals_row_h;
+    let row_inv_disc_top_y = totals_top_y - 2.0 * totals_row_h;
+    let row_total_top_y = totals_top_y - (totals_rows - 1.0) * totals_row_h;
     let row1_y = row1_top_y - cell_pad_y;
     let row2_y = row2_top_y - cell_pad_y;
-    let row3_y = row3_top_y - cell_pad_y;
+    let row_inv_disc_y = row_inv_disc_top_y - cell_pad_y;
+    let row3_y = row_total_top_y - cell_pad_y;
 
     let totals_label_size = 8.8;
     let totals_value_size = 9.3;
@@ -1980,6 +2152,28 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         row2_y,
     );
 
+    if has_invoice_discount {
+        let label = match payload.invoice_discount_percent.filter(|p| p.is_finite() && *p > 0.0) {
+            Some(p) => format!(
+                "{} {}% ({})",
+                &labels.invoice_discount,
+                fmt_pct(p),
+                &payload.currency
+            ),
+            None => format!("{} ({})", &labels.invoice_discount, &payload.currency),
+        };
+        push_line(&layer, &font, &label, totals_label_size, label_x, row_inv_disc_y);
+        push_line_right_measured(
+            &layer,
+            &font_bold,
+            &ttf_face,
+            &fmt_money(invoice_discount),
+            totals_value_size,
+            value_right,
+            row_inv_disc_y,
+        );
+    }
+
     push_line(
         &layer,
         &font_bold,
@@ -1988,7 +2182,8 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         label_x,
         row3_y,
     );
-    let total_due = payload.subtotal - payload.discount_total;
+    // Rounded once in `invoice_amounts`, so it equals the line totals less the invoice discount.
+    let total_due = payload.total;
     push_line_right_measured(
         &layer,
         &font_bold,
@@ -2001,9 +2196,9 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
 
     // Box lines
     // Remove the totals top border to avoid a rule visually sticking to the first totals row.
-    draw_rule_with_thickness(&layer, totals_left, totals_box_right, totals_top_y - 3.0 * totals_row_h, 0.85);
+    draw_rule_with_thickness(&layer, totals_left, totals_box_right, totals_top_y - totals_rows * totals_row_h, 0.85);
 
-    y = totals_top_y - 3.0 * totals_row_h - 7.0;
+    y = totals_top_y - totals_rows * totals_row_h - 7.0;
 
     // Add a bit of air between the rule above and the notes title.
     let section_gap_after_rule: f32 = 3.0;
@@ -2024,32 +2219,78 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         y,
     );
     y -= 4.4;
-    push_line(
-        &layer,
-        &font,
-        &format!("{}: {}", &labels.service_date, &payload.service_date),
-        8.5,
-        content_left_x,
-        y,
-    );
-    y -= 4.4;
+    if let Some(valid_until) = valid_until {
+        // A quote isn't payable yet: only its validity, no service date or payment details.
+        push_line(
+            &layer,
+            &font,
+            &format!("{}: {}", &labels.quote_valid_until, valid_until),
+            8.5,
+            content_left_x,
+            y,
+        );
+        y -= 6.0;
+    } else {
+        push_line(
+            &layer,
+            &font,
+            &format!("{}: {}", &labels.service_date, &payload.service_date),
+            8.5,
+            content_left_x,
+            y,
+        );
+        y -= 4.4;
 
-    // - Reference number (invoice number)
-    push_line(
-        &layer,
-        &font,
-        &format!("{}: {}", &labels.reference_number, &payload.invoice_number),
-        8.5,
-        content_left_x,
-        y,
-    );
-    y -= 6.0;
+        // - Payment method (if set)
+        if let Some(method) = payload.payment_method {
+            let method_label = match method {
+                PaymentMethod::Transfer => &labels.payment_method_transfer,
+                PaymentMethod::Cash => &labels.payment_method_cash,
+                PaymentMethod::Card => &labels.payment_method_card,
+            };
+            push_line(
+                &layer,
+                &font,
+                &format!("{}: {}", &labels.payment_method, method_label),
+                8.5,
+                content_left_x,
+                y,
+            );
+            y -= 4.4;
+        }
+
+        // - Reference number (model 97 payment reference, or the invoice number for older invoices)
+        let reference = payload
+            .payment_reference
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(&payload.invoice_number);
+        push_line(
+            &layer,
+            &font,
+            &format!("{}: {}", &labels.reference_number, reference),
+            8.5,
+            content_left_x,
+            y,
+        );
+        y -= 6.0;
+    }
+
+    // - Custom fields (PO number, cost center, …)
+    if !payload.custom_fields.is_empty() {
+        for f in &payload.custom_fields {
+            push_line(&layer, &font, &format!("{}: {}", f.label, f.value), 8.5, content_left_x, y);
+            y -= 4.4;
+        }
+        y -= 1.6;
+    }
 
     // - User notes (if present)
     if let Some(notes) = &payload.notes {
         let notes = notes.trim();
         if !notes.is_empty() {
-            for line in split_and_wrap_lines(notes, 95) {
+            for line in split_and_wrap_lines_by_width_mm(&ttf_face, notes, 8.5, content_width) {
                 if y < footer_note_bottom_y + 35.0 {
                     break;
                 }
@@ -2068,22 +2309,34 @@ fn generate_pdf_bytes(payload: &InvoicePdfPayload, logo_url: Option<&str>) -> Re
         if y < footer_note_bottom_y + 12.0 {
             break;
         }
-        push_line(&layer, &font, &line, 8.5, content_left_x, y);
+        push_line(&layer, &font, &line, footer_note_font_size, content_left_x, y);
         y -= 4.4;
     }
 
+    // Signature area: "Fakturu izdao: …" right-aligned under a signature line, only when set.
+    if let Some(issued_by) = payload.issued_by.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        let text = format!("{}: {}", labels.issued_by, issued_by);
+        let text_w = text_width_mm_ttf(&ttf_face, &text, 8.5);
+        let sig_y = (y - 10.0).max(footer_note_bottom_y);
+        draw_rule_with_thickness(&layer, content_right_x - text_w.max(55.0), content_right_x, sig_y + 5.0, 0.3);
+        push_line_right_measured(&layer, &font, &ttf_face, &text, 8.5, content_right_x, sig_y);
+    }
+
     // F) Footer / branding (tiny or omitted)
     if !labels.footer_generated.trim().is_empty() {
         push_line(&layer, &font, &labels.footer_generated, 6.0, content_left_x, 4.0);
     }
 
-    let mut writer = std::io::BufWriter::new(Vec::<u8>::new());
-    doc.save(&mut writer).map_err(|e| e.to_string())?;
-    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    let bytes = pdf_util::save_pdf(doc)?;
+    if archival {
+        let doc_title = if valid_until.is_some() { &labels.quote_title } else { &labels.doc_title };
+        let title = format!("{} {}", doc_title, payload.invoice_number);
+        return pdfa::convert_to_pdfa1b(&bytes, &title);
+    }
     Ok(bytes)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum SmtpTlsMode {
     Implicit,
@@ -2099,6 +2352,31 @@ impl SmtpTlsMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SmtpAuthMode {
+    #[default]
+    Password,
+    Oauth2,
+}
+
+impl SmtpAuthMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SmtpAuthMode::Password => "password",
+            SmtpAuthMode::Oauth2 => "oauth2",
+        }
+    }
+}
+
+fn parse_smtp_auth_mode_str(v: &str) -> SmtpAuthMode {
+    if v.trim().eq_ignore_ascii_case("oauth2") {
+        SmtpAuthMode::Oauth2
+    } else {
+        SmtpAuthMode::Password
+    }
+}
+
 fn default_smtp_tls_mode_for_port(port: i64) -> SmtpTlsMode {
     match port {
         465 => SmtpTlsMode::Implicit,
@@ -2122,7 +2400,7 @@ fn resolved_smtp_tls_mode(mode: Option<SmtpTlsMode>, port: i64) -> SmtpTlsMode {
     mode.unwrap_or_else(|| default_smtp_tls_mode_for_port(port))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     #[serde(default)]
@@ -2141,7 +2419,11 @@ pub struct Settings {
     pub company_email: String,
     #[serde(default)]
     pub company_phone: String,
+    /// Number of the default account in `bank_accounts`, kept for code that needs a single account.
     pub bank_account: String,
+    /// Dinar and foreign-currency accounts; invoices use the one in their currency.
+    #[serde(default)]
+    pub bank_accounts: Vec<BankAccount>,
     pub logo_url: String,
     pub invoice_prefix: String,
     pub next_invoice_number: i64,
@@ -2155,19 +2437,179 @@ pub struct Settings {
     pub smtp_user: String,
     #[serde(default)]
     pub smtp_password: String,
+    /// Set on settings returned to the UI, which never receives the password itself.
+    #[serde(default)]
+    pub smtp_password_configured: bool,
     #[serde(default)]
     pub smtp_from: String,
     #[serde(default = "default_smtp_use_tls")]
     pub smtp_use_tls: bool,
     #[serde(default)]
     pub smtp_tls_mode: Option<SmtpTlsMode>,
+    #[serde(default = "default_smtp_timeout_seconds")]
+    pub smtp_timeout_seconds: i64,
+    #[serde(default)]
+    pub smtp_reply_to: String,
+    #[serde(default)]
+    pub smtp_from_name: String,
+    /// BCC the From address on invoice emails to keep an archival copy.
+    #[serde(default)]
+    pub send_copy_to_self: bool,
+    #[serde(default)]
+    pub smtp_auth_mode: SmtpAuthMode,
+    #[serde(default)]
+    pub smtp_oauth_client_id: String,
+    /// Keyring sentinel once saved; never returned to the UI.
+    #[serde(default)]
+    pub smtp_oauth_client_secret: String,
+    /// Keyring sentinel once saved; never returned to the UI.
+    #[serde(default)]
+    pub smtp_oauth_refresh_token: String,
+    #[serde(default)]
+    pub smtp_oauth_token_endpoint: String,
+    /// Set on settings returned to the UI in place of the refresh token.
+    #[serde(default)]
+    pub smtp_oauth_configured: bool,
+    /// Skip certificate verification. Only allowed for private hosts or with the acknowledgement.
+    #[serde(default)]
+    pub smtp_accept_invalid_certs: bool,
+    /// "I understand the risk" confirmation for `smtp_accept_invalid_certs` on a public host.
+    #[serde(default)]
+    pub smtp_accept_invalid_certs_acknowledged: bool,
+    /// PEM certificate (internal CA or self-signed server cert). When set, it is the only trust root.
+    #[serde(default)]
+    pub smtp_pinned_cert_pem: String,
+    /// Yearly turnover above which the paušal status is lost (RSD).
+    #[serde(default = "default_turnover_limit_rsd")]
+    pub turnover_limit_rsd: f64,
+    /// Yearly turnover above which VAT registration is required (RSD).
+    #[serde(default = "default_vat_limit_rsd")]
+    pub vat_limit_rsd: f64,
+    /// Copy the database into `backups/` on startup when the newest copy is older than a day.
+    #[serde(default = "default_auto_backup_enabled")]
+    pub auto_backup_enabled: bool,
+    /// How many automatic backups to keep; older ones are deleted.
+    #[serde(default = "default_auto_backup_keep")]
+    pub auto_backup_keep: i64,
+    /// Dialect used by the CSV exports unless a call overrides it.
+    #[serde(default)]
+    pub csv_options: CsvOptions,
+    /// Notes a new invoice starts with when it is created without any.
+    #[serde(default)]
+    pub default_invoice_notes: String,
+    /// Replaces the built-in legal note on invoices, per language; blank keeps the built-in one.
+    #[serde(default)]
+    pub legal_note_override: LegalNoteOverride,
+    /// How line totals, discounts and invoice totals are rounded to the cent on new invoices.
+    #[serde(default)]
+    pub rounding_mode: RoundingMode,
+    /// Quotes are numbered apart from invoices, as `{quotePrefix}-{nextQuoteNumber}`.
+    #[serde(default = "default_quote_prefix")]
+    pub quote_prefix: String,
+    #[serde(default = "default_next_quote_number")]
+    pub next_quote_number: i64,
+    /// Invoice events are POSTed here when set; see `webhooks`.
+    #[serde(default)]
+    pub webhook_url: String,
+    /// HMAC key for the `X-Pausaler-Signature` header, kept in the OS keyring like the SMTP password.
+    #[serde(default)]
+    pub webhook_secret: String,
+    /// Set on settings returned to the UI, which never receives the secret itself.
+    #[serde(default)]
+    pub webhook_secret_configured: bool,
+    /// Name of exported invoice PDFs; see `pdf_filename` for the placeholders.
+    #[serde(default = "default_pdf_filename_template")]
+    pub pdf_filename_template: String,
+    /// Person printed as "Fakturu izdao" on new invoices unless the invoice names someone else.
+    #[serde(default)]
+    pub issued_by: Option<String>,
+    /// Recipient of `email_report_to_accountant`.
+    #[serde(default)]
+    pub accountant_email: String,
+    /// A DRAFT older than this is suggested for sending or cancelling.
+    #[serde(default = "default_stale_draft_days")]
+    pub stale_draft_days: i64,
+    /// Days past due before a SENT invoice is suggested for a reminder.
+    #[serde(default)]
+    pub overdue_grace_days: i64,
+    /// Labels the invoice form offers for custom fields (PO number, cost center, …).
+    #[serde(default)]
+    pub custom_field_labels: Vec<String>,
+    /// Invoice emails larger than this many megabytes are not sent; most providers reject them.
+    #[serde(default = "default_email_max_message_mb")]
+    pub email_max_message_mb: i64,
+    /// Colors of the invoice email, with a dark variant for clients in dark mode.
+    #[serde(default)]
+    pub email_theme: EmailTheme,
+}
+
+/// Custom legal note text per invoice language. `{INVOICE_NUMBER}` is substituted like in the
+/// built-in templates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalNoteOverride {
+    #[serde(default)]
+    pub sr: String,
+    #[serde(default)]
+    pub en: String,
+}
+
+impl LegalNoteOverride {
+    /// The override for `lang`, if it isn't blank. Other languages always use their template.
+    fn for_lang(&self, lang: &str) -> Option<&str> {
+        let text = match lang {
+            "sr" => &self.sr,
+            "en" => &self.en,
+            _ => return None,
+        };
+        Some(text.as_str()).filter(|t| !t.trim().is_empty())
+    }
 }
 
 fn default_smtp_use_tls() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+const DEFAULT_TURNOVER_LIMIT_RSD: f64 = 6_000_000.0;
+const DEFAULT_VAT_LIMIT_RSD: f64 = 8_000_000.0;
+
+fn default_turnover_limit_rsd() -> f64 {
+    DEFAULT_TURNOVER_LIMIT_RSD
+}
+
+fn default_vat_limit_rsd() -> f64 {
+    DEFAULT_VAT_LIMIT_RSD
+}
+
+const DEFAULT_AUTO_BACKUP_KEEP: i64 = 7;
+const MAX_AUTO_BACKUP_KEEP: i64 = 365;
+
+fn default_auto_backup_enabled() -> bool {
+    true
+}
+
+fn default_auto_backup_keep() -> i64 {
+    DEFAULT_AUTO_BACKUP_KEEP
+}
+
+const DEFAULT_SMTP_TIMEOUT_SECONDS: i64 = 30;
+const MAX_SMTP_TIMEOUT_SECONDS: i64 = 300;
+
+fn default_smtp_timeout_seconds() -> i64 {
+    DEFAULT_SMTP_TIMEOUT_SECONDS
+}
+
+/// Effective SMTP socket timeout; non-positive values fall back to the default.
+fn smtp_timeout(seconds: i64) -> std::time::Duration {
+    let secs = if seconds <= 0 {
+        DEFAULT_SMTP_TIMEOUT_SECONDS
+    } else {
+        seconds.min(MAX_SMTP_TIMEOUT_SECONDS)
+    };
+    std::time::Duration::from_secs(secs as u64)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingsPatch {
     pub is_configured: Option<bool>,
@@ -2181,7 +2623,8 @@ pub struct SettingsPatch {
     pub company_email: Option<String>,
     pub company_phone: Option<String>,
     pub bank_account: Option<String>,
-    pub logo_url: Option<String>,
+    #[serde(default)]
+    pub bank_accounts: Option<Vec<BankAccount>>,
     pub invoice_prefix: Option<String>,
     pub next_invoice_number: Option<i64>,
     pub default_currency: Option<String>,
@@ -2193,9 +2636,73 @@ pub struct SettingsPatch {
     pub smtp_from: Option<String>,
     pub smtp_use_tls: Option<bool>,
     pub smtp_tls_mode: Option<SmtpTlsMode>,
+    #[serde(default)]
+    pub smtp_timeout_seconds: Option<i64>,
+    #[serde(default)]
+    pub smtp_reply_to: Option<String>,
+    #[serde(default)]
+    pub smtp_from_name: Option<String>,
+    #[serde(default)]
+    pub send_copy_to_self: Option<bool>,
+    #[serde(default)]
+    pub smtp_auth_mode: Option<SmtpAuthMode>,
+    #[serde(default)]
+    pub smtp_oauth_client_id: Option<String>,
+    #[serde(default)]
+    pub smtp_oauth_client_secret: Option<String>,
+    #[serde(default)]
+    pub smtp_oauth_refresh_token: Option<String>,
+    #[serde(default)]
+    pub smtp_oauth_token_endpoint: Option<String>,
+    #[serde(default)]
+    pub smtp_accept_invalid_certs: Option<bool>,
+    #[serde(default)]
+    pub smtp_accept_invalid_certs_acknowledged: Option<bool>,
+    #[serde(default)]
+    pub smtp_pinned_cert_pem: Option<String>,
+    #[serde(default)]
+    pub turnover_limit_rsd: Option<f64>,
+    #[serde(default)]
+    pub vat_limit_rsd: Option<f64>,
+    #[serde(default)]
+    pub auto_backup_enabled: Option<bool>,
+    #[serde(default)]
+    pub auto_backup_keep: Option<i64>,
+    #[serde(default)]
+    pub csv_options: Option<CsvOptions>,
+    #[serde(default)]
+    pub default_invoice_notes: Option<String>,
+    #[serde(default)]
+    pub legal_note_override: Option<LegalNoteOverride>,
+    #[serde(default)]
+    pub rounding_mode: Option<RoundingMode>,
+    #[serde(default)]
+    pub quote_prefix: Option<String>,
+    #[serde(default)]
+    pub next_quote_number: Option<i64>,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    #[serde(default)]
+    pub pdf_filename_template: Option<String>,
+    #[serde(default)]
+    pub issued_by: Option<String>,
+    #[serde(default)]
+    pub accountant_email: Option<String>,
+    #[serde(default)]
+    pub stale_draft_days: Option<i64>,
+    #[serde(default)]
+    pub overdue_grace_days: Option<i64>,
+    #[serde(default)]
+    pub custom_field_labels: Option<Vec<String>>,
+    #[serde(default)]
+    pub email_max_message_mb: Option<i64>,
+    #[serde(default)]
+    pub email_theme: Option<EmailTheme>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Client {
     pub id: String,
@@ -2209,10 +2716,51 @@ pub struct Client {
     #[serde(default)]
     pub postal_code: String,
     pub email: String,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+    /// Pre-filled on new invoices for this client (e.g. "EUR").
+    #[serde(default)]
+    pub default_currency: Option<String>,
+    /// PDF and email language for this client ("sr", "en" or "bilingual"); falls back to settings.
+    #[serde(default)]
+    pub preferred_language: Option<String>,
+    /// Due date = issue date + these days, when an invoice has no explicit due date.
+    #[serde(default)]
+    pub payment_terms_days: Option<i64>,
+    /// Default invoice email recipient; `email` is used when empty.
+    #[serde(default)]
+    pub billing_email: Option<String>,
+    /// Hidden from the client picker; existing invoices keep referencing it.
+    #[serde(default)]
+    pub archived: bool,
+    /// Client outside Serbia: `pib` holds its VAT ID and no matični broj is required.
+    #[serde(default)]
+    pub is_foreign: bool,
+    /// ISO 3166-1 alpha-2 country code (e.g. "DE"), for foreign clients.
+    #[serde(default)]
+    pub country: Option<String>,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Client {
+    /// Foreign business with a VAT ID: the invoice carries the reverse-charge sentence.
+    fn reverse_charge(&self) -> bool {
+        self.is_foreign && !self.pib.trim().is_empty()
+    }
+
+    /// Default recipient for invoice emails: the billing email, then the contact email.
+    fn invoice_recipient(&self) -> Option<&str> {
+        self.billing_email
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .or_else(|| Some(self.email.trim()).filter(|s| !s.is_empty()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewClient {
     pub name: String,
@@ -2225,9 +2773,33 @@ pub struct NewClient {
     #[serde(default)]
     pub postal_code: String,
     pub email: String,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+    #[serde(default)]
+    pub default_currency: Option<String>,
+    #[serde(default)]
+    pub preferred_language: Option<String>,
+    #[serde(default)]
+    pub payment_terms_days: Option<i64>,
+    #[serde(default)]
+    pub billing_email: Option<String>,
+    #[serde(default)]
+    pub is_foreign: bool,
+    #[serde(default)]
+    pub country: Option<String>,
+    /// Repeating a create with the same key within 24h returns the first client.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Optional client contact fields are stored as NULL rather than an empty string.
+fn non_blank(v: Option<String>) -> Option<String> {
+    v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InvoiceItem {
     pub id: String,
@@ -2239,9 +2811,12 @@ pub struct InvoiceItem {
     #[serde(default)]
     pub discount_amount: Option<f64>,
     pub total: f64,
+    /// Catalog item the line was picked from; the line keeps its own copy of the details.
+    #[serde(default)]
+    pub catalog_item_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum InvoiceStatus {
     Draft,
@@ -2265,7 +2840,47 @@ fn default_invoice_status() -> InvoiceStatus {
     InvoiceStatus::Draft
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentMethod {
+    Transfer,
+    Cash,
+    Card,
+}
+
+/// Payment reference ("poziv na broj") in model 97, e.g. `97 04-2024-001`.
+///
+/// Control digits follow ISO 7064 MOD 97-10 as prescribed by NBS: letters map to 10..35,
+/// separators are ignored, and `KK = 98 - (P * 100 mod 97)`.
+fn compute_model97_reference(number: &str) -> Result<String, String> {
+    let cleaned: String = number
+        .trim()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let cleaned = cleaned.trim_matches('-').to_string();
+    if !cleaned.chars().any(|c| c.is_ascii_alphanumeric()) {
+        return Err("Payment reference requires an invoice number with digits or letters.".to_string());
+    }
+
+    let mut rem: u32 = 0;
+    for c in cleaned.chars().filter(|c| *c != '-') {
+        let v = c.to_digit(36).unwrap_or(0);
+        // Letters expand to two digits.
+        rem = if v >= 10 { (rem * 100 + v) % 97 } else { (rem * 10 + v) % 97 };
+    }
+    let control = 98 - (rem * 100) % 97;
+
+    // Poziv na broj is limited to 22 characters (control digits + separators included).
+    let reference = format!("{:02}-{}", control, cleaned);
+    if reference.len() > 22 {
+        return Err("Invoice number is too long for a model 97 payment reference.".to_string());
+    }
+    Ok(format!("97 {}", reference))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Invoice {
     pub id: String,
@@ -2283,12 +2898,43 @@ pub struct Invoice {
     pub currency: String,
     pub items: Vec<InvoiceItem>,
     pub subtotal: f64,
+    /// Flat discount on the whole invoice, applied after line discounts.
+    #[serde(default)]
+    pub invoice_discount: Option<f64>,
+    /// Percent discount on the whole invoice (0-100), applied after line discounts.
+    #[serde(default)]
+    pub invoice_discount_percent: Option<f64>,
     pub total: f64,
     pub notes: String,
+    #[serde(default)]
+    pub payment_method: Option<PaymentMethod>,
+    /// Payment reference with model prefix ("97 04-2024-001").
+    #[serde(default)]
+    pub payment_reference: Option<String>,
+    /// Default-currency units per one unit of `currency` (NBS middle rate), for foreign-currency
+    /// invoices; used to bring them into the KPO book and turnover reports.
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    /// Brought in with its original number, from another tool by `import_invoices_csv` or from
+    /// another installation by `import_invoice_json`.
+    #[serde(default)]
+    pub imported: bool,
+    /// Rounding of the totals, taken from the settings when the invoice is created.
+    #[serde(default)]
+    pub rounding_mode: RoundingMode,
+    /// Quote the invoice was converted from.
+    #[serde(default)]
+    pub quote_id: Option<String>,
+    /// Person who issued the invoice; the settings default when it was created without one.
+    #[serde(default)]
+    pub issued_by: Option<String>,
+    /// Client-specific label/value rows (PO number, cost center, …).
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewInvoice {
     pub client_id: String,
@@ -2302,11 +2948,30 @@ pub struct NewInvoice {
     pub currency: String,
     pub items: Vec<InvoiceItem>,
     pub subtotal: f64,
+    #[serde(default)]
+    pub invoice_discount: Option<f64>,
+    #[serde(default)]
+    pub invoice_discount_percent: Option<f64>,
     pub total: f64,
     pub notes: String,
+    #[serde(default)]
+    pub payment_method: Option<PaymentMethod>,
+    /// When omitted, a model 97 reference is generated from the invoice number.
+    #[serde(default)]
+    pub payment_reference: Option<String>,
+    #[serde(default)]
+    pub exchange_rate: Option<f64>,
+    /// Overrides the settings' `issued_by` for this invoice.
+    #[serde(default)]
+    pub issued_by: Option<String>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    /// Repeating a create with the same key within 24h returns the first invoice.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InvoicePatch {
     pub invoice_number: Option<String>,
@@ -2319,11 +2984,40 @@ pub struct InvoicePatch {
     pub currency: Option<String>,
     pub items: Option<Vec<InvoiceItem>>,
     pub subtotal: Option<f64>,
+    #[serde(default)]
+    pub invoice_discount: Option<Option<f64>>,
+    #[serde(default)]
+    pub invoice_discount_percent: Option<Option<f64>>,
     pub total: Option<f64>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub payment_method: Option<Option<PaymentMethod>>,
+    #[serde(default)]
+    pub payment_reference: Option<Option<String>>,
+    #[serde(default)]
+    pub exchange_rate: Option<Option<f64>>,
+    #[serde(default)]
+    pub issued_by: Option<Option<String>>,
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomField>>,
+    /// Only kept for PAID invoices; a PAID invoice without one is stamped with today.
+    #[serde(default)]
+    pub paid_at: Option<Option<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `create_invoice` result: the invoice itself plus non-blocking turnover limit warnings.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedInvoice {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    /// Omitted when there are none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schemars(default)]
+    pub limit_warnings: Vec<LimitWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Expense {
     pub id: String,
@@ -2336,9 +3030,18 @@ pub struct Expense {
     #[serde(default)]
     pub notes: Option<String>,
     pub created_at: String,
+    /// Schedule in `recurring_expenses` that generated this expense.
+    #[serde(default)]
+    pub recurring_id: Option<String>,
+    /// Set when the expense is in the trash; it can be restored until purged.
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// Invoice this expense was passed through to (billable expense).
+    #[serde(default)]
+    pub invoice_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewExpense {
     pub title: String,
@@ -2349,9 +3052,12 @@ pub struct NewExpense {
     pub category: Option<String>,
     #[serde(default)]
     pub notes: Option<String>,
+    /// Repeating a create with the same key within 24h returns the first expense.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExpensePatch {
     #[serde(default)]
@@ -2366,6 +3072,16 @@ pub struct ExpensePatch {
     pub category: Option<Option<String>>,
     #[serde(default)]
     pub notes: Option<Option<String>>,
+    #[serde(default)]
+    pub invoice_id: Option<Option<String>>,
+}
+
+/// One part of `split_expense`; currency, category and date come from the original.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseSplitPart {
+    pub title: String,
+    pub amount: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2377,6 +3093,48 @@ pub struct ExpenseRange {
     pub to: Option<String>,
 }
 
+/// Outcome of a date-range export.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeExportResult {
+    pub output_path: String,
+    /// Bounds actually used, normalized to `YYYY-MM-DD`.
+    pub from: String,
+    pub to: String,
+    /// Invoices or expenses in the range; 0 means the file holds only headers.
+    pub row_count: usize,
+}
+
+/// Validates a `YYYY-MM-DD` date (unpadded month/day accepted) and returns it zero-padded.
+fn normalize_date_bound(label: &str, value: &str) -> Result<String, String> {
+    recurring_expenses::parse_ymd(value)
+        .map(recurring_expenses::format_ymd)
+        .ok_or_else(|| format!("Invalid {label} date \"{}\"; expected YYYY-MM-DD.", value.trim()))
+}
+
+/// Normalized inclusive bounds, swapped when given in reverse order.
+fn normalize_date_range(from: &str, to: &str) -> Result<(String, String), String> {
+    let from = normalize_date_bound("start", from)?;
+    let to = normalize_date_bound("end", to)?;
+    Ok(if from <= to { (from, to) } else { (to, from) })
+}
+
+/// Like `normalize_date_range` for optional bounds; blank bounds are open.
+fn normalize_optional_date_range(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let bound = |label: &str, v: Option<String>| match v {
+        Some(v) if !v.trim().is_empty() => normalize_date_bound(label, &v).map(Some),
+        _ => Ok(None),
+    };
+    let (from, to) = (bound("start", from)?, bound("end", to)?);
+    Ok(match (from, to) {
+        (Some(f), Some(t)) if f > t => (Some(t), Some(f)),
+        other => other,
+    })
+}
+
 const SETTINGS_ID: &str = "default";
 
 fn now_iso() -> String {
@@ -2385,6 +3143,63 @@ fn now_iso() -> String {
         .unwrap_or_else(|_| "1970-01-01T00:00:00Z".to_string())
 }
 
+/// `YYYY-MM-DD` plus `days`; `None` when the date does not parse.
+fn add_days_ymd(ymd: &str, days: i64) -> Option<String> {
+    let mut parts = ymd.trim().get(..10)?.splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    let date = time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day).ok()?;
+    let d = date.checked_add(time::Duration::days(days))?;
+    Some(format!("{:04}-{:02}-{:02}", d.year(), u8::from(d.month()), d.day()))
+}
+
+/// Validates a `YYYY-MM-DD` calendar date (month and day may omit the leading zero) and returns it
+/// zero-padded; impossible dates such as 2025-02-31 are rejected. `field` names the date in errors.
+fn normalize_ymd(field: &str, v: &str) -> Result<String, String> {
+    let v = v.trim();
+    let invalid = || format!("{field} \"{v}\" is not a valid date (expected YYYY-MM-DD).");
+    let parts: Vec<&str> = v.split('-').collect();
+    let [y, m, d] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let digits = |p: &str, max_len: usize| !p.is_empty() && p.len() <= max_len && p.bytes().all(|b| b.is_ascii_digit());
+    if y.len() != 4 || !digits(y, 4) || !digits(m, 2) || !digits(d, 2) {
+        return Err(invalid());
+    }
+    let year: i32 = y.parse().map_err(|_| invalid())?;
+    let month = m.parse::<u8>().ok().and_then(|m| time::Month::try_from(m).ok()).ok_or_else(invalid)?;
+    let day: u8 = d.parse().map_err(|_| invalid())?;
+    let date = time::Date::from_calendar_date(year, month, day).map_err(|_| invalid())?;
+    Ok(format!("{:04}-{:02}-{:02}", date.year(), u8::from(date.month()), date.day()))
+}
+
+fn validate_exchange_rate(rate: Option<f64>) -> Result<(), String> {
+    match rate {
+        Some(r) if !r.is_finite() || r <= 0.0 => Err("Exchange rate must be greater than 0.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Amount payable (after the invoice-level discount) in `default_currency`: as-is for
+/// default-currency invoices, converted with the stored exchange rate otherwise. `None` when a
+/// foreign-currency invoice has no rate.
+fn invoice_total_in_currency(invoice: &Invoice, default_currency: &str) -> Option<f64> {
+    let payable = invoice.total;
+    if invoice.currency.trim().eq_ignore_ascii_case(default_currency.trim()) {
+        return Some(payable);
+    }
+    invoice.exchange_rate.map(|rate| payable * rate)
+}
+
+/// Document language for an invoice: the client's preference, else the app language.
+fn invoice_language(settings: &Settings, client: Option<&Client>) -> String {
+    client
+        .and_then(|c| c.preferred_language.clone())
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| settings.language.clone())
+}
+
 fn today_ymd() -> String {
     let d = OffsetDateTime::now_utc().date();
     format!("{:04}-{:02}-{:02}", d.year(), u8::from(d.month()), d.day())
@@ -2402,6 +3217,7 @@ fn default_settings() -> Settings {
         company_email: "".to_string(),
         company_phone: "".to_string(),
         bank_account: "".to_string(),
+        bank_accounts: Vec::new(),
         logo_url: "".to_string(),
         invoice_prefix: "INV".to_string(),
         next_invoice_number: 1,
@@ -2411,57 +3227,112 @@ fn default_settings() -> Settings {
         smtp_port: 587,
         smtp_user: "".to_string(),
         smtp_password: "".to_string(),
+        smtp_password_configured: false,
         smtp_from: "".to_string(),
         smtp_use_tls: true,
         smtp_tls_mode: Some(SmtpTlsMode::Starttls),
+        smtp_timeout_seconds: DEFAULT_SMTP_TIMEOUT_SECONDS,
+        smtp_reply_to: "".to_string(),
+        smtp_from_name: "".to_string(),
+        send_copy_to_self: false,
+        smtp_auth_mode: SmtpAuthMode::Password,
+        smtp_oauth_client_id: "".to_string(),
+        smtp_oauth_client_secret: "".to_string(),
+        smtp_oauth_refresh_token: "".to_string(),
+        smtp_oauth_token_endpoint: "".to_string(),
+        smtp_oauth_configured: false,
+        smtp_accept_invalid_certs: false,
+        smtp_accept_invalid_certs_acknowledged: false,
+        smtp_pinned_cert_pem: "".to_string(),
+        turnover_limit_rsd: DEFAULT_TURNOVER_LIMIT_RSD,
+        vat_limit_rsd: DEFAULT_VAT_LIMIT_RSD,
+        auto_backup_enabled: true,
+        auto_backup_keep: DEFAULT_AUTO_BACKUP_KEEP,
+        csv_options: CsvOptions::default(),
+        default_invoice_notes: "".to_string(),
+        legal_note_override: LegalNoteOverride::default(),
+        rounding_mode: RoundingMode::default(),
+        quote_prefix: default_quote_prefix(),
+        next_quote_number: default_next_quote_number(),
+        webhook_url: "".to_string(),
+        webhook_secret: "".to_string(),
+        webhook_secret_configured: false,
+        pdf_filename_template: default_pdf_filename_template(),
+        issued_by: None,
+        accountant_email: "".to_string(),
+        stale_draft_days: default_stale_draft_days(),
+        overdue_grace_days: 0,
+        custom_field_labels: Vec::new(),
+        email_max_message_mb: default_email_max_message_mb(),
+        email_theme: EmailTheme::default(),
     }
 }
 
-fn format_invoice_number(prefix: &str, next: i64) -> String {
-    format!("{}-{:0>4}", prefix, next)
+fn default_stale_draft_days() -> i64 {
+    status_suggestions::DEFAULT_STALE_DRAFT_DAYS
 }
 
-fn sqlite_error_string(err: &rusqlite::Error) -> String {
-    match err {
-        rusqlite::Error::SqliteFailure(code, msg) => {
-            let message = msg.clone().unwrap_or_else(|| "".to_string());
-            format!(
-                "sqlite(code={:?}, extended_code={}, msg={})",
-                code.code, code.extended_code, message
-            )
-        }
-        other => other.to_string(),
-    }
+fn default_email_max_message_mb() -> i64 {
+    DEFAULT_EMAIL_MAX_MESSAGE_MB
 }
 
-fn resolve_db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let mut candidates: Vec<PathBuf> = Vec::new();
+fn default_pdf_filename_template() -> String {
+    pdf_filename::DEFAULT_PDF_FILENAME_TEMPLATE.to_string()
+}
 
-    if let Ok(dir) = app.path().app_data_dir() {
-        candidates.push(dir.join("pausaler.db"));
-    }
-    if let Ok(dir) = app.path().app_local_data_dir() {
-        candidates.push(dir.join("pausaler.db"));
-    }
-    if let Ok(exe) = std::env::current_exe() {
-        if let Some(dir) = exe.parent() {
-            candidates.push(dir.join("pausaler.db"));
-        }
+fn default_quote_prefix() -> String {
+    "PON".to_string()
+}
+
+fn default_next_quote_number() -> i64 {
+    1
+}
+
+fn validate_limit_rsd(label: &str, v: f64) -> Result<f64, String> {
+    if !v.is_finite() || v <= 0.0 {
+        return Err(format!("{label} must be greater than 0."));
     }
-    if let Ok(cwd) = std::env::current_dir() {
-        candidates.push(cwd.join("pausaler.db"));
+    Ok(v)
+}
+
+fn validate_auto_backup_keep(v: i64) -> Result<i64, String> {
+    if !(1..=MAX_AUTO_BACKUP_KEEP).contains(&v) {
+        return Err(format!("Number of backups to keep must be between 1 and {MAX_AUTO_BACKUP_KEEP}."));
     }
+    Ok(v)
+}
+
+fn format_invoice_number(prefix: &str, next: i64) -> String {
+    format!("{}-{:0>4}", prefix, next)
+}
+
+/// Wraps a validation message so it can be returned from inside a `with_write` closure.
+fn validation_to_sql_error(message: String) -> rusqlite::Error {
+    AppError::invalid(message).into()
+}
 
-    for p in &candidates {
-        if p.exists() {
-            return Ok(p.clone());
+/// Logs a failed `with_read`/`with_write` closure; errors it returned reach the command unchanged.
+fn db_operation_error(op_name: &'static str, err: rusqlite::Error) -> AppError {
+    let err = AppError::from(err);
+    tracing::error!(target: "sqlite", op = op_name, error = %err, "database operation failed");
+    err
+}
+
+fn sqlite_error_string(err: &rusqlite::Error) -> String {
+    match err {
+        rusqlite::Error::SqliteFailure(code, msg) => {
+            let message = msg.clone().unwrap_or_else(|| "".to_string());
+            format!(
+                "sqlite(code={:?}, extended_code={}, msg={})",
+                code.code, code.extended_code, message
+            )
         }
+        other => other.to_string(),
     }
+}
 
-    candidates
-        .into_iter()
-        .next()
-        .ok_or_else(|| "Unable to resolve database path".to_string())
+fn resolve_db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    db_location::resolve(app).map(|loc| loc.path)
 }
 
 fn remove_if_exists(path: &std::path::Path) -> std::io::Result<()> {
@@ -2487,16 +3358,26 @@ fn shm_path(db_path: &std::path::Path) -> PathBuf {
     db_path.with_file_name(format!("{}-shm", name))
 }
 
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
 fn configure_sqlite(conn: &Connection) -> Result<(), rusqlite::Error> {
     // Apply PRAGMAs on init (outside any transaction).
     conn.execute_batch(
         "PRAGMA journal_mode = WAL;\n\
-         PRAGMA synchronous = NORMAL;\n\
-         PRAGMA foreign_keys = ON;\n\
+         PRAGMA synchronous = NORMAL;\n",
+    )?;
+    configure_connection(conn)
+}
+
+/// The per-connection PRAGMAs, also applied to the read-only connections of `read_pool`; the
+/// journal mode belongs to the file and is set by the writer.
+fn configure_connection(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;\n\
          PRAGMA temp_store = MEMORY;\n\
          PRAGMA busy_timeout = 5000;\n",
     )?;
-    conn.busy_timeout(Duration::from_millis(5000))?;
+    conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
     Ok(())
 }
 
@@ -2533,6 +3414,43 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             smtpFrom TEXT NOT NULL DEFAULT '',
             smtpUseTls INTEGER NOT NULL DEFAULT 1,
             smtpTlsMode TEXT NOT NULL DEFAULT '',
+            smtpTimeoutSeconds INTEGER NOT NULL DEFAULT 30,
+            smtpReplyTo TEXT NOT NULL DEFAULT '',
+            smtpFromName TEXT NOT NULL DEFAULT '',
+            sendCopyToSelf INTEGER NOT NULL DEFAULT 0,
+            smtpAuthMode TEXT NOT NULL DEFAULT 'password',
+            smtpOauthClientId TEXT NOT NULL DEFAULT '',
+            smtpOauthClientSecret TEXT NOT NULL DEFAULT '',
+            smtpOauthRefreshToken TEXT NOT NULL DEFAULT '',
+            smtpOauthTokenEndpoint TEXT NOT NULL DEFAULT '',
+            smtpAcceptInvalidCerts INTEGER NOT NULL DEFAULT 0,
+            smtpAcceptInvalidCertsAcknowledged INTEGER NOT NULL DEFAULT 0,
+            smtpPinnedCertPem TEXT NOT NULL DEFAULT '',
+            turnoverLimitRsd REAL NOT NULL DEFAULT 6000000,
+            vatLimitRsd REAL NOT NULL DEFAULT 8000000,
+            autoBackupEnabled INTEGER NOT NULL DEFAULT 1,
+            autoBackupKeep INTEGER NOT NULL DEFAULT 7,
+            csvDelimiter TEXT NOT NULL DEFAULT ',',
+            csvDecimalComma INTEGER NOT NULL DEFAULT 0,
+            csvIncludeBom INTEGER NOT NULL DEFAULT 0,
+            csvLineEnding TEXT NOT NULL DEFAULT 'crlf',
+            defaultInvoiceNotes TEXT NOT NULL DEFAULT '',
+            legalNoteOverrideSr TEXT NOT NULL DEFAULT '',
+            legalNoteOverrideEn TEXT NOT NULL DEFAULT '',
+            roundingMode TEXT NOT NULL DEFAULT 'halfUp',
+            quotePrefix TEXT NOT NULL DEFAULT 'PON',
+            nextQuoteNumber INTEGER NOT NULL DEFAULT 1,
+            webhookUrl TEXT NOT NULL DEFAULT '',
+            webhookSecret TEXT NOT NULL DEFAULT '',
+            bankAccounts TEXT NOT NULL DEFAULT '[]',
+            pdfFilenameTemplate TEXT NOT NULL DEFAULT '{number}-{client}',
+            issuedBy TEXT NOT NULL DEFAULT '',
+            accountantEmail TEXT NOT NULL DEFAULT '',
+            staleDraftDays INTEGER NOT NULL DEFAULT 14,
+            overdueGraceDays INTEGER NOT NULL DEFAULT 0,
+            customFieldLabels TEXT NOT NULL DEFAULT '[]',
+            emailMaxMessageMb INTEGER NOT NULL DEFAULT 15,
+            emailTheme TEXT NOT NULL DEFAULT '{}',
             data_json TEXT NOT NULL,
             updatedAt TEXT NOT NULL
         );
@@ -2545,6 +3463,14 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             address TEXT NOT NULL,
             email TEXT NOT NULL,
             phone TEXT,
+            website TEXT,
+            defaultCurrency TEXT,
+            preferredLanguage TEXT,
+            paymentTermsDays INTEGER,
+            billingEmail TEXT,
+            archived INTEGER NOT NULL DEFAULT 0,
+            isForeign INTEGER NOT NULL DEFAULT 0,
+            country TEXT,
             createdAt TEXT NOT NULL,
             data_json TEXT
         );
@@ -2563,6 +3489,17 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             data_json TEXT NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS invoice_payments (
+            id TEXT PRIMARY KEY NOT NULL,
+            invoiceId TEXT NOT NULL,
+            amount REAL NOT NULL,
+            currency TEXT NOT NULL,
+            date TEXT NOT NULL,
+            reference TEXT,
+            payer TEXT,
+            createdAt TEXT NOT NULL
+        );
+
         CREATE TABLE IF NOT EXISTS expenses (
             id TEXT PRIMARY KEY NOT NULL,
             title TEXT NOT NULL,
@@ -2571,9 +3508,82 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             date TEXT NOT NULL,
             category TEXT,
             notes TEXT,
+            createdAt TEXT NOT NULL,
+            recurringId TEXT,
+            deletedAt TEXT,
+            invoiceId TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS recurring_expenses (
+            id TEXT PRIMARY KEY NOT NULL,
+            title TEXT NOT NULL,
+            amount REAL NOT NULL,
+            currency TEXT NOT NULL,
+            category TEXT,
+            notes TEXT,
+            interval TEXT NOT NULL,
+            startDate TEXT NOT NULL,
+            endDate TEXT,
+            nextRunDate TEXT NOT NULL,
             createdAt TEXT NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS catalog_items (
+            id TEXT PRIMARY KEY NOT NULL,
+            name TEXT NOT NULL,
+            unit TEXT,
+            unitPrice REAL NOT NULL,
+            vatRate REAL,
+            active INTEGER NOT NULL DEFAULT 1,
+            createdAt TEXT NOT NULL,
+            updatedAt TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS time_entries (
+            id TEXT PRIMARY KEY NOT NULL,
+            clientId TEXT NOT NULL,
+            date TEXT NOT NULL,
+            minutes INTEGER NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            hourlyRate REAL NOT NULL,
+            billedInvoiceId TEXT,
+            createdAt TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS quotes (
+            id TEXT PRIMARY KEY NOT NULL,
+            quoteNumber TEXT NOT NULL,
+            clientId TEXT NOT NULL,
+            issueDate TEXT NOT NULL,
+            validUntil TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'DRAFT',
+            currency TEXT NOT NULL,
+            totalAmount REAL NOT NULL,
+            invoiceId TEXT,
+            createdAt TEXT NOT NULL,
+            data_json TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS obligations (
+            id TEXT PRIMARY KEY NOT NULL,
+            validFrom TEXT NOT NULL,
+            validTo TEXT,
+            tax REAL NOT NULL,
+            pension REAL NOT NULL,
+            health REAL NOT NULL,
+            unemployment REAL NOT NULL,
+            notes TEXT,
+            createdAt TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS obligation_months (
+            month TEXT PRIMARY KEY NOT NULL,
+            obligationId TEXT NOT NULL,
+            total REAL NOT NULL,
+            generatedAt TEXT NOT NULL,
+            paidAt TEXT
+        );
+
         CREATE TABLE IF NOT EXISTS offers (
             id TEXT PRIMARY KEY NOT NULL,
             clientEmail TEXT NOT NULL,
@@ -2590,13 +3600,60 @@ fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             data_json TEXT NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS email_outbox (
+            id TEXT PRIMARY KEY NOT NULL,
+            invoiceId TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'QUEUED',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            createdAt TEXT NOT NULL,
+            updatedAt TEXT NOT NULL,
+            sentAt TEXT,
+            data_json TEXT NOT NULL,
+            selfCopy INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id TEXT PRIMARY KEY NOT NULL,
+            event TEXT NOT NULL,
+            invoiceId TEXT,
+            status TEXT NOT NULL DEFAULT 'PENDING',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            nextAttemptAt INTEGER NOT NULL,
+            responseStatus INTEGER,
+            error TEXT,
+            createdAt TEXT NOT NULL,
+            deliveredAt TEXT,
+            payload TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS idempotency (
+            scope TEXT NOT NULL,
+            key TEXT NOT NULL,
+            entityId TEXT NOT NULL,
+            createdAt INTEGER NOT NULL,
+            PRIMARY KEY (scope, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            createdAt TEXT NOT NULL,
+            action TEXT NOT NULL,
+            entityId TEXT NOT NULL,
+            data_json TEXT NOT NULL
+        );
+
         CREATE INDEX IF NOT EXISTS idx_invoices_invoiceNumber ON invoices(invoiceNumber);
         CREATE INDEX IF NOT EXISTS idx_invoices_clientId ON invoices(clientId);
+        CREATE INDEX IF NOT EXISTS idx_invoice_payments_invoiceId ON invoice_payments(invoiceId);
+        CREATE INDEX IF NOT EXISTS idx_time_entries_clientId ON time_entries(clientId);
+        CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_status ON webhook_deliveries(status, nextAttemptAt);
         CREATE INDEX IF NOT EXISTS idx_clients_name ON clients(name);
         CREATE INDEX IF NOT EXISTS idx_expenses_date ON expenses(date);
         CREATE INDEX IF NOT EXISTS idx_offers_createdAt ON offers(createdAt);
         CREATE INDEX IF NOT EXISTS idx_offers_status ON offers(status);
         CREATE INDEX IF NOT EXISTS idx_offers_clientEmail ON offers(clientEmail);
+        CREATE INDEX IF NOT EXISTS idx_email_outbox_status ON email_outbox(status, createdAt);
         "#,
     )?;
     Ok(())
@@ -2619,120 +3676,595 @@ fn app_meta_set(conn: &Connection, key: &str, value: &str) -> Result<(), rusqlit
     Ok(())
 }
 
-fn apply_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let mut v: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+/// Newest schema version this build knows; databases above it are refused rather than guessed at.
+const SCHEMA_VERSION: i64 = 46;
 
-    if v > 0 && v < 2 {
-        conn.execute_batch("PRAGMA user_version = 2;")?;
-        v = 2;
+fn schema_version(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+}
+
+/// Fails with a readable message when the file was written by a newer app version.
+fn check_schema_version(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    let v = schema_version(conn)?;
+    if v > SCHEMA_VERSION {
+        return Err(validation_to_sql_error(format!(
+            "This database was created by a newer version of Pausaler (schema v{v}; this version supports up to v{SCHEMA_VERSION}). Update the app or restore a backup made with this version."
+        )));
     }
+    Ok(v)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        params![table, column],
+        |r| r.get(0),
+    )
+}
+
+/// `ALTER TABLE .. ADD COLUMN` that is a no-op when the column is already there (tables created
+/// by `init_schema` already have every column, and a step may be re-run after a failed start).
+fn add_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<(), rusqlite::Error> {
+    if !column_exists(conn, table, column)? {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl};"))?;
+    }
+    Ok(())
+}
+
+/// Runs one step and bumps `user_version` in the same transaction, so a failing step leaves the
+/// database exactly at the previous version.
+fn migration_step<F>(conn: &Connection, version: i64, f: F) -> Result<(), rusqlite::Error>
+where
+    F: FnOnce(&Connection) -> Result<(), rusqlite::Error>,
+{
+    let tx = conn.unchecked_transaction()?;
+    f(&tx)?;
+    tx.execute_batch(&format!("PRAGMA user_version = {version};"))?;
+    tx.commit()
+}
+
+/// Copies the database to `pausaler.pre-migrate-v{from}.db` next to it before the first pending
+/// step. An existing snapshot for the same version is kept: it predates any failed attempt.
+fn snapshot_before_migration(conn: &Connection, from: i64) -> Result<(), rusqlite::Error> {
+    let Some(db_path) = conn.path().filter(|p| !p.is_empty()).map(PathBuf::from) else {
+        return Ok(());
+    };
+    let stem = db_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "pausaler".to_string());
+    let target = db_path.with_file_name(format!("{stem}.pre-migrate-v{from}.db"));
+    if target.exists() {
+        return Ok(());
+    }
+    conn.execute("VACUUM INTO ?1", params![target.to_string_lossy()])?;
+    println!("Migration: snapshot v{} -> {}", from, target.display());
+    Ok(())
+}
+
+fn apply_migrations(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let mut v = check_schema_version(conn)?;
 
     if v == 0 {
-        conn.execute_batch("PRAGMA user_version = 9;")?;
+        return migration_step(conn, SCHEMA_VERSION, |c| c.execute_batch(search::SEARCH_INDEX_SQL));
+    }
+    if v == SCHEMA_VERSION {
         return Ok(());
     }
+    snapshot_before_migration(conn, v)?;
+
+    if v < 2 {
+        migration_step(conn, 2, |_| Ok(()))?;
+        v = 2;
+    }
 
     if v < 3 {
-        conn.execute_batch(
-            "ALTER TABLE invoices ADD COLUMN status TEXT NOT NULL DEFAULT 'DRAFT';\n\
-             ALTER TABLE invoices ADD COLUMN dueDate TEXT;\n\
-             ALTER TABLE invoices ADD COLUMN paidAt TEXT;\n\
-             PRAGMA user_version = 3;\n",
-        )?;
+        migration_step(conn, 3, |c| {
+            add_column(c, "invoices", "status", "TEXT NOT NULL DEFAULT 'DRAFT'")?;
+            add_column(c, "invoices", "dueDate", "TEXT")?;
+            add_column(c, "invoices", "paidAt", "TEXT")
+        })?;
         v = 3;
     }
 
     if v < 4 {
-        conn.execute_batch(
-            "ALTER TABLE settings ADD COLUMN smtpHost TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN smtpPort INTEGER NOT NULL DEFAULT 587;\n\
-             ALTER TABLE settings ADD COLUMN smtpUser TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN smtpPassword TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN smtpFrom TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN smtpUseTls INTEGER NOT NULL DEFAULT 1;\n\
-             PRAGMA user_version = 4;\n",
-        )?;
+        migration_step(conn, 4, |c| {
+            add_column(c, "settings", "smtpHost", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpPort", "INTEGER NOT NULL DEFAULT 587")?;
+            add_column(c, "settings", "smtpUser", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpPassword", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpFrom", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpUseTls", "INTEGER NOT NULL DEFAULT 1")
+        })?;
         v = 4;
     }
 
     if v < 5 {
-        conn.execute_batch(
-            "ALTER TABLE settings ADD COLUMN smtpTlsMode TEXT NOT NULL DEFAULT '';\n\
-             PRAGMA user_version = 5;\n",
-        )?;
+        migration_step(conn, 5, |c| add_column(c, "settings", "smtpTlsMode", "TEXT NOT NULL DEFAULT ''"))?;
         v = 5;
     }
 
     if v < 6 {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS expenses (\n\
-                id TEXT PRIMARY KEY NOT NULL,\n\
-                title TEXT NOT NULL,\n\
-                amount REAL NOT NULL,\n\
-                currency TEXT NOT NULL,\n\
-                date TEXT NOT NULL,\n\
-                category TEXT,\n\
-                notes TEXT,\n\
-                createdAt TEXT NOT NULL\n\
-            );\n\
-             CREATE INDEX IF NOT EXISTS idx_expenses_date ON expenses(date);\n\
-             PRAGMA user_version = 6;\n",
-        )?;
+        migration_step(conn, 6, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS expenses (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    title TEXT NOT NULL,\n\
+                    amount REAL NOT NULL,\n\
+                    currency TEXT NOT NULL,\n\
+                    date TEXT NOT NULL,\n\
+                    category TEXT,\n\
+                    notes TEXT,\n\
+                    createdAt TEXT NOT NULL\n\
+                );\n\
+                 CREATE INDEX IF NOT EXISTS idx_expenses_date ON expenses(date);\n",
+            )
+        })?;
         v = 6;
     }
 
     if v < 7 {
-        conn.execute_batch(
-            "ALTER TABLE settings ADD COLUMN maticniBroj TEXT;\n\
-             ALTER TABLE clients ADD COLUMN maticniBroj TEXT;\n\
-             PRAGMA user_version = 7;\n",
-        )?;
+        migration_step(conn, 7, |c| {
+            add_column(c, "settings", "maticniBroj", "TEXT")?;
+            add_column(c, "clients", "maticniBroj", "TEXT")
+        })?;
         v = 7;
     }
 
     if v < 8 {
-        conn.execute_batch(
-            "ALTER TABLE settings ADD COLUMN companyAddressLine TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN companyCity TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN companyPostalCode TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN companyEmail TEXT NOT NULL DEFAULT '';\n\
-             ALTER TABLE settings ADD COLUMN companyPhone TEXT NOT NULL DEFAULT '';\n\
-             UPDATE settings SET companyAddressLine = CASE\n\
-                 WHEN TRIM(COALESCE(companyAddressLine,'')) = '' THEN COALESCE(address,'')\n\
-                 ELSE companyAddressLine\n\
-             END;\n\
-             PRAGMA user_version = 8;\n",
-        )?;
+        migration_step(conn, 8, |c| {
+            add_column(c, "settings", "companyAddressLine", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "companyCity", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "companyPostalCode", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "companyEmail", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "companyPhone", "TEXT NOT NULL DEFAULT ''")?;
+            c.execute_batch(
+                "UPDATE settings SET companyAddressLine = CASE\n\
+                     WHEN TRIM(COALESCE(companyAddressLine,'')) = '' THEN COALESCE(address,'')\n\
+                     ELSE companyAddressLine\n\
+                 END;\n",
+            )
+        })?;
         v = 8;
     }
 
     if v < 9 {
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS offers (\n\
-                id TEXT PRIMARY KEY NOT NULL,\n\
-                clientEmail TEXT NOT NULL,\n\
-                clientName TEXT NOT NULL,\n\
-                subject TEXT NOT NULL,\n\
-                body TEXT NOT NULL,\n\
-                amount REAL NOT NULL,\n\
-                currency TEXT NOT NULL,\n\
-                validUntil TEXT NOT NULL,\n\
-                status TEXT NOT NULL DEFAULT 'DRAFT',\n\
-                createdAt TEXT NOT NULL,\n\
-                sentAt TEXT,\n\
-                failedReason TEXT,\n\
-                data_json TEXT NOT NULL\n\
-            );\n\
-             CREATE INDEX IF NOT EXISTS idx_offers_createdAt ON offers(createdAt);\n\
-             CREATE INDEX IF NOT EXISTS idx_offers_status ON offers(status);\n\
-             CREATE INDEX IF NOT EXISTS idx_offers_clientEmail ON offers(clientEmail);\n\
-             PRAGMA user_version = 9;\n",
-        )?;
+        migration_step(conn, 9, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS offers (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    clientEmail TEXT NOT NULL,\n\
+                    clientName TEXT NOT NULL,\n\
+                    subject TEXT NOT NULL,\n\
+                    body TEXT NOT NULL,\n\
+                    amount REAL NOT NULL,\n\
+                    currency TEXT NOT NULL,\n\
+                    validUntil TEXT NOT NULL,\n\
+                    status TEXT NOT NULL DEFAULT 'DRAFT',\n\
+                    createdAt TEXT NOT NULL,\n\
+                    sentAt TEXT,\n\
+                    failedReason TEXT,\n\
+                    data_json TEXT NOT NULL\n\
+                );\n\
+                 CREATE INDEX IF NOT EXISTS idx_offers_createdAt ON offers(createdAt);\n\
+                 CREATE INDEX IF NOT EXISTS idx_offers_status ON offers(status);\n\
+                 CREATE INDEX IF NOT EXISTS idx_offers_clientEmail ON offers(clientEmail);\n",
+            )
+        })?;
+        v = 9;
+    }
+
+    if v < 10 {
+        migration_step(conn, 10, |c| {
+            add_column(c, "settings", "smtpTimeoutSeconds", "INTEGER NOT NULL DEFAULT 30")
+        })?;
+        v = 10;
+    }
+
+    if v < 11 {
+        migration_step(conn, 11, |c| {
+            add_column(c, "settings", "smtpReplyTo", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpFromName", "TEXT NOT NULL DEFAULT ''")
+        })?;
+        v = 11;
+    }
+
+    if v < 12 {
+        migration_step(conn, 12, |c| add_column(c, "settings", "sendCopyToSelf", "INTEGER NOT NULL DEFAULT 0"))?;
+        v = 12;
+    }
+
+    if v < 13 {
+        migration_step(conn, 13, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS email_outbox (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    invoiceId TEXT NOT NULL,\n\
+                    status TEXT NOT NULL DEFAULT 'QUEUED',\n\
+                    attempts INTEGER NOT NULL DEFAULT 0,\n\
+                    error TEXT,\n\
+                    createdAt TEXT NOT NULL,\n\
+                    updatedAt TEXT NOT NULL,\n\
+                    sentAt TEXT,\n\
+                    data_json TEXT NOT NULL\n\
+                );\n\
+                 CREATE INDEX IF NOT EXISTS idx_email_outbox_status ON email_outbox(status, createdAt);\n",
+            )
+        })?;
+        v = 13;
     }
 
+    if v < 14 {
+        migration_step(conn, 14, |c| {
+            add_column(c, "settings", "smtpAuthMode", "TEXT NOT NULL DEFAULT 'password'")?;
+            add_column(c, "settings", "smtpOauthClientId", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpOauthClientSecret", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpOauthRefreshToken", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "smtpOauthTokenEndpoint", "TEXT NOT NULL DEFAULT ''")
+        })?;
+        v = 14;
+    }
+
+    if v < 15 {
+        migration_step(conn, 15, |c| {
+            add_column(c, "settings", "smtpAcceptInvalidCerts", "INTEGER NOT NULL DEFAULT 0")?;
+            add_column(c, "settings", "smtpAcceptInvalidCertsAcknowledged", "INTEGER NOT NULL DEFAULT 0")?;
+            add_column(c, "settings", "smtpPinnedCertPem", "TEXT NOT NULL DEFAULT ''")
+        })?;
+        v = 15;
+    }
+
+    if v < 16 {
+        // Older rows only have the phone in its column; copy it into data_json so `Client` sees it.
+        migration_step(conn, 16, |c| {
+            add_column(c, "clients", "website", "TEXT")?;
+            c.execute_batch(
+                "UPDATE clients SET data_json = json_set(data_json, '$.phone', TRIM(phone))\n\
+                  WHERE TRIM(COALESCE(phone, '')) <> ''\n\
+                    AND json_valid(data_json)\n\
+                    AND json_extract(data_json, '$.phone') IS NULL;\n",
+            )
+        })?;
+        v = 16;
+    }
+
+    if v < 17 {
+        migration_step(conn, 17, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS audit_log (\n\
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,\n\
+                    createdAt TEXT NOT NULL,\n\
+                    action TEXT NOT NULL,\n\
+                    entityId TEXT NOT NULL,\n\
+                    data_json TEXT NOT NULL\n\
+                );\n",
+            )
+        })?;
+        v = 17;
+    }
+
+    if v < 18 {
+        migration_step(conn, 18, |c| {
+            add_column(c, "clients", "defaultCurrency", "TEXT")?;
+            add_column(c, "clients", "preferredLanguage", "TEXT")?;
+            add_column(c, "clients", "paymentTermsDays", "INTEGER")?;
+            add_column(c, "clients", "billingEmail", "TEXT")
+        })?;
+        v = 18;
+    }
+
+    if v < 19 {
+        migration_step(conn, 19, |c| add_column(c, "clients", "archived", "INTEGER NOT NULL DEFAULT 0"))?;
+        v = 19;
+    }
+
+    if v < 20 {
+        migration_step(conn, 20, |c| {
+            add_column(c, "clients", "isForeign", "INTEGER NOT NULL DEFAULT 0")?;
+            add_column(c, "clients", "country", "TEXT")
+        })?;
+        v = 20;
+    }
+
+    if v < 21 {
+        migration_step(conn, 21, |c| {
+            add_column(c, "expenses", "recurringId", "TEXT")?;
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS recurring_expenses (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    title TEXT NOT NULL,\n\
+                    amount REAL NOT NULL,\n\
+                    currency TEXT NOT NULL,\n\
+                    category TEXT,\n\
+                    notes TEXT,\n\
+                    interval TEXT NOT NULL,\n\
+                    startDate TEXT NOT NULL,\n\
+                    endDate TEXT,\n\
+                    nextRunDate TEXT NOT NULL,\n\
+                    createdAt TEXT NOT NULL\n\
+                );\n",
+            )
+        })?;
+        v = 21;
+    }
+
+    if v < 22 {
+        migration_step(conn, 22, |c| add_column(c, "expenses", "deletedAt", "TEXT"))?;
+        v = 22;
+    }
+
+    if v < 23 {
+        migration_step(conn, 23, |c| add_column(c, "expenses", "invoiceId", "TEXT"))?;
+        v = 23;
+    }
+
+    if v < 24 {
+        migration_step(conn, 24, |c| {
+            add_column(c, "settings", "turnoverLimitRsd", "REAL NOT NULL DEFAULT 6000000")?;
+            add_column(c, "settings", "vatLimitRsd", "REAL NOT NULL DEFAULT 8000000")
+        })?;
+        v = 24;
+    }
+
+    if v < 25 {
+        migration_step(conn, 25, |c| {
+            add_column(c, "settings", "autoBackupEnabled", "INTEGER NOT NULL DEFAULT 1")?;
+            add_column(c, "settings", "autoBackupKeep", "INTEGER NOT NULL DEFAULT 7")
+        })?;
+        v = 25;
+    }
+
+    if v < 26 {
+        migration_step(conn, 26, |c| {
+            add_column(c, "settings", "csvDelimiter", "TEXT NOT NULL DEFAULT ','")?;
+            add_column(c, "settings", "csvDecimalComma", "INTEGER NOT NULL DEFAULT 0")?;
+            add_column(c, "settings", "csvIncludeBom", "INTEGER NOT NULL DEFAULT 0")?;
+            add_column(c, "settings", "csvLineEnding", "TEXT NOT NULL DEFAULT 'crlf'")
+        })?;
+        v = 26;
+    }
+
+    if v < 27 {
+        migration_step(conn, 27, |c| {
+            c.execute_batch(search::SEARCH_INDEX_SQL)?;
+            c.execute_batch(search::SEARCH_BACKFILL_SQL)
+        })?;
+        v = 27;
+    }
+
+    if v < 28 {
+        migration_step(conn, 28, |c| {
+            add_column(c, "settings", "defaultInvoiceNotes", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "legalNoteOverrideSr", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "legalNoteOverrideEn", "TEXT NOT NULL DEFAULT ''")
+        })?;
+        v = 28;
+    }
+
+    if v < 29 {
+        migration_step(conn, 29, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS obligations (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    validFrom TEXT NOT NULL,\n\
+                    validTo TEXT,\n\
+                    tax REAL NOT NULL,\n\
+                    pension REAL NOT NULL,\n\
+                    health REAL NOT NULL,\n\
+                    unemployment REAL NOT NULL,\n\
+                    notes TEXT,\n\
+                    createdAt TEXT NOT NULL\n\
+                );\n\
+                CREATE TABLE IF NOT EXISTS obligation_months (\n\
+                    month TEXT PRIMARY KEY NOT NULL,\n\
+                    obligationId TEXT NOT NULL,\n\
+                    total REAL NOT NULL,\n\
+                    generatedAt TEXT NOT NULL,\n\
+                    paidAt TEXT\n\
+                );\n",
+            )
+        })?;
+        v = 29;
+    }
+
+    if v < 30 {
+        migration_step(conn, 30, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS invoice_payments (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    invoiceId TEXT NOT NULL,\n\
+                    amount REAL NOT NULL,\n\
+                    currency TEXT NOT NULL,\n\
+                    date TEXT NOT NULL,\n\
+                    reference TEXT,\n\
+                    payer TEXT,\n\
+                    createdAt TEXT NOT NULL\n\
+                );\n\
+                CREATE INDEX IF NOT EXISTS idx_invoice_payments_invoiceId ON invoice_payments(invoiceId);\n",
+            )
+        })?;
+        v = 30;
+    }
+
+    if v < 31 {
+        migration_step(conn, 31, |c| add_column(c, "settings", "roundingMode", "TEXT NOT NULL DEFAULT 'halfUp'"))?;
+        v = 31;
+    }
+
+    if v < 32 {
+        migration_step(conn, 32, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS catalog_items (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    name TEXT NOT NULL,\n\
+                    unit TEXT,\n\
+                    unitPrice REAL NOT NULL,\n\
+                    vatRate REAL,\n\
+                    active INTEGER NOT NULL DEFAULT 1,\n\
+                    createdAt TEXT NOT NULL,\n\
+                    updatedAt TEXT NOT NULL\n\
+                );\n",
+            )
+        })?;
+        v = 32;
+    }
+
+    if v < 33 {
+        migration_step(conn, 33, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS time_entries (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    clientId TEXT NOT NULL,\n\
+                    date TEXT NOT NULL,\n\
+                    minutes INTEGER NOT NULL,\n\
+                    description TEXT NOT NULL DEFAULT '',\n\
+                    hourlyRate REAL NOT NULL,\n\
+                    billedInvoiceId TEXT,\n\
+                    createdAt TEXT NOT NULL\n\
+                );\n\
+                CREATE INDEX IF NOT EXISTS idx_time_entries_clientId ON time_entries(clientId);\n",
+            )
+        })?;
+        v = 33;
+    }
+
+    if v < 34 {
+        migration_step(conn, 34, |c| {
+            add_column(c, "settings", "quotePrefix", "TEXT NOT NULL DEFAULT 'PON'")?;
+            add_column(c, "settings", "nextQuoteNumber", "INTEGER NOT NULL DEFAULT 1")?;
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS quotes (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    quoteNumber TEXT NOT NULL,\n\
+                    clientId TEXT NOT NULL,\n\
+                    issueDate TEXT NOT NULL,\n\
+                    validUntil TEXT NOT NULL,\n\
+                    status TEXT NOT NULL DEFAULT 'DRAFT',\n\
+                    currency TEXT NOT NULL,\n\
+                    totalAmount REAL NOT NULL,\n\
+                    invoiceId TEXT,\n\
+                    createdAt TEXT NOT NULL,\n\
+                    data_json TEXT NOT NULL\n\
+                );\n",
+            )
+        })?;
+        v = 34;
+    }
+
+    if v < 35 {
+        migration_step(conn, 35, |c| {
+            add_column(c, "settings", "webhookUrl", "TEXT NOT NULL DEFAULT ''")?;
+            add_column(c, "settings", "webhookSecret", "TEXT NOT NULL DEFAULT ''")?;
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS webhook_deliveries (\n\
+                    id TEXT PRIMARY KEY NOT NULL,\n\
+                    event TEXT NOT NULL,\n\
+                    invoiceId TEXT,\n\
+                    status TEXT NOT NULL DEFAULT 'PENDING',\n\
+                    attempts INTEGER NOT NULL DEFAULT 0,\n\
+                    nextAttemptAt INTEGER NOT NULL,\n\
+                    responseStatus INTEGER,\n\
+                    error TEXT,\n\
+                    createdAt TEXT NOT NULL,\n\
+                    deliveredAt TEXT,\n\
+                    payload TEXT NOT NULL\n\
+                );\n\
+                CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_status ON webhook_deliveries(status, nextAttemptAt);\n",
+            )
+        })?;
+        v = 35;
+    }
+
+    if v < 36 {
+        migration_step(conn, 36, |c| {
+            c.execute_batch(
+                "CREATE TABLE IF NOT EXISTS idempotency (\n\
+                    scope TEXT NOT NULL,\n\
+                    key TEXT NOT NULL,\n\
+                    entityId TEXT NOT NULL,\n\
+                    createdAt INTEGER NOT NULL,\n\
+                    PRIMARY KEY (scope, key)\n\
+                );\n",
+            )
+        })?;
+        v = 36;
+    }
+
+    if v < 37 {
+        migration_step(conn, 37, |c| {
+            add_column(c, "settings", "bankAccounts", "TEXT NOT NULL DEFAULT '[]'")?;
+            // The single account becomes the default dinar account.
+            c.execute(
+                "UPDATE settings
+                 SET bankAccounts = json_array(json_object(
+                     'accountNumber', TRIM(bankAccount), 'currency', 'RSD', 'bankName', '',
+                     'iban', '', 'swift', '', 'isDefault', json('true')))
+                 WHERE TRIM(bankAccount) <> ''",
+                [],
+            )?;
+            Ok(())
+        })?;
+        v = 37;
+    }
+
+    if v < 38 {
+        migration_step(conn, 38, |c| {
+            add_column(c, "settings", "pdfFilenameTemplate", "TEXT NOT NULL DEFAULT '{number}-{client}'")
+        })?;
+        v = 38;
+    }
+
+    if v < 39 {
+        migration_step(conn, 39, |c| add_column(c, "settings", "issuedBy", "TEXT NOT NULL DEFAULT ''"))?;
+        v = 39;
+    }
+
+    if v < 40 {
+        migration_step(conn, 40, |c| add_column(c, "settings", "accountantEmail", "TEXT NOT NULL DEFAULT ''"))?;
+        v = 40;
+    }
+
+    if v < 41 {
+        migration_step(conn, 41, |c| {
+            add_column(c, "settings", "staleDraftDays", "INTEGER NOT NULL DEFAULT 14")?;
+            add_column(c, "settings", "overdueGraceDays", "INTEGER NOT NULL DEFAULT 0")
+        })?;
+        v = 41;
+    }
+
+    if v < 42 {
+        migration_step(conn, 42, |c| add_column(c, "settings", "customFieldLabels", "TEXT NOT NULL DEFAULT '[]'"))?;
+        v = 42;
+    }
+
+    if v < 43 {
+        migration_step(conn, 43, |c| add_column(c, "settings", "emailMaxMessageMb", "INTEGER NOT NULL DEFAULT 15"))?;
+        v = 43;
+    }
+
+    if v < 44 {
+        migration_step(conn, 44, |c| add_column(c, "settings", "emailTheme", "TEXT NOT NULL DEFAULT '{}'"))?;
+        v = 44;
+    }
+
+    if v < 45 {
+        migration_step(conn, 45, recompute_discounted_invoice_totals)?;
+        v = 45;
+    }
+
+    if v < 46 {
+        migration_step(conn, 46, |c| add_column(c, "email_outbox", "selfCopy", "INTEGER"))?;
+        v = 46;
+    }
+
+    debug_assert_eq!(v, SCHEMA_VERSION, "a migration step did not advance the version");
     Ok(())
 }
 
+fn bank_accounts_json(accounts: &[BankAccount]) -> String {
+    serde_json::to_string(accounts).unwrap_or_else(|_| "[]".to_string())
+}
+
 fn ensure_settings_row(conn: &Connection) -> Result<(), rusqlite::Error> {
     let count: i64 = conn
         .query_row(
@@ -2755,6 +4287,16 @@ fn ensure_settings_row(conn: &Connection) -> Result<(), rusqlite::Error> {
             bankAccount, logoUrl,
             invoicePrefix, nextInvoiceNumber, defaultCurrency, language,
             smtpHost, smtpPort, smtpUser, smtpPassword, smtpFrom, smtpUseTls, smtpTlsMode,
+            smtpTimeoutSeconds, smtpReplyTo, smtpFromName, sendCopyToSelf,
+            smtpAuthMode, smtpOauthClientId, smtpOauthClientSecret, smtpOauthRefreshToken, smtpOauthTokenEndpoint,
+            smtpAcceptInvalidCerts, smtpAcceptInvalidCertsAcknowledged, smtpPinnedCertPem,
+            turnoverLimitRsd, vatLimitRsd,
+            autoBackupEnabled, autoBackupKeep,
+            csvDelimiter, csvDecimalComma, csvIncludeBom, csvLineEnding,
+            defaultInvoiceNotes, legalNoteOverrideSr, legalNoteOverrideEn,
+            roundingMode, quotePrefix, nextQuoteNumber,
+            webhookUrl, webhookSecret, bankAccounts, pdfFilenameTemplate, issuedBy, accountantEmail,
+            staleDraftDays, overdueGraceDays, customFieldLabels, emailMaxMessageMb, emailTheme,
             data_json, updatedAt
         ) VALUES (
             ?1, ?2, ?3, ?4, ?5, ?6,
@@ -2762,6 +4304,16 @@ fn ensure_settings_row(conn: &Connection) -> Result<(), rusqlite::Error> {
             ?12, ?13,
             ?14, ?15, ?16, ?17,
             ?18, ?19, ?20, ?21, ?22, ?23, ?24,
+            ?27, ?28, ?29, ?30,
+            ?31, ?32, ?33, ?34, ?35,
+            ?36, ?37, ?38,
+            ?39, ?40,
+            ?41, ?42,
+            ?43, ?44, ?45, ?46,
+            ?47, ?48, ?49,
+            ?50, ?51, ?52,
+            ?53, ?54, ?55, ?56, ?57, ?58,
+            ?59, ?60, ?61, ?62, ?63,
             ?25, ?26
         )"#,
         params![
@@ -2791,15 +4343,136 @@ fn ensure_settings_row(conn: &Connection) -> Result<(), rusqlite::Error> {
             resolved_smtp_tls_mode(s.smtp_tls_mode, s.smtp_port).as_str(),
             data_json,
             now,
+            s.smtp_timeout_seconds,
+            s.smtp_reply_to,
+            s.smtp_from_name,
+            s.send_copy_to_self as i32,
+            s.smtp_auth_mode.as_str(),
+            s.smtp_oauth_client_id,
+            s.smtp_oauth_client_secret,
+            s.smtp_oauth_refresh_token,
+            s.smtp_oauth_token_endpoint,
+            s.smtp_accept_invalid_certs as i32,
+            s.smtp_accept_invalid_certs_acknowledged as i32,
+            s.smtp_pinned_cert_pem,
+            s.turnover_limit_rsd,
+            s.vat_limit_rsd,
+            s.auto_backup_enabled as i32,
+            s.auto_backup_keep,
+            s.csv_options.delimiter.to_string(),
+            s.csv_options.decimal_comma as i32,
+            s.csv_options.include_bom as i32,
+            s.csv_options.line_ending.as_str(),
+            s.default_invoice_notes,
+            s.legal_note_override.sr,
+            s.legal_note_override.en,
+            s.rounding_mode.as_str(),
+            s.quote_prefix,
+            s.next_quote_number,
+            s.webhook_url,
+            s.webhook_secret,
+            bank_accounts_json(&s.bank_accounts),
+            s.pdf_filename_template,
+            s.issued_by.as_deref().unwrap_or(""),
+            s.accountant_email,
+            s.stale_draft_days,
+            s.overdue_grace_days,
+            custom_fields::custom_field_labels_json(&s.custom_field_labels),
+            s.email_max_message_mb,
+            email_theme::email_theme_json(&s.email_theme),
         ],
     )?;
     Ok(())
 }
 
+/// Moves a plaintext SMTP password into the OS keyring and replaces it with the sentinel in both
+/// the `smtpPassword` column and `data_json`. Runs on every start until it succeeds.
+fn migrate_smtp_password_to_keyring(conn: &Connection) -> Result<(), String> {
+    let row: Option<(String, String)> = conn
+        .query_row(
+            "SELECT smtpPassword, data_json FROM settings WHERE id = ?1",
+            params![SETTINGS_ID],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((column, data_json)) = row else {
+        return Ok(());
+    };
+    let mut json: serde_json::Value = serde_json::from_str(&data_json).unwrap_or(serde_json::Value::Null);
+    let json_password = json
+        .get("smtpPassword")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    // The column is authoritative (see `read_settings_from_conn`); data_json may hold a stale copy.
+    let plaintext = if secrets::is_plaintext(&column) {
+        column.clone()
+    } else {
+        String::new()
+    };
+    if plaintext.is_empty() && !secrets::is_plaintext(&json_password) {
+        return Ok(());
+    }
+    if !plaintext.is_empty() {
+        secrets::store(secrets::SMTP_PASSWORD, &plaintext)?;
+    }
+
+    let new_value = if plaintext.is_empty() && column.is_empty() {
+        ""
+    } else {
+        secrets::KEYRING_SENTINEL
+    };
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert("smtpPassword".to_string(), serde_json::Value::String(new_value.to_string()));
+    }
+    let data_json = if json.is_object() { json.to_string() } else { data_json };
+    conn.execute(
+        "UPDATE settings SET smtpPassword = ?2, data_json = ?3 WHERE id = ?1",
+        params![SETTINGS_ID, new_value, data_json],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Startup bookkeeping that writes to the database. Skipped when starting read-only and run by
+/// `retry_writable` once writes work.
+fn run_startup_writes(app: &tauri::AppHandle, conn: &Connection) {
+    if let Err(e) = license_activation::record_clock(conn, OffsetDateTime::now_utc()) {
+        tracing::warn!(target: "license", error = %e, "failed to record the clock");
+    }
+    if let Err(e) = license_activation::notify_expiry(app, conn) {
+        tracing::warn!(target: "license", error = %e, "failed to check the license expiry");
+    }
+    if let Err(e) = trial::start_trial(conn) {
+        tracing::warn!(target: "license", error = %e, "failed to record the trial start");
+    }
+    if let Err(e) = migrate_smtp_password_to_keyring(conn) {
+        // Keep the plaintext password usable and retry on the next start.
+        eprintln!("[settings] SMTP password not moved to the OS credential store: {e}");
+    }
+    match resolve_app_data_root(app).and_then(|root| company_logo::migrate_logo_setting(conn, &root)) {
+        Ok(false) => {}
+        Ok(true) => tracing::info!(target: "settings", "company logo reference updated"),
+        // The data URL keeps working for PDFs; retry on the next start.
+        Err(e) => tracing::warn!(target: "settings", error = %e, "company logo not moved to a file"),
+    }
+    match outbox::fail_interrupted_jobs(conn) {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!(target: "outbox", count = n, "emails interrupted mid-send marked as failed"),
+        Err(e) => tracing::error!(target: "outbox", error = %e, "failed to check interrupted emails"),
+    }
+}
+
 #[derive(Clone)]
 struct DbState {
+    /// The writer; reads go through `readers`.
     conn: Arc<Mutex<Connection>>,
+    readers: Arc<read_pool::ReadPool>,
     write_lock: Arc<Mutex<()>>,
+    mode: Arc<app_mode::WriteMode>,
+    app: tauri::AppHandle,
 }
 
 impl DbState {
@@ -2809,61 +4482,137 @@ impl DbState {
             std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
 
-        let conn = Connection::open(path).map_err(|e| e.to_string())?;
+        let conn = Connection::open(&path).map_err(|e| e.to_string())?;
         configure_sqlite(&conn).map_err(|e| e.to_string())?;
-        init_schema(&conn).map_err(|e| e.to_string())?;
-        apply_migrations(&conn).map_err(|e| e.to_string())?;
-        ensure_settings_row(&conn).map_err(|e| e.to_string())?;
+        // Before `init_schema`: a newer file must not be touched at all.
+        let version = check_schema_version(&conn).map_err(|e| e.to_string())?;
+        let read_only = app_mode::probe_writable(&conn).map_err(|e| e.to_string())?;
+        match read_only {
+            None => {
+                init_schema(&conn).map_err(|e| e.to_string())?;
+                apply_migrations(&conn).map_err(|e| e.to_string())?;
+                ensure_settings_row(&conn).map_err(|e| e.to_string())?;
+            }
+            // Without writes nothing can be upgraded, so only a file at this version opens.
+            Some(reason) if version < SCHEMA_VERSION => {
+                return Err(format!(
+                    "{} The database has to be upgraded (schema v{version} to v{SCHEMA_VERSION}) before this version can open it.",
+                    AppError::ReadOnly(reason)
+                ));
+            }
+            Some(reason) => tracing::warn!(target: "sqlite", ?reason, "starting read-only"),
+        }
+        logging::restore_saved_level(&conn);
+        match read_settings_from_conn(&conn) {
+            Ok(settings) => errors::set_language(&settings.language),
+            Err(e) => tracing::warn!(target: "settings", error = %e, "failed to read the app language"),
+        }
+        if read_only.is_none() {
+            run_startup_writes(app, &conn);
+        }
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            // Only now, with the schema up to date, may readers open.
+            readers: Arc::new(read_pool::ReadPool::new(path, read_pool::READ_POOL_SIZE)),
             write_lock: Arc::new(Mutex::new(())),
+            mode: Arc::new(app_mode::WriteMode::new(read_only)),
+            app: app.clone(),
         })
     }
 
-    async fn with_read<T, F>(&self, op_name: &'static str, f: F) -> Result<T, String>
+    /// Runs `f` on a read-only connection from the pool, next to other reads and the writer.
+    /// When no reader can be opened (a read-only folder without the WAL index, say), it falls
+    /// back to the writer connection.
+    async fn with_read<T, F>(&self, op_name: &'static str, f: F) -> Result<T, AppError>
     where
         T: Send + 'static,
         F: FnOnce(&Connection) -> Result<T, rusqlite::Error> + Send + 'static,
     {
         let conn = self.conn.clone();
+        let readers = self.readers.clone();
         tauri::async_runtime::spawn_blocking(move || {
-            let guard = conn.lock().map_err(|_| "db mutex poisoned".to_string())?;
-            f(&guard).map_err(|e| {
-                let msg = sqlite_error_string(&e);
-                eprintln!("[sqlite] {{ op: {:?}, error: {:?} }}", op_name, msg);
-                msg
-            })
+            let result = match readers.get() {
+                Ok(reader) => f(&reader),
+                Err(e) => {
+                    tracing::warn!(target: "sqlite", op = op_name, error = %e, "no read connection; using the writer");
+                    let guard = conn.lock().map_err(|_| AppError::Database("db mutex poisoned".to_string()))?;
+                    f(&guard)
+                }
+            };
+            result.map_err(|e| db_operation_error(op_name, e))
         })
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(|e| AppError::Other(e.to_string()))?
     }
 
-    async fn with_write<T, F>(&self, op_name: &'static str, f: F) -> Result<T, String>
+    /// Serialized write. Commands that change invoices, clients, expenses or settings use
+    /// `with_write_notify` instead so open windows hear about it. Fails fast in read-only mode, and
+    /// a busy or read-only database switches into it.
+    async fn with_write<T, F>(&self, op_name: &'static str, f: F) -> Result<T, AppError>
     where
         T: Send + 'static,
         F: FnOnce(&mut Connection) -> Result<T, rusqlite::Error> + Send + 'static,
     {
+        if let Some(reason) = self.mode.read_only() {
+            return Err(AppError::ReadOnly(reason));
+        }
         let conn = self.conn.clone();
         let write_lock = self.write_lock.clone();
-        tauri::async_runtime::spawn_blocking(move || {
-            let _wg = write_lock.lock().map_err(|_| "write mutex poisoned".to_string())?;
-            let mut guard = conn.lock().map_err(|_| "db mutex poisoned".to_string())?;
-            f(&mut guard).map_err(|e| {
-                let msg = sqlite_error_string(&e);
-                eprintln!("[sqlite] {{ op: {:?}, error: {:?} }}", op_name, msg);
-                msg
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let _wg = write_lock.lock().map_err(|_| AppError::Database("write mutex poisoned".to_string()))?;
+            let mut guard = conn.lock().map_err(|_| AppError::Database("db mutex poisoned".to_string()))?;
+            f(&mut guard).map_err(|e| match app_mode::read_only_reason(&e) {
+                Some(reason) => AppError::ReadOnly(reason),
+                None => db_operation_error(op_name, e),
             })
         })
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(|e| AppError::Other(e.to_string()))?;
+        if let Err(AppError::ReadOnly(reason)) = &result {
+            self.mode.set(&self.app, Some(*reason));
+        }
+        result
+    }
+
+    /// `with_write` for commands that change invoices, clients, expenses or settings: the closure
+    /// records what it changed and the `data://…-changed` events go out after it succeeds.
+    async fn with_write_notify<T, F>(&self, op_name: &'static str, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection, &mut DataChanges) -> Result<T, rusqlite::Error> + Send + 'static,
+    {
+        let (out, changes) = self
+            .with_write(op_name, move |conn| {
+                let mut changes = DataChanges::default();
+                let out = f(conn, &mut changes)?;
+                Ok((out, changes))
+            })
+            .await?;
+        changes.emit(&self.app);
+        Ok(out)
+    }
+
+    /// `with_write_notify` for commands that create data. Once the trial has ended without a
+    /// valid license they fail with `LicenseRequired` before `f` runs, so no create path can
+    /// forget the check.
+    async fn with_create_notify<T, F>(&self, op_name: &'static str, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection, &mut DataChanges) -> Result<T, rusqlite::Error> + Send + 'static,
+    {
+        self.with_write_notify(op_name, move |conn, changes| {
+            trial::require_license(conn)?;
+            f(conn, changes)
+        })
+        .await
     }
 }
 
 fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Error> {
     let row = conn
         .query_row(
-            "SELECT data_json, isConfigured, companyName, COALESCE(maticniBroj,''), pib, address, companyAddressLine, companyCity, companyPostalCode, companyEmail, companyPhone, bankAccount, logoUrl, invoicePrefix, nextInvoiceNumber, defaultCurrency, language, smtpHost, smtpPort, smtpUser, smtpPassword, smtpFrom, smtpUseTls, smtpTlsMode FROM settings WHERE id = ?1",
+            "SELECT data_json, isConfigured, companyName, COALESCE(maticniBroj,''), pib, address, companyAddressLine, companyCity, companyPostalCode, companyEmail, companyPhone, bankAccount, logoUrl, invoicePrefix, nextInvoiceNumber, defaultCurrency, language, smtpHost, smtpPort, smtpUser, smtpPassword, smtpFrom, smtpUseTls, smtpTlsMode, smtpTimeoutSeconds, smtpReplyTo, smtpFromName, sendCopyToSelf, smtpAuthMode, smtpOauthClientId, smtpOauthClientSecret, smtpOauthRefreshToken, smtpOauthTokenEndpoint, smtpAcceptInvalidCerts, smtpAcceptInvalidCertsAcknowledged, smtpPinnedCertPem, turnoverLimitRsd, vatLimitRsd, autoBackupEnabled, autoBackupKeep, csvDelimiter, csvDecimalComma, csvIncludeBom, csvLineEnding, defaultInvoiceNotes, legalNoteOverrideSr, legalNoteOverrideEn, roundingMode, quotePrefix, nextQuoteNumber, webhookUrl, webhookSecret, bankAccounts, pdfFilenameTemplate, issuedBy, accountantEmail, staleDraftDays, overdueGraceDays, customFieldLabels, emailMaxMessageMb, emailTheme FROM settings WHERE id = ?1",
             params![SETTINGS_ID],
             |r| {
                 Ok((
@@ -2891,6 +4640,46 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
                     r.get::<_, String>(21)?,
                     r.get::<_, i64>(22)?,
                     r.get::<_, String>(23)?,
+                    r.get::<_, i64>(24)?,
+                    r.get::<_, String>(25)?,
+                    r.get::<_, String>(26)?,
+                    r.get::<_, i64>(27)?,
+                    (
+                        r.get::<_, String>(28)?,
+                        r.get::<_, String>(29)?,
+                        r.get::<_, String>(30)?,
+                        r.get::<_, String>(31)?,
+                        r.get::<_, String>(32)?,
+                    ),
+                    (
+                        r.get::<_, i64>(33)?,
+                        r.get::<_, i64>(34)?,
+                        r.get::<_, String>(35)?,
+                    ),
+                    (r.get::<_, f64>(36)?, r.get::<_, f64>(37)?),
+                    (r.get::<_, i64>(38)?, r.get::<_, i64>(39)?),
+                    (
+                        r.get::<_, String>(40)?,
+                        r.get::<_, i64>(41)?,
+                        r.get::<_, i64>(42)?,
+                        r.get::<_, String>(43)?,
+                    ),
+                    (
+                        r.get::<_, String>(44)?,
+                        r.get::<_, String>(45)?,
+                        r.get::<_, String>(46)?,
+                    ),
+                    r.get::<_, String>(47)?,
+                    (r.get::<_, String>(48)?, r.get::<_, i64>(49)?),
+                    (r.get::<_, String>(50)?, r.get::<_, String>(51)?),
+                    (r.get::<_, String>(52)?, r.get::<_, String>(53)?, r.get::<_, String>(54)?, r.get::<_, String>(55)?),
+                    (
+                        r.get::<_, i64>(56)?,
+                        r.get::<_, i64>(57)?,
+                        r.get::<_, String>(58)?,
+                        r.get::<_, i64>(59)?,
+                        r.get::<_, String>(60)?,
+                    ),
                 ))
             },
         )
@@ -2921,7 +4710,45 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
         smtp_from,
         smtp_use_tls,
         smtp_tls_mode,
+        smtp_timeout_seconds,
+        smtp_reply_to,
+        smtp_from_name,
+        send_copy_to_self,
+        (
+            smtp_auth_mode,
+            smtp_oauth_client_id,
+            smtp_oauth_client_secret,
+            smtp_oauth_refresh_token,
+            smtp_oauth_token_endpoint,
+        ),
+        (smtp_accept_invalid_certs, smtp_accept_invalid_certs_acknowledged, smtp_pinned_cert_pem),
+        (turnover_limit_rsd, vat_limit_rsd),
+        (auto_backup_enabled, auto_backup_keep),
+        (csv_delimiter, csv_decimal_comma, csv_include_bom, csv_line_ending),
+        (default_invoice_notes, legal_note_override_sr, legal_note_override_en),
+        rounding_mode,
+        (quote_prefix, next_quote_number),
+        (webhook_url, webhook_secret),
+        (bank_accounts_json, pdf_filename_template, issued_by, accountant_email),
+        (stale_draft_days, overdue_grace_days, custom_field_labels_json, email_max_message_mb, email_theme_json),
     )) = row {
+        let bank_accounts: Vec<BankAccount> = serde_json::from_str(&bank_accounts_json).unwrap_or_default();
+        let custom_field_labels: Vec<String> = serde_json::from_str(&custom_field_labels_json).unwrap_or_default();
+        let email_theme = email_theme::parse_email_theme(&email_theme_json);
+        let csv_options = CsvOptions {
+            delimiter: csv_delimiter
+                .chars()
+                .next()
+                .filter(|c| CSV_DELIMITERS.contains(c))
+                .unwrap_or_else(default_csv_delimiter),
+            decimal_comma: csv_decimal_comma != 0,
+            include_bom: csv_include_bom != 0,
+            line_ending: parse_csv_line_ending_str(&csv_line_ending),
+        };
+        let legal_note_override = LegalNoteOverride {
+            sr: legal_note_override_sr,
+            en: legal_note_override_en,
+        };
         if let Ok(mut parsed) = serde_json::from_str::<Settings>(&data_json) {
             if let Some(v) = is_cfg {
                 parsed.is_configured = Some(v != 0);
@@ -2960,6 +4787,40 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
             parsed.smtp_password = smtp_password;
             parsed.smtp_from = smtp_from;
             parsed.smtp_use_tls = smtp_use_tls != 0;
+            parsed.smtp_timeout_seconds = smtp_timeout_seconds;
+            parsed.smtp_reply_to = smtp_reply_to;
+            parsed.smtp_from_name = smtp_from_name;
+            parsed.send_copy_to_self = send_copy_to_self != 0;
+            parsed.smtp_auth_mode = parse_smtp_auth_mode_str(&smtp_auth_mode);
+            parsed.smtp_oauth_client_id = smtp_oauth_client_id;
+            parsed.smtp_oauth_client_secret = smtp_oauth_client_secret;
+            parsed.smtp_oauth_refresh_token = smtp_oauth_refresh_token;
+            parsed.smtp_oauth_token_endpoint = smtp_oauth_token_endpoint;
+            parsed.smtp_accept_invalid_certs = smtp_accept_invalid_certs != 0;
+            parsed.smtp_accept_invalid_certs_acknowledged = smtp_accept_invalid_certs_acknowledged != 0;
+            parsed.smtp_pinned_cert_pem = smtp_pinned_cert_pem;
+            parsed.turnover_limit_rsd = turnover_limit_rsd;
+            parsed.vat_limit_rsd = vat_limit_rsd;
+            parsed.auto_backup_enabled = auto_backup_enabled != 0;
+            parsed.auto_backup_keep = auto_backup_keep;
+            parsed.csv_options = csv_options;
+            parsed.default_invoice_notes = default_invoice_notes;
+            parsed.legal_note_override = legal_note_override;
+            parsed.rounding_mode = RoundingMode::parse(&rounding_mode);
+            // Like `nextInvoiceNumber`, advanced by `create_quote` in the column only.
+            parsed.quote_prefix = quote_prefix.clone();
+            parsed.next_quote_number = next_quote_number;
+            parsed.webhook_url = webhook_url;
+            parsed.webhook_secret = webhook_secret;
+            parsed.bank_accounts = bank_accounts;
+            parsed.pdf_filename_template = pdf_filename_template;
+            parsed.issued_by = non_blank(Some(issued_by));
+            parsed.accountant_email = accountant_email;
+            parsed.stale_draft_days = stale_draft_days;
+            parsed.overdue_grace_days = overdue_grace_days;
+            parsed.custom_field_labels = custom_field_labels;
+            parsed.email_max_message_mb = email_max_message_mb;
+            parsed.email_theme = email_theme;
             if parsed.smtp_tls_mode.is_none() {
                 parsed.smtp_tls_mode = parse_smtp_tls_mode_str(&smtp_tls_mode);
             }
@@ -2986,6 +4847,7 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
             company_email,
             company_phone,
             bank_account: bank,
+            bank_accounts,
             logo_url: logo,
             invoice_prefix: prefix,
             next_invoice_number: next,
@@ -2995,9 +4857,44 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
             smtp_port,
             smtp_user,
             smtp_password,
+            smtp_password_configured: false,
             smtp_from,
             smtp_use_tls: smtp_use_tls != 0,
             smtp_tls_mode: Some(mode),
+            smtp_timeout_seconds,
+            smtp_reply_to,
+            smtp_from_name,
+            send_copy_to_self: send_copy_to_self != 0,
+            smtp_auth_mode: parse_smtp_auth_mode_str(&smtp_auth_mode),
+            smtp_oauth_client_id,
+            smtp_oauth_client_secret,
+            smtp_oauth_refresh_token,
+            smtp_oauth_token_endpoint,
+            smtp_oauth_configured: false,
+            smtp_accept_invalid_certs: smtp_accept_invalid_certs != 0,
+            smtp_accept_invalid_certs_acknowledged: smtp_accept_invalid_certs_acknowledged != 0,
+            smtp_pinned_cert_pem,
+            turnover_limit_rsd,
+            vat_limit_rsd,
+            auto_backup_enabled: auto_backup_enabled != 0,
+            auto_backup_keep,
+            csv_options,
+            default_invoice_notes,
+            legal_note_override,
+            rounding_mode: RoundingMode::parse(&rounding_mode),
+            quote_prefix,
+            next_quote_number,
+            webhook_url,
+            webhook_secret,
+            webhook_secret_configured: false,
+            pdf_filename_template,
+            issued_by: non_blank(Some(issued_by)),
+            accountant_email,
+            stale_draft_days,
+            overdue_grace_days,
+            custom_field_labels,
+            email_max_message_mb,
+            email_theme,
         });
     }
 
@@ -3005,172 +4902,464 @@ fn read_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Erro
 }
 
 #[tauri::command]
-async fn get_settings(state: tauri::State<'_, DbState>) -> Result<Settings, String> {
-    state.with_read("get_settings", |conn| read_settings_from_conn(conn)).await
+async fn get_settings(state: tauri::State<'_, DbState>) -> Result<Settings, AppError> {
+    state
+        .with_read("get_settings", |conn| read_settings_from_conn(conn))
+        .await
+        .map(settings_for_ui)
+}
+
+/// Strips the SMTP password (sentinel or legacy plaintext) and the other secrets before settings
+/// leave the backend.
+fn settings_for_ui(mut s: Settings) -> Settings {
+    s.smtp_password_configured = !s.smtp_password.is_empty();
+    s.smtp_password = String::new();
+    s.smtp_oauth_configured = !s.smtp_oauth_refresh_token.is_empty();
+    s.smtp_oauth_client_secret = String::new();
+    s.smtp_oauth_refresh_token = String::new();
+    s.webhook_secret_configured = !s.webhook_secret.is_empty();
+    s.webhook_secret = String::new();
+    s
 }
 
 #[tauri::command]
-async fn update_settings(state: tauri::State<'_, DbState>, patch: SettingsPatch) -> Result<Settings, String> {
+async fn update_settings(state: tauri::State<'_, DbState>, patch: SettingsPatch) -> Result<Settings, AppError> {
     state
-        .with_write("update_settings", move |conn| {
-            let mut current = read_settings_from_conn(conn)?;
+        .with_write_notify("update_settings", move |conn, changes| {
+            // The UPDATE has committed by now; the keyring follows it.
+            let (settings, staged) = apply_settings_patch(conn, patch)?;
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            staged.store().map_err(validation_to_sql_error)?;
+            Ok(settings)
+        })
+        .await
+        .map(settings_for_ui)
+}
+
+/// Applies `patch` to the stored settings and writes the row: validation, port-based TLS-mode
+/// defaults and the setup completeness check all run here. `import_settings` goes through it too.
+/// New secrets come back staged; the caller stores them once the row is committed.
+fn apply_settings_patch(
+    conn: &Connection,
+    patch: SettingsPatch,
+) -> Result<(Settings, secrets::StagedSecrets), rusqlite::Error> {
+    let mut current = read_settings_from_conn(conn)?;
+    let mut staged = secrets::StagedSecrets::default();
+    let was_configured = current.is_configured.unwrap_or(false);
+
+    if let Some(v) = patch.is_configured {
+        current.is_configured = Some(v);
+    }
+    if let Some(v) = patch.company_name {
+        current.company_name = v;
+    }
+    if let Some(v) = patch.language {
+        current.language = v;
+    }
+    let tax_ids_before = (current.pib.clone(), current.registration_number.clone());
+    if let Some(v) = patch.registration_number {
+        current.registration_number = v;
+    }
+    if let Some(v) = patch.pib {
+        current.pib = v;
+    }
+    // The company is always Serbian, so there is no override here.
+    tax_ids::validate_changed_tax_ids(
+        &current.language,
+        &tax_ids_before,
+        &current.pib,
+        &current.registration_number,
+    )
+    .map_err(validation_to_sql_error)?;
+    if let Some(v) = patch.company_address_line {
+        current.company_address_line = v;
+    }
+    if let Some(v) = patch.company_city {
+        current.company_city = v;
+    }
+    if let Some(v) = patch.company_postal_code {
+        current.company_postal_code = v;
+    }
+    if let Some(v) = patch.company_email {
+        current.company_email = v;
+    }
+    if let Some(v) = patch.company_phone {
+        current.company_phone = v;
+    }
+    // The list wins over the single account sent by the setup wizard and older settings files.
+    if let Some(v) = patch.bank_accounts {
+        current.bank_accounts =
+            bank_accounts::normalize_bank_accounts(v, &current.language).map_err(validation_to_sql_error)?;
+        current.bank_account = bank_accounts::default_account_number(&current.bank_accounts);
+    } else if let Some(v) = patch.bank_account {
+        bank_accounts::set_default_account_number(&mut current.bank_accounts, &v);
+        current.bank_account = v;
+    }
+    // The logo is changed only through `set_company_logo`.
+    if let Some(v) = patch.invoice_prefix {
+        current.invoice_prefix = v;
+    }
+    if let Some(v) = patch.next_invoice_number {
+        current.next_invoice_number = settings_validation::check_next_invoice_number(v)
+            .map_err(|e| validation_to_sql_error(e.message(&current.language, &v.to_string())))?;
+    }
+    if let Some(v) = patch.default_currency {
+        current.default_currency = v;
+    }
+    if let Some(v) = patch.smtp_host {
+        current.smtp_host = v;
+    }
 
-            if let Some(v) = patch.is_configured {
-                current.is_configured = Some(v);
-            }
-            if let Some(v) = patch.company_name {
-                current.company_name = v;
-            }
-            if let Some(v) = patch.registration_number {
-                current.registration_number = v;
-            }
-            if let Some(v) = patch.pib {
-                current.pib = v;
-            }
-            if let Some(v) = patch.company_address_line {
-                current.company_address_line = v;
-            }
-            if let Some(v) = patch.company_city {
-                current.company_city = v;
-            }
-            if let Some(v) = patch.company_postal_code {
-                current.company_postal_code = v;
-            }
-            if let Some(v) = patch.company_email {
-                current.company_email = v;
-            }
-            if let Some(v) = patch.company_phone {
-                current.company_phone = v;
-            }
-            if let Some(v) = patch.bank_account {
-                current.bank_account = v;
-            }
-            if let Some(v) = patch.logo_url {
-                current.logo_url = v;
-            }
-            if let Some(v) = patch.invoice_prefix {
-                current.invoice_prefix = v;
-            }
-            if let Some(v) = patch.next_invoice_number {
-                current.next_invoice_number = v;
-            }
-            if let Some(v) = patch.default_currency {
-                current.default_currency = v;
-            }
-            if let Some(v) = patch.language {
-                current.language = v;
-            }
-            if let Some(v) = patch.smtp_host {
-                current.smtp_host = v;
-            }
+    let mut smtp_port_changed = false;
+    if let Some(v) = patch.smtp_port {
+        current.smtp_port = v;
+        smtp_port_changed = true;
+    }
+    if let Some(v) = patch.smtp_user {
+        current.smtp_user = v;
+    }
+    if let Some(v) = patch.smtp_password {
+        if !v.trim().is_empty() {
+            staged.add(secrets::SMTP_PASSWORD, &v);
+            current.smtp_password = secrets::KEYRING_SENTINEL.to_string();
+        }
+    }
+    if let Some(v) = patch.smtp_from {
+        current.smtp_from = v;
+    }
+    if let Some(v) = patch.smtp_use_tls {
+        current.smtp_use_tls = v;
+    }
 
-            let mut smtp_port_changed = false;
-            if let Some(v) = patch.smtp_port {
-                current.smtp_port = v;
-                smtp_port_changed = true;
-            }
-            if let Some(v) = patch.smtp_user {
-                current.smtp_user = v;
-            }
-            if let Some(v) = patch.smtp_password {
-                if !v.trim().is_empty() {
-                    current.smtp_password = v;
-                }
-            }
-            if let Some(v) = patch.smtp_from {
-                current.smtp_from = v;
-            }
-            if let Some(v) = patch.smtp_use_tls {
-                current.smtp_use_tls = v;
-            }
+    if let Some(v) = patch.smtp_timeout_seconds {
+        if !(1..=MAX_SMTP_TIMEOUT_SECONDS).contains(&v) {
+            return Err(validation_to_sql_error(format!(
+                "SMTP timeout must be between 1 and {MAX_SMTP_TIMEOUT_SECONDS} seconds."
+            )));
+        }
+        current.smtp_timeout_seconds = v;
+    }
 
-            let smtp_tls_mode_changed = patch.smtp_tls_mode.is_some();
-            if let Some(v) = patch.smtp_tls_mode {
-                current.smtp_tls_mode = Some(v);
-            }
+    if let Some(v) = patch.smtp_reply_to {
+        let v = v.trim().to_string();
+        validate_smtp_reply_to(&v).map_err(validation_to_sql_error)?;
+        current.smtp_reply_to = v;
+    }
+    if let Some(v) = patch.smtp_from_name {
+        let v = v.trim().to_string();
+        validate_smtp_from_name(&v).map_err(validation_to_sql_error)?;
+        current.smtp_from_name = v;
+    }
+    if let Some(v) = patch.send_copy_to_self {
+        current.send_copy_to_self = v;
+    }
 
-            // Apply defaults based on well-known ports if the user didn't explicitly set the TLS mode.
-            if smtp_port_changed && !smtp_tls_mode_changed {
-                if current.smtp_port == 465 {
-                    current.smtp_tls_mode = Some(SmtpTlsMode::Implicit);
-                }
-                if current.smtp_port == 587 {
-                    current.smtp_tls_mode = Some(SmtpTlsMode::Starttls);
-                }
-            }
-            if current.smtp_tls_mode.is_none() {
-                current.smtp_tls_mode = Some(default_smtp_tls_mode_for_port(current.smtp_port));
-            }
+    let oauth_before = (
+        current.smtp_auth_mode,
+        current.smtp_user.clone(),
+        current.smtp_oauth_client_id.clone(),
+        current.smtp_oauth_token_endpoint.clone(),
+    );
+    if let Some(v) = patch.smtp_auth_mode {
+        current.smtp_auth_mode = v;
+    }
+    if let Some(v) = patch.smtp_oauth_client_id {
+        current.smtp_oauth_client_id = v.trim().to_string();
+    }
+    if let Some(v) = patch.smtp_oauth_token_endpoint {
+        let v = v.trim().to_string();
+        validate_smtp_oauth_token_endpoint(&v).map_err(validation_to_sql_error)?;
+        current.smtp_oauth_token_endpoint = v;
+    }
+    // Blank secrets keep the stored value, like `smtp_password`.
+    let mut oauth_secret_changed = false;
+    if let Some(v) = patch.smtp_oauth_client_secret.filter(|v| !v.trim().is_empty()) {
+        staged.add(secrets::SMTP_OAUTH_CLIENT_SECRET, v.trim());
+        current.smtp_oauth_client_secret = secrets::KEYRING_SENTINEL.to_string();
+        oauth_secret_changed = true;
+    }
+    if let Some(v) = patch.smtp_oauth_refresh_token.filter(|v| !v.trim().is_empty()) {
+        staged.add(secrets::SMTP_OAUTH_REFRESH_TOKEN, v.trim());
+        current.smtp_oauth_refresh_token = secrets::KEYRING_SENTINEL.to_string();
+        oauth_secret_changed = true;
+    }
+    let oauth_after = (
+        current.smtp_auth_mode,
+        current.smtp_user.clone(),
+        current.smtp_oauth_client_id.clone(),
+        current.smtp_oauth_token_endpoint.clone(),
+    );
+    if oauth_secret_changed || oauth_before != oauth_after {
+        smtp_oauth::invalidate_cached_token();
+    }
 
-            let now = now_iso();
-            let json = serde_json::to_string(&current).unwrap_or_else(|_| "{}".to_string());
-            let is_cfg = current.is_configured.unwrap_or(false);
+    if let Some(v) = patch.smtp_pinned_cert_pem {
+        let v = v.trim().to_string();
+        if !v.is_empty() {
+            parse_pinned_certificate(&v).map_err(validation_to_sql_error)?;
+        }
+        current.smtp_pinned_cert_pem = v;
+    }
+    if let Some(v) = patch.smtp_accept_invalid_certs_acknowledged {
+        current.smtp_accept_invalid_certs_acknowledged = v;
+    }
+    if let Some(v) = patch.smtp_accept_invalid_certs {
+        current.smtp_accept_invalid_certs = v;
+    }
+    validate_smtp_accept_invalid_certs(&current).map_err(validation_to_sql_error)?;
 
-            conn.execute(
-                r#"UPDATE settings SET
-                    isConfigured = ?2,
-                    companyName = ?3,
-                    maticniBroj = ?4,
-                    pib = ?5,
-                    address = ?6,
-                    companyAddressLine = ?7,
-                    companyCity = ?8,
-                    companyPostalCode = ?9,
-                    companyEmail = ?10,
-                    companyPhone = ?11,
-                    bankAccount = ?12,
-                    logoUrl = ?13,
-                    invoicePrefix = ?14,
-                    nextInvoiceNumber = ?15,
-                    defaultCurrency = ?16,
-                    language = ?17,
-                    smtpHost = ?18,
-                    smtpPort = ?19,
-                    smtpUser = ?20,
-                    smtpPassword = ?21,
-                    smtpFrom = ?22,
-                    smtpUseTls = ?23,
-                    smtpTlsMode = ?24,
-                    data_json = ?25,
-                    updatedAt = ?26
-                   WHERE id = ?1"#,
-                params![
-                    SETTINGS_ID,
-                    is_cfg as i32,
-                    current.company_name,
-                    current.registration_number,
-                    current.pib,
-                    current.company_address_line.clone(),
-                    current.company_address_line,
-                    current.company_city,
-                    current.company_postal_code,
-                    current.company_email,
-                    current.company_phone,
-                    current.bank_account,
-                    current.logo_url,
-                    current.invoice_prefix,
-                    current.next_invoice_number,
-                    current.default_currency,
-                    current.language,
-                    current.smtp_host,
-                    current.smtp_port,
-                    current.smtp_user,
-                    current.smtp_password,
-                    current.smtp_from,
-                    current.smtp_use_tls as i32,
-                    resolved_smtp_tls_mode(current.smtp_tls_mode, current.smtp_port).as_str(),
-                    json,
-                    now,
-                ],
-            )?;
+    if let Some(v) = patch.turnover_limit_rsd {
+        current.turnover_limit_rsd = validate_limit_rsd("Paušal turnover limit", v).map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.vat_limit_rsd {
+        current.vat_limit_rsd = validate_limit_rsd("VAT threshold", v).map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.auto_backup_enabled {
+        current.auto_backup_enabled = v;
+    }
+    if let Some(v) = patch.auto_backup_keep {
+        current.auto_backup_keep = validate_auto_backup_keep(v).map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.csv_options {
+        current.csv_options = validate_csv_options(v).map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.default_invoice_notes {
+        current.default_invoice_notes = v;
+    }
+    if let Some(v) = patch.legal_note_override {
+        current.legal_note_override = v;
+    }
+    if let Some(v) = patch.rounding_mode {
+        current.rounding_mode = v;
+    }
+    if let Some(v) = patch.quote_prefix {
+        current.quote_prefix = v;
+    }
+    if let Some(v) = patch.next_quote_number {
+        current.next_quote_number = settings_validation::check_next_quote_number(v)
+            .map_err(|e| validation_to_sql_error(e.message(&current.language, &v.to_string())))?;
+    }
+    if let Some(v) = patch.webhook_url {
+        let v = v.trim().to_string();
+        webhooks::validate_webhook_url(&v).map_err(validation_to_sql_error)?;
+        current.webhook_url = v;
+    }
+    if let Some(v) = patch.pdf_filename_template {
+        let v = v.trim().to_string();
+        pdf_filename::validate_pdf_filename_template(&v).map_err(validation_to_sql_error)?;
+        current.pdf_filename_template = v;
+    }
+    if let Some(v) = patch.issued_by {
+        current.issued_by = non_blank(Some(v));
+    }
+    if let Some(v) = patch.accountant_email {
+        let v = v.trim().to_string();
+        if !v.is_empty() {
+            v.parse::<Mailbox>()
+                .map_err(|_| validation_to_sql_error(format!("Invalid accountant email address: \"{v}\"")))?;
+        }
+        current.accountant_email = v;
+    }
+    if let Some(v) = patch.stale_draft_days {
+        current.stale_draft_days =
+            status_suggestions::check_threshold_days(v, 1, "Stale draft days").map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.overdue_grace_days {
+        current.overdue_grace_days =
+            status_suggestions::check_threshold_days(v, 0, "Overdue grace days").map_err(validation_to_sql_error)?;
+    }
+    if let Some(v) = patch.custom_field_labels {
+        current.custom_field_labels = custom_fields::normalize_custom_field_labels(v)?;
+    }
+    if let Some(v) = patch.email_max_message_mb {
+        if !(1..=MAX_EMAIL_MESSAGE_MB).contains(&v) {
+            return Err(AppError::validation(
+                "emailMaxMessageMb",
+                "invalid",
+                format!("The email size limit must be between 1 and {MAX_EMAIL_MESSAGE_MB} MB."),
+            )
+            .into());
+        }
+        current.email_max_message_mb = v;
+    }
+    if let Some(v) = patch.email_theme {
+        current.email_theme = email_theme::normalize_email_theme(v)?;
+    }
+    // Blank keeps the stored secret, like `smtp_password`.
+    if let Some(v) = patch.webhook_secret.filter(|v| !v.trim().is_empty()) {
+        staged.add(secrets::WEBHOOK_SECRET, v.trim());
+        current.webhook_secret = secrets::KEYRING_SENTINEL.to_string();
+    }
 
-            Ok(current)
-        })
-        .await
+    let smtp_tls_mode_changed = patch.smtp_tls_mode.is_some();
+    if let Some(v) = patch.smtp_tls_mode {
+        current.smtp_tls_mode = Some(v);
+    }
+
+    // Apply defaults based on well-known ports if the user didn't explicitly set the TLS mode.
+    if smtp_port_changed && !smtp_tls_mode_changed {
+        if current.smtp_port == 465 {
+            current.smtp_tls_mode = Some(SmtpTlsMode::Implicit);
+        }
+        if current.smtp_port == 587 {
+            current.smtp_tls_mode = Some(SmtpTlsMode::Starttls);
+        }
+    }
+    if current.smtp_tls_mode.is_none() {
+        current.smtp_tls_mode = Some(default_smtp_tls_mode_for_port(current.smtp_port));
+    }
+
+    let is_cfg = current.is_configured.unwrap_or(false);
+    // Finishing the setup needs everything an invoice PDF will ask for.
+    if is_cfg && !was_configured {
+        let problems = settings_validation::settings_problems(&current);
+        if !problems.is_empty() {
+            let messages: Vec<String> = problems.into_iter().map(|p| p.message).collect();
+            return Err(validation_to_sql_error(messages.join("\n")));
+        }
+    }
+
+    let now = now_iso();
+    let json = serde_json::to_string(&current).unwrap_or_else(|_| "{}".to_string());
+
+    conn.execute(
+        r#"UPDATE settings SET
+            isConfigured = ?2,
+            companyName = ?3,
+            maticniBroj = ?4,
+            pib = ?5,
+            address = ?6,
+            companyAddressLine = ?7,
+            companyCity = ?8,
+            companyPostalCode = ?9,
+            companyEmail = ?10,
+            companyPhone = ?11,
+            bankAccount = ?12,
+            logoUrl = ?13,
+            invoicePrefix = ?14,
+            nextInvoiceNumber = ?15,
+            defaultCurrency = ?16,
+            language = ?17,
+            smtpHost = ?18,
+            smtpPort = ?19,
+            smtpUser = ?20,
+            smtpPassword = ?21,
+            smtpFrom = ?22,
+            smtpUseTls = ?23,
+            smtpTlsMode = ?24,
+            data_json = ?25,
+            updatedAt = ?26,
+            smtpTimeoutSeconds = ?27,
+            smtpReplyTo = ?28,
+            smtpFromName = ?29,
+            sendCopyToSelf = ?30,
+            smtpAuthMode = ?31,
+            smtpOauthClientId = ?32,
+            smtpOauthClientSecret = ?33,
+            smtpOauthRefreshToken = ?34,
+            smtpOauthTokenEndpoint = ?35,
+            smtpAcceptInvalidCerts = ?36,
+            smtpAcceptInvalidCertsAcknowledged = ?37,
+            smtpPinnedCertPem = ?38,
+            turnoverLimitRsd = ?39,
+            vatLimitRsd = ?40,
+            autoBackupEnabled = ?41,
+            autoBackupKeep = ?42,
+            csvDelimiter = ?43,
+            csvDecimalComma = ?44,
+            csvIncludeBom = ?45,
+            csvLineEnding = ?46,
+            defaultInvoiceNotes = ?47,
+            legalNoteOverrideSr = ?48,
+            legalNoteOverrideEn = ?49,
+            roundingMode = ?50,
+            quotePrefix = ?51,
+            nextQuoteNumber = ?52,
+            webhookUrl = ?53,
+            webhookSecret = ?54,
+            bankAccounts = ?55,
+            pdfFilenameTemplate = ?56,
+            issuedBy = ?57,
+            accountantEmail = ?58,
+            staleDraftDays = ?59,
+            overdueGraceDays = ?60,
+            customFieldLabels = ?61,
+            emailMaxMessageMb = ?62,
+            emailTheme = ?63
+           WHERE id = ?1"#,
+        params![
+            SETTINGS_ID,
+            is_cfg as i32,
+            current.company_name,
+            current.registration_number,
+            current.pib,
+            current.company_address_line.clone(),
+            current.company_address_line,
+            current.company_city,
+            current.company_postal_code,
+            current.company_email,
+            current.company_phone,
+            current.bank_account,
+            current.logo_url,
+            current.invoice_prefix,
+            current.next_invoice_number,
+            current.default_currency,
+            current.language,
+            current.smtp_host,
+            current.smtp_port,
+            current.smtp_user,
+            current.smtp_password,
+            current.smtp_from,
+            current.smtp_use_tls as i32,
+            resolved_smtp_tls_mode(current.smtp_tls_mode, current.smtp_port).as_str(),
+            json,
+            now,
+            current.smtp_timeout_seconds,
+            current.smtp_reply_to,
+            current.smtp_from_name,
+            current.send_copy_to_self as i32,
+            current.smtp_auth_mode.as_str(),
+            current.smtp_oauth_client_id,
+            current.smtp_oauth_client_secret,
+            current.smtp_oauth_refresh_token,
+            current.smtp_oauth_token_endpoint,
+            current.smtp_accept_invalid_certs as i32,
+            current.smtp_accept_invalid_certs_acknowledged as i32,
+            current.smtp_pinned_cert_pem,
+            current.turnover_limit_rsd,
+            current.vat_limit_rsd,
+            current.auto_backup_enabled as i32,
+            current.auto_backup_keep,
+            current.csv_options.delimiter.to_string(),
+            current.csv_options.decimal_comma as i32,
+            current.csv_options.include_bom as i32,
+            current.csv_options.line_ending.as_str(),
+            current.default_invoice_notes,
+            current.legal_note_override.sr,
+            current.legal_note_override.en,
+            current.rounding_mode.as_str(),
+            current.quote_prefix,
+            current.next_quote_number,
+            current.webhook_url,
+            current.webhook_secret,
+            bank_accounts_json(&current.bank_accounts),
+            current.pdf_filename_template,
+            current.issued_by.as_deref().unwrap_or(""),
+            current.accountant_email,
+            current.stale_draft_days,
+            current.overdue_grace_days,
+            custom_fields::custom_field_labels_json(&current.custom_field_labels),
+            current.email_max_message_mb,
+            email_theme::email_theme_json(&current.email_theme),
+        ],
+    )?;
+
+    errors::set_language(&current.language);
+    Ok((current, staged))
 }
 
 #[tauri::command]
-async fn generate_invoice_number(state: tauri::State<'_, DbState>) -> Result<String, String> {
+async fn generate_invoice_number(state: tauri::State<'_, DbState>) -> Result<String, AppError> {
     state
         .with_read("generate_invoice_number", |conn| {
             let s = read_settings_from_conn(conn)?;
@@ -3180,7 +5369,7 @@ async fn generate_invoice_number(state: tauri::State<'_, DbState>) -> Result<Str
 }
 
 #[tauri::command]
-async fn preview_next_invoice_number(state: tauri::State<'_, DbState>) -> Result<String, String> {
+async fn preview_next_invoice_number(state: tauri::State<'_, DbState>) -> Result<String, AppError> {
     // Must match the real atomic assignment logic used in `create_invoice`.
     state
         .with_read("preview_next_invoice_number", |conn| {
@@ -3195,27 +5384,34 @@ async fn preview_next_invoice_number(state: tauri::State<'_, DbState>) -> Result
 }
 
 #[tauri::command]
-async fn get_all_clients(state: tauri::State<'_, DbState>) -> Result<Vec<Client>, String> {
-    state
-        .with_read("get_all_clients", |conn| {
-            let mut stmt = conn.prepare("SELECT data_json FROM clients ORDER BY createdAt DESC")?;
-            let mut rows = stmt.query([])?;
+async fn get_all_clients(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    include_archived: Option<bool>,
+) -> Result<Vec<Client>, AppError> {
+    let include_archived = include_archived.unwrap_or(false);
+    let (out, corrupt) = state
+        .with_read("get_all_clients", move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, data_json FROM clients WHERE (?1 OR archived = 0) ORDER BY createdAt DESC",
+            )?;
+            let mut rows = stmt.query(params![include_archived])?;
             let mut out: Vec<Client> = Vec::new();
+            let mut corrupt = Vec::new();
             while let Some(row) = rows.next()? {
-                let json: Option<String> = row.get(0)?;
-                if let Some(j) = json {
-                    if let Ok(c) = serde_json::from_str::<Client>(&j) {
-                        out.push(c);
-                    }
+                if let Some(c) = corrupt_rows::parse_row("clients", row.get(0)?, row.get(1)?, row.get(2)?, &mut corrupt) {
+                    out.push(c);
                 }
             }
-            Ok(out)
+            Ok((out, corrupt))
         })
-        .await
+        .await?;
+    corrupt_rows::report(&app, &corrupt);
+    Ok(out)
 }
 
 #[tauri::command]
-async fn get_client_by_id(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Client>, String> {
+async fn get_client_by_id(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Client>, AppError> {
     state
         .with_read("get_client_by_id", move |conn| {
             let json: Option<String> = conn
@@ -3235,9 +5431,39 @@ async fn get_client_by_id(state: tauri::State<'_, DbState>, id: String) -> Resul
 }
 
 #[tauri::command]
-async fn create_client(state: tauri::State<'_, DbState>, input: NewClient) -> Result<Client, String> {
+async fn create_client(
+    state: tauri::State<'_, DbState>,
+    input: NewClient,
+    allow_duplicate: Option<bool>,
+) -> Result<CreateClientResult, AppError> {
     state
-        .with_write("create_client", move |conn| {
+        .with_create_notify("create_client", move |conn, changes| {
+            let idempotency_key = non_blank(input.idempotency_key.clone());
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            // Checked before the duplicate check, which would otherwise match the replayed client.
+            if let Some(client) =
+                idempotency::replay(&tx, IdempotencyScope::Client, idempotency_key.as_deref(), read_client_from_conn)?
+            {
+                return Ok(CreateClientResult::Created { client: Box::new(client) });
+            }
+            // Foreign clients carry non-Serbian identifiers.
+            if !input.is_foreign {
+                let lang = read_settings_from_conn(&tx)?.language;
+                tax_ids::validate_tax_ids_for(&lang, &input.pib, &input.registration_number)
+                    .map_err(validation_to_sql_error)?;
+            }
+            if !allow_duplicate.unwrap_or(false) {
+                if let Some((existing, matched_on)) =
+                    clients::find_conflicting_client(&tx, &input.pib, &input.registration_number)?
+                {
+                    return Ok(CreateClientResult::Duplicate {
+                        existing_id: existing.id,
+                        existing_name: existing.name,
+                        matched_on,
+                    });
+                }
+            }
+
             let created = Client {
                 id: Uuid::new_v4().to_string(),
                 name: input.name,
@@ -3247,24 +5473,25 @@ async fn create_client(state: tauri::State<'_, DbState>, input: NewClient) -> Re
                 city: input.city,
                 postal_code: input.postal_code,
                 email: input.email,
+                phone: non_blank(input.phone),
+                website: non_blank(input.website),
+                default_currency: non_blank(input.default_currency),
+                preferred_language: non_blank(input.preferred_language),
+                payment_terms_days: input.payment_terms_days,
+                billing_email: non_blank(input.billing_email),
+                archived: false,
+                is_foreign: input.is_foreign,
+                country: if input.is_foreign { non_blank(input.country) } else { None },
                 created_at: now_iso(),
             };
-            let json = serde_json::to_string(&created).unwrap_or_else(|_| "{}".to_string());
-            conn.execute(
-                r#"INSERT INTO clients (id, name, maticniBroj, pib, address, email, phone, createdAt, data_json)
-                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7, ?8)"#,
-                params![
-                    created.id,
-                    created.name,
-                    created.registration_number,
-                    created.pib,
-                    created.address,
-                    created.email,
-                    created.created_at,
-                    json,
-                ],
-            )?;
-            Ok(created)
+            let created = clients::normalize_client_defaults(created).map_err(validation_to_sql_error)?;
+            clients::insert_client_row(&tx, &created)?;
+            if let Some(key) = idempotency_key.as_deref() {
+                idempotency::remember(&tx, IdempotencyScope::Client, key, &created.id)?;
+            }
+            tx.commit()?;
+            changes.record(DataEntity::Clients, DataOp::Created, created.id.clone());
+            Ok(CreateClientResult::Created { client: Box::new(created) })
         })
         .await
 }
@@ -3274,10 +5501,12 @@ async fn update_client(
     state: tauri::State<'_, DbState>,
     id: String,
     patch: serde_json::Value,
-) -> Result<Option<Client>, String> {
+    propagate_name: Option<bool>,
+) -> Result<Option<Client>, AppError> {
     state
-        .with_write("update_client", move |conn| {
-            let existing_json: Option<String> = conn
+        .with_write_notify("update_client", move |conn, changes| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let existing_json: Option<String> = tx
                 .query_row(
                     "SELECT data_json FROM clients WHERE id = ?1",
                     params![&id],
@@ -3289,6 +5518,8 @@ async fn update_client(
                 Ok(v) => v,
                 Err(_) => return Ok(None),
             };
+            let tax_ids_before = (existing.pib.clone(), existing.registration_number.clone());
+            let name_before = existing.name.clone();
 
             if let Some(v) = patch.get("name").and_then(|v| v.as_str()) {
                 existing.name = v.to_string();
@@ -3319,12 +5550,50 @@ async fn update_client(
             if let Some(v) = patch.get("email").and_then(|v| v.as_str()) {
                 existing.email = v.to_string();
             }
+            // A string sets the field; an explicit null clears it.
+            if let Some(v) = patch.get("phone") {
+                existing.phone = non_blank(v.as_str().map(str::to_string));
+            }
+            if let Some(v) = patch.get("website") {
+                existing.website = non_blank(v.as_str().map(str::to_string));
+            }
+            if let Some(v) = patch.get("defaultCurrency") {
+                existing.default_currency = non_blank(v.as_str().map(str::to_string));
+            }
+            if let Some(v) = patch.get("preferredLanguage") {
+                existing.preferred_language = non_blank(v.as_str().map(str::to_string));
+            }
+            if let Some(v) = patch.get("paymentTermsDays") {
+                existing.payment_terms_days = v.as_i64();
+            }
+            if let Some(v) = patch.get("billingEmail") {
+                existing.billing_email = non_blank(v.as_str().map(str::to_string));
+            }
+            if let Some(v) = patch.get("isForeign").and_then(|v| v.as_bool()) {
+                existing.is_foreign = v;
+            }
+            if let Some(v) = patch.get("country") {
+                existing.country = non_blank(v.as_str().map(str::to_string));
+            }
+            if !existing.is_foreign {
+                existing.country = None;
+            }
 
-            let json = serde_json::to_string(&existing).unwrap_or_else(|_| "{}".to_string());
-            conn.execute(
-                r#"UPDATE clients SET name=?2, maticniBroj=?3, pib=?4, address=?5, email=?6, data_json=?7 WHERE id=?1"#,
-                params![id, existing.name, existing.registration_number, existing.pib, existing.address, existing.email, json],
-            )?;
+            if !existing.is_foreign {
+                let lang = read_settings_from_conn(&tx)?.language;
+                tax_ids::validate_changed_tax_ids(&lang, &tax_ids_before, &existing.pib, &existing.registration_number)
+                    .map_err(validation_to_sql_error)?;
+            }
+
+            let existing = clients::normalize_client_defaults(existing).map_err(validation_to_sql_error)?;
+            clients::write_client_row(&tx, &existing)?;
+            // Issued invoices keep the name they were issued under unless the caller opts in.
+            if propagate_name.unwrap_or(false) && existing.name != name_before {
+                let renamed = clients::rename_client_on_invoices(&tx, &existing.id, &existing.name)?;
+                changes.record_all(DataEntity::Invoices, DataOp::Updated, renamed);
+            }
+            tx.commit()?;
+            changes.record(DataEntity::Clients, DataOp::Updated, existing.id.clone());
 
             Ok(Some(existing))
         })
@@ -3332,31 +5601,40 @@ async fn update_client(
 }
 
 #[tauri::command]
-async fn delete_client(state: tauri::State<'_, DbState>, id: String) -> Result<bool, String> {
+async fn delete_client(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    force: Option<bool>,
+) -> Result<DeleteClientResult, AppError> {
     state
-        .with_write("delete_client", move |conn| {
-            conn.execute("DELETE FROM clients WHERE id = ?1", params![id])?;
-            Ok(true)
+        .with_write_notify("delete_client", move |conn, changes| {
+            let result = clients::delete_client_in_conn(conn, &id, force.unwrap_or(false))?;
+            if result.deleted {
+                changes.record(DataEntity::Clients, DataOp::Deleted, id);
+            }
+            Ok(result)
         })
         .await
 }
 
 #[tauri::command]
-async fn get_all_invoices(state: tauri::State<'_, DbState>) -> Result<Vec<Invoice>, String> {
-    state
+async fn get_all_invoices(app: tauri::AppHandle, state: tauri::State<'_, DbState>) -> Result<Vec<Invoice>, AppError> {
+    let (out, corrupt) = state
         .with_read("get_all_invoices", |conn| {
-            let mut stmt = conn.prepare("SELECT data_json FROM invoices ORDER BY createdAt DESC")?;
+            let mut stmt = conn.prepare("SELECT id, invoiceNumber, data_json FROM invoices ORDER BY createdAt DESC")?;
             let mut rows = stmt.query([])?;
             let mut out: Vec<Invoice> = Vec::new();
+            let mut corrupt = Vec::new();
             while let Some(row) = rows.next()? {
-                let json: String = row.get(0)?;
-                if let Ok(inv) = serde_json::from_str::<Invoice>(&json) {
+                if let Some(inv) = corrupt_rows::parse_row("invoices", row.get(0)?, row.get(1)?, row.get(2)?, &mut corrupt) {
                     out.push(inv);
                 }
             }
-            Ok(out)
+            Ok((out, corrupt))
         })
-        .await
+        .await?;
+    corrupt_rows::report(&app, &corrupt);
+    Ok(out)
 }
 
 #[tauri::command]
@@ -3364,14 +5642,16 @@ async fn list_invoices_range(
     state: tauri::State<'_, DbState>,
     from: String,
     to: String,
-) -> Result<Vec<Invoice>, String> {
+) -> Result<Vec<Invoice>, AppError> {
+    let (from, to) = normalize_date_range(&from, &to)?;
     state
         .with_read("list_invoices_range", move |conn| {
+            // `paidAt` is a timestamp; compare its date part so the last day is included.
             let mut stmt = conn.prepare(
                 r#"SELECT data_json
                    FROM invoices
                    WHERE (issueDate >= ?1 AND issueDate <= ?2)
-                      OR (paidAt IS NOT NULL AND paidAt >= ?1 AND paidAt <= ?2)
+                      OR (paidAt IS NOT NULL AND substr(paidAt, 1, 10) >= ?1 AND substr(paidAt, 1, 10) <= ?2)
                    ORDER BY createdAt DESC"#,
             )?;
             let mut rows = stmt.query(params![from, to])?;
@@ -3388,7 +5668,7 @@ async fn list_invoices_range(
 }
 
 #[tauri::command]
-async fn get_invoice_by_id(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Invoice>, String> {
+async fn get_invoice_by_id(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Invoice>, AppError> {
     state
         .with_read("get_invoice_by_id", move |conn| {
             let json: Option<String> = conn
@@ -3407,72 +5687,160 @@ async fn get_invoice_by_id(state: tauri::State<'_, DbState>, id: String) -> Resu
         .await
 }
 
-#[tauri::command]
-async fn create_invoice(state: tauri::State<'_, DbState>, input: NewInvoice) -> Result<Invoice, String> {
-    state
-        .with_write("create_invoice", move |conn| {
-            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+/// Numbers and inserts a new invoice inside `tx` and advances the invoice counter. Amounts are
+/// recomputed from the items; `quote_id` links an invoice converted from a quote.
+pub(crate) fn insert_new_invoice(
+    tx: &Transaction<'_>,
+    input: NewInvoice,
+    quote_id: Option<String>,
+) -> Result<Invoice, rusqlite::Error> {
+    let (prefix, next_num): (String, i64) = tx.query_row(
+        "SELECT invoicePrefix, nextInvoiceNumber FROM settings WHERE id = ?1",
+        params![SETTINGS_ID],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )?;
 
-            let (prefix, next_num): (String, i64) = tx.query_row(
-                "SELECT invoicePrefix, nextInvoiceNumber FROM settings WHERE id = ?1",
-                params![SETTINGS_ID],
-                |r| Ok((r.get(0)?, r.get(1)?)),
-            )?;
+    let invoice_number = format_invoice_number(&prefix, next_num);
+    let client = read_client_from_conn(tx, &input.client_id)?;
+    if let Some(c) = client.as_ref().filter(|c| c.archived) {
+        return Err(validation_to_sql_error(format!(
+            "Client \"{}\" is archived. Unarchive it on the Clients page before creating a new invoice.",
+            c.name
+        )));
+    }
+    let currency = match input.currency.trim() {
+        "" => match client.as_ref().and_then(|c| c.default_currency.clone()) {
+            Some(c) => c,
+            None => read_settings_from_conn(tx)?.default_currency,
+        },
+        c => c.to_string(),
+    };
+    let notes = if input.notes.trim().is_empty() {
+        read_settings_from_conn(tx)?.default_invoice_notes
+    } else {
+        input.notes
+    };
+    let issued_by = match non_blank(input.issued_by) {
+        Some(p) => Some(p),
+        None => read_settings_from_conn(tx)?.issued_by,
+    };
+    let custom_fields = custom_fields::normalize_custom_fields(input.custom_fields)?;
+    let due_date = input.due_date.filter(|d| !d.trim().is_empty()).or_else(|| {
+        let days = client.as_ref()?.payment_terms_days?;
+        add_days_ymd(&input.issue_date, days)
+    });
+    let payment_reference = match input
+        .payment_reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(r) => Some(r.to_string()),
+        None => compute_model97_reference(&invoice_number).ok(),
+    };
+
+    let status = input.status.unwrap_or(InvoiceStatus::Draft);
+    let paid_at = if status == InvoiceStatus::Paid {
+        Some(today_ymd())
+    } else {
+        None
+    };
 
-            let invoice_number = format_invoice_number(&prefix, next_num);
+    let mut items = input.items;
+    catalog::apply_catalog_snapshots(tx, &mut items)?;
+
+    let mut created = Invoice {
+        id: Uuid::new_v4().to_string(),
+        invoice_number: invoice_number,
+        client_id: input.client_id,
+        client_name: input.client_name,
+        issue_date: input.issue_date,
+        service_date: input.service_date,
+        status,
+        due_date,
+        paid_at,
+        currency,
+        items,
+        subtotal: input.subtotal,
+        invoice_discount: input.invoice_discount,
+        invoice_discount_percent: input.invoice_discount_percent,
+        total: input.total,
+        notes,
+        payment_method: input.payment_method,
+        payment_reference,
+        exchange_rate: input.exchange_rate,
+        imported: false,
+        rounding_mode: read_settings_from_conn(tx)?.rounding_mode,
+        quote_id,
+        issued_by,
+        custom_fields,
+        created_at: now_iso(),
+    };
+    recompute_invoice_totals(&mut created);
 
-            let status = input.status.unwrap_or(InvoiceStatus::Draft);
-            let paid_at = if status == InvoiceStatus::Paid {
-                Some(today_ymd())
-            } else {
-                None
-            };
+    let json = serde_json::to_string(&created).unwrap_or_else(|_| "{}".to_string());
+    tx.execute(
+        r#"INSERT INTO invoices (
+            id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+        params![
+            created.id,
+            created.invoice_number,
+            created.client_id,
+            created.issue_date,
+            created.status.as_str(),
+            created.due_date,
+            created.paid_at,
+            created.currency,
+            created.total,
+            created.created_at,
+            json,
+        ],
+    )?;
 
-            let created = Invoice {
-                id: Uuid::new_v4().to_string(),
-                invoice_number: invoice_number,
-                client_id: input.client_id,
-                client_name: input.client_name,
-                issue_date: input.issue_date,
-                service_date: input.service_date,
-                status,
-                due_date: input.due_date,
-                paid_at,
-                currency: input.currency,
-                items: input.items,
-                subtotal: input.subtotal,
-                total: input.total,
-                notes: input.notes,
-                created_at: now_iso(),
-            };
+    tx.execute(
+        "UPDATE settings SET nextInvoiceNumber = nextInvoiceNumber + 1, updatedAt = ?2 WHERE id = ?1",
+        params![SETTINGS_ID, now_iso()],
+    )?;
 
-            let json = serde_json::to_string(&created).unwrap_or_else(|_| "{}".to_string());
-            tx.execute(
-                r#"INSERT INTO invoices (
-                    id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
-                params![
-                    created.id,
-                    created.invoice_number,
-                    created.client_id,
-                    created.issue_date,
-                    created.status.as_str(),
-                    created.due_date,
-                    created.paid_at,
-                    created.currency,
-                    created.total,
-                    created.created_at,
-                    json,
-                ],
-            )?;
+    webhooks::invoice_created(tx, &created);
+    Ok(created)
+}
 
-            tx.execute(
-                "UPDATE settings SET nextInvoiceNumber = nextInvoiceNumber + 1, updatedAt = ?2 WHERE id = ?1",
-                params![SETTINGS_ID, now_iso()],
-            )?;
+#[tauri::command]
+async fn create_invoice(state: tauri::State<'_, DbState>, mut input: NewInvoice) -> Result<CreatedInvoice, AppError> {
+    validate_invoice_discount(&input.items, input.invoice_discount, input.invoice_discount_percent)?;
+    input.issue_date = normalize_ymd("Issue date", &input.issue_date)?;
+    input.service_date = normalize_ymd("Service date", &input.service_date)?;
+    input.due_date = match input.due_date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(d) => Some(normalize_ymd("Due date", d)?),
+        None => None,
+    };
+    validate_exchange_rate(input.exchange_rate)?;
+    let idempotency_key = non_blank(input.idempotency_key.take());
+
+    state
+        .with_create_notify("create_invoice", move |conn, changes| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            if let Some(invoice) =
+                idempotency::replay(&tx, IdempotencyScope::Invoice, idempotency_key.as_deref(), read_invoice_from_conn)?
+            {
+                let limit_warnings = limit_warnings_for_invoice(&tx, &invoice)?;
+                return Ok(CreatedInvoice { invoice, limit_warnings });
+            }
+            let created = insert_new_invoice(&tx, input, None)?;
+            if let Some(key) = idempotency_key.as_deref() {
+                idempotency::remember(&tx, IdempotencyScope::Invoice, key, &created.id)?;
+            }
 
+            let limit_warnings = limit_warnings_for_invoice(&tx, &created)?;
             tx.commit()?;
-            Ok(created)
+            changes.record(DataEntity::Invoices, DataOp::Created, created.id.clone());
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            Ok(CreatedInvoice {
+                invoice: created,
+                limit_warnings,
+            })
         })
         .await
 }
@@ -3481,10 +5849,32 @@ async fn create_invoice(state: tauri::State<'_, DbState>, input: NewInvoice) ->
 async fn update_invoice(
     state: tauri::State<'_, DbState>,
     id: String,
-    patch: InvoicePatch,
-) -> Result<Option<Invoice>, String> {
+    mut patch: InvoicePatch,
+) -> Result<Option<Invoice>, AppError> {
+    if let Some(d) = patch.issue_date.as_deref() {
+        patch.issue_date = Some(normalize_ymd("Issue date", d)?);
+    }
+    if let Some(d) = patch.service_date.as_deref() {
+        patch.service_date = Some(normalize_ymd("Service date", d)?);
+    }
+    if let Some(rate) = patch.exchange_rate {
+        validate_exchange_rate(rate)?;
+    }
+    if let Some(Some(d)) = patch.due_date.as_ref() {
+        patch.due_date = Some(match d.trim() {
+            "" => None,
+            d => Some(normalize_ymd("Due date", d)?),
+        });
+    }
+    if let Some(Some(d)) = patch.paid_at.as_ref() {
+        patch.paid_at = Some(match d.trim() {
+            "" => None,
+            d => Some(normalize_ymd("Paid date", d)?),
+        });
+    }
     state
-        .with_write("update_invoice", move |conn| {
+        .with_write_notify("update_invoice", move |conn, changes| {
+            trial::require_license(conn)?;
             let json: Option<String> = conn
                 .query_row(
                     "SELECT data_json FROM invoices WHERE id = ?1",
@@ -3497,6 +5887,7 @@ async fn update_invoice(
                 Ok(v) => v,
                 Err(_) => return Ok(None),
             };
+            let previous_status = existing.status;
 
             if let Some(v) = patch.invoice_number {
                 existing.invoice_number = v;
@@ -3522,18 +5913,51 @@ async fn update_invoice(
             if let Some(v) = patch.currency {
                 existing.currency = v;
             }
-            if let Some(v) = patch.items {
+            if let Some(mut v) = patch.items {
+                catalog::apply_catalog_snapshots(conn, &mut v)?;
                 existing.items = v;
             }
             if let Some(v) = patch.subtotal {
                 existing.subtotal = v;
             }
+            if let Some(v) = patch.invoice_discount {
+                existing.invoice_discount = v;
+            }
+            if let Some(v) = patch.invoice_discount_percent {
+                existing.invoice_discount_percent = v;
+            }
             if let Some(v) = patch.total {
                 existing.total = v;
             }
             if let Some(v) = patch.notes {
                 existing.notes = v;
             }
+            if let Some(v) = patch.payment_method {
+                existing.payment_method = v;
+            }
+            if let Some(v) = patch.payment_reference {
+                existing.payment_reference = v.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+            }
+            if let Some(v) = patch.exchange_rate {
+                existing.exchange_rate = v;
+            }
+            if let Some(v) = patch.issued_by {
+                existing.issued_by = non_blank(v);
+            }
+            if let Some(v) = patch.custom_fields {
+                existing.custom_fields = custom_fields::normalize_custom_fields(v)?;
+            }
+            if let Some(v) = patch.paid_at {
+                existing.paid_at = v;
+            }
+
+            validate_invoice_discount(
+                &existing.items,
+                existing.invoice_discount,
+                existing.invoice_discount_percent,
+            )
+            .map_err(validation_to_sql_error)?;
+            recompute_invoice_totals(&mut existing);
 
             // Enforce PAID <-> paidAt invariant.
             if existing.status == InvoiceStatus::Paid {
@@ -3561,16 +5985,24 @@ async fn update_invoice(
                 ],
             )?;
 
+            webhooks::invoice_status_changed(conn, &existing, previous_status);
+            changes.record(DataEntity::Invoices, DataOp::Updated, existing.id.clone());
             Ok(Some(existing))
         })
         .await
 }
 
 #[tauri::command]
-async fn delete_invoice(state: tauri::State<'_, DbState>, id: String) -> Result<bool, String> {
+async fn delete_invoice(state: tauri::State<'_, DbState>, id: String) -> Result<bool, AppError> {
     state
-        .with_write("delete_invoice", move |conn| {
-            conn.execute("DELETE FROM invoices WHERE id = ?1", params![id])?;
+        .with_write_notify("delete_invoice", move |conn, changes| {
+            conn.execute("DELETE FROM invoice_payments WHERE invoiceId = ?1", params![id])?;
+            // Time billed to the invoice becomes billable again.
+            conn.execute("UPDATE time_entries SET billedInvoiceId = NULL WHERE billedInvoiceId = ?1", params![id])?;
+            quotes::unlink_invoice(conn, &id)?;
+            if conn.execute("DELETE FROM invoices WHERE id = ?1", params![id])? > 0 {
+                changes.record(DataEntity::Invoices, DataOp::Deleted, id);
+            }
             Ok(true)
         })
         .await
@@ -3580,34 +6012,23 @@ async fn delete_invoice(state: tauri::State<'_, DbState>, id: String) -> Result<
 async fn list_expenses(
     state: tauri::State<'_, DbState>,
     range: Option<ExpenseRange>,
-) -> Result<Vec<Expense>, String> {
+) -> Result<Vec<Expense>, AppError> {
+    let (from, to) = match range {
+        Some(r) => normalize_optional_date_range(r.from, r.to)?,
+        None => (None, None),
+    };
     state
         .with_read("list_expenses", move |conn| {
-            let (from, to) = match range {
-                Some(r) => (r.from, r.to),
-                None => (None, None),
-            };
-
-            let mut stmt = conn.prepare(
-                r#"SELECT id, title, amount, currency, date, category, notes, createdAt
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT {EXPENSE_COLUMNS}
                    FROM expenses
-                   WHERE (?1 IS NULL OR date >= ?1)
+                   WHERE deletedAt IS NULL
+                     AND (?1 IS NULL OR date >= ?1)
                      AND (?2 IS NULL OR date <= ?2)
-                   ORDER BY date DESC, createdAt DESC"#,
-            )?;
+                   ORDER BY date DESC, createdAt DESC"#
+            ))?;
 
-            let rows = stmt.query_map(params![from, to], |r| {
-                Ok(Expense {
-                    id: r.get(0)?,
-                    title: r.get(1)?,
-                    amount: r.get(2)?,
-                    currency: r.get(3)?,
-                    date: r.get(4)?,
-                    category: r.get(5)?,
-                    notes: r.get(6)?,
-                    created_at: r.get(7)?,
-                })
-            })?;
+            let rows = stmt.query_map(params![from, to], expense_from_row)?;
 
             let mut out = Vec::new();
             for row in rows {
@@ -3618,11 +6039,18 @@ async fn list_expenses(
         .await
 }
 
+#[tauri::command]
+async fn get_expense_by_id(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Expense>, AppError> {
+    state
+        .with_read("get_expense_by_id", move |conn| read_expense_from_conn(conn, &id))
+        .await
+}
+
 #[tauri::command]
 async fn create_expense(
     state: tauri::State<'_, DbState>,
     input: NewExpense,
-) -> Result<Expense, String> {
+) -> Result<Expense, AppError> {
     let NewExpense {
         title,
         amount,
@@ -3630,7 +6058,9 @@ async fn create_expense(
         date,
         category,
         notes,
+        idempotency_key,
     } = input;
+    let idempotency_key = non_blank(idempotency_key);
 
     let title = title.trim().to_string();
     let currency = currency.trim().to_string();
@@ -3645,24 +6075,31 @@ async fn create_expense(
     });
 
     if title.is_empty() {
-        return Err("Title is required.".to_string());
+        return Err(AppError::validation("title", "required", "Title is required."));
     }
     if !amount.is_finite() || amount <= 0.0 {
-        return Err("Amount must be greater than 0.".to_string());
+        return Err(AppError::validation("amount", "positive", "Amount must be greater than 0."));
     }
     if currency.is_empty() {
-        return Err("Currency is required.".to_string());
+        return Err(AppError::validation("currency", "required", "Currency is required."));
     }
     if date.is_empty() {
-        return Err("Date is required.".to_string());
+        return Err(AppError::validation("date", "required", "Date is required."));
     }
+    let date = normalize_ymd("Date", &date).map_err(|e| AppError::validation("date", "date", e))?;
 
     state
-        .with_write("create_expense", move |conn| {
+        .with_create_notify("create_expense", move |conn, changes| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            if let Some(expense) = idempotency::replay(&tx, IdempotencyScope::Expense, idempotency_key.as_deref(), |c, id| {
+                Ok(read_expense_from_conn(c, id)?.filter(|e| e.deleted_at.is_none()))
+            })? {
+                return Ok(expense);
+            }
             let id = Uuid::new_v4().to_string();
             let created_at = now_iso();
 
-            conn.execute(
+            tx.execute(
                 r#"INSERT INTO expenses (id, title, amount, currency, date, category, notes, createdAt)
                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
                 params![
@@ -3676,7 +6113,12 @@ async fn create_expense(
                     created_at,
                 ],
             )?;
+            if let Some(key) = idempotency_key.as_deref() {
+                idempotency::remember(&tx, IdempotencyScope::Expense, key, &id)?;
+            }
+            tx.commit()?;
 
+            changes.record(DataEntity::Expenses, DataOp::Created, id.clone());
             Ok(Expense {
                 id,
                 title,
@@ -3686,6 +6128,9 @@ async fn create_expense(
                 category,
                 notes,
                 created_at,
+                recurring_id: None,
+                deleted_at: None,
+                invoice_id: None,
             })
         })
         .await
@@ -3695,31 +6140,32 @@ async fn create_expense(
 async fn update_expense(
     state: tauri::State<'_, DbState>,
     id: String,
-    patch: ExpensePatch,
-) -> Result<Option<Expense>, String> {
+    mut patch: ExpensePatch,
+) -> Result<Option<Expense>, AppError> {
     if let Some(t) = patch.title.as_deref() {
         if t.trim().is_empty() {
-            return Err("Title is required.".to_string());
+            return Err(AppError::validation("title", "required", "Title is required."));
         }
     }
     if let Some(a) = patch.amount {
         if !a.is_finite() || a <= 0.0 {
-            return Err("Amount must be greater than 0.".to_string());
+            return Err(AppError::validation("amount", "positive", "Amount must be greater than 0."));
         }
     }
     if let Some(c) = patch.currency.as_deref() {
         if c.trim().is_empty() {
-            return Err("Currency is required.".to_string());
+            return Err(AppError::validation("currency", "required", "Currency is required."));
         }
     }
     if let Some(d) = patch.date.as_deref() {
         if d.trim().is_empty() {
-            return Err("Date is required.".to_string());
+            return Err(AppError::validation("date", "required", "Date is required."));
         }
+        patch.date = Some(normalize_ymd("Date", d).map_err(|e| AppError::validation("date", "date", e))?);
     }
 
     state
-        .with_write("update_expense", move |conn| {
+        .with_write_notify("update_expense", move |conn, changes| {
             let mut existing = match read_expense_from_conn(conn, &id)? {
                 Some(e) => e,
                 None => return Ok(None),
@@ -3743,6 +6189,15 @@ async fn update_expense(
             if let Some(v) = patch.notes {
                 existing.notes = v;
             }
+            if let Some(v) = patch.invoice_id {
+                let v = v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+                if let Some(invoice_id) = v.as_deref() {
+                    if read_invoice_from_conn(conn, invoice_id)?.is_none() {
+                        return Err(validation_to_sql_error("Invoice not found.".to_string()));
+                    }
+                }
+                existing.invoice_id = v;
+            }
 
             existing.title = existing.title.trim().to_string();
             existing.currency = existing.currency.trim().to_string();
@@ -3762,7 +6217,7 @@ async fn update_expense(
 
             conn.execute(
                 r#"UPDATE expenses
-                   SET title=?2, amount=?3, currency=?4, date=?5, category=?6, notes=?7
+                   SET title=?2, amount=?3, currency=?4, date=?5, category=?6, notes=?7, invoiceId=?8
                    WHERE id=?1"#,
                 params![
                     id,
@@ -3772,40 +6227,345 @@ async fn update_expense(
                     existing.date,
                     existing.category,
                     existing.notes,
+                    existing.invoice_id,
                 ],
             )?;
 
+            changes.record(DataEntity::Expenses, DataOp::Updated, existing.id.clone());
             Ok(Some(existing))
         })
         .await
 }
 
+/// Copy of an expense dated `new_date`, not linked to a recurring schedule.
+fn duplicate_expense_in_conn(conn: &Connection, id: &str, new_date: &str) -> Result<Option<Expense>, rusqlite::Error> {
+    let Some(original) = read_expense_from_conn(conn, id)?.filter(|e| e.deleted_at.is_none()) else {
+        return Ok(None);
+    };
+    let copy = Expense {
+        id: Uuid::new_v4().to_string(),
+        date: new_date.to_string(),
+        created_at: now_iso(),
+        recurring_id: None,
+        invoice_id: None,
+        ..original
+    };
+    insert_expense_row(conn, &copy)?;
+    Ok(Some(copy))
+}
+
 #[tauri::command]
-async fn delete_expense(state: tauri::State<'_, DbState>, id: String) -> Result<bool, String> {
+async fn duplicate_expense(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    new_date: String,
+) -> Result<Option<Expense>, AppError> {
+    let new_date = normalize_ymd("Date", &new_date)?;
     state
-        .with_write("delete_expense", move |conn| {
-            let affected = conn.execute("DELETE FROM expenses WHERE id = ?1", params![id])?;
-            Ok(affected > 0)
+        .with_create_notify("duplicate_expense", move |conn, changes| {
+            let copy = duplicate_expense_in_conn(conn, &id, &new_date)?;
+            changes.record_all(DataEntity::Expenses, DataOp::Created, copy.as_ref().map(|e| e.id.clone()));
+            Ok(copy)
         })
         .await
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn to_cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Replaces an expense with `parts` in one transaction. The parts keep the original's currency,
+/// category, date, notes and schedule, and their amounts must add up to the original to the cent.
+fn split_expense_in_conn(
+    conn: &mut Connection,
+    id: &str,
+    parts: &[ExpenseSplitPart],
+) -> Result<Option<Vec<Expense>>, rusqlite::Error> {
+    if parts.len() < 2 {
+        return Err(AppError::validation("parts", "invalid", "Split into at least two parts.").into());
+    }
+    for p in parts {
+        if p.title.trim().is_empty() {
+            return Err(AppError::validation("title", "required", "Title is required.").into());
+        }
+        if !p.amount.is_finite() || to_cents(p.amount) <= 0 {
+            return Err(AppError::validation("amount", "positive", "Amount must be greater than 0.").into());
+        }
+    }
+
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(original) = read_expense_from_conn(&tx, id)?.filter(|e| e.deleted_at.is_none()) else {
+        return Ok(None);
+    };
+    let parts_cents: i64 = parts.iter().map(|p| to_cents(p.amount)).sum();
+    let original_cents = to_cents(original.amount);
+    if parts_cents != original_cents {
+        return Err(validation_to_sql_error(format!(
+            "Parts add up to {:.2} but the expense is {:.2} {}.",
+            parts_cents as f64 / 100.0,
+            original_cents as f64 / 100.0,
+            original.currency
+        )));
+    }
+
+    let created_at = now_iso();
+    let mut out = Vec::with_capacity(parts.len());
+    for p in parts {
+        let part = Expense {
+            id: Uuid::new_v4().to_string(),
+            title: p.title.trim().to_string(),
+            amount: to_cents(p.amount) as f64 / 100.0,
+            created_at: created_at.clone(),
+            ..original.clone()
+        };
+        insert_expense_row(&tx, &part)?;
+        out.push(part);
+    }
+    tx.execute("DELETE FROM expenses WHERE id = ?1", params![original.id])?;
+    tx.commit()?;
+    Ok(Some(out))
+}
+
+#[tauri::command]
+async fn split_expense(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    parts: Vec<ExpenseSplitPart>,
+) -> Result<Option<Vec<Expense>>, AppError> {
+    state
+        .with_create_notify("split_expense", move |conn, changes| {
+            let parts = split_expense_in_conn(conn, &id, &parts)?;
+            if let Some(parts) = &parts {
+                changes.record(DataEntity::Expenses, DataOp::Deleted, id);
+                changes.record_all(DataEntity::Expenses, DataOp::Created, parts.iter().map(|e| e.id.clone()));
+            }
+            Ok(parts)
+        })
+        .await
+}
+
+#[tauri::command]
+async fn list_expenses_for_invoice(
+    state: tauri::State<'_, DbState>,
+    invoice_id: String,
+) -> Result<Vec<Expense>, AppError> {
+    state
+        .with_read("list_expenses_for_invoice", move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT {EXPENSE_COLUMNS}
+                   FROM expenses
+                   WHERE invoiceId = ?1 AND deletedAt IS NULL
+                   ORDER BY date ASC, createdAt ASC"#
+            ))?;
+            let rows = stmt.query_map(params![invoice_id], expense_from_row)?;
+            rows.collect()
+        })
+        .await
+}
+
+/// Appends one line per expense (title, quantity 1, amount) to a draft invoice, recomputes its
+/// totals and links the expenses to it, all in one transaction.
+fn bill_expenses_to_invoice_in_conn(
+    conn: &mut Connection,
+    expense_ids: &[String],
+    invoice_id: &str,
+) -> Result<Option<Invoice>, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(mut invoice) = read_invoice_from_conn(&tx, invoice_id)? else {
+        return Ok(None);
+    };
+    if invoice.status != InvoiceStatus::Draft {
+        return Err(validation_to_sql_error(format!(
+            "Invoice {} is not a draft; expenses can only be billed to draft invoices.",
+            invoice.invoice_number
+        )));
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    for id in expense_ids.iter().map(String::as_str).filter(|id| seen.insert(id)) {
+        let expense = read_expense_from_conn(&tx, id)?
+            .filter(|e| e.deleted_at.is_none())
+            .ok_or_else(|| validation_to_sql_error(format!("Expense {id} was not found.")))?;
+        if expense.invoice_id.is_some() {
+            return Err(validation_to_sql_error(format!(
+                "Expense \"{}\" has already been billed.",
+                expense.title
+            )));
+        }
+        if expense.currency.trim() != invoice.currency.trim() {
+            return Err(validation_to_sql_error(format!(
+                "Expense \"{}\" is in {}, but the invoice is in {}.",
+                expense.title, expense.currency, invoice.currency
+            )));
+        }
+        invoice.items.push(InvoiceItem {
+            id: Uuid::new_v4().to_string(),
+            description: expense.title,
+            unit: None,
+            quantity: 1.0,
+            unit_price: expense.amount,
+            discount_amount: None,
+            total: expense.amount,
+            catalog_item_id: None,
+        });
+        tx.execute("UPDATE expenses SET invoiceId = ?2 WHERE id = ?1", params![id, invoice.id])?;
+    }
+
+    recompute_invoice_totals(&mut invoice);
+    let json = serde_json::to_string(&invoice).unwrap_or_else(|_| "{}".to_string());
+    tx.execute(
+        "UPDATE invoices SET totalAmount = ?2, data_json = ?3 WHERE id = ?1",
+        params![invoice.id, invoice.total, json],
+    )?;
+    tx.commit()?;
+    Ok(Some(invoice))
+}
+
+#[tauri::command]
+async fn bill_expenses_to_invoice(
+    state: tauri::State<'_, DbState>,
+    expense_ids: Vec<String>,
+    invoice_id: String,
+) -> Result<Option<Invoice>, AppError> {
+    if expense_ids.is_empty() {
+        return Err(AppError::validation("expenseIds", "required", "Select at least one expense."));
+    }
+    state
+        .with_write_notify("bill_expenses_to_invoice", move |conn, changes| {
+            let invoice = bill_expenses_to_invoice_in_conn(conn, &expense_ids, &invoice_id)?;
+            if invoice.is_some() {
+                changes.record(DataEntity::Invoices, DataOp::Updated, invoice_id);
+                changes.record_all(DataEntity::Expenses, DataOp::Updated, expense_ids);
+            }
+            Ok(invoice)
+        })
+        .await
+}
+
+/// Moves an expense to the trash and returns it (with `deletedAt` set) so the UI can offer undo.
+/// Returns `None` when the expense doesn't exist or is already deleted.
+fn soft_delete_expense_in_conn(conn: &Connection, id: &str) -> Result<Option<Expense>, rusqlite::Error> {
+    let affected = conn.execute(
+        "UPDATE expenses SET deletedAt = ?2 WHERE id = ?1 AND deletedAt IS NULL",
+        params![id, now_iso()],
+    )?;
+    if affected == 0 {
+        return Ok(None);
+    }
+    read_expense_from_conn(conn, id)
+}
+
+#[tauri::command]
+async fn delete_expense(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Expense>, AppError> {
+    state
+        .with_write_notify("delete_expense", move |conn, changes| {
+            let deleted = soft_delete_expense_in_conn(conn, &id)?;
+            if deleted.is_some() {
+                changes.record(DataEntity::Expenses, DataOp::Deleted, id);
+            }
+            Ok(deleted)
+        })
+        .await
+}
+
+#[tauri::command]
+async fn restore_expense(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Expense>, AppError> {
+    state
+        .with_write_notify("restore_expense", move |conn, changes| {
+            if conn.execute("UPDATE expenses SET deletedAt = NULL WHERE id = ?1", params![id])? > 0 {
+                changes.record(DataEntity::Expenses, DataOp::Updated, id.clone());
+            }
+            read_expense_from_conn(conn, &id)
+        })
+        .await
+}
+
+/// Permanently removes expenses deleted before `cutoff` (an RFC 3339 timestamp like `now_iso`).
+fn purge_deleted_expenses_in_conn(conn: &Connection, cutoff: &str) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM expenses WHERE deletedAt IS NOT NULL AND deletedAt < ?1",
+        params![cutoff],
+    )
+}
+
+/// Housekeeping: permanently removes expenses that have been in the trash for more than
+/// `older_than_days` days. Returns how many were removed.
+#[tauri::command]
+async fn purge_deleted_expenses(state: tauri::State<'_, DbState>, older_than_days: i64) -> Result<usize, AppError> {
+    if older_than_days < 0 {
+        return Err(AppError::validation("olderThanDays", "invalid", "older_than_days must not be negative."));
+    }
+    let cutoff = (OffsetDateTime::now_utc() - time::Duration::days(older_than_days))
+        .format(&Rfc3339)
+        .map_err(|e| e.to_string())?;
+    state
+        .with_write_notify("purge_deleted_expenses", move |conn, changes| {
+            let purged = purge_deleted_expenses_in_conn(conn, &cutoff)?;
+            // Only trashed rows go, so nothing a list shows changes; the trash view reloads.
+            if purged > 0 {
+                changes.replaced(DataEntity::Expenses);
+            }
+            Ok(purged)
+        })
+        .await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SendInvoiceEmailInput {
     pub invoice_id: String,
-    pub to: String,
+    #[serde(deserialize_with = "deserialize_recipients")]
+    pub to: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_recipients")]
+    pub cc: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_recipients")]
+    pub bcc: Vec<String>,
     pub subject: String,
     #[serde(default)]
     pub body: Option<String>,
     #[serde(default = "default_true")]
     pub include_pdf: bool,
+    /// Also attach the UBL 2.1 XML e-invoice.
+    #[serde(default)]
+    pub include_xml: bool,
 }
 
 fn default_true() -> bool {
     true
 }
 
+/// Accepts a list of addresses or a single address string (older frontends send `to: "a@b.rs"`).
+fn deserialize_recipients<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(s)) => vec![s],
+        Some(OneOrMany::Many(v)) => v,
+    })
+}
+
+/// Parses recipient addresses, skipping blanks. Errors name the offending address.
+fn parse_recipient_mailboxes(kind: &str, addresses: &[String]) -> Result<Vec<Mailbox>, String> {
+    addresses
+        .iter()
+        .map(|a| a.trim())
+        .filter(|a| !a.is_empty())
+        .map(|a| {
+            a.parse::<Mailbox>()
+                .map_err(|_| format!("Invalid {kind} email address: \"{a}\""))
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SendLicenseRequestEmailInput {
@@ -3817,51 +6577,114 @@ pub struct SendLicenseRequestEmailInput {
 
 #[tauri::command]
 async fn send_invoice_email(
+    app: tauri::AppHandle,
     state: tauri::State<'_, DbState>,
-    input: SendInvoiceEmailInput,
-) -> Result<bool, String> {
-    let (settings, invoice, client, to, subject, body, include_pdf) = state
+    worker: tauri::State<'_, outbox::OutboxWorker>,
+    mut input: SendInvoiceEmailInput,
+) -> Result<String, AppError> {
+    state
+        .with_write("send_invoice_email_license", |conn| {
+            trial::require_license(conn)?;
+            features::require_feature(conn, Feature::EmailSending)
+        })
+        .await?;
+    if input.to.iter().all(|t| t.trim().is_empty()) {
+        let invoice_id = input.invoice_id.clone();
+        let recipient = state
+            .with_read("send_invoice_email_recipient", move |conn| {
+                let Some(invoice) = read_invoice_from_conn(conn, &invoice_id)? else {
+                    return Ok(None);
+                };
+                let client = read_client_from_conn(conn, &invoice.client_id)?;
+                Ok(client.as_ref().and_then(Client::invoice_recipient).map(str::to_string))
+            })
+            .await?;
+        input.to = recipient.into_iter().collect();
+    }
+
+    // Reject bad input now; SMTP problems surface later through `email://status`.
+    if parse_recipient_mailboxes("recipient", &input.to)?.is_empty() {
+        return Err(AppError::validation("to", "required", "Recipient email address is required."));
+    }
+    parse_recipient_mailboxes("CC", &input.cc)?;
+    parse_recipient_mailboxes("BCC", &input.bcc)?;
+    if input.subject.trim().is_empty() {
+        return Err(AppError::validation("subject", "required", "Email subject is required."));
+    }
+
+    outbox::enqueue_invoice_email(&app, &state, &worker, input).await
+}
+
+/// Default for `Settings.email_max_message_mb`; Gmail and Outlook take 20–25 MB, but attachments
+/// grow by a third in transit and smaller providers cap lower.
+const DEFAULT_EMAIL_MAX_MESSAGE_MB: i64 = 15;
+const MAX_EMAIL_MESSAGE_MB: i64 = 100;
+
+/// Refuses a formatted message over the configured size, naming the attachments that make it up,
+/// instead of leaving it to the server's rejection.
+fn check_email_size(limit_mb: i64, message_bytes: usize, attachments: &[(String, usize)]) -> Result<(), AppError> {
+    let limit_mb = if limit_mb > 0 { limit_mb } else { DEFAULT_EMAIL_MAX_MESSAGE_MB };
+    if message_bytes as u64 <= limit_mb as u64 * 1024 * 1024 {
+        return Ok(());
+    }
+    Err(AppError::AttachmentTooLarge { limit_mb, message_bytes, attachments: attachments.to_vec() })
+}
+
+/// Builds and sends an invoice email. Called by the outbox worker, which records the returned
+/// flag: whether the sender's own address got a copy.
+pub(crate) async fn deliver_invoice_email(db: &DbState, input: SendInvoiceEmailInput) -> Result<bool, AppError> {
+    let (settings, invoice, client, (to, cc, bcc), subject, body, (include_pdf, include_xml)) = db
         .with_read("send_invoice_email_prepare", move |conn| {
             let settings = read_settings_from_conn(conn)?;
             let invoice = read_invoice_from_conn(conn, &input.invoice_id)?
-                .ok_or_else(|| rusqlite::Error::QueryReturnedNoRows)?;
+                .ok_or_else(|| AppError::not_found("invoice", &input.invoice_id))?;
             let client = read_client_from_conn(conn, &invoice.client_id)?;
 
             Ok((
                 settings,
                 invoice,
                 client,
-                input.to,
+                (input.to, input.cc, input.bcc),
                 input.subject,
-                input.body,
-                input.include_pdf,
-            ))
-        })
-        .await
-        .map_err(|e| {
-            if e.contains("QueryReturnedNoRows") {
-                "Invoice not found".to_string()
-            } else {
-                e
-            }
-        })?;
+                input.body,
+                (input.include_pdf, input.include_xml),
+            ))
+        })
+        .await?;
 
     validate_smtp_settings(&settings)?;
 
-    if to.trim().is_empty() {
-        return Err("Recipient email address is required.".to_string());
+    let to_mailboxes = parse_recipient_mailboxes("recipient", &to)?;
+    let cc_mailboxes = parse_recipient_mailboxes("CC", &cc)?;
+    let mut bcc_mailboxes = parse_recipient_mailboxes("BCC", &bcc)?;
+    let self_copy = if settings.send_copy_to_self {
+        self_copy_mailbox(&settings, &to_mailboxes, &cc_mailboxes, &bcc_mailboxes)?
+    } else {
+        None
+    };
+    let self_copy_included = settings.send_copy_to_self;
+    bcc_mailboxes.extend(self_copy);
+    if to_mailboxes.is_empty() {
+        return Err(AppError::validation("to", "required", "Recipient email address is required."));
     }
     if subject.trim().is_empty() {
-        return Err("Email subject is required.".to_string());
+        return Err(AppError::validation("subject", "required", "Email subject is required."));
     }
 
-    let from_mailbox: Mailbox = settings
-        .smtp_from
-        .parse()
-        .map_err(|_| "Invalid From address in SMTP settings.".to_string())?;
-    let to_mailbox: Mailbox = to
-        .parse()
-        .map_err(|_| "Invalid recipient email address.".to_string())?;
+    let mut builder = Message::builder().from(sender_mailbox(&settings)?).subject(subject);
+    if let Some(reply_to) = reply_to_mailbox(&settings)? {
+        builder = builder.reply_to(reply_to);
+    }
+    for m in to_mailboxes {
+        builder = builder.to(m);
+    }
+    for m in cc_mailboxes {
+        builder = builder.cc(m);
+    }
+    // lettre drops the Bcc header from the sent message; recipients stay in the envelope.
+    for m in bcc_mailboxes {
+        builder = builder.bcc(m);
+    }
 
     let (html_body, text_body) =
         render_invoice_email(&settings, &invoice, client.as_ref(), include_pdf, body.as_deref())?;
@@ -3869,39 +6692,94 @@ async fn send_invoice_email(
         .singlepart(SinglePart::plain(text_body))
         .singlepart(SinglePart::html(html_body));
 
-    let email = if include_pdf {
+    let mut attachments: Vec<SinglePart> = Vec::new();
+    let mut attachment_sizes: Vec<(String, usize)> = Vec::new();
+    if include_pdf || include_xml {
         let payload = build_invoice_pdf_payload_from_db(&invoice, client.as_ref(), &settings);
-        let pdf_bytes = generate_pdf_bytes(&payload, Some(settings.logo_url.as_str()))?;
-        let filename = sanitize_filename(&format!("{}.pdf", invoice.invoice_number));
-
-        let content_type = ContentType::parse("application/pdf")
-            .map_err(|e| format!("Failed to build PDF attachment content type: {e}"))?;
-        let attachment = Attachment::new(filename).body(pdf_bytes, content_type);
-
-        Message::builder()
-            .from(from_mailbox)
-            .to(to_mailbox)
-            .subject(subject)
-            .multipart(MultiPart::mixed().multipart(alternative).singlepart(attachment))
+        if include_pdf {
+            let pdf_bytes = generate_pdf_bytes(&payload, Some(settings.logo_url.as_str()), false)?;
+            let filename = pdf_filename::pdf_attachment_name(
+                &settings.pdf_filename_template,
+                &PdfFilenameFields {
+                    number: &invoice.invoice_number,
+                    client: &invoice.client_name,
+                    issue_date: &invoice.issue_date,
+                },
+            );
+            let content_type = ContentType::parse("application/pdf")
+                .map_err(|e| format!("Failed to build PDF attachment content type: {e}"))?;
+            attachment_sizes.push((filename.clone(), pdf_bytes.len()));
+            attachments.push(Attachment::new(filename).body(pdf_bytes, content_type));
+        }
+        if include_xml {
+            let xml = ubl::render_ubl_invoice(&payload, invoice.due_date.as_deref());
+            let filename = attachment_filename(&format!("{}.xml", invoice.invoice_number));
+            let content_type = ContentType::parse("application/xml")
+                .map_err(|e| format!("Failed to build XML attachment content type: {e}"))?;
+            attachment_sizes.push((filename.clone(), xml.len()));
+            attachments.push(Attachment::new(filename).body(xml.into_bytes(), content_type));
+        }
+    }
+
+    let email = if attachments.is_empty() {
+        builder
+            .multipart(alternative)
             .map_err(|e| format!("Failed to build email: {e}"))?
     } else {
-        Message::builder()
-            .from(from_mailbox)
-            .to(to_mailbox)
-            .subject(subject)
-            .multipart(alternative)
+        let mut mixed = MultiPart::mixed().multipart(alternative);
+        for attachment in attachments {
+            mixed = mixed.singlepart(attachment);
+        }
+        builder
+            .multipart(mixed)
             .map_err(|e| format!("Failed to build email: {e}"))?
     };
+    check_email_size(settings.email_max_message_mb, email.formatted().len(), &attachment_sizes)?;
 
     let settings = std::sync::Arc::new(settings);
 
     send_email_via_smtp(settings, email, "invoice").await?;
+    tracing::info!(
+        target: "email",
+        op = "invoice",
+        invoice = %invoice.invoice_number,
+        self_copy = self_copy_included,
+        "email sent"
+    );
 
-    Ok(true)
+    Ok(self_copy_included)
+}
+
+/// Mailbox for the archival BCC, or `None` when the From address already receives the message.
+/// The copy is the same message, so the personal note arrives exactly as the client saw it.
+fn self_copy_mailbox(
+    settings: &Settings,
+    to: &[Mailbox],
+    cc: &[Mailbox],
+    bcc: &[Mailbox],
+) -> Result<Option<Mailbox>, String> {
+    let own = sender_mailbox(settings)?;
+    let already_included = to
+        .iter()
+        .chain(cc)
+        .chain(bcc)
+        .any(|m| m.email.to_string().eq_ignore_ascii_case(own.email.as_ref()));
+    Ok(if already_included {
+        None
+    } else {
+        Some(Mailbox::new(None, own.email))
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmtpTestResult {
+    pub sent: bool,
+    pub trust_path: SmtpTrustPath,
 }
 
 #[tauri::command]
-async fn send_test_email(state: tauri::State<'_, DbState>) -> Result<bool, String> {
+async fn send_test_email(state: tauri::State<'_, DbState>) -> Result<SmtpTestResult, AppError> {
     let settings = state
         .with_read("send_test_email_settings", move |conn| read_settings_from_conn(conn))
         .await?;
@@ -3910,7 +6788,11 @@ async fn send_test_email(state: tauri::State<'_, DbState>) -> Result<bool, Strin
 
     let to_raw = settings.company_email.trim().to_string();
     if to_raw.is_empty() {
-        return Err("Company email is missing (Settings → Company → Email).".to_string());
+        return Err(AppError::validation(
+            "companyEmail",
+            "required",
+            "Company email is missing (Settings → Company → Email).",
+        ));
     }
 
     let from_mailbox: Mailbox = settings
@@ -3950,64 +6832,154 @@ async fn send_test_email(state: tauri::State<'_, DbState>) -> Result<bool, Strin
         )
         .map_err(|e| format!("Failed to build email: {e}"))?;
 
+    let credentials = resolve_smtp_credentials(&settings).await.map_err(AppError::SmtpSend)?;
+    let trust_path = smtp_trust_path(&settings);
     let settings = std::sync::Arc::new(settings);
 
     tauri::async_runtime::spawn_blocking(move || {
-        let transport = build_smtp_transport(&settings)?;
+        let transport = build_smtp_transport(&settings, credentials)?;
         transport.send(&email).map_err(|e| {
-            eprintln!("[email] test send failed: {e}");
-            format!("Failed to send email: {e}")
+            tracing::error!(target: "email", op = "test", trust = trust_path.as_str(), error = %e, "email send failed");
+            AppError::SmtpSend(format!("{e} (certificate trust: {})", trust_path.as_str()))
         })?;
-        Ok::<(), String>(())
+        Ok::<(), AppError>(())
     })
     .await
     .map_err(|e| e.to_string())??;
 
-    Ok(true)
+    Ok(SmtpTestResult { sent: true, trust_path })
 }
 
 #[tauri::command]
 async fn export_invoice_pdf_to_downloads(
     state: tauri::State<'_, DbState>,
     app: tauri::AppHandle,
-    payload: InvoicePdfPayload,
-) -> Result<String, String> {
-    let logo_url = state
+    mut payload: InvoicePdfPayload,
+    archival: Option<bool>,
+    open_after_export: Option<bool>,
+    unique_suffix: Option<bool>,
+    if_exists: Option<ExistingFile>,
+) -> Result<ExportedFile, AppError> {
+    let (logo_url, legal_note_override, rounding_mode, filename_template) = state
         .with_read("export_invoice_pdf_to_downloads_settings", move |conn| {
             let settings = read_settings_from_conn(conn)?;
-            Ok(settings.logo_url)
+            Ok((
+                settings.logo_url,
+                settings.legal_note_override,
+                settings.rounding_mode,
+                settings.pdf_filename_template,
+            ))
         })
         .await?;
+    payload.legal_note_override = legal_note_override;
+    payload.round_amounts(rounding_mode);
     let logo_url = logo_url.trim().to_string();
-    let bytes = generate_pdf_bytes(&payload, if logo_url.is_empty() { None } else { Some(logo_url.as_str()) })?;
+    let bytes = generate_pdf_bytes(
+        &payload,
+        if logo_url.is_empty() { None } else { Some(logo_url.as_str()) },
+        archival.unwrap_or(false),
+    )?;
 
     let downloads_dir = app
         .path()
         .download_dir()
         .map_err(|e| e.to_string())?;
 
-    let client_part = payload.client.name.trim();
-    let client_part = if client_part.is_empty() { "client" } else { client_part };
-    // NOTE: in debug builds, add a timestamp suffix to avoid PDF viewer caching false negatives.
-    // (Safe to revert later; release builds keep the stable name.)
-    let mut filename_stem = format!("{}-{}", payload.invoice_number, client_part);
-    if cfg!(debug_assertions) {
-        let ts_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis();
-        filename_stem.push_str(&format!("-{}", ts_ms));
+    let mut full_path = pdf_filename::pdf_export_path(
+        &downloads_dir,
+        &filename_template,
+        &PdfFilenameFields {
+            number: &payload.invoice_number,
+            client: &payload.client.name,
+            issue_date: &payload.issue_date,
+        },
+    )?;
+    if unique_suffix.unwrap_or(false) {
+        full_path = pdf_filename::with_unique_suffix(&full_path);
     }
-    let filename = sanitize_filename(&format!("{}.pdf", filename_stem));
-    let full_path = downloads_dir.join(filename);
+    let full_path = pdf_filename::resolve_existing(full_path, if_exists.unwrap_or_default())?;
 
     std::fs::write(&full_path, bytes).map_err(|e| e.to_string())?;
 
-    Ok(full_path.to_string_lossy().to_string())
+    let path = full_path.to_string_lossy().to_string();
+    Ok(ExportedFile::new(&app, path, open_after_export.unwrap_or(false)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CsvLineEnding {
+    Crlf,
+    Lf,
+}
+
+impl CsvLineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            CsvLineEnding::Crlf => "crlf",
+            CsvLineEnding::Lf => "lf",
+        }
+    }
+
+    fn eol(self) -> &'static str {
+        match self {
+            CsvLineEnding::Crlf => "\r\n",
+            CsvLineEnding::Lf => "\n",
+        }
+    }
+}
+
+fn parse_csv_line_ending_str(s: &str) -> CsvLineEnding {
+    match s.trim().to_lowercase().as_str() {
+        "lf" => CsvLineEnding::Lf,
+        _ => CsvLineEnding::Crlf,
+    }
+}
+
+/// CSV dialect of the exports. The default is plain RFC 4180; Excel with a Serbian locale wants
+/// `;`, decimal commas and a BOM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvOptions {
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: char,
+    #[serde(default)]
+    pub decimal_comma: bool,
+    #[serde(default)]
+    pub include_bom: bool,
+    #[serde(default = "default_csv_line_ending")]
+    pub line_ending: CsvLineEnding,
+}
+
+const CSV_DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+fn default_csv_line_ending() -> CsvLineEnding {
+    CsvLineEnding::Crlf
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: default_csv_delimiter(),
+            decimal_comma: false,
+            include_bom: false,
+            line_ending: default_csv_line_ending(),
+        }
+    }
+}
+
+fn validate_csv_options(o: CsvOptions) -> Result<CsvOptions, String> {
+    if !CSV_DELIMITERS.contains(&o.delimiter) {
+        return Err("CSV delimiter must be a comma, semicolon, tab or |.".to_string());
+    }
+    Ok(o)
 }
 
-fn csv_escape_field(input: &str) -> String {
-    let needs_quotes = input.contains(',') || input.contains('"') || input.contains('\n') || input.contains('\r');
+fn csv_escape_field(input: &str, opts: &CsvOptions) -> String {
+    let needs_quotes = input.contains(opts.delimiter) || input.contains('"') || input.contains('\n') || input.contains('\r');
     if !needs_quotes {
         return input.to_string();
     }
@@ -4015,28 +6987,130 @@ fn csv_escape_field(input: &str) -> String {
     format!("\"{}\"", escaped)
 }
 
-fn csv_join_row(fields: &[String]) -> String {
+fn csv_join_row(fields: &[String], opts: &CsvOptions) -> String {
     let mut out = String::new();
     for (i, f) in fields.iter().enumerate() {
         if i > 0 {
-            out.push(',');
+            out.push(opts.delimiter);
         }
-        out.push_str(&csv_escape_field(f));
+        out.push_str(&csv_escape_field(f, opts));
     }
     out
 }
 
-fn format_money_csv(v: f64) -> String {
-    // Raw decimal, dot separator, deterministic 2 decimals.
-    format!("{:.2}", v)
+/// Header row from static column names.
+fn csv_header_row(header: &[&str], opts: &CsvOptions) -> String {
+    csv_join_row(&header.iter().map(|s| s.to_string()).collect::<Vec<_>>(), opts)
+}
+
+/// Joins the rows with the configured line ending (plus a trailing one) and prepends the BOM.
+fn csv_document(lines: &[String], opts: &CsvOptions) -> String {
+    let eol = opts.line_ending.eol();
+    let bom = if opts.include_bom { "\u{feff}" } else { "" };
+    format!("{bom}{}{eol}", lines.join(eol))
+}
+
+/// Streams CSV rows to `W`; the bytes match `csv_document` over the same rows.
+struct CsvWriter<W: Write> {
+    out: std::io::BufWriter<W>,
+    opts: CsvOptions,
+    rows: usize,
+}
+
+impl<W: Write> CsvWriter<W> {
+    fn new(out: W, opts: CsvOptions) -> std::io::Result<Self> {
+        let mut out = std::io::BufWriter::new(out);
+        if opts.include_bom {
+            out.write_all("\u{feff}".as_bytes())?;
+        }
+        Ok(Self { out, opts, rows: 0 })
+    }
+
+    fn row(&mut self, fields: &[String]) -> std::io::Result<()> {
+        self.out.write_all(csv_join_row(fields, &self.opts).as_bytes())?;
+        self.out.write_all(self.opts.line_ending.eol().as_bytes())?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> std::io::Result<W> {
+        self.out.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+const CSV_PROGRESS_EVERY: usize = 500;
+const CSV_PROGRESS_EVENT: &str = "csv_export_progress";
+
+/// Sent every `CSV_PROGRESS_EVERY` rows while a CSV export streams to disk.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CsvExportProgress {
+    output_path: String,
+    rows: usize,
+}
+
+/// CSV file export: rows go through a `CsvWriter` into the file, flushed and reported every
+/// `CSV_PROGRESS_EVERY` rows. Write errors surface as `rusqlite::Error` so the export can run
+/// inside `with_read` while the rows are read.
+struct CsvFileExport {
+    writer: CsvWriter<std::fs::File>,
+    app: tauri::AppHandle,
+    output_path: String,
+}
+
+fn csv_write_error(e: std::io::Error) -> rusqlite::Error {
+    validation_to_sql_error(format!("Failed to write CSV: {e}"))
+}
+
+impl CsvFileExport {
+    fn create(app: tauri::AppHandle, output_path: String, opts: CsvOptions, header: &[&str]) -> Result<Self, rusqlite::Error> {
+        let path = std::path::Path::new(&output_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(csv_write_error)?;
+        }
+        let file = std::fs::File::create(path).map_err(csv_write_error)?;
+        let mut writer = CsvWriter::new(file, opts).map_err(csv_write_error)?;
+        writer
+            .row(&header.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+            .map_err(csv_write_error)?;
+        Ok(Self { writer, app, output_path })
+    }
+
+    fn row(&mut self, fields: &[String]) -> Result<(), rusqlite::Error> {
+        self.writer.row(fields).map_err(csv_write_error)?;
+        if self.writer.rows.is_multiple_of(CSV_PROGRESS_EVERY) {
+            self.writer.out.flush().map_err(csv_write_error)?;
+            let _ = self.app.emit(
+                CSV_PROGRESS_EVENT,
+                CsvExportProgress {
+                    output_path: self.output_path.clone(),
+                    rows: self.writer.rows,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), rusqlite::Error> {
+        self.writer.finish().and_then(|f| f.sync_all()).map_err(csv_write_error)
+    }
+}
+
+fn with_decimal_separator(s: String, opts: &CsvOptions) -> String {
+    if opts.decimal_comma { s.replace('.', ",") } else { s }
+}
+
+fn format_money_csv(v: f64, opts: &CsvOptions) -> String {
+    // Raw decimal, no grouping, deterministic 2 decimals.
+    with_decimal_separator(format!("{:.2}", v), opts)
 }
 
-fn format_quantity_csv(v: f64) -> String {
+fn format_quantity_csv(v: f64, opts: &CsvOptions) -> String {
     // Keep quantities readable without scientific notation for typical invoice values.
     // Trim trailing zeros for determinism.
     let s = format!("{:.6}", v);
     let s = s.trim_end_matches('0').trim_end_matches('.');
-    if s.is_empty() { "0".to_string() } else { s.to_string() }
+    with_decimal_separator(if s.is_empty() { "0".to_string() } else { s.to_string() }, opts)
 }
 
 fn write_text_file(path: &std::path::Path, contents: &str) -> Result<(), String> {
@@ -4046,67 +7120,137 @@ fn write_text_file(path: &std::path::Path, contents: &str) -> Result<(), String>
     std::fs::write(path, contents).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-async fn export_invoices_csv(
-    state: tauri::State<'_, DbState>,
-    from: String,
-    to: String,
-    output_path: String,
-) -> Result<String, String> {
-    let (default_currency, invoices) = state
-        .with_read("export_invoices_csv", move |conn| {
-            let settings = read_settings_from_conn(conn)?;
-            let mut stmt = conn.prepare(
-                r#"SELECT data_json
-                   FROM invoices
-                   WHERE issueDate >= ?1 AND issueDate <= ?2
-                   ORDER BY issueDate ASC, createdAt ASC"#,
-            )?;
-            let mut rows = stmt.query(params![from, to])?;
-            let mut out: Vec<Invoice> = Vec::new();
-            while let Some(row) = rows.next()? {
-                let json: String = row.get(0)?;
-                if let Ok(inv) = serde_json::from_str::<Invoice>(&json) {
-                    out.push(inv);
-                }
-            }
-            Ok((settings.default_currency, out))
-        })
-        .await?;
+fn write_bytes_file(path: &std::path::Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, contents).map_err(|e| e.to_string())
+}
 
-    let header = [
-        "invoiceId",
-        "invoiceNumber",
-        "issueDate",
-        "serviceDate",
-        "dueDate",
-        "paidAt",
-        "status",
-        "clientId",
-        "clientName",
-        "currency",
-        "isDefaultCurrency",
-        "subtotal",
-        "total",
-        "itemId",
-        "itemDescription",
-        "itemQuantity",
-        "itemUnitPrice",
-        "itemTotal",
-        "notes",
-        "createdAt",
-    ];
+/// The dialect passed by the caller, or the one saved in settings.
+fn resolve_csv_options(conn: &Connection, requested: Option<CsvOptions>) -> Result<CsvOptions, rusqlite::Error> {
+    match requested {
+        Some(o) => validate_csv_options(o).map_err(validation_to_sql_error),
+        None => read_settings_from_conn(conn).map(|s| s.csv_options),
+    }
+}
+
+/// Feeds each invoice issued in `[from, to]` to `f` in export order, straight off the cursor.
+fn for_each_export_invoice(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    mut f: impl FnMut(Invoice) -> Result<(), rusqlite::Error>,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT data_json
+           FROM invoices
+           WHERE issueDate >= ?1 AND issueDate <= ?2
+           ORDER BY issueDate ASC, createdAt ASC"#,
+    )?;
+    let mut rows = stmt.query(params![from, to])?;
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        if let Ok(inv) = serde_json::from_str::<Invoice>(&json) {
+            f(inv)?;
+        }
+    }
+    Ok(())
+}
 
-    let mut lines: Vec<String> = Vec::new();
-    lines.push(csv_join_row(&header.iter().map(|s| s.to_string()).collect::<Vec<_>>()));
+/// Feeds each non-deleted expense dated in `[from, to]` to `f` in export order.
+fn for_each_export_expense(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    mut f: impl FnMut(Expense) -> Result<(), rusqlite::Error>,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        r#"SELECT {EXPENSE_COLUMNS}
+           FROM expenses
+           WHERE deletedAt IS NULL AND date >= ?1 AND date <= ?2
+           ORDER BY date ASC, createdAt ASC"#
+    ))?;
+    let rows = stmt.query_map(params![from, to], expense_from_row)?;
+    for row in rows {
+        f(row?)?;
+    }
+    Ok(())
+}
 
-    for inv in invoices {
-        let is_default = inv.currency.trim() == default_currency.trim();
-        let due = inv.due_date.clone().unwrap_or_default();
-        let paid = inv.paid_at.clone().unwrap_or_default();
+/// Invoices issued in `[from, to]` with the default currency; used by the XLSX export and the
+/// period archive.
+fn invoices_for_export(conn: &Connection, from: &str, to: &str) -> Result<(String, Vec<Invoice>), rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let mut out: Vec<Invoice> = Vec::new();
+    for_each_export_invoice(conn, from, to, |inv| {
+        out.push(inv);
+        Ok(())
+    })?;
+    Ok((settings.default_currency, out))
+}
 
-        for item in inv.items.iter() {
-            let row = vec![
+/// Non-deleted expenses dated in `[from, to]` with the default currency; used by the XLSX export
+/// and the period archive.
+fn expenses_for_export(conn: &Connection, from: &str, to: &str) -> Result<(String, Vec<Expense>), rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let mut out: Vec<Expense> = Vec::new();
+    for_each_export_expense(conn, from, to, |exp| {
+        out.push(exp);
+        Ok(())
+    })?;
+    Ok((settings.default_currency, out))
+}
+
+const INVOICE_CSV_HEADER: [&str; 22] = [
+    "invoiceId",
+    "invoiceNumber",
+    "issueDate",
+    "serviceDate",
+    "dueDate",
+    "paidAt",
+    "status",
+    "clientId",
+    "clientName",
+    "currency",
+    "isDefaultCurrency",
+    "subtotal",
+    "total",
+    "itemId",
+    "itemDescription",
+    "itemQuantity",
+    "itemUnitPrice",
+    "itemTotal",
+    "notes",
+    "createdAt",
+    "issuedBy",
+    "customFields",
+];
+
+const EXPENSE_CSV_HEADER: [&str; 9] = [
+    "expenseId",
+    "date",
+    "title",
+    "category",
+    "amount",
+    "currency",
+    "isDefaultCurrency",
+    "notes",
+    "createdAt",
+];
+
+/// One CSV row per invoice item.
+fn invoice_csv_rows(default_currency: &str, inv: &Invoice, opts: &CsvOptions) -> Vec<Vec<String>> {
+    let is_default = inv.currency.trim() == default_currency.trim();
+    let due = inv.due_date.clone().unwrap_or_default();
+    let paid = inv.paid_at.clone().unwrap_or_default();
+    let amounts = invoice_amounts_of(inv);
+
+    inv.items
+        .iter()
+        .zip(&amounts.lines)
+        .map(|(item, line)| {
+            vec![
                 inv.id.clone(),
                 inv.invoice_number.clone(),
                 inv.issue_date.clone(),
@@ -4118,99 +7262,138 @@ async fn export_invoices_csv(
                 inv.client_name.clone(),
                 inv.currency.clone(),
                 if is_default { "true".to_string() } else { "false".to_string() },
-                format_money_csv(inv.subtotal),
-                format_money_csv(inv.total),
+                format_money_csv(from_minor(amounts.subtotal), opts),
+                format_money_csv(from_minor(amounts.total), opts),
                 item.id.clone(),
                 item.description.clone(),
-                format_quantity_csv(item.quantity),
-                format_money_csv(item.unit_price),
-                format_money_csv(item.total),
+                format_quantity_csv(item.quantity, opts),
+                format_money_csv(item.unit_price, opts),
+                format_money_csv(from_minor(line.total), opts),
                 inv.notes.clone(),
                 inv.created_at.clone(),
-            ];
-            lines.push(csv_join_row(&row));
+                inv.issued_by.clone().unwrap_or_default(),
+                custom_fields::custom_fields_csv(&inv.custom_fields),
+            ]
+        })
+        .collect()
+}
+
+fn expense_csv_row(default_currency: &str, exp: &Expense, opts: &CsvOptions) -> Vec<String> {
+    let is_default = exp.currency.trim() == default_currency.trim();
+    vec![
+        exp.id.clone(),
+        exp.date.clone(),
+        exp.title.clone(),
+        exp.category.clone().unwrap_or_default(),
+        format_money_csv(exp.amount, opts),
+        exp.currency.clone(),
+        if is_default { "true".to_string() } else { "false".to_string() },
+        exp.notes.clone().unwrap_or_default(),
+        exp.created_at.clone(),
+    ]
+}
+
+pub(crate) fn render_invoices_csv(default_currency: &str, invoices: &[Invoice], opts: &CsvOptions) -> String {
+    let mut lines = vec![csv_header_row(&INVOICE_CSV_HEADER, opts)];
+    for inv in invoices {
+        for row in invoice_csv_rows(default_currency, inv, opts) {
+            lines.push(csv_join_row(&row, opts));
         }
     }
+    csv_document(&lines, opts)
+}
+
+pub(crate) fn render_expenses_csv(default_currency: &str, expenses: &[Expense], opts: &CsvOptions) -> String {
+    let mut lines = vec![csv_header_row(&EXPENSE_CSV_HEADER, opts)];
+    for exp in expenses {
+        lines.push(csv_join_row(&expense_csv_row(default_currency, exp, opts), opts));
+    }
+    csv_document(&lines, opts)
+}
+
+/// Streams the invoices of `[from, to]` into a CSV file as they are read.
+#[tauri::command]
+async fn export_invoices_csv(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    from: String,
+    to: String,
+    output_path: String,
+    csv_options: Option<CsvOptions>,
+) -> Result<RangeExportResult, AppError> {
+    let (from, to) = normalize_date_range(&from, &to)?;
+    let (range_from, range_to, path) = (from.clone(), to.clone(), output_path.clone());
+    let row_count = state
+        .with_read("export_invoices_csv", move |conn| {
+            let opts = resolve_csv_options(conn, csv_options)?;
+            let default_currency = read_settings_from_conn(conn)?.default_currency;
+            let mut csv = CsvFileExport::create(app, path, opts, &INVOICE_CSV_HEADER)?;
+            let mut row_count = 0usize;
+            for_each_export_invoice(conn, &range_from, &range_to, |inv| {
+                row_count += 1;
+                for row in invoice_csv_rows(&default_currency, &inv, &opts) {
+                    csv.row(&row)?;
+                }
+                Ok(())
+            })?;
+            csv.finish()?;
+            Ok(row_count)
+        })
+        .await?;
+    Ok(RangeExportResult {
+        output_path,
+        from,
+        to,
+        row_count,
+    })
+}
 
-    let csv = lines.join("\r\n") + "\r\n";
+#[tauri::command]
+async fn export_invoice_ubl_to_path(
+    state: tauri::State<'_, DbState>,
+    invoice_id: String,
+    output_path: String,
+) -> Result<String, AppError> {
+    let xml = state
+        .with_read("export_invoice_ubl_to_path", move |conn| ubl::generate_ubl_xml(conn, &invoice_id))
+        .await?;
     let path = std::path::PathBuf::from(&output_path);
-    write_text_file(&path, &csv)?;
+    write_text_file(&path, &xml)?;
     Ok(output_path)
 }
 
+/// Streams the expenses of `[from, to]` into a CSV file as they are read.
 #[tauri::command]
 async fn export_expenses_csv(
+    app: tauri::AppHandle,
     state: tauri::State<'_, DbState>,
     from: String,
     to: String,
     output_path: String,
-) -> Result<String, String> {
-    let (default_currency, expenses) = state
+    csv_options: Option<CsvOptions>,
+) -> Result<RangeExportResult, AppError> {
+    let (from, to) = normalize_date_range(&from, &to)?;
+    let (range_from, range_to, path) = (from.clone(), to.clone(), output_path.clone());
+    let row_count = state
         .with_read("export_expenses_csv", move |conn| {
-            let settings = read_settings_from_conn(conn)?;
-            let mut stmt = conn.prepare(
-                r#"SELECT id, title, amount, currency, date, category, notes, createdAt
-                   FROM expenses
-                   WHERE date >= ?1 AND date <= ?2
-                   ORDER BY date ASC, createdAt ASC"#,
-            )?;
-
-            let rows = stmt.query_map(params![from, to], |r| {
-                Ok(Expense {
-                    id: r.get(0)?,
-                    title: r.get(1)?,
-                    amount: r.get(2)?,
-                    currency: r.get(3)?,
-                    date: r.get(4)?,
-                    category: r.get(5)?,
-                    notes: r.get(6)?,
-                    created_at: r.get(7)?,
-                })
+            let opts = resolve_csv_options(conn, csv_options)?;
+            let default_currency = read_settings_from_conn(conn)?.default_currency;
+            let mut csv = CsvFileExport::create(app, path, opts, &EXPENSE_CSV_HEADER)?;
+            let mut row_count = 0usize;
+            for_each_export_expense(conn, &range_from, &range_to, |exp| {
+                row_count += 1;
+                csv.row(&expense_csv_row(&default_currency, &exp, &opts))
             })?;
-
-            let mut out: Vec<Expense> = Vec::new();
-            for row in rows {
-                out.push(row?);
-            }
-            Ok((settings.default_currency, out))
+            csv.finish()?;
+            Ok(row_count)
         })
         .await?;
-
-    let header = [
-        "expenseId",
-        "date",
-        "title",
-        "category",
-        "amount",
-        "currency",
-        "isDefaultCurrency",
-        "notes",
-        "createdAt",
-    ];
-
-    let mut lines: Vec<String> = Vec::new();
-    lines.push(csv_join_row(&header.iter().map(|s| s.to_string()).collect::<Vec<_>>()));
-
-    for exp in expenses {
-        let is_default = exp.currency.trim() == default_currency.trim();
-        let row = vec![
-            exp.id,
-            exp.date,
-            exp.title,
-            exp.category.unwrap_or_default(),
-            format_money_csv(exp.amount),
-            exp.currency,
-            if is_default { "true".to_string() } else { "false".to_string() },
-            exp.notes.unwrap_or_default(),
-            exp.created_at,
-        ];
-        lines.push(csv_join_row(&row));
-    }
-
-    let csv = lines.join("\r\n") + "\r\n";
-    let path = std::path::PathBuf::from(&output_path);
-    write_text_file(&path, &csv)?;
-    Ok(output_path)
+    Ok(RangeExportResult {
+        output_path,
+        from,
+        to,
+        row_count,
+    })
 }
 
 #[tauri::command]
@@ -4282,10 +7465,10 @@ fn copy_dir_recursive(src: &PathBuf, dest: &PathBuf) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn download_update_installer(app: tauri::AppHandle, url: String) -> Result<String, String> {
+async fn download_update_installer(app: tauri::AppHandle, url: String) -> Result<String, AppError> {
     let u = url.trim();
     if u.is_empty() {
-        return Err("Missing download URL".to_string());
+        return Err(AppError::validation("url", "required", "Missing download URL"));
     }
 
     let dir = resolve_updates_dir(&app)?;
@@ -4309,7 +7492,7 @@ async fn download_update_installer(app: tauri::AppHandle, url: String) -> Result
 
     let status = resp.status();
     if !status.is_success() {
-        return Err(format!("Download failed (HTTP {status})"));
+        return Err(format!("Download failed (HTTP {status})").into());
     }
 
     let total = resp.content_length();
@@ -4343,14 +7526,14 @@ async fn download_update_installer(app: tauri::AppHandle, url: String) -> Result
 }
 
 #[tauri::command]
-fn run_installer_and_exit(app: tauri::AppHandle, installer_path: String) -> Result<bool, String> {
+fn run_installer_and_exit(app: tauri::AppHandle, installer_path: String) -> Result<bool, AppError> {
     if !cfg!(target_os = "windows") {
-        return Err("Update installer is only supported on Windows.".to_string());
+        return Err("Update installer is only supported on Windows.".into());
     }
 
     let p = PathBuf::from(installer_path);
     if !p.exists() {
-        return Err("Installer file not found".to_string());
+        return Err("Installer file not found".into());
     }
 
     std::process::Command::new(&p)
@@ -4366,6 +7549,25 @@ pub fn run() {
     tauri::Builder::default()
         .setup(|app| {
             let handle = app.handle();
+            match resolve_app_data_root(handle) {
+                Ok(root) => {
+                    if let Err(e) = logging::init(&root.join(logging::LOG_DIR)) {
+                        eprintln!("Startup: file logging disabled: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Startup: file logging disabled: {}", e),
+            }
+            // Before anything touches the database: a second launch only brings the first forward.
+            match resolve_app_data_root(handle).and_then(|root| single_instance::acquire(handle, &root)) {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::info!(target: "startup", "already running; focused the open window");
+                    std::process::exit(0);
+                }
+                Err(e) => tracing::warn!(target: "startup", error = %e, "single-instance check failed"),
+            }
+            #[cfg(debug_assertions)]
+            bindings::export();
             {
                 let root = resolve_app_data_root(&handle)?;
                 if let Ok(dir) = handle.path().app_data_dir() {
@@ -4373,8 +7575,17 @@ pub fn run() {
                 } else {
                     println!("Startup: app_data_dir = <unavailable>");
                 }
-                let db_path = resolve_db_path(&handle)?;
-                println!("Startup: db_path = {}", db_path.display());
+                let db_location = db_location::resolve(&handle)?;
+                let db_path = db_location.path.clone();
+                println!("Startup: db_path = {} (source={:?})", db_path.display(), db_location.source);
+                if !db_location.others.is_empty() {
+                    let others: Vec<String> = db_location.others.iter().map(|(_, p)| p.to_string_lossy().to_string()).collect();
+                    eprintln!("Startup: other database files exist and are ignored: {}", others.join(", "));
+                    let _ = handle.emit(
+                        "database_candidates_found",
+                        serde_json::json!({ "path": db_path.to_string_lossy(), "others": others }),
+                    );
+                }
                 let db_wal = wal_path(&db_path);
                 let db_shm = shm_path(&db_path);
                 println!(
@@ -4484,9 +7695,34 @@ pub fn run() {
                     }
                 }
                 println!("Continuing normal startup");
+
+                let backup_state = backups::AutoBackupState::default();
+                match backups::run_startup_backup(&db_path, &root.join(backups::BACKUPS_DIR), OffsetDateTime::now_utc()) {
+                    Ok(Some(path)) => println!("Startup: automatic backup -> {}", path.display()),
+                    Ok(None) => {}
+                    Err(e) => {
+                        // Never block startup on a failed backup.
+                        eprintln!("Startup: automatic backup failed: {}", e);
+                        let _ = handle.emit("auto_backup_failed", serde_json::json!({ "message": e }));
+                        if let Ok(mut last) = backup_state.last_error.lock() {
+                            *last = Some(e);
+                        }
+                    }
+                }
+                app.manage(backup_state);
             }
             let db = DbState::new(&handle)?;
+            // A set PIN locks the app from the start; reading it can only fail on a broken database.
+            let start_locked = match db.conn.lock() {
+                Ok(conn) => app_lock::pin_is_set(&conn).unwrap_or(false),
+                Err(_) => false,
+            };
+            app.manage(app_lock::AppLock::new(start_locked));
             app.manage(db);
+            app.manage(outbox::OutboxWorker::default());
+            outbox::spawn_outbox_worker(handle.clone());
+            webhooks::spawn_webhook_worker(handle.clone());
+            idempotency::spawn_idempotency_housekeeping(handle.clone());
 
             // Best-effort sanity check: never panic/crash if embedded labels are invalid.
             sanity_check_embedded_invoice_email_labels();
@@ -4495,7 +7731,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(app_lock::guarded(tauri::generate_handler![
             greet,
             quit_app,
             download_update_installer,
@@ -4504,10 +7740,35 @@ pub fn run() {
             get_last_backup_metadata,
             inspect_backup_archive,
             stage_restore_archive,
+            list_backups,
+            restore_backup,
             list_serbia_cities,
             export_invoice_pdf_to_downloads,
+            open_path,
+            reveal_in_folder,
             export_invoices_csv,
+            export_invoice_ubl_to_path,
             export_expenses_csv,
+            export_invoices_xlsx,
+            export_expenses_xlsx,
+            export_period_archive,
+            email_report_to_accountant,
+            export_kpo_csv,
+            export_kpo_pdf,
+            export_monthly_report_pdf,
+            dashboard_summary,
+            top_clients,
+            top_items,
+            limit_status,
+            accounts_receivable_report,
+            suggest_status_updates,
+            get_app_mode,
+            retry_writable,
+            export_accounts_receivable_csv,
+            tax_summary,
+            export_tax_summary_csv,
+            export_all_json,
+            import_all_json,
             get_app_meta,
             set_app_meta,
             hash_pib,
@@ -4515,15 +7776,49 @@ pub fn run() {
             get_force_lock_level_env,
             generate_activation_code,
             verify_license,
+            get_activation_code,
+            activate_license,
+            get_license_status,
+            license_expiry_info,
+            deactivate_license,
+            is_feature_enabled,
+            get_trial_status,
             get_settings,
             update_settings,
+            validate_settings,
+            set_company_logo,
+            get_company_logo,
+            preview_legal_note,
+        list_available_languages,
+            export_settings,
+            import_settings,
+            get_setup_state,
+            complete_setup_step,
+            finish_setup,
             generate_invoice_number,
             preview_next_invoice_number,
             get_all_clients,
+            find_duplicate_clients,
+            merge_clients,
+            archive_client,
+            unarchive_client,
+            import_clients_csv,
+            read_csv_headers,
+            validate_tax_ids,
             get_client_by_id,
             create_client,
             update_client,
             delete_client,
+            check_data_consistency,
+            repair_data_consistency,
+            list_corrupt_rows,
+            repair_corrupt_row,
+            database_maintenance,
+            get_database_info,
+            move_database_to,
+            get_recent_logs,
+            get_log_level,
+            set_log_level,
             get_all_offers,
             get_offer_by_id,
             create_offer,
@@ -4539,75 +7834,347 @@ pub fn run() {
             list_expenses,
             create_expense,
             update_expense,
+            get_expense_by_id,
             delete_expense,
+            restore_expense,
+            duplicate_expense,
+            split_expense,
+            list_expenses_for_invoice,
+            bill_expenses_to_invoice,
+            purge_deleted_expenses,
+            expense_summary,
+            import_expenses_csv,
+            import_invoices_csv,
+            export_invoice_json,
+            import_invoice_json,
+            invoices_by_year,
+            import_payments_csv,
+            resolve_payment_match,
+            global_search,
+            list_recurring_expenses,
+            create_recurring_expense,
+            update_recurring_expense,
+            delete_recurring_expense,
+            run_due_recurring_expenses,
+            list_obligations,
+            create_obligation,
+            update_obligation,
+            delete_obligation,
+            generate_monthly_obligation_expenses,
+            set_obligation_month_paid,
+            obligations_status,
+            list_catalog_items,
+            create_catalog_item,
+            update_catalog_item,
+            delete_catalog_item,
+            search_catalog,
+            catalog_usage_report,
+            list_time_entries,
+            create_time_entry,
+            update_time_entry,
+            delete_time_entry,
+            get_active_timer,
+            start_timer,
+            stop_timer,
+            bill_time_entries,
+            unbilled_time_report,
+            list_quotes,
+            get_quote,
+            create_quote,
+            update_quote,
+            delete_quote,
+            convert_quote_to_invoice,
+            export_quote_pdf_to_downloads,
+            send_quote_email,
+            test_webhook,
+            list_webhook_deliveries,
+            get_app_lock_status,
+            set_app_pin,
+            clear_app_pin,
+            lock_app,
+            verify_app_pin,
             send_invoice_email,
+            list_outbox,
+            retry_outbox_item,
+            cancel_outbox_item,
             send_test_email,
             send_license_request_email
-        ])
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
-fn validate_smtp_settings(s: &Settings) -> Result<(), String> {
-    if s.smtp_host.trim().is_empty() {
-        return Err("SMTP is not configured: missing host (Settings → Email).".to_string());
+fn validate_smtp_settings(s: &Settings) -> Result<(), AppError> {
+    if s.smtp_host.trim().is_empty() {
+        return Err(AppError::smtp_config("missingHost", "SMTP is not configured: missing host (Settings → Email)."));
+    }
+    if s.smtp_port <= 0 || s.smtp_port > 65535 {
+        return Err(AppError::smtp_config("invalidPort", "SMTP is not configured: invalid port (Settings → Email)."));
+    }
+    if s.smtp_from.trim().is_empty() {
+        return Err(AppError::smtp_config(
+            "missingFrom",
+            "SMTP is not configured: missing From address (Settings → Email).",
+        ));
+    }
+    match s.smtp_auth_mode {
+        SmtpAuthMode::Password => {
+            let user_empty = s.smtp_user.trim().is_empty();
+            let pass_empty = s.smtp_password.trim().is_empty();
+            if user_empty ^ pass_empty {
+                return Err(AppError::smtp_config(
+                    "authPair",
+                    "SMTP auth is not configured correctly: set both user and password, or leave both empty.",
+                ));
+            }
+        }
+        SmtpAuthMode::Oauth2 => {
+            if s.smtp_user.trim().is_empty() {
+                return Err(AppError::smtp_config(
+                    "oauthMissingUser",
+                    "SMTP OAuth2 is not configured: missing user (the mailbox address) (Settings → Email → Authentication).",
+                ));
+            }
+            if s.smtp_oauth_client_id.trim().is_empty() {
+                return Err(AppError::smtp_config(
+                    "oauthMissingClientId",
+                    "SMTP OAuth2 is not configured: missing client ID (Settings → Email → Authentication).",
+                ));
+            }
+            if s.smtp_oauth_refresh_token.is_empty() {
+                return Err(AppError::smtp_config(
+                    "oauthMissingRefreshToken",
+                    "SMTP OAuth2 is not configured: missing refresh token (Settings → Email → Authentication).",
+                ));
+            }
+            if s.smtp_oauth_token_endpoint.trim().is_empty() {
+                return Err(AppError::smtp_config(
+                    "oauthMissingTokenEndpoint",
+                    "SMTP OAuth2 is not configured: missing token endpoint (Settings → Email → Authentication).",
+                ));
+            }
+            validate_smtp_oauth_token_endpoint(s.smtp_oauth_token_endpoint.trim())
+                .map_err(|e| AppError::smtp_config("oauthTokenEndpoint", e))?;
+        }
+    }
+
+    validate_smtp_accept_invalid_certs(s).map_err(|e| AppError::smtp_config("invalidCertsPublicHost", e))?;
+
+    if s.smtp_use_tls {
+        let mode = resolved_smtp_tls_mode(s.smtp_tls_mode, s.smtp_port);
+        if s.smtp_port == 465 && mode != SmtpTlsMode::Implicit {
+            return Err(AppError::smtp_config(
+                "tlsMismatch",
+                "SMTP TLS mode mismatch: port 465 requires Implicit TLS (SMTPS).",
+            ));
+        }
+        if s.smtp_port == 587 && mode != SmtpTlsMode::Starttls {
+            return Err(AppError::smtp_config("tlsMismatch", "SMTP TLS mode mismatch: port 587 requires STARTTLS."));
+        }
+    }
+    Ok(())
+}
+
+const MAX_SMTP_FROM_NAME_CHARS: usize = 100;
+
+/// How the SMTP server certificate is trusted; reported by the test email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SmtpTrustPath {
+    SystemRoots,
+    PinnedCertificate,
+    AcceptInvalidCerts,
+    NoTls,
+}
+
+impl SmtpTrustPath {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SmtpTrustPath::SystemRoots => "system roots",
+            SmtpTrustPath::PinnedCertificate => "pinned certificate",
+            SmtpTrustPath::AcceptInvalidCerts => "invalid certificates accepted",
+            SmtpTrustPath::NoTls => "no TLS",
+        }
     }
-    if s.smtp_port <= 0 || s.smtp_port > 65535 {
-        return Err("SMTP is not configured: invalid port (Settings → Email).".to_string());
+}
+
+fn smtp_trust_path(s: &Settings) -> SmtpTrustPath {
+    if !s.smtp_use_tls {
+        SmtpTrustPath::NoTls
+    } else if !s.smtp_pinned_cert_pem.trim().is_empty() {
+        SmtpTrustPath::PinnedCertificate
+    } else if s.smtp_accept_invalid_certs {
+        SmtpTrustPath::AcceptInvalidCerts
+    } else {
+        SmtpTrustPath::SystemRoots
     }
-    if s.smtp_from.trim().is_empty() {
-        return Err("SMTP is not configured: missing From address (Settings → Email).".to_string());
+}
+
+fn parse_pinned_certificate(pem: &str) -> Result<Certificate, String> {
+    // The PEM parser accepts input without any certificate block, which would trust nothing.
+    if !pem.contains("-----BEGIN CERTIFICATE-----") {
+        return Err("Invalid pinned SMTP certificate: expected a PEM block (-----BEGIN CERTIFICATE-----).".to_string());
+    }
+    Certificate::from_pem(pem.as_bytes()).map_err(|e| format!("Invalid pinned SMTP certificate (PEM): {e}"))
+}
+
+/// Loopback, RFC 1918 / unique-local, link-local and `.local`/`.lan`-style names.
+fn is_private_smtp_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(ip)) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+        Ok(std::net::IpAddr::V6(ip)) => {
+            let first = ip.segments()[0];
+            ip.is_loopback() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+        }
+        Err(_) => {
+            let h = host.trim_end_matches('.').to_ascii_lowercase();
+            h == "localhost"
+                || [".localhost", ".local", ".lan", ".internal", ".home.arpa"]
+                    .iter()
+                    .any(|suffix| h.ends_with(suffix))
+        }
     }
-    let user_empty = s.smtp_user.trim().is_empty();
-    let pass_empty = s.smtp_password.trim().is_empty();
-    if user_empty ^ pass_empty {
-        return Err("SMTP auth is not configured correctly: set both user and password, or leave both empty.".to_string());
+}
+
+fn validate_smtp_accept_invalid_certs(s: &Settings) -> Result<(), String> {
+    if s.smtp_accept_invalid_certs
+        && !s.smtp_accept_invalid_certs_acknowledged
+        && !is_private_smtp_host(&s.smtp_host)
+    {
+        return Err(format!(
+            "Accepting invalid SMTP certificates is only allowed for private hosts; {} is public. Pin the server certificate instead, or confirm that you understand the risk.",
+            s.smtp_host.trim()
+        ));
     }
+    Ok(())
+}
 
-    if s.smtp_use_tls {
-        let mode = resolved_smtp_tls_mode(s.smtp_tls_mode, s.smtp_port);
-        if s.smtp_port == 465 && mode != SmtpTlsMode::Implicit {
-            return Err("SMTP TLS mode mismatch: port 465 requires Implicit TLS (SMTPS).".to_string());
+/// TLS parameters for both the implicit TLS and STARTTLS paths. A pinned certificate replaces
+/// the system roots entirely.
+fn smtp_tls_parameters(s: &Settings, host: &str) -> Result<TlsParameters, String> {
+    let mut builder = TlsParameters::builder(host.to_string());
+    match smtp_trust_path(s) {
+        SmtpTrustPath::PinnedCertificate => {
+            let cert = parse_pinned_certificate(s.smtp_pinned_cert_pem.trim())?;
+            builder = builder
+                .certificate_store(CertificateStore::None)
+                .add_root_certificate(cert);
         }
-        if s.smtp_port == 587 && mode != SmtpTlsMode::Starttls {
-            return Err("SMTP TLS mode mismatch: port 587 requires STARTTLS.".to_string());
+        SmtpTrustPath::AcceptInvalidCerts => {
+            builder = builder.dangerous_accept_invalid_certs(true);
+        }
+        SmtpTrustPath::SystemRoots | SmtpTrustPath::NoTls => {}
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to configure TLS parameters: {e}"))
+}
+
+fn validate_smtp_oauth_token_endpoint(v: &str) -> Result<(), String> {
+    if v.is_empty() {
+        return Ok(());
+    }
+    match tauri::Url::parse(v) {
+        Ok(url) if url.scheme() == "https" => Ok(()),
+        _ => Err(format!("OAuth2 token endpoint must be an https:// URL: {v}")),
+    }
+}
+
+/// Credentials for `build_smtp_transport`. OAuth2 needs an async token refresh, so this runs
+/// before the blocking send.
+async fn resolve_smtp_credentials(s: &Settings) -> Result<Option<Credentials>, String> {
+    match s.smtp_auth_mode {
+        SmtpAuthMode::Password => {
+            if s.smtp_user.trim().is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(Credentials::new(
+                s.smtp_user.clone(),
+                secrets::resolve(secrets::SMTP_PASSWORD, &s.smtp_password)?,
+            )))
         }
+        SmtpAuthMode::Oauth2 => {
+            let token = smtp_oauth::access_token(s).await?;
+            Ok(Some(Credentials::new(s.smtp_user.trim().to_string(), token)))
+        }
+    }
+}
+
+fn validate_smtp_reply_to(v: &str) -> Result<(), String> {
+    if v.is_empty() {
+        return Ok(());
+    }
+    v.parse::<lettre::Address>()
+        .map(|_| ())
+        .map_err(|_| format!("Invalid Reply-To address: {v}"))
+}
+
+fn validate_smtp_from_name(v: &str) -> Result<(), String> {
+    if v.chars().any(char::is_control) {
+        return Err("Sender display name must not contain line breaks or control characters.".to_string());
+    }
+    if v.chars().count() > MAX_SMTP_FROM_NAME_CHARS {
+        return Err(format!("Sender display name must be at most {MAX_SMTP_FROM_NAME_CHARS} characters."));
     }
     Ok(())
 }
 
-fn build_smtp_transport(s: &Settings) -> Result<SmtpTransport, String> {
+/// From mailbox: `smtp_from` with `smtp_from_name` as the display name when set.
+fn sender_mailbox(s: &Settings) -> Result<Mailbox, String> {
+    let mut mailbox: Mailbox = s
+        .smtp_from
+        .parse()
+        .map_err(|_| "Invalid From address in SMTP settings.".to_string())?;
+    let name = s.smtp_from_name.trim();
+    if !name.is_empty() {
+        validate_smtp_from_name(name)?;
+        mailbox.name = Some(name.to_string());
+    }
+    Ok(mailbox)
+}
+
+fn reply_to_mailbox(s: &Settings) -> Result<Option<Mailbox>, String> {
+    let v = s.smtp_reply_to.trim();
+    if v.is_empty() {
+        return Ok(None);
+    }
+    validate_smtp_reply_to(v)?;
+    v.parse::<Mailbox>()
+        .map(Some)
+        .map_err(|_| format!("Invalid Reply-To address: {v}"))
+}
+
+fn build_smtp_transport(s: &Settings, credentials: Option<Credentials>) -> Result<SmtpTransport, AppError> {
     validate_smtp_settings(s)?;
     let port: u16 = u16::try_from(s.smtp_port)
-        .map_err(|_| "SMTP is not configured: invalid port (Settings → Email).".to_string())?;
+        .map_err(|_| AppError::smtp_config("invalidPort", "SMTP is not configured: invalid port (Settings → Email)."))?;
 
     let host = s.smtp_host.trim();
     if host.is_empty() {
-        return Err("SMTP is not configured: missing host (Settings → Email).".to_string());
+        return Err(AppError::smtp_config("missingHost", "SMTP is not configured: missing host (Settings → Email)."));
     }
 
     let mut builder = if s.smtp_use_tls {
+        let tls_params = smtp_tls_parameters(s, host).map_err(|e| AppError::smtp_config("tls", e))?;
         match resolved_smtp_tls_mode(s.smtp_tls_mode, s.smtp_port) {
-            SmtpTlsMode::Implicit => {
-                let tls_params = TlsParameters::new(host.to_string())
-                    .map_err(|e| format!("Failed to configure TLS parameters: {e}"))?;
-                SmtpTransport::builder_dangerous(host)
-                    .port(port)
-                    .tls(Tls::Wrapper(tls_params))
-            }
-            SmtpTlsMode::Starttls => SmtpTransport::starttls_relay(host)
-                .map_err(|e| format!("Invalid SMTP host: {e}"))?
-                .port(port),
+            SmtpTlsMode::Implicit => SmtpTransport::builder_dangerous(host)
+                .port(port)
+                .tls(Tls::Wrapper(tls_params)),
+            SmtpTlsMode::Starttls => SmtpTransport::builder_dangerous(host)
+                .port(port)
+                .tls(Tls::Required(tls_params)),
         }
     } else {
         SmtpTransport::builder_dangerous(host).port(port)
     };
+    builder = builder.timeout(Some(smtp_timeout(s.smtp_timeout_seconds)));
 
-    if !s.smtp_user.trim().is_empty() {
-        builder = builder.credentials(Credentials::new(
-            s.smtp_user.clone(),
-            s.smtp_password.clone(),
-        ));
+    if let Some(credentials) = credentials {
+        builder = builder.credentials(credentials);
+        if s.smtp_auth_mode == SmtpAuthMode::Oauth2 {
+            builder = builder.authentication(vec![Mechanism::Xoauth2]);
+        }
     }
 
     Ok(builder.build())
@@ -4625,22 +8192,51 @@ fn read_invoice_from_conn(conn: &Connection, id: &str) -> Result<Option<Invoice>
     Ok(json.and_then(|j| serde_json::from_str::<Invoice>(&j).ok()))
 }
 
+/// Column list matching `expense_from_row`.
+const EXPENSE_COLUMNS: &str = "id, title, amount, currency, date, category, notes, createdAt, recurringId, deletedAt, invoiceId";
+
+fn expense_from_row(r: &rusqlite::Row<'_>) -> Result<Expense, rusqlite::Error> {
+    Ok(Expense {
+        id: r.get(0)?,
+        title: r.get(1)?,
+        amount: r.get(2)?,
+        currency: r.get(3)?,
+        date: r.get(4)?,
+        category: r.get(5)?,
+        notes: r.get(6)?,
+        created_at: r.get(7)?,
+        recurring_id: r.get(8)?,
+        deleted_at: r.get(9)?,
+        invoice_id: r.get(10)?,
+    })
+}
+
+fn insert_expense_row(conn: &Connection, e: &Expense) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"INSERT INTO expenses (id, title, amount, currency, date, category, notes, createdAt, recurringId, deletedAt, invoiceId)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+        params![
+            e.id,
+            e.title,
+            e.amount,
+            e.currency,
+            e.date,
+            e.category,
+            e.notes,
+            e.created_at,
+            e.recurring_id,
+            e.deleted_at,
+            e.invoice_id,
+        ],
+    )?;
+    Ok(())
+}
+
 fn read_expense_from_conn(conn: &Connection, id: &str) -> Result<Option<Expense>, rusqlite::Error> {
     conn.query_row(
-        "SELECT id, title, amount, currency, date, category, notes, createdAt FROM expenses WHERE id = ?1",
+        &format!("SELECT {EXPENSE_COLUMNS} FROM expenses WHERE id = ?1"),
         params![id],
-        |r| {
-            Ok(Expense {
-                id: r.get(0)?,
-                title: r.get(1)?,
-                amount: r.get(2)?,
-                currency: r.get(3)?,
-                date: r.get(4)?,
-                category: r.get(5)?,
-                notes: r.get(6)?,
-                created_at: r.get(7)?,
-            })
-        },
+        expense_from_row,
     )
     .optional()
 }
@@ -4657,44 +8253,122 @@ fn read_client_from_conn(conn: &Connection, id: &str) -> Result<Option<Client>,
     Ok(json.and_then(|j| serde_json::from_str::<Client>(&j).ok()))
 }
 
-fn build_invoice_pdf_payload_from_db(invoice: &Invoice, client: Option<&Client>, settings: &Settings) -> InvoicePdfPayload {
-    let mut computed_subtotal: f64 = 0.0;
-    let mut computed_discount_total: f64 = 0.0;
-    let mut computed_total: f64 = 0.0;
+/// Sum of line totals after line discounts (each line discount clamped to its line subtotal).
+fn invoice_net_after_line_discounts(items: &[InvoiceItem]) -> f64 {
+    items
+        .iter()
+        .map(|it| {
+            let line_subtotal = it.quantity * it.unit_price;
+            line_subtotal - it.discount_amount.unwrap_or(0.0).clamp(0.0, line_subtotal.max(0.0))
+        })
+        .sum()
+}
+
+fn money_lines(items: &[InvoiceItem]) -> impl Iterator<Item = money::Line> + '_ {
+    items.iter().map(|it| money::Line {
+        quantity: it.quantity,
+        unit_price: it.unit_price,
+        discount: it.discount_amount,
+    })
+}
+
+/// Line totals, discounts and the amount to pay, rounded with the invoice's rounding mode.
+fn invoice_amounts_of(invoice: &Invoice) -> InvoiceAmounts {
+    invoice_amounts(
+        money_lines(&invoice.items),
+        invoice.invoice_discount,
+        invoice.invoice_discount_percent,
+        invoice.rounding_mode,
+    )
+}
+
+/// Recomputes the stored line totals, subtotal and total (the amount to pay, after the invoice
+/// discount) from the items, so they match what the PDF prints whatever the frontend sent.
+fn recompute_invoice_totals(invoice: &mut Invoice) {
+    let amounts = invoice_amounts_of(invoice);
+    for (item, line) in invoice.items.iter_mut().zip(&amounts.lines) {
+        item.total = from_minor(line.total);
+    }
+    invoice.subtotal = from_minor(amounts.subtotal);
+    invoice.total = from_minor(amounts.total);
+}
+
+/// Older versions stored the total of discounted invoices before the invoice discount.
+fn recompute_discounted_invoice_totals(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, data_json FROM invoices
+         WHERE json_valid(data_json)
+           AND (COALESCE(json_extract(data_json, '$.invoiceDiscount'), 0) > 0
+                OR COALESCE(json_extract(data_json, '$.invoiceDiscountPercent'), 0) > 0)",
+    )?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, json) in rows {
+        let Ok(mut invoice) = serde_json::from_str::<Invoice>(&json) else {
+            continue;
+        };
+        invoice.total = from_minor(invoice_amounts_of(&invoice).total);
+        let json = serde_json::to_string(&invoice).unwrap_or(json);
+        conn.execute(
+            "UPDATE invoices SET totalAmount = ?2, data_json = ?3 WHERE id = ?1",
+            params![id, invoice.total, json],
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_invoice_discount(items: &[InvoiceItem], amount: Option<f64>, percent: Option<f64>) -> Result<(), String> {
+    if let Some(a) = amount {
+        if !a.is_finite() || a < 0.0 {
+            return Err("Invoice discount must be a non-negative amount.".to_string());
+        }
+    }
+    if let Some(p) = percent {
+        if !p.is_finite() || !(0.0..=100.0).contains(&p) {
+            return Err("Invoice discount percent must be between 0 and 100.".to_string());
+        }
+    }
 
+    let net = invoice_net_after_line_discounts(items).max(0.0);
+    let requested = amount.unwrap_or(0.0) + net * percent.unwrap_or(0.0) / 100.0;
+    // Small epsilon so a discount equal to the net (total = 0) isn't rejected by float noise.
+    if requested > net + 0.005 {
+        return Err("Invoice discount cannot exceed the invoice total (total can't be negative).".to_string());
+    }
+    Ok(())
+}
+
+fn build_invoice_pdf_payload_from_db(invoice: &Invoice, client: Option<&Client>, settings: &Settings) -> InvoicePdfPayload {
+    let amounts = invoice_amounts_of(invoice);
+    let payment_account = bank_accounts::payment_account(settings, &invoice.currency);
     let items: Vec<InvoicePdfItem> = invoice
         .items
         .iter()
-        .map(|it| {
-            let line_subtotal = it.quantity * it.unit_price;
-            let raw_discount = it.discount_amount.unwrap_or(0.0);
-            let line_discount = raw_discount.clamp(0.0, line_subtotal);
-            let line_total = line_subtotal - line_discount;
-
-            computed_subtotal += line_subtotal;
-            computed_discount_total += line_discount;
-            computed_total += line_total;
-
-            InvoicePdfItem {
-                description: it.description.clone(),
-                unit: it.unit.clone().filter(|s| !s.trim().is_empty()),
-                quantity: it.quantity,
-                unit_price: it.unit_price,
-                discount_amount: if line_discount > 0.0 { Some(line_discount) } else { None },
-                total: line_total,
-            }
+        .zip(&amounts.lines)
+        .map(|(it, line)| InvoicePdfItem {
+            description: it.description.clone(),
+            unit: it.unit.clone().filter(|s| !s.trim().is_empty()),
+            quantity: it.quantity,
+            unit_price: it.unit_price,
+            discount_amount: (line.discount > 0).then(|| from_minor(line.discount)),
+            total: from_minor(line.total),
         })
         .collect();
 
     InvoicePdfPayload {
-        language: Some(settings.language.clone()),
+        language: Some(invoice_language(settings, client)),
         invoice_number: invoice.invoice_number.clone(),
         issue_date: invoice.issue_date.clone(),
         service_date: invoice.service_date.clone(),
         currency: invoice.currency.clone(),
-        subtotal: computed_subtotal,
-        discount_total: computed_discount_total,
-        total: computed_total,
+        subtotal: from_minor(amounts.subtotal),
+        discount_total: from_minor(amounts.line_discounts),
+        invoice_discount: (amounts.invoice_discount > 0).then(|| from_minor(amounts.invoice_discount)),
+        invoice_discount_percent: invoice.invoice_discount_percent.filter(|p| *p > 0.0),
+        payment_method: invoice.payment_method,
+        payment_reference: invoice.payment_reference.clone(),
+        total: from_minor(amounts.total),
         notes: Some(invoice.notes.clone()),
         company: InvoicePdfCompany {
             company_name: settings.company_name.clone(),
@@ -4719,7 +8393,9 @@ fn build_invoice_pdf_payload_from_db(invoice: &Invoice, client: Option<&Client>,
             address_line: Some(settings.company_address_line.clone()).filter(|s| !s.trim().is_empty()),
             postal_code: Some(settings.company_postal_code.clone()).filter(|s| !s.trim().is_empty()),
             city: Some(settings.company_city.clone()).filter(|s| !s.trim().is_empty()),
-            bank_account: settings.bank_account.clone(),
+            bank_account: payment_account.account_number,
+            iban: payment_account.iban,
+            swift: payment_account.swift,
             email: Some(settings.company_email.clone()).filter(|s| !s.trim().is_empty()),
             phone: Some(settings.company_phone.clone()).filter(|s| !s.trim().is_empty()),
         },
@@ -4734,110 +8410,126 @@ fn build_invoice_pdf_payload_from_db(invoice: &Invoice, client: Option<&Client>,
             postal_code: client.map(|c| c.postal_code.clone()).filter(|s| !s.trim().is_empty()),
             city: client.map(|c| c.city.clone()).filter(|s| !s.trim().is_empty()),
             email: client.map(|c| c.email.clone()).filter(|s| !s.trim().is_empty()),
-            phone: None,
+            phone: client.and_then(|c| c.phone.clone()).filter(|s| !s.trim().is_empty()),
+            is_foreign: client.is_some_and(|c| c.is_foreign),
+            country: client.and_then(|c| c.country.clone()),
         },
         items,
+        legal_note_override: settings.legal_note_override.clone(),
+        valid_until: None,
+        issued_by: invoice.issued_by.clone(),
+        custom_fields: invoice.custom_fields.clone(),
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct MandatoryInvoiceNoteLocale {
     lines: Vec<String>,
+    /// Appended for foreign clients with a VAT ID.
+    #[serde(default, rename = "reverseChargeLines")]
+    reverse_charge_lines: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct MandatoryInvoiceNoteTemplates {
-    sr: MandatoryInvoiceNoteLocale,
-    en: MandatoryInvoiceNoteLocale,
-}
-
-static MANDATORY_NOTE_TEMPLATES: OnceLock<MandatoryInvoiceNoteTemplates> = OnceLock::new();
+static MANDATORY_NOTE_TEMPLATES: OnceLock<LocaleTable> = OnceLock::new();
 
-fn mandatory_invoice_note_templates() -> &'static MandatoryInvoiceNoteTemplates {
-    MANDATORY_NOTE_TEMPLATES.get_or_init(|| {
-        let json = include_str!("../../src/shared/mandatoryInvoiceNote.json");
-        serde_json::from_str::<MandatoryInvoiceNoteTemplates>(json)
-            .unwrap_or_else(|_| MandatoryInvoiceNoteTemplates {
-                sr: MandatoryInvoiceNoteLocale { lines: vec![] },
-                en: MandatoryInvoiceNoteLocale { lines: vec![] },
-            })
-    })
+/// Note template of `lang`, falling back like the PDF labels; empty when the table is unreadable.
+fn mandatory_invoice_note_locale(lang: &str) -> MandatoryInvoiceNoteLocale {
+    let table = MANDATORY_NOTE_TEMPLATES
+        .get_or_init(|| locales::parse_table(include_str!("../../src/shared/mandatoryInvoiceNote.json")));
+    locales::resolve(table, lang).unwrap_or_default()
 }
 
-fn mandatory_invoice_note_lines(lang: &str, invoice_number: &str) -> Vec<String> {
-    let l = lang.to_ascii_lowercase();
-    let templates = mandatory_invoice_note_templates();
-    let lines = if l.starts_with("en") {
-        &templates.en.lines
+/// Legal note lines for the invoice language. A non-blank override replaces the built-in lines
+/// of its language; the reverse-charge lines always come from the templates.
+fn mandatory_invoice_note_lines(
+    lang: &str,
+    invoice_number: &str,
+    reverse_charge: bool,
+    overrides: &LegalNoteOverride,
+) -> Vec<String> {
+    let locale_lines = |key: &str| -> Vec<String> {
+        let loc = mandatory_invoice_note_locale(key);
+        let mut lines: Vec<String> = match overrides.for_lang(key) {
+            Some(text) => text.trim().lines().map(|line| line.trim_end().to_string()).collect(),
+            None => loc.lines,
+        };
+        if reverse_charge {
+            lines.extend(loc.reverse_charge_lines);
+        }
+        lines
+    };
+    let lines = if is_bilingual_pdf_language(lang) {
+        let mut lines = locale_lines("sr");
+        lines.extend(locale_lines("en"));
+        lines
     } else {
-        &templates.sr.lines
+        locale_lines(&locales::language_code(lang))
     };
 
     lines
-        .iter()
+        .into_iter()
         .map(|line| line.replace("{INVOICE_NUMBER}", invoice_number))
         .collect()
 }
 
-fn mandatory_invoice_note_text(lang: &str, invoice_number: &str) -> String {
-    mandatory_invoice_note_lines(lang, invoice_number).join("\n")
+fn mandatory_invoice_note_text(
+    lang: &str,
+    invoice_number: &str,
+    reverse_charge: bool,
+    overrides: &LegalNoteOverride,
+) -> String {
+    mandatory_invoice_note_lines(lang, invoice_number, reverse_charge, overrides).join("\n")
 }
 
-fn mandatory_invoice_note_html(lang: &str, invoice_number: &str) -> String {
-    mandatory_invoice_note_lines(lang, invoice_number)
+fn mandatory_invoice_note_html(
+    lang: &str,
+    invoice_number: &str,
+    reverse_charge: bool,
+    overrides: &LegalNoteOverride,
+) -> String {
+    mandatory_invoice_note_lines(lang, invoice_number, reverse_charge, overrides)
         .into_iter()
         .map(|l| escape_html(&l))
         .collect::<Vec<_>>()
         .join("<br/>")
 }
 
-fn draw_inline_labeled_row(
-    layer: &printpdf::PdfLayerReference,
-    font: &printpdf::IndirectFontRef,
-    ttf_face: &ttf_parser::Face<'_>,
-    label: &str,
-    value: &str,
-    font_size: f32,
-    x: f32,
-    y: f32,
-    max_width_total: f32,
-    line_height: f32,
-    row_gap: f32,
-) -> f32 {
-    let v = value.trim();
-    if v.is_empty() {
-        return y;
-    }
-
-    // Exactly ONE space after the colon.
-    let prefix = format!("{}: ", label);
-    let prefix_w = text_width_mm_ttf(ttf_face, &prefix, font_size);
-    let value_x = x + prefix_w;
-    let value_w = (max_width_total - prefix_w).max(6.0);
-
-    let value_lines = wrap_text_by_width_mm(ttf_face, v, font_size, value_w);
-    if value_lines.is_empty() {
-        return y;
-    }
-
-    push_line(layer, font, &prefix, font_size, x, y);
-    push_line(layer, font, &value_lines[0], font_size, value_x, y);
-
-    for (idx, line) in value_lines.iter().enumerate().skip(1) {
-        let yy = y - (idx as f32) * line_height;
-        push_line(layer, font, line, font_size, value_x, yy);
+/// The legal note as invoice `sample_number` would print it in `lang`. `text` previews an unsaved
+/// override; without it the saved one (or the built-in note) is used.
+#[tauri::command]
+async fn preview_legal_note(
+    state: tauri::State<'_, DbState>,
+    lang: String,
+    sample_number: String,
+    text: Option<String>,
+) -> Result<String, AppError> {
+    let mut overrides = state
+        .with_read("preview_legal_note", |conn| Ok(read_settings_from_conn(conn)?.legal_note_override))
+        .await?;
+    let lang = lang.trim().to_ascii_lowercase();
+    if let Some(text) = text {
+        match locales::language_code(&lang).as_str() {
+            "en" => overrides.en = text,
+            "sr" => overrides.sr = text,
+            _ => {}
+        }
     }
-
-    y - (value_lines.len() as f32) * line_height - row_gap
+    Ok(mandatory_invoice_note_text(&lang, sample_number.trim(), false, &overrides))
 }
 
 #[tauri::command]
-async fn get_app_meta(state: tauri::State<'_, DbState>, key: String) -> Result<Option<String>, String> {
+async fn get_app_meta(state: tauri::State<'_, DbState>, key: String) -> Result<Option<String>, AppError> {
+    if app_lock::is_reserved_meta_key(&key) {
+        return Err(format!("{key} can't be read directly.").into());
+    }
     state.with_read("get_app_meta", move |conn| app_meta_get(conn, &key)).await
 }
 
 #[tauri::command]
-async fn set_app_meta(state: tauri::State<'_, DbState>, key: String, value: String) -> Result<bool, String> {
+async fn set_app_meta(state: tauri::State<'_, DbState>, key: String, value: String) -> Result<bool, AppError> {
+    if app_lock::is_reserved_meta_key(&key) {
+        return Err(format!("{key} can't be written directly.").into());
+    }
     state
         .with_write("set_app_meta", move |conn| {
             app_meta_set(conn, &key, &value)?;
@@ -4848,7 +8540,7 @@ async fn set_app_meta(state: tauri::State<'_, DbState>, key: String, value: Stri
 
 #[tauri::command]
 fn hash_pib(pib: String) -> String {
-    license::crypto::sha256_hex(pib.trim())
+    license_activation::pib_hash(&pib)
 }
 
 #[tauri::command]
@@ -4897,19 +8589,16 @@ fn get_force_lock_level_env() -> Option<String> {
 }
 
 #[tauri::command]
-fn generate_activation_code(pib: String) -> Result<String, String> {
-    let pib_hash = license::crypto::sha256_hex(pib.trim());
-    let app_id = "com.dstankovski.pausaler-app".to_string();
+fn generate_activation_code(pib: String) -> Result<String, AppError> {
+    let pib_hash = license_activation::pib_hash(&pib);
     let issued_at = OffsetDateTime::now_utc().unix_timestamp();
-    license::activation_code::generate_activation_code(pib_hash, app_id, issued_at)
+    Ok(license::activation_code::generate_activation_code(pib_hash, license_activation::APP_ID.to_string(), issued_at)?)
 }
 
 #[tauri::command]
-fn verify_license(license: String, pib: String) -> Result<license::license_payload::VerifiedLicenseInfo, String> {
-    let public_key_pem = include_str!("../assets/public_key.pem");
-    let pib_hash = license::crypto::sha256_hex(pib.trim());
-    let now = OffsetDateTime::now_utc();
-    license::license_validator::verify_license(&license, &pib_hash, public_key_pem, now)
+fn verify_license(license: String, pib: String) -> Result<license::license_payload::VerifiedLicenseInfo, AppError> {
+    let pib_hashes = license_activation::accepted_pib_hashes(&pib);
+    Ok(license_activation::verify(&license, &pib_hashes, license_activation::PUBLIC_KEYS, OffsetDateTime::now_utc())?)
 }
 
 /// Sends a generic license request email using configured SMTP.
@@ -4919,7 +8608,7 @@ async fn send_license_request_email(
     state: tauri::State<'_, DbState>,
     input: SendLicenseRequestEmailInput,
 )
-    -> Result<bool, String>
+    -> Result<bool, AppError>
 {
     let settings = state
         .with_read("send_license_request_email_settings", move |conn| read_settings_from_conn(conn))
@@ -5090,24 +8779,93 @@ async fn send_license_request_email(
 async fn send_email_via_smtp(
     settings: std::sync::Arc<Settings>,
     email: Message,
-    _label: &str,
-) -> Result<(), String> {
+    label: &str,
+) -> Result<(), AppError> {
     let host = settings.smtp_host.clone();
     let port = settings.smtp_port;
     let tls_mode = resolved_smtp_tls_mode(settings.smtp_tls_mode, settings.smtp_port);
-    let _ = (host, port, tls_mode);
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let transport = build_smtp_transport(&settings)?;
-        transport.send(&email).map_err(|e| format!("Failed to send email: {e}"))?;
-        Ok::<(), String>(())
+    let credentials = resolve_smtp_credentials(&settings).await.map_err(AppError::SmtpSend)?;
+    let oauth = settings.smtp_auth_mode == SmtpAuthMode::Oauth2;
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let transport = build_smtp_transport(&settings, credentials)?;
+        send_with_retry(&transport, &email).map_err(AppError::SmtpSend)
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?;
+    if let Err(e) = &result {
+        tracing::error!(
+            target: "email",
+            op = label,
+            host = %host,
+            port,
+            tls = tls_mode.as_str(),
+            oauth,
+            error = %e,
+            "email send failed"
+        );
+        if oauth {
+            // The server may have revoked the cached token; fetch a fresh one next time.
+            smtp_oauth::invalidate_cached_token();
+        }
+    }
+    result?;
 
     Ok(())
 }
 
+const SMTP_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// 4xx replies and dropped/timed-out connections are worth one more attempt; 5xx and auth/TLS
+/// failures are not.
+fn is_transient_smtp_error(e: &lettre::transport::smtp::Error) -> bool {
+    if e.is_transient() || e.is_timeout() {
+        return true;
+    }
+    let mut source = std::error::Error::source(e);
+    while let Some(err) = source {
+        if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
+            return is_transient_io_error_kind(io_err.kind());
+        }
+        source = err.source();
+    }
+    false
+}
+
+fn is_transient_io_error_kind(kind: std::io::ErrorKind) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        kind,
+        ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::Interrupted
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+/// Sends once and, on a transient failure, retries a single time after a short backoff.
+fn send_with_retry(transport: &SmtpTransport, email: &Message) -> Result<(), String> {
+    match transport.send(email) {
+        Ok(_) => Ok(()),
+        Err(first) if is_transient_smtp_error(&first) => {
+            tracing::warn!(
+                target: "email",
+                op = "send_retry",
+                error = %first,
+                "transient send failure, retrying once"
+            );
+            std::thread::sleep(SMTP_RETRY_BACKOFF);
+            transport
+                .send(email)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to send email after 1 retry: {e} (first attempt: {first})"))
+        }
+        Err(e) => Err(format!("Failed to send email (not retried): {e}")),
+    }
+}
+
 fn read_metadata_from_zip<R: std::io::Read + std::io::Seek>(mut ar: ZipArchive<R>) -> Result<BackupMetadataResult, String> {
     let mut file = ar.by_name("metadata.json").map_err(|_| "metadata.json not found".to_string())?;
     let mut buf = Vec::new();
@@ -5125,28 +8883,23 @@ fn read_metadata_from_zip<R: std::io::Read + std::io::Seek>(mut ar: ZipArchive<R
 }
 
 #[tauri::command]
-async fn inspect_backup_archive(archive_path: String) -> Result<BackupMetadataResult, String> {
+async fn inspect_backup_archive(archive_path: String) -> Result<BackupMetadataResult, AppError> {
     let f = std::fs::File::open(&archive_path).map_err(|e| e.to_string())?;
     let ar = ZipArchive::new(f).map_err(|e| e.to_string())?;
-    read_metadata_from_zip(ar)
+    Ok(read_metadata_from_zip(ar)?)
 }
 
 #[tauri::command]
-async fn create_backup_archive(app: tauri::AppHandle, dest_path: String) -> Result<BackupResult, String> {
+async fn create_backup_archive(app: tauri::AppHandle, dest_path: String) -> Result<BackupResult, AppError> {
     // Resolve destination and ensure parent exists
     let dest = PathBuf::from(dest_path);
     let parent = dest.parent().ok_or_else(|| "Invalid destination path".to_string())?;
     fs::create_dir_all(parent).map_err(|e| e.to_string())?;
 
-    // Resolve app_data_dir strictly from current runtime
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app_data_dir: {}", e))?;
-    let db_path = app_data_dir.join("pausaler.db");
+    // Back up the recorded database, wherever the user moved it
+    let db_path = resolve_db_path(&app)?;
 
     // Diagnostics before zipping
-    println!("Backup: app_data_dir = {}", app_data_dir.display());
     println!("Backup: db_path = {}", db_path.display());
     let db_meta = fs::metadata(&db_path).ok();
     let db_exists = db_meta.is_some();
@@ -5156,7 +8909,7 @@ async fn create_backup_archive(app: tauri::AppHandle, dest_path: String) -> Resu
 
     // Safety guards
     if !db_exists {
-        return Err(format!("No database found at {}", db_path.display()));
+        return Err(format!("No database found at {}", db_path.display()).into());
     }
     const DB_SUSPICIOUS_MIN_SIZE_BYTES: u64 = 200 * 1024; // 200KB
     if db_size < DB_SUSPICIOUS_MIN_SIZE_BYTES {
@@ -5164,7 +8917,7 @@ async fn create_backup_archive(app: tauri::AppHandle, dest_path: String) -> Resu
             "Database appears too small ({} bytes) at {}. Backup aborted.",
             db_size,
             db_path.display()
-        ));
+        ).into());
     }
 
     // Force WAL changes into main DB before zipping
@@ -5226,11 +8979,11 @@ async fn create_backup_archive(app: tauri::AppHandle, dest_path: String) -> Resu
 }
 
 #[tauri::command]
-async fn get_last_backup_metadata(app: tauri::AppHandle) -> Result<LastBackupInfo, String> {
+async fn get_last_backup_metadata(app: tauri::AppHandle) -> Result<LastBackupInfo, AppError> {
     let root = resolve_app_data_root(&app)?;
     let lb_path = root.join("last-backup.json");
     if !lb_path.exists() {
-        return Err("NO_LAST_BACKUP".to_string());
+        return Err("NO_LAST_BACKUP".into());
     }
     let buf = fs::read(&lb_path).map_err(|e| e.to_string())?;
     let parsed: LastBackupJson = serde_json::from_slice(&buf).map_err(|e| e.to_string())?;
@@ -5246,7 +8999,7 @@ async fn get_last_backup_metadata(app: tauri::AppHandle) -> Result<LastBackupInf
 }
 
 #[tauri::command]
-async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> Result<RestoreStageResult, String> {
+async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> Result<RestoreStageResult, AppError> {
     let f = std::fs::File::open(&archive_path).map_err(|e| e.to_string())?;
     let mut ar = ZipArchive::new(f).map_err(|e| e.to_string())?;
     let _meta = read_metadata_from_zip(ZipArchive::new(std::fs::File::open(&archive_path).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?)?;
@@ -5256,7 +9009,7 @@ async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> R
         let name = ar.by_index(i).map_err(|e| e.to_string())?.name().to_string();
         if name == "pausaler.db" { has_db = true; break; }
     }
-    if !has_db { return Err("Archive missing pausaler.db".to_string()); }
+    if !has_db { return Err("Archive missing pausaler.db".into()); }
 
     let root = resolve_app_data_root(&app)?;
     let stage_dir = root.join("restore_stage").join(format!("{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis()));
@@ -5267,7 +9020,7 @@ async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> R
         let name = file.name().to_string();
         let allowed = name == "pausaler.db" || name == "metadata.json" || name.starts_with("assets/");
         if !allowed { continue; }
-        if name.contains("../") { return Err("Invalid archive entry path".to_string()); }
+        if name.contains("../") { return Err("Invalid archive entry path".into()); }
         let out_path = safe_join(&stage_dir, &name).ok_or_else(|| "Invalid path".to_string())?;
         if let Some(parent) = out_path.parent() { fs::create_dir_all(parent).map_err(|e| e.to_string())?; }
         let mut out_file = std::fs::File::create(&out_path).map_err(|e| e.to_string())?;
@@ -5275,7 +9028,7 @@ async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> R
     }
 
     let staged_db = stage_dir.join("pausaler.db");
-    if !staged_db.exists() { return Err("Failed to stage database".to_string()); }
+    if !staged_db.exists() { return Err("Failed to stage database".into()); }
 
     let restore_dir = root.join("restore");
     fs::create_dir_all(&restore_dir).map_err(|e| e.to_string())?;
@@ -5293,4 +9046,1069 @@ async fn stage_restore_archive(app: tauri::AppHandle, archive_path: String) -> R
     std::fs::write(&plan_path, serde_json::to_vec(&plan).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
 
     Ok(RestoreStageResult { staged_at: plan["createdAt"].as_str().unwrap_or("").to_string(), requires_restart: true })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf_util::wrap_text_by_width_mm;
+    use std::io::Cursor;
+
+    // An issuer block as tall as a company name and five rows.
+    const BLOCK_TOP_Y: f32 = 260.0;
+    const BLOCK_BOTTOM_Y: f32 = 236.0;
+
+    /// The header columns `render_pdf_bytes` uses with a logo, and the logo placed in them.
+    fn place(px_w: u32, px_h: u32) -> (HeaderColumns, LogoPlacement) {
+        let columns = HeaderColumns::new(true);
+        (columns, columns.place_logo(px_w, px_h, BLOCK_TOP_Y, BLOCK_BOTTOM_Y))
+    }
+
+    /// Asserts the logo lies inside the logo box, right of the issuer column.
+    fn assert_in_logo_box(columns: &HeaderColumns, p: &LogoPlacement, px_w: u32) {
+        let w_mm = px_w as f32 / LOGO_DPI * 25.4 * p.scale;
+        assert!(columns.issuer_left_x < columns.left_col_right_x, "{columns:?}");
+        assert!(p.x > columns.left_col_right_x, "logo x {} overlaps issuer text ({columns:?})", p.x);
+        assert!(p.x >= columns.logo_box_left_x - 0.001);
+        assert!(p.x + w_mm <= columns.right_x + 0.001);
+        assert!(w_mm <= MAX_LOGO_W_MM + 0.001);
+    }
+
+    #[test]
+    fn issuer_column_takes_the_full_width_without_a_logo() {
+        let with_logo = HeaderColumns::new(true);
+        let without = HeaderColumns::new(false);
+        assert_eq!(without.left_col_right_x, without.right_x);
+        assert!(with_logo.issuer_left_x < with_logo.left_col_right_x);
+        assert!(with_logo.left_col_right_x < with_logo.logo_box_left_x);
+        assert!(with_logo.logo_box_left_x < with_logo.right_x);
+        assert_eq!(with_logo.issuer_left_x, without.issuer_left_x);
+    }
+
+    fn png_data_url(w: u32, h: u32) -> String {
+        use base64::Engine as _;
+        let img = printpdf::image_crate::DynamicImage::new_rgb8(w, h);
+        let mut buf = Vec::new();
+        img.write_to(&mut Cursor::new(&mut buf), printpdf::image_crate::ImageOutputFormat::Png)
+            .unwrap();
+        format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(buf)
+        )
+    }
+
+    #[test]
+    fn wide_banner_logo_stays_right_of_issuer_text() {
+        let (columns, p) = place(6000, 60);
+        assert_in_logo_box(&columns, &p, 6000);
+        // Width-limited logos are centered vertically in the issuer block.
+        let mid = p.bottom_y + p.h_mm / 2.0;
+        assert!((mid - (BLOCK_TOP_Y + BLOCK_BOTTOM_Y) / 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn tall_logo_is_height_limited_and_top_aligned() {
+        let (columns, p) = place(60, 6000);
+        assert_in_logo_box(&columns, &p, 60);
+        assert!(p.h_mm <= BLOCK_TOP_Y - BLOCK_BOTTOM_Y + 0.001);
+        assert!((p.bottom_y + p.h_mm - BLOCK_TOP_Y).abs() < 0.01);
+    }
+
+    #[test]
+    fn square_logo_fills_block_height() {
+        let (columns, p) = place(600, 600);
+        assert_in_logo_box(&columns, &p, 600);
+        assert!((p.h_mm - (BLOCK_TOP_Y - BLOCK_BOTTOM_Y)).abs() < 0.01);
+    }
+
+    #[test]
+    fn tiny_logo_is_rejected() {
+        let err = decode_logo_image(&png_data_url(20, 20)).unwrap_err();
+        assert!(err.contains("minimum"), "{err}");
+        assert!(decode_logo_image(&png_data_url(50, 50)).is_ok());
+    }
+
+    fn sample_payload() -> InvoicePdfPayload {
+        InvoicePdfPayload {
+            language: Some("sr".to_string()),
+            invoice_number: "2024-001".to_string(),
+            issue_date: "2024-03-01".to_string(),
+            service_date: "2024-03-01".to_string(),
+            currency: "RSD".to_string(),
+            subtotal: 1000.0,
+            discount_total: 0.0,
+            invoice_discount: None,
+            invoice_discount_percent: None,
+            total: 1000.0,
+            notes: None,
+            payment_method: Some(PaymentMethod::Transfer),
+            payment_reference: Some("97 04-2024-001".to_string()),
+            company: InvoicePdfCompany {
+                company_name: "Preduzetnik Petrović".to_string(),
+                registration_number: "12345678".to_string(),
+                pib: "123456789".to_string(),
+                address: "Knez Mihailova 1".to_string(),
+                address_line: None,
+                postal_code: Some("11000".to_string()),
+                city: Some("Beograd".to_string()),
+                bank_account: "160-0000000000000-00".to_string(),
+                iban: Some("RS35260005601001611379".to_string()),
+                swift: Some("AIKBRS22".to_string()),
+                email: None,
+                phone: None,
+            },
+            client: InvoicePdfClient {
+                name: "Klijent d.o.o.".to_string(),
+                registration_number: Some("87654321".to_string()),
+                pib: Some("987654321".to_string()),
+                address: Some("Bulevar 2".to_string()),
+                address_line: None,
+                postal_code: None,
+                city: None,
+                email: None,
+                phone: None,
+                is_foreign: false,
+                country: None,
+            },
+            items: vec![InvoicePdfItem {
+                description: "Usluga programiranja".to_string(),
+                unit: Some("sat".to_string()),
+                quantity: 10.0,
+                unit_price: 100.0,
+                discount_amount: None,
+                total: 1000.0,
+            }],
+            legal_note_override: Default::default(),
+            valid_until: None,
+            issued_by: Some("Petar Petrović".to_string()),
+            custom_fields: vec![CustomField { label: "PO broj".to_string(), value: "4500123".to_string() }],
+        }
+    }
+
+    #[test]
+    fn archival_pdf_has_pdfa_structures() {
+        use printpdf::lopdf::{Document, Object};
+
+        let logo = png_data_url(80, 80);
+        let bytes = generate_pdf_bytes(&sample_payload(), Some(&logo), true).unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.4\n%"));
+        assert!(bytes[10..14].iter().all(|b| *b > 127));
+
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert!(doc.trailer.get(b"ID").is_ok());
+        let catalog = doc.catalog().unwrap();
+        assert!(catalog.get(b"OCProperties").is_err());
+
+        let intents = catalog.get(b"OutputIntents").unwrap().as_array().unwrap();
+        let intent = intents[0].as_dict().unwrap();
+        assert_eq!(intent.get(b"S").unwrap().as_name().unwrap(), b"GTS_PDFA1");
+        let icc_id = intent.get(b"DestOutputProfile").unwrap().as_reference().unwrap();
+        let icc = doc.get_object(icc_id).unwrap().as_stream().unwrap();
+        assert_eq!(icc.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+
+        let meta_id = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+        let meta = doc.get_object(meta_id).unwrap().as_stream().unwrap();
+        assert!(meta.dict.get(b"Filter").is_err());
+        let xmp = String::from_utf8_lossy(&meta.content);
+        assert!(xmp.contains("<pdfaid:part>1</pdfaid:part>"));
+        assert!(xmp.contains("<pdfaid:conformance>B</pdfaid:conformance>"));
+
+        let mut saw_font_file = false;
+        for obj in doc.objects.values() {
+            match obj {
+                Object::Stream(st)
+                    if st.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice()) =>
+                {
+                    assert!(st.dict.get(b"SMask").is_err());
+                    assert!(!st.dict.get(b"Interpolate").unwrap().as_bool().unwrap());
+                }
+                Object::Dictionary(d) => {
+                    if d.get(b"FontFile2").is_ok() {
+                        saw_font_file = true;
+                    }
+                    if let Ok(desc) = d.get(b"DescendantFonts").and_then(Object::as_array) {
+                        let cid = desc[0].as_dict().unwrap();
+                        assert_eq!(cid.get(b"CIDToGIDMap").unwrap().as_name().unwrap(), b"Identity");
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert!(saw_font_file);
+    }
+
+    #[test]
+    fn regular_pdf_has_no_output_intent() {
+        let bytes = generate_pdf_bytes(&sample_payload(), None, false).unwrap();
+        let doc = printpdf::lopdf::Document::load_mem(&bytes).unwrap();
+        assert!(doc.catalog().unwrap().get(b"OutputIntents").is_err());
+    }
+
+    #[test]
+    fn bilingual_labels_join_both_locales() {
+        let labels = pdf_labels("bilingual");
+        assert_eq!(labels.invoice_title, "FAKTURA / INVOICE");
+        assert_eq!(labels.total_for_payment, "UKUPNO ZA UPLATU / TOTAL DUE");
+        // Identical labels are not duplicated; errors stay Serbian.
+        assert_eq!(labels.col_amount, "TOTAL");
+        assert_eq!(labels.err_too_many_items, pdf_labels("sr").err_too_many_items);
+        assert!(labels.invoice_title_service_invoice_no.ends_with(": "));
+    }
+
+    #[test]
+    fn bilingual_pdf_renders() {
+        let mut payload = sample_payload();
+        payload.language = Some("bilingual".to_string());
+        assert!(generate_pdf_bytes(&payload, None, false).is_ok());
+    }
+
+    fn test_face() -> ttf_parser::Face<'static> {
+        ttf_parser::Face::parse(include_bytes!("../assets/DejaVuSans.ttf"), 0).unwrap()
+    }
+
+    fn assert_wrapped_within(face: &ttf_parser::Face<'_>, input: &str, size: f32, max_w: f32) -> Vec<String> {
+        let lines = wrap_text_by_width_mm(face, input, size, max_w);
+        for line in &lines {
+            assert!(text_width_mm_ttf(face, line, size) <= max_w + 0.001, "line too wide: {line}");
+        }
+        let joined: String = lines.concat().split_whitespace().collect();
+        let expected: String = input.split_whitespace().collect();
+        assert_eq!(joined, expected, "text was lost while wrapping");
+        lines
+    }
+
+    #[test]
+    fn measured_wrap_handles_cyrillic() {
+        let face = test_face();
+        let text = "Израда веб апликације за евиденцију рачуна и праћење уплата клијената током године";
+        let lines = assert_wrapped_within(&face, text, 8.3, 60.0);
+        assert!(lines.len() > 1);
+    }
+
+    #[test]
+    fn measured_wrap_handles_mixed_scripts() {
+        let face = test_face();
+        let text = "Consulting услуге — održavanje sistema (Ђорђе Šćepanović) WWWWWWWW iiiiiiii";
+        assert_wrapped_within(&face, text, 8.3, 35.0);
+    }
+
+    #[test]
+    fn measured_wrap_breaks_long_token() {
+        let face = test_face();
+        let token = format!("https://example.com/{}", "a".repeat(180));
+        assert_eq!(token.chars().count(), 200);
+        let lines = assert_wrapped_within(&face, &format!("Link: {token} kraj"), 8.3, 50.0);
+        assert!(lines.len() >= 4);
+    }
+
+    #[test]
+    fn split_wrap_keeps_explicit_line_breaks() {
+        let face = test_face();
+        let lines = split_and_wrap_lines_by_width_mm(&face, "prvi\n\n  drugi  ", 8.5, 100.0);
+        assert_eq!(lines, vec!["prvi".to_string(), "drugi".to_string()]);
+    }
+
+    fn model97_is_valid(reference: &str) -> bool {
+        let body = reference.strip_prefix("97 ").unwrap();
+        let (control, number) = body.split_at(2);
+        let digits: String = number
+            .chars()
+            .filter(|c| *c != '-')
+            .map(|c| c.to_digit(36).unwrap().to_string())
+            .collect::<String>()
+            + control;
+        digits.chars().fold(0u32, |rem, c| (rem * 10 + c.to_digit(10).unwrap()) % 97) == 1
+    }
+
+    #[test]
+    fn model97_reference_known_examples() {
+        assert_eq!(compute_model97_reference("2024-001").unwrap(), "97 04-2024-001");
+        assert_eq!(compute_model97_reference("12345").unwrap(), "97 20-12345");
+        assert_eq!(compute_model97_reference("1").unwrap(), "97 95-1");
+        // Letters map to 10..35 (ISO 7064), case-insensitive.
+        assert_eq!(compute_model97_reference("inv-2024-7").unwrap(), "97 52-INV-2024-7");
+    }
+
+    #[test]
+    fn model97_reference_passes_mod97_check() {
+        for n in ["2024-001", "FAK/2024/15", "000123", "A1B2C3"] {
+            let r = compute_model97_reference(n).unwrap();
+            assert!(model97_is_valid(&r), "{n} -> {r}");
+        }
+    }
+
+    #[test]
+    fn model97_reference_rejects_bad_input() {
+        assert!(compute_model97_reference(" -- ").is_err());
+        assert!(compute_model97_reference(&"9".repeat(30)).is_err());
+    }
+
+    #[test]
+    fn send_invoice_email_input_accepts_single_or_many_recipients() {
+        let legacy: SendInvoiceEmailInput = serde_json::from_value(serde_json::json!({
+            "invoiceId": "1", "to": "a@example.com", "subject": "S"
+        }))
+        .unwrap();
+        assert_eq!(legacy.to, vec!["a@example.com"]);
+        assert!(legacy.cc.is_empty() && legacy.bcc.is_empty());
+
+        let many: SendInvoiceEmailInput = serde_json::from_value(serde_json::json!({
+            "invoiceId": "1", "to": ["a@example.com", "b@example.com"], "cc": "c@example.com",
+            "bcc": ["d@example.com"], "subject": "S"
+        }))
+        .unwrap();
+        assert_eq!(many.to.len(), 2);
+        assert_eq!(many.cc, vec!["c@example.com"]);
+        assert_eq!(many.bcc, vec!["d@example.com"]);
+    }
+
+    #[test]
+    fn invalid_recipient_is_named_in_error() {
+        let err = parse_recipient_mailboxes("CC", &["ok@example.com".into(), "not-an-email".into()]).unwrap_err();
+        assert!(err.contains("CC") && err.contains("not-an-email"), "{err}");
+    }
+
+    #[test]
+    fn oversized_email_lists_its_attachments() {
+        let attachments = vec![("2026-001.pdf".to_string(), 16 * 1024 * 1024), ("2026-001.xml".to_string(), 3000)];
+        assert!(check_email_size(15, 15 * 1024 * 1024, &attachments).is_ok());
+        let err = check_email_size(15, 22 * 1024 * 1024, &attachments).unwrap_err();
+        assert_eq!(err.code(), "ATTACHMENT_TOO_LARGE");
+        assert_eq!(
+            err.message("en"),
+            "The email is too large to send (22.0 MB, the limit is 15 MB). Attachments: 2026-001.pdf (16.0 MB), \
+             2026-001.xml (3 KB). Use a smaller logo or raise the limit (Settings → Email)."
+        );
+        assert_eq!(serde_json::to_value(&err).unwrap()["details"]["attachments"][1]["bytes"], 3000);
+        // A zero from an old settings row means the default.
+        assert!(check_email_size(0, 16 * 1024 * 1024, &[]).is_err());
+    }
+
+    #[test]
+    fn svg_logo_is_rejected() {
+        let err = decode_logo_image("data:image/svg+xml;base64,PHN2Zz48L3N2Zz4=").unwrap_err();
+        assert!(err.contains("SVG"), "{err}");
+    }
+
+    #[test]
+    fn smtp_timeout_falls_back_and_clamps() {
+        assert_eq!(smtp_timeout(0).as_secs(), DEFAULT_SMTP_TIMEOUT_SECONDS as u64);
+        assert_eq!(smtp_timeout(10).as_secs(), 10);
+        assert_eq!(smtp_timeout(10_000).as_secs(), MAX_SMTP_TIMEOUT_SECONDS as u64);
+    }
+
+    #[test]
+    fn only_dropped_connections_count_as_transient_io() {
+        assert!(is_transient_io_error_kind(std::io::ErrorKind::ConnectionReset));
+        assert!(is_transient_io_error_kind(std::io::ErrorKind::TimedOut));
+        assert!(!is_transient_io_error_kind(std::io::ErrorKind::ConnectionRefused));
+        assert!(!is_transient_io_error_kind(std::io::ErrorKind::PermissionDenied));
+    }
+
+    #[test]
+    fn settings_for_ui_never_exposes_smtp_password() {
+        let mut s = default_settings();
+        s.smtp_password = "hunter2".into();
+        let ui = settings_for_ui(s);
+        assert!(ui.smtp_password.is_empty() && ui.smtp_password_configured);
+        assert!(!settings_for_ui(default_settings()).smtp_password_configured);
+
+        assert!(secrets::is_plaintext("hunter2"));
+        assert!(!secrets::is_plaintext(secrets::KEYRING_SENTINEL));
+        assert!(!secrets::is_plaintext(""));
+    }
+
+    #[test]
+    fn self_copy_is_skipped_when_sender_already_receives() {
+        let mut s = default_settings();
+        s.smtp_from = "Office@Example.com".into();
+        let client: Mailbox = "client@example.com".parse().unwrap();
+        let copy = self_copy_mailbox(&s, std::slice::from_ref(&client), &[], &[]).unwrap();
+        assert_eq!(copy.unwrap().email.to_string(), "Office@Example.com");
+
+        let own: Mailbox = "office@example.com".parse().unwrap();
+        assert!(self_copy_mailbox(&s, &[client], &[own], &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn oauth2_settings_require_token_fields_and_stay_hidden() {
+        let mut s = default_settings();
+        s.smtp_host = "smtp.gmail.com".into();
+        s.smtp_from = "me@example.com".into();
+        s.smtp_user = "me@example.com".into();
+        s.smtp_auth_mode = SmtpAuthMode::Oauth2;
+        s.smtp_oauth_client_id = "client".into();
+        s.smtp_oauth_token_endpoint = "https://oauth2.googleapis.com/token".into();
+        let err = validate_smtp_settings(&s).unwrap_err();
+        assert_eq!(err.code(), "SMTP_CONFIG");
+        assert!(err.to_string().contains("refresh token"), "{err}");
+
+        s.smtp_oauth_refresh_token = secrets::KEYRING_SENTINEL.into();
+        validate_smtp_settings(&s).unwrap();
+
+        s.smtp_oauth_token_endpoint = "http://example.com/token".into();
+        assert!(validate_smtp_settings(&s).unwrap_err().to_string().contains("https://"));
+
+        s.smtp_oauth_client_secret = secrets::KEYRING_SENTINEL.into();
+        let ui = settings_for_ui(s);
+        assert!(ui.smtp_oauth_configured);
+        assert!(ui.smtp_oauth_refresh_token.is_empty() && ui.smtp_oauth_client_secret.is_empty());
+    }
+
+    #[test]
+    fn accept_invalid_certs_requires_private_host_or_acknowledgement() {
+        let mut s = default_settings();
+        s.smtp_accept_invalid_certs = true;
+        for host in ["192.168.1.10", "10.0.0.5", "127.0.0.1", "fd00::1", "mail.lan", "localhost"] {
+            s.smtp_host = host.into();
+            validate_smtp_accept_invalid_certs(&s).unwrap();
+        }
+
+        s.smtp_host = "smtp.example.com".into();
+        assert!(validate_smtp_accept_invalid_certs(&s).is_err());
+        s.smtp_host = "8.8.8.8".into();
+        assert!(validate_smtp_accept_invalid_certs(&s).is_err());
+        s.smtp_accept_invalid_certs_acknowledged = true;
+        validate_smtp_accept_invalid_certs(&s).unwrap();
+        assert_eq!(smtp_trust_path(&s), SmtpTrustPath::AcceptInvalidCerts);
+
+        s.smtp_pinned_cert_pem = "-----BEGIN CERTIFICATE-----".into();
+        assert_eq!(smtp_trust_path(&s), SmtpTrustPath::PinnedCertificate);
+        assert!(parse_pinned_certificate("not a certificate").is_err());
+    }
+
+    #[test]
+    fn client_phone_is_backfilled_into_data_json() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "DROP TABLE clients;\n\
+             ALTER TABLE expenses DROP COLUMN recurringId;\n\
+             ALTER TABLE expenses DROP COLUMN deletedAt;\n\
+             ALTER TABLE expenses DROP COLUMN invoiceId;\n\
+             ALTER TABLE settings DROP COLUMN turnoverLimitRsd;\n\
+             ALTER TABLE settings DROP COLUMN vatLimitRsd;\n\
+             ALTER TABLE settings DROP COLUMN autoBackupEnabled;\n\
+             ALTER TABLE settings DROP COLUMN autoBackupKeep;\n\
+             ALTER TABLE settings DROP COLUMN csvDelimiter;\n\
+             ALTER TABLE settings DROP COLUMN csvDecimalComma;\n\
+             ALTER TABLE settings DROP COLUMN csvIncludeBom;\n\
+             ALTER TABLE settings DROP COLUMN csvLineEnding;\n\
+             ALTER TABLE settings DROP COLUMN defaultInvoiceNotes;\n\
+             ALTER TABLE settings DROP COLUMN legalNoteOverrideSr;\n\
+             ALTER TABLE settings DROP COLUMN legalNoteOverrideEn;\n\
+             ALTER TABLE settings DROP COLUMN roundingMode;\n\
+             CREATE TABLE clients (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL, maticniBroj TEXT NOT NULL DEFAULT '',\n\
+                 pib TEXT NOT NULL, address TEXT NOT NULL, email TEXT NOT NULL, phone TEXT, createdAt TEXT NOT NULL, data_json TEXT);\n\
+             INSERT INTO clients (id, name, maticniBroj, pib, address, email, phone, createdAt, data_json)\n\
+             VALUES ('c1', 'Klijent', '', '100000001', 'Ulica 1', 'k@example.com', ' 011 123 456 ', 't',\n\
+                     '{\"id\":\"c1\",\"name\":\"Klijent\",\"pib\":\"100000001\",\"address\":\"Ulica 1\",\"email\":\"k@example.com\",\"createdAt\":\"t\"}');\n\
+             PRAGMA user_version = 15;",
+        )
+        .unwrap();
+        apply_migrations(&conn).unwrap();
+
+        let json: String = conn
+            .query_row("SELECT data_json FROM clients WHERE id = 'c1'", [], |r| r.get(0))
+            .unwrap();
+        let client: Client = serde_json::from_str(&json).unwrap();
+        assert_eq!(client.phone.as_deref(), Some("011 123 456"));
+        assert_eq!(client.website, None);
+    }
+
+    /// Schema objects by the version that introduced them, newest last; `schema_fixture` strips
+    /// everything newer than the requested version from a current schema.
+    const SCHEMA_HISTORY: &[(i64, &str, Option<&str>)] = &[
+        (3, "invoices", Some("status")),
+        (3, "invoices", Some("dueDate")),
+        (3, "invoices", Some("paidAt")),
+        (4, "settings", Some("smtpHost")),
+        (4, "settings", Some("smtpPort")),
+        (4, "settings", Some("smtpUser")),
+        (4, "settings", Some("smtpPassword")),
+        (4, "settings", Some("smtpFrom")),
+        (4, "settings", Some("smtpUseTls")),
+        (5, "settings", Some("smtpTlsMode")),
+        (6, "expenses", None),
+        (7, "settings", Some("maticniBroj")),
+        (7, "clients", Some("maticniBroj")),
+        (8, "settings", Some("companyAddressLine")),
+        (8, "settings", Some("companyCity")),
+        (8, "settings", Some("companyPostalCode")),
+        (8, "settings", Some("companyEmail")),
+        (8, "settings", Some("companyPhone")),
+        (9, "offers", None),
+        (10, "settings", Some("smtpTimeoutSeconds")),
+        (11, "settings", Some("smtpReplyTo")),
+        (11, "settings", Some("smtpFromName")),
+        (12, "settings", Some("sendCopyToSelf")),
+        (13, "email_outbox", None),
+        (14, "settings", Some("smtpAuthMode")),
+        (14, "settings", Some("smtpOauthClientId")),
+        (14, "settings", Some("smtpOauthClientSecret")),
+        (14, "settings", Some("smtpOauthRefreshToken")),
+        (14, "settings", Some("smtpOauthTokenEndpoint")),
+        (15, "settings", Some("smtpAcceptInvalidCerts")),
+        (15, "settings", Some("smtpAcceptInvalidCertsAcknowledged")),
+        (15, "settings", Some("smtpPinnedCertPem")),
+        (16, "clients", Some("website")),
+        (17, "audit_log", None),
+        (18, "clients", Some("defaultCurrency")),
+        (18, "clients", Some("preferredLanguage")),
+        (18, "clients", Some("paymentTermsDays")),
+        (18, "clients", Some("billingEmail")),
+        (19, "clients", Some("archived")),
+        (20, "clients", Some("isForeign")),
+        (20, "clients", Some("country")),
+        (21, "expenses", Some("recurringId")),
+        (21, "recurring_expenses", None),
+        (22, "expenses", Some("deletedAt")),
+        (23, "expenses", Some("invoiceId")),
+        (24, "settings", Some("turnoverLimitRsd")),
+        (24, "settings", Some("vatLimitRsd")),
+        (25, "settings", Some("autoBackupEnabled")),
+        (25, "settings", Some("autoBackupKeep")),
+        (26, "settings", Some("csvDelimiter")),
+        (26, "settings", Some("csvDecimalComma")),
+        (26, "settings", Some("csvIncludeBom")),
+        (26, "settings", Some("csvLineEnding")),
+        (28, "settings", Some("defaultInvoiceNotes")),
+        (28, "settings", Some("legalNoteOverrideSr")),
+        (28, "settings", Some("legalNoteOverrideEn")),
+        (29, "obligations", None),
+        (29, "obligation_months", None),
+        (30, "invoice_payments", None),
+        (31, "settings", Some("roundingMode")),
+        (32, "catalog_items", None),
+        (33, "time_entries", None),
+        (34, "settings", Some("quotePrefix")),
+        (34, "settings", Some("nextQuoteNumber")),
+        (34, "quotes", None),
+        (35, "settings", Some("webhookUrl")),
+        (35, "settings", Some("webhookSecret")),
+        (35, "webhook_deliveries", None),
+        (36, "idempotency", None),
+        (37, "settings", Some("bankAccounts")),
+        (38, "settings", Some("pdfFilenameTemplate")),
+        (39, "settings", Some("issuedBy")),
+        (40, "settings", Some("accountantEmail")),
+        (41, "settings", Some("staleDraftDays")),
+        (41, "settings", Some("overdueGraceDays")),
+        (42, "settings", Some("customFieldLabels")),
+        (43, "settings", Some("emailMaxMessageMb")),
+        (44, "settings", Some("emailTheme")),
+        (46, "email_outbox", Some("selfCopy")),
+    ];
+
+    /// A database as a release at `version` left it, with one invoice and client in it.
+    fn schema_fixture(version: i64) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        for (_, table, column) in SCHEMA_HISTORY.iter().rev().filter(|(v, _, _)| *v > version) {
+            match column {
+                Some(c) => conn.execute_batch(&format!("ALTER TABLE {table} DROP COLUMN {c}")).unwrap(),
+                None => conn.execute_batch(&format!("DROP TABLE {table}")).unwrap(),
+            }
+        }
+        conn.execute_batch(
+            "INSERT INTO clients (id, name, pib, address, email, phone, createdAt, data_json)\n\
+             VALUES ('c1', 'Klijent', '100000001', 'Ulica 1', 'k@example.com', '011', 't',\n\
+                     '{\"id\":\"c1\",\"name\":\"Klijent\",\"pib\":\"100000001\",\"address\":\"Ulica 1\",\"email\":\"k@example.com\",\"createdAt\":\"t\"}');\n\
+             INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)\n\
+             VALUES ('i1', 'INV-1', 'c1', '2024-01-10', 'RSD', 100, 't', '{\"invoiceNumber\":\"INV-1\",\"clientName\":\"Klijent\",\"items\":[]}');",
+        )
+        .unwrap();
+        if version >= 27 {
+            conn.execute_batch(search::SEARCH_INDEX_SQL).unwrap();
+            conn.execute_batch(search::SEARCH_BACKFILL_SQL).unwrap();
+        }
+        conn.execute_batch(&format!("PRAGMA user_version = {version};")).unwrap();
+        conn
+    }
+
+    fn table_columns(conn: &Connection) -> Vec<(String, Vec<String>)> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'search_index%' ORDER BY name")
+            .unwrap();
+        let tables: Vec<String> = stmt.query_map([], |r| r.get(0)).unwrap().map(|r| r.unwrap()).collect();
+        tables
+            .into_iter()
+            .map(|t| {
+                let mut cols: Vec<String> = conn
+                    .prepare("SELECT name FROM pragma_table_info(?1)")
+                    .unwrap()
+                    .query_map(params![t], |r| r.get(0))
+                    .unwrap()
+                    .map(|r| r.unwrap())
+                    .collect();
+                cols.sort();
+                (t, cols)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_historical_version_migrates_to_the_current_schema() {
+        let fresh = Connection::open_in_memory().unwrap();
+        init_schema(&fresh).unwrap();
+        apply_migrations(&fresh).unwrap();
+        let expected = table_columns(&fresh);
+
+        for version in 2..SCHEMA_VERSION {
+            // With and without `init_schema` first: startup runs it, but it must not be required.
+            for with_init in [false, true] {
+                let conn = schema_fixture(version);
+                if with_init {
+                    init_schema(&conn).unwrap();
+                }
+                apply_migrations(&conn).unwrap_or_else(|e| panic!("v{version} (init={with_init}): {e}"));
+                assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+                assert_eq!(table_columns(&conn), expected, "v{version} (init={with_init})");
+                let hits: i64 = conn
+                    .query_row("SELECT COUNT(*) FROM search_index WHERE search_index MATCH 'klijent'", [], |r| r.get(0))
+                    .unwrap();
+                assert_eq!(hits, 2, "v{version} (init={with_init})");
+            }
+        }
+    }
+
+    #[test]
+    fn migrations_rerun_safely_and_newer_databases_are_refused() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+        // Every column already exists; replaying from v6 used to fail on "duplicate column".
+        conn.execute_batch("PRAGMA user_version = 6;").unwrap();
+        apply_migrations(&conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        conn.execute_batch(&format!("PRAGMA user_version = {};", SCHEMA_VERSION + 1)).unwrap();
+        let err = apply_migrations(&conn).unwrap_err().to_string();
+        assert!(err.contains("newer version"), "{err}");
+    }
+
+    #[test]
+    fn single_bank_account_becomes_the_default_dinar_account() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+        ensure_settings_row(&conn).unwrap();
+        conn.execute_batch(
+            "UPDATE settings SET bankAccount = '160-0000000123456-54', bankAccounts = '[]'; PRAGMA user_version = 36;",
+        )
+        .unwrap();
+        apply_migrations(&conn).unwrap();
+        let settings = read_settings_from_conn(&conn).unwrap();
+        assert_eq!(settings.bank_accounts.len(), 1);
+        assert_eq!(settings.bank_accounts[0].account_number, "160-0000000123456-54");
+        assert_eq!(settings.bank_accounts[0].currency, "RSD");
+        assert!(settings.bank_accounts[0].is_default);
+
+        let patch: SettingsPatch = serde_json::from_value(serde_json::json!({
+            "bankAccounts": [
+                settings.bank_accounts[0],
+                { "currency": "eur", "iban": "RS35260005601001611379", "swift": "AIKBRS22" }
+            ]
+        }))
+        .unwrap();
+        let (settings, _) = apply_settings_patch(&conn, patch).unwrap();
+        let mut invoice: Invoice = serde_json::from_value(serde_json::json!({
+            "id": "i", "invoiceNumber": "1", "clientId": "c", "clientName": "K", "issueDate": "2025-01-01",
+            "serviceDate": "2025-01-01", "status": "SENT",
+            "currency": "EUR", "items": [], "subtotal": 0.0, "total": 0.0, "notes": "", "createdAt": "t"
+        }))
+        .unwrap();
+        let company = build_invoice_pdf_payload_from_db(&invoice, None, &settings).company;
+        assert_eq!(company.iban.as_deref(), Some("RS35260005601001611379"));
+        assert_eq!(company.bank_account, "");
+        invoice.currency = "RSD".to_string();
+        let company = build_invoice_pdf_payload_from_db(&invoice, None, &settings).company;
+        assert_eq!((company.bank_account.as_str(), company.iban), ("160-0000000123456-54", None));
+    }
+
+    fn discounted_invoice(invoice_discount: Option<f64>, invoice_discount_percent: Option<f64>) -> Invoice {
+        serde_json::from_value(serde_json::json!({
+            "id": "i", "invoiceNumber": "1", "clientId": "c", "clientName": "K", "issueDate": "2025-01-01",
+            "serviceDate": "2025-01-01", "currency": "RSD", "subtotal": 1000.0, "total": 900.0, "notes": "",
+            "createdAt": "t", "invoiceDiscount": invoice_discount, "invoiceDiscountPercent": invoice_discount_percent,
+            "items": [{"id": "a", "description": "Rad", "quantity": 2.0, "unitPrice": 500.0,
+                       "discountAmount": 100.0, "total": 900.0}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn invoice_discount_is_validated_against_the_net() {
+        let items = discounted_invoice(None, None).items;
+        // 1000 less the 100 line discount.
+        assert!(validate_invoice_discount(&items, Some(900.0), None).is_ok());
+        assert!(validate_invoice_discount(&items, Some(400.0), Some(50.0)).is_ok());
+        let err = validate_invoice_discount(&items, Some(900.01), None).unwrap_err();
+        assert!(err.contains("cannot exceed"), "{err}");
+        assert!(validate_invoice_discount(&items, Some(500.0), Some(50.0)).is_err());
+        assert!(validate_invoice_discount(&items, Some(-1.0), None).is_err());
+        assert!(validate_invoice_discount(&items, None, Some(100.5)).is_err());
+    }
+
+    #[test]
+    fn stored_total_has_the_invoice_discount_taken_off_and_clamps_at_zero() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        ensure_settings_row(&conn).unwrap();
+        let settings = read_settings_from_conn(&conn).unwrap();
+
+        let mut invoice = discounted_invoice(Some(100.0), Some(10.0));
+        recompute_invoice_totals(&mut invoice);
+        // 900 net, less 100 flat and 10% of the net.
+        assert_eq!((invoice.subtotal, invoice.total), (1000.0, 710.0));
+        let payload = build_invoice_pdf_payload_from_db(&invoice, None, &settings);
+        assert_eq!((payload.invoice_discount, payload.total), (Some(190.0), invoice.total));
+
+        let mut invoice = discounted_invoice(Some(5000.0), None);
+        recompute_invoice_totals(&mut invoice);
+        assert_eq!(invoice.total, 0.0);
+    }
+
+    #[test]
+    fn discounted_invoice_totals_are_recomputed_on_upgrade() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        apply_migrations(&conn).unwrap();
+        let json = serde_json::to_string(&discounted_invoice(None, Some(10.0))).unwrap();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+             VALUES ('i', '1', 'c', '2025-01-01', 'RSD', 900, 't', ?1)",
+            params![json],
+        )
+        .unwrap();
+        conn.execute_batch("PRAGMA user_version = 44;").unwrap();
+        apply_migrations(&conn).unwrap();
+
+        let stored: f64 = conn.query_row("SELECT totalAmount FROM invoices WHERE id = 'i'", [], |r| r.get(0)).unwrap();
+        assert_eq!(stored, 810.0);
+        assert_eq!(read_invoice_from_conn(&conn, "i").unwrap().unwrap().total, 810.0);
+    }
+
+    #[test]
+    fn issued_by_is_stored_and_printed_only_when_set() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        ensure_settings_row(&conn).unwrap();
+        let patch: SettingsPatch =
+            serde_json::from_value(serde_json::json!({ "pib": "101134702", "issuedBy": " Petar Petrović " })).unwrap();
+        let (settings, _) = apply_settings_patch(&conn, patch).unwrap();
+        assert_eq!(read_settings_from_conn(&conn).unwrap().issued_by.as_deref(), Some("Petar Petrović"));
+
+        let mut invoice: Invoice = serde_json::from_value(serde_json::json!({
+            "id": "i", "invoiceNumber": "1", "clientId": "c", "clientName": "K", "issueDate": "2025-01-01",
+            "serviceDate": "2025-01-01", "status": "SENT", "currency": "RSD", "items": [],
+            "subtotal": 0.0, "total": 0.0, "notes": "", "createdAt": "t", "issuedBy": "Petar Petrović"
+        }))
+        .unwrap();
+        let (text, html) = render_invoice_email(&settings, &invoice, None, true, None).unwrap();
+        assert!(text.contains("Fakturu izdao: Petar Petrović"), "{text}");
+        assert!(html.contains("Petar Petrović"));
+        invoice.issued_by = None;
+        let (text, _) = render_invoice_email(&settings, &invoice, None, true, None).unwrap();
+        assert!(!text.contains("Fakturu izdao"), "{text}");
+
+        let patch: SettingsPatch = serde_json::from_value(serde_json::json!({ "issuedBy": "  " })).unwrap();
+        assert_eq!(apply_settings_patch(&conn, patch).unwrap().0.issued_by, None);
+    }
+
+    #[test]
+    fn failed_step_rolls_back_after_snapshot() {
+        let dir = std::env::temp_dir().join(format!("pausaler-migrate-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let conn = Connection::open(dir.join("pausaler.db")).unwrap();
+        init_schema(&conn).unwrap();
+        // A table the v6 step cannot index makes that step fail half way.
+        conn.execute_batch("DROP TABLE expenses; CREATE TABLE expenses (id TEXT); PRAGMA user_version = 5;")
+            .unwrap();
+
+        assert!(apply_migrations(&conn).is_err());
+        assert_eq!(schema_version(&conn).unwrap(), 5);
+        let snapshot = Connection::open(dir.join("pausaler.pre-migrate-v5.db")).unwrap();
+        assert_eq!(schema_version(&snapshot).unwrap(), 5);
+
+        drop(snapshot);
+        drop(conn);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn client_defaults_drive_language_recipient_and_due_date() {
+        let mut client: Client = serde_json::from_value(serde_json::json!({
+            "id": "c", "name": "GmbH", "pib": "", "address": "", "email": "contact@gmbh.de", "createdAt": "t",
+            "defaultCurrency": " eur ", "preferredLanguage": "EN", "paymentTermsDays": 30
+        }))
+        .unwrap();
+        client = clients::normalize_client_defaults(client).unwrap();
+        assert_eq!(client.default_currency.as_deref(), Some("EUR"));
+        assert_eq!(invoice_language(&default_settings(), Some(&client)), "en");
+        assert_eq!(invoice_language(&default_settings(), None), "sr");
+
+        assert_eq!(client.invoice_recipient(), Some("contact@gmbh.de"));
+        client.billing_email = Some("billing@gmbh.de".into());
+        assert_eq!(client.invoice_recipient(), Some("billing@gmbh.de"));
+
+        assert_eq!(add_days_ymd("2024-02-15", 30).as_deref(), Some("2024-03-16"));
+        assert_eq!(add_days_ymd("not a date", 30), None);
+
+        client.payment_terms_days = Some(400);
+        assert!(clients::normalize_client_defaults(client).is_err());
+    }
+
+    #[test]
+    fn foreign_client_waives_mb_and_adds_reverse_charge_note() {
+        let mut payload = sample_payload();
+        payload.client.registration_number = None;
+        assert!(generate_pdf_bytes(&payload, None, false).is_err());
+
+        payload.client.is_foreign = true;
+        payload.client.pib = Some("DE123456789".to_string());
+        payload.client.country = Some("DE".to_string());
+        assert!(payload.client.reverse_charge());
+        assert!(generate_pdf_bytes(&payload, None, false).is_ok());
+
+        let none = LegalNoteOverride::default();
+        let note = mandatory_invoice_note_text("en", "1", true, &none);
+        assert!(note.contains("Reverse charge"), "{note}");
+        assert!(!mandatory_invoice_note_text("en", "1", false, &none).contains("Reverse charge"));
+    }
+
+    #[test]
+    fn legal_note_override_replaces_the_template_of_its_language() {
+        let overrides = LegalNoteOverride {
+            sr: "Napomena za {INVOICE_NUMBER}\nDruga linija  ".to_string(),
+            en: "   ".to_string(),
+        };
+        assert_eq!(
+            mandatory_invoice_note_lines("sr", "7", false, &overrides),
+            ["Napomena za 7", "Druga linija"]
+        );
+        let built_in = mandatory_invoice_note_lines("en", "7", false, &LegalNoteOverride::default());
+        assert_eq!(mandatory_invoice_note_lines("en", "7", false, &overrides), built_in);
+
+        let reverse = mandatory_invoice_note_lines("sr", "7", true, &overrides);
+        assert_eq!(reverse[..2], ["Napomena za 7", "Druga linija"]);
+        assert!(reverse.len() > 2);
+    }
+
+    #[test]
+    fn dates_are_validated_and_zero_padded() {
+        assert_eq!(normalize_ymd("Date", " 2025-1-5 ").unwrap(), "2025-01-05");
+        assert_eq!(normalize_ymd("Date", "2024-02-29").unwrap(), "2024-02-29");
+        for bad in ["2025-02-29", "31.02.2025", "2025-13-01", "2025-00-10", "25-01-05", "2025-01-05T10:00", ""] {
+            let err = normalize_ymd("Due date", bad).unwrap_err();
+            assert!(err.starts_with("Due date"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn deleted_expenses_can_be_restored_until_purged() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO expenses (id, title, amount, currency, date, createdAt)
+             VALUES ('e1', 'Gorivo', 5000, 'RSD', '2025-03-01', 't')",
+            [],
+        )
+        .unwrap();
+
+        let deleted = soft_delete_expense_in_conn(&conn, "e1").unwrap().unwrap();
+        assert_eq!(deleted.title, "Gorivo");
+        assert!(deleted.deleted_at.is_some());
+        assert!(soft_delete_expense_in_conn(&conn, "e1").unwrap().is_none());
+
+        assert_eq!(purge_deleted_expenses_in_conn(&conn, "2000-01-01T00:00:00Z").unwrap(), 0);
+        conn.execute("UPDATE expenses SET deletedAt = NULL WHERE id = 'e1'", []).unwrap();
+        assert!(read_expense_from_conn(&conn, "e1").unwrap().unwrap().deleted_at.is_none());
+
+        soft_delete_expense_in_conn(&conn, "e1").unwrap();
+        assert_eq!(purge_deleted_expenses_in_conn(&conn, "9999-01-01T00:00:00Z").unwrap(), 1);
+        assert!(read_expense_from_conn(&conn, "e1").unwrap().is_none());
+    }
+
+    #[test]
+    fn split_must_match_original_to_the_cent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO expenses (id, title, amount, currency, date, category, createdAt)
+             VALUES ('e1', 'Kartica', 100.10, 'EUR', '2025-03-01', 'Oprema', 't')",
+            [],
+        )
+        .unwrap();
+        let part = |title: &str, amount: f64| ExpenseSplitPart {
+            title: title.to_string(),
+            amount,
+        };
+
+        let err = split_expense_in_conn(&mut conn, "e1", &[part("A", 50.0), part("B", 50.09)]).unwrap_err();
+        assert!(err.to_string().contains("100.09"), "{err}");
+        assert!(read_expense_from_conn(&conn, "e1").unwrap().is_some());
+
+        let parts = split_expense_in_conn(&mut conn, "e1", &[part("Miš", 30.03), part(" Tastatura ", 70.07)])
+            .unwrap()
+            .unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].title, "Tastatura");
+        assert!(parts.iter().all(|p| p.currency == "EUR"
+            && p.date == "2025-03-01"
+            && p.category.as_deref() == Some("Oprema")));
+        assert!(read_expense_from_conn(&conn, "e1").unwrap().is_none());
+
+        let copy = duplicate_expense_in_conn(&conn, &parts[0].id, "2025-04-01").unwrap().unwrap();
+        assert_eq!((copy.title.as_str(), copy.amount, copy.date.as_str()), ("Miš", 30.03, "2025-04-01"));
+        assert_ne!(copy.id, parts[0].id);
+    }
+
+    #[test]
+    fn billing_expenses_appends_items_to_draft_invoices_only_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let invoice = |id: &str, status: &str| {
+            serde_json::json!({
+                "id": id, "invoiceNumber": id, "clientId": "c", "clientName": "Klijent",
+                "issueDate": "2025-03-01", "serviceDate": "2025-03-01", "status": status,
+                "currency": "RSD", "subtotal": 10000.0, "total": 9000.0, "notes": "", "createdAt": "t",
+                "items": [{"id": "i1", "description": "Rad", "quantity": 1.0, "unitPrice": 10000.0,
+                           "discountAmount": 1000.0, "total": 9000.0}]
+            })
+            .to_string()
+        };
+        for (id, status) in [("draft", "DRAFT"), ("sent", "SENT")] {
+            conn.execute(
+                "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+                 VALUES (?1, ?1, 'c', '2025-03-01', ?2, 'RSD', 9000, 't', ?3)",
+                params![id, status, invoice(id, status)],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO expenses (id, title, amount, currency, date, createdAt) VALUES
+                 ('e1', 'Putni trošak', 2500, 'RSD', '2025-03-02', 't'),
+                 ('e2', 'Licenca', 100, 'EUR', '2025-03-02', 't');",
+        )
+        .unwrap();
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let err = bill_expenses_to_invoice_in_conn(&mut conn, &ids(&["e1"]), "sent").unwrap_err();
+        assert!(err.to_string().contains("draft"), "{err}");
+        let err = bill_expenses_to_invoice_in_conn(&mut conn, &ids(&["e1", "e2"]), "draft").unwrap_err();
+        assert!(err.to_string().contains("EUR"), "{err}");
+        assert!(read_expense_from_conn(&conn, "e1").unwrap().unwrap().invoice_id.is_none());
+
+        let billed = bill_expenses_to_invoice_in_conn(&mut conn, &ids(&["e1", "e1"]), "draft")
+            .unwrap()
+            .unwrap();
+        assert_eq!(billed.items.len(), 2);
+        assert_eq!(billed.items[1].description, "Putni trošak");
+        assert_eq!((billed.subtotal, billed.total), (12500.0, 11500.0));
+        let stored = read_invoice_from_conn(&conn, "draft").unwrap().unwrap();
+        assert_eq!(stored.total, 11500.0);
+        assert_eq!(read_expense_from_conn(&conn, "e1").unwrap().unwrap().invoice_id.as_deref(), Some("draft"));
+
+        let err = bill_expenses_to_invoice_in_conn(&mut conn, &ids(&["e1"]), "draft").unwrap_err();
+        assert!(err.to_string().contains("already been billed"), "{err}");
+    }
+
+    #[test]
+    fn date_ranges_are_validated_padded_and_ordered() {
+        assert_eq!(
+            normalize_date_range("2025-3-1", "2025-03-31").unwrap(),
+            ("2025-03-01".to_string(), "2025-03-31".to_string())
+        );
+        assert_eq!(
+            normalize_date_range("2025-12-31", "2025-01-01").unwrap(),
+            ("2025-01-01".to_string(), "2025-12-31".to_string())
+        );
+        let err = normalize_date_range("2025-02-30", "2025-03-01").unwrap_err();
+        assert!(err.contains("start") && err.contains("2025-02-30"), "{err}");
+        assert!(normalize_date_range("2025-01-01", "31.12.2025").unwrap_err().contains("end"));
+
+        assert_eq!(
+            normalize_optional_date_range(Some("2025-6-30".into()), Some("2025-01-01".into())).unwrap(),
+            (Some("2025-01-01".to_string()), Some("2025-06-30".to_string()))
+        );
+        assert_eq!(normalize_optional_date_range(Some(" ".into()), None).unwrap(), (None, None));
+        assert!(normalize_optional_date_range(None, Some("2025-13-01".into())).is_err());
+    }
+
+    #[test]
+    fn streamed_csv_matches_the_buffered_document() {
+        let invoice: Invoice = serde_json::from_value(serde_json::json!({
+            "id": "i1", "invoiceNumber": "2025-1", "clientId": "c", "clientName": "Klijent, d.o.o.",
+            "issueDate": "2025-03-01", "serviceDate": "2025-03-01", "status": "SENT", "currency": "RSD",
+            "subtotal": 1500.0, "total": 1500.0, "notes": "a \"b\"", "createdAt": "t",
+            "issuedBy": "Petar Petrović", "customFields": [{ "label": "PO", "value": "4500" }], "items": [
+                {"id": "a", "description": "Rad", "quantity": 1.5, "unitPrice": 1000.0, "total": 1500.0},
+                {"id": "b", "description": "Putni\ntrošak", "quantity": 0.0, "unitPrice": 0.0, "total": 0.0}
+            ]
+        }))
+        .unwrap();
+
+        let plain = CsvOptions::default();
+        let mut w = CsvWriter::new(Vec::new(), plain).unwrap();
+        w.row(&INVOICE_CSV_HEADER.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        for row in invoice_csv_rows("RSD", &invoice, &plain) {
+            w.row(&row).unwrap();
+        }
+        let streamed = String::from_utf8(w.finish().unwrap()).unwrap();
+        assert_eq!(streamed, render_invoices_csv("RSD", std::slice::from_ref(&invoice), &plain));
+        assert!(
+            streamed.ends_with("\"a \"\"b\"\"\",t,Petar Petrović,\"[{\"\"label\"\":\"\"PO\"\",\"\"value\"\":\"\"4500\"\"}]\"\r\n"),
+            "{streamed}"
+        );
+        assert_eq!(
+            streamed.split("\r\n").nth(1).unwrap(),
+            "i1,2025-1,2025-03-01,2025-03-01,,,SENT,c,\"Klijent, d.o.o.\",RSD,true,1500.00,1500.00,a,Rad,1.5,1000.00,1500.00,\"a \"\"b\"\"\",t,Petar Petrović,\"[{\"\"label\"\":\"\"PO\"\",\"\"value\"\":\"\"4500\"\"}]\""
+        );
+
+        let excel = CsvOptions {
+            delimiter: ';',
+            decimal_comma: true,
+            include_bom: true,
+            line_ending: CsvLineEnding::Lf,
+        };
+        let mut w = CsvWriter::new(Vec::new(), excel).unwrap();
+        for row in invoice_csv_rows("EUR", &invoice, &excel) {
+            w.row(&row).unwrap();
+        }
+        let lines: Vec<String> = invoice_csv_rows("EUR", &invoice, &excel)
+            .iter()
+            .map(|r| csv_join_row(r, &excel))
+            .collect();
+        assert_eq!(w.finish().unwrap(), csv_document(&lines, &excel).into_bytes());
+    }
+
+    #[test]
+    fn csv_dialect_controls_delimiter_decimals_bom_and_quoting() {
+        let excel = CsvOptions {
+            delimiter: ';',
+            decimal_comma: true,
+            include_bom: true,
+            line_ending: CsvLineEnding::Lf,
+        };
+        let lines = [
+            csv_join_row(&["Usluga, mesec".to_string(), format_money_csv(1234.5, &excel)], &excel),
+            csv_join_row(&["a;b".to_string(), format_quantity_csv(1.25, &excel)], &excel),
+        ];
+        assert_eq!(csv_document(&lines, &excel), "\u{feff}Usluga, mesec;1234,50\n\"a;b\";1,25\n");
+
+        let plain = CsvOptions::default();
+        let row = csv_join_row(&["Usluga, mesec".to_string(), format_money_csv(1234.5, &plain)], &plain);
+        assert_eq!(csv_document(&[row], &plain), "\"Usluga, mesec\",1234.50\r\n");
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        ensure_settings_row(&conn).unwrap();
+        assert_eq!(resolve_csv_options(&conn, None).unwrap(), plain);
+        conn.execute("UPDATE settings SET csvDelimiter = ';', csvDecimalComma = 1, csvLineEnding = 'lf'", [])
+            .unwrap();
+        let saved = resolve_csv_options(&conn, None).unwrap();
+        assert_eq!((saved.delimiter, saved.decimal_comma, saved.line_ending), (';', true, CsvLineEnding::Lf));
+        assert!(resolve_csv_options(&conn, Some(CsvOptions { delimiter: 'x', ..plain })).is_err());
+    }
+}
@@ -8,6 +8,23 @@ pub fn sha256_hex(input: &str) -> String {
     out.iter().map(|b| format!("{b:02x}")).collect()
 }
 
+/// PIB hash carried in activation codes and licenses: `sha256_hex("<app_id>:<pib>")`. The app id
+/// is the salt, so a table of hashed PIBs built for another product (or for the old unsalted
+/// hash) doesn't apply. It is public, so the PIB is obscured rather than secret.
+pub fn pib_hash(app_id: &str, pib: &str) -> String {
+    sha256_hex(&format!("{app_id}:{}", pib.trim()))
+}
+
+/// Unsalted `sha256_hex(pib)` of licenses issued before [`pib_hash`].
+pub fn legacy_pib_hash(pib: &str) -> String {
+    sha256_hex(pib.trim())
+}
+
+/// Equality whose running time depends only on the lengths, not on where the inputs differ.
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 pub fn base64url_encode(bytes: &[u8]) -> String {
     URL_SAFE_NO_PAD.encode(bytes)
 }
@@ -30,6 +47,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pib_hash_is_salted_with_the_app_id() {
+        assert_eq!(pib_hash("app", " 101134702 "), sha256_hex("app:101134702"));
+        assert_ne!(pib_hash("app", "101134702"), pib_hash("other", "101134702"));
+        assert_eq!(legacy_pib_hash("101134702"), sha256_hex("101134702"));
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "abcd"));
+    }
+
     #[test]
     fn base64url_roundtrip() {
         let bytes = b"hello world".to_vec();
@@ -15,6 +15,9 @@ pub struct LicensePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub valid_until: Option<String>,
     pub pib_hash: String,
+    /// The app the license is for; licenses issued before it was added don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,4 +26,7 @@ pub struct VerifiedLicenseInfo {
     pub valid_until: Option<String>,
     pub is_valid: bool,
     pub reason: Option<String>,
+    /// Key id of the trusted key that verified the signature.
+    #[serde(default)]
+    pub key_id: Option<String>,
 }
@@ -4,8 +4,9 @@ use serde::Deserialize;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
-use super::crypto::base64url_decode;
+use super::crypto::{base64url_decode, constant_time_eq};
 use super::license_payload::{LicenseType, VerifiedLicenseInfo};
+use crate::license_activation::APP_ID;
 
 fn parse_time_rfc3339(s: &str) -> Result<OffsetDateTime, String> {
     OffsetDateTime::parse(s, &Rfc3339).map_err(|e| format!("invalid datetime: {e}"))
@@ -40,15 +41,35 @@ fn parse_ed25519_public_key_from_spki_pem(public_key_pem: &str) -> Result<Verify
     VerifyingKey::from_bytes(&pk).map_err(|e| format!("invalid public key bytes: {e}"))
 }
 
-fn verify_ed25519_signature(public_key_pem: &str, payload_bytes: &[u8], signature_bytes: &[u8]) -> Result<(), String> {
-    let vk = parse_ed25519_public_key_from_spki_pem(public_key_pem)?;
+/// Key id of a public key: its first 8 bytes in hex. Licenses may name it in a `kid.` prefix.
+pub fn key_id(vk: &VerifyingKey) -> String {
+    vk.to_bytes()[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
 
+/// Tries the trusted keys, the one named by `kid` first, and returns the id of the key that
+/// verified the signature.
+fn verify_ed25519_signature(
+    public_keys_pem: &[&str],
+    kid: Option<&str>,
+    payload_bytes: &[u8],
+    signature_bytes: &[u8],
+) -> Result<String, String> {
     let sig: [u8; 64] = signature_bytes
         .try_into()
         .map_err(|_| "invalid signature length".to_string())?;
-
-    vk.verify_strict(payload_bytes, &ed25519_dalek::Signature::from(sig))
-        .map_err(|_| "signature verification failed".to_string())
+    let sig = ed25519_dalek::Signature::from(sig);
+
+    let mut keys = public_keys_pem
+        .iter()
+        .map(|pem| parse_ed25519_public_key_from_spki_pem(pem))
+        .collect::<Result<Vec<_>, _>>()?;
+    // The kid is unauthenticated: it only decides the order.
+    keys.sort_by_key(|vk| Some(key_id(vk).as_str()) != kid);
+
+    keys.iter()
+        .find(|vk| vk.verify_strict(payload_bytes, &sig).is_ok())
+        .map(key_id)
+        .ok_or_else(|| "signature verification failed".to_string())
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -57,44 +78,83 @@ struct IncomingLicensePayload {
     pub valid_from: String,
     pub valid_until: Option<String>,
     pub pib_hash: String,
+    /// Missing in licenses issued before the claim existed; those are accepted.
+    #[serde(default)]
+    pub app_id: Option<String>,
 }
 
-pub fn verify_license(license_str: &str, expected_pib_hash: &str, public_key_pem: &str, now: OffsetDateTime) -> Result<VerifiedLicenseInfo, String> {
-    let parts: Vec<&str> = license_str.split('.').collect();
-    if parts.len() != 2 {
-        return Ok(VerifiedLicenseInfo {
-            license_type: None,
-            valid_until: None,
-            is_valid: false,
-            reason: Some("invalid_format".to_string()),
-        });
+fn rejected(reason: &str) -> VerifiedLicenseInfo {
+    VerifiedLicenseInfo {
+        license_type: None,
+        valid_until: None,
+        is_valid: false,
+        reason: Some(reason.to_string()),
+        key_id: None,
     }
+}
 
-    let payload_bytes = base64url_decode(parts[0])?;
-    let signature_bytes = base64url_decode(parts[1])?;
+/// Verifies `payload.sig` or `kid.payload.sig` against any of `public_keys_pem`, so licenses
+/// signed with a retired key keep working after a key rotation.
+///
+/// A license that can't be read comes back invalid with a reason: `invalid_format` (wrong
+/// segment count), `invalid_encoding` (bad base64url), `invalid_signature_length` or
+/// `invalid_payload` (bad JSON or dates). One signed for another app is `app_mismatch`. Only a
+/// signature none of the keys verifies, or an unusable public key, is an `Err`.
+///
+/// `expected_pib_hashes` are the accepted forms of the company's PIB hash (salted, then legacy);
+/// each is compared in constant time.
+pub fn verify_license(license_str: &str, expected_pib_hashes: &[&str], public_keys_pem: &[&str], now: OffsetDateTime) -> Result<VerifiedLicenseInfo, String> {
+    let parts: Vec<&str> = license_str.split('.').collect();
+    let (kid, payload_b64, signature_b64) = match parts[..] {
+        [payload, sig] => (None, payload, sig),
+        [kid, payload, sig] => (Some(kid), payload, sig),
+        _ => return Ok(rejected("invalid_format")),
+    };
+
+    let (Ok(payload_bytes), Ok(signature_bytes)) = (base64url_decode(payload_b64), base64url_decode(signature_b64)) else {
+        return Ok(rejected("invalid_encoding"));
+    };
+    if signature_bytes.len() != ed25519_dalek::SIGNATURE_LENGTH {
+        return Ok(rejected("invalid_signature_length"));
+    }
 
-    let payload: IncomingLicensePayload = serde_json::from_slice(&payload_bytes)
-        .map_err(|e| format!("invalid payload json: {e}"))?;
+    let Ok(payload) = serde_json::from_slice::<IncomingLicensePayload>(&payload_bytes) else {
+        return Ok(rejected("invalid_payload"));
+    };
+    let license_type = Some(format!("{:?}", payload.license_type).to_ascii_uppercase());
 
-    if payload.pib_hash != expected_pib_hash {
+    let pib_matches = expected_pib_hashes
+        .iter()
+        .fold(false, |matched, expected| matched | constant_time_eq(&payload.pib_hash, expected));
+    if !pib_matches {
         return Ok(VerifiedLicenseInfo {
-            license_type: Some(format!("{:?}", payload.license_type).to_ascii_uppercase()),
+            license_type,
             valid_until: payload.valid_until.clone(),
             is_valid: false,
             reason: Some("pib_mismatch".to_string()),
+            key_id: None,
         });
     }
 
-    verify_ed25519_signature(public_key_pem, &payload_bytes, &signature_bytes)?;
+    let key_id = Some(verify_ed25519_signature(public_keys_pem, kid, &payload_bytes, &signature_bytes)?);
+
+    let invalid = |reason: &str| VerifiedLicenseInfo {
+        license_type: license_type.clone(),
+        valid_until: payload.valid_until.clone(),
+        is_valid: false,
+        reason: Some(reason.to_string()),
+        key_id: key_id.clone(),
+    };
 
-    let valid_from = parse_time_rfc3339(&payload.valid_from)?;
+    if payload.app_id.as_deref().is_some_and(|id| id != APP_ID) {
+        return Ok(invalid("app_mismatch"));
+    }
+
+    let Ok(valid_from) = parse_time_rfc3339(&payload.valid_from) else {
+        return Ok(invalid("invalid_payload"));
+    };
     if now < valid_from {
-        return Ok(VerifiedLicenseInfo {
-            license_type: Some(format!("{:?}", payload.license_type).to_ascii_uppercase()),
-            valid_until: payload.valid_until.clone(),
-            is_valid: false,
-            reason: Some("not_yet_valid".to_string()),
-        });
+        return Ok(invalid("not_yet_valid"));
     }
 
     match payload.license_type {
@@ -104,18 +164,18 @@ pub fn verify_license(license_str: &str, expected_pib_hash: &str, public_key_pem
                 valid_until: None,
                 is_valid: true,
                 reason: None,
+                key_id,
             })
         }
         LicenseType::Yearly => {
-            let until = payload.valid_until.clone().ok_or_else(|| "missing valid_until".to_string())?;
-            let valid_until = parse_time_rfc3339(&until)?;
+            let Some(until) = payload.valid_until.clone() else {
+                return Ok(invalid("invalid_payload"));
+            };
+            let Ok(valid_until) = parse_time_rfc3339(&until) else {
+                return Ok(invalid("invalid_payload"));
+            };
             if now > valid_until {
-                return Ok(VerifiedLicenseInfo {
-                    license_type: Some("YEARLY".to_string()),
-                    valid_until: Some(until),
-                    is_valid: false,
-                    reason: Some("expired".to_string()),
-                });
+                return Ok(invalid("expired"));
             }
 
             Ok(VerifiedLicenseInfo {
@@ -123,6 +183,7 @@ pub fn verify_license(license_str: &str, expected_pib_hash: &str, public_key_pem
                 valid_until: Some(until),
                 is_valid: true,
                 reason: None,
+                key_id,
             })
         }
     }
@@ -170,6 +231,7 @@ mod tests {
             valid_from: "2025-01-01T00:00:00Z".to_string(),
             valid_until: None,
             pib_hash: "aaa".to_string(),
+            app_id: None,
         };
 
         let payload_bytes = serde_json::to_vec(&payload).unwrap();
@@ -181,7 +243,7 @@ mod tests {
         );
 
         let now = OffsetDateTime::parse("2025-01-02T00:00:00Z", &Rfc3339).unwrap();
-        let res = verify_license(&license, "bbb", &vk_pem, now).unwrap();
+        let res = verify_license(&license, &["bbb"], &[&vk_pem], now).unwrap();
         assert!(!res.is_valid);
         assert_eq!(res.reason.as_deref(), Some("pib_mismatch"));
     }
@@ -197,6 +259,7 @@ mod tests {
             valid_from: "2024-01-01T00:00:00Z".to_string(),
             valid_until: Some("2024-12-31T23:59:59Z".to_string()),
             pib_hash: "hash".to_string(),
+            app_id: None,
         };
 
         let payload_bytes = serde_json::to_vec(&payload).unwrap();
@@ -208,7 +271,7 @@ mod tests {
         );
 
         let now = OffsetDateTime::parse("2025-01-01T00:00:00Z", &Rfc3339).unwrap();
-        let res = verify_license(&license, "hash", &vk_pem, now).unwrap();
+        let res = verify_license(&license, &["hash"], &[&vk_pem], now).unwrap();
         assert!(!res.is_valid);
         assert_eq!(res.reason.as_deref(), Some("expired"));
     }
@@ -224,6 +287,7 @@ mod tests {
             valid_from: "2025-01-01T00:00:00Z".to_string(),
             valid_until: None,
             pib_hash: "hash".to_string(),
+            app_id: None,
         };
 
         let payload_bytes = serde_json::to_vec(&payload).unwrap();
@@ -237,7 +301,7 @@ mod tests {
         );
 
         let now = OffsetDateTime::parse("2025-01-01T00:00:01Z", &Rfc3339).unwrap();
-        let res = verify_license(&license, "hash", &vk_pem, now);
+        let res = verify_license(&license, &["hash"], &[&vk_pem], now);
         assert!(res.is_err());
     }
 
@@ -252,6 +316,7 @@ mod tests {
             valid_from: "2025-01-01T00:00:00Z".to_string(),
             valid_until: None,
             pib_hash: "hash".to_string(),
+            app_id: None,
         };
 
         let payload_bytes = serde_json::to_vec(&payload).unwrap();
@@ -264,8 +329,75 @@ mod tests {
         );
 
         let now = OffsetDateTime::parse("2025-01-01T00:00:01Z", &Rfc3339).unwrap();
-        let res = verify_license(&license, "hash", &vk_pem, now).unwrap();
+        let res = verify_license(&license, &["hash"], &[&vk_pem], now).unwrap();
         assert!(res.is_valid);
         assert_eq!(res.license_type.as_deref(), Some("LIFETIME"));
     }
+
+    #[test]
+    fn verify_tries_every_trusted_key() {
+        let old = keypair_from_seed([17u8; 32]);
+        let new = keypair_from_seed([19u8; 32]);
+        let old_pem = public_key_pem_from_verifying_key(&old.verifying_key());
+        let new_pem = public_key_pem_from_verifying_key(&new.verifying_key());
+
+        let payload = LicensePayload {
+            license_type: LicenseType::Lifetime,
+            valid_from: "2025-01-01T00:00:00Z".to_string(),
+            valid_until: None,
+            pib_hash: "hash".to_string(),
+            app_id: None,
+        };
+        let payload_bytes = serde_json::to_vec(&payload).unwrap();
+        let body = format!(
+            "{}.{}",
+            base64url_encode(&payload_bytes),
+            base64url_encode(&old.sign(&payload_bytes).to_bytes())
+        );
+        let old_kid = key_id(&old.verifying_key());
+        let now = OffsetDateTime::parse("2025-01-01T00:00:01Z", &Rfc3339).unwrap();
+
+        // Issued before the rotation, without a kid.
+        let res = verify_license(&body, &["hash"], &[&new_pem, &old_pem], now).unwrap();
+        assert!(res.is_valid);
+        assert_eq!(res.key_id.as_deref(), Some(old_kid.as_str()));
+
+        // A kid naming the other key is only a hint.
+        let kid = key_id(&new.verifying_key());
+        let res = verify_license(&format!("{kid}.{body}"), &["hash"], &[&new_pem, &old_pem], now).unwrap();
+        assert_eq!(res.key_id.as_deref(), Some(old_kid.as_str()));
+
+        assert!(verify_license(&format!("{old_kid}.{body}"), &["hash"], &[&new_pem], now).is_err());
+    }
+
+    #[test]
+    fn malformed_licenses_are_invalid_with_a_reason() {
+        let sk = keypair_from_seed([23u8; 32]);
+        let vk_pem = public_key_pem_from_verifying_key(&sk.verifying_key());
+        let now = OffsetDateTime::parse("2025-06-01T00:00:00Z", &Rfc3339).unwrap();
+        let signed = |json: &str| {
+            let sig = sk.sign(json.as_bytes());
+            format!("{}.{}", base64url_encode(json.as_bytes()), base64url_encode(&sig.to_bytes()))
+        };
+        let reason = |license: &str| verify_license(license, &["hash"], &[&vk_pem], now).unwrap().reason;
+        let lifetime = r#"{"license_type":"LIFETIME","valid_from":"2025-01-01T00:00:00Z","pib_hash":"hash""#;
+
+        assert_eq!(reason("abc").as_deref(), Some("invalid_format"));
+        assert_eq!(reason("a.b.c.d").as_deref(), Some("invalid_format"));
+        assert_eq!(reason("!!!.AAAA").as_deref(), Some("invalid_encoding"));
+        let payload = base64url_encode(format!("{lifetime}}}").as_bytes());
+        assert_eq!(reason(&format!("{payload}.AAAA")).as_deref(), Some("invalid_signature_length"));
+        let garbage = base64url_encode(b"not json");
+        assert_eq!(reason(&format!("{garbage}.{}", base64url_encode(&[0u8; 64]))).as_deref(), Some("invalid_payload"));
+        let bad_date = signed(r#"{"license_type":"LIFETIME","valid_from":"yesterday","pib_hash":"hash"}"#);
+        assert_eq!(reason(&bad_date).as_deref(), Some("invalid_payload"));
+        let no_until = signed(r#"{"license_type":"YEARLY","valid_from":"2025-01-01T00:00:00Z","pib_hash":"hash"}"#);
+        assert_eq!(reason(&no_until).as_deref(), Some("invalid_payload"));
+
+        let sibling = signed(&format!(r#"{lifetime},"app_id":"com.dstankovski.other-app"}}"#));
+        let res = verify_license(&sibling, &["hash"], &[&vk_pem], now).unwrap();
+        assert_eq!((res.is_valid, res.reason.as_deref()), (false, Some("app_mismatch")));
+        let ours = signed(&format!(r#"{lifetime},"app_id":"{APP_ID}"}}"#));
+        assert!(verify_license(&ours, &["hash"], &[&vk_pem], now).unwrap().is_valid);
+    }
 }
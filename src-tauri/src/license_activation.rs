@@ -0,0 +1,436 @@
+//! Activation of the app with a license issued for the company's PIB. The license string is kept
+//! in `app_meta` and re-verified against the current clock whenever its status is asked for, so
+//! a yearly license stops counting once it expires.
+//!
+//! Setting the clock back would revive an expired license, so the latest time seen at startup
+//! and on every successful verification is kept too. A clock that is clearly behind it makes the
+//! license invalid (`clock_tampering`) until the clock catches up.
+//!
+//! A yearly license keeps working for [`GRACE_DAYS`] after it expires; the app warns 30, 14 and
+//! 3 days before that through `license://expiring`.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::Emitter;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::license::license_payload::VerifiedLicenseInfo;
+use crate::license::{activation_code, crypto, license_validator};
+use crate::{app_meta_get, app_meta_set, logging, read_settings_from_conn, validation_to_sql_error, DbState};
+
+/// `app_meta` key of the activated license (the key older versions wrote from the frontend).
+pub(crate) const LICENSE_KEY: &str = "licenseRaw";
+
+pub(crate) const APP_ID: &str = "com.dstankovski.pausaler-app";
+
+/// Trusted license signing keys, newest first. A rotated-out key stays here so the licenses it
+/// signed keep working.
+pub(crate) const PUBLIC_KEYS: &[&str] = &[include_str!("../assets/public_key.pem")];
+
+/// `app_meta` key of the latest clock reading (unix seconds); it never moves back.
+const LAST_SEEN_KEY: &str = "license_last_seen";
+
+/// How far the clock may be behind the last seen time. Timestamps are UTC, so DST never moves
+/// them; this covers a timezone set wrong and then corrected (UTC−12 to UTC+14) and small
+/// clock adjustments.
+const CLOCK_TOLERANCE_SECONDS: i64 = 26 * 60 * 60;
+
+const CLOCK_TAMPERING: &str = "clock_tampering";
+
+/// Days an expired yearly license keeps working.
+pub(crate) const GRACE_DAYS: i64 = 7;
+
+/// Days before expiry at which `license://expiring` is emitted, largest first.
+const EXPIRY_WARNING_DAYS: [i64; 3] = [30, 14, 3];
+
+/// `app_meta` key of the last warning sent: `<valid_until>|<days>`.
+const EXPIRY_NOTIFIED_KEY: &str = "license_expiry_notified";
+
+pub(crate) const LICENSE_EXPIRING_EVENT: &str = "license://expiring";
+
+const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+/// PIB hash put into new activation codes, salted with [`APP_ID`].
+pub(crate) fn pib_hash(pib: &str) -> String {
+    crypto::pib_hash(APP_ID, pib)
+}
+
+/// Hashes a license for `pib` may carry: the salted one, then the unsalted one of licenses
+/// issued before it.
+pub(crate) fn accepted_pib_hashes(pib: &str) -> [String; 2] {
+    [pib_hash(pib), crypto::legacy_pib_hash(pib)]
+}
+
+fn settings_pib(conn: &Connection) -> Result<String, rusqlite::Error> {
+    let pib = read_settings_from_conn(conn)?.pib;
+    if pib.trim().is_empty() {
+        return Err(validation_to_sql_error("PIB is missing in Settings.".to_string()));
+    }
+    Ok(pib)
+}
+
+fn last_seen(conn: &Connection) -> Result<Option<i64>, rusqlite::Error> {
+    Ok(app_meta_get(conn, LAST_SEEN_KEY)?.and_then(|v| v.trim().parse().ok()))
+}
+
+/// Moves the last seen time forward to `now`; an earlier `now` leaves it as is.
+pub(crate) fn record_clock(conn: &Connection, now: OffsetDateTime) -> Result<(), rusqlite::Error> {
+    let now = now.unix_timestamp();
+    if last_seen(conn)?.is_none_or(|seen| now > seen) {
+        app_meta_set(conn, LAST_SEEN_KEY, &now.to_string())?;
+    }
+    Ok(())
+}
+
+fn clock_is_behind(conn: &Connection, now: OffsetDateTime) -> Result<bool, rusqlite::Error> {
+    Ok(last_seen(conn)?.is_some_and(|seen| seen - now.unix_timestamp() > CLOCK_TOLERANCE_SECONDS))
+}
+
+pub(crate) fn verify(license: &str, pib_hashes: &[String], public_keys: &[&str], now: OffsetDateTime) -> Result<VerifiedLicenseInfo, String> {
+    logging::register_secret(license);
+    let pib_hashes: Vec<&str> = pib_hashes.iter().map(String::as_str).collect();
+    license_validator::verify_license(license, &pib_hashes, public_keys, now)
+}
+
+/// Stores `license` when it is valid for the company now. A rejected license fails with the
+/// validator's reason (`expired`, `pib_mismatch`, `not_yet_valid`, `app_mismatch`, or one of the
+/// `invalid_*` reasons for an unreadable one), with `clock_tampering` while the clock is behind,
+/// or, for a forged one, its error message.
+fn activate_in_conn(
+    conn: &Connection,
+    license: &str,
+    public_keys: &[&str],
+    now: OffsetDateTime,
+) -> Result<VerifiedLicenseInfo, rusqlite::Error> {
+    let license = license.trim();
+    if clock_is_behind(conn, now)? {
+        return Err(validation_to_sql_error(CLOCK_TAMPERING.to_string()));
+    }
+    let info = verify(license, &accepted_pib_hashes(&settings_pib(conn)?), public_keys, now).map_err(validation_to_sql_error)?;
+    if !info.is_valid {
+        let reason = info.reason.unwrap_or_else(|| "license_invalid".to_string());
+        return Err(validation_to_sql_error(reason));
+    }
+    app_meta_set(conn, LICENSE_KEY, license)?;
+    record_clock(conn, now)?;
+    Ok(info)
+}
+
+/// The stored license checked against the current PIB and `now`; `None` when none is stored.
+/// A valid result moves the last seen time forward.
+fn stored_license_status(
+    conn: &Connection,
+    public_keys: &[&str],
+    now: OffsetDateTime,
+) -> Result<Option<VerifiedLicenseInfo>, rusqlite::Error> {
+    let Some(license) = app_meta_get(conn, LICENSE_KEY)?.filter(|l| !l.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let pib_hashes = accepted_pib_hashes(&read_settings_from_conn(conn)?.pib);
+    let info = match verify(license.trim(), &pib_hashes, public_keys, now) {
+        Ok(info) if info.is_valid && clock_is_behind(conn, now)? => VerifiedLicenseInfo {
+            is_valid: false,
+            reason: Some(CLOCK_TAMPERING.to_string()),
+            ..info
+        },
+        Ok(info) => {
+            if info.is_valid {
+                record_clock(conn, now)?;
+            }
+            info
+        }
+        // A stored license that no longer parses is reported like any other invalid one.
+        Err(_) => VerifiedLicenseInfo {
+            license_type: None,
+            valid_until: None,
+            is_valid: false,
+            reason: Some("license_invalid".to_string()),
+            key_id: None,
+        },
+    };
+    Ok(Some(info))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseExpiryInfo {
+    pub license_type: Option<String>,
+    pub valid_until: Option<String>,
+    /// Whole days until `valid_until`, negative once expired; `None` for lifetime licenses.
+    pub days_remaining: Option<i64>,
+    /// Expired, but still working for `grace_days_remaining` more days.
+    pub grace_period: bool,
+    pub grace_days_remaining: Option<i64>,
+}
+
+/// Days from `now` to `until`, rounded up so the last day counts as 1.
+fn days_until(until: OffsetDateTime, now: OffsetDateTime) -> i64 {
+    let seconds = (until - now).whole_seconds();
+    seconds.div_euclid(DAY_SECONDS) + i64::from(seconds.rem_euclid(DAY_SECONDS) > 0)
+}
+
+fn expiry_from(info: VerifiedLicenseInfo, now: OffsetDateTime) -> LicenseExpiryInfo {
+    let until = info.valid_until.as_deref().and_then(|u| OffsetDateTime::parse(u, &Rfc3339).ok());
+    let grace_days = until
+        .filter(|_| info.reason.as_deref() == Some("expired"))
+        .map(|u| days_until(u + time::Duration::days(GRACE_DAYS), now))
+        .filter(|d| *d > 0);
+    LicenseExpiryInfo {
+        license_type: info.license_type,
+        valid_until: info.valid_until,
+        days_remaining: until.map(|u| days_until(u, now)),
+        grace_period: grace_days.is_some(),
+        grace_days_remaining: grace_days,
+    }
+}
+
+fn expiry_info(
+    conn: &Connection,
+    public_keys: &[&str],
+    now: OffsetDateTime,
+) -> Result<Option<LicenseExpiryInfo>, rusqlite::Error> {
+    Ok(stored_license_status(conn, public_keys, now)?.map(|info| expiry_from(info, now)))
+}
+
+/// Type (`YEARLY`/`LIFETIME`) of the activated license while it may be used at `now`: valid, or
+/// expired within the grace period.
+pub(crate) fn usable_license_type(conn: &Connection, now: OffsetDateTime) -> Result<Option<String>, rusqlite::Error> {
+    Ok(stored_license_status(conn, PUBLIC_KEYS, now)?
+        .filter(|info| info.is_valid || expiry_from(info.clone(), now).grace_period)
+        .and_then(|info| info.license_type))
+}
+
+/// Whether the activated license may be used at `now`.
+pub(crate) fn has_valid_license(conn: &Connection, now: OffsetDateTime) -> Result<bool, rusqlite::Error> {
+    Ok(usable_license_type(conn, now)?.is_some())
+}
+
+/// The warning to send at `now`, if a threshold was crossed since the last one for this license.
+fn due_expiry_warning(
+    conn: &Connection,
+    public_keys: &[&str],
+    now: OffsetDateTime,
+) -> Result<Option<(LicenseExpiryInfo, i64)>, rusqlite::Error> {
+    let Some(info) = expiry_info(conn, public_keys, now)? else {
+        return Ok(None);
+    };
+    let (Some(days), Some(until)) = (info.days_remaining, info.valid_until.clone()) else {
+        return Ok(None);
+    };
+    let Some(threshold) = EXPIRY_WARNING_DAYS.into_iter().filter(|t| days < *t).min() else {
+        return Ok(None);
+    };
+    let marker = format!("{until}|{threshold}");
+    if app_meta_get(conn, EXPIRY_NOTIFIED_KEY)?.as_deref() == Some(marker.as_str()) {
+        return Ok(None);
+    }
+    app_meta_set(conn, EXPIRY_NOTIFIED_KEY, &marker)?;
+    Ok(Some((info, threshold)))
+}
+
+/// Startup check: emits `license://expiring` once per threshold and license.
+pub(crate) fn notify_expiry(app: &tauri::AppHandle, conn: &Connection) -> Result<(), rusqlite::Error> {
+    if let Some((info, threshold)) = due_expiry_warning(conn, PUBLIC_KEYS, OffsetDateTime::now_utc())? {
+        let _ = app.emit(
+            LICENSE_EXPIRING_EVENT,
+            serde_json::json!({ "threshold": threshold, "info": info }),
+        );
+    }
+    Ok(())
+}
+
+/// Forgets the activated license and its expiry warnings.
+pub(crate) fn clear_license(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM app_meta WHERE key IN (?1, ?2)",
+        [LICENSE_KEY, EXPIRY_NOTIFIED_KEY],
+    )?;
+    Ok(())
+}
+
+/// Activation code for the vendor, bound to the PIB in the settings.
+#[tauri::command]
+pub(crate) async fn get_activation_code(state: tauri::State<'_, DbState>) -> Result<String, AppError> {
+    let pib = state.with_read("get_activation_code", settings_pib).await?;
+    let issued_at = OffsetDateTime::now_utc().unix_timestamp();
+    Ok(activation_code::generate_activation_code(pib_hash(&pib), APP_ID.to_string(), issued_at)?)
+}
+
+#[tauri::command]
+pub(crate) async fn activate_license(
+    state: tauri::State<'_, DbState>,
+    license_str: String,
+) -> Result<VerifiedLicenseInfo, AppError> {
+    state
+        .with_write("activate_license", move |conn| {
+            activate_in_conn(conn, &license_str, PUBLIC_KEYS, OffsetDateTime::now_utc())
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn license_expiry_info(
+    state: tauri::State<'_, DbState>,
+) -> Result<Option<LicenseExpiryInfo>, AppError> {
+    state
+        .with_write("license_expiry_info", |conn| {
+            expiry_info(conn, PUBLIC_KEYS, OffsetDateTime::now_utc())
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_license_status(
+    state: tauri::State<'_, DbState>,
+) -> Result<Option<VerifiedLicenseInfo>, AppError> {
+    state
+        .with_write("get_license_status", |conn| {
+            stored_license_status(conn, PUBLIC_KEYS, OffsetDateTime::now_utc())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+    use ed25519_dalek::{Signer, SigningKey};
+    use time::format_description::well_known::Rfc3339;
+
+    use crate::license::license_payload::{LicensePayload, LicenseType};
+
+    fn public_key_pem(sk: &SigningKey) -> String {
+        let mut der = vec![0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+        der.extend_from_slice(&sk.verifying_key().to_bytes());
+        let b64 = base64::engine::general_purpose::STANDARD.encode(der);
+        format!("-----BEGIN PUBLIC KEY-----\n{b64}\n-----END PUBLIC KEY-----\n")
+    }
+
+    fn yearly_license(sk: &SigningKey, pib: &str, valid_until: &str) -> String {
+        yearly_license_for_hash(sk, pib_hash(pib), valid_until)
+    }
+
+    fn yearly_license_for_hash(sk: &SigningKey, pib_hash: String, valid_until: &str) -> String {
+        let payload = LicensePayload {
+            license_type: LicenseType::Yearly,
+            valid_from: "2025-01-01T00:00:00Z".to_string(),
+            valid_until: Some(valid_until.to_string()),
+            pib_hash,
+            app_id: Some(APP_ID.to_string()),
+        };
+        let bytes = serde_json::to_vec(&payload).unwrap();
+        let sig = sk.sign(&bytes);
+        format!("{}.{}", crypto::base64url_encode(&bytes), crypto::base64url_encode(&sig.to_bytes()))
+    }
+
+    #[test]
+    fn activation_keeps_only_valid_licenses_and_status_follows_the_clock() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let patch = crate::SettingsPatch {
+            pib: Some("101134702".to_string()),
+            ..Default::default()
+        };
+        let _ = crate::apply_settings_patch(&conn, patch).unwrap();
+        let sk = SigningKey::from_bytes(&[3u8; 32]);
+        let pem = public_key_pem(&sk);
+        let at = |s: &str| OffsetDateTime::parse(s, &Rfc3339).unwrap();
+        let now = at("2025-06-01T00:00:00Z");
+
+        let err = activate_in_conn(&conn, &yearly_license(&sk, "100000001", "2025-12-31T00:00:00Z"), &[pem.as_str()], now)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "pib_mismatch");
+        let err = activate_in_conn(&conn, &yearly_license(&sk, "101134702", "2025-03-01T00:00:00Z"), &[pem.as_str()], now)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "expired");
+        assert!(stored_license_status(&conn, &[pem.as_str()], now).unwrap().is_none());
+
+        let license = yearly_license(&sk, "101134702", "2025-12-31T00:00:00Z");
+        assert!(activate_in_conn(&conn, &format!(" {license}\n"), &[pem.as_str()], now).unwrap().is_valid);
+        assert_eq!(app_meta_get(&conn, LICENSE_KEY).unwrap().as_deref(), Some(license.as_str()));
+        assert!(stored_license_status(&conn, &[pem.as_str()], now).unwrap().unwrap().is_valid);
+        let later = stored_license_status(&conn, &[pem.as_str()], at("2026-01-15T00:00:00Z")).unwrap().unwrap();
+        assert_eq!((later.is_valid, later.reason.as_deref()), (false, Some("expired")));
+
+        // Licenses issued with the unsalted hash keep working.
+        let legacy = yearly_license_for_hash(&sk, crypto::sha256_hex("101134702"), "2025-12-31T00:00:00Z");
+        assert!(activate_in_conn(&conn, &legacy, &[pem.as_str()], now).unwrap().is_valid);
+        let other = yearly_license_for_hash(&sk, crypto::sha256_hex("100000001"), "2025-12-31T00:00:00Z");
+        let err = activate_in_conn(&conn, &other, &[pem.as_str()], now).unwrap_err();
+        assert_eq!(err.to_string(), "pib_mismatch");
+    }
+
+    #[test]
+    fn clock_set_back_past_the_tolerance_invalidates_the_license() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let patch = crate::SettingsPatch {
+            pib: Some("101134702".to_string()),
+            ..Default::default()
+        };
+        let _ = crate::apply_settings_patch(&conn, patch).unwrap();
+        let sk = SigningKey::from_bytes(&[5u8; 32]);
+        let pem = public_key_pem(&sk);
+        let at = |s: &str| OffsetDateTime::parse(s, &Rfc3339).unwrap();
+        let status = |now: &str| stored_license_status(&conn, &[pem.as_str()], at(now)).unwrap().unwrap();
+
+        let license = yearly_license(&sk, "101134702", "2025-12-31T00:00:00Z");
+        activate_in_conn(&conn, &license, &[pem.as_str()], at("2025-03-01T12:00:00Z")).unwrap();
+        // Seen in December, after expiry: the stored last time moves forward.
+        record_clock(&conn, at("2025-12-20T12:00:00Z")).unwrap();
+
+        // The clock an hour back (DST ending), the same wall time in UTC+14 and a timezone
+        // corrected by a full day stay within the tolerance.
+        assert!(status("2025-12-20T11:00:00Z").is_valid);
+        assert!(status("2025-12-20T12:00:00+14:00").is_valid);
+        assert!(status("2025-12-19T11:00:00Z").is_valid);
+
+        // A day and a half back is tampering, even though the license would be valid then.
+        let back = status("2025-12-19T00:00:00Z");
+        assert_eq!((back.is_valid, back.reason.as_deref()), (false, Some("clock_tampering")));
+        let err = activate_in_conn(&conn, &license, &[pem.as_str()], at("2025-06-01T00:00:00Z")).unwrap_err();
+        assert_eq!(err.to_string(), "clock_tampering");
+
+        // Once the clock catches up the license counts again.
+        assert!(status("2025-12-20T13:00:00Z").is_valid);
+        assert_eq!(last_seen(&conn).unwrap(), Some(at("2025-12-20T13:00:00Z").unix_timestamp()));
+    }
+
+    #[test]
+    fn expiry_warnings_fire_once_per_threshold_and_grace_keeps_the_license_usable() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let patch = crate::SettingsPatch {
+            pib: Some("101134702".to_string()),
+            ..Default::default()
+        };
+        let _ = crate::apply_settings_patch(&conn, patch).unwrap();
+        let sk = SigningKey::from_bytes(&[6u8; 32]);
+        let pem = public_key_pem(&sk);
+        let at = |s: &str| OffsetDateTime::parse(s, &Rfc3339).unwrap();
+        let license = yearly_license(&sk, "101134702", "2025-12-31T00:00:00Z");
+        activate_in_conn(&conn, &license, &[pem.as_str()], at("2025-06-01T00:00:00Z")).unwrap();
+
+        let warning = |now: &str| due_expiry_warning(&conn, &[pem.as_str()], at(now)).unwrap().map(|(_, t)| t);
+        assert_eq!(warning("2025-11-15T00:00:00Z"), None);
+        assert_eq!(warning("2025-12-05T00:00:00Z"), Some(30));
+        assert_eq!(warning("2025-12-06T00:00:00Z"), None);
+        // A launch after a long break skips straight to the smallest threshold crossed.
+        assert_eq!(warning("2025-12-29T00:00:00Z"), Some(3));
+        assert_eq!(warning("2025-12-30T00:00:00Z"), None);
+
+        let info = expiry_info(&conn, &[pem.as_str()], at("2026-01-03T12:00:00Z")).unwrap().unwrap();
+        assert_eq!(info.days_remaining, Some(-3));
+        assert_eq!((info.grace_period, info.grace_days_remaining), (true, Some(4)));
+        let info = expiry_info(&conn, &[pem.as_str()], at("2026-01-08T00:00:00Z")).unwrap().unwrap();
+        assert!(!info.grace_period);
+    }
+}
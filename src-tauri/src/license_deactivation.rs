@@ -0,0 +1,184 @@
+//! Moving a license to another PIB. A license is bound to the PIB it was issued for, so when the
+//! company is re-registered it is deactivated here: the license is removed and a receipt is
+//! returned for the vendor, whose `license-generator reissue` turns it and the new activation
+//! code into a license of the same type and validity.
+//!
+//! The receipt (`payload.signature`, base64url) is signed with a key generated for this
+//! installation so it can't be edited on the way; the original license inside it still carries
+//! the vendor's signature, which is what the generator trusts.
+
+use ed25519_dalek::{Signer, SigningKey};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::license::crypto;
+use crate::license::license_payload::LicensePayload;
+use crate::license_activation::{clear_license, verify, APP_ID, LICENSE_KEY, PUBLIC_KEYS};
+use crate::{app_meta_get, app_meta_set, audit, logging, validation_to_sql_error, DbState};
+
+/// `app_meta` key of this installation's receipt signing seed (base64url).
+const INSTALL_KEY: &str = "install_signing_key";
+
+/// `app_meta` key of the last receipt, so it can be copied again later.
+const RECEIPT_KEY: &str = "license_deactivation_receipt";
+
+const RECEIPT_KIND: &str = "deactivation";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeactivationReceipt {
+    pub kind: String,
+    pub app_id: String,
+    /// PIB hash the license was bound to.
+    pub pib_hash: String,
+    /// SHA-256 (hex) of `license`.
+    pub license_hash: String,
+    pub license: String,
+    pub deactivated_at: String,
+    /// Public key (base64url) that signed the receipt.
+    pub install_key: String,
+}
+
+fn install_key(conn: &Connection) -> Result<SigningKey, rusqlite::Error> {
+    let stored = app_meta_get(conn, INSTALL_KEY)?
+        .and_then(|s| crypto::base64url_decode(s.trim()).ok())
+        .and_then(|b| <[u8; 32]>::try_from(b).ok());
+    if let Some(seed) = stored {
+        return Ok(SigningKey::from_bytes(&seed));
+    }
+    let seed: [u8; 32] = rand::random();
+    app_meta_set(conn, INSTALL_KEY, &crypto::base64url_encode(&seed))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// The PIB hash a license is bound to, read from its payload.
+fn license_pib_hash(license: &str) -> Option<String> {
+    let payload = crypto::base64url_decode(license.rsplit('.').nth(1)?).ok()?;
+    serde_json::from_slice::<LicensePayload>(&payload).ok().map(|p| p.pib_hash)
+}
+
+/// Removes the activated license and returns the signed receipt. The license only has to carry
+/// a trusted signature; a PIB that no longer matches it is the reason to deactivate.
+fn deactivate_in_conn(
+    conn: &mut Connection,
+    public_keys: &[&str],
+    now: OffsetDateTime,
+) -> Result<String, rusqlite::Error> {
+    let license = app_meta_get(conn, LICENSE_KEY)?
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .ok_or_else(|| validation_to_sql_error("No license is activated.".to_string()))?;
+    let pib_hash = license_pib_hash(&license).ok_or_else(|| validation_to_sql_error("license_invalid".to_string()))?;
+    let info = verify(&license, std::slice::from_ref(&pib_hash), public_keys, now).map_err(validation_to_sql_error)?;
+    if info.key_id.is_none() {
+        return Err(validation_to_sql_error(info.reason.unwrap_or_else(|| "license_invalid".to_string())));
+    }
+
+    let deactivated_at = now.format(&Rfc3339).map_err(|e| validation_to_sql_error(e.to_string()))?;
+
+    let tx = conn.transaction()?;
+    let sk = install_key(&tx)?;
+    let receipt = DeactivationReceipt {
+        kind: RECEIPT_KIND.to_string(),
+        app_id: APP_ID.to_string(),
+        pib_hash,
+        license_hash: crypto::sha256_hex(&license),
+        license,
+        deactivated_at,
+        install_key: crypto::base64url_encode(&sk.verifying_key().to_bytes()),
+    };
+    let payload = serde_json::to_vec(&receipt).map_err(|e| validation_to_sql_error(e.to_string()))?;
+    let text = format!(
+        "{}.{}",
+        crypto::base64url_encode(&payload),
+        crypto::base64url_encode(&sk.sign(&payload).to_bytes())
+    );
+    logging::register_secret(&text);
+
+    clear_license(&tx)?;
+    app_meta_set(&tx, RECEIPT_KEY, &text)?;
+    audit::record(
+        &tx,
+        "license_deactivated",
+        &receipt.license_hash,
+        &serde_json::json!({ "pibHash": receipt.pib_hash, "deactivatedAt": receipt.deactivated_at }),
+    )?;
+    tx.commit()?;
+    Ok(text)
+}
+
+/// Deactivates the license for a transfer to a new PIB; returns the receipt for the vendor.
+#[tauri::command]
+pub(crate) async fn deactivate_license(state: tauri::State<'_, DbState>) -> Result<String, AppError> {
+    state
+        .with_write("deactivate_license", |conn| {
+            deactivate_in_conn(conn, PUBLIC_KEYS, OffsetDateTime::now_utc())
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license::license_payload::LicenseType;
+    use crate::license_activation::pib_hash;
+    use base64::Engine as _;
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    #[test]
+    fn deactivation_removes_the_license_and_signs_a_receipt() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let now = OffsetDateTime::parse("2025-06-01T00:00:00Z", &Rfc3339).unwrap();
+
+        let err = deactivate_in_conn(&mut conn, &[], now).unwrap_err();
+        assert!(err.to_string().contains("No license"), "{err}");
+
+        let vendor = SigningKey::from_bytes(&[29u8; 32]);
+        let mut der = vec![0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+        der.extend_from_slice(&vendor.verifying_key().to_bytes());
+        let pem = format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            base64::engine::general_purpose::STANDARD.encode(der)
+        );
+        let payload = serde_json::to_vec(&LicensePayload {
+            license_type: LicenseType::Lifetime,
+            valid_from: "2025-01-01T00:00:00Z".to_string(),
+            valid_until: None,
+            pib_hash: pib_hash("101134702"),
+            app_id: Some(APP_ID.to_string()),
+        })
+        .unwrap();
+        let license = format!(
+            "{}.{}",
+            crypto::base64url_encode(&payload),
+            crypto::base64url_encode(&vendor.sign(&payload).to_bytes())
+        );
+        // The company has since moved to another PIB; the license no longer matches it.
+        app_meta_set(&conn, LICENSE_KEY, &license).unwrap();
+
+        let text = deactivate_in_conn(&mut conn, &[&pem], now).unwrap();
+        assert_eq!(app_meta_get(&conn, LICENSE_KEY).unwrap(), None);
+        assert_eq!(app_meta_get(&conn, RECEIPT_KEY).unwrap().as_deref(), Some(text.as_str()));
+
+        let (payload_b64, sig_b64) = text.split_once('.').unwrap();
+        let payload = crypto::base64url_decode(payload_b64).unwrap();
+        let receipt: DeactivationReceipt = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(receipt.pib_hash, pib_hash("101134702"));
+        assert_eq!(receipt.license_hash, crypto::sha256_hex(&license));
+        let install_key: [u8; 32] = crypto::base64url_decode(&receipt.install_key).unwrap().try_into().unwrap();
+        let sig: [u8; 64] = crypto::base64url_decode(sig_b64).unwrap().try_into().unwrap();
+        VerifyingKey::from_bytes(&install_key)
+            .unwrap()
+            .verify_strict(&payload, &Signature::from_bytes(&sig))
+            .unwrap();
+
+        // A forged license is not deactivated.
+        app_meta_set(&conn, LICENSE_KEY, &license.replace(".", ".x")).unwrap();
+        assert!(deactivate_in_conn(&mut conn, &[&pem], now).is_err());
+    }
+}
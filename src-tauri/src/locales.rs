@@ -0,0 +1,138 @@
+//! Language tables for the PDF, the invoice email and the legal note. Each JSON file in
+//! `src/shared` maps a language code to its strings, so a new language only needs new entries
+//! there. A language (or a single key) missing from a table falls back to English, then Serbian.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Tried after the requested language, in order.
+const FALLBACK_LANGUAGES: [&str; 2] = ["en", "sr"];
+
+pub(crate) type LocaleTable = HashMap<String, Map<String, Value>>;
+
+/// Primary subtag in lower case: "sr-Latn-RS" → "sr".
+pub(crate) fn language_code(lang: &str) -> String {
+    let lang = lang.trim();
+    let end = lang.find(['-', '_']).unwrap_or(lang.len());
+    lang[..end].to_ascii_lowercase()
+}
+
+/// Parses a `{ "<lang>": { … } }` file; a broken file yields an empty table.
+pub(crate) fn parse_table(json: &str) -> LocaleTable {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// Strings of `lang`, each key missing from it taken from English, then Serbian.
+pub(crate) fn resolve<T: DeserializeOwned>(table: &LocaleTable, lang: &str) -> Result<T, String> {
+    let code = language_code(lang);
+    let mut merged = Map::new();
+    for key in FALLBACK_LANGUAGES.iter().rev().copied().chain([code.as_str()]) {
+        if let Some(strings) = table.get(key) {
+            merged.extend(strings.clone());
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|e| format!("Incomplete language table ({code}): {e}"))
+}
+
+/// Languages present in `table`: Serbian and English first, the rest alphabetically.
+pub(crate) fn table_languages(table: &LocaleTable) -> Vec<String> {
+    let mut langs: Vec<String> = table.keys().cloned().collect();
+    let rank = |l: &str| FALLBACK_LANGUAGES.iter().rev().position(|f| *f == l).unwrap_or(usize::MAX);
+    langs.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    langs
+}
+
+/// Languages the PDF can be printed in.
+pub(crate) fn available_languages() -> Vec<String> {
+    table_languages(crate::pdf_label_table())
+}
+
+pub(crate) fn is_available_language(lang: &str) -> bool {
+    crate::pdf_label_table().contains_key(&language_code(lang))
+}
+
+/// Digit grouping and decimal separator of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NumberStyle {
+    thousands: char,
+    decimal: char,
+}
+
+impl NumberStyle {
+    pub(crate) const EN: NumberStyle = NumberStyle { thousands: ',', decimal: '.' };
+    pub(crate) const SR: NumberStyle = NumberStyle { thousands: '.', decimal: ',' };
+
+    /// English and the languages that write numbers like it; everyone else groups with '.' and
+    /// uses a decimal comma, as Serbian does.
+    pub(crate) fn for_language(lang: &str) -> NumberStyle {
+        match language_code(lang).as_str() {
+            "en" => NumberStyle::EN,
+            _ => NumberStyle::SR,
+        }
+    }
+
+    /// Two decimals with grouped thousands: 16.200,00 / 16,200.00.
+    pub(crate) fn money(self, v: f64) -> String {
+        let s = format!("{:.2}", v);
+        let (int_part, dec_part) = s.split_once('.').unwrap_or((&s, "00"));
+        let (sign, digits) = int_part.strip_prefix('-').map_or(("", int_part), |d| ("-", d));
+        let mut grouped = String::new();
+        for (i, ch) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(self.thousands);
+            }
+            grouped.push(ch);
+        }
+        format!("{sign}{grouped}{}{dec_part}", self.decimal)
+    }
+
+    /// Two decimals, no grouping.
+    pub(crate) fn quantity(self, v: f64) -> String {
+        format!("{:.2}", v).replace('.', &self.decimal.to_string())
+    }
+
+    /// Up to two decimals, trailing zeros dropped.
+    pub(crate) fn percent(self, v: f64) -> String {
+        let s = format!("{:.2}", v);
+        s.trim_end_matches('0').trim_end_matches('.').replace('.', &self.decimal.to_string())
+    }
+}
+
+/// Codes of the languages invoices can be printed and emailed in.
+#[tauri::command]
+pub(crate) fn list_available_languages() -> Vec<String> {
+    available_languages()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_languages_and_keys_fall_back_to_english_then_serbian() {
+        let table = parse_table(
+            r#"{
+                "sr": { "a": "sr-a", "b": "sr-b", "c": "sr-c" },
+                "en": { "a": "en-a", "b": "en-b" },
+                "de": { "a": "de-a" }
+            }"#,
+        );
+        let de: HashMap<String, String> = resolve(&table, "de-AT").unwrap();
+        assert_eq!((de["a"].as_str(), de["b"].as_str(), de["c"].as_str()), ("de-a", "en-b", "sr-c"));
+        let mk: HashMap<String, String> = resolve(&table, "mk").unwrap();
+        assert_eq!(mk["a"], "en-a");
+        assert_eq!(table_languages(&table), ["sr", "en", "de"]);
+    }
+
+    #[test]
+    fn numbers_follow_the_language() {
+        assert_eq!(NumberStyle::for_language("sr").money(1234567.5), "1.234.567,50");
+        assert_eq!(NumberStyle::for_language("en-US").money(-1234.0), "-1,234.00");
+        assert_eq!(NumberStyle::for_language("de").money(999.999), "1.000,00");
+        assert_eq!(NumberStyle::SR.quantity(2.5), "2,50");
+        assert_eq!(NumberStyle::EN.percent(12.50), "12.5");
+        assert_eq!(NumberStyle::SR.percent(20.0), "20");
+    }
+}
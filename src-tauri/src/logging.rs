@@ -0,0 +1,385 @@
+//! Structured logging: JSON lines in a daily-rotated file under `<app data>/logs` plus a plain
+//! copy on stderr. Everything written passes through [`redact`], so SMTP credentials and license
+//! strings never reach the disk. The level is adjustable at runtime and kept in `app_meta`.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::errors::AppError;
+use crate::{app_meta_get, app_meta_set, resolve_app_data_root, DbState};
+
+pub(crate) const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "pausaler";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 14;
+/// `app_meta` key holding the chosen level.
+const LOG_LEVEL_KEY: &str = "logLevel";
+const MAX_RECENT_LINES: usize = 2000;
+
+const REDACTED: &str = "[redacted]";
+/// Key fragments whose values are always hidden (`smtpPassword`, `refresh_token`, `license`, ...).
+const SENSITIVE_KEYS: [&str; 5] = ["password", "secret", "token", "license", "authorization"];
+/// Unbroken base64url runs at least this long are treated as license strings or tokens.
+const OPAQUE_TOKEN_MIN_LEN: usize = 48;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+static SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Marks a value (password, token, license) for redaction wherever it shows up in log output.
+pub(crate) fn register_secret(value: &str) {
+    let value = value.trim();
+    if value.len() < 4 || value == crate::secrets::KEYRING_SENTINEL {
+        return;
+    }
+    if let Ok(mut secrets) = SECRETS.lock() {
+        if !secrets.iter().any(|s| s == value) {
+            secrets.push(value.to_string());
+        }
+    }
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+/// Hides the value following a sensitive key in `key=value`, `key: value` and JSON
+/// (`"key":"value"`, also with escaped quotes) forms.
+fn redact_key_values(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let Some(key) = SENSITIVE_KEYS.iter().find(|k| lower.as_bytes()[i..].starts_with(k.as_bytes())) else {
+            i += 1;
+            continue;
+        };
+        let mut j = i + key.len();
+        while j < bytes.len() && (bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_') {
+            j += 1;
+        }
+        while j < bytes.len() && matches!(bytes[j], b'"' | b'\\' | b' ') {
+            j += 1;
+        }
+        if j >= bytes.len() || !matches!(bytes[j], b'=' | b':') {
+            i = j.max(i + 1);
+            continue;
+        }
+        j += 1;
+        while j < bytes.len() && matches!(bytes[j], b'"' | b'\\' | b' ') {
+            j += 1;
+        }
+        let start = j;
+        while j < bytes.len() && !matches!(bytes[j], b'"' | b'\\' | b' ' | b',' | b'}' | b'&' | b';' | b'\n') {
+            j += 1;
+        }
+        if j > start {
+            out.push_str(&text[copied..start]);
+            out.push_str(REDACTED);
+            copied = j;
+        }
+        i = j;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+fn redact_opaque_tokens(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut run = String::new();
+    let flush = |run: &mut String, out: &mut String| {
+        if run.len() >= OPAQUE_TOKEN_MIN_LEN {
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(run);
+        }
+        run.clear();
+    };
+    for c in text.chars() {
+        if is_token_char(c) {
+            run.push(c);
+        } else {
+            flush(&mut run, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut run, &mut out);
+    out
+}
+
+/// Scrubs registered secrets, sensitive key/value pairs and long opaque tokens from `text`.
+pub(crate) fn redact(text: &str) -> String {
+    let mut out = text.to_string();
+    if let Ok(secrets) = SECRETS.lock() {
+        for s in secrets.iter() {
+            if out.contains(s.as_str()) {
+                out = out.replace(s.as_str(), REDACTED);
+            }
+        }
+    }
+    redact_opaque_tokens(&redact_key_values(&out))
+}
+
+#[derive(Clone)]
+struct Redacting<M>(M);
+
+struct RedactingWriter<W>(W);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    // The fmt layer hands over each formatted event in one call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(redact(&String::from_utf8_lossy(buf)).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "error" => Some(LevelFilter::ERROR),
+        "warn" | "warning" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+fn level_name(level: LevelFilter) -> &'static str {
+    match level {
+        LevelFilter::ERROR => "error",
+        LevelFilter::WARN => "warn",
+        LevelFilter::DEBUG => "debug",
+        LevelFilter::TRACE => "trace",
+        LevelFilter::OFF => "off",
+        _ => "info",
+    }
+}
+
+/// Installs the global subscriber. Called once at startup; failures only disable file logging.
+pub(crate) fn init(log_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(log_dir).map_err(|e| e.to_string())?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| e.to_string())?;
+
+    let (filter, handle) = reload::Layer::new(LevelFilter::INFO);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .json()
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_writer(Redacting(appender)),
+        )
+        .with(fmt::layer().with_ansi(false).with_writer(Redacting(io::stderr)))
+        .try_init()
+        .map_err(|e| e.to_string())?;
+    let _ = LEVEL_HANDLE.set(handle);
+    Ok(())
+}
+
+fn apply_level(level: LevelFilter) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        if let Err(e) = handle.modify(|f| *f = level) {
+            tracing::warn!(target: "logging", error = %e, "failed to change log level");
+        }
+    }
+}
+
+fn current_level() -> LevelFilter {
+    LEVEL_HANDLE
+        .get()
+        .and_then(|h| h.clone_current())
+        .unwrap_or(LevelFilter::INFO)
+}
+
+/// Applies the level saved by `set_log_level`, if any.
+pub(crate) fn restore_saved_level(conn: &rusqlite::Connection) {
+    if let Ok(Some(saved)) = app_meta_get(conn, LOG_LEVEL_KEY) {
+        if let Some(level) = parse_level(&saved) {
+            apply_level(level);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogEntry {
+    pub(crate) timestamp: String,
+    pub(crate) level: String,
+    pub(crate) target: String,
+    pub(crate) message: String,
+    /// Structured fields of the event other than the message (`op`, `error`, ...).
+    pub(crate) fields: serde_json::Map<String, JsonValue>,
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => 1,
+        "WARN" => 2,
+        "INFO" => 3,
+        "DEBUG" => 4,
+        _ => 5,
+    }
+}
+
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let v: JsonValue = serde_json::from_str(line).ok()?;
+    let text = |key: &str| v.get(key).and_then(|x| x.as_str()).unwrap_or("").to_string();
+    let mut fields = v.get("fields").and_then(|f| f.as_object()).cloned().unwrap_or_default();
+    let message = match fields.remove("message") {
+        Some(JsonValue::String(s)) => s,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: text("timestamp"),
+        level: text("level"),
+        target: text("target"),
+        message,
+        fields,
+    })
+}
+
+/// The last `lines` entries at `min_level` or more severe, oldest first, across rotated files.
+fn read_recent(dir: &Path, lines: usize, min_level: LevelFilter) -> Result<Vec<LogEntry>, String> {
+    let max_rank = level_rank(level_name(min_level));
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(rd) => rd
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy())
+                    .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX) && n.ends_with(LOG_FILE_SUFFIX))
+            })
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    // Rotated names carry the date, so name order is age order.
+    files.sort();
+
+    let mut out = Vec::new();
+    for file in files.iter().rev() {
+        let content = fs::read_to_string(file).map_err(|e| e.to_string())?;
+        for line in content.lines().rev() {
+            if let Some(entry) = parse_entry(line) {
+                if level_rank(&entry.level) <= max_rank {
+                    out.push(entry);
+                    if out.len() >= lines {
+                        out.reverse();
+                        return Ok(out);
+                    }
+                }
+            }
+        }
+    }
+    out.reverse();
+    Ok(out)
+}
+
+#[tauri::command]
+pub(crate) async fn get_recent_logs(
+    app: tauri::AppHandle,
+    lines: Option<u32>,
+    level: Option<String>,
+) -> Result<Vec<LogEntry>, AppError> {
+    let dir = resolve_app_data_root(&app)?.join(LOG_DIR);
+    let lines = (lines.unwrap_or(200) as usize).clamp(1, MAX_RECENT_LINES);
+    let min_level = match level.as_deref() {
+        Some(l) if !l.trim().is_empty() => parse_level(l).ok_or_else(|| format!("Unknown log level: {l}"))?,
+        _ => LevelFilter::TRACE,
+    };
+    let entries = tauri::async_runtime::spawn_blocking(move || read_recent(&dir, lines, min_level))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(entries)
+}
+
+#[tauri::command]
+pub(crate) fn get_log_level() -> String {
+    level_name(current_level()).to_string()
+}
+
+#[tauri::command]
+pub(crate) async fn set_log_level(state: tauri::State<'_, DbState>, level: String) -> Result<String, AppError> {
+    let parsed = parse_level(&level).ok_or_else(|| format!("Unknown log level: {level}"))?;
+    let name = level_name(parsed);
+    state
+        .with_write("set_log_level", move |conn| app_meta_set(conn, LOG_LEVEL_KEY, name))
+        .await?;
+    apply_level(parsed);
+    tracing::info!(target: "logging", level = name, "log level changed");
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secrets_in_plain_and_json_output() {
+        register_secret("hunter2-smtp");
+        let line = r#"{"fields":{"message":"login failed for hunter2-smtp","body":"{\"smtpPassword\":\"abc\",\"host\":\"mail\"}"}}"#;
+        let out = redact(line);
+        assert!(!out.contains("hunter2-smtp") && !out.contains("abc"), "{out}");
+        assert!(out.contains(r#"\"host\":\"mail\""#));
+
+        assert_eq!(redact("refresh_token=xyz&grant=1"), "refresh_token=[redacted]&grant=1");
+        let license = format!("{}.{}", "eyJwaWJfaGFzaCI6ImFiYyIsImxpY2Vuc2VfdHlwZSI6IllFQVJMWSJ9", "c2lnbmF0dXJl");
+        assert_eq!(redact(&format!("verify {license} ok")), "verify [redacted] ok");
+        assert_eq!(redact("op=send invoice=INV-2025-001"), "op=send invoice=INV-2025-001");
+    }
+
+    #[test]
+    fn recent_logs_filter_by_level_across_files() {
+        let dir = std::env::temp_dir().join(format!("pausaler-logs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let line = |level: &str, msg: &str| {
+            format!(r#"{{"timestamp":"t","level":"{level}","fields":{{"message":"{msg}","op":"x"}},"target":"email"}}"#)
+        };
+        fs::write(dir.join("pausaler.2025-03-01.log"), [line("ERROR", "old"), line("INFO", "a")].join("\n")).unwrap();
+        fs::write(dir.join("pausaler.2025-03-02.log"), [line("WARN", "b"), "not json".into(), line("DEBUG", "c")].join("\n"))
+            .unwrap();
+
+        let all = read_recent(&dir, 10, LevelFilter::TRACE).unwrap();
+        assert_eq!(all.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), ["old", "a", "b", "c"]);
+        assert_eq!(all[0].fields.get("op"), Some(&JsonValue::String("x".into())));
+
+        let warn = read_recent(&dir, 10, LevelFilter::WARN).unwrap();
+        assert_eq!(warn.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), ["old", "b"]);
+        let last = read_recent(&dir, 1, LevelFilter::TRACE).unwrap();
+        assert_eq!(last[0].message, "c");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
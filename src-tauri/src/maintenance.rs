@@ -0,0 +1,132 @@
+//! Database health check and compaction: `integrity_check`, then (when asked) a truncating WAL
+//! checkpoint, `ANALYZE` and `VACUUM`. Runs on the shared connection under the write lock.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::{now_iso, wal_path, DbState};
+
+/// Rows reported by `integrity_check` are capped; one line is enough to know a restore is due.
+const INTEGRITY_MAX_ERRORS: u32 = 100;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MaintenanceReport {
+    pub(crate) integrity_ok: bool,
+    pub(crate) integrity_errors: Vec<String>,
+    /// Set when the integrity check failed: compaction was not attempted and the user should
+    /// restore a backup instead of continuing to write to this file.
+    pub(crate) restore_recommended: bool,
+    pub(crate) compacted: bool,
+    /// `true` when the checkpoint could not complete because another connection held the WAL.
+    pub(crate) checkpoint_busy: bool,
+    pub(crate) db_size_before: u64,
+    pub(crate) db_size_after: u64,
+    pub(crate) wal_size_before: u64,
+    pub(crate) wal_size_after: u64,
+    pub(crate) checked_at: String,
+}
+
+fn file_len(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// (database, WAL) sizes in bytes; both zero for an in-memory connection.
+fn file_sizes(conn: &Connection) -> (u64, u64) {
+    match conn.path().filter(|p| !p.is_empty()) {
+        Some(p) => {
+            let db = Path::new(p);
+            (file_len(db), file_len(&wal_path(db)))
+        }
+        None => (0, 0),
+    }
+}
+
+fn integrity_errors(conn: &Connection) -> Vec<String> {
+    let rows = conn
+        .prepare(&format!("PRAGMA integrity_check({INTEGRITY_MAX_ERRORS})"))
+        .and_then(|mut stmt| {
+            stmt.query_map([], |r| r.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()
+        });
+    match rows {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => Vec::new(),
+        Ok(rows) => rows,
+        // A badly damaged file can fail the pragma itself (SQLITE_CORRUPT / NOTADB).
+        Err(e) => vec![e.to_string()],
+    }
+}
+
+pub(crate) fn run_maintenance(conn: &Connection, compact: bool) -> Result<MaintenanceReport, rusqlite::Error> {
+    let (db_size_before, wal_size_before) = file_sizes(conn);
+    let errors = integrity_errors(conn);
+    let integrity_ok = errors.is_empty();
+
+    let mut checkpoint_busy = false;
+    let compacted = compact && integrity_ok;
+    if compacted {
+        checkpoint_busy = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |r| r.get::<_, i64>(0))? != 0;
+        conn.execute_batch("ANALYZE; VACUUM;")?;
+        // VACUUM goes through the WAL too; fold it back so the reported size is the real one.
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |r| r.get::<_, i64>(0))?;
+    }
+
+    let (db_size_after, wal_size_after) = file_sizes(conn);
+    Ok(MaintenanceReport {
+        integrity_ok,
+        integrity_errors: errors,
+        restore_recommended: !integrity_ok,
+        compacted,
+        checkpoint_busy,
+        db_size_before,
+        db_size_after,
+        wal_size_before,
+        wal_size_after,
+        checked_at: now_iso(),
+    })
+}
+
+/// Integrity check only when `compact` is false (the health panel), full maintenance otherwise.
+#[tauri::command]
+pub(crate) async fn database_maintenance(
+    state: tauri::State<'_, DbState>,
+    compact: Option<bool>,
+) -> Result<MaintenanceReport, AppError> {
+    let compact = compact.unwrap_or(false);
+    state
+        .with_write("database_maintenance", move |conn| run_maintenance(conn, compact))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compaction_shrinks_file_and_empties_wal() {
+        let dir = std::env::temp_dir().join(format!("pausaler-maint-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = Connection::open(dir.join("pausaler.db")).unwrap();
+        crate::configure_sqlite(&conn).unwrap();
+        conn.execute_batch("CREATE TABLE blob_t (b BLOB)").unwrap();
+        for _ in 0..200 {
+            conn.execute("INSERT INTO blob_t VALUES (zeroblob(4096))", []).unwrap();
+        }
+        conn.execute_batch("DELETE FROM blob_t").unwrap();
+
+        let check = run_maintenance(&conn, false).unwrap();
+        assert!(check.integrity_ok && !check.restore_recommended && !check.compacted);
+        assert!(check.wal_size_before > 0);
+
+        let report = run_maintenance(&conn, true).unwrap();
+        assert!(report.compacted);
+        assert_eq!(report.wal_size_after, 0);
+        assert!(report.db_size_after < 100 * 1024);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,188 @@
+//! Invoice amounts in minor units (para/cents). Line totals, discounts and the invoice total are
+//! rounded once each, in the order they are printed, and then added as integers, so the sum of
+//! the printed line totals always equals the printed total. The PDF, the stored totals and the
+//! CSV export go through [`invoice_amounts`]; the email and reports use the stored total, which
+//! already has the invoice discount taken off.
+//!
+//! The rounding mode is a setting and is stamped on each invoice when it is created, so changing
+//! it later doesn't change the total of an invoice already issued.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingMode {
+    /// Halves away from zero (0.005 → 0.01); the usual commercial rounding.
+    #[default]
+    HalfUp,
+    /// Halves to the even cent (banker's rounding).
+    HalfEven,
+    /// Toward zero: fractions of a cent are dropped.
+    Down,
+}
+
+impl RoundingMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "halfUp",
+            RoundingMode::HalfEven => "halfEven",
+            RoundingMode::Down => "down",
+        }
+    }
+
+    pub(crate) fn parse(s: &str) -> RoundingMode {
+        match s.trim() {
+            "halfEven" => RoundingMode::HalfEven,
+            "down" => RoundingMode::Down,
+            _ => RoundingMode::HalfUp,
+        }
+    }
+
+    /// Rounds a value already in minor units to a whole number of them. A fraction within float
+    /// noise of one half counts as a half, so `2.675` (stored as 2.67499…) rounds like 2.675.
+    fn round(self, minor: f64) -> i64 {
+        if !minor.is_finite() {
+            return 0;
+        }
+        let abs = minor.abs();
+        let whole = abs.floor();
+        let frac = abs - whole;
+        let tolerance = 1e-7_f64.max(abs * f64::EPSILON * 16.0);
+        let up = match self {
+            RoundingMode::Down => frac > 1.0 - tolerance,
+            _ if (frac - 0.5).abs() <= tolerance => match self {
+                RoundingMode::HalfEven => whole % 2.0 != 0.0,
+                _ => true,
+            },
+            _ => frac > 0.5,
+        };
+        let rounded = (whole + if up { 1.0 } else { 0.0 }) as i64;
+        if minor < 0.0 {
+            -rounded
+        } else {
+            rounded
+        }
+    }
+
+    /// `amount` in minor units.
+    pub(crate) fn to_minor(self, amount: f64) -> i64 {
+        self.round(amount * 100.0)
+    }
+}
+
+pub(crate) fn from_minor(minor: i64) -> f64 {
+    minor as f64 / 100.0
+}
+
+/// One line as a quantity, unit price and optional discount amount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Line {
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub discount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LineAmounts {
+    /// Quantity × unit price.
+    pub subtotal: i64,
+    /// Clamped to the line subtotal.
+    pub discount: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct InvoiceAmounts {
+    pub lines: Vec<LineAmounts>,
+    /// Sum of the line subtotals.
+    pub subtotal: i64,
+    /// Sum of the line discounts.
+    pub line_discounts: i64,
+    /// Sum of the line totals.
+    pub net: i64,
+    /// Flat plus percent invoice discount, clamped to the net.
+    pub invoice_discount: i64,
+    /// Net less the invoice discount; the amount to pay.
+    pub total: i64,
+}
+
+/// Flat `discount` plus `discount_percent` (0-100) of `net`, clamped to the net.
+pub(crate) fn invoice_discount(net: i64, discount: Option<f64>, discount_percent: Option<f64>, mode: RoundingMode) -> i64 {
+    let net = net.max(0);
+    let flat = mode.to_minor(discount.filter(|v| v.is_finite()).unwrap_or(0.0).max(0.0));
+    let percent = discount_percent.filter(|v| v.is_finite()).unwrap_or(0.0).clamp(0.0, 100.0);
+    (flat + mode.round(net as f64 * percent / 100.0)).clamp(0, net)
+}
+
+/// Amounts of an invoice with `lines`, a flat `discount` and a `discount_percent` (0-100) of the
+/// net after line discounts.
+pub(crate) fn invoice_amounts(
+    lines: impl IntoIterator<Item = Line>,
+    discount: Option<f64>,
+    discount_percent: Option<f64>,
+    mode: RoundingMode,
+) -> InvoiceAmounts {
+    let mut out = InvoiceAmounts::default();
+    for line in lines {
+        let subtotal = mode.to_minor(line.quantity * line.unit_price);
+        let discount = mode
+            .to_minor(line.discount.filter(|d| d.is_finite()).unwrap_or(0.0))
+            .clamp(0, subtotal.max(0));
+        let amounts = LineAmounts { subtotal, discount, total: subtotal - discount };
+        out.subtotal += amounts.subtotal;
+        out.line_discounts += amounts.discount;
+        out.net += amounts.total;
+        out.lines.push(amounts);
+    }
+    out.invoice_discount = invoice_discount(out.net, discount, discount_percent, mode);
+    out.total = (out.net - out.invoice_discount).max(0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn rounds_halves_by_mode() {
+        let cases = [(2.675, 268, 268, 267), (0.125, 13, 12, 12), (-1.005, -101, -100, -100), (10.0, 1000, 1000, 1000)];
+        for (v, half_up, half_even, down) in cases {
+            assert_eq!(RoundingMode::HalfUp.to_minor(v), half_up, "{v}");
+            assert_eq!(RoundingMode::HalfEven.to_minor(v), half_even, "{v}");
+            assert_eq!(RoundingMode::Down.to_minor(v), down, "{v}");
+        }
+        assert_eq!(RoundingMode::HalfUp.to_minor(3.0 * 33.335), 10001);
+        assert_eq!(RoundingMode::parse(RoundingMode::HalfEven.as_str()), RoundingMode::HalfEven);
+    }
+
+    #[test]
+    fn line_totals_always_add_up_to_the_total() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2133);
+        for _ in 0..5_000 {
+            let mode = [RoundingMode::HalfUp, RoundingMode::HalfEven, RoundingMode::Down][rng.gen_range(0..3)];
+            let lines: Vec<Line> = (0..rng.gen_range(1..8))
+                .map(|_| Line {
+                    quantity: rng.gen_range(1..100_000) as f64 / 1000.0,
+                    unit_price: rng.gen_range(1..10_000_000) as f64 / 1000.0,
+                    discount: rng.gen_bool(0.3).then(|| rng.gen_range(0..50_000) as f64 / 1000.0),
+                })
+                .collect();
+            let flat = rng.gen_bool(0.2).then(|| rng.gen_range(0..10_000) as f64 / 100.0);
+            let percent = rng.gen_bool(0.3).then(|| rng.gen_range(0..10_000) as f64 / 100.0);
+            let a = invoice_amounts(lines.iter().copied(), flat, percent, mode);
+
+            // What the PDF prints: each line total, then the totals box, all in cents.
+            let printed: Vec<f64> = a.lines.iter().map(|l| from_minor(l.total)).collect();
+            let printed_sum: i64 = printed.iter().map(|v| RoundingMode::HalfUp.to_minor(*v)).sum();
+            assert_eq!(printed_sum, a.net);
+            assert_eq!(a.subtotal - a.line_discounts, a.net);
+            assert_eq!(a.net - a.invoice_discount, a.total);
+            assert!(a.total >= 0 && a.invoice_discount <= a.net);
+            for l in &a.lines {
+                assert!(l.discount >= 0 && l.discount <= l.subtotal);
+            }
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Printable monthly report: invoices issued and paid, expenses by category and the net result.
+//! Like the dashboard, issued follows the issue date and paid follows `paidAt`; amounts in
+//! different currencies are never added together, each currency gets its own subtotals.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+
+use crate::errors::AppError;
+use crate::file_opener::ExportedFile;
+use crate::kpo::{company_address_one_line, format_date_dmy};
+use crate::pdf_util::{
+    draw_rule, draw_rule_with_thickness, embed_font, push_line, push_line_right_measured, save_pdf,
+    wrap_text_by_width_mm,
+};
+use crate::{format_money_sr, read_settings_from_conn, validation_to_sql_error, DbState};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MonthlyReportInvoice {
+    pub invoice_number: String,
+    pub client_name: String,
+    /// Issue date for issued invoices, payment date for paid ones, `YYYY-MM-DD`.
+    pub date: String,
+    pub currency: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MonthlyReportCategory {
+    /// Empty when uncategorized.
+    pub category: String,
+    pub count: i64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct MonthlyReportCurrency {
+    pub currency: String,
+    pub issued: f64,
+    pub paid: f64,
+    pub expenses: f64,
+    /// Cash-basis result: `paid - expenses`.
+    pub net: f64,
+    pub categories: Vec<MonthlyReportCategory>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MonthlyReport {
+    pub year: i32,
+    pub month: u32,
+    pub company_name: String,
+    pub pib: String,
+    pub company_address: String,
+    pub issued: Vec<MonthlyReportInvoice>,
+    pub paid: Vec<MonthlyReportInvoice>,
+    pub currencies: Vec<MonthlyReportCurrency>,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn read_invoices(conn: &Connection, sql: &str, from: &str, to: &str) -> Result<Vec<MonthlyReportInvoice>, rusqlite::Error> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![from, to], |r| {
+        Ok(MonthlyReportInvoice {
+            invoice_number: r.get(0)?,
+            client_name: r.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            date: r.get(2)?,
+            currency: r.get(3)?,
+            amount: r.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn slot<'a>(currencies: &'a mut BTreeMap<String, MonthlyReportCurrency>, currency: &str) -> &'a mut MonthlyReportCurrency {
+    currencies.entry(currency.to_string()).or_insert_with(|| MonthlyReportCurrency {
+        currency: currency.to_string(),
+        ..Default::default()
+    })
+}
+
+pub(crate) fn monthly_report_in_conn(conn: &Connection, year: i32, month: u32) -> Result<MonthlyReport, rusqlite::Error> {
+    if !(1..=12).contains(&month) {
+        return Err(validation_to_sql_error(format!("Invalid month: {month}.")));
+    }
+    let settings = read_settings_from_conn(conn)?;
+    let from = format!("{year:04}-{month:02}-01");
+    let to = format!("{year:04}-{month:02}-31");
+
+    let issued = read_invoices(
+        conn,
+        r#"SELECT invoiceNumber, json_extract(data_json, '$.clientName'), issueDate, currency, totalAmount
+           FROM invoices
+           WHERE status IN ('SENT', 'PAID') AND issueDate >= ?1 AND issueDate <= ?2
+           ORDER BY issueDate ASC, createdAt ASC"#,
+        &from,
+        &to,
+    )?;
+    // paidAt may be a bare date or a full timestamp; only the date part decides the month.
+    let paid = read_invoices(
+        conn,
+        r#"SELECT invoiceNumber, client_name, paid_on, currency, totalAmount
+           FROM (
+             SELECT invoiceNumber, json_extract(data_json, '$.clientName') AS client_name, currency, totalAmount, createdAt,
+                    substr(COALESCE(NULLIF(TRIM(paidAt), ''), issueDate), 1, 10) AS paid_on
+             FROM invoices
+             WHERE status = 'PAID'
+           )
+           WHERE paid_on >= ?1 AND paid_on <= ?2
+           ORDER BY paid_on ASC, createdAt ASC"#,
+        &from,
+        &to,
+    )?;
+
+    let mut currencies: BTreeMap<String, MonthlyReportCurrency> = BTreeMap::new();
+    for inv in &issued {
+        slot(&mut currencies, &inv.currency).issued += inv.amount;
+    }
+    for inv in &paid {
+        slot(&mut currencies, &inv.currency).paid += inv.amount;
+    }
+
+    let mut stmt = conn.prepare(
+        r#"SELECT currency, COALESCE(NULLIF(TRIM(category), ''), '') AS k, SUM(amount), COUNT(*)
+           FROM expenses
+           WHERE deletedAt IS NULL AND date >= ?1 AND date <= ?2
+           GROUP BY currency, k
+           ORDER BY currency, k"#,
+    )?;
+    let mut rows = stmt.query(params![from, to])?;
+    while let Some(row) = rows.next()? {
+        let currency: String = row.get(0)?;
+        let amount: f64 = row.get(2)?;
+        let totals = slot(&mut currencies, &currency);
+        totals.expenses += amount;
+        totals.categories.push(MonthlyReportCategory {
+            category: row.get(1)?,
+            count: row.get(3)?,
+            amount: round2(amount),
+        });
+    }
+
+    let currencies = currencies
+        .into_values()
+        .map(|mut c| {
+            c.issued = round2(c.issued);
+            c.paid = round2(c.paid);
+            c.expenses = round2(c.expenses);
+            c.net = round2(c.paid - c.expenses);
+            c
+        })
+        .collect();
+
+    Ok(MonthlyReport {
+        year,
+        month,
+        company_address: company_address_one_line(&settings),
+        company_name: settings.company_name,
+        pib: settings.pib,
+        issued,
+        paid,
+        currencies,
+    })
+}
+
+fn money(amount: f64, currency: &str) -> String {
+    format!("{} {currency}", format_money_sr(amount))
+}
+
+/// Current page of the report; starts a new page when the next block doesn't fit.
+struct Pages<'a> {
+    doc: &'a printpdf::PdfDocumentReference,
+    layer: printpdf::PdfLayerReference,
+    y: f32,
+}
+
+impl Pages<'_> {
+    const PAGE_W: f32 = 210.0;
+    const PAGE_H: f32 = 297.0;
+    const MARGIN_TOP: f32 = 18.0;
+    const MARGIN_BOTTOM: f32 = 16.0;
+
+    fn ensure(&mut self, needed: f32) {
+        if self.y - needed < Self::MARGIN_BOTTOM {
+            let (page, layer) = self.doc.add_page(printpdf::Mm(Self::PAGE_W), printpdf::Mm(Self::PAGE_H), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = Self::PAGE_H - Self::MARGIN_TOP;
+        }
+    }
+}
+
+pub(crate) fn render_monthly_report_pdf(report: &MonthlyReport) -> Result<Vec<u8>, String> {
+    use printpdf::{Mm, PdfDocument};
+
+    const MARGIN_X: f32 = 16.0;
+    const FONT: f32 = 9.0;
+    const ROW_H: f32 = 4.8;
+
+    let title = format!("Mesečni izveštaj {:02}/{}", report.month, report.year);
+    let (doc, page1, layer1) = PdfDocument::new(&title, Mm(Pages::PAGE_W), Mm(Pages::PAGE_H), "Layer 1");
+    let (font, face) = embed_font(&doc)?;
+
+    let left = MARGIN_X;
+    let right = Pages::PAGE_W - MARGIN_X;
+    let col_number = left;
+    let col_date = left + 30.0;
+    let col_client = col_date + 24.0;
+    let client_width = right - 40.0 - col_client;
+    let col_count = right - 40.0;
+
+    let mut pages = Pages {
+        doc: &doc,
+        layer: doc.get_page(page1).get_layer(layer1),
+        y: Pages::PAGE_H - Pages::MARGIN_TOP,
+    };
+
+    push_line(&pages.layer, &font, "MESEČNI IZVEŠTAJ O PRIHODIMA I RASHODIMA", 12.0, left, pages.y);
+    push_line_right_measured(&pages.layer, &font, &face, &format!("{:02}/{}", report.month, report.year), 12.0, right, pages.y);
+    pages.y -= 7.0;
+    push_line(&pages.layer, &font, &report.company_name, FONT, left, pages.y);
+    pages.y -= ROW_H;
+    if !report.pib.trim().is_empty() {
+        push_line(&pages.layer, &font, &format!("PIB: {}", report.pib), FONT, left, pages.y);
+        pages.y -= ROW_H;
+    }
+    if !report.company_address.is_empty() {
+        push_line(&pages.layer, &font, &report.company_address, FONT, left, pages.y);
+        pages.y -= ROW_H;
+    }
+    draw_rule_with_thickness(&pages.layer, left, right, pages.y + 1.5, 0.6);
+    pages.y -= ROW_H;
+
+    let section = |pages: &mut Pages, heading: &str| {
+        pages.ensure(ROW_H * 4.0);
+        pages.y -= 2.0;
+        push_line(&pages.layer, &font, heading, 10.5, left, pages.y);
+        pages.y -= ROW_H + 1.0;
+    };
+    let subtotals = |pages: &mut Pages, label: &str, totals: &[(String, f64)]| {
+        pages.ensure(ROW_H * totals.len().max(1) as f32 + 2.0);
+        draw_rule(&pages.layer, left, right, pages.y + ROW_H - 1.2);
+        for (currency, amount) in totals {
+            push_line(&pages.layer, &font, &format!("{label} {currency}"), FONT, col_client, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &money(*amount, currency), FONT, right, pages.y);
+            pages.y -= ROW_H;
+        }
+        pages.y -= ROW_H * 0.5;
+    };
+    let invoice_table = |pages: &mut Pages, heading: &str, date_label: &str, invoices: &[MonthlyReportInvoice], totals: Vec<(String, f64)>| {
+        section(pages, heading);
+        if invoices.is_empty() {
+            push_line(&pages.layer, &font, "Nema faktura u ovom mesecu.", FONT, left, pages.y);
+            pages.y -= ROW_H * 1.5;
+            return;
+        }
+        push_line(&pages.layer, &font, "Broj", FONT, col_number, pages.y);
+        push_line(&pages.layer, &font, date_label, FONT, col_date, pages.y);
+        push_line(&pages.layer, &font, "Klijent", FONT, col_client, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Iznos", FONT, right, pages.y);
+        draw_rule(&pages.layer, left, right, pages.y - 1.6);
+        pages.y -= ROW_H + 0.6;
+        for inv in invoices {
+            let client = wrap_text_by_width_mm(&face, &inv.client_name, FONT, client_width);
+            pages.ensure(ROW_H * client.len().max(1) as f32);
+            push_line(&pages.layer, &font, &inv.invoice_number, FONT, col_number, pages.y);
+            push_line(&pages.layer, &font, &format_date_dmy(&inv.date), FONT, col_date, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &money(inv.amount, &inv.currency), FONT, right, pages.y);
+            for line in &client {
+                push_line(&pages.layer, &font, line, FONT, col_client, pages.y);
+                pages.y -= ROW_H;
+            }
+            if client.is_empty() {
+                pages.y -= ROW_H;
+            }
+        }
+        subtotals(pages, "Ukupno", &totals);
+    };
+
+    let per_currency = |f: fn(&MonthlyReportCurrency) -> f64| -> Vec<(String, f64)> {
+        report.currencies.iter().filter(|c| f(c) != 0.0).map(|c| (c.currency.clone(), f(c))).collect()
+    };
+    invoice_table(&mut pages, "Izdate fakture", "Datum izdavanja", &report.issued, per_currency(|c| c.issued));
+    invoice_table(&mut pages, "Naplaćene fakture", "Datum naplate", &report.paid, per_currency(|c| c.paid));
+
+    section(&mut pages, "Rashodi po kategorijama");
+    let with_expenses: Vec<&MonthlyReportCurrency> = report.currencies.iter().filter(|c| !c.categories.is_empty()).collect();
+    if with_expenses.is_empty() {
+        push_line(&pages.layer, &font, "Nema rashoda u ovom mesecu.", FONT, left, pages.y);
+        pages.y -= ROW_H * 1.5;
+    } else {
+        push_line(&pages.layer, &font, "Kategorija", FONT, col_number, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Broj stavki", FONT, col_count, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Iznos", FONT, right, pages.y);
+        draw_rule(&pages.layer, left, right, pages.y - 1.6);
+        pages.y -= ROW_H + 0.6;
+        for c in with_expenses {
+            for cat in &c.categories {
+                pages.ensure(ROW_H);
+                let name = if cat.category.is_empty() { "Bez kategorije" } else { cat.category.as_str() };
+                push_line(&pages.layer, &font, name, FONT, col_number, pages.y);
+                push_line_right_measured(&pages.layer, &font, &face, &cat.count.to_string(), FONT, col_count, pages.y);
+                push_line_right_measured(&pages.layer, &font, &face, &money(cat.amount, &c.currency), FONT, right, pages.y);
+                pages.y -= ROW_H;
+            }
+            subtotals(&mut pages, "Ukupno", &[(c.currency.clone(), c.expenses)]);
+        }
+    }
+
+    section(&mut pages, "Rezime");
+    if report.currencies.is_empty() {
+        push_line(&pages.layer, &font, "Nema prometa u ovom mesecu.", FONT, left, pages.y);
+    } else {
+        let col_issued = right - 3.0 * 38.0;
+        let col_paid = right - 2.0 * 38.0;
+        let col_expenses = right - 38.0;
+        push_line(&pages.layer, &font, "Valuta", FONT, left, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Izdato", FONT, col_issued, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Naplaćeno", FONT, col_paid, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Rashodi", FONT, col_expenses, pages.y);
+        push_line_right_measured(&pages.layer, &font, &face, "Neto (naplaćeno − rashodi)", FONT, right, pages.y);
+        draw_rule(&pages.layer, left, right, pages.y - 1.6);
+        pages.y -= ROW_H + 0.6;
+        for c in &report.currencies {
+            pages.ensure(ROW_H);
+            push_line(&pages.layer, &font, &c.currency, FONT, left, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &format_money_sr(c.issued), FONT, col_issued, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &format_money_sr(c.paid), FONT, col_paid, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &format_money_sr(c.expenses), FONT, col_expenses, pages.y);
+            push_line_right_measured(&pages.layer, &font, &face, &format_money_sr(c.net), FONT, right, pages.y);
+            pages.y -= ROW_H;
+        }
+        draw_rule_with_thickness(&pages.layer, left, right, pages.y + ROW_H - 1.2, 0.6);
+    }
+
+    save_pdf(doc)
+}
+
+/// Writes the report for `month` (1–12) of `year` as a PDF, opening it when `open_after_export` is set.
+#[tauri::command]
+pub(crate) async fn export_monthly_report_pdf(
+    state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+    year: i32,
+    month: u32,
+    output_path: String,
+    open_after_export: Option<bool>,
+) -> Result<ExportedFile, AppError> {
+    let report = state
+        .with_read("export_monthly_report_pdf", move |conn| monthly_report_in_conn(conn, year, month))
+        .await?;
+    let bytes = render_monthly_report_pdf(&report)?;
+    let path = std::path::PathBuf::from(&output_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(ExportedFile::new(&app, output_path, open_after_export.unwrap_or(false)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{expense, invoice};
+
+    #[test]
+    fn report_splits_issued_paid_and_expenses_per_currency() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        invoice("1").issued("2025-03-05").amount("RSD", 60000.0).insert(&conn);
+        invoice("2")
+            .client("c", "Klijent 2")
+            .issued("2025-02-20")
+            .status("PAID")
+            .paid_at("2025-03-02T10:00:00Z")
+            .amount("EUR", 500.0)
+            .insert(&conn);
+        invoice("3").issued("2025-03-10").status("PAID").paid_at("2025-04-01").amount("EUR", 300.0).insert(&conn);
+        invoice("4").issued("2025-03-11").status("DRAFT").amount("RSD", 999.0).insert(&conn);
+        expense("e1").on("2025-03-01").amount("RSD", 1000.0).category("Kancelarija").insert(&conn);
+        expense("e2").on("2025-03-31").amount("RSD", 250.5).category("Kancelarija").insert(&conn);
+        expense("e3").on("2025-03-15").amount("RSD", 100.0).insert(&conn);
+        expense("e4").on("2025-04-01").amount("RSD", 7777.0).insert(&conn);
+
+        let report = monthly_report_in_conn(&conn, 2025, 3).unwrap();
+        let issued: Vec<_> = report.issued.iter().map(|i| i.invoice_number.as_str()).collect();
+        assert_eq!(issued, vec!["1", "3"]);
+        assert_eq!(report.paid.len(), 1);
+        assert_eq!(report.paid[0].date, "2025-03-02");
+        assert_eq!(report.paid[0].client_name, "Klijent 2");
+
+        let eur = &report.currencies[0];
+        assert_eq!((eur.currency.as_str(), eur.issued, eur.paid, eur.net), ("EUR", 300.0, 500.0, 500.0));
+        let rsd = &report.currencies[1];
+        assert_eq!((rsd.currency.as_str(), rsd.issued, rsd.expenses, rsd.net), ("RSD", 60000.0, 1350.5, -1350.5));
+        let categories: Vec<_> = rsd.categories.iter().map(|c| (c.category.as_str(), c.count, c.amount)).collect();
+        assert_eq!(categories, vec![("", 1, 100.0), ("Kancelarija", 2, 1250.5)]);
+
+        assert!(render_monthly_report_pdf(&report).unwrap().starts_with(b"%PDF"));
+        assert!(monthly_report_in_conn(&conn, 2025, 13).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+//! Monthly tax and contributions from the tax decision (rešenje). Each decision holds the monthly
+//! porez, PIO, zdravstvo and nezaposlenost amounts from the month it takes effect; for any month
+//! the latest decision in force applies, so a new one entered mid-year only changes the months
+//! from its start. Generating a month turns its amounts into categorized expenses and records
+//! the month in `obligation_months`, which also tracks when it was paid. A month is generated
+//! once; editing a decision later doesn't touch months already generated.
+
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::data_events::DataEntity;
+use crate::recurring_expenses::{format_ymd, parse_ymd};
+use crate::{now_iso, validation_to_sql_error, DbState};
+
+/// Category of the generated expenses.
+pub(crate) const OBLIGATIONS_CATEGORY: &str = "Porezi i doprinosi";
+
+const OBLIGATIONS_CURRENCY: &str = "RSD";
+
+/// Day of the following month the obligations of a month are due (and dated).
+const DUE_DAY: u8 = 15;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Obligation {
+    pub id: String,
+    /// First month (`YYYY-MM`) the decision applies to.
+    pub valid_from: String,
+    /// Last month it applies to; open-ended when `None`.
+    #[serde(default)]
+    pub valid_to: Option<String>,
+    /// Porez na prihod od samostalne delatnosti.
+    pub tax: f64,
+    /// Doprinos za PIO.
+    pub pension: f64,
+    /// Doprinos za zdravstveno osiguranje.
+    pub health: f64,
+    /// Doprinos za osiguranje za slučaj nezaposlenosti.
+    pub unemployment: f64,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObligationInput {
+    pub valid_from: String,
+    #[serde(default)]
+    pub valid_to: Option<String>,
+    pub tax: f64,
+    pub pension: f64,
+    pub health: f64,
+    pub unemployment: f64,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObligationMonthStatus {
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Decision the month was generated from, or the one in force for it.
+    pub obligation_id: Option<String>,
+    pub total: f64,
+    pub generated_at: Option<String>,
+    pub paid_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedObligations {
+    pub month: String,
+    /// 0 when the month was already generated.
+    pub expenses_created: usize,
+}
+
+impl Obligation {
+    fn total(&self) -> f64 {
+        self.tax + self.pension + self.health + self.unemployment
+    }
+
+    /// Expense title and amount of each part.
+    fn parts(&self) -> [(&'static str, f64); 4] {
+        [
+            ("Porez na prihod od samostalne delatnosti", self.tax),
+            ("Doprinos za PIO", self.pension),
+            ("Doprinos za zdravstveno osiguranje", self.health),
+            ("Doprinos za osiguranje za slučaj nezaposlenosti", self.unemployment),
+        ]
+    }
+}
+
+/// `(year, month)` of a `YYYY-MM` string.
+fn parse_month(v: &str) -> Option<(i32, u8)> {
+    let (year, month) = v.trim().split_once('-')?;
+    let (year, month): (i32, u8) = (year.parse().ok()?, month.parse().ok()?);
+    ((1..=12).contains(&month) && (1900..=9999).contains(&year) && v.trim().len() == 7).then_some((year, month))
+}
+
+fn normalize_month(label: &str, v: &str) -> Result<String, String> {
+    let (year, month) = parse_month(v).ok_or_else(|| format!("{label} must be YYYY-MM."))?;
+    Ok(format!("{year:04}-{month:02}"))
+}
+
+/// Date the obligations of `month` are due: the 15th of the following month.
+fn due_date(month: &str) -> Option<String> {
+    let (year, month) = parse_month(month)?;
+    let (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    parse_ymd(&format!("{year:04}-{month:02}-{DUE_DAY:02}")).map(format_ymd)
+}
+
+fn clean_optional(v: Option<String>) -> Option<String> {
+    v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn obligation_from_input(id: String, created_at: String, input: ObligationInput) -> Result<Obligation, String> {
+    let valid_from = normalize_month("Valid from", &input.valid_from)?;
+    let valid_to = match clean_optional(input.valid_to) {
+        Some(v) => Some(normalize_month("Valid to", &v)?),
+        None => None,
+    };
+    if valid_to.as_deref().is_some_and(|to| to < valid_from.as_str()) {
+        return Err("Valid to must not be before valid from.".to_string());
+    }
+    let obligation = Obligation {
+        id,
+        valid_from,
+        valid_to,
+        tax: input.tax,
+        pension: input.pension,
+        health: input.health,
+        unemployment: input.unemployment,
+        notes: clean_optional(input.notes),
+        created_at,
+    };
+    let amounts = obligation.parts().map(|(_, amount)| amount);
+    if amounts.iter().any(|a| !a.is_finite() || *a < 0.0) {
+        return Err("Amounts must be 0 or more.".to_string());
+    }
+    if obligation.total() <= 0.0 {
+        return Err("Enter at least one amount from the tax decision.".to_string());
+    }
+    Ok(obligation)
+}
+
+const OBLIGATION_COLUMNS: &str = "id, validFrom, validTo, tax, pension, health, unemployment, notes, createdAt";
+
+fn obligation_from_row(r: &rusqlite::Row<'_>) -> Result<Obligation, rusqlite::Error> {
+    Ok(Obligation {
+        id: r.get(0)?,
+        valid_from: r.get(1)?,
+        valid_to: r.get(2)?,
+        tax: r.get(3)?,
+        pension: r.get(4)?,
+        health: r.get(5)?,
+        unemployment: r.get(6)?,
+        notes: r.get(7)?,
+        created_at: r.get(8)?,
+    })
+}
+
+fn write_obligation(conn: &Connection, o: &Obligation) -> Result<(), rusqlite::Error> {
+    let clash: Option<String> = conn
+        .query_row(
+            "SELECT id FROM obligations WHERE validFrom = ?1 AND id <> ?2",
+            params![o.valid_from, o.id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if clash.is_some() {
+        return Err(validation_to_sql_error(format!(
+            "A tax decision valid from {} already exists.",
+            o.valid_from
+        )));
+    }
+    conn.execute(
+        r#"INSERT INTO obligations (id, validFrom, validTo, tax, pension, health, unemployment, notes, createdAt)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+           ON CONFLICT(id) DO UPDATE SET validFrom=excluded.validFrom, validTo=excluded.validTo,
+               tax=excluded.tax, pension=excluded.pension, health=excluded.health,
+               unemployment=excluded.unemployment, notes=excluded.notes"#,
+        params![o.id, o.valid_from, o.valid_to, o.tax, o.pension, o.health, o.unemployment, o.notes, o.created_at],
+    )?;
+    Ok(())
+}
+
+/// The decision in force for `month`: the latest one started by then and not yet ended.
+fn obligation_for_month(conn: &Connection, month: &str) -> Result<Option<Obligation>, rusqlite::Error> {
+    conn.query_row(
+        &format!(
+            "SELECT {OBLIGATION_COLUMNS} FROM obligations
+             WHERE validFrom <= ?1 AND (validTo IS NULL OR validTo >= ?1)
+             ORDER BY validFrom DESC LIMIT 1"
+        ),
+        params![month],
+        obligation_from_row,
+    )
+    .optional()
+}
+
+/// Creates the expenses of `month` from the decision in force for it. A month already generated
+/// is left alone.
+pub(crate) fn generate_month_in_conn(conn: &mut Connection, month: &str) -> Result<GeneratedObligations, rusqlite::Error> {
+    let month = normalize_month("Month", month).map_err(validation_to_sql_error)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let generated: Option<String> = tx
+        .query_row("SELECT generatedAt FROM obligation_months WHERE month = ?1", params![month], |r| r.get(0))
+        .optional()?;
+    if generated.is_some() {
+        return Ok(GeneratedObligations { month, expenses_created: 0 });
+    }
+    let obligation = obligation_for_month(&tx, &month)?
+        .ok_or_else(|| validation_to_sql_error(format!("No tax decision covers {month}.")))?;
+    let date = due_date(&month).ok_or_else(|| validation_to_sql_error(format!("Invalid month {month}.")))?;
+    let label = format!("{}/{}", &month[5..], &month[..4]);
+    let now = now_iso();
+
+    let mut created = 0;
+    for (title, amount) in obligation.parts() {
+        if amount <= 0.0 {
+            continue;
+        }
+        tx.execute(
+            r#"INSERT INTO expenses (id, title, amount, currency, date, category, notes, createdAt)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7)"#,
+            params![
+                Uuid::new_v4().to_string(),
+                format!("{title} {label}"),
+                amount,
+                OBLIGATIONS_CURRENCY,
+                date,
+                OBLIGATIONS_CATEGORY,
+                now,
+            ],
+        )?;
+        created += 1;
+    }
+    tx.execute(
+        "INSERT INTO obligation_months (month, obligationId, total, generatedAt) VALUES (?1, ?2, ?3, ?4)",
+        params![month, obligation.id, obligation.total(), now],
+    )?;
+    tx.commit()?;
+    Ok(GeneratedObligations { month, expenses_created: created })
+}
+
+/// Each month of `year`: generated months as recorded, the others with the decision in force.
+pub(crate) fn status_in_conn(conn: &Connection, year: i32) -> Result<Vec<ObligationMonthStatus>, rusqlite::Error> {
+    (1..=12)
+        .map(|m| {
+            let month = format!("{year:04}-{m:02}");
+            let recorded = conn
+                .query_row(
+                    "SELECT obligationId, total, generatedAt, paidAt FROM obligation_months WHERE month = ?1",
+                    params![month],
+                    |r| {
+                        Ok(ObligationMonthStatus {
+                            month: month.clone(),
+                            obligation_id: r.get(0)?,
+                            total: r.get(1)?,
+                            generated_at: r.get(2)?,
+                            paid_at: r.get(3)?,
+                        })
+                    },
+                )
+                .optional()?;
+            if let Some(status) = recorded {
+                return Ok(status);
+            }
+            let obligation = obligation_for_month(conn, &month)?;
+            Ok(ObligationMonthStatus {
+                total: obligation.as_ref().map_or(0.0, Obligation::total),
+                obligation_id: obligation.map(|o| o.id),
+                month,
+                generated_at: None,
+                paid_at: None,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub(crate) async fn list_obligations(state: tauri::State<'_, DbState>) -> Result<Vec<Obligation>, AppError> {
+    state
+        .with_read("list_obligations", |conn| {
+            let mut stmt = conn.prepare(&format!("SELECT {OBLIGATION_COLUMNS} FROM obligations ORDER BY validFrom DESC"))?;
+            let rows = stmt.query_map([], obligation_from_row)?;
+            rows.collect()
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_obligation(
+    state: tauri::State<'_, DbState>,
+    input: ObligationInput,
+) -> Result<Obligation, AppError> {
+    let obligation = obligation_from_input(Uuid::new_v4().to_string(), now_iso(), input)?;
+    state
+        .with_write("create_obligation", move |conn| {
+            write_obligation(conn, &obligation)?;
+            Ok(obligation)
+        })
+        .await
+}
+
+/// Changes a decision; months already generated keep their expenses and recorded total.
+#[tauri::command]
+pub(crate) async fn update_obligation(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    input: ObligationInput,
+) -> Result<Option<Obligation>, AppError> {
+    state
+        .with_write("update_obligation", move |conn| {
+            let created_at: Option<String> = conn
+                .query_row("SELECT createdAt FROM obligations WHERE id = ?1", params![id], |r| r.get(0))
+                .optional()?;
+            let Some(created_at) = created_at else {
+                return Ok(None);
+            };
+            let obligation = obligation_from_input(id, created_at, input).map_err(validation_to_sql_error)?;
+            write_obligation(conn, &obligation)?;
+            Ok(Some(obligation))
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_obligation(state: tauri::State<'_, DbState>, id: String) -> Result<bool, AppError> {
+    state
+        .with_write("delete_obligation", move |conn| {
+            Ok(conn.execute("DELETE FROM obligations WHERE id = ?1", params![id])? > 0)
+        })
+        .await
+}
+
+/// Creates the expenses of `month` (`YYYY-MM`) from the tax decision in force for it.
+#[tauri::command]
+pub(crate) async fn generate_monthly_obligation_expenses(
+    state: tauri::State<'_, DbState>,
+    month: String,
+) -> Result<GeneratedObligations, AppError> {
+    state
+        .with_create_notify("generate_monthly_obligation_expenses", move |conn, changes| {
+            let generated = generate_month_in_conn(conn, &month)?;
+            if generated.expenses_created > 0 {
+                changes.replaced(DataEntity::Expenses);
+            }
+            Ok(generated)
+        })
+        .await
+}
+
+/// Marks a generated month as paid today, or clears the mark.
+#[tauri::command]
+pub(crate) async fn set_obligation_month_paid(
+    state: tauri::State<'_, DbState>,
+    month: String,
+    paid: bool,
+) -> Result<bool, AppError> {
+    let month = normalize_month("Month", &month)?;
+    state
+        .with_write("set_obligation_month_paid", move |conn| {
+            let paid_at = paid.then(now_iso);
+            Ok(conn.execute(
+                "UPDATE obligation_months SET paidAt = ?2 WHERE month = ?1",
+                params![month, paid_at],
+            )? > 0)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn obligations_status(
+    state: tauri::State<'_, DbState>,
+    year: i32,
+) -> Result<Vec<ObligationMonthStatus>, AppError> {
+    state
+        .with_read("obligations_status", move |conn| status_in_conn(conn, year))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(valid_from: &str, tax: f64, pension: f64) -> ObligationInput {
+        ObligationInput {
+            valid_from: valid_from.to_string(),
+            valid_to: None,
+            tax,
+            pension,
+            health: 3000.0,
+            unemployment: 0.0,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn a_new_decision_only_changes_months_from_its_start() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let first = obligation_from_input("a".to_string(), "t".to_string(), input("2025-01", 5000.0, 10000.0)).unwrap();
+        write_obligation(&conn, &first).unwrap();
+
+        let created = generate_month_in_conn(&mut conn, "2025-06").unwrap();
+        assert_eq!(created.expenses_created, 3);
+        assert_eq!(generate_month_in_conn(&mut conn, "2025-06").unwrap().expenses_created, 0);
+
+        // The decision changes from July; June keeps what it was generated with.
+        let second = obligation_from_input("b".to_string(), "t".to_string(), input("2025-07", 6000.0, 12000.0)).unwrap();
+        write_obligation(&conn, &second).unwrap();
+        generate_month_in_conn(&mut conn, "2025-12").unwrap();
+
+        let status = status_in_conn(&conn, 2025).unwrap();
+        let by_month = |m: usize| (status[m - 1].obligation_id.as_deref(), status[m - 1].total, status[m - 1].generated_at.is_some());
+        assert_eq!(by_month(5), (Some("a"), 18000.0, false));
+        assert_eq!(by_month(6), (Some("a"), 18000.0, true));
+        assert_eq!(by_month(7), (Some("b"), 21000.0, false));
+        assert_eq!(by_month(12), (Some("b"), 21000.0, true));
+
+        let expenses: Vec<(String, f64, String, String)> = {
+            let mut stmt = conn
+                .prepare("SELECT title, amount, date, category FROM expenses ORDER BY date, amount")
+                .unwrap();
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))).unwrap();
+            rows.collect::<Result<_, _>>().unwrap()
+        };
+        assert_eq!(expenses.len(), 6);
+        assert_eq!(expenses[0].0, "Doprinos za zdravstveno osiguranje 06/2025");
+        assert_eq!((expenses[0].2.as_str(), expenses[0].3.as_str()), ("2025-07-15", OBLIGATIONS_CATEGORY));
+        assert_eq!((expenses[5].1, expenses[5].2.as_str()), (12000.0, "2026-01-15"));
+
+        let err = generate_month_in_conn(&mut conn, "2024-12").unwrap_err();
+        assert!(err.to_string().contains("No tax decision"), "{err}");
+        assert!(write_obligation(&conn, &Obligation { id: "c".to_string(), ..second }).is_err());
+    }
+
+    #[test]
+    fn decisions_are_validated() {
+        let bad = |i: ObligationInput| obligation_from_input("x".to_string(), "t".to_string(), i).unwrap_err();
+        assert_eq!(bad(input("2025-13", 1.0, 1.0)), "Valid from must be YYYY-MM.");
+        assert_eq!(bad(input("2025-01", -1.0, 1.0)), "Amounts must be 0 or more.");
+        let mut empty = input("2025-01", 0.0, 0.0);
+        empty.health = 0.0;
+        assert_eq!(bad(empty), "Enter at least one amount from the tax decision.");
+        let mut reversed = input("2025-06", 1.0, 1.0);
+        reversed.valid_to = Some("2025-01".to_string());
+        assert_eq!(bad(reversed), "Valid to must not be before valid from.");
+        assert_eq!(due_date("2025-12").as_deref(), Some("2026-01-15"));
+    }
+}
@@ -5,9 +5,13 @@ use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::errors::AppError;
+use crate::features::{self, Feature};
+use crate::trial;
 use crate::{
-    escape_html, format_money, now_iso, read_settings_from_conn, send_email_via_smtp,
-    validate_smtp_settings, DbState, Settings,
+    escape_html, format_money, now_iso, read_settings_from_conn, reply_to_mailbox,
+    send_email_via_smtp, sender_mailbox, validate_smtp_settings, validation_to_sql_error, DbState,
+    Settings,
 };
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -132,13 +136,6 @@ fn validate_offer(offer: &Offer) -> Result<(), String> {
     Ok(())
 }
 
-fn validation_to_sql_error(message: String) -> rusqlite::Error {
-    rusqlite::Error::ToSqlConversionFailure(Box::new(std::io::Error::new(
-        std::io::ErrorKind::InvalidInput,
-        message,
-    )))
-}
-
 fn read_offer_from_conn(conn: &Connection, id: &str) -> Result<Option<Offer>, rusqlite::Error> {
     let json: Option<String> = conn
         .query_row(
@@ -245,7 +242,7 @@ fn render_offer_email(settings: &Settings, offer: &Offer) -> (String, String) {
 }
 
 #[tauri::command]
-pub(crate) async fn get_all_offers(state: tauri::State<'_, DbState>) -> Result<Vec<Offer>, String> {
+pub(crate) async fn get_all_offers(state: tauri::State<'_, DbState>) -> Result<Vec<Offer>, AppError> {
     state
         .with_read("get_all_offers", |conn| {
             let mut stmt = conn.prepare("SELECT data_json FROM offers ORDER BY createdAt DESC")?;
@@ -266,7 +263,7 @@ pub(crate) async fn get_all_offers(state: tauri::State<'_, DbState>) -> Result<V
 pub(crate) async fn get_offer_by_id(
     state: tauri::State<'_, DbState>,
     id: String,
-) -> Result<Option<Offer>, String> {
+) -> Result<Option<Offer>, AppError> {
     state
         .with_read("get_offer_by_id", move |conn| read_offer_from_conn(conn, &id))
         .await
@@ -276,7 +273,7 @@ pub(crate) async fn get_offer_by_id(
 pub(crate) async fn create_offer(
     state: tauri::State<'_, DbState>,
     input: NewOffer,
-) -> Result<Offer, String> {
+) -> Result<Offer, AppError> {
     let created = Offer {
         id: Uuid::new_v4().to_string(),
         client_email: required_trimmed(input.client_email, "Client email")?,
@@ -295,7 +292,7 @@ pub(crate) async fn create_offer(
     validate_offer(&created)?;
 
     state
-        .with_write("create_offer", move |conn| {
+        .with_create_notify("create_offer", move |conn, _| {
             insert_offer(conn, &created)?;
             Ok(created)
         })
@@ -307,10 +304,10 @@ pub(crate) async fn update_offer(
     state: tauri::State<'_, DbState>,
     id: String,
     patch: OfferPatch,
-) -> Result<Option<Offer>, String> {
+) -> Result<Option<Offer>, AppError> {
     if let Some(amount) = patch.amount {
         if !amount.is_finite() || amount <= 0.0 {
-            return Err("Amount must be greater than 0.".to_string());
+            return Err(AppError::validation("amount", "positive", "Amount must be greater than 0."));
         }
     }
 
@@ -363,7 +360,7 @@ pub(crate) async fn update_offer(
 pub(crate) async fn delete_offer(
     state: tauri::State<'_, DbState>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     state
         .with_write("delete_offer", move |conn| {
             let affected = conn.execute("DELETE FROM offers WHERE id = ?1", params![id])?;
@@ -376,48 +373,45 @@ pub(crate) async fn delete_offer(
 pub(crate) async fn send_offer_email(
     state: tauri::State<'_, DbState>,
     input: SendOfferEmailInput,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
+    state
+        .with_write("send_offer_email_license", |conn| {
+            trial::require_license(conn)?;
+            features::require_feature(conn, Feature::EmailSending)
+        })
+        .await?;
     let offer_id = input.offer_id;
     let (settings, offer) = state
         .with_read("send_offer_email_prepare", move |conn| {
             let settings = read_settings_from_conn(conn)?;
             let offer = read_offer_from_conn(conn, &offer_id)?
-                .ok_or_else(|| rusqlite::Error::QueryReturnedNoRows)?;
+                .ok_or_else(|| AppError::not_found("offer", offer_id))?;
             Ok((settings, offer))
         })
-        .await
-        .map_err(|e| {
-            if e.contains("QueryReturnedNoRows") {
-                "Offer not found".to_string()
-            } else {
-                e
-            }
-        })?;
+        .await?;
 
     validate_smtp_settings(&settings)?;
 
     let to = offer.client_email.trim().to_string();
     let subject = offer.subject.trim().to_string();
     if to.is_empty() {
-        return Err("Recipient email address is required.".to_string());
+        return Err(AppError::validation("clientEmail", "required", "Recipient email address is required."));
     }
     if subject.is_empty() {
-        return Err("Email subject is required.".to_string());
+        return Err(AppError::validation("subject", "required", "Email subject is required."));
     }
 
-    let from_mailbox: Mailbox = settings
-        .smtp_from
-        .parse()
-        .map_err(|_| "Invalid From address in SMTP settings.".to_string())?;
+    let from_mailbox = sender_mailbox(&settings)?;
     let to_mailbox: Mailbox = to
         .parse()
         .map_err(|_| "Invalid recipient email address.".to_string())?;
 
     let (html_body, text_body) = render_offer_email(&settings, &offer);
-    let email = Message::builder()
-        .from(from_mailbox)
-        .to(to_mailbox)
-        .subject(subject)
+    let mut builder = Message::builder().from(from_mailbox).to(to_mailbox).subject(subject);
+    if let Some(reply_to) = reply_to_mailbox(&settings)? {
+        builder = builder.reply_to(reply_to);
+    }
+    let email = builder
         .multipart(
             MultiPart::alternative()
                 .singlepart(SinglePart::plain(text_body))
@@ -444,10 +438,10 @@ pub(crate) async fn send_offer_email(
                     Ok(true)
                 })
                 .await
-                .map_err(|e| format!("Email sent, but failed to persist SENT status: {e}"))
+                .map_err(|e| AppError::Database(format!("Email sent, but failed to persist SENT status: {e}")))
         }
         Err(err) => {
-            let failure_reason = err.clone();
+            let failure_reason = err.to_string();
             let offer_id = offer.id.clone();
             match state
                 .with_write("send_offer_email_mark_failed", move |conn| {
@@ -464,9 +458,9 @@ pub(crate) async fn send_offer_email(
                 .await
             {
                 Ok(()) => Err(err),
-                Err(persist_err) => Err(format!(
+                Err(persist_err) => Err(AppError::Other(format!(
                     "{err} (also failed to persist FAILED status: {persist_err})"
-                )),
+                ))),
             }
         }
     }
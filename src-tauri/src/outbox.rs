@@ -0,0 +1,417 @@
+//! Invoice email outbox.
+//!
+//! `send_invoice_email` only queues a job; a single background worker sends queued jobs one at a
+//! time and reports every status change through the `email://status` event.
+
+use std::sync::Arc;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::{
+    deliver_invoice_email, now_iso, read_invoice_from_conn, validation_to_sql_error, webhooks, DbState,
+    SendInvoiceEmailInput,
+};
+
+pub(crate) const OUTBOX_STATUS_EVENT: &str = "email://status";
+
+const INTERRUPTED_ERROR: &str =
+    "Interrupted while sending (the app was closed). The email may already have been delivered; check your Sent folder before retrying.";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum OutboxStatus {
+    Queued,
+    Sending,
+    Sent,
+    Failed,
+    Cancelled,
+}
+
+impl OutboxStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OutboxStatus::Queued => "QUEUED",
+            OutboxStatus::Sending => "SENDING",
+            OutboxStatus::Sent => "SENT",
+            OutboxStatus::Failed => "FAILED",
+            OutboxStatus::Cancelled => "CANCELLED",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "QUEUED" => Some(OutboxStatus::Queued),
+            "SENDING" => Some(OutboxStatus::Sending),
+            "SENT" => Some(OutboxStatus::Sent),
+            "FAILED" => Some(OutboxStatus::Failed),
+            "CANCELLED" => Some(OutboxStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+    pub id: String,
+    pub invoice_id: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub status: OutboxStatus,
+    pub attempts: i64,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub sent_at: Option<String>,
+    /// Whether the sender's own address got a copy (the "send a copy to myself" setting). Set
+    /// once the email is sent.
+    pub self_copy: Option<bool>,
+}
+
+/// Wakes the worker when a job is queued or re-queued.
+#[derive(Clone, Default)]
+pub(crate) struct OutboxWorker {
+    notify: Arc<Notify>,
+}
+
+impl OutboxWorker {
+    fn wake(&self) {
+        self.notify.notify_one();
+    }
+}
+
+const OUTBOX_COLUMNS: &str =
+    "id, invoiceId, status, attempts, error, createdAt, updatedAt, sentAt, data_json, selfCopy";
+
+fn row_to_item(r: &rusqlite::Row<'_>) -> Result<OutboxItem, rusqlite::Error> {
+    let status: String = r.get(2)?;
+    let data_json: String = r.get(8)?;
+    let input = serde_json::from_str::<SendInvoiceEmailInput>(&data_json).ok();
+    Ok(OutboxItem {
+        id: r.get(0)?,
+        invoice_id: r.get(1)?,
+        to: input.as_ref().map(|i| i.to.clone()).unwrap_or_default(),
+        subject: input.map(|i| i.subject).unwrap_or_default(),
+        status: OutboxStatus::parse(&status).unwrap_or(OutboxStatus::Failed),
+        attempts: r.get(3)?,
+        error: r.get(4)?,
+        created_at: r.get(5)?,
+        updated_at: r.get(6)?,
+        sent_at: r.get(7)?,
+        self_copy: r.get(9)?,
+    })
+}
+
+fn read_outbox_item(conn: &Connection, id: &str) -> Result<Option<OutboxItem>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {OUTBOX_COLUMNS} FROM email_outbox WHERE id = ?1"),
+        params![id],
+        row_to_item,
+    )
+    .optional()
+}
+
+fn set_status(
+    conn: &Connection,
+    id: &str,
+    status: OutboxStatus,
+    error: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    let now = now_iso();
+    let sent_at = (status == OutboxStatus::Sent).then(|| now.clone());
+    conn.execute(
+        "UPDATE email_outbox SET status = ?2, error = ?3, updatedAt = ?4, sentAt = COALESCE(?5, sentAt) WHERE id = ?1",
+        params![id, status.as_str(), error, now, sent_at],
+    )?;
+    Ok(())
+}
+
+/// Marks a job SENT and records whether the sender got a copy.
+fn mark_sent(conn: &Connection, id: &str, self_copy: bool) -> Result<(), rusqlite::Error> {
+    set_status(conn, id, OutboxStatus::Sent, None)?;
+    conn.execute("UPDATE email_outbox SET selfCopy = ?2 WHERE id = ?1", params![id, self_copy])?;
+    Ok(())
+}
+
+/// A job left in SENDING means the app stopped mid-send. Whether the server accepted it is
+/// unknown, so it is never re-sent automatically; it is marked FAILED for the user to decide.
+pub(crate) fn fail_interrupted_jobs(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "UPDATE email_outbox SET status = 'FAILED', error = ?1, updatedAt = ?2 WHERE status = 'SENDING'",
+        params![INTERRUPTED_ERROR, now_iso()],
+    )
+}
+
+fn emit_status(app: &tauri::AppHandle, item: &OutboxItem) {
+    let _ = app.emit(OUTBOX_STATUS_EVENT, item);
+}
+
+/// Queues an invoice email. An identical job that is still queued or sending is reused, so a
+/// double click does not send the email twice.
+pub(crate) async fn enqueue_invoice_email(
+    app: &tauri::AppHandle,
+    db: &DbState,
+    worker: &OutboxWorker,
+    input: SendInvoiceEmailInput,
+) -> Result<String, AppError> {
+    let data_json = serde_json::to_string(&input).map_err(|e| e.to_string())?;
+    let item = db
+        .with_write("enqueue_invoice_email", move |conn| {
+            let exists: Option<i64> = conn
+                .query_row(
+                    "SELECT 1 FROM invoices WHERE id = ?1",
+                    params![input.invoice_id],
+                    |r| r.get(0),
+                )
+                .optional()?;
+            if exists.is_none() {
+                return Err(AppError::not_found("invoice", input.invoice_id).into());
+            }
+
+            let existing: Option<String> = conn
+                .query_row(
+                    "SELECT id FROM email_outbox WHERE invoiceId = ?1 AND status IN ('QUEUED', 'SENDING') AND data_json = ?2",
+                    params![input.invoice_id, data_json],
+                    |r| r.get(0),
+                )
+                .optional()?;
+            let id = match existing {
+                Some(id) => id,
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    let now = now_iso();
+                    conn.execute(
+                        "INSERT INTO email_outbox (id, invoiceId, status, attempts, error, createdAt, updatedAt, sentAt, data_json) VALUES (?1, ?2, 'QUEUED', 0, NULL, ?3, ?3, NULL, ?4)",
+                        params![id, input.invoice_id, now, data_json],
+                    )?;
+                    id
+                }
+            };
+            read_outbox_item(conn, &id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        })
+        .await?;
+
+    emit_status(app, &item);
+    worker.wake();
+    Ok(item.id)
+}
+
+/// Atomically moves the oldest queued job to SENDING.
+async fn claim_next_job(db: &DbState) -> Result<Option<(OutboxItem, SendInvoiceEmailInput)>, AppError> {
+    db.with_write("claim_outbox_job", |conn| {
+        let next: Option<(String, String)> = conn
+            .query_row(
+                "SELECT id, data_json FROM email_outbox WHERE status = 'QUEUED' ORDER BY createdAt LIMIT 1",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        let Some((id, data_json)) = next else {
+            return Ok(None);
+        };
+
+        let claimed = conn.execute(
+            "UPDATE email_outbox SET status = 'SENDING', attempts = attempts + 1, error = NULL, updatedAt = ?2 WHERE id = ?1 AND status = 'QUEUED'",
+            params![id, now_iso()],
+        )?;
+        if claimed != 1 {
+            return Ok(None);
+        }
+
+        let item = read_outbox_item(conn, &id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        match serde_json::from_str::<SendInvoiceEmailInput>(&data_json) {
+            Ok(input) => Ok(Some((item, input))),
+            Err(e) => {
+                set_status(conn, &id, OutboxStatus::Failed, Some(&format!("Corrupted outbox job: {e}")))?;
+                Ok(None)
+            }
+        }
+    })
+    .await
+}
+
+async fn finish_job(
+    db: &DbState,
+    id: String,
+    result: Result<bool, String>,
+) -> Result<Option<OutboxItem>, AppError> {
+    db.with_write("finish_outbox_job", move |conn| {
+        match &result {
+            Ok(self_copy) => mark_sent(conn, &id, *self_copy)?,
+            Err(e) => set_status(conn, &id, OutboxStatus::Failed, Some(e))?,
+        }
+        let item = read_outbox_item(conn, &id)?;
+        if let (Ok(_), Some(item)) = (&result, &item) {
+            if let Some(invoice) = read_invoice_from_conn(conn, &item.invoice_id)? {
+                webhooks::invoice_email_sent(conn, &invoice, &item.to);
+            }
+        }
+        Ok(item)
+    })
+    .await
+}
+
+/// Spawns the single outbox worker. Must run after `DbState` and `OutboxWorker` are managed.
+pub(crate) fn spawn_outbox_worker(app: tauri::AppHandle) {
+    let db = app.state::<DbState>().inner().clone();
+    let worker = app.state::<OutboxWorker>().inner().clone();
+    // Pick up jobs queued before the last shutdown.
+    worker.wake();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            worker.notify.notified().await;
+            loop {
+                let (item, input) = match claim_next_job(&db).await {
+                    Ok(Some(job)) => job,
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::error!(target: "outbox", op = "claim", error = %e, "failed to claim job");
+                        break;
+                    }
+                };
+                emit_status(&app, &item);
+
+                let result = deliver_invoice_email(&db, input).await.map_err(|e| e.to_string());
+                if let Err(e) = &result {
+                    tracing::error!(target: "outbox", op = "send", job = %item.id, error = %e, "email job failed");
+                }
+                match finish_job(&db, item.id.clone(), result).await {
+                    Ok(Some(done)) => emit_status(&app, &done),
+                    Ok(None) => {}
+                    // Leaves the job in SENDING; it becomes FAILED (not re-sent) on next start.
+                    Err(e) => tracing::error!(target: "outbox", op = "record", job = %item.id, error = %e, "failed to record job result"),
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn list_outbox(state: tauri::State<'_, DbState>) -> Result<Vec<OutboxItem>, AppError> {
+    state
+        .with_read("list_outbox", |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {OUTBOX_COLUMNS} FROM email_outbox ORDER BY createdAt DESC LIMIT 200"
+            ))?;
+            let rows = stmt.query_map([], row_to_item)?;
+            rows.collect()
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn retry_outbox_item(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    worker: tauri::State<'_, OutboxWorker>,
+    id: String,
+) -> Result<OutboxItem, AppError> {
+    let item = state
+        .with_write("retry_outbox_item", move |conn| {
+            let item = read_outbox_item(conn, &id)?
+                .ok_or_else(|| AppError::not_found("outboxItem", &id))?;
+            if !matches!(item.status, OutboxStatus::Failed | OutboxStatus::Cancelled) {
+                return Err(validation_to_sql_error(format!(
+                    "Only failed or cancelled emails can be retried (status: {}).",
+                    item.status.as_str()
+                )));
+            }
+            set_status(conn, &id, OutboxStatus::Queued, None)?;
+            read_outbox_item(conn, &id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+        })
+        .await?;
+
+    emit_status(&app, &item);
+    worker.wake();
+    Ok(item)
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_outbox_item(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    id: String,
+) -> Result<OutboxItem, AppError> {
+    let item = state
+        .with_write("cancel_outbox_item", move |conn| {
+            let cancelled = conn.execute(
+                "UPDATE email_outbox SET status = 'CANCELLED', updatedAt = ?2 WHERE id = ?1 AND status = 'QUEUED'",
+                params![id, now_iso()],
+            )?;
+            let item = read_outbox_item(conn, &id)?
+                .ok_or_else(|| AppError::not_found("outboxItem", &id))?;
+            if cancelled == 0 {
+                return Err(validation_to_sql_error(format!(
+                    "Only queued emails can be cancelled (status: {}).",
+                    item.status.as_str()
+                )));
+            }
+            Ok(item)
+        })
+        .await?;
+
+    emit_status(&app, &item);
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outbox_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        conn
+    }
+
+    fn insert_job(conn: &Connection, id: &str, status: &str) {
+        conn.execute(
+            "INSERT INTO email_outbox (id, invoiceId, status, attempts, error, createdAt, updatedAt, sentAt, data_json) VALUES (?1, 'inv', ?2, 1, NULL, 't', 't', NULL, '{}')",
+            params![id, status],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn interrupted_jobs_are_failed_not_resent() {
+        let conn = outbox_conn();
+        insert_job(&conn, "a", "SENDING");
+        insert_job(&conn, "b", "QUEUED");
+        assert_eq!(fail_interrupted_jobs(&conn).unwrap(), 1);
+
+        let a = read_outbox_item(&conn, "a").unwrap().unwrap();
+        assert_eq!(a.status, OutboxStatus::Failed);
+        assert_eq!(a.error.as_deref(), Some(INTERRUPTED_ERROR));
+        assert_eq!(read_outbox_item(&conn, "b").unwrap().unwrap().status, OutboxStatus::Queued);
+    }
+
+    #[test]
+    fn sent_status_records_sent_at() {
+        let conn = outbox_conn();
+        insert_job(&conn, "a", "SENDING");
+        set_status(&conn, "a", OutboxStatus::Sent, None).unwrap();
+        let a = read_outbox_item(&conn, "a").unwrap().unwrap();
+        assert_eq!(a.status, OutboxStatus::Sent);
+        assert!(a.sent_at.is_some());
+    }
+
+    #[test]
+    fn sent_job_records_the_self_copy() {
+        let conn = outbox_conn();
+        insert_job(&conn, "a", "SENDING");
+        insert_job(&conn, "b", "SENDING");
+        assert_eq!(read_outbox_item(&conn, "a").unwrap().unwrap().self_copy, None);
+
+        mark_sent(&conn, "a", true).unwrap();
+        mark_sent(&conn, "b", false).unwrap();
+        let a = read_outbox_item(&conn, "a").unwrap().unwrap();
+        assert_eq!((a.status, a.self_copy), (OutboxStatus::Sent, Some(true)));
+        assert_eq!(read_outbox_item(&conn, "b").unwrap().unwrap().self_copy, Some(false));
+    }
+}
@@ -0,0 +1,612 @@
+//! Incoming payments from bank statement CSV exports, matched to sent invoices and recorded in
+//! `invoice_payments`. A credit row is matched by its payment reference (poziv na broj) against
+//! the invoice's model 97 reference or number; without one, by an amount equal to what is still
+//! outstanding on an invoice whose client name matches the payer. Only a single unambiguous
+//! match is recorded. Rows with several candidates, or an amount-only match, are returned for
+//! the user to resolve with `resolve_payment_match`. An invoice whose payments cover its total
+//! is marked paid on the date of the last payment.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::csv_reader::{self, CsvTable};
+use crate::data_events::{DataEntity, DataOp};
+use crate::expense_import::{parse_amount, parse_bank_date};
+use crate::{
+    invoice_total_in_currency, now_iso, read_invoice_from_conn, read_settings_from_conn, trial, validation_to_sql_error,
+    webhooks, DbState, InvoiceStatus,
+};
+
+/// Legal-form words left out when comparing a payer with a client name.
+const LEGAL_FORM_WORDS: [&str; 6] = ["doo", "ad", "pr", "szr", "str", "preduzetnik"];
+
+/// CSV header for each payment field; date and amount are required.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentCsvMapping {
+    pub date: String,
+    /// Credit (incoming) amount column, or a signed amount column; empty, zero and negative
+    /// (debit) amounts are skipped.
+    pub amount: String,
+    /// Poziv na broj.
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// Payer name (nalogodavac).
+    #[serde(default)]
+    pub payer: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// One incoming payment read from the statement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRow {
+    /// 1-based line in the CSV file.
+    pub line: usize,
+    pub date: String,
+    pub amount: f64,
+    pub currency: String,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub payer: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaymentMatchKind {
+    Reference,
+    AmountAndName,
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedPayment {
+    #[serde(flatten)]
+    pub row: PaymentRow,
+    pub payment_id: String,
+    pub invoice_id: String,
+    pub invoice_number: String,
+    pub matched_by: PaymentMatchKind,
+    /// True when this payment settled the invoice and it was marked paid.
+    pub invoice_paid: bool,
+}
+
+/// An open invoice a payment could belong to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentCandidate {
+    pub invoice_id: String,
+    pub invoice_number: String,
+    pub client_name: String,
+    pub currency: String,
+    pub outstanding: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmbiguousPayment {
+    #[serde(flatten)]
+    pub row: PaymentRow,
+    pub reason: String,
+    pub candidates: Vec<PaymentCandidate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPaymentRow {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentImportReport {
+    pub matched: Vec<MatchedPayment>,
+    /// Not recorded; resolve each with `resolve_payment_match`.
+    pub ambiguous: Vec<AmbiguousPayment>,
+    pub unmatched: Vec<PaymentRow>,
+    /// Already recorded (same date, amount, reference and payer).
+    pub duplicates: Vec<PaymentRow>,
+    /// Debit rows and rows that couldn't be read.
+    pub skipped: Vec<SkippedPaymentRow>,
+}
+
+struct MappedColumns {
+    date: usize,
+    amount: usize,
+    reference: Option<usize>,
+    payer: Option<usize>,
+    currency: Option<usize>,
+}
+
+fn resolve_mapping(table: &CsvTable, mapping: &PaymentCsvMapping) -> Result<MappedColumns, String> {
+    let column = |header: &str| {
+        table
+            .column(header)
+            .ok_or_else(|| format!("Column \"{}\" was not found in the CSV header.", header.trim()))
+    };
+    let optional = |header: &Option<String>| match header.as_deref().map(str::trim) {
+        Some(h) if !h.is_empty() => column(h).map(Some),
+        _ => Ok(None),
+    };
+    for (header, field) in [(&mapping.date, "date"), (&mapping.amount, "amount")] {
+        if header.trim().is_empty() {
+            return Err(format!("Choose the CSV column that holds the {field}."));
+        }
+    }
+    Ok(MappedColumns {
+        date: column(&mapping.date)?,
+        amount: column(&mapping.amount)?,
+        reference: optional(&mapping.reference)?,
+        payer: optional(&mapping.payer)?,
+        currency: optional(&mapping.currency)?,
+    })
+}
+
+fn cell(row: &[String], idx: Option<usize>) -> String {
+    idx.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).unwrap_or_default()
+}
+
+/// `Ok(None)` for debit and empty rows.
+fn payment_from_csv_row(
+    cols: &MappedColumns,
+    default_currency: &str,
+    line: usize,
+    row: &[String],
+) -> Result<Option<PaymentRow>, String> {
+    let raw_amount = cell(row, Some(cols.amount));
+    if raw_amount.is_empty() {
+        return Ok(None);
+    }
+    let amount = parse_amount(&raw_amount).ok_or_else(|| format!("Invalid amount \"{raw_amount}\"."))?;
+    if amount <= 0.0 {
+        return Ok(None);
+    }
+    let raw_date = cell(row, Some(cols.date));
+    let date = parse_bank_date(&raw_date).ok_or_else(|| format!("Invalid date \"{raw_date}\"."))?;
+    let currency = Some(cell(row, cols.currency).to_uppercase())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| default_currency.to_string());
+    Ok(Some(PaymentRow {
+        line,
+        date,
+        amount: (amount * 100.0).round() / 100.0,
+        currency,
+        reference: Some(cell(row, cols.reference)).filter(|r| !r.is_empty()),
+        payer: Some(cell(row, cols.payer)).filter(|p| !p.is_empty()),
+    }))
+}
+
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Letters and digits only, uppercased: `97 12-04-2024-001` and `(97) 1204 2024001` compare equal.
+fn reference_key(v: &str) -> String {
+    v.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+fn name_words(v: &str) -> HashSet<String> {
+    v.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() > 1 && !LEGAL_FORM_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Every word of the shorter name appears in the longer one, legal forms aside.
+fn names_match(payer: &str, client: &str) -> bool {
+    let (a, b) = (name_words(payer), name_words(client));
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !short.is_empty() && short.is_subset(&long)
+}
+
+fn duplicate_key(row: &PaymentRow) -> (String, i64, String, String) {
+    (
+        row.date.clone(),
+        cents(row.amount),
+        row.reference.as_deref().map(reference_key).unwrap_or_default(),
+        row.payer.as_deref().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+    )
+}
+
+struct OpenInvoice {
+    id: String,
+    number: String,
+    client_name: String,
+    currency: String,
+    payable: f64,
+    paid: f64,
+    reference_key: String,
+    number_key: String,
+}
+
+impl OpenInvoice {
+    fn outstanding(&self) -> f64 {
+        ((self.payable - self.paid) * 100.0).round() / 100.0
+    }
+
+    fn matches_reference(&self, key: &str) -> bool {
+        !key.is_empty()
+            && (key == self.number_key
+                || (!self.reference_key.is_empty()
+                    && (key == self.reference_key || format!("97{key}") == self.reference_key)))
+    }
+
+    fn candidate(&self) -> PaymentCandidate {
+        PaymentCandidate {
+            invoice_id: self.id.clone(),
+            invoice_number: self.number.clone(),
+            client_name: self.client_name.clone(),
+            currency: self.currency.clone(),
+            outstanding: self.outstanding(),
+        }
+    }
+}
+
+fn paid_so_far(conn: &Connection, invoice_id: &str) -> Result<f64, rusqlite::Error> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM invoice_payments WHERE invoiceId = ?1",
+        params![invoice_id],
+        |r| r.get(0),
+    )
+}
+
+fn open_invoice(conn: &Connection, id: &str) -> Result<Option<OpenInvoice>, rusqlite::Error> {
+    let Some(inv) = read_invoice_from_conn(conn, id)? else {
+        return Ok(None);
+    };
+    if inv.status != InvoiceStatus::Sent {
+        return Ok(None);
+    }
+    Ok(Some(OpenInvoice {
+        payable: invoice_total_in_currency(&inv, &inv.currency).unwrap_or(inv.total),
+        paid: paid_so_far(conn, &inv.id)?,
+        reference_key: inv.payment_reference.as_deref().map(reference_key).unwrap_or_default(),
+        number_key: reference_key(&inv.invoice_number),
+        id: inv.id,
+        number: inv.invoice_number,
+        client_name: inv.client_name,
+        currency: inv.currency,
+    }))
+}
+
+fn open_invoices(conn: &Connection) -> Result<Vec<OpenInvoice>, rusqlite::Error> {
+    let ids: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT id FROM invoices WHERE status = 'SENT' ORDER BY issueDate, invoiceNumber")?;
+        let rows = stmt.query_map([], |r| r.get(0))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    let mut out = Vec::with_capacity(ids.len());
+    for id in ids {
+        out.extend(open_invoice(conn, &id)?);
+    }
+    Ok(out)
+}
+
+enum Match {
+    One(usize, PaymentMatchKind),
+    Ambiguous(String, Vec<usize>),
+    None,
+}
+
+fn find_match(row: &PaymentRow, invoices: &[OpenInvoice]) -> Match {
+    let open: Vec<usize> = (0..invoices.len())
+        .filter(|&i| invoices[i].currency.eq_ignore_ascii_case(&row.currency) && invoices[i].outstanding() > 0.0)
+        .collect();
+
+    let key = row.reference.as_deref().map(reference_key).unwrap_or_default();
+    let by_reference: Vec<usize> = open.iter().copied().filter(|&i| invoices[i].matches_reference(&key)).collect();
+    match by_reference.as_slice() {
+        [i] if cents(row.amount) <= cents(invoices[*i].outstanding()) => return Match::One(*i, PaymentMatchKind::Reference),
+        [_] => return Match::Ambiguous("The amount is more than the invoice's outstanding balance.".to_string(), by_reference),
+        [] => {}
+        _ => return Match::Ambiguous("The payment reference matches several invoices.".to_string(), by_reference),
+    }
+
+    let by_amount: Vec<usize> = open
+        .into_iter()
+        .filter(|&i| cents(invoices[i].outstanding()) == cents(row.amount))
+        .collect();
+    let by_name: Vec<usize> = by_amount
+        .iter()
+        .copied()
+        .filter(|&i| row.payer.as_deref().is_some_and(|p| names_match(p, &invoices[i].client_name)))
+        .collect();
+    match (by_name.as_slice(), by_amount.is_empty()) {
+        ([i], _) => Match::One(*i, PaymentMatchKind::AmountAndName),
+        ([], true) => Match::None,
+        ([], false) => Match::Ambiguous("Only the amount matches; the payer doesn't match the client.".to_string(), by_amount),
+        _ => Match::Ambiguous("Several invoices from this client have the same outstanding amount.".to_string(), by_name),
+    }
+}
+
+/// Inserts the payment and marks the invoice paid once its total is covered. Returns the
+/// payment id and whether the invoice was marked paid.
+fn record_payment(conn: &Connection, invoice: &mut OpenInvoice, row: &PaymentRow) -> Result<(String, bool), rusqlite::Error> {
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        r#"INSERT INTO invoice_payments (id, invoiceId, amount, currency, date, reference, payer, createdAt)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+        params![id, invoice.id, row.amount, row.currency, row.date, row.reference, row.payer, now_iso()],
+    )?;
+    invoice.paid += row.amount;
+    if cents(invoice.outstanding()) > 0 {
+        return Ok((id, false));
+    }
+    let Some(mut inv) = read_invoice_from_conn(conn, &invoice.id)? else {
+        return Ok((id, false));
+    };
+    let previous_status = inv.status;
+    inv.status = InvoiceStatus::Paid;
+    inv.paid_at = Some(row.date.clone());
+    let json = serde_json::to_string(&inv).unwrap_or_else(|_| "{}".to_string());
+    conn.execute(
+        "UPDATE invoices SET status = ?2, paidAt = ?3, data_json = ?4 WHERE id = ?1",
+        params![inv.id, inv.status.as_str(), inv.paid_at, json],
+    )?;
+    webhooks::invoice_status_changed(conn, &inv, previous_status);
+    Ok((id, true))
+}
+
+fn recorded_keys(conn: &Connection) -> Result<HashSet<(String, i64, String, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT date, amount, currency, reference, payer FROM invoice_payments")?;
+    let rows = stmt.query_map([], |r| {
+        Ok(PaymentRow {
+            line: 0,
+            date: r.get(0)?,
+            amount: r.get(1)?,
+            currency: r.get(2)?,
+            reference: r.get(3)?,
+            payer: r.get(4)?,
+        })
+    })?;
+    let mut keys = HashSet::new();
+    for row in rows {
+        keys.insert(duplicate_key(&row?));
+    }
+    Ok(keys)
+}
+
+/// Reads every credit row, records the single-match ones in one transaction and sorts the rest
+/// into the report's buckets.
+pub(crate) fn import_payments_from_table(
+    conn: &mut Connection,
+    table: &CsvTable,
+    mapping: &PaymentCsvMapping,
+    default_currency: &str,
+) -> Result<PaymentImportReport, rusqlite::Error> {
+    let cols = resolve_mapping(table, mapping).map_err(validation_to_sql_error)?;
+    let default_currency = default_currency.trim().to_uppercase();
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let mut invoices = open_invoices(&tx)?;
+    let mut known = recorded_keys(&tx)?;
+
+    let mut report = PaymentImportReport::default();
+    for (line, cells) in &table.rows {
+        let row = match payment_from_csv_row(&cols, &default_currency, *line, cells) {
+            Ok(Some(row)) => row,
+            Ok(None) => {
+                let message = "Debit (outgoing payment).".to_string();
+                report.skipped.push(SkippedPaymentRow { line: *line, message });
+                continue;
+            }
+            Err(message) => {
+                report.skipped.push(SkippedPaymentRow { line: *line, message });
+                continue;
+            }
+        };
+        if !known.insert(duplicate_key(&row)) {
+            report.duplicates.push(row);
+            continue;
+        }
+        match find_match(&row, &invoices) {
+            Match::One(i, matched_by) => {
+                let (payment_id, invoice_paid) = record_payment(&tx, &mut invoices[i], &row)?;
+                report.matched.push(MatchedPayment {
+                    payment_id,
+                    invoice_id: invoices[i].id.clone(),
+                    invoice_number: invoices[i].number.clone(),
+                    matched_by,
+                    invoice_paid,
+                    row,
+                });
+            }
+            Match::Ambiguous(reason, candidates) => report.ambiguous.push(AmbiguousPayment {
+                candidates: candidates.iter().map(|&i| invoices[i].candidate()).collect(),
+                reason,
+                row,
+            }),
+            Match::None => report.unmatched.push(row),
+        }
+    }
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Records `row` against `invoice_id` as chosen by the user.
+pub(crate) fn resolve_payment_in_conn(
+    conn: &mut Connection,
+    row: &PaymentRow,
+    invoice_id: &str,
+) -> Result<MatchedPayment, rusqlite::Error> {
+    if parse_bank_date(&row.date).as_deref() != Some(row.date.as_str()) {
+        return Err(validation_to_sql_error(format!("Invalid payment date \"{}\".", row.date)));
+    }
+    if !row.amount.is_finite() || row.amount <= 0.0 {
+        return Err(validation_to_sql_error("Payment amount must be greater than zero.".to_string()));
+    }
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(mut invoice) = open_invoice(&tx, invoice_id)? else {
+        return Err(validation_to_sql_error("Payments can only be recorded for sent, unpaid invoices.".to_string()));
+    };
+    if !invoice.currency.eq_ignore_ascii_case(&row.currency) {
+        return Err(validation_to_sql_error(format!(
+            "The payment is in {} but the invoice is in {}.",
+            row.currency, invoice.currency
+        )));
+    }
+    if recorded_keys(&tx)?.contains(&duplicate_key(row)) {
+        return Err(validation_to_sql_error("This payment has already been recorded.".to_string()));
+    }
+    let (payment_id, invoice_paid) = record_payment(&tx, &mut invoice, row)?;
+    tx.commit()?;
+    Ok(MatchedPayment {
+        row: row.clone(),
+        payment_id,
+        invoice_id: invoice.id,
+        invoice_number: invoice.number,
+        matched_by: PaymentMatchKind::Manual,
+        invoice_paid,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn import_payments_csv(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    mapping: PaymentCsvMapping,
+) -> Result<PaymentImportReport, AppError> {
+    let table = csv_reader::read_csv_file(&path)?;
+    state
+        .with_write_notify("import_payments_csv", move |conn, changes| {
+            trial::require_license(conn)?;
+            let default_currency = read_settings_from_conn(conn)?.default_currency;
+            let report = import_payments_from_table(conn, &table, &mapping, &default_currency)?;
+            let updated: HashSet<String> = report.matched.iter().map(|m| m.invoice_id.clone()).collect();
+            changes.record_all(DataEntity::Invoices, DataOp::Updated, updated);
+            Ok(report)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn resolve_payment_match(
+    state: tauri::State<'_, DbState>,
+    payment_row: PaymentRow,
+    invoice_id: String,
+) -> Result<MatchedPayment, AppError> {
+    state
+        .with_write_notify("resolve_payment_match", move |conn, changes| {
+            trial::require_license(conn)?;
+            let matched = resolve_payment_in_conn(conn, &payment_row, &invoice_id)?;
+            changes.record(DataEntity::Invoices, DataOp::Updated, matched.invoice_id.clone());
+            Ok(matched)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn insert_invoice(conn: &Connection, id: &str, number: &str, client: &str, total: f64, reference: Option<&str>) {
+        let json = serde_json::json!({
+            "id": id, "invoiceNumber": number, "clientId": client, "clientName": client,
+            "issueDate": "2025-03-01", "serviceDate": "2025-03-01", "status": "SENT",
+            "currency": "RSD", "subtotal": total, "total": total, "notes": "", "createdAt": "t",
+            "items": [], "paymentReference": reference
+        })
+        .to_string();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+             VALUES (?1, ?2, ?3, '2025-03-01', 'SENT', 'RSD', ?4, 't', ?5)",
+            params![id, number, client, total, json],
+        )
+        .unwrap();
+    }
+
+    fn status(conn: &Connection, id: &str) -> (String, Option<String>) {
+        conn.query_row("SELECT status, paidAt FROM invoices WHERE id = ?1", [id], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+    }
+
+    fn mapping() -> PaymentCsvMapping {
+        PaymentCsvMapping {
+            date: "Datum".into(),
+            amount: "Odobrenje".into(),
+            reference: Some("Poziv na broj".into()),
+            payer: Some("Nalogodavac".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_by_reference_or_amount_and_name_and_never_applies_ambiguous_rows() {
+        let mut conn = open_db();
+        insert_invoice(&conn, "a", "01-2025", "Alfa d.o.o.", 1000.0, Some("97 31-01-2025"));
+        insert_invoice(&conn, "b", "02-2025", "Beta", 500.0, None);
+        insert_invoice(&conn, "c", "03-2025", "Gama", 500.0, None);
+        insert_invoice(&conn, "d", "04-2025", "Delta", 700.0, None);
+        let table = csv_reader::parse_csv(
+            "Datum;Nalogodavac;Poziv na broj;Odobrenje\n\
+             10.03.2025;ALFA DOO Beograd;(97) 3101-2025;400,00\n\
+             11.03.2025;Alfa DOO;97 31-01-2025;600,00\n\
+             12.03.2025;Beta;;500,00\n\
+             13.03.2025;Neko treći;;500,00\n\
+             14.03.2025;Delta;;999,00\n\
+             15.03.2025;Delta;;-20,00\n\
+             11.03.2025;Alfa DOO;97 31-01-2025;600,00\n",
+        )
+        .unwrap();
+
+        let report = import_payments_from_table(&mut conn, &table, &mapping(), "RSD").unwrap();
+        let matched: Vec<_> = report
+            .matched
+            .iter()
+            .map(|m| (m.row.line, m.invoice_id.as_str(), m.matched_by, m.invoice_paid))
+            .collect();
+        assert_eq!(
+            matched,
+            vec![
+                (2, "a", PaymentMatchKind::Reference, false),
+                (3, "a", PaymentMatchKind::Reference, true),
+                (4, "b", PaymentMatchKind::AmountAndName, true),
+            ]
+        );
+        assert_eq!(status(&conn, "a"), ("PAID".to_string(), Some("2025-03-11".to_string())));
+
+        // Beta is paid, so 500 now only matches Gama by amount; that is not enough to apply it.
+        assert_eq!(report.ambiguous.len(), 1);
+        let ids: Vec<_> = report.ambiguous[0].candidates.iter().map(|c| c.invoice_id.as_str()).collect();
+        assert_eq!(ids, vec!["c"]);
+        assert_eq!(status(&conn, "c").0, "SENT");
+        assert_eq!(report.unmatched.iter().map(|r| r.line).collect::<Vec<_>>(), vec![6]);
+        assert_eq!(report.skipped.iter().map(|r| r.line).collect::<Vec<_>>(), vec![7]);
+        assert_eq!(report.duplicates.iter().map(|r| r.line).collect::<Vec<_>>(), vec![8]);
+
+        let resolved = resolve_payment_in_conn(&mut conn, &report.ambiguous[0].row, "c").unwrap();
+        assert!(resolved.invoice_paid);
+        assert_eq!(status(&conn, "c").0, "PAID");
+        let again = resolve_payment_in_conn(&mut conn, &report.ambiguous[0].row, "d").unwrap_err();
+        assert!(again.to_string().contains("already been recorded"), "{again}");
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM invoice_payments", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn same_reference_on_several_invoices_is_ambiguous() {
+        let mut conn = open_db();
+        insert_invoice(&conn, "a", "7", "Alfa", 100.0, None);
+        insert_invoice(&conn, "b", "07", "Beta", 100.0, Some("97 7"));
+        let table = csv_reader::parse_csv("Datum;Nalogodavac;Poziv na broj;Odobrenje\n01.04.2025;Alfa;7;100,00\n").unwrap();
+        let report = import_payments_from_table(&mut conn, &table, &mapping(), "RSD").unwrap();
+        assert!(report.matched.is_empty());
+        assert_eq!(report.ambiguous[0].candidates.len(), 2);
+        assert!(names_match("ALFA PR Novi Sad", "Alfa"));
+        assert!(!names_match("Alfa", "Beta"));
+    }
+}
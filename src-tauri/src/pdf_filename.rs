@@ -0,0 +1,341 @@
+//! File names of exported invoice PDFs, from the `pdf_filename_template` setting.
+//!
+//! The template may contain `/` to sort exports into folders, e.g. `{year}/{client}/{number}`.
+//! Each substituted value goes through `sanitize_filename` on its own, so a client name can't add
+//! folders; `..` and other dot-only segments are dropped, so the result always stays inside the
+//! export directory. `.pdf` is appended unless the template already ends with it.
+//!
+//! Files on disk get ASCII names, with Serbian letters transliterated ("Đorđević" → "Djordjevic"),
+//! since zip tools and network shares still mangle anything else. Email attachments keep the
+//! letters as they are; lettre encodes the name per RFC 2231.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+pub(crate) const DEFAULT_PDF_FILENAME_TEMPLATE: &str = "{number}-{client}";
+
+const PLACEHOLDERS: [&str; 5] = ["number", "client", "year", "month", "issueDate"];
+
+/// Which characters a file name may keep besides `-`, `_`, `.` and spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    /// ASCII letters and digits; Serbian letters are transliterated.
+    Ascii,
+    /// Letters and digits of any script, for attachment names.
+    Unicode,
+}
+
+/// ASCII spelling of a Serbian Latin or Cyrillic letter that has none of its own.
+fn transliterate(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        'đ' | 'ђ' => "dj",
+        'Đ' | 'Ђ' => "Dj",
+        'ć' | 'č' | 'ћ' | 'ч' | 'ц' => "c",
+        'Ć' | 'Č' | 'Ћ' | 'Ч' | 'Ц' => "C",
+        'š' | 'ш' => "s",
+        'Š' | 'Ш' => "S",
+        'ž' | 'ж' | 'з' => "z",
+        'Ž' | 'Ж' | 'З' => "Z",
+        'џ' => "dz",
+        'Џ' => "Dz",
+        'љ' => "lj",
+        'Љ' => "Lj",
+        'њ' => "nj",
+        'Њ' => "Nj",
+        'а' => "a",
+        'А' => "A",
+        'б' => "b",
+        'Б' => "B",
+        'в' => "v",
+        'В' => "V",
+        'г' => "g",
+        'Г' => "G",
+        'д' => "d",
+        'Д' => "D",
+        'е' => "e",
+        'Е' => "E",
+        'и' => "i",
+        'И' => "I",
+        'ј' => "j",
+        'Ј' => "J",
+        'к' => "k",
+        'К' => "K",
+        'л' => "l",
+        'Л' => "L",
+        'м' => "m",
+        'М' => "M",
+        'н' => "n",
+        'Н' => "N",
+        'о' => "o",
+        'О' => "O",
+        'п' => "p",
+        'П' => "P",
+        'р' => "r",
+        'Р' => "R",
+        'с' => "s",
+        'С' => "S",
+        'т' => "t",
+        'Т' => "T",
+        'у' => "u",
+        'У' => "U",
+        'ф' => "f",
+        'Ф' => "F",
+        'х' => "h",
+        'Х' => "H",
+        _ => return None,
+    })
+}
+
+/// `input` with every character a file name can't have replaced by `_`.
+fn clean_chars(input: &str, charset: Charset) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        if ch.is_ascii_alphanumeric() || "-_. ".contains(ch) || (charset == Charset::Unicode && ch.is_alphanumeric()) {
+            out.push(ch);
+        } else if let Some(ascii) = transliterate(ch).filter(|_| charset == Charset::Ascii) {
+            out.push_str(ascii);
+        } else {
+            out.push('_');
+        }
+    }
+    out
+}
+
+fn sanitize(input: &str, charset: Charset) -> String {
+    let cleaned = clean_chars(input, charset);
+    let trimmed = cleaned.trim();
+    if trimmed.is_empty() { "invoice".to_string() } else { trimmed.to_string() }
+}
+
+/// A single file name on disk: ASCII only, without path separators.
+pub(crate) fn sanitize_filename(input: &str) -> String {
+    sanitize(input, Charset::Ascii)
+}
+
+/// A single attachment name: like `sanitize_filename`, but letters of any script are kept.
+pub(crate) fn attachment_filename(input: &str) -> String {
+    sanitize(input, Charset::Unicode)
+}
+
+/// Invoice values the placeholders stand for.
+pub(crate) struct PdfFilenameFields<'a> {
+    pub number: &'a str,
+    pub client: &'a str,
+    /// `YYYY-MM-DD`; `{year}` and `{month}` are taken from it.
+    pub issue_date: &'a str,
+}
+
+impl PdfFilenameFields<'_> {
+    fn value(&self, placeholder: &str, charset: Charset) -> Option<String> {
+        let date = self.issue_date.trim();
+        let value = match placeholder {
+            "number" => self.number.trim(),
+            "client" => Some(self.client.trim()).filter(|c| !c.is_empty()).unwrap_or("client"),
+            "year" => date.get(0..4).unwrap_or(date),
+            "month" => date.get(5..7).unwrap_or(""),
+            "issueDate" => date,
+            _ => return None,
+        };
+        Some(sanitize(value, charset))
+    }
+}
+
+/// Checks a template from the settings form: known placeholders, closed braces, some text.
+pub(crate) fn validate_pdf_filename_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("The PDF file name template is empty.".to_string());
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!("Unclosed placeholder in PDF file name template \"{template}\"."));
+        };
+        let name = &rest[start + 1..start + len];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Unknown placeholder {{{name}}} in PDF file name template; use {}.",
+                PLACEHOLDERS.map(|p| format!("{{{p}}}")).join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+fn render_segment(segment: &str, fields: &PdfFilenameFields, charset: Charset) -> String {
+    let mut out = String::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        match after.find('}').and_then(|end| fields.value(&after[1..end], charset).map(|v| (end, v))) {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    // Literal text is cleaned like the values; dots and spaces at the ends are not allowed on
+    // Windows and would let `..` through.
+    clean_chars(&out, charset).trim_matches(|c| c == '.' || c == ' ').to_string()
+}
+
+/// The relative path (`/`-separated folders, then the file name ending in `.pdf`).
+pub(crate) fn render_pdf_filename(template: &str, fields: &PdfFilenameFields) -> Vec<String> {
+    render_segments(template, fields, Charset::Ascii)
+}
+
+fn render_segments(template: &str, fields: &PdfFilenameFields, charset: Charset) -> Vec<String> {
+    let template = template.trim();
+    let template = if template.to_ascii_lowercase().ends_with(".pdf") { &template[..template.len() - 4] } else { template };
+    let mut segments: Vec<String> = template
+        .split(['/', '\\'])
+        .map(|s| render_segment(s, fields, charset))
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.is_empty() {
+        segments.push(render_segment(DEFAULT_PDF_FILENAME_TEMPLATE, fields, charset));
+    }
+    if let Some(last) = segments.last_mut() {
+        last.push_str(".pdf");
+    }
+    segments
+}
+
+/// Where a file export goes under `dir`; folders from the template are created.
+pub(crate) fn pdf_export_path(dir: &Path, template: &str, fields: &PdfFilenameFields) -> Result<PathBuf, String> {
+    let mut path = dir.to_path_buf();
+    path.extend(render_pdf_filename(template, fields));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+/// What a file export does when the target file already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ExistingFile {
+    /// Fail without touching the existing file.
+    Error,
+    #[default]
+    Overwrite,
+    /// Write `name (2).pdf`, `name (3).pdf`, … next to it.
+    AutoNumber,
+}
+
+/// Appends `-{unix millis}` to the file name, for callers that want every export kept apart.
+pub(crate) fn with_unique_suffix(path: &Path) -> PathBuf {
+    let ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    with_stem_suffix(path, &format!("-{ms}"))
+}
+
+fn with_stem_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}{suffix}.{}", ext.to_string_lossy()),
+        None => format!("{stem}{suffix}"),
+    };
+    path.with_file_name(name)
+}
+
+/// The path to write to, given what should happen when `path` already exists.
+pub(crate) fn resolve_existing(path: PathBuf, mode: ExistingFile) -> Result<PathBuf, String> {
+    if !path.exists() {
+        return Ok(path);
+    }
+    match mode {
+        ExistingFile::Overwrite => Ok(path),
+        ExistingFile::Error => Err(format!("File already exists: {}", path.display())),
+        ExistingFile::AutoNumber => (2..10_000)
+            .map(|n| with_stem_suffix(&path, &format!(" ({n})")))
+            .find(|candidate| !candidate.exists())
+            .ok_or_else(|| format!("Too many files named like {}", path.display())),
+    }
+}
+
+/// Name of the attachment in an invoice email: the file name without the template's folders.
+pub(crate) fn pdf_attachment_name(template: &str, fields: &PdfFilenameFields) -> String {
+    render_segments(template, fields, Charset::Unicode).pop().unwrap_or_else(|| "invoice.pdf".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: PdfFilenameFields = PdfFilenameFields {
+        number: "INV/2025-7",
+        client: "Firma d.o.o. / Beograd",
+        issue_date: "2025-03-05",
+    };
+
+    #[test]
+    fn renders_folders_and_sanitizes_each_value() {
+        assert_eq!(render_pdf_filename(DEFAULT_PDF_FILENAME_TEMPLATE, &FIELDS), ["INV_2025-7-Firma d.o.o. _ Beograd.pdf"]);
+        assert_eq!(
+            render_pdf_filename("{year}/{client}/{number}.pdf", &FIELDS),
+            ["2025", "Firma d.o.o. _ Beograd", "INV_2025-7.pdf"]
+        );
+        assert_eq!(render_pdf_filename("{issueDate}_{month}", &FIELDS), ["2025-03-05_03.pdf"]);
+        assert_eq!(pdf_attachment_name("{year}\\{client}\\{number}", &FIELDS), "INV_2025-7.pdf");
+    }
+
+    #[test]
+    fn existing_files_are_kept_numbered_or_rejected() {
+        let dir = std::env::temp_dir().join(format!("pausaler-pdf-name-{}", uuid::Uuid::new_v4()));
+        let path = pdf_export_path(&dir, "{year}/{number}", &FIELDS).unwrap();
+        assert_eq!(path, dir.join("2025").join("INV_2025-7.pdf"));
+        assert_eq!(resolve_existing(path.clone(), ExistingFile::Error).unwrap(), path);
+
+        std::fs::write(&path, b"1").unwrap();
+        assert_eq!(resolve_existing(path.clone(), ExistingFile::Overwrite).unwrap(), path);
+        assert!(resolve_existing(path.clone(), ExistingFile::Error).unwrap_err().contains("already exists"));
+        let second = resolve_existing(path.clone(), ExistingFile::AutoNumber).unwrap();
+        assert_eq!(second, dir.join("2025").join("INV_2025-7 (2).pdf"));
+        std::fs::write(&second, b"2").unwrap();
+        let third = resolve_existing(path.clone(), ExistingFile::AutoNumber).unwrap();
+        assert_eq!(third.file_name().unwrap(), "INV_2025-7 (3).pdf");
+
+        let suffixed = with_unique_suffix(&path).file_name().unwrap().to_string_lossy().to_string();
+        assert!(suffixed.starts_with("INV_2025-7-") && suffixed.ends_with(".pdf"), "{suffixed}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn strips_traversal_and_rejects_unknown_placeholders() {
+        assert_eq!(render_pdf_filename("../../{year}/./../{number}", &FIELDS), ["2025", "INV_2025-7.pdf"]);
+        assert_eq!(render_pdf_filename("/etc/{number}", &FIELDS), ["etc", "INV_2025-7.pdf"]);
+        let dotted = PdfFilenameFields { client: "..", ..FIELDS };
+        assert_eq!(render_pdf_filename("{client}/{number}", &dotted), ["INV_2025-7.pdf"]);
+        assert_eq!(render_pdf_filename("//", &FIELDS), ["INV_2025-7-Firma d.o.o. _ Beograd.pdf"]);
+
+        assert!(validate_pdf_filename_template("{year}/{client}/{number}").is_ok());
+        assert!(validate_pdf_filename_template("{numbr}").unwrap_err().contains("{numbr}"));
+        assert!(validate_pdf_filename_template("{number").is_err());
+        assert!(validate_pdf_filename_template("  ").is_err());
+    }
+
+    #[test]
+    fn serbian_names_are_transliterated_on_disk_and_kept_in_attachments() {
+        let latin = PdfFilenameFields { number: "2025-7", client: "Đorđević doo, Čačak", ..FIELDS };
+        assert_eq!(render_pdf_filename("{number}-{client}", &latin), ["2025-7-Djordjevic doo_ Cacak.pdf"]);
+        assert_eq!(pdf_attachment_name("{number}-{client}", &latin), "2025-7-Đorđević doo_ Čačak.pdf");
+
+        let cyrillic = PdfFilenameFields { number: "2025-7", client: "Љубиша Шћекић ПР", ..FIELDS };
+        assert_eq!(render_pdf_filename("{client}/Рачун {number}", &cyrillic), ["Ljubisa Scekic PR", "Racun 2025-7.pdf"]);
+        assert_eq!(pdf_attachment_name("Рачун {number} {client}", &cyrillic), "Рачун 2025-7 Љубиша Шћекић ПР.pdf");
+
+        assert_eq!(sanitize_filename("Žuta kuća/Ђурђевак"), "Zuta kuca_Djurdjevak");
+        assert_eq!(attachment_filename("Žuta kuća/Ђурђевак"), "Žuta kuća_Ђурђевак");
+        assert_eq!(sanitize_filename("日本"), "__");
+    }
+}
@@ -0,0 +1,98 @@
+//! The company logo as it goes into an invoice PDF. printpdf embeds raw pixels, so a 1200 px photo
+//! alone made the PDF several megabytes, too big for some mail servers once attached.
+//!
+//! The logo is shrunk to what the logo box shows at print resolution, and photographic logos are
+//! JPEG-compressed. Flat-colored logos stay lossless: JPEG smears their edges and they compress
+//! well anyway. Logos with transparency stay lossless too, since a JPEG stream has no alpha.
+
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use printpdf::image_crate::{DynamicImage, GenericImageView, ImageOutputFormat};
+use printpdf::{ColorBits, ColorSpace, Image, ImageFilter, ImageXObject, Px};
+
+/// Longer side of the embedded logo: the box is at most 45 mm wide, 531 px at 300 dpi.
+const MAX_LOGO_PX: u32 = 600;
+const JPEG_QUALITY: u8 = 85;
+/// Distinct colors from which a logo counts as a photo rather than flat artwork.
+const PHOTO_MIN_COLORS: usize = 1024;
+
+/// `img` scaled down to fit `MAX_LOGO_PX`; smaller logos are returned as they are.
+pub(crate) fn shrink(img: DynamicImage) -> DynamicImage {
+    if img.width().max(img.height()) <= MAX_LOGO_PX {
+        return img;
+    }
+    img.thumbnail(MAX_LOGO_PX, MAX_LOGO_PX)
+}
+
+fn is_photographic(img: &DynamicImage) -> bool {
+    if img.color().has_alpha() && img.pixels().any(|(_, _, p)| p.0[3] < u8::MAX) {
+        return false;
+    }
+    let mut colors = HashSet::new();
+    img.to_rgb8().pixels().any(|p| {
+        colors.insert(p.0);
+        colors.len() >= PHOTO_MIN_COLORS
+    })
+}
+
+fn encode_jpeg(img: &DynamicImage) -> Option<Vec<u8>> {
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(img.to_rgb8())
+        .write_to(&mut Cursor::new(&mut jpeg), ImageOutputFormat::Jpeg(JPEG_QUALITY))
+        .inspect_err(|e| tracing::warn!(target: "pdf", error = %e, "JPEG encoding of the logo failed"))
+        .ok()?;
+    Some(jpeg)
+}
+
+/// The logo as a PDF image: a DCT (JPEG) stream for photos, raw pixels otherwise.
+pub(crate) fn embed(img: &DynamicImage) -> Image {
+    let jpeg = if is_photographic(img) { encode_jpeg(img) } else { None };
+    let Some(jpeg) = jpeg else {
+        return Image::from_dynamic_image(img);
+    };
+    // The encoder writes JFIF YCbCr, which viewers convert back on their own; printpdf's
+    // `ColorTransform 0` goes under a misspelled `DecodeParams` key and is ignored.
+    Image::from(ImageXObject {
+        width: Px(img.width() as usize),
+        height: Px(img.height() as usize),
+        color_space: ColorSpace::Rgb,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: jpeg,
+        image_filter: Some(ImageFilter::DCT),
+        smask: None,
+        clipping_bbox: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::image_crate::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    #[test]
+    fn photos_become_small_jpegs_and_flat_logos_stay_lossless() {
+        let photo = DynamicImage::ImageRgb8(RgbImage::from_fn(1200, 800, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, ((x * y) % 256) as u8])
+        }));
+        let photo = shrink(photo);
+        assert_eq!((photo.width(), photo.height()), (600, 400));
+        let embedded = embed(&photo).image;
+        assert!(matches!(embedded.image_filter, Some(ImageFilter::DCT)));
+        assert!(embedded.image_data.starts_with(&[0xFF, 0xD8]));
+        assert!(embedded.image_data.len() < 600 * 400 * 3 / 4, "{}", embedded.image_data.len());
+
+        let flat = DynamicImage::ImageRgb8(RgbImage::from_fn(300, 100, |x, _| {
+            if x < 150 { Rgb([200, 30, 30]) } else { Rgb([255, 255, 255]) }
+        }));
+        assert_eq!(shrink(flat.clone()).width(), 300);
+        assert!(embed(&flat).image.image_filter.is_none());
+
+        // Transparency needs the soft mask a JPEG can't carry.
+        let translucent = DynamicImage::ImageRgba8(RgbaImage::from_fn(100, 100, |x, y| {
+            Rgba([(x * 2) as u8, (y * 2) as u8, ((x * y) % 256) as u8, if x < 10 { 0 } else { 255 }])
+        }));
+        assert!(embed(&translucent).image.image_filter.is_none());
+    }
+}
@@ -0,0 +1,304 @@
+//! Low-level printpdf helpers shared by the invoice, KPO and monthly report renderers.
+//!
+//! Coordinates are millimeters from the bottom-left corner of the page, font sizes are points.
+//! Text is measured with the embedded DejaVu Sans metrics, since printpdf exposes none.
+
+use std::io::Cursor;
+
+/// DejaVu Sans, embedded in every generated PDF so Cyrillic and Serbian Latin render.
+static DEJAVU_SANS: &[u8] = include_bytes!("../assets/DejaVuSans.ttf");
+
+/// Embeds DejaVu Sans into `doc` and parses it again for text measurement.
+pub(crate) fn embed_font(
+    doc: &printpdf::PdfDocumentReference,
+) -> Result<(printpdf::IndirectFontRef, ttf_parser::Face<'static>), String> {
+    let font = doc
+        .add_external_font(Cursor::new(DEJAVU_SANS))
+        .map_err(|e| e.to_string())?;
+    let face = ttf_parser::Face::parse(DEJAVU_SANS, 0)
+        .map_err(|_| "Failed to parse embedded font for measurement".to_string())?;
+    Ok((font, face))
+}
+
+pub(crate) fn save_pdf(doc: printpdf::PdfDocumentReference) -> Result<Vec<u8>, String> {
+    let mut writer = std::io::BufWriter::new(Vec::<u8>::new());
+    doc.save(&mut writer).map_err(|e| e.to_string())?;
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+pub(crate) fn push_line(
+    layer: &printpdf::PdfLayerReference,
+    font: &printpdf::IndirectFontRef,
+    text: &str,
+    font_size: f32,
+    x: f32,
+    y: f32,
+) {
+    use printpdf::Mm;
+    layer.use_text(text, font_size, Mm(x), Mm(y), font);
+}
+
+#[allow(dead_code)]
+pub(crate) fn draw_rule(layer: &printpdf::PdfLayerReference, x1: f32, x2: f32, y: f32) {
+    use printpdf::Mm;
+    layer.add_line(printpdf::Line {
+        points: vec![
+            (printpdf::Point::new(Mm(x1), Mm(y)), false),
+            (printpdf::Point::new(Mm(x2), Mm(y)), false),
+        ],
+        is_closed: false,
+    });
+}
+
+pub(crate) fn draw_rule_with_thickness(
+    layer: &printpdf::PdfLayerReference,
+    x1: f32,
+    x2: f32,
+    y: f32,
+    thickness: f32,
+) {
+    use printpdf::Mm;
+    layer.set_outline_thickness(thickness);
+    layer.add_line(printpdf::Line {
+        points: vec![
+            (printpdf::Point::new(Mm(x1), Mm(y)), false),
+            (printpdf::Point::new(Mm(x2), Mm(y)), false),
+        ],
+        is_closed: false,
+    });
+}
+
+#[allow(dead_code)]
+pub(crate) fn push_line_right(
+    layer: &printpdf::PdfLayerReference,
+    font: &printpdf::IndirectFontRef,
+    text: &str,
+    font_size: f32,
+    x_right: f32,
+    y: f32,
+) {
+    // printpdf doesn't expose reliable text metrics; use a pragmatic estimate.
+    // This is good enough for numeric columns and matches the reference visually.
+    let width_est = (text.chars().count() as f32) * font_size * 0.42;
+    let x = (x_right - width_est).max(0.0);
+    push_line(layer, font, text, font_size, x, y);
+}
+
+pub(crate) fn text_width_mm_ttf(face: &ttf_parser::Face<'_>, text: &str, font_size_pt: f32) -> f32 {
+    // PDF font sizes are in points; our coordinates are in millimeters.
+    const PT_TO_MM: f32 = 25.4 / 72.0;
+    let units_per_em = face.units_per_em() as f32;
+    if units_per_em <= 0.0 {
+        return 0.0;
+    }
+
+    let mut width_units: i32 = 0;
+
+    for ch in text.chars() {
+        let Some(gid) = face.glyph_index(ch) else {
+            continue;
+        };
+
+        width_units += face.glyph_hor_advance(gid).unwrap_or(0) as i32;
+    }
+
+    let width_pt = (width_units as f32 / units_per_em) * font_size_pt;
+    width_pt * PT_TO_MM
+}
+
+pub(crate) fn font_ascent_mm(face: &ttf_parser::Face<'_>, font_size_pt: f32) -> f32 {
+    const PT_TO_MM: f32 = 25.4 / 72.0;
+    let units_per_em = face.units_per_em() as f32;
+    if units_per_em <= 0.0 {
+        return font_size_pt * PT_TO_MM * 0.80;
+    }
+
+    let asc_units = face.ascender() as f32;
+    (asc_units / units_per_em) * font_size_pt * PT_TO_MM
+}
+
+pub(crate) fn font_descent_mm(face: &ttf_parser::Face<'_>, font_size_pt: f32) -> f32 {
+    const PT_TO_MM: f32 = 25.4 / 72.0;
+    let units_per_em = face.units_per_em() as f32;
+    if units_per_em <= 0.0 {
+        return font_size_pt * PT_TO_MM * 0.20;
+    }
+
+    // descender is typically negative; convert to a positive magnitude in mm.
+    let desc_units = face.descender() as f32;
+    ((-desc_units).max(0.0) / units_per_em) * font_size_pt * PT_TO_MM
+}
+
+pub(crate) fn push_line_right_measured(
+    layer: &printpdf::PdfLayerReference,
+    font: &printpdf::IndirectFontRef,
+    ttf_face: &ttf_parser::Face<'_>,
+    text: &str,
+    font_size: f32,
+    x_right: f32,
+    y: f32,
+) {
+    let width_mm = text_width_mm_ttf(ttf_face, text, font_size);
+    let x = (x_right - width_mm).max(0.0);
+    push_line(layer, font, text, font_size, x, y);
+}
+
+/// Wraps each non-empty input line separately (explicit line breaks are kept).
+pub(crate) fn split_and_wrap_lines_by_width_mm(
+    ttf_face: &ttf_parser::Face<'_>,
+    input: &str,
+    font_size: f32,
+    max_width_mm: f32,
+) -> Vec<String> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .flat_map(|s| wrap_text_by_width_mm(ttf_face, s, font_size, max_width_mm))
+        .collect()
+}
+
+#[allow(dead_code)]
+pub(crate) fn fill_rect_gray(
+    layer: &printpdf::PdfLayerReference,
+    x: f32,
+    y_top: f32,
+    w: f32,
+    h: f32,
+    gray: f32,
+) {
+    use printpdf::{path::PaintMode, Color, Mm, Rect, Rgb};
+
+    layer.set_fill_color(Color::Rgb(Rgb::new(gray, gray, gray, None)));
+    // printpdf uses bottom-left origin; our y coordinates are already in that space.
+    let rect = Rect::new(Mm(x), Mm(y_top - h), Mm(x + w), Mm(y_top)).with_mode(PaintMode::Fill);
+    layer.add_rect(rect);
+    // reset fill to black
+    layer.set_fill_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+}
+
+/// Breaks a single word that is wider than `max_width_mm` into glyph chunks.
+/// The last (possibly short) chunk is returned separately so following words can join it.
+fn break_word_by_width_mm(
+    ttf_face: &ttf_parser::Face<'_>,
+    word: &str,
+    font_size: f32,
+    max_width_mm: f32,
+    out: &mut Vec<String>,
+) -> String {
+    let mut chunk = String::new();
+    for ch in word.chars() {
+        chunk.push(ch);
+        if text_width_mm_ttf(ttf_face, &chunk, font_size) > max_width_mm && chunk.chars().count() > 1 {
+            chunk.pop();
+            out.push(std::mem::take(&mut chunk));
+            chunk.push(ch);
+        }
+    }
+    chunk
+}
+
+/// Word-wraps text to a measured width (embedded font metrics), so Cyrillic, wide glyphs
+/// and long unbroken tokens (URLs, IBANs) stay inside the column.
+pub(crate) fn wrap_text_by_width_mm(
+    ttf_face: &ttf_parser::Face<'_>,
+    input: &str,
+    font_size: f32,
+    max_width_mm: f32,
+) -> Vec<String> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in s.split_whitespace() {
+        if !current.is_empty() {
+            let candidate = format!("{} {}", current, word);
+            if text_width_mm_ttf(ttf_face, &candidate, font_size) <= max_width_mm {
+                current = candidate;
+                continue;
+            }
+            out.push(std::mem::take(&mut current));
+        }
+
+        current = if text_width_mm_ttf(ttf_face, word, font_size) <= max_width_mm {
+            word.to_string()
+        } else {
+            break_word_by_width_mm(ttf_face, word, font_size, max_width_mm, &mut out)
+        };
+    }
+
+    if !current.is_empty() {
+        out.push(current);
+    }
+
+    out
+}
+
+pub(crate) fn draw_value_only_wrapped(
+    layer: &printpdf::PdfLayerReference,
+    font: &printpdf::IndirectFontRef,
+    ttf_face: &ttf_parser::Face<'_>,
+    value: &str,
+    font_size: f32,
+    x_value: f32,
+    y: f32,
+    max_width_value: f32,
+    line_height: f32,
+    row_gap: f32,
+) -> f32 {
+    let value_lines = wrap_text_by_width_mm(ttf_face, value, font_size, max_width_value);
+    if value_lines.is_empty() {
+        return y;
+    }
+
+    for (idx, line) in value_lines.iter().enumerate() {
+        let yy = y - (idx as f32) * line_height;
+        push_line(layer, font, line, font_size, x_value, yy);
+    }
+
+    y - (value_lines.len() as f32) * line_height - row_gap
+}
+
+pub(crate) fn draw_inline_labeled_row(
+    layer: &printpdf::PdfLayerReference,
+    font: &printpdf::IndirectFontRef,
+    ttf_face: &ttf_parser::Face<'_>,
+    label: &str,
+    value: &str,
+    font_size: f32,
+    x: f32,
+    y: f32,
+    max_width_total: f32,
+    line_height: f32,
+    row_gap: f32,
+) -> f32 {
+    let v = value.trim();
+    if v.is_empty() {
+        return y;
+    }
+
+    // Exactly ONE space after the colon.
+    let prefix = format!("{}: ", label);
+    let prefix_w = text_width_mm_ttf(ttf_face, &prefix, font_size);
+    let value_x = x + prefix_w;
+    let value_w = (max_width_total - prefix_w).max(6.0);
+
+    let value_lines = wrap_text_by_width_mm(ttf_face, v, font_size, value_w);
+    if value_lines.is_empty() {
+        return y;
+    }
+
+    push_line(layer, font, &prefix, font_size, x, y);
+    push_line(layer, font, &value_lines[0], font_size, value_x, y);
+
+    for (idx, line) in value_lines.iter().enumerate().skip(1) {
+        let yy = y - (idx as f32) * line_height;
+        push_line(layer, font, line, font_size, value_x, yy);
+    }
+
+    y - (value_lines.len() as f32) * line_height - row_gap
+}
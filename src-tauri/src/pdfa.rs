@@ -0,0 +1,485 @@
+//! PDF/A-1b post-processing for archival invoice exports.
+//!
+//! printpdf 0.7 has no real PDF/A support (its output intent is hardcoded to PDF/X + FOGRA39),
+//! so the normal PDF is rewritten here: sRGB output intent, XMP metadata with the PDF/A
+//! identification schema, matching Info dictionary, and removal of constructs PDF/A-1 forbids.
+//!
+//! The embedded font is already complete (printpdf is built without `font_subsetting`).
+
+use std::io::Write;
+
+use printpdf::image_crate::{DynamicImage, Rgb, RgbImage};
+use printpdf::lopdf::content::{Content, Operation};
+use printpdf::lopdf::xref::XrefType;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use time::OffsetDateTime;
+
+const PRODUCER: &str = "Paushaler";
+const OUTPUT_CONDITION: &str = "sRGB IEC61966-2.1";
+/// PDF/A requires a comment with at least four bytes > 127 right after the header line.
+const BINARY_COMMENT: &[u8] = b"%\xE2\xE3\xCF\xD3\n";
+
+/// Blends any alpha channel onto white. PDF/A-1 forbids transparency (image soft masks).
+pub(crate) fn flatten_alpha(img: DynamicImage) -> DynamicImage {
+    if !img.color().has_alpha() {
+        return img;
+    }
+    let rgba = img.to_rgba8();
+    let mut out = RgbImage::new(rgba.width(), rgba.height());
+    for (x, y, px) in rgba.enumerate_pixels() {
+        let a = px[3] as u32;
+        let blend = |c: u8| ((c as u32 * a + 255 * (255 - a) + 127) / 255) as u8;
+        out.put_pixel(x, y, Rgb([blend(px[0]), blend(px[1]), blend(px[2])]));
+    }
+    DynamicImage::ImageRgb8(out)
+}
+
+/// Rewrites a printpdf document into PDF/A-1b.
+pub(crate) fn convert_to_pdfa1b(pdf: &[u8], title: &str) -> Result<Vec<u8>, String> {
+    let mut doc = Document::load_mem(pdf).map_err(|e| format!("PDF/A: failed to parse PDF: {e}"))?;
+    let now = OffsetDateTime::now_utc();
+
+    // Fonts: CIDFontType2 descendants must carry an explicit CIDToGIDMap.
+    // Images: no soft masks, no interpolation.
+    for obj in doc.objects.values_mut() {
+        match obj {
+            Object::Dictionary(dict) => fix_type0_font(dict),
+            Object::Stream(stream) if is_name(stream.dict.get(b"Subtype").ok(), b"Image") => {
+                stream.dict.remove(b"SMask");
+                stream.dict.set("Interpolate", Object::Boolean(false));
+            }
+            _ => {}
+        }
+    }
+
+    let icc_id = {
+        let mut dict = Dictionary::new();
+        dict.set("N", Object::Integer(3));
+        dict.set("Alternate", Object::Name(b"DeviceRGB".to_vec()));
+        doc.add_object(Stream::new(dict, srgb_icc_profile()))
+    };
+
+    let metadata_id = {
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::Name(b"Metadata".to_vec()));
+        dict.set("Subtype", Object::Name(b"XML".to_vec()));
+        // XMP must stay readable without filters.
+        let stream = Stream::new(dict, xmp_packet(title, &now).into_bytes()).with_compression(false);
+        doc.add_object(stream)
+    };
+
+    let info_id = doc.add_object(info_dictionary(title, &now));
+    doc.trailer.set("Info", Object::Reference(info_id));
+
+    // Optional content (layers) is not allowed in PDF/A-1.
+    strip_optional_content(&mut doc)?;
+
+    let catalog_id = catalog_id(&doc)?;
+    let catalog = doc
+        .get_object_mut(catalog_id)
+        .and_then(Object::as_dict_mut)
+        .map_err(|e| format!("PDF/A: invalid catalog: {e}"))?;
+    catalog.remove(b"OCProperties");
+    catalog.set("Metadata", Object::Reference(metadata_id));
+    catalog.set("OutputIntents", Object::Array(vec![Object::Dictionary(output_intent(icc_id))]));
+
+    doc.version = "1.4".to_string();
+    save_with_binary_comment(&mut doc)
+}
+
+/// Removes the `/OC … BDC … EMC` sections printpdf wraps every layer in, the page resources
+/// naming the layers, and the layer (OCG) objects themselves.
+fn strip_optional_content(doc: &mut Document) -> Result<(), String> {
+    for page_id in doc.get_pages().into_values() {
+        let content = doc
+            .get_page_content(page_id)
+            .and_then(|bytes| Content::decode(&bytes))
+            .map_err(|e| format!("PDF/A: failed to read page content: {e}"))?;
+        let operations = without_optional_content(&content.operations);
+        if operations.len() != content.operations.len() {
+            let bytes = Content { operations }
+                .encode()
+                .map_err(|e| format!("PDF/A: failed to write page content: {e}"))?;
+            doc.change_page_content(page_id, bytes)
+                .map_err(|e| format!("PDF/A: failed to write page content: {e}"))?;
+        }
+
+        let (_, resource_ids) = doc.get_page_resources(page_id);
+        for id in resource_ids {
+            if let Ok(resources) = doc.get_object_mut(id).and_then(Object::as_dict_mut) {
+                resources.remove(b"Properties");
+            }
+        }
+        if let Ok(Object::Dictionary(resources)) =
+            doc.get_object_mut(page_id).and_then(Object::as_dict_mut).and_then(|page| page.get_mut(b"Resources"))
+        {
+            resources.remove(b"Properties");
+        }
+    }
+    doc.objects
+        .retain(|_, obj| !matches!(obj, Object::Dictionary(dict) if is_name(dict.get(b"Type").ok(), b"OCG")));
+    Ok(())
+}
+
+/// Drops every `/OC` marked-content section's `BDC` and its matching `EMC`, keeping the
+/// operators in between and any other marked content.
+fn without_optional_content(operations: &[Operation]) -> Vec<Operation> {
+    let mut open: Vec<bool> = Vec::new();
+    let mut kept = Vec::with_capacity(operations.len());
+    for op in operations {
+        match op.operator.as_str() {
+            "BDC" => {
+                let optional = is_name(op.operands.first(), b"OC");
+                open.push(optional);
+                if optional {
+                    continue;
+                }
+            }
+            "BMC" => open.push(false),
+            "EMC" => {
+                if open.pop() == Some(true) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        kept.push(op.clone());
+    }
+    kept
+}
+
+/// Saves `doc` with [`BINARY_COMMENT`] on the line after the header. lopdf 0.31 always writes
+/// the header line straight into its own offset count, so the document is written with a
+/// cross-reference table and the comment goes in between: every offset in the table, and the
+/// `startxref` pointer, moves past it by the comment's length.
+fn save_with_binary_comment(doc: &mut Document) -> Result<Vec<u8>, String> {
+    let invalid = |what: &str| format!("PDF/A: failed to write PDF: {what}");
+
+    doc.reference_table.cross_reference_type = XrefType::CrossReferenceTable;
+    let mut plain = Vec::new();
+    doc.save_to(&mut plain).map_err(|e| invalid(&e.to_string()))?;
+
+    let header_end = plain.iter().position(|&b| b == b'\n').ok_or_else(|| invalid("no header line"))? + 1;
+    let startxref = rfind(&plain, b"startxref\n").ok_or_else(|| invalid("no startxref"))?;
+    let xref_start: usize = std::str::from_utf8(&plain[startxref + b"startxref\n".len()..])
+        .ok()
+        .and_then(|rest| rest.lines().next())
+        .and_then(|offset| offset.trim().parse().ok())
+        .ok_or_else(|| invalid("bad startxref"))?;
+    let trailer = xref_start
+        + rfind(&plain[xref_start..startxref], b"trailer").ok_or_else(|| invalid("no trailer"))?;
+    let shift = BINARY_COMMENT.len();
+
+    let mut out = Vec::with_capacity(plain.len() + shift);
+    out.extend_from_slice(&plain[..header_end]);
+    out.extend_from_slice(BINARY_COMMENT);
+    out.extend_from_slice(&plain[header_end..xref_start]);
+    // In-use entries are `oooooooooo ggggg n \n`, 20 bytes with a 10-digit offset.
+    for line in plain[xref_start..trailer].split_inclusive(|&b| b == b'\n') {
+        let offset = std::str::from_utf8(&line[..line.len().min(10)]).ok().and_then(|o| o.parse::<usize>().ok());
+        match offset {
+            Some(offset) if line.len() == 20 && line[17] == b'n' => {
+                write!(out, "{:010}", offset + shift).map_err(|e| invalid(&e.to_string()))?;
+                out.extend_from_slice(&line[10..]);
+            }
+            _ => out.extend_from_slice(line),
+        }
+    }
+    out.extend_from_slice(&plain[trailer..startxref]);
+    write!(out, "startxref\n{}\n%%EOF", xref_start + shift).map_err(|e| invalid(&e.to_string()))?;
+    Ok(out)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+fn catalog_id(doc: &Document) -> Result<ObjectId, String> {
+    doc.trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .map_err(|e| format!("PDF/A: missing catalog: {e}"))
+}
+
+fn is_name(obj: Option<&Object>, name: &[u8]) -> bool {
+    matches!(obj, Some(Object::Name(n)) if n == name)
+}
+
+fn fix_type0_font(dict: &mut Dictionary) {
+    if !is_name(dict.get(b"Subtype").ok(), b"Type0") {
+        return;
+    }
+    if let Ok(Object::Array(descendants)) = dict.get_mut(b"DescendantFonts") {
+        for d in descendants.iter_mut() {
+            if let Object::Dictionary(cid_font) = d {
+                if is_name(cid_font.get(b"Subtype").ok(), b"CIDFontType2") {
+                    cid_font.set("CIDToGIDMap", Object::Name(b"Identity".to_vec()));
+                }
+            }
+        }
+    }
+}
+
+fn output_intent(icc_id: ObjectId) -> Dictionary {
+    let mut dict = Dictionary::new();
+    dict.set("Type", Object::Name(b"OutputIntent".to_vec()));
+    dict.set("S", Object::Name(b"GTS_PDFA1".to_vec()));
+    dict.set("OutputConditionIdentifier", pdf_text(OUTPUT_CONDITION));
+    dict.set("Info", pdf_text(OUTPUT_CONDITION));
+    dict.set("RegistryName", pdf_text("http://www.color.org"));
+    dict.set("DestOutputProfile", Object::Reference(icc_id));
+    dict
+}
+
+fn info_dictionary(title: &str, now: &OffsetDateTime) -> Dictionary {
+    let date = pdf_date(now);
+    let mut dict = Dictionary::new();
+    dict.set("Title", pdf_text(title));
+    dict.set("Producer", pdf_text(PRODUCER));
+    dict.set("Creator", pdf_text(PRODUCER));
+    dict.set("CreationDate", Object::String(date.clone().into_bytes(), StringFormat::Literal));
+    dict.set("ModDate", Object::String(date.into_bytes(), StringFormat::Literal));
+    dict
+}
+
+/// Text string: plain bytes for ASCII, UTF-16BE with BOM otherwise.
+fn pdf_text(s: &str) -> Object {
+    if s.is_ascii() {
+        return Object::String(s.as_bytes().to_vec(), StringFormat::Literal);
+    }
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in s.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+fn pdf_date(t: &OffsetDateTime) -> String {
+    format!(
+        "D:{:04}{:02}{:02}{:02}{:02}{:02}+00'00'",
+        t.year(),
+        t.month() as u8,
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second()
+    )
+}
+
+fn xmp_date(t: &OffsetDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        t.year(),
+        t.month() as u8,
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second()
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xmp_packet(title: &str, now: &OffsetDateTime) -> String {
+    let date = xmp_date(now);
+    let title = xml_escape(title);
+    format!(
+        r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+   <dc:format>application/pdf</dc:format>
+   <dc:title><rdf:Alt><rdf:li xml:lang="x-default">{title}</rdf:li></rdf:Alt></dc:title>
+  </rdf:Description>
+  <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+   <xmp:CreatorTool>{producer}</xmp:CreatorTool>
+   <xmp:CreateDate>{date}</xmp:CreateDate>
+   <xmp:ModifyDate>{date}</xmp:ModifyDate>
+   <xmp:MetadataDate>{date}</xmp:MetadataDate>
+  </rdf:Description>
+  <rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+   <pdf:Producer>{producer}</pdf:Producer>
+  </rdf:Description>
+  <rdf:Description rdf:about="" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+   <pdfaid:part>1</pdfaid:part>
+   <pdfaid:conformance>B</pdfaid:conformance>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+        bom = '\u{feff}',
+        producer = PRODUCER,
+    )
+}
+
+/// Builds a minimal ICC v2 display profile for sRGB (D50-adapted primaries, sampled sRGB TRC).
+fn srgb_icc_profile() -> Vec<u8> {
+    fn s15f16(v: f64) -> [u8; 4] {
+        ((v * 65536.0).round() as i32).to_be_bytes()
+    }
+    fn xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+        let mut t = b"XYZ \0\0\0\0".to_vec();
+        t.extend_from_slice(&s15f16(x));
+        t.extend_from_slice(&s15f16(y));
+        t.extend_from_slice(&s15f16(z));
+        t
+    }
+    fn desc_tag(text: &str) -> Vec<u8> {
+        let mut t = b"desc\0\0\0\0".to_vec();
+        t.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+        t.extend_from_slice(text.as_bytes());
+        t.push(0);
+        // Unicode (lang code + count) and ScriptCode (code + count + 67 bytes) parts, unused.
+        t.extend_from_slice(&[0u8; 8]);
+        t.extend_from_slice(&[0u8; 3]);
+        t.extend_from_slice(&[0u8; 67]);
+        t
+    }
+    fn text_tag(text: &str) -> Vec<u8> {
+        let mut t = b"text\0\0\0\0".to_vec();
+        t.extend_from_slice(text.as_bytes());
+        t.push(0);
+        t
+    }
+    fn trc_tag() -> Vec<u8> {
+        const N: usize = 256;
+        let mut t = b"curv\0\0\0\0".to_vec();
+        t.extend_from_slice(&(N as u32).to_be_bytes());
+        for i in 0..N {
+            let v = i as f64 / (N - 1) as f64;
+            let lin = if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) };
+            t.extend_from_slice(&((lin * 65535.0).round() as u16).to_be_bytes());
+        }
+        t
+    }
+
+    let trc = trc_tag();
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", desc_tag(OUTPUT_CONDITION)),
+        (b"cprt", text_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(0.9642, 1.0, 0.8249)),
+        (b"rXYZ", xyz_tag(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz_tag(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz_tag(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", trc.clone()),
+        (b"bTRC", trc),
+    ];
+
+    let table_len = 4 + 12 * tags.len();
+    let mut data = Vec::new();
+    let mut table = Vec::with_capacity(table_len);
+    table.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+    for (sig, body) in &tags {
+        let offset = 128 + table_len + data.len();
+        table.extend_from_slice(*sig);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        data.extend_from_slice(body);
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut header = Vec::with_capacity(128);
+    header.extend_from_slice(&(size as u32).to_be_bytes());
+    header.extend_from_slice(&[0u8; 4]); // CMM
+    header.extend_from_slice(&[0x02, 0x10, 0x00, 0x00]); // version 2.1
+    header.extend_from_slice(b"mntr");
+    header.extend_from_slice(b"RGB ");
+    header.extend_from_slice(b"XYZ ");
+    for v in [2024u16, 1, 1, 0, 0, 0] {
+        header.extend_from_slice(&v.to_be_bytes());
+    }
+    header.extend_from_slice(b"acsp");
+    header.extend_from_slice(&[0u8; 4]); // platform
+    header.extend_from_slice(&[0u8; 4]); // flags
+    header.extend_from_slice(&[0u8; 4]); // manufacturer
+    header.extend_from_slice(&[0u8; 4]); // model
+    header.extend_from_slice(&[0u8; 8]); // attributes
+    header.extend_from_slice(&[0u8; 4]); // rendering intent: perceptual
+    header.extend_from_slice(&s15f16(0.9642));
+    header.extend_from_slice(&s15f16(1.0));
+    header.extend_from_slice(&s15f16(0.8249));
+    header.extend_from_slice(&[0u8; 4]); // creator
+    header.resize(128, 0);
+
+    let mut out = header;
+    out.extend_from_slice(&table);
+    out.extend_from_slice(&data);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_profile_header_is_consistent() {
+        let icc = srgb_icc_profile();
+        assert_eq!(u32::from_be_bytes(icc[0..4].try_into().unwrap()) as usize, icc.len());
+        assert_eq!(&icc[12..16], b"mntr");
+        assert_eq!(&icc[16..20], b"RGB ");
+        assert_eq!(&icc[36..40], b"acsp");
+        // PDF/A-1 requires an ICC profile version below 4.
+        assert!(icc[8] < 4);
+    }
+
+    #[test]
+    fn conversion_drops_layers_and_keeps_offsets_after_the_binary_comment() {
+        use printpdf::{Mm, PdfDocument};
+
+        let (doc, page, layer) = PdfDocument::new("Faktura", Mm(210.0), Mm(297.0), "Layer 1");
+        let (font, _) = crate::pdf_util::embed_font(&doc).unwrap();
+        doc.get_page(page).get_layer(layer).use_text("Račun", 12.0, Mm(20.0), Mm(270.0), &font);
+        let pdf = convert_to_pdfa1b(&crate::pdf_util::save_pdf(doc).unwrap(), "Faktura 1").unwrap();
+
+        assert!(pdf.starts_with(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n"));
+        let startxref = rfind(&pdf, b"startxref\n").unwrap();
+        let xref_start: usize =
+            std::str::from_utf8(&pdf[startxref + 10..]).unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(pdf[xref_start..].starts_with(b"xref\n"));
+        let table = std::str::from_utf8(&pdf[xref_start..]).unwrap();
+        let mut id = 0;
+        for line in table.lines().skip(1).take_while(|l| !l.starts_with("trailer")) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() == 2 {
+                id = fields[0].parse().unwrap();
+                continue;
+            }
+            if fields[2] == "n" {
+                let offset: usize = fields[0].parse().unwrap();
+                assert!(pdf[offset..].starts_with(format!("{id} 0 obj").as_bytes()), "object {id}");
+            }
+            id += 1;
+        }
+
+        let doc = Document::load_mem(&pdf).unwrap();
+        let catalog = doc.get_dictionary(catalog_id(&doc).unwrap()).unwrap();
+        assert!(!catalog.has(b"OCProperties"));
+        let is_ocg = |o: &Object| matches!(o, Object::Dictionary(d) if is_name(d.get(b"Type").ok(), b"OCG"));
+        assert!(!doc.objects.values().any(is_ocg));
+        let page_id = doc.get_pages()[&1];
+        let (_, resource_ids) = doc.get_page_resources(page_id);
+        assert!(resource_ids.iter().all(|&id| !doc.get_dictionary(id).unwrap().has(b"Properties")));
+        let ops = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap().operations;
+        let operators: Vec<&str> = ops.iter().map(|op| op.operator.as_str()).collect();
+        assert!(!operators.contains(&"BDC") && !operators.contains(&"EMC"), "{operators:?}");
+        assert!(operators.contains(&"Tj") || operators.contains(&"TJ"), "{operators:?}");
+    }
+
+    #[test]
+    fn flatten_alpha_blends_onto_white() {
+        let mut img = printpdf::image_crate::RgbaImage::new(1, 1);
+        img.put_pixel(0, 0, printpdf::image_crate::Rgba([0, 0, 0, 0]));
+        let flat = flatten_alpha(DynamicImage::ImageRgba8(img));
+        assert!(!flat.color().has_alpha());
+        assert_eq!(flat.to_rgb8().get_pixel(0, 0).0, [255, 255, 255]);
+    }
+}
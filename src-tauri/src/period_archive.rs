@@ -0,0 +1,330 @@
+//! One ZIP for the accountant: every invoice PDF issued in a period, the invoice and expense
+//! CSVs, the KPO book of each year the period touches and a `manifest.json` with totals.
+//! Documents that fail to render are listed in `errors.txt` instead of aborting the export.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Seek, Write};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::Emitter;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::errors::AppError;
+use crate::features::{self, Feature};
+use crate::kpo::{kpo_book_in_conn, render_kpo_csv, KpoBook};
+use crate::pdf_filename::{render_pdf_filename, PdfFilenameFields};
+use crate::{
+    build_invoice_pdf_payload_from_db, expenses_for_export, generate_pdf_bytes, invoices_for_export,
+    normalize_date_range, now_iso, read_client_from_conn, read_settings_from_conn, render_expenses_csv,
+    render_invoices_csv, Client, DbState, Expense, Invoice, Settings,
+};
+
+pub(crate) const PERIOD_ARCHIVE_FORMAT: &str = "pausaler-period";
+const PROGRESS_EVENT: &str = "period_archive_progress";
+
+/// Everything the archive is built from, read in one go so rendering runs without the DB lock.
+pub(crate) struct PeriodData {
+    pub from: String,
+    pub to: String,
+    pub settings: Settings,
+    pub invoices: Vec<(Invoice, Option<Client>)>,
+    pub expenses: Vec<Expense>,
+    /// KPO book per calendar year in the period; a year that can't be booked keeps its error.
+    pub kpo: Vec<(i32, Result<KpoBook, String>)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodArchiveProgress {
+    pub done: usize,
+    pub total: usize,
+    /// Archive entry just written.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyTotals {
+    pub count: usize,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodManifest {
+    pub format: String,
+    pub from: String,
+    pub to: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub default_currency: String,
+    /// Invoice totals per currency.
+    pub invoices: BTreeMap<String, CurrencyTotals>,
+    /// Expense totals per currency.
+    pub expenses: BTreeMap<String, CurrencyTotals>,
+    pub files: Vec<String>,
+    pub error_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodArchiveResult {
+    pub output_path: String,
+    pub from: String,
+    pub to: String,
+    pub invoice_count: usize,
+    pub pdf_count: usize,
+    pub expense_count: usize,
+    /// One line per document left out; also written to `errors.txt`.
+    pub errors: Vec<String>,
+}
+
+pub(crate) fn load_period(conn: &Connection, from: &str, to: &str) -> Result<PeriodData, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let (_, invoices) = invoices_for_export(conn, from, to)?;
+    let invoices = invoices
+        .into_iter()
+        .map(|inv| {
+            let client = read_client_from_conn(conn, &inv.client_id)?;
+            Ok((inv, client))
+        })
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+    let (_, expenses) = expenses_for_export(conn, from, to)?;
+
+    let year = |d: &str| d.get(..4).and_then(|y| y.parse::<i32>().ok()).unwrap_or_default();
+    let kpo = (year(from)..=year(to))
+        .map(|y| (y, kpo_book_in_conn(conn, y).map_err(|e| e.to_string())))
+        .collect();
+
+    Ok(PeriodData {
+        from: from.to_string(),
+        to: to.to_string(),
+        settings,
+        invoices,
+        expenses,
+        kpo,
+    })
+}
+
+pub(crate) fn totals_by_currency<'a>(amounts: impl Iterator<Item = (&'a str, f64)>) -> BTreeMap<String, CurrencyTotals> {
+    let mut out: BTreeMap<String, CurrencyTotals> = BTreeMap::new();
+    for (currency, amount) in amounts {
+        let t = out.entry(currency.trim().to_string()).or_default();
+        t.count += 1;
+        t.amount += amount;
+    }
+    out
+}
+
+fn zip_err(e: impl std::fmt::Display) -> String {
+    format!("Failed to write archive: {e}")
+}
+
+/// Writes the archive into `out`, calling `progress(done, total, name)` after each entry.
+pub(crate) fn write_period_archive<W: Write + Seek>(
+    out: W,
+    data: &PeriodData,
+    created_at: &str,
+    mut progress: impl FnMut(usize, usize, &str),
+) -> Result<(Vec<String>, usize), String> {
+    let mut zip = ZipWriter::new(out);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let csv_opts = data.settings.csv_options;
+    let currency = data.settings.default_currency.clone();
+    let logo = Some(data.settings.logo_url.as_str()).filter(|l| !l.trim().is_empty());
+
+    // PDFs, the two CSVs, the KPO books and the manifest; errors.txt isn't counted.
+    let total = data.invoices.len() + 2 + data.kpo.len() + 1;
+    let mut done = 0usize;
+    let mut files: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    let put = |zip: &mut ZipWriter<W>, name: String, bytes: &[u8], files: &mut Vec<String>| -> Result<(), String> {
+        zip.start_file(name.as_str(), options).map_err(zip_err)?;
+        zip.write_all(bytes).map_err(zip_err)?;
+        files.push(name);
+        Ok(())
+    };
+
+    let mut pdf_count = 0usize;
+    let mut pdf_names: HashSet<String> = HashSet::new();
+    for (inv, client) in &data.invoices {
+        let payload = build_invoice_pdf_payload_from_db(inv, client.as_ref(), &data.settings);
+        let fields = PdfFilenameFields {
+            number: &inv.invoice_number,
+            client: &inv.client_name,
+            issue_date: &inv.issue_date,
+        };
+        let name = format!("invoices/{}", render_pdf_filename(&data.settings.pdf_filename_template, &fields).join("/"));
+        // A template without `{number}` can give several invoices the same name.
+        let name = (1..)
+            .map(|n| if n == 1 { name.clone() } else { format!("{}-{n}.pdf", name.trim_end_matches(".pdf")) })
+            .find(|candidate| pdf_names.insert(candidate.clone()))
+            .unwrap_or(name);
+        match generate_pdf_bytes(&payload, logo, false) {
+            Ok(bytes) => {
+                put(&mut zip, name.clone(), &bytes, &mut files)?;
+                pdf_count += 1;
+            }
+            Err(e) => errors.push(format!("Invoice {}: {e}", inv.invoice_number)),
+        }
+        done += 1;
+        progress(done, total, &name);
+    }
+
+    let invoices: Vec<Invoice> = data.invoices.iter().map(|(inv, _)| inv.clone()).collect();
+    let name = format!("invoices_{}_{}.csv", data.from, data.to);
+    put(&mut zip, name.clone(), render_invoices_csv(&currency, &invoices, &csv_opts).as_bytes(), &mut files)?;
+    done += 1;
+    progress(done, total, &name);
+
+    let name = format!("expenses_{}_{}.csv", data.from, data.to);
+    put(&mut zip, name.clone(), render_expenses_csv(&currency, &data.expenses, &csv_opts).as_bytes(), &mut files)?;
+    done += 1;
+    progress(done, total, &name);
+
+    for (year, book) in &data.kpo {
+        let name = format!("kpo-{year}.csv");
+        match book {
+            Ok(book) => put(&mut zip, name.clone(), render_kpo_csv(book, &csv_opts).as_bytes(), &mut files)?,
+            Err(e) => errors.push(format!("KPO {year}: {e}")),
+        }
+        done += 1;
+        progress(done, total, &name);
+    }
+
+    if !errors.is_empty() {
+        let text = errors.join("\n") + "\n";
+        put(&mut zip, "errors.txt".to_string(), text.as_bytes(), &mut files)?;
+    }
+
+    let manifest = PeriodManifest {
+        format: PERIOD_ARCHIVE_FORMAT.to_string(),
+        from: data.from.clone(),
+        to: data.to.clone(),
+        created_at: created_at.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        default_currency: currency,
+        invoices: totals_by_currency(data.invoices.iter().map(|(i, _)| (i.currency.as_str(), i.total))),
+        expenses: totals_by_currency(data.expenses.iter().map(|e| (e.currency.as_str(), e.amount))),
+        files,
+        error_count: errors.len(),
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file("manifest.json", options).map_err(zip_err)?;
+    zip.write_all(&json).map_err(zip_err)?;
+    done += 1;
+    progress(done, total, "manifest.json");
+
+    zip.finish().map_err(zip_err)?;
+    Ok((errors, pdf_count))
+}
+
+/// Writes the period archive to `output_path`, emitting `period_archive_progress` per document.
+#[tauri::command]
+pub(crate) async fn export_period_archive(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    from: String,
+    to: String,
+    output_path: String,
+) -> Result<PeriodArchiveResult, AppError> {
+    state
+        .with_write("export_period_archive_license", |conn| {
+            features::require_feature(conn, Feature::BatchExport)
+        })
+        .await?;
+    let (from, to) = normalize_date_range(&from, &to)?;
+    let (range_from, range_to) = (from.clone(), to.clone());
+    let data = state
+        .with_read("export_period_archive", move |conn| load_period(conn, &range_from, &range_to))
+        .await?;
+
+    let dest = std::path::PathBuf::from(&output_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        // Entries stream into a temp file next to the target, which only appears once complete.
+        let tmp = dest.with_extension("zip.tmp");
+        let file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+        let written = write_period_archive(file, &data, &now_iso(), |done, total, name| {
+            let _ = app.emit(
+                PROGRESS_EVENT,
+                PeriodArchiveProgress {
+                    done,
+                    total,
+                    name: name.to_string(),
+                },
+            );
+        });
+        let (errors, pdf_count) = match written {
+            Ok(v) => v,
+            Err(e) => {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e);
+            }
+        };
+        std::fs::rename(&tmp, &dest).map_err(|e| e.to_string())?;
+        Ok(PeriodArchiveResult {
+            output_path,
+            from,
+            to,
+            invoice_count: data.invoices.len(),
+            pdf_count,
+            expense_count: data.expenses.len(),
+            errors,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{expense, invoice};
+    use std::io::Read;
+
+    #[test]
+    fn archive_collects_pdf_failures_and_lists_totals() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        for (id, date, total) in [("1", "2025-03-01", 1000.0), ("2", "2025-03-20", 500.0), ("3", "2025-05-01", 7.0)] {
+            invoice(id).number(&format!("2025-{id}")).issued(date).amount("RSD", total).one_line("Rad").insert(&conn);
+        }
+        expense("e1").title("Gorivo").on("2025-03-05").amount("RSD", 300.0).insert(&conn);
+
+        let mut data = load_period(&conn, "2025-03-01", "2025-03-31").unwrap();
+        assert_eq!((data.invoices.len(), data.expenses.len(), data.kpo.len()), (2, 1, 1));
+        // An unsupported PDF language stands in for a legacy invoice that can't be rendered.
+        data.settings.language = "xx".to_string();
+
+        let mut events = Vec::new();
+        let mut buf = std::io::Cursor::new(Vec::new());
+        let (errors, pdf_count) = write_period_archive(&mut buf, &data, "2025-04-01T00:00:00Z", |done, total, name| {
+            events.push((done, total, name.to_string()))
+        })
+        .unwrap();
+        assert_eq!(pdf_count, 0);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(events.len(), 6);
+        assert_eq!(events.last().unwrap(), &(6, 6, "manifest.json".to_string()));
+
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(buf.into_inner())).unwrap();
+        let mut read = |name: &str| {
+            let mut out = String::new();
+            zip.by_name(name).unwrap().read_to_string(&mut out).unwrap();
+            out
+        };
+        assert!(read("errors.txt").contains("Invoice 2025-1"));
+        assert_eq!(read("expenses_2025-03-01_2025-03-31.csv").lines().count(), 2);
+        assert!(read("kpo-2025.csv").contains("Ukupno"));
+        let manifest: serde_json::Value = serde_json::from_str(&read("manifest.json")).unwrap();
+        assert_eq!(manifest["invoices"]["RSD"]["amount"], 1500.0);
+        assert_eq!(manifest["expenses"]["RSD"]["count"], 1);
+        assert_eq!(manifest["errorCount"], 2);
+    }
+}
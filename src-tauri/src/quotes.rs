@@ -0,0 +1,715 @@
+//! Quotes (ponude): priced offers with a validity date, numbered apart from invoices as
+//! `{quotePrefix}-{nextQuoteNumber}`. They print through the invoice PDF layout with a quote
+//! title and a "valid until" line, and `convert_quote_to_invoice` copies the items into a new
+//! draft invoice and links the two documents.
+//!
+//! Expiry is not stored: a DRAFT or SENT quote whose validity date has passed reads as EXPIRED,
+//! and moving the date forward makes it current again.
+
+use std::sync::Arc;
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Message, MultiPart, SinglePart};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::data_events::{DataEntity, DataOp};
+use crate::features::{self, Feature};
+use crate::file_opener::ExportedFile;
+use crate::money::{from_minor, invoice_amounts, Line, RoundingMode};
+use crate::pdf_filename::{attachment_filename, sanitize_filename};
+use crate::{
+    build_invoice_pdf_payload_from_db, escape_html, format_invoice_number, generate_pdf_bytes, insert_new_invoice,
+    normalize_ymd, now_iso, parse_recipient_mailboxes, read_client_from_conn, read_invoice_from_conn,
+    read_settings_from_conn, reply_to_mailbox, send_email_via_smtp, sender_mailbox, today_ymd,
+    trial, validate_smtp_settings, validation_to_sql_error, DbState, Invoice, InvoiceItem, InvoiceStatus,
+    NewInvoice, SETTINGS_ID,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuoteStatus {
+    Draft,
+    Sent,
+    Accepted,
+    Rejected,
+    /// Derived from `valid_until` when the quote is read; never stored.
+    Expired,
+}
+
+impl QuoteStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            QuoteStatus::Draft => "DRAFT",
+            QuoteStatus::Sent => "SENT",
+            QuoteStatus::Accepted => "ACCEPTED",
+            QuoteStatus::Rejected => "REJECTED",
+            QuoteStatus::Expired => "EXPIRED",
+        }
+    }
+}
+
+fn default_quote_status() -> QuoteStatus {
+    QuoteStatus::Draft
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub id: String,
+    pub quote_number: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub issue_date: String,
+    pub valid_until: String,
+    #[serde(default = "default_quote_status")]
+    pub status: QuoteStatus,
+    pub currency: String,
+    pub items: Vec<InvoiceItem>,
+    pub subtotal: f64,
+    /// Net after line discounts, rounded like an invoice.
+    pub total: f64,
+    #[serde(default)]
+    pub notes: String,
+    /// Invoice the quote was converted to; a converted quote can no longer be edited.
+    #[serde(default)]
+    pub invoice_id: Option<String>,
+    #[serde(default)]
+    pub sent_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewQuote {
+    pub client_id: String,
+    pub issue_date: String,
+    pub valid_until: String,
+    /// Blank takes the client's default currency, then the settings one.
+    #[serde(default)]
+    pub currency: String,
+    pub items: Vec<InvoiceItem>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotePatch {
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub issue_date: Option<String>,
+    #[serde(default)]
+    pub valid_until: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub items: Option<Vec<InvoiceItem>>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub status: Option<QuoteStatus>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendQuoteEmailInput {
+    pub quote_id: String,
+    /// Empty sends to the client's invoice address.
+    #[serde(default)]
+    pub to: Vec<String>,
+    pub subject: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Reports DRAFT and SENT quotes past `valid_until` as EXPIRED.
+fn apply_expiry(mut quote: Quote, today: &str) -> Quote {
+    if matches!(quote.status, QuoteStatus::Draft | QuoteStatus::Sent) && quote.valid_until.as_str() < today {
+        quote.status = QuoteStatus::Expired;
+    }
+    quote
+}
+
+fn recompute_totals(quote: &mut Quote, mode: RoundingMode) {
+    let lines = quote.items.iter().map(|it| Line {
+        quantity: it.quantity,
+        unit_price: it.unit_price,
+        discount: it.discount_amount,
+    });
+    let amounts = invoice_amounts(lines, None, None, mode);
+    for (item, line) in quote.items.iter_mut().zip(&amounts.lines) {
+        item.total = from_minor(line.total);
+    }
+    quote.subtotal = from_minor(amounts.subtotal);
+    quote.total = from_minor(amounts.net);
+}
+
+fn validate(quote: &Quote) -> Result<(), String> {
+    if quote.items.is_empty() {
+        return Err("A quote needs at least one item.".to_string());
+    }
+    if quote.items.iter().any(|it| it.description.trim().is_empty()) {
+        return Err("Every quote item needs a description.".to_string());
+    }
+    if quote.items.iter().any(|it| !it.quantity.is_finite() || !it.unit_price.is_finite() || it.unit_price < 0.0) {
+        return Err("Quote items need a valid quantity and a non-negative price.".to_string());
+    }
+    if quote.valid_until < quote.issue_date {
+        return Err("Valid until must not be before the issue date.".to_string());
+    }
+    if quote.currency.trim().is_empty() {
+        return Err("Currency is required.".to_string());
+    }
+    Ok(())
+}
+
+/// Stored quote without the expiry check; writes start from this.
+fn read_stored(conn: &Connection, id: &str) -> Result<Option<Quote>, rusqlite::Error> {
+    let json: Option<String> = conn
+        .query_row("SELECT data_json FROM quotes WHERE id = ?1", params![id], |r| r.get(0))
+        .optional()?;
+    Ok(json.and_then(|j| serde_json::from_str::<Quote>(&j).ok()))
+}
+
+pub(crate) fn read_quote_in_conn(conn: &Connection, id: &str, today: &str) -> Result<Option<Quote>, rusqlite::Error> {
+    Ok(read_stored(conn, id)?.map(|q| apply_expiry(q, today)))
+}
+
+pub(crate) fn list_quotes_in_conn(conn: &Connection, today: &str) -> Result<Vec<Quote>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT data_json FROM quotes ORDER BY issueDate DESC, quoteNumber DESC")?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))?;
+    let mut out = Vec::new();
+    for json in rows {
+        if let Ok(quote) = serde_json::from_str::<Quote>(&json?) {
+            out.push(apply_expiry(quote, today));
+        }
+    }
+    Ok(out)
+}
+
+fn write_quote(conn: &Connection, quote: &Quote) -> Result<(), rusqlite::Error> {
+    let json = serde_json::to_string(quote).unwrap_or_else(|_| "{}".to_string());
+    conn.execute(
+        r#"INSERT INTO quotes (
+                id, quoteNumber, clientId, issueDate, validUntil, status, currency, totalAmount,
+                invoiceId, createdAt, data_json
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+           ON CONFLICT(id) DO UPDATE SET quoteNumber=excluded.quoteNumber, clientId=excluded.clientId,
+               issueDate=excluded.issueDate, validUntil=excluded.validUntil, status=excluded.status,
+               currency=excluded.currency, totalAmount=excluded.totalAmount,
+               invoiceId=excluded.invoiceId, data_json=excluded.data_json"#,
+        params![
+            quote.id,
+            quote.quote_number,
+            quote.client_id,
+            quote.issue_date,
+            quote.valid_until,
+            quote.status.as_str(),
+            quote.currency,
+            quote.total,
+            quote.invoice_id,
+            quote.created_at,
+            json,
+        ],
+    )?;
+    Ok(())
+}
+
+fn client_name(conn: &Connection, client_id: &str) -> Result<String, rusqlite::Error> {
+    match read_client_from_conn(conn, client_id)? {
+        Some(c) => Ok(c.name),
+        None => Err(validation_to_sql_error("Client was not found.".to_string())),
+    }
+}
+
+fn ensure_editable(quote: &Quote) -> Result<(), rusqlite::Error> {
+    match quote.invoice_id {
+        Some(_) => Err(validation_to_sql_error(format!(
+            "Quote {} has been converted to an invoice and can no longer be changed.",
+            quote.quote_number
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Numbers and stores a new DRAFT quote, advancing `nextQuoteNumber`.
+pub(crate) fn create_quote_in_conn(conn: &mut Connection, input: NewQuote) -> Result<Quote, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let settings = read_settings_from_conn(&tx)?;
+    let client = read_client_from_conn(&tx, &input.client_id)?
+        .ok_or_else(|| validation_to_sql_error("Client was not found.".to_string()))?;
+    let currency = match input.currency.trim() {
+        "" => client.default_currency.clone().unwrap_or(settings.default_currency),
+        c => c.to_string(),
+    };
+    let mut quote = Quote {
+        id: Uuid::new_v4().to_string(),
+        quote_number: format_invoice_number(&settings.quote_prefix, settings.next_quote_number),
+        client_id: client.id,
+        client_name: client.name,
+        issue_date: input.issue_date,
+        valid_until: input.valid_until,
+        status: QuoteStatus::Draft,
+        currency,
+        items: input.items,
+        subtotal: 0.0,
+        total: 0.0,
+        notes: input.notes.trim().to_string(),
+        invoice_id: None,
+        sent_at: None,
+        created_at: now_iso(),
+    };
+    recompute_totals(&mut quote, settings.rounding_mode);
+    validate(&quote).map_err(validation_to_sql_error)?;
+    write_quote(&tx, &quote)?;
+    tx.execute(
+        "UPDATE settings SET nextQuoteNumber = nextQuoteNumber + 1, updatedAt = ?2 WHERE id = ?1",
+        params![SETTINGS_ID, now_iso()],
+    )?;
+    tx.commit()?;
+    Ok(quote)
+}
+
+pub(crate) fn update_quote_in_conn(
+    conn: &Connection,
+    id: &str,
+    patch: QuotePatch,
+    today: &str,
+) -> Result<Option<Quote>, rusqlite::Error> {
+    let Some(mut quote) = read_stored(conn, id)? else {
+        return Ok(None);
+    };
+    ensure_editable(&quote)?;
+    if let Some(v) = patch.client_id {
+        quote.client_name = client_name(conn, &v)?;
+        quote.client_id = v;
+    }
+    if let Some(v) = patch.issue_date {
+        quote.issue_date = v;
+    }
+    if let Some(v) = patch.valid_until {
+        quote.valid_until = v;
+    }
+    if let Some(v) = patch.currency {
+        quote.currency = v.trim().to_string();
+    }
+    if let Some(v) = patch.items {
+        quote.items = v;
+    }
+    if let Some(v) = patch.notes {
+        quote.notes = v.trim().to_string();
+    }
+    match patch.status {
+        Some(QuoteStatus::Expired) => {
+            return Err(validation_to_sql_error(
+                "A quote expires by its validity date; change the date instead.".to_string(),
+            ))
+        }
+        Some(v) => quote.status = v,
+        None => {}
+    }
+    recompute_totals(&mut quote, read_settings_from_conn(conn)?.rounding_mode);
+    validate(&quote).map_err(validation_to_sql_error)?;
+    write_quote(conn, &quote)?;
+    Ok(Some(apply_expiry(quote, today)))
+}
+
+/// Creates a draft invoice dated `today` with the quote's client, currency, items and notes,
+/// links the two and marks the quote ACCEPTED. Expired and rejected quotes are refused.
+pub(crate) fn convert_quote_in_conn(
+    conn: &mut Connection,
+    id: &str,
+    today: &str,
+) -> Result<Option<Invoice>, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(mut quote) = read_quote_in_conn(&tx, id, today)? else {
+        return Ok(None);
+    };
+    if let Some(invoice_id) = &quote.invoice_id {
+        let number = read_invoice_from_conn(&tx, invoice_id)?.map(|i| i.invoice_number).unwrap_or_default();
+        return Err(validation_to_sql_error(format!(
+            "Quote {} has already been converted to invoice {number}.",
+            quote.quote_number
+        )));
+    }
+    match quote.status {
+        QuoteStatus::Rejected => {
+            return Err(validation_to_sql_error(format!(
+                "Quote {} was rejected and can't be converted.",
+                quote.quote_number
+            )))
+        }
+        QuoteStatus::Expired => {
+            return Err(validation_to_sql_error(format!(
+                "Quote {} expired on {}; extend its validity before converting it.",
+                quote.quote_number, quote.valid_until
+            )))
+        }
+        _ => {}
+    }
+
+    let items = quote
+        .items
+        .iter()
+        .map(|it| InvoiceItem { id: Uuid::new_v4().to_string(), ..it.clone() })
+        .collect();
+    let input = NewInvoice {
+        client_id: quote.client_id.clone(),
+        client_name: quote.client_name.clone(),
+        issue_date: today.to_string(),
+        service_date: today.to_string(),
+        status: Some(InvoiceStatus::Draft),
+        due_date: None,
+        currency: quote.currency.clone(),
+        items,
+        subtotal: quote.subtotal,
+        invoice_discount: None,
+        invoice_discount_percent: None,
+        total: quote.total,
+        notes: quote.notes.clone(),
+        payment_method: None,
+        payment_reference: None,
+        exchange_rate: None,
+        issued_by: None,
+        custom_fields: Vec::new(),
+        idempotency_key: None,
+    };
+    let invoice = insert_new_invoice(&tx, input, Some(quote.id.clone()))?;
+
+    quote.status = QuoteStatus::Accepted;
+    quote.invoice_id = Some(invoice.id.clone());
+    write_quote(&tx, &quote)?;
+    tx.commit()?;
+    Ok(Some(invoice))
+}
+
+/// Drops the link to a deleted invoice, so the quote can be converted again.
+pub(crate) fn unlink_invoice(conn: &Connection, invoice_id: &str) -> Result<(), rusqlite::Error> {
+    let ids: Vec<String> = conn
+        .prepare("SELECT id FROM quotes WHERE invoiceId = ?1")?
+        .query_map(params![invoice_id], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+    for id in ids {
+        if let Some(mut quote) = read_stored(conn, &id)? {
+            quote.invoice_id = None;
+            write_quote(conn, &quote)?;
+        }
+    }
+    Ok(())
+}
+
+/// The quote as an invoice, so it prints and attaches through the invoice PDF code.
+fn as_invoice(quote: &Quote) -> Invoice {
+    Invoice {
+        id: quote.id.clone(),
+        invoice_number: quote.quote_number.clone(),
+        client_id: quote.client_id.clone(),
+        client_name: quote.client_name.clone(),
+        issue_date: quote.issue_date.clone(),
+        service_date: quote.issue_date.clone(),
+        status: InvoiceStatus::Draft,
+        due_date: None,
+        paid_at: None,
+        currency: quote.currency.clone(),
+        items: quote.items.clone(),
+        subtotal: quote.subtotal,
+        invoice_discount: None,
+        invoice_discount_percent: None,
+        total: quote.total,
+        notes: quote.notes.clone(),
+        payment_method: None,
+        payment_reference: None,
+        exchange_rate: None,
+        imported: false,
+        rounding_mode: RoundingMode::default(),
+        quote_id: None,
+        issued_by: None,
+        custom_fields: Vec::new(),
+        created_at: quote.created_at.clone(),
+    }
+}
+
+/// The quote PDF; also returns the client's email address for sending it.
+fn quote_pdf(conn: &Connection, id: &str) -> Result<(Quote, Vec<u8>, Option<String>), rusqlite::Error> {
+    let quote = read_quote_in_conn(conn, id, &today_ymd())?
+        .ok_or_else(|| validation_to_sql_error("Quote was not found.".to_string()))?;
+    let settings = read_settings_from_conn(conn)?;
+    let client = read_client_from_conn(conn, &quote.client_id)?;
+    let mut invoice = as_invoice(&quote);
+    invoice.rounding_mode = settings.rounding_mode;
+    let mut payload = build_invoice_pdf_payload_from_db(&invoice, client.as_ref(), &settings);
+    payload.valid_until = Some(quote.valid_until.clone());
+    let logo = settings.logo_url.trim();
+    let bytes = generate_pdf_bytes(&payload, Some(logo).filter(|l| !l.is_empty()), false)?;
+    let recipient = client.as_ref().and_then(|c| c.invoice_recipient()).map(str::to_string);
+    Ok((quote, bytes, recipient))
+}
+
+#[tauri::command]
+pub(crate) async fn list_quotes(state: tauri::State<'_, DbState>) -> Result<Vec<Quote>, AppError> {
+    state.with_read("list_quotes", |conn| list_quotes_in_conn(conn, &today_ymd())).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_quote(state: tauri::State<'_, DbState>, id: String) -> Result<Option<Quote>, AppError> {
+    state
+        .with_read("get_quote", move |conn| read_quote_in_conn(conn, &id, &today_ymd()))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_quote(state: tauri::State<'_, DbState>, mut input: NewQuote) -> Result<Quote, AppError> {
+    input.issue_date = normalize_ymd("Issue date", &input.issue_date)?;
+    input.valid_until = normalize_ymd("Valid until", &input.valid_until)?;
+    state
+        .with_create_notify("create_quote", move |conn, changes| {
+            let quote = create_quote_in_conn(conn, input)?;
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            Ok(quote)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn update_quote(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    mut patch: QuotePatch,
+) -> Result<Option<Quote>, AppError> {
+    if let Some(d) = patch.issue_date.as_deref() {
+        patch.issue_date = Some(normalize_ymd("Issue date", d)?);
+    }
+    if let Some(d) = patch.valid_until.as_deref() {
+        patch.valid_until = Some(normalize_ymd("Valid until", d)?);
+    }
+    state
+        .with_write("update_quote", move |conn| {
+            trial::require_license(conn)?;
+            update_quote_in_conn(conn, &id, patch, &today_ymd())
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_quote(state: tauri::State<'_, DbState>, id: String) -> Result<bool, AppError> {
+    state
+        .with_write("delete_quote", move |conn| {
+            let Some(quote) = read_stored(conn, &id)? else {
+                return Ok(false);
+            };
+            ensure_editable(&quote)?;
+            Ok(conn.execute("DELETE FROM quotes WHERE id = ?1", params![id])? > 0)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn convert_quote_to_invoice(
+    state: tauri::State<'_, DbState>,
+    quote_id: String,
+) -> Result<Option<Invoice>, AppError> {
+    state
+        .with_create_notify("convert_quote_to_invoice", move |conn, changes| {
+            let invoice = convert_quote_in_conn(conn, &quote_id, &today_ymd())?;
+            if let Some(invoice) = &invoice {
+                changes.record(DataEntity::Invoices, DataOp::Created, invoice.id.clone());
+                changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            }
+            Ok(invoice)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn export_quote_pdf_to_downloads(
+    state: tauri::State<'_, DbState>,
+    app: tauri::AppHandle,
+    id: String,
+    open_after_export: Option<bool>,
+) -> Result<ExportedFile, AppError> {
+    let (quote, bytes, _) = state.with_read("export_quote_pdf", move |conn| quote_pdf(conn, &id)).await?;
+    let downloads_dir = app.path().download_dir().map_err(|e| e.to_string())?;
+    let filename = sanitize_filename(&format!("{}-{}.pdf", quote.quote_number, quote.client_name.trim()));
+    let full_path = downloads_dir.join(filename);
+    std::fs::write(&full_path, bytes).map_err(|e| e.to_string())?;
+    let path = full_path.to_string_lossy().to_string();
+    Ok(ExportedFile::new(&app, path, open_after_export.unwrap_or(false)))
+}
+
+/// Sends the quote PDF with `body` as the message and marks a DRAFT quote SENT.
+#[tauri::command]
+pub(crate) async fn send_quote_email(
+    state: tauri::State<'_, DbState>,
+    input: SendQuoteEmailInput,
+) -> Result<Quote, AppError> {
+    state
+        .with_write("send_quote_email_license", |conn| {
+            trial::require_license(conn)?;
+            features::require_feature(conn, Feature::EmailSending)
+        })
+        .await?;
+    let quote_id = input.quote_id.clone();
+    let (settings, (quote, pdf, recipient)) = state
+        .with_read("send_quote_email_prepare", move |conn| {
+            Ok((read_settings_from_conn(conn)?, quote_pdf(conn, &quote_id)?))
+        })
+        .await?;
+    validate_smtp_settings(&settings)?;
+
+    let to = if input.to.iter().all(|t| t.trim().is_empty()) {
+        recipient.into_iter().collect()
+    } else {
+        input.to
+    };
+    let to = parse_recipient_mailboxes("recipient", &to)?;
+    if to.is_empty() {
+        return Err(AppError::validation("clientEmail", "required", "Recipient email address is required."));
+    }
+    if input.subject.trim().is_empty() {
+        return Err(AppError::validation("subject", "required", "Email subject is required."));
+    }
+
+    let mut builder = Message::builder().from(sender_mailbox(&settings)?).subject(input.subject.trim());
+    if let Some(reply_to) = reply_to_mailbox(&settings)? {
+        builder = builder.reply_to(reply_to);
+    }
+    for m in to {
+        builder = builder.to(m);
+    }
+    let html_body = format!(
+        "<!DOCTYPE html><html><body style=\"font-family:Arial,Helvetica,sans-serif;color:#111827;line-height:1.6;\"><p>{}</p></body></html>",
+        escape_html(input.body.trim()).replace('\n', "<br />")
+    );
+    let content_type = ContentType::parse("application/pdf")
+        .map_err(|e| format!("Failed to build PDF attachment content type: {e}"))?;
+    let attachment = Attachment::new(attachment_filename(&format!("{}.pdf", quote.quote_number))).body(pdf, content_type);
+    let email = builder
+        .multipart(
+            MultiPart::mixed()
+                .multipart(
+                    MultiPart::alternative()
+                        .singlepart(SinglePart::plain(input.body.trim().to_string()))
+                        .singlepart(SinglePart::html(html_body)),
+                )
+                .singlepart(attachment),
+        )
+        .map_err(|e| format!("Failed to build email: {e}"))?;
+
+    send_email_via_smtp(Arc::new(settings), email, "quote").await?;
+    tracing::info!(target: "email", op = "quote", quote = %quote.quote_number, "email sent");
+
+    let id = quote.id;
+    state
+        .with_write("send_quote_email_mark_sent", move |conn| {
+            let mut quote = read_stored(conn, &id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+            if quote.status == QuoteStatus::Draft {
+                quote.status = QuoteStatus::Sent;
+            }
+            quote.sent_at = Some(now_iso());
+            write_quote(conn, &quote)?;
+            Ok(apply_expiry(quote, &today_ymd()))
+        })
+        .await
+        .map_err(|e| AppError::Database(format!("Email sent, but failed to mark the quote as sent: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        let json = serde_json::json!({"id": "c1", "name": "Acme", "pib": "", "address": "", "email": "", "createdAt": "t"});
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json) VALUES ('c1', 'Acme', '', '', '', 't', ?1)",
+            params![json.to_string()],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn item(description: &str, quantity: f64, unit_price: f64) -> InvoiceItem {
+        InvoiceItem {
+            id: Uuid::new_v4().to_string(),
+            description: description.to_string(),
+            unit: Some("sat".to_string()),
+            quantity,
+            unit_price,
+            discount_amount: None,
+            total: 0.0,
+            catalog_item_id: None,
+        }
+    }
+
+    fn new_quote(valid_until: &str) -> NewQuote {
+        NewQuote {
+            client_id: "c1".to_string(),
+            issue_date: "2025-03-01".to_string(),
+            valid_until: valid_until.to_string(),
+            currency: String::new(),
+            items: vec![item("Izrada sajta", 10.0, 3000.0), item("Hosting", 1.0, 1200.0)],
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn quotes_are_numbered_apart_and_expire_on_read() {
+        let mut conn = setup();
+        let first = create_quote_in_conn(&mut conn, new_quote("2025-03-15")).unwrap();
+        let second = create_quote_in_conn(&mut conn, new_quote("2025-04-30")).unwrap();
+        assert_eq!((first.quote_number.as_str(), second.quote_number.as_str()), ("PON-0001", "PON-0002"));
+        assert_eq!(first.total, 31_200.0);
+        let next_invoice: i64 = conn.query_row("SELECT nextInvoiceNumber FROM settings", [], |r| r.get(0)).unwrap();
+        assert_eq!(next_invoice, 1);
+
+        let statuses = |today: &str| -> Vec<QuoteStatus> {
+            let mut quotes = list_quotes_in_conn(&conn, today).unwrap();
+            quotes.sort_by(|a, b| a.quote_number.cmp(&b.quote_number));
+            quotes.iter().map(|q| q.status).collect()
+        };
+        assert_eq!(statuses("2025-03-15"), vec![QuoteStatus::Draft, QuoteStatus::Draft]);
+        assert_eq!(statuses("2025-03-16"), vec![QuoteStatus::Expired, QuoteStatus::Draft]);
+        let stored: String = conn
+            .query_row("SELECT status FROM quotes WHERE id = ?1", params![first.id], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stored, "DRAFT");
+
+        // Extending the validity brings the quote back.
+        let patch = QuotePatch { valid_until: Some("2025-05-01".to_string()), ..Default::default() };
+        let revived = update_quote_in_conn(&conn, &first.id, patch, "2025-03-16").unwrap().unwrap();
+        assert_eq!(revived.status, QuoteStatus::Draft);
+        let patch = QuotePatch { status: Some(QuoteStatus::Expired), ..Default::default() };
+        assert!(update_quote_in_conn(&conn, &first.id, patch, "2025-03-16").is_err());
+    }
+
+    #[test]
+    fn converting_copies_items_and_links_both_documents() {
+        let mut conn = setup();
+        let quote = create_quote_in_conn(&mut conn, new_quote("2025-03-31")).unwrap();
+        assert!(convert_quote_in_conn(&mut conn, &quote.id, "2025-04-01").is_err(), "expired");
+
+        let invoice = convert_quote_in_conn(&mut conn, &quote.id, "2025-03-20").unwrap().unwrap();
+        assert_eq!(invoice.status, InvoiceStatus::Draft);
+        assert_eq!(invoice.issue_date, "2025-03-20");
+        assert_eq!(invoice.quote_id.as_deref(), Some(quote.id.as_str()));
+        assert_eq!(invoice.total, quote.total);
+        let descriptions: Vec<_> = invoice.items.iter().map(|i| i.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["Izrada sajta", "Hosting"]);
+
+        let converted = read_quote_in_conn(&conn, &quote.id, "2025-04-10").unwrap().unwrap();
+        assert_eq!(converted.status, QuoteStatus::Accepted);
+        assert_eq!(converted.invoice_id.as_deref(), Some(invoice.id.as_str()));
+        assert!(convert_quote_in_conn(&mut conn, &quote.id, "2025-03-20").is_err(), "converted twice");
+        assert!(update_quote_in_conn(&conn, &quote.id, QuotePatch::default(), "2025-03-20").is_err());
+
+        unlink_invoice(&conn, &invoice.id).unwrap();
+        assert_eq!(read_quote_in_conn(&conn, &quote.id, "2025-03-20").unwrap().unwrap().invoice_id, None);
+    }
+}
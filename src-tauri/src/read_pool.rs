@@ -0,0 +1,208 @@
+//! Read-only connections for `DbState::with_read`. Writes keep going through the one writer
+//! connection behind `write_lock`; reads take a connection from here instead, so under WAL a long
+//! CSV export reads its snapshot while `get_settings` and saves carry on.
+//!
+//! Connections are opened on first use, never before `DbState::new` has run the migrations on
+//! the writer, and at most `READ_POOL_SIZE` of them; a read beyond that waits for one to come back.
+//! Moving the database calls [`ReadPool::reset`], so later reads open the new file.
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::errors::AppError;
+
+pub(crate) const READ_POOL_SIZE: usize = 4;
+
+struct Idle {
+    path: PathBuf,
+    /// Bumped by `reset`; connections lent out before it are closed instead of coming back.
+    generation: u64,
+    connections: Vec<Connection>,
+    /// Open connections of the current generation, idle or lent out.
+    open: usize,
+}
+
+pub(crate) struct ReadPool {
+    size: usize,
+    idle: Mutex<Idle>,
+    returned: Condvar,
+}
+
+/// A connection lent out by [`ReadPool::get`]; goes back to the pool when dropped.
+pub(crate) struct PooledConnection<'a> {
+    pool: &'a ReadPool,
+    generation: u64,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("pooled connection taken twice")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+        // A read that failed half-way may leave its transaction open; that connection would keep
+        // reading the old snapshot.
+        let reusable = conn.is_autocommit() || conn.execute_batch("ROLLBACK;").is_ok();
+        if let Ok(mut idle) = self.pool.idle.lock() {
+            // One opened before a `reset` belongs to the old file and is just closed.
+            if idle.generation == self.generation {
+                if reusable {
+                    idle.connections.push(conn);
+                } else {
+                    idle.open -= 1;
+                }
+            }
+        }
+        self.pool.returned.notify_one();
+    }
+}
+
+fn open_reader(path: &Path) -> Result<Connection, rusqlite::Error> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI;
+    let conn = Connection::open_with_flags(path, flags)?;
+    crate::configure_connection(&conn)?;
+    Ok(conn)
+}
+
+impl ReadPool {
+    pub(crate) fn new(path: PathBuf, size: usize) -> Self {
+        ReadPool {
+            size: size.max(1),
+            idle: Mutex::new(Idle { path, generation: 0, connections: Vec::new(), open: 0 }),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// An idle connection, a newly opened one while the pool isn't full, or else the next one
+    /// returned.
+    pub(crate) fn get(&self) -> Result<PooledConnection<'_>, AppError> {
+        let poisoned = || AppError::Database("read pool mutex poisoned".to_string());
+        let mut idle = self.idle.lock().map_err(|_| poisoned())?;
+        loop {
+            let generation = idle.generation;
+            if let Some(conn) = idle.connections.pop() {
+                return Ok(PooledConnection { pool: self, generation, conn: Some(conn) });
+            }
+            if idle.open < self.size {
+                idle.open += 1;
+                let path = idle.path.clone();
+                drop(idle);
+                return match open_reader(&path) {
+                    Ok(conn) => Ok(PooledConnection { pool: self, generation, conn: Some(conn) }),
+                    Err(e) => {
+                        if let Ok(mut idle) = self.idle.lock() {
+                            if idle.generation == generation {
+                                idle.open -= 1;
+                            }
+                        }
+                        self.returned.notify_one();
+                        Err(e.into())
+                    }
+                };
+            }
+            idle = self.returned.wait(idle).map_err(|_| poisoned())?;
+        }
+    }
+
+    /// Points the pool at `path`: idle connections are closed now, lent-out ones when they come
+    /// back, and the next reads open the new file.
+    pub(crate) fn reset(&self, path: PathBuf) -> Result<(), AppError> {
+        let mut idle = self
+            .idle
+            .lock()
+            .map_err(|_| AppError::Database("read pool mutex poisoned".to_string()))?;
+        idle.connections.clear();
+        idle.open = 0;
+        idle.generation += 1;
+        idle.path = path;
+        drop(idle);
+        self.returned.notify_all();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    fn temp_db() -> PathBuf {
+        std::env::temp_dir().join(format!("pausaler-read-pool-{}.db", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn slow_read_does_not_hold_up_writes() {
+        let path = temp_db();
+        let writer = Connection::open(&path).unwrap();
+        crate::configure_sqlite(&writer).unwrap();
+        writer.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1), (2), (3);").unwrap();
+
+        let pool = Arc::new(ReadPool::new(path.clone(), 2));
+        let writes_done = Arc::new(AtomicBool::new(false));
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+
+        let slow_read = {
+            let pool = pool.clone();
+            let writes_done = writes_done.clone();
+            std::thread::spawn(move || {
+                let conn = pool.get().unwrap();
+                let mut stmt = conn.prepare("SELECT x FROM t").unwrap();
+                let mut rows = stmt.query([]).unwrap();
+                let mut seen = 0;
+                while rows.next().unwrap().is_some() {
+                    seen += 1;
+                    if seen == 1 {
+                        started_tx.send(()).unwrap();
+                        // Stay inside the read until every write went through.
+                        let deadline = Instant::now() + Duration::from_secs(10);
+                        while !writes_done.load(Ordering::SeqCst) && Instant::now() < deadline {
+                            std::thread::sleep(Duration::from_millis(5));
+                        }
+                    }
+                }
+                seen
+            })
+        };
+
+        started_rx.recv().unwrap();
+        let begun = Instant::now();
+        for i in 0..200 {
+            writer.execute("INSERT INTO t VALUES (?1)", [i]).unwrap();
+            if i % 50 == 0 {
+                // Reads from the other pooled connection go on too, and see the committed rows.
+                let count: i64 = pool.get().unwrap().query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+                assert_eq!(count, 3 + i + 1);
+            }
+        }
+        assert!(begun.elapsed() < Duration::from_secs(5), "writes waited for the read");
+        writes_done.store(true, Ordering::SeqCst);
+        // The slow read kept its snapshot.
+        assert_eq!(slow_read.join().unwrap(), 3);
+
+        let reader = pool.get().unwrap();
+        let count: i64 = reader.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 203);
+        assert!(reader.execute("INSERT INTO t VALUES (0)", []).is_err());
+        drop(reader);
+        assert_eq!(pool.idle.lock().unwrap().open, 2);
+
+        drop(writer);
+        drop(pool);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+}
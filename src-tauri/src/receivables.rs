@@ -0,0 +1,266 @@
+//! Outstanding receivables (aging): unpaid SENT invoices per client and currency, less partial
+//! payments already recorded, bucketed by days past due.
+
+use std::collections::BTreeMap;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use time::{Date, OffsetDateTime};
+
+use crate::errors::AppError;
+use crate::recurring_expenses::parse_ymd;
+use crate::{
+    csv_document, csv_header_row, csv_join_row, format_money_csv, invoice_total_in_currency, resolve_csv_options,
+    write_text_file, CsvOptions, DbState, Invoice,
+};
+
+/// Payment terms assumed for invoices without a due date whose client has no terms either.
+const DEFAULT_PAYMENT_TERMS_DAYS: i64 = 15;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgingBuckets {
+    /// Not yet due (or due today).
+    pub current: f64,
+    pub days_1_to_30: f64,
+    pub days_31_to_60: f64,
+    pub days_61_to_90: f64,
+    pub days_over_90: f64,
+    pub total: f64,
+    pub invoice_count: i64,
+}
+
+impl AgingBuckets {
+    fn add(&mut self, days_past_due: i64, amount: f64) {
+        let bucket = match days_past_due {
+            i64::MIN..=0 => &mut self.current,
+            1..=30 => &mut self.days_1_to_30,
+            31..=60 => &mut self.days_31_to_60,
+            61..=90 => &mut self.days_61_to_90,
+            _ => &mut self.days_over_90,
+        };
+        *bucket += amount;
+        self.total += amount;
+        self.invoice_count += 1;
+    }
+
+    fn finish(&mut self) {
+        for v in [
+            &mut self.current,
+            &mut self.days_1_to_30,
+            &mut self.days_31_to_60,
+            &mut self.days_61_to_90,
+            &mut self.days_over_90,
+            &mut self.total,
+        ] {
+            *v = (*v * 100.0).round() / 100.0;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivablesClientRow {
+    pub client_id: String,
+    pub client_name: String,
+    pub currency: String,
+    #[serde(flatten)]
+    pub buckets: AgingBuckets,
+    /// Days past due of the client's oldest unpaid invoice (negative when nothing is due yet).
+    pub max_days_past_due: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivablesCurrencyTotal {
+    pub currency: String,
+    #[serde(flatten)]
+    pub buckets: AgingBuckets,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivablesReport {
+    /// `YYYY-MM-DD` the days past due are counted to.
+    pub as_of: String,
+    /// Sorted by client name, then currency.
+    pub clients: Vec<ReceivablesClientRow>,
+    pub totals: Vec<ReceivablesCurrencyTotal>,
+}
+
+/// Due date, else issue date + client terms (or the default terms).
+pub(crate) fn effective_due_date(invoice: &Invoice, client_terms_days: Option<i64>) -> Option<Date> {
+    if let Some(due) = invoice.due_date.as_deref().and_then(parse_ymd) {
+        return Some(due);
+    }
+    let issue = parse_ymd(&invoice.issue_date)?;
+    issue.checked_add(time::Duration::days(client_terms_days.unwrap_or(DEFAULT_PAYMENT_TERMS_DAYS)))
+}
+
+pub(crate) fn receivables_report_in_conn(conn: &Connection, today: Date) -> Result<ReceivablesReport, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT i.data_json, c.paymentTermsDays,
+                  (SELECT COALESCE(SUM(p.amount), 0) FROM invoice_payments p WHERE p.invoiceId = i.id)
+           FROM invoices i
+           LEFT JOIN clients c ON c.id = i.clientId
+           WHERE i.status = 'SENT'"#,
+    )?;
+    let mut rows = stmt.query([])?;
+
+    // Keyed by lowercased name first so the result comes out sorted by client name.
+    let mut per_client: BTreeMap<(String, String, String), (String, AgingBuckets, i64)> = BTreeMap::new();
+    let mut totals: BTreeMap<String, AgingBuckets> = BTreeMap::new();
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        let terms: Option<i64> = row.get(1)?;
+        let paid: f64 = row.get(2)?;
+        let Ok(inv) = serde_json::from_str::<Invoice>(&json) else {
+            continue;
+        };
+        let Some(due) = effective_due_date(&inv, terms) else {
+            continue;
+        };
+        let days_past_due = (today - due).whole_days();
+        let amount = invoice_total_in_currency(&inv, &inv.currency).unwrap_or(inv.total) - paid;
+
+        let key = (inv.client_name.to_lowercase(), inv.client_id.clone(), inv.currency.clone());
+        let (_, buckets, max_days) = per_client
+            .entry(key)
+            .or_insert_with(|| (inv.client_name.clone(), AgingBuckets::default(), i64::MIN));
+        buckets.add(days_past_due, amount);
+        *max_days = (*max_days).max(days_past_due);
+        totals.entry(inv.currency.clone()).or_default().add(days_past_due, amount);
+    }
+
+    let clients = per_client
+        .into_iter()
+        .map(|((_, client_id, currency), (client_name, mut buckets, max_days_past_due))| {
+            buckets.finish();
+            ReceivablesClientRow {
+                client_id,
+                client_name,
+                currency,
+                buckets,
+                max_days_past_due,
+            }
+        })
+        .collect();
+    let totals = totals
+        .into_iter()
+        .map(|(currency, mut buckets)| {
+            buckets.finish();
+            ReceivablesCurrencyTotal { currency, buckets }
+        })
+        .collect();
+
+    Ok(ReceivablesReport {
+        as_of: format!("{:04}-{:02}-{:02}", today.year(), u8::from(today.month()), today.day()),
+        clients,
+        totals,
+    })
+}
+
+fn bucket_fields(b: &AgingBuckets, opts: &CsvOptions) -> [String; 7] {
+    [
+        b.invoice_count.to_string(),
+        format_money_csv(b.current, opts),
+        format_money_csv(b.days_1_to_30, opts),
+        format_money_csv(b.days_31_to_60, opts),
+        format_money_csv(b.days_61_to_90, opts),
+        format_money_csv(b.days_over_90, opts),
+        format_money_csv(b.total, opts),
+    ]
+}
+
+pub(crate) fn render_receivables_csv(report: &ReceivablesReport, opts: &CsvOptions) -> String {
+    let header = [
+        "clientId",
+        "clientName",
+        "currency",
+        "invoiceCount",
+        "current",
+        "days1To30",
+        "days31To60",
+        "days61To90",
+        "daysOver90",
+        "total",
+    ];
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(csv_header_row(&header, opts));
+    for c in &report.clients {
+        let mut row = vec![c.client_id.clone(), c.client_name.clone(), c.currency.clone()];
+        row.extend(bucket_fields(&c.buckets, opts));
+        lines.push(csv_join_row(&row, opts));
+    }
+    for t in &report.totals {
+        let mut row = vec![String::new(), "TOTAL".to_string(), t.currency.clone()];
+        row.extend(bucket_fields(&t.buckets, opts));
+        lines.push(csv_join_row(&row, opts));
+    }
+    csv_document(&lines, opts)
+}
+
+/// Unpaid SENT invoices per client and currency, bucketed by days past due as of today.
+#[tauri::command]
+pub(crate) async fn accounts_receivable_report(state: tauri::State<'_, DbState>) -> Result<ReceivablesReport, AppError> {
+    let today = OffsetDateTime::now_utc().date();
+    state
+        .with_read("accounts_receivable_report", move |conn| receivables_report_in_conn(conn, today))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn export_accounts_receivable_csv(
+    state: tauri::State<'_, DbState>,
+    output_path: String,
+    csv_options: Option<CsvOptions>,
+) -> Result<String, AppError> {
+    let today = OffsetDateTime::now_utc().date();
+    let (opts, report) = state
+        .with_read("export_accounts_receivable_csv", move |conn| {
+            Ok((resolve_csv_options(conn, csv_options)?, receivables_report_in_conn(conn, today)?))
+        })
+        .await?;
+    write_text_file(std::path::Path::new(&output_path), &render_receivables_csv(&report, &opts))?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{client, invoice};
+
+    #[test]
+    fn buckets_use_due_date_or_client_terms() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        client("b", "Beta").payment_terms(45).insert(&conn);
+        let alfa = |id: &str| invoice(id).client("a", "Alfa");
+        // as of 2025-06-30
+        alfa("1").issued("2025-06-01").due("2025-06-30").amount("RSD", 100.0).insert(&conn); // due today
+        alfa("2").issued("2025-05-01").due("2025-05-31").amount("RSD", 200.0).insert(&conn); // 30 days
+        alfa("3").issued("2025-01-01").due("2025-03-01").amount("RSD", 300.0).insert(&conn); // 121 days
+        alfa("4").issued("2025-01-01").status("PAID").amount("RSD", 999.0).insert(&conn);
+        alfa("5").issued("2025-04-01").due("2025-04-30").amount("EUR", 50.0).insert(&conn); // 61 days
+        invoice("6").client("b", "Beta").issued("2025-04-01").amount("RSD", 400.0).insert(&conn); // due 05-16 → 45 days
+        invoice("7").client("x", "Gama").issued("2025-06-01").amount("RSD", 10.0).insert(&conn); // due 06-16 → 14 days
+
+        let today = Date::from_calendar_date(2025, time::Month::June, 30).unwrap();
+        let r = receivables_report_in_conn(&conn, today).unwrap();
+        let rows: Vec<_> = r.clients.iter().map(|c| (c.client_name.as_str(), c.currency.as_str())).collect();
+        assert_eq!(rows, vec![("Alfa", "EUR"), ("Alfa", "RSD"), ("Beta", "RSD"), ("Gama", "RSD")]);
+
+        let alfa_rsd = &r.clients[1];
+        assert_eq!((alfa_rsd.buckets.current, alfa_rsd.buckets.days_1_to_30, alfa_rsd.buckets.days_over_90), (100.0, 200.0, 300.0));
+        assert_eq!((alfa_rsd.buckets.total, alfa_rsd.buckets.invoice_count, alfa_rsd.max_days_past_due), (600.0, 3, 121));
+        assert_eq!(r.clients[0].buckets.days_61_to_90, 50.0);
+        assert_eq!(r.clients[2].buckets.days_31_to_60, 400.0);
+        assert_eq!(r.clients[3].buckets.days_1_to_30, 10.0);
+
+        assert_eq!(r.totals.len(), 2);
+        assert_eq!((r.totals[1].currency.as_str(), r.totals[1].buckets.total, r.totals[1].buckets.invoice_count), ("RSD", 1010.0, 5));
+
+        let csv = render_receivables_csv(&r, &CsvOptions::default());
+        assert!(csv.contains("\r\n,TOTAL,RSD,5,100.00,210.00,400.00,0.00,300.00,1010.00\r\n"), "{csv}");
+    }
+}
@@ -0,0 +1,533 @@
+//! Recurring expense schedules (accounting fee, hosting, contributions, …). Due periods are
+//! turned into ordinary `expenses` rows that keep a `recurringId` back to their schedule.
+
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use time::{Date, Duration, Month};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::data_events::DataEntity;
+use crate::{now_iso, today_ymd, validation_to_sql_error, DbState};
+
+/// Upper bound of periods generated for one schedule in one run (a weekly schedule ~20 years back).
+const MAX_PERIODS_PER_RUN: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurrenceInterval {
+    Weekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl RecurrenceInterval {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecurrenceInterval::Weekly => "weekly",
+            RecurrenceInterval::Monthly => "monthly",
+            RecurrenceInterval::Quarterly => "quarterly",
+            RecurrenceInterval::Yearly => "yearly",
+        }
+    }
+
+    fn parse(v: &str) -> Option<Self> {
+        match v {
+            "weekly" => Some(RecurrenceInterval::Weekly),
+            "monthly" => Some(RecurrenceInterval::Monthly),
+            "quarterly" => Some(RecurrenceInterval::Quarterly),
+            "yearly" => Some(RecurrenceInterval::Yearly),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringExpense {
+    pub id: String,
+    pub title: String,
+    pub amount: f64,
+    pub currency: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub interval: RecurrenceInterval,
+    /// First occurrence; later ones keep its day of month (clamped to short months).
+    pub start_date: String,
+    /// Last day an occurrence may fall on.
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// Date of the next expense to generate.
+    pub next_run_date: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewRecurringExpense {
+    pub title: String,
+    pub amount: f64,
+    pub currency: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub interval: RecurrenceInterval,
+    pub start_date: String,
+    #[serde(default)]
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringExpensePatch {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub category: Option<Option<String>>,
+    #[serde(default)]
+    pub notes: Option<Option<String>>,
+    #[serde(default)]
+    pub interval: Option<RecurrenceInterval>,
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRecurringExpenseResult {
+    pub deleted: bool,
+    /// Generated expenses dated after today that were removed with the schedule.
+    pub expenses_deleted: usize,
+}
+
+pub(crate) fn parse_ymd(v: &str) -> Option<Date> {
+    let mut parts = v.trim().splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+pub(crate) fn format_ymd(d: Date) -> String {
+    format!("{:04}-{:02}-{:02}", d.year(), u8::from(d.month()), d.day())
+}
+
+/// `start` moved by `months`, keeping its day of month where the target month allows.
+fn add_months_clamped(start: Date, months: i64) -> Option<Date> {
+    let total = i64::from(start.year()) * 12 + i64::from(u8::from(start.month())) - 1 + months;
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = Month::try_from(u8::try_from(total.rem_euclid(12) + 1).ok()?).ok()?;
+    let day = start.day().min(month.length(year));
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+/// The `n`-th occurrence (0 = `start`). Computed from `start` so a 31st survives February.
+fn occurrence(start: Date, interval: RecurrenceInterval, n: i64) -> Option<Date> {
+    match interval {
+        RecurrenceInterval::Weekly => start.checked_add(Duration::weeks(n)),
+        RecurrenceInterval::Monthly => add_months_clamped(start, n),
+        RecurrenceInterval::Quarterly => add_months_clamped(start, n * 3),
+        RecurrenceInterval::Yearly => add_months_clamped(start, n * 12),
+    }
+}
+
+/// First occurrence strictly after `after`.
+fn occurrence_after(start: Date, interval: RecurrenceInterval, after: Date) -> Option<Date> {
+    (0..).map_while(|n| occurrence(start, interval, n)).find(|d| *d > after)
+}
+
+fn clean_optional(v: Option<String>) -> Option<String> {
+    v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn validate(r: &RecurringExpense) -> Result<(), String> {
+    if r.title.is_empty() {
+        return Err("Title is required.".to_string());
+    }
+    if !r.amount.is_finite() || r.amount <= 0.0 {
+        return Err("Amount must be greater than 0.".to_string());
+    }
+    if r.currency.is_empty() {
+        return Err("Currency is required.".to_string());
+    }
+    let start = parse_ymd(&r.start_date).ok_or_else(|| "Start date must be YYYY-MM-DD.".to_string())?;
+    if let Some(end) = r.end_date.as_deref() {
+        let end = parse_ymd(end).ok_or_else(|| "End date must be YYYY-MM-DD.".to_string())?;
+        if end < start {
+            return Err("End date must not be before the start date.".to_string());
+        }
+    }
+    Ok(())
+}
+
+const RECURRING_COLUMNS: &str =
+    "id, title, amount, currency, category, notes, interval, startDate, endDate, nextRunDate, createdAt";
+
+fn recurring_from_row(r: &rusqlite::Row<'_>) -> Result<RecurringExpense, rusqlite::Error> {
+    let interval: String = r.get(6)?;
+    Ok(RecurringExpense {
+        id: r.get(0)?,
+        title: r.get(1)?,
+        amount: r.get(2)?,
+        currency: r.get(3)?,
+        category: r.get(4)?,
+        notes: r.get(5)?,
+        interval: RecurrenceInterval::parse(&interval).unwrap_or(RecurrenceInterval::Monthly),
+        start_date: r.get(7)?,
+        end_date: r.get(8)?,
+        next_run_date: r.get(9)?,
+        created_at: r.get(10)?,
+    })
+}
+
+fn read_recurring(conn: &Connection, id: &str) -> Result<Option<RecurringExpense>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {RECURRING_COLUMNS} FROM recurring_expenses WHERE id = ?1"),
+        params![id],
+        recurring_from_row,
+    )
+    .optional()
+}
+
+fn write_recurring(conn: &Connection, r: &RecurringExpense) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"INSERT INTO recurring_expenses (id, title, amount, currency, category, notes, interval,
+               startDate, endDate, nextRunDate, createdAt)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+           ON CONFLICT(id) DO UPDATE SET title=excluded.title, amount=excluded.amount,
+               currency=excluded.currency, category=excluded.category, notes=excluded.notes,
+               interval=excluded.interval, startDate=excluded.startDate, endDate=excluded.endDate,
+               nextRunDate=excluded.nextRunDate"#,
+        params![
+            r.id,
+            r.title,
+            r.amount,
+            r.currency,
+            r.category,
+            r.notes,
+            r.interval.as_str(),
+            r.start_date,
+            r.end_date,
+            r.next_run_date,
+            r.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Inserts an expense for every occurrence on or before `today` (backfilling missed periods
+/// with their own dates) and advances each schedule. Returns the number of expenses created.
+pub(crate) fn run_due_in_conn(conn: &mut Connection, today: &str) -> Result<usize, rusqlite::Error> {
+    let Some(today) = parse_ymd(today) else {
+        return Ok(0);
+    };
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let due: Vec<RecurringExpense> = {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {RECURRING_COLUMNS} FROM recurring_expenses WHERE nextRunDate <= ?1"
+        ))?;
+        let rows = stmt.query_map(params![format_ymd(today)], recurring_from_row)?;
+        rows.collect::<Result<_, _>>()?
+    };
+
+    let mut created = 0;
+    for mut schedule in due {
+        let (Some(start), Some(mut next)) = (parse_ymd(&schedule.start_date), parse_ymd(&schedule.next_run_date))
+        else {
+            continue;
+        };
+        let end = schedule.end_date.as_deref().and_then(parse_ymd);
+        for _ in 0..MAX_PERIODS_PER_RUN {
+            if next > today || end.is_some_and(|e| next > e) {
+                break;
+            }
+            tx.execute(
+                r#"INSERT INTO expenses (id, title, amount, currency, date, category, notes, createdAt, recurringId)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+                params![
+                    Uuid::new_v4().to_string(),
+                    schedule.title,
+                    schedule.amount,
+                    schedule.currency,
+                    format_ymd(next),
+                    schedule.category,
+                    schedule.notes,
+                    now_iso(),
+                    schedule.id,
+                ],
+            )?;
+            created += 1;
+            match occurrence_after(start, schedule.interval, next) {
+                Some(d) => next = d,
+                None => break,
+            }
+        }
+        schedule.next_run_date = format_ymd(next);
+        tx.execute(
+            "UPDATE recurring_expenses SET nextRunDate = ?2 WHERE id = ?1",
+            params![schedule.id, schedule.next_run_date],
+        )?;
+    }
+    tx.commit()?;
+    Ok(created)
+}
+
+/// Removes a schedule. Expenses it already generated stay as ordinary expenses, except those
+/// dated after `today` when `delete_future` is set.
+pub(crate) fn delete_recurring_in_conn(
+    conn: &mut Connection,
+    id: &str,
+    delete_future: bool,
+    today: &str,
+) -> Result<DeleteRecurringExpenseResult, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let expenses_deleted = if delete_future {
+        tx.execute(
+            "DELETE FROM expenses WHERE recurringId = ?1 AND date > ?2",
+            params![id, today],
+        )?
+    } else {
+        0
+    };
+    tx.execute("UPDATE expenses SET recurringId = NULL WHERE recurringId = ?1", params![id])?;
+    let deleted = tx.execute("DELETE FROM recurring_expenses WHERE id = ?1", params![id])? > 0;
+    tx.commit()?;
+    Ok(DeleteRecurringExpenseResult {
+        deleted,
+        expenses_deleted,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn list_recurring_expenses(state: tauri::State<'_, DbState>) -> Result<Vec<RecurringExpense>, AppError> {
+    state
+        .with_read("list_recurring_expenses", |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {RECURRING_COLUMNS} FROM recurring_expenses ORDER BY nextRunDate ASC, title ASC"
+            ))?;
+            let rows = stmt.query_map([], recurring_from_row)?;
+            rows.collect()
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_recurring_expense(
+    state: tauri::State<'_, DbState>,
+    input: NewRecurringExpense,
+) -> Result<RecurringExpense, AppError> {
+    let start_date = input.start_date.trim().to_string();
+    let schedule = RecurringExpense {
+        id: Uuid::new_v4().to_string(),
+        title: input.title.trim().to_string(),
+        amount: input.amount,
+        currency: input.currency.trim().to_string(),
+        category: clean_optional(input.category),
+        notes: clean_optional(input.notes),
+        interval: input.interval,
+        next_run_date: start_date.clone(),
+        start_date,
+        end_date: clean_optional(input.end_date),
+        created_at: now_iso(),
+    };
+    validate(&schedule)?;
+
+    state
+        .with_create_notify("create_recurring_expense", move |conn, _| {
+            write_recurring(conn, &schedule)?;
+            Ok(schedule)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn update_recurring_expense(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    patch: RecurringExpensePatch,
+) -> Result<Option<RecurringExpense>, AppError> {
+    state
+        .with_write("update_recurring_expense", move |conn| {
+            let Some(mut schedule) = read_recurring(conn, &id)? else {
+                return Ok(None);
+            };
+            let timing_before = (schedule.interval, schedule.start_date.clone());
+
+            if let Some(v) = patch.title {
+                schedule.title = v.trim().to_string();
+            }
+            if let Some(v) = patch.amount {
+                schedule.amount = v;
+            }
+            if let Some(v) = patch.currency {
+                schedule.currency = v.trim().to_string();
+            }
+            if let Some(v) = patch.category {
+                schedule.category = clean_optional(v);
+            }
+            if let Some(v) = patch.notes {
+                schedule.notes = clean_optional(v);
+            }
+            if let Some(v) = patch.interval {
+                schedule.interval = v;
+            }
+            if let Some(v) = patch.start_date {
+                schedule.start_date = v.trim().to_string();
+            }
+            if let Some(v) = patch.end_date {
+                schedule.end_date = clean_optional(v);
+            }
+            validate(&schedule).map_err(validation_to_sql_error)?;
+
+            // A new rhythm continues after the last expense already generated.
+            if (schedule.interval, schedule.start_date.clone()) != timing_before {
+                let last: Option<String> = conn.query_row(
+                    "SELECT MAX(date) FROM expenses WHERE recurringId = ?1",
+                    params![schedule.id],
+                    |r| r.get(0),
+                )?;
+                let start = parse_ymd(&schedule.start_date);
+                let next = match (start, last.as_deref().and_then(parse_ymd)) {
+                    (Some(start), Some(last)) => occurrence_after(start, schedule.interval, last),
+                    (start, _) => start,
+                };
+                if let Some(next) = next {
+                    schedule.next_run_date = format_ymd(next);
+                }
+            }
+
+            write_recurring(conn, &schedule)?;
+            Ok(Some(schedule))
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_recurring_expense(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    delete_future: Option<bool>,
+) -> Result<DeleteRecurringExpenseResult, AppError> {
+    state
+        .with_write_notify("delete_recurring_expense", move |conn, changes| {
+            let result = delete_recurring_in_conn(conn, &id, delete_future.unwrap_or(false), &today_ymd())?;
+            // Generated expenses lose their schedule link, and future ones may be gone.
+            if result.deleted {
+                changes.replaced(DataEntity::Expenses);
+            }
+            Ok(result)
+        })
+        .await
+}
+
+/// Generates the expenses of all due schedules; the app calls this at startup.
+#[tauri::command]
+pub(crate) async fn run_due_recurring_expenses(state: tauri::State<'_, DbState>) -> Result<usize, AppError> {
+    state
+        .with_create_notify("run_due_recurring_expenses", |conn, changes| {
+            let generated = run_due_in_conn(conn, &today_ymd())?;
+            if generated > 0 {
+                changes.replaced(DataEntity::Expenses);
+            }
+            Ok(generated)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(v: &str) -> Date {
+        parse_ymd(v).unwrap()
+    }
+
+    #[test]
+    fn monthly_occurrences_keep_day_of_month() {
+        let start = d("2024-01-31");
+        let dates: Vec<String> = (0..4)
+            .map(|n| format_ymd(occurrence(start, RecurrenceInterval::Monthly, n).unwrap()))
+            .collect();
+        assert_eq!(dates, vec!["2024-01-31", "2024-02-29", "2024-03-31", "2024-04-30"]);
+        assert_eq!(
+            occurrence_after(start, RecurrenceInterval::Quarterly, d("2024-01-31")),
+            Some(d("2024-04-30"))
+        );
+        assert_eq!(occurrence(d("2024-12-15"), RecurrenceInterval::Monthly, 1), Some(d("2025-01-15")));
+    }
+
+    fn schedule(id: &str, start: &str, interval: RecurrenceInterval) -> RecurringExpense {
+        RecurringExpense {
+            id: id.to_string(),
+            title: "Knjigovođa".to_string(),
+            amount: 6000.0,
+            currency: "RSD".to_string(),
+            category: Some("Usluge".to_string()),
+            notes: None,
+            interval,
+            start_date: start.to_string(),
+            end_date: None,
+            next_run_date: start.to_string(),
+            created_at: "t".to_string(),
+        }
+    }
+
+    fn generated_dates(conn: &Connection, id: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT date FROM expenses WHERE recurringId = ?1 ORDER BY date")
+            .unwrap();
+        let rows = stmt.query_map(params![id], |r| r.get(0)).unwrap();
+        rows.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn run_due_backfills_missed_periods_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        write_recurring(&conn, &schedule("m", "2024-11-30", RecurrenceInterval::Monthly)).unwrap();
+        let mut ended = schedule("y", "2023-06-01", RecurrenceInterval::Yearly);
+        ended.end_date = Some("2024-12-31".to_string());
+        write_recurring(&conn, &ended).unwrap();
+
+        assert_eq!(run_due_in_conn(&mut conn, "2025-03-10").unwrap(), 6);
+        assert_eq!(
+            generated_dates(&conn, "m"),
+            vec!["2024-11-30", "2024-12-30", "2025-01-30", "2025-02-28"]
+        );
+        assert_eq!(generated_dates(&conn, "y"), vec!["2023-06-01", "2024-06-01"]);
+        assert_eq!(read_recurring(&conn, "m").unwrap().unwrap().next_run_date, "2025-03-30");
+
+        assert_eq!(run_due_in_conn(&mut conn, "2025-03-10").unwrap(), 0);
+    }
+
+    #[test]
+    fn delete_can_remove_future_generated_expenses() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        write_recurring(&conn, &schedule("w", "2025-01-01", RecurrenceInterval::Weekly)).unwrap();
+        run_due_in_conn(&mut conn, "2025-01-22").unwrap();
+
+        let res = delete_recurring_in_conn(&mut conn, "w", true, "2025-01-10").unwrap();
+        assert!(res.deleted);
+        assert_eq!(res.expenses_deleted, 2);
+        let remaining: Vec<(String, Option<String>)> = {
+            let mut stmt = conn.prepare("SELECT date, recurringId FROM expenses ORDER BY date").unwrap();
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+            rows.collect::<Result<_, _>>().unwrap()
+        };
+        assert_eq!(
+            remaining,
+            vec![("2025-01-01".to_string(), None), ("2025-01-08".to_string(), None)]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Global search over invoices, clients and expenses, backed by one FTS5 table kept in sync by
+//! triggers. Text is indexed as written; Serbian Latin and Cyrillic are bridged at query time by
+//! searching every term in both scripts, and the tokenizer folds case and diacritics.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::DbState;
+
+/// Search index, the views that shape each entity into a document, and the sync triggers.
+/// Created by the v27 migration (and for new databases); `SEARCH_BACKFILL_SQL` fills it once.
+pub(crate) const SEARCH_INDEX_SQL: &str = r#"
+    CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+        kind UNINDEXED,
+        ref_id UNINDEXED,
+        date UNINDEXED,
+        title,
+        body,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+
+    CREATE VIEW IF NOT EXISTS search_invoice_docs AS
+    SELECT
+        id,
+        issueDate AS date,
+        invoiceNumber || ' · ' || coalesce(json_extract(doc, '$.clientName'), '') AS title,
+        trim(
+            coalesce(json_extract(doc, '$.notes'), '') || ' ' ||
+            coalesce((SELECT group_concat(json_extract(it.value, '$.description'), ' ')
+                      FROM json_each(doc, '$.items') AS it), '')
+        ) AS body
+    FROM (
+        SELECT id, issueDate, invoiceNumber, CASE WHEN json_valid(data_json) THEN data_json END AS doc
+        FROM invoices
+    );
+
+    CREATE VIEW IF NOT EXISTS search_client_docs AS
+    SELECT id, NULL AS date, name AS title, trim(coalesce(pib, '') || ' ' || coalesce(email, '')) AS body
+    FROM clients;
+
+    CREATE VIEW IF NOT EXISTS search_expense_docs AS
+    SELECT id, date, title, coalesce(notes, '') AS body
+    FROM expenses
+    WHERE deletedAt IS NULL;
+
+    CREATE TRIGGER IF NOT EXISTS search_invoices_ai AFTER INSERT ON invoices BEGIN
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'invoice', id, date, title, body FROM search_invoice_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_invoices_au AFTER UPDATE ON invoices BEGIN
+        DELETE FROM search_index WHERE kind = 'invoice' AND ref_id = OLD.id;
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'invoice', id, date, title, body FROM search_invoice_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_invoices_ad AFTER DELETE ON invoices BEGIN
+        DELETE FROM search_index WHERE kind = 'invoice' AND ref_id = OLD.id;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_clients_ai AFTER INSERT ON clients BEGIN
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'client', id, date, title, body FROM search_client_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_clients_au AFTER UPDATE ON clients BEGIN
+        DELETE FROM search_index WHERE kind = 'client' AND ref_id = OLD.id;
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'client', id, date, title, body FROM search_client_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_clients_ad AFTER DELETE ON clients BEGIN
+        DELETE FROM search_index WHERE kind = 'client' AND ref_id = OLD.id;
+    END;
+
+    CREATE TRIGGER IF NOT EXISTS search_expenses_ai AFTER INSERT ON expenses BEGIN
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'expense', id, date, title, body FROM search_expense_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_expenses_au AFTER UPDATE ON expenses BEGIN
+        DELETE FROM search_index WHERE kind = 'expense' AND ref_id = OLD.id;
+        INSERT INTO search_index (kind, ref_id, date, title, body)
+        SELECT 'expense', id, date, title, body FROM search_expense_docs WHERE id = NEW.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS search_expenses_ad AFTER DELETE ON expenses BEGIN
+        DELETE FROM search_index WHERE kind = 'expense' AND ref_id = OLD.id;
+    END;
+"#;
+
+/// Indexes rows that existed before the triggers.
+pub(crate) const SEARCH_BACKFILL_SQL: &str = r#"
+    DELETE FROM search_index;
+    INSERT INTO search_index (kind, ref_id, date, title, body)
+    SELECT 'invoice', id, date, title, body FROM search_invoice_docs;
+    INSERT INTO search_index (kind, ref_id, date, title, body)
+    SELECT 'client', id, date, title, body FROM search_client_docs;
+    INSERT INTO search_index (kind, ref_id, date, title, body)
+    SELECT 'expense', id, date, title, body FROM search_expense_docs;
+"#;
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchKind {
+    Invoice,
+    Client,
+    Expense,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub id: String,
+    /// Invoice number and client, client name, or expense title.
+    pub title: String,
+    /// Excerpt of the notes, item descriptions, PIB or email around the match.
+    pub snippet: String,
+    /// Issue date of invoices and date of expenses (`YYYY-MM-DD`); none for clients.
+    pub date: Option<String>,
+}
+
+const CYRILLIC: [(char, &str); 30] = [
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('ђ', "đ"),
+    ('е', "e"),
+    ('ж', "ž"),
+    ('з', "z"),
+    ('и', "i"),
+    ('ј', "j"),
+    ('к', "k"),
+    ('л', "l"),
+    ('љ', "lj"),
+    ('м', "m"),
+    ('н', "n"),
+    ('њ', "nj"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('ћ', "ć"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "h"),
+    ('ц', "c"),
+    ('ч', "č"),
+    ('џ', "dž"),
+    ('ш', "š"),
+];
+
+/// Serbian Cyrillic to Latin; other characters are kept. Expects lowercase input.
+pub(crate) fn to_latin(term: &str) -> String {
+    term.chars()
+        .map(|c| match CYRILLIC.iter().find(|(cyr, _)| *cyr == c) {
+            Some((_, lat)) => lat.to_string(),
+            None => c.to_string(),
+        })
+        .collect()
+}
+
+/// Serbian Latin to Cyrillic, reading the digraphs lj, nj and dž as one letter. Expects
+/// lowercase input.
+fn to_cyrillic(term: &str) -> String {
+    let mut out = String::with_capacity(term.len() * 2);
+    let mut rest = term;
+    'outer: while let Some(c) = rest.chars().next() {
+        for (cyr, lat) in CYRILLIC.iter().filter(|(_, lat)| lat.chars().count() == 2) {
+            if rest.starts_with(lat) {
+                out.push(*cyr);
+                rest = &rest[lat.len()..];
+                continue 'outer;
+            }
+        }
+        let mapped = CYRILLIC
+            .iter()
+            .find(|(_, lat)| lat.chars().eq(std::iter::once(c)))
+            .map(|(cyr, _)| *cyr);
+        out.push(mapped.unwrap_or(c));
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/// FTS5 query where every word must match as a prefix, in either script. `None` when the query
+/// has no letters or digits.
+fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            let t = t.to_lowercase();
+            let latin = to_latin(&t);
+            let cyrillic = to_cyrillic(&latin);
+            if latin == cyrillic {
+                format!("\"{latin}\"*")
+            } else {
+                format!("(\"{latin}\"* OR \"{cyrillic}\"*)")
+            }
+        })
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" AND "))
+}
+
+/// Best matches first; title hits weigh more than hits in the body. Archived clients are left out
+/// unless `include_archived` is set; their invoices are still found.
+pub(crate) fn search(
+    conn: &Connection,
+    query: &str,
+    limit: u32,
+    include_archived: bool,
+) -> Result<Vec<SearchResult>, rusqlite::Error> {
+    let Some(expr) = match_expression(query) else {
+        return Ok(Vec::new());
+    };
+    let mut stmt = conn.prepare(
+        "SELECT kind, ref_id, title, snippet(search_index, 4, '', '', '…', 12), date
+         FROM search_index
+         WHERE search_index MATCH ?1
+           AND (?3 OR kind <> 'client' OR ref_id IN (SELECT id FROM clients WHERE archived = 0))
+         ORDER BY bm25(search_index, 0.0, 0.0, 0.0, 10.0, 1.0)
+         LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![expr, limit.clamp(1, MAX_LIMIT), include_archived], |r| {
+        let kind = match r.get::<_, String>(0)?.as_str() {
+            "invoice" => SearchKind::Invoice,
+            "client" => SearchKind::Client,
+            _ => SearchKind::Expense,
+        };
+        Ok(SearchResult {
+            kind,
+            id: r.get(1)?,
+            title: r.get(2)?,
+            snippet: r.get(3)?,
+            date: r.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[tauri::command]
+pub(crate) async fn global_search(
+    state: tauri::State<'_, DbState>,
+    query: String,
+    limit: Option<u32>,
+    include_archived: Option<bool>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let include_archived = include_archived.unwrap_or(false);
+    state
+        .with_read("global_search", move |conn| search(conn, &query, limit, include_archived))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn hits(conn: &Connection, query: &str) -> Vec<(SearchKind, String)> {
+        search(conn, query, 20, false).unwrap().into_iter().map(|r| (r.kind, r.id)).collect()
+    }
+
+    #[test]
+    fn transliterates_serbian_terms() {
+        assert_eq!(to_latin("љиљана џеп"), "ljiljana džep");
+        assert_eq!(to_cyrillic("ljiljana džep"), "љиљана џеп");
+        assert_eq!(
+            match_expression("Čačak 2024-001").as_deref(),
+            Some("(\"čačak\"* OR \"чачак\"*) AND \"2024\"* AND \"001\"*")
+        );
+        assert_eq!(match_expression(" \"*- "), None);
+    }
+
+    #[test]
+    fn finds_entities_across_scripts_and_follows_changes() {
+        let conn = setup();
+        conn.execute_batch(
+            r#"INSERT INTO clients (id, name, pib, address, email, createdAt, data_json)
+               VALUES ('c1', 'Београдска Фирма', '100000024', '', 'office@firma.rs', 't', '{}');
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+               VALUES ('i1', '2024-017', 'c1', '2024-03-01', 'RSD', 1, 't',
+                       '{"clientName":"Београдска Фирма","notes":"Hvala na saradnji","items":[{"description":"Održavanje sajta"}]}');
+               INSERT INTO expenses (id, title, amount, currency, date, notes, createdAt)
+               VALUES ('e1', 'Zakup kancelarije', 100, 'RSD', '2024-03-02', 'Čačak, mart', 't');"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hits(&conn, "beogradska"),
+            vec![(SearchKind::Client, "c1".to_string()), (SearchKind::Invoice, "i1".to_string())]
+        );
+        assert_eq!(hits(&conn, "ОДРЖАВАЊЕ"), vec![(SearchKind::Invoice, "i1".to_string())]);
+        assert_eq!(hits(&conn, "cacak"), vec![(SearchKind::Expense, "e1".to_string())]);
+        assert_eq!(hits(&conn, "2024-017"), vec![(SearchKind::Invoice, "i1".to_string())]);
+        assert_eq!(hits(&conn, "100000024"), vec![(SearchKind::Client, "c1".to_string())]);
+
+        let result = &search(&conn, "sajt", 20, false).unwrap()[0];
+        assert_eq!(result.title, "2024-017 · Београдска Фирма");
+        assert_eq!(result.date.as_deref(), Some("2024-03-01"));
+
+        conn.execute_batch(
+            "UPDATE expenses SET deletedAt = 't' WHERE id = 'e1';
+             UPDATE clients SET name = 'Nova Firma' WHERE id = 'c1';
+             DELETE FROM invoices WHERE id = 'i1';",
+        )
+        .unwrap();
+        assert!(hits(&conn, "cacak").is_empty());
+        assert!(hits(&conn, "beogradska").is_empty());
+        assert_eq!(hits(&conn, "нова"), vec![(SearchKind::Client, "c1".to_string())]);
+    }
+
+    #[test]
+    fn archived_clients_are_found_only_when_asked_for() {
+        let conn = setup();
+        conn.execute_batch(
+            r#"INSERT INTO clients (id, name, pib, address, email, createdAt, data_json, archived)
+               VALUES ('c1', 'Stara Firma', '', '', '', 't', '{}', 1),
+                      ('c2', 'Stara Radnja', '', '', '', 't', '{}', 0);
+               INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, currency, totalAmount, createdAt, data_json)
+               VALUES ('i1', '2024-001', 'c1', '2024-03-01', 'RSD', 1, 't', '{"clientName":"Stara Firma"}');"#,
+        )
+        .unwrap();
+
+        assert_eq!(hits(&conn, "stara firma"), vec![(SearchKind::Invoice, "i1".to_string())]);
+        assert_eq!(hits(&conn, "stara radnja"), vec![(SearchKind::Client, "c2".to_string())]);
+        let with_archived = search(&conn, "stara firma", 20, true).unwrap();
+        assert_eq!(
+            with_archived.into_iter().map(|r| (r.kind, r.id)).collect::<Vec<_>>(),
+            vec![(SearchKind::Client, "c1".to_string()), (SearchKind::Invoice, "i1".to_string())]
+        );
+
+        conn.execute("UPDATE clients SET archived = 0 WHERE id = 'c1'", []).unwrap();
+        assert_eq!(hits(&conn, "stara firma").len(), 2);
+    }
+}
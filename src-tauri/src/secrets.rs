@@ -0,0 +1,96 @@
+//! SMTP and webhook secrets in the OS credential store (Keychain, Windows Credential Manager,
+//! Secret Service). The database only ever holds [`KEYRING_SENTINEL`] in their place.
+
+const KEYRING_SERVICE: &str = "com.dstankovski.paushaler";
+
+/// Stored in the settings column and in `data_json` once the real value is in the keyring.
+pub(crate) const KEYRING_SENTINEL: &str = "__keyring__";
+
+/// A secret kept in the keyring: account name plus a label and the settings tab where it is
+/// entered, both used in error messages.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Secret {
+    account: &'static str,
+    label: &'static str,
+    tab: &'static str,
+}
+
+pub(crate) const SMTP_PASSWORD: Secret = Secret {
+    account: "smtp-password",
+    label: "SMTP password",
+    tab: "Email",
+};
+pub(crate) const SMTP_OAUTH_CLIENT_SECRET: Secret = Secret {
+    account: "smtp-oauth-client-secret",
+    label: "OAuth2 client secret",
+    tab: "Email",
+};
+pub(crate) const SMTP_OAUTH_REFRESH_TOKEN: Secret = Secret {
+    account: "smtp-oauth-refresh-token",
+    label: "OAuth2 refresh token",
+    tab: "Email",
+};
+pub(crate) const WEBHOOK_SECRET: Secret = Secret {
+    account: "webhook-secret",
+    label: "Webhook secret",
+    tab: "Webhooks",
+};
+
+fn entry(secret: Secret) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, secret.account)
+        .map_err(|e| format!("OS credential store is unavailable: {e}"))
+}
+
+pub(crate) fn store(secret: Secret, value: &str) -> Result<(), String> {
+    crate::logging::register_secret(value);
+    entry(secret)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to save {} to the OS credential store: {e}", secret.label))
+}
+
+/// Secrets from a settings change, held back until the settings row that points at them with
+/// [`KEYRING_SENTINEL`] is committed. A rejected or rolled-back change then leaves the keyring
+/// untouched.
+#[must_use = "store the staged secrets once the settings are committed"]
+#[derive(Default)]
+pub(crate) struct StagedSecrets(Vec<(Secret, String)>);
+
+impl StagedSecrets {
+    pub(crate) fn add(&mut self, secret: Secret, value: &str) {
+        self.0.push((secret, value.to_string()));
+    }
+
+    pub(crate) fn store(self) -> Result<(), String> {
+        for (secret, value) in self.0 {
+            store(secret, &value)?;
+        }
+        Ok(())
+    }
+}
+
+fn load(secret: Secret) -> Result<String, String> {
+    entry(secret)?.get_password().map_err(|e| match e {
+        keyring::Error::NoEntry => format!(
+            "{} is missing from the OS credential store; enter it again (Settings → {}).",
+            secret.label, secret.tab
+        ),
+        e => format!("Failed to read {} from the OS credential store: {e}", secret.label),
+    })
+}
+
+/// True when the value is a plaintext secret that still has to be moved to the keyring.
+pub(crate) fn is_plaintext(stored: &str) -> bool {
+    !stored.is_empty() && stored != KEYRING_SENTINEL
+}
+
+/// The secret to use: the keyring value for the sentinel, otherwise the stored value
+/// (a legacy plaintext password that could not be migrated yet).
+pub(crate) fn resolve(secret: Secret, stored: &str) -> Result<String, String> {
+    let value = if stored == KEYRING_SENTINEL {
+        load(secret)?
+    } else {
+        stored.to_string()
+    };
+    crate::logging::register_secret(&value);
+    Ok(value)
+}
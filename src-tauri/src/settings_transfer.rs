@@ -0,0 +1,189 @@
+//! Settings file for setting up Pausaler on another computer: the company, invoice and email
+//! settings plus the logo, without any business data. Importing goes through the same path as
+//! `update_settings`, so a file can't store anything the settings page would reject.
+
+use std::path::Path;
+
+use base64::Engine as _;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::AppError;
+use crate::company_logo::{store_logo, write_logo_setting};
+use crate::data_events::{DataEntity, DataOp};
+use crate::{
+    apply_settings_patch, now_iso, read_logo_bytes, read_settings_from_conn, resolve_app_data_root, secrets,
+    settings_for_ui, validation_to_sql_error, write_text_file, DbState, Settings, SettingsPatch, SETTINGS_ID,
+};
+
+pub(crate) const SETTINGS_FILE_FORMAT: &str = "pausaler-settings";
+pub(crate) const SETTINGS_FILE_VERSION: u32 = 1;
+
+/// SMTP login fields and the webhook secret, written only when the export asks for them.
+const SMTP_CREDENTIAL_FIELDS: [&str; 5] =
+    ["smtpUser", "smtpPassword", "smtpOauthClientSecret", "smtpOauthRefreshToken", "webhookSecret"];
+
+/// Fields that describe this computer's copy (the logo travels as `logoPng`).
+const LOCAL_FIELDS: [&str; 4] = ["logoUrl", "smtpPasswordConfigured", "smtpOauthConfigured", "webhookSecretConfigured"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFile {
+    pub format: String,
+    pub format_version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    /// `Settings` keys; a key that is missing keeps the importing computer's value.
+    pub settings: Map<String, Value>,
+    /// Base64 PNG of the company logo.
+    #[serde(default)]
+    pub logo_png: Option<String>,
+}
+
+/// The plaintext secret, or empty when none is stored.
+fn resolve_secret(secret: secrets::Secret, stored: &str) -> Result<String, String> {
+    if stored.is_empty() {
+        return Ok(String::new());
+    }
+    secrets::resolve(secret, stored)
+}
+
+fn build_settings_file(mut s: Settings, logo: Option<&[u8]>, include_smtp: bool) -> Result<SettingsFile, String> {
+    if include_smtp {
+        s.smtp_password = resolve_secret(secrets::SMTP_PASSWORD, &s.smtp_password)?;
+        s.smtp_oauth_client_secret = resolve_secret(secrets::SMTP_OAUTH_CLIENT_SECRET, &s.smtp_oauth_client_secret)?;
+        s.smtp_oauth_refresh_token = resolve_secret(secrets::SMTP_OAUTH_REFRESH_TOKEN, &s.smtp_oauth_refresh_token)?;
+        s.webhook_secret = resolve_secret(secrets::WEBHOOK_SECRET, &s.webhook_secret)?;
+    }
+    let Value::Object(mut settings) = serde_json::to_value(&s).map_err(|e| e.to_string())? else {
+        return Err("Settings did not serialize to an object.".to_string());
+    };
+    for field in LOCAL_FIELDS {
+        settings.remove(field);
+    }
+    if !include_smtp {
+        for field in SMTP_CREDENTIAL_FIELDS {
+            settings.remove(field);
+        }
+    }
+    Ok(SettingsFile {
+        format: SETTINGS_FILE_FORMAT.to_string(),
+        format_version: SETTINGS_FILE_VERSION,
+        exported_at: now_iso(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings,
+        logo_png: logo.map(|b| base64::engine::general_purpose::STANDARD.encode(b)),
+    })
+}
+
+/// Parses a settings file; only version 1 exists so far.
+fn parse_settings_file(text: &str) -> Result<SettingsFile, String> {
+    let doc: Value = serde_json::from_str(text).map_err(|e| format!("Invalid settings file: {e}"))?;
+    if doc.get("format").and_then(Value::as_str) != Some(SETTINGS_FILE_FORMAT) {
+        return Err("Not a Pausaler settings file.".to_string());
+    }
+    let version = doc.get("formatVersion").and_then(Value::as_u64).unwrap_or(0);
+    match version {
+        1 => serde_json::from_value(doc).map_err(|e| format!("Invalid settings file: {e}")),
+        v if v > u64::from(SETTINGS_FILE_VERSION) => Err(format!(
+            "This settings file was created by a newer version of Pausaler (format v{v}; this version reads up to v{SETTINGS_FILE_VERSION}). Update the app first."
+        )),
+        v => Err(format!("Unsupported settings file version {v}.")),
+    }
+}
+
+/// Applies the file in one transaction; the logo is replaced only when the settings are valid.
+fn import_settings_in_conn(conn: &mut Connection, file: SettingsFile, root: &Path) -> Result<Settings, rusqlite::Error> {
+    let patch: SettingsPatch = serde_json::from_value(Value::Object(file.settings))
+        .map_err(|e| validation_to_sql_error(format!("Invalid settings file: {e}")))?;
+    let tx = conn.transaction()?;
+    let (_, staged) = apply_settings_patch(&tx, patch)?;
+    if let Some(logo) = file.logo_png.filter(|l| !l.trim().is_empty()) {
+        let path = store_logo(root, &logo).map_err(validation_to_sql_error)?;
+        write_logo_setting(&tx, &path.to_string_lossy())?;
+    }
+    let settings = read_settings_from_conn(&tx)?;
+    tx.commit()?;
+    staged.store().map_err(validation_to_sql_error)?;
+    Ok(settings)
+}
+
+/// Writes the settings and the logo to `path`. SMTP login details are left out unless
+/// `include_smtp` is set; then they are written in plain text.
+#[tauri::command]
+pub(crate) async fn export_settings(
+    state: tauri::State<'_, DbState>,
+    path: String,
+    include_smtp: bool,
+) -> Result<String, AppError> {
+    let settings = state.with_read("export_settings", read_settings_from_conn).await?;
+    let file = tauri::async_runtime::spawn_blocking(move || {
+        let logo_url = settings.logo_url.trim().to_string();
+        let logo = if logo_url.is_empty() { None } else { Some(read_logo_bytes(&logo_url)?) };
+        build_settings_file(settings, logo.as_deref(), include_smtp)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    write_text_file(Path::new(&path), &json)?;
+    Ok(path)
+}
+
+/// Loads a file written by `export_settings`. Settings missing from the file keep their values.
+#[tauri::command]
+pub(crate) async fn import_settings(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    path: String,
+) -> Result<Settings, AppError> {
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read settings file: {e}"))?;
+    let file = parse_settings_file(&text)?;
+    let root = resolve_app_data_root(&app)?;
+    state
+        .with_write_notify("import_settings", move |conn, changes| {
+            let settings = import_settings_in_conn(conn, file, &root)?;
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            Ok(settings)
+        })
+        .await
+        .map(settings_for_ui)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn settings_round_trip_without_smtp_credentials() {
+        let mut s = crate::default_settings();
+        s.company_name = "Firma".to_string();
+        s.smtp_user = "office@example.com".to_string();
+        s.smtp_port = 465;
+        s.smtp_tls_mode = None;
+        let text = serde_json::to_string(&build_settings_file(s, None, false).unwrap()).unwrap();
+        assert!(!text.contains("office@example.com"));
+        assert!(!text.contains("logoUrl"));
+
+        let root = std::env::temp_dir().join(format!("pausaler-settings-{}", uuid::Uuid::new_v4()));
+        let mut conn = open_db();
+        let imported = import_settings_in_conn(&mut conn, parse_settings_file(&text).unwrap(), &root).unwrap();
+        assert_eq!(imported.company_name, "Firma");
+        assert_eq!(imported.smtp_user, "");
+        assert_eq!(imported.smtp_tls_mode, Some(crate::SmtpTlsMode::Implicit));
+
+        let mut doc: Value = serde_json::from_str(&text).unwrap();
+        doc["formatVersion"] = Value::from(SETTINGS_FILE_VERSION + 1);
+        let err = parse_settings_file(&doc.to_string()).unwrap_err();
+        assert!(err.contains("newer version"), "{err}");
+        assert!(parse_settings_file("{\"format\":\"pausaler-data\",\"formatVersion\":1}").is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! Checks that the company settings are complete enough to issue invoices: required fields,
+//! PIB / matični broj check digits, the bank account control number, the sender address,
+//! the invoice counter and the language.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use crate::locales::is_available_language;
+use crate::tax_ids::{check_mb, check_pib};
+use crate::{read_settings_from_conn, DbState, Settings};
+
+/// Upper bound for `next_invoice_number`; anything above is a typo, not a real counter.
+pub(crate) const MAX_NEXT_INVOICE_NUMBER: i64 = 999_999_999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SettingsError {
+    CompanyNameRequired,
+    PibRequired,
+    MbRequired,
+    BankAccountRequired,
+    BankAccountFormat,
+    BankAccountChecksum,
+    IbanInvalid,
+    SwiftInvalid,
+    CurrencyInvalid,
+    SmtpFromInvalid,
+    NextInvoiceNumberRange,
+    NextQuoteNumberRange,
+    LanguageUnsupported,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsMessagesLocale {
+    company_name_required: String,
+    pib_required: String,
+    mb_required: String,
+    bank_account_required: String,
+    bank_account_format: String,
+    bank_account_checksum: String,
+    iban_invalid: String,
+    swift_invalid: String,
+    currency_invalid: String,
+    smtp_from_invalid: String,
+    next_invoice_number_range: String,
+    next_quote_number_range: String,
+    language_unsupported: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SettingsMessagesFile {
+    sr: SettingsMessagesLocale,
+    en: SettingsMessagesLocale,
+}
+
+static SETTINGS_MESSAGES: OnceLock<Option<SettingsMessagesFile>> = OnceLock::new();
+
+impl SettingsError {
+    /// Localized message for `value`; `lang` follows the app language ("en…" or Serbian).
+    pub(crate) fn message(self, lang: &str, value: &str) -> String {
+        let file = SETTINGS_MESSAGES.get_or_init(|| {
+            serde_json::from_str(include_str!("../../src/shared/settingsValidationMessages.json")).ok()
+        });
+        let Some(file) = file else {
+            return format!("Invalid settings value \"{value}\".");
+        };
+        let loc = if lang.to_ascii_lowercase().starts_with("en") { &file.en } else { &file.sr };
+        let template = match self {
+            SettingsError::CompanyNameRequired => &loc.company_name_required,
+            SettingsError::PibRequired => &loc.pib_required,
+            SettingsError::MbRequired => &loc.mb_required,
+            SettingsError::BankAccountRequired => &loc.bank_account_required,
+            SettingsError::BankAccountFormat => &loc.bank_account_format,
+            SettingsError::BankAccountChecksum => &loc.bank_account_checksum,
+            SettingsError::IbanInvalid => &loc.iban_invalid,
+            SettingsError::SwiftInvalid => &loc.swift_invalid,
+            SettingsError::CurrencyInvalid => &loc.currency_invalid,
+            SettingsError::SmtpFromInvalid => &loc.smtp_from_invalid,
+            SettingsError::NextInvoiceNumberRange => &loc.next_invoice_number_range,
+            SettingsError::NextQuoteNumberRange => &loc.next_quote_number_range,
+            SettingsError::LanguageUnsupported => &loc.language_unsupported,
+        };
+        template
+            .replace("{value}", value)
+            .replace("{max}", &MAX_NEXT_INVOICE_NUMBER.to_string())
+    }
+}
+
+/// Validates a Serbian bank account (bank 3 digits, account up to 13, control 2; ISO 7064
+/// MOD 97-10) and returns it in the full `3-13-2` form. The short form `160-123456-54` is
+/// accepted and padded.
+pub(crate) fn check_bank_account(v: &str) -> Result<String, SettingsError> {
+    let compact: String = v.chars().filter(|c| !c.is_whitespace()).collect();
+    let (bank, account, control) = match compact.split('-').collect::<Vec<_>>()[..] {
+        [bank, account, control] => (bank, account, control),
+        [digits] if digits.len() == 18 && digits.is_ascii() => (&digits[..3], &digits[3..16], &digits[16..]),
+        _ => return Err(SettingsError::BankAccountFormat),
+    };
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if bank.len() != 3 || account.len() > 13 || control.len() != 2 {
+        return Err(SettingsError::BankAccountFormat);
+    }
+    if ![bank, account, control].into_iter().all(all_digits) {
+        return Err(SettingsError::BankAccountFormat);
+    }
+    let full = format!("{bank}{account:0>13}{control}");
+    let remainder = full.bytes().fold(0u32, |acc, b| (acc * 10 + u32::from(b - b'0')) % 97);
+    if remainder != 1 {
+        return Err(SettingsError::BankAccountChecksum);
+    }
+    Ok(format!("{bank}-{account:0>13}-{control}"))
+}
+
+/// Validates an IBAN (country, check digits, ISO 7064 MOD 97-10 over the rearranged number) and
+/// returns it without spaces, upper-cased.
+pub(crate) fn check_iban(v: &str) -> Result<String, SettingsError> {
+    let iban: String = v.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase();
+    let b = iban.as_bytes();
+    if !(15..=34).contains(&b.len())
+        || !b[..2].iter().all(u8::is_ascii_uppercase)
+        || !b[2..4].iter().all(u8::is_ascii_digit)
+        || !b.iter().all(u8::is_ascii_alphanumeric)
+    {
+        return Err(SettingsError::IbanInvalid);
+    }
+    let remainder = b[4..].iter().chain(&b[..4]).fold(0u32, |acc, &c| {
+        if c.is_ascii_digit() {
+            (acc * 10 + u32::from(c - b'0')) % 97
+        } else {
+            (acc * 100 + u32::from(c - b'A' + 10)) % 97
+        }
+    });
+    if remainder != 1 {
+        return Err(SettingsError::IbanInvalid);
+    }
+    Ok(iban)
+}
+
+/// Validates a SWIFT/BIC code: bank (4 letters), country (2 letters), location (2) and an
+/// optional branch (3). Returns it upper-cased.
+pub(crate) fn check_swift(v: &str) -> Result<String, SettingsError> {
+    let swift = v.trim().to_ascii_uppercase();
+    let b = swift.as_bytes();
+    if !matches!(b.len(), 8 | 11) || !b[..6].iter().all(u8::is_ascii_uppercase) || !b.iter().all(u8::is_ascii_alphanumeric) {
+        return Err(SettingsError::SwiftInvalid);
+    }
+    Ok(swift)
+}
+
+pub(crate) fn check_next_invoice_number(v: i64) -> Result<i64, SettingsError> {
+    if (1..=MAX_NEXT_INVOICE_NUMBER).contains(&v) {
+        Ok(v)
+    } else {
+        Err(SettingsError::NextInvoiceNumberRange)
+    }
+}
+
+pub(crate) fn check_next_quote_number(v: i64) -> Result<i64, SettingsError> {
+    check_next_invoice_number(v).map_err(|_| SettingsError::NextQuoteNumberRange)
+}
+
+/// One invalid or missing field; `field` is the camelCase `Settings` key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsProblem {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsValidation {
+    pub valid: bool,
+    pub problems: Vec<SettingsProblem>,
+}
+
+/// Every problem that would stop invoices from being issued, in form order.
+pub(crate) fn settings_problems(s: &Settings) -> Vec<SettingsProblem> {
+    let lang = s.language.as_str();
+    let mut problems = Vec::new();
+    let mut push = |field: &str, message: String| {
+        problems.push(SettingsProblem {
+            field: field.to_string(),
+            message,
+        });
+    };
+
+    if s.company_name.trim().is_empty() {
+        push("companyName", SettingsError::CompanyNameRequired.message(lang, ""));
+    }
+    let pib = s.pib.trim();
+    if pib.is_empty() {
+        push("pib", SettingsError::PibRequired.message(lang, ""));
+    } else if let Err(e) = check_pib(pib) {
+        push("pib", e.message(lang, pib));
+    }
+    let mb = s.registration_number.trim();
+    if mb.is_empty() {
+        push("registrationNumber", SettingsError::MbRequired.message(lang, ""));
+    } else if let Err(e) = check_mb(mb) {
+        push("registrationNumber", e.message(lang, mb));
+    }
+    let bank_account = s.bank_account.trim();
+    if bank_account.is_empty() {
+        push("bankAccount", SettingsError::BankAccountRequired.message(lang, ""));
+    } else if let Err(e) = check_bank_account(bank_account) {
+        push("bankAccount", e.message(lang, bank_account));
+    }
+    let smtp_from = s.smtp_from.trim();
+    if !smtp_from.is_empty() && smtp_from.parse::<lettre::Address>().is_err() {
+        push("smtpFrom", SettingsError::SmtpFromInvalid.message(lang, smtp_from));
+    }
+    if let Err(e) = check_next_invoice_number(s.next_invoice_number) {
+        push("nextInvoiceNumber", e.message(lang, &s.next_invoice_number.to_string()));
+    }
+    let language = s.language.trim();
+    if !is_available_language(language) {
+        push("language", SettingsError::LanguageUnsupported.message(lang, language));
+    }
+    problems
+}
+
+/// Checks the stored settings; the settings page shows the problems next to their fields.
+#[tauri::command]
+pub(crate) async fn validate_settings(state: tauri::State<'_, DbState>) -> Result<SettingsValidation, AppError> {
+    let problems = state
+        .with_read("validate_settings", |conn| Ok(settings_problems(&read_settings_from_conn(conn)?)))
+        .await?;
+    Ok(SettingsValidation {
+        valid: problems.is_empty(),
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bank_account_control_digits() {
+        assert_eq!(check_bank_account("160-0000000123456-54"), Ok("160-0000000123456-54".to_string()));
+        assert_eq!(check_bank_account("160-123456-54"), Ok("160-0000000123456-54".to_string()));
+        assert_eq!(check_bank_account("160 0000000123456 54"), Ok("160-0000000123456-54".to_string()));
+        assert_eq!(check_bank_account("160-0000000123456-55"), Err(SettingsError::BankAccountChecksum));
+        assert_eq!(check_bank_account("16-123456-54"), Err(SettingsError::BankAccountFormat));
+        assert_eq!(check_bank_account("160-12345678901234-54"), Err(SettingsError::BankAccountFormat));
+        assert_eq!(check_bank_account("160-12a456-54"), Err(SettingsError::BankAccountFormat));
+    }
+
+    #[test]
+    fn iban_and_swift_format() {
+        assert_eq!(check_iban("rs35 2600 0560 1001 6113 79"), Ok("RS35260005601001611379".to_string()));
+        assert_eq!(check_iban("DE89370400440532013000"), Ok("DE89370400440532013000".to_string()));
+        assert_eq!(check_iban("RS35260005601001611378"), Err(SettingsError::IbanInvalid));
+        assert_eq!(check_iban("RS35"), Err(SettingsError::IbanInvalid));
+        assert_eq!(check_swift(" aikbrs22 "), Ok("AIKBRS22".to_string()));
+        assert_eq!(check_swift("DEUTDEFF500"), Ok("DEUTDEFF500".to_string()));
+        assert_eq!(check_swift("AIKB22"), Err(SettingsError::SwiftInvalid));
+        assert_eq!(check_swift("AIK1RS22"), Err(SettingsError::SwiftInvalid));
+    }
+
+    #[test]
+    fn reports_each_problem_field_in_the_settings_language() {
+        let mut s = crate::default_settings();
+        s.language = "en".to_string();
+        s.pib = "100000001".to_string();
+        s.smtp_from = "not an address".to_string();
+        s.next_invoice_number = 0;
+        let fields: Vec<String> = settings_problems(&s).into_iter().map(|p| p.field).collect();
+        assert_eq!(
+            fields,
+            ["companyName", "pib", "registrationNumber", "bankAccount", "smtpFrom", "nextInvoiceNumber"]
+        );
+        assert!(settings_problems(&s)[1].message.contains("check digit"));
+
+        s.company_name = "Firma".to_string();
+        s.pib = "101134702".to_string();
+        s.registration_number = "07015410".to_string();
+        s.bank_account = "160-123456-54".to_string();
+        s.smtp_from = "office@example.com".to_string();
+        s.next_invoice_number = 42;
+        assert!(settings_problems(&s).is_empty());
+
+        s.language = "fr".to_string();
+        let problems = settings_problems(&s);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].message.contains("nije podržan"), "{}", problems[0].message);
+    }
+}
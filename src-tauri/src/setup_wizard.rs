@@ -0,0 +1,241 @@
+//! First-run setup as resumable steps. Each step saves only its own settings fields through
+//! `apply_settings_patch`; the list of completed steps is kept in `app_meta` so a restart in the
+//! middle of the wizard continues where it stopped. `finish_setup` flips `is_configured`, which
+//! runs the full settings validation.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::AppError;
+use crate::data_events::{DataEntity, DataOp};
+use crate::settings_validation::settings_problems;
+use crate::{
+    app_meta_get, app_meta_set, apply_settings_patch, read_settings_from_conn, validation_to_sql_error, DbState,
+    SettingsPatch, SETTINGS_ID,
+};
+
+/// `app_meta` key holding the completed steps as JSON.
+const SETUP_STATE_KEY: &str = "setup_state";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SetupStep {
+    Company,
+    Numbering,
+    Email,
+    License,
+}
+
+/// Wizard order.
+const SETUP_STEPS: [SetupStep; 4] = [SetupStep::Company, SetupStep::Numbering, SetupStep::Email, SetupStep::License];
+
+impl SetupStep {
+    /// `Settings` keys the step may change.
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            SetupStep::Company => &[
+                "companyName",
+                "registrationNumber",
+                "maticniBroj",
+                "pib",
+                "companyAddressLine",
+                "companyCity",
+                "companyPostalCode",
+                "companyEmail",
+                "companyPhone",
+                "bankAccount",
+                "bankAccounts",
+            ],
+            SetupStep::Numbering => &["invoicePrefix", "nextInvoiceNumber", "defaultCurrency"],
+            SetupStep::Email => &[
+                "smtpHost",
+                "smtpPort",
+                "smtpUser",
+                "smtpPassword",
+                "smtpFrom",
+                "smtpUseTls",
+                "smtpTlsMode",
+                "smtpTimeoutSeconds",
+                "smtpReplyTo",
+                "smtpFromName",
+                "sendCopyToSelf",
+                "smtpAuthMode",
+                "smtpOauthClientId",
+                "smtpOauthClientSecret",
+                "smtpOauthRefreshToken",
+                "smtpOauthTokenEndpoint",
+            ],
+            // Activation is stored by the license commands, not in settings.
+            SetupStep::License => &[],
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            SetupStep::Company => "company",
+            SetupStep::Numbering => "numbering",
+            SetupStep::Email => "email",
+            SetupStep::License => "license",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupState {
+    /// In wizard order.
+    pub completed_steps: Vec<SetupStep>,
+    /// First step not completed yet; `None` once all are done.
+    pub current_step: Option<SetupStep>,
+    pub is_configured: bool,
+}
+
+fn completed_steps(conn: &Connection) -> Result<Vec<SetupStep>, rusqlite::Error> {
+    let stored = app_meta_get(conn, SETUP_STATE_KEY)?.unwrap_or_default();
+    // An unreadable value only means the wizard starts over.
+    let done: Vec<SetupStep> = serde_json::from_str(&stored).unwrap_or_default();
+    Ok(SETUP_STEPS.into_iter().filter(|s| done.contains(s)).collect())
+}
+
+fn read_setup_state(conn: &Connection) -> Result<SetupState, rusqlite::Error> {
+    let completed_steps = completed_steps(conn)?;
+    Ok(SetupState {
+        current_step: SETUP_STEPS.into_iter().find(|s| !completed_steps.contains(s)),
+        completed_steps,
+        is_configured: read_settings_from_conn(conn)?.is_configured.unwrap_or(false),
+    })
+}
+
+/// Saves `payload` (camelCase `Settings` keys of this step only) and marks the step completed.
+/// Problems the settings validation reports for the step's fields reject it.
+fn complete_step_in_conn(
+    conn: &mut Connection,
+    step: SetupStep,
+    payload: Map<String, Value>,
+) -> Result<SetupState, rusqlite::Error> {
+    let fields = step.fields();
+    if let Some(key) = payload.keys().find(|k| !fields.contains(&k.as_str())) {
+        return Err(validation_to_sql_error(format!(
+            "\"{key}\" is not part of the {} setup step.",
+            step.as_str()
+        )));
+    }
+    let patch: SettingsPatch = serde_json::from_value(Value::Object(payload))
+        .map_err(|e| validation_to_sql_error(format!("Invalid {} step: {e}", step.as_str())))?;
+
+    let tx = conn.transaction()?;
+    let (settings, staged) = apply_settings_patch(&tx, patch)?;
+    let problems: Vec<String> = settings_problems(&settings)
+        .into_iter()
+        .filter(|p| fields.contains(&p.field.as_str()))
+        .map(|p| p.message)
+        .collect();
+    if !problems.is_empty() {
+        return Err(validation_to_sql_error(problems.join("\n")));
+    }
+    let mut done = completed_steps(&tx)?;
+    if !done.contains(&step) {
+        done.push(step);
+    }
+    let json = serde_json::to_string(&done).map_err(|e| validation_to_sql_error(e.to_string()))?;
+    app_meta_set(&tx, SETUP_STATE_KEY, &json)?;
+    let state = read_setup_state(&tx)?;
+    tx.commit()?;
+    staged.store().map_err(validation_to_sql_error)?;
+    Ok(state)
+}
+
+#[tauri::command]
+pub(crate) async fn get_setup_state(state: tauri::State<'_, DbState>) -> Result<SetupState, AppError> {
+    state.with_read("get_setup_state", read_setup_state).await
+}
+
+#[tauri::command]
+pub(crate) async fn complete_setup_step(
+    state: tauri::State<'_, DbState>,
+    step: SetupStep,
+    payload: Option<Map<String, Value>>,
+) -> Result<SetupState, AppError> {
+    state
+        .with_write_notify("complete_setup_step", move |conn, changes| {
+            let setup = complete_step_in_conn(conn, step, payload.unwrap_or_default())?;
+            if !step.fields().is_empty() {
+                changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            }
+            Ok(setup)
+        })
+        .await
+}
+
+/// Marks the company as configured. Refused with every settings problem when something an
+/// invoice needs is still missing.
+#[tauri::command]
+pub(crate) async fn finish_setup(state: tauri::State<'_, DbState>) -> Result<SetupState, AppError> {
+    state
+        .with_write_notify("finish_setup", |conn, changes| {
+            let settings = read_settings_from_conn(conn)?;
+            let problems = settings_problems(&settings);
+            if !problems.is_empty() {
+                let messages: Vec<String> = problems.into_iter().map(|p| p.message).collect();
+                return Err(validation_to_sql_error(messages.join("\n")));
+            }
+            let patch = SettingsPatch {
+                is_configured: Some(true),
+                ..Default::default()
+            };
+            let (_, staged) = apply_settings_patch(conn, patch)?;
+            staged.store().map_err(validation_to_sql_error)?;
+            changes.record(DataEntity::Settings, DataOp::Updated, SETTINGS_ID);
+            read_setup_state(conn)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        conn
+    }
+
+    fn payload(v: Value) -> Map<String, Value> {
+        v.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn steps_are_validated_and_persisted_one_at_a_time() {
+        let mut conn = open_db();
+        let start = read_setup_state(&conn).unwrap();
+        assert_eq!(start.current_step, Some(SetupStep::Company));
+        assert!(!start.is_configured);
+
+        let err = complete_step_in_conn(&mut conn, SetupStep::Numbering, payload(serde_json::json!({ "pib": "1" })))
+            .unwrap_err();
+        assert!(err.to_string().contains("\"pib\" is not part"), "{err}");
+
+        // An invalid bank account rejects the step and nothing of it is saved.
+        let company = serde_json::json!({
+            "companyName": "Firma",
+            "pib": "101134702",
+            "registrationNumber": "07015410",
+            "bankAccount": "160-123456-55",
+        });
+        assert!(complete_step_in_conn(&mut conn, SetupStep::Company, payload(company.clone())).is_err());
+        assert_eq!(read_settings_from_conn(&conn).unwrap().company_name, "");
+
+        let mut company = payload(company);
+        company.insert("bankAccount".into(), "160-123456-54".into());
+        complete_step_in_conn(&mut conn, SetupStep::Company, company).unwrap();
+        let state = complete_step_in_conn(&mut conn, SetupStep::Email, Map::new()).unwrap();
+        assert_eq!(state.completed_steps, [SetupStep::Company, SetupStep::Email]);
+        assert_eq!(state.current_step, Some(SetupStep::Numbering));
+        assert_eq!(read_setup_state(&conn).unwrap(), state);
+        assert_eq!(read_settings_from_conn(&conn).unwrap().company_name, "Firma");
+    }
+}
@@ -0,0 +1,166 @@
+//! One running app per user. The first instance writes `instance.lock` into the app data folder
+//! with its PID and a local port it listens on; a second launch finds the lock, asks the first
+//! instance to bring its window to the front and exits, so two processes never share the
+//! database.
+//!
+//! The lock isn't removed on exit. A lock whose PID no longer runs (a crash, or a normal exit) is
+//! stale and replaced; so is one whose PID was reused by another program, which shows as nobody
+//! answering on the port.
+
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+const LOCK_FILE: &str = "instance.lock";
+const FOCUS_MESSAGE: &str = "focus";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct InstanceLock {
+    pid: u32,
+    port: u16,
+}
+
+fn read_lock(path: &Path) -> Option<InstanceLock> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Whether `lock` was left behind: its process has exited, or it is our own PID from an earlier
+/// session (PIDs are reused).
+fn is_stale(lock: &InstanceLock, own_pid: u32) -> bool {
+    lock.pid == own_pid || !pid_alive(lock.pid)
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    // 0 and values beyond i32 would address process groups, not a process.
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // Signal 0 only checks that the process exists; EPERM means it does but belongs to someone else.
+    let signalled = unsafe { libc::kill(pid, 0) == 0 };
+    signalled || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+fn pid_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ACCESS_DENIED};
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+    const STILL_ACTIVE: u32 = 259;
+
+    if pid == 0 {
+        return false;
+    }
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut code = 0u32;
+        let alive = GetExitCodeProcess(handle, &mut code) != 0 && code == STILL_ACTIVE;
+        CloseHandle(handle);
+        alive
+    }
+}
+
+/// Asks the instance behind `lock` to show its window.
+fn signal_running(lock: &InstanceLock) -> std::io::Result<()> {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, lock.port));
+    let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+    writeln!(stream, "{FOCUS_MESSAGE}")
+}
+
+fn focus_main_window(app: &tauri::AppHandle) {
+    let Some(window) = app.webview_windows().into_values().next() else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+fn listen_for_focus(app: tauri::AppHandle, listener: TcpListener) {
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut line = String::new();
+            let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+            if BufReader::new(stream).read_line(&mut line).is_ok() && line.trim() == FOCUS_MESSAGE {
+                tracing::info!(target: "startup", "second launch; focusing the window");
+                focus_main_window(&app);
+            }
+        }
+    });
+}
+
+/// Takes the lock in `root`. `Ok(false)` when another instance is running and was asked to show
+/// its window; the caller should exit.
+pub(crate) fn acquire(app: &tauri::AppHandle, root: &Path) -> Result<bool, String> {
+    let path = root.join(LOCK_FILE);
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+    let own = InstanceLock {
+        pid: std::process::id(),
+        port: listener.local_addr().map_err(|e| e.to_string())?.port(),
+    };
+    fs::create_dir_all(root).map_err(|e| e.to_string())?;
+
+    // `create_new` so two launches at the same moment can't both take the lock.
+    for _ in 0..3 {
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let json = serde_json::to_vec(&own).map_err(|e| e.to_string())?;
+                file.write_all(&json).map_err(|e| e.to_string())?;
+                listen_for_focus(app.clone(), listener);
+                return Ok(true);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if let Some(lock) = read_lock(&path).filter(|l| !is_stale(l, own.pid)) {
+                    match signal_running(&lock) {
+                        Ok(()) => return Ok(false),
+                        Err(e) => tracing::warn!(target: "startup", pid = lock.pid, error = %e, "instance lock holder doesn't answer"),
+                    }
+                }
+                tracing::info!(target: "startup", "replacing a stale instance lock");
+                let _ = fs::remove_file(&path);
+            }
+            Err(e) => return Err(format!("Failed to create {}: {e}", path.display())),
+        }
+    }
+    Err(format!("Could not take the instance lock {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_of_an_exited_process_is_stale() {
+        let dir = std::env::temp_dir().join(format!("pausaler-instance-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LOCK_FILE);
+
+        let running = InstanceLock { pid: std::process::id(), port: 40000 };
+        fs::write(&path, serde_json::to_vec(&running).unwrap()).unwrap();
+        let lock = read_lock(&path).unwrap();
+        assert_eq!(lock, running);
+        assert!(!is_stale(&lock, 1));
+        // Our own PID in the file is a leftover from an earlier session.
+        assert!(is_stale(&lock, std::process::id()));
+
+        // Well above any PID the OS hands out.
+        let crashed = InstanceLock { pid: 2_000_000_000, port: 40000 };
+        assert!(is_stale(&crashed, 1));
+        assert!(is_stale(&InstanceLock { pid: 0, port: 40000 }, 1));
+
+        fs::write(&path, b"not json").unwrap();
+        assert!(read_lock(&path).is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,138 @@
+//! XOAUTH2 for SMTP (Gmail, Office 365): exchanges the stored refresh token for an access
+//! token and caches it until shortly before it expires.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::{secrets, Settings};
+
+/// Refresh this long before the provider's expiry so a send never starts with a stale token.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(120);
+/// Used when the token response has no `expires_in`.
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct CachedToken {
+    key: String,
+    access_token: String,
+    expires_at: Instant,
+}
+
+static TOKEN_CACHE: OnceLock<Mutex<Option<CachedToken>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<Option<CachedToken>> {
+    TOKEN_CACHE.get_or_init(|| Mutex::new(None))
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    /// Some providers (Microsoft) rotate the refresh token on every exchange.
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Token refresh errors point at the OAuth2 settings instead of looking like a send failure.
+fn refresh_error(detail: impl std::fmt::Display) -> String {
+    format!(
+        "SMTP OAuth2 token refresh failed; check client ID, client secret, refresh token and token endpoint (Settings → Email → Authentication): {detail}"
+    )
+}
+
+fn cache_key(s: &Settings) -> String {
+    format!(
+        "{}\n{}\n{}",
+        s.smtp_user.trim(),
+        s.smtp_oauth_client_id.trim(),
+        s.smtp_oauth_token_endpoint.trim()
+    )
+}
+
+/// Drops the cached access token, e.g. after the OAuth2 settings changed or the server rejected it.
+pub(crate) fn invalidate_cached_token() {
+    if let Ok(mut guard) = cache().lock() {
+        *guard = None;
+    }
+}
+
+/// Returns a valid access token, refreshing it through the token endpoint when needed.
+pub(crate) async fn access_token(s: &Settings) -> Result<String, String> {
+    let key = cache_key(s);
+    if let Ok(guard) = cache().lock() {
+        if let Some(cached) = guard.as_ref() {
+            if cached.key == key && Instant::now() + EXPIRY_MARGIN < cached.expires_at {
+                return Ok(cached.access_token.clone());
+            }
+        }
+    }
+
+    let client_secret =
+        secrets::resolve(secrets::SMTP_OAUTH_CLIENT_SECRET, &s.smtp_oauth_client_secret).map_err(refresh_error)?;
+    let refresh_token =
+        secrets::resolve(secrets::SMTP_OAUTH_REFRESH_TOKEN, &s.smtp_oauth_refresh_token).map_err(refresh_error)?;
+
+    let mut form: Vec<(&str, &str)> = vec![
+        ("grant_type", "refresh_token"),
+        ("client_id", s.smtp_oauth_client_id.trim()),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    if !client_secret.is_empty() {
+        form.push(("client_secret", client_secret.as_str()));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(TOKEN_REQUEST_TIMEOUT)
+        .build()
+        .map_err(refresh_error)?;
+    let resp = client
+        .post(s.smtp_oauth_token_endpoint.trim())
+        .form(&form)
+        .send()
+        .await
+        .map_err(refresh_error)?;
+    let status = resp.status();
+    let body = resp.text().await.map_err(refresh_error)?;
+
+    if !status.is_success() {
+        let detail = match serde_json::from_str::<TokenErrorResponse>(&body) {
+            Ok(e) => match e.error_description {
+                Some(d) => format!("{} ({d})", e.error),
+                None => e.error,
+            },
+            Err(_) => format!("HTTP {status}"),
+        };
+        return Err(refresh_error(detail));
+    }
+
+    let token: TokenResponse =
+        serde_json::from_str(&body).map_err(|e| refresh_error(format!("invalid token response: {e}")))?;
+    if let Some(rotated) = token.refresh_token.as_deref().filter(|t| !t.is_empty() && *t != refresh_token) {
+        if let Err(e) = secrets::store(secrets::SMTP_OAUTH_REFRESH_TOKEN, rotated) {
+            tracing::error!(target: "email", op = "oauth", error = %e, "failed to store rotated OAuth2 refresh token");
+        }
+    }
+
+    let lifetime = token
+        .expires_in
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TOKEN_LIFETIME);
+    if let Ok(mut guard) = cache().lock() {
+        *guard = Some(CachedToken {
+            key,
+            access_token: token.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+    }
+    Ok(token.access_token)
+}
@@ -0,0 +1,205 @@
+//! Invoices whose status looks out of date, with the fixes the UI can offer in one click:
+//! DRAFTs left unsent for `stale_draft_days`, SENT invoices more than `overdue_grace_days` past
+//! due, and PAID invoices without a payment date (older data). The fixes go through
+//! `update_invoice` and `send_invoice_email`; nothing here changes an invoice.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use time::{Date, OffsetDateTime};
+
+use crate::errors::AppError;
+use crate::receivables::effective_due_date;
+use crate::recurring_expenses::parse_ymd;
+use crate::{read_settings_from_conn, DbState, Invoice, InvoiceStatus};
+
+pub(crate) const DEFAULT_STALE_DRAFT_DAYS: i64 = 14;
+const MAX_THRESHOLD_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SuggestionReason {
+    StaleDraft,
+    Overdue,
+    PaidWithoutDate,
+}
+
+/// A fix for the suggestion; the UI maps each to an existing command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SuggestedAction {
+    /// Send the invoice email (the outbox marks it SENT).
+    Send,
+    /// `update_invoice` with status CANCELLED.
+    Cancel,
+    /// Send the invoice email again as a reminder.
+    SendReminder,
+    /// `update_invoice` with status PAID and `paid_at` from the suggestion.
+    MarkPaid,
+    /// `update_invoice` with `paid_at` from the suggestion.
+    SetPaidAt,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusSuggestion {
+    pub invoice_id: String,
+    pub invoice_number: String,
+    pub client_name: String,
+    pub reason: SuggestionReason,
+    /// Days since the draft was created, or days past due; 0 for a missing payment date.
+    pub days: i64,
+    /// Most likely fix first.
+    pub actions: Vec<SuggestedAction>,
+    /// Proposed payment date for `MarkPaid`/`SetPaidAt`: the last recorded payment, else the due
+    /// date or the issue date. `None` lets `update_invoice` use today.
+    pub paid_at: Option<String>,
+}
+
+/// Checks a threshold from the settings form.
+pub(crate) fn check_threshold_days(v: i64, min: i64, field: &str) -> Result<i64, String> {
+    if (min..=MAX_THRESHOLD_DAYS).contains(&v) {
+        Ok(v)
+    } else {
+        Err(format!("{field} must be between {min} and {MAX_THRESHOLD_DAYS}."))
+    }
+}
+
+pub(crate) fn suggestions_in_conn(conn: &Connection, today: Date) -> Result<Vec<StatusSuggestion>, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let mut stmt = conn.prepare(
+        r#"SELECT i.data_json, c.paymentTermsDays,
+                  (SELECT MAX(p.date) FROM invoice_payments p WHERE p.invoiceId = i.id)
+           FROM invoices i
+           LEFT JOIN clients c ON c.id = i.clientId
+           WHERE i.status IN ('DRAFT', 'SENT', 'PAID')
+           ORDER BY i.issueDate ASC, i.createdAt ASC"#,
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        let terms: Option<i64> = row.get(1)?;
+        let last_payment: Option<String> = row.get(2)?;
+        let Ok(inv) = serde_json::from_str::<Invoice>(&json) else {
+            continue;
+        };
+        let suggestion = |reason, days, actions: &[SuggestedAction], paid_at: Option<String>| StatusSuggestion {
+            invoice_id: inv.id.clone(),
+            invoice_number: inv.invoice_number.clone(),
+            client_name: inv.client_name.clone(),
+            reason,
+            days,
+            actions: actions.to_vec(),
+            paid_at,
+        };
+        match inv.status {
+            InvoiceStatus::Draft => {
+                // `createdAt` is a timestamp; drafts imported without one fall back to the issue date.
+                let created = inv.created_at.get(..10).and_then(parse_ymd).or_else(|| parse_ymd(&inv.issue_date));
+                let Some(created) = created else { continue };
+                let age = (today - created).whole_days();
+                if age >= settings.stale_draft_days {
+                    out.push(suggestion(
+                        SuggestionReason::StaleDraft,
+                        age,
+                        &[SuggestedAction::Send, SuggestedAction::Cancel],
+                        None,
+                    ));
+                }
+            }
+            InvoiceStatus::Sent => {
+                let Some(due) = effective_due_date(&inv, terms) else { continue };
+                let days_past_due = (today - due).whole_days();
+                if days_past_due > settings.overdue_grace_days {
+                    // A recorded payment makes "paid" the likelier answer.
+                    let actions = match last_payment {
+                        Some(_) => [SuggestedAction::MarkPaid, SuggestedAction::SendReminder],
+                        None => [SuggestedAction::SendReminder, SuggestedAction::MarkPaid],
+                    };
+                    out.push(suggestion(SuggestionReason::Overdue, days_past_due, &actions, last_payment));
+                }
+            }
+            InvoiceStatus::Paid if inv.paid_at.as_deref().is_none_or(|d| d.trim().is_empty()) => {
+                let paid_at = last_payment.or_else(|| inv.due_date.clone()).unwrap_or_else(|| inv.issue_date.clone());
+                out.push(suggestion(SuggestionReason::PaidWithoutDate, 0, &[SuggestedAction::SetPaidAt], Some(paid_at)));
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Suggested status fixes as of today, oldest invoices first.
+#[tauri::command]
+pub(crate) async fn suggest_status_updates(state: tauri::State<'_, DbState>) -> Result<Vec<StatusSuggestion>, AppError> {
+    let today = OffsetDateTime::now_utc().date();
+    state
+        .with_read("suggest_status_updates", move |conn| suggestions_in_conn(conn, today))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    fn insert_invoice(conn: &Connection, id: &str, status: &str, dates: (&str, Option<&str>, Option<&str>), created_at: &str) {
+        let (issue, due, paid) = dates;
+        let json = serde_json::json!({
+            "id": id, "invoiceNumber": id, "clientId": "c", "clientName": "Klijent",
+            "issueDate": issue, "serviceDate": issue, "dueDate": due, "paidAt": paid, "status": status,
+            "currency": "RSD", "subtotal": 100.0, "total": 100.0, "notes": "", "createdAt": created_at,
+            "items": []
+        })
+        .to_string();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json)
+             VALUES (?1, ?1, 'c', ?2, ?3, ?4, ?5, 'RSD', 100, ?6, ?7)",
+            params![id, issue, status, due, paid, created_at, json],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn finds_stale_drafts_overdue_and_undated_payments() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        // as of 2025-06-30, default thresholds (14 days, no grace)
+        insert_invoice(&conn, "d-old", "DRAFT", ("2025-06-01", None, None), "2025-06-01T08:00:00Z");
+        insert_invoice(&conn, "d-new", "DRAFT", ("2025-06-20", None, None), "2025-06-20T08:00:00Z");
+        insert_invoice(&conn, "s-late", "SENT", ("2025-05-01", Some("2025-06-10"), None), "t");
+        insert_invoice(&conn, "s-paid", "SENT", ("2025-05-02", None, None), "t"); // due 05-17 by default terms
+        insert_invoice(&conn, "s-ok", "SENT", ("2025-06-20", Some("2025-06-30"), None), "t");
+        insert_invoice(&conn, "p-legacy", "PAID", ("2024-01-05", Some("2024-01-20"), None), "t");
+        insert_invoice(&conn, "p-ok", "PAID", ("2024-01-06", None, Some("2024-01-10")), "t");
+        conn.execute(
+            "INSERT INTO invoice_payments (id, invoiceId, amount, currency, date, createdAt) VALUES ('p1', 's-paid', 100, 'RSD', '2025-05-15', 't')",
+            [],
+        )
+        .unwrap();
+
+        let today = Date::from_calendar_date(2025, time::Month::June, 30).unwrap();
+        let found = suggestions_in_conn(&conn, today).unwrap();
+        let summary: Vec<_> = found.iter().map(|s| (s.invoice_id.as_str(), s.reason, s.days)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("p-legacy", SuggestionReason::PaidWithoutDate, 0),
+                ("s-late", SuggestionReason::Overdue, 20),
+                ("s-paid", SuggestionReason::Overdue, 44),
+                ("d-old", SuggestionReason::StaleDraft, 29),
+            ]
+        );
+        assert_eq!(found[0].paid_at.as_deref(), Some("2024-01-20"));
+        assert_eq!(found[1].actions, [SuggestedAction::SendReminder, SuggestedAction::MarkPaid]);
+        assert_eq!(found[2].actions[0], SuggestedAction::MarkPaid);
+        assert_eq!(found[2].paid_at.as_deref(), Some("2025-05-15"));
+
+        conn.execute("UPDATE settings SET staleDraftDays = 60, overdueGraceDays = 30", []).unwrap();
+        let found = suggestions_in_conn(&conn, today).unwrap();
+        let ids: Vec<_> = found.iter().map(|s| s.invoice_id.as_str()).collect();
+        assert_eq!(ids, ["p-legacy", "s-paid"]);
+        assert!(check_threshold_days(0, 1, "Stale draft days").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Serbian tax identifier checks: PIB (9 digits, ISO 7064 MOD 11,10 check digit) and
+//! matični broj (8 digits, weighted modulus-11 check digit).
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppError;
+use crate::{read_settings_from_conn, DbState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaxIdError {
+    PibFormat,
+    PibChecksum,
+    MbFormat,
+    MbChecksum,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaxIdMessagesLocale {
+    pib_format: String,
+    pib_checksum: String,
+    mb_format: String,
+    mb_checksum: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TaxIdMessagesFile {
+    sr: TaxIdMessagesLocale,
+    en: TaxIdMessagesLocale,
+}
+
+static TAX_ID_MESSAGES: OnceLock<Option<TaxIdMessagesFile>> = OnceLock::new();
+
+impl TaxIdError {
+    /// Localized message for `value`; `lang` follows the app language ("en…" or Serbian).
+    pub(crate) fn message(self, lang: &str, value: &str) -> String {
+        let file = TAX_ID_MESSAGES.get_or_init(|| {
+            serde_json::from_str(include_str!("../../src/shared/taxIdMessages.json")).ok()
+        });
+        let Some(file) = file else {
+            return format!("Invalid tax identifier \"{value}\".");
+        };
+        let loc = if lang.to_ascii_lowercase().starts_with("en") { &file.en } else { &file.sr };
+        let template = match self {
+            TaxIdError::PibFormat => &loc.pib_format,
+            TaxIdError::PibChecksum => &loc.pib_checksum,
+            TaxIdError::MbFormat => &loc.mb_format,
+            TaxIdError::MbChecksum => &loc.mb_checksum,
+        };
+        template.replace("{value}", value)
+    }
+}
+
+/// Digits of `v` with whitespace removed, if exactly `len` ASCII digits remain.
+fn digits_of_len(v: &str, len: usize) -> Option<Vec<u32>> {
+    let compact: Vec<char> = v.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.len() != len {
+        return None;
+    }
+    compact.iter().map(|c| c.to_digit(10)).collect()
+}
+
+fn compact(digits: &[u32]) -> String {
+    digits.iter().map(|d| char::from_digit(*d, 10).unwrap_or('0')).collect()
+}
+
+/// Validates a PIB and returns it without whitespace.
+pub(crate) fn check_pib(v: &str) -> Result<String, TaxIdError> {
+    let digits = digits_of_len(v, 9).ok_or(TaxIdError::PibFormat)?;
+    let mut p = 10;
+    for d in &digits[..8] {
+        let s = match (p + d) % 10 {
+            0 => 10,
+            s => s,
+        };
+        p = (2 * s) % 11;
+    }
+    if (11 - p) % 10 != digits[8] {
+        return Err(TaxIdError::PibChecksum);
+    }
+    Ok(compact(&digits))
+}
+
+/// Validates a matični broj and returns it without whitespace.
+pub(crate) fn check_mb(v: &str) -> Result<String, TaxIdError> {
+    let digits = digits_of_len(v, 8).ok_or(TaxIdError::MbFormat)?;
+    let sum: u32 = digits[..7].iter().zip((2..=8).rev()).map(|(d, w)| d * w).sum();
+    let check = match 11 - sum % 11 {
+        10 | 11 => 0,
+        k => k,
+    };
+    if check != digits[7] {
+        return Err(TaxIdError::MbChecksum);
+    }
+    Ok(compact(&digits))
+}
+
+/// Checks the non-empty identifiers and returns the first localized error.
+pub(crate) fn validate_tax_ids_for(lang: &str, pib: &str, mb: &str) -> Result<(), String> {
+    let pib = pib.trim();
+    if !pib.is_empty() {
+        check_pib(pib).map_err(|e| e.message(lang, pib))?;
+    }
+    let mb = mb.trim();
+    if !mb.is_empty() {
+        check_mb(mb).map_err(|e| e.message(lang, mb))?;
+    }
+    Ok(())
+}
+
+/// Like `validate_tax_ids_for`, but skips identifiers equal to their previous `(pib, mb)`
+/// value, so a stored legacy value doesn't block unrelated edits.
+pub(crate) fn validate_changed_tax_ids(
+    lang: &str,
+    before: &(String, String),
+    pib: &str,
+    mb: &str,
+) -> Result<(), String> {
+    let pib = if pib != before.0 { pib } else { "" };
+    let mb = if mb != before.1 { mb } else { "" };
+    validate_tax_ids_for(lang, pib, mb)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxIdValidation {
+    pub valid: bool,
+    pub pib_error: Option<String>,
+    pub mb_error: Option<String>,
+}
+
+/// Field-level check for the UI (called on blur). Empty values are not errors.
+#[tauri::command]
+pub(crate) async fn validate_tax_ids(
+    state: tauri::State<'_, DbState>,
+    pib: Option<String>,
+    mb: Option<String>,
+) -> Result<TaxIdValidation, AppError> {
+    let lang = state
+        .with_read("validate_tax_ids", |conn| Ok(read_settings_from_conn(conn)?.language))
+        .await?;
+    let field_error = |v: Option<String>, check: fn(&str) -> Result<String, TaxIdError>| {
+        let v = v.unwrap_or_default();
+        let v = v.trim();
+        if v.is_empty() {
+            return None;
+        }
+        check(v).err().map(|e| e.message(&lang, v))
+    };
+    let pib_error = field_error(pib, check_pib);
+    let mb_error = field_error(mb, check_mb);
+    Ok(TaxIdValidation {
+        valid: pib_error.is_none() && mb_error.is_none(),
+        pib_error,
+        mb_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pib_and_mb_check_digits() {
+        assert_eq!(check_pib("101 134 702"), Ok("101134702".to_string()));
+        assert_eq!(check_pib("100002887"), Ok("100002887".to_string()));
+        assert_eq!(check_pib("100000001"), Err(TaxIdError::PibChecksum));
+        assert_eq!(check_pib("12345"), Err(TaxIdError::PibFormat));
+        assert_eq!(check_pib("10113470x"), Err(TaxIdError::PibFormat));
+
+        assert_eq!(check_mb("07015410"), Ok("07015410".to_string()));
+        assert_eq!(check_mb("07534183"), Ok("07534183".to_string()));
+        assert_eq!(check_mb("12345678"), Err(TaxIdError::MbChecksum));
+        assert_eq!(check_mb("1234567"), Err(TaxIdError::MbFormat));
+    }
+
+    #[test]
+    fn messages_follow_language() {
+        let err = validate_tax_ids_for("en", "", "12345678").unwrap_err();
+        assert!(err.contains("12345678") && err.contains("check digit"), "{err}");
+        let err = validate_tax_ids_for("sr", "100000001", "").unwrap_err();
+        assert!(err.contains("kontrolna cifra"), "{err}");
+        assert!(validate_tax_ids_for("sr", " ", "").is_ok());
+    }
+}
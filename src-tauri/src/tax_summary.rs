@@ -0,0 +1,315 @@
+//! Quarterly income/expense summary for advance tax payments. Income is cash basis (by `paidAt`),
+//! expenses go by their date. Everything is in the default currency; foreign invoices convert
+//! with their exchange rate, and amounts that can't be converted are listed separately.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::{
+    csv_document, csv_header_row, csv_join_row, format_money_csv, invoice_total_in_currency, read_settings_from_conn,
+    resolve_csv_options, write_text_file, CsvOptions, DbState, Invoice,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxTotals {
+    pub income: f64,
+    pub income_count: i64,
+    pub expenses: f64,
+    pub expense_count: i64,
+    /// `income - expenses`.
+    pub base: f64,
+}
+
+impl TaxTotals {
+    fn finish(&mut self) {
+        self.income = round2(self.income);
+        self.expenses = round2(self.expenses);
+        self.base = round2(self.income - self.expenses);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxQuarter {
+    /// 1-4.
+    pub quarter: u8,
+    pub from: String,
+    pub to: String,
+    #[serde(flatten)]
+    pub totals: TaxTotals,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaxAmountKind {
+    Income,
+    Expense,
+}
+
+/// Amounts in a currency other than the default that had no exchange rate to convert with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxUnconverted {
+    pub quarter: u8,
+    pub kind: TaxAmountKind,
+    pub currency: String,
+    pub amount: f64,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxSummary {
+    pub year: i32,
+    pub currency: String,
+    pub quarters: Vec<TaxQuarter>,
+    pub year_total: TaxTotals,
+    pub unconverted: Vec<TaxUnconverted>,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn quarter_of(ymd: &str) -> Option<u8> {
+    let month: u8 = ymd.get(5..7)?.parse().ok()?;
+    (1..=12).contains(&month).then(|| (month - 1) / 3 + 1)
+}
+
+fn quarter_bounds(year: i32, quarter: u8) -> (String, String) {
+    let (first, last, last_day) = match quarter {
+        1 => (1, 3, 31),
+        2 => (4, 6, 30),
+        3 => (7, 9, 30),
+        _ => (10, 12, 31),
+    };
+    (format!("{year:04}-{first:02}-01"), format!("{year:04}-{last:02}-{last_day:02}"))
+}
+
+pub(crate) fn tax_summary_in_conn(conn: &Connection, year: i32) -> Result<TaxSummary, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let currency = settings.default_currency;
+    let (from, to) = (format!("{year:04}-01-01"), format!("{year:04}-12-31"));
+
+    let mut quarters: [TaxTotals; 4] = Default::default();
+    let mut unconverted: BTreeMap<(u8, TaxAmountKind, String), (f64, i64)> = BTreeMap::new();
+
+    // paidAt may be a full timestamp; only its date decides the quarter.
+    let mut stmt = conn.prepare(
+        r#"SELECT data_json, paid_on
+           FROM (
+             SELECT data_json, substr(COALESCE(NULLIF(TRIM(paidAt), ''), issueDate), 1, 10) AS paid_on
+             FROM invoices
+             WHERE status = 'PAID'
+           )
+           WHERE paid_on >= ?1 AND paid_on <= ?2"#,
+    )?;
+    let mut rows = stmt.query(params![from, to])?;
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        let paid_on: String = row.get(1)?;
+        let (Ok(inv), Some(q)) = (serde_json::from_str::<Invoice>(&json), quarter_of(&paid_on)) else {
+            continue;
+        };
+        match invoice_total_in_currency(&inv, &currency) {
+            Some(amount) => {
+                let t = &mut quarters[usize::from(q - 1)];
+                t.income += amount;
+                t.income_count += 1;
+            }
+            None => {
+                let payable = invoice_total_in_currency(&inv, &inv.currency).unwrap_or(inv.total);
+                let e = unconverted.entry((q, TaxAmountKind::Income, inv.currency)).or_default();
+                e.0 += payable;
+                e.1 += 1;
+            }
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        r#"SELECT currency, (CAST(substr(date, 6, 2) AS INTEGER) + 2) / 3 AS q, SUM(amount), COUNT(*)
+           FROM expenses
+           WHERE deletedAt IS NULL AND date >= ?1 AND date <= ?2
+           GROUP BY 1, 2"#,
+    )?;
+    let mut rows = stmt.query(params![from, to])?;
+    while let Some(row) = rows.next()? {
+        let exp_currency: String = row.get(0)?;
+        let q: i64 = row.get(1)?;
+        let (amount, count): (f64, i64) = (row.get(2)?, row.get(3)?);
+        let Some(q) = u8::try_from(q).ok().filter(|q| (1..=4).contains(q)) else {
+            continue;
+        };
+        if exp_currency.trim().eq_ignore_ascii_case(currency.trim()) {
+            let t = &mut quarters[usize::from(q - 1)];
+            t.expenses += amount;
+            t.expense_count += count;
+        } else {
+            let e = unconverted.entry((q, TaxAmountKind::Expense, exp_currency)).or_default();
+            e.0 += amount;
+            e.1 += count;
+        }
+    }
+
+    let mut year_total = TaxTotals::default();
+    let quarters = quarters
+        .into_iter()
+        .zip(1u8..)
+        .map(|(mut totals, quarter)| {
+            year_total.income += totals.income;
+            year_total.income_count += totals.income_count;
+            year_total.expenses += totals.expenses;
+            year_total.expense_count += totals.expense_count;
+            totals.finish();
+            let (from, to) = quarter_bounds(year, quarter);
+            TaxQuarter { quarter, from, to, totals }
+        })
+        .collect();
+    year_total.finish();
+
+    let unconverted = unconverted
+        .into_iter()
+        .map(|((quarter, kind, currency), (amount, count))| TaxUnconverted {
+            quarter,
+            kind,
+            currency,
+            amount: round2(amount),
+            count,
+        })
+        .collect();
+
+    Ok(TaxSummary {
+        year,
+        currency,
+        quarters,
+        year_total,
+        unconverted,
+    })
+}
+
+pub(crate) fn render_tax_summary_csv(s: &TaxSummary, opts: &CsvOptions) -> String {
+    let header = [
+        "period",
+        "from",
+        "to",
+        "currency",
+        "converted",
+        "income",
+        "incomeCount",
+        "expenses",
+        "expenseCount",
+        "base",
+    ];
+    let totals_row = |period: String, from: &str, to: &str, t: &TaxTotals| {
+        vec![
+            period,
+            from.to_string(),
+            to.to_string(),
+            s.currency.clone(),
+            "true".to_string(),
+            format_money_csv(t.income, opts),
+            t.income_count.to_string(),
+            format_money_csv(t.expenses, opts),
+            t.expense_count.to_string(),
+            format_money_csv(t.base, opts),
+        ]
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(csv_header_row(&header, opts));
+    for q in &s.quarters {
+        lines.push(csv_join_row(&totals_row(format!("Q{}", q.quarter), &q.from, &q.to, &q.totals), opts));
+    }
+    let (from, to) = (format!("{:04}-01-01", s.year), format!("{:04}-12-31", s.year));
+    lines.push(csv_join_row(&totals_row(s.year.to_string(), &from, &to, &s.year_total), opts));
+
+    // Unconverted amounts keep their own currency and never enter the base.
+    for u in &s.unconverted {
+        let (from, to) = quarter_bounds(s.year, u.quarter);
+        let (income, income_count, expenses, expense_count) = match u.kind {
+            TaxAmountKind::Income => (format_money_csv(u.amount, opts), u.count.to_string(), String::new(), String::new()),
+            TaxAmountKind::Expense => (String::new(), String::new(), format_money_csv(u.amount, opts), u.count.to_string()),
+        };
+        let row = [
+            format!("Q{}", u.quarter),
+            from,
+            to,
+            u.currency.clone(),
+            "false".to_string(),
+            income,
+            income_count,
+            expenses,
+            expense_count,
+            String::new(),
+        ];
+        lines.push(csv_join_row(&row, opts));
+    }
+    csv_document(&lines, opts)
+}
+
+/// Quarterly paid income, expenses and base for `year`, in the default currency.
+#[tauri::command]
+pub(crate) async fn tax_summary(state: tauri::State<'_, DbState>, year: i32) -> Result<TaxSummary, AppError> {
+    state
+        .with_read("tax_summary", move |conn| tax_summary_in_conn(conn, year))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn export_tax_summary_csv(
+    state: tauri::State<'_, DbState>,
+    year: i32,
+    output_path: String,
+    csv_options: Option<CsvOptions>,
+) -> Result<String, AppError> {
+    let (opts, summary) = state
+        .with_read("export_tax_summary_csv", move |conn| {
+            Ok((resolve_csv_options(conn, csv_options)?, tax_summary_in_conn(conn, year)?))
+        })
+        .await?;
+    write_text_file(std::path::Path::new(&output_path), &render_tax_summary_csv(&summary, &opts))?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{expense, invoice};
+
+    #[test]
+    fn quarters_follow_payment_and_expense_dates_across_new_year() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        let paid = |id: &str, issue: &str| invoice(id).issued(issue).status("PAID");
+        paid("dec31", "2024-12-10").paid_at("2024-12-31").amount("RSD", 1000.0).insert(&conn);
+        paid("jan1", "2024-12-20").paid_at("2025-01-01T08:00:00Z").amount("RSD", 2000.0).insert(&conn);
+        paid("apr", "2025-03-30").paid_at("2025-04-01").amount("EUR", 100.0).exchange_rate(117.0).insert(&conn);
+        paid("usd", "2025-05-01").paid_at("2025-05-02").amount("USD", 50.0).insert(&conn);
+        paid("nopaid", "2025-12-31").amount("RSD", 300.0).insert(&conn);
+        expense("e0").on("2024-12-31").amount("RSD", 111.0).insert(&conn);
+        expense("e1").on("2025-01-01").amount("RSD", 400.0).insert(&conn);
+        expense("e2").on("2025-03-31").amount("RSD", 100.0).insert(&conn);
+        expense("e3").on("2025-04-01").amount("EUR", 10.0).insert(&conn);
+        expense("e4").on("2025-12-31").amount("RSD", 50.0).insert(&conn);
+
+        let s = tax_summary_in_conn(&conn, 2025).unwrap();
+        assert_eq!(s.currency, "RSD");
+        let q: Vec<_> = s.quarters.iter().map(|q| (q.totals.income, q.totals.expenses, q.totals.base)).collect();
+        assert_eq!(q, vec![(2000.0, 500.0, 1500.0), (11700.0, 0.0, 11700.0), (0.0, 0.0, 0.0), (300.0, 50.0, 250.0)]);
+        assert_eq!((s.quarters[0].from.as_str(), s.quarters[0].to.as_str()), ("2025-01-01", "2025-03-31"));
+        assert_eq!((s.year_total.income, s.year_total.income_count, s.year_total.base), (14000.0, 3, 13450.0));
+        let u: Vec<_> = s.unconverted.iter().map(|u| (u.quarter, u.kind, u.currency.as_str(), u.amount)).collect();
+        assert_eq!(u, vec![(2, TaxAmountKind::Income, "USD", 50.0), (2, TaxAmountKind::Expense, "EUR", 10.0)]);
+
+        let prev = tax_summary_in_conn(&conn, 2024).unwrap();
+        assert_eq!((prev.quarters[3].totals.income, prev.quarters[3].totals.expenses), (1000.0, 111.0));
+
+        let csv = render_tax_summary_csv(&s, &CsvOptions::default());
+        assert!(csv.contains("\r\n2025,2025-01-01,2025-12-31,RSD,true,14000.00,3,550.00,3,13450.00\r\n"), "{csv}");
+        assert!(csv.contains("\r\nQ2,2025-04-01,2025-06-30,USD,false,50.00,1,,,\r\n"), "{csv}");
+    }
+}
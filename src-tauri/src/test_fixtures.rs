@@ -0,0 +1,211 @@
+//! Rows for tests: invoices, expenses and clients inserted straight into the tables, with
+//! `data_json` shaped the way the app writes it. Every field has a default, so a test only sets
+//! what it is about:
+//!
+//! ```ignore
+//! invoice("1").issued("2025-03-05").status("PAID").paid_at("2025-03-10").amount("EUR", 500.0).insert(&conn);
+//! ```
+
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+
+pub(crate) struct InvoiceFixture {
+    id: String,
+    number: String,
+    client_id: String,
+    client_name: String,
+    issue_date: String,
+    status: String,
+    due_date: Option<String>,
+    paid_at: Option<String>,
+    currency: String,
+    total: f64,
+    exchange_rate: Option<f64>,
+    items: Value,
+}
+
+/// A SENT invoice numbered `id`, for client `c` ("Klijent"), issued 2025-01-01, of 0 RSD and
+/// without lines.
+pub(crate) fn invoice(id: &str) -> InvoiceFixture {
+    InvoiceFixture {
+        id: id.to_string(),
+        number: id.to_string(),
+        client_id: "c".to_string(),
+        client_name: "Klijent".to_string(),
+        issue_date: "2025-01-01".to_string(),
+        status: "SENT".to_string(),
+        due_date: None,
+        paid_at: None,
+        currency: "RSD".to_string(),
+        total: 0.0,
+        exchange_rate: None,
+        items: json!([]),
+    }
+}
+
+impl InvoiceFixture {
+    pub(crate) fn number(mut self, number: &str) -> Self {
+        self.number = number.to_string();
+        self
+    }
+
+    /// The client id and the name the invoice was issued to.
+    pub(crate) fn client(mut self, id: &str, name: &str) -> Self {
+        self.client_id = id.to_string();
+        self.client_name = name.to_string();
+        self
+    }
+
+    /// Issue date, also used as the service date.
+    pub(crate) fn issued(mut self, date: &str) -> Self {
+        self.issue_date = date.to_string();
+        self
+    }
+
+    pub(crate) fn status(mut self, status: &str) -> Self {
+        self.status = status.to_string();
+        self
+    }
+
+    pub(crate) fn due(mut self, date: &str) -> Self {
+        self.due_date = Some(date.to_string());
+        self
+    }
+
+    pub(crate) fn paid_at(mut self, at: &str) -> Self {
+        self.paid_at = Some(at.to_string());
+        self
+    }
+
+    /// Currency and total (also the subtotal).
+    pub(crate) fn amount(mut self, currency: &str, total: f64) -> Self {
+        self.currency = currency.to_string();
+        self.total = total;
+        self
+    }
+
+    pub(crate) fn exchange_rate(mut self, rate: f64) -> Self {
+        self.exchange_rate = Some(rate);
+        self
+    }
+
+    /// The `items` array as written.
+    pub(crate) fn items(mut self, items: Value) -> Self {
+        self.items = items;
+        self
+    }
+
+    /// A single line of quantity 1 carrying the whole total.
+    pub(crate) fn one_line(self, description: &str) -> Self {
+        let total = self.total;
+        self.items(json!([{
+            "id": "i1", "description": description, "quantity": 1.0, "unitPrice": total, "total": total
+        }]))
+    }
+
+    pub(crate) fn insert(self, conn: &Connection) {
+        let json = json!({
+            "id": self.id, "invoiceNumber": self.number, "clientId": self.client_id, "clientName": self.client_name,
+            "issueDate": self.issue_date, "serviceDate": self.issue_date, "dueDate": self.due_date,
+            "status": self.status, "paidAt": self.paid_at, "currency": self.currency,
+            "subtotal": self.total, "total": self.total, "notes": "", "createdAt": "t",
+            "items": self.items, "exchangeRate": self.exchange_rate
+        })
+        .to_string();
+        conn.execute(
+            "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, dueDate, paidAt, currency, totalAmount, createdAt, data_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 't', ?10)",
+            params![
+                self.id,
+                self.number,
+                self.client_id,
+                self.issue_date,
+                self.status,
+                self.due_date,
+                self.paid_at,
+                self.currency,
+                self.total,
+                json
+            ],
+        )
+        .unwrap();
+    }
+}
+
+pub(crate) struct ExpenseFixture {
+    id: String,
+    title: String,
+    date: String,
+    currency: String,
+    amount: f64,
+    category: Option<String>,
+}
+
+/// An expense titled `id`, of 0 RSD on 2025-01-01, without a category.
+pub(crate) fn expense(id: &str) -> ExpenseFixture {
+    ExpenseFixture {
+        id: id.to_string(),
+        title: id.to_string(),
+        date: "2025-01-01".to_string(),
+        currency: "RSD".to_string(),
+        amount: 0.0,
+        category: None,
+    }
+}
+
+impl ExpenseFixture {
+    pub(crate) fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub(crate) fn on(mut self, date: &str) -> Self {
+        self.date = date.to_string();
+        self
+    }
+
+    pub(crate) fn amount(mut self, currency: &str, amount: f64) -> Self {
+        self.currency = currency.to_string();
+        self.amount = amount;
+        self
+    }
+
+    pub(crate) fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    pub(crate) fn insert(self, conn: &Connection) {
+        conn.execute(
+            "INSERT INTO expenses (id, title, amount, currency, date, category, createdAt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 't')",
+            params![self.id, self.title, self.amount, self.currency, self.date, self.category],
+        )
+        .unwrap();
+    }
+}
+
+pub(crate) struct ClientFixture {
+    id: String,
+    name: String,
+    payment_terms_days: Option<i64>,
+}
+
+/// A domestic client without PIB, email or payment terms.
+pub(crate) fn client(id: &str, name: &str) -> ClientFixture {
+    ClientFixture { id: id.to_string(), name: name.to_string(), payment_terms_days: None }
+}
+
+impl ClientFixture {
+    pub(crate) fn payment_terms(mut self, days: i64) -> Self {
+        self.payment_terms_days = Some(days);
+        self
+    }
+
+    pub(crate) fn insert(self, conn: &Connection) {
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, paymentTermsDays, createdAt) VALUES (?1, ?2, '', '', '', ?3, 't')",
+            params![self.id, self.name, self.payment_terms_days],
+        )
+        .unwrap();
+    }
+}
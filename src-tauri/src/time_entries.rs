@@ -0,0 +1,575 @@
+//! Hourly work: time entries per client, a single running timer kept in `app_meta`, and billing
+//! of entries into a draft invoice. Billing groups the entries by description and rate into
+//! invoice items counted in hours ("sat") and stamps each entry with the invoice id in the same
+//! transaction, so an entry is billed at most once. Deleting the invoice releases its entries.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::data_events::{DataEntity, DataOp};
+use crate::recurring_expenses::format_ymd;
+use crate::{
+    app_meta_get, app_meta_set, normalize_ymd, now_iso, read_client_from_conn, read_invoice_from_conn,
+    recompute_invoice_totals, validation_to_sql_error, DbState, Invoice, InvoiceItem, InvoiceStatus,
+};
+
+const TIMER_KEY: &str = "timeTracking.activeTimer";
+
+/// Unit of the invoice items created from time entries.
+const HOURS_UNIT: &str = "sat";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntry {
+    pub id: String,
+    pub client_id: String,
+    pub date: String,
+    pub minutes: i64,
+    pub description: String,
+    /// Price of one hour in the currency of the invoice the entry is billed to.
+    pub hourly_rate: f64,
+    /// Invoice the entry was billed to; billed entries can no longer be edited.
+    #[serde(default)]
+    pub billed_invoice_id: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTimeEntry {
+    pub client_id: String,
+    pub date: String,
+    pub minutes: i64,
+    #[serde(default)]
+    pub description: String,
+    pub hourly_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntryPatch {
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+}
+
+/// The running timer; stopping it turns the elapsed time into a [`TimeEntry`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveTimer {
+    pub client_id: String,
+    #[serde(default)]
+    pub description: String,
+    pub hourly_rate: f64,
+    /// RFC 3339, UTC.
+    pub started_at: String,
+}
+
+/// Unbilled time of one client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbilledTime {
+    pub client_id: String,
+    pub client_name: String,
+    pub entries: usize,
+    pub minutes: i64,
+    /// Minutes as hours, rounded to 2 decimals like the invoice quantity.
+    pub hours: f64,
+    /// Hours × rate, summed over the entries.
+    pub amount: f64,
+    pub oldest_date: String,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn hours(minutes: i64) -> f64 {
+    round2(minutes as f64 / 60.0)
+}
+
+fn validate(conn: &Connection, e: &TimeEntry) -> Result<(), rusqlite::Error> {
+    let invalid = |msg: &str| Err(validation_to_sql_error(msg.to_string()));
+    if e.minutes <= 0 {
+        return invalid("Duration must be at least one minute.");
+    }
+    if !e.hourly_rate.is_finite() || e.hourly_rate < 0.0 {
+        return invalid("Hourly rate must not be negative.");
+    }
+    if read_client_from_conn(conn, &e.client_id)?.is_none() {
+        return invalid("Client was not found.");
+    }
+    Ok(())
+}
+
+const ENTRY_COLUMNS: &str = "id, clientId, date, minutes, description, hourlyRate, billedInvoiceId, createdAt";
+
+fn entry_from_row(r: &rusqlite::Row<'_>) -> Result<TimeEntry, rusqlite::Error> {
+    Ok(TimeEntry {
+        id: r.get(0)?,
+        client_id: r.get(1)?,
+        date: r.get(2)?,
+        minutes: r.get(3)?,
+        description: r.get(4)?,
+        hourly_rate: r.get(5)?,
+        billed_invoice_id: r.get(6)?,
+        created_at: r.get(7)?,
+    })
+}
+
+fn read_entry(conn: &Connection, id: &str) -> Result<Option<TimeEntry>, rusqlite::Error> {
+    conn.query_row(
+        &format!("SELECT {ENTRY_COLUMNS} FROM time_entries WHERE id = ?1"),
+        params![id],
+        entry_from_row,
+    )
+    .optional()
+}
+
+fn write_entry(conn: &Connection, e: &TimeEntry) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"INSERT INTO time_entries (id, clientId, date, minutes, description, hourlyRate, billedInvoiceId, createdAt)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+           ON CONFLICT(id) DO UPDATE SET clientId=excluded.clientId, date=excluded.date,
+               minutes=excluded.minutes, description=excluded.description,
+               hourlyRate=excluded.hourlyRate, billedInvoiceId=excluded.billedInvoiceId"#,
+        params![
+            e.id,
+            e.client_id,
+            e.date,
+            e.minutes,
+            e.description,
+            e.hourly_rate,
+            e.billed_invoice_id,
+            e.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+fn read_timer(conn: &Connection) -> Result<Option<ActiveTimer>, rusqlite::Error> {
+    Ok(app_meta_get(conn, TIMER_KEY)?.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+pub(crate) fn start_timer_in_conn(conn: &Connection, mut timer: ActiveTimer) -> Result<ActiveTimer, rusqlite::Error> {
+    if read_timer(conn)?.is_some() {
+        return Err(validation_to_sql_error("A timer is already running; stop it first.".to_string()));
+    }
+    timer.description = timer.description.trim().to_string();
+    if !timer.hourly_rate.is_finite() || timer.hourly_rate < 0.0 {
+        return Err(validation_to_sql_error("Hourly rate must not be negative.".to_string()));
+    }
+    if read_client_from_conn(conn, &timer.client_id)?.is_none() {
+        return Err(validation_to_sql_error("Client was not found.".to_string()));
+    }
+    let json = serde_json::to_string(&timer).map_err(|e| validation_to_sql_error(e.to_string()))?;
+    app_meta_set(conn, TIMER_KEY, &json)?;
+    Ok(timer)
+}
+
+/// Records the running timer as an entry dated the day it started, rounded to whole minutes
+/// (at least one). `None` when no timer is running.
+pub(crate) fn stop_timer_in_conn(conn: &mut Connection, now: OffsetDateTime) -> Result<Option<TimeEntry>, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(timer) = read_timer(&tx)? else {
+        return Ok(None);
+    };
+    tx.execute("DELETE FROM app_meta WHERE key = ?1", params![TIMER_KEY])?;
+    let started = OffsetDateTime::parse(&timer.started_at, &Rfc3339).unwrap_or(now);
+    let seconds = (now - started).whole_seconds().max(0);
+    let entry = TimeEntry {
+        id: Uuid::new_v4().to_string(),
+        client_id: timer.client_id,
+        date: format_ymd(started.date()),
+        minutes: ((seconds + 30) / 60).max(1),
+        description: timer.description,
+        hourly_rate: timer.hourly_rate,
+        billed_invoice_id: None,
+        created_at: now_iso(),
+    };
+    // The client may have been deleted while the timer ran; keep the time anyway.
+    write_entry(&tx, &entry)?;
+    tx.commit()?;
+    Ok(Some(entry))
+}
+
+/// Adds the entries to the draft invoice as one item per description and rate, in date order,
+/// and marks them billed. Fails without changes when an entry is billed already or belongs to
+/// another client.
+pub(crate) fn bill_time_entries_in_conn(
+    conn: &mut Connection,
+    entry_ids: &[String],
+    invoice_id: &str,
+) -> Result<Option<Invoice>, rusqlite::Error> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let Some(mut invoice) = read_invoice_from_conn(&tx, invoice_id)? else {
+        return Ok(None);
+    };
+    if invoice.status != InvoiceStatus::Draft {
+        return Err(validation_to_sql_error(format!(
+            "Invoice {} is not a draft; time can only be billed to draft invoices.",
+            invoice.invoice_number
+        )));
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut entries = Vec::new();
+    for id in entry_ids.iter().map(String::as_str).filter(|id| seen.insert(id)) {
+        let entry = read_entry(&tx, id)?
+            .ok_or_else(|| validation_to_sql_error(format!("Time entry {id} was not found.")))?;
+        if entry.billed_invoice_id.is_some() {
+            return Err(validation_to_sql_error(format!(
+                "Time entry of {} \"{}\" has already been billed.",
+                entry.date, entry.description
+            )));
+        }
+        if entry.client_id != invoice.client_id {
+            return Err(validation_to_sql_error(format!(
+                "Time entry of {} \"{}\" belongs to another client.",
+                entry.date, entry.description
+            )));
+        }
+        entries.push(entry);
+    }
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut groups: Vec<(String, f64, i64)> = Vec::new();
+    for entry in &entries {
+        let description = match entry.description.trim() {
+            "" => "Rad".to_string(),
+            d => d.to_string(),
+        };
+        match groups.iter_mut().find(|(d, rate, _)| *d == description && *rate == entry.hourly_rate) {
+            Some((_, _, minutes)) => *minutes += entry.minutes,
+            None => groups.push((description, entry.hourly_rate, entry.minutes)),
+        }
+    }
+    for (description, rate, minutes) in groups {
+        let quantity = hours(minutes);
+        invoice.items.push(InvoiceItem {
+            id: Uuid::new_v4().to_string(),
+            description,
+            unit: Some(HOURS_UNIT.to_string()),
+            quantity,
+            unit_price: rate,
+            discount_amount: None,
+            total: quantity * rate,
+            catalog_item_id: None,
+        });
+    }
+    for entry in &entries {
+        tx.execute(
+            "UPDATE time_entries SET billedInvoiceId = ?2 WHERE id = ?1",
+            params![entry.id, invoice.id],
+        )?;
+    }
+
+    recompute_invoice_totals(&mut invoice);
+    let json = serde_json::to_string(&invoice).unwrap_or_else(|_| "{}".to_string());
+    tx.execute(
+        "UPDATE invoices SET totalAmount = ?2, data_json = ?3 WHERE id = ?1",
+        params![invoice.id, invoice.total, json],
+    )?;
+    tx.commit()?;
+    Ok(Some(invoice))
+}
+
+/// Unbilled time per client, most hours first.
+pub(crate) fn unbilled_time_in_conn(conn: &Connection) -> Result<Vec<UnbilledTime>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT t.clientId, COALESCE(c.name, ''), COUNT(*), SUM(t.minutes),
+                  SUM(t.minutes * t.hourlyRate / 60.0), MIN(t.date)
+           FROM time_entries t
+           LEFT JOIN clients c ON c.id = t.clientId
+           WHERE t.billedInvoiceId IS NULL
+           GROUP BY t.clientId
+           ORDER BY SUM(t.minutes) DESC"#,
+    )?;
+    let rows = stmt.query_map([], |r| {
+        let minutes: i64 = r.get(3)?;
+        Ok(UnbilledTime {
+            client_id: r.get(0)?,
+            client_name: r.get(1)?,
+            entries: r.get::<_, i64>(2)? as usize,
+            minutes,
+            hours: hours(minutes),
+            amount: round2(r.get(4)?),
+            oldest_date: r.get(5)?,
+        })
+    })?;
+    rows.collect()
+}
+
+fn ensure_unbilled(entry: &TimeEntry) -> Result<(), rusqlite::Error> {
+    match entry.billed_invoice_id {
+        Some(_) => Err(validation_to_sql_error(
+            "This time entry has already been billed and can no longer be changed.".to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_time_entries(
+    state: tauri::State<'_, DbState>,
+    client_id: Option<String>,
+    unbilled_only: Option<bool>,
+) -> Result<Vec<TimeEntry>, AppError> {
+    state
+        .with_read("list_time_entries", move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT {ENTRY_COLUMNS}
+                   FROM time_entries
+                   WHERE (?1 IS NULL OR clientId = ?1) AND (?2 = 0 OR billedInvoiceId IS NULL)
+                   ORDER BY date DESC, createdAt DESC"#
+            ))?;
+            let rows = stmt.query_map(params![client_id, unbilled_only.unwrap_or(false)], entry_from_row)?;
+            rows.collect()
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn create_time_entry(state: tauri::State<'_, DbState>, input: NewTimeEntry) -> Result<TimeEntry, AppError> {
+    let entry = TimeEntry {
+        id: Uuid::new_v4().to_string(),
+        client_id: input.client_id.trim().to_string(),
+        date: normalize_ymd("Date", &input.date)?,
+        minutes: input.minutes,
+        description: input.description.trim().to_string(),
+        hourly_rate: input.hourly_rate,
+        billed_invoice_id: None,
+        created_at: now_iso(),
+    };
+    state
+        .with_create_notify("create_time_entry", move |conn, _| {
+            validate(conn, &entry)?;
+            write_entry(conn, &entry)?;
+            Ok(entry)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn update_time_entry(
+    state: tauri::State<'_, DbState>,
+    id: String,
+    mut patch: TimeEntryPatch,
+) -> Result<Option<TimeEntry>, AppError> {
+    if let Some(d) = patch.date.as_deref() {
+        patch.date = Some(normalize_ymd("Date", d)?);
+    }
+    state
+        .with_write("update_time_entry", move |conn| {
+            let Some(mut entry) = read_entry(conn, &id)? else {
+                return Ok(None);
+            };
+            ensure_unbilled(&entry)?;
+            if let Some(v) = patch.client_id {
+                entry.client_id = v.trim().to_string();
+            }
+            if let Some(v) = patch.date {
+                entry.date = v;
+            }
+            if let Some(v) = patch.minutes {
+                entry.minutes = v;
+            }
+            if let Some(v) = patch.description {
+                entry.description = v.trim().to_string();
+            }
+            if let Some(v) = patch.hourly_rate {
+                entry.hourly_rate = v;
+            }
+            validate(conn, &entry)?;
+            write_entry(conn, &entry)?;
+            Ok(Some(entry))
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_time_entry(state: tauri::State<'_, DbState>, id: String) -> Result<bool, AppError> {
+    state
+        .with_write("delete_time_entry", move |conn| {
+            let Some(entry) = read_entry(conn, &id)? else {
+                return Ok(false);
+            };
+            ensure_unbilled(&entry)?;
+            Ok(conn.execute("DELETE FROM time_entries WHERE id = ?1", params![id])? > 0)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn get_active_timer(state: tauri::State<'_, DbState>) -> Result<Option<ActiveTimer>, AppError> {
+    state.with_read("get_active_timer", read_timer).await
+}
+
+#[tauri::command]
+pub(crate) async fn start_timer(
+    state: tauri::State<'_, DbState>,
+    client_id: String,
+    description: Option<String>,
+    hourly_rate: f64,
+) -> Result<ActiveTimer, AppError> {
+    let timer = ActiveTimer {
+        client_id: client_id.trim().to_string(),
+        description: description.unwrap_or_default(),
+        hourly_rate,
+        started_at: now_iso(),
+    };
+    state
+        .with_write("start_timer", move |conn| start_timer_in_conn(conn, timer))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn stop_timer(state: tauri::State<'_, DbState>) -> Result<Option<TimeEntry>, AppError> {
+    state
+        .with_write("stop_timer", |conn| stop_timer_in_conn(conn, OffsetDateTime::now_utc()))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn bill_time_entries(
+    state: tauri::State<'_, DbState>,
+    entry_ids: Vec<String>,
+    invoice_id: String,
+) -> Result<Option<Invoice>, AppError> {
+    if entry_ids.is_empty() {
+        return Err(AppError::validation("entryIds", "required", "Select at least one time entry."));
+    }
+    state
+        // Billing adds invoice items, so it is gated like creating an invoice.
+        .with_create_notify("bill_time_entries", move |conn, changes| {
+            let invoice = bill_time_entries_in_conn(conn, &entry_ids, &invoice_id)?;
+            if invoice.is_some() {
+                changes.record(DataEntity::Invoices, DataOp::Updated, invoice_id);
+            }
+            Ok(invoice)
+        })
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn unbilled_time_report(state: tauri::State<'_, DbState>) -> Result<Vec<UnbilledTime>, AppError> {
+    state.with_read("unbilled_time_report", unbilled_time_in_conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        for (id, name) in [("c1", "Acme"), ("c2", "Drugi")] {
+            let json = serde_json::json!({"id": id, "name": name, "pib": "", "address": "", "email": "", "createdAt": "t"});
+            conn.execute(
+                "INSERT INTO clients (id, name, pib, address, email, createdAt, data_json) VALUES (?1, ?2, '', '', '', 't', ?3)",
+                params![id, name, json.to_string()],
+            )
+            .unwrap();
+        }
+        let invoice = |id: &str, status: &str| {
+            serde_json::json!({
+                "id": id, "invoiceNumber": id, "clientId": "c1", "clientName": "Acme",
+                "issueDate": "2025-03-01", "serviceDate": "2025-03-01", "status": status,
+                "currency": "RSD", "subtotal": 0.0, "total": 0.0, "notes": "", "createdAt": "t", "items": []
+            })
+            .to_string()
+        };
+        for (id, status) in [("draft", "DRAFT"), ("sent", "SENT")] {
+            conn.execute(
+                "INSERT INTO invoices (id, invoiceNumber, clientId, issueDate, status, currency, totalAmount, createdAt, data_json)
+                 VALUES (?1, ?1, 'c1', '2025-03-01', ?2, 'RSD', 0, 't', ?3)",
+                params![id, status, invoice(id, status)],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn entry(id: &str, client: &str, date: &str, minutes: i64, description: &str, rate: f64) -> TimeEntry {
+        TimeEntry {
+            id: id.to_string(),
+            client_id: client.to_string(),
+            date: date.to_string(),
+            minutes,
+            description: description.to_string(),
+            hourly_rate: rate,
+            billed_invoice_id: None,
+            created_at: "t".to_string(),
+        }
+    }
+
+    #[test]
+    fn billing_groups_entries_and_excludes_them_afterwards() {
+        let mut conn = setup();
+        write_entry(&conn, &entry("a", "c1", "2025-03-02", 50, "Razvoj", 3000.0)).unwrap();
+        write_entry(&conn, &entry("b", "c1", "2025-03-01", 45, "Razvoj", 3000.0)).unwrap();
+        write_entry(&conn, &entry("c", "c1", "2025-03-03", 20, "Konsultacije", 4000.0)).unwrap();
+        write_entry(&conn, &entry("d", "c2", "2025-03-03", 60, "Razvoj", 3000.0)).unwrap();
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let err = bill_time_entries_in_conn(&mut conn, &ids(&["a"]), "sent").unwrap_err();
+        assert!(err.to_string().contains("draft"), "{err}");
+        let err = bill_time_entries_in_conn(&mut conn, &ids(&["a", "d"]), "draft").unwrap_err();
+        assert!(err.to_string().contains("another client"), "{err}");
+        assert!(read_entry(&conn, "a").unwrap().unwrap().billed_invoice_id.is_none());
+
+        let invoice = bill_time_entries_in_conn(&mut conn, &ids(&["a", "b", "c", "a"]), "draft").unwrap().unwrap();
+        let items: Vec<_> = invoice
+            .items
+            .iter()
+            .map(|i| (i.description.as_str(), i.unit.as_deref(), i.quantity, i.unit_price, i.total))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("Razvoj", Some("sat"), 1.58, 3000.0, 4740.0),
+                ("Konsultacije", Some("sat"), 0.33, 4000.0, 1320.0),
+            ]
+        );
+        assert_eq!(invoice.total, 6060.0);
+
+        let err = bill_time_entries_in_conn(&mut conn, &ids(&["c"]), "draft").unwrap_err();
+        assert!(err.to_string().contains("already been billed"), "{err}");
+        let unbilled = unbilled_time_in_conn(&conn).unwrap();
+        assert_eq!(unbilled.len(), 1);
+        assert_eq!((unbilled[0].client_name.as_str(), unbilled[0].minutes, unbilled[0].amount), ("Drugi", 60, 3000.0));
+    }
+
+    #[test]
+    fn timer_is_single_and_stops_into_an_entry() {
+        let mut conn = setup();
+        let timer = ActiveTimer {
+            client_id: "c1".to_string(),
+            description: " Podrška ".to_string(),
+            hourly_rate: 2500.0,
+            started_at: "2025-03-04T23:30:10Z".to_string(),
+        };
+        start_timer_in_conn(&conn, timer.clone()).unwrap();
+        let err = start_timer_in_conn(&conn, timer).unwrap_err();
+        assert!(err.to_string().contains("already running"), "{err}");
+
+        let now = OffsetDateTime::parse("2025-03-05T01:05:50Z", &Rfc3339).unwrap();
+        let stopped = stop_timer_in_conn(&mut conn, now).unwrap().unwrap();
+        assert_eq!((stopped.date.as_str(), stopped.minutes, stopped.description.as_str()), ("2025-03-04", 96, "Podrška"));
+        assert!(read_timer(&conn).unwrap().is_none());
+        assert!(stop_timer_in_conn(&mut conn, now).unwrap().is_none());
+    }
+}
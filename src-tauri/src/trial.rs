@@ -0,0 +1,246 @@
+//! The 30-day trial before a license is required. The trial start is kept in `app_meta` together
+//! with the latest time the app has seen, sealed with a hash so an edited value is noticed.
+//! Turning the clock back doesn't give days back, and a deleted record restarts the trial from
+//! the oldest client, invoice or expense instead of from today.
+//!
+//! Once the trial has ended without a valid license, commands that create data or send email
+//! fail with [`AppError::LicenseRequired`]; reading and exporting keep working.
+
+use base64::Engine as _;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::errors::AppError;
+use crate::license::crypto::sha256_hex;
+use crate::license_activation::has_valid_license;
+use crate::{app_meta_get, app_meta_set, DbState};
+
+pub(crate) const TRIAL_DAYS: i64 = 30;
+
+const TRIAL_KEY: &str = "trial_record";
+
+/// Trial start written by the frontend before the trial moved here.
+const LEGACY_TRIAL_START_KEY: &str = "trialStartDate";
+
+const TRIAL_SEAL: &str = "pausaler-trial-v1";
+
+const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrialRecord {
+    started_at: i64,
+    /// Latest clock reading seen; never moves back.
+    last_seen: i64,
+}
+
+impl TrialRecord {
+    fn mac(&self) -> String {
+        sha256_hex(&format!("{TRIAL_SEAL}|{}|{}", self.started_at, self.last_seen))[..16].to_string()
+    }
+
+    fn seal(&self) -> String {
+        let text = format!("{}.{}.{}", self.started_at, self.last_seen, self.mac());
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(text)
+    }
+
+    fn unseal(stored: &str) -> Option<TrialRecord> {
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(stored.trim()).ok()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let mut parts = text.split('.');
+        let record = TrialRecord {
+            started_at: parts.next()?.parse().ok()?,
+            last_seen: parts.next()?.parse().ok()?,
+        };
+        (parts.next()? == record.mac() && parts.next().is_none()).then_some(record)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrialStatus {
+    pub started_at: String,
+    pub ends_at: String,
+    pub days_remaining: i64,
+    pub expired: bool,
+}
+
+fn iso(ts: i64) -> String {
+    OffsetDateTime::from_unix_timestamp(ts)
+        .ok()
+        .and_then(|t| t.format(&Rfc3339).ok())
+        .unwrap_or_default()
+}
+
+fn parse_iso(s: &str) -> Option<i64> {
+    OffsetDateTime::parse(s.trim(), &Rfc3339).ok().map(OffsetDateTime::unix_timestamp)
+}
+
+/// Where a trial without a record starts: the legacy frontend trial or the oldest data.
+fn inferred_start(conn: &Connection) -> Result<Option<i64>, rusqlite::Error> {
+    let legacy = app_meta_get(conn, LEGACY_TRIAL_START_KEY)?.as_deref().and_then(parse_iso);
+    let oldest: Option<String> = conn
+        .query_row(
+            "SELECT MIN(createdAt) FROM (
+                 SELECT createdAt FROM clients
+                 UNION ALL SELECT createdAt FROM invoices
+                 UNION ALL SELECT createdAt FROM expenses
+             )",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?
+        .flatten();
+    let oldest = oldest.as_deref().and_then(parse_iso);
+    Ok([legacy, oldest].into_iter().flatten().min())
+}
+
+/// The trial as it stands at `now`, plus whether that differs from the stored record. Nothing
+/// is written.
+fn peek_trial(conn: &Connection, now: i64) -> Result<(TrialRecord, bool), rusqlite::Error> {
+    let stored = app_meta_get(conn, TRIAL_KEY)?;
+    let mut record = match stored.as_deref() {
+        Some(s) => TrialRecord::unseal(s).unwrap_or(TrialRecord {
+            // An edited record ends the trial.
+            started_at: now - TRIAL_DAYS * DAY_SECONDS,
+            last_seen: now,
+        }),
+        None => {
+            let started_at = inferred_start(conn)?.map_or(now, |s| s.min(now));
+            TrialRecord { started_at, last_seen: now }
+        }
+    };
+    record.last_seen = record.last_seen.max(now);
+    let changed = stored.as_deref() != Some(record.seal().as_str());
+    Ok((record, changed))
+}
+
+/// Loads the trial (starting it on first use) and records `now` as seen.
+fn touch_trial(conn: &Connection, now: i64) -> Result<TrialRecord, rusqlite::Error> {
+    let (record, changed) = peek_trial(conn, now)?;
+    if changed {
+        app_meta_set(conn, TRIAL_KEY, &record.seal())?;
+    }
+    Ok(record)
+}
+
+fn trial_status(record: TrialRecord) -> TrialStatus {
+    let ends_at = record.started_at + TRIAL_DAYS * DAY_SECONDS;
+    let left = ends_at - record.last_seen;
+    TrialStatus {
+        started_at: iso(record.started_at),
+        ends_at: iso(ends_at),
+        days_remaining: ((left + DAY_SECONDS - 1) / DAY_SECONDS).max(0),
+        expired: left <= 0,
+    }
+}
+
+fn trial_status_at(conn: &Connection, now: i64) -> Result<TrialStatus, rusqlite::Error> {
+    touch_trial(conn, now).map(trial_status)
+}
+
+/// Whether the trial still runs at `now` (unix seconds).
+pub(crate) fn trial_active(conn: &Connection, now: i64) -> Result<bool, rusqlite::Error> {
+    Ok(!trial_status_at(conn, now)?.expired)
+}
+
+/// Fails with [`AppError::LicenseRequired`] when the trial is over and no valid license is stored.
+pub(crate) fn require_license(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let now = OffsetDateTime::now_utc();
+    if has_valid_license(conn, now)? || trial_active(conn, now.unix_timestamp())? {
+        return Ok(());
+    }
+    Err(AppError::LicenseRequired.into())
+}
+
+/// Ends the trial the way an edited record does.
+#[cfg(test)]
+pub(crate) fn end_trial(conn: &Connection) -> Result<(), rusqlite::Error> {
+    app_meta_set(conn, TRIAL_KEY, "edited")
+}
+
+/// Creates the trial on first launch.
+pub(crate) fn start_trial(conn: &Connection) -> Result<(), rusqlite::Error> {
+    touch_trial(conn, OffsetDateTime::now_utc().unix_timestamp()).map(|_| ())
+}
+
+#[tauri::command]
+pub(crate) async fn get_trial_status(state: tauri::State<'_, DbState>) -> Result<TrialStatus, AppError> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    // The clock reading is recorded only when the database can be written; read-only mode still
+    // reports the status.
+    if state.mode.read_only().is_none() {
+        match state.with_write("get_trial_status", move |conn| trial_status_at(conn, now)).await {
+            Err(AppError::ReadOnly(_)) => {}
+            result => return result,
+        }
+    }
+    state
+        .with_read("get_trial_status", move |conn| peek_trial(conn, now).map(|(record, _)| trial_status(record)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::apply_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn status_can_be_read_without_recording_the_trial() {
+        let conn = open_db();
+        let t0 = 1_750_000_000;
+        let (record, changed) = peek_trial(&conn, t0).unwrap();
+        assert!(changed);
+        assert_eq!(trial_status(record).days_remaining, TRIAL_DAYS);
+        assert_eq!(app_meta_get(&conn, TRIAL_KEY).unwrap(), None);
+
+        trial_status_at(&conn, t0).unwrap();
+        let (record, changed) = peek_trial(&conn, t0 + 20 * DAY_SECONDS).unwrap();
+        assert!(changed);
+        assert_eq!(trial_status(record).days_remaining, 10);
+        // The later reading wasn't kept, so the clock going back to `t0` still shows the whole trial.
+        assert_eq!(trial_status_at(&conn, t0).unwrap().days_remaining, TRIAL_DAYS);
+    }
+
+    #[test]
+    fn moving_the_clock_back_does_not_extend_the_trial() {
+        let conn = open_db();
+        let t0 = 1_750_000_000;
+        assert_eq!(trial_status_at(&conn, t0).unwrap().days_remaining, TRIAL_DAYS);
+        assert_eq!(trial_status_at(&conn, t0 + 20 * DAY_SECONDS).unwrap().days_remaining, 10);
+
+        let back = trial_status_at(&conn, t0 + 5 * DAY_SECONDS).unwrap();
+        assert_eq!((back.days_remaining, back.expired), (10, false));
+        assert!(trial_status_at(&conn, t0 + 30 * DAY_SECONDS).unwrap().expired);
+        assert!(trial_status_at(&conn, t0).unwrap().expired);
+    }
+
+    #[test]
+    fn edited_or_deleted_records_do_not_restart_the_trial() {
+        let conn = open_db();
+        let t0 = 1_750_000_000;
+        app_meta_set(&conn, LEGACY_TRIAL_START_KEY, &iso(t0)).unwrap();
+        let status = trial_status_at(&conn, t0 + 3 * DAY_SECONDS).unwrap();
+        assert_eq!((status.started_at, status.days_remaining), (iso(t0), TRIAL_DAYS - 3));
+
+        let forged = TrialRecord { started_at: t0 + 40 * DAY_SECONDS, last_seen: t0 + 40 * DAY_SECONDS };
+        let text = format!("{}.{}.{}", forged.started_at, forged.last_seen, "0".repeat(16));
+        app_meta_set(&conn, TRIAL_KEY, &base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(text)).unwrap();
+        assert!(trial_status_at(&conn, t0 + 40 * DAY_SECONDS).unwrap().expired);
+
+        conn.execute("DELETE FROM app_meta", []).unwrap();
+        conn.execute(
+            "INSERT INTO clients (id, name, pib, address, email, createdAt) VALUES ('c1', 'Kupac', '', '', '', ?1)",
+            [iso(t0)],
+        )
+        .unwrap();
+        assert!(trial_status_at(&conn, t0 + 40 * DAY_SECONDS).unwrap().expired);
+    }
+}
@@ -0,0 +1,230 @@
+//! Yearly turnover against the paušal limit and the VAT threshold (both in RSD, configurable in
+//! settings). Turnover is the total of SENT and PAID invoices issued in the calendar year.
+
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use serde::Serialize;
+use time::{Date, OffsetDateTime};
+
+use crate::errors::AppError;
+use crate::{invoice_total_in_currency, read_settings_from_conn, DbState, Invoice, Settings};
+
+/// Share of a limit at which a warning is raised before the limit itself.
+const WARNING_SHARE: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitKind {
+    /// Paušal status is lost above this turnover.
+    Turnover,
+    /// VAT registration is required above this turnover.
+    Vat,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitUsage {
+    pub kind: LimitKind,
+    pub limit_rsd: f64,
+    pub used_percent: f64,
+    pub remaining_rsd: f64,
+    pub projected_percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitStatus {
+    pub year: i32,
+    pub turnover_rsd: f64,
+    pub invoice_count: usize,
+    /// Year-end turnover at the current run rate (equal to `turnover_rsd` for past years).
+    pub projected_turnover_rsd: f64,
+    pub limits: Vec<LimitUsage>,
+    /// Invoice numbers left out because they couldn't be converted to RSD (no exchange rate).
+    pub unconverted_invoices: Vec<String>,
+}
+
+/// Raised by `create_invoice` when the new invoice pushes turnover past 80% or 100% of a limit.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitWarning {
+    pub kind: LimitKind,
+    /// 80 or 100.
+    pub threshold_percent: u8,
+    pub limit_rsd: f64,
+    pub turnover_rsd: f64,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn limits(settings: &Settings) -> [(LimitKind, f64); 2] {
+    [
+        (LimitKind::Turnover, settings.turnover_limit_rsd),
+        (LimitKind::Vat, settings.vat_limit_rsd),
+    ]
+}
+
+/// Payable amount in RSD. Stored rates convert into the default currency, so foreign invoices
+/// only convert when that is RSD.
+pub(crate) fn invoice_total_rsd(invoice: &Invoice, default_currency: &str) -> Option<f64> {
+    if invoice.currency.trim().eq_ignore_ascii_case("RSD") || default_currency.trim().eq_ignore_ascii_case("RSD") {
+        invoice_total_in_currency(invoice, "RSD")
+    } else {
+        None
+    }
+}
+
+/// Turnover of `year` in RSD, the number of counted invoices and the ones that couldn't be converted.
+fn turnover_rsd_in_conn(
+    conn: &Connection,
+    year: i32,
+    default_currency: &str,
+    exclude_id: Option<&str>,
+) -> Result<(f64, usize, Vec<String>), rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"SELECT data_json
+           FROM invoices
+           WHERE status IN ('SENT', 'PAID') AND issueDate >= ?1 AND issueDate <= ?2
+             AND (?3 IS NULL OR id <> ?3)
+           ORDER BY issueDate ASC, createdAt ASC"#,
+    )?;
+    let mut rows = stmt.query(params![format!("{year:04}-01-01"), format!("{year:04}-12-31"), exclude_id])?;
+    let mut total = 0.0;
+    let mut count = 0;
+    let mut unconverted = Vec::new();
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        let Ok(inv) = serde_json::from_str::<Invoice>(&json) else {
+            continue;
+        };
+        match invoice_total_rsd(&inv, default_currency) {
+            Some(v) => {
+                total += v;
+                count += 1;
+            }
+            None => unconverted.push(inv.invoice_number),
+        }
+    }
+    Ok((total, count, unconverted))
+}
+
+pub(crate) fn limit_status_in_conn(conn: &Connection, year: i32, today: Date) -> Result<LimitStatus, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let (turnover, invoice_count, unconverted_invoices) =
+        turnover_rsd_in_conn(conn, year, &settings.default_currency, None)?;
+
+    let days_in_year = time::util::days_in_year(year) as f64;
+    let projected = if today.year() == year {
+        turnover / today.ordinal() as f64 * days_in_year
+    } else {
+        turnover
+    };
+
+    let percent = |v: f64, limit: f64| round2(v / limit * 100.0);
+    let limits = limits(&settings)
+        .into_iter()
+        .map(|(kind, limit)| LimitUsage {
+            kind,
+            limit_rsd: limit,
+            used_percent: percent(turnover, limit),
+            remaining_rsd: round2((limit - turnover).max(0.0)),
+            projected_percent: percent(projected, limit),
+        })
+        .collect();
+
+    Ok(LimitStatus {
+        year,
+        turnover_rsd: round2(turnover),
+        invoice_count,
+        projected_turnover_rsd: round2(projected),
+        limits,
+        unconverted_invoices,
+    })
+}
+
+/// Warnings for limits whose 80% or 100% mark lies in `(before, after]`; only the highest mark
+/// crossed per limit is reported.
+fn crossed_limits(settings: &Settings, before: f64, after: f64) -> Vec<LimitWarning> {
+    limits(settings)
+        .into_iter()
+        .filter_map(|(kind, limit)| {
+            [(100, 1.0), (80, WARNING_SHARE)]
+                .into_iter()
+                .find(|(_, share)| before < limit * share && after >= limit * share)
+                .map(|(threshold_percent, _)| LimitWarning {
+                    kind,
+                    threshold_percent,
+                    limit_rsd: limit,
+                    turnover_rsd: round2(after),
+                })
+        })
+        .collect()
+}
+
+/// Limit warnings caused by adding `invoice` to the turnover of its issue year. Non-blocking:
+/// invoices that can't be converted to RSD produce no warning.
+pub(crate) fn limit_warnings_for_invoice(conn: &Connection, invoice: &Invoice) -> Result<Vec<LimitWarning>, rusqlite::Error> {
+    let Some(year) = invoice.issue_date.get(..4).and_then(|y| y.parse::<i32>().ok()) else {
+        return Ok(Vec::new());
+    };
+    let settings = read_settings_from_conn(conn)?;
+    let Some(amount) = invoice_total_rsd(invoice, &settings.default_currency) else {
+        return Ok(Vec::new());
+    };
+    let (before, _, _) = turnover_rsd_in_conn(conn, year, &settings.default_currency, Some(&invoice.id))?;
+    Ok(crossed_limits(&settings, before, before + amount))
+}
+
+/// Turnover for `year` against the paušal limit and the VAT threshold.
+#[tauri::command]
+pub(crate) async fn limit_status(state: tauri::State<'_, DbState>, year: i32) -> Result<LimitStatus, AppError> {
+    let today = OffsetDateTime::now_utc().date();
+    state
+        .with_read("limit_status", move |conn| limit_status_in_conn(conn, year, today))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::invoice;
+    use time::Month;
+
+    #[test]
+    fn status_converts_foreign_invoices_and_projects_the_run_rate() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        invoice("a").issued("2025-01-10").status("PAID").amount("RSD", 1_000_000.0).insert(&conn);
+        invoice("b").issued("2025-02-10").amount("EUR", 10_000.0).exchange_rate(117.2).insert(&conn);
+        invoice("c").issued("2025-02-11").amount("USD", 500.0).insert(&conn);
+        invoice("d").issued("2025-02-12").status("DRAFT").amount("RSD", 9_000_000.0).insert(&conn);
+
+        let today = Date::from_calendar_date(2025, Month::March, 1).unwrap(); // day 60 of 365
+        let s = limit_status_in_conn(&conn, 2025, today).unwrap();
+        assert_eq!((s.turnover_rsd, s.invoice_count), (2_172_000.0, 2));
+        assert_eq!(s.unconverted_invoices, vec!["c".to_string()]);
+        assert_eq!(s.projected_turnover_rsd, round2(2_172_000.0 / 60.0 * 365.0));
+        assert_eq!(s.limits[0].kind, LimitKind::Turnover);
+        assert_eq!((s.limits[0].used_percent, s.limits[0].remaining_rsd), (36.2, 3_828_000.0));
+        assert_eq!(s.limits[1].used_percent, 27.15);
+
+        let past = limit_status_in_conn(&conn, 2025, Date::from_calendar_date(2026, Month::June, 1).unwrap()).unwrap();
+        assert_eq!(past.projected_turnover_rsd, past.turnover_rsd);
+    }
+
+    #[test]
+    fn warnings_fire_once_when_a_mark_is_crossed() {
+        let settings = crate::default_settings();
+        assert!(crossed_limits(&settings, 1_000_000.0, 4_000_000.0).is_empty());
+
+        let w = crossed_limits(&settings, 4_700_000.0, 4_800_000.0);
+        assert_eq!(w.len(), 1);
+        assert_eq!((w[0].kind, w[0].threshold_percent), (LimitKind::Turnover, 80));
+        assert!(crossed_limits(&settings, 4_800_000.0, 4_900_000.0).is_empty());
+
+        let w = crossed_limits(&settings, 5_900_000.0, 6_500_000.0);
+        assert_eq!(w.iter().map(|w| (w.kind, w.threshold_percent)).collect::<Vec<_>>(), vec![(LimitKind::Turnover, 100), (LimitKind::Vat, 80)]);
+    }
+}
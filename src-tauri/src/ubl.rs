@@ -0,0 +1,447 @@
+//! Minimal UBL 2.1 Invoice XML for clients that need a machine-readable invoice.
+//!
+//! Built from the same [`InvoicePdfPayload`] as the PDF so both documents show identical totals.
+//! Elements are written in the order required by the UBL 2.1 schema.
+
+use rusqlite::Connection;
+
+use crate::{
+    build_invoice_pdf_payload_from_db, read_client_from_conn, read_invoice_from_conn,
+    read_settings_from_conn, validation_to_sql_error, InvoicePdfPayload, PaymentMethod,
+};
+
+const UBL_INVOICE_NS: &str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+const UBL_CAC_NS: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2";
+const UBL_CBC_NS: &str = "urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2";
+const COUNTRY_CODE: &str = "RS";
+/// UNCL1001 commercial invoice.
+const INVOICE_TYPE_CODE: &str = "380";
+/// UNCL5305 "not subject to VAT": paušal entrepreneurs outside the VAT system.
+const TAX_CATEGORY_ID: &str = "O";
+const TAX_EXEMPTION_REASON: &str = "Oslobođeno od PDV-a po članu 33. Zakona o porezu na dodatu vrednost.";
+
+/// Loads the invoice with its client and seller settings and renders it as UBL XML.
+pub(crate) fn generate_ubl_xml(conn: &Connection, invoice_id: &str) -> Result<String, rusqlite::Error> {
+    let settings = read_settings_from_conn(conn)?;
+    let invoice = read_invoice_from_conn(conn, invoice_id)?
+        .ok_or_else(|| validation_to_sql_error("Invoice not found".to_string()))?;
+    let client = read_client_from_conn(conn, &invoice.client_id)?;
+    let payload = build_invoice_pdf_payload_from_db(&invoice, client.as_ref(), &settings);
+    Ok(render_ubl_invoice(&payload, invoice.due_date.as_deref()))
+}
+
+pub(crate) fn render_ubl_invoice(p: &InvoicePdfPayload, due_date: Option<&str>) -> String {
+    let currency = xml_escape(p.currency.trim());
+    let amount = |v: f64| format!("<cbc:Amount currencyID=\"{currency}\">{}</cbc:Amount>", money(v));
+    let tagged = |tag: &str, v: f64| format!("<cbc:{tag} currencyID=\"{currency}\">{}</cbc:{tag}>", money(v));
+
+    let line_extension_total: f64 = p.items.iter().map(|it| it.total).sum();
+    let invoice_discount = p.invoice_discount.unwrap_or(0.0);
+
+    let mut x = String::new();
+    x.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    x.push_str(&format!(
+        "<Invoice xmlns=\"{UBL_INVOICE_NS}\" xmlns:cac=\"{UBL_CAC_NS}\" xmlns:cbc=\"{UBL_CBC_NS}\">\n"
+    ));
+    x.push_str("  <cbc:UBLVersionID>2.1</cbc:UBLVersionID>\n");
+    x.push_str(&format!("  <cbc:ID>{}</cbc:ID>\n", xml_escape(&p.invoice_number)));
+    x.push_str(&format!("  <cbc:IssueDate>{}</cbc:IssueDate>\n", xml_escape(&p.issue_date)));
+    if let Some(due) = due_date.map(str::trim).filter(|d| !d.is_empty()) {
+        x.push_str(&format!("  <cbc:DueDate>{}</cbc:DueDate>\n", xml_escape(due)));
+    }
+    x.push_str(&format!("  <cbc:InvoiceTypeCode>{INVOICE_TYPE_CODE}</cbc:InvoiceTypeCode>\n"));
+    if let Some(notes) = p.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        x.push_str(&format!("  <cbc:Note>{}</cbc:Note>\n", xml_escape(notes)));
+    }
+    x.push_str(&format!("  <cbc:DocumentCurrencyCode>{currency}</cbc:DocumentCurrencyCode>\n"));
+
+    let c = &p.company;
+    x.push_str("  <cac:AccountingSupplierParty>\n");
+    push_party(
+        &mut x,
+        &Party {
+            name: &c.company_name,
+            street: c.address_line.as_deref(),
+            city: c.city.as_deref(),
+            postal_code: c.postal_code.as_deref(),
+            country: None,
+            pib: Some(c.pib.as_str()),
+            registration_number: Some(c.registration_number.as_str()),
+            email: c.email.as_deref(),
+        },
+    );
+    x.push_str("  </cac:AccountingSupplierParty>\n");
+
+    let b = &p.client;
+    x.push_str("  <cac:AccountingCustomerParty>\n");
+    push_party(
+        &mut x,
+        &Party {
+            name: &b.name,
+            street: b.address_line.as_deref().or(b.address.as_deref()),
+            city: b.city.as_deref(),
+            postal_code: b.postal_code.as_deref(),
+            country: b.country.as_deref().filter(|_| b.is_foreign),
+            pib: b.pib.as_deref(),
+            registration_number: b.registration_number.as_deref(),
+            email: b.email.as_deref(),
+        },
+    );
+    x.push_str("  </cac:AccountingCustomerParty>\n");
+
+    if !p.service_date.trim().is_empty() {
+        x.push_str(&format!(
+            "  <cac:Delivery><cbc:ActualDeliveryDate>{}</cbc:ActualDeliveryDate></cac:Delivery>\n",
+            xml_escape(p.service_date.trim())
+        ));
+    }
+
+    let means_code = match p.payment_method.unwrap_or(PaymentMethod::Transfer) {
+        PaymentMethod::Transfer => "30",
+        PaymentMethod::Cash => "10",
+        PaymentMethod::Card => "48",
+    };
+    x.push_str("  <cac:PaymentMeans>\n");
+    x.push_str(&format!("    <cbc:PaymentMeansCode>{means_code}</cbc:PaymentMeansCode>\n"));
+    let reference = p
+        .payment_reference
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or(p.invoice_number.as_str());
+    x.push_str(&format!("    <cbc:PaymentID>{}</cbc:PaymentID>\n", xml_escape(reference)));
+    // Foreign-currency invoices are paid to the IBAN, with the SWIFT code as the branch ID.
+    let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let account_id = non_empty(&c.iban).unwrap_or_else(|| c.bank_account.trim().to_string());
+    if !account_id.is_empty() {
+        x.push_str(&format!("    <cac:PayeeFinancialAccount><cbc:ID>{}</cbc:ID>", xml_escape(&account_id)));
+        if let Some(swift) = non_empty(&c.swift) {
+            x.push_str(&format!(
+                "<cac:FinancialInstitutionBranch><cbc:ID>{}</cbc:ID></cac:FinancialInstitutionBranch>",
+                xml_escape(&swift)
+            ));
+        }
+        x.push_str("</cac:PayeeFinancialAccount>\n");
+    }
+    x.push_str("  </cac:PaymentMeans>\n");
+
+    if invoice_discount > 0.0 {
+        x.push_str("  <cac:AllowanceCharge>\n");
+        x.push_str("    <cbc:ChargeIndicator>false</cbc:ChargeIndicator>\n");
+        x.push_str("    <cbc:AllowanceChargeReason>Discount</cbc:AllowanceChargeReason>\n");
+        x.push_str(&format!("    {}\n", amount(invoice_discount)));
+        push_tax_category(&mut x, "    ", "TaxCategory");
+        x.push_str("  </cac:AllowanceCharge>\n");
+    }
+
+    x.push_str("  <cac:TaxTotal>\n");
+    x.push_str(&format!("    {}\n", tagged("TaxAmount", 0.0)));
+    x.push_str("    <cac:TaxSubtotal>\n");
+    x.push_str(&format!("      {}\n", tagged("TaxableAmount", p.total)));
+    x.push_str(&format!("      {}\n", tagged("TaxAmount", 0.0)));
+    push_tax_category(&mut x, "      ", "TaxCategory");
+    x.push_str("    </cac:TaxSubtotal>\n");
+    x.push_str("  </cac:TaxTotal>\n");
+
+    x.push_str("  <cac:LegalMonetaryTotal>\n");
+    x.push_str(&format!("    {}\n", tagged("LineExtensionAmount", line_extension_total)));
+    x.push_str(&format!("    {}\n", tagged("TaxExclusiveAmount", p.total)));
+    x.push_str(&format!("    {}\n", tagged("TaxInclusiveAmount", p.total)));
+    if invoice_discount > 0.0 {
+        x.push_str(&format!("    {}\n", tagged("AllowanceTotalAmount", invoice_discount)));
+    }
+    x.push_str(&format!("    {}\n", tagged("PayableAmount", p.total)));
+    x.push_str("  </cac:LegalMonetaryTotal>\n");
+
+    for (i, it) in p.items.iter().enumerate() {
+        x.push_str("  <cac:InvoiceLine>\n");
+        x.push_str(&format!("    <cbc:ID>{}</cbc:ID>\n", i + 1));
+        x.push_str(&format!(
+            "    <cbc:InvoicedQuantity unitCode=\"{}\">{}</cbc:InvoicedQuantity>\n",
+            unit_code(it.unit.as_deref()),
+            quantity(it.quantity)
+        ));
+        x.push_str(&format!("    {}\n", tagged("LineExtensionAmount", it.total)));
+        if let Some(discount) = it.discount_amount.filter(|d| *d > 0.0) {
+            x.push_str("    <cac:AllowanceCharge>\n");
+            x.push_str("      <cbc:ChargeIndicator>false</cbc:ChargeIndicator>\n");
+            x.push_str("      <cbc:AllowanceChargeReason>Discount</cbc:AllowanceChargeReason>\n");
+            x.push_str(&format!("      {}\n", amount(discount)));
+            x.push_str("    </cac:AllowanceCharge>\n");
+        }
+        x.push_str("    <cac:Item>\n");
+        x.push_str(&format!("      <cbc:Name>{}</cbc:Name>\n", xml_escape(it.description.trim())));
+        push_tax_category(&mut x, "      ", "ClassifiedTaxCategory");
+        x.push_str("    </cac:Item>\n");
+        x.push_str(&format!(
+            "    <cac:Price>{}</cac:Price>\n",
+            tagged("PriceAmount", it.unit_price)
+        ));
+        x.push_str("  </cac:InvoiceLine>\n");
+    }
+
+    x.push_str("</Invoice>\n");
+    x
+}
+
+struct Party<'a> {
+    name: &'a str,
+    street: Option<&'a str>,
+    city: Option<&'a str>,
+    postal_code: Option<&'a str>,
+    /// ISO 3166-1 alpha-2; Serbia when absent.
+    country: Option<&'a str>,
+    pib: Option<&'a str>,
+    registration_number: Option<&'a str>,
+    email: Option<&'a str>,
+}
+
+fn non_empty(v: Option<&str>) -> Option<&str> {
+    v.map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn push_party(x: &mut String, party: &Party<'_>) {
+    x.push_str("    <cac:Party>\n");
+    x.push_str(&format!(
+        "      <cac:PartyName><cbc:Name>{}</cbc:Name></cac:PartyName>\n",
+        xml_escape(party.name.trim())
+    ));
+    x.push_str("      <cac:PostalAddress>\n");
+    if let Some(v) = non_empty(party.street) {
+        x.push_str(&format!("        <cbc:StreetName>{}</cbc:StreetName>\n", xml_escape(v)));
+    }
+    if let Some(v) = non_empty(party.city) {
+        x.push_str(&format!("        <cbc:CityName>{}</cbc:CityName>\n", xml_escape(v)));
+    }
+    if let Some(v) = non_empty(party.postal_code) {
+        x.push_str(&format!("        <cbc:PostalZone>{}</cbc:PostalZone>\n", xml_escape(v)));
+    }
+    let country = non_empty(party.country).unwrap_or(COUNTRY_CODE);
+    x.push_str(&format!(
+        "        <cac:Country><cbc:IdentificationCode>{}</cbc:IdentificationCode></cac:Country>\n",
+        xml_escape(country)
+    ));
+    x.push_str("      </cac:PostalAddress>\n");
+    if let Some(pib) = non_empty(party.pib) {
+        x.push_str("      <cac:PartyTaxScheme>\n");
+        x.push_str(&format!("        <cbc:CompanyID>{}</cbc:CompanyID>\n", xml_escape(pib)));
+        x.push_str("        <cac:TaxScheme><cbc:ID>VAT</cbc:ID></cac:TaxScheme>\n");
+        x.push_str("      </cac:PartyTaxScheme>\n");
+    }
+    x.push_str("      <cac:PartyLegalEntity>\n");
+    x.push_str(&format!(
+        "        <cbc:RegistrationName>{}</cbc:RegistrationName>\n",
+        xml_escape(party.name.trim())
+    ));
+    if let Some(mb) = non_empty(party.registration_number) {
+        x.push_str(&format!("        <cbc:CompanyID>{}</cbc:CompanyID>\n", xml_escape(mb)));
+    }
+    x.push_str("      </cac:PartyLegalEntity>\n");
+    if let Some(email) = non_empty(party.email) {
+        x.push_str(&format!(
+            "      <cac:Contact><cbc:ElectronicMail>{}</cbc:ElectronicMail></cac:Contact>\n",
+            xml_escape(email)
+        ));
+    }
+    x.push_str("    </cac:Party>\n");
+}
+
+fn push_tax_category(x: &mut String, indent: &str, element: &str) {
+    x.push_str(&format!("{indent}<cac:{element}>\n"));
+    x.push_str(&format!("{indent}  <cbc:ID>{TAX_CATEGORY_ID}</cbc:ID>\n"));
+    if element == "TaxCategory" {
+        x.push_str(&format!(
+            "{indent}  <cbc:TaxExemptionReason>{}</cbc:TaxExemptionReason>\n",
+            xml_escape(TAX_EXEMPTION_REASON)
+        ));
+    }
+    x.push_str(&format!("{indent}  <cac:TaxScheme><cbc:ID>VAT</cbc:ID></cac:TaxScheme>\n"));
+    x.push_str(&format!("{indent}</cac:{element}>\n"));
+}
+
+/// UN/ECE Recommendation 20 codes for the app's units.
+fn unit_code(unit: Option<&str>) -> &'static str {
+    match unit.map(|u| u.trim().to_lowercase()).as_deref() {
+        Some("kom") => "H87",
+        Some("sat") | Some("h") => "HUR",
+        Some("m2") | Some("m²") => "MTK",
+        _ => "C62",
+    }
+}
+
+fn money(v: f64) -> String {
+    format!("{v:.2}")
+}
+
+fn quantity(v: f64) -> String {
+    let s = format!("{v:.4}");
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InvoicePdfClient, InvoicePdfCompany, InvoicePdfItem};
+
+    fn payload() -> InvoicePdfPayload {
+        InvoicePdfPayload {
+            language: Some("sr".into()),
+            invoice_number: "INV-0007".into(),
+            issue_date: "2024-04-01".into(),
+            service_date: "2024-03-31".into(),
+            currency: "RSD".into(),
+            subtotal: 13000.0,
+            discount_total: 1000.0,
+            invoice_discount: Some(500.0),
+            invoice_discount_percent: None,
+            total: 11500.0,
+            notes: Some("Hvala & pozdrav".into()),
+            payment_method: Some(PaymentMethod::Transfer),
+            payment_reference: Some("97 04-INV-0007".into()),
+            company: InvoicePdfCompany {
+                company_name: "Moja <Firma>".into(),
+                registration_number: "12345678".into(),
+                pib: "100000001".into(),
+                address: "Ulica 1".into(),
+                address_line: Some("Ulica 1".into()),
+                postal_code: Some("11000".into()),
+                city: Some("Beograd".into()),
+                bank_account: "160-0000000000000-00".into(),
+                iban: None,
+                swift: None,
+                email: Some("office@example.com".into()),
+                phone: None,
+            },
+            client: InvoicePdfClient {
+                name: "Klijent d.o.o.".into(),
+                registration_number: Some("87654321".into()),
+                pib: Some("100000002".into()),
+                address: Some("Bulevar 2".into()),
+                address_line: Some("Bulevar 2".into()),
+                postal_code: Some("21000".into()),
+                city: Some("Novi Sad".into()),
+                email: None,
+                phone: None,
+                is_foreign: false,
+                country: None,
+            },
+            items: vec![
+                InvoicePdfItem {
+                    description: "Razvoj softvera".into(),
+                    unit: Some("sat".into()),
+                    quantity: 10.0,
+                    unit_price: 1000.0,
+                    discount_amount: Some(1000.0),
+                    total: 9000.0,
+                },
+                InvoicePdfItem {
+                    description: "Hosting".into(),
+                    unit: None,
+                    quantity: 1.5,
+                    unit_price: 2000.0,
+                    discount_amount: None,
+                    total: 3000.0,
+                },
+            ],
+            legal_note_override: Default::default(),
+            valid_until: None,
+            issued_by: None,
+            custom_fields: Vec::new(),
+        }
+    }
+
+    fn child<'a, 'i>(node: roxmltree::Node<'a, 'i>, name: &str) -> roxmltree::Node<'a, 'i> {
+        node.children()
+            .find(|n| n.tag_name().name() == name)
+            .unwrap_or_else(|| panic!("missing <{name}>"))
+    }
+
+    fn amount_of(node: roxmltree::Node<'_, '_>, name: &str) -> f64 {
+        child(node, name).text().unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn ubl_round_trip_preserves_totals() {
+        let p = payload();
+        let xml = render_ubl_invoice(&p, Some("2024-04-15"));
+        let doc = roxmltree::Document::parse(&xml).expect("well-formed XML");
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().namespace(), Some(UBL_INVOICE_NS));
+        assert_eq!(child(root, "ID").text(), Some("INV-0007"));
+        assert_eq!(child(root, "DueDate").text(), Some("2024-04-15"));
+        assert_eq!(child(root, "DocumentCurrencyCode").text(), Some("RSD"));
+
+        let totals = child(root, "LegalMonetaryTotal");
+        let lines: Vec<_> = root
+            .children()
+            .filter(|n| n.tag_name().name() == "InvoiceLine")
+            .collect();
+        assert_eq!(lines.len(), 2);
+        let line_sum: f64 = lines.iter().map(|l| amount_of(*l, "LineExtensionAmount")).sum();
+        assert_eq!(amount_of(totals, "LineExtensionAmount"), line_sum);
+        assert_eq!(
+            amount_of(totals, "LineExtensionAmount") - amount_of(totals, "AllowanceTotalAmount"),
+            amount_of(totals, "TaxExclusiveAmount")
+        );
+        assert_eq!(amount_of(totals, "PayableAmount"), p.total);
+        assert_eq!(
+            child(totals, "PayableAmount").attribute("currencyID"),
+            Some("RSD")
+        );
+
+        let supplier = child(child(root, "AccountingSupplierParty"), "Party");
+        assert_eq!(
+            child(child(supplier, "PartyTaxScheme"), "CompanyID").text(),
+            Some("100000001")
+        );
+        assert_eq!(
+            child(child(supplier, "PartyLegalEntity"), "CompanyID").text(),
+            Some("12345678")
+        );
+        assert_eq!(
+            child(child(supplier, "PartyName"), "Name").text(),
+            Some("Moja <Firma>")
+        );
+        assert_eq!(child(lines[0], "InvoicedQuantity").attribute("unitCode"), Some("HUR"));
+        assert_eq!(child(lines[1], "InvoicedQuantity").text(), Some("1.5"));
+    }
+
+    #[test]
+    fn ubl_elements_follow_schema_order() {
+        let xml = render_ubl_invoice(&payload(), None);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let order: Vec<&str> = doc
+            .root_element()
+            .children()
+            .filter(|n| n.is_element())
+            .map(|n| n.tag_name().name())
+            .collect();
+        assert_eq!(
+            order,
+            [
+                "UBLVersionID",
+                "ID",
+                "IssueDate",
+                "InvoiceTypeCode",
+                "Note",
+                "DocumentCurrencyCode",
+                "AccountingSupplierParty",
+                "AccountingCustomerParty",
+                "Delivery",
+                "PaymentMeans",
+                "AllowanceCharge",
+                "TaxTotal",
+                "LegalMonetaryTotal",
+                "InvoiceLine",
+                "InvoiceLine",
+            ]
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Invoice event webhooks.
+//!
+//! Commands only insert a row into `webhook_deliveries`, in the same transaction as the change
+//! they announce, and only when a webhook URL is set; a failure to queue is logged and never fails
+//! the command. A single background worker POSTs due deliveries in order and reschedules failed
+//! ones with a growing delay, so events queued while the dashboard (or the network) is down are
+//! delivered once it is back. Invoices brought in by imports or archive restores are not announced.
+//!
+//! Every body is signed with HMAC-SHA256 under the webhook secret; the hex digest is sent as
+//! `X-Pausaler-Signature: sha256=<hex>`. Deliveries may repeat after a crash, so receivers should
+//! de-duplicate on the `id` field.
+
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::{
+    now_iso, read_settings_from_conn, secrets, sqlite_error_string, validation_to_sql_error, DbState, Invoice,
+    InvoiceStatus, SETTINGS_ID,
+};
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts before a delivery is given up; with the backoff below that is about three days.
+const MAX_ATTEMPTS: i64 = 20;
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 6 * 60 * 60;
+
+/// How long the worker sleeps when nothing is scheduled; queuing wakes it earlier.
+const IDLE_POLL_SECS: i64 = 5 * 60;
+
+/// Delivered rows older than this are removed when the app starts.
+const KEEP_DELIVERED_DAYS: i64 = 30;
+
+/// Wakes the worker after a delivery is queued. A wake before the commit is harmless: the worker
+/// reads through `DbState`, which waits for the writing transaction to finish.
+static WAKE: Notify = Notify::const_new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WebhookEvent {
+    InvoiceCreated,
+    InvoiceStatusChanged,
+    InvoiceEmailSent,
+    /// Sent by `test_webhook`; never queued.
+    Ping,
+}
+
+impl WebhookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::InvoiceCreated => "invoice.created",
+            WebhookEvent::InvoiceStatusChanged => "invoice.status_changed",
+            WebhookEvent::InvoiceEmailSent => "invoice.email_sent",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "PENDING",
+            DeliveryStatus::Delivered => "DELIVERED",
+            DeliveryStatus::Failed => "FAILED",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "DELIVERED" => DeliveryStatus::Delivered,
+            "FAILED" => DeliveryStatus::Failed,
+            _ => DeliveryStatus::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub event: String,
+    pub invoice_id: Option<String>,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    /// When a pending delivery is tried next.
+    pub next_attempt_at: Option<String>,
+    /// HTTP status of the last attempt that got a response.
+    pub response_status: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// Outcome of `test_webhook`: the HTTP status the endpoint answered with.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTestResult {
+    pub response_status: u16,
+}
+
+/// Empty disables webhooks; otherwise an absolute http(s) URL. Plain http is allowed for
+/// dashboards on the local network.
+pub(crate) fn validate_webhook_url(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Ok(());
+    }
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| "Webhook URL must start with http:// or https://.".to_string())?;
+    if rest.is_empty() || rest.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err("Webhook URL is not valid.".to_string());
+    }
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let ipad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    let opad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    let inner = Sha256::new().chain_update(&ipad).chain_update(message).finalize();
+    Sha256::new().chain_update(&opad).chain_update(inner).finalize().into()
+}
+
+/// Value of the `X-Pausaler-Signature` header for `body`.
+pub(crate) fn signature(secret: &str, body: &str) -> String {
+    let digest: String = hmac_sha256(secret.as_bytes(), body.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    format!("sha256={digest}")
+}
+
+/// Seconds to wait after the `attempts`-th failed attempt: 30 s, doubling, at most six hours.
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let doublings = (attempts - 1).clamp(0, 20) as u32;
+    FIRST_RETRY_SECS.saturating_mul(1 << doublings).min(MAX_RETRY_SECS)
+}
+
+fn unix_now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn unix_to_iso(secs: i64) -> Option<String> {
+    OffsetDateTime::from_unix_timestamp(secs).ok()?.format(&Rfc3339).ok()
+}
+
+fn invoice_json(invoice: &Invoice) -> Value {
+    json!({
+        "id": invoice.id,
+        "invoiceNumber": invoice.invoice_number,
+        "clientId": invoice.client_id,
+        "clientName": invoice.client_name,
+        "issueDate": invoice.issue_date,
+        "dueDate": invoice.due_date,
+        "status": invoice.status,
+        "currency": invoice.currency,
+        "total": invoice.total,
+    })
+}
+
+fn body(id: &str, event: WebhookEvent, data: Value) -> String {
+    json!({
+        "id": id,
+        "event": event.as_str(),
+        "occurredAt": now_iso(),
+        "data": data,
+    })
+    .to_string()
+}
+
+fn try_enqueue(conn: &Connection, event: WebhookEvent, invoice_id: &str, data: Value) -> Result<bool, rusqlite::Error> {
+    let url: String = conn.query_row("SELECT webhookUrl FROM settings WHERE id = ?1", params![SETTINGS_ID], |r| {
+        r.get(0)
+    })?;
+    if url.trim().is_empty() {
+        return Ok(false);
+    }
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO webhook_deliveries (id, event, invoiceId, status, attempts, nextAttemptAt, createdAt, payload) VALUES (?1, ?2, ?3, 'PENDING', 0, ?4, ?5, ?6)",
+        params![id, event.as_str(), invoice_id, unix_now(), now_iso(), body(&id, event, data)],
+    )?;
+    Ok(true)
+}
+
+/// Queues `event` when a webhook URL is set. Never fails: the change being announced matters
+/// more than the announcement.
+fn enqueue(conn: &Connection, event: WebhookEvent, invoice_id: &str, data: Value) {
+    match try_enqueue(conn, event, invoice_id, data) {
+        Ok(true) => WAKE.notify_one(),
+        Ok(false) => {}
+        Err(e) => tracing::error!(target: "webhooks", event = event.as_str(), error = %e, "failed to queue webhook"),
+    }
+}
+
+pub(crate) fn invoice_created(conn: &Connection, invoice: &Invoice) {
+    enqueue(conn, WebhookEvent::InvoiceCreated, &invoice.id, json!({ "invoice": invoice_json(invoice) }));
+}
+
+/// Queues `invoice.status_changed` when `previous` differs from the invoice's current status.
+pub(crate) fn invoice_status_changed(conn: &Connection, invoice: &Invoice, previous: InvoiceStatus) {
+    if invoice.status == previous {
+        return;
+    }
+    enqueue(
+        conn,
+        WebhookEvent::InvoiceStatusChanged,
+        &invoice.id,
+        json!({ "invoice": invoice_json(invoice), "previousStatus": previous }),
+    );
+}
+
+pub(crate) fn invoice_email_sent(conn: &Connection, invoice: &Invoice, recipients: &[String]) {
+    enqueue(
+        conn,
+        WebhookEvent::InvoiceEmailSent,
+        &invoice.id,
+        json!({ "invoice": invoice_json(invoice), "recipients": recipients }),
+    );
+}
+
+const DELIVERY_COLUMNS: &str =
+    "id, event, invoiceId, status, attempts, nextAttemptAt, responseStatus, error, createdAt, deliveredAt";
+
+fn row_to_delivery(r: &rusqlite::Row<'_>) -> Result<WebhookDelivery, rusqlite::Error> {
+    let status = DeliveryStatus::parse(&r.get::<_, String>(3)?);
+    let next_attempt_at: i64 = r.get(5)?;
+    Ok(WebhookDelivery {
+        id: r.get(0)?,
+        event: r.get(1)?,
+        invoice_id: r.get(2)?,
+        status,
+        attempts: r.get(4)?,
+        next_attempt_at: (status == DeliveryStatus::Pending)
+            .then(|| unix_to_iso(next_attempt_at))
+            .flatten(),
+        response_status: r.get(6)?,
+        error: r.get(7)?,
+        created_at: r.get(8)?,
+        delivered_at: r.get(9)?,
+    })
+}
+
+/// The oldest pending delivery that is due at `now`: id, event and body.
+fn next_due(conn: &Connection, now: i64) -> Result<Option<(String, String, String)>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT id, event, payload FROM webhook_deliveries WHERE status = 'PENDING' AND nextAttemptAt <= ?1 ORDER BY rowid LIMIT 1",
+        params![now],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )
+    .optional()
+}
+
+/// Records one attempt: delivered, rescheduled, or failed for good after `MAX_ATTEMPTS`.
+fn record_attempt(
+    conn: &Connection,
+    id: &str,
+    now: i64,
+    response_status: Option<u16>,
+    error: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    let attempts: i64 =
+        conn.query_row("SELECT attempts FROM webhook_deliveries WHERE id = ?1", params![id], |r| r.get(0))?;
+    let attempts = attempts + 1;
+    let (status, next_attempt_at, delivered_at) = match error {
+        None => (DeliveryStatus::Delivered, now, unix_to_iso(now)),
+        Some(_) if attempts >= MAX_ATTEMPTS => (DeliveryStatus::Failed, now, None),
+        Some(_) => (DeliveryStatus::Pending, now + retry_delay_secs(attempts), None),
+    };
+    conn.execute(
+        "UPDATE webhook_deliveries SET status = ?2, attempts = ?3, nextAttemptAt = ?4, responseStatus = COALESCE(?5, responseStatus), error = ?6, deliveredAt = ?7 WHERE id = ?1",
+        params![id, status.as_str(), attempts, next_attempt_at, response_status, error, delivered_at],
+    )?;
+    Ok(())
+}
+
+/// Seconds until the next pending delivery is due, or `None` when nothing is pending.
+fn secs_until_next(conn: &Connection, now: i64) -> Result<Option<i64>, rusqlite::Error> {
+    let next: Option<i64> = conn.query_row(
+        "SELECT MIN(nextAttemptAt) FROM webhook_deliveries WHERE status = 'PENDING'",
+        [],
+        |r| r.get(0),
+    )?;
+    Ok(next.map(|at| (at - now).max(0)))
+}
+
+fn prune_delivered(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    let cutoff = OffsetDateTime::now_utc() - time::Duration::days(KEEP_DELIVERED_DAYS);
+    let cutoff = cutoff.format(&Rfc3339).unwrap_or_default();
+    conn.execute(
+        "DELETE FROM webhook_deliveries WHERE status = 'DELIVERED' AND deliveredAt < ?1",
+        params![cutoff],
+    )
+}
+
+/// URL and plaintext secret from settings; the secret may be empty (unsigned requests).
+fn endpoint(conn: &Connection) -> Result<(String, String), rusqlite::Error> {
+    let s = read_settings_from_conn(conn)?;
+    let secret = if s.webhook_secret.is_empty() {
+        String::new()
+    } else {
+        secrets::resolve(secrets::WEBHOOK_SECRET, &s.webhook_secret).map_err(validation_to_sql_error)?
+    };
+    Ok((s.webhook_url.trim().to_string(), secret))
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))
+}
+
+/// POSTs one signed body. Returns the response status, or an error with the status when the
+/// endpoint did not answer 2xx.
+async fn post(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    id: &str,
+    event: &str,
+    body: String,
+) -> Result<u16, (Option<u16>, String)> {
+    if url.is_empty() {
+        return Err((None, "Webhook URL is not set.".to_string()));
+    }
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(reqwest::header::USER_AGENT, concat!("Pausaler/", env!("CARGO_PKG_VERSION")))
+        .header("X-Pausaler-Event", event)
+        .header("X-Pausaler-Delivery", id);
+    if !secret.is_empty() {
+        request = request.header("X-Pausaler-Signature", signature(secret, &body));
+    }
+    let resp = request.body(body).send().await.map_err(|e| (None, format!("Request failed: {e}")))?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("Endpoint answered HTTP {status}")))
+    }
+}
+
+/// Sends every due delivery. Returns seconds until the next one is due.
+async fn deliver_due(db: &DbState, client: &reqwest::Client) -> Result<Option<i64>, AppError> {
+    loop {
+        let job = db
+            .with_read("webhook_next_due", |conn| {
+                let Some(job) = next_due(conn, unix_now())? else {
+                    return Ok(None);
+                };
+                Ok(Some((job, endpoint(conn))))
+            })
+            .await?;
+        let Some(((id, event, body), endpoint)) = job else {
+            return db.with_read("webhook_next_wait", |conn| secs_until_next(conn, unix_now())).await;
+        };
+
+        let result = match endpoint {
+            Ok((url, secret)) => post(client, &url, &secret, &id, &event, body).await,
+            Err(e) => Err((None, sqlite_error_string(&e))),
+        };
+        let (response_status, error) = match result {
+            Ok(status) => (Some(status), None),
+            Err((status, e)) => {
+                tracing::warn!(target: "webhooks", delivery = %id, event = %event, error = %e, "webhook delivery failed");
+                (status, Some(e))
+            }
+        };
+        db.with_write("webhook_record_attempt", move |conn| {
+            record_attempt(conn, &id, unix_now(), response_status, error.as_deref())
+        })
+        .await?;
+    }
+}
+
+/// Spawns the single webhook worker. Must run after `DbState` is managed.
+pub(crate) fn spawn_webhook_worker(app: tauri::AppHandle) {
+    let db = app.state::<DbState>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        match db.with_write("webhook_prune", |conn| prune_delivered(conn)).await {
+            Ok(n) if n > 0 => tracing::info!(target: "webhooks", count = n, "old webhook deliveries removed"),
+            Ok(_) => {}
+            Err(e) => tracing::error!(target: "webhooks", error = %e, "failed to prune webhook deliveries"),
+        }
+        let client = match http_client() {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!(target: "webhooks", error = %e, "webhook worker not started");
+                return;
+            }
+        };
+        loop {
+            let wait = match deliver_due(&db, &client).await {
+                Ok(next) => next.unwrap_or(IDLE_POLL_SECS),
+                Err(e) => {
+                    tracing::error!(target: "webhooks", error = %e, "webhook worker failed");
+                    IDLE_POLL_SECS
+                }
+            };
+            let wait = Duration::from_secs(wait.clamp(1, IDLE_POLL_SECS) as u64);
+            let _ = tokio::time::timeout(wait, WAKE.notified()).await;
+        }
+    });
+}
+
+/// Sends a `ping` event to the saved URL right away and reports the answer; nothing is queued.
+#[tauri::command]
+pub(crate) async fn test_webhook(state: tauri::State<'_, DbState>) -> Result<WebhookTestResult, AppError> {
+    let (url, secret) = state.with_read("test_webhook", endpoint).await?;
+    let id = Uuid::new_v4().to_string();
+    let body = body(&id, WebhookEvent::Ping, json!({}));
+    let client = http_client()?;
+    post(&client, &url, &secret, &id, WebhookEvent::Ping.as_str(), body)
+        .await
+        .map(|response_status| WebhookTestResult { response_status })
+        .map_err(|(_, e)| AppError::Other(e))
+}
+
+#[tauri::command]
+pub(crate) async fn list_webhook_deliveries(state: tauri::State<'_, DbState>) -> Result<Vec<WebhookDelivery>, AppError> {
+    state
+        .with_read("list_webhook_deliveries", |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries ORDER BY rowid DESC LIMIT 200"
+            ))?;
+            let rows = stmt.query_map([], row_to_delivery)?;
+            rows.collect()
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook_conn(url: &str) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_schema(&conn).unwrap();
+        crate::ensure_settings_row(&conn).unwrap();
+        conn.execute("UPDATE settings SET webhookUrl = ?1", params![url]).unwrap();
+        conn
+    }
+
+    fn list(conn: &Connection) -> Vec<WebhookDelivery> {
+        let mut stmt = conn
+            .prepare(&format!("SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries ORDER BY rowid"))
+            .unwrap();
+        let rows = stmt.query_map([], row_to_delivery).unwrap();
+        rows.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn signature_matches_rfc_4231_vector() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            signature("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than the block are hashed first (test case 6).
+        let digest = hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(digest[..4], [0x60, 0xe4, 0x31, 0x59]);
+    }
+
+    #[test]
+    fn events_queue_only_with_a_url_and_retry_with_backoff() {
+        let mut invoice: Invoice = serde_json::from_value(json!({
+            "id": "inv-1", "invoiceNumber": "INV-0001", "clientId": "c1", "clientName": "Acme",
+            "issueDate": "2024-05-01", "serviceDate": "2024-05-01", "status": "DRAFT", "currency": "RSD",
+            "items": [], "subtotal": 100.0, "total": 100.0, "notes": "", "createdAt": "t"
+        }))
+        .unwrap();
+
+        let off = webhook_conn("");
+        invoice_created(&off, &invoice);
+        assert!(list(&off).is_empty());
+
+        let conn = webhook_conn("http://dashboard.lan/hook");
+        invoice_created(&conn, &invoice);
+        invoice_status_changed(&conn, &invoice, InvoiceStatus::Draft);
+        invoice.status = InvoiceStatus::Sent;
+        invoice_status_changed(&conn, &invoice, InvoiceStatus::Draft);
+        let queued = list(&conn);
+        assert_eq!(queued.len(), 2);
+        assert_eq!(queued[1].event, "invoice.status_changed");
+
+        let now = unix_now();
+        let (id, _, body) = next_due(&conn, now).unwrap().unwrap();
+        assert_eq!(id, queued[0].id);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["data"]["invoice"]["invoiceNumber"], "INV-0001");
+
+        record_attempt(&conn, &id, now, Some(503), Some("Endpoint answered HTTP 503")).unwrap();
+        assert_eq!(next_due(&conn, now).unwrap().unwrap().0, queued[1].id);
+        assert_eq!(secs_until_next(&conn, now).unwrap(), Some(0));
+        record_attempt(&conn, &queued[1].id, now, Some(200), None).unwrap();
+        assert!(next_due(&conn, now).unwrap().is_none());
+        assert_eq!(secs_until_next(&conn, now).unwrap(), Some(FIRST_RETRY_SECS));
+        assert_eq!(next_due(&conn, now + FIRST_RETRY_SECS).unwrap().unwrap().0, id);
+
+        let after = list(&conn);
+        assert_eq!((after[0].status, after[0].attempts, after[0].response_status), (DeliveryStatus::Pending, 1, Some(503)));
+        assert_eq!(after[1].status, DeliveryStatus::Delivered);
+        assert!(after[1].delivered_at.is_some());
+
+        assert_eq!(retry_delay_secs(2), 2 * FIRST_RETRY_SECS);
+        assert_eq!(retry_delay_secs(MAX_ATTEMPTS), MAX_RETRY_SECS);
+        conn.execute("UPDATE webhook_deliveries SET attempts = ?2 WHERE id = ?1", params![id, MAX_ATTEMPTS - 1])
+            .unwrap();
+        record_attempt(&conn, &id, now, None, Some("Request failed")).unwrap();
+        assert_eq!(list(&conn)[0].status, DeliveryStatus::Failed);
+    }
+}
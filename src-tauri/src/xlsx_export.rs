@@ -0,0 +1,313 @@
+//! Excel exports of invoices and expenses. Rows come from the same queries as the CSV exports;
+//! amounts are numeric cells and dates real date cells, so no locale has to re-parse them.
+
+use std::collections::BTreeMap;
+
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+
+use crate::errors::AppError;
+use crate::{
+    expenses_for_export, invoices_for_export, normalize_date_range, write_bytes_file, DbState, Expense, Invoice,
+    RangeExportResult,
+};
+
+const MONEY_FORMAT: &str = "#,##0.00";
+const QUANTITY_FORMAT: &str = "#,##0.####";
+const DATE_FORMAT: &str = "dd.mm.yyyy";
+
+struct Formats {
+    header: Format,
+    money: Format,
+    quantity: Format,
+    date: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            money: Format::new().set_num_format(MONEY_FORMAT),
+            quantity: Format::new().set_num_format(QUANTITY_FORMAT),
+            date: Format::new().set_num_format(DATE_FORMAT),
+        }
+    }
+}
+
+fn xlsx_err(e: XlsxError) -> String {
+    format!("Failed to write XLSX: {e}")
+}
+
+/// Date part of `YYYY-MM-DD` or an RFC 3339 timestamp.
+fn excel_date(value: &str) -> Option<ExcelDateTime> {
+    let ymd = value.get(..10)?;
+    let year: u16 = ymd.get(..4)?.parse().ok()?;
+    let month: u8 = ymd.get(5..7)?.parse().ok()?;
+    let day: u8 = ymd.get(8..10)?.parse().ok()?;
+    ExcelDateTime::from_ymd(year, month, day).ok()
+}
+
+fn add_sheet<'a>(workbook: &'a mut Workbook, name: &str, header: &[&str], f: &Formats) -> Result<&'a mut Worksheet, XlsxError> {
+    let ws = workbook.add_worksheet();
+    ws.set_name(name)?;
+    for (col, title) in (0u16..).zip(header) {
+        ws.write_string_with_format(0, col, *title, &f.header)?;
+    }
+    ws.set_freeze_panes(1, 0)?;
+    Ok(ws)
+}
+
+/// Writes a date cell; text that isn't a date is kept as-is, empty values leave the cell blank.
+fn write_date(ws: &mut Worksheet, row: u32, col: u16, value: &str, f: &Formats) -> Result<(), XlsxError> {
+    match excel_date(value) {
+        Some(d) => ws.write_datetime_with_format(row, col, d, &f.date).map(|_| ()),
+        None if value.trim().is_empty() => Ok(()),
+        None => ws.write_string(row, col, value).map(|_| ()),
+    }
+}
+
+fn write_opt_date(ws: &mut Worksheet, row: u32, col: u16, value: Option<&str>, f: &Formats) -> Result<(), XlsxError> {
+    write_date(ws, row, col, value.unwrap_or_default(), f)
+}
+
+fn month_of(date: &str) -> String {
+    date.get(..7).unwrap_or(date).to_string()
+}
+
+fn is_default_currency(currency: &str, default_currency: &str) -> bool {
+    currency.trim() == default_currency.trim()
+}
+
+pub(crate) fn render_invoices_xlsx(default_currency: &str, invoices: &[Invoice]) -> Result<Vec<u8>, String> {
+    invoices_workbook(default_currency, invoices).map_err(xlsx_err)
+}
+
+fn invoices_workbook(default_currency: &str, invoices: &[Invoice]) -> Result<Vec<u8>, XlsxError> {
+    let f = Formats::new();
+    let mut wb = Workbook::new();
+
+    let ws = add_sheet(
+        &mut wb,
+        "Invoices",
+        &[
+            "invoiceId",
+            "invoiceNumber",
+            "issueDate",
+            "serviceDate",
+            "dueDate",
+            "paidAt",
+            "status",
+            "clientId",
+            "clientName",
+            "currency",
+            "isDefaultCurrency",
+            "subtotal",
+            "total",
+            "notes",
+            "createdAt",
+        ],
+        &f,
+    )?;
+    for (row, inv) in (1u32..).zip(invoices) {
+        ws.write_string(row, 0, &inv.id)?;
+        ws.write_string(row, 1, &inv.invoice_number)?;
+        write_date(ws, row, 2, &inv.issue_date, &f)?;
+        write_date(ws, row, 3, &inv.service_date, &f)?;
+        write_opt_date(ws, row, 4, inv.due_date.as_deref(), &f)?;
+        write_opt_date(ws, row, 5, inv.paid_at.as_deref(), &f)?;
+        ws.write_string(row, 6, inv.status.as_str())?;
+        ws.write_string(row, 7, &inv.client_id)?;
+        ws.write_string(row, 8, &inv.client_name)?;
+        ws.write_string(row, 9, &inv.currency)?;
+        ws.write_boolean(row, 10, is_default_currency(&inv.currency, default_currency))?;
+        ws.write_number_with_format(row, 11, inv.subtotal, &f.money)?;
+        ws.write_number_with_format(row, 12, inv.total, &f.money)?;
+        ws.write_string(row, 13, &inv.notes)?;
+        ws.write_string(row, 14, &inv.created_at)?;
+    }
+    ws.autofit();
+
+    let ws = add_sheet(
+        &mut wb,
+        "Items",
+        &[
+            "invoiceId",
+            "invoiceNumber",
+            "issueDate",
+            "currency",
+            "itemId",
+            "itemDescription",
+            "itemQuantity",
+            "itemUnitPrice",
+            "itemTotal",
+        ],
+        &f,
+    )?;
+    let items = invoices.iter().flat_map(|inv| inv.items.iter().map(move |item| (inv, item)));
+    for (row, (inv, item)) in (1u32..).zip(items) {
+        ws.write_string(row, 0, &inv.id)?;
+        ws.write_string(row, 1, &inv.invoice_number)?;
+        write_date(ws, row, 2, &inv.issue_date, &f)?;
+        ws.write_string(row, 3, &inv.currency)?;
+        ws.write_string(row, 4, &item.id)?;
+        ws.write_string(row, 5, &item.description)?;
+        ws.write_number_with_format(row, 6, item.quantity, &f.quantity)?;
+        ws.write_number_with_format(row, 7, item.unit_price, &f.money)?;
+        ws.write_number_with_format(row, 8, item.total, &f.money)?;
+    }
+    ws.autofit();
+
+    let mut months: BTreeMap<(String, String), (f64, f64, f64)> = BTreeMap::new();
+    for inv in invoices {
+        let e = months.entry((month_of(&inv.issue_date), inv.currency.clone())).or_default();
+        e.0 += 1.0;
+        e.1 += inv.subtotal;
+        e.2 += inv.total;
+    }
+    let ws = add_sheet(&mut wb, "Summary", &["month", "currency", "invoiceCount", "subtotal", "total"], &f)?;
+    for (row, ((month, currency), (count, subtotal, total))) in (1u32..).zip(months) {
+        ws.write_string(row, 0, &month)?;
+        ws.write_string(row, 1, &currency)?;
+        ws.write_number(row, 2, count)?;
+        ws.write_number_with_format(row, 3, subtotal, &f.money)?;
+        ws.write_number_with_format(row, 4, total, &f.money)?;
+    }
+    ws.autofit();
+
+    wb.save_to_buffer()
+}
+
+pub(crate) fn render_expenses_xlsx(default_currency: &str, expenses: &[Expense]) -> Result<Vec<u8>, String> {
+    expenses_workbook(default_currency, expenses).map_err(xlsx_err)
+}
+
+fn expenses_workbook(default_currency: &str, expenses: &[Expense]) -> Result<Vec<u8>, XlsxError> {
+    let f = Formats::new();
+    let mut wb = Workbook::new();
+
+    let ws = add_sheet(
+        &mut wb,
+        "Expenses",
+        &["expenseId", "date", "title", "category", "amount", "currency", "isDefaultCurrency", "notes", "createdAt"],
+        &f,
+    )?;
+    for (row, exp) in (1u32..).zip(expenses) {
+        ws.write_string(row, 0, &exp.id)?;
+        write_date(ws, row, 1, &exp.date, &f)?;
+        ws.write_string(row, 2, &exp.title)?;
+        ws.write_string(row, 3, exp.category.as_deref().unwrap_or_default())?;
+        ws.write_number_with_format(row, 4, exp.amount, &f.money)?;
+        ws.write_string(row, 5, &exp.currency)?;
+        ws.write_boolean(row, 6, is_default_currency(&exp.currency, default_currency))?;
+        ws.write_string(row, 7, exp.notes.as_deref().unwrap_or_default())?;
+        ws.write_string(row, 8, &exp.created_at)?;
+    }
+    ws.autofit();
+
+    let mut months: BTreeMap<(String, String), (f64, f64)> = BTreeMap::new();
+    for exp in expenses {
+        let e = months.entry((month_of(&exp.date), exp.currency.clone())).or_default();
+        e.0 += 1.0;
+        e.1 += exp.amount;
+    }
+    let ws = add_sheet(&mut wb, "Summary", &["month", "currency", "expenseCount", "amount"], &f)?;
+    for (row, ((month, currency), (count, amount))) in (1u32..).zip(months) {
+        ws.write_string(row, 0, &month)?;
+        ws.write_string(row, 1, &currency)?;
+        ws.write_number(row, 2, count)?;
+        ws.write_number_with_format(row, 3, amount, &f.money)?;
+    }
+    ws.autofit();
+
+    wb.save_to_buffer()
+}
+
+#[tauri::command]
+pub(crate) async fn export_invoices_xlsx(
+    state: tauri::State<'_, DbState>,
+    from: String,
+    to: String,
+    output_path: String,
+) -> Result<RangeExportResult, AppError> {
+    let (from, to) = normalize_date_range(&from, &to)?;
+    let (range_from, range_to) = (from.clone(), to.clone());
+    let (default_currency, invoices) = state
+        .with_read("export_invoices_xlsx", move |conn| invoices_for_export(conn, &range_from, &range_to))
+        .await?;
+    let bytes = render_invoices_xlsx(&default_currency, &invoices)?;
+    write_bytes_file(std::path::Path::new(&output_path), &bytes)?;
+    Ok(RangeExportResult {
+        output_path,
+        from,
+        to,
+        row_count: invoices.len(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn export_expenses_xlsx(
+    state: tauri::State<'_, DbState>,
+    from: String,
+    to: String,
+    output_path: String,
+) -> Result<RangeExportResult, AppError> {
+    let (from, to) = normalize_date_range(&from, &to)?;
+    let (range_from, range_to) = (from.clone(), to.clone());
+    let (default_currency, expenses) = state
+        .with_read("export_expenses_xlsx", move |conn| expenses_for_export(conn, &range_from, &range_to))
+        .await?;
+    let bytes = render_expenses_xlsx(&default_currency, &expenses)?;
+    write_bytes_file(std::path::Path::new(&output_path), &bytes)?;
+    Ok(RangeExportResult {
+        output_path,
+        from,
+        to,
+        row_count: expenses.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn part(bytes: &[u8], name: &str) -> String {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut out = String::new();
+        zip.by_name(name).unwrap().read_to_string(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn expenses_workbook_has_typed_cells_frozen_header_and_summary() {
+        let expense = |id: &str, date: &str, amount: f64| Expense {
+            id: id.to_string(),
+            title: format!("Trošak {id}"),
+            amount,
+            currency: "RSD".to_string(),
+            date: date.to_string(),
+            category: None,
+            notes: None,
+            created_at: "t".to_string(),
+            recurring_id: None,
+            deleted_at: None,
+            invoice_id: None,
+        };
+        let bytes = render_expenses_xlsx(
+            "RSD",
+            &[expense("a", "2025-01-31", 1234.5), expense("b", "2025-01-02", 100.0), expense("c", "2025-02-01", 7.0)],
+        )
+        .unwrap();
+
+        let workbook = part(&bytes, "xl/workbook.xml");
+        assert!(workbook.contains(r#"name="Expenses""#) && workbook.contains(r#"name="Summary""#), "{workbook}");
+
+        let sheet = part(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet.contains(r#"ySplit="1""#), "header row is not frozen");
+        assert!(sheet.contains("<v>1234.5</v>"), "amount is not a numeric cell");
+        // 2025-01-31 as an Excel serial date.
+        assert!(sheet.contains("<v>45688</v>"), "date is not a date cell");
+
+        let summary = part(&bytes, "xl/worksheets/sheet2.xml");
+        assert!(summary.contains("<v>1334.5</v>") && summary.contains("<v>7</v>"), "{summary}");
+    }
+}
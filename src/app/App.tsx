@@ -22,9 +22,14 @@ import { getStorage } from './services/storageProvider';
 import { SetupCompanyPage } from './pages/SetupCompanyPage';
 import { message } from 'antd';
 import { listen } from '@tauri-apps/api/event';
+import type { CorruptRow, OutboxItem } from './types';
+import type { LicenseExpiryInfo } from './types/license';
 
 const storage = getStorage();
 
+/** Deleted expenses stay restorable for this long before startup housekeeping purges them. */
+const DELETED_EXPENSE_RETENTION_DAYS = 30;
+
 export default function App() {
   const { i18n: i18nFromHook } = useTranslation();
   const [needsSetup, setNeedsSetup] = useState<boolean | null>(null);
@@ -35,6 +40,14 @@ export default function App() {
       const shouldSetup = settings.isConfigured !== true;
       setNeedsSetup(shouldSetup);
 
+      try {
+        const created = await storage.runDueRecurringExpenses();
+        if (created > 0) {
+          message.info(i18n.t('expenses.recurring.generated', { count: created }));
+        }
+      } catch {}
+      void storage.purgeDeletedExpenses(DELETED_EXPENSE_RETENTION_DAYS).catch(() => {});
+
       const lang = normalizeLanguage(settings.language);
       if (i18n.language !== lang) {
         void i18n.changeLanguage(lang);
@@ -56,6 +69,88 @@ export default function App() {
     };
   }, []);
 
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        unlisten = await listen<{ message: string }>('auto_backup_failed', (event) => {
+          message.warning(i18n.t('settings.backup.auto.failed', { message: event.payload.message }));
+        });
+      } catch {}
+    })();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        unlisten = await listen<{ path: string; others: string[] }>('database_candidates_found', (event) => {
+          message.warning(
+            i18n.t('settings.backup.location.othersFound', { path: event.payload.path, count: event.payload.others.length }),
+          );
+        });
+      } catch {}
+    })();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        unlisten = await listen<{ threshold: number; info: LicenseExpiryInfo }>('license://expiring', (event) => {
+          const days = event.payload.info.daysRemaining ?? event.payload.threshold;
+          message.warning(i18n.t('license.expiring', { days }));
+        });
+      } catch {}
+    })();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        unlisten = await listen<OutboxItem>('email://status', (event) => {
+          const item = event.payload;
+          if (item.status === 'SENT') {
+            message.success(i18n.t('invoiceEmail.sent'));
+          } else if (item.status === 'FAILED') {
+            message.error(item.error || i18n.t('invoiceEmail.sendError'));
+          }
+        });
+      } catch {}
+    })();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        unlisten = await listen<CorruptRow>('data://corrupt-row', (event) => {
+          // Sent once per row on every list read; the key keeps it to a single notice.
+          message.warning({
+            key: 'corrupt-row',
+            content: i18n.t('settings.backup.consistency.corruptNotice', { label: event.payload.label }),
+          });
+        });
+      } catch {}
+    })();
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
   const appLang = normalizeLanguage(i18nFromHook.language);
   const antdLocale = appLang === 'en' ? enUS : srRS;
 
@@ -0,0 +1,80 @@
+import { useEffect, useState, type ReactNode } from 'react';
+import { Button, Card, Input, Space, Typography } from 'antd';
+import { LockOutlined } from '@ant-design/icons';
+import { useTranslation } from 'react-i18next';
+
+import { useAppLock } from '../hooks/useAppLock';
+import { getStorage } from '../services/storageProvider';
+
+const storage = getStorage();
+
+/** Renders `children` only while the app is unlocked; locking unmounts them, dropping loaded data. */
+export function AppLockGate({ children }: { children: ReactNode }) {
+  const { status, refresh } = useAppLock();
+
+  if (!status) return null;
+  if (status.locked) return <LockScreen retryAfterSecs={status.retryAfterSecs ?? 0} onAttempt={refresh} />;
+  return <>{children}</>;
+}
+
+function LockScreen({ retryAfterSecs, onAttempt }: { retryAfterSecs: number; onAttempt: () => Promise<void> }) {
+  const { t } = useTranslation();
+  const [pin, setPin] = useState('');
+  const [checking, setChecking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [wait, setWait] = useState(retryAfterSecs);
+
+  useEffect(() => setWait(retryAfterSecs), [retryAfterSecs]);
+
+  useEffect(() => {
+    if (wait <= 0) return;
+    const timer = window.setTimeout(() => setWait((w) => w - 1), 1000);
+    return () => window.clearTimeout(timer);
+  }, [wait]);
+
+  const submit = async () => {
+    if (!pin || wait > 0) return;
+    setChecking(true);
+    try {
+      const ok = await storage.verifyAppPin(pin);
+      if (!ok) setError(t('appLock.wrongPin'));
+    } catch (e: any) {
+      setError(String(e?.message ?? e));
+    } finally {
+      setPin('');
+      setChecking(false);
+      // A correct PIN arrives as an `app_lock://changed` event; this picks up a new delay.
+      await onAttempt();
+    }
+  };
+
+  return (
+    <div style={{ height: '100vh', display: 'flex', alignItems: 'center', justifyContent: 'center' }}>
+      <Card style={{ width: 340 }}>
+        <Space direction="vertical" style={{ width: '100%' }} size="middle">
+          <Typography.Title level={4} style={{ margin: 0 }}>
+            <LockOutlined /> {t('appLock.title')}
+          </Typography.Title>
+          <Typography.Text type="secondary">{t('appLock.hint')}</Typography.Text>
+          <Input.Password
+            autoFocus
+            inputMode="numeric"
+            maxLength={12}
+            value={pin}
+            disabled={wait > 0}
+            onChange={(e) => setPin(e.target.value.replace(/\D/g, ''))}
+            onPressEnter={() => void submit()}
+          />
+          {wait > 0 ? (
+            <Typography.Text type="warning">{t('appLock.retryIn', { seconds: wait })}</Typography.Text>
+          ) : error ? (
+            <Typography.Text type="danger">{error}</Typography.Text>
+          ) : null}
+          <Button type="primary" block loading={checking} disabled={!pin || wait > 0} onClick={() => void submit()}>
+            {t('appLock.unlock')}
+          </Button>
+        </Space>
+      </Card>
+    </div>
+  );
+}
@@ -0,0 +1,90 @@
+import { useState } from 'react';
+import { Button, Input, Popconfirm, Space, Typography, message } from 'antd';
+import { LockOutlined } from '@ant-design/icons';
+import { useTranslation } from 'react-i18next';
+
+import { useAppLock } from '../hooks/useAppLock';
+import { getStorage } from '../services/storageProvider';
+
+const storage = getStorage();
+
+/** Set, change or remove the app PIN, and lock the app right away. */
+export function AppPinSettings() {
+  const { t } = useTranslation();
+  const { status } = useAppLock();
+  const [pin, setPin] = useState('');
+  const [confirm, setConfirm] = useState('');
+  const [saving, setSaving] = useState(false);
+
+  const save = async () => {
+    if (pin !== confirm) {
+      message.error(t('appLock.settings.mismatch'));
+      return;
+    }
+    setSaving(true);
+    try {
+      await storage.setAppPin(pin);
+      setPin('');
+      setConfirm('');
+      message.success(t('appLock.settings.saved'));
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const clear = async () => {
+    try {
+      await storage.clearAppPin();
+      message.success(t('appLock.settings.cleared'));
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const digitsOnly = (v: string) => v.replace(/\D/g, '');
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('appLock.settings.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('appLock.settings.help')}</Typography.Paragraph>
+      <Typography.Paragraph>
+        {status?.pinSet ? t('appLock.settings.statusSet') : t('appLock.settings.statusNotSet')}
+      </Typography.Paragraph>
+      <Space wrap align="start">
+        <Input.Password
+          placeholder={status?.pinSet ? t('appLock.settings.newPin') : t('appLock.settings.pin')}
+          inputMode="numeric"
+          maxLength={12}
+          value={pin}
+          onChange={(e) => setPin(digitsOnly(e.target.value))}
+          autoComplete="new-password"
+          style={{ width: 180 }}
+        />
+        <Input.Password
+          placeholder={t('appLock.settings.confirmPin')}
+          inputMode="numeric"
+          maxLength={12}
+          value={confirm}
+          onChange={(e) => setConfirm(digitsOnly(e.target.value))}
+          autoComplete="new-password"
+          style={{ width: 180 }}
+        />
+        <Button type="primary" loading={saving} disabled={pin.length < 4} onClick={() => void save()}>
+          {status?.pinSet ? t('appLock.settings.change') : t('appLock.settings.set')}
+        </Button>
+      </Space>
+      {status?.pinSet ? (
+        <Space style={{ marginTop: 12 }}>
+          <Button icon={<LockOutlined />} onClick={() => void storage.lockApp().catch((e) => message.error(String(e?.message ?? e)))}>
+            {t('appLock.lockNow')}
+          </Button>
+          <Popconfirm title={t('appLock.settings.clearConfirm')} onConfirm={() => void clear()}>
+            <Button danger>{t('appLock.settings.clear')}</Button>
+          </Popconfirm>
+        </Space>
+      ) : null}
+    </div>
+  );
+}
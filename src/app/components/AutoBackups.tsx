@@ -0,0 +1,104 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Form, InputNumber, Popconfirm, Switch, Table, Typography, message } from 'antd';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { listAutoBackups, quitApp, restoreAutoBackup, type AutoBackupEntry, type AutoBackupList } from '../services/backupService';
+
+/** Automatic backup settings (fields of the surrounding settings form) and one-click restore. */
+export function AutoBackups({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [list, setList] = useState<AutoBackupList | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    let mounted = true;
+    setLoading(true);
+    listAutoBackups()
+      .then((res) => {
+        if (mounted) setList(res);
+      })
+      .catch(() => {})
+      .finally(() => {
+        if (mounted) setLoading(false);
+      });
+    return () => {
+      mounted = false;
+    };
+  }, []);
+
+  const restore = async (name: string) => {
+    try {
+      const staged = await restoreAutoBackup(name);
+      if (staged.requiresRestart) {
+        message.success(t('settings.backup.restoreStaged'));
+        await quitApp();
+      }
+    } catch (e: any) {
+      const msg = e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e);
+      message.error(msg || t('settings.backup.restoreStageError'));
+    }
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.auto.title')}</Typography.Title>
+      {list?.lastError ? (
+        <Alert
+          type="warning"
+          showIcon
+          style={{ marginBottom: 12 }}
+          message={t('settings.backup.auto.failed', { message: list.lastError })}
+        />
+      ) : null}
+      <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+        <Form.Item
+          label={t('settings.backup.auto.enabled')}
+          name="autoBackupEnabled"
+          valuePropName="checked"
+          extra={t('settings.backup.auto.enabledHelp')}
+        >
+          <Switch disabled={disabled} />
+        </Form.Item>
+        <Form.Item label={t('settings.backup.auto.keep')} name="autoBackupKeep">
+          <InputNumber min={1} max={365} precision={0} style={{ width: '100%' }} disabled={disabled} />
+        </Form.Item>
+      </div>
+      <Table<AutoBackupEntry>
+        size="small"
+        loading={loading}
+        rowKey="name"
+        dataSource={list?.backups ?? []}
+        pagination={false}
+        locale={{ emptyText: t('settings.backup.auto.empty') }}
+        columns={[
+          {
+            title: t('settings.backup.restoreConfirmCreatedAt'),
+            dataIndex: 'createdAt',
+            key: 'createdAt',
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY HH:mm'),
+          },
+          { title: t('settings.backup.auto.file'), dataIndex: 'name', key: 'name' },
+          {
+            title: t('settings.backup.auto.sizeKb'),
+            dataIndex: 'sizeBytes',
+            key: 'sizeBytes',
+            align: 'right',
+            render: (v: number) => Math.round(v / 1024),
+          },
+          {
+            key: 'actions',
+            align: 'right',
+            render: (_, b) => (
+              <Popconfirm title={t('settings.backup.auto.restoreConfirm')} onConfirm={() => void restore(b.name)} disabled={disabled}>
+                <Button size="small" disabled={disabled}>
+                  {t('settings.backup.auto.restore')}
+                </Button>
+              </Popconfirm>
+            ),
+          },
+        ]}
+      />
+    </div>
+  );
+}
@@ -0,0 +1,102 @@
+import { Button, Checkbox, Form, Input, Select, Space, Typography } from 'antd';
+import { DeleteOutlined, PlusOutlined } from '@ant-design/icons';
+import { useTranslation } from 'react-i18next';
+
+import { CURRENCY_VALUES, type BankAccount } from '../types';
+
+/**
+ * Company bank accounts (the `bankAccounts` list of the surrounding settings form). Invoices use
+ * the account in their currency, otherwise the default one; the backend checks the numbers on save.
+ */
+export function BankAccountsFields() {
+  const { t } = useTranslation();
+  const form = Form.useFormInstance();
+
+  const makeDefault = (index: number) => {
+    const accounts: BankAccount[] = form.getFieldValue('bankAccounts') ?? [];
+    form.setFieldValue(
+      'bankAccounts',
+      accounts.map((a, i) => ({ ...a, isDefault: i === index }))
+    );
+  };
+
+  return (
+    <div>
+      <Typography.Paragraph type="secondary" style={{ marginTop: 0 }}>
+        {t('settings.bankAccounts.help')}
+      </Typography.Paragraph>
+      <Form.List
+        name="bankAccounts"
+        rules={[
+          {
+            validator: async (_rule, accounts?: BankAccount[]) => {
+              if (!accounts || accounts.length === 0) throw new Error(t('settings.bankReq'));
+            },
+          },
+        ]}
+      >
+        {(fields, { add, remove }, { errors }) => (
+          <>
+            {fields.map(({ key, name }) => (
+              <div key={key} style={{ border: '1px solid #f0f0f0', borderRadius: 8, padding: '12px 12px 0', marginBottom: 12 }}>
+                <div style={{ display: 'grid', gridTemplateColumns: '2fr 1fr 2fr', gap: 16 }}>
+                  <Form.Item
+                    label={t('settings.bankAccounts.accountNumber')}
+                    name={[name, 'accountNumber']}
+                    dependencies={[['bankAccounts', name, 'iban']]}
+                    rules={[
+                      {
+                        validator: async (_rule, value) => {
+                          const iban = String(form.getFieldValue(['bankAccounts', name, 'iban']) ?? '').trim();
+                          if (!String(value ?? '').trim() && !iban) throw new Error(t('settings.bankAccounts.numberOrIbanReq'));
+                        },
+                      },
+                    ]}
+                  >
+                    <Input placeholder="160-5100000000000-00" />
+                  </Form.Item>
+                  <Form.Item
+                    label={t('settings.bankAccounts.currency')}
+                    name={[name, 'currency']}
+                    rules={[{ required: true, message: t('settings.currencyReq') }]}
+                  >
+                    <Select options={CURRENCY_VALUES.map((c) => ({ value: c, label: t(`currencies.${c}`) }))} />
+                  </Form.Item>
+                  <Form.Item label={t('settings.bankAccounts.bankName')} name={[name, 'bankName']}>
+                    <Input />
+                  </Form.Item>
+                </div>
+                <div style={{ display: 'grid', gridTemplateColumns: '2fr 1fr 2fr', gap: 16 }}>
+                  <Form.Item label={t('settings.bankAccounts.iban')} name={[name, 'iban']} extra={t('settings.bankAccounts.ibanHelp')}>
+                    <Input placeholder="RS35 2600 0560 1001 6113 79" />
+                  </Form.Item>
+                  <Form.Item label={t('settings.bankAccounts.swift')} name={[name, 'swift']}>
+                    <Input placeholder="AIKBRS22" />
+                  </Form.Item>
+                  <Space align="center" style={{ justifyContent: 'space-between', marginBottom: 24 }}>
+                    <Form.Item name={[name, 'isDefault']} valuePropName="checked" noStyle>
+                      <Checkbox onChange={(e) => e.target.checked && makeDefault(name)}>
+                        {t('settings.bankAccounts.isDefault')}
+                      </Checkbox>
+                    </Form.Item>
+                    <Button danger type="text" icon={<DeleteOutlined />} onClick={() => remove(name)}>
+                      {t('settings.bankAccounts.remove')}
+                    </Button>
+                  </Space>
+                </div>
+              </div>
+            ))}
+            <Form.ErrorList errors={errors} />
+            <Button
+              type="dashed"
+              icon={<PlusOutlined />}
+              onClick={() => add({ accountNumber: '', currency: 'RSD', bankName: '', iban: '', swift: '', isDefault: fields.length === 0 })}
+            >
+              {t('settings.bankAccounts.add')}
+            </Button>
+          </>
+        )}
+      </Form.List>
+    </div>
+  );
+}
@@ -0,0 +1,91 @@
+import { useEffect, useState } from 'react';
+import { Modal, Table, message } from 'antd';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { Expense, Invoice } from '../types';
+
+const storage = getStorage();
+
+type Props = {
+  invoice: Invoice;
+  open: boolean;
+  onClose: () => void;
+  onBilled: (invoice: Invoice) => void;
+};
+
+/** Picks unbilled expenses in the invoice currency and appends them as lines to a draft invoice. */
+export function BillExpensesModal({ invoice, open, onClose, onBilled }: Props) {
+  const { t } = useTranslation();
+  const [expenses, setExpenses] = useState<Expense[]>([]);
+  const [selected, setSelected] = useState<string[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [billing, setBilling] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setSelected([]);
+    setLoading(true);
+    void storage
+      .listExpenses()
+      .then((all) => setExpenses(all.filter((e) => !e.invoiceId && e.currency === invoice.currency)))
+      .finally(() => setLoading(false));
+  }, [open, invoice.currency]);
+
+  const handleOk = async () => {
+    setBilling(true);
+    try {
+      const updated = await storage.billExpensesToInvoice(selected, invoice.id);
+      if (!updated) {
+        message.error(t('invoices.notFound'));
+        return;
+      }
+      message.success(t('invoiceView.billExpenses.done', { count: selected.length }));
+      onBilled(updated);
+      onClose();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setBilling(false);
+    }
+  };
+
+  return (
+    <Modal
+      title={t('invoiceView.billExpenses.title')}
+      open={open}
+      onCancel={onClose}
+      onOk={() => void handleOk()}
+      okText={t('invoiceView.billExpenses.run')}
+      okButtonProps={{ disabled: selected.length === 0, loading: billing }}
+      cancelText={t('common.cancel')}
+      width={720}
+    >
+      <Table
+        size="small"
+        rowKey="id"
+        loading={loading}
+        dataSource={expenses}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('invoiceView.billExpenses.empty', { currency: invoice.currency }) }}
+        rowSelection={{ selectedRowKeys: selected, onChange: (keys) => setSelected(keys as string[]) }}
+        columns={[
+          {
+            title: t('expenses.date'),
+            dataIndex: 'date',
+            width: 110,
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+          },
+          { title: t('expenses.titleCol'), dataIndex: 'title' },
+          {
+            title: t('expenses.amount'),
+            key: 'amount',
+            align: 'right' as const,
+            render: (_: unknown, r: Expense) => `${r.amount.toFixed(2)} ${r.currency}`,
+          },
+        ]}
+      />
+    </Modal>
+  );
+}
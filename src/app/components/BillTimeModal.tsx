@@ -0,0 +1,104 @@
+import { useEffect, useState } from 'react';
+import { Modal, Table, message } from 'antd';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { Invoice, TimeEntry } from '../types';
+
+const storage = getStorage();
+
+type Props = {
+  invoice: Invoice;
+  open: boolean;
+  onClose: () => void;
+  onBilled: (invoice: Invoice) => void;
+};
+
+/** Picks the client's unbilled time and appends it as hourly lines to a draft invoice. */
+export function BillTimeModal({ invoice, open, onClose, onBilled }: Props) {
+  const { t } = useTranslation();
+  const [entries, setEntries] = useState<TimeEntry[]>([]);
+  const [selected, setSelected] = useState<string[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [billing, setBilling] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setSelected([]);
+    setLoading(true);
+    void storage
+      .listTimeEntries({ clientId: invoice.clientId, unbilledOnly: true })
+      .then(setEntries)
+      .finally(() => setLoading(false));
+  }, [open, invoice.clientId]);
+
+  const handleOk = async () => {
+    setBilling(true);
+    try {
+      const updated = await storage.billTimeEntries(selected, invoice.id);
+      if (!updated) {
+        message.error(t('invoices.notFound'));
+        return;
+      }
+      message.success(t('invoiceView.billTime.done', { count: selected.length }));
+      onBilled(updated);
+      onClose();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setBilling(false);
+    }
+  };
+
+  return (
+    <Modal
+      title={t('invoiceView.billTime.title')}
+      open={open}
+      onCancel={onClose}
+      onOk={() => void handleOk()}
+      okText={t('invoiceView.billTime.run')}
+      okButtonProps={{ disabled: selected.length === 0, loading: billing }}
+      cancelText={t('common.cancel')}
+      width={720}
+    >
+      <Table
+        size="small"
+        rowKey="id"
+        loading={loading}
+        dataSource={entries}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('invoiceView.billTime.empty') }}
+        rowSelection={{ selectedRowKeys: selected, onChange: (keys) => setSelected(keys as string[]) }}
+        columns={[
+          {
+            title: t('time.date'),
+            dataIndex: 'date',
+            width: 110,
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+          },
+          { title: t('time.description'), dataIndex: 'description' },
+          {
+            title: t('time.duration'),
+            dataIndex: 'minutes',
+            align: 'right' as const,
+            width: 100,
+            render: (v: number) => formatMinutes(v),
+          },
+          {
+            title: t('time.hourlyRate'),
+            dataIndex: 'hourlyRate',
+            align: 'right' as const,
+            width: 120,
+            render: (v: number) => `${v.toFixed(2)} ${invoice.currency}`,
+          },
+        ]}
+      />
+    </Modal>
+  );
+}
+
+/** `95` → `1:35`. */
+export function formatMinutes(minutes: number): string {
+  return `${Math.floor(minutes / 60)}:${String(minutes % 60).padStart(2, '0')}`;
+}
@@ -0,0 +1,234 @@
+import { useEffect, useState } from 'react';
+import { Button, DatePicker, Form, Input, InputNumber, Modal, Select, Space, Switch, Table, Tabs, Tag, message } from 'antd';
+import { DeleteOutlined, EditOutlined, PlusOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { INVOICE_UNIT_VALUES, invoiceUnitLabel, normalizeInvoiceUnit } from '../types';
+import type { CatalogItem, CatalogItemInput, CatalogUsage, InvoiceUnit } from '../types';
+
+const storage = getStorage();
+
+type ItemFormValues = {
+  name: string;
+  unit: InvoiceUnit;
+  unitPrice: number;
+  vatRate?: number | null;
+  active: boolean;
+};
+
+type Props = {
+  open: boolean;
+  canWrite: boolean;
+  onClose: () => void;
+};
+
+export function CatalogModal({ open, canWrite, onClose }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<ItemFormValues>();
+  const [items, setItems] = useState<CatalogItem[]>([]);
+  const [editing, setEditing] = useState<CatalogItem | 'new' | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [usage, setUsage] = useState<CatalogUsage[]>([]);
+  const [range, setRange] = useState<[dayjs.Dayjs, dayjs.Dayjs] | null>(null);
+
+  const refresh = async () => setItems(await storage.listCatalogItems({ includeInactive: true }));
+
+  const refreshUsage = async (r = range) =>
+    setUsage(await storage.catalogUsageReport(r?.[0].format('YYYY-MM-DD'), r?.[1].format('YYYY-MM-DD')));
+
+  useEffect(() => {
+    if (!open) return;
+    void refresh();
+    void refreshUsage();
+  }, [open]);
+
+  const startEditing = (item: CatalogItem | 'new') => {
+    form.resetFields();
+    form.setFieldsValue(
+      item === 'new'
+        ? { unit: 'kom', unitPrice: 0, active: true }
+        : { ...item, unit: normalizeInvoiceUnit(item.unit) }
+    );
+    setEditing(item);
+  };
+
+  const handleSave = async (values: ItemFormValues) => {
+    const input: CatalogItemInput = {
+      name: values.name.trim(),
+      unit: values.unit,
+      unitPrice: values.unitPrice,
+      vatRate: values.vatRate ?? null,
+      active: values.active,
+    };
+    setSaving(true);
+    try {
+      if (editing === 'new') {
+        await storage.createCatalogItem(input);
+      } else if (editing) {
+        await storage.updateCatalogItem(editing.id, input);
+      }
+      message.success(t('catalog.saved'));
+      setEditing(null);
+      await refresh();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = (item: CatalogItem) => {
+    Modal.confirm({
+      title: t('catalog.deleteTitle'),
+      content: t('catalog.deleteDesc', { name: item.name }),
+      okText: t('common.delete'),
+      okButtonProps: { danger: true },
+      cancelText: t('common.cancel'),
+      onOk: async () => {
+        if (await storage.deleteCatalogItem(item.id)) message.success(t('catalog.deleted'));
+        await refresh();
+      },
+    });
+  };
+
+  const itemsTab = (
+    <>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={items}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('catalog.empty') }}
+        columns={[
+          {
+            title: t('catalog.name'),
+            dataIndex: 'name',
+            render: (v: string, r: CatalogItem) => (
+              <Space size={6}>
+                {v}
+                {r.active ? null : <Tag>{t('catalog.inactive')}</Tag>}
+              </Space>
+            ),
+          },
+          { title: t('newInvoice.unit'), dataIndex: 'unit', width: 90, render: (v: string | null) => invoiceUnitLabel(normalizeInvoiceUnit(v)) },
+          {
+            title: t('newInvoice.unitPrice'),
+            dataIndex: 'unitPrice',
+            align: 'right' as const,
+            width: 130,
+            render: (v: number) => v.toFixed(2),
+          },
+          {
+            title: t('catalog.vatRate'),
+            dataIndex: 'vatRate',
+            align: 'right' as const,
+            width: 90,
+            render: (v: number | null) => (v == null ? '—' : `${v}%`),
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 110,
+            render: (_: unknown, r: CatalogItem) => (
+              <Space size={0}>
+                <Button type="link" icon={<EditOutlined />} disabled={!canWrite} onClick={() => startEditing(r)} />
+                <Button type="link" danger icon={<DeleteOutlined />} disabled={!canWrite} onClick={() => handleDelete(r)} />
+              </Space>
+            ),
+          },
+        ]}
+      />
+
+      {editing ? (
+        <Form form={form} layout="vertical" onFinish={(v) => void handleSave(v)} style={{ marginTop: 16 }}>
+          <Form.Item label={t('catalog.name')} name="name" rules={[{ required: true, message: t('catalog.nameReq') }]}>
+            <Input placeholder={t('catalog.namePlaceholder')} />
+          </Form.Item>
+          <Space style={{ width: '100%' }} size={12} align="start">
+            <Form.Item label={t('newInvoice.unit')} name="unit">
+              <Select style={{ width: 120 }} options={INVOICE_UNIT_VALUES.map((u) => ({ value: u, label: invoiceUnitLabel(u) }))} />
+            </Form.Item>
+            <Form.Item label={t('newInvoice.unitPrice')} name="unitPrice" rules={[{ required: true, message: t('catalog.priceReq') }]}>
+              <InputNumber min={0} step={0.01} style={{ width: 160 }} />
+            </Form.Item>
+            <Form.Item label={t('catalog.vatRate')} name="vatRate" extra={t('catalog.vatRateHelp')}>
+              <InputNumber min={0} max={100} step={1} style={{ width: 120 }} addonAfter="%" />
+            </Form.Item>
+            <Form.Item label={t('catalog.active')} name="active" valuePropName="checked">
+              <Switch />
+            </Form.Item>
+          </Space>
+          <Space>
+            <Button onClick={() => setEditing(null)}>{t('common.cancel')}</Button>
+            <Button type="primary" htmlType="submit" loading={saving}>
+              {t('common.save')}
+            </Button>
+          </Space>
+        </Form>
+      ) : (
+        <Button icon={<PlusOutlined />} disabled={!canWrite} onClick={() => startEditing('new')} style={{ marginTop: 12 }}>
+          {t('catalog.add')}
+        </Button>
+      )}
+    </>
+  );
+
+  const usageTab = (
+    <>
+      <DatePicker.RangePicker
+        format="DD.MM.YYYY"
+        value={range}
+        onChange={(v) => {
+          const next = v && v[0] && v[1] ? ([v[0], v[1]] as [dayjs.Dayjs, dayjs.Dayjs]) : null;
+          setRange(next);
+          void refreshUsage(next);
+        }}
+        style={{ marginBottom: 12 }}
+      />
+      <Table
+        size="small"
+        rowKey={(r) => `${r.catalogItemId}-${r.currency}`}
+        dataSource={usage}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('catalog.usageEmpty') }}
+        columns={[
+          { title: t('catalog.name'), dataIndex: 'name' },
+          { title: t('catalog.lines'), dataIndex: 'lines', align: 'right' as const, width: 80 },
+          { title: t('catalog.invoices'), dataIndex: 'invoices', align: 'right' as const, width: 80 },
+          { title: t('newInvoice.quantity'), dataIndex: 'quantity', align: 'right' as const, width: 90 },
+          {
+            title: t('catalog.averagePrice'),
+            key: 'average',
+            align: 'right' as const,
+            render: (_: unknown, r: CatalogUsage) => `${r.averageUnitPrice.toFixed(2)} ${r.currency}`,
+          },
+          {
+            title: t('catalog.billed'),
+            key: 'total',
+            align: 'right' as const,
+            render: (_: unknown, r: CatalogUsage) => `${r.total.toFixed(2)} ${r.currency}`,
+          },
+          {
+            title: t('catalog.lastBilled'),
+            dataIndex: 'lastBilled',
+            width: 110,
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+          },
+        ]}
+      />
+    </>
+  );
+
+  return (
+    <Modal title={t('catalog.title')} open={open} onCancel={onClose} footer={null} width={880}>
+      <Tabs
+        items={[
+          { key: 'items', label: t('catalog.itemsTab'), children: itemsTab },
+          { key: 'usage', label: t('catalog.usageTab'), children: usageTab },
+        ]}
+      />
+    </Modal>
+  );
+}
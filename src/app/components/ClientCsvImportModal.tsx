@@ -0,0 +1,179 @@
+import { useState } from 'react';
+import { Alert, Button, Form, Modal, Select, Table, Tag, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { ClientCsvMapping, ClientImportReport, ClientImportStatus } from '../types';
+
+const storage = getStorage();
+
+const FIELDS: (keyof ClientCsvMapping)[] = [
+  'name',
+  'pib',
+  'registrationNumber',
+  'address',
+  'postalCode',
+  'city',
+  'email',
+  'phone',
+];
+
+/** Header names commonly used for each field, for pre-filling the mapping. */
+const HEADER_GUESSES: Record<keyof ClientCsvMapping, string[]> = {
+  name: ['name', 'naziv', 'ime', 'klijent', 'firma', 'company'],
+  pib: ['pib', 'vat', 'vat id', 'tax id'],
+  registrationNumber: ['mb', 'maticni broj', 'matični broj', 'registration number'],
+  address: ['address', 'adresa', 'ulica'],
+  postalCode: ['postal code', 'zip', 'postanski broj', 'poštanski broj'],
+  city: ['city', 'grad', 'mesto'],
+  email: ['email', 'e-mail', 'mail'],
+  phone: ['phone', 'telefon', 'tel'],
+};
+
+function guessMapping(headers: string[]): Partial<ClientCsvMapping> {
+  const out: Partial<ClientCsvMapping> = {};
+  for (const field of FIELDS) {
+    const hit = headers.find((h) => HEADER_GUESSES[field].includes(h.trim().toLowerCase()));
+    if (hit) out[field] = hit;
+  }
+  return out;
+}
+
+const STATUS_COLORS: Record<ClientImportStatus, string> = {
+  imported: 'green',
+  skipped: 'red',
+  duplicate: 'orange',
+};
+
+type Props = {
+  open: boolean;
+  onClose: () => void;
+  onImported: () => void;
+};
+
+export function ClientCsvImportModal({ open: visible, onClose, onImported }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<ClientCsvMapping>();
+  const [path, setPath] = useState<string | null>(null);
+  const [headers, setHeaders] = useState<string[]>([]);
+  const [report, setReport] = useState<ClientImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+
+  const reset = () => {
+    setPath(null);
+    setHeaders([]);
+    setReport(null);
+    form.resetFields();
+  };
+
+  const handleClose = () => {
+    reset();
+    onClose();
+  };
+
+  const pickFile = async () => {
+    const selected = await open({
+      title: t('clients.import.pickFile'),
+      filters: [{ name: 'CSV', extensions: ['csv', 'txt'] }],
+      multiple: false,
+    });
+    const file = Array.isArray(selected) ? selected[0] : selected;
+    if (!file) return;
+    try {
+      const next = await storage.readCsvHeaders(file);
+      setPath(file);
+      setHeaders(next);
+      setReport(null);
+      form.setFieldsValue(guessMapping(next));
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const runImport = async (mapping: ClientCsvMapping) => {
+    if (!path) return;
+    setImporting(true);
+    try {
+      const res = await storage.importClientsCsv(path, mapping);
+      setReport(res);
+      if (res.imported > 0) onImported();
+    } catch (e: any) {
+      message.error(t('clients.import.failed', { message: String(e?.message ?? e) }));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const headerOptions = headers.map((h) => ({ value: h, label: h }));
+
+  return (
+    <Modal title={t('clients.import.title')} open={visible} onCancel={handleClose} footer={null} width={720}>
+      {report ? (
+        <>
+          <Alert
+            type={report.skipped > 0 ? 'warning' : 'success'}
+            showIcon
+            message={t('clients.import.summary', {
+              imported: report.imported,
+              skipped: report.skipped,
+              duplicates: report.duplicates,
+            })}
+            style={{ marginBottom: 12 }}
+          />
+          <Table
+            size="small"
+            rowKey="line"
+            dataSource={report.rows}
+            pagination={{ pageSize: 10 }}
+            columns={[
+              { title: t('clients.import.line'), dataIndex: 'line', width: 70 },
+              { title: t('clients.name'), dataIndex: 'name' },
+              {
+                title: t('clients.import.status'),
+                dataIndex: 'status',
+                width: 120,
+                render: (s: ClientImportStatus) => <Tag color={STATUS_COLORS[s]}>{t(`clients.import.statuses.${s}`)}</Tag>,
+              },
+              { title: t('clients.import.message'), dataIndex: 'message' },
+            ]}
+          />
+          <div style={{ display: 'flex', justifyContent: 'flex-end', marginTop: 12 }}>
+            <Button type="primary" onClick={handleClose}>
+              {t('common.close')}
+            </Button>
+          </div>
+        </>
+      ) : (
+        <>
+          <Button onClick={() => void pickFile()} style={{ marginBottom: 12 }}>
+            {path ? t('clients.import.changeFile') : t('clients.import.pickFile')}
+          </Button>
+          {path ? <div style={{ marginBottom: 12, wordBreak: 'break-all' }}>{path}</div> : null}
+          {headers.length > 0 ? (
+            <Form form={form} layout="vertical" onFinish={(v) => void runImport(v)}>
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 12 }}>
+                {FIELDS.map((field) => (
+                  <Form.Item
+                    key={field}
+                    label={t(`clients.import.fields.${field}`)}
+                    name={field}
+                    rules={field === 'name' ? [{ required: true, message: t('clients.import.nameRequired') }] : []}
+                  >
+                    <Select allowClear options={headerOptions} placeholder={t('clients.import.notMapped')} />
+                  </Form.Item>
+                ))}
+              </div>
+              <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8 }}>
+                <Button onClick={handleClose}>{t('common.cancel')}</Button>
+                <Button type="primary" htmlType="submit" loading={importing}>
+                  {t('clients.import.run')}
+                </Button>
+              </div>
+            </Form>
+          ) : null}
+        </>
+      )}
+    </Modal>
+  );
+}
@@ -0,0 +1,103 @@
+import { useEffect, useState } from 'react';
+import { Button, Input, Modal, Table, Typography, message } from 'antd';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { errorMessage } from '../services/commandError';
+import type { CorruptRow } from '../types';
+
+const storage = getStorage();
+
+/** Unreadable invoices and clients, each rebuilt from its columns on request. */
+export function CorruptRows({ disabled, onRepaired }: { disabled: boolean; onRepaired: () => void }) {
+  const { t } = useTranslation();
+  const [rows, setRows] = useState<CorruptRow[]>([]);
+  const [target, setTarget] = useState<CorruptRow | null>(null);
+  const [patch, setPatch] = useState('');
+  const [repairing, setRepairing] = useState(false);
+
+  const load = async () => {
+    try {
+      setRows(await storage.listCorruptRows());
+    } catch (e) {
+      message.error(errorMessage(e));
+    }
+  };
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  const close = () => {
+    setTarget(null);
+    setPatch('');
+  };
+
+  const repair = async () => {
+    if (!target) return;
+    setRepairing(true);
+    try {
+      const res = await storage.repairCorruptRow(target.id, patch.trim() || undefined);
+      if (res.droppedKeys.length > 0) {
+        message.warning(t('settings.backup.consistency.corrupt.repairedDropped', { keys: res.droppedKeys.join(', ') }));
+      } else {
+        message.success(t('settings.backup.consistency.corrupt.repaired'));
+      }
+      close();
+      await load();
+      onRepaired();
+    } catch (e) {
+      message.error(errorMessage(e));
+    } finally {
+      setRepairing(false);
+    }
+  };
+
+  if (rows.length === 0) return null;
+
+  return (
+    <>
+      <Table<CorruptRow>
+        size="small"
+        rowKey={(r) => `${r.table}:${r.id}`}
+        dataSource={rows}
+        pagination={false}
+        style={{ marginBottom: 12 }}
+        columns={[
+          { title: t('settings.backup.consistency.table'), dataIndex: 'table', width: 90 },
+          { title: t('settings.backup.consistency.corrupt.label'), dataIndex: 'label', width: 160 },
+          { title: t('settings.backup.consistency.corrupt.error'), dataIndex: 'error', ellipsis: true },
+          {
+            key: 'actions',
+            width: 100,
+            render: (_: unknown, row: CorruptRow) => (
+              <Button size="small" disabled={disabled} onClick={() => setTarget(row)}>
+                {t('settings.backup.consistency.corrupt.repair')}
+              </Button>
+            ),
+          },
+        ]}
+      />
+      <Modal
+        title={target ? t('settings.backup.consistency.corrupt.title', { label: target.label }) : null}
+        open={target !== null}
+        onCancel={close}
+        onOk={() => void repair()}
+        okText={t('settings.backup.consistency.corrupt.repair')}
+        confirmLoading={repairing}
+      >
+        <Typography.Paragraph type="secondary">{t('settings.backup.consistency.corrupt.help')}</Typography.Paragraph>
+        <Typography.Text type="danger">{target?.error}</Typography.Text>
+        <Typography.Paragraph style={{ marginTop: 12, marginBottom: 4 }}>
+          {t('settings.backup.consistency.corrupt.patch')}
+        </Typography.Paragraph>
+        <Input.TextArea
+          rows={4}
+          value={patch}
+          onChange={(e) => setPatch(e.target.value)}
+          style={{ fontFamily: 'monospace' }}
+        />
+      </Modal>
+    </>
+  );
+}
@@ -0,0 +1,44 @@
+import { Form, Select, Switch, Typography } from 'antd';
+import { useTranslation } from 'react-i18next';
+
+import type { CsvDelimiter } from '../types';
+
+const DELIMITERS: { value: CsvDelimiter; key: string }[] = [
+  { value: ',', key: 'comma' },
+  { value: ';', key: 'semicolon' },
+  { value: '\t', key: 'tab' },
+  { value: '|', key: 'pipe' },
+];
+
+/** CSV export dialect (fields of the surrounding settings form). */
+export function CsvOptionsFields() {
+  const { t } = useTranslation();
+
+  return (
+    <div>
+      <Typography.Title level={5} style={{ marginTop: 0 }}>{t('settings.csv.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.csv.help')}</Typography.Paragraph>
+      <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+        <Form.Item label={t('settings.csv.delimiter')} name={['csvOptions', 'delimiter']}>
+          <Select options={DELIMITERS.map((d) => ({ value: d.value, label: t(`settings.csv.delimiters.${d.key}`) }))} />
+        </Form.Item>
+        <Form.Item label={t('settings.csv.lineEnding')} name={['csvOptions', 'lineEnding']}>
+          <Select
+            options={(['crlf', 'lf'] as const).map((v) => ({ value: v, label: t(`settings.csv.lineEndings.${v}`) }))}
+          />
+        </Form.Item>
+        <Form.Item label={t('settings.csv.decimalComma')} name={['csvOptions', 'decimalComma']} valuePropName="checked">
+          <Switch />
+        </Form.Item>
+        <Form.Item
+          label={t('settings.csv.includeBom')}
+          name={['csvOptions', 'includeBom']}
+          valuePropName="checked"
+          extra={t('settings.csv.includeBomHelp')}
+        >
+          <Switch />
+        </Form.Item>
+      </div>
+    </div>
+  );
+}
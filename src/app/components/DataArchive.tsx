@@ -0,0 +1,109 @@
+import { useState } from 'react';
+import { Button, Modal, Select, Space, Typography, message } from 'antd';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import {
+  exportAllJson,
+  importAllJson,
+  type DataArchiveConflict,
+  type DataArchiveImportMode,
+} from '../services/backupService';
+
+/** Export/import of the whole database as one JSON archive. */
+export function DataArchive({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [mode, setMode] = useState<DataArchiveImportMode>('merge');
+  const [onConflict, setOnConflict] = useState<DataArchiveConflict>('skip');
+  const [busy, setBusy] = useState<'export' | 'import' | null>(null);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: `pausaler-data-${dayjs().format('YYYY-MM-DD')}.json`,
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+    });
+    if (!path) return;
+    setBusy('export');
+    try {
+      await exportAllJson(path);
+      message.success(t('settings.backup.archive.exported', { path }));
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const runImport = async (path: string) => {
+    setBusy('import');
+    try {
+      const report = await importAllJson(path, mode, onConflict);
+      const sum = (k: 'inserted' | 'duplicated' | 'skipped') => report.tables.reduce((acc, r) => acc + r[k], 0);
+      message.success(
+        t('settings.backup.archive.imported', {
+          inserted: sum('inserted'),
+          duplicated: sum('duplicated'),
+          skipped: sum('skipped'),
+        })
+      );
+      // Cached settings and lists are stale after a replace.
+      if (report.settingsImported) window.location.reload();
+    } catch (e: any) {
+      message.error(t('settings.backup.archive.importError', { message: errorText(e) }));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleImport = async () => {
+    const picked = await open({ filters: [{ name: 'JSON', extensions: ['json'] }], multiple: false });
+    if (!picked) return;
+    const path = Array.isArray(picked) ? picked[0] : picked;
+    if (mode === 'replace') {
+      Modal.confirm({
+        content: t('settings.backup.archive.replaceConfirm'),
+        okButtonProps: { danger: true },
+        onOk: () => runImport(path),
+      });
+      return;
+    }
+    await runImport(path);
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.archive.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.backup.archive.help')}</Typography.Paragraph>
+      <Space wrap style={{ marginBottom: 12 }}>
+        <Button onClick={() => void handleExport()} loading={busy === 'export'}>
+          {t('settings.backup.archive.exportButton')}
+        </Button>
+        <Select<DataArchiveImportMode>
+          aria-label={t('settings.backup.archive.mode')}
+          value={mode}
+          onChange={setMode}
+          style={{ width: 240 }}
+          options={(['merge', 'replace'] as const).map((m) => ({ value: m, label: t(`settings.backup.archive.modes.${m}`) }))}
+        />
+        {mode === 'merge' ? (
+          <Select<DataArchiveConflict>
+            aria-label={t('settings.backup.archive.conflict')}
+            value={onConflict}
+            onChange={setOnConflict}
+            style={{ width: 200 }}
+            options={(['skip', 'duplicate'] as const).map((c) => ({
+              value: c,
+              label: `${t('settings.backup.archive.conflict')}: ${t(`settings.backup.archive.conflicts.${c}`)}`,
+            }))}
+          />
+        ) : null}
+        <Button onClick={() => void handleImport()} loading={busy === 'import'} disabled={disabled}>
+          {t('settings.backup.archive.importButton')}
+        </Button>
+      </Space>
+    </div>
+  );
+}
@@ -0,0 +1,122 @@
+import { useState } from 'react';
+import { Alert, Button, Popconfirm, Space, Table, Typography, message } from 'antd';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { CorruptRows } from './CorruptRows';
+import type { ConsistencyIssue, ConsistencyReport, RepairStrategy } from '../types';
+
+const storage = getStorage();
+
+const show = (v: unknown) => (v === null || v === undefined ? '—' : typeof v === 'string' ? v : JSON.stringify(v));
+
+/** Column vs data_json check for invoices and clients, with a repair in either direction. */
+export function DataConsistency({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<ConsistencyReport | null>(null);
+  const [busy, setBusy] = useState<'check' | RepairStrategy | null>(null);
+  // Remounts the unreadable-row list so every check reloads it.
+  const [checks, setChecks] = useState(0);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  const check = async () => {
+    setBusy('check');
+    try {
+      setReport(await storage.checkDataConsistency());
+      setChecks((n) => n + 1);
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const repair = async (strategy: RepairStrategy) => {
+    setBusy(strategy);
+    try {
+      const res = await storage.repairDataConsistency(strategy);
+      message.success(t('settings.backup.consistency.repaired', { rows: res.rowsRepaired, fields: res.fieldsRepaired }));
+      setReport(res.remaining);
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.consistency.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.backup.consistency.help')}</Typography.Paragraph>
+      <Space wrap style={{ marginBottom: 12 }}>
+        <Button onClick={() => void check()} loading={busy === 'check'}>
+          {t('settings.backup.consistency.check')}
+        </Button>
+        {report && report.issues.length > 0
+          ? (['fromJson', 'fromColumns'] as const).map((strategy) => (
+              <Popconfirm
+                key={strategy}
+                title={t('settings.backup.consistency.repairConfirm')}
+                onConfirm={() => void repair(strategy)}
+                disabled={disabled}
+              >
+                <Button loading={busy === strategy} disabled={disabled}>
+                  {t(`settings.backup.consistency.${strategy === 'fromJson' ? 'repairFromJson' : 'repairFromColumns'}`)}
+                </Button>
+              </Popconfirm>
+            ))
+          : null}
+      </Space>
+      {report ? (
+        <>
+          <Alert
+            type={report.issues.length > 0 ? 'warning' : 'success'}
+            showIcon
+            style={{ marginBottom: 12 }}
+            message={
+              report.issues.length > 0
+                ? t('settings.backup.consistency.found', { count: report.issues.length })
+                : t('settings.backup.consistency.ok', {
+                    invoices: report.invoicesChecked,
+                    clients: report.clientsChecked,
+                  })
+            }
+          />
+          {report.unreadable.length > 0 ? (
+            <Alert
+              type="error"
+              showIcon
+              style={{ marginBottom: 12 }}
+              message={t('settings.backup.consistency.unreadable', { count: report.unreadable.length })}
+            />
+          ) : null}
+          <CorruptRows key={checks} disabled={disabled} onRepaired={() => void check()} />
+          {report.issues.length > 0 ? (
+            <Table<ConsistencyIssue>
+              size="small"
+              rowKey={(r) => `${r.table}:${r.id}:${r.field}`}
+              dataSource={report.issues}
+              pagination={{ pageSize: 10 }}
+              columns={[
+                { title: t('settings.backup.consistency.table'), dataIndex: 'table', width: 90 },
+                { title: t('settings.backup.consistency.record'), dataIndex: 'id', ellipsis: true },
+                { title: t('settings.backup.consistency.field'), dataIndex: 'field', width: 130 },
+                {
+                  title: t('settings.backup.consistency.column'),
+                  dataIndex: 'columnValue',
+                  render: (v: unknown) => show(v),
+                },
+                {
+                  title: t('settings.backup.consistency.json'),
+                  dataIndex: 'jsonValue',
+                  render: (v: unknown) => show(v),
+                },
+              ]}
+            />
+          ) : null}
+        </>
+      ) : null}
+    </div>
+  );
+}
@@ -0,0 +1,101 @@
+import { useState } from 'react';
+import { Alert, Button, Descriptions, Popconfirm, Space, Typography, message } from 'antd';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { MaintenanceReport } from '../types';
+
+const storage = getStorage();
+
+const formatSize = (bytes: number) =>
+  bytes >= 1024 * 1024 ? `${(bytes / (1024 * 1024)).toFixed(1)} MB` : `${Math.round(bytes / 1024)} KB`;
+
+/** Integrity check of the database file and a "compact" action (checkpoint, ANALYZE, VACUUM). */
+export function DatabaseHealth({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<MaintenanceReport | null>(null);
+  const [busy, setBusy] = useState<'check' | 'compact' | null>(null);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  const run = async (compact: boolean) => {
+    setBusy(compact ? 'compact' : 'check');
+    try {
+      const res = await storage.databaseMaintenance(compact);
+      setReport(res);
+      if (res.compacted) {
+        message.success(
+          t('settings.backup.health.compacted', {
+            before: formatSize(res.dbSizeBefore + res.walSizeBefore),
+            after: formatSize(res.dbSizeAfter + res.walSizeAfter),
+          }),
+        );
+      }
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.health.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.backup.health.help')}</Typography.Paragraph>
+      <Space wrap style={{ marginBottom: 12 }}>
+        <Button onClick={() => void run(false)} loading={busy === 'check'} disabled={busy === 'compact'}>
+          {t('settings.backup.health.check')}
+        </Button>
+        <Popconfirm
+          title={t('settings.backup.health.compactConfirm')}
+          onConfirm={() => void run(true)}
+          disabled={disabled || report?.restoreRecommended}
+        >
+          <Button
+            loading={busy === 'compact'}
+            disabled={disabled || busy === 'check' || report?.restoreRecommended}
+          >
+            {t('settings.backup.health.compact')}
+          </Button>
+        </Popconfirm>
+      </Space>
+      {report ? (
+        <>
+          {report.restoreRecommended ? (
+            <Alert
+              type="error"
+              showIcon
+              style={{ marginBottom: 12 }}
+              message={t('settings.backup.health.corrupted')}
+              description={
+                <ul style={{ margin: 0, paddingLeft: 18 }}>
+                  {report.integrityErrors.slice(0, 10).map((err, i) => (
+                    <li key={i}>{err}</li>
+                  ))}
+                </ul>
+              }
+            />
+          ) : (
+            <Alert type="success" showIcon style={{ marginBottom: 12 }} message={t('settings.backup.health.ok')} />
+          )}
+          {report.checkpointBusy ? (
+            <Alert
+              type="warning"
+              showIcon
+              style={{ marginBottom: 12 }}
+              message={t('settings.backup.health.checkpointBusy')}
+            />
+          ) : null}
+          <Descriptions size="small" column={1} bordered>
+            <Descriptions.Item label={t('settings.backup.health.dbSize')}>{formatSize(report.dbSizeAfter)}</Descriptions.Item>
+            <Descriptions.Item label={t('settings.backup.health.walSize')}>{formatSize(report.walSizeAfter)}</Descriptions.Item>
+            <Descriptions.Item label={t('settings.backup.health.checkedAt')}>
+              {dayjs(report.checkedAt).format('DD.MM.YYYY HH:mm')}
+            </Descriptions.Item>
+          </Descriptions>
+        </>
+      ) : null}
+    </div>
+  );
+}
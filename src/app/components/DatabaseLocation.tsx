@@ -0,0 +1,93 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Descriptions, Modal, Typography, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+
+import { getDatabaseInfo, moveDatabaseTo, type DatabaseInfo } from '../services/backupService';
+
+const formatSize = (bytes: number) =>
+  bytes >= 1024 * 1024 ? `${(bytes / (1024 * 1024)).toFixed(1)} MB` : `${Math.round(bytes / 1024)} KB`;
+
+/** Shows which database file is in use and moves it to another folder. */
+export function DatabaseLocation({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [info, setInfo] = useState<DatabaseInfo | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  useEffect(() => {
+    let mounted = true;
+    getDatabaseInfo()
+      .then((res) => {
+        if (mounted) setInfo(res);
+      })
+      .catch(() => {});
+    return () => {
+      mounted = false;
+    };
+  }, []);
+
+  const move = async () => {
+    const dir = await open({ title: t('settings.backup.location.pickFolder'), directory: true, multiple: false });
+    const path = Array.isArray(dir) ? dir[0] : dir;
+    if (!path) return;
+    Modal.confirm({
+      title: t('settings.backup.location.moveConfirm', { path }),
+      onOk: async () => {
+        setBusy(true);
+        try {
+          const res = await moveDatabaseTo(path);
+          setInfo(res.database);
+          message.success(t('settings.backup.location.moved', { path: res.oldBackupPath }));
+        } catch (e: any) {
+          message.error(errorText(e));
+        } finally {
+          setBusy(false);
+        }
+      },
+    });
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.location.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.backup.location.help')}</Typography.Paragraph>
+      {info ? (
+        <>
+          <Descriptions size="small" column={1} bordered style={{ marginBottom: 12 }}>
+            <Descriptions.Item label={t('settings.backup.location.path')}>
+              <Typography.Text copyable>{info.path}</Typography.Text>
+            </Descriptions.Item>
+            <Descriptions.Item label={t('settings.backup.location.size')}>
+              {formatSize(info.sizeBytes + info.walSizeBytes)}
+            </Descriptions.Item>
+            <Descriptions.Item label={t('settings.backup.location.source')}>
+              {t(`settings.backup.location.sources.${info.source}`)}
+            </Descriptions.Item>
+          </Descriptions>
+          {info.otherCandidates.length > 0 ? (
+            <Alert
+              type="warning"
+              showIcon
+              style={{ marginBottom: 12 }}
+              message={t('settings.backup.location.othersTitle')}
+              description={
+                <ul style={{ margin: 0, paddingLeft: 18 }}>
+                  {info.otherCandidates.map((c) => (
+                    <li key={c.path}>
+                      {c.path} ({formatSize(c.sizeBytes)})
+                    </li>
+                  ))}
+                </ul>
+              }
+            />
+          ) : null}
+        </>
+      ) : null}
+      <Button onClick={() => void move()} loading={busy} disabled={disabled || !info}>
+        {t('settings.backup.location.move')}
+      </Button>
+    </div>
+  );
+}
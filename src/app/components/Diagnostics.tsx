@@ -0,0 +1,142 @@
+import { useEffect, useState } from 'react';
+import { Button, Select, Space, Table, Tag, Typography, message } from 'antd';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { LogEntry, LogLevel } from '../types';
+
+const storage = getStorage();
+
+const LEVELS: LogLevel[] = ['error', 'warn', 'info', 'debug', 'trace'];
+const LEVEL_COLORS: Record<string, string> = { ERROR: 'red', WARN: 'orange', INFO: 'blue', DEBUG: 'default', TRACE: 'default' };
+const MAX_LINES = 300;
+
+const formatFields = (fields: Record<string, unknown>) =>
+  Object.entries(fields)
+    .map(([k, v]) => `${k}=${typeof v === 'string' ? v : JSON.stringify(v)}`)
+    .join(' ');
+
+/** Recent app log entries and the log level setting, for support requests. */
+export function Diagnostics({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [entries, setEntries] = useState<LogEntry[]>([]);
+  const [filter, setFilter] = useState<LogLevel | 'all'>('warn');
+  const [level, setLevel] = useState<LogLevel | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  const load = async (show: LogLevel | 'all') => {
+    setLoading(true);
+    try {
+      setEntries(await storage.getRecentLogs(MAX_LINES, show === 'all' ? undefined : show));
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    void load(filter);
+  }, [filter]);
+
+  useEffect(() => {
+    storage
+      .getLogLevel()
+      .then(setLevel)
+      .catch(() => {});
+  }, []);
+
+  const changeLevel = async (next: LogLevel) => {
+    try {
+      const saved = await storage.setLogLevel(next);
+      setLevel(saved);
+      message.success(t('settings.diagnostics.levelSaved', { level: t(`settings.diagnostics.levels.${saved}`) }));
+    } catch (e: any) {
+      message.error(errorText(e));
+    }
+  };
+
+  const copy = async () => {
+    const text = entries
+      .map((e) => `${e.timestamp} ${e.level} ${e.target}: ${e.message} ${formatFields(e.fields)}`.trim())
+      .join('\n');
+    try {
+      await navigator.clipboard.writeText(text);
+      message.success(t('settings.diagnostics.copied'));
+    } catch (e: any) {
+      message.error(errorText(e));
+    }
+  };
+
+  const levelOptions = LEVELS.map((l) => ({ value: l, label: t(`settings.diagnostics.levels.${l}`) }));
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.diagnostics.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.diagnostics.help')}</Typography.Paragraph>
+      <Space wrap style={{ marginBottom: 12 }}>
+        <Typography.Text>{t('settings.diagnostics.level')}:</Typography.Text>
+        <Select<LogLevel>
+          style={{ width: 200 }}
+          value={level ?? undefined}
+          options={levelOptions}
+          onChange={(v) => void changeLevel(v)}
+          disabled={disabled || level === null}
+        />
+        <Typography.Text>{t('settings.diagnostics.show')}:</Typography.Text>
+        <Select<LogLevel | 'all'>
+          style={{ width: 200 }}
+          value={filter}
+          options={[...levelOptions, { value: 'all', label: t('settings.diagnostics.all') }]}
+          onChange={setFilter}
+        />
+        <Button onClick={() => void load(filter)} loading={loading}>
+          {t('settings.diagnostics.refresh')}
+        </Button>
+        <Button onClick={() => void copy()} disabled={entries.length === 0}>
+          {t('settings.diagnostics.copy')}
+        </Button>
+      </Space>
+      <Table<LogEntry>
+        size="small"
+        loading={loading}
+        rowKey={(_, i) => String(i)}
+        dataSource={[...entries].reverse()}
+        pagination={{ pageSize: 20 }}
+        locale={{ emptyText: t('settings.diagnostics.empty') }}
+        columns={[
+          {
+            title: t('settings.diagnostics.time'),
+            dataIndex: 'timestamp',
+            width: 150,
+            render: (v: string) => (dayjs(v).isValid() ? dayjs(v).format('DD.MM.YYYY HH:mm:ss') : v),
+          },
+          {
+            title: '',
+            dataIndex: 'level',
+            width: 80,
+            render: (v: string) => <Tag color={LEVEL_COLORS[v] ?? 'default'}>{v}</Tag>,
+          },
+          { title: t('settings.diagnostics.source'), dataIndex: 'target', width: 110 },
+          {
+            title: t('settings.diagnostics.message'),
+            key: 'message',
+            render: (_, e) => (
+              <span>
+                {e.message}
+                {Object.keys(e.fields).length > 0 ? (
+                  <Typography.Text type="secondary" style={{ display: 'block', fontSize: 12 }}>
+                    {formatFields(e.fields)}
+                  </Typography.Text>
+                ) : null}
+              </span>
+            ),
+          },
+        ]}
+      />
+    </div>
+  );
+}
@@ -0,0 +1,43 @@
+import { Button, ColorPicker, Form, Typography } from 'antd';
+import { useTranslation } from 'react-i18next';
+
+import { DEFAULT_EMAIL_THEME } from '../services/defaultSettings';
+import type { EmailPalette } from '../types';
+
+const SCHEMES = ['light', 'dark'] as const;
+const ROLES: (keyof EmailPalette)[] = ['background', 'card', 'border', 'accent', 'text', 'muted', 'faint'];
+
+/** Colors of the invoice email, light and dark (fields of the surrounding settings form). */
+export function EmailThemeFields() {
+  const { t } = useTranslation();
+  const form = Form.useFormInstance();
+
+  return (
+    <div>
+      <Typography.Title level={5}>{t('settings.emailTheme.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.emailTheme.help')}</Typography.Paragraph>
+      <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+        {SCHEMES.map((scheme) => (
+          <div key={scheme}>
+            <Typography.Text strong>{t(`settings.emailTheme.${scheme}`)}</Typography.Text>
+            <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', columnGap: 12, marginTop: 8 }}>
+              {ROLES.map((role) => (
+                <Form.Item
+                  key={role}
+                  label={t(`settings.emailTheme.roles.${role}`)}
+                  name={['emailTheme', scheme, role]}
+                  getValueFromEvent={(color) => color.toHexString()}
+                >
+                  <ColorPicker disabledAlpha showText />
+                </Form.Item>
+              ))}
+            </div>
+          </div>
+        ))}
+      </div>
+      <Button size="small" onClick={() => form.setFieldValue('emailTheme', DEFAULT_EMAIL_THEME)}>
+        {t('settings.emailTheme.reset')}
+      </Button>
+    </div>
+  );
+}
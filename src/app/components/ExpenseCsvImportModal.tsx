@@ -0,0 +1,223 @@
+import { useState } from 'react';
+import { Alert, Button, Checkbox, Form, Modal, Select, Table, Tag, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { CURRENCY_VALUES } from '../types';
+import type { ExpenseCsvMapping, ExpenseImportReport, ExpenseImportRow, ExpenseImportStatus } from '../types';
+
+const storage = getStorage();
+
+type ColumnField = 'date' | 'title' | 'amount' | 'credit' | 'currency' | 'category';
+
+const FIELDS: ColumnField[] = ['date', 'title', 'amount', 'credit', 'currency', 'category'];
+const REQUIRED: ColumnField[] = ['date', 'title', 'amount'];
+
+/** Header names commonly used by Serbian banks for each field, for pre-filling the mapping. */
+const HEADER_GUESSES: Record<ColumnField, string[]> = {
+  date: ['date', 'datum', 'datum valute', 'datum knjiženja', 'datum knjizenja'],
+  title: ['description', 'opis', 'svrha', 'svrha plaćanja', 'svrha placanja', 'primalac'],
+  amount: ['debit', 'amount', 'iznos', 'zaduženje', 'zaduzenje', 'duguje', 'isplata'],
+  credit: ['credit', 'odobrenje', 'potražuje', 'potrazuje', 'uplata'],
+  currency: ['currency', 'valuta'],
+  category: ['category', 'kategorija'],
+};
+
+function guessMapping(headers: string[]): Partial<ExpenseCsvMapping> {
+  const out: Partial<ExpenseCsvMapping> = {};
+  for (const field of FIELDS) {
+    const hit = headers.find((h) => HEADER_GUESSES[field].includes(h.trim().toLowerCase()));
+    if (hit) out[field] = hit;
+  }
+  return out;
+}
+
+const STATUS_COLORS: Record<ExpenseImportStatus, string> = {
+  imported: 'green',
+  skipped: 'red',
+  duplicate: 'orange',
+};
+
+type FormValues = ExpenseCsvMapping & { defaultCurrency: string };
+
+type Props = {
+  open: boolean;
+  defaultCurrency: string;
+  onClose: () => void;
+  onImported: () => void;
+};
+
+export function ExpenseCsvImportModal({ open: visible, defaultCurrency, onClose, onImported }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<FormValues>();
+  const [path, setPath] = useState<string | null>(null);
+  const [headers, setHeaders] = useState<string[]>([]);
+  const [report, setReport] = useState<ExpenseImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [values, setValues] = useState<FormValues | null>(null);
+
+  const reset = () => {
+    setPath(null);
+    setHeaders([]);
+    setReport(null);
+    setValues(null);
+    form.resetFields();
+  };
+
+  const handleClose = () => {
+    reset();
+    onClose();
+  };
+
+  const pickFile = async () => {
+    const selected = await open({
+      title: t('expenses.import.pickFile'),
+      filters: [{ name: 'CSV', extensions: ['csv', 'txt'] }],
+      multiple: false,
+    });
+    const file = Array.isArray(selected) ? selected[0] : selected;
+    if (!file) return;
+    try {
+      const next = await storage.readCsvHeaders(file);
+      setPath(file);
+      setHeaders(next);
+      setReport(null);
+      form.setFieldsValue({ ...guessMapping(next), defaultCurrency, signedAmounts: false });
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const run = async (next: FormValues, preview: boolean) => {
+    if (!path) return;
+    const { defaultCurrency: currency, ...mapping } = next;
+    setValues(next);
+    setImporting(true);
+    try {
+      const res = await storage.importExpensesCsv(path, mapping, currency, { preview });
+      setReport(res);
+      if (!res.preview && res.imported > 0) onImported();
+    } catch (e: any) {
+      message.error(t('expenses.import.failed', { message: String(e?.message ?? e) }));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const headerOptions = headers.map((h) => ({ value: h, label: h }));
+  const summaryKey = report?.preview ? 'expenses.import.previewSummary' : 'expenses.import.summary';
+
+  return (
+    <Modal title={t('expenses.import.title')} open={visible} onCancel={handleClose} footer={null} width={820}>
+      {report ? (
+        <>
+          <Alert
+            type={report.skipped > 0 ? 'warning' : 'success'}
+            showIcon
+            message={t(summaryKey, {
+              imported: report.imported,
+              skipped: report.skipped,
+              duplicates: report.duplicates,
+            })}
+            style={{ marginBottom: 12 }}
+          />
+          <Table
+            size="small"
+            rowKey="line"
+            dataSource={report.rows}
+            pagination={{ pageSize: 10 }}
+            columns={[
+              { title: t('expenses.import.line'), dataIndex: 'line', width: 60 },
+              {
+                title: t('expenses.date'),
+                dataIndex: 'date',
+                width: 110,
+                render: (v?: string | null) => (v ? dayjs(v).format('DD.MM.YYYY') : ''),
+              },
+              { title: t('expenses.titleCol'), dataIndex: 'title' },
+              {
+                title: t('expenses.amount'),
+                key: 'amount',
+                align: 'right' as const,
+                render: (_: unknown, r: ExpenseImportRow) =>
+                  r.amount != null ? `${r.amount.toFixed(2)} ${r.currency ?? ''}` : '',
+              },
+              {
+                title: t('expenses.import.status'),
+                dataIndex: 'status',
+                width: 120,
+                render: (s: ExpenseImportStatus) => (
+                  <Tag color={STATUS_COLORS[s]}>{t(`expenses.import.statuses.${s}`)}</Tag>
+                ),
+              },
+              { title: t('expenses.import.message'), dataIndex: 'message' },
+            ]}
+          />
+          <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8, marginTop: 12 }}>
+            {report.preview ? (
+              <>
+                <Button onClick={() => setReport(null)}>{t('expenses.import.back')}</Button>
+                <Button
+                  type="primary"
+                  loading={importing}
+                  disabled={report.imported === 0 || !values}
+                  onClick={() => values && void run(values, false)}
+                >
+                  {t('expenses.import.run')}
+                </Button>
+              </>
+            ) : (
+              <Button type="primary" onClick={handleClose}>
+                {t('common.close')}
+              </Button>
+            )}
+          </div>
+        </>
+      ) : (
+        <>
+          <Button onClick={() => void pickFile()} style={{ marginBottom: 12 }}>
+            {path ? t('expenses.import.changeFile') : t('expenses.import.pickFile')}
+          </Button>
+          {path ? <div style={{ marginBottom: 12, wordBreak: 'break-all' }}>{path}</div> : null}
+          {headers.length > 0 ? (
+            <Form form={form} layout="vertical" initialValues={values ?? undefined}>
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 12 }}>
+                {FIELDS.map((field) => (
+                  <Form.Item
+                    key={field}
+                    label={t(`expenses.import.fields.${field}`)}
+                    name={field}
+                    rules={REQUIRED.includes(field) ? [{ required: true, message: t('expenses.import.required') }] : []}
+                  >
+                    <Select allowClear options={headerOptions} placeholder={t('expenses.import.notMapped')} />
+                  </Form.Item>
+                ))}
+                <Form.Item label={t('expenses.import.defaultCurrency')} name="defaultCurrency" rules={[{ required: true }]}>
+                  <Select options={CURRENCY_VALUES.map((c) => ({ value: c, label: t(`currencies.${c}`) }))} />
+                </Form.Item>
+              </div>
+              <Form.Item name="signedAmounts" valuePropName="checked">
+                <Checkbox>{t('expenses.import.signedAmounts')}</Checkbox>
+              </Form.Item>
+              <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8 }}>
+                <Button onClick={handleClose}>{t('common.cancel')}</Button>
+                <Button loading={importing} onClick={() => void form.validateFields().then((v) => run(v, true))}>
+                  {t('expenses.import.preview')}
+                </Button>
+                <Button
+                  type="primary"
+                  loading={importing}
+                  onClick={() => void form.validateFields().then((v) => run(v, false))}
+                >
+                  {t('expenses.import.run')}
+                </Button>
+              </div>
+            </Form>
+          ) : null}
+        </>
+      )}
+    </Modal>
+  );
+}
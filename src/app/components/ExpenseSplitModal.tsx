@@ -0,0 +1,117 @@
+import { useEffect } from 'react';
+import { Alert, Button, Form, Input, InputNumber, Modal, Space, message } from 'antd';
+import { DeleteOutlined, PlusOutlined } from '@ant-design/icons';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { Expense, ExpenseSplitPart } from '../types';
+
+const storage = getStorage();
+
+type FormValues = { parts: ExpenseSplitPart[] };
+
+const toCents = (v: number | null | undefined) => Math.round((v ?? 0) * 100);
+
+type Props = {
+  expense: Expense | null;
+  onClose: () => void;
+  onSplit: () => void;
+};
+
+export function ExpenseSplitModal({ expense, onClose, onSplit }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<FormValues>();
+  const parts = Form.useWatch('parts', form) ?? [];
+
+  useEffect(() => {
+    if (!expense) return;
+    form.setFieldsValue({
+      parts: [
+        { title: expense.title, amount: expense.amount },
+        { title: '', amount: 0 },
+      ],
+    });
+  }, [expense, form]);
+
+  if (!expense) return null;
+
+  const remainingCents = toCents(expense.amount) - parts.reduce((sum, p) => sum + toCents(p?.amount), 0);
+
+  const handleFinish = async (values: FormValues) => {
+    try {
+      const res = await storage.splitExpense(expense.id, values.parts);
+      if (!res) {
+        message.error(t('expenses.notFound'));
+      } else {
+        message.success(t('expenses.split.done', { count: res.length }));
+      }
+      onSplit();
+      onClose();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  return (
+    <Modal title={t('expenses.split.title')} open onCancel={onClose} footer={null} width={620} destroyOnClose>
+      <p>
+        {expense.title} — {expense.amount.toFixed(2)} {expense.currency}
+      </p>
+      <Form form={form} layout="vertical" onFinish={(v) => void handleFinish(v)}>
+        <Form.List name="parts">
+          {(fields, { add, remove }) => (
+            <>
+              {fields.map((field) => (
+                <Space key={field.key} align="start" style={{ display: 'flex' }}>
+                  <Form.Item
+                    name={[field.name, 'title']}
+                    rules={[{ required: true, message: t('expenses.titleReq') }]}
+                    style={{ width: 320 }}
+                  >
+                    <Input placeholder={t('expenses.titlePlaceholder')} />
+                  </Form.Item>
+                  <Form.Item
+                    name={[field.name, 'amount']}
+                    rules={[{ required: true, message: t('expenses.amountReq') }]}
+                  >
+                    <InputNumber min={0.01} step={0.01} style={{ width: 140 }} />
+                  </Form.Item>
+                  <Button
+                    type="text"
+                    danger
+                    icon={<DeleteOutlined />}
+                    disabled={fields.length <= 2}
+                    onClick={() => remove(field.name)}
+                  />
+                </Space>
+              ))}
+              <Button
+                type="dashed"
+                icon={<PlusOutlined />}
+                onClick={() => add({ title: '', amount: Math.max(remainingCents, 0) / 100 })}
+                style={{ marginBottom: 12 }}
+              >
+                {t('expenses.split.addPart')}
+              </Button>
+            </>
+          )}
+        </Form.List>
+        <Alert
+          type={remainingCents === 0 ? 'success' : 'warning'}
+          showIcon
+          message={t('expenses.split.remaining', {
+            amount: (remainingCents / 100).toFixed(2),
+            currency: expense.currency,
+          })}
+          style={{ marginBottom: 12 }}
+        />
+        <Space style={{ width: '100%', justifyContent: 'flex-end' }}>
+          <Button onClick={onClose}>{t('common.cancel')}</Button>
+          <Button type="primary" htmlType="submit" disabled={remainingCents !== 0}>
+            {t('expenses.split.run')}
+          </Button>
+        </Space>
+      </Form>
+    </Modal>
+  );
+}
@@ -0,0 +1,151 @@
+import { useEffect, useRef, useState } from 'react';
+import { Empty, Input, List, Modal, Tag, Typography } from 'antd';
+import type { InputRef } from 'antd';
+import { useNavigate } from 'react-router-dom';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { SearchKind, SearchResult } from '../types';
+
+const storage = getStorage();
+
+const KIND_COLORS: Record<SearchKind, string> = {
+  invoice: 'blue',
+  client: 'green',
+  expense: 'orange',
+};
+
+function targetPath(r: SearchResult): string {
+  switch (r.kind) {
+    case 'invoice':
+      return `/invoices/view/${r.id}`;
+    case 'client':
+      return '/clients';
+    case 'expense':
+      return '/expenses';
+  }
+}
+
+/** Command-palette search over invoices, clients and expenses; opened with Ctrl+K / Cmd+K. */
+export function GlobalSearch() {
+  const { t } = useTranslation();
+  const navigate = useNavigate();
+  const inputRef = useRef<InputRef>(null);
+  const [visible, setVisible] = useState(false);
+  const [query, setQuery] = useState('');
+  const [results, setResults] = useState<SearchResult[]>([]);
+  const [active, setActive] = useState(0);
+
+  useEffect(() => {
+    const onKey = (e: KeyboardEvent) => {
+      if ((e.ctrlKey || e.metaKey) && e.key.toLowerCase() === 'k') {
+        e.preventDefault();
+        setVisible((v) => !v);
+      }
+    };
+    window.addEventListener('keydown', onKey);
+    return () => window.removeEventListener('keydown', onKey);
+  }, []);
+
+  useEffect(() => {
+    if (!query.trim()) {
+      setResults([]);
+      return;
+    }
+    let cancelled = false;
+    const timer = window.setTimeout(() => {
+      storage
+        .globalSearch(query)
+        .then((next) => {
+          if (cancelled) return;
+          setResults(next);
+          setActive(0);
+        })
+        .catch(() => {
+          if (!cancelled) setResults([]);
+        });
+    }, 150);
+    return () => {
+      cancelled = true;
+      window.clearTimeout(timer);
+    };
+  }, [query]);
+
+  const close = () => {
+    setVisible(false);
+    setQuery('');
+    setResults([]);
+  };
+
+  const pick = (r: SearchResult | undefined) => {
+    if (!r) return;
+    close();
+    navigate(targetPath(r));
+  };
+
+  const onKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'ArrowDown') {
+      e.preventDefault();
+      setActive((i) => Math.min(i + 1, results.length - 1));
+    } else if (e.key === 'ArrowUp') {
+      e.preventDefault();
+      setActive((i) => Math.max(i - 1, 0));
+    } else if (e.key === 'Enter') {
+      pick(results[active]);
+    }
+  };
+
+  return (
+    <Modal
+      open={visible}
+      onCancel={close}
+      footer={null}
+      closable={false}
+      width={640}
+      afterOpenChange={(open) => open && inputRef.current?.focus()}
+      destroyOnClose
+    >
+      <Input
+        ref={inputRef}
+        size="large"
+        allowClear
+        placeholder={t('search.placeholder')}
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+        onKeyDown={onKeyDown}
+      />
+      {query.trim() ? (
+        <List
+          style={{ marginTop: 12, maxHeight: 420, overflow: 'auto' }}
+          dataSource={results}
+          locale={{ emptyText: <Empty image={Empty.PRESENTED_IMAGE_SIMPLE} description={t('search.empty')} /> }}
+          renderItem={(r, i) => (
+            <List.Item
+              key={`${r.kind}:${r.id}`}
+              onClick={() => pick(r)}
+              onMouseEnter={() => setActive(i)}
+              style={{
+                cursor: 'pointer',
+                padding: '8px 12px',
+                borderRadius: 6,
+                background: i === active ? 'rgba(0, 0, 0, 0.04)' : undefined,
+              }}
+            >
+              <List.Item.Meta
+                title={
+                  <span>
+                    <Tag color={KIND_COLORS[r.kind]}>{t(`search.kinds.${r.kind}`)}</Tag>
+                    {r.title}
+                  </span>
+                }
+                description={r.snippet ? <Typography.Text type="secondary">{r.snippet}</Typography.Text> : null}
+              />
+              {r.date ? <Typography.Text type="secondary">{dayjs(r.date).format('DD.MM.YYYY')}</Typography.Text> : null}
+            </List.Item>
+          )}
+        />
+      ) : null}
+    </Modal>
+  );
+}
@@ -0,0 +1,251 @@
+import { useState } from 'react';
+import { Alert, Button, Form, Modal, Select, Table, Tag, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { CURRENCY_VALUES } from '../types';
+import type { InvoiceCsvMapping, InvoiceImportReport, InvoiceImportRow, InvoiceImportStatus } from '../types';
+
+const storage = getStorage();
+
+type ColumnField = keyof InvoiceCsvMapping;
+
+const FIELDS: ColumnField[] = [
+  'invoiceNumber',
+  'issueDate',
+  'clientName',
+  'clientPib',
+  'itemDescription',
+  'itemQuantity',
+  'itemUnit',
+  'itemUnitPrice',
+  'itemTotal',
+  'serviceDate',
+  'dueDate',
+  'paidAt',
+  'currency',
+  'status',
+  'notes',
+];
+const REQUIRED: ColumnField[] = ['invoiceNumber', 'issueDate', 'clientName', 'itemDescription'];
+
+/** Header names other invoicing tools commonly use for each field, for pre-filling the mapping. */
+const HEADER_GUESSES: Record<ColumnField, string[]> = {
+  invoiceNumber: ['invoice number', 'number', 'broj', 'broj fakture', 'broj računa', 'broj racuna'],
+  issueDate: ['issue date', 'date', 'datum', 'datum izdavanja', 'datum fakture'],
+  clientName: ['client', 'customer', 'kupac', 'klijent', 'naziv kupca'],
+  clientPib: ['pib', 'vat', 'tax id', 'pib kupca'],
+  itemDescription: ['description', 'item', 'opis', 'stavka', 'naziv stavke', 'usluga'],
+  itemQuantity: ['quantity', 'qty', 'količina', 'kolicina', 'kol'],
+  itemUnit: ['unit', 'jedinica', 'jm', 'jedinica mere'],
+  itemUnitPrice: ['unit price', 'price', 'cena', 'jedinična cena', 'jedinicna cena'],
+  itemTotal: ['total', 'amount', 'iznos', 'ukupno'],
+  serviceDate: ['service date', 'datum prometa'],
+  dueDate: ['due date', 'rok plaćanja', 'rok placanja', 'valuta plaćanja'],
+  paidAt: ['paid at', 'payment date', 'datum plaćanja', 'datum placanja'],
+  currency: ['currency', 'valuta'],
+  status: ['status'],
+  notes: ['notes', 'note', 'napomena'],
+};
+
+function guessMapping(headers: string[]): Partial<InvoiceCsvMapping> {
+  const out: Partial<InvoiceCsvMapping> = {};
+  for (const field of FIELDS) {
+    const hit = headers.find((h) => HEADER_GUESSES[field].includes(h.trim().toLowerCase()));
+    if (hit) out[field] = hit;
+  }
+  return out;
+}
+
+const STATUS_COLORS: Record<InvoiceImportStatus, string> = {
+  imported: 'green',
+  skipped: 'red',
+  duplicate: 'orange',
+};
+
+type FormValues = InvoiceCsvMapping & { defaultCurrency: string };
+
+type Props = {
+  open: boolean;
+  defaultCurrency: string;
+  onClose: () => void;
+  onImported: () => void;
+};
+
+export function InvoiceCsvImportModal({ open: visible, defaultCurrency, onClose, onImported }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<FormValues>();
+  const [path, setPath] = useState<string | null>(null);
+  const [headers, setHeaders] = useState<string[]>([]);
+  const [report, setReport] = useState<InvoiceImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [values, setValues] = useState<FormValues | null>(null);
+
+  const reset = () => {
+    setPath(null);
+    setHeaders([]);
+    setReport(null);
+    setValues(null);
+    form.resetFields();
+  };
+
+  const handleClose = () => {
+    reset();
+    onClose();
+  };
+
+  const pickFile = async () => {
+    const selected = await open({
+      title: t('invoices.import.pickFile'),
+      filters: [{ name: 'CSV', extensions: ['csv', 'txt'] }],
+      multiple: false,
+    });
+    const file = Array.isArray(selected) ? selected[0] : selected;
+    if (!file) return;
+    try {
+      const next = await storage.readCsvHeaders(file);
+      setPath(file);
+      setHeaders(next);
+      setReport(null);
+      form.setFieldsValue({ ...guessMapping(next), defaultCurrency });
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const run = async (next: FormValues, preview: boolean) => {
+    if (!path) return;
+    const { defaultCurrency: currency, ...mapping } = next;
+    setValues(next);
+    setImporting(true);
+    try {
+      const res = await storage.importInvoicesCsv(path, mapping, { preview, defaultCurrency: currency });
+      setReport(res);
+      if (!res.preview && res.imported > 0) onImported();
+    } catch (e: any) {
+      message.error(t('invoices.import.failed', { message: String(e?.message ?? e) }));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const headerOptions = headers.map((h) => ({ value: h, label: h }));
+  const summaryKey = report?.preview ? 'invoices.import.previewSummary' : 'invoices.import.summary';
+
+  return (
+    <Modal title={t('invoices.import.title')} open={visible} onCancel={handleClose} footer={null} width={860}>
+      {report ? (
+        <>
+          <Alert
+            type={report.skipped > 0 ? 'warning' : 'success'}
+            showIcon
+            message={t(summaryKey, {
+              imported: report.imported,
+              skipped: report.skipped,
+              duplicates: report.duplicates,
+              clients: report.clientsCreated,
+            })}
+            style={{ marginBottom: 12 }}
+          />
+          <Table
+            size="small"
+            rowKey="line"
+            dataSource={report.rows}
+            pagination={{ pageSize: 10 }}
+            columns={[
+              { title: t('invoices.import.line'), dataIndex: 'line', width: 60 },
+              { title: t('invoices.number'), dataIndex: 'invoiceNumber', width: 130 },
+              {
+                title: t('invoices.client'),
+                key: 'client',
+                render: (_: unknown, r: InvoiceImportRow) => (
+                  <>
+                    {r.clientName}
+                    {r.clientCreated ? <Tag style={{ marginLeft: 6 }}>{t('invoices.import.newClient')}</Tag> : null}
+                  </>
+                ),
+              },
+              { title: t('invoices.import.items'), dataIndex: 'itemCount', width: 70, align: 'right' as const },
+              {
+                title: t('invoices.amount'),
+                key: 'total',
+                align: 'right' as const,
+                render: (_: unknown, r: InvoiceImportRow) =>
+                  r.total != null ? `${r.total.toFixed(2)} ${r.currency ?? ''}` : '',
+              },
+              {
+                title: t('invoices.import.status'),
+                dataIndex: 'status',
+                width: 120,
+                render: (s: InvoiceImportStatus) => (
+                  <Tag color={STATUS_COLORS[s]}>{t(`invoices.import.statuses.${s}`)}</Tag>
+                ),
+              },
+              { title: t('invoices.import.message'), dataIndex: 'message' },
+            ]}
+          />
+          <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8, marginTop: 12 }}>
+            {report.preview ? (
+              <>
+                <Button onClick={() => setReport(null)}>{t('invoices.import.back')}</Button>
+                <Button
+                  type="primary"
+                  loading={importing}
+                  disabled={report.imported === 0 || !values}
+                  onClick={() => values && void run(values, false)}
+                >
+                  {t('invoices.import.run')}
+                </Button>
+              </>
+            ) : (
+              <Button type="primary" onClick={handleClose}>
+                {t('common.close')}
+              </Button>
+            )}
+          </div>
+        </>
+      ) : (
+        <>
+          <Button onClick={() => void pickFile()} style={{ marginBottom: 12 }}>
+            {path ? t('invoices.import.changeFile') : t('invoices.import.pickFile')}
+          </Button>
+          {path ? <div style={{ marginBottom: 12, wordBreak: 'break-all' }}>{path}</div> : null}
+          {headers.length > 0 ? (
+            <Form form={form} layout="vertical" initialValues={values ?? undefined}>
+              <Alert type="info" showIcon message={t('invoices.import.help')} style={{ marginBottom: 12 }} />
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr 1fr', gap: 12 }}>
+                {FIELDS.map((field) => (
+                  <Form.Item
+                    key={field}
+                    label={t(`invoices.import.fields.${field}`)}
+                    name={field}
+                    rules={REQUIRED.includes(field) ? [{ required: true, message: t('invoices.import.required') }] : []}
+                  >
+                    <Select allowClear options={headerOptions} placeholder={t('invoices.import.notMapped')} />
+                  </Form.Item>
+                ))}
+                <Form.Item label={t('invoices.import.defaultCurrency')} name="defaultCurrency" rules={[{ required: true }]}>
+                  <Select options={CURRENCY_VALUES.map((c) => ({ value: c, label: t(`currencies.${c}`) }))} />
+                </Form.Item>
+              </div>
+              <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8 }}>
+                <Button onClick={handleClose}>{t('common.cancel')}</Button>
+                <Button loading={importing} onClick={() => void form.validateFields().then((v) => run(v, true))}>
+                  {t('invoices.import.preview')}
+                </Button>
+                <Button
+                  type="primary"
+                  loading={importing}
+                  onClick={() => void form.validateFields().then((v) => run(v, false))}
+                >
+                  {t('invoices.import.run')}
+                </Button>
+              </div>
+            </Form>
+          ) : null}
+        </>
+      )}
+    </Modal>
+  );
+}
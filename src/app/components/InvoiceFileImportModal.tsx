@@ -0,0 +1,114 @@
+import { useState } from 'react';
+import { Alert, Button, Checkbox, Modal, Typography, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { errorMessage, toCommandError } from '../services/commandError';
+import type { InvoiceFileImportOptions } from '../types';
+
+const storage = getStorage();
+
+type Props = {
+  open: boolean;
+  onClose: () => void;
+  onImported: () => void;
+};
+
+/** Imports one invoice exported with "Export JSON", e.g. from another Paušaler installation. */
+export function InvoiceFileImportModal({ open: visible, onClose, onImported }: Props) {
+  const { t } = useTranslation();
+  const [path, setPath] = useState<string | null>(null);
+  const [keepNumber, setKeepNumber] = useState(true);
+  const [conflict, setConflict] = useState(false);
+  const [importing, setImporting] = useState(false);
+
+  const handleClose = () => {
+    setPath(null);
+    setConflict(false);
+    setKeepNumber(true);
+    onClose();
+  };
+
+  const pickFile = async () => {
+    const selected = await open({
+      title: t('invoices.fileImport.pickFile'),
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+      multiple: false,
+    });
+    const file = Array.isArray(selected) ? selected[0] : selected;
+    if (!file) return;
+    setPath(file);
+    setConflict(false);
+  };
+
+  const run = async (options: InvoiceFileImportOptions) => {
+    if (!path) return;
+    setImporting(true);
+    try {
+      const res = await storage.importInvoiceJson(path, options);
+      message.success(
+        t(res.clientCreated ? 'invoices.fileImport.doneWithClient' : 'invoices.fileImport.done', {
+          number: res.invoice.invoiceNumber,
+        })
+      );
+      onImported();
+      handleClose();
+    } catch (e) {
+      // A taken number is reported back; renumbering is the user's call.
+      if (toCommandError(e).code === 'CONFLICT') {
+        setConflict(true);
+      } else {
+        message.error(t('invoices.fileImport.failed', { message: errorMessage(e) }));
+      }
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  return (
+    <Modal title={t('invoices.fileImport.title')} open={visible} onCancel={handleClose} footer={null}>
+      <Typography.Paragraph type="secondary">{t('invoices.fileImport.help')}</Typography.Paragraph>
+      <div style={{ display: 'flex', alignItems: 'center', gap: 8, marginBottom: 12 }}>
+        <Button onClick={() => void pickFile()}>
+          {path ? t('invoices.fileImport.changeFile') : t('invoices.fileImport.pickFile')}
+        </Button>
+        {path ? <Typography.Text ellipsis>{path}</Typography.Text> : null}
+      </div>
+      <Checkbox
+        checked={keepNumber}
+        onChange={(e) => {
+          setKeepNumber(e.target.checked);
+          setConflict(false);
+        }}
+      >
+        {t('invoices.fileImport.keepNumber')}
+      </Checkbox>
+      {conflict ? (
+        <Alert
+          type="warning"
+          showIcon
+          style={{ marginTop: 12 }}
+          message={t('invoices.fileImport.conflict')}
+          description={t('invoices.fileImport.conflictHelp')}
+        />
+      ) : null}
+      <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8, marginTop: 16 }}>
+        <Button onClick={handleClose}>{t('common.cancel')}</Button>
+        {conflict ? (
+          <Button
+            type="primary"
+            loading={importing}
+            onClick={() => void run({ keepNumber: true, renumberOnConflict: true })}
+          >
+            {t('invoices.fileImport.renumber')}
+          </Button>
+        ) : (
+          <Button type="primary" loading={importing} disabled={!path} onClick={() => void run({ keepNumber })}>
+            {t('invoices.fileImport.run')}
+          </Button>
+        )}
+      </div>
+    </Modal>
+  );
+}
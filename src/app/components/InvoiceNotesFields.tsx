@@ -0,0 +1,55 @@
+import { useState } from 'react';
+import { Button, Form, Input, Typography, message } from 'antd';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+
+const storage = getStorage();
+
+const LANGS = ['sr', 'en'] as const;
+type Lang = (typeof LANGS)[number];
+
+/** Default invoice notes and the per-language legal note override (fields of the surrounding settings form). */
+export function InvoiceNotesFields({ sampleNumber }: { sampleNumber: string }) {
+  const { t } = useTranslation();
+  const form = Form.useFormInstance();
+  const [previews, setPreviews] = useState<Partial<Record<Lang, string>>>({});
+
+  const preview = async (lang: Lang) => {
+    const text = String(form.getFieldValue(['legalNoteOverride', lang]) ?? '');
+    try {
+      const rendered = await storage.previewLegalNote(lang, sampleNumber, text);
+      setPreviews((p) => ({ ...p, [lang]: rendered }));
+    } catch (e: any) {
+      message.error(e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+    }
+  };
+
+  return (
+    <div>
+      <Form.Item label={t('settings.notes.defaultNotes')} name="defaultInvoiceNotes" extra={t('settings.notes.defaultNotesHelp')}>
+        <Input.TextArea rows={3} />
+      </Form.Item>
+
+      <Typography.Title level={5}>{t('settings.notes.legalTitle')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.notes.legalHelp')}</Typography.Paragraph>
+      <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+        {LANGS.map((lang) => (
+          <div key={lang}>
+            <Form.Item label={t(`settings.notes.legal.${lang}`)} name={['legalNoteOverride', lang]}>
+              <Input.TextArea rows={4} placeholder={t('settings.notes.legalPlaceholder')} />
+            </Form.Item>
+            <Button size="small" onClick={() => void preview(lang)}>
+              {t('settings.notes.preview')}
+            </Button>
+            {previews[lang] !== undefined ? (
+              <div style={{ padding: 12, background: '#f5f5f5', borderRadius: 8, marginTop: 8, whiteSpace: 'pre-wrap' }}>
+                {previews[lang]}
+              </div>
+            ) : null}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
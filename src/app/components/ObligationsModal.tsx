@@ -0,0 +1,261 @@
+import { useEffect, useState } from 'react';
+import { Button, DatePicker, Divider, Form, Input, InputNumber, Modal, Select, Space, Table, Tag, message } from 'antd';
+import { DeleteOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { Obligation, ObligationMonthStatus } from '../types';
+
+const storage = getStorage();
+
+type DecisionFormValues = {
+  validFrom: dayjs.Dayjs;
+  validTo?: dayjs.Dayjs | null;
+  tax?: number;
+  pension?: number;
+  health?: number;
+  unemployment?: number;
+  notes?: string;
+};
+
+type Props = {
+  open: boolean;
+  canWrite: boolean;
+  onClose: () => void;
+  /** Called after expenses were generated. */
+  onChanged: () => void;
+};
+
+const money = (v: number) => `${v.toFixed(2)} RSD`;
+
+export function ObligationsModal({ open, canWrite, onClose, onChanged }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<DecisionFormValues>();
+  const [year, setYear] = useState(dayjs().year());
+  const [decisions, setDecisions] = useState<Obligation[]>([]);
+  const [months, setMonths] = useState<ObligationMonthStatus[]>([]);
+  const [adding, setAdding] = useState(false);
+  const [busyMonth, setBusyMonth] = useState<string | null>(null);
+
+  const refresh = async () => {
+    const [d, m] = await Promise.all([storage.listObligations(), storage.obligationsStatus(year)]);
+    setDecisions(d);
+    setMonths(m);
+  };
+
+  useEffect(() => {
+    if (open) void refresh();
+  }, [open, year]);
+
+  const run = async (month: string, action: () => Promise<void>) => {
+    setBusyMonth(month);
+    try {
+      await action();
+      await refresh();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setBusyMonth(null);
+    }
+  };
+
+  const handleGenerate = (month: string) =>
+    run(month, async () => {
+      const res = await storage.generateMonthlyObligationExpenses(month);
+      if (res.expensesCreated > 0) {
+        message.success(t('expenses.obligations.generated', { count: res.expensesCreated }));
+        onChanged();
+      }
+    });
+
+  const handlePaid = (month: string, paid: boolean) =>
+    run(month, async () => {
+      await storage.setObligationMonthPaid(month, paid);
+    });
+
+  const handleCreate = async (values: DecisionFormValues) => {
+    try {
+      await storage.createObligation({
+        validFrom: values.validFrom.format('YYYY-MM'),
+        validTo: values.validTo ? values.validTo.format('YYYY-MM') : null,
+        tax: values.tax ?? 0,
+        pension: values.pension ?? 0,
+        health: values.health ?? 0,
+        unemployment: values.unemployment ?? 0,
+        notes: values.notes?.trim() || null,
+      });
+      message.success(t('expenses.obligations.created'));
+      setAdding(false);
+      await refresh();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const handleDelete = (decision: Obligation) => {
+    Modal.confirm({
+      title: t('expenses.obligations.deleteTitle'),
+      content: t('expenses.obligations.deleteDesc'),
+      okText: t('common.delete'),
+      okButtonProps: { danger: true },
+      cancelText: t('common.cancel'),
+      onOk: async () => {
+        await storage.deleteObligation(decision.id);
+        await refresh();
+      },
+    });
+  };
+
+  const monthLabel = (v: string) => dayjs(`${v}-01`).format('MM/YYYY');
+  const currentYear = dayjs().year();
+
+  return (
+    <Modal title={t('expenses.obligations.title')} open={open} onCancel={onClose} footer={null} width={860}>
+      <Space style={{ marginBottom: 12 }}>
+        <span>{t('expenses.obligations.year')}</span>
+        <Select
+          value={year}
+          style={{ width: 110 }}
+          onChange={setYear}
+          options={Array.from({ length: 6 }, (_, i) => ({ value: currentYear + 1 - i, label: String(currentYear + 1 - i) }))}
+        />
+      </Space>
+      <Table
+        size="small"
+        rowKey="month"
+        dataSource={months}
+        pagination={false}
+        columns={[
+          { title: t('expenses.obligations.month'), dataIndex: 'month', render: monthLabel },
+          {
+            title: t('expenses.amount'),
+            key: 'total',
+            align: 'right' as const,
+            render: (_: unknown, r: ObligationMonthStatus) => (r.obligationId ? money(r.total) : '—'),
+          },
+          {
+            title: t('expenses.obligations.status'),
+            key: 'status',
+            render: (_: unknown, r: ObligationMonthStatus) =>
+              r.paidAt ? (
+                <Tag color="green">{t('expenses.obligations.paid')}</Tag>
+              ) : r.generatedAt ? (
+                <Tag color="blue">{t('expenses.obligations.generatedTag')}</Tag>
+              ) : (
+                <Tag>{r.obligationId ? t('expenses.obligations.pending') : t('expenses.obligations.noDecision')}</Tag>
+              ),
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 200,
+            render: (_: unknown, r: ObligationMonthStatus) =>
+              !r.generatedAt ? (
+                <Button
+                  type="link"
+                  disabled={!canWrite || !r.obligationId}
+                  loading={busyMonth === r.month}
+                  onClick={() => void handleGenerate(r.month)}
+                >
+                  {t('expenses.obligations.generate')}
+                </Button>
+              ) : (
+                <Button
+                  type="link"
+                  disabled={!canWrite}
+                  loading={busyMonth === r.month}
+                  onClick={() => void handlePaid(r.month, !r.paidAt)}
+                >
+                  {r.paidAt ? t('expenses.obligations.markUnpaid') : t('expenses.obligations.markPaid')}
+                </Button>
+              ),
+          },
+        ]}
+      />
+
+      <Divider orientation="left">{t('expenses.obligations.decisions')}</Divider>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={decisions}
+        pagination={false}
+        locale={{ emptyText: t('expenses.obligations.empty') }}
+        columns={[
+          {
+            title: t('expenses.obligations.validFrom'),
+            key: 'validity',
+            render: (_: unknown, r: Obligation) =>
+              `${monthLabel(r.validFrom)} – ${r.validTo ? monthLabel(r.validTo) : ''}`,
+          },
+          { title: t('expenses.obligations.tax'), dataIndex: 'tax', align: 'right' as const, render: money },
+          { title: t('expenses.obligations.pension'), dataIndex: 'pension', align: 'right' as const, render: money },
+          { title: t('expenses.obligations.health'), dataIndex: 'health', align: 'right' as const, render: money },
+          {
+            title: t('expenses.obligations.unemployment'),
+            dataIndex: 'unemployment',
+            align: 'right' as const,
+            render: money,
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 110,
+            render: (_: unknown, r: Obligation) => (
+              <Button type="link" danger icon={<DeleteOutlined />} disabled={!canWrite} onClick={() => handleDelete(r)}>
+                {t('common.delete')}
+              </Button>
+            ),
+          },
+        ]}
+      />
+
+      {adding ? (
+        <Form form={form} layout="vertical" onFinish={(v) => void handleCreate(v)} style={{ marginTop: 16 }}>
+          <Space style={{ width: '100%' }} size={12} align="start">
+            <Form.Item
+              label={t('expenses.obligations.validFrom')}
+              name="validFrom"
+              rules={[{ required: true, message: t('expenses.dateReq') }]}
+            >
+              <DatePicker picker="month" format="MM/YYYY" />
+            </Form.Item>
+            <Form.Item label={t('expenses.obligations.validTo')} name="validTo">
+              <DatePicker picker="month" format="MM/YYYY" allowClear />
+            </Form.Item>
+          </Space>
+          <Space style={{ width: '100%' }} size={12} align="start" wrap>
+            {(['tax', 'pension', 'health', 'unemployment'] as const).map((field) => (
+              <Form.Item key={field} label={t(`expenses.obligations.${field}`)} name={field}>
+                <InputNumber min={0} step={0.01} style={{ width: 180 }} addonAfter="RSD" />
+              </Form.Item>
+            ))}
+          </Space>
+          <Form.Item label={t('expenses.notes')} name="notes">
+            <Input.TextArea rows={2} placeholder={t('expenses.obligations.notesPlaceholder')} />
+          </Form.Item>
+          <Space style={{ width: '100%', justifyContent: 'flex-end' }}>
+            <Button onClick={() => setAdding(false)}>{t('common.cancel')}</Button>
+            <Button type="primary" htmlType="submit" disabled={!canWrite}>
+              {t('expenses.obligations.add')}
+            </Button>
+          </Space>
+        </Form>
+      ) : (
+        <div style={{ display: 'flex', justifyContent: 'flex-end', marginTop: 16 }}>
+          <Button
+            type="primary"
+            onClick={() => {
+              form.resetFields();
+              form.setFieldsValue({ validFrom: dayjs().startOf('year') });
+              setAdding(true);
+            }}
+            disabled={!canWrite}
+          >
+            {t('expenses.obligations.add')}
+          </Button>
+        </div>
+      )}
+    </Modal>
+  );
+}
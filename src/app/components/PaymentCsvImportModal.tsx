@@ -0,0 +1,277 @@
+import { useState } from 'react';
+import { Alert, Button, Divider, Form, Modal, Select, Space, Table, Tag, message } from 'antd';
+import { open } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { AmbiguousPayment, MatchedPayment, PaymentCsvMapping, PaymentImportReport, PaymentRow } from '../types';
+
+const storage = getStorage();
+
+type ColumnField = keyof PaymentCsvMapping;
+
+const FIELDS: ColumnField[] = ['date', 'amount', 'reference', 'payer', 'currency'];
+const REQUIRED: ColumnField[] = ['date', 'amount'];
+
+/** Header names commonly used by Serbian banks for each field, for pre-filling the mapping. */
+const HEADER_GUESSES: Record<ColumnField, string[]> = {
+  date: ['date', 'datum', 'datum valute', 'datum knjiženja', 'datum knjizenja'],
+  amount: ['credit', 'odobrenje', 'potražuje', 'potrazuje', 'uplata', 'amount', 'iznos'],
+  reference: ['reference', 'poziv na broj', 'poziv na broj odobrenja', 'poziv'],
+  payer: ['payer', 'nalogodavac', 'uplatilac', 'naziv'],
+  currency: ['currency', 'valuta'],
+};
+
+function guessMapping(headers: string[]): Partial<PaymentCsvMapping> {
+  const out: Partial<PaymentCsvMapping> = {};
+  for (const field of FIELDS) {
+    const hit = headers.find((h) => HEADER_GUESSES[field].includes(h.trim().toLowerCase()));
+    if (hit) out[field] = hit;
+  }
+  return out;
+}
+
+const rowKey = (r: PaymentRow) => String(r.line);
+
+type Props = {
+  open: boolean;
+  onClose: () => void;
+  /** Called after payments were recorded. */
+  onImported: () => void;
+};
+
+export function PaymentCsvImportModal({ open: visible, onClose, onImported }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<PaymentCsvMapping>();
+  const [path, setPath] = useState<string | null>(null);
+  const [headers, setHeaders] = useState<string[]>([]);
+  const [report, setReport] = useState<PaymentImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [choices, setChoices] = useState<Record<number, string>>({});
+  const [resolving, setResolving] = useState<number | null>(null);
+
+  const reset = () => {
+    setPath(null);
+    setHeaders([]);
+    setReport(null);
+    setChoices({});
+    form.resetFields();
+  };
+
+  const handleClose = () => {
+    reset();
+    onClose();
+  };
+
+  const pickFile = async () => {
+    const selected = await open({
+      title: t('invoices.payments.pickFile'),
+      filters: [{ name: 'CSV', extensions: ['csv', 'txt'] }],
+      multiple: false,
+    });
+    const file = Array.isArray(selected) ? selected[0] : selected;
+    if (!file) return;
+    try {
+      const next = await storage.readCsvHeaders(file);
+      setPath(file);
+      setHeaders(next);
+      setReport(null);
+      form.setFieldsValue(guessMapping(next));
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const run = async (mapping: PaymentCsvMapping) => {
+    if (!path) return;
+    setImporting(true);
+    try {
+      const res = await storage.importPaymentsCsv(path, mapping);
+      setReport(res);
+      if (res.matched.length > 0) onImported();
+    } catch (e: any) {
+      message.error(t('invoices.payments.failed', { message: String(e?.message ?? e) }));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const resolve = async (row: AmbiguousPayment) => {
+    const invoiceId = choices[row.line];
+    if (!invoiceId || !report) return;
+    setResolving(row.line);
+    try {
+      const { line, date, amount, currency, reference, payer } = row;
+      const matched = await storage.resolvePaymentMatch({ line, date, amount, currency, reference, payer }, invoiceId);
+      setReport({
+        ...report,
+        matched: [...report.matched, matched],
+        ambiguous: report.ambiguous.filter((r) => r.line !== row.line),
+      });
+      onImported();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setResolving(null);
+    }
+  };
+
+  const headerOptions = headers.map((h) => ({ value: h, label: h }));
+  const dateCol = {
+    title: t('invoices.payments.date'),
+    dataIndex: 'date',
+    width: 110,
+    render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+  };
+  const amountCol = {
+    title: t('invoices.amount'),
+    key: 'amount',
+    align: 'right' as const,
+    render: (_: unknown, r: PaymentRow) => `${r.amount.toFixed(2)} ${r.currency}`,
+  };
+  const payerCol = { title: t('invoices.payments.fields.payer'), dataIndex: 'payer' };
+  const referenceCol = { title: t('invoices.payments.fields.reference'), dataIndex: 'reference' };
+
+  return (
+    <Modal title={t('invoices.payments.title')} open={visible} onCancel={handleClose} footer={null} width={920}>
+      {report ? (
+        <>
+          <Alert
+            type={report.ambiguous.length + report.unmatched.length > 0 ? 'warning' : 'success'}
+            showIcon
+            message={t('invoices.payments.summary', {
+              matched: report.matched.length,
+              ambiguous: report.ambiguous.length,
+              unmatched: report.unmatched.length,
+              duplicates: report.duplicates.length,
+              skipped: report.skipped.length,
+            })}
+            style={{ marginBottom: 12 }}
+          />
+          {report.matched.length > 0 ? (
+            <>
+              <Divider orientation="left">{t('invoices.payments.matched')}</Divider>
+              <Table
+                size="small"
+                rowKey="paymentId"
+                dataSource={report.matched}
+                pagination={{ pageSize: 5 }}
+                columns={[
+                  dateCol,
+                  payerCol,
+                  amountCol,
+                  { title: t('invoices.number'), dataIndex: 'invoiceNumber' },
+                  {
+                    title: t('invoices.status'),
+                    key: 'status',
+                    render: (_: unknown, r: MatchedPayment) => (
+                      <Space size={4}>
+                        <Tag>{t(`invoices.payments.matchedBy.${r.matchedBy}`)}</Tag>
+                        {r.invoicePaid ? <Tag color="green">{t('invoiceStatus.PAID')}</Tag> : null}
+                      </Space>
+                    ),
+                  },
+                ]}
+              />
+            </>
+          ) : null}
+          {report.ambiguous.length > 0 ? (
+            <>
+              <Divider orientation="left">{t('invoices.payments.ambiguous')}</Divider>
+              <Table
+                size="small"
+                rowKey={rowKey}
+                dataSource={report.ambiguous}
+                pagination={false}
+                columns={[
+                  dateCol,
+                  payerCol,
+                  referenceCol,
+                  amountCol,
+                  {
+                    title: t('invoices.payments.invoice'),
+                    key: 'invoice',
+                    width: 300,
+                    render: (_: unknown, r: AmbiguousPayment) => (
+                      <Space direction="vertical" size={4} style={{ width: '100%' }}>
+                        <span style={{ fontSize: 12, opacity: 0.75 }}>{r.reason}</span>
+                        <Space.Compact style={{ width: '100%' }}>
+                          <Select
+                            style={{ width: '100%' }}
+                            placeholder={t('invoices.payments.pickInvoice')}
+                            value={choices[r.line]}
+                            onChange={(v) => setChoices({ ...choices, [r.line]: v })}
+                            options={r.candidates.map((c) => ({
+                              value: c.invoiceId,
+                              label: `${c.invoiceNumber} · ${c.clientName} · ${c.outstanding.toFixed(2)} ${c.currency}`,
+                            }))}
+                          />
+                          <Button
+                            type="primary"
+                            disabled={!choices[r.line]}
+                            loading={resolving === r.line}
+                            onClick={() => void resolve(r)}
+                          >
+                            {t('invoices.payments.apply')}
+                          </Button>
+                        </Space.Compact>
+                      </Space>
+                    ),
+                  },
+                ]}
+              />
+            </>
+          ) : null}
+          {report.unmatched.length > 0 ? (
+            <>
+              <Divider orientation="left">{t('invoices.payments.unmatched')}</Divider>
+              <Table
+                size="small"
+                rowKey={rowKey}
+                dataSource={report.unmatched}
+                pagination={{ pageSize: 5 }}
+                columns={[dateCol, payerCol, referenceCol, amountCol]}
+              />
+            </>
+          ) : null}
+          <div style={{ display: 'flex', justifyContent: 'flex-end', marginTop: 12 }}>
+            <Button type="primary" onClick={handleClose}>
+              {t('common.close')}
+            </Button>
+          </div>
+        </>
+      ) : (
+        <>
+          <Alert type="info" showIcon message={t('invoices.payments.help')} style={{ marginBottom: 12 }} />
+          <Button onClick={() => void pickFile()} style={{ marginBottom: 12 }}>
+            {path ? t('invoices.payments.changeFile') : t('invoices.payments.pickFile')}
+          </Button>
+          {path ? <div style={{ marginBottom: 12, wordBreak: 'break-all' }}>{path}</div> : null}
+          {headers.length > 0 ? (
+            <Form form={form} layout="vertical">
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 12 }}>
+                {FIELDS.map((field) => (
+                  <Form.Item
+                    key={field}
+                    label={t(`invoices.payments.fields.${field}`)}
+                    name={field}
+                    rules={REQUIRED.includes(field) ? [{ required: true, message: t('invoices.payments.required') }] : []}
+                  >
+                    <Select allowClear options={headerOptions} placeholder={t('invoices.payments.notMapped')} />
+                  </Form.Item>
+                ))}
+              </div>
+              <div style={{ display: 'flex', justifyContent: 'flex-end', gap: 8 }}>
+                <Button onClick={handleClose}>{t('common.cancel')}</Button>
+                <Button type="primary" loading={importing} onClick={() => void form.validateFields().then(run)}>
+                  {t('invoices.payments.run')}
+                </Button>
+              </div>
+            </Form>
+          ) : null}
+        </>
+      )}
+    </Modal>
+  );
+}
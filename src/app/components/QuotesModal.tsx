@@ -0,0 +1,343 @@
+import { useEffect, useState } from 'react';
+import { Button, DatePicker, Form, Input, InputNumber, Modal, Popconfirm, Select, Space, Table, Tag, message } from 'antd';
+import {
+  CloseCircleOutlined,
+  DeleteOutlined,
+  EditOutlined,
+  FileDoneOutlined,
+  FilePdfOutlined,
+  MailOutlined,
+  MinusCircleOutlined,
+  PlusOutlined,
+} from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useNavigate } from 'react-router-dom';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { useClients } from '../hooks/useClients';
+import { featureLockedMessage } from '../services/featureGate';
+import { CURRENCY_VALUES, INVOICE_UNIT_VALUES, invoiceUnitLabel } from '../types';
+import type { InvoiceUnit, Quote, QuoteInput, QuoteStatus } from '../types';
+
+const storage = getStorage();
+
+const STATUS_COLORS: Record<QuoteStatus, string> = {
+  DRAFT: 'gold',
+  SENT: 'blue',
+  ACCEPTED: 'green',
+  REJECTED: 'red',
+  EXPIRED: 'default',
+};
+
+type ItemValues = { description: string; unit: InvoiceUnit; quantity: number; unitPrice: number };
+
+type QuoteFormValues = {
+  clientId: string;
+  issueDate: dayjs.Dayjs;
+  validUntil: dayjs.Dayjs;
+  currency?: string;
+  items: ItemValues[];
+  notes?: string;
+};
+
+type EmailFormValues = { to?: string; subject: string; body: string };
+
+type Props = {
+  open: boolean;
+  canWrite: boolean;
+  canSend: boolean;
+  onClose: () => void;
+};
+
+export function QuotesModal({ open, canWrite, canSend, onClose }: Props) {
+  const { t } = useTranslation();
+  const navigate = useNavigate();
+  const { clients } = useClients();
+  const [form] = Form.useForm<QuoteFormValues>();
+  const [emailForm] = Form.useForm<EmailFormValues>();
+  const [quotes, setQuotes] = useState<Quote[]>([]);
+  const [editing, setEditing] = useState<Quote | 'new' | null>(null);
+  const [emailing, setEmailing] = useState<Quote | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  const refresh = async () => setQuotes(await storage.listQuotes());
+
+  useEffect(() => {
+    if (open) void refresh();
+  }, [open]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    try {
+      await action();
+      await refresh();
+    } catch (e: any) {
+      message.error(featureLockedMessage(e, t) ?? String(e?.message ?? e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const startEditing = (quote: Quote | 'new') => {
+    form.resetFields();
+    form.setFieldsValue(
+      quote === 'new'
+        ? {
+            issueDate: dayjs(),
+            validUntil: dayjs().add(15, 'day'),
+            items: [{ description: '', unit: 'usluga', quantity: 1, unitPrice: 0 }],
+          }
+        : {
+            clientId: quote.clientId,
+            issueDate: dayjs(quote.issueDate),
+            validUntil: dayjs(quote.validUntil),
+            currency: quote.currency,
+            items: quote.items.map((it) => ({
+              description: it.description,
+              unit: it.unit,
+              quantity: it.quantity,
+              unitPrice: it.unitPrice,
+            })),
+            notes: quote.notes,
+          }
+    );
+    setEditing(quote);
+  };
+
+  const handleSave = (values: QuoteFormValues) =>
+    run(async () => {
+      const input: QuoteInput = {
+        clientId: values.clientId,
+        issueDate: values.issueDate.format('YYYY-MM-DD'),
+        validUntil: values.validUntil.format('YYYY-MM-DD'),
+        currency: values.currency ?? '',
+        items: values.items.map((it) => ({
+          id: crypto.randomUUID(),
+          description: it.description.trim(),
+          unit: it.unit,
+          quantity: it.quantity,
+          unitPrice: it.unitPrice,
+          total: it.quantity * it.unitPrice,
+        })),
+        notes: values.notes?.trim() ?? '',
+      };
+      if (editing === 'new') {
+        const created = await storage.createQuote(input);
+        message.success(t('quotes.created', { number: created.quoteNumber }));
+      } else if (editing) {
+        await storage.updateQuote(editing.id, input);
+        message.success(t('quotes.saved'));
+      }
+      setEditing(null);
+    });
+
+  const handleExport = (quote: Quote) =>
+    run(async () => {
+      const file = await storage.exportQuotePdf(quote.id, true);
+      message.success(t('quotes.exported', { path: file.path }));
+    });
+
+  const startEmail = (quote: Quote) => {
+    emailForm.resetFields();
+    emailForm.setFieldsValue({
+      subject: t('quotes.emailSubject', { number: quote.quoteNumber }),
+      body: t('quotes.emailBody', {
+        client: quote.clientName,
+        number: quote.quoteNumber,
+        validUntil: dayjs(quote.validUntil).format('DD.MM.YYYY'),
+      }),
+    });
+    setEmailing(quote);
+  };
+
+  const handleSend = (values: EmailFormValues) =>
+    run(async () => {
+      if (!emailing) return;
+      await storage.sendQuoteEmail({
+        quoteId: emailing.id,
+        to: (values.to ?? '').split(/[,;]/).map((s) => s.trim()).filter(Boolean),
+        subject: values.subject,
+        body: values.body,
+      });
+      message.success(t('quotes.sent'));
+      setEmailing(null);
+    });
+
+  const handleConvert = (quote: Quote) =>
+    run(async () => {
+      const invoice = await storage.convertQuoteToInvoice(quote.id);
+      if (!invoice) return;
+      message.success(t('quotes.converted', { number: invoice.invoiceNumber }));
+      onClose();
+      navigate(`/invoices/view/${invoice.id}`);
+    });
+
+  const locked = (q: Quote) => !canWrite || !!q.invoiceId;
+
+  const editor = editing ? (
+    <Form form={form} layout="vertical" onFinish={(v) => void handleSave(v)} style={{ marginTop: 16 }}>
+      <Space style={{ width: '100%' }} size={12} align="start" wrap>
+        <Form.Item label={t('quotes.client')} name="clientId" rules={[{ required: true, message: t('quotes.clientReq') }]}>
+          <Select
+            showSearch
+            optionFilterProp="label"
+            options={clients.filter((c) => !c.archived).map((c) => ({ value: c.id, label: c.name }))}
+            style={{ width: 240 }}
+          />
+        </Form.Item>
+        <Form.Item label={t('quotes.issueDate')} name="issueDate" rules={[{ required: true }]}>
+          <DatePicker format="DD.MM.YYYY" />
+        </Form.Item>
+        <Form.Item label={t('quotes.validUntil')} name="validUntil" rules={[{ required: true }]}>
+          <DatePicker format="DD.MM.YYYY" />
+        </Form.Item>
+        <Form.Item label={t('quotes.currency')} name="currency">
+          <Select allowClear style={{ width: 110 }} options={CURRENCY_VALUES.map((c) => ({ value: c, label: c }))} />
+        </Form.Item>
+      </Space>
+      <Form.List name="items">
+        {(fields, { add, remove }) => (
+          <>
+            {fields.map((field) => (
+              <Space key={field.key} align="start" style={{ display: 'flex' }}>
+                <Form.Item name={[field.name, 'description']} rules={[{ required: true, message: t('quotes.descriptionReq') }]}>
+                  <Input placeholder={t('newInvoice.description')} style={{ width: 300 }} />
+                </Form.Item>
+                <Form.Item name={[field.name, 'unit']}>
+                  <Select style={{ width: 100 }} options={INVOICE_UNIT_VALUES.map((u) => ({ value: u, label: invoiceUnitLabel(u) }))} />
+                </Form.Item>
+                <Form.Item name={[field.name, 'quantity']} rules={[{ required: true }]}>
+                  <InputNumber min={0} step={1} placeholder={t('newInvoice.quantity')} style={{ width: 90 }} />
+                </Form.Item>
+                <Form.Item name={[field.name, 'unitPrice']} rules={[{ required: true }]}>
+                  <InputNumber min={0} step={0.01} placeholder={t('newInvoice.unitPrice')} style={{ width: 130 }} />
+                </Form.Item>
+                <Button type="text" icon={<MinusCircleOutlined />} disabled={fields.length < 2} onClick={() => remove(field.name)} />
+              </Space>
+            ))}
+            <Button
+              type="dashed"
+              icon={<PlusOutlined />}
+              onClick={() => add({ description: '', unit: 'usluga', quantity: 1, unitPrice: 0 })}
+              style={{ marginBottom: 16 }}
+            >
+              {t('newInvoice.addItem')}
+            </Button>
+          </>
+        )}
+      </Form.List>
+      <Form.Item label={t('quotes.notes')} name="notes">
+        <Input.TextArea rows={2} />
+      </Form.Item>
+      <Space>
+        <Button onClick={() => setEditing(null)}>{t('common.cancel')}</Button>
+        <Button type="primary" htmlType="submit" loading={busy}>
+          {t('common.save')}
+        </Button>
+      </Space>
+    </Form>
+  ) : (
+    <Button icon={<PlusOutlined />} disabled={!canWrite} onClick={() => startEditing('new')} style={{ marginTop: 12 }}>
+      {t('quotes.add')}
+    </Button>
+  );
+
+  return (
+    <Modal title={t('quotes.title')} open={open} onCancel={onClose} footer={null} width={1000}>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={quotes}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('quotes.empty') }}
+        columns={[
+          { title: t('quotes.number'), dataIndex: 'quoteNumber', width: 110 },
+          { title: t('quotes.client'), dataIndex: 'clientName' },
+          {
+            title: t('quotes.validUntil'),
+            dataIndex: 'validUntil',
+            width: 110,
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+          },
+          {
+            title: t('quotes.total'),
+            key: 'total',
+            align: 'right' as const,
+            render: (_: unknown, r: Quote) => `${r.total.toFixed(2)} ${r.currency}`,
+          },
+          {
+            title: t('invoices.status'),
+            dataIndex: 'status',
+            width: 110,
+            render: (v: QuoteStatus) => <Tag color={STATUS_COLORS[v]}>{t(`quoteStatus.${v}`)}</Tag>,
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 230,
+            render: (_: unknown, r: Quote) => (
+              <Space size={0}>
+                <Button type="link" icon={<EditOutlined />} disabled={locked(r)} onClick={() => startEditing(r)} />
+                <Button type="link" icon={<FilePdfOutlined />} title={t('quotes.pdf')} onClick={() => void handleExport(r)} />
+                <Button type="link" icon={<MailOutlined />} title={t('quotes.send')} disabled={!canSend} onClick={() => startEmail(r)} />
+                <Popconfirm
+                  title={t('quotes.convertConfirm', { number: r.quoteNumber })}
+                  disabled={locked(r) || r.status === 'REJECTED' || r.status === 'EXPIRED'}
+                  onConfirm={() => void handleConvert(r)}
+                  okText={t('common.yes')}
+                  cancelText={t('common.no')}
+                >
+                  <Button
+                    type="link"
+                    icon={<FileDoneOutlined />}
+                    title={t('quotes.convert')}
+                    disabled={locked(r) || r.status === 'REJECTED' || r.status === 'EXPIRED'}
+                  />
+                </Popconfirm>
+                <Button
+                  type="link"
+                  icon={<CloseCircleOutlined />}
+                  title={t('quotes.reject')}
+                  disabled={locked(r) || r.status === 'REJECTED'}
+                  onClick={() => void run(() => storage.updateQuote(r.id, { status: 'REJECTED' }))}
+                />
+                <Popconfirm
+                  title={t('quotes.deleteConfirm', { number: r.quoteNumber })}
+                  disabled={locked(r)}
+                  onConfirm={() => void run(() => storage.deleteQuote(r.id))}
+                  okText={t('common.yes')}
+                  cancelText={t('common.no')}
+                >
+                  <Button type="link" danger icon={<DeleteOutlined />} disabled={locked(r)} />
+                </Popconfirm>
+              </Space>
+            ),
+          },
+        ]}
+      />
+      {editor}
+
+      <Modal
+        title={t('quotes.sendTitle', { number: emailing?.quoteNumber ?? '' })}
+        open={!!emailing}
+        onCancel={() => setEmailing(null)}
+        onOk={() => emailForm.submit()}
+        okText={t('quotes.send')}
+        confirmLoading={busy}
+      >
+        <Form form={emailForm} layout="vertical" onFinish={(v) => void handleSend(v)}>
+          <Form.Item label={t('quotes.to')} name="to" extra={t('quotes.toHelp')}>
+            <Input />
+          </Form.Item>
+          <Form.Item label={t('quotes.subject')} name="subject" rules={[{ required: true }]}>
+            <Input />
+          </Form.Item>
+          <Form.Item label={t('quotes.body')} name="body">
+            <Input.TextArea rows={6} />
+          </Form.Item>
+        </Form>
+      </Modal>
+    </Modal>
+  );
+}
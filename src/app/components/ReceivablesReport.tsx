@@ -0,0 +1,127 @@
+import { useEffect, useState } from 'react';
+import { Button, Card, Space, Table, Typography, message } from 'antd';
+import { DownloadOutlined, ReloadOutlined } from '@ant-design/icons';
+import { save } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { AgingBuckets, ReceivablesClientRow, ReceivablesReport as Report } from '../types';
+
+const storage = getStorage();
+
+const BUCKETS: (keyof Omit<AgingBuckets, 'total' | 'invoiceCount'>)[] = [
+  'current',
+  'days1To30',
+  'days31To60',
+  'days61To90',
+  'daysOver90',
+];
+
+const money = (v: number) => (v === 0 ? '' : v.toFixed(2));
+
+/** Unpaid SENT invoices per client and currency, bucketed by days past due. */
+export function ReceivablesReport() {
+  const { t } = useTranslation();
+  const [report, setReport] = useState<Report | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const load = async () => {
+    setLoading(true);
+    try {
+      setReport(await storage.accountsReceivableReport());
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    void load();
+  }, []);
+
+  const exportCsv = async () => {
+    const path = await save({
+      defaultPath: `receivables_${dayjs().format('YYYY-MM-DD')}.csv`,
+      filters: [{ name: 'CSV', extensions: ['csv'] }],
+    });
+    if (!path) return;
+    try {
+      await storage.exportAccountsReceivableCsv(path);
+      message.success(t('exports.success', { files: path }));
+    } catch (e: any) {
+      message.error(t('exports.errors.failed', { message: String(e?.message ?? e) }));
+    }
+  };
+
+  const bucketColumns = BUCKETS.map((b) => ({
+    title: t(`reports.receivables.buckets.${b}`),
+    dataIndex: b,
+    key: b,
+    align: 'right' as const,
+    render: money,
+  }));
+
+  return (
+    <Card
+      title={t('reports.receivables.title')}
+      extra={
+        <Space>
+          <Button icon={<ReloadOutlined />} onClick={() => void load()} loading={loading} />
+          <Button icon={<DownloadOutlined />} onClick={() => void exportCsv()} disabled={!report?.clients.length}>
+            {t('reports.receivables.exportCsv')}
+          </Button>
+        </Space>
+      }
+    >
+      {report ? (
+        <Typography.Paragraph type="secondary">
+          {t('reports.receivables.asOf', { date: dayjs(report.asOf).format('DD.MM.YYYY') })}
+        </Typography.Paragraph>
+      ) : null}
+      <Table<ReceivablesClientRow>
+        size="small"
+        loading={loading}
+        rowKey={(r) => `${r.clientId}:${r.currency}`}
+        dataSource={report?.clients ?? []}
+        pagination={false}
+        locale={{ emptyText: t('reports.receivables.empty') }}
+        columns={[
+          { title: t('reports.receivables.client'), dataIndex: 'clientName', key: 'clientName' },
+          { title: t('invoices.currency'), dataIndex: 'currency', key: 'currency', width: 80 },
+          { title: t('reports.receivables.invoices'), dataIndex: 'invoiceCount', key: 'invoiceCount', width: 90, align: 'right' },
+          ...bucketColumns,
+          {
+            title: t('reports.table.total'),
+            dataIndex: 'total',
+            key: 'total',
+            align: 'right',
+            render: (v: number) => <strong>{v.toFixed(2)}</strong>,
+          },
+        ]}
+        summary={() =>
+          (report?.totals ?? []).map((row) => (
+            <Table.Summary.Row key={row.currency}>
+              <Table.Summary.Cell index={0}>
+                <strong>{t('reports.table.total')}</strong>
+              </Table.Summary.Cell>
+              <Table.Summary.Cell index={1}>{row.currency}</Table.Summary.Cell>
+              <Table.Summary.Cell index={2} align="right">
+                {row.invoiceCount}
+              </Table.Summary.Cell>
+              {BUCKETS.map((b, i) => (
+                <Table.Summary.Cell key={b} index={3 + i} align="right">
+                  {money(row[b])}
+                </Table.Summary.Cell>
+              ))}
+              <Table.Summary.Cell index={3 + BUCKETS.length} align="right">
+                <strong>{row.total.toFixed(2)}</strong>
+              </Table.Summary.Cell>
+            </Table.Summary.Row>
+          ))
+        }
+      />
+    </Card>
+  );
+}
@@ -0,0 +1,201 @@
+import { useEffect, useState } from 'react';
+import { Button, Checkbox, DatePicker, Form, Input, InputNumber, Modal, Select, Space, Table, message } from 'antd';
+import { DeleteOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { CURRENCY_VALUES, RECURRENCE_INTERVAL_VALUES } from '../types';
+import type { RecurrenceInterval, RecurringExpense } from '../types';
+
+const storage = getStorage();
+
+type ScheduleFormValues = {
+  title: string;
+  amount: number;
+  currency: string;
+  category?: string;
+  notes?: string;
+  interval: RecurrenceInterval;
+  startDate: dayjs.Dayjs;
+  endDate?: dayjs.Dayjs | null;
+};
+
+type Props = {
+  open: boolean;
+  canWrite: boolean;
+  defaultCurrency: string;
+  onClose: () => void;
+  /** Called after generated expenses may have changed. */
+  onChanged: () => void;
+};
+
+export function RecurringExpensesModal({ open, canWrite, defaultCurrency, onClose, onChanged }: Props) {
+  const { t } = useTranslation();
+  const [form] = Form.useForm<ScheduleFormValues>();
+  const [schedules, setSchedules] = useState<RecurringExpense[]>([]);
+  const [adding, setAdding] = useState(false);
+
+  const refresh = async () => setSchedules(await storage.listRecurringExpenses());
+
+  useEffect(() => {
+    if (open) void refresh();
+  }, [open]);
+
+  const startAdding = () => {
+    form.resetFields();
+    form.setFieldsValue({ currency: defaultCurrency, interval: 'monthly', startDate: dayjs() });
+    setAdding(true);
+  };
+
+  const handleCreate = async (values: ScheduleFormValues) => {
+    try {
+      await storage.createRecurringExpense({
+        title: values.title.trim(),
+        amount: values.amount,
+        currency: values.currency,
+        category: values.category?.trim() || null,
+        notes: values.notes?.trim() || null,
+        interval: values.interval,
+        startDate: values.startDate.format('YYYY-MM-DD'),
+        endDate: values.endDate ? values.endDate.format('YYYY-MM-DD') : null,
+      });
+      const created = await storage.runDueRecurringExpenses();
+      message.success(t('expenses.recurring.created'));
+      if (created > 0) {
+        message.info(t('expenses.recurring.generated', { count: created }));
+        onChanged();
+      }
+      setAdding(false);
+      await refresh();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const handleDelete = (schedule: RecurringExpense) => {
+    let deleteFuture = false;
+    Modal.confirm({
+      title: t('expenses.recurring.deleteTitle'),
+      content: (
+        <>
+          <p>{t('expenses.recurring.deleteDesc')}</p>
+          <Checkbox onChange={(e) => (deleteFuture = e.target.checked)}>{t('expenses.recurring.deleteFuture')}</Checkbox>
+        </>
+      ),
+      okText: t('common.delete'),
+      okButtonProps: { danger: true },
+      cancelText: t('common.cancel'),
+      onOk: async () => {
+        const res = await storage.deleteRecurringExpense(schedule.id, { deleteFuture });
+        if (res.deleted) message.success(t('expenses.recurring.deleted'));
+        if (res.expensesDeleted > 0) onChanged();
+        await refresh();
+      },
+    });
+  };
+
+  return (
+    <Modal title={t('expenses.recurring.title')} open={open} onCancel={onClose} footer={null} width={820}>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={schedules}
+        pagination={false}
+        locale={{ emptyText: t('expenses.recurring.empty') }}
+        columns={[
+          { title: t('expenses.titleCol'), dataIndex: 'title' },
+          {
+            title: t('expenses.amount'),
+            key: 'amount',
+            align: 'right' as const,
+            render: (_: unknown, r: RecurringExpense) => `${r.amount.toFixed(2)} ${r.currency}`,
+          },
+          {
+            title: t('expenses.recurring.interval'),
+            dataIndex: 'interval',
+            render: (v: RecurrenceInterval) => t(`expenses.recurring.intervals.${v}`),
+          },
+          {
+            title: t('expenses.recurring.nextRunDate'),
+            dataIndex: 'nextRunDate',
+            render: (v: string, r: RecurringExpense) =>
+              r.endDate && v > r.endDate ? '—' : dayjs(v).format('DD.MM.YYYY'),
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 110,
+            render: (_: unknown, r: RecurringExpense) => (
+              <Button type="link" danger icon={<DeleteOutlined />} disabled={!canWrite} onClick={() => handleDelete(r)}>
+                {t('common.delete')}
+              </Button>
+            ),
+          },
+        ]}
+      />
+
+      {adding ? (
+        <Form form={form} layout="vertical" onFinish={(v) => void handleCreate(v)} style={{ marginTop: 16 }}>
+          <Form.Item label={t('expenses.titleCol')} name="title" rules={[{ required: true, message: t('expenses.titleReq') }]}>
+            <Input placeholder={t('expenses.titlePlaceholder')} />
+          </Form.Item>
+          <Space style={{ width: '100%' }} size={12} align="start">
+            <Form.Item
+              label={t('expenses.amount')}
+              name="amount"
+              rules={[{ required: true, message: t('expenses.amountReq') }]}
+            >
+              <InputNumber min={0.01} step={0.01} style={{ width: 160 }} />
+            </Form.Item>
+            <Form.Item label={t('expenses.currency')} name="currency" rules={[{ required: true }]}>
+              <Select
+                style={{ width: 140 }}
+                options={CURRENCY_VALUES.map((c) => ({ value: c, label: t(`currencies.${c}`) }))}
+              />
+            </Form.Item>
+            <Form.Item label={t('expenses.category')} name="category">
+              <Input placeholder={t('expenses.categoryPlaceholder')} />
+            </Form.Item>
+          </Space>
+          <Space style={{ width: '100%' }} size={12} align="start">
+            <Form.Item label={t('expenses.recurring.interval')} name="interval" rules={[{ required: true }]}>
+              <Select
+                style={{ width: 160 }}
+                options={RECURRENCE_INTERVAL_VALUES.map((v) => ({
+                  value: v,
+                  label: t(`expenses.recurring.intervals.${v}`),
+                }))}
+              />
+            </Form.Item>
+            <Form.Item
+              label={t('expenses.recurring.startDate')}
+              name="startDate"
+              rules={[{ required: true, message: t('expenses.dateReq') }]}
+            >
+              <DatePicker format="DD.MM.YYYY" />
+            </Form.Item>
+            <Form.Item label={t('expenses.recurring.endDate')} name="endDate">
+              <DatePicker format="DD.MM.YYYY" allowClear />
+            </Form.Item>
+          </Space>
+          <Form.Item label={t('expenses.notes')} name="notes">
+            <Input.TextArea rows={2} placeholder={t('expenses.notesPlaceholder')} />
+          </Form.Item>
+          <Space style={{ width: '100%', justifyContent: 'flex-end' }}>
+            <Button onClick={() => setAdding(false)}>{t('common.cancel')}</Button>
+            <Button type="primary" htmlType="submit" disabled={!canWrite}>
+              {t('expenses.recurring.add')}
+            </Button>
+          </Space>
+        </Form>
+      ) : (
+        <div style={{ display: 'flex', justifyContent: 'flex-end', marginTop: 16 }}>
+          <Button type="primary" onClick={startAdding} disabled={!canWrite}>
+            {t('expenses.recurring.add')}
+          </Button>
+        </div>
+      )}
+    </Modal>
+  );
+}
@@ -0,0 +1,78 @@
+import { useState } from 'react';
+import { Button, Checkbox, Modal, Space, Typography, message } from 'antd';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+
+const storage = getStorage();
+
+/** Export/import of the settings and logo alone, for setting up a second computer. */
+export function SettingsTransfer({ disabled }: { disabled: boolean }) {
+  const { t } = useTranslation();
+  const [includeSmtp, setIncludeSmtp] = useState(false);
+  const [busy, setBusy] = useState<'export' | 'import' | null>(null);
+
+  const errorText = (e: any) => (e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e));
+
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: `pausaler-settings-${dayjs().format('YYYY-MM-DD')}.json`,
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+    });
+    if (!path) return;
+    setBusy('export');
+    try {
+      await storage.exportSettings(path, includeSmtp);
+      message.success(t('settings.backup.settingsFile.exported', { path }));
+    } catch (e: any) {
+      message.error(errorText(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const runImport = async (path: string) => {
+    setBusy('import');
+    try {
+      await storage.importSettings(path);
+      message.success(t('settings.backup.settingsFile.imported'));
+    } catch (e: any) {
+      message.error(t('settings.backup.settingsFile.importError', { message: errorText(e) }));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleImport = async () => {
+    const picked = await open({ filters: [{ name: 'JSON', extensions: ['json'] }], multiple: false });
+    if (!picked) return;
+    const path = Array.isArray(picked) ? picked[0] : picked;
+    Modal.confirm({
+      content: t('settings.backup.settingsFile.importConfirm'),
+      onOk: () => runImport(path),
+    });
+  };
+
+  return (
+    <div>
+      <Typography.Title level={4} style={{ marginTop: 0 }}>{t('settings.backup.settingsFile.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('settings.backup.settingsFile.help')}</Typography.Paragraph>
+      <Space wrap style={{ marginBottom: 12 }}>
+        <Checkbox checked={includeSmtp} onChange={(e) => setIncludeSmtp(e.target.checked)}>
+          {t('settings.backup.settingsFile.includeSmtp')}
+        </Checkbox>
+        <Button onClick={() => void handleExport()} loading={busy === 'export'}>
+          {t('settings.backup.settingsFile.exportButton')}
+        </Button>
+        <Button onClick={() => void handleImport()} loading={busy === 'import'} disabled={disabled}>
+          {t('settings.backup.settingsFile.importButton')}
+        </Button>
+      </Space>
+      {includeSmtp ? (
+        <Typography.Paragraph type="warning">{t('settings.backup.settingsFile.includeSmtpWarning')}</Typography.Paragraph>
+      ) : null}
+    </div>
+  );
+}
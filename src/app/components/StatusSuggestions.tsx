@@ -0,0 +1,118 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button, Card, List, Space, Tag, Typography, message } from 'antd';
+import { ReloadOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useNavigate } from 'react-router-dom';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { useDataChanged } from '../hooks/useDataChanged';
+import type { StatusSuggestion, SuggestedAction } from '../types';
+
+const storage = getStorage();
+
+const REASON_COLORS: Record<StatusSuggestion['reason'], string> = {
+  STALE_DRAFT: 'default',
+  OVERDUE: 'volcano',
+  PAID_WITHOUT_DATE: 'gold',
+};
+
+/** Invoices whose status looks out of date, with one-click fixes; hidden when there are none. */
+export function StatusSuggestions({ canWrite }: { canWrite: boolean }) {
+  const { t } = useTranslation();
+  const navigate = useNavigate();
+  const [suggestions, setSuggestions] = useState<StatusSuggestion[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [busyId, setBusyId] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    try {
+      setSuggestions(await storage.suggestStatusUpdates());
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void load();
+  }, [load]);
+
+  useDataChanged('invoices', () => void load());
+
+  const apply = async (s: StatusSuggestion, action: SuggestedAction) => {
+    // Sending goes through the invoice view, where the email can be reviewed first.
+    if (action === 'SEND' || action === 'SEND_REMINDER') {
+      navigate(`/invoices/view/${s.invoiceId}`);
+      return;
+    }
+    setBusyId(s.invoiceId);
+    try {
+      if (action === 'CANCEL') {
+        await storage.updateInvoice(s.invoiceId, { status: 'CANCELLED' });
+      } else if (action === 'MARK_PAID') {
+        await storage.updateInvoice(s.invoiceId, { status: 'PAID', paidAt: s.paidAt });
+      } else {
+        await storage.updateInvoice(s.invoiceId, { paidAt: s.paidAt });
+      }
+      message.success(t('invoices.suggestions.applied', { number: s.invoiceNumber }));
+      await load();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  const actionLabel = (s: StatusSuggestion, action: SuggestedAction) =>
+    action === 'MARK_PAID' || action === 'SET_PAID_AT'
+      ? t(`invoices.suggestions.actions.${action}`, {
+          date: s.paidAt ? dayjs(s.paidAt).format('DD.MM.YYYY') : t('invoices.suggestions.today'),
+        })
+      : t(`invoices.suggestions.actions.${action}`);
+
+  if (!suggestions.length) return null;
+
+  return (
+    <Card
+      size="small"
+      title={t('invoices.suggestions.title', { count: suggestions.length })}
+      extra={<Button size="small" icon={<ReloadOutlined />} onClick={() => void load()} loading={loading} />}
+      style={{ marginBottom: 16 }}
+    >
+      <List
+        size="small"
+        dataSource={suggestions}
+        pagination={suggestions.length > 5 ? { pageSize: 5, size: 'small' } : false}
+        renderItem={(s) => (
+          <List.Item
+            actions={s.actions.map((action, i) => (
+              <Button
+                key={action}
+                size="small"
+                type={i === 0 ? 'primary' : 'default'}
+                danger={action === 'CANCEL'}
+                disabled={!canWrite}
+                loading={busyId === s.invoiceId && action !== 'SEND' && action !== 'SEND_REMINDER'}
+                onClick={() => void apply(s, action)}
+              >
+                {actionLabel(s, action)}
+              </Button>
+            ))}
+          >
+            <Space size="small" wrap>
+              <Tag color={REASON_COLORS[s.reason]}>{t(`invoices.suggestions.reasons.${s.reason}`)}</Tag>
+              <Typography.Text strong>{s.invoiceNumber}</Typography.Text>
+              <Typography.Text>{s.clientName}</Typography.Text>
+              <Typography.Text type="secondary">
+                {t(`invoices.suggestions.details.${s.reason}`, { count: s.days })}
+              </Typography.Text>
+            </Space>
+          </List.Item>
+        )}
+      />
+    </Card>
+  );
+}
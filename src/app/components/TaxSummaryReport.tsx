@@ -0,0 +1,159 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, Card, DatePicker, Space, Table, Typography, message } from 'antd';
+import { DownloadOutlined } from '@ant-design/icons';
+import { save } from '@tauri-apps/plugin-dialog';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { TaxQuarter, TaxSummary, TaxUnconverted } from '../types';
+
+const storage = getStorage();
+
+const fmtDate = (ymd: string) => dayjs(ymd).format('DD.MM.YYYY');
+
+/** Quarterly paid income, expenses and base for advance tax payments. */
+export function TaxSummaryReport() {
+  const { t } = useTranslation();
+  const [year, setYear] = useState(dayjs().year());
+  const [summary, setSummary] = useState<TaxSummary | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    setLoading(true);
+    storage
+      .taxSummary(year)
+      .then((s) => {
+        if (!cancelled) setSummary(s);
+      })
+      .catch((e: any) => message.error(String(e?.message ?? e)))
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [year]);
+
+  const exportCsv = async () => {
+    const path = await save({
+      defaultPath: `porez_${year}.csv`,
+      filters: [{ name: 'CSV', extensions: ['csv'] }],
+    });
+    if (!path) return;
+    try {
+      await storage.exportTaxSummaryCsv(year, path);
+      message.success(t('exports.success', { files: path }));
+    } catch (e: any) {
+      message.error(t('exports.errors.failed', { message: String(e?.message ?? e) }));
+    }
+  };
+
+  const isEmpty = !summary || (summary.yearTotal.incomeCount === 0 && summary.yearTotal.expenseCount === 0);
+
+  return (
+    <Card
+      title={t('reports.tax.title')}
+      extra={
+        <Space>
+          <DatePicker
+            picker="year"
+            allowClear={false}
+            value={dayjs().year(year)}
+            onChange={(d) => d && setYear(d.year())}
+          />
+          <Button icon={<DownloadOutlined />} onClick={() => void exportCsv()} disabled={isEmpty}>
+            {t('reports.tax.exportCsv')}
+          </Button>
+        </Space>
+      }
+    >
+      {summary ? (
+        <Typography.Paragraph type="secondary">{t('reports.tax.note', { currency: summary.currency })}</Typography.Paragraph>
+      ) : null}
+      <Table<TaxQuarter>
+        size="small"
+        loading={loading}
+        rowKey="quarter"
+        dataSource={isEmpty ? [] : summary?.quarters ?? []}
+        pagination={false}
+        locale={{ emptyText: t('reports.tax.empty') }}
+        columns={[
+          {
+            title: t('reports.tax.period'),
+            key: 'period',
+            render: (_, q) => `Q${q.quarter} (${fmtDate(q.from)} – ${fmtDate(q.to)})`,
+          },
+          {
+            title: t('reports.tax.income'),
+            dataIndex: 'income',
+            key: 'income',
+            align: 'right',
+            render: (v: number, q) => `${v.toFixed(2)} (${q.incomeCount})`,
+          },
+          {
+            title: t('reports.tax.expenses'),
+            dataIndex: 'expenses',
+            key: 'expenses',
+            align: 'right',
+            render: (v: number, q) => `${v.toFixed(2)} (${q.expenseCount})`,
+          },
+          {
+            title: t('reports.tax.base'),
+            dataIndex: 'base',
+            key: 'base',
+            align: 'right',
+            render: (v: number) => <strong>{v.toFixed(2)}</strong>,
+          },
+        ]}
+        summary={() =>
+          summary && !isEmpty ? (
+            <Table.Summary.Row>
+              <Table.Summary.Cell index={0}>
+                <strong>{t('reports.table.total')}</strong>
+              </Table.Summary.Cell>
+              <Table.Summary.Cell index={1} align="right">
+                {summary.yearTotal.income.toFixed(2)}
+              </Table.Summary.Cell>
+              <Table.Summary.Cell index={2} align="right">
+                {summary.yearTotal.expenses.toFixed(2)}
+              </Table.Summary.Cell>
+              <Table.Summary.Cell index={3} align="right">
+                <strong>{summary.yearTotal.base.toFixed(2)}</strong>
+              </Table.Summary.Cell>
+            </Table.Summary.Row>
+          ) : null
+        }
+      />
+      {summary?.unconverted.length ? (
+        <Space direction="vertical" style={{ width: '100%', marginTop: 16 }}>
+          <Alert type="warning" showIcon message={t('reports.tax.unconvertedTitle')} description={t('reports.tax.unconvertedNote')} />
+          <Table<TaxUnconverted>
+            size="small"
+            rowKey={(u) => `${u.quarter}:${u.kind}:${u.currency}`}
+            dataSource={summary.unconverted}
+            pagination={false}
+            columns={[
+              { title: t('reports.tax.period'), dataIndex: 'quarter', key: 'quarter', render: (q: number) => `Q${q}` },
+              {
+                title: t('reports.tax.kind'),
+                dataIndex: 'kind',
+                key: 'kind',
+                render: (k: TaxUnconverted['kind']) => t(`reports.tax.kinds.${k}`),
+              },
+              { title: t('invoices.currency'), dataIndex: 'currency', key: 'currency' },
+              {
+                title: t('reports.table.total'),
+                dataIndex: 'amount',
+                key: 'amount',
+                align: 'right',
+                render: (v: number, u) => `${v.toFixed(2)} (${u.count})`,
+              },
+            ]}
+          />
+        </Space>
+      ) : null}
+    </Card>
+  );
+}
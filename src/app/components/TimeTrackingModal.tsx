@@ -0,0 +1,270 @@
+import { useEffect, useState } from 'react';
+import { Alert, Button, DatePicker, Form, Input, InputNumber, Modal, Select, Space, Switch, Table, Tabs, Tag, message } from 'antd';
+import { DeleteOutlined, PauseCircleOutlined, PlayCircleOutlined, PlusOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import { useClients } from '../hooks/useClients';
+import { formatMinutes } from './BillTimeModal';
+import type { ActiveTimer, TimeEntry, UnbilledTime } from '../types';
+
+const storage = getStorage();
+
+type EntryFormValues = {
+  clientId: string;
+  date: dayjs.Dayjs;
+  hours: number;
+  minutes: number;
+  description?: string;
+  hourlyRate: number;
+};
+
+type TimerFormValues = {
+  clientId: string;
+  description?: string;
+  hourlyRate: number;
+};
+
+type Props = {
+  open: boolean;
+  canWrite: boolean;
+  onClose: () => void;
+};
+
+export function TimeTrackingModal({ open, canWrite, onClose }: Props) {
+  const { t } = useTranslation();
+  const { clients } = useClients();
+  const [entryForm] = Form.useForm<EntryFormValues>();
+  const [timerForm] = Form.useForm<TimerFormValues>();
+  const [entries, setEntries] = useState<TimeEntry[]>([]);
+  const [unbilledOnly, setUnbilledOnly] = useState(true);
+  const [unbilled, setUnbilled] = useState<UnbilledTime[]>([]);
+  const [timer, setTimer] = useState<ActiveTimer | null>(null);
+  const [now, setNow] = useState(dayjs());
+  const [adding, setAdding] = useState(false);
+
+  const clientName = (id: string) => clients.find((c) => c.id === id)?.name ?? id;
+  const clientOptions = clients.map((c) => ({ value: c.id, label: c.name }));
+
+  const refresh = async (onlyUnbilled = unbilledOnly) => {
+    const [nextEntries, nextUnbilled, nextTimer] = await Promise.all([
+      storage.listTimeEntries({ unbilledOnly: onlyUnbilled }),
+      storage.unbilledTimeReport(),
+      storage.getActiveTimer(),
+    ]);
+    setEntries(nextEntries);
+    setUnbilled(nextUnbilled);
+    setTimer(nextTimer);
+  };
+
+  useEffect(() => {
+    if (open) void refresh();
+  }, [open]);
+
+  useEffect(() => {
+    if (!open || !timer) return;
+    const id = window.setInterval(() => setNow(dayjs()), 1000);
+    return () => window.clearInterval(id);
+  }, [open, timer]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    try {
+      await action();
+      await refresh();
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const startTimer = (values: TimerFormValues) =>
+    run(() => storage.startTimer(values.clientId, values.description?.trim() ?? '', values.hourlyRate));
+
+  const stopTimer = () =>
+    run(async () => {
+      const entry = await storage.stopTimer();
+      if (entry) message.success(t('time.recorded', { duration: formatMinutes(entry.minutes) }));
+    });
+
+  const addEntry = (values: EntryFormValues) =>
+    run(async () => {
+      await storage.createTimeEntry({
+        clientId: values.clientId,
+        date: values.date.format('YYYY-MM-DD'),
+        minutes: (values.hours ?? 0) * 60 + (values.minutes ?? 0),
+        description: values.description?.trim() ?? '',
+        hourlyRate: values.hourlyRate,
+      });
+      setAdding(false);
+    });
+
+  const startAdding = () => {
+    entryForm.resetFields();
+    entryForm.setFieldsValue({ date: dayjs(), hours: 1, minutes: 0 });
+    setAdding(true);
+  };
+
+  const elapsed = timer ? Math.max(0, now.diff(dayjs(timer.startedAt), 'second')) : 0;
+  const elapsedLabel = `${formatMinutes(Math.floor(elapsed / 60))}:${String(elapsed % 60).padStart(2, '0')}`;
+
+  const timerBar = timer ? (
+    <Alert
+      type="info"
+      showIcon
+      icon={<PlayCircleOutlined />}
+      message={`${clientName(timer.clientId)}${timer.description ? ` · ${timer.description}` : ''} — ${elapsedLabel}`}
+      action={
+        <Button icon={<PauseCircleOutlined />} onClick={() => void stopTimer()} disabled={!canWrite}>
+          {t('time.stop')}
+        </Button>
+      }
+      style={{ marginBottom: 12 }}
+    />
+  ) : (
+    <Form form={timerForm} layout="inline" onFinish={(v) => void startTimer(v)} style={{ marginBottom: 12, rowGap: 8 }}>
+      <Form.Item name="clientId" rules={[{ required: true, message: t('time.clientReq') }]}>
+        <Select showSearch optionFilterProp="label" placeholder={t('time.client')} options={clientOptions} style={{ width: 200 }} />
+      </Form.Item>
+      <Form.Item name="description">
+        <Input placeholder={t('time.description')} style={{ width: 220 }} />
+      </Form.Item>
+      <Form.Item name="hourlyRate" rules={[{ required: true, message: t('time.rateReq') }]}>
+        <InputNumber min={0} step={100} placeholder={t('time.hourlyRate')} style={{ width: 130 }} />
+      </Form.Item>
+      <Button type="primary" htmlType="submit" icon={<PlayCircleOutlined />} disabled={!canWrite}>
+        {t('time.start')}
+      </Button>
+    </Form>
+  );
+
+  const entriesTab = (
+    <>
+      <Space style={{ marginBottom: 12 }}>
+        <Switch
+          checked={unbilledOnly}
+          onChange={(v) => {
+            setUnbilledOnly(v);
+            void refresh(v);
+          }}
+        />
+        {t('time.unbilledOnly')}
+      </Space>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={entries}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('time.empty') }}
+        columns={[
+          {
+            title: t('time.date'),
+            dataIndex: 'date',
+            width: 110,
+            render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+          },
+          { title: t('time.client'), dataIndex: 'clientId', render: (v: string) => clientName(v) },
+          {
+            title: t('time.description'),
+            dataIndex: 'description',
+            render: (v: string, r: TimeEntry) => (
+              <Space size={6}>
+                {v}
+                {r.billedInvoiceId ? <Tag color="green">{t('time.billed')}</Tag> : null}
+              </Space>
+            ),
+          },
+          { title: t('time.duration'), dataIndex: 'minutes', align: 'right' as const, width: 90, render: formatMinutes },
+          {
+            title: t('time.hourlyRate'),
+            dataIndex: 'hourlyRate',
+            align: 'right' as const,
+            width: 110,
+            render: (v: number) => v.toFixed(2),
+          },
+          {
+            title: t('common.actions'),
+            key: 'actions',
+            width: 70,
+            render: (_: unknown, r: TimeEntry) => (
+              <Button
+                type="link"
+                danger
+                icon={<DeleteOutlined />}
+                disabled={!canWrite || !!r.billedInvoiceId}
+                onClick={() => void run(() => storage.deleteTimeEntry(r.id))}
+              />
+            ),
+          },
+        ]}
+      />
+      {adding ? (
+        <Form form={entryForm} layout="vertical" onFinish={(v) => void addEntry(v)} style={{ marginTop: 16 }}>
+          <Space style={{ width: '100%' }} size={12} align="start" wrap>
+            <Form.Item label={t('time.client')} name="clientId" rules={[{ required: true, message: t('time.clientReq') }]}>
+              <Select showSearch optionFilterProp="label" options={clientOptions} style={{ width: 220 }} />
+            </Form.Item>
+            <Form.Item label={t('time.date')} name="date" rules={[{ required: true }]}>
+              <DatePicker format="DD.MM.YYYY" />
+            </Form.Item>
+            <Form.Item label={t('time.hours')} name="hours">
+              <InputNumber min={0} max={24} style={{ width: 80 }} />
+            </Form.Item>
+            <Form.Item label={t('time.minutes')} name="minutes">
+              <InputNumber min={0} max={59} style={{ width: 80 }} />
+            </Form.Item>
+            <Form.Item label={t('time.hourlyRate')} name="hourlyRate" rules={[{ required: true, message: t('time.rateReq') }]}>
+              <InputNumber min={0} step={100} style={{ width: 130 }} />
+            </Form.Item>
+          </Space>
+          <Form.Item label={t('time.description')} name="description">
+            <Input />
+          </Form.Item>
+          <Space>
+            <Button onClick={() => setAdding(false)}>{t('common.cancel')}</Button>
+            <Button type="primary" htmlType="submit">
+              {t('common.save')}
+            </Button>
+          </Space>
+        </Form>
+      ) : (
+        <Button icon={<PlusOutlined />} disabled={!canWrite} onClick={startAdding} style={{ marginTop: 12 }}>
+          {t('time.add')}
+        </Button>
+      )}
+    </>
+  );
+
+  const unbilledTab = (
+    <Table
+      size="small"
+      rowKey="clientId"
+      dataSource={unbilled}
+      pagination={false}
+      locale={{ emptyText: t('time.unbilledEmpty') }}
+      columns={[
+        { title: t('time.client'), dataIndex: 'clientName' },
+        { title: t('time.entries'), dataIndex: 'entries', align: 'right' as const, width: 90 },
+        { title: t('time.hours'), dataIndex: 'hours', align: 'right' as const, width: 90, render: (v: number) => v.toFixed(2) },
+        { title: t('time.amount'), dataIndex: 'amount', align: 'right' as const, render: (v: number) => v.toFixed(2) },
+        {
+          title: t('time.oldest'),
+          dataIndex: 'oldestDate',
+          width: 110,
+          render: (v: string) => dayjs(v).format('DD.MM.YYYY'),
+        },
+      ]}
+    />
+  );
+
+  return (
+    <Modal title={t('time.title')} open={open} onCancel={onClose} footer={null} width={900}>
+      {timerBar}
+      <Tabs
+        items={[
+          { key: 'entries', label: t('time.entriesTab'), children: entriesTab },
+          { key: 'unbilled', label: t('time.unbilledTab'), children: unbilledTab },
+        ]}
+      />
+    </Modal>
+  );
+}
@@ -0,0 +1,112 @@
+import { useEffect, useState } from 'react';
+import { Button, Form, Input, Space, Table, Tag, Typography, message } from 'antd';
+import { ApiOutlined, ReloadOutlined } from '@ant-design/icons';
+import dayjs from 'dayjs';
+import { useTranslation } from 'react-i18next';
+
+import { getStorage } from '../services/storageProvider';
+import type { WebhookDelivery, WebhookDeliveryStatus } from '../types';
+
+const storage = getStorage();
+
+const STATUS_COLORS: Record<WebhookDeliveryStatus, string> = {
+  PENDING: 'gold',
+  DELIVERED: 'green',
+  FAILED: 'red',
+};
+
+/** Webhook URL and secret (fields of the surrounding settings form), a test button and recent deliveries. */
+export function WebhookFields({ secretSaved, canWrite }: { secretSaved: boolean; canWrite: boolean }) {
+  const { t } = useTranslation();
+  const [deliveries, setDeliveries] = useState<WebhookDelivery[]>([]);
+  const [testing, setTesting] = useState(false);
+
+  const refresh = async () => {
+    try {
+      setDeliveries(await storage.listWebhookDeliveries());
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  useEffect(() => {
+    void refresh();
+  }, []);
+
+  const test = async () => {
+    setTesting(true);
+    try {
+      const result = await storage.testWebhook();
+      message.success(t('settings.webhooks.testOk', { status: result.responseStatus }));
+    } catch (e: any) {
+      message.error(t('settings.webhooks.testFailed', { error: String(e?.message ?? e) }));
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  const formatTime = (v?: string | null) => (v ? dayjs(v).format('DD.MM.YYYY HH:mm') : '—');
+
+  return (
+    <div>
+      <Typography.Paragraph type="secondary" style={{ marginTop: 0 }}>
+        {t('settings.webhooks.help')}
+      </Typography.Paragraph>
+      <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+        <Form.Item label={t('settings.webhooks.url')} name="webhookUrl" extra={t('settings.webhooks.urlHelp')}>
+          <Input placeholder="https://dashboard.example.com/hooks/pausaler" />
+        </Form.Item>
+        <Form.Item
+          label={t('settings.webhooks.secret')}
+          name="webhookSecret"
+          extra={secretSaved ? t('settings.webhooks.secretSaved') : t('settings.webhooks.secretHelp')}
+        >
+          <Input.Password autoComplete="new-password" />
+        </Form.Item>
+      </div>
+
+      <Space style={{ marginBottom: 12 }}>
+        <Button icon={<ApiOutlined />} loading={testing} disabled={!canWrite} onClick={() => void test()}>
+          {t('settings.webhooks.test')}
+        </Button>
+        <Typography.Text type="secondary">{t('settings.webhooks.testHelp')}</Typography.Text>
+      </Space>
+
+      <Space style={{ width: '100%', justifyContent: 'space-between', marginTop: 8 }}>
+        <Typography.Title level={5} style={{ margin: 0 }}>
+          {t('settings.webhooks.deliveries')}
+        </Typography.Title>
+        <Button size="small" icon={<ReloadOutlined />} onClick={() => void refresh()} />
+      </Space>
+      <Table
+        size="small"
+        rowKey="id"
+        dataSource={deliveries}
+        pagination={{ pageSize: 8 }}
+        locale={{ emptyText: t('settings.webhooks.empty') }}
+        style={{ marginTop: 8 }}
+        columns={[
+          { title: t('settings.webhooks.createdAt'), dataIndex: 'createdAt', width: 150, render: formatTime },
+          { title: t('settings.webhooks.event'), dataIndex: 'event' },
+          {
+            title: t('settings.webhooks.status'),
+            dataIndex: 'status',
+            width: 120,
+            render: (v: WebhookDeliveryStatus) => <Tag color={STATUS_COLORS[v]}>{t(`settings.webhooks.statuses.${v}`)}</Tag>,
+          },
+          { title: t('settings.webhooks.attempts'), dataIndex: 'attempts', align: 'right' as const, width: 80 },
+          {
+            title: t('settings.webhooks.detail'),
+            key: 'detail',
+            render: (_: unknown, r: WebhookDelivery) =>
+              r.status === 'DELIVERED'
+                ? formatTime(r.deliveredAt)
+                : [r.error, r.nextAttemptAt ? t('settings.webhooks.nextAttempt', { at: formatTime(r.nextAttemptAt) }) : null]
+                    .filter(Boolean)
+                    .join(' · '),
+          },
+        ]}
+      />
+    </div>
+  );
+}
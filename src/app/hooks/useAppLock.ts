@@ -0,0 +1,39 @@
+import { useCallback, useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+
+import { getStorage } from '../services/storageProvider';
+import type { AppLockStatus } from '../types';
+
+const storage = getStorage();
+
+/** PIN / lock state, kept current from the backend's `app_lock://changed` events. */
+export function useAppLock() {
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+
+  const refresh = useCallback(async () => {
+    try {
+      setStatus(await storage.getAppLockStatus());
+    } catch {
+      setStatus({ pinSet: false, locked: false });
+    }
+  }, []);
+
+  useEffect(() => {
+    void refresh();
+    let cancelled = false;
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        const off = await listen<AppLockStatus>('app_lock://changed', (event) => setStatus(event.payload));
+        if (cancelled) off();
+        else unlisten = off;
+      } catch {}
+    })();
+    return () => {
+      cancelled = true;
+      if (unlisten) unlisten();
+    };
+  }, [refresh]);
+
+  return { status, refresh };
+}
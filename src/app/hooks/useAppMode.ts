@@ -0,0 +1,41 @@
+import { useCallback, useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+
+import { getStorage } from '../services/storageProvider';
+import type { AppMode } from '../types';
+
+const storage = getStorage();
+
+/** Read-only mode, kept current from the backend's `app_mode://changed` events. */
+export function useAppMode() {
+  const [mode, setMode] = useState<AppMode>({ readOnly: false });
+  const [retrying, setRetrying] = useState(false);
+
+  const retry = useCallback(async () => {
+    setRetrying(true);
+    try {
+      setMode(await storage.retryWritable());
+    } finally {
+      setRetrying(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    void storage.getAppMode().then(setMode, () => {});
+    let cancelled = false;
+    let unlisten: (() => void) | null = null;
+    void (async () => {
+      try {
+        const off = await listen<AppMode>('app_mode://changed', (event) => setMode(event.payload));
+        if (cancelled) off();
+        else unlisten = off;
+      } catch {}
+    })();
+    return () => {
+      cancelled = true;
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  return { mode, retrying, retry };
+}
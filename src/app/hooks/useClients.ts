@@ -1,33 +1,39 @@
 import { useCallback, useEffect, useState } from 'react';
 import { getStorage } from '../services/storageProvider';
 import { Client } from '../types';
+import { useDataChanged } from './useDataChanged';
 
 const storage = getStorage();
 
-export const useClients = () => {
+export const useClients = (options?: { includeArchived?: boolean }) => {
+    const includeArchived = options?.includeArchived ?? false;
     const [clients, setClients] = useState<Client[]>([]);
 
     const refresh = useCallback(async () => {
-        const next = await storage.getAllClients();
+        const next = await storage.getAllClients({ includeArchived });
         setClients(next);
-    }, []);
+    }, [includeArchived]);
 
     useEffect(() => {
         void refresh();
     }, [refresh]);
 
+    useDataChanged('clients', () => void refresh());
+
     const createClient = useCallback(
-        async (data: Omit<Client, 'id' | 'createdAt'>) => {
-            const created = await storage.createClient(data);
-            await refresh();
-            return created;
+        async (data: Omit<Client, 'id' | 'createdAt'>, options?: { allowDuplicate?: boolean }) => {
+            const result = await storage.createClient(data, options);
+            if (result.status === 'created') {
+                await refresh();
+            }
+            return result;
         },
         [refresh]
     );
 
     const updateClient = useCallback(
-        async (id: string, patch: Partial<Client>) => {
-            const updated = await storage.updateClient(id, patch);
+        async (id: string, patch: Partial<Client>, options?: { propagateName?: boolean }) => {
+            const updated = await storage.updateClient(id, patch, options);
             await refresh();
             return updated;
         },
@@ -35,13 +41,33 @@ export const useClients = () => {
     );
 
     const deleteClient = useCallback(
-        async (id: string) => {
-            const ok = await storage.deleteClient(id);
+        async (id: string, options?: { force?: boolean }) => {
+            const result = await storage.deleteClient(id, options);
+            await refresh();
+            return result;
+        },
+        [refresh]
+    );
+
+    const setArchived = useCallback(
+        async (id: string, archived: boolean) => {
+            const updated = archived ? await storage.archiveClient(id) : await storage.unarchiveClient(id);
+            await refresh();
+            return updated;
+        },
+        [refresh]
+    );
+
+    const findDuplicates = useCallback(() => storage.findDuplicateClients(), []);
+
+    const mergeClients = useCallback(
+        async (keepId: string, removeId: string) => {
+            const result = await storage.mergeClients(keepId, removeId);
             await refresh();
-            return ok;
+            return result;
         },
         [refresh]
     );
 
-    return { clients, refresh, createClient, updateClient, deleteClient };
+    return { clients, refresh, createClient, updateClient, deleteClient, setArchived, findDuplicates, mergeClients };
 };
\ No newline at end of file
@@ -0,0 +1,40 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { getStorage } from '../services/storageProvider';
+import { useDataChanged } from './useDataChanged';
+
+const storage = getStorage();
+
+/** Object URL of the stored company logo ('' when none) and a setter that replaces or removes it. */
+export function useCompanyLogo() {
+    const [logoSrc, setLogoSrc] = useState('');
+    const urlRef = useRef('');
+
+    const refresh = useCallback(async () => {
+        const bytes = await storage.getCompanyLogo();
+        const next = bytes.byteLength > 0 ? URL.createObjectURL(new Blob([bytes], { type: 'image/png' })) : '';
+        if (urlRef.current) URL.revokeObjectURL(urlRef.current);
+        urlRef.current = next;
+        setLogoSrc(next);
+    }, []);
+
+    useEffect(() => {
+        void refresh().catch(() => {});
+        return () => {
+            if (urlRef.current) URL.revokeObjectURL(urlRef.current);
+            urlRef.current = '';
+        };
+    }, [refresh]);
+
+    useDataChanged('settings', () => void refresh().catch(() => {}));
+
+    /** `source` is a data URL of the picked image; an empty string removes the logo. */
+    const setLogo = useCallback(
+        async (source: string) => {
+            await storage.setCompanyLogo(source);
+            await refresh();
+        },
+        [refresh]
+    );
+
+    return { logoSrc, setLogo };
+}
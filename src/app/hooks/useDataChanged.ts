@@ -0,0 +1,27 @@
+import { useEffect, useRef } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import type { DataChangedEvent, DataEntity } from '../types';
+
+/** Calls `onChange` whenever the backend commits a change to `entity`, from any window. */
+export function useDataChanged(entity: DataEntity, onChange: (event: DataChangedEvent) => void) {
+    const callbackRef = useRef(onChange);
+    callbackRef.current = onChange;
+
+    useEffect(() => {
+        let cancelled = false;
+        let unlisten: (() => void) | null = null;
+        void (async () => {
+            try {
+                const off = await listen<DataChangedEvent>(`data://${entity}-changed`, (event) => {
+                    callbackRef.current(event.payload);
+                });
+                if (cancelled) off();
+                else unlisten = off;
+            } catch {}
+        })();
+        return () => {
+            cancelled = true;
+            if (unlisten) unlisten();
+        };
+    }, [entity]);
+}
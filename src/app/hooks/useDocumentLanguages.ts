@@ -0,0 +1,30 @@
+import { useEffect, useMemo, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { getStorage } from '../services/storageProvider';
+
+const storage = getStorage();
+
+const BUILT_IN = ['sr', 'en'];
+
+/** Select options for the languages invoices can be printed and emailed in. */
+export function useDocumentLanguages() {
+    const { t, i18n } = useTranslation();
+    const [codes, setCodes] = useState<string[]>(BUILT_IN);
+
+    useEffect(() => {
+        storage
+            .listAvailableLanguages()
+            .then((langs) => {
+                if (langs.length > 0) setCodes(langs);
+            })
+            .catch(() => {});
+    }, []);
+
+    return useMemo(() => {
+        const names = new Intl.DisplayNames([i18n.language], { type: 'language' });
+        return codes.map((code) => ({
+            value: code,
+            label: code === 'sr' ? t('settings.langSr') : code === 'en' ? t('settings.langEn') : (names.of(code) ?? code),
+        }));
+    }, [codes, i18n.language, t]);
+}
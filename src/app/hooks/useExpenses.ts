@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useRef, useState } from 'react';
 import type { Expense, ExpenseRange } from '../types';
 import { getStorage } from '../services/storageProvider';
+import { useDataChanged } from './useDataChanged';
 
 const storage = getStorage();
 
@@ -25,6 +26,8 @@ export function useExpenses() {
     void listExpenses();
   }, [listExpenses]);
 
+  useDataChanged('expenses', () => void refresh());
+
   const createExpense = useCallback(
     async (data: Omit<Expense, 'id' | 'createdAt'>) => {
       const created = await storage.createExpense(data);
@@ -45,9 +48,27 @@ export function useExpenses() {
 
   const deleteExpense = useCallback(
     async (id: string) => {
-      const ok = await storage.deleteExpense(id);
+      const deleted = await storage.deleteExpense(id);
+      await refresh();
+      return deleted;
+    },
+    [refresh]
+  );
+
+  const restoreExpense = useCallback(
+    async (id: string) => {
+      const restored = await storage.restoreExpense(id);
+      await refresh();
+      return restored;
+    },
+    [refresh]
+  );
+
+  const duplicateExpense = useCallback(
+    async (id: string, newDate: string) => {
+      const copy = await storage.duplicateExpense(id, newDate);
       await refresh();
-      return ok;
+      return copy;
     },
     [refresh]
   );
@@ -59,5 +80,7 @@ export function useExpenses() {
     createExpense,
     updateExpense,
     deleteExpense,
+    restoreExpense,
+    duplicateExpense,
   };
 }
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useState } from 'react';
 import { Invoice } from '../types';
 import { getStorage } from '../services/storageProvider';
+import { useDataChanged } from './useDataChanged';
 
 const storage = getStorage();
 
@@ -16,6 +17,8 @@ export const useInvoices = () => {
         void refresh();
     }, [refresh]);
 
+    useDataChanged('invoices', () => void refresh());
+
     const createInvoice = useCallback(
         async (data: Omit<Invoice, 'id' | 'createdAt'>) => {
             const created = await storage.createInvoice(data);
@@ -45,6 +48,7 @@ export const useInvoices = () => {
 
     return {
         invoices,
+        refresh,
         createInvoice,
         updateInvoice,
         deleteInvoice,
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useState } from 'react';
 import { Settings } from '../types';
 import { getStorage } from '../services/storageProvider';
+import { useDataChanged } from './useDataChanged';
 
 type UseSettingsResult = {
     settings: Settings | null;
@@ -31,6 +32,13 @@ export function useSettings(): UseSettingsResult {
         };
     }, []);
 
+    useDataChanged('settings', () => {
+        void getStorage()
+            .getSettings()
+            .then(setSettings)
+            .catch(() => {});
+    });
+
     const save = useCallback(async (next: Settings) => {
         const storage = getStorage();
         setLoading(true);
@@ -1,21 +1,30 @@
 import { useState } from 'react';
-import { Alert, Layout, Menu, theme } from 'antd';
-import { FileTextOutlined, UserOutlined, SettingOutlined, FlagOutlined, DashboardOutlined, BarChartOutlined, DollarOutlined, ExportOutlined, FileSearchOutlined, SafetyCertificateOutlined, GlobalOutlined, MailOutlined } from '@ant-design/icons';
+import { Alert, Button, Layout, Menu, message, theme } from 'antd';
+import { FileTextOutlined, UserOutlined, SettingOutlined, FlagOutlined, DashboardOutlined, BarChartOutlined, DollarOutlined, ExportOutlined, FileSearchOutlined, SafetyCertificateOutlined, GlobalOutlined, MailOutlined, LockOutlined } from '@ant-design/icons';
 import { Link, Navigate, Outlet, useLocation } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import { TitleBar } from '../components/TitleBar';
 import { LicenseGate } from '../components/LicenseGate';
+import { GlobalSearch } from '../components/GlobalSearch';
 import { useUpdateBadge } from '../hooks/useUpdateBadge';
+import { useAppLock } from '../hooks/useAppLock';
+import { useAppMode } from '../hooks/useAppMode';
+import { errorMessage } from '../services/commandError';
+import { getStorage } from '../services/storageProvider';
 import { open } from '@tauri-apps/plugin-shell';
 
 const { Sider, Content } = Layout;
 
+const storage = getStorage();
+
 export function MainLayout({ needsSetup = false }: { needsSetup?: boolean }) {
   const { t } = useTranslation();
   const [collapsed, setCollapsed] = useState(false);
   const [dismissedUpdateBanner, setDismissedUpdateBanner] = useState(false);
   const location = useLocation();
   const updateBadge = useUpdateBadge();
+  const { status: lockStatus } = useAppLock();
+  const { mode: appMode, retrying: retryingWritable, retry: retryWritable } = useAppMode();
   const {
     token: { colorBgContainer },
   } = theme.useToken();
@@ -103,6 +112,16 @@ export function MainLayout({ needsSetup = false }: { needsSetup?: boolean }) {
       disabled: false,
       label: <span>Website</span>,
     },
+    ...(lockStatus?.pinSet
+      ? [
+          {
+            key: '__lock',
+            icon: <LockOutlined />,
+            disabled: false,
+            label: <span>{t('appLock.lockNow')}</span>,
+          },
+        ]
+      : []),
   ];
 
   return (
@@ -116,6 +135,7 @@ export function MainLayout({ needsSetup = false }: { needsSetup?: boolean }) {
       }}
     >
       <TitleBar />
+      {needsSetup ? null : <GlobalSearch />}
 
       {updateBadge.available && !dismissedUpdateBanner ? (
         <Alert
@@ -140,6 +160,31 @@ export function MainLayout({ needsSetup = false }: { needsSetup?: boolean }) {
         />
       ) : null}
 
+      {appMode.readOnly ? (
+        <Alert
+          type="error"
+          showIcon
+          banner
+          message={appMode.reason === 'READ_ONLY_FILE' ? t('appMode.readOnlyFile') : t('appMode.locked')}
+          action={
+            <Button
+              size="small"
+              loading={retryingWritable}
+              onClick={() =>
+                void retryWritable()
+                  .then((next) => {
+                    if (next.readOnly) message.warning(t('appMode.stillReadOnly'));
+                    else message.success(t('appMode.writable'));
+                  })
+                  .catch((e) => message.error(errorMessage(e)))
+              }
+            >
+              {t('appMode.retry')}
+            </Button>
+          }
+        />
+      ) : null}
+
       <Layout style={{ flex: 1, minHeight: 0 }}>
         <Sider
           collapsible
@@ -175,6 +220,9 @@ export function MainLayout({ needsSetup = false }: { needsSetup?: boolean }) {
               if (e.key === '__website') {
                 void open('https://pausaler.rs/');
               }
+              if (e.key === '__lock') {
+                void storage.lockApp();
+              }
             }}
           />
         </Sider>
@@ -7,6 +7,7 @@ export const en = {
     no: 'No',
     cancel: 'Cancel',
     save: 'Save',
+    undo: 'Undo',
     delete: 'Delete',
     edit: 'Edit',
     view: 'View',
@@ -15,7 +16,9 @@ export const en = {
     actions: 'Actions',
     loading: 'Loading…',
     path: 'Path: {{path}}',
+    showInFolder: 'Show in folder',
     reset: 'Reset',
+    close: 'Close',
   },
   nav: {
     invoices: 'Invoices',
@@ -28,6 +31,48 @@ export const en = {
     settings: 'Settings',
     license: 'License',
   },
+  appLock: {
+    title: 'Pausaler is locked',
+    hint: 'Enter the PIN to continue.',
+    unlock: 'Unlock',
+    wrongPin: 'Wrong PIN.',
+    retryIn: 'Too many wrong PINs. Try again in {{seconds}} s.',
+    lockNow: 'Lock now',
+    settings: {
+      tab: 'Security',
+      title: 'App PIN',
+      help: 'With a PIN set, the app starts locked and shows nothing until the PIN is entered. After a few wrong PINs, further attempts have to wait longer and longer.',
+      statusSet: 'A PIN is set.',
+      statusNotSet: 'No PIN is set; the app opens without one.',
+      pin: 'PIN (4–12 digits)',
+      newPin: 'New PIN (4–12 digits)',
+      confirmPin: 'Repeat the PIN',
+      set: 'Set PIN',
+      change: 'Change PIN',
+      saved: 'PIN saved',
+      mismatch: 'The PINs do not match.',
+      clear: 'Remove PIN',
+      clearConfirm: 'Remove the PIN? The app will open without it.',
+      cleared: 'PIN removed',
+    },
+  },
+  appMode: {
+    locked: 'Read-only: the database is in use by another program or another Pausaler window. You can browse, but changes won\'t be saved.',
+    readOnlyFile: 'Read-only: the database file can\'t be written (read-only folder or network share). You can browse, but changes won\'t be saved.',
+    retry: 'Try again',
+    stillReadOnly: 'The database still can\'t be written.',
+    writable: 'Changes can be saved again.',
+  },
+  search: {
+    open: 'Search',
+    placeholder: 'Search invoices, clients and expenses…',
+    empty: 'Nothing found',
+    kinds: {
+      invoice: 'Invoice',
+      client: 'Client',
+      expense: 'Expense',
+    },
+  },
   app: {
     brand: 'Invoicing',
     title: 'Invoicing System',
@@ -47,11 +92,129 @@ export const en = {
     dateFrom: 'From',
     dateTo: 'To',
     totalCount: 'Total {{count}} invoices',
+    years: {
+      count: '{{count}} invoices',
+      unknown: 'No issue date',
+    },
     empty: 'No invoices',
     createFirst: 'Create first invoice',
     deletedSuccess: 'Invoice deleted successfully',
     notFound: 'Invoice not found',
     deleteConfirm: 'Are you sure you want to delete this invoice?',
+    importedTag: 'Imported',
+    import: {
+      open: 'Import from CSV',
+      title: 'Import invoices from another tool (CSV)',
+      help: 'One row per invoice item; rows with the same invoice number become one invoice. Original numbers are kept and missing clients are created.',
+      pickFile: 'Choose CSV file',
+      changeFile: 'Choose another file',
+      notMapped: 'Not mapped',
+      required: 'Choose the matching column',
+      defaultCurrency: 'Currency when the file has none',
+      preview: 'Preview',
+      run: 'Import',
+      back: 'Back',
+      failed: 'Import failed, nothing was imported: {{message}}',
+      summary: 'Imported: {{imported}}, skipped: {{skipped}}, duplicates: {{duplicates}}, new clients: {{clients}}',
+      previewSummary:
+        'Would import: {{imported}}, skipped: {{skipped}}, duplicates: {{duplicates}}, new clients: {{clients}}',
+      line: 'Line',
+      items: 'Items',
+      newClient: 'new',
+      status: 'Status',
+      message: 'Note',
+      statuses: {
+        imported: 'Imported',
+        skipped: 'Skipped',
+        duplicate: 'Duplicate',
+      },
+      fields: {
+        invoiceNumber: 'Invoice number',
+        issueDate: 'Issue date',
+        clientName: 'Client name',
+        clientPib: 'Client PIB',
+        itemDescription: 'Item description',
+        itemQuantity: 'Quantity',
+        itemUnit: 'Unit',
+        itemUnitPrice: 'Unit price',
+        itemTotal: 'Item total',
+        serviceDate: 'Service date',
+        dueDate: 'Due date',
+        paidAt: 'Payment date',
+        currency: 'Currency',
+        status: 'Status',
+        notes: 'Notes',
+      },
+    },
+    fileImport: {
+      open: 'Import invoice file',
+      title: 'Import an invoice file (JSON)',
+      help: 'An invoice exported with "Export JSON", for example from another computer. The client is matched by PIB or created.',
+      pickFile: 'Choose JSON file',
+      changeFile: 'Choose another file',
+      keepNumber: 'Keep the invoice number from the file',
+      run: 'Import',
+      renumber: 'Import with a new number',
+      conflict: 'An invoice with this number already exists',
+      conflictHelp: 'Nothing was imported. You can import the invoice under the next free number instead.',
+      done: 'Invoice {{number}} imported',
+      doneWithClient: 'Invoice {{number}} imported, its client was added',
+      failed: 'Import failed: {{message}}',
+    },
+    suggestions: {
+      title: 'Status suggestions ({{count}})',
+      today: 'today',
+      applied: 'Invoice {{number}} updated',
+      reasons: {
+        STALE_DRAFT: 'Unsent draft',
+        OVERDUE: 'Overdue',
+        PAID_WITHOUT_DATE: 'No payment date',
+      },
+      details: {
+        STALE_DRAFT: 'draft for {{count}} days',
+        OVERDUE: '{{count}} days past due',
+        PAID_WITHOUT_DATE: 'marked paid without a payment date',
+      },
+      actions: {
+        SEND: 'Send',
+        CANCEL: 'Cancel invoice',
+        SEND_REMINDER: 'Send reminder',
+        MARK_PAID: 'Mark paid ({{date}})',
+        SET_PAID_AT: 'Set paid on {{date}}',
+      },
+    },
+    payments: {
+      open: 'Import payments',
+      title: 'Import incoming payments (bank statement CSV)',
+      help: 'Each incoming payment is matched to a sent invoice by its payment reference, or by the outstanding amount and the client name. Only clear matches are recorded; the rest are listed below for you to assign.',
+      pickFile: 'Choose CSV file',
+      changeFile: 'Choose another file',
+      notMapped: 'Not mapped',
+      required: 'Choose the matching column',
+      run: 'Import',
+      failed: 'Import failed, no payments were recorded: {{message}}',
+      summary:
+        'Recorded: {{matched}}, to review: {{ambiguous}}, unmatched: {{unmatched}}, already recorded: {{duplicates}}, skipped: {{skipped}}',
+      matched: 'Recorded',
+      ambiguous: 'To review',
+      unmatched: 'No matching invoice',
+      date: 'Date',
+      invoice: 'Invoice',
+      pickInvoice: 'Choose invoice',
+      apply: 'Record',
+      matchedBy: {
+        reference: 'By reference',
+        amountAndName: 'By amount and payer',
+        manual: 'Chosen manually',
+      },
+      fields: {
+        date: 'Date',
+        amount: 'Amount (credit)',
+        reference: 'Payment reference',
+        payer: 'Payer',
+        currency: 'Currency',
+      },
+    },
   },
   invoiceStatus: {
     DRAFT: 'Draft',
@@ -62,9 +225,26 @@ export const en = {
     OVERDUE_DAYS: 'Overdue by {{days}} days',
   },
   invoiceView: {
+    billExpenses: {
+      open: 'Bill expenses',
+      title: 'Bill expenses to this invoice',
+      run: 'Add to invoice',
+      empty: 'No unbilled expenses in {{currency}}',
+      done: '{{count}} expense(s) added to the invoice',
+    },
+    billTime: {
+      open: 'Bill time',
+      title: 'Bill time to this invoice',
+      run: 'Add to invoice',
+      empty: 'No unbilled time for this client',
+      done: '{{count}} time entries added to the invoice',
+    },
     back: 'Back to list',
     edit: 'Edit',
     exportPdf: 'Export PDF',
+    exportJson: 'Export JSON',
+    jsonExported: 'Invoice saved to {{path}}',
+    jsonError: 'Error exporting the invoice',
     sendEmail: 'Send email',
     missingItems: 'Invoice has no items',
     missingCompany: 'Company details are missing in Settings',
@@ -92,16 +272,71 @@ export const en = {
     subject: 'Subject',
     body: 'Message (optional)',
     includePdf: 'Attach PDF',
+    includeXml: 'Attach XML e-invoice (UBL)',
     cancel: 'Cancel',
     send: 'Send',
     defaultSubject: 'Invoice {{invoiceNumber}}',
     missingClientEmail: 'Client has no email address',
     smtpNotConfigured: 'SMTP settings are not configured (Settings → Email)',
     sent: 'Email sent',
+    queued: 'Email queued for sending',
     sendError: 'Error sending email',
     toReq: 'Enter recipient email',
     subjectReq: 'Enter subject',
   },
+  catalog: {
+    open: 'Catalog',
+    title: 'Products and services',
+    itemsTab: 'Items',
+    usageTab: 'Usage',
+    empty: 'No catalog items yet',
+    add: 'Add item',
+    name: 'Name',
+    namePlaceholder: 'e.g. Website development',
+    nameReq: 'Enter a name',
+    priceReq: 'Enter a price',
+    vatRate: 'VAT',
+    vatRateHelp: 'Only inside the VAT system',
+    active: 'Active',
+    inactive: 'Inactive',
+    saved: 'Catalog item saved',
+    deleteTitle: 'Delete catalog item?',
+    deleteDesc: 'Invoices that used "{{name}}" keep their lines; the item only disappears from the catalog.',
+    deleted: 'Catalog item deleted',
+    usageEmpty: 'No sent or paid invoice uses catalog items in this period',
+    lines: 'Lines',
+    invoices: 'Invoices',
+    averagePrice: 'Average price',
+    billed: 'Billed',
+    lastBilled: 'Last billed',
+    pick: 'From catalog',
+  },
+  time: {
+    open: 'Time',
+    title: 'Time tracking',
+    start: 'Start',
+    stop: 'Stop',
+    recorded: 'Recorded {{duration}} h',
+    client: 'Client',
+    clientReq: 'Choose a client',
+    description: 'Description',
+    hourlyRate: 'Hourly rate',
+    rateReq: 'Enter the hourly rate',
+    date: 'Date',
+    duration: 'Duration',
+    hours: 'Hours',
+    minutes: 'Minutes',
+    add: 'Log time',
+    empty: 'No time entries',
+    billed: 'Billed',
+    unbilledOnly: 'Unbilled only',
+    entriesTab: 'Entries',
+    unbilledTab: 'Unbilled time',
+    unbilledEmpty: 'All logged time has been billed',
+    entries: 'Entries',
+    amount: 'Amount',
+    oldest: 'Oldest',
+  },
   newInvoice: {
     titleNew: 'New invoice',
     titleEdit: 'Edit invoice',
@@ -123,8 +358,16 @@ export const en = {
     emptyItems: 'Add invoice items',
     notes: 'Notes',
     notesPlaceholder: 'Enter additional notes…',
+    issuedBy: 'Issued by',
+    addCustomField: 'Add field (PO number, cost center, …)',
+    customFieldLabel: 'Label',
+    customFieldValue: 'Value',
+    customFieldTooLong: 'At most {{max}} characters',
     summary: 'Summary',
     subtotal: 'Subtotal',
+    invoiceDiscount: 'Invoice discount',
+    invoiceDiscountPercent: 'Invoice discount (%)',
+    invoiceDiscountTooLarge: 'The invoice discount cannot exceed the invoice total.',
     total: 'TOTAL',
     description: 'Description',
     descriptionPlaceholder: 'Enter service description…',
@@ -135,6 +378,8 @@ export const en = {
     lineTotal: 'Total',
     selectClient: 'Select client',
     selectCurrency: 'Select currency',
+    exchangeRate: 'Exchange rate ({{defaultCurrency}} per 1 {{currency}})',
+    exchangeRateHelp: 'NBS middle rate on the issue date; used for the KPO book and turnover reports.',
     issueDate: 'Issue date',
     serviceDate: 'Service date',
     invoiceNumberPreviewLabel: 'Invoice number',
@@ -147,6 +392,8 @@ export const en = {
     statusTitle: 'Status',
     statusLicensed: 'Licensed',
     statusLicensedUntil: 'Licensed until {{until}}',
+    statusGrace: 'The license has expired. Everything keeps working for {{days}} more days; renew it before then.',
+    expiring: 'Your license expires in {{days}} days. Renew it on the License page.',
     typeLifetime: 'No-limit / Lifetime',
     statusTrial: 'Trial active ({{days}} days remaining)',
     statusTrialUnknown: 'Trial active',
@@ -168,6 +415,37 @@ export const en = {
     activate: 'Activate',
     activated: 'License activated',
     invalidLicense: 'Invalid license',
+    reasons: {
+      expired: 'This license has expired.',
+      pib_mismatch: 'This license was issued for a different PIB.',
+      not_yet_valid: 'This license is not valid yet.',
+      invalid_format: 'This is not a license string.',
+      invalid_encoding: 'The license is damaged. Copy it again without changes.',
+      invalid_signature_length: 'The license is damaged. Copy it again without changes.',
+      invalid_payload: 'The license is damaged. Copy it again without changes.',
+      app_mismatch: 'This license was issued for a different application.',
+      clock_tampering: 'The computer clock is behind. Correct the date and time to use the license.',
+      license_invalid: 'Invalid license',
+    },
+    transferTitle: 'Transfer license',
+    transferHelp: 'Changed your PIB? Deactivate the license here and send the receipt to the vendor together with a new activation code. You will receive a license of the same type and validity for the new PIB.',
+    deactivate: 'Deactivate license',
+    deactivateConfirmTitle: 'Deactivate the license?',
+    deactivateConfirmBody: 'The license is removed from this computer. Keep the receipt: the vendor needs it to issue the replacement.',
+    deactivated: 'License deactivated',
+    deactivateError: 'Failed to deactivate the license',
+    receiptLabel: 'Deactivation receipt',
+    featureLocked: '{{feature}} is not included in your license. It is available with the {{plan}} license.',
+    features: {
+      emailSending: 'Sending email',
+      batchExport: 'Period archive export',
+    },
+    plans: {
+      none: 'No license',
+      trial: 'Trial',
+      yearly: 'Yearly',
+      lifetime: 'Lifetime',
+    },
     lockedTitle: 'App locked',
     lockedDescription: 'Your trial has expired or a license is required to unlock editing, exports, and email.',
     openLicense: 'Open License',
@@ -177,7 +455,7 @@ export const en = {
     trialUnknown: 'Unknown',
     trialEndsAt: 'Trial ends at',
     trialStartsNowTitle: 'Trial and activation',
-    trialStartsNowDesc: 'After saving, your 7-day trial starts. You can generate the activation code anytime.',
+    trialStartsNowDesc: 'Your 30-day trial runs from the first launch. You can generate the activation code anytime.',
     sendEmailTitle: 'License request (email)',
     sendEmailHelp: 'Send your activation code to the vendor to receive a license (no attachments).',
     sendEmail: 'Send email',
@@ -230,6 +508,80 @@ export const en = {
     postalCodeInvalid: 'Postal code can contain digits and -',
     postalCodePlaceholder: '11000',
     email: 'Email',
+    phone: 'Phone',
+    website: 'Website',
+    websiteInvalid: 'Enter a valid URL (https://…)',
+    duplicateTitle: 'Client already exists',
+    duplicateContent: '"{{name}}" has the same {{field}}. Create another client anyway?',
+    duplicateSelected: '"{{name}}" already has the same {{field}}; the existing client was selected.',
+    createAnyway: 'Create anyway',
+    renameTitle: 'Client renamed',
+    renameContent:
+      'Existing invoices show the name "{{from}}". Update them to "{{to}}" as well? Issued invoices usually keep the name they were issued under.',
+    renameInvoices: 'Update invoices',
+    renameKeep: 'Keep old name on invoices',
+    findDuplicates: 'Find duplicates',
+    duplicatesTitle: 'Possible duplicate clients',
+    noDuplicates: 'No duplicate clients found.',
+    mergeInto: 'Merge into "{{name}}"',
+    mergeConfirm: 'Move all invoices of "{{remove}}" to "{{keep}}" and delete "{{remove}}"?',
+    merged: 'Clients merged into "{{name}}"; invoices moved: {{count}}.',
+    deleteBlockedTitle: 'Client has invoices',
+    deleteBlockedContent: 'This client is used on {{count}} invoice(s). If you delete it, the invoices keep the client name but lose the link to the client. Delete anyway?',
+    deleteAnyway: 'Delete anyway',
+    importCsv: 'Import CSV',
+    isForeign: 'Foreign client (no Serbian PIB / MB)',
+    country: 'Country (ISO code)',
+    countryReq: 'Enter the country code',
+    countryInvalid: 'Use a 2-letter code, e.g. DE',
+    foreignVatId: 'VAT ID',
+    archive: 'Archive',
+    unarchive: 'Unarchive',
+    archived: 'Client archived',
+    unarchived: 'Client restored',
+    archivedTag: 'Archived',
+    showArchived: 'Show archived',
+    defaultsTitle: 'Invoice defaults',
+    defaultCurrency: 'Default currency',
+    preferredLanguage: 'Invoice language',
+    langBilingual: 'Bilingual (sr / en)',
+    useSettingsDefault: 'As in settings',
+    paymentTermsDays: 'Payment terms (days)',
+    billingEmail: 'Billing email',
+    billingEmailHelp: 'Invoices are emailed here; the contact email is used when empty.',
+    import: {
+      title: 'Import clients from CSV',
+      pickFile: 'Choose CSV file',
+      changeFile: 'Choose another file',
+      notMapped: 'Not imported',
+      nameRequired: 'Choose the column with the client name',
+      run: 'Import',
+      failed: 'Import failed, nothing was imported: {{message}}',
+      summary: 'Imported: {{imported}}, skipped: {{skipped}}, duplicates: {{duplicates}}',
+      line: 'Line',
+      status: 'Status',
+      message: 'Note',
+      statuses: {
+        imported: 'Imported',
+        skipped: 'Skipped',
+        duplicate: 'Duplicate',
+      },
+      fields: {
+        name: 'Name',
+        pib: 'PIB',
+        registrationNumber: 'Registration number (MB)',
+        address: 'Address',
+        postalCode: 'Postal code',
+        city: 'City',
+        email: 'Email',
+        phone: 'Phone',
+      },
+    },
+    duplicateMatch: {
+      pib: 'PIB',
+      registrationNumber: 'registration number (MB)',
+      name: 'name',
+    },
     deleteTitle: 'Delete client',
     deleteDesc: 'Are you sure you want to delete this client?',
     deleted: 'Client deleted successfully',
@@ -252,6 +604,7 @@ export const en = {
     title: 'Settings',
     saved: 'Settings saved successfully',
     saveError: 'Error saving settings',
+    problemsTitle: 'Invoices cannot be issued until these settings are fixed',
     companyCard: 'Company details',
     companyName: 'Company name',
     companyNameReq: 'Enter company name',
@@ -262,6 +615,20 @@ export const en = {
     vatReq: 'Enter VAT ID',
     bankAccount: 'Bank account',
     bankReq: 'Enter bank account',
+    bankAccounts: {
+      title: 'Bank accounts',
+      help: 'Invoices show the account kept in the invoice currency, otherwise the default one. Foreign-currency invoices also show IBAN and SWIFT.',
+      accountNumber: 'Account number',
+      currency: 'Currency',
+      bankName: 'Bank',
+      iban: 'IBAN',
+      ibanHelp: 'For payments from abroad.',
+      swift: 'SWIFT/BIC',
+      isDefault: 'Default account',
+      add: 'Add account',
+      remove: 'Remove',
+      numberOrIbanReq: 'Enter an account number or IBAN',
+    },
     address: 'Address',
     addressReq: 'Enter address',
     addressPlaceholder: 'Street and number, postal code, city',
@@ -294,6 +661,49 @@ export const en = {
     defaultCurrency: 'Default currency',
     currencyReq: 'Select currency',
     example: 'Invoice number example',
+    turnoverLimitRsd: 'Paušal yearly turnover limit',
+    vatLimitRsd: 'VAT registration threshold',
+    limitsHelp: 'Used for the turnover warnings; update them when the law changes.',
+    roundingMode: 'Rounding of amounts',
+    roundingModeHelp: 'Applies to new invoices; issued invoices keep the rounding they were created with.',
+    roundingModes: { halfUp: 'Half up (0.005 → 0.01)', halfEven: 'Half to even', down: 'Down (truncate)' },
+    quotePrefix: 'Quote prefix',
+    nextQuoteNumber: 'Next quote number',
+    issuedBy: 'Issued by',
+    issuedByHelp: 'Printed in the signature area of new invoices; each invoice can name someone else.',
+    issuedByPlaceholder: 'First and last name',
+    customFieldLabels: 'Custom invoice fields',
+    customFieldLabelsHelp: 'Labels new invoices offer as extra rows (e.g. PO number, cost center); rows left empty are not printed.',
+    customFieldLabelsPlaceholder: 'Type a label and press Enter',
+    customFieldLabelsMax: 'At most {{max}} fields',
+    staleDraftDays: 'Suggest sending drafts after (days)',
+    staleDraftDaysHelp: 'Drafts this old are listed on the Invoices page with "Send" and "Cancel".',
+    overdueGraceDays: 'Overdue grace period (days)',
+    overdueGraceDaysHelp: 'Sent invoices this many days past due are listed for a reminder or marking paid.',
+    pdfFilenameTemplate: 'PDF file name',
+    pdfFilenameTemplateHelp:
+      'Used for exported invoices and email attachments. Placeholders: {number}, {client}, {year}, {month}, {issueDate}; "/" sorts exports into folders (attachments keep only the file name).',
+    pdfFilenameTemplateReq: 'Enter a file name template',
+    csv: {
+      title: 'CSV exports',
+      help: 'Format of exported CSV files. Excel with Serbian regional settings expects a semicolon, decimal comma and BOM.',
+      delimiter: 'Delimiter',
+      delimiters: { comma: 'Comma (,)', semicolon: 'Semicolon (;)', tab: 'Tab', pipe: 'Pipe (|)' },
+      lineEnding: 'Line ending',
+      lineEndings: { crlf: 'Windows (CRLF)', lf: 'Unix (LF)' },
+      decimalComma: 'Decimal comma',
+      includeBom: 'UTF-8 BOM',
+      includeBomHelp: 'Lets Excel detect the encoding so č, ć, š, ž and đ display correctly.',
+    },
+    notes: {
+      defaultNotes: 'Default invoice notes',
+      defaultNotesHelp: 'Used for new invoices created without notes.',
+      legalTitle: 'Legal note',
+      legalHelp: 'Replaces the built-in legal note on invoices. {INVOICE_NUMBER} is replaced with the invoice number; leave empty to keep the built-in text.',
+      legal: { sr: 'Serbian text', en: 'English text' },
+      legalPlaceholder: 'Built-in text',
+      preview: 'Preview',
+    },
     save: 'Save settings',
     languageCard: 'Language',
     language: 'Language',
@@ -312,6 +722,14 @@ export const en = {
     smtpPasswordPlaceholder: 'password',
     smtpFrom: 'From address',
     smtpFromPlaceholder: 'invoices@yourcompany.com',
+    smtpFromName: 'Sender name',
+    smtpFromNamePlaceholder: 'Your company name',
+    smtpReplyTo: 'Reply-To address',
+    smtpReplyToPlaceholder: 'office@yourcompany.com',
+    smtpReplyToInvalid: 'Enter a valid email address',
+    accountantEmail: 'Accountant email',
+    accountantEmailHelp: 'Reports sent from Exports → "Email to accountant" go to this address.',
+    accountantEmailPlaceholder: 'accountant@office.com',
     smtpTlsMode: 'TLS mode',
     smtpTlsModeHelp: 'Port 465 uses implicit TLS (SMTPS). Port 587 uses STARTTLS.',
     smtpTlsModeImplicit: 'Implicit TLS (465)',
@@ -320,6 +738,47 @@ export const en = {
     smtpTlsModeMismatch465: 'Port 465 requires Implicit TLS (SMTPS).',
     smtpTlsModeMismatch587: 'Port 587 requires STARTTLS.',
     smtpUseTls: 'Use TLS',
+    smtpAuthMode: 'Authentication',
+    smtpAuthModePassword: 'Username and password',
+    smtpAuthModeOauth2: 'OAuth2 (Gmail, Office 365)',
+    smtpOauthClientId: 'OAuth2 client ID',
+    smtpOauthClientSecret: 'OAuth2 client secret',
+    smtpOauthRefreshToken: 'OAuth2 refresh token',
+    smtpOauthTokenEndpoint: 'Token endpoint',
+    smtpOauthTokenEndpointInvalid: 'Token endpoint must start with https://',
+    smtpOauthSecretPlaceholder: 'Leave blank to keep the saved value',
+    smtpOauthConfigured: 'A refresh token is saved.',
+    smtpPinnedCertPem: 'Pinned server certificate (PEM)',
+    smtpPinnedCertPemHelp: 'For servers with a self-signed or internal CA certificate. When set, only this certificate is trusted.',
+    smtpAcceptInvalidCerts: 'Accept invalid certificates',
+    smtpAcceptInvalidCertsHelp: 'Disables certificate verification. Allowed only for private network hosts unless you confirm below.',
+    smtpAcceptInvalidCertsAcknowledged: 'I understand that the connection can be intercepted',
+    smtpTrustPath: {
+      systemRoots: 'system certificates',
+      pinnedCertificate: 'pinned certificate',
+      acceptInvalidCerts: 'certificate not verified',
+      noTls: 'no TLS',
+    },
+    sendCopyToSelf: 'Send me a copy',
+    sendCopyToSelfHelp: 'Invoice emails are also sent (BCC) to the From address.',
+    emailMaxMessageMb: 'Maximum email size',
+    emailMaxMessageMbHelp: 'Larger invoice emails are not sent, so the mail server does not reject them. Attachments grow by about a third when sent.',
+    emailTheme: {
+      title: 'Email colors',
+      help: 'Colors of the invoice email. Mail clients in dark mode that support it use the dark colors; the others always show the light ones.',
+      light: 'Light',
+      dark: 'Dark',
+      reset: 'Restore default colors',
+      roles: {
+        background: 'Background',
+        card: 'Card',
+        border: 'Borders',
+        accent: 'Title and total',
+        text: 'Text',
+        muted: 'Labels',
+        faint: 'Footer',
+      },
+    },
     emailHelp: {
       title: 'How to configure email sending?',
       intro: 'These settings allow the app to send invoices by email automatically.',
@@ -371,9 +830,48 @@ export const en = {
       testButton: 'Test email settings',
       testHelp: 'Sends a test email to your company email address.',
       testSuccess: 'Test email sent successfully.',
+      testSuccessTrust: 'Test email sent successfully (certificate trust: {{trust}}).',
       testError: 'Failed to send test email: {{message}}',
     },
 
+    diagnostics: {
+      title: 'Diagnostics',
+      help: 'Recent entries from the app log. Passwords and license keys are never written to it.',
+      level: 'Log level',
+      show: 'Show',
+      levels: { error: 'Errors', warn: 'Warnings', info: 'Info', debug: 'Debug', trace: 'Trace' },
+      all: 'All',
+      refresh: 'Refresh',
+      copy: 'Copy',
+      copied: 'Log copied to clipboard.',
+      levelSaved: 'Log level set to {{level}}.',
+      empty: 'No log entries.',
+      time: 'Time',
+      source: 'Source',
+      message: 'Message',
+    },
+    webhooks: {
+      tab: 'Webhooks',
+      help: 'Pausaler can notify your own dashboard or automation about invoice events: a signed HTTP POST is sent when an invoice is created, its status changes, or it is emailed. Events wait in a queue while the endpoint is unreachable.',
+      url: 'Webhook URL',
+      urlHelp: 'Leave empty to turn webhooks off.',
+      secret: 'Shared secret',
+      secretHelp: 'Each request carries X-Pausaler-Signature: sha256=<HMAC-SHA256 of the body>.',
+      secretSaved: 'A secret is saved; leave blank to keep it.',
+      test: 'Send test event',
+      testHelp: 'Uses the saved URL and secret.',
+      testOk: 'The endpoint answered HTTP {{status}}.',
+      testFailed: 'Test failed: {{error}}',
+      deliveries: 'Recent deliveries',
+      empty: 'No events sent yet',
+      createdAt: 'Time',
+      event: 'Event',
+      status: 'Status',
+      attempts: 'Attempts',
+      detail: 'Details',
+      nextAttempt: 'next attempt {{at}}',
+      statuses: { PENDING: 'Pending', DELIVERED: 'Delivered', FAILED: 'Failed' },
+    },
     updates: {
       tab: 'About / Updates',
       currentVersion: 'Current version',
@@ -420,6 +918,112 @@ export const en = {
       restoreStaged: 'Restore staged. The app will exit now. Start it again to apply the restore.',
       restoreStageError: 'Failed to stage restore',
       restoreAppliedSuccess: 'Backup restore applied successfully.',
+      archive: {
+        title: 'Data archive (JSON)',
+        help: 'Everything in one readable JSON file, for moving to another computer. The SMTP password is not included.',
+        exportButton: 'Export JSON…',
+        importButton: 'Import JSON…',
+        exported: 'Data exported: {{path}}',
+        mode: 'Import mode',
+        modes: {
+          merge: 'Merge with current data',
+          replace: 'Replace current data',
+        },
+        conflict: 'Existing IDs',
+        conflicts: {
+          skip: 'Skip',
+          duplicate: 'Import as copies',
+        },
+        replaceConfirm: 'All clients, invoices, expenses, offers and settings will be replaced by the archive. Continue?',
+        imported: 'Imported: {{inserted}} new, {{duplicated}} copies, {{skipped}} skipped.',
+        importError: 'Import failed: {{message}}',
+      },
+      settingsFile: {
+        title: 'Settings file',
+        help: 'Company, invoice and email settings with the logo, without any data. Use it to set up Pausaler on another computer.',
+        includeSmtp: 'Include SMTP login',
+        includeSmtpWarning: 'The SMTP password and OAuth tokens will be written to the file in plain text. Keep the file private.',
+        exportButton: 'Export settings…',
+        importButton: 'Import settings…',
+        exported: 'Settings exported: {{path}}',
+        importConfirm: 'Current settings will be overwritten by the file. Settings missing from the file are kept. Continue?',
+        imported: 'Settings imported.',
+        importError: 'Import failed: {{message}}',
+      },
+      auto: {
+        title: 'Automatic backups',
+        enabled: 'Back up on startup',
+        enabledHelp: 'A copy of the database is saved when the app starts, at most once a day.',
+        keep: 'Backups to keep',
+        file: 'File',
+        sizeKb: 'Size (KB)',
+        empty: 'No automatic backups yet',
+        restore: 'Restore',
+        restoreConfirm: 'Restore this backup? The app will close and the current data is replaced on next start.',
+        failed: 'Automatic backup failed: {{message}}',
+      },
+      consistency: {
+        title: 'Data consistency',
+        help: 'Checks that invoice and client records agree with their stored documents, and repairs differences.',
+        check: 'Check',
+        ok: 'No differences in {{invoices}} invoices and {{clients}} clients.',
+        found: '{{count}} differences found.',
+        unreadable: '{{count}} records cannot be read and are missing from the lists.',
+        corruptNotice: 'Record "{{label}}" cannot be read and is missing from the list. Repair it under Settings → Data consistency.',
+        corrupt: {
+          label: 'Number / name',
+          error: 'Error',
+          repair: 'Repair',
+          title: 'Repair "{{label}}"',
+          help: 'The record is rebuilt from its indexed fields, keeping whatever is still readable in the old document. The old document is kept in the audit log.',
+          patch: 'Corrections (optional JSON object, e.g. {"notes": "..."})',
+          repaired: 'Record repaired.',
+          repairedDropped: 'Record repaired; these fields could not be kept: {{keys}}.',
+        },
+        table: 'Table',
+        record: 'Record',
+        field: 'Field',
+        column: 'Column',
+        json: 'Document',
+        repairFromJson: 'Repair from documents',
+        repairFromColumns: 'Repair from columns',
+        repairConfirm: 'Rewrite the differing values? Old values are kept in the audit log.',
+        repaired: 'Repaired {{fields}} values in {{rows}} records.',
+      },
+      health: {
+        title: 'Database health',
+        help: 'Checks the database file for damage and can compact it to reclaim unused space.',
+        check: 'Check',
+        compact: 'Compact database',
+        compactConfirm: 'Compact the database now? Other changes wait until it finishes.',
+        ok: 'Integrity check passed.',
+        corrupted: 'The database file is damaged. Do not keep working with it; restore a recent backup.',
+        dbSize: 'Database size',
+        walSize: 'Journal (WAL) size',
+        compacted: 'Database compacted: {{before}} → {{after}}.',
+        checkpointBusy: 'The journal could not be fully merged; try again later.',
+        checkedAt: 'Checked',
+      },
+      location: {
+        title: 'Database location',
+        help: 'The app always opens this file. Moving it copies and verifies the data first; the old file is kept as .bak.',
+        path: 'File',
+        size: 'Size',
+        source: 'Chosen from',
+        sources: {
+          appData: 'App data folder',
+          appLocalData: 'Local app data folder',
+          exeDir: 'Program folder',
+          currentDir: 'Working folder',
+          moved: 'Moved by you',
+        },
+        othersFound: 'Using {{path}}; {{count}} other database file(s) were found and are ignored. See Settings → Backup.',
+        othersTitle: 'Other database files found (not used):',
+        move: 'Move database…',
+        pickFolder: 'Choose a folder for the database',
+        moveConfirm: 'Move the database to {{path}}?',
+        moved: 'Database moved. The previous file was kept as {{path}}.',
+      },
     },
   },
   setup: {
@@ -429,6 +1033,18 @@ export const en = {
       button: 'Restore from backup…',
     },
   },
+  limits: {
+    title: 'Turnover limits ({{year}})',
+    turnover: 'Paušal limit',
+    vat: 'VAT threshold',
+    usage: '{{used}} of {{limit}} RSD',
+    projected: 'Projected at year end: {{percent}}%',
+    unconverted: 'Not counted (missing exchange rate): {{invoices}}',
+    warning: {
+      turnover: 'Yearly turnover reached {{percent}}% of the paušal limit ({{turnover}} of {{limit}} RSD).',
+      vat: 'Yearly turnover reached {{percent}}% of the VAT threshold ({{turnover}} of {{limit}} RSD).',
+    },
+  },
   overview: {
     title: 'Overview',
     scopeMonth: 'This Month',
@@ -443,6 +1059,14 @@ export const en = {
     tableMonth: 'Month',
     tableIssued: 'Issued',
     tablePaid: 'Paid',
+    topClients: 'Top clients (paid)',
+    topItems: 'Top items (paid)',
+    topClient: 'Client',
+    topItem: 'Item',
+    topInvoices: 'Invoices',
+    topRevenue: 'Revenue',
+    topShare: 'Share',
+    topEmpty: 'No paid invoices in this period',
   },
   reports: {
     title: 'Reports',
@@ -450,6 +1074,40 @@ export const en = {
     tabs: {
       monthly: 'Monthly',
       yearly: 'Yearly',
+      receivables: 'Receivables',
+      tax: 'Tax',
+    },
+    tax: {
+      title: 'Quarterly tax summary',
+      note: 'Income is counted by payment date, expenses by their date. Amounts are in {{currency}}; foreign invoices are converted with their exchange rate.',
+      period: 'Period',
+      income: 'Paid income',
+      expenses: 'Expenses',
+      base: 'Base',
+      empty: 'No paid invoices or expenses in this year',
+      exportCsv: 'Export CSV',
+      unconvertedTitle: 'Not converted (no exchange rate)',
+      unconvertedNote: 'These amounts are not part of the base. Add an exchange rate to the invoice to include it.',
+      kind: 'Type',
+      kinds: {
+        income: 'Income',
+        expense: 'Expense',
+      },
+    },
+    receivables: {
+      title: 'Outstanding receivables',
+      asOf: 'Days past due as of {{date}}. Invoices without a due date use the client payment terms (or 15 days).',
+      client: 'Client',
+      invoices: 'Invoices',
+      empty: 'No unpaid sent invoices',
+      exportCsv: 'Export CSV',
+      buckets: {
+        current: 'Not due',
+        days1To30: '1–30 days',
+        days31To60: '31–60 days',
+        days61To90: '61–90 days',
+        daysOver90: '90+ days',
+      },
     },
     kpi: {
       issued: 'Issued',
@@ -504,12 +1162,105 @@ export const en = {
     deleteTitle: 'Delete expense',
     deleteDesc: 'Are you sure you want to delete this expense?',
     deleted: 'Expense deleted successfully',
+    restored: 'Expense restored',
     notFound: 'Expense not found',
     updated: 'Expense updated successfully',
     created: 'Expense added successfully',
     modalEdit: 'Edit expense',
     modalAdd: 'Add expense',
     update: 'Update',
+    duplicate: 'Duplicate (dated today)',
+    duplicated: 'Expense duplicated',
+    split: {
+      open: 'Split',
+      title: 'Split expense',
+      addPart: 'Add part',
+      remaining: 'Left to allocate: {{amount}} {{currency}}',
+      run: 'Split',
+      done: 'Expense split into {{count}} parts',
+    },
+    import: {
+      open: 'Import from bank CSV',
+      title: 'Import expenses from bank statement CSV',
+      pickFile: 'Choose CSV file',
+      changeFile: 'Choose another file',
+      notMapped: 'Not mapped',
+      required: 'Choose the matching column',
+      signedAmounts: 'Single signed amount column (debits are negative)',
+      defaultCurrency: 'Currency when the file has none',
+      preview: 'Preview',
+      run: 'Import',
+      back: 'Back',
+      failed: 'Import failed, nothing was imported: {{message}}',
+      summary: 'Imported: {{imported}}, skipped: {{skipped}}, duplicates: {{duplicates}}',
+      previewSummary: 'Would import: {{imported}}, skipped: {{skipped}}, duplicates: {{duplicates}}',
+      line: 'Line',
+      status: 'Status',
+      message: 'Note',
+      statuses: {
+        imported: 'Imported',
+        skipped: 'Skipped',
+        duplicate: 'Duplicate',
+      },
+      fields: {
+        date: 'Date',
+        title: 'Description',
+        amount: 'Debit amount',
+        credit: 'Credit amount',
+        currency: 'Currency',
+        category: 'Category',
+      },
+    },
+    recurring: {
+      open: 'Recurring',
+      title: 'Recurring expenses',
+      empty: 'No recurring expenses',
+      add: 'Add schedule',
+      interval: 'Repeats',
+      intervals: {
+        weekly: 'Weekly',
+        monthly: 'Monthly',
+        quarterly: 'Quarterly',
+        yearly: 'Yearly',
+      },
+      startDate: 'First date',
+      endDate: 'Until (optional)',
+      nextRunDate: 'Next',
+      created: 'Schedule added',
+      deleted: 'Schedule deleted',
+      deleteTitle: 'Delete schedule',
+      deleteDesc: 'Expenses it already created are kept.',
+      deleteFuture: 'Also delete its expenses dated after today',
+      generated: '{{count}} recurring expense(s) added',
+    },
+    obligations: {
+      open: 'Taxes & contributions',
+      title: 'Taxes and contributions',
+      year: 'Year',
+      month: 'Month',
+      status: 'Status',
+      pending: 'Not generated',
+      noDecision: 'No tax decision',
+      generatedTag: 'Generated',
+      paid: 'Paid',
+      generate: 'Generate expenses',
+      markPaid: 'Mark as paid',
+      markUnpaid: 'Mark as unpaid',
+      generated: '{{count}} expense(s) added',
+      decisions: 'Tax decisions (rešenje)',
+      empty: 'Enter the monthly amounts from your tax decision',
+      add: 'Add tax decision',
+      validFrom: 'Valid from',
+      validTo: 'Valid until (optional)',
+      tax: 'Income tax',
+      pension: 'Pension (PIO)',
+      health: 'Health insurance',
+      unemployment: 'Unemployment',
+      notesPlaceholder: 'e.g. decision number',
+      created: 'Tax decision added',
+      deleteTitle: 'Delete tax decision',
+      deleteDesc: 'Expenses already generated from it are kept.',
+    },
   },
 
   offers: {
@@ -548,6 +1299,45 @@ export const en = {
     SENT: 'Sent',
     FAILED: 'Failed',
   },
+  quotes: {
+    title: 'Quotes',
+    add: 'New quote',
+    empty: 'No quotes yet',
+    number: 'Number',
+    client: 'Client',
+    clientReq: 'Select a client',
+    issueDate: 'Issue date',
+    validUntil: 'Valid until',
+    currency: 'Currency',
+    total: 'Total',
+    notes: 'Notes',
+    descriptionReq: 'Enter a description',
+    created: 'Quote {{number}} created',
+    saved: 'Quote saved',
+    pdf: 'Export PDF',
+    exported: 'PDF saved to {{path}}',
+    send: 'Send',
+    sendTitle: 'Send quote {{number}}',
+    sent: 'Quote sent',
+    to: 'To',
+    toHelp: "Separate addresses with commas; leave empty to use the client's email.",
+    subject: 'Subject',
+    body: 'Message',
+    emailSubject: 'Quote {{number}}',
+    emailBody: 'Dear {{client}},\n\nplease find attached quote {{number}}, valid until {{validUntil}}.\n\nKind regards',
+    convert: 'Convert to invoice',
+    convertConfirm: 'Create a draft invoice from quote {{number}}?',
+    converted: 'Draft invoice {{number}} created',
+    reject: 'Mark as rejected',
+    deleteConfirm: 'Delete quote {{number}}?',
+  },
+  quoteStatus: {
+    DRAFT: 'Draft',
+    SENT: 'Sent',
+    ACCEPTED: 'Accepted',
+    REJECTED: 'Rejected',
+    EXPIRED: 'Expired',
+  },
   newOffer: {
     titleNew: 'New offer',
     titleEdit: 'Edit offer',
@@ -586,6 +1376,12 @@ export const en = {
     title: 'Accounting Export (CSV)',
     pickFolderTitle: 'Select export folder',
     typeLabel: 'Export type',
+    formatLabel: 'Format',
+    formatHelp: 'Excel files keep amounts as numbers and dates as dates, whatever the system locale.',
+    formats: {
+      csv: 'CSV',
+      xlsx: 'Excel (XLSX)',
+    },
     types: {
       invoices: 'Invoices',
       expenses: 'Expenses',
@@ -598,10 +1394,42 @@ export const en = {
     fromReq: 'Select start date',
     toReq: 'Select end date',
     success: 'Exported: {{files}}',
+    empty: 'No records between {{from}} and {{to}}; the exported files contain only headers.',
+    archive: {
+      button: 'Export period archive (ZIP)',
+      help: 'One ZIP with every invoice PDF of the period, the invoice and expense CSVs, the KPO book and a manifest with totals.',
+      partial: '{{file}} saved; {{count}} document(s) could not be created, see errors.txt in the archive.',
+    },
+    accountant: {
+      title: 'Email to accountant',
+      hint: 'Sends the chosen reports for the period above as attachments of one email, with totals in the message.',
+      kinds: {
+        invoicesCsv: 'Invoices (CSV)',
+        expensesCsv: 'Expenses (CSV)',
+        kpo: 'KPO book (CSV)',
+      },
+      kindsReq: 'Choose at least one report',
+      noRecipient: 'Set the accountant email address in Settings → Email first.',
+      button: 'Email to accountant',
+      sent: 'Sent to {{recipient}}: {{files}}',
+    },
     errors: {
       invalidRange: 'Invalid date range',
       failed: 'Export failed: {{message}}',
     },
+    kpo: {
+      title: 'KPO book',
+      hint: 'Sent and paid invoices of the year in the default currency, with a running total. Foreign-currency invoices need an exchange rate.',
+      year: 'Year',
+      exportCsv: 'Export KPO (CSV)',
+      exportPdf: 'Export KPO (PDF)',
+    },
+    monthlyReport: {
+      title: 'Monthly report',
+      hint: 'Invoices issued and paid, expenses by category and the net result (paid minus expenses) for one month, with separate totals per currency.',
+      month: 'Month',
+      exportPdf: 'Export report (PDF)',
+    },
   },
   currencies: {
     RSD: 'RSD (Dinar)',
@@ -7,6 +7,7 @@ export const sr = {
     no: 'Ne',
     cancel: 'Otkaži',
     save: 'Sačuvaj',
+    undo: 'Poništi',
     delete: 'Obriši',
     edit: 'Izmeni',
     view: 'Pregled',
@@ -15,10 +16,12 @@ export const sr = {
     actions: 'Akcije',
     loading: 'Učitavanje…',
     path: 'Putanja: {{path}}',
+    showInFolder: 'Prikaži u folderu',
     registrationNumber: 'Matični broj',
     address: 'Adresa',
     postalCodeAndCity: 'Poštanski broj i grad',
     reset: 'Resetuj',
+    close: 'Zatvori',
   },
     email: 'Email',
     phone: 'Telefon',
@@ -33,6 +36,48 @@ export const sr = {
     settings: 'Podešavanja',
     license: 'Licenca',
   },
+  appLock: {
+    title: 'Pausaler je zaključan',
+    hint: 'Unesite PIN za nastavak.',
+    unlock: 'Otključaj',
+    wrongPin: 'Pogrešan PIN.',
+    retryIn: 'Previše pogrešnih PIN-ova. Pokušajte ponovo za {{seconds}} s.',
+    lockNow: 'Zaključaj',
+    settings: {
+      tab: 'Bezbednost',
+      title: 'PIN aplikacije',
+      help: 'Kada je PIN postavljen, aplikacija se pokreće zaključana i ništa ne prikazuje dok se PIN ne unese. Posle nekoliko pogrešnih PIN-ova, svaki sledeći pokušaj mora da čeka sve duže.',
+      statusSet: 'PIN je postavljen.',
+      statusNotSet: 'PIN nije postavljen; aplikacija se otvara bez njega.',
+      pin: 'PIN (4–12 cifara)',
+      newPin: 'Novi PIN (4–12 cifara)',
+      confirmPin: 'Ponovite PIN',
+      set: 'Postavi PIN',
+      change: 'Promeni PIN',
+      saved: 'PIN je sačuvan',
+      mismatch: 'PIN-ovi se ne poklapaju.',
+      clear: 'Ukloni PIN',
+      clearConfirm: 'Ukloniti PIN? Aplikacija će se otvarati bez njega.',
+      cleared: 'PIN je uklonjen',
+    },
+  },
+  appMode: {
+    locked: 'Samo za čitanje: bazu podataka koristi drugi program ili drugi prozor Pausalera. Možete pregledati podatke, ali izmene se neće sačuvati.',
+    readOnlyFile: 'Samo za čitanje: u fajl baze podataka ne može da se upisuje (folder samo za čitanje ili mrežni disk). Možete pregledati podatke, ali izmene se neće sačuvati.',
+    retry: 'Pokušaj ponovo',
+    stillReadOnly: 'U bazu podataka i dalje ne može da se upisuje.',
+    writable: 'Izmene ponovo mogu da se sačuvaju.',
+  },
+  search: {
+    open: 'Pretraga',
+    placeholder: 'Pretraži fakture, klijente i troškove…',
+    empty: 'Nema rezultata',
+    kinds: {
+      invoice: 'Faktura',
+      client: 'Klijent',
+      expense: 'Trošak',
+    },
+  },
   app: {
     brand: 'Fakturisanje',
     title: 'Sistem za fakturisanje',
@@ -52,11 +97,129 @@ export const sr = {
     dateFrom: 'Datum od',
     dateTo: 'Datum do',
     totalCount: 'Ukupno {{count}} faktura',
+    years: {
+      count: '{{count}} faktura',
+      unknown: 'Bez datuma izdavanja',
+    },
     empty: 'Nema faktura',
     createFirst: 'Kreiraj prvu fakturu',
     deletedSuccess: 'Faktura je uspešno obrisana',
     notFound: 'Faktura nije pronađena',
     deleteConfirm: 'Da li ste sigurni da želite da obrišete ovu fakturu?',
+    importedTag: 'Uvezena',
+    import: {
+      open: 'Uvoz iz CSV-a',
+      title: 'Uvoz faktura iz drugog programa (CSV)',
+      help: 'Jedan red po stavci fakture; redovi sa istim brojem fakture čine jednu fakturu. Originalni brojevi se zadržavaju, a nepostojeći klijenti se kreiraju.',
+      pickFile: 'Izaberi CSV fajl',
+      changeFile: 'Izaberi drugi fajl',
+      notMapped: 'Nije mapirano',
+      required: 'Izaberite odgovarajuću kolonu',
+      defaultCurrency: 'Valuta ako je fajl ne sadrži',
+      preview: 'Pregled',
+      run: 'Uvezi',
+      back: 'Nazad',
+      failed: 'Uvoz nije uspeo, ništa nije uvezeno: {{message}}',
+      summary: 'Uvezeno: {{imported}}, preskočeno: {{skipped}}, duplikati: {{duplicates}}, novi klijenti: {{clients}}',
+      previewSummary:
+        'Biće uvezeno: {{imported}}, preskočeno: {{skipped}}, duplikati: {{duplicates}}, novi klijenti: {{clients}}',
+      line: 'Red',
+      items: 'Stavke',
+      newClient: 'novi',
+      status: 'Status',
+      message: 'Napomena',
+      statuses: {
+        imported: 'Uvezeno',
+        skipped: 'Preskočeno',
+        duplicate: 'Duplikat',
+      },
+      fields: {
+        invoiceNumber: 'Broj fakture',
+        issueDate: 'Datum izdavanja',
+        clientName: 'Naziv klijenta',
+        clientPib: 'PIB klijenta',
+        itemDescription: 'Opis stavke',
+        itemQuantity: 'Količina',
+        itemUnit: 'Jedinica mere',
+        itemUnitPrice: 'Jedinična cena',
+        itemTotal: 'Iznos stavke',
+        serviceDate: 'Datum prometa',
+        dueDate: 'Rok plaćanja',
+        paidAt: 'Datum plaćanja',
+        currency: 'Valuta',
+        status: 'Status',
+        notes: 'Napomena',
+      },
+    },
+    fileImport: {
+      open: 'Uvoz fajla fakture',
+      title: 'Uvoz fakture iz fajla (JSON)',
+      help: 'Faktura izvezena opcijom „Izvezi JSON”, na primer sa drugog računara. Klijent se prepoznaje po PIB-u ili se kreira.',
+      pickFile: 'Izaberi JSON fajl',
+      changeFile: 'Izaberi drugi fajl',
+      keepNumber: 'Zadrži broj fakture iz fajla',
+      run: 'Uvezi',
+      renumber: 'Uvezi pod novim brojem',
+      conflict: 'Faktura sa ovim brojem već postoji',
+      conflictHelp: 'Ništa nije uvezeno. Fakturu možete uvesti pod sledećim slobodnim brojem.',
+      done: 'Faktura {{number}} je uvezena',
+      doneWithClient: 'Faktura {{number}} je uvezena, njen klijent je dodat',
+      failed: 'Uvoz nije uspeo: {{message}}',
+    },
+    suggestions: {
+      title: 'Predlozi za status ({{count}})',
+      today: 'danas',
+      applied: 'Faktura {{number}} je ažurirana',
+      reasons: {
+        STALE_DRAFT: 'Neposlat nacrt',
+        OVERDUE: 'Dospela',
+        PAID_WITHOUT_DATE: 'Bez datuma plaćanja',
+      },
+      details: {
+        STALE_DRAFT: 'nacrt već {{count}} dana',
+        OVERDUE: '{{count}} dana posle roka',
+        PAID_WITHOUT_DATE: 'označena kao plaćena bez datuma plaćanja',
+      },
+      actions: {
+        SEND: 'Pošalji',
+        CANCEL: 'Otkaži fakturu',
+        SEND_REMINDER: 'Pošalji podsetnik',
+        MARK_PAID: 'Označi kao plaćenu ({{date}})',
+        SET_PAID_AT: 'Postavi datum plaćanja {{date}}',
+      },
+    },
+    payments: {
+      open: 'Uvoz uplata',
+      title: 'Uvoz priliva (CSV izvod banke)',
+      help: 'Svaka uplata se povezuje sa poslatom fakturom po pozivu na broj, ili po preostalom iznosu i nazivu klijenta. Evidentiraju se samo jednoznačna poklapanja; ostale uplate su navedene ispod da ih dodelite.',
+      pickFile: 'Izaberi CSV fajl',
+      changeFile: 'Izaberi drugi fajl',
+      notMapped: 'Nije mapirano',
+      required: 'Izaberite odgovarajuću kolonu',
+      run: 'Uvezi',
+      failed: 'Uvoz nije uspeo, nijedna uplata nije evidentirana: {{message}}',
+      summary:
+        'Evidentirano: {{matched}}, za proveru: {{ambiguous}}, bez fakture: {{unmatched}}, već evidentirano: {{duplicates}}, preskočeno: {{skipped}}',
+      matched: 'Evidentirano',
+      ambiguous: 'Za proveru',
+      unmatched: 'Nema odgovarajuće fakture',
+      date: 'Datum',
+      invoice: 'Faktura',
+      pickInvoice: 'Izaberite fakturu',
+      apply: 'Evidentiraj',
+      matchedBy: {
+        reference: 'Po pozivu na broj',
+        amountAndName: 'Po iznosu i uplatiocu',
+        manual: 'Ručno izabrano',
+      },
+      fields: {
+        date: 'Datum',
+        amount: 'Iznos (priliv)',
+        reference: 'Poziv na broj',
+        payer: 'Uplatilac',
+        currency: 'Valuta',
+      },
+    },
   },
   invoiceStatus: {
     DRAFT: 'Nacrt',
@@ -67,9 +230,26 @@ export const sr = {
     OVERDUE_DAYS: 'Kasni {{days}} dana',
   },
   invoiceView: {
+    billExpenses: {
+      open: 'Dodaj troškove',
+      title: 'Prefakturisanje troškova',
+      run: 'Dodaj na fakturu',
+      empty: 'Nema nefakturisanih troškova u valuti {{currency}}',
+      done: 'Dodato troškova na fakturu: {{count}}',
+    },
+    billTime: {
+      open: 'Dodaj sate',
+      title: 'Fakturisanje evidentiranog vremena',
+      run: 'Dodaj na fakturu',
+      empty: 'Nema nefakturisanog vremena za ovog klijenta',
+      done: 'Dodato unosa vremena na fakturu: {{count}}',
+    },
     back: 'Nazad na listu',
     edit: 'Izmeni',
     exportPdf: 'Izvezi PDF',
+    exportJson: 'Izvezi JSON',
+    jsonExported: 'Faktura je sačuvana u {{path}}',
+    jsonError: 'Greška pri izvozu fakture',
     sendEmail: 'Pošalji email',
     missingItems: 'Faktura nema stavke',
     missingCompany: 'Nedostaju podaci firme u Podešavanjima',
@@ -97,16 +277,71 @@ export const sr = {
     subject: 'Naslov',
     body: 'Poruka (opciono)',
     includePdf: 'Priloži PDF',
+    includeXml: 'Priloži XML e-fakturu (UBL)',
     cancel: 'Otkaži',
     send: 'Pošalji',
     defaultSubject: 'Faktura {{invoiceNumber}}',
     missingClientEmail: 'Klijent nema email adresu',
     smtpNotConfigured: 'SMTP podešavanja nisu podešena (Podešavanja → Email)',
     sent: 'Email je poslat',
+    queued: 'Email je u redu za slanje',
     sendError: 'Greška pri slanju email-a',
     toReq: 'Unesite email primaoca',
     subjectReq: 'Unesite naslov',
   },
+  catalog: {
+    open: 'Katalog',
+    title: 'Proizvodi i usluge',
+    itemsTab: 'Stavke',
+    usageTab: 'Korišćenje',
+    empty: 'Katalog je prazan',
+    add: 'Dodaj stavku',
+    name: 'Naziv',
+    namePlaceholder: 'npr. Izrada web sajta',
+    nameReq: 'Unesite naziv',
+    priceReq: 'Unesite cenu',
+    vatRate: 'PDV',
+    vatRateHelp: 'Samo za obveznike PDV-a',
+    active: 'Aktivna',
+    inactive: 'Neaktivna',
+    saved: 'Stavka kataloga je sačuvana',
+    deleteTitle: 'Obrisati stavku kataloga?',
+    deleteDesc: 'Fakture na kojima je korišćena stavka „{{name}}“ zadržavaju svoje redove; stavka se briše samo iz kataloga.',
+    deleted: 'Stavka kataloga je obrisana',
+    usageEmpty: 'Nijedna poslata ili plaćena faktura u ovom periodu ne koristi stavke iz kataloga',
+    lines: 'Redova',
+    invoices: 'Faktura',
+    averagePrice: 'Prosečna cena',
+    billed: 'Fakturisano',
+    lastBilled: 'Poslednji put',
+    pick: 'Iz kataloga',
+  },
+  time: {
+    open: 'Vreme',
+    title: 'Evidencija vremena',
+    start: 'Pokreni',
+    stop: 'Zaustavi',
+    recorded: 'Evidentirano {{duration}} h',
+    client: 'Klijent',
+    clientReq: 'Izaberite klijenta',
+    description: 'Opis',
+    hourlyRate: 'Cena po satu',
+    rateReq: 'Unesite cenu po satu',
+    date: 'Datum',
+    duration: 'Trajanje',
+    hours: 'Sati',
+    minutes: 'Minuta',
+    add: 'Unesi vreme',
+    empty: 'Nema unosa vremena',
+    billed: 'Fakturisano',
+    unbilledOnly: 'Samo nefakturisano',
+    entriesTab: 'Unosi',
+    unbilledTab: 'Nefakturisano vreme',
+    unbilledEmpty: 'Sve evidentirano vreme je fakturisano',
+    entries: 'Unosa',
+    amount: 'Iznos',
+    oldest: 'Najstariji',
+  },
   newInvoice: {
     titleNew: 'Nova faktura',
     titleEdit: 'Izmena fakture',
@@ -128,8 +363,16 @@ export const sr = {
     emptyItems: 'Dodajte stavke fakture',
     notes: 'Napomene',
     notesPlaceholder: 'Unesite dodatne napomene…',
+    issuedBy: 'Fakturu izdao',
+    addCustomField: 'Dodaj polje (broj narudžbenice, mesto troška, …)',
+    customFieldLabel: 'Naziv',
+    customFieldValue: 'Vrednost',
+    customFieldTooLong: 'Najviše {{max}} karaktera',
     summary: 'Rekapitulacija',
     subtotal: 'Osnovica',
+    invoiceDiscount: 'Popust na račun',
+    invoiceDiscountPercent: 'Popust na račun (%)',
+    invoiceDiscountTooLarge: 'Popust na račun ne može biti veći od ukupnog iznosa.',
     total: 'UKUPNO',
     description: 'Opis',
     descriptionPlaceholder: 'Unesite opis usluge…',
@@ -140,6 +383,8 @@ export const sr = {
     lineTotal: 'Ukupno',
     selectClient: 'Izaberite klijenta',
     selectCurrency: 'Izaberite valutu',
+    exchangeRate: 'Kurs ({{defaultCurrency}} za 1 {{currency}})',
+    exchangeRateHelp: 'Srednji kurs NBS na dan izdavanja; koristi se za KPO knjigu i izveštaje o prometu.',
     issueDate: 'Datum izdavanja',
     serviceDate: 'Datum prometa',
     invoiceNumberPreviewLabel: 'Broj fakture',
@@ -152,6 +397,8 @@ export const sr = {
     statusTitle: 'Status',
     statusLicensed: 'Aktivirana licenca',
     statusLicensedUntil: 'Licenca važi do {{until}}',
+    statusGrace: 'Licenca je istekla. Sve radi još {{days}} dana; obnovite je do tada.',
+    expiring: 'Licenca ističe za {{days}} dana. Obnovite je na stranici Licenca.',
     typeLifetime: 'Neograničena / Doživotna',
     statusTrial: 'Probni period (preostalo {{days}} dana)',
     statusTrialUnknown: 'Probni period je aktivan',
@@ -173,6 +420,37 @@ export const sr = {
     activate: 'Aktiviraj',
     activated: 'Licenca je aktivirana',
     invalidLicense: 'Neispravna licenca',
+    reasons: {
+      expired: 'Licenca je istekla.',
+      pib_mismatch: 'Licenca je izdata za drugi PIB.',
+      not_yet_valid: 'Licenca još nije važeća.',
+      invalid_format: 'Ovo nije licenca.',
+      invalid_encoding: 'Licenca je oštećena. Kopirajte je ponovo bez izmena.',
+      invalid_signature_length: 'Licenca je oštećena. Kopirajte je ponovo bez izmena.',
+      invalid_payload: 'Licenca je oštećena. Kopirajte je ponovo bez izmena.',
+      app_mismatch: 'Licenca je izdata za drugu aplikaciju.',
+      clock_tampering: 'Sat računara kasni. Podesite tačan datum i vreme da biste koristili licencu.',
+      license_invalid: 'Neispravna licenca',
+    },
+    transferTitle: 'Prenos licence',
+    transferHelp: 'Promenili ste PIB? Deaktivirajte licencu ovde i pošaljite potvrdu prodavcu zajedno sa novim aktivacionim kodom. Dobićete licencu iste vrste i važenja za novi PIB.',
+    deactivate: 'Deaktiviraj licencu',
+    deactivateConfirmTitle: 'Deaktivirati licencu?',
+    deactivateConfirmBody: 'Licenca se uklanja sa ovog računara. Sačuvajte potvrdu: prodavcu je potrebna za izdavanje zamenske licence.',
+    deactivated: 'Licenca je deaktivirana',
+    deactivateError: 'Deaktivacija licence nije uspela',
+    receiptLabel: 'Potvrda o deaktivaciji',
+    featureLocked: '{{feature}} nije uključeno u vašu licencu. Dostupno je uz licencu {{plan}}.',
+    features: {
+      emailSending: 'Slanje email-a',
+      batchExport: 'Izvoz arhive perioda',
+    },
+    plans: {
+      none: 'Bez licence',
+      trial: 'Probni period',
+      yearly: 'Godišnja',
+      lifetime: 'Doživotna',
+    },
     lockedTitle: 'Aplikacija je zaključana',
       lockedDescription: 'Probni period je istekao ili je potrebna licenca za otključavanje izmene, izvoza i slanja email-a.',
     openLicense: 'Otvori licencu',
@@ -182,7 +460,7 @@ export const sr = {
     trialUnknown: 'Nepoznato',
     trialEndsAt: 'Probni period ističe',
     trialStartsNowTitle: 'Probni period i aktivacija',
-    trialStartsNowDesc: 'Nakon čuvanja, počinje probni period od 7 dana. Aktivacioni kod možete generisati u bilo kom trenutku.',
+    trialStartsNowDesc: 'Probni period od 30 dana teče od prvog pokretanja. Aktivacioni kod možete generisati u bilo kom trenutku.',
     sendEmailTitle: 'Zahtev za licencu (email)',
     sendEmailHelp: 'Pošaljite aktivacioni kod prodavcu da biste dobili licencu (bez priloga).',
     sendEmail: 'Pošalji email',
@@ -235,6 +513,80 @@ export const sr = {
     postalCodeInvalid: 'Poštanski broj može sadržati cifre i -',
     postalCodePlaceholder: '11000',
     email: 'Email',
+    phone: 'Telefon',
+    website: 'Veb sajt',
+    websiteInvalid: 'Unesite ispravan URL (https://…)',
+    duplicateTitle: 'Klijent već postoji',
+    duplicateContent: '„{{name}}” ima isti {{field}}. Da li ipak želite da kreirate novog klijenta?',
+    duplicateSelected: '„{{name}}” već ima isti {{field}}; izabran je postojeći klijent.',
+    createAnyway: 'Ipak kreiraj',
+    renameTitle: 'Klijent je preimenovan',
+    renameContent:
+      'Postojeće fakture prikazuju naziv „{{from}}”. Da li i njih da izmenim na „{{to}}”? Izdate fakture obično zadržavaju naziv pod kojim su izdate.',
+    renameInvoices: 'Izmeni fakture',
+    renameKeep: 'Zadrži stari naziv na fakturama',
+    findDuplicates: 'Pronađi duplikate',
+    duplicatesTitle: 'Mogući duplikati klijenata',
+    noDuplicates: 'Nema duplikata klijenata.',
+    mergeInto: 'Spoji u „{{name}}”',
+    mergeConfirm: 'Prebaciti sve fakture klijenta „{{remove}}” na „{{keep}}” i obrisati „{{remove}}”?',
+    merged: 'Klijenti su spojeni u „{{name}}”; prebačeno faktura: {{count}}.',
+    deleteBlockedTitle: 'Klijent ima fakture',
+    deleteBlockedContent: 'Ovaj klijent se koristi na {{count}} faktura. Ako ga obrišete, fakture zadržavaju naziv klijenta, ali gube vezu sa klijentom. Ipak obrisati?',
+    deleteAnyway: 'Ipak obriši',
+    importCsv: 'Uvoz CSV',
+    isForeign: 'Strani klijent (bez srpskog PIB-a / MB-a)',
+    country: 'Država (ISO kod)',
+    countryReq: 'Unesite kod države',
+    countryInvalid: 'Koristite dvoslovni kod, npr. DE',
+    foreignVatId: 'PDV broj (VAT ID)',
+    archive: 'Arhiviraj',
+    unarchive: 'Vrati iz arhive',
+    archived: 'Klijent je arhiviran',
+    unarchived: 'Klijent je vraćen iz arhive',
+    archivedTag: 'Arhiviran',
+    showArchived: 'Prikaži arhivirane',
+    defaultsTitle: 'Podrazumevano za fakture',
+    defaultCurrency: 'Podrazumevana valuta',
+    preferredLanguage: 'Jezik fakture',
+    langBilingual: 'Dvojezično (sr / en)',
+    useSettingsDefault: 'Kao u podešavanjima',
+    paymentTermsDays: 'Rok plaćanja (dana)',
+    billingEmail: 'Email za fakture',
+    billingEmailHelp: 'Fakture se šalju na ovu adresu; ako je prazna, koristi se kontakt email.',
+    import: {
+      title: 'Uvoz klijenata iz CSV fajla',
+      pickFile: 'Izaberite CSV fajl',
+      changeFile: 'Izaberite drugi fajl',
+      notMapped: 'Ne uvozi se',
+      nameRequired: 'Izaberite kolonu sa nazivom klijenta',
+      run: 'Uvezi',
+      failed: 'Uvoz nije uspeo, ništa nije uvezeno: {{message}}',
+      summary: 'Uvezeno: {{imported}}, preskočeno: {{skipped}}, duplikata: {{duplicates}}',
+      line: 'Red',
+      status: 'Status',
+      message: 'Napomena',
+      statuses: {
+        imported: 'Uvezen',
+        skipped: 'Preskočen',
+        duplicate: 'Duplikat',
+      },
+      fields: {
+        name: 'Naziv',
+        pib: 'PIB',
+        registrationNumber: 'Matični broj (MB)',
+        address: 'Adresa',
+        postalCode: 'Poštanski broj',
+        city: 'Grad',
+        email: 'Email',
+        phone: 'Telefon',
+      },
+    },
+    duplicateMatch: {
+      pib: 'PIB',
+      registrationNumber: 'matični broj (MB)',
+      name: 'naziv',
+    },
     deleteTitle: 'Brisanje klijenta',
     deleteDesc: 'Da li ste sigurni da želite da obrišete ovog klijenta?',
     deleted: 'Klijent je uspešno obrisan',
@@ -257,6 +609,7 @@ export const sr = {
     title: 'Podešavanja',
     saved: 'Podešavanja su uspešno sačuvana',
     saveError: 'Greška pri čuvanju podešavanja',
+    problemsTitle: 'Fakture ne mogu da se izdaju dok se ova podešavanja ne isprave',
     companyCard: 'Podaci o preduzeću',
     companyName: 'Naziv preduzeća',
     companyNameReq: 'Unesite naziv preduzeća',
@@ -267,6 +620,20 @@ export const sr = {
     vatReq: 'Unesite PIB',
     bankAccount: 'Tekući račun',
     bankReq: 'Unesite broj tekućeg računa',
+    bankAccounts: {
+      title: 'Bankovni računi',
+      help: 'Na fakturi se prikazuje račun u valuti fakture, a ako ga nema podrazumevani račun. Na deviznim fakturama se prikazuju i IBAN i SWIFT.',
+      accountNumber: 'Broj računa',
+      currency: 'Valuta',
+      bankName: 'Banka',
+      iban: 'IBAN',
+      ibanHelp: 'Za uplate iz inostranstva.',
+      swift: 'SWIFT/BIC',
+      isDefault: 'Podrazumevani račun',
+      add: 'Dodaj račun',
+      remove: 'Ukloni',
+      numberOrIbanReq: 'Unesite broj računa ili IBAN',
+    },
     address: 'Adresa',
     addressReq: 'Unesite adresu',
     addressPlaceholder: 'Ulica i broj, poštanski broj, grad',
@@ -299,6 +666,49 @@ export const sr = {
     defaultCurrency: 'Podrazumevana valuta',
     currencyReq: 'Izaberite valutu',
     example: 'Primer broja fakture',
+    turnoverLimitRsd: 'Godišnji limit prometa za paušalce',
+    vatLimitRsd: 'Prag za ulazak u sistem PDV-a',
+    limitsHelp: 'Koristi se za upozorenja o prometu; izmenite ako se zakon promeni.',
+    roundingMode: 'Zaokruživanje iznosa',
+    roundingModeHelp: 'Važi za nove fakture; izdate fakture zadržavaju zaokruživanje sa kojim su napravljene.',
+    roundingModes: { halfUp: 'Na bližu vrednost (0,005 → 0,01)', halfEven: 'Na parnu vrednost', down: 'Naniže (odsecanje)' },
+    quotePrefix: 'Prefiks ponude',
+    nextQuoteNumber: 'Sledeći broj ponude',
+    issuedBy: 'Fakturu izdao',
+    issuedByHelp: 'Štampa se u delu za potpis na novim fakturama; svaka faktura može navesti drugu osobu.',
+    issuedByPlaceholder: 'Ime i prezime',
+    customFieldLabels: 'Dodatna polja na fakturi',
+    customFieldLabelsHelp: 'Nazivi koje nove fakture nude kao dodatne redove (npr. broj narudžbenice, mesto troška); prazni redovi se ne štampaju.',
+    customFieldLabelsPlaceholder: 'Unesite naziv i pritisnite Enter',
+    customFieldLabelsMax: 'Najviše {{max}} polja',
+    staleDraftDays: 'Predloži slanje nacrta posle (dana)',
+    staleDraftDaysHelp: 'Ovako stari nacrti se prikazuju na stranici Fakture uz „Pošalji" i „Otkaži".',
+    overdueGraceDays: 'Tolerancija kašnjenja (dana)',
+    overdueGraceDaysHelp: 'Poslate fakture toliko dana posle roka se predlažu za podsetnik ili označavanje kao plaćene.',
+    pdfFilenameTemplate: 'Naziv PDF fajla',
+    pdfFilenameTemplateHelp:
+      'Koristi se za izvezene fakture i priloge u e-pošti. Oznake: {number}, {client}, {year}, {month}, {issueDate}; „/“ raspoređuje izvoz po folderima (prilog zadržava samo naziv fajla).',
+    pdfFilenameTemplateReq: 'Unesite šablon naziva fajla',
+    csv: {
+      title: 'CSV izvozi',
+      help: 'Format izvezenih CSV fajlova. Excel sa srpskim regionalnim podešavanjima očekuje tačku-zarez, decimalni zarez i BOM.',
+      delimiter: 'Separator',
+      delimiters: { comma: 'Zarez (,)', semicolon: 'Tačka-zarez (;)', tab: 'Tab', pipe: 'Uspravna crta (|)' },
+      lineEnding: 'Kraj reda',
+      lineEndings: { crlf: 'Windows (CRLF)', lf: 'Unix (LF)' },
+      decimalComma: 'Decimalni zarez',
+      includeBom: 'UTF-8 BOM',
+      includeBomHelp: 'Omogućava Excelu da prepozna kodiranje, pa se č, ć, š, ž i đ ispravno prikazuju.',
+    },
+    notes: {
+      defaultNotes: 'Podrazumevana napomena fakture',
+      defaultNotesHelp: 'Koristi se za nove fakture kreirane bez napomene.',
+      legalTitle: 'Zakonska napomena',
+      legalHelp: 'Zamenjuje ugrađenu zakonsku napomenu na fakturama. {INVOICE_NUMBER} se zamenjuje brojem fakture; ostavite prazno za ugrađeni tekst.',
+      legal: { sr: 'Tekst na srpskom', en: 'Tekst na engleskom' },
+      legalPlaceholder: 'Ugrađeni tekst',
+      preview: 'Pregled',
+    },
     save: 'Sačuvaj podešavanja',
     languageCard: 'Jezik',
     language: 'Jezik',
@@ -317,6 +727,14 @@ export const sr = {
     smtpPasswordPlaceholder: 'lozinka',
     smtpFrom: 'From adresa',
     smtpFromPlaceholder: 'fakture@firma.com',
+    smtpFromName: 'Ime pošiljaoca',
+    smtpFromNamePlaceholder: 'Naziv vaše firme',
+    smtpReplyTo: 'Reply-To adresa',
+    smtpReplyToPlaceholder: 'kancelarija@firma.com',
+    smtpReplyToInvalid: 'Unesite ispravnu email adresu',
+    accountantEmail: 'Email knjigovođe',
+    accountantEmailHelp: 'Izveštaji poslati iz Izvoza → „Pošalji knjigovođi" stižu na ovu adresu.',
+    accountantEmailPlaceholder: 'knjigovodja@agencija.rs',
     smtpTlsMode: 'TLS režim',
     smtpTlsModeHelp: 'Port 465 koristi implicitni TLS (SMTPS). Port 587 koristi STARTTLS.',
     smtpTlsModeImplicit: 'Implicitni TLS (465)',
@@ -325,6 +743,47 @@ export const sr = {
     smtpTlsModeMismatch465: 'Port 465 zahteva implicitni TLS (SMTPS).',
     smtpTlsModeMismatch587: 'Port 587 zahteva STARTTLS.',
     smtpUseTls: 'Koristi TLS',
+    smtpAuthMode: 'Autentifikacija',
+    smtpAuthModePassword: 'Korisničko ime i lozinka',
+    smtpAuthModeOauth2: 'OAuth2 (Gmail, Office 365)',
+    smtpOauthClientId: 'OAuth2 client ID',
+    smtpOauthClientSecret: 'OAuth2 client secret',
+    smtpOauthRefreshToken: 'OAuth2 refresh token',
+    smtpOauthTokenEndpoint: 'Token endpoint',
+    smtpOauthTokenEndpointInvalid: 'Token endpoint mora počinjati sa https://',
+    smtpOauthSecretPlaceholder: 'Ostavite prazno da zadržite sačuvanu vrednost',
+    smtpOauthConfigured: 'Refresh token je sačuvan.',
+    smtpPinnedCertPem: 'Zakačen sertifikat servera (PEM)',
+    smtpPinnedCertPemHelp: 'Za servere sa samopotpisanim ili internim CA sertifikatom. Kada je postavljen, veruje se samo ovom sertifikatu.',
+    smtpAcceptInvalidCerts: 'Prihvati nevažeće sertifikate',
+    smtpAcceptInvalidCertsHelp: 'Isključuje proveru sertifikata. Dozvoljeno samo za servere u privatnoj mreži, osim ako potvrdite ispod.',
+    smtpAcceptInvalidCertsAcknowledged: 'Razumem da veza može biti presretnuta',
+    smtpTrustPath: {
+      systemRoots: 'sistemski sertifikati',
+      pinnedCertificate: 'zakačen sertifikat',
+      acceptInvalidCerts: 'sertifikat nije proveren',
+      noTls: 'bez TLS-a',
+    },
+    sendCopyToSelf: 'Pošalji kopiju meni',
+    sendCopyToSelfHelp: 'Email sa fakturom se šalje i na From adresu (BCC).',
+    emailMaxMessageMb: 'Najveća veličina emaila',
+    emailMaxMessageMbHelp: 'Veći emailovi sa fakturom se ne šalju, da ih mail server ne bi odbio. Prilozi pri slanju porastu za oko trećinu.',
+    emailTheme: {
+      title: 'Boje emaila',
+      help: 'Boje emaila sa fakturom. Mail programi u tamnom režimu koji to podržavaju koriste tamne boje; ostali uvek prikazuju svetle.',
+      light: 'Svetle',
+      dark: 'Tamne',
+      reset: 'Vrati podrazumevane boje',
+      roles: {
+        background: 'Pozadina',
+        card: 'Kartica',
+        border: 'Ivice',
+        accent: 'Naslov i ukupan iznos',
+        text: 'Tekst',
+        muted: 'Oznake',
+        faint: 'Podnožje',
+      },
+    },
     emailHelp: {
       title: 'Kako da podesite slanje email faktura?',
       intro: 'Ova podešavanja omogućavaju da aplikacija automatski šalje fakture email-om.',
@@ -376,9 +835,48 @@ export const sr = {
       testButton: 'Testiraj email podešavanja',
       testHelp: 'Šalje test email na email adresu firme.',
       testSuccess: 'Test email je uspešno poslat.',
+      testSuccessTrust: 'Test email je uspešno poslat (poverenje u sertifikat: {{trust}}).',
       testError: 'Neuspešno slanje test email-a: {{message}}',
     },
 
+    diagnostics: {
+      title: 'Dijagnostika',
+      help: 'Poslednji zapisi iz dnevnika aplikacije. Lozinke i licencni ključevi se nikada ne upisuju.',
+      level: 'Nivo dnevnika',
+      show: 'Prikaži',
+      levels: { error: 'Greške', warn: 'Upozorenja', info: 'Informacije', debug: 'Otklanjanje grešaka', trace: 'Praćenje' },
+      all: 'Sve',
+      refresh: 'Osveži',
+      copy: 'Kopiraj',
+      copied: 'Dnevnik je kopiran.',
+      levelSaved: 'Nivo dnevnika je postavljen na {{level}}.',
+      empty: 'Nema zapisa.',
+      time: 'Vreme',
+      source: 'Izvor',
+      message: 'Poruka',
+    },
+    webhooks: {
+      tab: 'Webhook',
+      help: 'Pausaler može da obaveštava vaš dashboard ili automatizaciju o događajima na fakturama: potpisan HTTP POST se šalje kada se faktura napravi, promeni joj se status ili se pošalje emailom. Događaji čekaju u redu dok adresa nije dostupna.',
+      url: 'Webhook adresa (URL)',
+      urlHelp: 'Ostavite prazno da isključite webhook.',
+      secret: 'Zajednička tajna',
+      secretHelp: 'Svaki zahtev nosi X-Pausaler-Signature: sha256=<HMAC-SHA256 tela zahteva>.',
+      secretSaved: 'Tajna je sačuvana; ostavite prazno da je zadržite.',
+      test: 'Pošalji probni događaj',
+      testHelp: 'Koristi sačuvanu adresu i tajnu.',
+      testOk: 'Adresa je odgovorila sa HTTP {{status}}.',
+      testFailed: 'Proba nije uspela: {{error}}',
+      deliveries: 'Poslednja slanja',
+      empty: 'Još nema poslatih događaja',
+      createdAt: 'Vreme',
+      event: 'Događaj',
+      status: 'Status',
+      attempts: 'Pokušaji',
+      detail: 'Detalji',
+      nextAttempt: 'sledeći pokušaj {{at}}',
+      statuses: { PENDING: 'Na čekanju', DELIVERED: 'Isporučeno', FAILED: 'Neuspešno' },
+    },
     updates: {
       tab: 'O aplikaciji / Ažuriranja',
       currentVersion: 'Trenutna verzija',
@@ -425,6 +923,112 @@ export const sr = {
       restoreStaged: 'Aplikacija će se zatvoriti nakon vraćanja rezervne kopije.\nPotrebno je da je ponovo pokrenete ručno.',
       restoreStageError: 'Neuspešno pripremanje vraćanja',
       restoreAppliedSuccess: 'Vraćanje rezervne kopije je uspešno primenjeno.',
+      archive: {
+        title: 'Arhiva podataka (JSON)',
+        help: 'Svi podaci u jednom čitljivom JSON fajlu, za prelazak na drugi računar. SMTP lozinka nije uključena.',
+        exportButton: 'Izvezi JSON…',
+        importButton: 'Uvezi JSON…',
+        exported: 'Podaci izvezeni: {{path}}',
+        mode: 'Način uvoza',
+        modes: {
+          merge: 'Spoji sa postojećim podacima',
+          replace: 'Zameni postojeće podatke',
+        },
+        conflict: 'Postojeći ID-jevi',
+        conflicts: {
+          skip: 'Preskoči',
+          duplicate: 'Uvezi kao kopije',
+        },
+        replaceConfirm: 'Svi klijenti, fakture, troškovi, ponude i podešavanja biće zamenjeni podacima iz arhive. Nastaviti?',
+        imported: 'Uvezeno: {{inserted}} novih, {{duplicated}} kopija, {{skipped}} preskočeno.',
+        importError: 'Uvoz nije uspeo: {{message}}',
+      },
+      settingsFile: {
+        title: 'Fajl podešavanja',
+        help: 'Podešavanja firme, faktura i email-a sa logom, bez podataka. Koristite ga za podešavanje Pausalera na drugom računaru.',
+        includeSmtp: 'Uključi SMTP prijavu',
+        includeSmtpWarning: 'SMTP lozinka i OAuth tokeni biće upisani u fajl kao običan tekst. Čuvajte fajl na sigurnom.',
+        exportButton: 'Izvezi podešavanja…',
+        importButton: 'Uvezi podešavanja…',
+        exported: 'Podešavanja izvezena: {{path}}',
+        importConfirm: 'Trenutna podešavanja biće zamenjena podacima iz fajla. Podešavanja kojih nema u fajlu ostaju ista. Nastaviti?',
+        imported: 'Podešavanja uvezena.',
+        importError: 'Uvoz nije uspeo: {{message}}',
+      },
+      auto: {
+        title: 'Automatske rezervne kopije',
+        enabled: 'Napravi kopiju pri pokretanju',
+        enabledHelp: 'Kopija baze se čuva pri pokretanju aplikacije, najviše jednom dnevno.',
+        keep: 'Broj kopija koje se čuvaju',
+        file: 'Fajl',
+        sizeKb: 'Veličina (KB)',
+        empty: 'Još nema automatskih kopija',
+        restore: 'Vrati',
+        restoreConfirm: 'Vratiti ovu kopiju? Aplikacija će se zatvoriti, a trenutni podaci biće zamenjeni pri sledećem pokretanju.',
+        failed: 'Automatska rezervna kopija nije uspela: {{message}}',
+      },
+      consistency: {
+        title: 'Doslednost podataka',
+        help: 'Proverava da li se zapisi faktura i klijenata slažu sa sačuvanim dokumentima i ispravlja razlike.',
+        check: 'Proveri',
+        ok: 'Nema razlika u {{invoices}} faktura i {{clients}} klijenata.',
+        found: 'Pronađeno razlika: {{count}}.',
+        unreadable: 'Zapisa koji ne mogu da se pročitaju: {{count}}; ne prikazuju se u listama.',
+        corruptNotice: 'Zapis „{{label}}" ne može da se pročita i ne prikazuje se u listi. Popravite ga u Podešavanja → Doslednost podataka.',
+        corrupt: {
+          label: 'Broj / naziv',
+          error: 'Greška',
+          repair: 'Popravi',
+          title: 'Popravka „{{label}}"',
+          help: 'Zapis se ponovo sastavlja iz indeksiranih polja, uz sve što je u starom dokumentu još čitljivo. Stari dokument ostaje u dnevniku izmena.',
+          patch: 'Ispravke (opcioni JSON objekat, npr. {"notes": "..."})',
+          repaired: 'Zapis je popravljen.',
+          repairedDropped: 'Zapis je popravljen; ova polja nisu mogla da se zadrže: {{keys}}.',
+        },
+        table: 'Tabela',
+        record: 'Zapis',
+        field: 'Polje',
+        column: 'Kolona',
+        json: 'Dokument',
+        repairFromJson: 'Ispravi prema dokumentima',
+        repairFromColumns: 'Ispravi prema kolonama',
+        repairConfirm: 'Prepisati vrednosti koje se razlikuju? Stare vrednosti ostaju u dnevniku izmena.',
+        repaired: 'Ispravljeno {{fields}} vrednosti u {{rows}} zapisa.',
+      },
+      health: {
+        title: 'Stanje baze',
+        help: 'Proverava da li je datoteka baze oštećena i može da je sažme kako bi oslobodila neiskorišćen prostor.',
+        check: 'Proveri',
+        compact: 'Sažmi bazu',
+        compactConfirm: 'Sažeti bazu sada? Ostale izmene čekaju dok se ne završi.',
+        ok: 'Provera integriteta je prošla.',
+        corrupted: 'Datoteka baze je oštećena. Ne nastavljajte rad sa njom; vratite nedavnu rezervnu kopiju.',
+        dbSize: 'Veličina baze',
+        walSize: 'Veličina dnevnika (WAL)',
+        compacted: 'Baza je sažeta: {{before}} → {{after}}.',
+        checkpointBusy: 'Dnevnik nije mogao u potpunosti da se spoji; pokušajte ponovo kasnije.',
+        checkedAt: 'Provereno',
+      },
+      location: {
+        title: 'Lokacija baze',
+        help: 'Aplikacija uvek otvara ovu datoteku. Premeštanje prvo kopira i proverava podatke; stara datoteka ostaje kao .bak.',
+        path: 'Datoteka',
+        size: 'Veličina',
+        source: 'Izabrano iz',
+        sources: {
+          appData: 'Folder podataka aplikacije',
+          appLocalData: 'Lokalni folder podataka aplikacije',
+          exeDir: 'Folder programa',
+          currentDir: 'Radni folder',
+          moved: 'Premešteno ručno',
+        },
+        othersFound: 'Koristi se {{path}}; pronađeno je još datoteka baze koje se ne koriste: {{count}}. Pogledajte Podešavanja → Rezervna kopija.',
+        othersTitle: 'Pronađene druge datoteke baze (ne koriste se):',
+        move: 'Premesti bazu…',
+        pickFolder: 'Izaberite folder za bazu',
+        moveConfirm: 'Premestiti bazu u {{path}}?',
+        moved: 'Baza je premeštena. Prethodna datoteka je sačuvana kao {{path}}.',
+      },
     },
   },
   setup: {
@@ -434,6 +1038,18 @@ export const sr = {
       button: 'Vrati rezervnu kopiju…',
     },
   },
+  limits: {
+    title: 'Limiti prometa ({{year}})',
+    turnover: 'Limit za paušal',
+    vat: 'Prag za PDV',
+    usage: '{{used}} od {{limit}} RSD',
+    projected: 'Projekcija do kraja godine: {{percent}}%',
+    unconverted: 'Nije uračunato (nedostaje kurs): {{invoices}}',
+    warning: {
+      turnover: 'Godišnji promet je dostigao {{percent}}% limita za paušal ({{turnover}} od {{limit}} RSD).',
+      vat: 'Godišnji promet je dostigao {{percent}}% praga za PDV ({{turnover}} od {{limit}} RSD).',
+    },
+  },
   overview: {
     title: 'Pregled',
     scopeMonth: 'Ovaj mesec',
@@ -448,6 +1064,14 @@ export const sr = {
     tableMonth: 'Mesec',
     tableIssued: 'Izdato',
     tablePaid: 'Plaćeno',
+    topClients: 'Najbolji klijenti (plaćeno)',
+    topItems: 'Najprodavanije stavke (plaćeno)',
+    topClient: 'Klijent',
+    topItem: 'Stavka',
+    topInvoices: 'Fakture',
+    topRevenue: 'Prihod',
+    topShare: 'Udeo',
+    topEmpty: 'Nema plaćenih faktura u ovom periodu',
   },
   reports: {
     title: 'Izveštaji',
@@ -455,6 +1079,40 @@ export const sr = {
     tabs: {
       monthly: 'Mesečni',
       yearly: 'Godišnji',
+      receivables: 'Potraživanja',
+      tax: 'Porez',
+    },
+    tax: {
+      title: 'Kvartalni pregled za porez',
+      note: 'Prihodi se računaju po datumu plaćanja, troškovi po datumu troška. Iznosi su u {{currency}}; fakture u stranoj valuti se preračunavaju po kursu sa fakture.',
+      period: 'Period',
+      income: 'Naplaćeni prihodi',
+      expenses: 'Troškovi',
+      base: 'Osnovica',
+      empty: 'Nema plaćenih faktura ni troškova u ovoj godini',
+      exportCsv: 'Izvezi CSV',
+      unconvertedTitle: 'Nepreračunato (nema kursa)',
+      unconvertedNote: 'Ovi iznosi nisu deo osnovice. Unesite kurs na fakturi da bi bila uključena.',
+      kind: 'Vrsta',
+      kinds: {
+        income: 'Prihod',
+        expense: 'Trošak',
+      },
+    },
+    receivables: {
+      title: 'Nenaplaćena potraživanja',
+      asOf: 'Dani kašnjenja na dan {{date}}. Za fakture bez roka plaćanja koristi se rok klijenta (ili 15 dana).',
+      client: 'Klijent',
+      invoices: 'Fakture',
+      empty: 'Nema neplaćenih poslatih faktura',
+      exportCsv: 'Izvezi CSV',
+      buckets: {
+        current: 'Nije dospelo',
+        days1To30: '1–30 dana',
+        days31To60: '31–60 dana',
+        days61To90: '61–90 dana',
+        daysOver90: '90+ dana',
+      },
     },
     kpi: {
       issued: 'Izdato',
@@ -509,12 +1167,105 @@ export const sr = {
     deleteTitle: 'Brisanje troška',
     deleteDesc: 'Da li ste sigurni da želite da obrišete ovaj trošak?',
     deleted: 'Trošak je uspešno obrisan',
+    restored: 'Trošak je vraćen',
     notFound: 'Trošak nije pronađen',
     updated: 'Trošak je uspešno ažuriran',
     created: 'Trošak je uspešno dodat',
     modalEdit: 'Izmeni trošak',
     modalAdd: 'Dodaj trošak',
     update: 'Ažuriraj',
+    duplicate: 'Dupliraj (sa današnjim datumom)',
+    duplicated: 'Trošak je dupliran',
+    split: {
+      open: 'Podeli',
+      title: 'Podeli trošak',
+      addPart: 'Dodaj deo',
+      remaining: 'Preostalo za raspodelu: {{amount}} {{currency}}',
+      run: 'Podeli',
+      done: 'Trošak je podeljen na {{count}} dela',
+    },
+    import: {
+      open: 'Uvoz iz izvoda (CSV)',
+      title: 'Uvoz troškova iz CSV izvoda banke',
+      pickFile: 'Izaberi CSV fajl',
+      changeFile: 'Izaberi drugi fajl',
+      notMapped: 'Nije mapirano',
+      required: 'Izaberite odgovarajuću kolonu',
+      signedAmounts: 'Jedna kolona iznosa sa predznakom (zaduženja su negativna)',
+      defaultCurrency: 'Valuta ako je fajl ne sadrži',
+      preview: 'Pregled',
+      run: 'Uvezi',
+      back: 'Nazad',
+      failed: 'Uvoz nije uspeo, ništa nije uvezeno: {{message}}',
+      summary: 'Uvezeno: {{imported}}, preskočeno: {{skipped}}, duplikati: {{duplicates}}',
+      previewSummary: 'Biće uvezeno: {{imported}}, preskočeno: {{skipped}}, duplikati: {{duplicates}}',
+      line: 'Red',
+      status: 'Status',
+      message: 'Napomena',
+      statuses: {
+        imported: 'Uvezeno',
+        skipped: 'Preskočeno',
+        duplicate: 'Duplikat',
+      },
+      fields: {
+        date: 'Datum',
+        title: 'Opis',
+        amount: 'Iznos zaduženja',
+        credit: 'Iznos odobrenja',
+        currency: 'Valuta',
+        category: 'Kategorija',
+      },
+    },
+    recurring: {
+      open: 'Ponavljajući',
+      title: 'Ponavljajući troškovi',
+      empty: 'Nema ponavljajućih troškova',
+      add: 'Dodaj raspored',
+      interval: 'Ponavlja se',
+      intervals: {
+        weekly: 'Nedeljno',
+        monthly: 'Mesečno',
+        quarterly: 'Kvartalno',
+        yearly: 'Godišnje',
+      },
+      startDate: 'Prvi datum',
+      endDate: 'Do (opciono)',
+      nextRunDate: 'Sledeći',
+      created: 'Raspored je dodat',
+      deleted: 'Raspored je obrisan',
+      deleteTitle: 'Brisanje rasporeda',
+      deleteDesc: 'Troškovi koje je već napravio ostaju.',
+      deleteFuture: 'Obriši i njegove troškove sa datumom posle danas',
+      generated: 'Dodato ponavljajućih troškova: {{count}}',
+    },
+    obligations: {
+      open: 'Porezi i doprinosi',
+      title: 'Porezi i doprinosi',
+      year: 'Godina',
+      month: 'Mesec',
+      status: 'Status',
+      pending: 'Nije generisano',
+      noDecision: 'Nema rešenja',
+      generatedTag: 'Generisano',
+      paid: 'Plaćeno',
+      generate: 'Generiši troškove',
+      markPaid: 'Označi kao plaćeno',
+      markUnpaid: 'Označi kao neplaćeno',
+      generated: 'Dodato troškova: {{count}}',
+      decisions: 'Rešenja',
+      empty: 'Unesite mesečne iznose iz rešenja',
+      add: 'Dodaj rešenje',
+      validFrom: 'Važi od',
+      validTo: 'Važi do (opciono)',
+      tax: 'Porez',
+      pension: 'PIO',
+      health: 'Zdravstvo',
+      unemployment: 'Nezaposlenost',
+      notesPlaceholder: 'npr. broj rešenja',
+      created: 'Rešenje je dodato',
+      deleteTitle: 'Brisanje rešenja',
+      deleteDesc: 'Troškovi koji su već generisani iz njega ostaju.',
+    },
   },
 
   offers: {
@@ -553,6 +1304,45 @@ export const sr = {
     SENT: 'Poslata',
     FAILED: 'Neuspešno slanje',
   },
+  quotes: {
+    title: 'Numerisane ponude',
+    add: 'Nova ponuda',
+    empty: 'Još nema ponuda',
+    number: 'Broj',
+    client: 'Klijent',
+    clientReq: 'Izaberite klijenta',
+    issueDate: 'Datum izdavanja',
+    validUntil: 'Važi do',
+    currency: 'Valuta',
+    total: 'Ukupno',
+    notes: 'Napomena',
+    descriptionReq: 'Unesite opis',
+    created: 'Ponuda {{number}} je napravljena',
+    saved: 'Ponuda je sačuvana',
+    pdf: 'Izvezi PDF',
+    exported: 'PDF je sačuvan u {{path}}',
+    send: 'Pošalji',
+    sendTitle: 'Slanje ponude {{number}}',
+    sent: 'Ponuda je poslata',
+    to: 'Primaoci',
+    toHelp: 'Adrese odvojite zarezom; ako ostavite prazno, koristi se email klijenta.',
+    subject: 'Naslov',
+    body: 'Poruka',
+    emailSubject: 'Ponuda {{number}}',
+    emailBody: 'Poštovani {{client}},\n\nu prilogu je ponuda {{number}} koja važi do {{validUntil}}.\n\nSrdačan pozdrav',
+    convert: 'Pretvori u fakturu',
+    convertConfirm: 'Napraviti nacrt fakture iz ponude {{number}}?',
+    converted: 'Napravljen je nacrt fakture {{number}}',
+    reject: 'Označi kao odbijenu',
+    deleteConfirm: 'Obrisati ponudu {{number}}?',
+  },
+  quoteStatus: {
+    DRAFT: 'Nacrt',
+    SENT: 'Poslata',
+    ACCEPTED: 'Prihvaćena',
+    REJECTED: 'Odbijena',
+    EXPIRED: 'Istekla',
+  },
   newOffer: {
     titleNew: 'Nova ponuda',
     titleEdit: 'Izmena ponude',
@@ -591,6 +1381,12 @@ export const sr = {
     title: 'Računovodstveni izvoz (CSV)',
     pickFolderTitle: 'Izaberite folder za izvoz',
     typeLabel: 'Tip izvoza',
+    formatLabel: 'Format',
+    formatHelp: 'Excel fajlovi čuvaju iznose kao brojeve i datume kao datume, bez obzira na jezička podešavanja sistema.',
+    formats: {
+      csv: 'CSV',
+      xlsx: 'Excel (XLSX)',
+    },
     types: {
       invoices: 'Fakture',
       expenses: 'Troškovi',
@@ -603,10 +1399,42 @@ export const sr = {
     fromReq: 'Izaberite datum od',
     toReq: 'Izaberite datum do',
     success: 'Izvezeno: {{files}}',
+    empty: 'Nema zapisa između {{from}} i {{to}}; izvezeni fajlovi sadrže samo zaglavlja.',
+    archive: {
+      button: 'Izvezi arhivu perioda (ZIP)',
+      help: 'Jedan ZIP sa PDF-om svake fakture iz perioda, CSV-ovima faktura i troškova, KPO knjigom i pregledom ukupnih iznosa.',
+      partial: '{{file}} je sačuvan; {{count}} dokument(a) nije moguće napraviti, pogledajte errors.txt u arhivi.',
+    },
+    accountant: {
+      title: 'Pošalji knjigovođi',
+      hint: 'Šalje izabrane izveštaje za period iznad kao priloge jednog emaila, sa ukupnim iznosima u poruci.',
+      kinds: {
+        invoicesCsv: 'Fakture (CSV)',
+        expensesCsv: 'Troškovi (CSV)',
+        kpo: 'KPO knjiga (CSV)',
+      },
+      kindsReq: 'Izaberite bar jedan izveštaj',
+      noRecipient: 'Prvo unesite email adresu knjigovođe u Podešavanja → Email.',
+      button: 'Pošalji knjigovođi',
+      sent: 'Poslato na {{recipient}}: {{files}}',
+    },
     errors: {
       invalidRange: 'Neispravan opseg datuma',
       failed: 'Greška pri izvozu: {{message}}',
     },
+    kpo: {
+      title: 'KPO knjiga',
+      hint: 'Poslate i plaćene fakture za godinu u osnovnoj valuti, sa kumulativnim zbirom. Za fakture u stranoj valuti potreban je kurs.',
+      year: 'Godina',
+      exportCsv: 'Izvezi KPO (CSV)',
+      exportPdf: 'Izvezi KPO (PDF)',
+    },
+    monthlyReport: {
+      title: 'Mesečni izveštaj',
+      hint: 'Izdate i naplaćene fakture, rashodi po kategorijama i neto rezultat (naplaćeno minus rashodi) za jedan mesec, sa posebnim zbirovima po valuti.',
+      month: 'Mesec',
+      exportPdf: 'Izvezi izveštaj (PDF)',
+    },
   },
   currencies: {
     RSD: 'RSD (Dinar)',
@@ -10,14 +10,22 @@ import {
   Popconfirm,
   Empty,
   Select,
+  InputNumber,
+  Divider,
+  Switch,
+  Tag,
+  Checkbox,
 } from 'antd';
 import { PlusOutlined, EditOutlined, DeleteOutlined } from '@ant-design/icons';
-import { Client } from '../types';
+import { CURRENCY_VALUES, Client, DuplicateClientGroup } from '../types';
 import {useClients} from "../hooks/useClients.ts";
 import { useTranslation } from 'react-i18next';
 import { useSerbiaCities, type SerbiaCitySelectOption } from '../hooks/useSerbiaCities';
+import { useDocumentLanguages } from '../hooks/useDocumentLanguages';
 import { useLicenseGate } from '../components/LicenseGate';
 import { isFeatureAllowed } from '../services/featureGate';
+import { ClientCsvImportModal } from '../components/ClientCsvImportModal';
+import { taxIdRule } from '../services/taxIds';
 
 export function ClientsPage() {
   const { t } = useTranslation();
@@ -26,10 +34,16 @@ export function ClientsPage() {
   const [isModalVisible, setIsModalVisible] = useState(false);
   const [editingClient, setEditingClient] = useState<Client | null>(null);
   const [form] = Form.useForm();
+  const isForeign = Form.useWatch('isForeign', form);
 
   const serbiaCities = useSerbiaCities();
+  const documentLanguages = useDocumentLanguages();
 
-  const { clients, createClient, updateClient, deleteClient } = useClients();
+  const [showArchived, setShowArchived] = useState(false);
+  const { clients, refresh, createClient, updateClient, deleteClient, setArchived, findDuplicates, mergeClients } =
+    useClients({ includeArchived: showArchived });
+  const [duplicateGroups, setDuplicateGroups] = useState<DuplicateClientGroup[] | null>(null);
+  const [isImportVisible, setIsImportVisible] = useState(false);
 
   // Search / filter / sort state
   const [query, setQuery] = useState('');
@@ -68,6 +82,8 @@ export function ClientsPage() {
           c.city,
           c.postalCode,
           c.registrationNumber,
+          c.phone,
+          c.website,
         ];
         const hay = normalizeSerbianLatin(fields.map((v) => String(v ?? '')).join(' \u0000 '));
         return hay.includes(q);
@@ -135,8 +151,24 @@ export function ClientsPage() {
       message.error(t('license.lockedDescription'));
       return;
     }
-    const ok = await deleteClient(id);
-    if (ok) {
+    let result = await deleteClient(id);
+    if (result.blocked) {
+      const { invoiceCount } = result;
+      const confirmed = await new Promise<boolean>((resolve) => {
+        Modal.confirm({
+          title: t('clients.deleteBlockedTitle'),
+          content: t('clients.deleteBlockedContent', { count: invoiceCount }),
+          okText: t('clients.deleteAnyway'),
+          okButtonProps: { danger: true },
+          cancelText: t('common.cancel'),
+          onOk: () => resolve(true),
+          onCancel: () => resolve(false),
+        });
+      });
+      if (!confirmed) return;
+      result = await deleteClient(id, { force: true });
+    }
+    if (result.deleted) {
       message.success(t('clients.deleted'));
     } else {
       message.error(t('clients.notFound'));
@@ -150,22 +182,105 @@ export function ClientsPage() {
     }
     // Ensure we don't persist the helper field `cityObj`
     const { cityObj: _ignore, ...payload } = values as any;
+    payload.isForeign = !!payload.isForeign;
+    try {
+      await submitClient(payload);
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
+  const submitClient = async (payload: any) => {
+    // Cleared optional fields come back as undefined, which would not clear them on update.
+    for (const key of ['phone', 'website', 'defaultCurrency', 'preferredLanguage', 'paymentTermsDays', 'billingEmail', 'country']) {
+      payload[key] = payload[key] ?? null;
+    }
     if (editingClient) {
-      const updated = await updateClient(editingClient.id, payload);
+      let propagateName = false;
+      if (payload.name && payload.name.trim() !== editingClient.name.trim()) {
+        propagateName = await new Promise<boolean>((resolve) => {
+          Modal.confirm({
+            title: t('clients.renameTitle'),
+            content: t('clients.renameContent', { from: editingClient.name, to: payload.name }),
+            okText: t('clients.renameInvoices'),
+            cancelText: t('clients.renameKeep'),
+            onOk: () => resolve(true),
+            onCancel: () => resolve(false),
+          });
+        });
+      }
+      const updated = await updateClient(editingClient.id, payload, { propagateName });
       if (updated) {
         message.success(t('clients.updated'));
       } else {
         message.error(t('clients.notFound'));
       }
     } else {
-      await createClient(payload as any);
-      message.success(t('clients.created'));
+      let result = await createClient(payload);
+      if (result.status === 'duplicate') {
+        const { existingName, matchedOn } = result;
+        const confirmed = await new Promise<boolean>((resolve) => {
+          Modal.confirm({
+            title: t('clients.duplicateTitle'),
+            content: t('clients.duplicateContent', {
+              name: existingName,
+              field: t(`clients.duplicateMatch.${matchedOn}`),
+            }),
+            okText: t('clients.createAnyway'),
+            cancelText: t('common.cancel'),
+            onOk: () => resolve(true),
+            onCancel: () => resolve(false),
+          });
+        });
+        if (!confirmed) return;
+        result = await createClient(payload, { allowDuplicate: true });
+      }
+      if (result.status === 'created') {
+        message.success(t('clients.created'));
+      }
     }
 
     setIsModalVisible(false);
     form.resetFields();
   };
 
+  const handleToggleArchived = async (client: Client) => {
+    if (!canWriteClients) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    const updated = await setArchived(client.id, !client.archived);
+    if (!updated) {
+      message.error(t('clients.notFound'));
+      return;
+    }
+    message.success(updated.archived ? t('clients.archived') : t('clients.unarchived'));
+  };
+
+  const handleFindDuplicates = async () => {
+    const groups = await findDuplicates();
+    if (groups.length === 0) {
+      message.info(t('clients.noDuplicates'));
+      return;
+    }
+    setDuplicateGroups(groups);
+  };
+
+  const handleMerge = async (keep: Client, remove: Client) => {
+    if (!canWriteClients) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    try {
+      const { invoicesMoved } = await mergeClients(keep.id, remove.id);
+      message.success(t('clients.merged', { name: keep.name, count: invoicesMoved }));
+      const groups = await findDuplicates();
+      setDuplicateGroups(groups.length ? groups : null);
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
   const sortedClients = visibleClients; // kept name for minimal downstream changes
 
   const columns = [
@@ -173,7 +288,12 @@ export function ClientsPage() {
       title: t('clients.name'),
       dataIndex: 'name',
       key: 'name',
-      render: (text: string) => <strong>{text}</strong>,
+      render: (text: string, record: Client) => (
+        <>
+          <strong>{text}</strong>
+          {record.archived ? <Tag style={{ marginLeft: 8 }}>{t('clients.archivedTag')}</Tag> : null}
+        </>
+      ),
       sorter: true,
       sortOrder: sorter.field === 'name' ? sorter.order : undefined,
     },
@@ -220,6 +340,13 @@ export function ClientsPage() {
             >
               {t('common.edit')}
             </Button>
+            <Button
+                type="link"
+                disabled={!canWriteClients}
+                onClick={() => void handleToggleArchived(record)}
+            >
+              {record.archived ? t('clients.unarchive') : t('clients.archive')}
+            </Button>
             <Popconfirm
                 title={t('clients.deleteTitle')}
                 description={t('clients.deleteDesc')}
@@ -248,15 +375,23 @@ export function ClientsPage() {
             }}
         >
           <h2 style={{ margin: 0 }}>{t('clients.title')}</h2>
-          <Button
-              type="primary"
-              icon={<PlusOutlined />}
-              size="large"
-              disabled={!canWriteClients}
-              onClick={handleAdd}
-          >
-            {t('clients.add')}
-          </Button>
+          <Space>
+            <Button size="large" onClick={() => void handleFindDuplicates()}>
+              {t('clients.findDuplicates')}
+            </Button>
+            <Button size="large" disabled={!canWriteClients} onClick={() => setIsImportVisible(true)}>
+              {t('clients.importCsv')}
+            </Button>
+            <Button
+                type="primary"
+                icon={<PlusOutlined />}
+                size="large"
+                disabled={!canWriteClients}
+                onClick={handleAdd}
+            >
+              {t('clients.add')}
+            </Button>
+          </Space>
         </div>
         {/* Filters toolbar (above table) */}
         <div
@@ -290,6 +425,10 @@ export function ClientsPage() {
             onChange={(v) => setCityFilter(v)}
             options={cityFilterOptions.map((c) => ({ label: c, value: c }))}
           />
+          <Space>
+            <Switch checked={showArchived} onChange={setShowArchived} />
+            <span>{t('clients.showArchived')}</span>
+          </Space>
           <div style={{ marginLeft: 'auto' }}>
             <Button size="middle" onClick={() => { setQuery(''); setCityFilter(undefined); }}>
               {t('common.reset')}
@@ -351,18 +490,43 @@ export function ClientsPage() {
               <Input placeholder={t('clients.companyNamePlaceholder')} />
             </Form.Item>
 
+            <Form.Item name="isForeign" valuePropName="checked">
+              <Checkbox>{t('clients.isForeign')}</Checkbox>
+            </Form.Item>
+
+            {isForeign ? (
+              <Form.Item
+                label={t('clients.country')}
+                name="country"
+                rules={[
+                  { required: true, message: t('clients.countryReq') },
+                  { pattern: /^[A-Za-z]{2}$/, message: t('clients.countryInvalid') },
+                ]}
+              >
+                <Input placeholder="DE" maxLength={2} style={{ textTransform: 'uppercase' }} />
+              </Form.Item>
+            ) : null}
+
             <Form.Item
-                label={t('clients.vatId')}
+                label={isForeign ? t('clients.foreignVatId') : t('clients.vatId')}
                 name="pib"
-                rules={[{ required: true, message: t('clients.vatReq') }]}
+                validateTrigger={['onChange', 'onBlur']}
+                rules={[
+                  { required: !isForeign, message: t('clients.vatReq') },
+                  taxIdRule('pib', () => !!form.getFieldValue('isForeign')),
+                ]}
             >
-              <Input placeholder="123456789" />
+              <Input placeholder={isForeign ? 'DE123456789' : '123456789'} />
             </Form.Item>
 
             <Form.Item
               label={t('clients.companyRegNumber')}
               name="registrationNumber"
-              rules={[{ required: true, message: t('clients.companyRegNumberReq') }]}
+              validateTrigger={['onChange', 'onBlur']}
+              rules={[
+                { required: !isForeign, message: t('clients.companyRegNumberReq') },
+                taxIdRule('mb', () => !!form.getFieldValue('isForeign')),
+              ]}
             >
               <Input placeholder="12345678" />
             </Form.Item>
@@ -442,6 +606,50 @@ export function ClientsPage() {
               <Input placeholder="kontakt@firma.rs" />
             </Form.Item>
 
+            <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+              <Form.Item label={t('clients.phone')} name="phone">
+                <Input placeholder={t('settings.companyPhonePlaceholder')} />
+              </Form.Item>
+              <Form.Item
+                label={t('clients.website')}
+                name="website"
+                rules={[{ type: 'url', message: t('clients.websiteInvalid') }]}
+              >
+                <Input placeholder="https://firma.rs" />
+              </Form.Item>
+            </div>
+
+            <Divider orientation="left" plain style={{ margin: '4px 0 12px' }}>
+              {t('clients.defaultsTitle')}
+            </Divider>
+            <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+              <Form.Item label={t('clients.defaultCurrency')} name="defaultCurrency">
+                <Select
+                  allowClear
+                  placeholder={t('clients.useSettingsDefault')}
+                  options={CURRENCY_VALUES.map((c) => ({ value: c, label: t(`currencies.${c}`) }))}
+                />
+              </Form.Item>
+              <Form.Item label={t('clients.preferredLanguage')} name="preferredLanguage">
+                <Select
+                  allowClear
+                  placeholder={t('clients.useSettingsDefault')}
+                  options={[...documentLanguages, { value: 'bilingual', label: t('clients.langBilingual') }]}
+                />
+              </Form.Item>
+              <Form.Item label={t('clients.paymentTermsDays')} name="paymentTermsDays">
+                <InputNumber min={0} max={365} precision={0} style={{ width: '100%' }} />
+              </Form.Item>
+              <Form.Item
+                label={t('clients.billingEmail')}
+                name="billingEmail"
+                extra={t('clients.billingEmailHelp')}
+                rules={[{ type: 'email', message: t('clients.emailInvalid') }]}
+              >
+                <Input placeholder="racuni@firma.rs" />
+              </Form.Item>
+            </div>
+
             <Form.Item>
               <Space style={{ width: '100%', justifyContent: 'flex-end' }}>
                 <Button
@@ -459,6 +667,49 @@ export function ClientsPage() {
             </Form.Item>
           </Form>
         </Modal>
+
+        <ClientCsvImportModal
+            open={isImportVisible}
+            onClose={() => setIsImportVisible(false)}
+            onImported={() => void refresh()}
+        />
+
+        <Modal
+            title={t('clients.duplicatesTitle')}
+            open={duplicateGroups !== null}
+            footer={null}
+            onCancel={() => setDuplicateGroups(null)}
+        >
+          {(duplicateGroups ?? []).map((g) => (
+            <div key={`${g.matchedOn}:${g.key}`} style={{ marginBottom: 12 }}>
+              <div style={{ fontWeight: 600 }}>
+                {t(`clients.duplicateMatch.${g.matchedOn}`)}: {g.key}
+              </div>
+              <ul style={{ margin: '4px 0 0 0', paddingLeft: 20 }}>
+                {g.clients.map((c, idx) => (
+                  <li key={c.id}>
+                    {c.name}
+                    {c.pib ? ` · PIB ${c.pib}` : ''}
+                    {c.registrationNumber ? ` · MB ${c.registrationNumber}` : ''}
+                    {idx > 0 ? (
+                      <Popconfirm
+                        title={t('clients.mergeConfirm', { remove: c.name, keep: g.clients[0].name })}
+                        onConfirm={() => handleMerge(g.clients[0], c)}
+                        okText={t('common.yes')}
+                        cancelText={t('common.no')}
+                        disabled={!canWriteClients}
+                      >
+                        <Button type="link" size="small" disabled={!canWriteClients}>
+                          {t('clients.mergeInto', { name: g.clients[0].name })}
+                        </Button>
+                      </Popconfirm>
+                    ) : null}
+                  </li>
+                ))}
+              </ul>
+            </div>
+          ))}
+        </Modal>
       </div>
   );
 }
\ No newline at end of file
@@ -11,9 +11,19 @@ import {
   Select,
   Space,
   Table,
+  Tooltip,
   message,
 } from 'antd';
-import { PlusOutlined, EditOutlined, DeleteOutlined } from '@ant-design/icons';
+import {
+  PlusOutlined,
+  EditOutlined,
+  DeleteOutlined,
+  SyncOutlined,
+  BankOutlined,
+  UploadOutlined,
+  CopyOutlined,
+  ScissorOutlined,
+} from '@ant-design/icons';
 import dayjs from 'dayjs';
 import { useTranslation } from 'react-i18next';
 
@@ -23,6 +33,10 @@ import { useExpenses } from '../hooks/useExpenses';
 import { useSettings } from '../hooks/useSettings';
 import { useLicenseGate } from '../components/LicenseGate';
 import { isFeatureAllowed } from '../services/featureGate';
+import { RecurringExpensesModal } from '../components/RecurringExpensesModal';
+import { ObligationsModal } from '../components/ObligationsModal';
+import { ExpenseCsvImportModal } from '../components/ExpenseCsvImportModal';
+import { ExpenseSplitModal } from '../components/ExpenseSplitModal';
 
 const { RangePicker } = DatePicker;
 
@@ -43,12 +57,25 @@ export function ExpensesPage() {
   const { status } = useLicenseGate();
   const canWriteExpenses = isFeatureAllowed(status, 'EXPENSES_WRITE');
 
-  const { expenses, listExpenses, createExpense, updateExpense, deleteExpense } = useExpenses();
+  const {
+    expenses,
+    listExpenses,
+    refresh,
+    createExpense,
+    updateExpense,
+    deleteExpense,
+    restoreExpense,
+    duplicateExpense,
+  } = useExpenses();
 
   const [searchText, setSearchText] = useState('');
   const [dateRange, setDateRange] = useState<[dayjs.Dayjs | null, dayjs.Dayjs | null] | null>(null);
 
   const [isModalVisible, setIsModalVisible] = useState(false);
+  const [isRecurringOpen, setIsRecurringOpen] = useState(false);
+  const [isObligationsOpen, setIsObligationsOpen] = useState(false);
+  const [isImportOpen, setIsImportOpen] = useState(false);
+  const [splitting, setSplitting] = useState<Expense | null>(null);
   const [editingExpense, setEditingExpense] = useState<Expense | null>(null);
   const [form] = Form.useForm<ExpenseFormValues>();
 
@@ -90,14 +117,53 @@ export function ExpensesPage() {
     setIsModalVisible(true);
   };
 
+  const handleDuplicate = async (record: Expense) => {
+    if (!canWriteExpenses) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    try {
+      const copy = await duplicateExpense(record.id, dayjs().format('YYYY-MM-DD'));
+      if (copy) {
+        message.success(t('expenses.duplicated'));
+        handleEdit(copy);
+      } else {
+        message.error(t('expenses.notFound'));
+      }
+    } catch (e: any) {
+      message.error(String(e?.message ?? e));
+    }
+  };
+
   const handleDelete = async (id: string) => {
     if (!canWriteExpenses) {
       message.error(t('license.lockedDescription'));
       return;
     }
-    const ok = await deleteExpense(id);
-    if (ok) {
-      message.success(t('expenses.deleted'));
+    const deleted = await deleteExpense(id);
+    if (deleted) {
+      const key = `expense-deleted-${deleted.id}`;
+      message.success({
+        key,
+        duration: 8,
+        content: (
+          <span>
+            {t('expenses.deleted')}{' '}
+            <Button
+              type="link"
+              size="small"
+              onClick={() => {
+                message.destroy(key);
+                void restoreExpense(deleted.id).then((restored) => {
+                  if (restored) message.success(t('expenses.restored'));
+                });
+              }}
+            >
+              {t('common.undo')}
+            </Button>
+          </span>
+        ),
+      });
     } else {
       message.error(t('expenses.notFound'));
     }
@@ -204,12 +270,28 @@ export function ExpensesPage() {
     {
       title: t('common.actions'),
       key: 'actions',
-      width: 160,
+      width: 200,
       render: (_: unknown, record: Expense) => (
         <Space size="small">
           <Button type="link" icon={<EditOutlined />} onClick={() => handleEdit(record)} disabled={!canWriteExpenses}>
             {t('common.edit')}
           </Button>
+          <Tooltip title={t('expenses.duplicate')}>
+            <Button
+              type="link"
+              icon={<CopyOutlined />}
+              onClick={() => void handleDuplicate(record)}
+              disabled={!canWriteExpenses}
+            />
+          </Tooltip>
+          <Tooltip title={t('expenses.split.open')}>
+            <Button
+              type="link"
+              icon={<ScissorOutlined />}
+              onClick={() => setSplitting(record)}
+              disabled={!canWriteExpenses}
+            />
+          </Tooltip>
           <Popconfirm
             title={t('expenses.deleteTitle')}
             description={t('expenses.deleteDesc')}
@@ -258,6 +340,15 @@ export function ExpensesPage() {
             format="DD.MM.YYYY"
             allowClear
           />
+          <Button size="large" icon={<UploadOutlined />} onClick={() => setIsImportOpen(true)} disabled={!canWriteExpenses}>
+            {t('expenses.import.open')}
+          </Button>
+          <Button size="large" icon={<SyncOutlined />} onClick={() => setIsRecurringOpen(true)}>
+            {t('expenses.recurring.open')}
+          </Button>
+          <Button size="large" icon={<BankOutlined />} onClick={() => setIsObligationsOpen(true)}>
+            {t('expenses.obligations.open')}
+          </Button>
           <Button type="primary" icon={<PlusOutlined />} size="large" onClick={handleAdd} disabled={!canWriteExpenses}>
             {t('expenses.add')}
           </Button>
@@ -355,6 +446,30 @@ export function ExpensesPage() {
           </Form.Item>
         </Form>
       </Modal>
+
+      <RecurringExpensesModal
+        open={isRecurringOpen}
+        canWrite={canWriteExpenses}
+        defaultCurrency={defaultCurrency}
+        onClose={() => setIsRecurringOpen(false)}
+        onChanged={() => void refresh()}
+      />
+
+      <ObligationsModal
+        open={isObligationsOpen}
+        canWrite={canWriteExpenses}
+        onClose={() => setIsObligationsOpen(false)}
+        onChanged={() => void refresh()}
+      />
+
+      <ExpenseSplitModal expense={splitting} onClose={() => setSplitting(null)} onSplit={() => void refresh()} />
+
+      <ExpenseCsvImportModal
+        open={isImportOpen}
+        defaultCurrency={defaultCurrency}
+        onClose={() => setIsImportOpen(false)}
+        onImported={() => void refresh()}
+      />
     </div>
   );
 }
@@ -1,19 +1,26 @@
 import { useMemo, useState } from 'react';
-import { Button, DatePicker, Form, Select, Space, Typography, message } from 'antd';
+import { Button, Checkbox, DatePicker, Divider, Form, Progress, Select, Space, Typography, message } from 'antd';
 import dayjs from 'dayjs';
 import { useTranslation } from 'react-i18next';
-import { open } from '@tauri-apps/plugin-dialog';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import { listen } from '@tauri-apps/api/event';
 
 import { getStorage } from '../services/storageProvider';
 import { useLicenseGate } from '../components/LicenseGate';
-import { isFeatureAllowed } from '../services/featureGate';
+import { featureLockedMessage, isFeatureAllowed } from '../services/featureGate';
+import type { AccountantReportKind, PeriodArchiveProgress, RangeExportResult } from '../types';
 
 const storage = getStorage();
 
 type ExportType = 'invoices' | 'expenses' | 'both';
 
+const ACCOUNTANT_REPORT_KINDS: AccountantReportKind[] = ['invoicesCsv', 'expensesCsv', 'kpo'];
+
+type ExportFormat = 'csv' | 'xlsx';
+
 type ExportFormValues = {
   exportType: ExportType;
+  format: ExportFormat;
   from: dayjs.Dayjs;
   to: dayjs.Dayjs;
 };
@@ -37,14 +44,24 @@ export function ExportsPage() {
   const { status } = useLicenseGate();
 
   const canExportCsv = isFeatureAllowed(status, 'EXPORTS_CSV');
+  const canSendEmail = isFeatureAllowed(status, 'INVOICES_SEND_EMAIL');
 
   const [exporting, setExporting] = useState(false);
+  const [kpoYear, setKpoYear] = useState(() => dayjs().year());
+  const [kpoExporting, setKpoExporting] = useState<'csv' | 'pdf' | null>(null);
+  const [reportMonth, setReportMonth] = useState(() => dayjs().subtract(1, 'month').startOf('month'));
+  const [reportExporting, setReportExporting] = useState(false);
+  const [archiveProgress, setArchiveProgress] = useState<PeriodArchiveProgress | null>(null);
+  const [archiving, setArchiving] = useState(false);
+  const [accountantKinds, setAccountantKinds] = useState<AccountantReportKind[]>(ACCOUNTANT_REPORT_KINDS);
+  const [sendingToAccountant, setSendingToAccountant] = useState(false);
   const [form] = Form.useForm<ExportFormValues>();
 
   const initialValues = useMemo<ExportFormValues>(() => {
     const now = dayjs();
     return {
       exportType: 'both',
+      format: 'csv',
       from: now.startOf('month'),
       to: now.endOf('month'),
     };
@@ -77,24 +94,31 @@ export function ExportsPage() {
 
     setExporting(true);
     try {
-      const savedPaths: string[] = [];
+      const results: RangeExportResult[] = [];
 
-      const invoicesName = `invoices_${from}_${to}.csv`;
-      const expensesName = `expenses_${from}_${to}.csv`;
+      const xlsx = values.format === 'xlsx';
+      const invoicesName = `invoices_${from}_${to}.${values.format}`;
+      const expensesName = `expenses_${from}_${to}.${values.format}`;
 
       if (values.exportType === 'invoices' || values.exportType === 'both') {
         const outPath = joinPath(folderPath, invoicesName);
-        const p = await storage.exportInvoicesCsv(from, to, outPath);
-        savedPaths.push(p);
+        results.push(
+          xlsx ? await storage.exportInvoicesXlsx(from, to, outPath) : await storage.exportInvoicesCsv(from, to, outPath)
+        );
       }
       if (values.exportType === 'expenses' || values.exportType === 'both') {
         const outPath = joinPath(folderPath, expensesName);
-        const p = await storage.exportExpensesCsv(from, to, outPath);
-        savedPaths.push(p);
+        results.push(
+          xlsx ? await storage.exportExpensesXlsx(from, to, outPath) : await storage.exportExpensesCsv(from, to, outPath)
+        );
       }
 
-      const names = savedPaths.map(basename);
-      message.success(t('exports.success', { files: names.join(', ') }));
+      const names = results.map((r) => `${basename(r.outputPath)} (${r.rowCount})`);
+      if (results.every((r) => r.rowCount === 0)) {
+        message.warning(t('exports.empty', { from: results[0]?.from ?? from, to: results[0]?.to ?? to }));
+      } else {
+        message.success(t('exports.success', { files: names.join(', ') }));
+      }
     } catch (e) {
       const msg = (e as any)?.message ?? String(e);
       message.error(t('exports.errors.failed', { message: msg }));
@@ -103,6 +127,144 @@ export function ExportsPage() {
     }
   };
 
+  const handleArchiveExport = async () => {
+    if (!canExportCsv) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    const values = await form.validateFields();
+    const from = values.from.format('YYYY-MM-DD');
+    const to = values.to.format('YYYY-MM-DD');
+    const outPath = await save({
+      defaultPath: `pausaler_${from}_${to}.zip`,
+      filters: [{ name: 'ZIP', extensions: ['zip'] }],
+    });
+    if (!outPath) return;
+
+    setArchiving(true);
+    setArchiveProgress(null);
+    const unlisten = await listen<PeriodArchiveProgress>('period_archive_progress', (e) => setArchiveProgress(e.payload));
+    try {
+      const res = await storage.exportPeriodArchive(from, to, outPath);
+      if (res.errors.length > 0) {
+        message.warning(t('exports.archive.partial', { file: basename(res.outputPath), count: res.errors.length }));
+      } else {
+        message.success(t('exports.success', { files: basename(res.outputPath) }));
+      }
+    } catch (e) {
+      const locked = featureLockedMessage(e, t);
+      if (locked) {
+        message.error(locked);
+        return;
+      }
+      const msg = (e as any)?.message ?? String(e);
+      message.error(t('exports.errors.failed', { message: msg }));
+    } finally {
+      unlisten();
+      setArchiving(false);
+      setArchiveProgress(null);
+    }
+  };
+
+  const handleEmailToAccountant = async () => {
+    if (!canSendEmail) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    if (accountantKinds.length === 0) {
+      message.error(t('exports.accountant.kindsReq'));
+      return;
+    }
+    const values = await form.validateFields();
+    const settings = await storage.getSettings();
+    if (!settings.accountantEmail?.trim()) {
+      message.error(t('exports.accountant.noRecipient'));
+      return;
+    }
+
+    setSendingToAccountant(true);
+    try {
+      const res = await storage.emailReportToAccountant(
+        values.from.format('YYYY-MM-DD'),
+        values.to.format('YYYY-MM-DD'),
+        accountantKinds
+      );
+      message.success(t('exports.accountant.sent', { recipient: res.recipient, files: res.attachments.join(', ') }));
+    } catch (e) {
+      const locked = featureLockedMessage(e, t);
+      if (locked) {
+        message.error(locked);
+        return;
+      }
+      const msg = (e as any)?.message ?? String(e);
+      message.error(t('exports.errors.failed', { message: msg }));
+    } finally {
+      setSendingToAccountant(false);
+    }
+  };
+
+  const handleKpoExport = async (format: 'csv' | 'pdf') => {
+    if (!canExportCsv) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    const picked = await open({
+      directory: true,
+      multiple: false,
+      title: t('exports.pickFolderTitle'),
+    });
+    const folderPath = Array.isArray(picked) ? picked[0] : picked;
+    if (!folderPath) return;
+
+    setKpoExporting(format);
+    try {
+      const outPath = joinPath(folderPath, `kpo_${kpoYear}.${format}`);
+      if (format === 'csv') {
+        const p = await storage.exportKpoCsv(kpoYear, outPath);
+        message.success(t('exports.success', { files: basename(p) }));
+      } else {
+        const exported = await storage.exportKpoPdf(kpoYear, outPath, true);
+        message.success(t('exports.success', { files: basename(exported.path) }));
+        if (exported.openError) message.warning(exported.openError);
+      }
+    } catch (e) {
+      const msg = (e as any)?.message ?? String(e);
+      message.error(t('exports.errors.failed', { message: msg }));
+    } finally {
+      setKpoExporting(null);
+    }
+  };
+
+  const handleMonthlyReportExport = async () => {
+    if (!canExportCsv) {
+      message.error(t('license.lockedDescription'));
+      return;
+    }
+    const picked = await open({
+      directory: true,
+      multiple: false,
+      title: t('exports.pickFolderTitle'),
+    });
+    const folderPath = Array.isArray(picked) ? picked[0] : picked;
+    if (!folderPath) return;
+
+    setReportExporting(true);
+    try {
+      const outPath = joinPath(folderPath, `izvestaj_${reportMonth.format('YYYY-MM')}.pdf`);
+      const exported = await storage.exportMonthlyReportPdf(reportMonth.year(), reportMonth.month() + 1, outPath, true);
+      message.success(t('exports.success', { files: basename(exported.path) }));
+      if (exported.openError) message.warning(exported.openError);
+    } catch (e) {
+      const msg = (e as any)?.message ?? String(e);
+      message.error(t('exports.errors.failed', { message: msg }));
+    } finally {
+      setReportExporting(false);
+    }
+  };
+
+  const currentYear = dayjs().year();
+  const kpoYearOptions = Array.from({ length: 6 }, (_, i) => ({ value: currentYear - i, label: String(currentYear - i) }));
+
   return (
     <div>
       <Typography.Title level={3} style={{ marginTop: 0 }}>
@@ -128,6 +290,15 @@ export function ExportsPage() {
           />
         </Form.Item>
 
+        <Form.Item label={t('exports.formatLabel')} name="format" extra={t('exports.formatHelp')}>
+          <Select
+            options={[
+              { value: 'csv', label: t('exports.formats.csv') },
+              { value: 'xlsx', label: t('exports.formats.xlsx') },
+            ]}
+          />
+        </Form.Item>
+
         <Space size={12} style={{ width: '100%' }}>
           <Form.Item
             label={t('exports.from')}
@@ -152,8 +323,82 @@ export function ExportsPage() {
           <Button type="primary" onClick={() => void handleExport()} loading={exporting} disabled={!canExportCsv}>
             {t('exports.exportButton')}
           </Button>
+          <Button
+            style={{ marginLeft: 8 }}
+            onClick={() => void handleArchiveExport()}
+            loading={archiving}
+            disabled={!canExportCsv}
+          >
+            {t('exports.archive.button')}
+          </Button>
+          <Typography.Paragraph type="secondary" style={{ marginTop: 8, marginBottom: 0 }}>
+            {t('exports.archive.help')}
+          </Typography.Paragraph>
+          {archiveProgress ? (
+            <Progress
+              percent={Math.round((archiveProgress.done / Math.max(archiveProgress.total, 1)) * 100)}
+              format={() => `${archiveProgress.done}/${archiveProgress.total}`}
+            />
+          ) : null}
         </div>
       </Form>
+
+      <Divider />
+
+      <Typography.Title level={4}>{t('exports.accountant.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('exports.accountant.hint')}</Typography.Paragraph>
+      <Space direction="vertical">
+        <Checkbox.Group
+          value={accountantKinds}
+          onChange={(v) => setAccountantKinds(v as AccountantReportKind[])}
+          options={ACCOUNTANT_REPORT_KINDS.map((k) => ({ value: k, label: t(`exports.accountant.kinds.${k}`) }))}
+        />
+        <Button
+          onClick={() => void handleEmailToAccountant()}
+          loading={sendingToAccountant}
+          disabled={!canSendEmail || accountantKinds.length === 0}
+        >
+          {t('exports.accountant.button')}
+        </Button>
+      </Space>
+
+      <Divider />
+
+      <Typography.Title level={4}>{t('exports.kpo.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('exports.kpo.hint')}</Typography.Paragraph>
+      <Space>
+        <Select
+          aria-label={t('exports.kpo.year')}
+          value={kpoYear}
+          onChange={setKpoYear}
+          options={kpoYearOptions}
+          style={{ width: 120 }}
+        />
+        <Button onClick={() => void handleKpoExport('csv')} loading={kpoExporting === 'csv'} disabled={!canExportCsv}>
+          {t('exports.kpo.exportCsv')}
+        </Button>
+        <Button onClick={() => void handleKpoExport('pdf')} loading={kpoExporting === 'pdf'} disabled={!canExportCsv}>
+          {t('exports.kpo.exportPdf')}
+        </Button>
+      </Space>
+
+      <Divider />
+
+      <Typography.Title level={4}>{t('exports.monthlyReport.title')}</Typography.Title>
+      <Typography.Paragraph type="secondary">{t('exports.monthlyReport.hint')}</Typography.Paragraph>
+      <Space>
+        <DatePicker
+          picker="month"
+          aria-label={t('exports.monthlyReport.month')}
+          value={reportMonth}
+          onChange={(v) => v && setReportMonth(v.startOf('month'))}
+          allowClear={false}
+          format="MM.YYYY"
+        />
+        <Button onClick={() => void handleMonthlyReportExport()} loading={reportExporting} disabled={!canExportCsv}>
+          {t('exports.monthlyReport.exportPdf')}
+        </Button>
+      </Space>
     </div>
   );
 }
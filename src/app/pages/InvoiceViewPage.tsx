@@ -17,22 +17,36 @@ import {
   Checkbox,
   Tooltip,
 } from 'antd';
-import { ArrowLeftOutlined, EditOutlined, FilePdfOutlined, MailOutlined } from '@ant-design/icons';
+import {
+  ArrowLeftOutlined,
+  ClockCircleOutlined,
+  DollarOutlined,
+  EditOutlined,
+  FilePdfOutlined,
+  FileTextOutlined,
+  MailOutlined,
+} from '@ant-design/icons';
+import { save } from '@tauri-apps/plugin-dialog';
 import { Client, Invoice, InvoiceItem, Settings, INVOICE_STATUS_VALUES, invoiceUnitLabel, normalizeInvoiceUnit } from '../types';
 import { getStorage } from '../services/storageProvider';
 import dayjs from 'dayjs';
 import { getInvoiceOverdueDays, isInvoiceOverdue } from '../services/invoiceOverdue';
+import { paymentAccountFor } from '../services/bankAccounts';
 import { isSmtpConfigured } from '../services/smtp';
 import {
   buildInvoicePdfPayload,
   exportInvoicePdfToDownloads,
-  openGeneratedPdf,
+  revealInFolder,
 } from '../services/invoicePdf';
 import { mandatoryInvoiceNoteLines } from '../services/mandatoryInvoiceNote';
 import { useTranslation } from 'react-i18next';
 import { getNumberLocale, normalizeLanguage } from '../i18n';
 import { useLicenseGate } from '../components/LicenseGate';
-import { isFeatureAllowed } from '../services/featureGate';
+import { featureLockedMessage, isFeatureAllowed } from '../services/featureGate';
+import { errorMessage } from '../services/commandError';
+import { BillExpensesModal } from '../components/BillExpensesModal';
+import { BillTimeModal } from '../components/BillTimeModal';
+import { useCompanyLogo } from '../hooks/useCompanyLogo';
 
 const storage = getStorage();
 
@@ -50,12 +64,15 @@ export function InvoiceViewPage() {
   const [invoice, setInvoice] = useState<Invoice | null>(null);
   const [client, setClient] = useState<Client | undefined>(undefined);
   const [settings, setSettings] = useState<Settings | null>(null);
+  const { logoSrc } = useCompanyLogo();
   const [exporting, setExporting] = useState(false);
   const [updatingMeta, setUpdatingMeta] = useState(false);
 
   const [sendEmailOpen, setSendEmailOpen] = useState(false);
+  const [billExpensesOpen, setBillExpensesOpen] = useState(false);
+  const [billTimeOpen, setBillTimeOpen] = useState(false);
   const [sendingEmail, setSendingEmail] = useState(false);
-  const [sendEmailForm] = Form.useForm<{ to: string; subject: string; body?: string; includePdf: boolean }>();
+  const [sendEmailForm] = Form.useForm<{ to: string; subject: string; body?: string; includePdf: boolean; includeXml?: boolean }>();
 
   useEffect(() => {
     if (!id) return;
@@ -85,6 +102,8 @@ export function InvoiceViewPage() {
   const mandatoryNoteLines = mandatoryInvoiceNoteLines({
     language: i18n.language,
     invoiceNumber: invoice.invoiceNumber,
+    reverseCharge: !!client?.isForeign && !!client.pib?.trim(),
+    override: settings.legalNoteOverride,
   });
 
   const numberLocale = getNumberLocale(normalizeLanguage(i18n.language));
@@ -130,23 +149,47 @@ export function InvoiceViewPage() {
 
     try {
       setExporting(true);
-      const path = await exportInvoicePdfToDownloads(payload);
-
-      message.success(t('invoiceView.pdfGenerated'));
-
-      try {
-        await openGeneratedPdf(path);
-      } catch {
-        message.info(t('common.path', { path }));
+      const exported = await exportInvoicePdfToDownloads(payload, { openAfterExport: true });
+
+      message.success({
+        content: (
+          <span>
+            {t('invoiceView.pdfGenerated')}
+            <Button
+              type="link"
+              size="small"
+              onClick={() => void revealInFolder(exported.path).catch((e) => message.error(String(e)))}
+            >
+              {t('common.showInFolder')}
+            </Button>
+          </span>
+        ),
+      });
+      if (!exported.opened) {
+        message.warning(exported.openError ?? t('common.path', { path: exported.path }));
       }
     } catch (e) {
-      const msg = typeof e === 'string' ? e : t('invoiceView.pdfError');
+      const msg = errorMessage(e, t('invoiceView.pdfError'));
       message.error(msg);
     } finally {
       setExporting(false);
     }
   };
 
+  const handleExportJson = async () => {
+    const path = await save({
+      defaultPath: `${invoice.invoiceNumber.replace(/[\\/:*?"<>|]/g, '-')}.json`,
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+    });
+    if (!path) return;
+    try {
+      const written = await storage.exportInvoiceJson(invoice.id, path);
+      message.success(t('invoiceView.jsonExported', { path: written }));
+    } catch (e) {
+      message.error(errorMessage(e, t('invoiceView.jsonError')));
+    }
+  };
+
   const handleUpdateInvoice = async (patch: Partial<Invoice>) => {
     if (!canWriteInvoices) {
       message.error(t('license.lockedDescription'));
@@ -174,7 +217,7 @@ export function InvoiceViewPage() {
   const overdueDays = getInvoiceOverdueDays(invoice);
 
   const smtpConfigured = isSmtpConfigured(settings);
-  const clientEmail = (client?.email ?? '').trim();
+  const clientEmail = (client?.billingEmail || client?.email || '').trim();
 
   const sendEmailDisabledReason = !smtpConfigured
     ? t('invoiceEmail.smtpNotConfigured')
@@ -188,6 +231,7 @@ export function InvoiceViewPage() {
       subject: t('invoiceEmail.defaultSubject', { invoiceNumber: invoice.invoiceNumber }),
       body: '',
       includePdf: true,
+      includeXml: false,
     });
     setSendEmailOpen(true);
   };
@@ -240,15 +284,16 @@ export function InvoiceViewPage() {
         subject: values.subject,
         body: values.body,
         includePdf: values.includePdf,
+        includeXml: !!values.includeXml,
       });
 
-      message.success(t('invoiceEmail.sent'));
+      message.success(t('invoiceEmail.queued'));
       setSendEmailOpen(false);
     } catch (e: unknown) {
       // AntD form validation errors throw; ignore those.
       if (typeof e === 'object' && e !== null && 'errorFields' in e) return;
 
-      const msg = typeof e === 'string' ? e : t('invoiceEmail.sendError');
+      const msg = featureLockedMessage(e, t) ?? errorMessage(e, t('invoiceEmail.sendError'));
       message.error(msg);
     } finally {
       setSendingEmail(false);
@@ -319,6 +364,16 @@ export function InvoiceViewPage() {
               </Button>
             </span>
           </Tooltip>
+          {invoice.status === 'DRAFT' ? (
+            <Button icon={<DollarOutlined />} disabled={!canWriteInvoices} onClick={() => setBillExpensesOpen(true)}>
+              {t('invoiceView.billExpenses.open')}
+            </Button>
+          ) : null}
+          {invoice.status === 'DRAFT' ? (
+            <Button icon={<ClockCircleOutlined />} disabled={!canWriteInvoices} onClick={() => setBillTimeOpen(true)}>
+              {t('invoiceView.billTime.open')}
+            </Button>
+          ) : null}
           <Button
             icon={<EditOutlined />}
             disabled={!canWriteInvoices}
@@ -326,6 +381,9 @@ export function InvoiceViewPage() {
           >
             {t('common.edit')}
           </Button>
+          <Button icon={<FileTextOutlined />} onClick={() => void handleExportJson()}>
+            {t('invoiceView.exportJson')}
+          </Button>
           <Button
             type="primary"
             icon={<FilePdfOutlined />}
@@ -342,9 +400,9 @@ export function InvoiceViewPage() {
         <div style={{ marginBottom: 32 }}>
           <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'flex-start' }}>
             <div>
-              {settings.logoUrl && (
+              {logoSrc && (
                 <img
-                  src={settings.logoUrl}
+                  src={logoSrc}
                   alt=""
                   style={{ maxHeight: 80, marginBottom: 16, objectFit: 'contain' }}
                 />
@@ -360,6 +418,7 @@ export function InvoiceViewPage() {
                 const registrationNumber = (settings.registrationNumber ?? '').trim();
                 const companyEmail = (settings.companyEmail ?? '').trim();
                 const companyPhone = (settings.companyPhone ?? '').trim();
+                const payment = paymentAccountFor(settings, invoice.currency);
 
                 return (
                   <Descriptions column={1} size="small" style={{ marginTop: 8 }}>
@@ -370,7 +429,11 @@ export function InvoiceViewPage() {
                     <Descriptions.Item label={t('settings.address')}>{addressFull || '-'}</Descriptions.Item>
                     <Descriptions.Item label={t('settings.companyEmail')}>{companyEmail || '-'}</Descriptions.Item>
                     <Descriptions.Item label={t('settings.companyPhone')}>{companyPhone || '-'}</Descriptions.Item>
-                    <Descriptions.Item label={t('settings.bankAccount')}>{settings.bankAccount}</Descriptions.Item>
+                    {payment.accountNumber ? (
+                      <Descriptions.Item label={t('settings.bankAccount')}>{payment.accountNumber}</Descriptions.Item>
+                    ) : null}
+                    {payment.iban ? <Descriptions.Item label="IBAN">{payment.iban}</Descriptions.Item> : null}
+                    {payment.swift ? <Descriptions.Item label="SWIFT/BIC">{payment.swift}</Descriptions.Item> : null}
                   </Descriptions>
                 );
               })()}
@@ -566,8 +629,25 @@ export function InvoiceViewPage() {
           <Form.Item name="includePdf" valuePropName="checked">
             <Checkbox>{t('invoiceEmail.includePdf')}</Checkbox>
           </Form.Item>
+
+          <Form.Item name="includeXml" valuePropName="checked">
+            <Checkbox>{t('invoiceEmail.includeXml')}</Checkbox>
+          </Form.Item>
         </Form>
       </Modal>
+
+      <BillExpensesModal
+        invoice={invoice}
+        open={billExpensesOpen}
+        onClose={() => setBillExpensesOpen(false)}
+        onBilled={setInvoice}
+      />
+      <BillTimeModal
+        invoice={invoice}
+        open={billTimeOpen}
+        onClose={() => setBillTimeOpen(false)}
+        onBilled={setInvoice}
+      />
     </div>
   );
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-import { useState } from 'react';
+import { useEffect, useState, type ReactNode } from 'react';
 import {
     Button,
     Table,
@@ -18,11 +18,16 @@ import {
     FilePdfOutlined,
     DeleteOutlined,
     EditOutlined,
+    UploadOutlined,
+    BankOutlined,
+    AppstoreOutlined,
+    ClockCircleOutlined,
+    FileTextOutlined,
 } from '@ant-design/icons';
 import { useNavigate } from 'react-router-dom';
 import dayjs from 'dayjs';
 
-import { Invoice, INVOICE_STATUS_VALUES } from '../types';
+import { Invoice, INVOICE_STATUS_VALUES, type InvoiceYear } from '../types';
 import { useInvoices } from '../hooks/useInvoices.ts';
 import { useClients } from '../hooks/useClients.ts';
 import { isInvoiceOverdue } from '../services/invoiceOverdue';
@@ -30,12 +35,20 @@ import { getStorage } from '../services/storageProvider';
 import {
     buildInvoicePdfPayload,
     exportInvoicePdfToDownloads,
-    openGeneratedPdf,
+    revealInFolder,
 } from '../services/invoicePdf';
 import { useTranslation } from 'react-i18next';
 import { getNumberLocale, normalizeLanguage } from '../i18n';
 import { useLicenseGate } from '../components/LicenseGate';
 import { isFeatureAllowed } from '../services/featureGate';
+import { errorMessage } from '../services/commandError';
+import { useSettings } from '../hooks/useSettings';
+import { InvoiceCsvImportModal } from '../components/InvoiceCsvImportModal';
+import { InvoiceFileImportModal } from '../components/InvoiceFileImportModal';
+import { PaymentCsvImportModal } from '../components/PaymentCsvImportModal';
+import { CatalogModal } from '../components/CatalogModal';
+import { TimeTrackingModal } from '../components/TimeTrackingModal';
+import { StatusSuggestions } from '../components/StatusSuggestions';
 
 const storage = getStorage();
 
@@ -43,6 +56,17 @@ const { RangePicker } = DatePicker;
 
 type InvoiceStatusFilter = Invoice['status'] | 'OVERDUE';
 
+/** A year separator row between the invoices of the table. */
+type YearRow = { kind: 'year'; key: string; year: number | null };
+type ListRow = Invoice | YearRow;
+
+const isYearRow = (row: ListRow): row is YearRow => 'kind' in row && row.kind === 'year';
+
+/** The issue year the way `invoices_by_year` derives it; null when the date isn't `YYYY-MM-DD`. */
+function issueYear(invoice: Invoice): number | null {
+    return /^\d{4}-[01]\d-[0-3]\d/.test(invoice.issueDate ?? '') ? Number(invoice.issueDate.slice(0, 4)) : null;
+}
+
 export function InvoicesPage() {
     const { t, i18n } = useTranslation();
     const navigate = useNavigate();
@@ -51,10 +75,18 @@ export function InvoicesPage() {
     const canWriteInvoices = isFeatureAllowed(status, 'INVOICES_WRITE');
     const canExportPdf = isFeatureAllowed(status, 'INVOICES_EXPORT_PDF');
 
-    const { invoices, deleteInvoice } = useInvoices();
-    const { clients } = useClients();
+    const { invoices, deleteInvoice, refresh } = useInvoices();
+    const { settings } = useSettings();
+    // Archived clients still appear in the filter: their invoices remain.
+    const { clients } = useClients({ includeArchived: true });
 
     const [exportingId, setExportingId] = useState<string | null>(null);
+    const [isImportOpen, setIsImportOpen] = useState(false);
+    const [isFileImportOpen, setIsFileImportOpen] = useState(false);
+    const [isPaymentsOpen, setIsPaymentsOpen] = useState(false);
+    const [isCatalogOpen, setIsCatalogOpen] = useState(false);
+    const [isTimeOpen, setIsTimeOpen] = useState(false);
+    const [years, setYears] = useState<InvoiceYear[]>([]);
 
     const [searchText, setSearchText] = useState('');
     const [selectedClient, setSelectedClient] = useState<string | undefined>();
@@ -63,6 +95,14 @@ export function InvoicesPage() {
         [dayjs.Dayjs | null, dayjs.Dayjs | null] | null
     >(null);
 
+    // Reloaded with the list, so the separators follow every change.
+    useEffect(() => {
+        void storage
+            .invoicesByYear()
+            .then(setYears)
+            .catch(() => setYears([]));
+    }, [invoices]);
+
     const handleDelete = async (id: string) => {
         if (!canWriteInvoices) {
             message.error(t('license.lockedDescription'));
@@ -127,17 +167,27 @@ export function InvoicesPage() {
 
         try {
             setExportingId(invoice.id);
-            const path = await exportInvoicePdfToDownloads(payload);
-
-            message.success(t('invoiceView.pdfGenerated'));
-
-            try {
-                await openGeneratedPdf(path);
-            } catch {
-                message.info(t('common.path', { path }));
+            const exported = await exportInvoicePdfToDownloads(payload, { openAfterExport: true });
+
+            message.success({
+                content: (
+                    <span>
+                        {t('invoiceView.pdfGenerated')}
+                        <Button
+                            type="link"
+                            size="small"
+                            onClick={() => void revealInFolder(exported.path).catch((e) => message.error(String(e)))}
+                        >
+                            {t('common.showInFolder')}
+                        </Button>
+                    </span>
+                ),
+            });
+            if (!exported.opened) {
+                message.warning(exported.openError ?? t('common.path', { path: exported.path }));
             }
         } catch (e) {
-                const msg = typeof e === 'string' ? e : t('invoiceView.pdfError');
+                const msg = errorMessage(e, t('invoiceView.pdfError'));
             message.error(msg);
         } finally {
             setExportingId(null);
@@ -174,17 +224,57 @@ export function InvoicesPage() {
         return matchesSearch && matchesClient && matchesStatus && matchesDate;
     });
 
+    // Newest year first, invoices without a usable issue date last; newest first within a year.
     const sortedInvoices = [...filteredInvoices].sort(
-        (a, b) => new Date(b.createdAt).getTime() - new Date(a.createdAt).getTime()
+        (a, b) =>
+            (issueYear(b) ?? -1) - (issueYear(a) ?? -1) ||
+            new Date(b.createdAt).getTime() - new Date(a.createdAt).getTime()
     );
 
+    const rows: ListRow[] = [];
+    for (const invoice of sortedInvoices) {
+        const year = issueYear(invoice);
+        const last = rows[rows.length - 1];
+        if (!last || (isYearRow(last) ? last.year : issueYear(last)) !== year) {
+            rows.push({ kind: 'year', key: `year-${year ?? 'unknown'}`, year });
+        }
+        rows.push(invoice);
+    }
+
+    const numberLocale = getNumberLocale(normalizeLanguage(i18n.language));
+
+    /** "2024 — 38 faktura — 3.450.000,00 RSD"; the amounts are of sent and paid invoices. */
+    const yearLabel = (year: number | null) => {
+        const summary = years.find((y) => y.year === year);
+        const issued = new Map<string, number>();
+        for (const total of summary?.totals ?? []) {
+            if (total.status === 'SENT' || total.status === 'PAID') {
+                issued.set(total.currency, (issued.get(total.currency) ?? 0) + total.total);
+            }
+        }
+        const parts = [
+            year === null ? t('invoices.years.unknown') : String(year),
+            t('invoices.years.count', { count: summary?.count ?? 0 }),
+            ...[...issued].map(
+                ([currency, amount]) =>
+                    `${amount.toLocaleString(numberLocale, { minimumFractionDigits: 2 })} ${currency}`
+            ),
+        ];
+        return parts.join(' — ');
+    };
+
     const columns = [
         {
             title: t('invoices.number'),
             dataIndex: 'invoiceNumber',
             key: 'invoiceNumber',
             width: 150,
-            render: (text: string) => <strong>{text}</strong>,
+            render: (text: string, record: Invoice) => (
+                <>
+                    <strong>{text}</strong>
+                    {record.imported ? <Tag style={{ marginLeft: 6 }}>{t('invoices.importedTag')}</Tag> : null}
+                </>
+            ),
         },
         {
             title: t('invoices.status'),
@@ -228,7 +318,7 @@ export function InvoicesPage() {
             width: 150,
             render: (total: number, record: Invoice) => (
                 <strong>
-                    {total.toLocaleString(getNumberLocale(normalizeLanguage(i18n.language)), { minimumFractionDigits: 2 })}{' '}
+                    {total.toLocaleString(numberLocale, { minimumFractionDigits: 2 })}{' '}
                     {record.currency}
                 </strong>
             ),
@@ -298,6 +388,20 @@ export function InvoicesPage() {
         },
     ];
 
+    const tableColumns = columns.map((column, index) => {
+        const render = column.render as ((value: any, record: Invoice) => ReactNode) | undefined;
+        return {
+            ...column,
+            onCell: (row: ListRow) => (isYearRow(row) ? { colSpan: index === 0 ? columns.length : 0 } : {}),
+            render: (value: any, row: ListRow) => {
+                if (isYearRow(row)) {
+                    return index === 0 ? <strong>{yearLabel(row.year)}</strong> : null;
+                }
+                return render ? render(value, row) : value;
+            },
+        };
+    });
+
     return (
         <div>
             <div
@@ -309,15 +413,47 @@ export function InvoicesPage() {
                 }}
             >
                 <h2 style={{ margin: 0 }}>{t('invoices.title')}</h2>
-                <Button
-                    type="primary"
-                    icon={<PlusOutlined />}
-                    size="large"
-                    disabled={!canWriteInvoices}
-                    onClick={() => navigate('/invoices/new')}
-                >
-                    {t('invoices.new')}
-                </Button>
+                <Space>
+                    <Button icon={<ClockCircleOutlined />} size="large" onClick={() => setIsTimeOpen(true)}>
+                        {t('time.open')}
+                    </Button>
+                    <Button icon={<AppstoreOutlined />} size="large" onClick={() => setIsCatalogOpen(true)}>
+                        {t('catalog.open')}
+                    </Button>
+                    <Button
+                        icon={<BankOutlined />}
+                        size="large"
+                        disabled={!canWriteInvoices}
+                        onClick={() => setIsPaymentsOpen(true)}
+                    >
+                        {t('invoices.payments.open')}
+                    </Button>
+                    <Button
+                        icon={<UploadOutlined />}
+                        size="large"
+                        disabled={!canWriteInvoices}
+                        onClick={() => setIsImportOpen(true)}
+                    >
+                        {t('invoices.import.open')}
+                    </Button>
+                    <Button
+                        icon={<FileTextOutlined />}
+                        size="large"
+                        disabled={!canWriteInvoices}
+                        onClick={() => setIsFileImportOpen(true)}
+                    >
+                        {t('invoices.fileImport.open')}
+                    </Button>
+                    <Button
+                        type="primary"
+                        icon={<PlusOutlined />}
+                        size="large"
+                        disabled={!canWriteInvoices}
+                        onClick={() => navigate('/invoices/new')}
+                    >
+                        {t('invoices.new')}
+                    </Button>
+                </Space>
             </div>
 
             <Space direction="vertical" size="middle" style={{ width: '100%', marginBottom: 16 }}>
@@ -357,14 +493,16 @@ export function InvoicesPage() {
                 </Space>
             </Space>
 
-            <Table
-                columns={columns}
-                dataSource={sortedInvoices}
-                rowKey="id"
+            <StatusSuggestions canWrite={canWriteInvoices} />
+
+            <Table<ListRow>
+                columns={tableColumns}
+                dataSource={rows}
+                rowKey={(row) => (isYearRow(row) ? row.key : row.id)}
                 pagination={{
                     pageSize: 10,
                     showSizeChanger: true,
-                    showTotal: (total) => t('invoices.totalCount', { count: total }),
+                    showTotal: () => t('invoices.totalCount', { count: sortedInvoices.length }),
                 }}
                 locale={{
                     emptyText: (
@@ -380,6 +518,25 @@ export function InvoicesPage() {
                     ),
                 }}
             />
+
+            <InvoiceCsvImportModal
+                open={isImportOpen}
+                defaultCurrency={settings?.defaultCurrency ?? 'RSD'}
+                onClose={() => setIsImportOpen(false)}
+                onImported={() => void refresh()}
+            />
+            <InvoiceFileImportModal
+                open={isFileImportOpen}
+                onClose={() => setIsFileImportOpen(false)}
+                onImported={() => void refresh()}
+            />
+            <PaymentCsvImportModal
+                open={isPaymentsOpen}
+                onClose={() => setIsPaymentsOpen(false)}
+                onImported={() => void refresh()}
+            />
+            <CatalogModal open={isCatalogOpen} canWrite={canWriteInvoices} onClose={() => setIsCatalogOpen(false)} />
+            <TimeTrackingModal open={isTimeOpen} canWrite={canWriteInvoices} onClose={() => setIsTimeOpen(false)} />
         </div>
     );
 }
\ No newline at end of file
@@ -2,7 +2,14 @@ import { useEffect, useMemo, useState } from 'react';
 import { Alert, Button, Card, Descriptions, Form, Input, Modal, Radio, Space, Typography, message } from 'antd';
 import { CopyOutlined, KeyOutlined, SafetyCertificateOutlined } from '@ant-design/icons';
 import { useTranslation } from 'react-i18next';
-import { generateActivationCode, getLicenseStatus, getStoredLicense, validateAndStoreLicense } from '../services/licenseService';
+import {
+  deactivateStoredLicense,
+  generateActivationCode,
+  getDeactivationReceipt,
+  getLicenseStatus,
+  getStoredLicense,
+  validateAndStoreLicense,
+} from '../services/licenseService';
 import { getAppMeta, setAppMeta } from '../services/licenseCodeGenerator';
 import type { LicenseStatus } from '../types/license';
 import { getDevForcedLockInfo, setDevForcedLockLevelPersisted } from '../services/devLockService';
@@ -44,14 +51,13 @@ export function LicensePage() {
   const [licenseInput, setLicenseInput] = useState('');
   const [activating, setActivating] = useState(false);
 
+  const [hasStoredLicense, setHasStoredLicense] = useState(false);
+  const [receipt, setReceipt] = useState<string | null>(null);
+  const [deactivating, setDeactivating] = useState(false);
+
   const trialInfo = getTrialInfo();
 
-  const trialRemainingDays = useMemo(() => {
-    if (!trialInfo) return null;
-    const endMs = Date.parse(trialInfo.trialEndsAt);
-    if (!Number.isFinite(endMs)) return null;
-    return msToDays(endMs - Date.now());
-  }, [trialInfo]);
+  const trialRemainingDays = trialInfo ? trialInfo.daysRemaining : null;
 
   const refresh = async () => {
     setStatusLoading(true);
@@ -64,7 +70,9 @@ export function LicensePage() {
       const s = await getLicenseStatus();
       setStatus(s);
 
-      if (s.isLicensed) {
+      if (s.gracePeriod) {
+        setStatusText(t('license.statusGrace', { days: s.graceDaysRemaining ?? 0 }));
+      } else if (s.isLicensed) {
         if (s.validUntil) {
           const d = dayjs(s.validUntil);
           const until = d.isValid() ? d.format('YYYY-MM-DD') : s.validUntil;
@@ -85,9 +93,11 @@ export function LicensePage() {
       }
 
       const stored = getStoredLicense();
+      setHasStoredLicense(!!stored);
       if (stored && !licenseInput.trim()) {
         setLicenseInput(stored);
       }
+      setReceipt(await getDeactivationReceipt());
 
       if (import.meta.env.DEV) {
         setDevLockInfoLoading(true);
@@ -167,9 +177,11 @@ export function LicensePage() {
     if (activating) return;
     setActivating(true);
     try {
-      const ok = await validateAndStoreLicense(licenseInput);
-      if (!ok) {
-        message.error(t('license.invalidLicense'));
+      const result = await validateAndStoreLicense(licenseInput);
+      if (!result.activated) {
+        message.error(
+          result.reason ? t(`license.reasons.${result.reason}`, { defaultValue: result.reason }) : t('license.invalidLicense')
+        );
         return;
       }
       message.success(t('license.activated'));
@@ -179,6 +191,38 @@ export function LicensePage() {
     }
   };
 
+  const doDeactivate = () => {
+    Modal.confirm({
+      title: t('license.deactivateConfirmTitle'),
+      content: t('license.deactivateConfirmBody'),
+      okText: t('license.deactivate'),
+      okButtonProps: { danger: true },
+      onOk: async () => {
+        setDeactivating(true);
+        try {
+          setReceipt(await deactivateStoredLicense());
+          setLicenseInput('');
+          message.success(t('license.deactivated'));
+          await refresh();
+        } catch (e) {
+          message.error(`${t('license.deactivateError')}: ${String(e)}`);
+        } finally {
+          setDeactivating(false);
+        }
+      },
+    });
+  };
+
+  const doCopyReceipt = async () => {
+    if (!receipt) return;
+    try {
+      await navigator.clipboard.writeText(receipt);
+      message.success(t('license.copied'));
+    } catch {
+      message.error(t('license.copyError'));
+    }
+  };
+
   const vendorEmail = useMemo(() => 'dragisa1984@yahoo.com', []);
 
   const defaultEmailSubject = useMemo(() => {
@@ -280,8 +324,9 @@ export function LicensePage() {
 
   const statusReasonText = useMemo(() => {
     if (!status?.reason) return null;
-    return String(status.reason);
-  }, [status]);
+    const reason = String(status.reason);
+    return t(`license.reasons.${reason}`, { defaultValue: reason });
+  }, [status, t]);
 
   return (
     <div style={{ maxWidth: 900 }}>
@@ -290,7 +335,7 @@ export function LicensePage() {
       </Typography.Title>
 
       <Alert
-        type="info"
+        type={status?.gracePeriod ? 'warning' : 'info'}
         showIcon
         message={t('license.statusTitle')}
         description={statusLoading ? t('common.loading') : statusText}
@@ -411,6 +456,35 @@ export function LicensePage() {
         </Card>
       ) : null}
 
+      {hasStoredLicense || receipt ? (
+        <Card title={t('license.transferTitle')} style={{ marginTop: 16 }}>
+          <Space direction="vertical" style={{ width: '100%' }} size="middle">
+            <Typography.Paragraph style={{ marginBottom: 0 }}>
+              {t('license.transferHelp')}
+            </Typography.Paragraph>
+
+            {hasStoredLicense ? (
+              <Space>
+                <Button danger onClick={doDeactivate} loading={deactivating}>
+                  {t('license.deactivate')}
+                </Button>
+              </Space>
+            ) : null}
+
+            {receipt ? (
+              <Form layout="vertical">
+                <Form.Item label={t('license.receiptLabel')} style={{ marginBottom: 8 }}>
+                  <Input.TextArea value={receipt} readOnly autoSize={{ minRows: 3, maxRows: 8 }} />
+                </Form.Item>
+                <Button icon={<CopyOutlined />} onClick={() => void doCopyReceipt()}>
+                  {t('license.copy')}
+                </Button>
+              </Form>
+            ) : null}
+          </Space>
+        </Card>
+      ) : null}
+
       {import.meta.env.DEV ? (
         <Card title="Dev Tools" style={{ marginTop: 16 }}>
           <Space direction="vertical" style={{ width: '100%' }} size="middle">
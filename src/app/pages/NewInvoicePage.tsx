@@ -1,5 +1,6 @@
 import { useEffect, useMemo, useState } from 'react';
 import {
+  AutoComplete,
   Form,
   Input,
   Select,
@@ -23,10 +24,12 @@ import { useNavigate, useLocation } from 'react-router-dom';
 import dayjs from 'dayjs';
 
 import {
+  CatalogItem,
   Client,
   CURRENCY_VALUES,
   Invoice,
   INVOICE_UNIT_VALUES,
+  InvoiceCustomField,
   InvoiceItem,
   InvoiceUnit,
   invoiceUnitLabel,
@@ -41,6 +44,13 @@ import { useSerbiaCities, type SerbiaCitySelectOption } from '../hooks/useSerbia
 
 const storage = getStorage();
 
+/** Quantity × unit price less the line discount, which is clamped to the line. */
+function lineTotal(item: InvoiceItem): number {
+  const lineSubtotal = Number(item.quantity || 0) * Number(item.unitPrice || 0);
+  const lineDiscount = Math.min(Math.max(Number(item.discountAmount || 0), 0), lineSubtotal);
+  return lineSubtotal - lineDiscount;
+}
+
 type LocationState =
   | { duplicate?: Invoice; duplicateId?: string }
   | { edit?: Invoice; editId?: string }
@@ -61,11 +71,19 @@ export function NewInvoicePage() {
   const numberLocale = getNumberLocale(normalizeLanguage(i18n.language));
 
   const [form] = Form.useForm();
+  const selectedClientId = Form.useWatch('clientId', form);
+  const selectedCurrency = Form.useWatch('currency', form);
+  const invoiceDiscount = Form.useWatch('invoiceDiscount', form);
+  const invoiceDiscountPercent = Form.useWatch('invoiceDiscountPercent', form);
+  const [defaultCurrency, setDefaultCurrency] = useState<string | null>(null);
   const [items, setItems] = useState<InvoiceItem[]>([]);
+  const [catalogMatches, setCatalogMatches] = useState<CatalogItem[]>([]);
   const [clients, setClients] = useState<Client[]>([]);
   const [isClientModalVisible, setIsClientModalVisible] = useState(false);
   const [clientForm] = Form.useForm();
   const [invoiceNumberPreview, setInvoiceNumberPreview] = useState<string | null>(null);
+  // One key per opened form, so a double-submitted click creates a single invoice.
+  const [idempotencyKey] = useState(() => crypto.randomUUID());
 
   const editId = useMemo(() => {
     if (!state) return undefined;
@@ -129,11 +147,18 @@ export function NewInvoicePage() {
       };
     });
 
+  useEffect(() => {
+    void storage.getSettings().then((s) => setDefaultCurrency(s.defaultCurrency));
+  }, []);
+
+  const needsExchangeRate = !!defaultCurrency && !!selectedCurrency && selectedCurrency !== defaultCurrency;
+
   useEffect(() => {
     let cancelled = false;
 
     void (async () => {
-      const loadedClients = await storage.getAllClients();
+      // Archived clients are loaded so an existing invoice still shows its client.
+      const loadedClients = await storage.getAllClients({ includeArchived: true });
       if (!cancelled) setClients(loadedClients);
 
       if (editId) {
@@ -149,7 +174,12 @@ export function NewInvoicePage() {
           issueDate: dayjs(existing.issueDate),
           serviceDate: dayjs(existing.serviceDate),
           currency: existing.currency,
+          exchangeRate: existing.exchangeRate ?? undefined,
+          invoiceDiscount: existing.invoiceDiscount || undefined,
+          invoiceDiscountPercent: existing.invoiceDiscountPercent || undefined,
           notes: existing.notes,
+          issuedBy: existing.issuedBy ?? '',
+          customFields: existing.customFields ?? [],
         });
         if (!cancelled) setItems(normalizeItems(existing.items));
         return;
@@ -168,7 +198,11 @@ export function NewInvoicePage() {
           issueDate: dayjs(),
           serviceDate: dayjs(),
           currency: existing.currency,
+          invoiceDiscount: existing.invoiceDiscount || undefined,
+          invoiceDiscountPercent: existing.invoiceDiscountPercent || undefined,
           notes: existing.notes,
+          issuedBy: existing.issuedBy ?? '',
+          customFields: existing.customFields ?? [],
         });
         if (!cancelled) setItems(normalizeItems(existing.items));
         return;
@@ -181,7 +215,11 @@ export function NewInvoicePage() {
           issueDate: dayjs(),
           serviceDate: dayjs(),
           currency: d.currency,
+          invoiceDiscount: d.invoiceDiscount || undefined,
+          invoiceDiscountPercent: d.invoiceDiscountPercent || undefined,
           notes: d.notes,
+          issuedBy: d.issuedBy ?? '',
+          customFields: d.customFields ?? [],
         });
         if (!cancelled) setItems(normalizeItems(d.items));
         return;
@@ -192,6 +230,8 @@ export function NewInvoicePage() {
         issueDate: dayjs(),
         serviceDate: dayjs(),
         currency: settings.defaultCurrency,
+        issuedBy: settings.issuedBy ?? '',
+        customFields: (settings.customFieldLabels ?? []).map((label) => ({ label, value: '' })),
       });
       if (!cancelled) setItems([]);
     })();
@@ -245,16 +285,38 @@ export function NewInvoicePage() {
         }
 
         if (field === 'quantity' || field === 'unitPrice' || field === 'discountAmount') {
-          const lineSubtotal = Number(updated.quantity || 0) * Number(updated.unitPrice || 0);
-          const rawDiscount = Number(updated.discountAmount || 0);
-          const lineDiscount = Math.min(Math.max(rawDiscount, 0), lineSubtotal);
-          updated.total = lineSubtotal - lineDiscount;
+          updated.total = lineTotal(updated);
         }
         return updated;
       })
     );
   };
 
+  const searchCatalog = (query: string) => {
+    storage
+      .searchCatalog(query, 10)
+      .then(setCatalogMatches)
+      .catch(() => setCatalogMatches([]));
+  };
+
+  /** Fills the line from a catalog item; the line keeps this copy even if the catalog changes later. */
+  const handlePickCatalogItem = (id: string, entry: CatalogItem) => {
+    setItems((prev) =>
+      prev.map((item) => {
+        if (item.id !== id) return item;
+        const updated: InvoiceItem = {
+          ...item,
+          description: entry.name,
+          unit: normalizeInvoiceUnit(entry.unit),
+          unitPrice: entry.unitPrice,
+          catalogItemId: entry.id,
+        };
+        updated.total = lineTotal(updated);
+        return updated;
+      })
+    );
+  };
+
   const calculateTotals = () => {
     const subtotal = items.reduce(
       (sum, item) => sum + Number(item.quantity || 0) * Number(item.unitPrice || 0),
@@ -266,8 +328,14 @@ export function NewInvoicePage() {
       const lineDiscount = Math.min(Math.max(rawDiscount, 0), lineSubtotal);
       return sum + lineDiscount;
     }, 0);
-    const total = subtotal - discountTotal;
-    return { subtotal, total };
+    // The invoice discount comes off the net after line discounts, as on the PDF.
+    const net = Math.max(subtotal - discountTotal, 0);
+    const flat = Math.max(Number(invoiceDiscount || 0), 0);
+    const percent = Math.min(Math.max(Number(invoiceDiscountPercent || 0), 0), 100);
+    const requestedDiscount = flat + (net * percent) / 100;
+    const invoiceDiscountAmount = Math.min(requestedDiscount, net);
+    const total = net - invoiceDiscountAmount;
+    return { subtotal, net, requestedDiscount, invoiceDiscount: invoiceDiscountAmount, total };
   };
 
   const handleAddClient = async (values: Omit<Client, 'id' | 'createdAt'>) => {
@@ -275,7 +343,21 @@ export function NewInvoicePage() {
       message.error(t('license.lockedDescription'));
       return;
     }
-    const newClient = await storage.createClient(values);
+    const result = await storage.createClient(values);
+    if (result.status === 'duplicate') {
+      // Reuse the existing client instead of splitting its invoice history.
+      form.setFieldValue('clientId', result.existingId);
+      setIsClientModalVisible(false);
+      clientForm.resetFields();
+      message.info(
+        t('clients.duplicateSelected', {
+          name: result.existingName,
+          field: t(`clients.duplicateMatch.${result.matchedOn}`),
+        })
+      );
+      return;
+    }
+    const newClient = result.client;
     setClients((prev) => [...prev, newClient]);
     form.setFieldValue('clientId', newClient.id);
     setIsClientModalVisible(false);
@@ -312,6 +394,15 @@ export function NewInvoicePage() {
       }
 
       const totals = calculateTotals();
+      // Small epsilon so a discount equal to the net isn't rejected by float noise.
+      if (totals.requestedDiscount > totals.net + 0.005) {
+        message.error(t('newInvoice.invoiceDiscountTooLarge'));
+        return;
+      }
+      // Rows left without a value (an unused default label) are not stored.
+      const customFields: InvoiceCustomField[] = (values.customFields ?? [])
+        .map((f: InvoiceCustomField) => ({ label: (f.label ?? '').trim(), value: (f.value ?? '').trim() }))
+        .filter((f: InvoiceCustomField) => f.value);
 
       if (editId) {
         const updated: Partial<Invoice> = {
@@ -320,10 +411,16 @@ export function NewInvoicePage() {
           issueDate: values.issueDate.format('YYYY-MM-DD'),
           serviceDate: values.serviceDate.format('YYYY-MM-DD'),
           currency: values.currency,
+          exchangeRate: needsExchangeRate ? (values.exchangeRate ?? null) : null,
           items,
           subtotal: totals.subtotal,
+          // 0 rather than null: a null in the patch leaves the stored discount unchanged.
+          invoiceDiscount: values.invoiceDiscount ?? 0,
+          invoiceDiscountPercent: values.invoiceDiscountPercent ?? 0,
           total: totals.total,
           notes: values.notes || '',
+          issuedBy: values.issuedBy?.trim() ?? '',
+          customFields,
         };
 
         const saved = await storage.updateInvoice(editId, updated);
@@ -354,13 +451,28 @@ export function NewInvoicePage() {
         dueDate: null,
         paidAt: null,
         currency: values.currency,
+        exchangeRate: needsExchangeRate ? (values.exchangeRate ?? null) : null,
         items,
         subtotal: totals.subtotal,
+        invoiceDiscount: values.invoiceDiscount ?? null,
+        invoiceDiscountPercent: values.invoiceDiscountPercent ?? null,
         total: totals.total,
         notes: values.notes || '',
+        issuedBy: values.issuedBy?.trim() || null,
+        customFields,
       };
-      const created = await storage.createInvoice(invoice);
+      const created = await storage.createInvoice(invoice, { idempotencyKey });
       message.success(t('newInvoice.created'));
+      for (const w of created.limitWarnings ?? []) {
+        message.warning(
+          t(`limits.warning.${w.kind}`, {
+            percent: w.thresholdPercent,
+            limit: w.limitRsd.toLocaleString(numberLocale),
+            turnover: w.turnoverRsd.toLocaleString(numberLocale),
+          }),
+          8,
+        );
+      }
 
       if (exportPDF) {
         message.info(t('newInvoice.exportInDev'));
@@ -387,13 +499,22 @@ export function NewInvoicePage() {
       key: 'description',
       width: '34%',
       render: (_: string, record: InvoiceItem) => (
-        <Input
+        <AutoComplete
           placeholder={t('newInvoice.descriptionPlaceholder')}
           value={record.description}
           disabled={!canWriteInvoices}
-          onChange={(e) =>
-            handleItemChange(record.id, 'description', e.target.value)
+          options={catalogMatches.map((c) => ({
+            value: c.name,
+            label: `${c.name} · ${c.unitPrice.toFixed(2)}`,
+            entry: c,
+          }))}
+          onFocus={() => searchCatalog(record.description)}
+          onSearch={searchCatalog}
+          onSelect={(_, option) => handlePickCatalogItem(record.id, option.entry)}
+          onChange={(value) =>
+            handleItemChange(record.id, 'description', value)
           }
+          style={{ width: '100%' }}
         />
       ),
     },
@@ -554,7 +675,15 @@ export function NewInvoicePage() {
                 placeholder={t('newInvoice.selectClient')}
                 showSearch
                 optionFilterProp="label"
-                options={clients.map((c) => ({ label: c.name, value: c.id }))}
+                options={clients
+                  .filter((c) => !c.archived || c.id === selectedClientId)
+                  .map((c) => ({ label: c.name, value: c.id }))}
+                onChange={(id: string) => {
+                  const selected = clients.find((c) => c.id === id);
+                  if (!isEditMode && selected?.defaultCurrency) {
+                    form.setFieldValue('currency', selected.defaultCurrency);
+                  }
+                }}
                 dropdownRender={(menu) => (
                   <>
                     {menu}
@@ -590,6 +719,16 @@ export function NewInvoicePage() {
               />
             </Form.Item>
 
+            {needsExchangeRate && (
+              <Form.Item
+                label={t('newInvoice.exchangeRate', { currency: selectedCurrency, defaultCurrency })}
+                help={t('newInvoice.exchangeRateHelp')}
+                name="exchangeRate"
+              >
+                <InputNumber min={0.000001} step={0.0001} style={{ width: '100%' }} />
+              </Form.Item>
+            )}
+
             {!isEditMode && (
               <Form.Item
                 label={t('newInvoice.invoiceNumberPreviewLabel')}
@@ -641,9 +780,43 @@ export function NewInvoicePage() {
 
         <div style={{ display: 'grid', gridTemplateColumns: '1fr 400px', gap: 24 }}>
           <Card title={t('newInvoice.notes')}>
-            <Form.Item name="notes" style={{ marginBottom: 0 }}>
+            <Form.Item name="notes">
               <Input.TextArea rows={6} placeholder={t('newInvoice.notesPlaceholder')} />
             </Form.Item>
+            <Form.Item label={t('newInvoice.issuedBy')} name="issuedBy">
+              <Input placeholder={t('settings.issuedByPlaceholder')} />
+            </Form.Item>
+            <Form.List name="customFields">
+              {(fields, { add, remove }) => (
+                <>
+                  {fields.map((field) => (
+                    <Space key={field.key} align="baseline" style={{ display: 'flex' }}>
+                      <Form.Item
+                        name={[field.name, 'label']}
+                        rules={[{ max: 40, message: t('newInvoice.customFieldTooLong', { max: 40 }) }]}
+                      >
+                        <Input placeholder={t('newInvoice.customFieldLabel')} />
+                      </Form.Item>
+                      <Form.Item
+                        name={[field.name, 'value']}
+                        rules={[{ max: 120, message: t('newInvoice.customFieldTooLong', { max: 120 }) }]}
+                      >
+                        <Input placeholder={t('newInvoice.customFieldValue')} />
+                      </Form.Item>
+                      <Button type="text" danger icon={<DeleteOutlined />} onClick={() => remove(field.name)} />
+                    </Space>
+                  ))}
+                  <Button
+                    type="dashed"
+                    icon={<PlusOutlined />}
+                    onClick={() => add({ label: '', value: '' })}
+                    disabled={fields.length >= 5}
+                  >
+                    {t('newInvoice.addCustomField')}
+                  </Button>
+                </>
+              )}
+            </Form.List>
           </Card>
 
           <Card title={t('newInvoice.summary')}>
@@ -654,6 +827,26 @@ export function NewInvoicePage() {
                   {totals.subtotal.toLocaleString(numberLocale, { minimumFractionDigits: 2 })}
                 </strong>
               </div>
+              <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 12 }}>
+                <Form.Item label={t('newInvoice.invoiceDiscount')} name="invoiceDiscount" style={{ marginBottom: 12 }}>
+                  <InputNumber min={0} step={100} precision={2} style={{ width: '100%' }} />
+                </Form.Item>
+                <Form.Item
+                  label={t('newInvoice.invoiceDiscountPercent')}
+                  name="invoiceDiscountPercent"
+                  style={{ marginBottom: 12 }}
+                >
+                  <InputNumber min={0} max={100} step={1} precision={2} style={{ width: '100%' }} />
+                </Form.Item>
+              </div>
+              {totals.invoiceDiscount > 0 && (
+                <div style={{ display: 'flex', justifyContent: 'space-between', marginBottom: 12 }}>
+                  <span>{t('newInvoice.invoiceDiscount')}:</span>
+                  <strong>
+                    -{totals.invoiceDiscount.toLocaleString(numberLocale, { minimumFractionDigits: 2 })}
+                  </strong>
+                </div>
+              )}
               <Divider style={{ margin: '12px 0' }} />
               <div style={{ display: 'flex', justifyContent: 'space-between', fontSize: 18 }}>
                 <strong>{t('newInvoice.total')}:</strong>
@@ -22,7 +22,7 @@ import type { Offer } from '../types';
 import { useOffers } from '../hooks/useOffers';
 import { getStorage } from '../services/storageProvider';
 import { useLicenseGate } from '../components/LicenseGate';
-import { isFeatureAllowed } from '../services/featureGate';
+import { featureLockedMessage, isFeatureAllowed } from '../services/featureGate';
 import { useSettings } from '../hooks/useSettings';
 
 const storage = getStorage();
@@ -203,7 +203,10 @@ export function NewOfferPage() {
       if ((error as { errorFields?: unknown[] })?.errorFields) {
         message.error(t('newOffer.validationError'));
       } else {
-        if (didAttemptSend) {
+        const locked = featureLockedMessage(error, t);
+        if (locked) {
+          message.error(locked);
+        } else if (didAttemptSend) {
           console.error('Failed to send offer', error);
           setOfferStatus('FAILED');
           const nextFailureCount = sendFailureCount + 1;
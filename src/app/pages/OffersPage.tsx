@@ -13,6 +13,7 @@ import {
 import {
   DeleteOutlined,
   EditOutlined,
+  FileTextOutlined,
   MailOutlined,
   PlusOutlined,
 } from '@ant-design/icons';
@@ -23,7 +24,9 @@ import { useTranslation } from 'react-i18next';
 import type { Offer } from '../types';
 import { useOffers } from '../hooks/useOffers';
 import { useLicenseGate } from '../components/LicenseGate';
-import { isFeatureAllowed } from '../services/featureGate';
+import { QuotesModal } from '../components/QuotesModal';
+import { featureLockedMessage, isFeatureAllowed } from '../services/featureGate';
+import { errorMessage } from '../services/commandError';
 import { getNumberLocale, normalizeLanguage } from '../i18n';
 
 function renderOfferStatusTag(t: (key: string) => string, status: Offer['status']) {
@@ -48,6 +51,7 @@ export function OffersPage() {
 
   const [searchText, setSearchText] = useState('');
   const [sendingId, setSendingId] = useState<string | null>(null);
+  const [quotesOpen, setQuotesOpen] = useState(false);
 
   const filteredOffers = useMemo(() => {
     const query = searchText.trim().toLowerCase();
@@ -90,7 +94,7 @@ export function OffersPage() {
       await sendOfferEmail(offerId);
       message.success(t('offers.sendSuccess'));
     } catch (error) {
-      const msg = typeof error === 'string' ? error : t('offers.sendError');
+      const msg = featureLockedMessage(error, t) ?? errorMessage(error, t('offers.sendError'));
       message.error(msg);
     } finally {
       setSendingId(null);
@@ -214,6 +218,9 @@ export function OffersPage() {
             allowClear
             style={{ width: 280 }}
           />
+          <Button icon={<FileTextOutlined />} size="large" onClick={() => setQuotesOpen(true)}>
+            {t('quotes.title')}
+          </Button>
           <Button
             type="primary"
             icon={<PlusOutlined />}
@@ -244,6 +251,13 @@ export function OffersPage() {
           ),
         }}
       />
+
+      <QuotesModal
+        open={quotesOpen}
+        canWrite={canWriteOffers}
+        canSend={canSendOffers}
+        onClose={() => setQuotesOpen(false)}
+      />
     </div>
   );
 }
\ No newline at end of file
@@ -1,9 +1,12 @@
 import { useEffect, useMemo, useState } from 'react';
-import { Card, Col, Radio, Row, Space, Statistic, Table, Typography } from 'antd';
+import { Card, Col, Progress, Radio, Row, Space, Statistic, Table, Typography } from 'antd';
 import { useTranslation } from 'react-i18next';
 import { useInvoices } from '../hooks/useInvoices';
-import { useExpenses } from '../hooks/useExpenses';
 import { useSettings } from '../hooks/useSettings';
+import { getStorage } from '../services/storageProvider';
+import type { DashboardSummary, LimitStatus, TopClient, TopItem, TopRanking } from '../types';
+
+const storage = getStorage();
 
 type Scope = 'month' | 'year';
 
@@ -12,13 +15,6 @@ type Period = {
   endUtcMs: number;
 };
 
-const pad2 = (n: number) => String(n).padStart(2, '0');
-
-function utcMsToYmd(ms: number): string {
-  const d = new Date(ms);
-  return `${d.getUTCFullYear()}-${pad2(d.getUTCMonth() + 1)}-${pad2(d.getUTCDate())}`;
-}
-
 function parseYmdOrIsoToUtcMs(value?: string | null): number | null {
   if (!value) return null;
   const s = value.trim();
@@ -64,113 +60,94 @@ function formatMoneyAmount(v: number): string {
   return v.toFixed(2);
 }
 
+function toYmd(ms: number): string {
+  return new Date(ms).toISOString().slice(0, 10);
+}
+
 export function OverviewPage() {
   const { t, i18n } = useTranslation();
   const { invoices } = useInvoices();
   const { settings } = useSettings();
   const defaultCurrency = settings?.defaultCurrency ?? 'RSD';
 
-  const { expenses, listExpenses } = useExpenses();
   const [scope, setScope] = useState<Scope>('month');
+  const [summary, setSummary] = useState<DashboardSummary | null>(null);
+  const [limitStatus, setLimitStatus] = useState<LimitStatus | null>(null);
+  const [topClients, setTopClients] = useState<TopRanking<TopClient>[]>([]);
+  const [topItems, setTopItems] = useState<TopRanking<TopItem>[]>([]);
 
   const period = useMemo(() => currentPeriod(scope), [scope]);
+  const year = new Date(period.startUtcMs).getUTCFullYear();
 
   useEffect(() => {
-    const from = utcMsToYmd(period.startUtcMs);
-    const to = utcMsToYmd(period.endUtcMs);
-    void listExpenses({ from, to });
-  }, [listExpenses, period.endUtcMs, period.startUtcMs]);
-
-  const stats = useMemo(() => {
-    const issuedInPeriod = invoices
-      .filter((inv) => inv.currency === defaultCurrency)
-      .filter((inv) => inPeriod(parseYmdOrIsoToUtcMs(inv.issueDate), period));
-
-    // Paid alignment choice: use paidAt when present; fallback to issueDate.
-    const paidInPeriod = invoices.filter((inv) => {
-      if (inv.status !== 'PAID') return false;
-      if (inv.currency !== defaultCurrency) return false;
-      const paidAtMs = parseYmdOrIsoToUtcMs(inv.paidAt);
-      const issueMs = parseYmdOrIsoToUtcMs(inv.issueDate);
-      return inPeriod(paidAtMs ?? issueMs, period);
+    let cancelled = false;
+    void storage.dashboardSummary(year).then((s) => {
+      if (!cancelled) setSummary(s);
     });
+    void storage.limitStatus(year).then((s) => {
+      if (!cancelled) setLimitStatus(s);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [year, invoices]);
 
-    const issuedAmount = issuedInPeriod.reduce((sum, inv) => sum + (inv.total || 0), 0);
-    const paidAmount = paidInPeriod.reduce((sum, inv) => sum + (inv.total || 0), 0);
-
-    const overdue = issuedInPeriod.filter((inv) => {
-      if (inv.status === 'PAID') return false;
-      const dueMs = parseYmdOrIsoToUtcMs(inv.dueDate);
-      if (dueMs === null) return false;
-      return dueMs < todayUtcMs();
+  useEffect(() => {
+    let cancelled = false;
+    const range = { from: toYmd(period.startUtcMs), to: toYmd(period.endUtcMs) };
+    void storage.topClients(range).then((r) => {
+      if (!cancelled) setTopClients(r);
     });
+    void storage.topItems(range).then((r) => {
+      if (!cancelled) setTopItems(r);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [period, invoices]);
 
-    const overdueAmount = overdue.reduce((sum, inv) => sum + (inv.total || 0), 0);
-    const unpaidAmount = issuedAmount - paidAmount;
+  const currencySummary = summary?.currencies.find((c) => c.currency === defaultCurrency);
+  const clientRanking = topClients.find((r) => r.currency === defaultCurrency)?.entries ?? [];
+  const itemRanking = topItems.find((r) => r.currency === defaultCurrency)?.entries ?? [];
+
+  const stats = useMemo(() => {
+    const monthIndex = new Date(period.startUtcMs).getUTCMonth();
+    const totals =
+      scope === 'month' ? currencySummary?.months[monthIndex] : currencySummary?.yearToDate;
 
-    const expensesInPeriod = expenses
-      .filter((e) => e.currency === defaultCurrency)
-      .filter((e) => inPeriod(parseYmdOrIsoToUtcMs(e.date), period));
-    const expensesAmount = expensesInPeriod.reduce((sum, e) => sum + (e.amount || 0), 0);
-    const netAmount = issuedAmount - expensesAmount;
+    // Overdue depends on due dates (not aggregated by the backend), so it still uses the invoice list.
+    const overdue = invoices
+      .filter((inv) => inv.currency === defaultCurrency)
+      .filter((inv) => inPeriod(parseYmdOrIsoToUtcMs(inv.issueDate), period))
+      .filter((inv) => {
+        if (inv.status !== 'SENT') return false;
+        const dueMs = parseYmdOrIsoToUtcMs(inv.dueDate);
+        if (dueMs === null) return false;
+        return dueMs < todayUtcMs();
+      });
 
     return {
-      issuedAmount,
-      paidAmount,
-      unpaidAmount,
+      issuedAmount: totals?.invoiced ?? 0,
+      paidAmount: totals?.paid ?? 0,
+      unpaidAmount: totals?.outstanding ?? 0,
       overdueCount: overdue.length,
-      overdueAmount,
-      expensesAmount,
-      netAmount,
+      overdueAmount: overdue.reduce((sum, inv) => sum + (inv.total || 0), 0),
+      expensesAmount: totals?.expenses ?? 0,
+      netAmount: totals?.net ?? 0,
       currencySuffix: defaultCurrency,
     };
-  }, [defaultCurrency, expenses, invoices, period]);
+  }, [currencySummary, defaultCurrency, invoices, period, scope]);
 
   const tableData = useMemo(() => {
-    if (scope !== 'year') return [];
-
-    const now = new Date();
-    const year = now.getUTCFullYear();
-    const endMs = todayUtcMs();
-
-    const rows = Array.from({ length: 12 }, (_, idx) => {
-      const monthIndex = idx; // 0-11
-      const start = Date.UTC(year, monthIndex, 1);
-      const next = Date.UTC(year, monthIndex + 1, 1);
-      const monthPeriod: Period = {
-        startUtcMs: start,
-        endUtcMs: Math.min(endMs, next - 1),
-      };
-
-      const issued = invoices
-        .filter((inv) => inv.currency === defaultCurrency)
-        .filter((inv) => inPeriod(parseYmdOrIsoToUtcMs(inv.issueDate), monthPeriod))
-        .reduce((sum, inv) => sum + (inv.total || 0), 0);
-
-      const paid = invoices
-        .filter((inv) => {
-          if (inv.status !== 'PAID') return false;
-          if (inv.currency !== defaultCurrency) return false;
-          const paidAtMs = parseYmdOrIsoToUtcMs(inv.paidAt);
-          const issueMs = parseYmdOrIsoToUtcMs(inv.issueDate);
-          return inPeriod(paidAtMs ?? issueMs, monthPeriod);
-        })
-        .reduce((sum, inv) => sum + (inv.total || 0), 0);
-
-      const monthLabel = new Date(Date.UTC(year, monthIndex, 1)).toLocaleString(i18n.language, {
-        month: 'short',
-      });
-
-      return {
-        key: `${year}-${pad2(monthIndex + 1)}`,
-        month: monthLabel,
-        issued,
-        paid,
-      };
-    });
+    if (scope !== 'year' || !currencySummary) return [];
 
-    return rows;
-  }, [defaultCurrency, i18n.language, invoices, scope]);
+    return currencySummary.months.map((m, monthIndex) => ({
+      key: m.month,
+      month: new Date(Date.UTC(year, monthIndex, 1)).toLocaleString(i18n.language, { month: 'short' }),
+      issued: m.invoiced,
+      paid: m.paid,
+    }));
+  }, [currencySummary, i18n.language, scope, year]);
 
   return (
     <Space direction="vertical" size={16} style={{ width: '100%' }}>
@@ -252,6 +229,86 @@ export function OverviewPage() {
         </Col>
       </Row>
 
+      <Row gutter={[16, 16]}>
+        <Col xs={24} lg={12}>
+          <Card title={t('overview.topClients')}>
+            <Table
+              dataSource={clientRanking}
+              rowKey="clientId"
+              size="small"
+              pagination={false}
+              locale={{ emptyText: t('overview.topEmpty') }}
+              columns={[
+                { title: t('overview.topClient'), dataIndex: 'name', key: 'name' },
+                { title: t('overview.topInvoices'), dataIndex: 'invoiceCount', key: 'invoiceCount', align: 'right' },
+                {
+                  title: t('overview.topRevenue'),
+                  dataIndex: 'revenue',
+                  key: 'revenue',
+                  align: 'right',
+                  render: (v: number) => `${formatMoneyAmount(v)} ${stats.currencySuffix}`,
+                },
+                { title: t('overview.topShare'), dataIndex: 'share', key: 'share', align: 'right', render: (v: number) => `${v.toFixed(1)}%` },
+              ]}
+            />
+          </Card>
+        </Col>
+        <Col xs={24} lg={12}>
+          <Card title={t('overview.topItems')}>
+            <Table
+              dataSource={itemRanking}
+              rowKey="description"
+              size="small"
+              pagination={false}
+              locale={{ emptyText: t('overview.topEmpty') }}
+              columns={[
+                { title: t('overview.topItem'), dataIndex: 'description', key: 'description' },
+                { title: t('overview.topInvoices'), dataIndex: 'invoiceCount', key: 'invoiceCount', align: 'right' },
+                {
+                  title: t('overview.topRevenue'),
+                  dataIndex: 'revenue',
+                  key: 'revenue',
+                  align: 'right',
+                  render: (v: number) => `${formatMoneyAmount(v)} ${stats.currencySuffix}`,
+                },
+                { title: t('overview.topShare'), dataIndex: 'share', key: 'share', align: 'right', render: (v: number) => `${v.toFixed(1)}%` },
+              ]}
+            />
+          </Card>
+        </Col>
+      </Row>
+
+      {limitStatus && (
+        <Card title={t('limits.title', { year: limitStatus.year })}>
+          <Row gutter={[16, 16]}>
+            {limitStatus.limits.map((l) => (
+              <Col key={l.kind} xs={24} md={12}>
+                <Typography.Text strong>{t(`limits.${l.kind}`)}</Typography.Text>
+                <Progress
+                  percent={Math.min(l.usedPercent, 100)}
+                  format={() => `${l.usedPercent.toFixed(1)}%`}
+                  status={l.usedPercent >= 100 ? 'exception' : 'normal'}
+                  strokeColor={l.usedPercent >= 80 && l.usedPercent < 100 ? '#faad14' : undefined}
+                />
+                <Typography.Text type="secondary">
+                  {t('limits.usage', {
+                    used: formatMoneyAmount(limitStatus.turnoverRsd),
+                    limit: formatMoneyAmount(l.limitRsd),
+                  })}
+                  {' · '}
+                  {t('limits.projected', { percent: l.projectedPercent.toFixed(1) })}
+                </Typography.Text>
+              </Col>
+            ))}
+          </Row>
+          {limitStatus.unconvertedInvoices.length > 0 && (
+            <Typography.Paragraph type="warning" style={{ marginTop: 12, marginBottom: 0 }}>
+              {t('limits.unconverted', { invoices: limitStatus.unconvertedInvoices.join(', ') })}
+            </Typography.Paragraph>
+          )}
+        </Card>
+      )}
+
       {scope === 'year' && (
         <Card title={t('overview.tableTitle')}>
           <Table
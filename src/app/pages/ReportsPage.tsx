@@ -5,9 +5,11 @@ import { InfoCircleOutlined } from '@ant-design/icons';
 import dayjs from 'dayjs';
 import { useTranslation } from 'react-i18next';
 
-import type { Expense, Invoice } from '../types';
+import type { ExpenseCurrencySummary, Invoice } from '../types';
 import { getStorage } from '../services/storageProvider';
 import { useSettings } from '../hooks/useSettings';
+import { ReceivablesReport } from '../components/ReceivablesReport';
+import { TaxSummaryReport } from '../components/TaxSummaryReport';
 
 const storage = getStorage();
 
@@ -68,7 +70,7 @@ export function ReportsPage() {
   const { settings } = useSettings();
   const defaultCurrency = settings?.defaultCurrency ?? 'RSD';
 
-  const [activeTab, setActiveTab] = useState<'monthly' | 'yearly'>('monthly');
+  const [activeTab, setActiveTab] = useState<'monthly' | 'yearly' | 'receivables' | 'tax'>('monthly');
 
   const [selectedMonth, setSelectedMonth] = useState<dayjs.Dayjs>(() => dayjs().startOf('month'));
   const [selectedYear, setSelectedYear] = useState<dayjs.Dayjs>(() => dayjs().startOf('year'));
@@ -77,10 +79,10 @@ export function ReportsPage() {
   const [yearlyLoading, setYearlyLoading] = useState(false);
 
   const [monthlyInvoices, setMonthlyInvoices] = useState<Invoice[]>([]);
-  const [monthlyExpenses, setMonthlyExpenses] = useState<Expense[]>([]);
+  const [monthlyExpenses, setMonthlyExpenses] = useState<ExpenseCurrencySummary[]>([]);
 
   const [yearlyInvoices, setYearlyInvoices] = useState<Invoice[]>([]);
-  const [yearlyExpenses, setYearlyExpenses] = useState<Expense[]>([]);
+  const [yearlyExpenses, setYearlyExpenses] = useState<ExpenseCurrencySummary[]>([]);
 
   const monthRange = useMemo(() => {
     const from = selectedMonth.startOf('month').format('YYYY-MM-DD');
@@ -102,7 +104,7 @@ export function ReportsPage() {
       try {
         const [inv, exp] = await Promise.all([
           storage.listInvoicesRange(monthRange.from, monthRange.to),
-          storage.listExpenses({ from: monthRange.from, to: monthRange.to }),
+          storage.expenseSummary({ from: monthRange.from, to: monthRange.to }, 'month'),
         ]);
         setMonthlyInvoices(inv);
         setMonthlyExpenses(exp);
@@ -120,7 +122,7 @@ export function ReportsPage() {
       try {
         const [inv, exp] = await Promise.all([
           storage.listInvoicesRange(yearRange.from, yearRange.to),
-          storage.listExpenses({ from: yearRange.from, to: yearRange.to }),
+          storage.expenseSummary({ from: yearRange.from, to: yearRange.to }, 'month'),
         ]);
         setYearlyInvoices(inv);
         setYearlyExpenses(exp);
@@ -144,13 +146,11 @@ export function ReportsPage() {
       .filter((inv) => inv.status === 'PAID')
       .filter((inv) => inYmdRange(normalizeToYmd(inv.paidAt), from, to));
 
-    const expensesInRange = monthlyExpenses
-      .filter((e) => e.currency === defaultCurrency)
-      .filter((e) => inYmdRange(normalizeToYmd(e.date), from, to));
+    const expenses = monthlyExpenses.find((s) => s.currency === defaultCurrency);
 
     const issuedTotal = issued.reduce((sum, inv) => sum + (inv.total || 0), 0);
     const paidTotal = paid.reduce((sum, inv) => sum + (inv.total || 0), 0);
-    const expensesTotal = expensesInRange.reduce((sum, e) => sum + (e.amount || 0), 0);
+    const expensesTotal = expenses?.total ?? 0;
 
     return {
       issuedTotal,
@@ -158,13 +158,14 @@ export function ReportsPage() {
       paidTotal,
       paidCount: paid.length,
       expensesTotal,
-      expensesCount: expensesInRange.length,
+      expensesCount: expenses?.count ?? 0,
       net: paidTotal - expensesTotal,
     };
   }, [defaultCurrency, monthRange.from, monthRange.to, monthlyExpenses, monthlyInvoices]);
 
   const yearRows = useMemo<YearRow[]>(() => {
     const year = selectedYear.year();
+    const yearlyExpenseRows = yearlyExpenses.find((s) => s.currency === defaultCurrency)?.rows ?? [];
 
     const monthLabels = [
       t('reports.months.jan'),
@@ -195,16 +196,15 @@ export function ReportsPage() {
         .filter((inv) => inv.status === 'PAID')
         .filter((inv) => inYmdRange(normalizeToYmd(inv.paidAt), from, to));
 
-      const expensesInRange = yearlyExpenses
-        .filter((e) => e.currency === defaultCurrency)
-        .filter((e) => inYmdRange(normalizeToYmd(e.date), from, to));
+      const key = `${year}-${String(monthIndex + 1).padStart(2, '0')}`;
+      const expenses = yearlyExpenseRows.find((r) => r.key === key);
 
       const issuedTotal = issued.reduce((sum, inv) => sum + (inv.total || 0), 0);
       const paidTotal = paid.reduce((sum, inv) => sum + (inv.total || 0), 0);
-      const expensesTotal = expensesInRange.reduce((sum, e) => sum + (e.amount || 0), 0);
+      const expensesTotal = expenses?.total ?? 0;
 
       return {
-        key: `${year}-${String(monthIndex + 1).padStart(2, '0')}`,
+        key,
         monthIndex,
         monthLabel: monthLabels[monthIndex] ?? String(monthIndex + 1),
         issuedTotal,
@@ -212,7 +212,7 @@ export function ReportsPage() {
         paidTotal,
         paidCount: paid.length,
         expensesTotal,
-        expensesCount: expensesInRange.length,
+        expensesCount: expenses?.count ?? 0,
         net: paidTotal - expensesTotal,
       };
     });
@@ -463,7 +463,7 @@ export function ReportsPage() {
 
       <Tabs
         activeKey={activeTab}
-        onChange={(k) => setActiveTab(k as 'monthly' | 'yearly')}
+        onChange={(k) => setActiveTab(k as 'monthly' | 'yearly' | 'receivables' | 'tax')}
         items={[
           {
             key: 'monthly',
@@ -475,6 +475,16 @@ export function ReportsPage() {
             label: t('reports.tabs.yearly'),
             children: renderYearly(),
           },
+          {
+            key: 'receivables',
+            label: t('reports.tabs.receivables'),
+            children: <ReceivablesReport />,
+          },
+          {
+            key: 'tax',
+            label: t('reports.tabs.tax'),
+            children: <TaxSummaryReport />,
+          },
         ]}
       />
     </Space>
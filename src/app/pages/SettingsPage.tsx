@@ -2,8 +2,11 @@ import { useEffect, useMemo, useState } from 'react';
 import { Alert, Collapse, Descriptions, Divider, Form, Input, InputNumber, Button, message, Select, Space, Upload, Switch, Tabs, Typography } from 'antd';
 import { SaveOutlined, UploadOutlined } from '@ant-design/icons';
 import { InfoCircleOutlined, MailOutlined } from '@ant-design/icons';
-import { Settings, CURRENCY_VALUES } from '../types';
+import { Settings, CURRENCY_VALUES, type SettingsProblem } from '../types';
 import { useSettings } from '../hooks/useSettings';
+import { useCompanyLogo } from '../hooks/useCompanyLogo';
+import { getStorage } from '../services/storageProvider';
+import { taxIdRule } from '../services/taxIds';
 import { useTranslation } from 'react-i18next';
 import i18n, { normalizeLanguage } from '../i18n';
 import { useSerbiaCities, type SerbiaCitySelectOption } from '../hooks/useSerbiaCities';
@@ -14,6 +17,19 @@ import { sendTestEmail } from '../services/smtpTest';
 import { getVersion } from '@tauri-apps/api/app';
 import { open } from '@tauri-apps/plugin-shell';
 import { checkForUpdatesCached, type UpdateManifest } from '../services/updateService.ts';
+import { AutoBackups } from '../components/AutoBackups';
+import { BankAccountsFields } from '../components/BankAccountsFields';
+import { CsvOptionsFields } from '../components/CsvOptionsFields';
+import { EmailThemeFields } from '../components/EmailThemeFields';
+import { InvoiceNotesFields } from '../components/InvoiceNotesFields';
+import { DataArchive } from '../components/DataArchive';
+import { SettingsTransfer } from '../components/SettingsTransfer';
+import { WebhookFields } from '../components/WebhookFields';
+import { AppPinSettings } from '../components/AppPinSettings';
+import { DataConsistency } from '../components/DataConsistency';
+import { DatabaseHealth } from '../components/DatabaseHealth';
+import { DatabaseLocation } from '../components/DatabaseLocation';
+import { Diagnostics } from '../components/Diagnostics';
 import { createBackupArchive, inspectBackupArchive, pickBackupOpenPath, pickBackupSavePath, quitApp, stageRestoreArchive, getLastBackupMetadata, type LastBackupInfo } from '../services/backupService';
 
 function sanitizeSmtpPassword(value: string): string {
@@ -24,9 +40,10 @@ export function SettingsPage() {
   const { t } = useTranslation();
   const [form] = Form.useForm<Settings>();
   const { settings, loading, save } = useSettings();
-  const [logoUrl, setLogoUrl] = useState('');
+  const { logoSrc, setLogo } = useCompanyLogo();
   const [testingEmail, setTestingEmail] = useState(false);
   const [activeTabKey, setActiveTabKey] = useState<string>('company');
+  const [problems, setProblems] = useState<SettingsProblem[]>([]);
   const serbiaCities = useSerbiaCities();
   // SMTP password UX state
   const [smtpPasswordSaved, setSmtpPasswordSaved] = useState(false);
@@ -71,8 +88,12 @@ export function SettingsPage() {
 
     setTestingEmail(true);
     try {
-      await sendTestEmail();
-      message.success(t('settings.emailHelp.testSuccess'));
+      const result = await sendTestEmail();
+      message.success(
+        t('settings.emailHelp.testSuccessTrust', {
+          trust: t(`settings.smtpTrustPath.${result.trustPath}`),
+        })
+      );
     } catch (e: any) {
       const msg = (e && typeof e === 'object' && 'message' in e) ? String(e.message) : String(e);
       message.error(t('settings.emailHelp.testError', { message: msg }));
@@ -81,6 +102,14 @@ export function SettingsPage() {
     }
   };
 
+  useEffect(() => {
+    if (!settings?.isConfigured) return;
+    getStorage()
+      .validateSettings()
+      .then((res) => setProblems(res.problems))
+      .catch(() => {});
+  }, [settings]);
+
   useEffect(() => {
     if (!settings) return;
     const next: any = { ...settings };
@@ -88,9 +117,8 @@ export function SettingsPage() {
       next.smtpTlsMode = next.smtpPort === 465 ? 'implicit' : 'starttls';
     }
     form.setFieldsValue(next);
-    setLogoUrl(settings.logoUrl || '');
     // Derive whether password exists; do not prefill password field for security
-    const hasPwd = !!String(settings.smtpPassword ?? '').trim();
+    const hasPwd = settings.smtpPasswordConfigured ?? !!String(settings.smtpPassword ?? '').trim();
     setSmtpPasswordSaved(hasPwd);
     setSmtpEditMode(false);
     form.setFieldValue('smtpPassword', '');
@@ -234,25 +262,39 @@ export function SettingsPage() {
         return;
       }
       const sanitizedSmtpPassword = sanitizeSmtpPassword(String(values.smtpPassword ?? ''));
-      await save({ ...values, smtpPassword: sanitizedSmtpPassword, logoUrl });
+      await save({ ...values, smtpPassword: sanitizedSmtpPassword });
       message.success(t('settings.saved'));
       await i18n.changeLanguage(normalizeLanguage(values.language));
-    } catch {
-      message.error(t('settings.saveError'));
+    } catch (e: any) {
+      // Backend validation (e.g. a bank account control number) comes back as a readable message.
+      const detail = e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e ?? '');
+      message.error(detail ? `${t('settings.saveError')}: ${detail}` : t('settings.saveError'));
     }
   };
 
   const handleLogoUpload = (file: File) => {
     const reader = new FileReader();
-    reader.onload = (e) => {
-      const dataUrl = e.target?.result as string;
-      setLogoUrl(dataUrl);
-      message.success(t('settings.logoLoaded'));
+    reader.onload = async (e) => {
+      try {
+        await setLogo(e.target?.result as string);
+        message.success(t('settings.logoLoaded'));
+      } catch (err: any) {
+        message.error(err && typeof err === 'object' && 'message' in err ? String(err.message) : String(err));
+      }
     };
     reader.readAsDataURL(file);
     return false;
   };
 
+  const handleLogoRemove = async () => {
+    try {
+      await setLogo('');
+      message.success(t('settings.logoRemoved'));
+    } catch (err: any) {
+      message.error(err && typeof err === 'object' && 'message' in err ? String(err.message) : String(err));
+    }
+  };
+
   return (
       <div style={{ maxWidth: '100%', minHeight: 'calc(100vh - 220px)' }}>
         <div style={{ marginBottom: 24 }}>
@@ -277,6 +319,21 @@ export function SettingsPage() {
                 label: t('settings.companyCard'),
                 children: (
                   <div style={{ paddingTop: 8 }}>
+                    {problems.length > 0 ? (
+                      <Alert
+                        type="warning"
+                        showIcon
+                        style={{ marginBottom: 16 }}
+                        message={t('settings.problemsTitle')}
+                        description={
+                          <ul style={{ margin: 0, paddingLeft: 18 }}>
+                            {problems.map((p) => (
+                              <li key={p.field}>{p.message}</li>
+                            ))}
+                          </ul>
+                        }
+                      />
+                    ) : null}
                     <Form.Item
                       label={t('settings.companyName')}
                       name="companyName"
@@ -289,7 +346,8 @@ export function SettingsPage() {
                       <Form.Item
                         label={t('settings.vatId')}
                         name="pib"
-                        rules={[{ required: true, message: t('settings.vatReq') }]}
+                        validateTrigger={['onChange', 'onBlur']}
+                        rules={[{ required: true, message: t('settings.vatReq') }, taxIdRule('pib')]}
                       >
                         <Input placeholder="123456789" />
                       </Form.Item>
@@ -297,18 +355,11 @@ export function SettingsPage() {
                       <Form.Item
                         label={t('settings.companyRegNumber')}
                         name="registrationNumber"
-                        rules={[{ required: true, message: t('settings.companyRegNumberReq') }]}
+                        validateTrigger={['onChange', 'onBlur']}
+                        rules={[{ required: true, message: t('settings.companyRegNumberReq') }, taxIdRule('mb')]}
                       >
                         <Input placeholder="12345678" />
                       </Form.Item>
-
-                      <Form.Item
-                        label={t('settings.bankAccount')}
-                        name="bankAccount"
-                        rules={[{ required: true, message: t('settings.bankReq') }]}
-                      >
-                        <Input placeholder="160-5100000000000-00" />
-                      </Form.Item>
                     </div>
 
                     <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
@@ -405,26 +456,26 @@ export function SettingsPage() {
                       </Form.Item>
                     </div>
 
+                    <Typography.Title level={5}>{t('settings.bankAccounts.title')}</Typography.Title>
+                    <BankAccountsFields />
+
                     <Form.Item label={t('settings.logo')}>
                       <div style={{ display: 'flex', alignItems: 'center', gap: 16 }}>
                         <Upload accept="image/*" beforeUpload={handleLogoUpload} showUploadList={false}>
                           <Button icon={<UploadOutlined />}>{t('settings.uploadLogo')}</Button>
                         </Upload>
 
-                        {logoUrl && (
+                        {logoSrc && (
                           <div>
                             <img
-                              src={logoUrl}
+                              src={logoSrc}
                               alt="Company logo"
                               style={{ maxHeight: 80, maxWidth: 200, objectFit: 'contain' }}
                             />
                             <Button
                               type="link"
                               danger
-                              onClick={() => {
-                                setLogoUrl('');
-                                message.success(t('settings.logoRemoved'));
-                              }}
+                              onClick={() => void handleLogoRemove()}
                             >
                               {t('settings.removeLogo')}
                             </Button>
@@ -530,6 +581,30 @@ export function SettingsPage() {
                       </Button>
                       <Typography.Text type="secondary">{t('settings.backup.restoreHelp')}</Typography.Text>
                     </Space>
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <DataArchive disabled={!canWriteSettings} />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <SettingsTransfer disabled={!canWriteSettings} />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <AutoBackups disabled={!canWriteSettings} />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <DataConsistency disabled={!canWriteSettings} />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <DatabaseHealth disabled={!canWriteSettings} />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <DatabaseLocation disabled={!canWriteSettings} />
                   </div>
                 ),
               },
@@ -564,6 +639,81 @@ export function SettingsPage() {
                       </Form.Item>
                     </div>
 
+                    <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+                      <Form.Item
+                        label={t('settings.turnoverLimitRsd')}
+                        name="turnoverLimitRsd"
+                        extra={t('settings.limitsHelp')}
+                      >
+                        <InputNumber min={1} step={100000} style={{ width: '100%' }} addonAfter="RSD" />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.vatLimitRsd')} name="vatLimitRsd">
+                        <InputNumber min={1} step={100000} style={{ width: '100%' }} addonAfter="RSD" />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.roundingMode')} name="roundingMode" extra={t('settings.roundingModeHelp')}>
+                        <Select
+                          options={(['halfUp', 'halfEven', 'down'] as const).map((m) => ({ value: m, label: t(`settings.roundingModes.${m}`) }))}
+                        />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.quotePrefix')} name="quotePrefix">
+                        <Input placeholder="PON" />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.nextQuoteNumber')} name="nextQuoteNumber">
+                        <InputNumber min={1} style={{ width: '100%' }} />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.staleDraftDays')} name="staleDraftDays" extra={t('settings.staleDraftDaysHelp')}>
+                        <InputNumber min={1} max={365} precision={0} style={{ width: '100%' }} />
+                      </Form.Item>
+
+                      <Form.Item label={t('settings.overdueGraceDays')} name="overdueGraceDays" extra={t('settings.overdueGraceDaysHelp')}>
+                        <InputNumber min={0} max={365} precision={0} style={{ width: '100%' }} />
+                      </Form.Item>
+
+                      <Form.Item
+                        label={t('settings.issuedBy')}
+                        name="issuedBy"
+                        extra={t('settings.issuedByHelp')}
+                        style={{ gridColumn: '1 / -1' }}
+                      >
+                        <Input placeholder={t('settings.issuedByPlaceholder')} />
+                      </Form.Item>
+
+                      <Form.Item
+                        label={t('settings.customFieldLabels')}
+                        name="customFieldLabels"
+                        extra={t('settings.customFieldLabelsHelp')}
+                        rules={[{ type: 'array', max: 5, message: t('settings.customFieldLabelsMax', { max: 5 }) }]}
+                        style={{ gridColumn: '1 / -1' }}
+                      >
+                        <Select mode="tags" open={false} tokenSeparators={[',']} placeholder={t('settings.customFieldLabelsPlaceholder')} />
+                      </Form.Item>
+
+                      <Form.Item
+                        label={t('settings.pdfFilenameTemplate')}
+                        name="pdfFilenameTemplate"
+                        extra={t('settings.pdfFilenameTemplateHelp')}
+                        rules={[{ required: true, whitespace: true, message: t('settings.pdfFilenameTemplateReq') }]}
+                        style={{ gridColumn: '1 / -1' }}
+                      >
+                        <Input placeholder="{year}/{client}/{number}" />
+                      </Form.Item>
+                    </div>
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <InvoiceNotesFields
+                      sampleNumber={`${form.getFieldValue('invoicePrefix') || 'INV'}-${(form.getFieldValue('nextInvoiceNumber') || 1).toString().padStart(4, '0')}`}
+                    />
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <CsvOptionsFields />
+
                     <div style={{ padding: 16, background: '#f5f5f5', borderRadius: 8, marginTop: 16 }}>
                       <strong>{t('settings.example')}:</strong>{' '}
                       {form.getFieldValue('invoicePrefix') || 'INV'}-
@@ -631,10 +781,69 @@ export function SettingsPage() {
                       </Form.Item>
                     </div>
 
+                    <Form.Item label={t('settings.smtpAuthMode')} name="smtpAuthMode" initialValue="password">
+                      <Select
+                        options={[
+                          { value: 'password', label: t('settings.smtpAuthModePassword') },
+                          { value: 'oauth2', label: t('settings.smtpAuthModeOauth2') },
+                        ]}
+                      />
+                    </Form.Item>
+
+                    <Form.Item dependencies={['smtpAuthMode']} noStyle>
+                      {({ getFieldValue }) =>
+                        getFieldValue('smtpAuthMode') === 'oauth2' ? (
+                          <div style={{ display: 'grid', gridTemplateColumns: '1fr 1fr', gap: 16 }}>
+                            <Form.Item label={t('settings.smtpOauthClientId')} name="smtpOauthClientId">
+                              <Input />
+                            </Form.Item>
+                            <Form.Item label={t('settings.smtpOauthClientSecret')} name="smtpOauthClientSecret">
+                              <Input.Password autoComplete="new-password" placeholder={t('settings.smtpOauthSecretPlaceholder')} />
+                            </Form.Item>
+                            <Form.Item
+                              label={t('settings.smtpOauthRefreshToken')}
+                              name="smtpOauthRefreshToken"
+                              extra={settings?.smtpOauthConfigured ? t('settings.smtpOauthConfigured') : undefined}
+                            >
+                              <Input.Password autoComplete="new-password" placeholder={t('settings.smtpOauthSecretPlaceholder')} />
+                            </Form.Item>
+                            <Form.Item
+                              label={t('settings.smtpOauthTokenEndpoint')}
+                              name="smtpOauthTokenEndpoint"
+                              rules={[{ pattern: /^https:\/\//, message: t('settings.smtpOauthTokenEndpointInvalid') }]}
+                            >
+                              <Input placeholder="https://oauth2.googleapis.com/token" />
+                            </Form.Item>
+                          </div>
+                        ) : null
+                      }
+                    </Form.Item>
+
                     <Form.Item label={t('settings.smtpFrom')} name="smtpFrom">
                       <Input placeholder={t('settings.smtpFromPlaceholder')} />
                     </Form.Item>
 
+                    <Form.Item label={t('settings.smtpFromName')} name="smtpFromName">
+                      <Input maxLength={100} placeholder={t('settings.smtpFromNamePlaceholder')} />
+                    </Form.Item>
+
+                    <Form.Item
+                      label={t('settings.smtpReplyTo')}
+                      name="smtpReplyTo"
+                      rules={[{ type: 'email', message: t('settings.smtpReplyToInvalid') }]}
+                    >
+                      <Input placeholder={t('settings.smtpReplyToPlaceholder')} />
+                    </Form.Item>
+
+                    <Form.Item
+                      label={t('settings.accountantEmail')}
+                      name="accountantEmail"
+                      extra={t('settings.accountantEmailHelp')}
+                      rules={[{ type: 'email', message: t('settings.smtpReplyToInvalid') }]}
+                    >
+                      <Input placeholder={t('settings.accountantEmailPlaceholder')} />
+                    </Form.Item>
+
                     <Form.Item dependencies={['smtpUseTls']} noStyle>
                       {({ getFieldValue }) => (
                         <Form.Item
@@ -677,6 +886,56 @@ export function SettingsPage() {
                       <Switch />
                     </Form.Item>
 
+                    <Form.Item
+                      label={t('settings.smtpPinnedCertPem')}
+                      name="smtpPinnedCertPem"
+                      extra={t('settings.smtpPinnedCertPemHelp')}
+                    >
+                      <Input.TextArea rows={4} placeholder="-----BEGIN CERTIFICATE-----" style={{ fontFamily: 'monospace' }} />
+                    </Form.Item>
+
+                    <Form.Item
+                      label={t('settings.smtpAcceptInvalidCerts')}
+                      name="smtpAcceptInvalidCerts"
+                      valuePropName="checked"
+                      extra={t('settings.smtpAcceptInvalidCertsHelp')}
+                    >
+                      <Switch />
+                    </Form.Item>
+
+                    <Form.Item dependencies={['smtpAcceptInvalidCerts']} noStyle>
+                      {({ getFieldValue }) =>
+                        getFieldValue('smtpAcceptInvalidCerts') ? (
+                          <Form.Item
+                            label={t('settings.smtpAcceptInvalidCertsAcknowledged')}
+                            name="smtpAcceptInvalidCertsAcknowledged"
+                            valuePropName="checked"
+                          >
+                            <Switch />
+                          </Form.Item>
+                        ) : null
+                      }
+                    </Form.Item>
+
+                    <Form.Item
+                      label={t('settings.sendCopyToSelf')}
+                      name="sendCopyToSelf"
+                      valuePropName="checked"
+                      extra={t('settings.sendCopyToSelfHelp')}
+                    >
+                      <Switch />
+                    </Form.Item>
+
+                    <Form.Item
+                      label={t('settings.emailMaxMessageMb')}
+                      name="emailMaxMessageMb"
+                      extra={t('settings.emailMaxMessageMbHelp')}
+                    >
+                      <InputNumber min={1} max={100} precision={0} style={{ width: '100%' }} addonAfter="MB" />
+                    </Form.Item>
+
+                    <EmailThemeFields />
+
                     <Divider style={{ margin: '12px 0' }} />
 
                     {!smtpActive ? (
@@ -993,6 +1252,24 @@ export function SettingsPage() {
                   </div>
                 ),
               },
+              {
+                key: 'webhooks',
+                label: t('settings.webhooks.tab'),
+                children: (
+                  <div style={{ paddingTop: 8 }}>
+                    <WebhookFields secretSaved={!!settings?.webhookSecretConfigured} canWrite={canWriteSettings} />
+                  </div>
+                ),
+              },
+              {
+                key: 'security',
+                label: t('appLock.settings.tab'),
+                children: (
+                  <div style={{ paddingTop: 8 }}>
+                    <AppPinSettings />
+                  </div>
+                ),
+              },
               {
                 key: 'language',
                 label: t('settings.languageCard'),
@@ -1118,6 +1395,10 @@ export function SettingsPage() {
                         />
                       </div>
                     ) : null}
+
+                    <Divider style={{ margin: '12px 0' }} />
+
+                    <Diagnostics disabled={!canWriteSettings} />
                   </div>
                 ),
               },
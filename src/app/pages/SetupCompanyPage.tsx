@@ -4,10 +4,10 @@ import { UploadOutlined } from '@ant-design/icons';
 import { useNavigate } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import type { Settings } from '../types';
+import { useCompanyLogo } from '../hooks/useCompanyLogo';
 import { useSerbiaCities, type SerbiaCitySelectOption } from '../hooks/useSerbiaCities';
 import { getStorage } from '../services/storageProvider';
 import { generateActivationCode } from '../services/licenseCodeGenerator';
-import { startTrialIfNeeded } from '../services/trialService';
 import { pickBackupOpenPath, inspectBackupArchive, stageRestoreArchive, quitApp } from '../services/backupService';
 
 type SetupCompanyPageProps = {
@@ -34,7 +34,7 @@ export function SetupCompanyPage({ onCompleted }: SetupCompanyPageProps) {
   const navigate = useNavigate();
   const [form] = Form.useForm<SetupCompanyForm>();
   const [saving, setSaving] = useState(false);
-  const [logoUrl, setLogoUrl] = useState('');
+  const { logoSrc, setLogo } = useCompanyLogo();
   const [activationCode, setActivationCode] = useState('');
   const [generating, setGenerating] = useState(false);
 
@@ -45,31 +45,51 @@ export function SetupCompanyPage({ onCompleted }: SetupCompanyPageProps) {
 
   const handleLogoUpload = (file: File) => {
     const reader = new FileReader();
-    reader.onload = (e) => {
-      const dataUrl = e.target?.result as string;
-      setLogoUrl(dataUrl);
-      message.success(t('settings.logoLoaded'));
+    reader.onload = async (e) => {
+      try {
+        await setLogo(e.target?.result as string);
+        message.success(t('settings.logoLoaded'));
+      } catch (err: any) {
+        message.error(err && typeof err === 'object' && 'message' in err ? String(err.message) : String(err));
+      }
     };
     reader.readAsDataURL(file);
     return false;
   };
 
+  const handleLogoRemove = async () => {
+    try {
+      await setLogo('');
+      message.success(t('settings.logoRemoved'));
+    } catch (err: any) {
+      message.error(err && typeof err === 'object' && 'message' in err ? String(err.message) : String(err));
+    }
+  };
+
   const handleSubmit = async (values: SetupCompanyForm) => {
     setSaving(true);
     try {
-      await storage.updateSettings({
-        ...values,
-        logoUrl,
-        isConfigured: true,
+      // Only the company fields; the form also carries the city picker's own value.
+      await storage.completeSetupStep('company', {
+        companyName: values.companyName,
+        registrationNumber: values.registrationNumber,
+        pib: values.pib,
+        companyAddressLine: values.companyAddressLine,
+        companyCity: values.companyCity,
+        companyPostalCode: values.companyPostalCode,
+        bankAccount: values.bankAccount,
+        companyEmail: values.companyEmail,
+        companyPhone: values.companyPhone,
       });
-
-      startTrialIfNeeded(values.pib);
+      await storage.finishSetup();
 
       onCompleted?.();
       message.success(t('settings.saved'));
       navigate('/', { replace: true });
-    } catch {
-      message.error(t('settings.saveError'));
+    } catch (e: any) {
+      // Finishing setup is refused with the list of missing or invalid company fields.
+      const msg = e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e ?? '');
+      message.error(msg || t('settings.saveError'));
     } finally {
       setSaving(false);
     }
@@ -290,20 +310,17 @@ export function SetupCompanyPage({ onCompleted }: SetupCompanyPageProps) {
                 <Button icon={<UploadOutlined />}>{t('settings.uploadLogo')}</Button>
               </Upload>
 
-              {logoUrl && (
+              {logoSrc && (
                 <div>
                   <img
-                    src={logoUrl}
+                    src={logoSrc}
                     alt="Company logo"
                     style={{ maxHeight: 80, maxWidth: 200, objectFit: 'contain' }}
                   />
                   <Button
                     type="link"
                     danger
-                    onClick={() => {
-                      setLogoUrl('');
-                      message.success(t('settings.logoRemoved'));
-                    }}
+                    onClick={() => void handleLogoRemove()}
                   >
                     {t('settings.removeLogo')}
                   </Button>
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invokeCommand } from './commandError';
 import { open, save } from '@tauri-apps/plugin-dialog';
 import i18n from '../i18n';
 
@@ -22,6 +22,19 @@ export type RestoreStageResult = {
   requiresRestart: boolean;
 };
 
+export type AutoBackupEntry = {
+  name: string;
+  createdAt: string;
+  sizeBytes: number;
+};
+
+export type AutoBackupList = {
+  /** Newest first. */
+  backups: AutoBackupEntry[];
+  /** Why the last startup backup failed, if it did. */
+  lastError?: string | null;
+};
+
 export type LastBackupInfo = {
   path: string;
   createdAt: string;
@@ -51,27 +64,91 @@ export async function pickBackupOpenPath(): Promise<string | null> {
 }
 
 export async function createBackupArchive(destPath: string): Promise<BackupResult> {
-  const res = await invoke<BackupResult>('create_backup_archive', { destPath });
+  const res = await invokeCommand<BackupResult>('create_backup_archive', { destPath });
   return res;
 }
 
 export async function inspectBackupArchive(archivePath: string): Promise<BackupMetadataResult> {
-  const res = await invoke<BackupMetadataResult>('inspect_backup_archive', { archivePath });
+  const res = await invokeCommand<BackupMetadataResult>('inspect_backup_archive', { archivePath });
   return res;
 }
 
 export async function stageRestoreArchive(archivePath: string): Promise<RestoreStageResult> {
-  const res = await invoke<RestoreStageResult>('stage_restore_archive', { archivePath });
+  const res = await invokeCommand<RestoreStageResult>('stage_restore_archive', { archivePath });
   return res;
 }
 
+export async function listAutoBackups(): Promise<AutoBackupList> {
+  return invokeCommand<AutoBackupList>('list_backups');
+}
+
+/** Stages an automatic backup for restore; it is applied on the next start. */
+export async function restoreAutoBackup(name: string): Promise<RestoreStageResult> {
+  return invokeCommand<RestoreStageResult>('restore_backup', { name });
+}
+
+export type DatabaseSource = 'appData' | 'appLocalData' | 'exeDir' | 'currentDir' | 'moved';
+
+export type DatabaseInfo = {
+  path: string;
+  sizeBytes: number;
+  walSizeBytes: number;
+  /** Which probed location was recorded on first start, or `moved`. */
+  source: DatabaseSource;
+  locationFile: string;
+  /** Other `pausaler.db` files in probed locations; the app does not use them. */
+  otherCandidates: { source: DatabaseSource; path: string; sizeBytes: number }[];
+};
+
+export type DatabaseMoveResult = {
+  database: DatabaseInfo;
+  oldBackupPath: string;
+};
+
+export async function getDatabaseInfo(): Promise<DatabaseInfo> {
+  return invokeCommand<DatabaseInfo>('get_database_info');
+}
+
+/** Copies the database to `path` (a folder or file), verifies it and switches to it; the old file is kept as `.bak`. */
+export async function moveDatabaseTo(path: string): Promise<DatabaseMoveResult> {
+  return invokeCommand<DatabaseMoveResult>('move_database_to', { path });
+}
+
+export type DataArchiveImportMode = 'replace' | 'merge';
+export type DataArchiveConflict = 'skip' | 'duplicate';
+
+export type DataArchiveTableReport = {
+  table: string;
+  inserted: number;
+  skipped: number;
+  duplicated: number;
+};
+
+export type DataArchiveImportReport = {
+  settingsImported: boolean;
+  tables: DataArchiveTableReport[];
+};
+
+/** Writes settings (without the SMTP password) and all data to one JSON file. */
+export async function exportAllJson(outputPath: string): Promise<string> {
+  return invokeCommand<string>('export_all_json', { outputPath });
+}
+
+export async function importAllJson(
+  path: string,
+  mode: DataArchiveImportMode,
+  onConflict: DataArchiveConflict
+): Promise<DataArchiveImportReport> {
+  return invokeCommand<DataArchiveImportReport>('import_all_json', { path, mode, onConflict });
+}
+
 export async function quitApp(): Promise<void> {
-  await invoke('quit_app');
+  await invokeCommand('quit_app');
 }
 
 export async function getLastBackupMetadata(): Promise<LastBackupInfo | null> {
   try {
-    const res = await invoke<LastBackupInfo>('get_last_backup_metadata');
+    const res = await invokeCommand<LastBackupInfo>('get_last_backup_metadata');
     return res;
   } catch (e: any) {
     const msg = e && typeof e === 'object' && 'message' in e ? String(e.message) : String(e);
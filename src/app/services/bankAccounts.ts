@@ -0,0 +1,23 @@
+import type { BankAccount, Settings } from '../types';
+
+export type PaymentAccount = {
+  accountNumber: string;
+  /** Only for foreign-currency invoices; a dinar invoice is paid to the domestic number. */
+  iban: string | null;
+  swift: string | null;
+};
+
+/** Same choice as the backend: an account in `currency` (the default one if several are), else the default account. */
+export function accountForCurrency(accounts: BankAccount[], currency: string): BankAccount | undefined {
+  const code = currency.trim().toUpperCase();
+  const matching = accounts.filter((a) => a.currency.toUpperCase() === code);
+  return matching.find((a) => a.isDefault) ?? matching[0] ?? accounts.find((a) => a.isDefault) ?? accounts[0];
+}
+
+export function paymentAccountFor(settings: Settings, currency: string): PaymentAccount {
+  const account = accountForCurrency(settings.bankAccounts ?? [], currency);
+  if (!account) return { accountNumber: settings.bankAccount, iban: null, swift: null };
+  const foreign = currency.trim().toUpperCase() !== 'RSD';
+  const foreignOnly = (v: string) => (foreign && v.trim() ? v.trim() : null);
+  return { accountNumber: account.accountNumber, iban: foreignOnly(account.iban), swift: foreignOnly(account.swift) };
+}
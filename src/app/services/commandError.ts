@@ -0,0 +1,54 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { CommandErrorCode, CommandErrorPayload } from '../types';
+
+/**
+ * A rejected Tauri command. `toString()` gives the localized message, so screens that still
+ * show `String(e)` keep working while they move to checking `code`.
+ */
+export class CommandError extends Error {
+  readonly code: CommandErrorCode;
+  readonly details: Record<string, unknown> | null;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.message);
+    this.name = 'CommandError';
+    this.code = payload.code;
+    this.details = payload.details ?? null;
+  }
+
+  override toString(): string {
+    return this.message;
+  }
+}
+
+function isPayload(value: unknown): value is CommandErrorPayload {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as { code?: unknown }).code === 'string' &&
+    typeof (value as { message?: unknown }).message === 'string'
+  );
+}
+
+/** Wraps what a command rejected with; plain strings (older commands) become `OTHER`. */
+export function toCommandError(error: unknown): CommandError {
+  if (error instanceof CommandError) return error;
+  if (isPayload(error)) return new CommandError(error);
+  const message = typeof error === 'string' ? error : error instanceof Error ? error.message : String(error);
+  return new CommandError({ code: 'OTHER', message, details: null });
+}
+
+/** Message to show for a failed call, or `fallback` when there's nothing useful to show. */
+export function errorMessage(error: unknown, fallback?: string): string {
+  const message = toCommandError(error).message.trim();
+  return message || fallback || '';
+}
+
+/** `invoke` that always rejects with a {@link CommandError}. */
+export async function invokeCommand<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  try {
+    return await invoke<T>(cmd, args);
+  } catch (e) {
+    throw toCommandError(e);
+  }
+}
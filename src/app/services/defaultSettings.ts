@@ -1,4 +1,26 @@
-import type { Settings } from '../types';
+import type { EmailTheme, Settings } from '../types';
+
+/** Mirrors `EmailTheme::default()` in src-tauri/src/email_theme.rs. */
+export const DEFAULT_EMAIL_THEME: EmailTheme = {
+  light: {
+    background: '#f6f7f9',
+    card: '#ffffff',
+    border: '#e6e8ec',
+    accent: '#111827',
+    text: '#111827',
+    muted: '#4b5563',
+    faint: '#6b7280',
+  },
+  dark: {
+    background: '#0f1115',
+    card: '#1b1e24',
+    border: '#2f343d',
+    accent: '#f9fafb',
+    text: '#e5e7eb',
+    muted: '#aab1bd',
+    faint: '#8e96a3',
+  },
+};
 
 export const defaultSettings: Settings = {
   isConfigured: false,
@@ -11,6 +33,7 @@ export const defaultSettings: Settings = {
   companyEmail: '',
   companyPhone: '',
   bankAccount: '',
+  bankAccounts: [],
   logoUrl: '',
   invoicePrefix: 'INV',
   nextInvoiceNumber: 1,
@@ -23,4 +46,31 @@ export const defaultSettings: Settings = {
   smtpFrom: '',
   smtpUseTls: true,
   smtpTlsMode: 'starttls',
+  smtpTimeoutSeconds: 30,
+  smtpReplyTo: '',
+  smtpFromName: '',
+  sendCopyToSelf: false,
+  smtpAuthMode: 'password',
+  smtpOauthClientId: '',
+  smtpOauthTokenEndpoint: '',
+  smtpAcceptInvalidCerts: false,
+  smtpAcceptInvalidCertsAcknowledged: false,
+  smtpPinnedCertPem: '',
+  turnoverLimitRsd: 6_000_000,
+  vatLimitRsd: 8_000_000,
+  autoBackupEnabled: true,
+  autoBackupKeep: 7,
+  csvOptions: { delimiter: ',', decimalComma: false, includeBom: false, lineEnding: 'crlf' },
+  roundingMode: 'halfUp',
+  quotePrefix: 'PON',
+  nextQuoteNumber: 1,
+  webhookUrl: '',
+  pdfFilenameTemplate: '{number}-{client}',
+  issuedBy: null,
+  accountantEmail: '',
+  staleDraftDays: 14,
+  overdueGraceDays: 0,
+  customFieldLabels: [],
+  emailMaxMessageMb: 15,
+  emailTheme: DEFAULT_EMAIL_THEME,
 };
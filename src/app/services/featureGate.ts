@@ -1,4 +1,5 @@
-import type { LicenseStatus, LockLevel } from '../types/license';
+import type { FeatureLocked, LicenseStatus, LockLevel } from '../types/license';
+import { toCommandError } from './commandError';
 
 export type Feature =
   | 'APP_ACCESS'
@@ -61,3 +62,32 @@ export function isFeatureAllowed(status: LicenseStatus, feature: Feature): boole
   const level = status.lockLevel ?? 'NONE';
   return MATRIX[level]?.[feature] ?? false;
 }
+
+const FEATURE_LOCKED_PREFIX = 'feature_locked: ';
+
+/** The `FEATURE_LOCKED` error of a command whose feature the plan doesn't include, or `null`. */
+export function parseFeatureLocked(error: unknown): FeatureLocked | null {
+  const err = toCommandError(error);
+  if (err.code === 'FEATURE_LOCKED' && err.details) return err.details as FeatureLocked;
+  // Errors from before typed command errors carry the lock in the message text.
+  const text = err.message;
+  if (!text.startsWith(FEATURE_LOCKED_PREFIX)) return null;
+  try {
+    return JSON.parse(text.slice(FEATURE_LOCKED_PREFIX.length)) as FeatureLocked;
+  } catch {
+    return null;
+  }
+}
+
+/** Translated "needs plan X" message for a `feature_locked` error, or `null` for other errors. */
+export function featureLockedMessage(
+  error: unknown,
+  t: (key: string, options?: Record<string, unknown>) => string
+): string | null {
+  const locked = parseFeatureLocked(error);
+  if (!locked) return null;
+  return t('license.featureLocked', {
+    feature: t(`license.features.${locked.feature}`),
+    plan: t(`license.plans.${locked.requiredPlan ?? 'lifetime'}`),
+  });
+}
@@ -1,61 +1,26 @@
-import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-shell';
+import { invokeCommand } from './commandError';
 
 import { normalizeInvoiceUnit } from '../types';
-import type { Client, Invoice, Settings } from '../types';
+import type { Client, ExportedFile, Invoice, Settings } from '../types';
+import type { InvoicePdfPayload } from '../types/bindings';
+import { paymentAccountFor } from './bankAccounts';
 import { formatCompanyAddressMultiline } from './companyAddress';
 
-export type InvoicePdfPayload = {
-  language: 'sr' | 'en';
-  invoice_number: string;
-  issue_date: string;
-  service_date: string;
-  currency: string;
-  subtotal: number;
-  discount_total: number;
-  total: number;
-  notes?: string | null;
-  company: {
-    company_name: string;
-    registration_number: string;
-    pib: string;
-    address: string;
-    address_line?: string | null;
-    postal_code?: string | null;
-    city?: string | null;
-    bank_account: string;
-    email?: string | null;
-    phone?: string | null;
-  };
-  client: {
-    name: string;
-    registration_number?: string | null;
-    pib?: string | null;
-    address?: string | null;
-    address_line?: string | null;
-    postal_code?: string | null;
-    city?: string | null;
-    email?: string | null;
-    phone?: string | null;
-  };
-  items: Array<{
-    description: string;
-    unit?: string | null;
-    quantity: number;
-    unit_price: number;
-    discount_amount?: number | null;
-    total: number;
-  }>;
-};
+export type { InvoicePdfPayload };
 
 function clampMoney(value: number, min: number, max: number): number {
   if (!Number.isFinite(value)) return min;
   return Math.min(Math.max(value, min), max);
 }
 
-function computeInvoiceTotals(items: Invoice['items']): {
+function computeInvoiceTotals(
+  items: Invoice['items'],
+  invoiceDiscount?: number | null,
+  invoiceDiscountPercent?: number | null
+): {
   subtotal: number;
   discountTotal: number;
+  invoiceDiscount: number;
   total: number;
 } {
   const subtotal = items.reduce((sum, it) => sum + Number(it.quantity) * Number(it.unitPrice), 0);
@@ -65,8 +30,12 @@ function computeInvoiceTotals(items: Invoice['items']): {
     const lineDiscount = clampMoney(raw, 0, lineSubtotal);
     return sum + lineDiscount;
   }, 0);
-  const total = subtotal - discountTotal;
-  return { subtotal, discountTotal, total };
+  const net = Math.max(subtotal - discountTotal, 0);
+  const flat = clampMoney(Number(invoiceDiscount ?? 0), 0, Number.MAX_VALUE);
+  const pct = clampMoney(Number(invoiceDiscountPercent ?? 0), 0, 100);
+  const resolvedInvoiceDiscount = clampMoney(flat + (net * pct) / 100, 0, net);
+  const total = net - resolvedInvoiceDiscount;
+  return { subtotal, discountTotal, invoiceDiscount: resolvedInvoiceDiscount, total };
 }
 
 export function buildInvoicePdfPayload(args: {
@@ -76,18 +45,25 @@ export function buildInvoicePdfPayload(args: {
 }): InvoicePdfPayload {
   const { invoice, client, settings } = args;
 
-  const totals = computeInvoiceTotals(invoice.items);
+  const totals = computeInvoiceTotals(invoice.items, invoice.invoiceDiscount, invoice.invoiceDiscountPercent);
+  const account = paymentAccountFor(settings, invoice.currency);
 
   return {
-    language: settings.language,
+    language: client?.preferredLanguage || settings.language,
     invoice_number: invoice.invoiceNumber,
     issue_date: invoice.issueDate,
     service_date: invoice.serviceDate,
     currency: invoice.currency,
     subtotal: totals.subtotal,
     discount_total: totals.discountTotal,
+    invoice_discount: totals.invoiceDiscount > 0 ? totals.invoiceDiscount : null,
+    invoice_discount_percent: invoice.invoiceDiscountPercent ? invoice.invoiceDiscountPercent : null,
     total: totals.total,
     notes: invoice.notes ? invoice.notes : null,
+    payment_method: invoice.paymentMethod ?? null,
+    payment_reference: invoice.paymentReference ? invoice.paymentReference : null,
+    issued_by: invoice.issuedBy?.trim() ? invoice.issuedBy.trim() : null,
+    custom_fields: invoice.customFields ?? [],
     company: {
       company_name: settings.companyName,
       registration_number: settings.registrationNumber,
@@ -96,7 +72,9 @@ export function buildInvoicePdfPayload(args: {
       address_line: settings.companyAddressLine,
       postal_code: settings.companyPostalCode,
       city: settings.companyCity,
-      bank_account: settings.bankAccount,
+      bank_account: account.accountNumber,
+      iban: account.iban,
+      swift: account.swift,
       email: settings.companyEmail?.trim() ? settings.companyEmail.trim() : null,
       phone: settings.companyPhone?.trim() ? settings.companyPhone.trim() : null,
     },
@@ -110,6 +88,8 @@ export function buildInvoicePdfPayload(args: {
       city: (client as any)?.city ?? null,
       email: client?.email ?? null,
       phone: (client as any)?.phone ?? null,
+      is_foreign: client?.isForeign ?? false,
+      country: client?.country ?? null,
     },
     items: invoice.items.map((it) => ({
       description: it.description,
@@ -122,10 +102,33 @@ export function buildInvoicePdfPayload(args: {
   };
 }
 
-export async function exportInvoicePdfToDownloads(payload: InvoicePdfPayload): Promise<string> {
-  return invoke<string>('export_invoice_pdf_to_downloads', { payload });
+/** What an export does when the file already exists; `autoNumber` writes `name (2).pdf`, … */
+export type ExistingFileMode = 'error' | 'overwrite' | 'autoNumber';
+
+/**
+ * `archival` produces a PDF/A-1b file (for long-term archiving); `openAfterExport` opens it in the
+ * system PDF viewer, reporting a failure in the result instead of rejecting. `uniqueSuffix` appends
+ * a timestamp to the file name; `ifExists` defaults to overwriting.
+ */
+export async function exportInvoicePdfToDownloads(
+  payload: InvoicePdfPayload,
+  options?: { archival?: boolean; openAfterExport?: boolean; uniqueSuffix?: boolean; ifExists?: ExistingFileMode }
+): Promise<ExportedFile> {
+  return invokeCommand<ExportedFile>('export_invoice_pdf_to_downloads', {
+    payload,
+    archival: options?.archival ?? false,
+    openAfterExport: options?.openAfterExport ?? false,
+    uniqueSuffix: options?.uniqueSuffix ?? false,
+    ifExists: options?.ifExists ?? 'overwrite',
+  });
+}
+
+/** Opens an exported document in the application registered for its type. */
+export async function openPath(path: string): Promise<void> {
+  await invokeCommand<void>('open_path', { path });
 }
 
-export async function openGeneratedPdf(path: string): Promise<void> {
-  await open(path);
+/** Shows the file selected in the file manager, or opens its folder. */
+export async function revealInFolder(path: string): Promise<void> {
+  await invokeCommand<void>('reveal_in_folder', { path });
 }
@@ -1,30 +1,77 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invokeCommand } from './commandError';
+
+import type { LicenseExpiryInfo, PlanFeature } from '../types/license';
 
 export type RustVerifiedLicenseInfo = {
   license_type?: string | null;
   valid_until?: string | null;
   is_valid: boolean;
   reason?: string | null;
+  /** Id of the trusted key that verified the signature. */
+  key_id?: string | null;
 };
 
 export async function hashPib(pib: string): Promise<string> {
-  return invoke<string>('hash_pib', { pib });
+  return invokeCommand<string>('hash_pib', { pib });
 }
 
 export async function generateActivationCode(pib: string): Promise<string> {
-  return invoke<string>('generate_activation_code', { pib });
+  return invokeCommand<string>('generate_activation_code', { pib });
 }
 
 export async function verifyLicense(license: string, pib: string): Promise<RustVerifiedLicenseInfo> {
-  return invoke<RustVerifiedLicenseInfo>('verify_license', { license, pib });
+  return invokeCommand<RustVerifiedLicenseInfo>('verify_license', { license, pib });
+}
+
+/** Activation code for the PIB in the settings. */
+export async function getActivationCode(): Promise<string> {
+  return invokeCommand<string>('get_activation_code');
+}
+
+/** Stores the license; rejects with the validator's reason (`expired`, `pib_mismatch`, …). */
+export async function activateLicense(licenseStr: string): Promise<RustVerifiedLicenseInfo> {
+  return invokeCommand<RustVerifiedLicenseInfo>('activate_license', { licenseStr });
+}
+
+/** Removes the stored license for a move to a new PIB; resolves to the receipt for the vendor. */
+export async function deactivateLicense(): Promise<string> {
+  return invokeCommand<string>('deactivate_license');
+}
+
+/** Whether the current plan (license type or trial) includes `feature`. */
+export async function isFeatureEnabled(feature: PlanFeature): Promise<boolean> {
+  return invokeCommand<boolean>('is_feature_enabled', { feature });
+}
+
+/** The stored license re-verified now; `null` when none is activated. */
+export async function getStoredLicenseStatus(): Promise<RustVerifiedLicenseInfo | null> {
+  const res = await invokeCommand<RustVerifiedLicenseInfo | null>('get_license_status');
+  return res ?? null;
+}
+
+/** Days until the stored license expires and its grace period; `null` when none is activated. */
+export async function getLicenseExpiryInfo(): Promise<LicenseExpiryInfo | null> {
+  const res = await invokeCommand<LicenseExpiryInfo | null>('license_expiry_info');
+  return res ?? null;
+}
+
+export type RustTrialStatus = {
+  startedAt: string;
+  endsAt: string;
+  daysRemaining: number;
+  expired: boolean;
+};
+
+export async function getTrialStatus(): Promise<RustTrialStatus> {
+  return invokeCommand<RustTrialStatus>('get_trial_status');
 }
 
 export async function getForceLockedEnv(): Promise<boolean> {
-  return invoke<boolean>('get_force_locked_env');
+  return invokeCommand<boolean>('get_force_locked_env');
 }
 
 export async function getForceLockLevelEnv(): Promise<'VIEW_ONLY' | 'HARD' | null> {
-  const res = await invoke<string | null>('get_force_lock_level_env');
+  const res = await invokeCommand<string | null>('get_force_lock_level_env');
   if (!res) return null;
   const v = String(res).trim().toUpperCase();
   if (v === 'VIEW_ONLY') return 'VIEW_ONLY';
@@ -33,10 +80,10 @@ export async function getForceLockLevelEnv(): Promise<'VIEW_ONLY' | 'HARD' | nul
 }
 
 export async function getAppMeta(key: string): Promise<string | null> {
-  const res = await invoke<string | null>('get_app_meta', { key });
+  const res = await invokeCommand<string | null>('get_app_meta', { key });
   return res ?? null;
 }
 
 export async function setAppMeta(key: string, value: string): Promise<boolean> {
-  return invoke<boolean>('set_app_meta', { key, value });
+  return invokeCommand<boolean>('set_app_meta', { key, value });
 }
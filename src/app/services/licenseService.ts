@@ -1,12 +1,20 @@
 import { getStorage } from './storageProvider';
 import type { LicenseStatus, LockLevel, LockReason } from '../types/license';
-import { ensureTrialHydrated, getTrialInfo, isTrialActive, startTrialIfNeeded } from './trialService';
-import { generateActivationCode as rustGenerateActivationCode, getAppMeta, setAppMeta, verifyLicense as rustVerifyLicense } from './licenseCodeGenerator';
+import { ensureTrialHydrated, getTrialInfo, isTrialActive } from './trialService';
+import {
+  activateLicense,
+  deactivateLicense,
+  getActivationCode,
+  getAppMeta,
+  getLicenseExpiryInfo,
+  getStoredLicenseStatus,
+} from './licenseCodeGenerator';
 import { getDevForcedLockInfo } from './devLockService';
 
 const storage = getStorage();
 
 const LICENSE_RAW_KEY = 'licenseRaw';
+const DEACTIVATION_RECEIPT_KEY = 'license_deactivation_receipt';
 
 let cachedLicenseRaw: string | null = null;
 let licenseHydrated = false;
@@ -19,26 +27,34 @@ async function hydrateLicense(): Promise<void> {
 }
 
 export async function generateActivationCode(): Promise<string> {
-  const settings = await storage.getSettings();
-  const pib = String(settings.pib ?? '').trim();
-  if (!pib) throw new Error('PIB is missing');
-  return rustGenerateActivationCode(pib);
+  return getActivationCode();
 }
 
-export async function validateAndStoreLicense(licenseString: string): Promise<boolean> {
-  const settings = await storage.getSettings();
-  const pib = String(settings.pib ?? '').trim();
-  if (!pib) return false;
-
+/** Activates `licenseString`; `reason` is the validator's code when it is rejected. */
+export async function validateAndStoreLicense(licenseString: string): Promise<{ activated: boolean; reason?: string }> {
   const raw = String(licenseString ?? '').trim();
-  if (!raw) return false;
+  if (!raw) return { activated: false };
 
-  const verified = await rustVerifyLicense(raw, pib);
-  if (!verified.is_valid) return false;
-
-  await setAppMeta(LICENSE_RAW_KEY, raw);
+  try {
+    await activateLicense(raw);
+  } catch (e) {
+    return { activated: false, reason: String(e) };
+  }
   cachedLicenseRaw = raw;
-  return true;
+  return { activated: true };
+}
+
+/** Deactivates the stored license; resolves to the receipt the vendor needs to reissue it. */
+export async function deactivateStoredLicense(): Promise<string> {
+  const receipt = await deactivateLicense();
+  cachedLicenseRaw = null;
+  return receipt;
+}
+
+/** Receipt of the last deactivation, if any. */
+export async function getDeactivationReceipt(): Promise<string | null> {
+  const receipt = await getAppMeta(DEACTIVATION_RECEIPT_KEY);
+  return receipt ? String(receipt) : null;
 }
 
 export async function getLicenseStatus(): Promise<LicenseStatus> {
@@ -69,7 +85,6 @@ export async function getLicenseStatus(): Promise<LicenseStatus> {
   }
 
   const pib = String(settings.pib ?? '').trim();
-  if (pib) startTrialIfNeeded(pib);
   if (!pib) {
     return {
       isLicensed: false,
@@ -86,8 +101,8 @@ export async function getLicenseStatus(): Promise<LicenseStatus> {
 
   const trialActive = isTrialActive();
 
-  if (cachedLicenseRaw) {
-    const verified = await rustVerifyLicense(cachedLicenseRaw, pib);
+  const verified = await getStoredLicenseStatus();
+  if (verified) {
     if (verified.is_valid) {
       return {
         isLicensed: true,
@@ -98,6 +113,21 @@ export async function getLicenseStatus(): Promise<LicenseStatus> {
       };
     }
 
+    // The backend keeps an expired yearly license working for a few days; nag, don't lock.
+    const expiry = verified.reason === 'expired' ? await getLicenseExpiryInfo() : null;
+    if (expiry?.gracePeriod) {
+      return {
+        isLicensed: true,
+        isTrialActive: trialActive,
+        lockLevel: 'NONE',
+        isLocked: false,
+        reason: 'expired',
+        validUntil: verified.valid_until ?? undefined,
+        gracePeriod: true,
+        graceDaysRemaining: expiry.graceDaysRemaining ?? undefined,
+      };
+    }
+
     if (trialActive) {
       return {
         isLicensed: false,
@@ -1,4 +1,5 @@
 import templates from '../../shared/mandatoryInvoiceNote.json';
+import type { LegalNoteOverride } from '../types';
 
 type Templates = typeof templates;
 
@@ -11,15 +12,24 @@ function normalizeLang(lang: string): Lang {
 export function mandatoryInvoiceNoteLines(args: {
   language: string;
   invoiceNumber: string;
+  /** Foreign client with a VAT ID: append the reverse-charge sentence. */
+  reverseCharge?: boolean;
+  /** From settings; a non-blank text replaces the built-in lines of its language. */
+  override?: LegalNoteOverride;
 }): string[] {
   const lang = normalizeLang(args.language);
-  const lines = (templates as Templates)[lang].lines as string[];
-  return lines.map((l) => l.replace('{INVOICE_NUMBER}', args.invoiceNumber));
+  const locale = (templates as Templates)[lang];
+  const custom = args.override?.[lang]?.trim();
+  const base = custom ? custom.split(/\r?\n/).map((l) => l.trimEnd()) : (locale.lines as string[]);
+  const lines = [...base, ...(args.reverseCharge ? locale.reverseChargeLines : [])] as string[];
+  return lines.map((l) => l.split('{INVOICE_NUMBER}').join(args.invoiceNumber));
 }
 
 export function mandatoryInvoiceNoteText(args: {
   language: string;
   invoiceNumber: string;
+  reverseCharge?: boolean;
+  override?: LegalNoteOverride;
 }): string {
   return mandatoryInvoiceNoteLines(args).join('\n');
 }
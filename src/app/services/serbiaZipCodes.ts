@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invokeCommand } from './commandError';
 
 export type SerbiaCityDto = {
   city: string;
@@ -13,7 +13,7 @@ export type SerbiaCityOption = {
 };
 
 export async function listSerbiaCities(search?: string): Promise<SerbiaCityDto[]> {
-  return invoke<SerbiaCityDto[]>('list_serbia_cities', { search });
+  return invokeCommand<SerbiaCityDto[]>('list_serbia_cities', { search });
 }
 
 export function toSerbiaCityOptions(rows: SerbiaCityDto[]): SerbiaCityOption[] {
@@ -1,5 +1,12 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invokeCommand } from './commandError';
 
-export async function sendTestEmail(): Promise<boolean> {
-  return invoke<boolean>('send_test_email');
+export type SmtpTrustPath = 'systemRoots' | 'pinnedCertificate' | 'acceptInvalidCerts' | 'noTls';
+
+export interface SmtpTestResult {
+  sent: boolean;
+  trustPath: SmtpTrustPath;
+}
+
+export async function sendTestEmail(): Promise<SmtpTestResult> {
+  return invokeCommand<SmtpTestResult>('send_test_email');
 }
@@ -1,24 +1,11 @@
-import { invoke } from '@tauri-apps/api/core';
 
+import { invokeCommand } from './commandError';
 import type { StorageAdapter } from './storageAdapter';
 import { normalizeInvoiceUnit } from '../types';
-import type { Client, Expense, ExpenseRange, Invoice, Offer, Settings } from '../types';
-
-type NewInvoice = {
-  clientId: string;
-  clientName: string;
-  issueDate: string;
-  serviceDate: string;
-  status?: Invoice['status'];
-  dueDate?: Invoice['dueDate'];
-  currency: string;
-  items: Invoice['items'];
-  subtotal: number;
-  total: number;
-  notes: string;
-};
-
-function normalizeInvoiceUnits(invoice: Invoice): Invoice {
+import type { NewInvoice } from '../types/bindings';
+import type { AccountantReportKind, AccountantReportResult, ActiveTimer, AppLockStatus, AppMode, CatalogItem, CatalogItemInput, CatalogUsage, Client, ClientCsvMapping, ClientImportReport, ConsistencyReport, ConsistencyRepairResult, CorruptRow, CorruptRowRepair, CreateClientResult, CreatedInvoice, DashboardSummary, DeleteClientResult, DeleteRecurringExpenseResult, DuplicateClientGroup, Expense, ExpenseCsvMapping, ExpenseCurrencySummary, ExpenseGroupBy, ExpenseImportReport, ExpenseRange, ExpenseSplitPart, ExportedFile, GeneratedObligations, Invoice, InvoiceCsvMapping, InvoiceFileImportOptions, InvoiceFileImportResult, InvoiceImportOptions, InvoiceImportReport, InvoiceYear, LimitStatus, LogEntry, LogLevel, MaintenanceReport, MatchedPayment, MergeClientsResult, Obligation, ObligationInput, ObligationMonthStatus, Offer, OutboxItem, PaymentCsvMapping, PaymentImportReport, PaymentRow, PeriodArchiveResult, Quote, QuoteInput, QuoteStatus, RangeExportResult, ReceivablesReport, RecurringExpense, RepairStrategy, SearchResult, Settings, SettingsValidation, SetupState, SetupStep, StatusSuggestion, TaxIdValidation, TaxSummary, TimeEntry, TimeEntryInput, TopClient, TopItem, TopRanking, UnbilledTime, WebhookDelivery } from '../types';
+
+function normalizeInvoiceUnits<T extends Pick<Invoice, 'items'>>(invoice: T): T {
   return {
     ...invoice,
     items: (invoice.items ?? []).map((it: any) => ({
@@ -48,7 +35,7 @@ function logSqlError(context: string, err: unknown): void {
 
 async function invokeLogged<T>(context: string, cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
-    return await invoke<T>(cmd, args);
+    return await invokeCommand<T>(cmd, args);
   } catch (e) {
     logSqlError(context, e);
     throw e;
@@ -63,6 +50,33 @@ export function createSqliteStorageAdapter(): StorageAdapter {
     updateSettings: async (patch: Partial<Settings>): Promise<Settings> =>
       invokeLogged<Settings>('updateSettings', 'update_settings', { patch }),
 
+    validateSettings: async (): Promise<SettingsValidation> =>
+      invokeLogged<SettingsValidation>('validateSettings', 'validate_settings'),
+
+    setCompanyLogo: async (source: string): Promise<Settings> =>
+      invokeLogged<Settings>('setCompanyLogo', 'set_company_logo', { source }),
+
+    getCompanyLogo: async (): Promise<ArrayBuffer> => invokeLogged<ArrayBuffer>('getCompanyLogo', 'get_company_logo'),
+
+    listAvailableLanguages: async (): Promise<string[]> =>
+      invokeLogged<string[]>('listAvailableLanguages', 'list_available_languages'),
+
+    previewLegalNote: async (lang: string, sampleNumber: string, text?: string): Promise<string> =>
+      invokeLogged<string>('previewLegalNote', 'preview_legal_note', { lang, sampleNumber, text: text ?? null }),
+
+    exportSettings: async (path: string, includeSmtp: boolean): Promise<string> =>
+      invokeLogged<string>('exportSettings', 'export_settings', { path, includeSmtp }),
+
+    importSettings: async (path: string): Promise<Settings> =>
+      invokeLogged<Settings>('importSettings', 'import_settings', { path }),
+
+    getSetupState: async (): Promise<SetupState> => invokeLogged<SetupState>('getSetupState', 'get_setup_state'),
+
+    completeSetupStep: async (step: SetupStep, payload: Partial<Settings>): Promise<SetupState> =>
+      invokeLogged<SetupState>('completeSetupStep', 'complete_setup_step', { step, payload }),
+
+    finishSetup: async (): Promise<SetupState> => invokeLogged<SetupState>('finishSetup', 'finish_setup'),
+
     generateInvoiceNumber: async (): Promise<string> =>
       invokeLogged<string>('generateInvoiceNumber', 'generate_invoice_number'),
 
@@ -70,23 +84,57 @@ export function createSqliteStorageAdapter(): StorageAdapter {
       invokeLogged<string>('previewNextInvoiceNumber', 'preview_next_invoice_number'),
 
     // Clients
-    getAllClients: async (): Promise<Client[]> => invokeLogged<Client[]>('getAllClients', 'get_all_clients'),
+    getAllClients: async (options?: { includeArchived?: boolean }): Promise<Client[]> =>
+      invokeLogged<Client[]>('getAllClients', 'get_all_clients', {
+        includeArchived: options?.includeArchived ?? false,
+      }),
 
     getClientById: async (id: string): Promise<Client | undefined> => {
       const res = await invokeLogged<Client | null>('getClientById', 'get_client_by_id', { id });
       return res ?? undefined;
     },
 
-    createClient: async (data: Omit<Client, 'id' | 'createdAt'>): Promise<Client> =>
-      invokeLogged<Client>('createClient', 'create_client', { input: data }),
-
-    updateClient: async (id: string, patch: Partial<Client>): Promise<Client | null> => {
-      const res = await invokeLogged<Client | null>('updateClient', 'update_client', { id, patch });
+    createClient: async (
+      data: Omit<Client, 'id' | 'createdAt'>,
+      options?: { allowDuplicate?: boolean; idempotencyKey?: string }
+    ): Promise<CreateClientResult> =>
+      invokeLogged<CreateClientResult>('createClient', 'create_client', {
+        input: { ...data, idempotencyKey: options?.idempotencyKey },
+        allowDuplicate: options?.allowDuplicate ?? false,
+      }),
+
+    updateClient: async (id: string, patch: Partial<Client>, options?: { propagateName?: boolean }): Promise<Client | null> => {
+      const res = await invokeLogged<Client | null>('updateClient', 'update_client', {
+        id,
+        patch,
+        propagateName: options?.propagateName ?? false,
+      });
       return res ?? null;
     },
 
-    deleteClient: async (id: string): Promise<boolean> =>
-      invokeLogged<boolean>('deleteClient', 'delete_client', { id }),
+    validateTaxIds: async (pib?: string, mb?: string): Promise<TaxIdValidation> =>
+      invokeLogged<TaxIdValidation>('validateTaxIds', 'validate_tax_ids', { pib: pib ?? null, mb: mb ?? null }),
+
+    deleteClient: async (id: string, options?: { force?: boolean }): Promise<DeleteClientResult> =>
+      invokeLogged<DeleteClientResult>('deleteClient', 'delete_client', { id, force: options?.force ?? false }),
+
+    archiveClient: async (id: string): Promise<Client | null> =>
+      invokeLogged<Client | null>('archiveClient', 'archive_client', { id }),
+
+    unarchiveClient: async (id: string): Promise<Client | null> =>
+      invokeLogged<Client | null>('unarchiveClient', 'unarchive_client', { id }),
+
+    findDuplicateClients: async (): Promise<DuplicateClientGroup[]> =>
+      invokeLogged<DuplicateClientGroup[]>('findDuplicateClients', 'find_duplicate_clients'),
+
+    mergeClients: async (keepId: string, removeId: string): Promise<MergeClientsResult> =>
+      invokeLogged<MergeClientsResult>('mergeClients', 'merge_clients', { keepId, removeId }),
+
+    readCsvHeaders: async (path: string): Promise<string[]> =>
+      invokeLogged<string[]>('readCsvHeaders', 'read_csv_headers', { path }),
+
+    importClientsCsv: async (path: string, mapping: ClientCsvMapping): Promise<ClientImportReport> =>
+      invokeLogged<ClientImportReport>('importClientsCsv', 'import_clients_csv', { path, mapping }),
 
     // Invoices
     getAllInvoices: async (): Promise<Invoice[]> => {
@@ -104,14 +152,14 @@ export function createSqliteStorageAdapter(): StorageAdapter {
       return res ? normalizeInvoiceUnits(res) : undefined;
     },
 
-    createInvoice: async (data: Omit<Invoice, 'id' | 'createdAt'>): Promise<Invoice> => {
+    createInvoice: async (data: Omit<Invoice, 'id' | 'createdAt'>, options?: { idempotencyKey?: string }): Promise<CreatedInvoice> => {
       // Invoice number is generated atomically on the Rust side inside a single transaction.
       // We ignore any invoiceNumber coming from the UI.
       const { invoiceNumber: _ignored, paidAt: _paidAtIgnored, ...rest } = data as Invoice;
       const normalized = normalizeInvoiceUnits(rest as unknown as Invoice);
-      const input = normalized as unknown as NewInvoice;
-      const created = await invokeLogged<Invoice>('createInvoice', 'create_invoice', { input });
-      return normalizeInvoiceUnits(created);
+      const input: NewInvoice = { ...(normalized as unknown as NewInvoice), idempotencyKey: options?.idempotencyKey };
+      const created = await invokeLogged<CreatedInvoice>('createInvoice', 'create_invoice', { input });
+      return { ...normalizeInvoiceUnits(created), limitWarnings: created.limitWarnings };
     },
 
     updateInvoice: async (id: string, patch: Partial<Invoice>): Promise<Invoice | null> => {
@@ -127,16 +175,310 @@ export function createSqliteStorageAdapter(): StorageAdapter {
     listExpenses: async (range?: ExpenseRange): Promise<Expense[]> =>
       invokeLogged<Expense[]>('listExpenses', 'list_expenses', { range: range ?? null }),
 
-    createExpense: async (data: Omit<Expense, 'id' | 'createdAt'>): Promise<Expense> =>
-      invokeLogged<Expense>('createExpense', 'create_expense', { input: data }),
+    getExpenseById: async (id: string): Promise<Expense | null> => {
+      const res = await invokeLogged<Expense | null>('getExpenseById', 'get_expense_by_id', { id });
+      return res ?? null;
+    },
+
+    createExpense: async (data: Omit<Expense, 'id' | 'createdAt'>, options?: { idempotencyKey?: string }): Promise<Expense> =>
+      invokeLogged<Expense>('createExpense', 'create_expense', {
+        input: { ...data, idempotencyKey: options?.idempotencyKey },
+      }),
 
     updateExpense: async (id: string, patch: Partial<Omit<Expense, 'id' | 'createdAt'>>): Promise<Expense | null> => {
       const res = await invokeLogged<Expense | null>('updateExpense', 'update_expense', { id, patch });
       return res ?? null;
     },
 
-    deleteExpense: async (id: string): Promise<boolean> =>
-      invokeLogged<boolean>('deleteExpense', 'delete_expense', { id }),
+    deleteExpense: async (id: string): Promise<Expense | null> => {
+      const res = await invokeLogged<Expense | null>('deleteExpense', 'delete_expense', { id });
+      return res ?? null;
+    },
+
+    restoreExpense: async (id: string): Promise<Expense | null> => {
+      const res = await invokeLogged<Expense | null>('restoreExpense', 'restore_expense', { id });
+      return res ?? null;
+    },
+
+    duplicateExpense: async (id: string, newDate: string): Promise<Expense | null> => {
+      const res = await invokeLogged<Expense | null>('duplicateExpense', 'duplicate_expense', { id, newDate });
+      return res ?? null;
+    },
+
+    splitExpense: async (id: string, parts: ExpenseSplitPart[]): Promise<Expense[] | null> => {
+      const res = await invokeLogged<Expense[] | null>('splitExpense', 'split_expense', { id, parts });
+      return res ?? null;
+    },
+
+    listExpensesForInvoice: async (invoiceId: string): Promise<Expense[]> =>
+      invokeLogged<Expense[]>('listExpensesForInvoice', 'list_expenses_for_invoice', { invoiceId }),
+
+    billExpensesToInvoice: async (expenseIds: string[], invoiceId: string): Promise<Invoice | null> => {
+      const res = await invokeLogged<Invoice | null>('billExpensesToInvoice', 'bill_expenses_to_invoice', {
+        expenseIds,
+        invoiceId,
+      });
+      return res ? normalizeInvoiceUnits(res) : null;
+    },
+
+    purgeDeletedExpenses: async (olderThanDays: number): Promise<number> =>
+      invokeLogged<number>('purgeDeletedExpenses', 'purge_deleted_expenses', { olderThanDays }),
+
+    importExpensesCsv: async (
+      path: string,
+      mapping: ExpenseCsvMapping,
+      defaultCurrency: string,
+      options?: { preview?: boolean }
+    ): Promise<ExpenseImportReport> =>
+      invokeLogged<ExpenseImportReport>('importExpensesCsv', 'import_expenses_csv', {
+        path,
+        mapping,
+        defaultCurrency,
+        preview: options?.preview ?? false,
+      }),
+
+    importPaymentsCsv: async (path: string, mapping: PaymentCsvMapping): Promise<PaymentImportReport> =>
+      invokeLogged<PaymentImportReport>('importPaymentsCsv', 'import_payments_csv', { path, mapping }),
+
+    resolvePaymentMatch: async (paymentRow: PaymentRow, invoiceId: string): Promise<MatchedPayment> =>
+      invokeLogged<MatchedPayment>('resolvePaymentMatch', 'resolve_payment_match', { paymentRow, invoiceId }),
+
+    importInvoicesCsv: async (
+      path: string,
+      mapping: InvoiceCsvMapping,
+      options?: InvoiceImportOptions
+    ): Promise<InvoiceImportReport> =>
+      invokeLogged<InvoiceImportReport>('importInvoicesCsv', 'import_invoices_csv', {
+        path,
+        mapping,
+        options: options ?? null,
+      }),
+    exportInvoiceJson: async (id: string, path: string): Promise<string> =>
+      invokeLogged<string>('exportInvoiceJson', 'export_invoice_json', { id, path }),
+    importInvoiceJson: async (path: string, options?: InvoiceFileImportOptions): Promise<InvoiceFileImportResult> =>
+      invokeLogged<InvoiceFileImportResult>('importInvoiceJson', 'import_invoice_json', {
+        path,
+        options: options ?? null,
+      }),
+    invoicesByYear: async (): Promise<InvoiceYear[]> => invokeLogged<InvoiceYear[]>('invoicesByYear', 'invoices_by_year'),
+
+    checkDataConsistency: async (): Promise<ConsistencyReport> =>
+      invokeLogged<ConsistencyReport>('checkDataConsistency', 'check_data_consistency'),
+
+    repairDataConsistency: async (strategy: RepairStrategy): Promise<ConsistencyRepairResult> =>
+      invokeLogged<ConsistencyRepairResult>('repairDataConsistency', 'repair_data_consistency', { strategy }),
+
+    listCorruptRows: async (): Promise<CorruptRow[]> => invokeLogged<CorruptRow[]>('listCorruptRows', 'list_corrupt_rows'),
+
+    repairCorruptRow: async (id: string, patchJson?: string): Promise<CorruptRowRepair> =>
+      invokeLogged<CorruptRowRepair>('repairCorruptRow', 'repair_corrupt_row', { id, patchJson: patchJson ?? null }),
+
+    databaseMaintenance: async (compact: boolean): Promise<MaintenanceReport> =>
+      invokeLogged<MaintenanceReport>('databaseMaintenance', 'database_maintenance', { compact }),
+
+    getRecentLogs: async (lines: number, level?: LogLevel): Promise<LogEntry[]> =>
+      invokeLogged<LogEntry[]>('getRecentLogs', 'get_recent_logs', { lines, level: level ?? null }),
+
+    getLogLevel: async (): Promise<LogLevel> => invokeLogged<LogLevel>('getLogLevel', 'get_log_level'),
+
+    setLogLevel: async (level: LogLevel): Promise<LogLevel> =>
+      invokeLogged<LogLevel>('setLogLevel', 'set_log_level', { level }),
+
+    globalSearch: async (
+      query: string,
+      limit?: number,
+      options?: { includeArchived?: boolean },
+    ): Promise<SearchResult[]> =>
+      invokeLogged<SearchResult[]>('globalSearch', 'global_search', {
+        query,
+        limit: limit ?? null,
+        includeArchived: options?.includeArchived ?? false,
+      }),
+
+    expenseSummary: async (range: ExpenseRange, groupBy: ExpenseGroupBy): Promise<ExpenseCurrencySummary[]> =>
+      invokeLogged<ExpenseCurrencySummary[]>('expenseSummary', 'expense_summary', {
+        from: range.from ?? null,
+        to: range.to ?? null,
+        groupBy,
+      }),
+
+    dashboardSummary: async (year: number): Promise<DashboardSummary> =>
+      invokeLogged<DashboardSummary>('dashboardSummary', 'dashboard_summary', { year }),
+
+    topClients: async (range: ExpenseRange, limit?: number): Promise<TopRanking<TopClient>[]> =>
+      invokeLogged<TopRanking<TopClient>[]>('topClients', 'top_clients', {
+        from: range.from ?? null,
+        to: range.to ?? null,
+        limit: limit ?? null,
+      }),
+
+    topItems: async (range: ExpenseRange, limit?: number): Promise<TopRanking<TopItem>[]> =>
+      invokeLogged<TopRanking<TopItem>[]>('topItems', 'top_items', {
+        from: range.from ?? null,
+        to: range.to ?? null,
+        limit: limit ?? null,
+      }),
+
+    limitStatus: async (year: number): Promise<LimitStatus> =>
+      invokeLogged<LimitStatus>('limitStatus', 'limit_status', { year }),
+
+    accountsReceivableReport: async (): Promise<ReceivablesReport> =>
+      invokeLogged<ReceivablesReport>('accountsReceivableReport', 'accounts_receivable_report'),
+
+    suggestStatusUpdates: async (): Promise<StatusSuggestion[]> =>
+      invokeLogged<StatusSuggestion[]>('suggestStatusUpdates', 'suggest_status_updates'),
+
+    exportAccountsReceivableCsv: async (outputPath: string): Promise<string> =>
+      invokeLogged<string>('exportAccountsReceivableCsv', 'export_accounts_receivable_csv', { outputPath }),
+
+    taxSummary: async (year: number): Promise<TaxSummary> =>
+      invokeLogged<TaxSummary>('taxSummary', 'tax_summary', { year }),
+
+    exportTaxSummaryCsv: async (year: number, outputPath: string): Promise<string> =>
+      invokeLogged<string>('exportTaxSummaryCsv', 'export_tax_summary_csv', { year, outputPath }),
+
+    listRecurringExpenses: async (): Promise<RecurringExpense[]> =>
+      invokeLogged<RecurringExpense[]>('listRecurringExpenses', 'list_recurring_expenses'),
+
+    createRecurringExpense: async (
+      data: Omit<RecurringExpense, 'id' | 'createdAt' | 'nextRunDate'>
+    ): Promise<RecurringExpense> =>
+      invokeLogged<RecurringExpense>('createRecurringExpense', 'create_recurring_expense', { input: data }),
+
+    updateRecurringExpense: async (
+      id: string,
+      patch: Partial<Omit<RecurringExpense, 'id' | 'createdAt' | 'nextRunDate'>>
+    ): Promise<RecurringExpense | null> => {
+      const res = await invokeLogged<RecurringExpense | null>('updateRecurringExpense', 'update_recurring_expense', {
+        id,
+        patch,
+      });
+      return res ?? null;
+    },
+
+    deleteRecurringExpense: async (
+      id: string,
+      options?: { deleteFuture?: boolean }
+    ): Promise<DeleteRecurringExpenseResult> =>
+      invokeLogged<DeleteRecurringExpenseResult>('deleteRecurringExpense', 'delete_recurring_expense', {
+        id,
+        deleteFuture: options?.deleteFuture ?? false,
+      }),
+
+    runDueRecurringExpenses: async (): Promise<number> =>
+      invokeLogged<number>('runDueRecurringExpenses', 'run_due_recurring_expenses'),
+
+    listCatalogItems: async (options?: { includeInactive?: boolean }): Promise<CatalogItem[]> =>
+      invokeLogged<CatalogItem[]>('listCatalogItems', 'list_catalog_items', {
+        includeInactive: options?.includeInactive ?? false,
+      }),
+
+    createCatalogItem: async (input: CatalogItemInput): Promise<CatalogItem> =>
+      invokeLogged<CatalogItem>('createCatalogItem', 'create_catalog_item', { input }),
+
+    updateCatalogItem: async (id: string, patch: Partial<CatalogItemInput>): Promise<CatalogItem | null> => {
+      const res = await invokeLogged<CatalogItem | null>('updateCatalogItem', 'update_catalog_item', { id, patch });
+      return res ?? null;
+    },
+
+    deleteCatalogItem: async (id: string): Promise<boolean> =>
+      invokeLogged<boolean>('deleteCatalogItem', 'delete_catalog_item', { id }),
+
+    searchCatalog: async (query: string, limit?: number): Promise<CatalogItem[]> =>
+      invokeLogged<CatalogItem[]>('searchCatalog', 'search_catalog', { query, limit: limit ?? null }),
+
+    catalogUsageReport: async (from?: string | null, to?: string | null): Promise<CatalogUsage[]> =>
+      invokeLogged<CatalogUsage[]>('catalogUsageReport', 'catalog_usage_report', { from: from ?? null, to: to ?? null }),
+
+    listTimeEntries: async (options?: { clientId?: string | null; unbilledOnly?: boolean }): Promise<TimeEntry[]> =>
+      invokeLogged<TimeEntry[]>('listTimeEntries', 'list_time_entries', {
+        clientId: options?.clientId ?? null,
+        unbilledOnly: options?.unbilledOnly ?? false,
+      }),
+
+    createTimeEntry: async (input: TimeEntryInput): Promise<TimeEntry> =>
+      invokeLogged<TimeEntry>('createTimeEntry', 'create_time_entry', { input }),
+
+    updateTimeEntry: async (id: string, patch: Partial<TimeEntryInput>): Promise<TimeEntry | null> => {
+      const res = await invokeLogged<TimeEntry | null>('updateTimeEntry', 'update_time_entry', { id, patch });
+      return res ?? null;
+    },
+
+    deleteTimeEntry: async (id: string): Promise<boolean> =>
+      invokeLogged<boolean>('deleteTimeEntry', 'delete_time_entry', { id }),
+
+    getActiveTimer: async (): Promise<ActiveTimer | null> => {
+      const res = await invokeLogged<ActiveTimer | null>('getActiveTimer', 'get_active_timer');
+      return res ?? null;
+    },
+
+    startTimer: async (clientId: string, description: string, hourlyRate: number): Promise<ActiveTimer> =>
+      invokeLogged<ActiveTimer>('startTimer', 'start_timer', { clientId, description, hourlyRate }),
+
+    stopTimer: async (): Promise<TimeEntry | null> => {
+      const res = await invokeLogged<TimeEntry | null>('stopTimer', 'stop_timer');
+      return res ?? null;
+    },
+
+    billTimeEntries: async (entryIds: string[], invoiceId: string): Promise<Invoice | null> => {
+      const res = await invokeLogged<Invoice | null>('billTimeEntries', 'bill_time_entries', { entryIds, invoiceId });
+      return res ? normalizeInvoiceUnits(res) : null;
+    },
+
+    unbilledTimeReport: async (): Promise<UnbilledTime[]> =>
+      invokeLogged<UnbilledTime[]>('unbilledTimeReport', 'unbilled_time_report'),
+
+    listQuotes: async (): Promise<Quote[]> => {
+      const res = await invokeLogged<Quote[]>('listQuotes', 'list_quotes');
+      return res.map(normalizeInvoiceUnits);
+    },
+
+    createQuote: async (input: QuoteInput): Promise<Quote> =>
+      normalizeInvoiceUnits(await invokeLogged<Quote>('createQuote', 'create_quote', { input })),
+
+    updateQuote: async (id: string, patch: Partial<QuoteInput> & { status?: QuoteStatus }): Promise<Quote | null> => {
+      const res = await invokeLogged<Quote | null>('updateQuote', 'update_quote', { id, patch });
+      return res ? normalizeInvoiceUnits(res) : null;
+    },
+
+    deleteQuote: async (id: string): Promise<boolean> =>
+      invokeLogged<boolean>('deleteQuote', 'delete_quote', { id }),
+
+    convertQuoteToInvoice: async (quoteId: string): Promise<Invoice | null> => {
+      const res = await invokeLogged<Invoice | null>('convertQuoteToInvoice', 'convert_quote_to_invoice', { quoteId });
+      return res ? normalizeInvoiceUnits(res) : null;
+    },
+
+    exportQuotePdf: async (id: string, openAfterExport = false): Promise<ExportedFile> =>
+      invokeLogged<ExportedFile>('exportQuotePdf', 'export_quote_pdf_to_downloads', { id, openAfterExport }),
+
+    sendQuoteEmail: async (input: { quoteId: string; to: string[]; subject: string; body: string }): Promise<Quote> =>
+      invokeLogged<Quote>('sendQuoteEmail', 'send_quote_email', { input }),
+
+    listObligations: async (): Promise<Obligation[]> =>
+      invokeLogged<Obligation[]>('listObligations', 'list_obligations'),
+
+    createObligation: async (input: ObligationInput): Promise<Obligation> =>
+      invokeLogged<Obligation>('createObligation', 'create_obligation', { input }),
+
+    updateObligation: async (id: string, input: ObligationInput): Promise<Obligation | null> => {
+      const res = await invokeLogged<Obligation | null>('updateObligation', 'update_obligation', { id, input });
+      return res ?? null;
+    },
+
+    deleteObligation: async (id: string): Promise<boolean> =>
+      invokeLogged<boolean>('deleteObligation', 'delete_obligation', { id }),
+
+    generateMonthlyObligationExpenses: async (month: string): Promise<GeneratedObligations> =>
+      invokeLogged<GeneratedObligations>('generateMonthlyObligationExpenses', 'generate_monthly_obligation_expenses', {
+        month,
+      }),
+
+    setObligationMonthPaid: async (month: string, paid: boolean): Promise<boolean> =>
+      invokeLogged<boolean>('setObligationMonthPaid', 'set_obligation_month_paid', { month, paid }),
+
+    obligationsStatus: async (year: number): Promise<ObligationMonthStatus[]> =>
+      invokeLogged<ObligationMonthStatus[]>('obligationsStatus', 'obligations_status', { year }),
 
     // Offers
     getAllOffers: async (): Promise<Offer[]> =>
@@ -162,20 +504,81 @@ export function createSqliteStorageAdapter(): StorageAdapter {
       invokeLogged<boolean>('sendOfferEmail', 'send_offer_email', { input }),
 
     // Exports
-    exportInvoicesCsv: async (from: string, to: string, outputPath: string): Promise<string> =>
-      invokeLogged<string>('exportInvoicesCsv', 'export_invoices_csv', { from, to, outputPath }),
+    exportInvoicesCsv: async (from: string, to: string, outputPath: string): Promise<RangeExportResult> =>
+      invokeLogged<RangeExportResult>('exportInvoicesCsv', 'export_invoices_csv', { from, to, outputPath }),
+
+    exportExpensesCsv: async (from: string, to: string, outputPath: string): Promise<RangeExportResult> =>
+      invokeLogged<RangeExportResult>('exportExpensesCsv', 'export_expenses_csv', { from, to, outputPath }),
+
+    exportInvoicesXlsx: async (from: string, to: string, outputPath: string): Promise<RangeExportResult> =>
+      invokeLogged<RangeExportResult>('exportInvoicesXlsx', 'export_invoices_xlsx', { from, to, outputPath }),
+
+    exportExpensesXlsx: async (from: string, to: string, outputPath: string): Promise<RangeExportResult> =>
+      invokeLogged<RangeExportResult>('exportExpensesXlsx', 'export_expenses_xlsx', { from, to, outputPath }),
+
+    exportPeriodArchive: async (from: string, to: string, outputPath: string): Promise<PeriodArchiveResult> =>
+      invokeLogged<PeriodArchiveResult>('exportPeriodArchive', 'export_period_archive', { from, to, outputPath }),
+
+    emailReportToAccountant: async (
+      from: string,
+      to: string,
+      reportKinds: AccountantReportKind[]
+    ): Promise<AccountantReportResult> =>
+      invokeLogged<AccountantReportResult>('emailReportToAccountant', 'email_report_to_accountant', { from, to, reportKinds }),
+
+    exportKpoCsv: async (year: number, outputPath: string): Promise<string> =>
+      invokeLogged<string>('exportKpoCsv', 'export_kpo_csv', { year, outputPath }),
+
+    exportKpoPdf: async (year: number, outputPath: string, openAfterExport = false): Promise<ExportedFile> =>
+      invokeLogged<ExportedFile>('exportKpoPdf', 'export_kpo_pdf', { year, outputPath, openAfterExport }),
+
+    exportMonthlyReportPdf: async (year: number, month: number, outputPath: string, openAfterExport = false): Promise<ExportedFile> =>
+      invokeLogged<ExportedFile>('exportMonthlyReportPdf', 'export_monthly_report_pdf', { year, month, outputPath, openAfterExport }),
 
-    exportExpensesCsv: async (from: string, to: string, outputPath: string): Promise<string> =>
-      invokeLogged<string>('exportExpensesCsv', 'export_expenses_csv', { from, to, outputPath }),
+    exportInvoiceUblToPath: async (invoiceId: string, outputPath: string): Promise<string> =>
+      invokeLogged<string>('exportInvoiceUblToPath', 'export_invoice_ubl_to_path', { invoiceId, outputPath }),
 
     // Email
     sendInvoiceEmail: async (input: {
       invoiceId: string;
-      to: string;
+      to: string | string[];
+      cc?: string[];
+      bcc?: string[];
       subject: string;
       body?: string;
       includePdf: boolean;
-    }): Promise<boolean> => invokeLogged<boolean>('sendInvoiceEmail', 'send_invoice_email', { input }),
+      includeXml?: boolean;
+    }): Promise<string> => invokeLogged<string>('sendInvoiceEmail', 'send_invoice_email', { input }),
+
+    listOutbox: async (): Promise<OutboxItem[]> => invokeLogged<OutboxItem[]>('listOutbox', 'list_outbox'),
+
+    retryOutboxItem: async (id: string): Promise<OutboxItem> =>
+      invokeLogged<OutboxItem>('retryOutboxItem', 'retry_outbox_item', { id }),
+
+    cancelOutboxItem: async (id: string): Promise<OutboxItem> =>
+      invokeLogged<OutboxItem>('cancelOutboxItem', 'cancel_outbox_item', { id }),
+
+    testWebhook: async (): Promise<{ responseStatus: number }> =>
+      invokeLogged<{ responseStatus: number }>('testWebhook', 'test_webhook'),
+
+    listWebhookDeliveries: async (): Promise<WebhookDelivery[]> =>
+      invokeLogged<WebhookDelivery[]>('listWebhookDeliveries', 'list_webhook_deliveries'),
+
+    getAppLockStatus: async (): Promise<AppLockStatus> =>
+      invokeLogged<AppLockStatus>('getAppLockStatus', 'get_app_lock_status'),
+
+    setAppPin: async (pin: string): Promise<void> => invokeLogged<void>('setAppPin', 'set_app_pin', { pin }),
+
+    clearAppPin: async (): Promise<void> => invokeLogged<void>('clearAppPin', 'clear_app_pin'),
+
+    lockApp: async (): Promise<void> => invokeLogged<void>('lockApp', 'lock_app'),
+
+    verifyAppPin: async (pin: string): Promise<boolean> =>
+      invokeLogged<boolean>('verifyAppPin', 'verify_app_pin', { pin }),
+
+    getAppMode: async (): Promise<AppMode> => invokeLogged<AppMode>('getAppMode', 'get_app_mode'),
+
+    retryWritable: async (): Promise<AppMode> => invokeLogged<AppMode>('retryWritable', 'retry_writable'),
 
     sendLicenseRequestEmail: async (input: {
       to: string;
@@ -1,4 +1,4 @@
-import type { Client, Expense, ExpenseRange, Invoice, Offer, Settings } from '../types';
+import type { AccountantReportKind, AccountantReportResult, ActiveTimer, AppLockStatus, AppMode, CatalogItem, CatalogItemInput, CatalogUsage, Client, ClientCsvMapping, ClientImportReport, ConsistencyReport, ConsistencyRepairResult, CorruptRow, CorruptRowRepair, CreateClientResult, CreatedInvoice, DashboardSummary, DeleteClientResult, DeleteRecurringExpenseResult, DuplicateClientGroup, Expense, ExpenseCsvMapping, ExpenseCurrencySummary, ExpenseGroupBy, ExpenseImportReport, ExpenseRange, ExpenseSplitPart, ExportedFile, GeneratedObligations, Invoice, InvoiceCsvMapping, InvoiceFileImportOptions, InvoiceFileImportResult, InvoiceImportOptions, InvoiceImportReport, InvoiceYear, LimitStatus, LogEntry, LogLevel, MaintenanceReport, MatchedPayment, MergeClientsResult, Obligation, ObligationInput, ObligationMonthStatus, Offer, OutboxItem, PaymentCsvMapping, PaymentImportReport, PaymentRow, PeriodArchiveResult, Quote, QuoteInput, QuoteStatus, RangeExportResult, ReceivablesReport, RecurringExpense, RepairStrategy, SearchResult, Settings, SettingsValidation, SetupState, SetupStep, StatusSuggestion, TaxIdValidation, TaxSummary, TimeEntry, TimeEntryInput, TopClient, TopItem, TopRanking, UnbilledTime, WebhookDelivery } from '../types';
 
 /**
  * Thin async abstraction over the persistence layer.
@@ -10,32 +10,184 @@ export interface StorageAdapter {
   // Settings
   getSettings(): Promise<Settings>;
   updateSettings(patch: Partial<Settings>): Promise<Settings>;
+  /** Required company fields, check digits and counters the invoices depend on. */
+  validateSettings(): Promise<SettingsValidation>;
+  /** Stores the logo (data URL, base64 or file path) as a file; an empty source removes it. */
+  setCompanyLogo(source: string): Promise<Settings>;
+  /** PNG bytes of the logo; empty when none is set. */
+  getCompanyLogo(): Promise<ArrayBuffer>;
+  /** The legal note for `sampleNumber`; `text` previews an unsaved override for `lang`. */
+  listAvailableLanguages(): Promise<string[]>;
+  previewLegalNote(lang: string, sampleNumber: string, text?: string): Promise<string>;
+  /** Writes the settings and logo to a JSON file; SMTP login details only with `includeSmtp`. */
+  exportSettings(path: string, includeSmtp: boolean): Promise<string>;
+  /** Applies a file written by `exportSettings`, validated like a settings save. */
+  importSettings(path: string): Promise<Settings>;
+  /** Progress of the first-run wizard; survives restarts. */
+  getSetupState(): Promise<SetupState>;
+  /** Saves only the settings fields of `step` and marks it completed. */
+  completeSetupStep(step: SetupStep, payload: Partial<Settings>): Promise<SetupState>;
+  /** Runs the full settings validation and marks the company as configured. */
+  finishSetup(): Promise<SetupState>;
   generateInvoiceNumber(): Promise<string>;
   previewNextInvoiceNumber(): Promise<string>;
 
   // Clients
-  getAllClients(): Promise<Client[]>;
+  getAllClients(options?: { includeArchived?: boolean }): Promise<Client[]>;
   getClientById(id: string): Promise<Client | undefined>;
-  createClient(data: Omit<Client, 'id' | 'createdAt'>): Promise<Client>;
-  updateClient(id: string, patch: Partial<Client>): Promise<Client | null>;
-  deleteClient(id: string): Promise<boolean>;
+  /**
+   * Creates with the same `idempotencyKey` within 24 hours return the first result instead of
+   * creating again (also for invoices and expenses); generate one key per form, not per click.
+   */
+  createClient(
+    data: Omit<Client, 'id' | 'createdAt'>,
+    options?: { allowDuplicate?: boolean; idempotencyKey?: string }
+  ): Promise<CreateClientResult>;
+  /** With `propagateName`, a changed name is also written into the client's existing invoices. */
+  updateClient(id: string, patch: Partial<Client>, options?: { propagateName?: boolean }): Promise<Client | null>;
+  /** PIB / matični broj format and check digit; empty values are not errors. */
+  validateTaxIds(pib?: string, mb?: string): Promise<TaxIdValidation>;
+  deleteClient(id: string, options?: { force?: boolean }): Promise<DeleteClientResult>;
+  archiveClient(id: string): Promise<Client | null>;
+  unarchiveClient(id: string): Promise<Client | null>;
+  findDuplicateClients(): Promise<DuplicateClientGroup[]>;
+  /** Moves all invoices of `removeId` to `keepId`, fills missing fields and deletes `removeId`. */
+  mergeClients(keepId: string, removeId: string): Promise<MergeClientsResult>;
+  readCsvHeaders(path: string): Promise<string[]>;
+  /** Imports all valid rows in one transaction and reports the outcome of every row. */
+  importClientsCsv(path: string, mapping: ClientCsvMapping): Promise<ClientImportReport>;
 
   // Invoices
   getAllInvoices(): Promise<Invoice[]>;
   listInvoicesRange(from: string, to: string): Promise<Invoice[]>;
   getInvoiceById(id: string): Promise<Invoice | undefined>;
-  createInvoice(data: Omit<Invoice, 'id' | 'createdAt'>): Promise<Invoice>;
+  createInvoice(data: Omit<Invoice, 'id' | 'createdAt'>, options?: { idempotencyKey?: string }): Promise<CreatedInvoice>;
   updateInvoice(id: string, patch: Partial<Invoice>): Promise<Invoice | null>;
   deleteInvoice(id: string): Promise<boolean>;
 
   // Expenses
   listExpenses(range?: ExpenseRange): Promise<Expense[]>;
-  createExpense(data: Omit<Expense, 'id' | 'createdAt'>): Promise<Expense>;
+  getExpenseById(id: string): Promise<Expense | null>;
+  createExpense(data: Omit<Expense, 'id' | 'createdAt'>, options?: { idempotencyKey?: string }): Promise<Expense>;
   updateExpense(
     id: string,
     patch: Partial<Omit<Expense, 'id' | 'createdAt'>>
   ): Promise<Expense | null>;
-  deleteExpense(id: string): Promise<boolean>;
+  /** Soft-deletes and returns the deleted expense (for undo), or null if it was not found. */
+  deleteExpense(id: string): Promise<Expense | null>;
+  restoreExpense(id: string): Promise<Expense | null>;
+  /** Copy of the expense dated `newDate` (YYYY-MM-DD). */
+  duplicateExpense(id: string, newDate: string): Promise<Expense | null>;
+  /** Replaces the expense with parts whose amounts must add up to it exactly. */
+  splitExpense(id: string, parts: ExpenseSplitPart[]): Promise<Expense[] | null>;
+  /** Permanently removes expenses deleted more than `olderThanDays` days ago; returns the count. */
+  listExpensesForInvoice(invoiceId: string): Promise<Expense[]>;
+  /** Appends the expenses as lines to a DRAFT invoice and returns the updated invoice. */
+  billExpensesToInvoice(expenseIds: string[], invoiceId: string): Promise<Invoice | null>;
+  purgeDeletedExpenses(olderThanDays: number): Promise<number>;
+  importExpensesCsv(
+    path: string,
+    mapping: ExpenseCsvMapping,
+    defaultCurrency: string,
+    options?: { preview?: boolean }
+  ): Promise<ExpenseImportReport>;
+  /** Records incoming payments from a bank statement CSV against sent invoices; ambiguous rows are only reported. */
+  importPaymentsCsv(path: string, mapping: PaymentCsvMapping): Promise<PaymentImportReport>;
+  /** Records a reported payment against the invoice the user picked. */
+  resolvePaymentMatch(paymentRow: PaymentRow, invoiceId: string): Promise<MatchedPayment>;
+  /** Creates invoices from another tool's CSV export, keeping their numbers; clients are matched or created. */
+  importInvoicesCsv(path: string, mapping: InvoiceCsvMapping, options?: InvoiceImportOptions): Promise<InvoiceImportReport>;
+  /** Writes one invoice with a snapshot of its client to `path`; returns the path. */
+  exportInvoiceJson(id: string, path: string): Promise<string>;
+  importInvoiceJson(path: string, options?: InvoiceFileImportOptions): Promise<InvoiceFileImportResult>;
+  /** Counts and totals per issue year and status/currency, newest year first. */
+  invoicesByYear(): Promise<InvoiceYear[]>;
+  /** Invoices and clients whose indexed columns disagree with their data_json. */
+  checkDataConsistency(): Promise<ConsistencyReport>;
+  repairDataConsistency(strategy: RepairStrategy): Promise<ConsistencyRepairResult>;
+  /** Invoices and clients whose data_json cannot be read. */
+  listCorruptRows(): Promise<CorruptRow[]>;
+  /** Rebuilds an unreadable row from its columns; `patchJson` is a JSON object applied on top. */
+  repairCorruptRow(id: string, patchJson?: string): Promise<CorruptRowRepair>;
+  /** Integrity check; with `compact` also checkpoint, ANALYZE and VACUUM. */
+  databaseMaintenance(compact: boolean): Promise<MaintenanceReport>;
+  /** Newest `lines` log entries at `level` or more severe, oldest first. */
+  getRecentLogs(lines: number, level?: LogLevel): Promise<LogEntry[]>;
+  getLogLevel(): Promise<LogLevel>;
+  /** Applies immediately and is remembered across restarts. */
+  setLogLevel(level: LogLevel): Promise<LogLevel>;
+  /**
+   * Invoices, clients and expenses matching every word of `query` (either script), best first.
+   * Archived clients are left out unless `includeArchived` is set.
+   */
+  globalSearch(query: string, limit?: number, options?: { includeArchived?: boolean }): Promise<SearchResult[]>;
+  expenseSummary(range: ExpenseRange, groupBy: ExpenseGroupBy): Promise<ExpenseCurrencySummary[]>;
+  /** Monthly invoiced/paid/outstanding/expense totals for `year`, per currency. */
+  dashboardSummary(year: number): Promise<DashboardSummary>;
+  /** Clients by paid revenue in the range (payment dates), at most `limit` per currency. */
+  topClients(range: ExpenseRange, limit?: number): Promise<TopRanking<TopClient>[]>;
+  /** Invoice items by paid revenue in the range, at most `limit` per currency. */
+  topItems(range: ExpenseRange, limit?: number): Promise<TopRanking<TopItem>[]>;
+  /** Turnover for `year` against the paušal limit and the VAT threshold. */
+  limitStatus(year: number): Promise<LimitStatus>;
+  /** Unpaid SENT invoices per client and currency, bucketed by days past due. */
+  accountsReceivableReport(): Promise<ReceivablesReport>;
+  /** Stale drafts, overdue and undated PAID invoices, with the fixes to offer. */
+  suggestStatusUpdates(): Promise<StatusSuggestion[]>;
+  exportAccountsReceivableCsv(outputPath: string): Promise<string>;
+  /** Quarterly paid income (by payment date), expenses and base for `year`, in the default currency. */
+  taxSummary(year: number): Promise<TaxSummary>;
+  exportTaxSummaryCsv(year: number, outputPath: string): Promise<string>;
+  listRecurringExpenses(): Promise<RecurringExpense[]>;
+  createRecurringExpense(
+    data: Omit<RecurringExpense, 'id' | 'createdAt' | 'nextRunDate'>
+  ): Promise<RecurringExpense>;
+  updateRecurringExpense(
+    id: string,
+    patch: Partial<Omit<RecurringExpense, 'id' | 'createdAt' | 'nextRunDate'>>
+  ): Promise<RecurringExpense | null>;
+  /** `deleteFuture` also removes generated expenses dated after today. */
+  deleteRecurringExpense(id: string, options?: { deleteFuture?: boolean }): Promise<DeleteRecurringExpenseResult>;
+  /** Creates the expenses of every elapsed period; returns how many were created. */
+  runDueRecurringExpenses(): Promise<number>;
+  listCatalogItems(options?: { includeInactive?: boolean }): Promise<CatalogItem[]>;
+  createCatalogItem(input: CatalogItemInput): Promise<CatalogItem>;
+  updateCatalogItem(id: string, patch: Partial<CatalogItemInput>): Promise<CatalogItem | null>;
+  deleteCatalogItem(id: string): Promise<boolean>;
+  /** Active catalog items matching every word of `query`, in either script and without diacritics. */
+  searchCatalog(query: string, limit?: number): Promise<CatalogItem[]>;
+  catalogUsageReport(from?: string | null, to?: string | null): Promise<CatalogUsage[]>;
+
+  listTimeEntries(options?: { clientId?: string | null; unbilledOnly?: boolean }): Promise<TimeEntry[]>;
+  createTimeEntry(input: TimeEntryInput): Promise<TimeEntry>;
+  updateTimeEntry(id: string, patch: Partial<TimeEntryInput>): Promise<TimeEntry | null>;
+  deleteTimeEntry(id: string): Promise<boolean>;
+  getActiveTimer(): Promise<ActiveTimer | null>;
+  startTimer(clientId: string, description: string, hourlyRate: number): Promise<ActiveTimer>;
+  /** Records the running timer as a time entry; `null` when none was running. */
+  stopTimer(): Promise<TimeEntry | null>;
+  /** Adds the entries to a draft invoice as hourly items and marks them billed. */
+  billTimeEntries(entryIds: string[], invoiceId: string): Promise<Invoice | null>;
+  unbilledTimeReport(): Promise<UnbilledTime[]>;
+
+  listQuotes(): Promise<Quote[]>;
+  createQuote(input: QuoteInput): Promise<Quote>;
+  updateQuote(id: string, patch: Partial<QuoteInput> & { status?: QuoteStatus }): Promise<Quote | null>;
+  deleteQuote(id: string): Promise<boolean>;
+  /** Creates a draft invoice from the quote and marks the quote accepted. */
+  convertQuoteToInvoice(quoteId: string): Promise<Invoice | null>;
+  exportQuotePdf(id: string, openAfterExport?: boolean): Promise<ExportedFile>;
+  /** An empty `to` sends to the client's invoice address. */
+  sendQuoteEmail(input: { quoteId: string; to: string[]; subject: string; body: string }): Promise<Quote>;
+
+  listObligations(): Promise<Obligation[]>;
+  createObligation(input: ObligationInput): Promise<Obligation>;
+  updateObligation(id: string, input: ObligationInput): Promise<Obligation | null>;
+  deleteObligation(id: string): Promise<boolean>;
+  /** Creates the tax and contribution expenses of `month` (YYYY-MM) from the decision in force. */
+  generateMonthlyObligationExpenses(month: string): Promise<GeneratedObligations>;
+  setObligationMonthPaid(month: string, paid: boolean): Promise<boolean>;
+  obligationsStatus(year: number): Promise<ObligationMonthStatus[]>;
 
   // Offers
   getAllOffers(): Promise<Offer[]>;
@@ -46,17 +198,52 @@ export interface StorageAdapter {
   sendOfferEmail(input: { offerId: string }): Promise<boolean>;
 
   // Exports
-  exportInvoicesCsv(from: string, to: string, outputPath: string): Promise<string>;
-  exportExpensesCsv(from: string, to: string, outputPath: string): Promise<string>;
+  exportInvoicesCsv(from: string, to: string, outputPath: string): Promise<RangeExportResult>;
+  exportExpensesCsv(from: string, to: string, outputPath: string): Promise<RangeExportResult>;
+  /** Same rows as the CSV exports, with numeric and date cells plus a monthly summary sheet. */
+  exportInvoicesXlsx(from: string, to: string, outputPath: string): Promise<RangeExportResult>;
+  exportExpensesXlsx(from: string, to: string, outputPath: string): Promise<RangeExportResult>;
+  /** ZIP with the invoice PDFs, CSVs and KPO books of the period; emits `period_archive_progress`. */
+  exportPeriodArchive(from: string, to: string, outputPath: string): Promise<PeriodArchiveResult>;
+  /** Emails the chosen reports of the period to `settings.accountantEmail`; nothing is sent if one fails. */
+  emailReportToAccountant(from: string, to: string, reportKinds: AccountantReportKind[]): Promise<AccountantReportResult>;
+  /** KPO book for `year` (SENT and PAID invoices, in the default currency). */
+  exportKpoCsv(year: number, outputPath: string): Promise<string>;
+  exportKpoPdf(year: number, outputPath: string, openAfterExport?: boolean): Promise<ExportedFile>;
+  exportMonthlyReportPdf(year: number, month: number, outputPath: string, openAfterExport?: boolean): Promise<ExportedFile>;
+  exportInvoiceUblToPath(invoiceId: string, outputPath: string): Promise<string>;
 
-  // Email
+  // Email (queued; progress arrives via the `email://status` event)
   sendInvoiceEmail(input: {
     invoiceId: string;
-    to: string;
+    to: string | string[];
+    cc?: string[];
+    bcc?: string[];
     subject: string;
     body?: string;
     includePdf: boolean;
-  }): Promise<boolean>;
+    includeXml?: boolean;
+  }): Promise<string>;
+  listOutbox(): Promise<OutboxItem[]>;
+  retryOutboxItem(id: string): Promise<OutboxItem>;
+  cancelOutboxItem(id: string): Promise<OutboxItem>;
+
+  // Invoice event webhooks
+  testWebhook(): Promise<{ responseStatus: number }>;
+  listWebhookDeliveries(): Promise<WebhookDelivery[]>;
+
+  // App PIN / lock screen; while locked every other call is rejected by the backend.
+  getAppLockStatus(): Promise<AppLockStatus>;
+  setAppPin(pin: string): Promise<void>;
+  clearAppPin(): Promise<void>;
+  lockApp(): Promise<void>;
+  /** `true` unlocks the app; `false` for a wrong PIN. Throws while attempts are being delayed. */
+  verifyAppPin(pin: string): Promise<boolean>;
+
+  // Read-only mode while the database file is locked or can't be written.
+  getAppMode(): Promise<AppMode>;
+  /** Checks again and leaves read-only mode if writes work now. */
+  retryWritable(): Promise<AppMode>;
 
   // License request email (no attachments)
   sendLicenseRequestEmail(input: {
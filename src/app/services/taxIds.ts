@@ -0,0 +1,20 @@
+import type { Rule } from 'antd/es/form';
+
+import { getStorage } from './storageProvider';
+
+/**
+ * Form rule that checks a PIB or matični broj with the backend (check digit included) on blur.
+ * The backend returns the message in the app language. `skip` disables it for foreign clients.
+ */
+export function taxIdRule(field: 'pib' | 'mb', skip?: () => boolean): Rule {
+  return {
+    validateTrigger: 'onBlur',
+    validator: async (_rule, value) => {
+      const v = String(value ?? '').trim();
+      if (!v || skip?.()) return;
+      const res = await getStorage().validateTaxIds(field === 'pib' ? v : undefined, field === 'mb' ? v : undefined);
+      const error = field === 'pib' ? res.pibError : res.mbError;
+      if (error) throw new Error(error);
+    },
+  };
+}
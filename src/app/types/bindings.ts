@@ -0,0 +1,592 @@
+// Generated from the Rust command types by src-tauri/src/bindings.rs. Do not edit by hand;
+// run `UPDATE_BINDINGS=1 cargo test bindings` in src-tauri (debug builds also rewrite it on startup).
+
+export interface BankAccount {
+  /** Domestic account in the `3-13-2` form; may be empty for an IBAN-only foreign account. */
+  accountNumber?: string;
+  /** ISO code of the currency the account is kept in. */
+  currency?: string;
+  bankName?: string;
+  iban?: string;
+  swift?: string;
+  isDefault?: boolean;
+}
+
+export interface Client {
+  id: string;
+  name: string;
+  registrationNumber?: string;
+  pib: string;
+  address: string;
+  city?: string;
+  postalCode?: string;
+  email: string;
+  phone?: string | null;
+  website?: string | null;
+  /** Pre-filled on new invoices for this client (e.g. "EUR"). */
+  defaultCurrency?: string | null;
+  /** PDF and email language for this client ("sr", "en" or "bilingual"); falls back to settings. */
+  preferredLanguage?: string | null;
+  /** Due date = issue date + these days, when an invoice has no explicit due date. */
+  paymentTermsDays?: number | null;
+  /** Default invoice email recipient; `email` is used when empty. */
+  billingEmail?: string | null;
+  /** Hidden from the client picker; existing invoices keep referencing it. */
+  archived?: boolean;
+  /** Client outside Serbia: `pib` holds its VAT ID and no matični broj is required. */
+  isForeign?: boolean;
+  /** ISO 3166-1 alpha-2 country code (e.g. "DE"), for foreign clients. */
+  country?: string | null;
+  createdAt: string;
+}
+
+/** CSV header for each client field; only `name` is required. */
+export interface ClientCsvMapping {
+  name: string;
+  pib?: string | null;
+  registrationNumber?: string | null;
+  address?: string | null;
+  postalCode?: string | null;
+  city?: string | null;
+  email?: string | null;
+  phone?: string | null;
+}
+
+export interface ClientImportReport {
+  imported: number;
+  skipped: number;
+  duplicates: number;
+  rows: ClientImportRow[];
+}
+
+export interface ClientImportRow {
+  /** 1-based line in the CSV file. */
+  line: number;
+  name: string;
+  status: ClientImportStatus;
+  message?: string | null;
+  clientId?: string | null;
+}
+
+export type ClientImportStatus = "imported" | "skipped" | "duplicate";
+
+/** Result of `create_client`: either the new client or the existing one it collides with. */
+export type CreateClientResult = { status: "created"; client: Client } | { status: "duplicate"; existingId: string; existingName: string; matchedOn: DuplicateMatch };
+
+/** `create_invoice` result: the invoice itself plus non-blocking turnover limit warnings. */
+export interface CreatedInvoice {
+  /** Omitted when there are none. */
+  limitWarnings?: LimitWarning[];
+  id: string;
+  invoiceNumber: string;
+  clientId: string;
+  clientName: string;
+  issueDate: string;
+  serviceDate: string;
+  status?: InvoiceStatus;
+  dueDate?: string | null;
+  paidAt?: string | null;
+  currency: string;
+  items: InvoiceItem[];
+  subtotal: number;
+  /** Flat discount on the whole invoice, applied after line discounts. */
+  invoiceDiscount?: number | null;
+  /** Percent discount on the whole invoice (0-100), applied after line discounts. */
+  invoiceDiscountPercent?: number | null;
+  total: number;
+  notes: string;
+  paymentMethod?: PaymentMethod | null;
+  /** Payment reference with model prefix ("97 04-2024-001"). */
+  paymentReference?: string | null;
+  /** Default-currency units per one unit of `currency` (NBS middle rate), for foreign-currency invoices; used to bring them into the KPO book and turnover reports. */
+  exchangeRate?: number | null;
+  /** Brought in with its original number, from another tool by `import_invoices_csv` or from another installation by `import_invoice_json`. */
+  imported?: boolean;
+  /** Rounding of the totals, taken from the settings when the invoice is created. */
+  roundingMode?: RoundingMode;
+  /** Quote the invoice was converted from. */
+  quoteId?: string | null;
+  /** Person who issued the invoice; the settings default when it was created without one. */
+  issuedBy?: string | null;
+  /** Client-specific label/value rows (PO number, cost center, …). */
+  customFields?: CustomField[];
+  createdAt: string;
+}
+
+export type CsvLineEnding = "crlf" | "lf";
+
+/** CSV dialect of the exports. The default is plain RFC 4180; Excel with a Serbian locale wants `;`, decimal commas and a BOM. */
+export interface CsvOptions {
+  delimiter?: string;
+  decimalComma?: boolean;
+  includeBom?: boolean;
+  lineEnding?: CsvLineEnding;
+}
+
+export interface CustomField {
+  label?: string;
+  value?: string;
+}
+
+/** Result of `delete_client`. `blocked` means invoices still reference the client and `force` was not set; nothing was deleted. */
+export interface DeleteClientResult {
+  deleted: boolean;
+  blocked: boolean;
+  invoiceCount: number;
+}
+
+export interface DuplicateClientGroup {
+  matchedOn: DuplicateMatch;
+  /** The normalized value the clients share. */
+  key: string;
+  clients: Client[];
+}
+
+export type DuplicateMatch = "pib" | "registrationNumber" | "name";
+
+export interface EmailPalette {
+  /** Around the card. */
+  background: string;
+  card: string;
+  /** Outline of the card and its boxes, and the dividers. */
+  border: string;
+  /** The title and the total. */
+  accent: string;
+  text: string;
+  /** Row labels. */
+  muted: string;
+  /** The issuer address and the footer. */
+  faint: string;
+}
+
+/** The light palette is what every client shows unless it switches to dark mode. */
+export interface EmailTheme {
+  light?: EmailPalette;
+  dark?: EmailPalette;
+}
+
+export interface Expense {
+  id: string;
+  title: string;
+  amount: number;
+  currency: string;
+  date: string;
+  category?: string | null;
+  notes?: string | null;
+  createdAt: string;
+  /** Schedule in `recurring_expenses` that generated this expense. */
+  recurringId?: string | null;
+  /** Set when the expense is in the trash; it can be restored until purged. */
+  deletedAt?: string | null;
+  /** Invoice this expense was passed through to (billable expense). */
+  invoiceId?: string | null;
+}
+
+export interface ExpensePatch {
+  title?: string | null;
+  amount?: number | null;
+  currency?: string | null;
+  date?: string | null;
+  category?: string | null;
+  notes?: string | null;
+  invoiceId?: string | null;
+}
+
+/** An exported file and the outcome of opening it when that was asked for. */
+export interface ExportedFile {
+  path: string;
+  opened: boolean;
+  /** Why the file couldn't be opened; `None` when it was, or wasn't asked to be. */
+  openError?: string | null;
+}
+
+export interface Invoice {
+  id: string;
+  invoiceNumber: string;
+  clientId: string;
+  clientName: string;
+  issueDate: string;
+  serviceDate: string;
+  status?: InvoiceStatus;
+  dueDate?: string | null;
+  paidAt?: string | null;
+  currency: string;
+  items: InvoiceItem[];
+  subtotal: number;
+  /** Flat discount on the whole invoice, applied after line discounts. */
+  invoiceDiscount?: number | null;
+  /** Percent discount on the whole invoice (0-100), applied after line discounts. */
+  invoiceDiscountPercent?: number | null;
+  total: number;
+  notes: string;
+  paymentMethod?: PaymentMethod | null;
+  /** Payment reference with model prefix ("97 04-2024-001"). */
+  paymentReference?: string | null;
+  /** Default-currency units per one unit of `currency` (NBS middle rate), for foreign-currency invoices; used to bring them into the KPO book and turnover reports. */
+  exchangeRate?: number | null;
+  /** Brought in with its original number, from another tool by `import_invoices_csv` or from another installation by `import_invoice_json`. */
+  imported?: boolean;
+  /** Rounding of the totals, taken from the settings when the invoice is created. */
+  roundingMode?: RoundingMode;
+  /** Quote the invoice was converted from. */
+  quoteId?: string | null;
+  /** Person who issued the invoice; the settings default when it was created without one. */
+  issuedBy?: string | null;
+  /** Client-specific label/value rows (PO number, cost center, …). */
+  customFields?: CustomField[];
+  createdAt: string;
+}
+
+export interface InvoiceItem {
+  id: string;
+  description: string;
+  unit?: string | null;
+  quantity: number;
+  unitPrice: number;
+  discountAmount?: number | null;
+  total: number;
+  /** Catalog item the line was picked from; the line keeps its own copy of the details. */
+  catalogItemId?: string | null;
+}
+
+export interface InvoicePatch {
+  invoiceNumber?: string | null;
+  clientId?: string | null;
+  clientName?: string | null;
+  issueDate?: string | null;
+  serviceDate?: string | null;
+  status?: InvoiceStatus | null;
+  dueDate?: string | null;
+  currency?: string | null;
+  items?: InvoiceItem[] | null;
+  subtotal?: number | null;
+  invoiceDiscount?: number | null;
+  invoiceDiscountPercent?: number | null;
+  total?: number | null;
+  notes?: string | null;
+  paymentMethod?: PaymentMethod | null;
+  paymentReference?: string | null;
+  exchangeRate?: number | null;
+  issuedBy?: string | null;
+  customFields?: CustomField[] | null;
+  /** Only kept for PAID invoices; a PAID invoice without one is stamped with today. */
+  paidAt?: string | null;
+}
+
+export interface InvoicePdfClient {
+  name: string;
+  registration_number?: string | null;
+  pib?: string | null;
+  address?: string | null;
+  address_line?: string | null;
+  postal_code?: string | null;
+  city?: string | null;
+  email?: string | null;
+  phone?: string | null;
+  /** Foreign buyer: no matični broj required, `pib` is printed as the VAT ID. */
+  is_foreign?: boolean;
+  country?: string | null;
+}
+
+export interface InvoicePdfCompany {
+  company_name: string;
+  registration_number: string;
+  pib: string;
+  address: string;
+  address_line?: string | null;
+  postal_code?: string | null;
+  city?: string | null;
+  bank_account: string;
+  /** Set for foreign-currency invoices only, printed under the account number. */
+  iban?: string | null;
+  swift?: string | null;
+  email?: string | null;
+  phone?: string | null;
+}
+
+export interface InvoicePdfItem {
+  description: string;
+  unit?: string | null;
+  quantity: number;
+  unit_price: number;
+  discount_amount?: number | null;
+  total: number;
+}
+
+export interface InvoicePdfPayload {
+  language?: string | null;
+  invoice_number: string;
+  issue_date: string;
+  service_date: string;
+  currency: string;
+  subtotal: number;
+  discount_total?: number;
+  /** Invoice-level discount amount (already resolved from flat/percent), applied after line discounts. */
+  invoice_discount?: number | null;
+  invoice_discount_percent?: number | null;
+  total: number;
+  notes?: string | null;
+  /** `TRANSFER` | `CASH` | `CARD` */
+  payment_method?: PaymentMethod | null;
+  /** Payment reference with model prefix; falls back to the invoice number when absent. */
+  payment_reference?: string | null;
+  company: InvoicePdfCompany;
+  client: InvoicePdfClient;
+  items: InvoicePdfItem[];
+  /** Taken from the settings when the PDF is generated, not from the frontend. */
+  legal_note_override?: LegalNoteOverride;
+  /** Set for quotes: the PDF gets the quote title and a validity line instead of the service date and payment details. */
+  valid_until?: string | null;
+  /** Person who issued the invoice, printed in the signature area. */
+  issued_by?: string | null;
+  /** Printed as "label: value" rows in the details block. */
+  custom_fields?: CustomField[];
+}
+
+export type InvoiceStatus = "DRAFT" | "SENT" | "PAID" | "CANCELLED";
+
+/** Custom legal note text per invoice language. `{INVOICE_NUMBER}` is substituted like in the built-in templates. */
+export interface LegalNoteOverride {
+  sr?: string;
+  en?: string;
+}
+
+export type LimitKind = "turnover" | "vat";
+
+/** Raised by `create_invoice` when the new invoice pushes turnover past 80% or 100% of a limit. */
+export interface LimitWarning {
+  kind: LimitKind;
+  /** 80 or 100. */
+  thresholdPercent: number;
+  limitRsd: number;
+  turnoverRsd: number;
+}
+
+export interface MergeClientsResult {
+  client: Client;
+  invoicesMoved: number;
+}
+
+export interface NewClient {
+  name: string;
+  registrationNumber?: string;
+  pib: string;
+  address: string;
+  city?: string;
+  postalCode?: string;
+  email: string;
+  phone?: string | null;
+  website?: string | null;
+  defaultCurrency?: string | null;
+  preferredLanguage?: string | null;
+  paymentTermsDays?: number | null;
+  billingEmail?: string | null;
+  isForeign?: boolean;
+  country?: string | null;
+  /** Repeating a create with the same key within 24h returns the first client. */
+  idempotencyKey?: string | null;
+}
+
+export interface NewExpense {
+  title: string;
+  amount: number;
+  currency: string;
+  date: string;
+  category?: string | null;
+  notes?: string | null;
+  /** Repeating a create with the same key within 24h returns the first expense. */
+  idempotencyKey?: string | null;
+}
+
+export interface NewInvoice {
+  clientId: string;
+  clientName: string;
+  issueDate: string;
+  serviceDate: string;
+  status?: InvoiceStatus | null;
+  dueDate?: string | null;
+  currency: string;
+  items: InvoiceItem[];
+  subtotal: number;
+  invoiceDiscount?: number | null;
+  invoiceDiscountPercent?: number | null;
+  total: number;
+  notes: string;
+  paymentMethod?: PaymentMethod | null;
+  /** When omitted, a model 97 reference is generated from the invoice number. */
+  paymentReference?: string | null;
+  exchangeRate?: number | null;
+  /** Overrides the settings' `issued_by` for this invoice. */
+  issuedBy?: string | null;
+  customFields?: CustomField[];
+  /** Repeating a create with the same key within 24h returns the first invoice. */
+  idempotencyKey?: string | null;
+}
+
+export type PaymentMethod = "TRANSFER" | "CASH" | "CARD";
+
+export type RoundingMode = "halfUp" | "halfEven" | "down";
+
+export interface SendInvoiceEmailInput {
+  invoiceId: string;
+  to: string[];
+  cc?: string[];
+  bcc?: string[];
+  subject: string;
+  body?: string | null;
+  includePdf?: boolean;
+  /** Also attach the UBL 2.1 XML e-invoice. */
+  includeXml?: boolean;
+}
+
+export interface Settings {
+  isConfigured?: boolean | null;
+  companyName: string;
+  registrationNumber?: string;
+  pib: string;
+  companyAddressLine?: string;
+  companyCity?: string;
+  companyPostalCode?: string;
+  companyEmail?: string;
+  companyPhone?: string;
+  /** Number of the default account in `bank_accounts`, kept for code that needs a single account. */
+  bankAccount: string;
+  /** Dinar and foreign-currency accounts; invoices use the one in their currency. */
+  bankAccounts?: BankAccount[];
+  logoUrl: string;
+  invoicePrefix: string;
+  nextInvoiceNumber: number;
+  defaultCurrency: string;
+  language: string;
+  smtpHost?: string;
+  smtpPort?: number;
+  smtpUser?: string;
+  smtpPassword?: string;
+  /** Set on settings returned to the UI, which never receives the password itself. */
+  smtpPasswordConfigured?: boolean;
+  smtpFrom?: string;
+  smtpUseTls?: boolean;
+  smtpTlsMode?: SmtpTlsMode | null;
+  smtpTimeoutSeconds?: number;
+  smtpReplyTo?: string;
+  smtpFromName?: string;
+  /** BCC the From address on invoice emails to keep an archival copy. */
+  sendCopyToSelf?: boolean;
+  smtpAuthMode?: SmtpAuthMode;
+  smtpOauthClientId?: string;
+  /** Keyring sentinel once saved; never returned to the UI. */
+  smtpOauthClientSecret?: string;
+  /** Keyring sentinel once saved; never returned to the UI. */
+  smtpOauthRefreshToken?: string;
+  smtpOauthTokenEndpoint?: string;
+  /** Set on settings returned to the UI in place of the refresh token. */
+  smtpOauthConfigured?: boolean;
+  /** Skip certificate verification. Only allowed for private hosts or with the acknowledgement. */
+  smtpAcceptInvalidCerts?: boolean;
+  /** "I understand the risk" confirmation for `smtp_accept_invalid_certs` on a public host. */
+  smtpAcceptInvalidCertsAcknowledged?: boolean;
+  /** PEM certificate (internal CA or self-signed server cert). When set, it is the only trust root. */
+  smtpPinnedCertPem?: string;
+  /** Yearly turnover above which the paušal status is lost (RSD). */
+  turnoverLimitRsd?: number;
+  /** Yearly turnover above which VAT registration is required (RSD). */
+  vatLimitRsd?: number;
+  /** Copy the database into `backups/` on startup when the newest copy is older than a day. */
+  autoBackupEnabled?: boolean;
+  /** How many automatic backups to keep; older ones are deleted. */
+  autoBackupKeep?: number;
+  /** Dialect used by the CSV exports unless a call overrides it. */
+  csvOptions?: CsvOptions;
+  /** Notes a new invoice starts with when it is created without any. */
+  defaultInvoiceNotes?: string;
+  /** Replaces the built-in legal note on invoices, per language; blank keeps the built-in one. */
+  legalNoteOverride?: LegalNoteOverride;
+  /** How line totals, discounts and invoice totals are rounded to the cent on new invoices. */
+  roundingMode?: RoundingMode;
+  /** Quotes are numbered apart from invoices, as `{quotePrefix}-{nextQuoteNumber}`. */
+  quotePrefix?: string;
+  nextQuoteNumber?: number;
+  /** Invoice events are POSTed here when set; see `webhooks`. */
+  webhookUrl?: string;
+  /** HMAC key for the `X-Pausaler-Signature` header, kept in the OS keyring like the SMTP password. */
+  webhookSecret?: string;
+  /** Set on settings returned to the UI, which never receives the secret itself. */
+  webhookSecretConfigured?: boolean;
+  /** Name of exported invoice PDFs; see `pdf_filename` for the placeholders. */
+  pdfFilenameTemplate?: string;
+  /** Person printed as "Fakturu izdao" on new invoices unless the invoice names someone else. */
+  issuedBy?: string | null;
+  /** Recipient of `email_report_to_accountant`. */
+  accountantEmail?: string;
+  /** A DRAFT older than this is suggested for sending or cancelling. */
+  staleDraftDays?: number;
+  /** Days past due before a SENT invoice is suggested for a reminder. */
+  overdueGraceDays?: number;
+  /** Labels the invoice form offers for custom fields (PO number, cost center, …). */
+  customFieldLabels?: string[];
+  /** Invoice emails larger than this many megabytes are not sent; most providers reject them. */
+  emailMaxMessageMb?: number;
+  /** Colors of the invoice email, with a dark variant for clients in dark mode. */
+  emailTheme?: EmailTheme;
+}
+
+export interface SettingsPatch {
+  isConfigured?: boolean | null;
+  companyName?: string | null;
+  registrationNumber?: string | null;
+  pib?: string | null;
+  companyAddressLine?: string | null;
+  companyCity?: string | null;
+  companyPostalCode?: string | null;
+  companyEmail?: string | null;
+  companyPhone?: string | null;
+  bankAccount?: string | null;
+  bankAccounts?: BankAccount[] | null;
+  invoicePrefix?: string | null;
+  nextInvoiceNumber?: number | null;
+  defaultCurrency?: string | null;
+  language?: string | null;
+  smtpHost?: string | null;
+  smtpPort?: number | null;
+  smtpUser?: string | null;
+  smtpPassword?: string | null;
+  smtpFrom?: string | null;
+  smtpUseTls?: boolean | null;
+  smtpTlsMode?: SmtpTlsMode | null;
+  smtpTimeoutSeconds?: number | null;
+  smtpReplyTo?: string | null;
+  smtpFromName?: string | null;
+  sendCopyToSelf?: boolean | null;
+  smtpAuthMode?: SmtpAuthMode | null;
+  smtpOauthClientId?: string | null;
+  smtpOauthClientSecret?: string | null;
+  smtpOauthRefreshToken?: string | null;
+  smtpOauthTokenEndpoint?: string | null;
+  smtpAcceptInvalidCerts?: boolean | null;
+  smtpAcceptInvalidCertsAcknowledged?: boolean | null;
+  smtpPinnedCertPem?: string | null;
+  turnoverLimitRsd?: number | null;
+  vatLimitRsd?: number | null;
+  autoBackupEnabled?: boolean | null;
+  autoBackupKeep?: number | null;
+  csvOptions?: CsvOptions | null;
+  defaultInvoiceNotes?: string | null;
+  legalNoteOverride?: LegalNoteOverride | null;
+  roundingMode?: RoundingMode | null;
+  quotePrefix?: string | null;
+  nextQuoteNumber?: number | null;
+  webhookUrl?: string | null;
+  webhookSecret?: string | null;
+  pdfFilenameTemplate?: string | null;
+  issuedBy?: string | null;
+  accountantEmail?: string | null;
+  staleDraftDays?: number | null;
+  overdueGraceDays?: number | null;
+  customFieldLabels?: string[] | null;
+  emailMaxMessageMb?: number | null;
+  emailTheme?: EmailTheme | null;
+}
+
+export type SmtpAuthMode = "password" | "oauth2";
+
+export type SmtpTlsMode = "implicit" | "starttls";
@@ -8,10 +8,107 @@ export interface Client {
   city: string;
   postalCode: string;
   email: string;
-  phone?: string;
+  phone?: string | null;
+  website?: string | null;
+  /** Pre-filled on new invoices for this client. */
+  defaultCurrency?: string | null;
+  /** PDF and email language for this client (a code from `listAvailableLanguages` or `bilingual`); falls back to settings. */
+  preferredLanguage?: string | null;
+  /** Due date = issue date + these days when an invoice has no due date. */
+  paymentTermsDays?: number | null;
+  /** Default invoice email recipient; `email` is used when empty. */
+  billingEmail?: string | null;
+  /** Hidden from the client picker; existing invoices keep referencing it. */
+  archived?: boolean;
+  /** Client outside Serbia: `pib` holds its VAT ID and no matični broj is required. */
+  isForeign?: boolean;
+  /** ISO 3166-1 alpha-2 country code, for foreign clients. */
+  country?: string | null;
   createdAt: string;
 }
 
+export type DuplicateMatch = 'pib' | 'registrationNumber' | 'name';
+
+/** `create_client` refuses a client whose PIB or MB already exists unless `allowDuplicate` is set. */
+export type CreateClientResult =
+  | { status: 'created'; client: Client }
+  | { status: 'duplicate'; existingId: string; existingName: string; matchedOn: DuplicateMatch };
+
+/** `blocked` means the client still has invoices and `force` was not set; nothing was deleted. */
+export interface DeleteClientResult {
+  deleted: boolean;
+  blocked: boolean;
+  invoiceCount: number;
+}
+
+/** CSV header for each client field; only `name` is required. */
+export interface ClientCsvMapping {
+  name: string;
+  pib?: string;
+  registrationNumber?: string;
+  address?: string;
+  postalCode?: string;
+  city?: string;
+  email?: string;
+  phone?: string;
+}
+
+export type ClientImportStatus = 'imported' | 'skipped' | 'duplicate';
+
+export interface ClientImportRow {
+  line: number;
+  name: string;
+  status: ClientImportStatus;
+  message?: string | null;
+  clientId?: string | null;
+}
+
+/** Result of `validate_tax_ids`; messages are in the app language. */
+export interface TaxIdValidation {
+  valid: boolean;
+  pibError: string | null;
+  mbError: string | null;
+}
+
+/** A missing or invalid company setting; `field` is the `Settings` key it belongs to. */
+export interface SettingsProblem {
+  field: string;
+  message: string;
+}
+
+export interface SettingsValidation {
+  valid: boolean;
+  problems: SettingsProblem[];
+}
+
+/** First-run wizard steps, in order. */
+export type SetupStep = 'company' | 'numbering' | 'email' | 'license';
+
+export interface SetupState {
+  completedSteps: SetupStep[];
+  /** First step not completed yet; null once all are done. */
+  currentStep: SetupStep | null;
+  isConfigured: boolean;
+}
+
+export interface ClientImportReport {
+  imported: number;
+  skipped: number;
+  duplicates: number;
+  rows: ClientImportRow[];
+}
+
+export interface MergeClientsResult {
+  client: Client;
+  invoicesMoved: number;
+}
+
+export interface DuplicateClientGroup {
+  matchedOn: DuplicateMatch;
+  key: string;
+  clients: Client[];
+}
+
 export interface InvoiceItem {
   id: string;
   description: string;
@@ -21,6 +118,8 @@ export interface InvoiceItem {
   /** Optional per-line absolute discount amount in invoice currency. */
   discountAmount?: number;
   total: number;
+  /** Catalog item the line was picked from; the line keeps its own description and price. */
+  catalogItemId?: string | null;
 }
 
 export const INVOICE_UNIT_VALUES = ['kom', 'sat', 'm2', 'usluga'] as const;
@@ -51,6 +150,9 @@ export function invoiceUnitLabel(unit: InvoiceUnit): string {
 export const INVOICE_STATUS_VALUES = ['DRAFT', 'SENT', 'PAID', 'CANCELLED'] as const;
 export type InvoiceStatus = (typeof INVOICE_STATUS_VALUES)[number];
 
+export const PAYMENT_METHOD_VALUES = ['TRANSFER', 'CASH', 'CARD'] as const;
+export type PaymentMethod = (typeof PAYMENT_METHOD_VALUES)[number];
+
 export interface Invoice {
   id: string;
   invoiceNumber: string;
@@ -64,11 +166,46 @@ export interface Invoice {
   currency: string;
   items: InvoiceItem[];
   subtotal: number;
+  /** Flat discount on the whole invoice, applied after line discounts. */
+  invoiceDiscount?: number | null;
+  /** Percent discount on the whole invoice (0-100), applied after line discounts. */
+  invoiceDiscountPercent?: number | null;
   total: number;
+  /** Rounding the totals were computed with; set by the backend when the invoice is created. */
+  roundingMode?: RoundingMode;
+  /** Quote the invoice was converted from. */
+  quoteId?: string | null;
   notes: string;
+  paymentMethod?: PaymentMethod | null;
+  /** Payment reference with model prefix, e.g. "97 04-2024-001". */
+  paymentReference?: string | null;
+  /** Default-currency units per one unit of `currency` (NBS middle rate), for foreign-currency invoices. */
+  exchangeRate?: number | null;
+  /** Brought in from another tool's CSV export, keeping its original number. */
+  imported?: boolean;
+  /** Person who issued the invoice; new invoices get the settings default when left empty. */
+  issuedBy?: string | null;
+  /** Client-specific rows (PO number, cost center, …) printed in the details block; at most 5. */
+  customFields?: InvoiceCustomField[];
   createdAt: string;
 }
 
+export interface InvoiceCustomField {
+  label: string;
+  value: string;
+}
+
+/** A company account; foreign-currency (devizni) accounts also carry IBAN and SWIFT. */
+export interface BankAccount {
+  /** Domestic number (`3-13-2`); may be empty for an IBAN-only account. */
+  accountNumber: string;
+  currency: string;
+  bankName: string;
+  iban: string;
+  swift: string;
+  isDefault: boolean;
+}
+
 export interface Settings {
   /**
    * Explicit onboarding marker. When false (or missing), the app may require the user
@@ -83,7 +220,11 @@ export interface Settings {
   companyPostalCode: string;
   companyEmail: string;
   companyPhone: string;
+  /** Number of the default entry in `bankAccounts`; kept in sync by the backend. */
   bankAccount: string;
+  /** Invoices use the account in their currency, otherwise the default one. */
+  bankAccounts: BankAccount[];
+  /** Path of the managed logo file; load the image with `getCompanyLogo`. */
   logoUrl: string;
   invoicePrefix: string;
   nextInvoiceNumber: number;
@@ -94,9 +235,199 @@ export interface Settings {
   smtpPort: number;
   smtpUser: string;
   smtpPassword: string;
+  /** Set by the backend; the password itself is never returned. */
+  smtpPasswordConfigured?: boolean;
   smtpFrom: string;
   smtpUseTls: boolean;
   smtpTlsMode: 'implicit' | 'starttls';
+  smtpTimeoutSeconds?: number;
+  smtpReplyTo?: string;
+  smtpFromName?: string;
+  sendCopyToSelf?: boolean;
+  smtpAuthMode?: 'password' | 'oauth2';
+  smtpOauthClientId?: string;
+  /** Write-only: the backend stores it in the OS keyring and returns it blank. */
+  smtpOauthClientSecret?: string;
+  /** Write-only: the backend stores it in the OS keyring and returns it blank. */
+  smtpOauthRefreshToken?: string;
+  smtpOauthTokenEndpoint?: string;
+  smtpOauthConfigured?: boolean;
+  smtpAcceptInvalidCerts?: boolean;
+  smtpAcceptInvalidCertsAcknowledged?: boolean;
+  /** PEM certificate; when set it is the only certificate trusted for the SMTP server. */
+  smtpPinnedCertPem?: string;
+  /** Yearly turnover above which the paušal status is lost (RSD). */
+  turnoverLimitRsd?: number;
+  /** Yearly turnover above which VAT registration is required (RSD). */
+  vatLimitRsd?: number;
+  /** Copy the database into the backups folder on startup, at most once a day. */
+  autoBackupEnabled?: boolean;
+  /** Number of automatic backups to keep. */
+  autoBackupKeep?: number;
+  /** Dialect used by the CSV exports. */
+  csvOptions?: CsvOptions;
+  /** Notes a new invoice starts with. */
+  defaultInvoiceNotes?: string;
+  /** Rounding of line and invoice totals; stamped on each new invoice. */
+  roundingMode?: RoundingMode;
+  /** Quotes are numbered `{quotePrefix}-{nextQuoteNumber}`, apart from invoices. */
+  quotePrefix?: string;
+  nextQuoteNumber?: number;
+  /** Invoice events are POSTed here when set. */
+  webhookUrl?: string;
+  /** Write-only: blank keeps the stored secret. */
+  webhookSecret?: string;
+  webhookSecretConfigured?: boolean;
+  /** Exported PDF name; `/` makes folders. Placeholders: `{number}`, `{client}`, `{year}`, `{month}`, `{issueDate}`. */
+  pdfFilenameTemplate?: string;
+  /** Default "Fakturu izdao" person for new invoices. */
+  issuedBy?: string | null;
+  /** Recipient of reports sent with "Email to accountant". */
+  accountantEmail?: string;
+  /** A DRAFT this many days old is suggested for sending or cancelling. */
+  staleDraftDays?: number;
+  /** Days past due before a SENT invoice is suggested for a reminder. */
+  overdueGraceDays?: number;
+  /** Labels the invoice form offers as custom fields (at most 5). */
+  customFieldLabels?: string[];
+  /** Invoice emails larger than this many megabytes are not sent. */
+  emailMaxMessageMb?: number;
+  /** Colors of the invoice email; the dark palette is for mail clients in dark mode. */
+  emailTheme?: EmailTheme;
+  /** Replaces the built-in legal note per language; blank keeps the built-in text. */
+  legalNoteOverride?: LegalNoteOverride;
+}
+
+export interface EmailPalette {
+  background: string;
+  card: string;
+  /** Outline of the card and its boxes, and the dividers. */
+  border: string;
+  /** The title and the total. */
+  accent: string;
+  text: string;
+  /** Row labels. */
+  muted: string;
+  /** The issuer address and the footer. */
+  faint: string;
+}
+
+export interface EmailTheme {
+  light: EmailPalette;
+  dark: EmailPalette;
+}
+
+/** `{INVOICE_NUMBER}` is replaced with the invoice number. */
+export interface LegalNoteOverride {
+  sr: string;
+  en: string;
+}
+
+export type RoundingMode = 'halfUp' | 'halfEven' | 'down';
+
+export type CsvDelimiter = ',' | ';' | '\t' | '|';
+
+export interface CsvOptions {
+  delimiter: CsvDelimiter;
+  /** Write `1234,50` instead of `1234.50`. */
+  decimalComma: boolean;
+  /** Prepend a UTF-8 BOM so Excel detects the encoding. */
+  includeBom: boolean;
+  lineEnding: 'crlf' | 'lf';
+}
+
+export type LimitKind = 'turnover' | 'vat';
+
+export interface LimitUsage {
+  kind: LimitKind;
+  limitRsd: number;
+  usedPercent: number;
+  remainingRsd: number;
+  projectedPercent: number;
+}
+
+export interface LimitStatus {
+  year: number;
+  turnoverRsd: number;
+  invoiceCount: number;
+  /** Year-end turnover at the current run rate. */
+  projectedTurnoverRsd: number;
+  limits: LimitUsage[];
+  /** Invoice numbers left out for lack of an exchange rate. */
+  unconvertedInvoices: string[];
+}
+
+/** Non-blocking: the invoice was created, but it pushed turnover past 80% or 100% of a limit. */
+export interface LimitWarning {
+  kind: LimitKind;
+  thresholdPercent: 80 | 100;
+  limitRsd: number;
+  turnoverRsd: number;
+}
+
+export type CreatedInvoice = Invoice & { limitWarnings?: LimitWarning[] };
+
+export interface AgingBuckets {
+  /** Not yet due (or due today). */
+  current: number;
+  days1To30: number;
+  days31To60: number;
+  days61To90: number;
+  daysOver90: number;
+  total: number;
+  invoiceCount: number;
+}
+
+export interface ReceivablesClientRow extends AgingBuckets {
+  clientId: string;
+  clientName: string;
+  currency: string;
+  maxDaysPastDue: number;
+}
+
+export interface ReceivablesCurrencyTotal extends AgingBuckets {
+  currency: string;
+}
+
+export interface ReceivablesReport {
+  /** YYYY-MM-DD */
+  asOf: string;
+  clients: ReceivablesClientRow[];
+  totals: ReceivablesCurrencyTotal[];
+}
+
+export interface TaxTotals {
+  income: number;
+  incomeCount: number;
+  expenses: number;
+  expenseCount: number;
+  /** income - expenses */
+  base: number;
+}
+
+export interface TaxQuarter extends TaxTotals {
+  /** 1-4 */
+  quarter: number;
+  from: string;
+  to: string;
+}
+
+/** Foreign-currency amounts that had no exchange rate; never part of the base. */
+export interface TaxUnconverted {
+  quarter: number;
+  kind: 'income' | 'expense';
+  currency: string;
+  amount: number;
+  count: number;
+}
+
+export interface TaxSummary {
+  year: number;
+  /** Default currency all converted amounts are in. */
+  currency: string;
+  quarters: TaxQuarter[];
+  yearTotal: TaxTotals;
+  unconverted: TaxUnconverted[];
 }
 
 export interface Expense {
@@ -109,6 +440,18 @@ export interface Expense {
   category?: string | null;
   notes?: string | null;
   createdAt: string;
+  /** Recurring schedule that generated this expense. */
+  recurringId?: string | null;
+  /** Set while the expense is in the trash. */
+  deletedAt?: string | null;
+  /** Invoice this expense was passed through to (billable expense). */
+  invoiceId?: string | null;
+}
+
+/** One part of a split expense; currency, category and date come from the original. */
+export interface ExpenseSplitPart {
+  title: string;
+  amount: number;
 }
 
 export interface ExpenseRange {
@@ -116,6 +459,576 @@ export interface ExpenseRange {
   to?: string;
 }
 
+/** A file written by an export command, and whether it was opened in the system viewer. */
+export interface ExportedFile {
+  path: string;
+  opened: boolean;
+  /** Why opening failed (e.g. no application registered for the type). */
+  openError: string | null;
+}
+
+/** Outcome of a date-range export; the backend normalizes and orders the bounds. */
+export interface RangeExportResult {
+  outputPath: string;
+  from: string;
+  to: string;
+  /** Invoices or expenses in the range; 0 means the file holds only headers. */
+  rowCount: number;
+}
+
+/** Payload of the `period_archive_progress` event, sent after each archive entry. */
+export interface PeriodArchiveProgress {
+  done: number;
+  total: number;
+  name: string;
+}
+
+/** A report `emailReportToAccountant` can attach. */
+export type AccountantReportKind = 'invoicesCsv' | 'expensesCsv' | 'kpo';
+
+export interface AccountantReportResult {
+  recipient: string;
+  from: string;
+  to: string;
+  /** Attachment file names, in the order sent. */
+  attachments: string[];
+}
+
+export type StatusSuggestionReason = 'STALE_DRAFT' | 'OVERDUE' | 'PAID_WITHOUT_DATE';
+
+/** `SEND`/`SEND_REMINDER` go through the invoice email; the rest through `updateInvoice`. */
+export type SuggestedAction = 'SEND' | 'CANCEL' | 'SEND_REMINDER' | 'MARK_PAID' | 'SET_PAID_AT';
+
+export interface StatusSuggestion {
+  invoiceId: string;
+  invoiceNumber: string;
+  clientName: string;
+  reason: StatusSuggestionReason;
+  /** Days since the draft was created, or days past due. */
+  days: number;
+  /** Most likely fix first. */
+  actions: SuggestedAction[];
+  /** Proposed date for `MARK_PAID`/`SET_PAID_AT`; null means today. */
+  paidAt: string | null;
+}
+
+export interface PeriodArchiveResult {
+  outputPath: string;
+  from: string;
+  to: string;
+  invoiceCount: number;
+  pdfCount: number;
+  expenseCount: number;
+  /** Documents left out of the archive; also listed in its errors.txt. */
+  errors: string[];
+}
+
+/** CSV header for each expense field; date, title and amount are required. */
+export interface ExpenseCsvMapping {
+  date: string;
+  title: string;
+  /** Debit column, or with `signedAmounts` a single column where debits are negative. */
+  amount: string;
+  credit?: string;
+  currency?: string;
+  category?: string;
+  signedAmounts?: boolean;
+}
+
+export type ExpenseImportStatus = 'imported' | 'skipped' | 'duplicate';
+
+export interface ExpenseImportRow {
+  line: number;
+  date?: string | null;
+  title: string;
+  amount?: number | null;
+  currency?: string | null;
+  status: ExpenseImportStatus;
+  message?: string | null;
+  expenseId?: string | null;
+}
+
+export interface ExpenseImportReport {
+  /** Nothing was written; `imported` counts the rows that would be imported. */
+  preview: boolean;
+  imported: number;
+  skipped: number;
+  duplicates: number;
+  rows: ExpenseImportRow[];
+}
+
+/** CSV header for each field of an incoming payment; date and amount are required. */
+export interface PaymentCsvMapping {
+  date: string;
+  /** Credit column, or a signed amount column; debit rows are skipped. */
+  amount: string;
+  /** Poziv na broj. */
+  reference?: string;
+  payer?: string;
+  currency?: string;
+}
+
+export interface PaymentRow {
+  line: number;
+  date: string;
+  amount: number;
+  currency: string;
+  reference?: string | null;
+  payer?: string | null;
+}
+
+export type PaymentMatchKind = 'reference' | 'amountAndName' | 'manual';
+
+export interface MatchedPayment extends PaymentRow {
+  paymentId: string;
+  invoiceId: string;
+  invoiceNumber: string;
+  matchedBy: PaymentMatchKind;
+  /** This payment settled the invoice and it was marked paid. */
+  invoicePaid: boolean;
+}
+
+export interface PaymentCandidate {
+  invoiceId: string;
+  invoiceNumber: string;
+  clientName: string;
+  currency: string;
+  outstanding: number;
+}
+
+export interface AmbiguousPayment extends PaymentRow {
+  reason: string;
+  candidates: PaymentCandidate[];
+}
+
+export interface PaymentImportReport {
+  matched: MatchedPayment[];
+  /** Not recorded; resolve each with `resolvePaymentMatch`. */
+  ambiguous: AmbiguousPayment[];
+  unmatched: PaymentRow[];
+  /** Already recorded. */
+  duplicates: PaymentRow[];
+  skipped: { line: number; message: string }[];
+}
+
+/** CSV header for each invoice field; one CSV row per invoice item, grouped by invoice number. */
+export interface InvoiceCsvMapping {
+  invoiceNumber: string;
+  issueDate: string;
+  clientName: string;
+  itemDescription: string;
+  itemQuantity?: string;
+  itemUnit?: string;
+  /** At least one of `itemUnitPrice` and `itemTotal` is required. */
+  itemUnitPrice?: string;
+  itemTotal?: string;
+  clientPib?: string;
+  serviceDate?: string;
+  dueDate?: string;
+  paidAt?: string;
+  currency?: string;
+  status?: string;
+  notes?: string;
+}
+
+export interface InvoiceImportOptions {
+  preview?: boolean;
+  /** Used when the currency column is not mapped or empty; the settings currency otherwise. */
+  defaultCurrency?: string;
+  /** The other tool's status values, matched case-insensitively. */
+  statusMap?: Record<string, Invoice['status']>;
+}
+
+export type InvoiceImportStatus = 'imported' | 'skipped' | 'duplicate';
+
+export interface InvoiceImportRow {
+  /** Line of the invoice's first row. */
+  line: number;
+  invoiceNumber: string;
+  clientName: string;
+  itemCount: number;
+  total?: number | null;
+  currency?: string | null;
+  status: InvoiceImportStatus;
+  message?: string | null;
+  clientCreated: boolean;
+  invoiceId?: string | null;
+}
+
+export interface InvoiceImportReport {
+  /** Nothing was written; the counts describe what would be imported. */
+  preview: boolean;
+  imported: number;
+  skipped: number;
+  duplicates: number;
+  clientsCreated: number;
+  rows: InvoiceImportRow[];
+}
+
+export interface InvoiceFileImportOptions {
+  /** Keep the number from the file when no invoice has it yet. */
+  keepNumber?: boolean;
+  /** With `keepNumber`, take a fresh number instead of failing when the number is taken. */
+  renumberOnConflict?: boolean;
+}
+
+export interface InvoiceFileImportResult {
+  invoice: Invoice;
+  clientCreated: boolean;
+  keptNumber: boolean;
+}
+
+export interface InvoiceYearTotal {
+  status: InvoiceStatus;
+  currency: string;
+  count: number;
+  total: number;
+}
+
+/** Invoices issued in one year; `year` is null for invoices without a usable issue date. */
+export interface InvoiceYear {
+  year: number | null;
+  count: number;
+  /** One entry per status and currency. */
+  totals: InvoiceYearTotal[];
+}
+
+/** Which side `repairDataConsistency` trusts: the JSON documents or the indexed columns. */
+export type RepairStrategy = 'fromJson' | 'fromColumns';
+
+export interface ConsistencyIssue {
+  table: 'invoices' | 'clients';
+  id: string;
+  /** Column name; the JSON key may differ (`totalAmount` is `total`). */
+  field: string;
+  columnValue: unknown;
+  jsonValue: unknown;
+}
+
+export interface ConsistencyReport {
+  invoicesChecked: number;
+  clientsChecked: number;
+  issues: ConsistencyIssue[];
+  /** Rows whose data_json cannot be parsed; no strategy repairs them. */
+  unreadable: { table: string; id: string; error: string }[];
+}
+
+/** An invoice or client whose data_json cannot be read; the lists leave it out until it is repaired. */
+export interface CorruptRow {
+  table: 'invoices' | 'clients';
+  id: string;
+  /** Invoice number or client name. */
+  label: string;
+  error: string;
+}
+
+export interface CorruptRowRepair {
+  table: 'invoices' | 'clients';
+  id: string;
+  /** Keys of the old document that were dropped because they no longer fit. */
+  droppedKeys: string[];
+}
+
+export interface ConsistencyRepairResult {
+  strategy: RepairStrategy;
+  rowsRepaired: number;
+  fieldsRepaired: number;
+  remaining: ConsistencyReport;
+}
+
+/** Result of `databaseMaintenance`; sizes are bytes, the WAL is the `-wal` file next to the database. */
+export interface MaintenanceReport {
+  integrityOk: boolean;
+  integrityErrors: string[];
+  /** The file is damaged: nothing was compacted and a backup should be restored. */
+  restoreRecommended: boolean;
+  compacted: boolean;
+  checkpointBusy: boolean;
+  dbSizeBefore: number;
+  dbSizeAfter: number;
+  walSizeBefore: number;
+  walSizeAfter: number;
+  checkedAt: string;
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/** One line of the app log; `fields` are the structured values of the event (`op`, `error`, ...). */
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+}
+
+export type DataEntity = 'invoices' | 'clients' | 'expenses' | 'settings';
+
+/** `replaced` is a bulk change (archive import, repair); `ids` is then empty and lists should reload. */
+export type DataOp = 'created' | 'updated' | 'deleted' | 'replaced';
+
+/** Payload of the `data://<entity>-changed` events sent after every committed write. */
+export interface DataChangedEvent {
+  op: DataOp;
+  ids: string[];
+}
+
+export type SearchKind = 'invoice' | 'client' | 'expense';
+
+/** One `globalSearch` hit; results arrive best match first. */
+export interface SearchResult {
+  kind: SearchKind;
+  id: string;
+  /** Invoice number and client, client name, or expense title. */
+  title: string;
+  /** Excerpt of notes, item descriptions, PIB or email around the match. */
+  snippet: string;
+  /** Invoice issue date or expense date (YYYY-MM-DD); null for clients. */
+  date?: string | null;
+}
+
+export type ExpenseGroupBy = 'month' | 'category' | 'currency';
+
+export interface ExpenseSummaryRow {
+  /** `YYYY-MM` for month, the category (empty when uncategorized), or the currency code. */
+  key: string;
+  total: number;
+  count: number;
+}
+
+/** Expense totals of one currency; amounts in different currencies are never summed. */
+export interface ExpenseCurrencySummary {
+  currency: string;
+  total: number;
+  count: number;
+  rows: ExpenseSummaryRow[];
+}
+
+export interface DashboardTotals {
+  /** SENT and PAID invoices by issue date. */
+  invoiced: number;
+  invoicedCount: number;
+  /** PAID invoices by payment date. */
+  paid: number;
+  paidCount: number;
+  /** SENT (unpaid) invoices by issue date. */
+  outstanding: number;
+  outstandingCount: number;
+  expenses: number;
+  expenseCount: number;
+  /** Cash basis: paid - expenses. */
+  net: number;
+}
+
+export interface DashboardMonth extends DashboardTotals {
+  /** YYYY-MM */
+  month: string;
+}
+
+export interface DashboardCurrency {
+  currency: string;
+  /** Twelve entries, January first. */
+  months: DashboardMonth[];
+  yearToDate: DashboardTotals;
+}
+
+export interface DashboardSummary {
+  year: number;
+  currencies: DashboardCurrency[];
+}
+
+export interface TopClient {
+  clientId: string;
+  name: string;
+  revenue: number;
+  invoiceCount: number;
+  /** Percent of the currency's paid revenue in the period. */
+  share: number;
+}
+
+export interface TopItem {
+  description: string;
+  revenue: number;
+  /** Invoices the item appears on. */
+  invoiceCount: number;
+  share: number;
+}
+
+/** Paid-revenue ranking in one currency; `total` covers the whole period, not only `entries`. */
+export interface TopRanking<T> {
+  currency: string;
+  total: number;
+  entries: T[];
+}
+
+export const RECURRENCE_INTERVAL_VALUES = ['weekly', 'monthly', 'quarterly', 'yearly'] as const;
+export type RecurrenceInterval = (typeof RECURRENCE_INTERVAL_VALUES)[number];
+
+export interface RecurringExpense {
+  id: string;
+  title: string;
+  amount: number;
+  currency: string;
+  category?: string | null;
+  notes?: string | null;
+  interval: RecurrenceInterval;
+  /** YYYY-MM-DD; later occurrences keep its day of month. */
+  startDate: string;
+  endDate?: string | null;
+  /** YYYY-MM-DD of the next expense to generate. */
+  nextRunDate: string;
+  createdAt: string;
+}
+
+export interface DeleteRecurringExpenseResult {
+  deleted: boolean;
+  expensesDeleted: number;
+}
+
+/** A product or service billed often; picking it fills an invoice line. */
+export interface CatalogItem {
+  id: string;
+  name: string;
+  unit?: string | null;
+  unitPrice: number;
+  /** Percent; only relevant inside the VAT system. */
+  vatRate?: number | null;
+  /** Inactive items are hidden when writing invoices but kept in the usage report. */
+  active: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CatalogItemInput {
+  name: string;
+  unit?: string | null;
+  unitPrice: number;
+  vatRate?: number | null;
+  active?: boolean;
+}
+
+/** How often and at what price a catalog item was billed, per currency (sent and paid invoices). */
+export interface CatalogUsage {
+  catalogItemId: string;
+  name: string;
+  currency: string;
+  lines: number;
+  invoices: number;
+  quantity: number;
+  total: number;
+  averageUnitPrice: number;
+  lastBilled: string;
+}
+
+/** Time worked for a client; billed entries are locked. */
+export interface TimeEntry {
+  id: string;
+  clientId: string;
+  date: string;
+  minutes: number;
+  description: string;
+  /** In the currency of the invoice the entry gets billed to. */
+  hourlyRate: number;
+  billedInvoiceId?: string | null;
+  createdAt: string;
+}
+
+export interface TimeEntryInput {
+  clientId: string;
+  date: string;
+  minutes: number;
+  description?: string;
+  hourlyRate: number;
+}
+
+/** The running timer; stopping it records a time entry. */
+export interface ActiveTimer {
+  clientId: string;
+  description: string;
+  hourlyRate: number;
+  /** RFC 3339, UTC. */
+  startedAt: string;
+}
+
+export interface UnbilledTime {
+  clientId: string;
+  clientName: string;
+  entries: number;
+  minutes: number;
+  hours: number;
+  amount: number;
+  oldestDate: string;
+}
+
+/** EXPIRED is not stored: DRAFT and SENT quotes read as expired after `validUntil`. */
+export type QuoteStatus = 'DRAFT' | 'SENT' | 'ACCEPTED' | 'REJECTED' | 'EXPIRED';
+
+/** A numbered quote (ponuda) with a validity date; converts into a draft invoice. */
+export interface Quote {
+  id: string;
+  quoteNumber: string;
+  clientId: string;
+  clientName: string;
+  issueDate: string;
+  validUntil: string;
+  status: QuoteStatus;
+  currency: string;
+  items: InvoiceItem[];
+  subtotal: number;
+  total: number;
+  notes: string;
+  /** Invoice the quote was converted to; converted quotes are locked. */
+  invoiceId?: string | null;
+  sentAt?: string | null;
+  createdAt: string;
+}
+
+export interface QuoteInput {
+  clientId: string;
+  issueDate: string;
+  validUntil: string;
+  /** Blank takes the client's default currency, then the settings one. */
+  currency?: string;
+  items: InvoiceItem[];
+  notes?: string;
+}
+
+/** Monthly amounts (RSD) from a tax decision (rešenje), from `validFrom` (YYYY-MM). */
+export interface Obligation {
+  id: string;
+  validFrom: string;
+  /** Last month (YYYY-MM) it applies to; open-ended when empty. */
+  validTo?: string | null;
+  /** Porez na prihod od samostalne delatnosti. */
+  tax: number;
+  /** Doprinos za PIO. */
+  pension: number;
+  /** Doprinos za zdravstveno osiguranje. */
+  health: number;
+  /** Doprinos za osiguranje za slučaj nezaposlenosti. */
+  unemployment: number;
+  notes?: string | null;
+  createdAt: string;
+}
+
+export type ObligationInput = Omit<Obligation, 'id' | 'createdAt'>;
+
+export interface ObligationMonthStatus {
+  /** YYYY-MM. */
+  month: string;
+  /** Decision the month was generated from, or the one in force for it. */
+  obligationId: string | null;
+  total: number;
+  generatedAt: string | null;
+  paidAt: string | null;
+}
+
+export interface GeneratedObligations {
+  month: string;
+  /** 0 when the month was already generated. */
+  expensesCreated: number;
+}
+
 export const OFFER_STATUS_VALUES = ['DRAFT', 'SENT', 'FAILED'] as const;
 export type OfferStatus = (typeof OFFER_STATUS_VALUES)[number];
 
@@ -134,5 +1047,79 @@ export interface Offer {
   failedReason?: string | null;
 }
 
+export const OUTBOX_STATUS_VALUES = ['QUEUED', 'SENDING', 'SENT', 'FAILED', 'CANCELLED'] as const;
+export type OutboxStatus = (typeof OUTBOX_STATUS_VALUES)[number];
+
+export interface OutboxItem {
+  id: string;
+  invoiceId: string;
+  to: string[];
+  subject: string;
+  status: OutboxStatus;
+  attempts: number;
+  error?: string | null;
+  createdAt: string;
+  updatedAt: string;
+  sentAt?: string | null;
+  /** Whether the sender's own address got a copy; set once sent. */
+  selfCopy?: boolean | null;
+}
+
+export type WebhookDeliveryStatus = 'PENDING' | 'DELIVERED' | 'FAILED';
+
+export interface WebhookDelivery {
+  id: string;
+  /** `invoice.created`, `invoice.status_changed` or `invoice.email_sent`. */
+  event: string;
+  invoiceId?: string | null;
+  status: WebhookDeliveryStatus;
+  attempts: number;
+  /** Set while pending: when the next attempt is made. */
+  nextAttemptAt?: string | null;
+  responseStatus?: number | null;
+  error?: string | null;
+  createdAt: string;
+  deliveredAt?: string | null;
+}
+
+export interface AppLockStatus {
+  pinSet: boolean;
+  locked: boolean;
+  /** Seconds until the next PIN attempt is accepted, after too many wrong ones. */
+  retryAfterSecs?: number | null;
+}
+
+/** `code` of a failed command (`errors.rs`). `OTHER` covers errors that aren't classified yet. */
+export type CommandErrorCode =
+  | 'NOT_FOUND'
+  | 'VALIDATION'
+  | 'CONFLICT'
+  | 'SMTP_CONFIG'
+  | 'SMTP_SEND'
+  | 'ATTACHMENT_TOO_LARGE'
+  | 'PDF'
+  | 'DATABASE'
+  | 'LICENSE_REQUIRED'
+  | 'FEATURE_LOCKED'
+  | 'APP_LOCKED'
+  | 'READ_ONLY'
+  | 'OTHER';
+
+/** What a failed command rejects with; `message` is already in the app language. */
+export interface CommandErrorPayload {
+  code: CommandErrorCode;
+  message: string;
+  /** `{entity, id}` for NOT_FOUND, `{field, code}` for VALIDATION, `{code}` for SMTP_CONFIG, `{limitMb, messageBytes, attachments}` for ATTACHMENT_TOO_LARGE, the lock for FEATURE_LOCKED, `{reason}` for READ_ONLY. */
+  details: Record<string, unknown> | null;
+}
+
+export type ReadOnlyReason = 'LOCKED' | 'READ_ONLY_FILE';
+
+/** Whether the database can be written; read-only while another program holds it or the file can't be written. */
+export interface AppMode {
+  readOnly: boolean;
+  reason?: ReadOnlyReason | null;
+}
+
 export const CURRENCY_VALUES = ['RSD', 'EUR', 'USD'] as const;
 export type CurrencyCode = (typeof CURRENCY_VALUES)[number];
@@ -16,6 +16,7 @@ export type LockReason =
 export type TrialInfo = {
   trialStartDate: string;
   trialEndsAt: string;
+  daysRemaining: number;
   status: 'ACTIVE' | 'EXPIRED';
 };
 
@@ -35,4 +36,29 @@ export type LicenseStatus = {
   isLocked: boolean;
   reason?: string;
   validUntil?: string;
+  /** Expired, but still usable for `graceDaysRemaining` more days. */
+  gracePeriod?: boolean;
+  graceDaysRemaining?: number;
+};
+
+/** Features that depend on the license type (`features.rs`). */
+export type PlanFeature = 'emailSending' | 'batchExport';
+
+export type Plan = 'none' | 'trial' | 'yearly' | 'lifetime';
+
+/** `details` of the `FEATURE_LOCKED` error of a command behind a feature. */
+export type FeatureLocked = {
+  feature: PlanFeature;
+  currentPlan: Plan;
+  /** Cheapest license that includes the feature. */
+  requiredPlan: Plan | null;
+};
+
+/** Result of `license_expiry_info`; `daysRemaining` is negative once expired. */
+export type LicenseExpiryInfo = {
+  licenseType: string | null;
+  validUntil: string | null;
+  daysRemaining: number | null;
+  gracePeriod: boolean;
+  graceDaysRemaining: number | null;
 };
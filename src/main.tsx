@@ -1,11 +1,14 @@
 import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './app/App';
+import { AppLockGate } from './app/components/AppLockGate';
 import './app/i18n';
 import './styles/index.css';
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
-    <App />
+    <AppLockGate>
+      <App />
+    </AppLockGate>
   </React.StrictMode>
 );